@@ -71,7 +71,7 @@ cargo run -q -p payments-cli -- webhook list
 # Trigger an Event
 print_step "Triggering Event: Creating Account & Deposit..."
 # Create Account
-ACCOUNT_JSON=$(cargo run -q -p payments-cli -- account create "Demo Corp" --currency USD)
+ACCOUNT_JSON=$(cargo run -q -p payments-cli -- account create "Demo Corp" --owner 00000000-0000-0000-0000-000000000000 --currency USD)
 # Extract ID more robustly (handle potential whitespace/newlines)
 ACCOUNT_ID=$(echo "$ACCOUNT_JSON" | grep -o '"id": *"[^"]*"' | cut -d'"' -f4)
 
@@ -0,0 +1,74 @@
+//! Benchmarks for currency conversion and `Money` arithmetic.
+//!
+//! ```bash
+//! cargo bench -p exchange-rates
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use exchange_rates::{
+    CurrencyCode, EUR, GBP, Money, USD, convert_dynamic, disable_fluctuation, enable_fluctuation,
+};
+
+fn bench_convert_dynamic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convert_dynamic");
+
+    disable_fluctuation();
+    group.bench_function("same_currency", |b| {
+        b.iter(|| convert_dynamic(black_box(10_000), CurrencyCode::USD, CurrencyCode::USD))
+    });
+    group.bench_function("usd_to_eur", |b| {
+        b.iter(|| convert_dynamic(black_box(10_000), CurrencyCode::USD, CurrencyCode::EUR))
+    });
+    group.bench_function("all_pairs", |b| {
+        b.iter(|| {
+            let mut total = 0i64;
+            for &from in CurrencyCode::all() {
+                for &to in CurrencyCode::all() {
+                    total += convert_dynamic(black_box(10_000), from, to);
+                }
+            }
+            total
+        })
+    });
+
+    // Fluctuation reads the system clock on every rate lookup.
+    enable_fluctuation();
+    group.bench_function("usd_to_eur_fluctuating", |b| {
+        b.iter(|| convert_dynamic(black_box(10_000), CurrencyCode::USD, CurrencyCode::EUR))
+    });
+    disable_fluctuation();
+
+    group.finish();
+}
+
+fn bench_money_arithmetic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("money");
+
+    let a = Money::<USD>::from_minor(123_456);
+    let b = Money::<USD>::from_minor(7_890);
+    group.bench_function("add", |bench| bench.iter(|| black_box(a) + black_box(b)));
+    group.bench_function("sub", |bench| bench.iter(|| black_box(a) - black_box(b)));
+    group.bench_function("sum_1000", |bench| {
+        bench.iter(|| {
+            (0..1000).fold(Money::<USD>::default(), |acc, i| {
+                acc + Money::from_minor(black_box(i))
+            })
+        })
+    });
+
+    let eur = Money::<EUR>::from_minor(123_456);
+    group.bench_function("convert_eur_to_gbp", |bench| {
+        bench.iter(|| black_box(eur).convert::<GBP>())
+    });
+    group.bench_function("convert_at_base_rate_eur_to_gbp", |bench| {
+        bench.iter(|| black_box(eur).convert_at_base_rate::<GBP>())
+    });
+    group.bench_function("display", |bench| bench.iter(|| black_box(eur).to_string()));
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_convert_dynamic, bench_money_arithmetic);
+criterion_main!(benches);
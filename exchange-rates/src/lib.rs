@@ -5,11 +5,23 @@
 //! that auto-generates all necessary types, traits, and conversions.
 //!
 //! # Adding a New Currency
-//! Simply add a line to the `define_currencies!` macro invocation:
+//! Currencies that only need converting can be registered at startup, without
+//! a code change:
+//! ```
+//! use exchange_rates::{CurrencyInfo, CurrencyRegistry};
+//!
+//! let yen: CurrencyInfo = "JPY:¥:1:0.0067".parse().unwrap();
+//! CurrencyRegistry::global().register(yen).unwrap();
+//! let converted = CurrencyRegistry::global().convert(10000, "USD", "JPY");
+//! ```
+//!
+//! A currency needs a line in the `define_currencies!` macro invocation (and
+//! its `From` impls) to get a marker type for the typed API and a
+//! [`CurrencyCode`]:
 //! ```ignore
 //! define_currencies! {
 //!     // ... existing currencies ...
-//!     JPY => ("JPY", "¥", "sen", 100, 0.0067),
+//!     JPY => ("JPY", "¥", "sen", 1, 0.0067, 0.5),
 //! }
 //! ```
 //!
@@ -33,6 +45,10 @@ use std::marker::PhantomData;
 use std::ops::{Add, Sub};
 use std::sync::atomic::{AtomicBool, Ordering};
 
+mod registry;
+
+pub use registry::{CurrencyInfo, CurrencyRegistry, RegistryError};
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Global Fluctuation Control
 // ─────────────────────────────────────────────────────────────────────────────
@@ -273,6 +289,12 @@ macro_rules! define_currencies {
                 }
             }
 
+            pub fn minor_units_per_major(&self) -> i32 {
+                match self {
+                    $(CurrencyCode::$name => $minor_per_major),*
+                }
+            }
+
             pub fn base_to_usd_rate(&self) -> f64 {
                 match self {
                     $(CurrencyCode::$name => $to_usd),*
@@ -285,6 +307,12 @@ macro_rules! define_currencies {
                 }
             }
 
+            pub fn max_variance_percent(&self) -> f64 {
+                match self {
+                    $(CurrencyCode::$name => $variance),*
+                }
+            }
+
             pub fn all() -> &'static [CurrencyCode] {
                 &[$(CurrencyCode::$name),*]
             }
@@ -307,20 +335,18 @@ macro_rules! define_currencies {
         }
 
         // ─────────────────────────────────────────────────────────────────────
-        // Runtime conversion function (dispatches to type-safe converters)
+        // Runtime conversion functions (read the global currency registry)
         // ─────────────────────────────────────────────────────────────────────
         pub fn convert_dynamic(amount: i64, from: CurrencyCode, to: CurrencyCode) -> i64 {
-            if from == to { return amount; }
-
-            // Use the base rates for conversion
-            let usd_amount = amount as f64 * from.to_usd_rate();
-            let target_amount = usd_amount / to.to_usd_rate();
-            target_amount.round() as i64
+            $crate::CurrencyRegistry::global()
+                .convert(amount, from.code(), to.code())
+                .expect("built-in currencies are always registered")
         }
 
         pub fn get_rate_dynamic(from: CurrencyCode, to: CurrencyCode) -> f64 {
-            if from == to { return 1.0; }
-            from.to_usd_rate() / to.to_usd_rate()
+            $crate::CurrencyRegistry::global()
+                .rate(from.code(), to.code())
+                .expect("built-in currencies are always registered")
         }
 
         pub fn get_all_rates(base: CurrencyCode) -> std::collections::HashMap<CurrencyCode, f64> {
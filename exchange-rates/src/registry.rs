@@ -0,0 +1,263 @@
+//! Runtime currency registry.
+//!
+//! The built-in currencies have marker types for the typed [`Money`](crate::Money)
+//! API and are always registered. Other currencies can be registered at
+//! startup, e.g. from configuration, and are then available to conversions
+//! and rate lookups by code without a code change.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{OnceLock, RwLock};
+
+use crate::{CurrencyCode, fluctuate};
+
+static GLOBAL: OnceLock<CurrencyRegistry> = OnceLock::new();
+
+/// Error type for registry operations.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RegistryError {
+    #[error("Unknown currency: {0}")]
+    UnknownCurrency(String),
+
+    #[error("{0} is a built-in currency and cannot be redefined")]
+    BuiltIn(String),
+
+    #[error("Invalid currency: {0}")]
+    Invalid(String),
+}
+
+/// A currency's metadata and base rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyInfo {
+    /// Three-letter ISO 4217 code, e.g. `JPY`
+    pub code: String,
+    pub symbol: String,
+    /// 100 for currencies with cents, 1 for currencies without minor units
+    pub minor_units_per_major: i32,
+    /// US dollars one major unit is worth
+    pub to_usd_rate: f64,
+    /// How far the rate may drift while fluctuation is enabled, in percent
+    pub max_variance_percent: f64,
+}
+
+impl CurrencyInfo {
+    fn builtin(code: CurrencyCode) -> Self {
+        Self {
+            code: code.code().to_string(),
+            symbol: code.symbol().to_string(),
+            minor_units_per_major: code.minor_units_per_major(),
+            to_usd_rate: code.base_to_usd_rate(),
+            max_variance_percent: code.max_variance_percent(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), RegistryError> {
+        let invalid =
+            |reason: &str| Err(RegistryError::Invalid(format!("{}: {}", self.code, reason)));
+        if self.code.len() != 3 || !self.code.bytes().all(|b| b.is_ascii_uppercase()) {
+            return invalid("code must be three uppercase letters");
+        }
+        if self.symbol.trim().is_empty() {
+            return invalid("symbol is empty");
+        }
+        let minor = self.minor_units_per_major;
+        if minor < 1 || 10i32.pow(minor.ilog10()) != minor {
+            return invalid("minor units per major must be 1, 10, 100, ...");
+        }
+        if !self.to_usd_rate.is_finite() || self.to_usd_rate <= 0.0 {
+            return invalid("rate must be positive");
+        }
+        if !(0.0..100.0).contains(&self.max_variance_percent) {
+            return invalid("variance must be between 0 and 100 percent");
+        }
+        Ok(())
+    }
+}
+
+/// Parses `CODE:SYMBOL:MINOR_UNITS_PER_MAJOR:USD_RATE`, e.g.
+/// `JPY:¥:1:0.0067`. Parsed currencies do not fluctuate.
+impl FromStr for CurrencyInfo {
+    type Err = RegistryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+        let [code, symbol, minor, rate] = parts[..] else {
+            return Err(RegistryError::Invalid(format!(
+                "'{}': expected CODE:SYMBOL:MINOR_UNITS_PER_MAJOR:USD_RATE",
+                s
+            )));
+        };
+        let info = Self {
+            code: code.to_ascii_uppercase(),
+            symbol: symbol.to_string(),
+            minor_units_per_major: minor.parse().map_err(|_| {
+                RegistryError::Invalid(format!("{}: invalid minor units '{}'", code, minor))
+            })?,
+            to_usd_rate: rate.parse().map_err(|_| {
+                RegistryError::Invalid(format!("{}: invalid rate '{}'", code, rate))
+            })?,
+            max_variance_percent: 0.0,
+        };
+        info.validate()?;
+        Ok(info)
+    }
+}
+
+/// Currencies available to conversions by code.
+///
+/// [`convert_dynamic`](crate::convert_dynamic) and
+/// [`get_rate_dynamic`](crate::get_rate_dynamic) read the
+/// [`global`](Self::global) registry.
+pub struct CurrencyRegistry {
+    currencies: RwLock<BTreeMap<String, CurrencyInfo>>,
+}
+
+impl Default for CurrencyRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CurrencyRegistry {
+    /// Creates a registry holding the built-in currencies.
+    pub fn new() -> Self {
+        let currencies = CurrencyCode::all()
+            .iter()
+            .map(|&code| (code.code().to_string(), CurrencyInfo::builtin(code)))
+            .collect();
+        Self {
+            currencies: RwLock::new(currencies),
+        }
+    }
+
+    /// Returns the process-wide registry.
+    pub fn global() -> &'static Self {
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// Adds a currency, replacing one registered before under the same code.
+    /// Built-in currencies keep their definitions.
+    pub fn register(&self, info: CurrencyInfo) -> Result<(), RegistryError> {
+        info.validate()?;
+        if info.code.parse::<CurrencyCode>().is_ok() {
+            return Err(RegistryError::BuiltIn(info.code));
+        }
+        self.currencies
+            .write()
+            .unwrap()
+            .insert(info.code.clone(), info);
+        Ok(())
+    }
+
+    /// Looks up a currency by code, in any case.
+    pub fn get(&self, code: &str) -> Option<CurrencyInfo> {
+        find(&self.currencies.read().unwrap(), code).ok().cloned()
+    }
+
+    /// Returns the codes of all registered currencies, in order.
+    pub fn codes(&self) -> Vec<String> {
+        self.currencies.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Returns how many major units of `to` one major unit of `from` buys.
+    pub fn rate(&self, from: &str, to: &str) -> Result<f64, RegistryError> {
+        let currencies = self.currencies.read().unwrap();
+        let (from, to) = (find(&currencies, from)?, find(&currencies, to)?);
+        Ok(rate_between(from, to))
+    }
+
+    /// Converts `amount` minor units of `from` into minor units of `to`.
+    pub fn convert(&self, amount: i64, from: &str, to: &str) -> Result<i64, RegistryError> {
+        let currencies = self.currencies.read().unwrap();
+        let (from, to) = (find(&currencies, from)?, find(&currencies, to)?);
+        if from.code == to.code {
+            return Ok(amount);
+        }
+        let usd_amount = amount as f64 * usd_rate(from);
+        let scale = to.minor_units_per_major as f64 / from.minor_units_per_major as f64;
+        Ok((usd_amount / usd_rate(to) * scale).round() as i64)
+    }
+
+    /// Returns the rate from `base` to every registered currency, by code.
+    pub fn rates(&self, base: &str) -> Result<BTreeMap<String, f64>, RegistryError> {
+        let currencies = self.currencies.read().unwrap();
+        let base = find(&currencies, base)?;
+        Ok(currencies
+            .values()
+            .map(|to| (to.code.clone(), rate_between(base, to)))
+            .collect())
+    }
+}
+
+/// Looks `code` up as given, then in upper case.
+fn find<'a>(
+    currencies: &'a BTreeMap<String, CurrencyInfo>,
+    code: &str,
+) -> Result<&'a CurrencyInfo, RegistryError> {
+    currencies
+        .get(code)
+        .or_else(|| currencies.get(&code.to_ascii_uppercase()))
+        .ok_or_else(|| RegistryError::UnknownCurrency(code.to_string()))
+}
+
+fn rate_between(from: &CurrencyInfo, to: &CurrencyInfo) -> f64 {
+    if from.code == to.code {
+        return 1.0;
+    }
+    usd_rate(from) / usd_rate(to)
+}
+
+fn usd_rate(currency: &CurrencyInfo) -> f64 {
+    fluctuate(currency.to_usd_rate, currency.max_variance_percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disable_fluctuation;
+
+    fn yen() -> CurrencyInfo {
+        "jpy:¥:1:0.0067".parse().unwrap()
+    }
+
+    #[test]
+    fn test_registered_currency_converts_with_its_minor_units() {
+        disable_fluctuation();
+        let registry = CurrencyRegistry::new();
+        assert!(registry.get("JPY").is_none());
+        registry.register(yen()).unwrap();
+
+        assert_eq!(registry.get("jpy").unwrap().symbol, "¥");
+        // $100.00 is about ¥14,925, and yen have no minor units.
+        assert_eq!(registry.convert(10_000, "USD", "JPY").unwrap(), 14_925);
+        assert_eq!(registry.convert(14_925, "JPY", "USD").unwrap(), 10_000);
+        assert!((registry.rate("JPY", "USD").unwrap() - 0.0067).abs() < 1e-9);
+        let rates = registry.rates("USD").unwrap();
+        assert_eq!(rates.len(), CurrencyCode::all().len() + 1);
+        assert_eq!(rates["USD"], 1.0);
+        assert_eq!(registry.codes(), ["EUR", "GBP", "INR", "JPY", "USD"]);
+    }
+
+    #[test]
+    fn test_registry_rejects_invalid_and_built_in_currencies() {
+        let registry = CurrencyRegistry::new();
+        assert_eq!(
+            registry.register(CurrencyInfo::builtin(CurrencyCode::USD)),
+            Err(RegistryError::BuiltIn("USD".into()))
+        );
+        for spec in [
+            "JPY:¥:1",
+            "YEN:¥:1:abc",
+            "JP:¥:1:0.0067",
+            "JPY: :1:0.0067",
+            "JPY:¥:50:0.0067",
+            "JPY:¥:1:-1",
+        ] {
+            assert!(spec.parse::<CurrencyInfo>().is_err(), "{}", spec);
+        }
+        assert!(matches!(
+            registry.convert(100, "USD", "XXX"),
+            Err(RegistryError::UnknownCurrency(_))
+        ));
+    }
+}
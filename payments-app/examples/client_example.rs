@@ -5,7 +5,7 @@
 use payments_client::PaymentsClient;
 use payments_hex::{PaymentService, inbound::HttpServer};
 use payments_repo::build_repo;
-use payments_types::CurrencyCode;
+use payments_types::{CurrencyCode, OwnerId, PositiveAmount};
 
 use std::net::SocketAddr;
 use tempfile::tempdir;
@@ -64,7 +64,9 @@ async fn main() -> anyhow::Result<()> {
     println!("✅ Server health: {health}");
 
     //assert response is error unauthorized
-    let response = client.create_account("Alice Corp", CurrencyCode::USD).await;
+    let response = client
+        .create_account(OwnerId::DEFAULT, "Alice Corp", CurrencyCode::USD)
+        .await;
     assert!(response.is_err());
     println!("✅ Unauthorized without key: {}", response.unwrap_err());
 
@@ -76,28 +78,40 @@ async fn main() -> anyhow::Result<()> {
 
     // Create accounts
     let alice = client
-        .create_account("Alice Corp", CurrencyCode::USD)
+        .create_account(OwnerId::DEFAULT, "Alice Corp", CurrencyCode::USD)
         .await?;
     println!("✅ Created account: {} (id={})", alice.name, alice.id);
 
-    let bob = client.create_account("Bob Inc", CurrencyCode::USD).await?;
+    let bob = client
+        .create_account(OwnerId::DEFAULT, "Bob Inc", CurrencyCode::USD)
+        .await?;
     println!("✅ Created account: {} (id={})", bob.name, bob.id);
 
     // Deposit to Alice
     let deposit = client
-        .deposit(alice.id, 10000, CurrencyCode::USD, None, None)
+        .deposit(
+            alice.id,
+            PositiveAmount::new(10000)?,
+            CurrencyCode::USD,
+            None,
+            None,
+        )
         .await?;
     println!("✅ Deposited $100.00 to Alice (tx={})", deposit.id);
 
     let alice = client.get_account(alice.id).await?;
-    println!(
-        "   Alice balance: ${:.2}",
-        alice.balance.amount() as f64 / 100.0
-    );
+    println!("   Alice balance: {}", alice.balance);
 
     // Transfer from Alice to Bob
     let transfer = client
-        .transfer(alice.id, bob.id, 3500, CurrencyCode::USD, None, None)
+        .transfer(
+            alice.id,
+            bob.id,
+            PositiveAmount::new(3500)?,
+            CurrencyCode::USD,
+            None,
+            None,
+        )
         .await?;
     println!(
         "✅ Transferred $35.00 from Alice to Bob (tx={})",
@@ -106,37 +120,29 @@ async fn main() -> anyhow::Result<()> {
 
     let alice = client.get_account(alice.id).await?;
     let bob = client.get_account(bob.id).await?;
-    println!(
-        "   Alice balance: ${:.2}",
-        alice.balance.amount() as f64 / 100.0
-    );
-    println!(
-        "   Bob balance: ${:.2}",
-        bob.balance.amount() as f64 / 100.0
-    );
+    println!("   Alice balance: {}", alice.balance);
+    println!("   Bob balance: {}", bob.balance);
 
     // Withdraw from Bob
     let withdraw = client
-        .withdraw(bob.id, 1500, CurrencyCode::USD, None, None)
+        .withdraw(
+            bob.id,
+            PositiveAmount::new(1500)?,
+            CurrencyCode::USD,
+            None,
+            None,
+        )
         .await?;
     println!("✅ Withdrew $15.00 from Bob (tx={})", withdraw.id);
 
     let bob = client.get_account(bob.id).await?;
-    println!(
-        "   Bob balance: ${:.2}",
-        bob.balance.amount() as f64 / 100.0
-    );
+    println!("   Bob balance: {}", bob.balance);
 
     // List all accounts
     let accounts = client.list_accounts().await?;
     println!("\n📋 All accounts:");
     for acc in accounts {
-        println!(
-            "   - {} ({}): ${:.2}",
-            acc.name,
-            acc.id,
-            acc.balance.amount() as f64 / 100.0
-        );
+        println!("   - {} ({}): {}", acc.name, acc.id, acc.balance);
     }
 
     println!("\n🎉 Example completed successfully!");
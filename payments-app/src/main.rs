@@ -4,16 +4,31 @@
 //! - Load configuration from environment
 //! - Initialize the repository adapter
 //! - Create the payment service
-//! - Start the HTTP server
+//! - Start the HTTP server, and the gRPC server if configured
 
 mod config;
+mod spec;
+
+use std::sync::Arc;
 
 use opentelemetry::global;
 use opentelemetry_sdk::{propagation::TraceContextPropagator, trace as sdktrace};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use payments_hex::{PaymentService, inbound::HttpServer};
-use payments_repo::build_repo;
+#[cfg(feature = "grpc")]
+use payments_hex::inbound::GrpcServer;
+use payments_hex::{
+    MetricsRegistry, PaymentService, SimulatedPayouts, StubBankPayouts,
+    inbound::HttpServer,
+    jobs::{
+        BalanceRecorder, DormancyDetector, HoldExpirer, LedgerAuditor, PaymentScheduler,
+        QuotaMonitor, RateRecorder, StatementScheduler,
+    },
+};
+use payments_repo::{CountingRepo, RetryPolicy, RetryRepo, build_repo, webhooks::WebhookWorker};
+use payments_types::{CurrencyRegistry, SystemClock};
+
+use crate::config::{IdempotencyBackend, PayoutRails};
 
 fn init_tracer() -> (sdktrace::Tracer, sdktrace::SdkTracerProvider) {
     global::set_text_map_propagator(TraceContextPropagator::new());
@@ -36,6 +51,13 @@ fn init_tracer() -> (sdktrace::Tracer, sdktrace::SdkTracerProvider) {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // `--dump-openapi <path>` writes the spec for the client generators and
+    // exits before any configuration is needed
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some(spec::DUMP_OPENAPI_FLAG) {
+        return spec::dump(args.next().as_deref().unwrap_or("-"));
+    }
+
     // Load environment variables
     dotenvy::dotenv().ok();
 
@@ -59,14 +81,209 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Starting payments server on port {}", config.port);
     tracing::info!("Using database: {}", config.database_url);
 
+    // Currencies that only need rates and conversions
+    for currency in config.currencies.iter().cloned() {
+        tracing::info!("Registering currency {}", currency.code);
+        CurrencyRegistry::global().register(currency)?;
+    }
+
     // Build repository (handles connection and migration)
-    let repo = build_repo(&config.database_url).await?;
+    let repo = Arc::new(build_repo(&config.database_url).await?);
+    let webhook_repo = repo.clone();
+
+    // Retry reads that hit a transient outage (pool timeout, connection reset),
+    // and count repository calls per request to make N+1 queries visible
+    let repo = Arc::new(CountingRepo::new(RetryRepo::new(
+        repo,
+        RetryPolicy::default(),
+    )));
+
+    // Optional canary that recomputes sampled balances from their history
+    if let Some(audit_config) = config.ledger_audit {
+        tracing::info!(
+            "Ledger audit enabled: {} accounts every {:?}",
+            audit_config.sample_size,
+            audit_config.interval
+        );
+        LedgerAuditor::new(repo.clone(), audit_config).spawn();
+    }
+
+    // One metrics registry shared by the service and the HTTP layer
+    let metrics = Arc::new(MetricsRegistry::default());
 
     // Create the payment service
-    let service = PaymentService::new(repo);
+    let mut service = PaymentService::builder(repo)
+        .with_limits(config.limits)
+        .with_gl_codes(config.gl_codes)
+        .with_amount_format(config.amount_format)
+        .with_storage_quotas(config.storage_quotas)
+        .with_metrics(metrics.clone());
+    if let Some(policy) = config.dormancy {
+        service = service.with_dormancy_policy(policy);
+    }
+    if let Some(rules) = config.risk_rules {
+        tracing::info!("Risk rules enabled: {:?}", rules);
+        service = service.with_risk_check(Arc::new(rules));
+    }
+    match config.payout_rails {
+        PayoutRails::Simulated { settle_after } => {
+            tracing::info!(
+                "Payouts simulated, settling after {}s",
+                settle_after.num_seconds()
+            );
+            service = service.with_payout_provider(Arc::new(
+                SimulatedPayouts::new(Arc::new(SystemClock)).with_settle_after(settle_after),
+            ));
+        }
+        PayoutRails::StubBank { bank } => {
+            tracing::warn!(
+                "Payouts go to the {} stub, which cannot send them yet",
+                bank
+            );
+            service = service.with_payout_provider(Arc::new(StubBankPayouts::new(bank)));
+        }
+    }
+    match config.idempotency_store {
+        IdempotencyBackend::Database => {}
+        #[cfg(feature = "redis")]
+        IdempotencyBackend::Redis { url, ttl } => {
+            tracing::info!("Idempotent responses kept in Redis for {}s", ttl.as_secs());
+            let store = payments_hex::RedisIdempotencyStore::connect(&url, ttl).await?;
+            service = service.with_idempotency_store(Arc::new(store));
+        }
+    }
+    if let Some(debtor) = config.settlement_debtor {
+        service = service.with_settlement_debtor(debtor);
+    }
+    match config.statement_links {
+        Some(links) => service = service.with_statement_links(links),
+        None => tracing::warn!(
+            "STATEMENT_URL_SECRET is not set: statement download links stop working on restart"
+        ),
+    }
+    match config.download_links {
+        Some(links) => service = service.with_download_links(links),
+        None => tracing::warn!(
+            "DOWNLOAD_URL_SECRET is not set: export download links point at localhost:3000 and only work on this instance"
+        ),
+    }
+    match config.session_tokens {
+        Some(tokens) => service = service.with_session_tokens(tokens),
+        None => tracing::warn!(
+            "SESSION_TOKEN_SECRET is not set: session tokens only work on this instance until it restarts"
+        ),
+    }
+    #[cfg(feature = "smtp")]
+    if let Some(smtp) = config.smtp {
+        tracing::info!("Emailing statements via SMTP as {}", smtp.from);
+        let notifier = payments_hex::SmtpNotifier::from_url(&smtp.url, &smtp.from)?;
+        service = service.with_notifier(Arc::new(notifier));
+    }
+    let service = Arc::new(service.build());
+
+    // The only sender of webhooks: payments store their webhook events in
+    // the same transaction, and the worker delivers them once committed
+    tokio::spawn(
+        WebhookWorker::new(webhook_repo)
+            .with_metrics(metrics.clone())
+            .with_concurrency(config.webhook_concurrency)
+            .run(),
+    );
+
+    // Optional monthly statement run
+    if let Some(interval) = config.statement_interval {
+        tracing::info!("Statement job enabled: checking every {:?}", interval);
+        StatementScheduler::new(service.clone(), interval).spawn();
+    }
+
+    // Optional exchange rate snapshots for as-of exposure reports
+    if let Some(interval) = config.rate_snapshot_interval {
+        tracing::info!("Rate snapshot job enabled: recording every {:?}", interval);
+        RateRecorder::new(service.clone(), interval).spawn();
+    }
 
     // Create and run the HTTP server
-    let server = HttpServer::new(service);
+    let server = HttpServer::new(service.clone()).with_metrics(metrics);
+    #[cfg(feature = "redis")]
+    let server = match &config.redis_url {
+        Some(url) => {
+            tracing::info!("Counting API key quotas in Redis, shared by every replica");
+            let store = payments_hex::RedisRateLimits::connect(url).await?;
+            server.with_rate_limit_store(Arc::new(store))
+        }
+        None => server,
+    };
+    if config.maintenance_mode {
+        tracing::warn!("Starting in read-only maintenance mode");
+        server.maintenance().enable(config.maintenance_reason);
+    }
+
+    // Scheduled payments, paused while in maintenance mode
+    match config.scheduled_payment_interval {
+        Some(interval) => {
+            tracing::info!("Payment scheduler enabled: checking every {:?}", interval);
+            PaymentScheduler::new(service.clone(), interval)
+                .with_maintenance(server.maintenance())
+                .spawn();
+        }
+        None => tracing::warn!("Payment scheduler disabled: scheduled payments will not run"),
+    }
+
+    // Hold expiry, paused while in maintenance mode
+    match config.hold_expiry_interval {
+        Some(interval) => {
+            tracing::info!("Hold expiry enabled: checking every {:?}", interval);
+            HoldExpirer::new(service.clone(), interval)
+                .with_maintenance(server.maintenance())
+                .spawn();
+        }
+        None => tracing::warn!("Hold expiry disabled: expired holds keep their funds reserved"),
+    }
+
+    // Optional end-of-day balance snapshots, paused while in maintenance mode
+    if let Some(interval) = config.balance_snapshot_interval {
+        tracing::info!(
+            "Balance snapshot job enabled: checking every {:?}",
+            interval
+        );
+        BalanceRecorder::new(service.clone(), interval)
+            .with_maintenance(server.maintenance())
+            .spawn();
+    }
+
+    // Optional dormant account detection, paused while in maintenance mode
+    if let Some(policy) = config.dormancy {
+        tracing::info!(
+            "Dormancy job enabled: accounts idle for {} days, checking every {:?}",
+            policy.idle_for.num_days(),
+            config.dormancy_interval
+        );
+        DormancyDetector::new(service.clone(), config.dormancy_interval)
+            .with_maintenance(server.maintenance())
+            .spawn();
+    }
+
+    // Optional storage quota checks, enabled by any quota
+    if config.storage_quotas.is_enabled() {
+        tracing::info!(
+            "Storage quota job enabled: {:?}, checking every {:?}",
+            config.storage_quotas,
+            config.storage_quota_interval
+        );
+        QuotaMonitor::new(service, config.storage_quota_interval).spawn();
+    }
+
+    // Optional gRPC API sharing the service and maintenance switch
+    #[cfg(feature = "grpc")]
+    if let Some(port) = config.grpc_port {
+        let grpc = GrpcServer::new(server.service()).with_maintenance(server.maintenance());
+        tokio::spawn(async move {
+            if let Err(e) = grpc.run(&format!("0.0.0.0:{}", port)).await {
+                tracing::error!("gRPC server failed: {}", e);
+            }
+        });
+    }
+
     let addr = format!("0.0.0.0:{}", config.port);
 
     server.run(&addr).await?;
@@ -0,0 +1,201 @@
+//! OpenAPI spec export for the client generators.
+//!
+//! `payments-server --dump-openapi <path>` writes the spec the server serves
+//! at `/api-docs/openapi.json` and exits without touching the database. The
+//! TypeScript and Python clients are generated from that file (see
+//! `scripts/common/generate_clients.sh`), so it is validated first: a missing
+//! schema or an undeclared path parameter makes the generators emit broken or
+//! silently incomplete code rather than fail.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, bail};
+use serde_json::Value;
+use utoipa::OpenApi;
+
+use payments_hex::ApiDoc;
+
+/// Command line flag that switches the binary into spec export mode.
+pub const DUMP_OPENAPI_FLAG: &str = "--dump-openapi";
+
+/// HTTP methods an OpenAPI path item can define operations for.
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// The spec as pretty-printed JSON with a trailing newline, exactly as
+/// committed to `clients/openapi.json`.
+pub fn render() -> anyhow::Result<String> {
+    let spec = serde_json::to_value(ApiDoc::openapi())?;
+    let problems = validate(&spec);
+    if !problems.is_empty() {
+        bail!("invalid OpenAPI spec:\n  {}", problems.join("\n  "));
+    }
+    Ok(format!("{}\n", serde_json::to_string_pretty(&spec)?))
+}
+
+/// Writes the validated spec to `path`, or to stdout when it is `-`.
+pub fn dump(path: &str) -> anyhow::Result<()> {
+    let spec = render()?;
+    if path == "-" {
+        print!("{}", spec);
+        return Ok(());
+    }
+    std::fs::write(Path::new(path), spec).with_context(|| format!("writing {}", path))?;
+    eprintln!("Wrote OpenAPI spec to {}", path);
+    Ok(())
+}
+
+/// Everything in `spec` a client generator would trip over, one line each;
+/// empty when the spec is usable.
+pub fn validate(spec: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return vec!["spec has no paths".to_string()];
+    };
+
+    let mut operation_ids = HashSet::new();
+    for (path, item) in paths {
+        let templated: Vec<&str> = path
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        for method in METHODS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            let label = format!("{} {}", method.to_uppercase(), path);
+
+            match operation.get("operationId").and_then(Value::as_str) {
+                Some(id) if !operation_ids.insert(id) => {
+                    problems.push(format!("{}: duplicate operationId `{}`", label, id));
+                }
+                Some(_) => {}
+                None => problems.push(format!("{}: missing operationId", label)),
+            }
+
+            let declared: HashSet<&str> = [item, operation]
+                .iter()
+                .filter_map(|node| node.get("parameters").and_then(Value::as_array))
+                .flatten()
+                .filter(|param| param.get("in").and_then(Value::as_str) == Some("path"))
+                .filter_map(|param| param.get("name").and_then(Value::as_str))
+                .collect();
+            for name in &templated {
+                if !declared.contains(name) {
+                    problems.push(format!(
+                        "{}: path parameter `{}` is not declared",
+                        label, name
+                    ));
+                }
+            }
+            for name in declared {
+                if !templated.contains(&name) {
+                    problems.push(format!(
+                        "{}: path parameter `{}` is not in the path",
+                        label, name
+                    ));
+                }
+            }
+
+            if operation
+                .get("responses")
+                .and_then(Value::as_object)
+                .is_none_or(|responses| responses.is_empty())
+            {
+                problems.push(format!("{}: no responses documented", label));
+            }
+        }
+    }
+
+    let mut refs = Vec::new();
+    collect_refs(spec, &mut refs);
+    for reference in refs {
+        let resolves = reference
+            .strip_prefix('#')
+            .and_then(|pointer| spec.pointer(pointer))
+            .is_some();
+        if !resolves {
+            problems.push(format!("unresolved $ref `{}`", reference));
+        }
+    }
+    problems
+}
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                refs.push(reference);
+            }
+            map.values().for_each(|child| collect_refs(child, refs));
+        }
+        Value::Array(items) => items.iter().for_each(|child| collect_refs(child, refs)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_served_spec_is_valid() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        assert_eq!(validate(&spec), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_committed_spec_is_up_to_date() {
+        let committed = include_str!("../../clients/openapi.json");
+        assert!(
+            render().unwrap() == committed,
+            "clients/openapi.json is stale; run \
+             `cargo run -p payments-app -- --dump-openapi clients/openapi.json`"
+        );
+    }
+
+    #[test]
+    fn test_reports_what_breaks_generators() {
+        let spec = json!({
+            "paths": {
+                "/api/accounts/{id}": {
+                    "get": {
+                        "operationId": "get_account",
+                        "responses": {
+                            "200": { "content": { "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Account" }
+                            } } }
+                        }
+                    },
+                    "delete": {
+                        "operationId": "get_account",
+                        "parameters": [{ "name": "id", "in": "path" }],
+                        "responses": { "204": {} }
+                    }
+                },
+                "/api/keys": {
+                    "post": {
+                        "parameters": [{ "name": "id", "in": "path" }],
+                        "responses": {}
+                    }
+                }
+            },
+            "components": { "schemas": {} }
+        });
+        assert_eq!(
+            validate(&spec),
+            vec![
+                "GET /api/accounts/{id}: path parameter `id` is not declared",
+                "DELETE /api/accounts/{id}: duplicate operationId `get_account`",
+                "POST /api/keys: missing operationId",
+                "POST /api/keys: path parameter `id` is not in the path",
+                "POST /api/keys: no responses documented",
+                "unresolved $ref `#/components/schemas/Account`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! `payments dashboard`: a terminal UI that polls the API.
+//!
+//! Shows accounts with their balances, the last day's transactions and how
+//! each webhook endpoint's recent deliveries went, refreshed on an interval.
+//! Each panel shows its own error, e.g. when the key lacks a scope, so the
+//! others keep updating.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Table},
+};
+
+use payments_client::{ClientError, PaymentsClient};
+use payments_types::{
+    Account, AccountId, AmountFormat, Transaction, TransactionSearchQuery, WebhookDeliveriesQuery,
+};
+
+/// Transactions shown, newest first.
+const RECENT_TRANSACTIONS: usize = 50;
+
+/// Deliveries per endpoint the webhook panel counts.
+const RECENT_DELIVERIES: usize = 50;
+
+/// How long to wait for a key press between checks for a refresh.
+const KEY_POLL: Duration = Duration::from_millis(100);
+
+/// How one webhook endpoint's recent deliveries went.
+struct EndpointStatus {
+    url: String,
+    is_active: bool,
+    delivered: usize,
+    pending: usize,
+    retrying: usize,
+    dead_lettered: usize,
+    /// Why the most recent failed delivery failed
+    last_error: Option<String>,
+}
+
+/// What the API returned on one refresh; each panel fails on its own.
+struct Snapshot {
+    accounts: Result<Vec<Account>, String>,
+    transactions: Result<Vec<Transaction>, String>,
+    webhooks: Result<Vec<EndpointStatus>, String>,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Runs the dashboard for the API at `api_url` until `q` or Esc is pressed,
+/// refreshing every `interval` and at once on `r`.
+pub async fn run(
+    client: &PaymentsClient,
+    api_url: &str,
+    interval: Duration,
+    format: &AmountFormat,
+) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run_in(&mut terminal, client, api_url, interval, format).await;
+    ratatui::restore();
+    result
+}
+
+async fn run_in(
+    terminal: &mut DefaultTerminal,
+    client: &PaymentsClient,
+    api_url: &str,
+    interval: Duration,
+    format: &AmountFormat,
+) -> Result<()> {
+    loop {
+        let snapshot = fetch(client).await;
+        terminal.draw(|frame| draw(frame, api_url, &snapshot, format))?;
+
+        let next = tokio::time::Instant::now() + interval;
+        while tokio::time::Instant::now() < next {
+            if event::poll(Duration::ZERO)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('r') => break,
+                    _ => {}
+                }
+            }
+            tokio::time::sleep(KEY_POLL).await;
+        }
+    }
+}
+
+async fn fetch(client: &PaymentsClient) -> Snapshot {
+    let since = Utc::now() - TimeDelta::days(1);
+    let search = TransactionSearchQuery {
+        limit: Some(RECENT_TRANSACTIONS),
+        from: Some(since),
+        ..Default::default()
+    };
+    let (accounts, transactions, webhooks) = tokio::join!(
+        client.list_accounts(),
+        client.search_transactions(&search),
+        webhook_statuses(client),
+    );
+    Snapshot {
+        accounts: accounts.map_err(|e| e.to_string()),
+        transactions: transactions
+            .map(|page| page.data)
+            .map_err(|e| e.to_string()),
+        webhooks: webhooks.map_err(|e| e.to_string()),
+        fetched_at: Utc::now(),
+    }
+}
+
+async fn webhook_statuses(client: &PaymentsClient) -> Result<Vec<EndpointStatus>, ClientError> {
+    let query = WebhookDeliveriesQuery {
+        limit: Some(RECENT_DELIVERIES),
+        ..Default::default()
+    };
+    let mut statuses = Vec::new();
+    for endpoint in client.list_webhooks().await? {
+        let deliveries = client.webhook_deliveries(&endpoint.id, &query).await?;
+        let count = |statuses: &[&str]| {
+            deliveries
+                .iter()
+                .filter(|d| statuses.contains(&d.status.as_str()))
+                .count()
+        };
+        statuses.push(EndpointStatus {
+            delivered: count(&["COMPLETED"]),
+            pending: count(&["PENDING", "PROCESSING"]),
+            retrying: count(&["FAILED"]),
+            dead_lettered: count(&["DEAD_LETTERED"]),
+            last_error: deliveries.iter().find_map(|d| d.last_error.clone()),
+            url: endpoint.url,
+            is_active: endpoint.is_active,
+        });
+    }
+    Ok(statuses)
+}
+
+fn draw(frame: &mut Frame, api_url: &str, snapshot: &Snapshot, format: &AmountFormat) {
+    let [header, accounts, transactions, webhooks] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Percentage(35),
+        Constraint::Percentage(40),
+        Constraint::Percentage(25),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(
+        Line::from(format!(
+            " {}  ·  updated {}  ·  r refresh  ·  q quit",
+            api_url,
+            snapshot.fetched_at.format("%H:%M:%S")
+        ))
+        .bold(),
+        header,
+    );
+    draw_accounts(frame, accounts, &snapshot.accounts, format);
+    draw_transactions(frame, transactions, &snapshot.transactions, format);
+    draw_webhooks(frame, webhooks, &snapshot.webhooks);
+}
+
+/// Draws `rows` as a table titled `title`, or the error that replaced them.
+fn draw_table<T>(
+    frame: &mut Frame,
+    area: Rect,
+    title: String,
+    data: &Result<Vec<T>, String>,
+    header: Row<'static>,
+    widths: &[Constraint],
+    row: impl Fn(&T) -> Row<'static>,
+) {
+    let block = Block::bordered().title(title);
+    match data {
+        Ok(items) => frame.render_widget(
+            Table::new(items.iter().map(row), widths.to_vec())
+                .header(header.style(Style::new().bold()))
+                .block(block),
+            area,
+        ),
+        Err(e) => frame.render_widget(
+            Paragraph::new(format!("✗ {}", e))
+                .style(Style::new().fg(Color::Red))
+                .block(block),
+            area,
+        ),
+    }
+}
+
+fn draw_accounts(
+    frame: &mut Frame,
+    area: Rect,
+    accounts: &Result<Vec<Account>, String>,
+    format: &AmountFormat,
+) {
+    let title = match accounts {
+        Ok(accounts) => format!(" Accounts ({}) ", accounts.len()),
+        Err(_) => " Accounts ".to_string(),
+    };
+    draw_table(
+        frame,
+        area,
+        title,
+        accounts,
+        Row::new(["ID", "Name", "Balance", "Held", "Currency", "Status"]),
+        &[
+            Constraint::Length(10),
+            Constraint::Fill(1),
+            Constraint::Length(16),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ],
+        |account| {
+            let currency = account.balance.currency();
+            Row::new([
+                short_id(account.id),
+                account.name.clone(),
+                format.format(account.balance.amount(), currency),
+                format.format(account.held, currency),
+                currency.to_string(),
+                account.status.to_string(),
+            ])
+        },
+    );
+}
+
+fn draw_transactions(
+    frame: &mut Frame,
+    area: Rect,
+    transactions: &Result<Vec<Transaction>, String>,
+    format: &AmountFormat,
+) {
+    draw_table(
+        frame,
+        area,
+        " Transactions, last 24 hours ".to_string(),
+        transactions,
+        Row::new(["Time", "Type", "Amount", "From", "To", "Reference"]),
+        &[
+            Constraint::Length(9),
+            Constraint::Length(12),
+            Constraint::Length(18),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Fill(1),
+        ],
+        |tx| {
+            let account = |id: Option<AccountId>| id.map_or("-".to_string(), short_id);
+            Row::new([
+                tx.created_at.format("%H:%M:%S").to_string(),
+                tx.transaction_type.to_string(),
+                format!(
+                    "{} {}",
+                    format.format(tx.amount.amount(), tx.amount.currency()),
+                    tx.amount.currency()
+                ),
+                account(tx.source_account_id),
+                account(tx.destination_account_id),
+                tx.reference.clone().unwrap_or_default(),
+            ])
+        },
+    );
+}
+
+fn draw_webhooks(frame: &mut Frame, area: Rect, webhooks: &Result<Vec<EndpointStatus>, String>) {
+    draw_table(
+        frame,
+        area,
+        format!(
+            " Webhook deliveries, last {} per endpoint ",
+            RECENT_DELIVERIES
+        ),
+        webhooks,
+        Row::new([
+            "Endpoint",
+            "Active",
+            "Delivered",
+            "Pending",
+            "Retrying",
+            "Dead",
+            "Last error",
+        ]),
+        &[
+            Constraint::Fill(2),
+            Constraint::Length(6),
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Length(8),
+            Constraint::Length(5),
+            Constraint::Fill(1),
+        ],
+        |endpoint| {
+            let row = Row::new([
+                endpoint.url.clone(),
+                if endpoint.is_active { "yes" } else { "no" }.to_string(),
+                endpoint.delivered.to_string(),
+                endpoint.pending.to_string(),
+                endpoint.retrying.to_string(),
+                endpoint.dead_lettered.to_string(),
+                endpoint.last_error.clone().unwrap_or_default(),
+            ]);
+            if endpoint.dead_lettered > 0 {
+                row.style(Style::new().fg(Color::Red))
+            } else if endpoint.retrying > 0 {
+                row.style(Style::new().fg(Color::Yellow))
+            } else {
+                row
+            }
+        },
+    );
+}
+
+/// The first eight characters of an account ID, enough to tell them apart.
+fn short_id(id: AccountId) -> String {
+    id.to_string().chars().take(8).collect()
+}
@@ -2,11 +2,28 @@
 //!
 //! Command-line interface for the Payments API.
 
+mod dashboard;
+
+use std::collections::HashMap;
+
 use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::{Parser, Subcommand};
 
 use payments_client::PaymentsClient;
-use payments_types::{AccountId, CurrencyCode};
+use payments_types::{
+    AccountId, AccountLimits, AccountRef, Alias, AmountFormat, ApiKeyScope, AuthorizeRequest,
+    BatchMode, BatchOperation, BatchRequest, BeneficiaryId, ChangeRequestId, ChangeStatus,
+    Counterparty, CreateBeneficiaryRequest, CreateQuoteRequest, CreateScheduledPaymentRequest,
+    CurrencyCode, DeadLetterQuery, DepositRequest, Diagnostics, EncryptedMemo, EventLogQuery,
+    ExportId, ExportRequest, ExportStatus, ExposureQuery, FeeScheduleId, FeeTier, FloatReportQuery,
+    HoldId, InboundPaymentRequest, JournalExportFormat, MemoKeyId, OwnerId, PaymentSchedule,
+    PositiveAmount, QuoteId, RegisterWebhookRequest, ScheduledPaymentId, ServiceHealth,
+    SettlementBatchId, SettlementBatchStatus, SettlementExportFormat, SpendingRules,
+    StatementFormat, StatementId, TransactionSearchQuery, TransactionType, TransferRequest,
+    UpdateOwnerRequest, UpdateWebhookRequest, WebhookDeliveriesQuery, WebhookEventsQuery,
+    WebhookSignatureAlgorithm, WithdrawRequest,
+};
 
 #[derive(Parser)]
 #[command(name = "payments")]
@@ -24,6 +41,14 @@ struct Cli {
     #[arg(long, env = "PAYMENTS_API_KEY")]
     api_key: Option<String>,
 
+    /// How amounts in messages are written, e.g. `decimals=0,rounding=truncate`
+    #[arg(
+        long,
+        env = "PAYMENTS_AMOUNT_FORMAT",
+        default_value = "decimals=currency"
+    )]
+    amount_format: AmountFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -45,11 +70,111 @@ enum Commands {
         #[command(subcommand)]
         action: WebhookCommands,
     },
+    /// Live and logged account and transaction events
+    #[command(alias = "events")]
+    Event {
+        #[command(subcommand)]
+        action: EventCommands,
+    },
     /// API key management
     Key {
         #[command(subcommand)]
         action: KeyCommands,
     },
+    /// Human-friendly account aliases such as `@alice-ops`
+    Alias {
+        #[command(subcommand)]
+        action: AliasCommands,
+    },
+    /// Customers that hold accounts
+    Owner {
+        #[command(subcommand)]
+        action: OwnerCommands,
+    },
+    /// Keys an account encrypts transaction memos with (metadata only)
+    MemoKey {
+        #[command(subcommand)]
+        action: MemoKeyCommands,
+    },
+    /// Fee schedules and their assignment to accounts
+    Fee {
+        #[command(subcommand)]
+        action: FeeCommands,
+    },
+    /// Settlement batches of outgoing payouts (admin key)
+    Settlement {
+        #[command(subcommand)]
+        action: SettlementCommands,
+    },
+    /// Download a period's journal entries for an accounting tool (admin key)
+    Journal {
+        /// First day of the period (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        from: String,
+        /// Last day of the period, inclusive (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        to: String,
+        /// `quickbooks` or `xero`
+        #[arg(long, default_value = "quickbooks")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Files rendered in the background and fetched through a signed link
+    /// (admin key)
+    Export {
+        #[command(subcommand)]
+        action: ExportCommands,
+    },
+    /// Monthly account statements
+    Statement {
+        #[command(subcommand)]
+        action: StatementCommands,
+    },
+    /// Treasury reports across all accounts (admin key)
+    Report {
+        #[command(subcommand)]
+        action: ReportCommands,
+    },
+    /// Recurring withdrawals and transfers
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleCommands,
+    },
+    /// Two-phase payments: authorize a hold, then capture or void it
+    Hold {
+        #[command(subcommand)]
+        action: HoldCommands,
+    },
+    /// Exchange rates locked for a later transfer
+    Quote {
+        #[command(subcommand)]
+        action: QuoteCommands,
+    },
+    /// External accounts to pay out to, verified by micro-deposits
+    Beneficiary {
+        #[command(subcommand)]
+        action: BeneficiaryCommands,
+    },
+    /// Key deletions and webhook URL changes awaiting a second admin
+    /// (admin key)
+    Change {
+        #[command(subcommand)]
+        action: ChangeCommands,
+    },
+    /// Read-only maintenance mode (admin key required to change it)
+    Maintenance {
+        #[command(subcommand)]
+        action: MaintenanceCommands,
+    },
+    /// Incident banner on the public status page (admin key)
+    Incident {
+        #[command(subcommand)]
+        action: IncidentCommands,
+    },
+    /// Print Prometheus metrics for the instance (admin key)
+    Metrics,
     /// Bootstrap the first API key
     Bootstrap {
         /// Name for the new API key
@@ -57,7 +182,23 @@ enum Commands {
         name: String,
     },
     /// Check API health
-    Health,
+    Health {
+        /// Also check readiness and show diagnostics as a table (requires
+        /// an admin key); exits non-zero when anything is degraded
+        #[arg(long, short)]
+        verbose: bool,
+    },
+    /// Show the public status page (no API key needed)
+    Status,
+    /// Show row counts against the soft storage quotas (requires an admin key)
+    Storage,
+    /// Live terminal view of accounts, recent transactions and webhook
+    /// deliveries; `q` quits, `r` refreshes
+    Dashboard {
+        /// Seconds between refreshes
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
 }
 
 #[derive(Subcommand)]
@@ -66,17 +207,112 @@ enum AccountCommands {
     Create {
         /// Account name
         name: String,
+        /// Owner (UUID) that holds the account; see `payments owner list`
+        #[arg(long)]
+        owner: String,
         /// Currency (USD, EUR, GBP, INR)
         #[arg(long, default_value = "USD")]
         currency: String,
+        /// How far below zero the balance may go, in cents
+        #[arg(long, default_value_t = 0)]
+        overdraft_limit: i64,
     },
     /// Get account details
     Get {
-        /// Account ID (UUID)
+        /// Account ID (UUID) or `@alias`
         id: String,
     },
     /// List all accounts
-    List,
+    List {
+        /// Only accounts held by this owner (UUID)
+        #[arg(long)]
+        owner: Option<String>,
+    },
+    /// Find accounts by name fragment or ID prefix
+    Search {
+        /// Name fragment (case-insensitive) or account ID prefix
+        query: String,
+        /// Maximum number of results
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Show or replace the purpose codes an account may pay out with
+    Rules {
+        /// Account ID (UUID) or `@alias`
+        id: String,
+        /// Replace the rules: purpose codes the account may use (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        allow: Option<Vec<String>>,
+        /// Replace the rules: purpose codes the account may never use (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        deny: Option<Vec<String>>,
+    },
+    /// Show or change an account's limits (amounts in cents)
+    Limits {
+        /// Account ID (UUID) or `@alias`
+        id: String,
+        /// Largest single deposit, withdrawal or outgoing transfer
+        #[arg(long)]
+        max_transaction: Option<i64>,
+        /// Largest balance the account may hold
+        #[arg(long)]
+        max_balance: Option<i64>,
+        /// Most the account may send out in any 24 hours
+        #[arg(long)]
+        daily_debit: Option<i64>,
+        /// Remove all limits before applying the options above
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Set how far below zero an account's balance may go (admin keys only)
+    Overdraft {
+        /// Account ID (UUID) or `@alias`
+        id: String,
+        /// Overdraft limit in cents; 0 allows no overdraft
+        limit: i64,
+    },
+    /// Reactivate a dormant account (admin keys only)
+    Reactivate {
+        /// Account ID (UUID) or `@alias`
+        id: String,
+    },
+    /// Stop money leaving an account (admin keys only)
+    Freeze {
+        /// Account ID (UUID) or `@alias`
+        id: String,
+    },
+    /// Return a frozen account to active use (admin keys only)
+    Unfreeze {
+        /// Account ID (UUID) or `@alias`
+        id: String,
+    },
+    /// Close an account with a zero balance for good (admin keys only)
+    Close {
+        /// Account ID (UUID) or `@alias`
+        id: String,
+    },
+    /// Merge a duplicate account into another, moving its balance over and
+    /// closing it (admin keys only)
+    Merge {
+        /// Account ID (UUID) or `@alias` of the duplicate
+        id: String,
+        /// Account ID (UUID) or `@alias` of the account to keep
+        #[arg(long)]
+        into: String,
+    },
+    /// Show an account's end-of-day balances
+    Balances {
+        /// Account ID (UUID) or `@alias`
+        id: String,
+        /// First day (YYYY-MM-DD, UTC); defaults to 30 days before `--to`
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day, inclusive (YYYY-MM-DD, UTC); defaults to yesterday
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Record end-of-day balances for all accounts now (admin keys only)
+    RecordBalances,
 }
 
 #[derive(Subcommand)]
@@ -86,26 +322,59 @@ enum TransactionCommands {
         #[arg(long)]
         account: String,
         #[arg(long)]
-        amount: i64,
+        amount: PositiveAmount,
         #[arg(long, default_value = "USD")]
         currency: String,
         #[arg(long)]
         idempotency_key: Option<String>,
         #[arg(long)]
         reference: Option<String>,
+        /// Name of the payer
+        #[arg(long)]
+        counterparty_name: Option<String>,
+        /// External identifier of the counterparty (IBAN, account number, ...)
+        #[arg(long, requires = "counterparty_name")]
+        counterparty_id: Option<String>,
+        /// Metadata to store with the transaction (repeatable)
+        #[arg(long = "metadata", value_name = "KEY=VALUE")]
+        metadata: Vec<String>,
+        /// Memo key (UUID) the memo is encrypted with
+        #[arg(long, requires = "memo")]
+        memo_key: Option<String>,
+        /// Encrypted memo, standard base64
+        #[arg(long, requires = "memo_key")]
+        memo: Option<String>,
     },
     /// Withdraw funds from an account
     Withdraw {
         #[arg(long)]
         account: String,
         #[arg(long)]
-        amount: i64,
+        amount: PositiveAmount,
         #[arg(long, default_value = "USD")]
         currency: String,
         #[arg(long)]
         idempotency_key: Option<String>,
         #[arg(long)]
         reference: Option<String>,
+        /// Name of the payee
+        #[arg(long)]
+        counterparty_name: Option<String>,
+        /// External identifier of the counterparty (IBAN, account number, ...)
+        #[arg(long, requires = "counterparty_name")]
+        counterparty_id: Option<String>,
+        /// What the payment is for (checked against the account's spending rules)
+        #[arg(long)]
+        purpose: Option<String>,
+        /// Metadata to store with the transaction (repeatable)
+        #[arg(long = "metadata", value_name = "KEY=VALUE")]
+        metadata: Vec<String>,
+        /// Memo key (UUID) the memo is encrypted with
+        #[arg(long, requires = "memo")]
+        memo_key: Option<String>,
+        /// Encrypted memo, standard base64
+        #[arg(long, requires = "memo_key")]
+        memo: Option<String>,
     },
     /// Transfer funds between accounts
     Transfer {
@@ -114,13 +383,100 @@ enum TransactionCommands {
         #[arg(long)]
         to: String,
         #[arg(long)]
-        amount: i64,
+        amount: PositiveAmount,
         #[arg(long, default_value = "USD")]
         currency: String,
         #[arg(long)]
         idempotency_key: Option<String>,
         #[arg(long)]
         reference: Option<String>,
+        /// What the payment is for (checked against the source account's spending rules)
+        #[arg(long)]
+        purpose: Option<String>,
+        /// Convert at the current exchange rate if `--to` holds another currency
+        #[arg(long)]
+        convert: bool,
+        /// Convert at the rate locked by this quote (UUID) instead
+        #[arg(long)]
+        quote: Option<String>,
+        /// Metadata to store with the transaction (repeatable)
+        #[arg(long = "metadata", value_name = "KEY=VALUE")]
+        metadata: Vec<String>,
+        /// Memo key (UUID) the memo is encrypted with
+        #[arg(long, requires = "memo")]
+        memo_key: Option<String>,
+        /// Encrypted memo, standard base64
+        #[arg(long, requires = "memo_key")]
+        memo: Option<String>,
+    },
+    /// Make many payments from a JSON file of operations
+    Batch {
+        /// JSON array of operations, each a deposit, withdraw or transfer
+        /// request with `"type"` set to which it is
+        #[arg(long)]
+        file: std::path::PathBuf,
+        /// `atomic` (all or nothing) or `best-effort`
+        #[arg(long, default_value = "atomic")]
+        mode: String,
+    },
+    /// Record a payment received over external rails (needs an integration
+    /// key with the inbound:write scope)
+    Inbound {
+        #[arg(long)]
+        account: String,
+        #[arg(long)]
+        amount: PositiveAmount,
+        #[arg(long, default_value = "USD")]
+        currency: String,
+        /// The rail's unique ID for the payment; reporting it again credits nothing
+        #[arg(long)]
+        external_reference: String,
+        /// Remittance information sent with the payment
+        #[arg(long)]
+        reference: Option<String>,
+        /// Name of the payer
+        #[arg(long)]
+        counterparty_name: Option<String>,
+        /// External identifier of the counterparty (IBAN, account number, ...)
+        #[arg(long, requires = "counterparty_name")]
+        counterparty_id: Option<String>,
+    },
+    /// Search transactions across accounts, newest first
+    Search {
+        /// Case-insensitive fragment of the reference
+        #[arg(long)]
+        reference: Option<String>,
+        /// Sent from or to this account (ID or @alias)
+        #[arg(long)]
+        account: Option<String>,
+        /// deposit, withdrawal or transfer
+        #[arg(long = "type")]
+        transaction_type: Option<String>,
+        #[arg(long)]
+        currency: Option<String>,
+        /// Smallest amount, in minor units
+        #[arg(long)]
+        min_amount: Option<i64>,
+        /// Largest amount, in minor units
+        #[arg(long)]
+        max_amount: Option<i64>,
+        /// Carrying this metadata key
+        #[arg(long)]
+        metadata_key: Option<String>,
+        /// With this value under `--metadata-key`
+        #[arg(long, requires = "metadata_key")]
+        metadata_value: Option<String>,
+        /// Created at or after this time (RFC 3339)
+        #[arg(long)]
+        from: Option<String>,
+        /// Created before this time (RFC 3339)
+        #[arg(long)]
+        to: Option<String>,
+        #[arg(long)]
+        limit: Option<usize>,
+        /// `next_cursor` of the previous page
+        #[arg(long)]
+        cursor: Option<String>,
     },
 }
 
@@ -131,12 +487,132 @@ enum WebhookCommands {
         /// URL to receive webhooks
         #[arg(long)]
         url: String,
-        /// Event types to subscribe to (comma-separated)
+        /// Event types to subscribe to (comma-separated); `*` or a prefix such
+        /// as `hold.*` matches several, and none means all events
         #[arg(long, value_delimiter = ',', default_value = "")]
         events: Vec<String>,
+        /// Give up on a delivery after this many milliseconds (default 10000)
+        #[arg(long)]
+        timeout_ms: Option<u32>,
+        /// Give up on connecting after this many milliseconds (default 3000)
+        #[arg(long)]
+        connect_timeout_ms: Option<u32>,
+        /// Sign deliveries with hmac-sha256 (default), hmac-sha512 or ed25519
+        #[arg(long)]
+        signature_algorithm: Option<String>,
+    },
+    /// Register several webhook endpoints from a JSON file, all or none
+    RegisterBatch {
+        /// JSON array of endpoints, each shaped like a `register` request:
+        /// `url`, `events`, `timeout_ms`, `connect_timeout_ms` and
+        /// `signature_algorithm`
+        #[arg(long)]
+        file: std::path::PathBuf,
     },
     /// List registered webhook endpoints
     List,
+    /// Show a webhook event with its full payload
+    Event {
+        /// Webhook event ID (UUID)
+        #[arg(long)]
+        id: String,
+    },
+    /// Export dead-lettered events with their payloads
+    DeadLetters {
+        /// Only events queued for this endpoint (UUID)
+        #[arg(long)]
+        endpoint: Option<String>,
+        /// Only events of this type, e.g. deposit.success
+        #[arg(long)]
+        event_type: Option<String>,
+        /// Only events dead-lettered at or after this time (RFC 3339)
+        #[arg(long)]
+        failed_since: Option<String>,
+    },
+    /// Requeue dead-lettered events for delivery; with no filter, every one
+    Retry {
+        /// Only events queued for this endpoint (UUID)
+        #[arg(long)]
+        endpoint: Option<String>,
+        /// Only events of this type, e.g. deposit.success
+        #[arg(long)]
+        event_type: Option<String>,
+        /// Only events dead-lettered at or after this time (RFC 3339)
+        #[arg(long)]
+        failed_since: Option<String>,
+    },
+    /// List an endpoint's events after a delivery sequence, to catch up on
+    /// missed deliveries
+    Events {
+        /// Webhook endpoint ID (UUID)
+        #[arg(long)]
+        id: String,
+        /// Last delivery sequence already received
+        #[arg(long, default_value = "0")]
+        after_sequence: i64,
+        /// Maximum number of events (default 100)
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Show an endpoint's delivery log, most recent first
+    Deliveries {
+        /// Webhook endpoint ID (UUID)
+        #[arg(long)]
+        id: String,
+        /// Only events in this state, e.g. FAILED or DEAD_LETTERED
+        #[arg(long)]
+        status: Option<String>,
+        /// Only events older than this delivery sequence, to page back
+        #[arg(long)]
+        before_sequence: Option<i64>,
+        /// Maximum number of events (default 100)
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Request a new URL for an endpoint (applied once another admin approves)
+    SetUrl {
+        /// Webhook endpoint ID (UUID)
+        #[arg(long)]
+        id: String,
+        /// New URL to receive webhooks
+        #[arg(long)]
+        url: String,
+    },
+    /// Change an endpoint's subscriptions, pause/resume its deliveries or
+    /// change how they are signed
+    Update {
+        /// Webhook endpoint ID (UUID)
+        #[arg(long)]
+        id: String,
+        /// Replace the subscribed event types (comma-separated); an empty
+        /// value subscribes to all events
+        #[arg(long, value_delimiter = ',')]
+        events: Option<Vec<String>>,
+        /// `false` pauses deliveries, `true` resumes them
+        #[arg(long)]
+        active: Option<bool>,
+        /// Sign deliveries with hmac-sha256, hmac-sha512 or ed25519
+        #[arg(long)]
+        signature_algorithm: Option<String>,
+    },
+    /// Give an endpoint a new signing secret
+    RotateSecret {
+        /// Webhook endpoint ID (UUID)
+        #[arg(long)]
+        id: String,
+    },
+    /// Delete an endpoint; its past events stay readable
+    Delete {
+        /// Webhook endpoint ID (UUID)
+        #[arg(long)]
+        id: String,
+    },
+    /// Requeue one failed or dead-lettered event for delivery
+    Redeliver {
+        /// Webhook event ID (UUID)
+        #[arg(long)]
+        id: String,
+    },
     /// Start a local webhook listener
     Listen {
         /// Port to listen on
@@ -145,6 +621,31 @@ enum WebhookCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum EventCommands {
+    /// Print events as they happen until interrupted
+    Tail {
+        /// Event types to show (comma-separated); `*` or a prefix such as
+        /// `deposit.*` matches several, and none means all events
+        #[arg(long, value_delimiter = ',')]
+        types: Vec<String>,
+        /// Print each event as a line of JSON, e.g. for `jq`
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export new events from the event log as newline-delimited JSON under
+    /// `<DIR>/day=YYYY-MM-DD/`, resuming from `<DIR>/cursor` (admin key)
+    Export {
+        /// Directory to write to; run again with the same one to export only
+        /// what was logged since
+        #[arg(long)]
+        dir: std::path::PathBuf,
+        /// Maximum events per file
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+}
+
 #[derive(Subcommand)]
 enum KeyCommands {
     /// Create a new API key
@@ -152,69 +653,1064 @@ enum KeyCommands {
         /// Name for the new key
         #[arg(long)]
         name: String,
+        /// Restrict the key to this account (UUID or `@alias`); omit for an admin key
+        #[arg(long, conflicts_with = "owner")]
+        account: Option<String>,
+        /// Restrict the key to the accounts this owner (UUID) holds
+        #[arg(long)]
+        owner: Option<String>,
+        /// Scopes to grant (comma-separated, e.g. `accounts:read,transactions:write`);
+        /// omit to grant every scope your key holds, or only the read scopes
+        /// to an account, owner or test key
+        #[arg(long, value_delimiter = ',')]
+        scopes: Option<Vec<ApiKeyScope>>,
+        /// Create an admin key in test mode (`sk_test_...`), which only sees
+        /// test accounts
+        #[arg(long, conflicts_with_all = ["account", "owner"])]
+        test: bool,
     },
     /// List all API keys
     List,
-    /// Delete (deactivate) an API key
+    /// Request that an API key be deleted (applied once another admin approves)
     Delete {
         /// API key ID (UUID)
         #[arg(long)]
         id: String,
     },
+    /// Show request and money-movement totals for an API key
+    Usage {
+        /// API key ID (UUID)
+        #[arg(long)]
+        id: String,
+    },
+    /// Show when an API key was created, used and deleted, and by whom
+    Audit {
+        /// API key ID (UUID)
+        #[arg(long)]
+        id: String,
+    },
+    /// Give an API key its own request quota, or clear it to use the default
+    RateLimit {
+        /// API key ID (UUID)
+        #[arg(long)]
+        id: String,
+        /// Requests allowed per minute; omit to go back to the server default
+        #[arg(long)]
+        per_minute: Option<u32>,
+    },
+    /// Issue a short-lived token that can only read one account, for dashboards
+    Session {
+        /// Account the token may read (UUID or `@alias`)
+        #[arg(long)]
+        account: String,
+        /// Seconds until the token expires (default 900, at most 3600)
+        #[arg(long)]
+        expires_in: Option<u64>,
+    },
 }
 
-fn parse_currency(s: &str) -> Result<CurrencyCode> {
-    match s.to_uppercase().as_str() {
-        "USD" => Ok(CurrencyCode::USD),
-        "EUR" => Ok(CurrencyCode::EUR),
-        "GBP" => Ok(CurrencyCode::GBP),
-        "INR" => Ok(CurrencyCode::INR),
-        _ => anyhow::bail!("Unknown currency: {}. Supported: USD, EUR, GBP, INR", s),
-    }
+#[derive(Subcommand)]
+enum AliasCommands {
+    /// Register an alias to an account
+    Add {
+        /// Account ID (UUID) or one of its aliases
+        account: String,
+        /// New alias, e.g. `@alice-ops`
+        alias: String,
+    },
+    /// List an account's aliases
+    List {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+    },
+    /// Remove an alias from an account
+    Remove {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+        /// Alias to remove
+        alias: String,
+    },
+    /// Show which account an alias belongs to
+    Resolve {
+        /// Alias to look up
+        alias: String,
+    },
 }
 
-fn parse_account_id(s: &str) -> Result<AccountId> {
-    s.parse()
-        .map_err(|_| anyhow::anyhow!("Invalid account ID: {}", s))
+#[derive(Subcommand)]
+enum OwnerCommands {
+    /// Create an owner (admin key)
+    Create {
+        /// Person or organization name
+        name: String,
+        /// Contact address
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// List every owner (admin key)
+    List,
+    /// Show an owner
+    Get {
+        /// Owner ID (UUID)
+        id: String,
+    },
+    /// Rename an owner or change its email (admin key)
+    Update {
+        /// Owner ID (UUID)
+        id: String,
+        /// New name
+        #[arg(long)]
+        name: Option<String>,
+        /// New contact address; an empty one clears it
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// Delete an owner that holds no accounts (admin key)
+    Delete {
+        /// Owner ID (UUID)
+        id: String,
+    },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    dotenvy::dotenv().ok();
-
-    let cli = Cli::parse();
+#[derive(Subcommand)]
+enum MemoKeyCommands {
+    /// Register a memo key's metadata to an account
+    Add {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+        /// Cipher the memos are encrypted with, e.g. `xchacha20poly1305`
+        #[arg(long)]
+        algorithm: String,
+        /// Your fingerprint of the key, e.g. `sha256:9f86d081884c7d65`
+        #[arg(long)]
+        fingerprint: String,
+    },
+    /// List an account's memo keys
+    List {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+    },
+    /// Retire a memo key so new memos can no longer use it
+    Retire {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+        /// Memo key ID (UUID)
+        key_id: String,
+    },
+}
 
-    let mut client = PaymentsClient::new(&cli.api_url);
-    if let Some(key) = cli.api_key {
-        client = client.with_api_key(key);
-    }
+#[derive(Subcommand)]
+enum FeeCommands {
+    /// Create a fee schedule (admin key)
+    Create {
+        /// Schedule name
+        #[arg(long)]
+        name: String,
+        /// Tier as comma-separated key=value pairs, in ascending order, e.g.
+        /// `up_to=10000,flat=25` then `bps=150,min=50,max=2500` (amounts in cents)
+        #[arg(long = "tier", required = true)]
+        tiers: Vec<String>,
+    },
+    /// List fee schedules
+    List,
+    /// Show an account's current fee schedule and assignment history
+    Show {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+    },
+    /// Apply a fee schedule to an account (admin key)
+    Assign {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+        /// Fee schedule ID (UUID)
+        #[arg(long)]
+        schedule: String,
+        /// When the schedule starts to apply (RFC 3339); defaults to now
+        #[arg(long)]
+        from: Option<String>,
+    },
+    /// Quote the fee for a payment from an account
+    Quote {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+        /// Payment amount in cents
+        #[arg(long)]
+        amount: PositiveAmount,
+    },
+}
 
-    match cli.command {
-        Commands::Health => {
-            let healthy = client.health().await?;
-            if healthy {
-                println!("✓ API is healthy");
-            } else {
-                println!("✗ API is not healthy");
-                std::process::exit(1);
-            }
-        }
+#[derive(Subcommand)]
+enum SettlementCommands {
+    /// Batch every unbatched payout made up to the cut-off
+    Create {
+        /// Latest withdrawal time to include (RFC 3339); defaults to now
+        #[arg(long)]
+        cutoff: Option<String>,
+    },
+    /// List settlement batches
+    List,
+    /// Show a settlement batch and its totals
+    Show {
+        /// Settlement batch ID (UUID)
+        batch: String,
+    },
+    /// Mark a batch as sent to the bank
+    Submit {
+        /// Settlement batch ID (UUID)
+        batch: String,
+    },
+    /// Mark a submitted batch as confirmed by the bank
+    Settle {
+        /// Settlement batch ID (UUID)
+        batch: String,
+    },
+    /// Cancel a batch, releasing its payouts into the next one
+    Cancel {
+        /// Settlement batch ID (UUID)
+        batch: String,
+    },
+    /// Download the batch file for the bank
+    Export {
+        /// Settlement batch ID (UUID)
+        batch: String,
+        /// `csv` or `pain001`
+        #[arg(long, default_value = "csv")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportCommands {
+    /// Render a period's journal entries and download the file when ready
+    Journal {
+        /// First day of the period (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        from: String,
+        /// Last day of the period, inclusive (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        to: String,
+        /// `quickbooks` or `xero`
+        #[arg(long, default_value = "quickbooks")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Render a settlement batch file and download it when ready
+    Settlement {
+        /// Settlement batch ID (UUID)
+        batch: String,
+        /// `csv` or `pain001`
+        #[arg(long, default_value = "csv")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Show an export and, once ready, a fresh download link
+    Show {
+        /// Export ID (UUID)
+        export: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportCommands {
+    /// Balances per currency, converted into a base currency
+    Exposure {
+        /// Currency to convert into
+        #[arg(long, default_value = "USD")]
+        base: String,
+        /// Use the rates recorded at this time (RFC 3339) instead of current
+        /// rates
+        #[arg(long)]
+        as_of: Option<String>,
+    },
+    /// The float per currency and money in and out of the ledger per day
+    Float {
+        /// First day (YYYY-MM-DD, UTC); defaults to 30 days before `--to`
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day, inclusive (YYYY-MM-DD, UTC); defaults to today
+        #[arg(long)]
+        to: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScheduleCommands {
+    /// Withdraw from `--from` on a schedule, or transfer to `--to` if given
+    Create {
+        #[arg(long)]
+        from: String,
+        #[arg(long)]
+        to: Option<String>,
+        #[arg(long)]
+        amount: PositiveAmount,
+        #[arg(long, default_value = "USD")]
+        currency: String,
+        #[arg(long)]
+        reference: Option<String>,
+        /// What the payment is for (checked against the source account's spending rules)
+        #[arg(long)]
+        purpose: Option<String>,
+        /// Repeat every this many seconds
+        #[arg(long, conflicts_with = "cron", required_unless_present = "cron")]
+        every: Option<u64>,
+        /// Five-field cron expression, e.g. "0 9 * * MON-FRI"
+        #[arg(long)]
+        cron: Option<String>,
+        /// IANA time zone the cron expression is read in
+        #[arg(long, default_value = "UTC", requires = "cron")]
+        timezone: String,
+        /// Earliest first run (RFC 3339); defaults to now
+        #[arg(long)]
+        start_at: Option<String>,
+    },
+    /// List scheduled payments
+    List {
+        /// Only payments drawing on this account
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Show a scheduled payment and its last run
+    Show {
+        /// Scheduled payment ID (UUID)
+        id: String,
+    },
+    /// Stop a scheduled payment from running
+    Pause {
+        /// Scheduled payment ID (UUID)
+        id: String,
+    },
+    /// Let a paused payment run again from its next occurrence
+    Resume {
+        /// Scheduled payment ID (UUID)
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum HoldCommands {
+    /// Reserve funds on an account without moving them
+    Authorize {
+        #[arg(long)]
+        account: String,
+        #[arg(long)]
+        amount: PositiveAmount,
+        #[arg(long, default_value = "USD")]
+        currency: String,
+        #[arg(long)]
+        reference: Option<String>,
+        /// What the payment is for (checked against the account's spending rules)
+        #[arg(long)]
+        purpose: Option<String>,
+        /// Release the hold after this many seconds (default 7 days)
+        #[arg(long)]
+        expires_in: Option<u64>,
+    },
+    /// Withdraw a hold's funds
+    Capture {
+        /// Hold ID (UUID)
+        id: String,
+        /// Amount to withdraw (default: the whole hold)
+        #[arg(long)]
+        amount: Option<PositiveAmount>,
+    },
+    /// Release a hold without moving funds
+    Void {
+        /// Hold ID (UUID)
+        id: String,
+    },
+    /// Show a hold
+    Show {
+        /// Hold ID (UUID)
+        id: String,
+    },
+    /// List an account's holds, newest first
+    List {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum QuoteCommands {
+    /// Lock the current rate for converting an amount
+    Create {
+        #[arg(long)]
+        from: String,
+        #[arg(long)]
+        to: String,
+        /// Amount to convert, in minor units of `--from`
+        #[arg(long)]
+        amount: PositiveAmount,
+        /// Hold the rate for this many seconds (default 60)
+        #[arg(long)]
+        expires_in: Option<u64>,
+    },
+    /// Show a quote
+    Show {
+        /// Quote ID (UUID)
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum BeneficiaryCommands {
+    /// Add a beneficiary and send it two micro-deposits to confirm
+    Add {
+        /// Account that pays the beneficiary (UUID or `@alias`)
+        #[arg(long)]
+        account: String,
+        #[arg(long)]
+        name: String,
+        /// Account at the external institution (IBAN, account number, ...)
+        #[arg(long)]
+        external_id: String,
+    },
+    /// Confirm the two micro-deposit amounts, in minor units
+    Verify {
+        /// Beneficiary ID (UUID)
+        id: String,
+        #[arg(long, required = true, num_args = 2, value_names = ["FIRST", "SECOND"])]
+        amounts: Vec<i64>,
+    },
+    /// Show a beneficiary
+    Show {
+        /// Beneficiary ID (UUID)
+        id: String,
+    },
+    /// List an account's beneficiaries, newest first
+    List {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum StatementCommands {
+    /// Show or change where an account's statements are emailed
+    Email {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+        /// Email statements to this address
+        #[arg(long, conflicts_with = "clear")]
+        set: Option<String>,
+        /// Stop emailing statements; they are announced by webhook instead
+        #[arg(long)]
+        clear: bool,
+    },
+    /// List an account's statements
+    List {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+    },
+    /// Show a statement and a fresh download link
+    Show {
+        /// Statement ID (UUID)
+        statement: String,
+    },
+    /// Issue statements for a month (admin key)
+    Issue {
+        /// Month to issue (YYYY-MM); defaults to the last full month
+        #[arg(long)]
+        month: Option<String>,
+    },
+    /// Generate a statement for any range of days and save it
+    Export {
+        /// Account ID (UUID) or `@alias`
+        account: String,
+        /// First day (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        from: String,
+        /// Last day, inclusive (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        to: String,
+        /// `csv` or `json`
+        #[arg(long, default_value = "csv")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Download a statement file
+    Download {
+        /// Statement ID (UUID)
+        statement: String,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ChangeCommands {
+    /// List changes, newest first
+    List {
+        /// Only changes in this state (`PENDING`, `APPROVED` or `REJECTED`)
+        #[arg(long)]
+        status: Option<ChangeStatus>,
+    },
+    /// Show a change
+    Show {
+        /// Change request ID (UUID)
+        id: String,
+    },
+    /// Approve and apply a change requested by another admin key
+    Approve {
+        /// Change request ID (UUID)
+        id: String,
+    },
+    /// Turn down a change without applying it
+    Reject {
+        /// Change request ID (UUID)
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MaintenanceCommands {
+    /// Show whether maintenance mode is on
+    Status,
+    /// Reject all mutations until disabled
+    Enable {
+        /// Reason returned to rejected callers
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Accept mutations again
+    Disable,
+}
+
+#[derive(Subcommand)]
+enum IncidentCommands {
+    /// Show a banner on the status page
+    Post {
+        /// Text integrators see, e.g. "Deposits are delayed"
+        message: String,
+    },
+    /// Remove the banner
+    Clear,
+}
+
+/// The `health --verbose` table rows for `diagnostics`.
+fn diagnostics_rows(diagnostics: &Diagnostics) -> Vec<(&'static str, String)> {
+    vec![
+        (
+            "database latency",
+            diagnostics
+                .database_latency_ms
+                .map_or("unreachable".to_string(), |ms| format!("{:.1} ms", ms)),
+        ),
+        (
+            "schema version",
+            format!("{:04}", diagnostics.schema_version),
+        ),
+        (
+            "webhook backlog",
+            diagnostics
+                .webhook_backlog
+                .map_or("unknown".to_string(), |n| format!("{} events", n)),
+        ),
+        (
+            "rate limit usage",
+            format!("{:.0}%", diagnostics.rate_limit_saturation * 100.0),
+        ),
+        (
+            "status",
+            match diagnostics.status {
+                ServiceHealth::Operational => "operational",
+                ServiceHealth::Degraded => "degraded",
+                ServiceHealth::Maintenance => "maintenance",
+            }
+            .to_string(),
+        ),
+    ]
+}
+
+fn parse_currency(s: &str) -> Result<CurrencyCode> {
+    match s.to_uppercase().as_str() {
+        "USD" => Ok(CurrencyCode::USD),
+        "EUR" => Ok(CurrencyCode::EUR),
+        "GBP" => Ok(CurrencyCode::GBP),
+        "INR" => Ok(CurrencyCode::INR),
+        _ => anyhow::bail!("Unknown currency: {}. Supported: USD, EUR, GBP, INR", s),
+    }
+}
+
+fn parse_account_ref(s: &str) -> Result<AccountRef> {
+    Ok(s.parse::<AccountRef>()?)
+}
+
+fn parse_transaction_type(s: &str) -> Result<TransactionType> {
+    match s.to_lowercase().as_str() {
+        "deposit" => Ok(TransactionType::Deposit),
+        "withdrawal" => Ok(TransactionType::Withdrawal),
+        "transfer" => Ok(TransactionType::Transfer),
+        _ => anyhow::bail!(
+            "Invalid transaction type: {} (use deposit, withdrawal or transfer)",
+            s
+        ),
+    }
+}
+
+fn parse_signature_algorithm(s: &str) -> Result<WebhookSignatureAlgorithm> {
+    Ok(s.parse::<WebhookSignatureAlgorithm>()?)
+}
+
+fn parse_batch_mode(s: &str) -> Result<BatchMode> {
+    match s.to_ascii_lowercase().replace('-', "_").as_str() {
+        "atomic" => Ok(BatchMode::Atomic),
+        "best_effort" => Ok(BatchMode::BestEffort),
+        _ => anyhow::bail!("Unknown mode: {}. Supported: atomic, best-effort", s),
+    }
+}
+
+fn parse_alias(s: &str) -> Result<Alias> {
+    Ok(Alias::parse(s)?)
+}
+
+/// Resolves an account ID or `@alias` to the account ID.
+async fn resolve_account_id(client: &PaymentsClient, s: &str) -> Result<AccountId> {
+    Ok(client.resolve_account(&parse_account_ref(s)?).await?)
+}
+
+fn parse_settlement_batch_id(s: &str) -> Result<SettlementBatchId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid settlement batch ID: {}", s))
+}
+
+fn parse_export_id(s: &str) -> Result<ExportId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid export ID: {}", s))
+}
+
+fn parse_journal_format(s: &str) -> Result<JournalExportFormat> {
+    match s.to_ascii_lowercase().as_str() {
+        "quickbooks" | "qbo" => Ok(JournalExportFormat::Quickbooks),
+        "xero" => Ok(JournalExportFormat::Xero),
+        _ => anyhow::bail!("Unknown format: {}. Supported: quickbooks, xero", s),
+    }
+}
+
+fn parse_settlement_format(s: &str) -> Result<SettlementExportFormat> {
+    match s.to_ascii_lowercase().as_str() {
+        "csv" => Ok(SettlementExportFormat::Csv),
+        "pain001" | "pain.001" => Ok(SettlementExportFormat::Pain001),
+        _ => anyhow::bail!("Unknown format: {}. Supported: csv, pain001", s),
+    }
+}
+
+fn parse_statement_format(s: &str) -> Result<StatementFormat> {
+    match s.to_ascii_lowercase().as_str() {
+        "csv" => Ok(StatementFormat::Csv),
+        "json" => Ok(StatementFormat::Json),
+        _ => anyhow::bail!("Unknown format: {}. Supported: csv, json", s),
+    }
+}
+
+/// Queues an export, waits for it to render and downloads the file.
+async fn run_export(client: &PaymentsClient, request: &ExportRequest) -> Result<String> {
+    let export = client.create_export(request).await?;
+    eprintln!("Export {} queued, waiting for it to render...", export.id);
+    loop {
+        let download = client.get_export(export.id).await?;
+        match download.export.status {
+            ExportStatus::Pending => tokio::time::sleep(std::time::Duration::from_secs(1)).await,
+            ExportStatus::Ready => {
+                let url = download
+                    .download_url
+                    .ok_or_else(|| anyhow::anyhow!("Export {} has no download link", export.id))?;
+                return Ok(client.download_export(&url).await?);
+            }
+            ExportStatus::Failed => anyhow::bail!(
+                "Export {} failed: {}",
+                export.id,
+                download.export.error.unwrap_or_default()
+            ),
+        }
+    }
+}
+
+fn write_output(output: Option<std::path::PathBuf>, file: &str) -> Result<()> {
+    match output {
+        Some(path) => {
+            std::fs::write(&path, file)?;
+            println!("✓ Written to {}", path.display());
+        }
+        None => print!("{}", file),
+    }
+    Ok(())
+}
+
+/// Writes each page of new events to its day's directory under `dir`, then
+/// records the page's cursor, so an interrupted export resumes where it
+/// stopped and at worst rewrites the last file.
+async fn export_event_log(
+    client: &PaymentsClient,
+    dir: &std::path::Path,
+    limit: Option<usize>,
+) -> Result<()> {
+    let cursor_path = dir.join("cursor");
+    let mut query = EventLogQuery {
+        after_sequence: match std::fs::read_to_string(&cursor_path) {
+            Ok(cursor) => cursor.trim().parse().map_err(|_| {
+                anyhow::anyhow!("Invalid cursor in {}: {}", cursor_path.display(), cursor)
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        },
+        limit,
+    };
+    let mut exported = 0;
+    loop {
+        let page = client.event_log(&query).await?;
+        let (Some(day), Some(first)) = (page.day, page.events.first()) else {
+            break;
+        };
+        let mut lines = String::new();
+        for event in &page.events {
+            lines.push_str(&serde_json::to_string(event)?);
+            lines.push('\n');
+        }
+        let partition = dir.join(format!("day={}", day));
+        std::fs::create_dir_all(&partition)?;
+        let path = partition.join(format!("events-{}-{}.ndjson", first.sequence, page.cursor));
+        std::fs::write(&path, lines)?;
+        std::fs::write(&cursor_path, page.cursor.to_string())?;
+        println!(
+            "✓ {} events written to {}",
+            page.events.len(),
+            path.display()
+        );
+        exported += page.events.len();
+        query.after_sequence = page.cursor;
+    }
+    println!(
+        "✓ Exported {} events; the log is exported up to sequence {}",
+        exported, query.after_sequence
+    );
+    Ok(())
+}
+
+fn parse_scheduled_payment_id(s: &str) -> Result<ScheduledPaymentId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid scheduled payment ID: {}", s))
+}
+
+fn parse_hold_id(s: &str) -> Result<HoldId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid hold ID: {}", s))
+}
+
+fn parse_quote_id(s: &str) -> Result<QuoteId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid quote ID: {}", s))
+}
+
+fn parse_beneficiary_id(s: &str) -> Result<BeneficiaryId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid beneficiary ID: {}", s))
+}
+
+fn parse_change_id(s: &str) -> Result<ChangeRequestId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid change request ID: {}", s))
+}
+
+fn parse_statement_id(s: &str) -> Result<StatementId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid statement ID: {}", s))
+}
+
+/// Parses the RFC 3339 value of option `--{flag}`.
+fn parse_timestamp(flag: &str, s: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| anyhow::anyhow!("Invalid --{} '{}': {}", flag, s, e))
+}
+
+/// Builds the dead-letter filter shared by `webhook dead-letters` and
+/// `webhook retry`.
+fn dead_letter_query(
+    endpoint: Option<String>,
+    event_type: Option<String>,
+    failed_since: Option<String>,
+) -> Result<DeadLetterQuery> {
+    Ok(DeadLetterQuery {
+        endpoint_id: endpoint
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid webhook endpoint ID: {}", s))
+            })
+            .transpose()?,
+        event_type,
+        failed_since: failed_since
+            .map(|s| parse_timestamp("failed-since", &s))
+            .transpose()?,
+    })
+}
+
+fn parse_date(flag: &str, s: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|e| anyhow::anyhow!("Invalid --{} '{}': {}", flag, s, e))
+}
+
+/// Parses `--metadata` flags, each `key=value`.
+fn parse_metadata(pairs: &[String]) -> Result<HashMap<String, String>> {
+    pairs
+        .iter()
+        .map(|pair| {
+            pair.split_once('=')
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .ok_or_else(|| anyhow::anyhow!("Invalid metadata '{}': expected key=value", pair))
+        })
+        .collect()
+}
+
+/// Builds the memo of `--memo-key` and `--memo`, which clap requires together.
+fn parse_memo(key_id: Option<String>, ciphertext: Option<String>) -> Result<Option<EncryptedMemo>> {
+    match (key_id, ciphertext) {
+        (Some(key_id), Some(ciphertext)) => Ok(Some(EncryptedMemo {
+            key_id: parse_memo_key_id(&key_id)?,
+            ciphertext,
+        })),
+        _ => Ok(None),
+    }
+}
+
+fn parse_memo_key_id(s: &str) -> Result<MemoKeyId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid memo key ID '{}'", s))
+}
+
+fn parse_owner_id(s: &str) -> Result<OwnerId> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid owner ID '{}'", s))
+}
+
+/// Parses a `--tier` value such as `up_to=10000,flat=25,bps=150,min=50,max=2500`.
+fn parse_fee_tier(s: &str) -> Result<FeeTier> {
+    let mut tier = FeeTier::default();
+    for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid tier field '{}': expected key=value", pair))?;
+        let amount = || -> Result<i64> {
+            value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid number for {}: {}", key, value))
+        };
+        match key {
+            "up_to" => tier.up_to = Some(amount()?),
+            "flat" => tier.flat_fee = amount()?,
+            "bps" => {
+                tier.percentage_bps = value
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid number for bps: {}", value))?
+            }
+            "min" => tier.min_fee = Some(amount()?),
+            "max" => tier.max_fee = Some(amount()?),
+            _ => anyhow::bail!(
+                "Unknown tier field '{}'. Supported: up_to, flat, bps, min, max",
+                key
+            ),
+        }
+    }
+    Ok(tier)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    let cli = Cli::parse();
+
+    let mut client = PaymentsClient::new(&cli.api_url);
+    if let Some(key) = cli.api_key {
+        client = client.with_api_key(key);
+    }
+
+    match cli.command {
+        Commands::Health { verbose: false } => {
+            let healthy = client.health().await?;
+            if healthy {
+                println!("✓ API is healthy");
+            } else {
+                println!("✗ API is not healthy");
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Health { verbose: true } => {
+            let healthy = client.health().await?;
+            let ready = client.ready().await?;
+            let mut rows = vec![
+                (
+                    "api",
+                    if healthy { "healthy" } else { "unhealthy" }.to_string(),
+                ),
+                (
+                    "readiness",
+                    if ready { "ready" } else { "not ready" }.to_string(),
+                ),
+            ];
+            let mut problems = Vec::new();
+            match client.diagnostics().await {
+                Ok(diagnostics) => {
+                    rows.extend(diagnostics_rows(&diagnostics));
+                    problems = diagnostics.problems;
+                }
+                // Authenticating needs the database, so diagnostics are
+                // expected to fail when the API is not ready.
+                Err(e) if !ready => problems.push(format!("diagnostics unavailable: {}", e)),
+                Err(e) => return Err(e.into()),
+            }
+            for (check, value) in &rows {
+                println!("{:<18} {}", check, value);
+            }
+            for problem in &problems {
+                println!("✗ {}", problem);
+            }
+            if !healthy || !ready || !problems.is_empty() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Status => {
+            let status = client.service_status().await?;
+            println!("{}", serde_json::to_string_pretty(&status)?);
+        }
+
+        Commands::Storage => {
+            let report = client.storage_report().await?;
+            for usage in &report.resources {
+                let quota = match (usage.limit, usage.used) {
+                    (Some(limit), Some(used)) => {
+                        format!("{} of {} ({:.0}%)", usage.rows, limit, used * 100.0)
+                    }
+                    _ => format!("{} (no quota)", usage.rows),
+                };
+                let marker = if usage.warning { "✗" } else { " " };
+                println!("{} {:<16} {}", marker, usage.resource, quota);
+            }
+        }
 
         Commands::Account { action } => match action {
-            AccountCommands::Create { name, currency } => {
+            AccountCommands::Create {
+                name,
+                owner,
+                currency,
+                overdraft_limit,
+            } => {
                 let currency = parse_currency(&currency)?;
-                let account = client.create_account(&name, currency).await?;
+                let account = client
+                    .create_account_with_overdraft(
+                        parse_owner_id(&owner)?,
+                        &name,
+                        currency,
+                        overdraft_limit,
+                    )
+                    .await?;
                 println!("{}", serde_json::to_string_pretty(&account)?);
             }
             AccountCommands::Get { id } => {
-                let account_id = parse_account_id(&id)?;
+                let account_id = resolve_account_id(&client, &id).await?;
                 let account = client.get_account(account_id).await?;
                 println!("{}", serde_json::to_string_pretty(&account)?);
             }
-            AccountCommands::List => {
-                let accounts = client.list_accounts().await?;
+            AccountCommands::List { owner } => {
+                let accounts = match owner {
+                    Some(owner) => client.list_owner_accounts(parse_owner_id(&owner)?).await?,
+                    None => client.list_accounts().await?,
+                };
+                println!("{}", serde_json::to_string_pretty(&accounts)?);
+            }
+            AccountCommands::Search { query, limit } => {
+                let accounts = client.search_accounts(&query, limit).await?;
                 println!("{}", serde_json::to_string_pretty(&accounts)?);
             }
+            AccountCommands::Rules { id, allow, deny } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let rules = if allow.is_none() && deny.is_none() {
+                    client.spending_rules(account_id).await?
+                } else {
+                    let rules = SpendingRules {
+                        allowed: allow.unwrap_or_default(),
+                        denied: deny.unwrap_or_default(),
+                    };
+                    client.set_spending_rules(account_id, &rules).await?
+                };
+                println!("{}", serde_json::to_string_pretty(&rules)?);
+            }
+            AccountCommands::Limits {
+                id,
+                max_transaction,
+                max_balance,
+                daily_debit,
+                clear,
+            } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let mut limits = client.account_limits(account_id).await?;
+                let changed = clear || max_transaction.or(max_balance).or(daily_debit).is_some();
+                if changed {
+                    if clear {
+                        limits = AccountLimits::default();
+                    }
+                    limits.max_transaction_amount =
+                        max_transaction.or(limits.max_transaction_amount);
+                    limits.max_balance = max_balance.or(limits.max_balance);
+                    limits.daily_debit_limit = daily_debit.or(limits.daily_debit_limit);
+                    limits = client.set_account_limits(account_id, &limits).await?;
+                }
+                println!("{}", serde_json::to_string_pretty(&limits)?);
+            }
+            AccountCommands::Reactivate { id } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let account = client.reactivate_account(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&account)?);
+            }
+            AccountCommands::Freeze { id } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let account = client.freeze_account(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&account)?);
+            }
+            AccountCommands::Unfreeze { id } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let account = client.unfreeze_account(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&account)?);
+            }
+            AccountCommands::Close { id } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let account = client.close_account(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&account)?);
+            }
+            AccountCommands::Overdraft { id, limit } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let account = client.set_overdraft_limit(account_id, limit).await?;
+                println!("{}", serde_json::to_string_pretty(&account)?);
+            }
+            AccountCommands::Merge { id, into } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let into = resolve_account_id(&client, &into).await?;
+                let merge = client.merge_account(account_id, into).await?;
+                println!("{}", serde_json::to_string_pretty(&merge)?);
+            }
+            AccountCommands::Balances { id, from, to } => {
+                let account_id = resolve_account_id(&client, &id).await?;
+                let from = from.map(|d| parse_date("from", &d)).transpose()?;
+                let to = to.map(|d| parse_date("to", &d)).transpose()?;
+                let history = client.balance_history(account_id, from, to).await?;
+                println!("{}", serde_json::to_string_pretty(&history)?);
+            }
+            AccountCommands::RecordBalances => {
+                let recorded = client.record_balance_snapshots().await?;
+                println!("✓ Recorded {} daily balances", recorded);
+            }
         },
 
         Commands::Transaction { action } => match action {
@@ -224,12 +1720,26 @@ async fn main() -> Result<()> {
                 currency,
                 idempotency_key,
                 reference,
+                counterparty_name,
+                counterparty_id,
+                metadata,
+                memo_key,
+                memo,
             } => {
-                let account_id = parse_account_id(&account)?;
-                let currency = parse_currency(&currency)?;
-                let tx = client
-                    .deposit(account_id, amount, currency, idempotency_key, reference)
-                    .await?;
+                let req = DepositRequest {
+                    account_id: parse_account_ref(&account)?,
+                    amount,
+                    currency: parse_currency(&currency)?,
+                    idempotency_key,
+                    reference,
+                    counterparty: counterparty_name.map(|name| Counterparty {
+                        name,
+                        external_id: counterparty_id,
+                    }),
+                    metadata: parse_metadata(&metadata)?,
+                    encrypted_memo: parse_memo(memo_key, memo)?,
+                };
+                let tx = client.send_deposit(&req).await?;
                 println!("{}", serde_json::to_string_pretty(&tx)?);
             }
             TransactionCommands::Withdraw {
@@ -238,12 +1748,28 @@ async fn main() -> Result<()> {
                 currency,
                 idempotency_key,
                 reference,
+                counterparty_name,
+                counterparty_id,
+                purpose,
+                metadata,
+                memo_key,
+                memo,
             } => {
-                let account_id = parse_account_id(&account)?;
-                let currency = parse_currency(&currency)?;
-                let tx = client
-                    .withdraw(account_id, amount, currency, idempotency_key, reference)
-                    .await?;
+                let req = WithdrawRequest {
+                    account_id: parse_account_ref(&account)?,
+                    amount,
+                    currency: parse_currency(&currency)?,
+                    idempotency_key,
+                    reference,
+                    counterparty: counterparty_name.map(|name| Counterparty {
+                        name,
+                        external_id: counterparty_id,
+                    }),
+                    purpose_code: purpose,
+                    metadata: parse_metadata(&metadata)?,
+                    encrypted_memo: parse_memo(memo_key, memo)?,
+                };
+                let tx = client.send_withdrawal(&req).await?;
                 println!("{}", serde_json::to_string_pretty(&tx)?);
             }
             TransactionCommands::Transfer {
@@ -253,28 +1779,218 @@ async fn main() -> Result<()> {
                 currency,
                 idempotency_key,
                 reference,
+                purpose,
+                convert,
+                quote,
+                metadata,
+                memo_key,
+                memo,
             } => {
-                let from_id = parse_account_id(&from)?;
-                let to_id = parse_account_id(&to)?;
-                let currency = parse_currency(&currency)?;
-                let tx = client
-                    .transfer(from_id, to_id, amount, currency, idempotency_key, reference)
-                    .await?;
+                let req = TransferRequest {
+                    from_account_id: parse_account_ref(&from)?,
+                    to_account_id: parse_account_ref(&to)?,
+                    amount,
+                    currency: parse_currency(&currency)?,
+                    idempotency_key,
+                    reference,
+                    purpose_code: purpose,
+                    metadata: parse_metadata(&metadata)?,
+                    convert_currency: convert,
+                    quote_id: quote.as_deref().map(parse_quote_id).transpose()?,
+                    encrypted_memo: parse_memo(memo_key, memo)?,
+                };
+                let tx = client.send_transfer(&req).await?;
                 println!("{}", serde_json::to_string_pretty(&tx)?);
             }
+            TransactionCommands::Batch { file, mode } => {
+                let operations: Vec<BatchOperation<AccountRef>> =
+                    serde_json::from_str(&std::fs::read_to_string(&file)?)?;
+                let req = BatchRequest {
+                    mode: parse_batch_mode(&mode)?,
+                    operations,
+                };
+                let response = client.batch(&req).await?;
+                println!("{}", serde_json::to_string_pretty(&response)?);
+            }
+            TransactionCommands::Inbound {
+                account,
+                amount,
+                currency,
+                external_reference,
+                reference,
+                counterparty_name,
+                counterparty_id,
+            } => {
+                let req = InboundPaymentRequest {
+                    account_id: parse_account_ref(&account)?,
+                    amount,
+                    currency: parse_currency(&currency)?,
+                    external_reference,
+                    reference,
+                    counterparty: counterparty_name.map(|name| Counterparty {
+                        name,
+                        external_id: counterparty_id,
+                    }),
+                };
+                let payment = client.receive_inbound_payment(&req).await?;
+                println!("{}", serde_json::to_string_pretty(&payment)?);
+            }
+            TransactionCommands::Search {
+                reference,
+                account,
+                transaction_type,
+                currency,
+                min_amount,
+                max_amount,
+                metadata_key,
+                metadata_value,
+                from,
+                to,
+                limit,
+                cursor,
+            } => {
+                let query = TransactionSearchQuery {
+                    limit,
+                    cursor,
+                    reference,
+                    metadata_key,
+                    metadata_value,
+                    account: account.as_deref().map(parse_account_ref).transpose()?,
+                    transaction_type: transaction_type
+                        .as_deref()
+                        .map(parse_transaction_type)
+                        .transpose()?,
+                    currency: currency.as_deref().map(parse_currency).transpose()?,
+                    from: from.map(|s| parse_timestamp("from", &s)).transpose()?,
+                    to: to.map(|s| parse_timestamp("to", &s)).transpose()?,
+                    min_amount,
+                    max_amount,
+                };
+                let page = client.search_transactions(&query).await?;
+                println!("{}", serde_json::to_string_pretty(&page)?);
+            }
         },
 
         Commands::Webhook { action } => match action {
-            WebhookCommands::Register { url, events } => {
+            WebhookCommands::Register {
+                url,
+                events,
+                timeout_ms,
+                connect_timeout_ms,
+                signature_algorithm,
+            } => {
                 // Filter out empty strings from events
                 let events: Vec<String> = events.into_iter().filter(|e| !e.is_empty()).collect();
-                let webhook = client.register_webhook(&url, events).await?;
+                let req = RegisterWebhookRequest {
+                    url,
+                    events,
+                    timeout_ms,
+                    connect_timeout_ms,
+                    signature_algorithm: signature_algorithm
+                        .as_deref()
+                        .map(parse_signature_algorithm)
+                        .transpose()?
+                        .unwrap_or_default(),
+                };
+                let webhook = client.send_webhook_registration(&req).await?;
                 println!("{}", serde_json::to_string_pretty(&webhook)?);
             }
+            WebhookCommands::RegisterBatch { file } => {
+                let endpoints: Vec<RegisterWebhookRequest> =
+                    serde_json::from_str(&std::fs::read_to_string(&file)?)?;
+                let webhooks = client.register_webhooks(endpoints).await?;
+                println!("{}", serde_json::to_string_pretty(&webhooks)?);
+            }
             WebhookCommands::List => {
                 let webhooks = client.list_webhooks().await?;
                 println!("{}", serde_json::to_string_pretty(&webhooks)?);
             }
+            WebhookCommands::Event { id } => {
+                let event = client.webhook_event(&id).await?;
+                println!("{}", serde_json::to_string_pretty(&event)?);
+            }
+            WebhookCommands::DeadLetters {
+                endpoint,
+                event_type,
+                failed_since,
+            } => {
+                let query = dead_letter_query(endpoint, event_type, failed_since)?;
+                let events = client.dead_lettered_webhooks(&query).await?;
+                println!("{}", serde_json::to_string_pretty(&events)?);
+            }
+            WebhookCommands::Retry {
+                endpoint,
+                event_type,
+                failed_since,
+            } => {
+                let query = dead_letter_query(endpoint, event_type, failed_since)?;
+                let result = client.retry_dead_lettered_webhooks(&query).await?;
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            WebhookCommands::Events {
+                id,
+                after_sequence,
+                limit,
+            } => {
+                let query = WebhookEventsQuery {
+                    after_sequence,
+                    limit,
+                };
+                let events = client.webhook_events_after(&id, &query).await?;
+                println!("{}", serde_json::to_string_pretty(&events)?);
+            }
+            WebhookCommands::Deliveries {
+                id,
+                status,
+                before_sequence,
+                limit,
+            } => {
+                let query = WebhookDeliveriesQuery {
+                    status,
+                    before_sequence,
+                    limit,
+                };
+                let deliveries = client.webhook_deliveries(&id, &query).await?;
+                println!("{}", serde_json::to_string_pretty(&deliveries)?);
+            }
+            WebhookCommands::SetUrl { id, url } => {
+                let change = client.update_webhook_url(&id, &url).await?;
+                println!(
+                    "✓ URL change requested; change {} awaits approval by another admin key",
+                    change.id
+                );
+            }
+            WebhookCommands::Update {
+                id,
+                events,
+                active,
+                signature_algorithm,
+            } => {
+                let req = UpdateWebhookRequest {
+                    events: events
+                        .map(|events| events.into_iter().filter(|e| !e.is_empty()).collect()),
+                    is_active: active,
+                    signature_algorithm: signature_algorithm
+                        .as_deref()
+                        .map(parse_signature_algorithm)
+                        .transpose()?,
+                    ..Default::default()
+                };
+                let webhook = client.send_webhook_update(&id, &req).await?;
+                println!("{}", serde_json::to_string_pretty(&webhook)?);
+            }
+            WebhookCommands::RotateSecret { id } => {
+                let webhook = client.rotate_webhook_secret(&id).await?;
+                println!("{}", serde_json::to_string_pretty(&webhook)?);
+            }
+            WebhookCommands::Delete { id } => {
+                client.delete_webhook(&id).await?;
+                println!("✓ Webhook endpoint {} deleted", id);
+            }
+            WebhookCommands::Redeliver { id } => {
+                let delivery = client.redeliver_webhook_event(&id).await?;
+                println!("✓ Webhook event {} queued for redelivery", delivery.id);
+            }
             WebhookCommands::Listen { port } => {
                 let app =
                     axum::Router::new().route("/webhook", axum::routing::post(handle_webhook));
@@ -285,9 +2001,63 @@ async fn main() -> Result<()> {
             }
         },
 
+        Commands::Event { action } => match action {
+            EventCommands::Tail { types, json } => {
+                let types = (!types.is_empty()).then(|| types.join(","));
+                let mut events = client.stream_events(types.as_deref()).await?;
+                while let Some(event) = events.next_event().await {
+                    let event = event?;
+                    if json {
+                        println!("{}", serde_json::to_string(&event)?);
+                    } else if event.event == "lagged" {
+                        eprintln!("⚠ Fell behind and missed {} events", event.data["missed"]);
+                    } else {
+                        println!(
+                            "{}  {:<26} {}",
+                            Utc::now().format("%H:%M:%S"),
+                            event.event,
+                            event.data
+                        );
+                    }
+                }
+                eprintln!("Stream closed by the server");
+            }
+            EventCommands::Export { dir, limit } => {
+                export_event_log(&client, &dir, limit).await?;
+            }
+        },
+
         Commands::Key { action } => match action {
-            KeyCommands::Create { name } => {
-                let api_key = client.create_api_key(&name).await?;
+            KeyCommands::Create {
+                name,
+                account,
+                owner,
+                scopes,
+                test,
+            } => {
+                let account_id = match account {
+                    Some(account) => Some(resolve_account_id(&client, &account).await?),
+                    None => None,
+                };
+                let api_key = match (owner, account_id, scopes) {
+                    (Some(owner), _, scopes) => {
+                        client
+                            .create_owner_api_key(&name, parse_owner_id(&owner)?, scopes.as_deref())
+                            .await?
+                    }
+                    (None, None, scopes) if test => {
+                        client.create_test_api_key(&name, scopes.as_deref()).await?
+                    }
+                    (None, account_id, Some(scopes)) => {
+                        client
+                            .create_api_key_with_scopes(&name, account_id, &scopes)
+                            .await?
+                    }
+                    (None, Some(account_id), None) => {
+                        client.create_scoped_api_key(&name, account_id).await?
+                    }
+                    (None, None, None) => client.create_api_key(&name).await?,
+                };
                 println!("{}", api_key);
             }
             KeyCommands::List => {
@@ -295,11 +2065,576 @@ async fn main() -> Result<()> {
                 println!("{}", serde_json::to_string_pretty(&keys)?);
             }
             KeyCommands::Delete { id } => {
-                client.delete_api_key(&id).await?;
-                println!("✓ API key deleted");
+                let change = client.delete_api_key(&id).await?;
+                println!(
+                    "✓ Deletion requested; change {} awaits approval by another admin key",
+                    change.id
+                );
+            }
+            KeyCommands::Usage { id } => {
+                let usage = client.api_key_usage(&id).await?;
+                println!("{}", serde_json::to_string_pretty(&usage)?);
+            }
+            KeyCommands::RateLimit { id, per_minute } => {
+                let key = client.set_api_key_rate_limit(&id, per_minute).await?;
+                println!("{}", serde_json::to_string_pretty(&key)?);
+            }
+            KeyCommands::Audit { id } => {
+                let audit = client.api_key_audit(&id).await?;
+                println!("{}", serde_json::to_string_pretty(&audit)?);
+            }
+            KeyCommands::Session {
+                account,
+                expires_in,
+            } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let session = client.create_session_token(account_id, expires_in).await?;
+                println!("✓ Session token issued (shown only once):");
+                println!("  {}", session.token);
+                println!("  Expires: {}", session.expires_at);
+            }
+        },
+
+        Commands::Alias { action } => match action {
+            AliasCommands::Add { account, alias } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let alias = client.add_account_alias(account_id, &alias).await?;
+                println!(
+                    "✓ {} now refers to account {}",
+                    alias.alias, alias.account_id
+                );
+            }
+            AliasCommands::List { account } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let aliases = client.account_aliases(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&aliases)?);
+            }
+            AliasCommands::Remove { account, alias } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let alias = parse_alias(&alias)?;
+                client.remove_account_alias(account_id, &alias).await?;
+                println!("✓ {} removed", alias);
+            }
+            AliasCommands::Resolve { alias } => {
+                let alias = client.get_account_alias(&parse_alias(&alias)?).await?;
+                println!("{}", serde_json::to_string_pretty(&alias)?);
+            }
+        },
+
+        Commands::Owner { action } => match action {
+            OwnerCommands::Create { name, email } => {
+                let owner = client.create_owner(&name, email.as_deref()).await?;
+                println!("{}", serde_json::to_string_pretty(&owner)?);
+            }
+            OwnerCommands::List => {
+                let owners = client.list_owners().await?;
+                println!("{}", serde_json::to_string_pretty(&owners)?);
+            }
+            OwnerCommands::Get { id } => {
+                let owner = client.get_owner(parse_owner_id(&id)?).await?;
+                println!("{}", serde_json::to_string_pretty(&owner)?);
+            }
+            OwnerCommands::Update { id, name, email } => {
+                let req = UpdateOwnerRequest { name, email };
+                let owner = client.update_owner(parse_owner_id(&id)?, &req).await?;
+                println!("{}", serde_json::to_string_pretty(&owner)?);
+            }
+            OwnerCommands::Delete { id } => {
+                let owner_id = parse_owner_id(&id)?;
+                client.delete_owner(owner_id).await?;
+                println!("✓ Owner {} deleted", owner_id);
+            }
+        },
+
+        Commands::MemoKey { action } => match action {
+            MemoKeyCommands::Add {
+                account,
+                algorithm,
+                fingerprint,
+            } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let key = client
+                    .add_memo_key(account_id, &algorithm, &fingerprint)
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&key)?);
+            }
+            MemoKeyCommands::List { account } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let keys = client.list_memo_keys(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&keys)?);
+            }
+            MemoKeyCommands::Retire { account, key_id } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let key = client
+                    .retire_memo_key(account_id, parse_memo_key_id(&key_id)?)
+                    .await?;
+                println!("✓ Memo key {} retired", key.id);
+            }
+        },
+
+        Commands::Fee { action } => match action {
+            FeeCommands::Create { name, tiers } => {
+                let tiers = tiers
+                    .iter()
+                    .map(|t| parse_fee_tier(t))
+                    .collect::<Result<Vec<_>>>()?;
+                let schedule = client.create_fee_schedule(&name, tiers).await?;
+                println!("{}", serde_json::to_string_pretty(&schedule)?);
+            }
+            FeeCommands::List => {
+                let schedules = client.list_fee_schedules().await?;
+                println!("{}", serde_json::to_string_pretty(&schedules)?);
+            }
+            FeeCommands::Show { account } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let fees = client.account_fees(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&fees)?);
+            }
+            FeeCommands::Assign {
+                account,
+                schedule,
+                from,
+            } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let schedule_id: FeeScheduleId = schedule
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid fee schedule ID: {}", schedule))?;
+                let effective_from = from.map(|f| parse_timestamp("from", &f)).transpose()?;
+                let assignment = client
+                    .assign_fee_schedule(account_id, schedule_id, effective_from)
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&assignment)?);
+            }
+            FeeCommands::Quote { account, amount } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let quote = client.quote_fee(account_id, amount).await?;
+                println!("{}", serde_json::to_string_pretty(&quote)?);
+            }
+        },
+
+        Commands::Settlement { action } => match action {
+            SettlementCommands::Create { cutoff } => {
+                let cutoff = cutoff.map(|c| parse_timestamp("cutoff", &c)).transpose()?;
+                let batch = client.create_settlement_batch(cutoff).await?;
+                println!("{}", serde_json::to_string_pretty(&batch)?);
+            }
+            SettlementCommands::List => {
+                let batches = client.list_settlement_batches().await?;
+                println!("{}", serde_json::to_string_pretty(&batches)?);
+            }
+            SettlementCommands::Show { batch } => {
+                let batch = client
+                    .get_settlement_batch(parse_settlement_batch_id(&batch)?)
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&batch)?);
+            }
+            SettlementCommands::Submit { batch } => {
+                let batch = client
+                    .update_settlement_batch_status(
+                        parse_settlement_batch_id(&batch)?,
+                        SettlementBatchStatus::Submitted,
+                    )
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&batch)?);
+            }
+            SettlementCommands::Settle { batch } => {
+                let batch = client
+                    .update_settlement_batch_status(
+                        parse_settlement_batch_id(&batch)?,
+                        SettlementBatchStatus::Settled,
+                    )
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&batch)?);
+            }
+            SettlementCommands::Cancel { batch } => {
+                let batch = client
+                    .update_settlement_batch_status(
+                        parse_settlement_batch_id(&batch)?,
+                        SettlementBatchStatus::Cancelled,
+                    )
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&batch)?);
+            }
+            SettlementCommands::Export {
+                batch,
+                format,
+                output,
+            } => {
+                let file = client
+                    .export_settlement_batch(
+                        parse_settlement_batch_id(&batch)?,
+                        parse_settlement_format(&format)?,
+                    )
+                    .await?;
+                write_output(output, &file)?;
+            }
+        },
+
+        Commands::Journal {
+            from,
+            to,
+            format,
+            output,
+        } => {
+            let file = client
+                .export_journal(
+                    parse_journal_format(&format)?,
+                    parse_date("from", &from)?,
+                    parse_date("to", &to)?,
+                )
+                .await?;
+            write_output(output, &file)?;
+        }
+
+        Commands::Export { action } => match action {
+            ExportCommands::Journal {
+                from,
+                to,
+                format,
+                output,
+            } => {
+                let request = ExportRequest::Journal {
+                    format: parse_journal_format(&format)?,
+                    from: parse_date("from", &from)?,
+                    to: parse_date("to", &to)?,
+                };
+                let file = run_export(&client, &request).await?;
+                write_output(output, &file)?;
+            }
+            ExportCommands::Settlement {
+                batch,
+                format,
+                output,
+            } => {
+                let request = ExportRequest::SettlementBatch {
+                    batch_id: parse_settlement_batch_id(&batch)?,
+                    format: parse_settlement_format(&format)?,
+                };
+                let file = run_export(&client, &request).await?;
+                write_output(output, &file)?;
+            }
+            ExportCommands::Show { export } => {
+                let export = client.get_export(parse_export_id(&export)?).await?;
+                println!("{}", serde_json::to_string_pretty(&export)?);
             }
         },
 
+        Commands::Statement { action } => match action {
+            StatementCommands::Email {
+                account,
+                set,
+                clear,
+            } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let email = if clear || set.is_some() {
+                    client
+                        .set_statement_email(account_id, set.as_deref())
+                        .await?
+                } else {
+                    client.statement_email(account_id).await?
+                };
+                println!("{}", serde_json::to_string_pretty(&email)?);
+            }
+            StatementCommands::List { account } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let statements = client.list_statements(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&statements)?);
+            }
+            StatementCommands::Show { statement } => {
+                let download = client
+                    .get_statement(parse_statement_id(&statement)?)
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&download)?);
+            }
+            StatementCommands::Issue { month } => {
+                let statements = client.issue_statements(month.as_deref()).await?;
+                println!("✓ Issued {} statement(s)", statements.len());
+                println!("{}", serde_json::to_string_pretty(&statements)?);
+            }
+            StatementCommands::Export {
+                account,
+                from,
+                to,
+                format,
+                output,
+            } => {
+                let file = client
+                    .download_account_statement(
+                        resolve_account_id(&client, &account).await?,
+                        parse_date("from", &from)?,
+                        parse_date("to", &to)?,
+                        parse_statement_format(&format)?,
+                    )
+                    .await?;
+                write_output(output, &file)?;
+            }
+            StatementCommands::Download { statement, output } => {
+                let download = client
+                    .get_statement(parse_statement_id(&statement)?)
+                    .await?;
+                let file = client.download_statement(&download).await?;
+                write_output(output, &file)?;
+            }
+        },
+
+        Commands::Report { action } => match action {
+            ReportCommands::Exposure { base, as_of } => {
+                let query = ExposureQuery {
+                    base: Some(parse_currency(&base)?),
+                    as_of: as_of.map(|s| parse_timestamp("as-of", &s)).transpose()?,
+                };
+                let report = client.exposure_report(&query).await?;
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+            ReportCommands::Float { from, to } => {
+                let query = FloatReportQuery {
+                    from: from.map(|d| parse_date("from", &d)).transpose()?,
+                    to: to.map(|d| parse_date("to", &d)).transpose()?,
+                };
+                let report = client.float_report(&query).await?;
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+        },
+
+        Commands::Schedule { action } => match action {
+            ScheduleCommands::Create {
+                from,
+                to,
+                amount,
+                currency,
+                reference,
+                purpose,
+                every,
+                cron,
+                timezone,
+                start_at,
+            } => {
+                let schedule = match (every, cron) {
+                    (_, Some(expression)) => PaymentSchedule::Cron {
+                        expression,
+                        timezone,
+                    },
+                    (Some(every_seconds), None) => PaymentSchedule::Interval { every_seconds },
+                    (None, None) => anyhow::bail!("Pass --every or --cron"),
+                };
+                let to_account_id = match to {
+                    Some(to) => Some(resolve_account_id(&client, &to).await?),
+                    None => None,
+                };
+                let req = CreateScheduledPaymentRequest {
+                    transaction_type: match to_account_id {
+                        Some(_) => TransactionType::Transfer,
+                        None => TransactionType::Withdrawal,
+                    },
+                    from_account_id: resolve_account_id(&client, &from).await?,
+                    to_account_id,
+                    amount,
+                    currency: parse_currency(&currency)?,
+                    reference,
+                    purpose_code: purpose,
+                    schedule,
+                    start_at: start_at
+                        .map(|s| parse_timestamp("start-at", &s))
+                        .transpose()?,
+                };
+                let payment = client.create_scheduled_payment(&req).await?;
+                println!(
+                    "✓ Scheduled payment {} created, first run at {}",
+                    payment.id, payment.next_run_at
+                );
+                println!("{}", serde_json::to_string_pretty(&payment)?);
+            }
+            ScheduleCommands::List { account } => {
+                let account_id = match account {
+                    Some(account) => Some(resolve_account_id(&client, &account).await?),
+                    None => None,
+                };
+                let payments = client.list_scheduled_payments(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&payments)?);
+            }
+            ScheduleCommands::Show { id } => {
+                let payment = client
+                    .get_scheduled_payment(parse_scheduled_payment_id(&id)?)
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&payment)?);
+            }
+            ScheduleCommands::Pause { id } => {
+                let payment = client
+                    .pause_scheduled_payment(parse_scheduled_payment_id(&id)?)
+                    .await?;
+                println!("✓ Scheduled payment {} paused", payment.id);
+            }
+            ScheduleCommands::Resume { id } => {
+                let payment = client
+                    .resume_scheduled_payment(parse_scheduled_payment_id(&id)?)
+                    .await?;
+                println!(
+                    "✓ Scheduled payment {} resumed, next run at {}",
+                    payment.id, payment.next_run_at
+                );
+            }
+        },
+
+        Commands::Hold { action } => match action {
+            HoldCommands::Authorize {
+                account,
+                amount,
+                currency,
+                reference,
+                purpose,
+                expires_in,
+            } => {
+                let req = AuthorizeRequest {
+                    account_id: resolve_account_id(&client, &account).await?,
+                    amount,
+                    currency: parse_currency(&currency)?,
+                    reference,
+                    purpose_code: purpose,
+                    expires_in_secs: expires_in,
+                };
+                let hold = client.authorize(&req).await?;
+                println!("✓ Hold {} placed, expires at {}", hold.id, hold.expires_at);
+                println!("{}", serde_json::to_string_pretty(&hold)?);
+            }
+            HoldCommands::Capture { id, amount } => {
+                let hold = client.capture_hold(parse_hold_id(&id)?, amount).await?;
+                println!(
+                    "✓ Hold {} captured: {} {}",
+                    hold.id,
+                    cli.amount_format
+                        .format(hold.captured_amount.unwrap_or_default(), hold.currency),
+                    hold.currency
+                );
+                println!("{}", serde_json::to_string_pretty(&hold)?);
+            }
+            HoldCommands::Void { id } => {
+                let hold = client.void_hold(parse_hold_id(&id)?).await?;
+                println!("✓ Hold {} voided", hold.id);
+            }
+            HoldCommands::Show { id } => {
+                let hold = client.get_hold(parse_hold_id(&id)?).await?;
+                println!("{}", serde_json::to_string_pretty(&hold)?);
+            }
+            HoldCommands::List { account } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let holds = client.list_holds(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&holds)?);
+            }
+        },
+
+        Commands::Quote { action } => match action {
+            QuoteCommands::Create {
+                from,
+                to,
+                amount,
+                expires_in,
+            } => {
+                let req = CreateQuoteRequest {
+                    from_currency: parse_currency(&from)?,
+                    to_currency: parse_currency(&to)?,
+                    amount,
+                    expires_in_secs: expires_in,
+                };
+                let quote = client.create_quote(&req).await?;
+                println!(
+                    "✓ Quote {} at {}, expires at {}",
+                    quote.id, quote.rate, quote.expires_at
+                );
+                println!("{}", serde_json::to_string_pretty(&quote)?);
+            }
+            QuoteCommands::Show { id } => {
+                let quote = client.get_quote(parse_quote_id(&id)?).await?;
+                println!("{}", serde_json::to_string_pretty(&quote)?);
+            }
+        },
+
+        Commands::Beneficiary { action } => match action {
+            BeneficiaryCommands::Add {
+                account,
+                name,
+                external_id,
+            } => {
+                let req = CreateBeneficiaryRequest {
+                    account_id: resolve_account_id(&client, &account).await?,
+                    name,
+                    external_id,
+                };
+                let beneficiary = client.create_beneficiary(&req).await?;
+                println!(
+                    "✓ Beneficiary {} added; confirm the two micro-deposits with `beneficiary verify`",
+                    beneficiary.id
+                );
+            }
+            BeneficiaryCommands::Verify { id, amounts } => {
+                let amounts = [amounts[0], amounts[1]];
+                let beneficiary = client
+                    .verify_beneficiary(parse_beneficiary_id(&id)?, amounts)
+                    .await?;
+                println!("✓ Beneficiary {} verified", beneficiary.id);
+            }
+            BeneficiaryCommands::Show { id } => {
+                let beneficiary = client.get_beneficiary(parse_beneficiary_id(&id)?).await?;
+                println!("{}", serde_json::to_string_pretty(&beneficiary)?);
+            }
+            BeneficiaryCommands::List { account } => {
+                let account_id = resolve_account_id(&client, &account).await?;
+                let beneficiaries = client.list_beneficiaries(account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&beneficiaries)?);
+            }
+        },
+
+        Commands::Change { action } => match action {
+            ChangeCommands::List { status } => {
+                let changes = client.list_changes(status).await?;
+                println!("{}", serde_json::to_string_pretty(&changes)?);
+            }
+            ChangeCommands::Show { id } => {
+                let change = client.get_change(parse_change_id(&id)?).await?;
+                println!("{}", serde_json::to_string_pretty(&change)?);
+            }
+            ChangeCommands::Approve { id } => {
+                let change = client.approve_change(parse_change_id(&id)?).await?;
+                println!("✓ Change {} approved and applied", change.id);
+            }
+            ChangeCommands::Reject { id } => {
+                let change = client.reject_change(parse_change_id(&id)?).await?;
+                println!("✓ Change {} rejected", change.id);
+            }
+        },
+
+        Commands::Maintenance { action } => {
+            let status = match action {
+                MaintenanceCommands::Status => client.maintenance_status().await?,
+                MaintenanceCommands::Enable { reason } => {
+                    client.set_maintenance(true, reason).await?
+                }
+                MaintenanceCommands::Disable => client.set_maintenance(false, None).await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&status)?);
+        }
+
+        Commands::Incident { action } => {
+            let status = match action {
+                IncidentCommands::Post { message } => client.set_incident(Some(message)).await?,
+                IncidentCommands::Clear => client.set_incident(None).await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&status)?);
+        }
+
+        Commands::Dashboard { interval } => {
+            dashboard::run(
+                &client,
+                &cli.api_url,
+                std::time::Duration::from_secs(interval.max(1)),
+                &cli.amount_format,
+            )
+            .await?;
+        }
+
+        Commands::Metrics => {
+            print!("{}", client.metrics().await?);
+        }
+
         Commands::Bootstrap { name } => {
             let api_key = client.bootstrap(&name).await?;
             println!("{}", api_key);
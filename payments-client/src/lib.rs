@@ -2,12 +2,41 @@
 //!
 //! A typed Rust client for the Payments API.
 
+pub mod webhook_signature;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use payments_types::{
-    Account, AccountId, CreateAccountRequest, CurrencyCode, DepositRequest, Transaction,
-    TransferRequest, WithdrawRequest,
+    Account, AccountAlias, AccountFees, AccountId, AccountLimits, AccountListQuery, AccountMerge,
+    AccountRef, AccountSearchQuery, AccountStatement, AccountStatementQuery, AddAliasRequest,
+    Alias, ApiKeyAudit, ApiKeyScope, ApiKeyUsage, AssignFeeScheduleRequest, AuthorizeRequest,
+    BalanceHistory, BalanceHistoryQuery, BalanceSnapshotResponse, BatchRequest, BatchResponse,
+    Beneficiary, BeneficiaryId, CaptureRequest, ChangeRequest, ChangeRequestId, ChangeRequestQuery,
+    ChangeStatus, CreateAccountRequest, CreateBeneficiaryRequest, CreateFeeScheduleRequest,
+    CreateMemoKeyRequest, CreateOwnerRequest, CreateQuoteRequest, CreateScheduledPaymentRequest,
+    CreateSessionTokenRequest, CreateSettlementBatchRequest, CurrencyCode, DeadLetterQuery,
+    DeadLetterRetryResponse, DepositRequest, Diagnostics, ErrorCode, EventLogPage, EventLogQuery,
+    EventStreamQuery, Export, ExportDownload, ExportId, ExportRequest, ExposureQuery,
+    ExposureReport, FeeAssignment, FeeQuote, FeeQuoteQuery, FeeSchedule, FeeScheduleId, FeeTier,
+    FloatReport, FloatReportQuery, Hold, HoldId, InboundPayment, InboundPaymentRequest,
+    IssueStatementsRequest, JournalExportFormat, JournalExportQuery, MaintenanceStatus, MemoKey,
+    MemoKeyId, MergeAccountRequest, Mode, Owner, OwnerId, PositiveAmount, Quote, QuoteId,
+    Readiness, RegisterWebhookRequest, RegisterWebhooksRequest, ScheduledPayment,
+    ScheduledPaymentId, ScheduledPaymentQuery, ServiceStatus, SessionToken,
+    SetApiKeyRateLimitRequest, SetIncidentRequest, SetMaintenanceRequest, SettlementBatch,
+    SettlementBatchId, SettlementBatchStatus, SettlementExportFormat, SettlementExportQuery,
+    SpendingRules, Statement, StatementDownload, StatementEmail, StatementFormat, StatementId,
+    StorageReport, Transaction, TransactionListQuery, TransactionPage, TransactionSearchQuery,
+    TransferRequest, UpdateAccountRequest, UpdateOwnerRequest, UpdateSettlementBatchStatusRequest,
+    UpdateWebhookRequest, VerifyBeneficiaryRequest, WebhookDeliveriesQuery,
+    WebhookDeliveryResponse, WebhookEventResponse, WebhookEventsQuery, WebhookSignatureAlgorithm,
+    WithWarnings, WithdrawRequest,
 };
 
-use reqwest::Client;
+use reqwest::header::{AUTHORIZATION, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
@@ -18,12 +47,29 @@ pub enum ClientError {
     Http(#[from] reqwest::Error),
 
     #[error("API error: {status} - {message}")]
-    Api { status: u16, message: String },
+    Api {
+        status: u16,
+        message: String,
+        /// Why the request failed, when the server sent a code this client
+        /// knows
+        error_code: Option<ErrorCode>,
+    },
 
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 }
 
+impl ClientError {
+    /// The API's machine-readable error code, for matching on specific
+    /// failures such as [`ErrorCode::InsufficientFunds`].
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            ClientError::Api { error_code, .. } => *error_code,
+            _ => None,
+        }
+    }
+}
+
 /// Response from webhook registration or listing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookResponse {
@@ -32,6 +78,38 @@ pub struct WebhookResponse {
     pub secret: String,
     pub events: Vec<String>,
     pub is_active: bool,
+    pub timeout_ms: u32,
+    pub connect_timeout_ms: u32,
+    #[serde(default)]
+    pub signature_algorithm: WebhookSignatureAlgorithm,
+    /// Whether the endpoint receives test or live events
+    #[serde(default)]
+    pub mode: Mode,
+    /// Hex Ed25519 key for verifying deliveries, on Ed25519 endpoints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+/// Response from batch webhook registration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegisteredWebhooks {
+    endpoints: Vec<WebhookResponse>,
+}
+
+/// A webhook event type and its payload fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventType {
+    pub name: String,
+    pub description: String,
+    pub payload: Vec<EventTypeField>,
+}
+
+/// A payload field of an [`EventType`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventTypeField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
 }
 
 /// API key information (without the raw key value).
@@ -39,9 +117,230 @@ pub struct WebhookResponse {
 pub struct ApiKeyInfo {
     pub id: String,
     pub name: String,
+    /// Account the key is restricted to; `None` for admin keys
+    #[serde(default)]
+    pub account_id: Option<AccountId>,
+    /// Owner whose accounts the key is restricted to
+    #[serde(default)]
+    pub owner_id: Option<OwnerId>,
+    #[serde(default)]
+    pub scopes: Vec<ApiKeyScope>,
+    /// Whether the key works on test or live accounts
+    #[serde(default)]
+    pub mode: Mode,
     pub is_active: bool,
     pub created_at: String,
     pub last_used_at: Option<String>,
+    /// Requests the key may make per minute; `None` for the server default
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
+}
+
+/// An event read from [`PaymentsClient::stream_events`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamedEvent {
+    /// Event type, e.g. `deposit.success`, or `lagged` when the stream fell
+    /// behind
+    pub event: String,
+    /// The event's webhook payload
+    pub data: serde_json::Value,
+}
+
+/// Live events from `GET /api/stream`, read one at a time with
+/// [`EventStream::next_event`].
+pub struct EventStream {
+    response: reqwest::Response,
+    /// Bytes received but not yet split into lines
+    buffer: Vec<u8>,
+    frame: SseFrame,
+}
+
+impl EventStream {
+    /// Waits for the next event; `None` once the server ends the stream.
+    pub async fn next_event(&mut self) -> Option<Result<StreamedEvent, ClientError>> {
+        loop {
+            while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                if let Some(event) = self.frame.push_line(line.trim_end_matches(['\n', '\r'])) {
+                    return Some(event);
+                }
+            }
+            match self.response.chunk().await {
+                Ok(Some(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+/// The fields of the Server-Sent Events frame being read.
+#[derive(Debug, Default)]
+struct SseFrame {
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseFrame {
+    /// Adds one line of the stream, returning the event once a blank line
+    /// ends a frame with data. Comments (keep-alives) are skipped.
+    fn push_line(&mut self, line: &str) -> Option<Result<StreamedEvent, ClientError>> {
+        if line.is_empty() {
+            let frame = std::mem::take(self);
+            if frame.data.is_empty() {
+                return None;
+            }
+            return Some(
+                serde_json::from_str(&frame.data.join("\n"))
+                    .map(|data| StreamedEvent {
+                        event: frame.event.unwrap_or_else(|| "message".to_string()),
+                        data,
+                    })
+                    .map_err(ClientError::from),
+            );
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Header the payment endpoints read an idempotency key from.
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Responses worth retrying: rate limited, or a gateway or the API itself
+/// temporarily unable to serve the request.
+const RETRYABLE_STATUSES: [StatusCode; 4] = [
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+/// Timeouts and retry policy of a [`PaymentsClient`].
+///
+/// Only requests that are safe to repeat are retried: reads, `PUT`s, and
+/// payments carrying an idempotency key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientConfig {
+    /// How long to wait for a connection to the API
+    pub connect_timeout: Duration,
+    /// How long one attempt may take, response body included; `None` waits
+    /// as long as the server does. Event streams are never timed out.
+    pub request_timeout: Option<Duration>,
+    /// Attempts made after the first one fails with a connection error, a
+    /// timeout, or a 429, 502, 503 or 504 response
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled before each one after it
+    pub initial_backoff: Duration,
+    /// Longest wait between attempts, including one asked for by a
+    /// `Retry-After` header
+    pub max_backoff: Duration,
+    /// Gives deposits, withdrawals and transfers without an idempotency key
+    /// a random one, so they can be retried without paying twice
+    pub generate_idempotency_keys: bool,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Some(Duration::from_secs(30)),
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            generate_idempotency_keys: true,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// How long to wait before retry number `retry` (from 0), honouring the
+    /// server's `Retry-After` when it sent one.
+    fn backoff(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| {
+                self.initial_backoff
+                    .saturating_mul(2u32.saturating_pow(retry))
+            })
+            .min(self.max_backoff)
+    }
+}
+
+/// Builds a [`PaymentsClient`]; see [`PaymentsClient::builder`].
+pub struct PaymentsClientBuilder {
+    base_url: String,
+    api_key: Option<String>,
+    config: ClientConfig,
+}
+
+impl PaymentsClientBuilder {
+    /// Sets the API key for authentication.
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Replaces the whole timeout and retry policy.
+    pub fn config(mut self, config: ClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets how long to wait for a connection to the API.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets how long one attempt may take, or `None` for no limit.
+    pub fn request_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.request_timeout = timeout;
+        self
+    }
+
+    /// Sets how many times a failed request that is safe to repeat is
+    /// retried; 0 makes a single attempt.
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.config.max_retries = retries;
+        self
+    }
+
+    /// Sets the wait before the first retry and the longest wait between
+    /// any two attempts.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.config.initial_backoff = initial;
+        self.config.max_backoff = max;
+        self
+    }
+
+    /// Sets whether payments without an idempotency key get a random one.
+    pub fn generate_idempotency_keys(mut self, generate: bool) -> Self {
+        self.config.generate_idempotency_keys = generate;
+        self
+    }
+
+    /// Builds the client.
+    pub fn build(self) -> Result<PaymentsClient, ClientError> {
+        let http = Client::builder()
+            .connect_timeout(self.config.connect_timeout)
+            .build()?;
+        Ok(PaymentsClient {
+            base_url: self.base_url.trim_end_matches('/').to_string(),
+            api_key: self.api_key,
+            http,
+            config: self.config,
+        })
+    }
 }
 
 /// Payments API client.
@@ -49,15 +348,29 @@ pub struct PaymentsClient {
     base_url: String,
     api_key: Option<String>,
     http: Client,
+    config: ClientConfig,
 }
 
 impl PaymentsClient {
-    /// Creates a new client.
+    /// Creates a new client with the default [`ClientConfig`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, like
+    /// [`reqwest::Client::new`]; use [`PaymentsClient::builder`] to handle
+    /// that as an error.
     pub fn new(base_url: impl Into<String>) -> Self {
-        Self {
-            base_url: base_url.into().trim_end_matches('/').to_string(),
+        Self::builder(base_url)
+            .build()
+            .expect("failed to initialize the HTTP client")
+    }
+
+    /// Starts building a client with its own timeouts and retry policy.
+    pub fn builder(base_url: impl Into<String>) -> PaymentsClientBuilder {
+        PaymentsClientBuilder {
+            base_url: base_url.into(),
             api_key: None,
-            http: Client::new(),
+            config: ClientConfig::default(),
         }
     }
 
@@ -67,16 +380,48 @@ impl PaymentsClient {
         self
     }
 
-    /// Checks if the API is healthy.
+    /// Checks if the API is live, i.e. the process is serving requests.
     pub async fn health(&self) -> Result<bool, ClientError> {
         let resp = self
             .http
-            .get(format!("{}/health", self.base_url))
+            .get(format!("{}/health/live", self.base_url))
+            .send()
+            .await?;
+        Ok(resp.status().is_success())
+    }
+
+    /// Checks whether the API is ready to serve requests, i.e. its database
+    /// is reachable. Works without an API key.
+    pub async fn ready(&self) -> Result<bool, ClientError> {
+        let resp = self
+            .http
+            .get(format!("{}/health/ready", self.base_url))
             .send()
             .await?;
         Ok(resp.status().is_success())
     }
 
+    /// Returns the readiness probe's report: each dependency's status and
+    /// latency, whether or not the API is ready. Works without an API key.
+    pub async fn readiness(&self) -> Result<Readiness, ClientError> {
+        let resp = self
+            .http
+            .get(format!("{}/health/ready", self.base_url))
+            .send()
+            .await?;
+        if resp.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            let body = resp.text().await?;
+            return Ok(serde_json::from_str(&body)?);
+        }
+        self.handle_response(resp).await
+    }
+
+    /// Returns the public status page: coarse health and the incident banner.
+    /// Works without an API key.
+    pub async fn service_status(&self) -> Result<ServiceStatus, ClientError> {
+        self.get("/status").await
+    }
+
     /// Bootstraps the first API key (only works when no keys exist).
     /// Returns the raw API key that should be saved securely.
     pub async fn bootstrap(&self, name: &str) -> Result<String, ClientError> {
@@ -99,32 +444,40 @@ impl PaymentsClient {
             .send()
             .await?;
 
-        let status = resp.status();
-        if status.is_success() {
+        if resp.status().is_success() {
             let body: BootstrapResponse = resp.json().await?;
             Ok(body.api_key)
         } else {
-            let body = resp.text().await.unwrap_or_default();
-            let message = serde_json::from_str::<serde_json::Value>(&body)
-                .ok()
-                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
-                .unwrap_or(body);
-            Err(ClientError::Api {
-                status: status.as_u16(),
-                message,
-            })
+            Err(Self::api_error(resp).await)
         }
     }
 
-    /// Creates a new account.
+    /// Creates a new account held by `owner_id`.
     pub async fn create_account(
         &self,
+        owner_id: OwnerId,
+        name: &str,
+        currency: CurrencyCode,
+    ) -> Result<Account, ClientError> {
+        self.create_account_with_overdraft(owner_id, name, currency, 0)
+            .await
+    }
+
+    /// Creates a new account held by `owner_id` whose balance may go
+    /// `overdraft_limit` minor units below zero.
+    pub async fn create_account_with_overdraft(
+        &self,
+        owner_id: OwnerId,
         name: &str,
         currency: CurrencyCode,
+        overdraft_limit: i64,
     ) -> Result<Account, ClientError> {
         let req = CreateAccountRequest {
+            owner_id,
             name: name.to_string(),
             currency,
+            overdraft_limit,
+            mode: None,
         };
         self.post("/api/accounts", &req).await
     }
@@ -139,11 +492,35 @@ impl PaymentsClient {
         self.get("/api/accounts").await
     }
 
+    /// Lists the accounts an owner holds, newest first.
+    pub async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+    ) -> Result<Vec<Account>, ClientError> {
+        let params = AccountListQuery {
+            owner_id: Some(owner_id),
+        };
+        self.get_with_query("/api/accounts", &params).await
+    }
+
+    /// Finds accounts whose name contains `query` or whose ID starts with it.
+    pub async fn search_accounts(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Account>, ClientError> {
+        let params = AccountSearchQuery {
+            q: query.to_string(),
+            limit,
+        };
+        self.get_with_query("/api/accounts/search", &params).await
+    }
+
     /// Deposits money into an account.
     pub async fn deposit(
         &self,
         account_id: AccountId,
-        amount: i64,
+        amount: PositiveAmount,
         currency: CurrencyCode,
         idempotency_key: Option<String>,
         reference: Option<String>,
@@ -154,15 +531,34 @@ impl PaymentsClient {
             currency,
             idempotency_key,
             reference,
+            counterparty: None,
+            metadata: HashMap::new(),
+            encrypted_memo: None,
         };
-        self.post("/api/transactions/deposit", &req).await
+        Ok(self.send_deposit(&req).await?.value)
+    }
+
+    /// Submits a fully specified deposit (e.g. with counterparty details),
+    /// returning it with any warnings the server raised.
+    ///
+    /// The account may be given as an [`AccountRef`] to deposit by alias.
+    pub async fn send_deposit<A: Serialize>(
+        &self,
+        req: &DepositRequest<A>,
+    ) -> Result<WithWarnings<Transaction>, ClientError> {
+        self.post_payment(
+            "/api/transactions/deposit",
+            req,
+            req.idempotency_key.as_deref(),
+        )
+        .await
     }
 
     /// Withdraws money from an account.
     pub async fn withdraw(
         &self,
         account_id: AccountId,
-        amount: i64,
+        amount: PositiveAmount,
         currency: CurrencyCode,
         idempotency_key: Option<String>,
         reference: Option<String>,
@@ -173,8 +569,28 @@ impl PaymentsClient {
             currency,
             idempotency_key,
             reference,
+            counterparty: None,
+            purpose_code: None,
+            metadata: HashMap::new(),
+            encrypted_memo: None,
         };
-        self.post("/api/transactions/withdraw", &req).await
+        Ok(self.send_withdrawal(&req).await?.value)
+    }
+
+    /// Submits a fully specified withdrawal (e.g. with counterparty details),
+    /// returning it with any warnings the server raised.
+    ///
+    /// The account may be given as an [`AccountRef`] to withdraw by alias.
+    pub async fn send_withdrawal<A: Serialize>(
+        &self,
+        req: &WithdrawRequest<A>,
+    ) -> Result<WithWarnings<Transaction>, ClientError> {
+        self.post_payment(
+            "/api/transactions/withdraw",
+            req,
+            req.idempotency_key.as_deref(),
+        )
+        .await
     }
 
     /// Transfers money between accounts.
@@ -182,7 +598,7 @@ impl PaymentsClient {
         &self,
         from_account_id: AccountId,
         to_account_id: AccountId,
-        amount: i64,
+        amount: PositiveAmount,
         currency: CurrencyCode,
         idempotency_key: Option<String>,
         reference: Option<String>,
@@ -194,156 +610,1588 @@ impl PaymentsClient {
             currency,
             idempotency_key,
             reference,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: HashMap::new(),
+            encrypted_memo: None,
         };
-        self.post("/api/transactions/transfer", &req).await
+        Ok(self.send_transfer(&req).await?.value)
     }
 
-    /// Registers a new webhook endpoint.
-    /// Returns the webhook with its secret for verifying signatures.
-    pub async fn register_webhook(
+    /// Sends a fully specified transfer request (e.g. with a purpose code),
+    /// returning it with any warnings the server raised.
+    ///
+    /// Either account may be given as an [`AccountRef`] to transfer by alias.
+    pub async fn send_transfer<A: Serialize>(
         &self,
-        url: &str,
-        events: Vec<String>,
-    ) -> Result<WebhookResponse, ClientError> {
-        #[derive(serde::Serialize)]
-        struct RegisterWebhookRequest {
-            url: String,
-            events: Vec<String>,
-        }
+        req: &TransferRequest<A>,
+    ) -> Result<WithWarnings<Transaction>, ClientError> {
+        self.post_payment(
+            "/api/transactions/transfer",
+            req,
+            req.idempotency_key.as_deref(),
+        )
+        .await
+    }
 
-        let req = RegisterWebhookRequest {
-            url: url.to_string(),
-            events,
-        };
-        self.post("/api/webhooks", &req).await
+    /// Makes many deposits, withdrawals and transfers in one request,
+    /// returning a result per operation in request order.
+    ///
+    /// Accounts may be given as [`AccountRef`]s to pay by alias. A failed
+    /// operation is reported in its result, not as an error.
+    pub async fn batch<A: Serialize>(
+        &self,
+        req: &BatchRequest<A>,
+    ) -> Result<BatchResponse, ClientError> {
+        self.post("/api/transactions/batch", req).await
     }
 
-    /// Lists all registered webhook endpoints.
-    pub async fn list_webhooks(&self) -> Result<Vec<WebhookResponse>, ClientError> {
-        self.get("/api/webhooks").await
+    /// Gets the spending rules applied to an account's outgoing payments.
+    pub async fn spending_rules(
+        &self,
+        account_id: AccountId,
+    ) -> Result<SpendingRules, ClientError> {
+        self.get(&format!("/api/accounts/{}/spending-rules", account_id))
+            .await
     }
 
-    // ─────────────────────────────────────────────────────────────────────────────
-    // API Key Management
-    // ─────────────────────────────────────────────────────────────────────────────
+    /// Replaces an account's spending rules (requires an admin key).
+    pub async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<SpendingRules, ClientError> {
+        self.put(
+            &format!("/api/accounts/{}/spending-rules", account_id),
+            rules,
+        )
+        .await
+    }
 
-    /// Creates a new API key (requires authentication).
-    /// Returns the raw API key that should be saved securely.
-    pub async fn create_api_key(&self, name: &str) -> Result<String, ClientError> {
-        #[derive(serde::Serialize)]
-        struct CreateApiKeyRequest {
-            name: String,
-        }
-        #[derive(serde::Deserialize)]
-        struct CreateApiKeyResponse {
-            api_key: String,
-        }
+    /// Gets the limits configured for an account.
+    pub async fn account_limits(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountLimits, ClientError> {
+        self.get(&format!("/api/accounts/{}/limits", account_id))
+            .await
+    }
 
-        let req = CreateApiKeyRequest {
-            name: name.to_string(),
+    /// Replaces an account's limits (requires an admin key).
+    ///
+    /// Pass `AccountLimits::default()` to clear them.
+    pub async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<AccountLimits, ClientError> {
+        self.put(&format!("/api/accounts/{}/limits", account_id), limits)
+            .await
+    }
+
+    /// Sets how far below zero an account's balance may go (requires an admin
+    /// key).
+    pub async fn set_overdraft_limit(
+        &self,
+        account_id: AccountId,
+        limit: i64,
+    ) -> Result<Account, ClientError> {
+        self.patch(
+            &format!("/api/accounts/{}", account_id),
+            &UpdateAccountRequest {
+                overdraft_limit: Some(limit),
+            },
+        )
+        .await
+    }
+
+    /// Returns a dormant account to active use (requires an admin key).
+    pub async fn reactivate_account(&self, account_id: AccountId) -> Result<Account, ClientError> {
+        self.post(
+            &format!("/api/accounts/{}/reactivate", account_id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Stops money leaving an account until it is unfrozen (requires an
+    /// admin key).
+    pub async fn freeze_account(&self, account_id: AccountId) -> Result<Account, ClientError> {
+        self.post(
+            &format!("/api/accounts/{}/freeze", account_id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Returns a frozen account to active use (requires an admin key).
+    pub async fn unfreeze_account(&self, account_id: AccountId) -> Result<Account, ClientError> {
+        self.post(
+            &format!("/api/accounts/{}/unfreeze", account_id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Closes an account with a zero balance for good (requires an admin
+    /// key).
+    pub async fn close_account(&self, account_id: AccountId) -> Result<Account, ClientError> {
+        self.post(
+            &format!("/api/accounts/{}/close", account_id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Merges a duplicate account into `into`, moving its balance over and
+    /// closing it (requires an admin key).
+    pub async fn merge_account(
+        &self,
+        account_id: AccountId,
+        into: AccountId,
+    ) -> Result<AccountMerge, ClientError> {
+        self.post(
+            &format!("/api/accounts/{}/merge", account_id),
+            &MergeAccountRequest { into: into.into() },
+        )
+        .await
+    }
+
+    /// Lists the aliases registered to an account, oldest first.
+    pub async fn account_aliases(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, ClientError> {
+        self.get(&format!("/api/accounts/{}/aliases", account_id))
+            .await
+    }
+
+    /// Registers an alias (with or without its `@`) to an account.
+    pub async fn add_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &str,
+    ) -> Result<AccountAlias, ClientError> {
+        let req = AddAliasRequest {
+            alias: alias.to_string(),
         };
-        let resp: CreateApiKeyResponse = self.post("/api/keys", &req).await?;
-        Ok(resp.api_key)
+        self.post(&format!("/api/accounts/{}/aliases", account_id), &req)
+            .await
     }
 
-    /// Lists all API keys (without exposing raw key values).
-    pub async fn list_api_keys(&self) -> Result<Vec<ApiKeyInfo>, ClientError> {
-        self.get("/api/keys").await
+    /// Removes an alias from an account, freeing it for reuse.
+    pub async fn remove_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<(), ClientError> {
+        self.delete(&format!(
+            "/api/accounts/{}/aliases/{}",
+            account_id,
+            alias.as_str()
+        ))
+        .await
     }
 
-    /// Deletes (deactivates) an API key by ID.
-    pub async fn delete_api_key(&self, id: &str) -> Result<(), ClientError> {
-        self.delete(&format!("/api/keys/{}", id)).await
+    /// Looks up the account an alias belongs to.
+    pub async fn get_account_alias(&self, alias: &Alias) -> Result<AccountAlias, ClientError> {
+        self.get(&format!("/api/aliases/{}", alias.as_str())).await
     }
 
-    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
-        let mut req = self.http.get(format!("{}{}", self.base_url, path));
-        if let Some(key) = &self.api_key {
-            req = req.header("Authorization", format!("Bearer {}", key));
+    /// Resolves an account reference to an account ID, looking up aliases.
+    pub async fn resolve_account(&self, account: &AccountRef) -> Result<AccountId, ClientError> {
+        match account {
+            AccountRef::Id(id) => Ok(*id),
+            AccountRef::Alias(alias) => Ok(self.get_account_alias(alias).await?.account_id),
         }
-        let resp = req.send().await?;
-        self.handle_response(resp).await
     }
 
-    async fn post<T: DeserializeOwned, B: serde::Serialize>(
+    /// Creates an account owner (requires an admin key).
+    pub async fn create_owner(
         &self,
-        path: &str,
-        body: &B,
-    ) -> Result<T, ClientError> {
-        let mut req = self
-            .http
-            .post(format!("{}{}", self.base_url, path))
-            .json(body);
-        if let Some(key) = &self.api_key {
-            req = req.header("Authorization", format!("Bearer {}", key));
-        }
-        let resp = req.send().await?;
-        self.handle_response(resp).await
+        name: &str,
+        email: Option<&str>,
+    ) -> Result<Owner, ClientError> {
+        let req = CreateOwnerRequest {
+            name: name.to_string(),
+            email: email.map(str::to_string),
+        };
+        self.post("/api/owners", &req).await
     }
 
-    async fn delete(&self, path: &str) -> Result<(), ClientError> {
-        let mut req = self.http.delete(format!("{}{}", self.base_url, path));
-        if let Some(key) = &self.api_key {
-            req = req.header("Authorization", format!("Bearer {}", key));
-        }
-        let resp = req.send().await?;
-        let status = resp.status();
-        if status.is_success() {
-            Ok(())
-        } else {
-            let body = resp.text().await.unwrap_or_default();
-            let message = serde_json::from_str::<serde_json::Value>(&body)
-                .ok()
-                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
-                .unwrap_or(body);
-            Err(ClientError::Api {
-                status: status.as_u16(),
-                message,
-            })
-        }
+    /// Lists every account owner, oldest first (requires an admin key).
+    pub async fn list_owners(&self) -> Result<Vec<Owner>, ClientError> {
+        self.get("/api/owners").await
     }
 
-    async fn handle_response<T: DeserializeOwned>(
+    /// Gets an account owner by ID.
+    pub async fn get_owner(&self, id: OwnerId) -> Result<Owner, ClientError> {
+        self.get(&format!("/api/owners/{}", id)).await
+    }
+
+    /// Renames an owner or changes its email (requires an admin key).
+    pub async fn update_owner(
         &self,
-        resp: reqwest::Response,
-    ) -> Result<T, ClientError> {
-        let status = resp.status();
-        if status.is_success() {
-            let body = resp.text().await?;
-            Ok(serde_json::from_str(&body)?)
-        } else {
-            let body = resp.text().await.unwrap_or_default();
-            let message = serde_json::from_str::<serde_json::Value>(&body)
-                .ok()
-                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
-                .unwrap_or(body);
-            Err(ClientError::Api {
-                status: status.as_u16(),
-                message,
-            })
-        }
+        id: OwnerId,
+        req: &UpdateOwnerRequest,
+    ) -> Result<Owner, ClientError> {
+        self.patch(&format!("/api/owners/{}", id), req).await
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Deletes an owner that holds no accounts (requires an admin key).
+    pub async fn delete_owner(&self, id: OwnerId) -> Result<(), ClientError> {
+        self.delete(&format!("/api/owners/{}", id)).await
+    }
 
-    #[test]
-    fn test_client_creation() {
-        let client = PaymentsClient::new("http://localhost:3000");
-        assert_eq!(client.base_url, "http://localhost:3000");
+    /// Lists an account's memo keys, retired ones included, oldest first.
+    pub async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, ClientError> {
+        self.get(&format!("/api/accounts/{}/memo-keys", account_id))
+            .await
     }
 
-    #[test]
-    fn test_client_with_trailing_slash() {
-        let client = PaymentsClient::new("http://localhost:3000/");
-        assert_eq!(client.base_url, "http://localhost:3000");
+    /// Registers the algorithm and fingerprint of a key the account
+    /// encrypts memos with. The key itself is never sent.
+    pub async fn add_memo_key(
+        &self,
+        account_id: AccountId,
+        algorithm: &str,
+        fingerprint: &str,
+    ) -> Result<MemoKey, ClientError> {
+        let req = CreateMemoKeyRequest {
+            algorithm: algorithm.to_string(),
+            fingerprint: fingerprint.to_string(),
+        };
+        self.post(&format!("/api/accounts/{}/memo-keys", account_id), &req)
+            .await
     }
 
-    #[test]
-    fn test_client_with_api_key() {
-        let client = PaymentsClient::new("http://localhost:3000").with_api_key("test-key");
-        assert_eq!(client.api_key, Some("test-key".to_string()));
+    /// Gets one of an account's memo keys.
+    pub async fn get_memo_key(
+        &self,
+        account_id: AccountId,
+        key_id: MemoKeyId,
+    ) -> Result<MemoKey, ClientError> {
+        self.get(&format!(
+            "/api/accounts/{}/memo-keys/{}",
+            account_id, key_id
+        ))
+        .await
+    }
+
+    /// Retires a memo key so new memos can no longer use it.
+    pub async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        key_id: MemoKeyId,
+    ) -> Result<MemoKey, ClientError> {
+        self.delete_with_response(&format!(
+            "/api/accounts/{}/memo-keys/{}",
+            account_id, key_id
+        ))
+        .await
+    }
+
+    /// Creates a fee schedule (requires an admin key).
+    pub async fn create_fee_schedule(
+        &self,
+        name: &str,
+        tiers: Vec<FeeTier>,
+    ) -> Result<FeeSchedule, ClientError> {
+        let req = CreateFeeScheduleRequest {
+            name: name.to_string(),
+            tiers,
+        };
+        self.post("/api/fee-schedules", &req).await
+    }
+
+    /// Lists fee schedules, newest first.
+    pub async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, ClientError> {
+        self.get("/api/fee-schedules").await
+    }
+
+    /// Gets a fee schedule by ID.
+    pub async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<FeeSchedule, ClientError> {
+        self.get(&format!("/api/fee-schedules/{}", id)).await
+    }
+
+    /// Gets an account's fee schedule assignments.
+    pub async fn account_fees(&self, account_id: AccountId) -> Result<AccountFees, ClientError> {
+        self.get(&format!("/api/accounts/{}/fees", account_id))
+            .await
+    }
+
+    /// Applies a fee schedule to an account from `effective_from` (default:
+    /// now). Requires an admin key.
+    pub async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: Option<DateTime<Utc>>,
+    ) -> Result<FeeAssignment, ClientError> {
+        let req = AssignFeeScheduleRequest {
+            fee_schedule_id,
+            effective_from,
+        };
+        self.post(&format!("/api/accounts/{}/fees", account_id), &req)
+            .await
+    }
+
+    /// Quotes the fee for a payment of `amount` from an account.
+    pub async fn quote_fee(
+        &self,
+        account_id: AccountId,
+        amount: PositiveAmount,
+    ) -> Result<FeeQuote, ClientError> {
+        self.get_with_query(
+            &format!("/api/accounts/{}/fees/quote", account_id),
+            &FeeQuoteQuery { amount },
+        )
+        .await
+    }
+
+    /// Batches every unbatched payout made up to `cutoff` (default: now).
+    /// Requires an admin key.
+    pub async fn create_settlement_batch(
+        &self,
+        cutoff: Option<DateTime<Utc>>,
+    ) -> Result<SettlementBatch, ClientError> {
+        let req = CreateSettlementBatchRequest { cutoff };
+        self.post("/api/settlement-batches", &req).await
+    }
+
+    /// Lists settlement batches, newest first (requires an admin key).
+    pub async fn list_settlement_batches(&self) -> Result<Vec<SettlementBatch>, ClientError> {
+        self.get("/api/settlement-batches").await
+    }
+
+    /// Gets a settlement batch by ID (requires an admin key).
+    pub async fn get_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<SettlementBatch, ClientError> {
+        self.get(&format!("/api/settlement-batches/{}", id)).await
+    }
+
+    /// Moves a settlement batch to `status` (requires an admin key).
+    pub async fn update_settlement_batch_status(
+        &self,
+        id: SettlementBatchId,
+        status: SettlementBatchStatus,
+    ) -> Result<SettlementBatch, ClientError> {
+        let req = UpdateSettlementBatchStatusRequest { status };
+        self.post(&format!("/api/settlement-batches/{}/status", id), &req)
+            .await
+    }
+
+    /// Downloads a settlement batch file (requires an admin key).
+    pub async fn export_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+        format: SettlementExportFormat,
+    ) -> Result<String, ClientError> {
+        self.get_text_with_query(
+            &format!("/api/settlement-batches/{}/export", id),
+            &SettlementExportQuery { format },
+        )
+        .await
+    }
+
+    /// Downloads the transactions made on the UTC days `from..=to` as a
+    /// journal-entry CSV for an accounting tool (requires an admin key).
+    pub async fn export_journal(
+        &self,
+        format: JournalExportFormat,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<String, ClientError> {
+        let query = JournalExportQuery { format, from, to };
+        self.get_text_with_query("/api/exports/journal", &query)
+            .await
+    }
+
+    /// Gets the logged events after `query.after_sequence` that were recorded
+    /// on one UTC day (requires an admin key). Ask again from the page's
+    /// cursor until a page comes back empty.
+    pub async fn event_log(&self, query: &EventLogQuery) -> Result<EventLogPage, ClientError> {
+        let body = self
+            .get_text_with_query("/api/exports/events", query)
+            .await?;
+        let events = body
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(EventLogPage::new(query.after_sequence, events))
+    }
+
+    /// Queues a file export to be rendered in the background (requires an
+    /// admin key). Poll [`get_export`](Self::get_export) until it is ready.
+    pub async fn create_export(&self, request: &ExportRequest) -> Result<Export, ClientError> {
+        self.post("/api/exports", request).await
+    }
+
+    /// Gets an export, with a download link once it is ready (requires an
+    /// admin key).
+    pub async fn get_export(&self, id: ExportId) -> Result<ExportDownload, ClientError> {
+        self.get(&format!("/api/exports/{}", id)).await
+    }
+
+    /// Downloads a ready export's file through its signed link.
+    ///
+    /// The request goes to this client's base URL, so it works even when
+    /// the server's public URL is not reachable from here.
+    pub async fn download_export(&self, download_url: &str) -> Result<String, ClientError> {
+        let path = reqwest::Url::parse(download_url)
+            .map(|link| link.path().to_string())
+            .unwrap_or_else(|_| download_url.to_string());
+        self.get_text_with_query(&path, &Vec::<(String, String)>::new())
+            .await
+    }
+
+    /// Reports balances per currency across all accounts, converted into
+    /// `query.base` (admin keys only).
+    pub async fn exposure_report(
+        &self,
+        query: &ExposureQuery,
+    ) -> Result<ExposureReport, ClientError> {
+        self.get_with_query("/api/reports/exposure", query).await
+    }
+
+    /// Reports the float per currency and the money that entered or left the
+    /// ledger each day (admin keys only).
+    pub async fn float_report(&self, query: &FloatReportQuery) -> Result<FloatReport, ClientError> {
+        self.get_with_query("/api/reports/float", query).await
+    }
+
+    /// Sets up a withdrawal or transfer to be made on a schedule.
+    pub async fn create_scheduled_payment(
+        &self,
+        req: &CreateScheduledPaymentRequest,
+    ) -> Result<ScheduledPayment, ClientError> {
+        self.post("/api/scheduled-payments", req).await
+    }
+
+    /// Lists scheduled payments, oldest first; only those drawing on
+    /// `account_id` when it is set.
+    pub async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+    ) -> Result<Vec<ScheduledPayment>, ClientError> {
+        self.get_with_query(
+            "/api/scheduled-payments",
+            &ScheduledPaymentQuery { account_id },
+        )
+        .await
+    }
+
+    /// Gets a scheduled payment and how its last run went.
+    pub async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<ScheduledPayment, ClientError> {
+        self.get(&format!("/api/scheduled-payments/{}", id)).await
+    }
+
+    /// Pauses a scheduled payment until it is resumed.
+    pub async fn pause_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<ScheduledPayment, ClientError> {
+        self.post(
+            &format!("/api/scheduled-payments/{}/pause", id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Resumes a paused scheduled payment from its next occurrence.
+    pub async fn resume_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<ScheduledPayment, ClientError> {
+        self.post(
+            &format!("/api/scheduled-payments/{}/resume", id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Places a hold on an account's funds.
+    pub async fn authorize(&self, req: &AuthorizeRequest) -> Result<Hold, ClientError> {
+        self.post("/api/transactions/authorize", req).await
+    }
+
+    /// Captures `amount` from a hold, or all of it when `amount` is `None`.
+    pub async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: Option<PositiveAmount>,
+    ) -> Result<Hold, ClientError> {
+        self.post(
+            &format!("/api/transactions/{}/capture", id),
+            &CaptureRequest { amount },
+        )
+        .await
+    }
+
+    /// Voids a hold, releasing its funds.
+    pub async fn void_hold(&self, id: HoldId) -> Result<Hold, ClientError> {
+        self.post(
+            &format!("/api/transactions/{}/void", id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Gets a hold by ID.
+    pub async fn get_hold(&self, id: HoldId) -> Result<Hold, ClientError> {
+        self.get(&format!("/api/holds/{}", id)).await
+    }
+
+    /// Lists an account's holds, newest first.
+    pub async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, ClientError> {
+        self.get(&format!("/api/accounts/{}/holds", account_id))
+            .await
+    }
+
+    /// Locks the current exchange rate for converting an amount; pass the
+    /// quote's ID as `quote_id` to [`send_transfer`](Self::send_transfer).
+    pub async fn create_quote(&self, req: &CreateQuoteRequest) -> Result<Quote, ClientError> {
+        self.post("/api/quotes", req).await
+    }
+
+    /// Gets a quote by ID.
+    pub async fn get_quote(&self, id: QuoteId) -> Result<Quote, ClientError> {
+        self.get(&format!("/api/quotes/{}", id)).await
+    }
+
+    /// Adds a beneficiary for an account to pay out to and sends it two
+    /// micro-deposits; confirm them with
+    /// [`verify_beneficiary`](Self::verify_beneficiary).
+    pub async fn create_beneficiary(
+        &self,
+        req: &CreateBeneficiaryRequest,
+    ) -> Result<Beneficiary, ClientError> {
+        self.post("/api/beneficiaries", req).await
+    }
+
+    /// Gets a beneficiary by ID.
+    pub async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Beneficiary, ClientError> {
+        self.get(&format!("/api/beneficiaries/{}", id)).await
+    }
+
+    /// Confirms the two micro-deposit `amounts` sent to a beneficiary, in
+    /// either order.
+    pub async fn verify_beneficiary(
+        &self,
+        id: BeneficiaryId,
+        amounts: [i64; 2],
+    ) -> Result<Beneficiary, ClientError> {
+        self.post(
+            &format!("/api/beneficiaries/{}/verify", id),
+            &VerifyBeneficiaryRequest {
+                amounts: amounts.to_vec(),
+            },
+        )
+        .await
+    }
+
+    /// Lists an account's beneficiaries, newest first.
+    pub async fn list_beneficiaries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, ClientError> {
+        self.get(&format!("/api/accounts/{}/beneficiaries", account_id))
+            .await
+    }
+
+    /// Gets the address an account's statements are emailed to.
+    pub async fn statement_email(
+        &self,
+        account_id: AccountId,
+    ) -> Result<StatementEmail, ClientError> {
+        self.get(&format!("/api/accounts/{}/statement-email", account_id))
+            .await
+    }
+
+    /// Sets the address an account's statements are emailed to; `None`
+    /// clears it so statements are announced by webhook only.
+    pub async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<StatementEmail, ClientError> {
+        let req = StatementEmail {
+            email: email.map(String::from),
+        };
+        self.put(
+            &format!("/api/accounts/{}/statement-email", account_id),
+            &req,
+        )
+        .await
+    }
+
+    /// Lists an account's statements, latest month first.
+    pub async fn list_statements(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Statement>, ClientError> {
+        self.get(&format!("/api/accounts/{}/statements", account_id))
+            .await
+    }
+
+    /// Generates a statement for the UTC days `from..=to`.
+    pub async fn account_statement(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<AccountStatement, ClientError> {
+        let query = AccountStatementQuery {
+            from,
+            to,
+            format: StatementFormat::Json,
+        };
+        self.get_with_query(&format!("/api/accounts/{}/statement", account_id), &query)
+            .await
+    }
+
+    /// Downloads a statement for the UTC days `from..=to` as `format`.
+    pub async fn download_account_statement(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+        format: StatementFormat,
+    ) -> Result<String, ClientError> {
+        let query = AccountStatementQuery { from, to, format };
+        self.get_text_with_query(&format!("/api/accounts/{}/statement", account_id), &query)
+            .await
+    }
+
+    /// Gets an account's recorded end-of-day balances over the UTC days
+    /// `from..=to` (default: the 30 days up to yesterday).
+    pub async fn balance_history(
+        &self,
+        account_id: AccountId,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<BalanceHistory, ClientError> {
+        let query = BalanceHistoryQuery { from, to };
+        self.get_with_query(
+            &format!("/api/accounts/{}/balance-history", account_id),
+            &query,
+        )
+        .await
+    }
+
+    /// Gets a statement with a fresh download link.
+    pub async fn get_statement(&self, id: StatementId) -> Result<StatementDownload, ClientError> {
+        self.get(&format!("/api/statements/{}", id)).await
+    }
+
+    /// Issues statements for `month` (`YYYY-MM`, default: the last full
+    /// month) and returns the new ones. Requires an admin key.
+    pub async fn issue_statements(
+        &self,
+        month: Option<&str>,
+    ) -> Result<Vec<Statement>, ClientError> {
+        let req = IssueStatementsRequest {
+            month: month.map(String::from),
+        };
+        self.post("/api/statements/issue", &req).await
+    }
+
+    /// Downloads a statement file through its signed link.
+    ///
+    /// The request goes to this client's base URL, so it works even when
+    /// the server's public URL is not reachable from here.
+    pub async fn download_statement(
+        &self,
+        download: &StatementDownload,
+    ) -> Result<String, ClientError> {
+        let query: Vec<(String, String)> = reqwest::Url::parse(&download.download_url)
+            .map(|link| link.query_pairs().into_owned().collect())
+            .unwrap_or_default();
+        self.get_text_with_query(
+            &format!("/api/statements/{}/download", download.statement.id),
+            &query,
+        )
+        .await
+    }
+
+    /// Lists a page of an account's transactions, newest first.
+    ///
+    /// Pass the page's `next_cursor` back in `query.cursor`, with the same
+    /// filters, to get the next page.
+    pub async fn list_transactions(
+        &self,
+        account_id: AccountId,
+        query: &TransactionListQuery,
+    ) -> Result<TransactionPage, ClientError> {
+        self.get_with_query(&format!("/api/accounts/{}/transactions", account_id), query)
+            .await
+    }
+
+    /// Lists every transaction of an account matching `query`'s filters,
+    /// newest first, following `next_cursor` from page to page.
+    pub async fn list_all_transactions(
+        &self,
+        account_id: AccountId,
+        query: &TransactionListQuery,
+    ) -> Result<Vec<Transaction>, ClientError> {
+        let mut query = query.clone();
+        let mut transactions = Vec::new();
+        loop {
+            let page = self.list_transactions(account_id, &query).await?;
+            transactions.extend(page.data);
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => return Ok(transactions),
+            }
+        }
+    }
+
+    /// Searches transactions across accounts, newest first; paged like
+    /// [`list_transactions`](Self::list_transactions).
+    pub async fn search_transactions(
+        &self,
+        query: &TransactionSearchQuery,
+    ) -> Result<TransactionPage, ClientError> {
+        self.get_with_query("/api/transactions/search", query).await
+    }
+
+    /// Records a payment received over external rails; needs a key with the
+    /// `inbound:write` scope.
+    ///
+    /// Reporting an external reference again returns the original deposit
+    /// with `duplicate` set. The account may be given as an [`AccountRef`].
+    pub async fn receive_inbound_payment<A: Serialize>(
+        &self,
+        req: &InboundPaymentRequest<A>,
+    ) -> Result<InboundPayment, ClientError> {
+        self.post("/api/inbound-payments", req).await
+    }
+
+    /// Registers a new webhook endpoint.
+    /// Returns the webhook with its secret for verifying signatures.
+    pub async fn register_webhook(
+        &self,
+        url: &str,
+        events: Vec<String>,
+    ) -> Result<WebhookResponse, ClientError> {
+        let req = RegisterWebhookRequest {
+            url: url.to_string(),
+            events,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            signature_algorithm: Default::default(),
+        };
+        self.send_webhook_registration(&req).await
+    }
+
+    /// Registers a webhook endpoint with explicit delivery timeouts or
+    /// signature algorithm.
+    pub async fn send_webhook_registration(
+        &self,
+        req: &RegisterWebhookRequest,
+    ) -> Result<WebhookResponse, ClientError> {
+        self.post("/api/webhooks", req).await
+    }
+
+    /// Registers several webhook endpoints at once: all of them or none.
+    /// Returns them in request order, each with its own secret.
+    pub async fn register_webhooks(
+        &self,
+        endpoints: Vec<RegisterWebhookRequest>,
+    ) -> Result<Vec<WebhookResponse>, ClientError> {
+        let req = RegisterWebhooksRequest { endpoints };
+        let response: RegisteredWebhooks = self.post("/api/webhooks/batch", &req).await?;
+        Ok(response.endpoints)
+    }
+
+    /// Requests a new URL for a webhook endpoint. Deliveries keep going to
+    /// the current URL until a different admin key approves the returned
+    /// change.
+    pub async fn update_webhook_url(
+        &self,
+        id: &str,
+        url: &str,
+    ) -> Result<ChangeRequest, ClientError> {
+        let req = UpdateWebhookRequest {
+            url: Some(url.to_string()),
+            ..Default::default()
+        };
+        self.patch(&format!("/api/webhooks/{}", id), &req).await
+    }
+
+    /// Replaces a webhook endpoint's subscriptions and/or pauses or resumes
+    /// its deliveries. Unlike a URL change, this applies at once.
+    pub async fn update_webhook(
+        &self,
+        id: &str,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+    ) -> Result<WebhookResponse, ClientError> {
+        let req = UpdateWebhookRequest {
+            events,
+            is_active,
+            ..Default::default()
+        };
+        self.patch(&format!("/api/webhooks/{}", id), &req).await
+    }
+
+    /// Updates a webhook endpoint with every change `req` allows at once,
+    /// such as its signature algorithm.
+    pub async fn send_webhook_update(
+        &self,
+        id: &str,
+        req: &UpdateWebhookRequest,
+    ) -> Result<WebhookResponse, ClientError> {
+        self.patch(&format!("/api/webhooks/{}", id), req).await
+    }
+
+    /// Gives a webhook endpoint a new signing secret; the response carries it.
+    pub async fn rotate_webhook_secret(&self, id: &str) -> Result<WebhookResponse, ClientError> {
+        self.post(
+            &format!("/api/webhooks/{}/rotate-secret", id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Deletes a webhook endpoint. Its past events stay readable.
+    pub async fn delete_webhook(&self, id: &str) -> Result<(), ClientError> {
+        self.delete(&format!("/api/webhooks/{}", id)).await
+    }
+
+    /// Lists the event types webhooks can subscribe to.
+    pub async fn list_event_types(&self) -> Result<Vec<EventType>, ClientError> {
+        self.get("/api/webhooks/events").await
+    }
+
+    /// Opens the live event stream, sending only events matching one of the
+    /// comma-separated `events` patterns (e.g. `deposit.*,transfer.success`),
+    /// or every event the key may see when `None`.
+    pub async fn stream_events(&self, events: Option<&str>) -> Result<EventStream, ClientError> {
+        let query = EventStreamQuery {
+            events: events.map(String::from),
+        };
+        // Sent without the request timeout, which would end the stream
+        let mut req = self
+            .http
+            .get(format!("{}/api/stream", self.base_url))
+            .query(&query);
+        if let Some(key) = &self.api_key {
+            req = req.header(AUTHORIZATION, format!("Bearer {}", key));
+        }
+        let resp = req.send().await?;
+        if !resp.status().is_success() {
+            return Err(Self::api_error(resp).await);
+        }
+        Ok(EventStream {
+            response: resp,
+            buffer: Vec::new(),
+            frame: SseFrame::default(),
+        })
+    }
+
+    /// Gets a webhook event with its full payload (admin keys only), e.g. one
+    /// delivered as a `payload_truncated` stub.
+    pub async fn webhook_event(&self, id: &str) -> Result<WebhookEventResponse, ClientError> {
+        self.get(&format!("/api/webhooks/events/{}", id)).await
+    }
+
+    /// Exports dead-lettered events matching `query`, oldest first, with
+    /// their full payloads (admin keys only).
+    pub async fn dead_lettered_webhooks(
+        &self,
+        query: &DeadLetterQuery,
+    ) -> Result<Vec<WebhookEventResponse>, ClientError> {
+        self.get_with_query("/api/webhooks/events/dead-letters", query)
+            .await
+    }
+
+    /// Requeues dead-lettered events matching `filter` for delivery (admin
+    /// keys only); an empty filter requeues every dead letter.
+    pub async fn retry_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterQuery,
+    ) -> Result<DeadLetterRetryResponse, ClientError> {
+        self.post("/api/webhooks/events/retry", filter).await
+    }
+
+    /// Lists an endpoint's events after `query.after_sequence`, lowest
+    /// sequence first (admin keys only), to catch up on missed deliveries.
+    pub async fn webhook_events_after(
+        &self,
+        endpoint_id: &str,
+        query: &WebhookEventsQuery,
+    ) -> Result<Vec<WebhookEventResponse>, ClientError> {
+        self.get_with_query(&format!("/api/webhooks/{}/events", endpoint_id), query)
+            .await
+    }
+
+    /// Lists an endpoint's delivery log, most recent first, without payloads
+    /// (admin keys only).
+    pub async fn webhook_deliveries(
+        &self,
+        endpoint_id: &str,
+        query: &WebhookDeliveriesQuery,
+    ) -> Result<Vec<WebhookDeliveryResponse>, ClientError> {
+        self.get_with_query(&format!("/api/webhooks/{}/deliveries", endpoint_id), query)
+            .await
+    }
+
+    /// Requeues a failed or dead-lettered event for delivery (admin keys
+    /// only).
+    pub async fn redeliver_webhook_event(
+        &self,
+        event_id: &str,
+    ) -> Result<WebhookDeliveryResponse, ClientError> {
+        self.post(
+            &format!("/api/webhooks/deliveries/{}/redeliver", event_id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Lists all registered webhook endpoints.
+    pub async fn list_webhooks(&self) -> Result<Vec<WebhookResponse>, ClientError> {
+        self.get("/api/webhooks").await
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // API Key Management
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Creates a new API key (requires an admin key).
+    /// Returns the raw API key that should be saved securely.
+    pub async fn create_api_key(&self, name: &str) -> Result<String, ClientError> {
+        self.post_api_key(name, None, None, None, None).await
+    }
+
+    /// Creates an admin key in test mode, which only sees and moves money
+    /// between test accounts, with `scopes` or only the read scopes
+    /// (requires an admin key).
+    /// Returns the raw `sk_test_` key that should be saved securely.
+    pub async fn create_test_api_key(
+        &self,
+        name: &str,
+        scopes: Option<&[ApiKeyScope]>,
+    ) -> Result<String, ClientError> {
+        self.post_api_key(name, None, None, scopes, Some(Mode::Test))
+            .await
+    }
+
+    /// Creates an API key that can only read `account_id` (requires an
+    /// admin key). Returns the raw API key that should be saved securely.
+    pub async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+    ) -> Result<String, ClientError> {
+        self.post_api_key(name, Some(account_id), None, None, None)
+            .await
+    }
+
+    /// Creates an API key that can only operate on the accounts `owner_id`
+    /// holds, with `scopes` or only the read scopes (requires an admin key). Returns the raw API key that should be saved securely.
+    pub async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: Option<&[ApiKeyScope]>,
+    ) -> Result<String, ClientError> {
+        self.post_api_key(name, None, Some(owner_id), scopes, None)
+            .await
+    }
+
+    /// Creates an API key holding only `scopes`, optionally restricted to
+    /// `account_id` (requires an admin key with every scope it grants).
+    /// Returns the raw API key that should be saved securely.
+    pub async fn create_api_key_with_scopes(
+        &self,
+        name: &str,
+        account_id: Option<AccountId>,
+        scopes: &[ApiKeyScope],
+    ) -> Result<String, ClientError> {
+        self.post_api_key(name, account_id, None, Some(scopes), None)
+            .await
+    }
+
+    async fn post_api_key(
+        &self,
+        name: &str,
+        account_id: Option<AccountId>,
+        owner_id: Option<OwnerId>,
+        scopes: Option<&[ApiKeyScope]>,
+        mode: Option<Mode>,
+    ) -> Result<String, ClientError> {
+        #[derive(serde::Serialize)]
+        struct CreateApiKeyRequest<'a> {
+            name: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            account_id: Option<AccountId>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            owner_id: Option<OwnerId>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            scopes: Option<&'a [ApiKeyScope]>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            mode: Option<Mode>,
+        }
+        #[derive(serde::Deserialize)]
+        struct CreateApiKeyResponse {
+            api_key: String,
+        }
+
+        let req = CreateApiKeyRequest {
+            name: name.to_string(),
+            account_id,
+            owner_id,
+            scopes,
+            mode,
+        };
+        let resp: CreateApiKeyResponse = self.post("/api/keys", &req).await?;
+        Ok(resp.api_key)
+    }
+
+    /// Lists all API keys (without exposing raw key values).
+    pub async fn list_api_keys(&self) -> Result<Vec<ApiKeyInfo>, ClientError> {
+        self.get("/api/keys").await
+    }
+
+    /// Gives an API key its own quota of requests per minute, or returns it
+    /// to the server-wide default when `None` (requires an admin key).
+    pub async fn set_api_key_rate_limit(
+        &self,
+        id: &str,
+        requests_per_minute: Option<u32>,
+    ) -> Result<ApiKeyInfo, ClientError> {
+        self.put(
+            &format!("/api/keys/{}/rate-limit", id),
+            &SetApiKeyRateLimitRequest {
+                requests_per_minute,
+            },
+        )
+        .await
+    }
+
+    /// Requests that an API key be deleted (deactivated). The key keeps
+    /// working until a different admin key approves the returned change.
+    pub async fn delete_api_key(&self, id: &str) -> Result<ChangeRequest, ClientError> {
+        self.delete_with_response(&format!("/api/keys/{}", id))
+            .await
+    }
+
+    /// Gets request and money-movement totals for an API key (admin keys, or
+    /// the key itself).
+    pub async fn api_key_usage(&self, id: &str) -> Result<ApiKeyUsage, ClientError> {
+        self.get(&format!("/api/keys/{}/usage", id)).await
+    }
+
+    /// Gets an API key's audit trail: when it was created, used and deleted,
+    /// and by which keys (admin keys only). Deleted keys stay viewable.
+    pub async fn api_key_audit(&self, id: &str) -> Result<ApiKeyAudit, ClientError> {
+        self.get(&format!("/api/keys/{}/audit", id)).await
+    }
+
+    /// Exchanges this client's API key for a token that can only read
+    /// `account_id`, valid for `expires_in_secs` (default 15 minutes, at most
+    /// an hour). Hand the token to a dashboard instead of the key.
+    pub async fn create_session_token(
+        &self,
+        account_id: AccountId,
+        expires_in_secs: Option<u64>,
+    ) -> Result<SessionToken, ClientError> {
+        let req = CreateSessionTokenRequest {
+            account_id,
+            expires_in_secs,
+        };
+        self.post("/api/session-tokens", &req).await
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Admin
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Returns whether the service is in read-only maintenance mode.
+    pub async fn maintenance_status(&self) -> Result<MaintenanceStatus, ClientError> {
+        self.get("/api/admin/maintenance").await
+    }
+
+    /// Switches maintenance mode on or off (requires an admin key).
+    pub async fn set_maintenance(
+        &self,
+        enabled: bool,
+        reason: Option<String>,
+    ) -> Result<MaintenanceStatus, ClientError> {
+        let req = SetMaintenanceRequest { enabled, reason };
+        self.put("/api/admin/maintenance", &req).await
+    }
+
+    /// Posts the incident banner shown on the status page, or clears it when
+    /// `message` is `None` (requires an admin key).
+    pub async fn set_incident(
+        &self,
+        message: Option<String>,
+    ) -> Result<ServiceStatus, ClientError> {
+        let req = SetIncidentRequest { message };
+        self.put("/api/admin/incident", &req).await
+    }
+
+    /// Fetches this instance's metrics in the Prometheus text format
+    /// (requires an admin key).
+    pub async fn metrics(&self) -> Result<String, ClientError> {
+        self.get_text_with_query("/metrics", &()).await
+    }
+
+    /// Returns database latency, schema version, webhook backlog and rate
+    /// limit saturation, with any problems (requires an admin key).
+    pub async fn diagnostics(&self) -> Result<Diagnostics, ClientError> {
+        self.get("/api/admin/diagnostics").await
+    }
+
+    /// Returns row counts against the soft storage quotas (requires an
+    /// admin key).
+    pub async fn storage_report(&self) -> Result<StorageReport, ClientError> {
+        self.get("/api/admin/storage").await
+    }
+
+    /// Records end-of-day balances now instead of waiting for the job and
+    /// returns how many were stored (requires an admin key).
+    pub async fn record_balance_snapshots(&self) -> Result<usize, ClientError> {
+        let resp: BalanceSnapshotResponse = self
+            .post("/api/admin/balance-snapshots", &serde_json::json!({}))
+            .await?;
+        Ok(resp.recorded)
+    }
+
+    /// Lists security changes (key deletions, webhook URL changes), newest
+    /// first, optionally only those in `status` (requires an admin key).
+    pub async fn list_changes(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, ClientError> {
+        self.get_with_query("/api/admin/changes", &ChangeRequestQuery { status })
+            .await
+    }
+
+    /// Gets a security change by ID (requires an admin key).
+    pub async fn get_change(&self, id: ChangeRequestId) -> Result<ChangeRequest, ClientError> {
+        self.get(&format!("/api/admin/changes/{}", id)).await
+    }
+
+    /// Approves and applies a change requested by a different admin key.
+    pub async fn approve_change(&self, id: ChangeRequestId) -> Result<ChangeRequest, ClientError> {
+        self.post(
+            &format!("/api/admin/changes/{}/approve", id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Rejects a pending change without applying it (requires an admin key).
+    pub async fn reject_change(&self, id: ChangeRequestId) -> Result<ChangeRequest, ClientError> {
+        self.post(
+            &format!("/api/admin/changes/{}/reject", id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let url = self.url(path);
+        let resp = self.send(true, || self.http.get(&url)).await?;
+        self.handle_response(resp).await
+    }
+
+    async fn get_with_query<T: DeserializeOwned, Q: serde::Serialize>(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T, ClientError> {
+        let url = self.url(path);
+        let resp = self.send(true, || self.http.get(&url).query(query)).await?;
+        self.handle_response(resp).await
+    }
+
+    /// Like `get_with_query`, but returns the raw body of a file download.
+    async fn get_text_with_query<Q: serde::Serialize>(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<String, ClientError> {
+        let url = self.url(path);
+        let resp = self.send(true, || self.http.get(&url).query(query)).await?;
+        if resp.status().is_success() {
+            Ok(resp.text().await?)
+        } else {
+            Err(Self::api_error(resp).await)
+        }
+    }
+
+    /// Posts `body` once; `POST`s are not safe to repeat in general.
+    async fn post<T: DeserializeOwned, B: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        let url = self.url(path);
+        let resp = self.send(false, || self.http.post(&url).json(body)).await?;
+        self.handle_response(resp).await
+    }
+
+    /// Posts a payment under `idempotency_key`, or a generated one when it
+    /// has none, so a retry returns the first attempt's transaction instead
+    /// of moving the money again.
+    async fn post_payment<T: DeserializeOwned, B: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+        idempotency_key: Option<&str>,
+    ) -> Result<T, ClientError> {
+        let key = match idempotency_key {
+            Some(key) => Some(key.to_string()),
+            None if self.config.generate_idempotency_keys => Some(uuid::Uuid::new_v4().to_string()),
+            None => None,
+        };
+        let url = self.url(path);
+        let resp = self
+            .send(key.is_some(), || {
+                let req = self.http.post(&url).json(body);
+                match &key {
+                    Some(key) => req.header(IDEMPOTENCY_KEY_HEADER, key),
+                    None => req,
+                }
+            })
+            .await?;
+        self.handle_response(resp).await
+    }
+
+    async fn put<T: DeserializeOwned, B: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        let url = self.url(path);
+        let resp = self.send(true, || self.http.put(&url).json(body)).await?;
+        self.handle_response(resp).await
+    }
+
+    async fn patch<T: DeserializeOwned, B: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        let url = self.url(path);
+        let resp = self
+            .send(false, || self.http.patch(&url).json(body))
+            .await?;
+        self.handle_response(resp).await
+    }
+
+    /// Deletes `path` once; deleting a key files a change request, and a
+    /// repeat would file a second one.
+    async fn delete_with_response<T: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<T, ClientError> {
+        let url = self.url(path);
+        let resp = self.send(false, || self.http.delete(&url)).await?;
+        self.handle_response(resp).await
+    }
+
+    /// Deletes `path` once, like [`Self::delete_with_response`].
+    async fn delete(&self, path: &str) -> Result<(), ClientError> {
+        let url = self.url(path);
+        let resp = self.send(false, || self.http.delete(&url)).await?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(Self::api_error(resp).await)
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Sends the request `build` makes with the API key and request timeout.
+    ///
+    /// When `retryable`, connection errors, timeouts and the
+    /// [`RETRYABLE_STATUSES`] are retried up to `max_retries` times, backing
+    /// off in between; the last attempt's outcome is returned.
+    async fn send(
+        &self,
+        retryable: bool,
+        build: impl Fn() -> RequestBuilder,
+    ) -> Result<Response, ClientError> {
+        let mut retry = 0;
+        loop {
+            let mut req = build();
+            if let Some(key) = &self.api_key {
+                req = req.header(AUTHORIZATION, format!("Bearer {}", key));
+            }
+            if let Some(timeout) = self.config.request_timeout {
+                req = req.timeout(timeout);
+            }
+            let result = req.send().await;
+            // Some(wait asked for by the server) when the attempt may be retried
+            let transient = match &result {
+                Ok(resp) if RETRYABLE_STATUSES.contains(&resp.status()) => Some(
+                    resp.headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse().ok())
+                        .map(Duration::from_secs),
+                ),
+                Err(e) if e.is_connect() || e.is_timeout() => Some(None),
+                _ => None,
+            };
+            match transient {
+                Some(retry_after) if retryable && retry < self.config.max_retries => {
+                    tokio::time::sleep(self.config.backoff(retry, retry_after)).await;
+                    retry += 1;
+                }
+                _ => return Ok(result?),
+            }
+        }
+    }
+
+    async fn handle_response<T: DeserializeOwned>(
+        &self,
+        resp: reqwest::Response,
+    ) -> Result<T, ClientError> {
+        if resp.status().is_success() {
+            let body = resp.text().await?;
+            Ok(serde_json::from_str(&body)?)
+        } else {
+            Err(Self::api_error(resp).await)
+        }
+    }
+
+    /// Builds an [`ClientError::Api`] from a failed response, preferring the
+    /// JSON `error` field over the raw body.
+    async fn api_error(resp: reqwest::Response) -> ClientError {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        let problem = serde_json::from_str::<serde_json::Value>(&body).ok();
+        let error_code = problem
+            .as_ref()
+            .and_then(|v| v.get("error_code"))
+            .and_then(|code| ErrorCode::deserialize(code).ok());
+        let message = problem
+            .as_ref()
+            .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
+            .unwrap_or(body);
+        ClientError::Api {
+            status: status.as_u16(),
+            message,
+            error_code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const CREATED: &str = "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\n\
+        Content-Length: 2\r\nConnection: close\r\n\r\n{}";
+
+    /// Answers one connection per canned response, in order, recording the
+    /// head of each request with its header names lowercased.
+    async fn serve(responses: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut chunk = [0; 4096];
+                let head = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    request.extend_from_slice(&chunk[..n]);
+                    if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break String::from_utf8_lossy(&request[..end]).to_lowercase();
+                    }
+                };
+                let body_len: usize = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length: "))
+                    .map_or(0, |len| len.parse().unwrap());
+                while request.len() < head.len() + 4 + body_len {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    request.extend_from_slice(&chunk[..n]);
+                }
+                seen.lock().unwrap().push(head);
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+        request
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+    }
+
+    fn fast_retries(url: &str) -> PaymentsClientBuilder {
+        PaymentsClient::builder(url).backoff(Duration::from_millis(1), Duration::from_millis(1))
+    }
+
+    #[test]
+    fn test_client_creation() {
+        let client = PaymentsClient::new("http://localhost:3000");
+        assert_eq!(client.base_url, "http://localhost:3000");
+    }
+
+    #[test]
+    fn test_client_with_trailing_slash() {
+        let client = PaymentsClient::new("http://localhost:3000/");
+        assert_eq!(client.base_url, "http://localhost:3000");
+    }
+
+    #[test]
+    fn test_sse_frames_become_events() {
+        let mut frame = SseFrame::default();
+        let mut events = Vec::new();
+        for line in [
+            ": keep-alive",
+            "",
+            "event: deposit.success",
+            "data: {\"amount\": 500}",
+            "",
+            "event:lagged",
+            "data:{\"missed\":",
+            "data: 3}",
+            "",
+        ] {
+            if let Some(event) = frame.push_line(line) {
+                events.push(event.unwrap());
+            }
+        }
+        assert_eq!(
+            events,
+            [
+                StreamedEvent {
+                    event: "deposit.success".into(),
+                    data: serde_json::json!({ "amount": 500 }),
+                },
+                StreamedEvent {
+                    event: "lagged".into(),
+                    data: serde_json::json!({ "missed": 3 }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_client_with_api_key() {
+        let client = PaymentsClient::new("http://localhost:3000").with_api_key("test-key");
+        assert_eq!(client.api_key, Some("test-key".to_string()));
+    }
+
+    #[test]
+    fn test_builder_sets_config() {
+        let client = PaymentsClient::builder("http://localhost:3000/")
+            .api_key("test-key")
+            .request_timeout(None)
+            .max_retries(0)
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url, "http://localhost:3000");
+        assert_eq!(client.api_key.as_deref(), Some("test-key"));
+        assert_eq!(
+            client.config,
+            ClientConfig {
+                request_timeout: None,
+                max_retries: 0,
+                ..ClientConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let config = ClientConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            ..ClientConfig::default()
+        };
+        let waits: Vec<_> = (0..5).map(|retry| config.backoff(retry, None)).collect();
+        assert_eq!(waits, [100, 200, 400, 800, 1000].map(Duration::from_millis));
+        assert_eq!(
+            config.backoff(0, Some(Duration::from_millis(300))),
+            Duration::from_millis(300)
+        );
+        assert_eq!(
+            config.backoff(0, Some(Duration::from_secs(60))),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_payments_retry_under_one_idempotency_key() {
+        let (url, requests) = serve(vec![UNAVAILABLE, UNAVAILABLE, CREATED]).await;
+        let client = fast_retries(&url).build().unwrap();
+        let result: serde_json::Value = client
+            .post_payment("/api/transactions/deposit", &serde_json::json!({}), None)
+            .await
+            .unwrap();
+        assert_eq!(result, serde_json::json!({}));
+
+        let requests = requests.lock().unwrap();
+        let keys: Vec<_> = requests
+            .iter()
+            .map(|r| header(r, "idempotency-key").unwrap())
+            .collect();
+        assert_eq!(keys.len(), 3);
+        assert!(keys.iter().all(|key| *key == keys[0]));
+    }
+
+    #[tokio::test]
+    async fn test_requests_unsafe_to_repeat_are_sent_once() {
+        let (url, requests) = serve(vec![UNAVAILABLE, CREATED, UNAVAILABLE]).await;
+        let client = fast_retries(&url)
+            .generate_idempotency_keys(false)
+            .build()
+            .unwrap();
+        let result = client
+            .post_payment::<serde_json::Value, _>("/api/transactions/deposit", &(), None)
+            .await;
+        assert!(matches!(result, Err(ClientError::Api { status: 503, .. })));
+        let result = client
+            .post::<serde_json::Value, _>("/api/accounts", &())
+            .await;
+        assert!(result.is_ok());
+        let result = client.delete_api_key("key").await;
+        assert!(matches!(result, Err(ClientError::Api { status: 503, .. })));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(header(&requests[0], "idempotency-key"), None);
+        assert!(requests[2].starts_with("delete /api/keys/key "));
+    }
+
+    #[tokio::test]
+    async fn test_reads_give_up_after_max_retries() {
+        let (url, requests) = serve(vec![UNAVAILABLE; 3]).await;
+        let client = fast_retries(&url).max_retries(1).build().unwrap();
+        let result = client.list_accounts().await;
+        assert!(matches!(result, Err(ClientError::Api { status: 503, .. })));
+        assert_eq!(requests.lock().unwrap().len(), 2);
     }
 }
@@ -0,0 +1,162 @@
+//! Verifying webhook deliveries.
+//!
+//! Each delivery names how it was signed in `X-Webhook-Signature-Algorithm`.
+//! HMAC signatures are checked with the endpoint's `secret`; Ed25519 ones
+//! with its `public_key`, so a receiver never needs to hold the secret.
+//! Signatures and public keys are hex.
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+
+pub use payments_types::WebhookSignatureAlgorithm;
+
+/// Verifies an HMAC-SHA256 `signature` of `message` under `secret`.
+pub fn verify_hmac_sha256(message: &[u8], signature: &str, secret: &str) -> bool {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(message);
+    hex::decode(signature).is_ok_and(|signature| mac.verify_slice(&signature).is_ok())
+}
+
+/// Verifies an HMAC-SHA512 `signature` of `message` under `secret`.
+pub fn verify_hmac_sha512(message: &[u8], signature: &str, secret: &str) -> bool {
+    let mut mac =
+        Hmac::<Sha512>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(message);
+    hex::decode(signature).is_ok_and(|signature| mac.verify_slice(&signature).is_ok())
+}
+
+/// Verifies an Ed25519 `signature` of `message` against `public_key`.
+pub fn verify_ed25519(message: &[u8], signature: &str, public_key: &str) -> bool {
+    let Some(key) = hex::decode(public_key)
+        .ok()
+        .and_then(|key| <[u8; 32]>::try_from(key).ok())
+        .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+    else {
+        return false;
+    };
+    let Some(signature) = hex::decode(signature)
+        .ok()
+        .and_then(|signature| Signature::from_slice(&signature).ok())
+    else {
+        return false;
+    };
+    key.verify_strict(message, &signature).is_ok()
+}
+
+/// Verifies `signature` of `message` with `algorithm`. `key` is the
+/// endpoint's secret for HMAC algorithms and its public key for Ed25519.
+pub fn verify_webhook_signature(
+    algorithm: WebhookSignatureAlgorithm,
+    message: &[u8],
+    signature: &str,
+    key: &str,
+) -> bool {
+    match algorithm {
+        WebhookSignatureAlgorithm::HmacSha256 => verify_hmac_sha256(message, signature, key),
+        WebhookSignatureAlgorithm::HmacSha512 => verify_hmac_sha512(message, signature, key),
+        WebhookSignatureAlgorithm::Ed25519 => verify_ed25519(message, signature, key),
+    }
+}
+
+/// Verifies a delivery's `X-Webhook-Delivery-Signature`, which signs
+/// `{sequence}.{body}` so the `X-Webhook-Delivery-Sequence` header can be
+/// trusted. `key` is as for [`verify_webhook_signature`].
+pub fn verify_webhook_delivery(
+    algorithm: WebhookSignatureAlgorithm,
+    sequence: i64,
+    body: &[u8],
+    signature: &str,
+    key: &str,
+) -> bool {
+    let mut message = format!("{}.", sequence).into_bytes();
+    message.extend_from_slice(body);
+    verify_webhook_signature(algorithm, &message, signature, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use sha2::Digest;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &[u8] = br#"{"event":"deposit.success"}"#;
+
+    fn hmac_sha256(message: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(message);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn hmac_sha512(message: &[u8]) -> String {
+        let mut mac = Hmac::<Sha512>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(message);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn ed25519_key() -> SigningKey {
+        SigningKey::from_bytes(&Sha256::digest(SECRET.as_bytes()).into())
+    }
+
+    #[test]
+    fn test_hmac_signatures_verify_with_the_secret() {
+        assert!(verify_hmac_sha256(BODY, &hmac_sha256(BODY), SECRET));
+        assert!(verify_hmac_sha512(BODY, &hmac_sha512(BODY), SECRET));
+
+        assert!(!verify_hmac_sha256(BODY, &hmac_sha256(BODY), "whsec_other"));
+        assert!(!verify_hmac_sha256(b"{}", &hmac_sha256(BODY), SECRET));
+        // An algorithm's signature does not pass as the other's
+        assert!(!verify_hmac_sha512(BODY, &hmac_sha256(BODY), SECRET));
+        assert!(!verify_hmac_sha256(BODY, "not hex", SECRET));
+    }
+
+    #[test]
+    fn test_ed25519_signatures_verify_with_the_public_key() {
+        let key = ed25519_key();
+        let public_key = hex::encode(key.verifying_key().to_bytes());
+        let signature = hex::encode(key.sign(BODY).to_bytes());
+
+        assert!(verify_ed25519(BODY, &signature, &public_key));
+        assert!(!verify_ed25519(b"{}", &signature, &public_key));
+        assert!(!verify_ed25519(BODY, &signature, &hex::encode([7u8; 32])));
+        assert!(!verify_ed25519(BODY, &signature, "short"));
+        assert!(!verify_ed25519(BODY, &signature[..64], &public_key));
+    }
+
+    #[test]
+    fn test_delivery_signatures_cover_the_sequence() {
+        let key = ed25519_key();
+        let public_key = hex::encode(key.verifying_key().to_bytes());
+        let message = [b"7.".as_slice(), BODY].concat();
+
+        let cases = [
+            (
+                WebhookSignatureAlgorithm::HmacSha256,
+                hmac_sha256(&message),
+                SECRET.to_string(),
+            ),
+            (
+                WebhookSignatureAlgorithm::HmacSha512,
+                hmac_sha512(&message),
+                SECRET.to_string(),
+            ),
+            (
+                WebhookSignatureAlgorithm::Ed25519,
+                hex::encode(key.sign(&message).to_bytes()),
+                public_key,
+            ),
+        ];
+        for (algorithm, signature, key) in cases {
+            assert!(
+                verify_webhook_delivery(algorithm, 7, BODY, &signature, &key),
+                "{algorithm}"
+            );
+            assert!(
+                !verify_webhook_delivery(algorithm, 8, BODY, &signature, &key),
+                "{algorithm}"
+            );
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Generates the gRPC adapter's message and service types from
+//! `proto/payments/v1/payments.proto` when the `grpc` feature is enabled.
+//!
+//! Uses a vendored `protoc`, so building needs no system install.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto");
+    #[cfg(feature = "grpc")]
+    {
+        let mut config = prost_build::Config::new();
+        config.protoc_executable(protoc_bin_vendored::protoc_bin_path()?);
+        tonic_prost_build::configure().compile_with_config(
+            config,
+            &["proto/payments/v1/payments.proto"],
+            &["proto"],
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,233 @@
+//! Journal-entry exports for accounting tools.
+//!
+//! Every transaction becomes one balanced journal: a debit and a credit of
+//! the same amount, on the GL accounts configured for its type. Amounts are
+//! written in major units in the transaction's own currency; transfers use
+//! the amount debited from the source account.
+
+use std::str::FromStr;
+
+use payments_types::{Transaction, TransactionType};
+
+use crate::settlement::{csv_field, major_units};
+
+/// QuickBooks limits journal numbers to 21 characters.
+const QUICKBOOKS_JOURNAL_NO_LEN: usize = 21;
+
+/// Xero needs a tax rate on every manual journal line; ledger movements of
+/// customer funds are outside the scope of sales tax.
+const XERO_TAX_RATE: &str = "Tax Exempt";
+
+/// GL accounts a transaction type is posted to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlMapping {
+    /// Account debited with the full amount
+    pub debit: String,
+    /// Account credited with the full amount
+    pub credit: String,
+}
+
+impl GlMapping {
+    fn new(debit: &str, credit: &str) -> Self {
+        Self {
+            debit: debit.to_string(),
+            credit: credit.to_string(),
+        }
+    }
+}
+
+/// GL account codes per transaction type.
+///
+/// The defaults post to a cash account `1000` and a customer funds liability
+/// `2000`: deposits debit cash, withdrawals credit it, and transfers move
+/// money within customer funds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlAccountCodes {
+    pub deposit: GlMapping,
+    pub withdrawal: GlMapping,
+    pub transfer: GlMapping,
+}
+
+impl Default for GlAccountCodes {
+    fn default() -> Self {
+        Self {
+            deposit: GlMapping::new("1000", "2000"),
+            withdrawal: GlMapping::new("2000", "1000"),
+            transfer: GlMapping::new("2000", "2000"),
+        }
+    }
+}
+
+impl GlAccountCodes {
+    /// Returns the accounts `transaction_type` is posted to.
+    pub fn for_type(&self, transaction_type: TransactionType) -> &GlMapping {
+        match transaction_type {
+            TransactionType::Deposit => &self.deposit,
+            TransactionType::Withdrawal => &self.withdrawal,
+            TransactionType::Transfer => &self.transfer,
+        }
+    }
+}
+
+/// Parses overrides of the default codes, e.g.
+/// `deposit=1010/2100,withdrawal=2100/1010` (`type=debit/credit`).
+/// Types left out keep their default accounts.
+impl FromStr for GlAccountCodes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut codes = Self::default();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let parsed = entry
+                .split_once('=')
+                .and_then(|(kind, accounts)| Some((kind, accounts.split_once('/')?)));
+            let Some((kind, (debit, credit))) = parsed else {
+                return Err(format!(
+                    "Invalid GL mapping '{}': expected type=debit/credit",
+                    entry
+                ));
+            };
+            let (debit, credit) = (debit.trim(), credit.trim());
+            if debit.is_empty() || credit.is_empty() {
+                return Err(format!("Invalid GL mapping '{}': empty account", entry));
+            }
+            let mapping = match kind.trim().to_ascii_lowercase().as_str() {
+                "deposit" => &mut codes.deposit,
+                "withdrawal" => &mut codes.withdrawal,
+                "transfer" => &mut codes.transfer,
+                other => return Err(format!("Unknown transaction type in GL mapping: {}", other)),
+            };
+            *mapping = GlMapping::new(debit, credit);
+        }
+        Ok(codes)
+    }
+}
+
+/// Renders `transactions` as a QuickBooks Online journal entry import.
+///
+/// Lines sharing a `JournalNo` form one entry; the number is the start of
+/// the transaction ID, with the full ID in the description.
+pub fn render_quickbooks(transactions: &[Transaction], codes: &GlAccountCodes) -> String {
+    let mut out =
+        String::from("JournalNo,JournalDate,AccountName,Debits,Credits,Description,Currency\n");
+    for tx in transactions {
+        let mapping = codes.for_type(tx.transaction_type);
+        let journal_no = tx.id.as_uuid().simple().to_string();
+        let journal_no = &journal_no[..QUICKBOOKS_JOURNAL_NO_LEN];
+        let date = tx.created_at.format("%m/%d/%Y");
+        let amount = major_units(tx.amount.amount(), tx.amount.currency());
+        let description = csv_field(Some(&description(tx)));
+        let currency = tx.amount.currency();
+        for (account, debit, credit) in [
+            (&mapping.debit, amount.as_str(), ""),
+            (&mapping.credit, "", amount.as_str()),
+        ] {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                journal_no,
+                date,
+                csv_field(Some(account)),
+                debit,
+                credit,
+                description,
+                currency
+            ));
+        }
+    }
+    out
+}
+
+/// Renders `transactions` as a Xero manual journal import.
+///
+/// Xero groups lines into journals by narration and date, and signs the
+/// amount: positive for a debit, negative for a credit.
+pub fn render_xero(transactions: &[Transaction], codes: &GlAccountCodes) -> String {
+    let mut out = String::from("*Narration,*Date,Description,*AccountCode,*TaxRate,*Amount\n");
+    for tx in transactions {
+        let mapping = codes.for_type(tx.transaction_type);
+        let narration = format!("{} {}", tx.transaction_type, tx.id);
+        let date = tx.created_at.format("%Y-%m-%d");
+        let amount = major_units(tx.amount.amount(), tx.amount.currency());
+        let description = csv_field(Some(&description(tx)));
+        for (account, sign) in [(&mapping.debit, ""), (&mapping.credit, "-")] {
+            out.push_str(&format!(
+                "{},{},{},{},{},{}{}\n",
+                narration,
+                date,
+                description,
+                csv_field(Some(account)),
+                XERO_TAX_RATE,
+                sign,
+                amount
+            ));
+        }
+    }
+    out
+}
+
+/// `DEPOSIT 7f6c… (USD) - March salary`: type, ID, currency and reference.
+fn description(tx: &Transaction) -> String {
+    let mut description = format!(
+        "{} {} ({})",
+        tx.transaction_type,
+        tx.id,
+        tx.amount.currency()
+    );
+    if let Some(reference) = &tx.reference {
+        description.push_str(" - ");
+        description.push_str(reference);
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use payments_types::{AccountId, CurrencyCode, DynMoney};
+
+    use super::*;
+
+    fn deposit(amount: i64, reference: &str) -> Transaction {
+        Transaction::deposit(
+            AccountId::new(),
+            DynMoney::new(amount, CurrencyCode::USD).unwrap(),
+            None,
+            Some(reference.into()),
+        )
+    }
+
+    #[test]
+    fn test_gl_codes_override_defaults() {
+        let codes: GlAccountCodes = "withdrawal = 2100/1010, TRANSFER=2100/2100"
+            .parse()
+            .unwrap();
+        assert_eq!(codes.deposit, GlAccountCodes::default().deposit);
+        assert_eq!(codes.withdrawal, GlMapping::new("2100", "1010"));
+        assert_eq!(codes.for_type(TransactionType::Transfer).debit, "2100");
+
+        for bad in ["deposit", "deposit=1000", "refund=1/2", "deposit=/2000"] {
+            assert!(bad.parse::<GlAccountCodes>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_quickbooks_entries_balance() {
+        let tx = deposit(12_345, "March, salary");
+        let csv = render_quickbooks(std::slice::from_ref(&tx), &GlAccountCodes::default());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let journal_no = &tx.id.as_uuid().simple().to_string()[..21];
+        assert!(lines[1].starts_with(&format!("{},", journal_no)));
+        assert!(lines[1].contains(",1000,123.45,,\"DEPOSIT "));
+        assert!(lines[2].contains(",2000,,123.45,\"DEPOSIT "));
+        assert!(lines[2].ends_with("- March, salary\",USD"));
+    }
+
+    #[test]
+    fn test_xero_signs_credits() {
+        let tx = deposit(500, "INV-1");
+        let csv = render_xero(&[tx], &GlAccountCodes::default());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[1].ends_with(",1000,Tax Exempt,5.00"));
+        assert!(lines[2].ends_with(",2000,Tax Exempt,-5.00"));
+    }
+}
@@ -0,0 +1,28 @@
+//! Dormant account policy.
+//!
+//! The [`DormancyDetector`](crate::jobs::DormancyDetector) flags accounts
+//! that have gone [`idle_for`](DormancyPolicy::idle_for) without a single
+//! transaction. A dormant account keeps receiving money; whether money may
+//! leave it is up to [`block_withdrawals`](DormancyPolicy::block_withdrawals).
+//! Either way it stays dormant until reactivated through the API.
+
+use chrono::TimeDelta;
+
+/// When accounts become dormant and what that prevents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DormancyPolicy {
+    /// How long an account must go without transactions; 365 days by default.
+    pub idle_for: TimeDelta,
+    /// Rejects withdrawals, outgoing transfers and holds on dormant accounts.
+    /// Off by default.
+    pub block_withdrawals: bool,
+}
+
+impl Default for DormancyPolicy {
+    fn default() -> Self {
+        Self {
+            idle_for: TimeDelta::days(365),
+            block_withdrawals: false,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Event publisher adapters.
+
+use payments_types::{DomainEvent, EventPublisher, Mode};
+use tokio::sync::broadcast;
+
+/// How many events a live stream may fall behind before it misses some.
+pub const LIVE_EVENT_CAPACITY: usize = 1024;
+
+/// A domain event as announced to live streams, with the mode of the
+/// accounts it concerns.
+#[derive(Debug, Clone)]
+pub struct LiveEvent {
+    pub mode: Mode,
+    pub event: DomainEvent,
+}
+
+/// Fans domain events out to any number of in-process subscribers (SSE
+/// streams, a message-bus forwarder, tests).
+///
+/// Slow subscribers that fall more than `capacity` events behind miss the
+/// oldest ones; publishing never blocks the request path.
+#[derive(Clone)]
+pub struct BroadcastPublisher {
+    sender: broadcast::Sender<DomainEvent>,
+}
+
+impl BroadcastPublisher {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Returns a receiver for events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[async_trait::async_trait]
+impl EventPublisher for BroadcastPublisher {
+    async fn publish(&self, event: &DomainEvent) {
+        // An error only means nobody is subscribed right now.
+        let _ = self.sender.send(event.clone());
+    }
+}
@@ -6,14 +6,19 @@ use axum::{
     Json,
     body::Body,
     extract::State,
-    http::{Request, StatusCode},
+    http::{HeaderValue, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 
-use payments_types::TransactionRepository;
+use payments_types::{ErrorCode, ProblemDetails, RepoError, TransactionRepository};
 
 use super::handlers::AppState;
+use crate::sessions::SESSION_TOKEN_PREFIX;
+
+/// Response header naming the mode, `test` or `live`, of the key a request
+/// was made with.
+pub const MODE_HEADER: &str = "x-payments-mode";
 
 /// Extracts the API key from the Authorization header.
 /// Expected format: "Bearer <api_key>" or just "<api_key>"
@@ -33,6 +38,13 @@ fn extract_api_key(auth_header: Option<&str>) -> Option<&str> {
 /// 2. Hashes it using SHA-256
 /// 3. Verifies the hash against the database
 /// 4. Returns 401 Unauthorized if validation fails
+/// 5. Marks the response with the key's mode in [`MODE_HEADER`]
+///
+/// Session tokens (`st_...`) are accepted in place of a key; see
+/// [`crate::sessions`].
+///
+/// Only the headers are checked: the body is passed on unread, so a request
+/// that fails authentication is answered before any of its body is read.
 ///
 /// Endpoints that bypass authentication:
 /// - `/health` - Health check endpoint
@@ -65,29 +77,79 @@ pub async fn auth_middleware<R: TransactionRepository>(
         }
     };
 
-    // Hash the API key
-    let key_hash = payments_repo::security::hash_api_key(api_key);
+    // Session tokens act as the key that issued them, restricted to reading
+    // one account; the claims mark the request as made with a token.
+    let is_session = api_key.starts_with(SESSION_TOKEN_PREFIX);
+    let verified = if is_session {
+        state
+            .service
+            .verify_session_token(api_key)
+            .await
+            .map(|verified| verified.map(|(key, claims)| (key, Some(claims))))
+    } else {
+        // Hash the API key and verify it against the database
+        let key_hash = payments_repo::security::hash_api_key(api_key);
+        state
+            .service
+            .repo()
+            .verify_api_key_hash(&key_hash)
+            .await
+            .map(|key| key.map(|key| (key, None)))
+    };
+    // Keys bound to an owner act on the accounts it holds right now
+    let verified = match verified {
+        Ok(Some((api_key, claims))) => state
+            .service
+            .actor_for(&api_key)
+            .await
+            .map(|actor| Some((api_key, actor, claims))),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
 
-    // Verify against database
-    match state.service.repo().verify_api_key_hash(&key_hash).await {
-        Ok(Some(api_key)) => {
+    match verified {
+        Ok(Some((api_key, actor, claims))) => {
             // API key is valid, proceed with the request
+            state.service.record_api_key_use(&api_key).await;
+            let mode = actor.mode;
+            request.extensions_mut().insert(actor);
             request.extensions_mut().insert(api_key);
-            next.run(request).await
+            if let Some(claims) = claims {
+                request.extensions_mut().insert(claims);
+            }
+            let mut response = next.run(request).await;
+            response
+                .headers_mut()
+                .insert(MODE_HEADER, HeaderValue::from_static(mode.as_str()));
+            response
         }
+        Ok(None) if is_session => unauthorized_response("Invalid or expired session token"),
         Ok(None) => {
             // API key not found or inactive
             unauthorized_response("Invalid API key")
         }
+        Err(RepoError::Unavailable(e)) => {
+            tracing::warn!("API key verification unavailable: {}", e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ProblemDetails::new(
+                    503,
+                    ErrorCode::ServiceUnavailable,
+                    "Service temporarily unavailable, please retry",
+                )),
+            )
+                .into_response()
+        }
         Err(e) => {
             // Database error
             tracing::error!("API key verification failed: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": "Internal server error",
-                    "code": 500
-                })),
+                Json(ProblemDetails::new(
+                    500,
+                    ErrorCode::InternalError,
+                    "Internal server error",
+                )),
             )
                 .into_response()
         }
@@ -97,10 +159,7 @@ pub async fn auth_middleware<R: TransactionRepository>(
 fn unauthorized_response(message: &str) -> Response {
     (
         StatusCode::UNAUTHORIZED,
-        Json(serde_json::json!({
-            "error": message,
-            "code": 401
-        })),
+        Json(ProblemDetails::new(401, ErrorCode::Unauthorized, message)),
     )
         .into_response()
 }
@@ -0,0 +1,107 @@
+//! Liveness, readiness and diagnostics for runbooks.
+//!
+//! The probes need no API key. `GET /health/live` answers as long as the
+//! process serves requests; `GET /health/ready` pings each dependency,
+//! reports its status and latency, and answers 503 while any is down, so
+//! load balancers and orchestrators can take an instance out of rotation
+//! without restarting it. `/health` and `/readyz` are kept as aliases. The
+//! admin-only `GET /api/admin/diagnostics` reports the numbers behind them
+//! and flags any that are past the limits below.
+
+use payments_types::Diagnostics;
+
+/// Path of the public liveness probe.
+pub const LIVE_PATH: &str = "/health/live";
+
+/// Path of the public readiness probe.
+pub const READY_PATH: &str = "/health/ready";
+
+/// Older path of the readiness probe.
+pub const READYZ_PATH: &str = "/readyz";
+
+/// Path of the admin diagnostics endpoint.
+pub const DIAGNOSTICS_PATH: &str = "/api/admin/diagnostics";
+
+/// Database round trips slower than this are reported as degraded.
+pub const SLOW_DATABASE_MS: f64 = 250.0;
+
+/// Webhook events waiting to be delivered beyond this are reported as
+/// degraded.
+pub const MAX_WEBHOOK_BACKLOG: i64 = 1_000;
+
+/// API key quota use beyond this share is reported as degraded.
+pub const MAX_RATE_LIMIT_SATURATION: f64 = 0.9;
+
+/// Returns one line for each check `diagnostics` fails.
+pub fn problems(diagnostics: &Diagnostics) -> Vec<String> {
+    let mut problems = Vec::new();
+    match diagnostics.database_latency_ms {
+        None => problems.push("database is not reachable".to_string()),
+        Some(ms) if ms > SLOW_DATABASE_MS => problems.push(format!(
+            "database answered in {:.0} ms (limit {:.0} ms)",
+            ms, SLOW_DATABASE_MS
+        )),
+        Some(_) => {}
+    }
+    if let Some(backlog) = diagnostics.webhook_backlog
+        && backlog > MAX_WEBHOOK_BACKLOG
+    {
+        problems.push(format!(
+            "webhook backlog is {} events (limit {})",
+            backlog, MAX_WEBHOOK_BACKLOG
+        ));
+    }
+    if diagnostics.rate_limit_saturation > MAX_RATE_LIMIT_SATURATION {
+        problems.push(format!(
+            "busiest API key has used {:.0}% of its quota (limit {:.0}%)",
+            diagnostics.rate_limit_saturation * 100.0,
+            MAX_RATE_LIMIT_SATURATION * 100.0
+        ));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use payments_types::ServiceHealth;
+
+    use super::*;
+
+    fn healthy() -> Diagnostics {
+        Diagnostics {
+            status: ServiceHealth::Operational,
+            database_latency_ms: Some(2.0),
+            schema_version: payments_repo::SCHEMA_VERSION,
+            webhook_backlog: Some(3),
+            rate_limit_saturation: 0.2,
+            problems: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_problems_flag_checks_past_their_limits() {
+        assert!(problems(&healthy()).is_empty());
+
+        let slow = Diagnostics {
+            database_latency_ms: Some(400.0),
+            webhook_backlog: Some(MAX_WEBHOOK_BACKLOG + 1),
+            rate_limit_saturation: 1.0,
+            ..healthy()
+        };
+        assert_eq!(
+            problems(&slow),
+            [
+                "database answered in 400 ms (limit 250 ms)",
+                "webhook backlog is 1001 events (limit 1000)",
+                "busiest API key has used 100% of its quota (limit 90%)",
+            ]
+        );
+
+        let down = Diagnostics {
+            database_latency_ms: None,
+            webhook_backlog: None,
+            ..healthy()
+        };
+        assert_eq!(problems(&down), ["database is not reachable"]);
+    }
+}
@@ -0,0 +1,116 @@
+//! Request body extractors.
+
+use axum::{
+    Json,
+    extract::{FromRequest, OptionalFromRequest, Request, rejection::JsonRejection},
+    response::{IntoResponse, Response},
+};
+
+use payments_types::AppError;
+
+use super::handlers::ApiError;
+
+/// What [`PositiveAmount`](payments_types::PositiveAmount) fails to parse
+/// with, as it appears in a body rejection.
+const NON_POSITIVE_AMOUNT: &str = "Amount must be positive";
+
+/// A JSON body that carries amounts, like [`Json`].
+///
+/// A zero or negative amount fails while the body is parsed, which axum
+/// reports as a plain-text `422`. This extractor answers it as the same
+/// `400 INVALID_AMOUNT` problem the service reports everywhere else; other
+/// rejections are left as axum makes them.
+pub struct PaymentJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for PaymentJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(Self(value)),
+            Err(rejection) => Err(reject(rejection)),
+        }
+    }
+}
+
+/// An optional body: `None` without a `Content-Type`, like `Option<Json<T>>`.
+impl<S, T> OptionalFromRequest<S> for PaymentJson<T>
+where
+    Json<T>: OptionalFromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        match <Json<T> as OptionalFromRequest<S>>::from_request(req, state).await {
+            Ok(value) => Ok(value.map(|Json(value)| Self(value))),
+            Err(rejection) => Err(reject(rejection)),
+        }
+    }
+}
+
+fn reject(rejection: JsonRejection) -> Response {
+    match rejection {
+        JsonRejection::JsonDataError(err) if err.body_text().contains(NON_POSITIVE_AMOUNT) => {
+            ApiError(AppError::InvalidAmount(err.body_text())).into_response()
+        }
+        rejection => rejection.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::StatusCode, routing::post};
+    use http_body_util::BodyExt;
+    use payments_types::{ErrorCode, PositiveAmount, ProblemDetails};
+    use serde::Deserialize;
+    use tower::ServiceExt;
+
+    #[derive(Deserialize)]
+    struct Payment {
+        amount: PositiveAmount,
+    }
+
+    async fn post_body(body: &'static str) -> Response {
+        let app =
+            Router::new().route(
+                "/",
+                post(|PaymentJson(payment): PaymentJson<Payment>| async move {
+                    payment.amount.to_string()
+                }),
+            );
+        let request = Request::post("/")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        app.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_non_positive_amounts_are_invalid_amount_problems() {
+        let response = post_body(r#"{"amount": 250}"#).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for body in [r#"{"amount": 0}"#, r#"{"amount": -5}"#] {
+            let response = post_body(body).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let bytes = response.into_body().collect().await.unwrap().to_bytes();
+            let problem: ProblemDetails = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(problem.error_code, ErrorCode::InvalidAmount);
+            assert!(
+                problem.error.contains(NON_POSITIVE_AMOUNT),
+                "{}",
+                problem.error
+            );
+        }
+
+        // Other malformed bodies are still axum's
+        let response = post_body(r#"{"amount": "ten"}"#).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
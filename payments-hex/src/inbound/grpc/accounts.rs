@@ -0,0 +1,107 @@
+//! `AccountService` RPCs.
+
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use payments_types::{ApiKeyScope, AppError, CreateAccountRequest, TransactionRepository};
+
+use super::proto::{self, account_service_server::AccountService};
+use super::{GrpcState, convert, status};
+
+pub(super) struct Accounts<R: TransactionRepository>(pub(super) Arc<GrpcState<R>>);
+
+#[tonic::async_trait]
+impl<R: TransactionRepository> AccountService for Accounts<R> {
+    #[tracing::instrument(skip_all, fields(owner = %request.get_ref().name))]
+    async fn create_account(
+        &self,
+        request: Request<proto::CreateAccountRequest>,
+    ) -> Result<Response<proto::Account>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::AccountsWrite)
+            .await?;
+        let req = request.into_inner().into_domain().map_err(status)?;
+        // Owner-bound keys only open accounts for their owner
+        if actor.owner_id.is_some() {
+            actor.ensure_owner(req.owner_id).map_err(status)?;
+        }
+        // Accounts are opened in the mode of the key opening them
+        let mode = req.mode.unwrap_or(actor.mode);
+        if mode != actor.mode {
+            return Err(status(AppError::AccessDenied(format!(
+                "a {} API key cannot open {} accounts",
+                actor.mode, mode
+            ))));
+        }
+        let req = CreateAccountRequest {
+            mode: Some(mode),
+            ..req
+        };
+        let account = self.0.service.create_account(req).await.map_err(status)?;
+        Ok(Response::new(account.into()))
+    }
+
+    #[tracing::instrument(skip_all, fields(account_id = %request.get_ref().id))]
+    async fn get_account(
+        &self,
+        request: Request<proto::GetAccountRequest>,
+    ) -> Result<Response<proto::Account>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::AccountsRead)
+            .await?;
+        let account = convert::account_ref(&request.get_ref().id).map_err(status)?;
+        let account_id = self
+            .0
+            .service
+            .resolve_account(&account)
+            .await
+            .map_err(status)?;
+        let account = self
+            .0
+            .service
+            .get_account(&actor, account_id)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(account.into()))
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn list_accounts(
+        &self,
+        request: Request<proto::ListAccountsRequest>,
+    ) -> Result<Response<proto::ListAccountsResponse>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::AccountsRead)
+            .await?;
+        // Scoped keys only ever see their own account, owner-bound keys
+        // their owner's, and every key only the accounts of its mode
+        let accounts = match (actor.account_id, actor.owner_id) {
+            (Some(account_id), _) => vec![
+                self.0
+                    .service
+                    .get_account(&actor, account_id)
+                    .await
+                    .map_err(status)?,
+            ],
+            (None, Some(owner_id)) => self
+                .0
+                .service
+                .list_owner_accounts(owner_id, actor.mode)
+                .await
+                .map_err(status)?,
+            (None, None) => self
+                .0
+                .service
+                .list_accounts(actor.mode)
+                .await
+                .map_err(status)?,
+        };
+        Ok(Response::new(proto::ListAccountsResponse {
+            accounts: accounts.into_iter().map(Into::into).collect(),
+        }))
+    }
+}
@@ -0,0 +1,206 @@
+//! Conversions between protobuf messages and domain types.
+
+use payments_types::{
+    Account, AccountRef, AppError, CreateAccountRequest, CurrencyCode, DepositRequest,
+    EncryptedMemo, PositiveAmount, RiskAssessment, Transaction, TransactionListQuery,
+    TransactionPage, TransferRequest, Warning, WebhookResponse, WithWarnings, WithdrawRequest,
+};
+
+use super::proto;
+
+/// Parses an account ID or `@alias`.
+pub(super) fn account_ref(id: &str) -> Result<AccountRef, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid account ID".into()))
+}
+
+fn encrypted_memo(memo: proto::EncryptedMemo) -> Result<EncryptedMemo, AppError> {
+    Ok(EncryptedMemo {
+        key_id: memo
+            .key_id
+            .parse()
+            .map_err(|_| AppError::BadRequest("Invalid memo key ID".into()))?,
+        ciphertext: memo.ciphertext,
+    })
+}
+
+fn currency(code: &str) -> Result<CurrencyCode, AppError> {
+    code.parse()
+        .map_err(|_| AppError::BadRequest(format!("Unsupported currency: {}", code)))
+}
+
+impl proto::CreateAccountRequest {
+    pub(super) fn into_domain(self) -> Result<CreateAccountRequest, AppError> {
+        let currency = match self.currency.as_str() {
+            "" => CurrencyCode::USD,
+            code => currency(code)?,
+        };
+        let owner_id = self
+            .owner_id
+            .parse()
+            .map_err(|_| AppError::BadRequest("Invalid owner ID".into()))?;
+        let mode = self
+            .mode
+            .map(|mode| mode.parse())
+            .transpose()
+            .map_err(AppError::BadRequest)?;
+        Ok(CreateAccountRequest {
+            owner_id,
+            name: self.name,
+            currency,
+            overdraft_limit: 0,
+            mode,
+        })
+    }
+}
+
+impl proto::DepositRequest {
+    pub(super) fn into_domain(self) -> Result<DepositRequest<AccountRef>, AppError> {
+        Ok(DepositRequest {
+            account_id: account_ref(&self.account_id)?,
+            amount: PositiveAmount::new(self.amount)?,
+            currency: currency(&self.currency)?,
+            idempotency_key: self.idempotency_key,
+            reference: self.reference,
+            counterparty: None,
+            metadata: self.metadata,
+            encrypted_memo: self.encrypted_memo.map(encrypted_memo).transpose()?,
+        })
+    }
+}
+
+impl proto::WithdrawRequest {
+    pub(super) fn into_domain(self) -> Result<WithdrawRequest<AccountRef>, AppError> {
+        Ok(WithdrawRequest {
+            account_id: account_ref(&self.account_id)?,
+            amount: PositiveAmount::new(self.amount)?,
+            currency: currency(&self.currency)?,
+            idempotency_key: self.idempotency_key,
+            reference: self.reference,
+            counterparty: None,
+            purpose_code: self.purpose_code,
+            metadata: self.metadata,
+            encrypted_memo: self.encrypted_memo.map(encrypted_memo).transpose()?,
+        })
+    }
+}
+
+impl proto::TransferRequest {
+    pub(super) fn into_domain(self) -> Result<TransferRequest<AccountRef>, AppError> {
+        Ok(TransferRequest {
+            from_account_id: account_ref(&self.from_account_id)?,
+            to_account_id: account_ref(&self.to_account_id)?,
+            amount: PositiveAmount::new(self.amount)?,
+            currency: currency(&self.currency)?,
+            idempotency_key: self.idempotency_key,
+            reference: self.reference,
+            purpose_code: self.purpose_code,
+            metadata: self.metadata,
+            convert_currency: self.convert_currency,
+            quote_id: None,
+            encrypted_memo: self.encrypted_memo.map(encrypted_memo).transpose()?,
+        })
+    }
+}
+
+impl proto::ListTransactionsRequest {
+    pub(super) fn query(&self) -> TransactionListQuery {
+        TransactionListQuery {
+            limit: self.limit.map(|limit| limit as usize),
+            cursor: self.cursor.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<Account> for proto::Account {
+    fn from(account: Account) -> Self {
+        Self {
+            id: account.id.to_string(),
+            name: account.name,
+            balance: account.balance.amount(),
+            held: account.held,
+            currency: account.balance.currency().to_string(),
+            status: account.status.as_ref().to_string(),
+            status_changed_at: account.status_changed_at.map(|at| at.to_rfc3339()),
+            created_at: account.created_at.to_rfc3339(),
+            owner_id: account.owner_id.to_string(),
+            mode: account.mode.to_string(),
+        }
+    }
+}
+
+impl From<RiskAssessment> for proto::RiskAssessment {
+    fn from(risk: RiskAssessment) -> Self {
+        Self {
+            decision: risk.decision.to_string(),
+            reasons: risk.reasons,
+        }
+    }
+}
+
+impl From<Transaction> for proto::Transaction {
+    fn from(tx: Transaction) -> Self {
+        Self {
+            id: tx.id.to_string(),
+            transaction_type: tx.transaction_type.to_string(),
+            amount: tx.amount.amount(),
+            currency: tx.amount.currency().to_string(),
+            source_account_id: tx.source_account_id.map(|id| id.to_string()),
+            destination_account_id: tx.destination_account_id.map(|id| id.to_string()),
+            idempotency_key: tx.idempotency_key,
+            reference: tx.reference,
+            purpose_code: tx.purpose_code,
+            risk: tx.risk.map(Into::into),
+            created_at: tx.created_at.to_rfc3339(),
+            metadata: tx.metadata,
+            encrypted_memo: tx.encrypted_memo.map(|memo| proto::EncryptedMemo {
+                key_id: memo.key_id.to_string(),
+                ciphertext: memo.ciphertext,
+            }),
+        }
+    }
+}
+
+impl From<Warning> for proto::Warning {
+    fn from(warning: Warning) -> Self {
+        Self {
+            code: warning.code,
+            message: warning.message,
+        }
+    }
+}
+
+impl From<WithWarnings<Transaction>> for proto::PaymentResponse {
+    fn from(payment: WithWarnings<Transaction>) -> Self {
+        Self {
+            transaction: Some(payment.value.into()),
+            warnings: payment.warnings.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<TransactionPage> for proto::TransactionPage {
+    fn from(page: TransactionPage) -> Self {
+        Self {
+            transactions: page.data.into_iter().map(Into::into).collect(),
+            next_cursor: page.next_cursor,
+        }
+    }
+}
+
+impl From<WebhookResponse> for proto::Webhook {
+    fn from(webhook: WebhookResponse) -> Self {
+        Self {
+            id: webhook.id.to_string(),
+            url: webhook.url,
+            secret: webhook.secret,
+            events: webhook.events,
+            is_active: webhook.is_active,
+            timeout_ms: webhook.timeout_ms,
+            connect_timeout_ms: webhook.connect_timeout_ms,
+            signature_algorithm: webhook.signature_algorithm.to_string(),
+            public_key: webhook.public_key,
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! gRPC Inbound Adapter
+//!
+//! Tonic-based server for the account, transaction and webhook RPCs in
+//! `proto/payments/v1/payments.proto`. It drives the same
+//! [`PaymentService`] as the HTTP adapter and honours the same API keys,
+//! scopes and maintenance mode; only rate limiting is HTTP-only.
+
+mod accounts;
+mod convert;
+mod transactions;
+mod webhooks;
+
+use std::sync::Arc;
+
+use tonic::transport::Server;
+use tonic::transport::server::{Router, TcpIncoming};
+use tonic::{Request, Status};
+
+use payments_types::{ActorContext, ApiKeyScope, AppError, RepoError, TransactionRepository};
+
+use super::maintenance::MaintenanceMode;
+use super::server::shutdown_signal;
+use crate::PaymentService;
+
+use accounts::Accounts;
+use transactions::Transactions;
+use webhooks::Webhooks;
+
+use proto::account_service_server::AccountServiceServer;
+use proto::transaction_service_server::TransactionServiceServer;
+use proto::webhook_service_server::WebhookServiceServer;
+
+/// Message types, services and clients generated from the proto file.
+pub mod proto {
+    tonic::include_proto!("payments.v1");
+}
+
+/// What every RPC handler needs.
+struct GrpcState<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    maintenance: Arc<MaintenanceMode>,
+}
+
+impl<R: TransactionRepository> GrpcState<R> {
+    /// Verifies the call's API key and that it has `scope`, the scope of the
+    /// equivalent HTTP route, returning who the call is made by.
+    ///
+    /// Expects `authorization` metadata of `Bearer <api_key>` or just the key.
+    /// Calls needing a write scope are refused while in maintenance mode.
+    async fn authorize<T>(
+        &self,
+        request: &Request<T>,
+        scope: ApiKeyScope,
+    ) -> Result<ActorContext, Status> {
+        let api_key = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.strip_prefix("Bearer ").unwrap_or(v))
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| Status::unauthenticated("Missing or invalid authorization metadata"))?;
+
+        let key_hash = payments_repo::security::hash_api_key(api_key);
+        let verified = match self.service.repo().verify_api_key_hash(&key_hash).await {
+            Ok(Some(api_key)) => self
+                .service
+                .actor_for(&api_key)
+                .await
+                .map(|actor| Some((api_key, actor))),
+            other => other.map(|_| None),
+        };
+        let (api_key, actor) = match verified {
+            Ok(Some(verified)) => verified,
+            Ok(None) => return Err(Status::unauthenticated("Invalid API key")),
+            Err(RepoError::Unavailable(e)) => {
+                tracing::warn!("API key verification unavailable: {}", e);
+                return Err(Status::unavailable(
+                    "Service temporarily unavailable, please retry",
+                ));
+            }
+            Err(e) => {
+                tracing::error!("API key verification failed: {}", e);
+                return Err(Status::internal("Internal server error"));
+            }
+        };
+        self.service.record_api_key_use(&api_key).await;
+        self.service.record_api_request(api_key.id, false).await;
+
+        if !api_key.has_scope(scope) {
+            return Err(Status::permission_denied(format!(
+                "API key is missing the {} scope",
+                scope
+            )));
+        }
+        if is_write(scope) && self.maintenance.is_enabled() {
+            let message = match self.maintenance.status().reason {
+                Some(reason) => format!(
+                    "Service is in read-only maintenance mode, please retry later: {}",
+                    reason
+                ),
+                None => "Service is in read-only maintenance mode, please retry later".into(),
+            };
+            return Err(Status::unavailable(message));
+        }
+        Ok(actor)
+    }
+}
+
+/// Returns `true` for the scopes of RPCs that change state.
+fn is_write(scope: ApiKeyScope) -> bool {
+    matches!(
+        scope,
+        ApiKeyScope::AccountsWrite | ApiKeyScope::TransactionsWrite | ApiKeyScope::WebhooksWrite
+    )
+}
+
+/// Maps an application error to the closest gRPC status.
+fn status(err: AppError) -> Status {
+    match err {
+        AppError::BadRequest(msg) | AppError::InvalidAmount(msg) => Status::invalid_argument(msg),
+        AppError::NotFound(msg) => Status::not_found(msg),
+        AppError::AccessDenied(_) => Status::permission_denied(err.to_string()),
+        AppError::AccountNotFound(_) => Status::not_found(err.to_string()),
+        AppError::CurrencyMismatch { .. } | AppError::CrossCurrencyTransfer => {
+            Status::invalid_argument(err.to_string())
+        }
+        AppError::InsufficientFunds { .. }
+        | AppError::AmountLimitExceeded { .. }
+        | AppError::BalanceLimitExceeded { .. }
+        | AppError::DailyDebitLimitExceeded { .. }
+        | AppError::HourlyTransactionLimitExceeded { .. }
+        | AppError::SpendingRuleViolation(_)
+        | AppError::AccountDormant(_)
+        | AppError::AccountFrozen(_)
+        | AppError::AccountClosed(_)
+        | AppError::RiskDenied(_)
+        | AppError::UnsupportedCurrency(_) => Status::failed_precondition(err.to_string()),
+        AppError::Conflict(msg) => Status::aborted(msg),
+        AppError::IdempotencyKeyConflict(_) => Status::already_exists(err.to_string()),
+        AppError::Internal(msg) => Status::internal(msg),
+        AppError::ServiceUnavailable(msg) => {
+            tracing::warn!("Service unavailable: {}", msg);
+            Status::unavailable("Service temporarily unavailable, please retry")
+        }
+    }
+}
+
+/// gRPC server for the Payments API.
+pub struct GrpcServer<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    maintenance: Arc<MaintenanceMode>,
+}
+
+impl<R: TransactionRepository> GrpcServer<R> {
+    /// Creates a new gRPC server with the given service.
+    ///
+    /// Pass an `Arc` to share the service with the HTTP server and jobs.
+    pub fn new(service: impl Into<Arc<PaymentService<R>>>) -> Self {
+        Self {
+            service: service.into(),
+            maintenance: Arc::default(),
+        }
+    }
+
+    /// Follows `maintenance`, e.g. the HTTP server's switch, instead of a
+    /// switch of its own.
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceMode>) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// Builds the tonic router with all services.
+    pub fn router(&self) -> Router {
+        let state = Arc::new(GrpcState {
+            service: self.service.clone(),
+            maintenance: self.maintenance.clone(),
+        });
+        Server::builder()
+            .add_service(AccountServiceServer::new(Accounts(state.clone())))
+            .add_service(TransactionServiceServer::new(Transactions(state.clone())))
+            .add_service(WebhookServiceServer::new(Webhooks(state)))
+    }
+
+    /// Starts the server on `addr`.
+    pub async fn run(self, addr: &str) -> anyhow::Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!("gRPC server listening on {}", listener.local_addr()?);
+
+        self.router()
+            .serve_with_incoming_shutdown(TcpIncoming::from(listener), shutdown_signal())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use payments_types::AccountId;
+    use tonic::Code;
+
+    use super::*;
+
+    #[test]
+    fn test_app_errors_map_to_status_codes() {
+        let cases = [
+            (AppError::BadRequest("bad".into()), Code::InvalidArgument),
+            (AppError::NotFound("gone".into()), Code::NotFound),
+            (
+                AppError::InsufficientFunds {
+                    available: 0,
+                    requested: 1,
+                },
+                Code::FailedPrecondition,
+            ),
+            (
+                AppError::RiskDenied("velocity".into()),
+                Code::FailedPrecondition,
+            ),
+            (
+                AppError::AccountFrozen(AccountId::new()),
+                Code::FailedPrecondition,
+            ),
+            (
+                AppError::UnsupportedCurrency("CHF".into()),
+                Code::FailedPrecondition,
+            ),
+            (AppError::Conflict("balance changed".into()), Code::Aborted),
+            (
+                AppError::IdempotencyKeyConflict("order-1".into()),
+                Code::AlreadyExists,
+            ),
+            (
+                AppError::AccessDenied("admin API key required".into()),
+                Code::PermissionDenied,
+            ),
+            (AppError::Internal("boom".into()), Code::Internal),
+            (AppError::ServiceUnavailable("db".into()), Code::Unavailable),
+        ];
+        for (err, code) in cases {
+            assert_eq!(status(err).code(), code);
+        }
+    }
+
+    #[test]
+    fn test_only_write_scopes_are_writes() {
+        assert!(is_write(ApiKeyScope::TransactionsWrite));
+        assert!(!is_write(ApiKeyScope::AccountsRead));
+        assert!(!is_write(ApiKeyScope::KeysAdmin));
+    }
+}
@@ -0,0 +1,98 @@
+//! `TransactionService` RPCs.
+
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use payments_types::{ApiKeyScope, TransactionRepository};
+
+use super::proto::{self, transaction_service_server::TransactionService};
+use super::{GrpcState, convert, status};
+
+pub(super) struct Transactions<R: TransactionRepository>(pub(super) Arc<GrpcState<R>>);
+
+#[tonic::async_trait]
+impl<R: TransactionRepository> TransactionService for Transactions<R> {
+    #[tracing::instrument(skip_all, fields(account_id = %request.get_ref().account_id, amount = request.get_ref().amount))]
+    async fn deposit(
+        &self,
+        request: Request<proto::DepositRequest>,
+    ) -> Result<Response<proto::PaymentResponse>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::TransactionsWrite)
+            .await?;
+        let req = request.into_inner().into_domain().map_err(status)?;
+        let tx = self
+            .0
+            .service
+            .deposit_as(&actor, req)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(tx.into()))
+    }
+
+    #[tracing::instrument(skip_all, fields(account_id = %request.get_ref().account_id, amount = request.get_ref().amount))]
+    async fn withdraw(
+        &self,
+        request: Request<proto::WithdrawRequest>,
+    ) -> Result<Response<proto::PaymentResponse>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::TransactionsWrite)
+            .await?;
+        let req = request.into_inner().into_domain().map_err(status)?;
+        let tx = self
+            .0
+            .service
+            .withdraw_as(&actor, req)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(tx.into()))
+    }
+
+    #[tracing::instrument(skip_all, fields(from = %request.get_ref().from_account_id, to = %request.get_ref().to_account_id, amount = request.get_ref().amount))]
+    async fn transfer(
+        &self,
+        request: Request<proto::TransferRequest>,
+    ) -> Result<Response<proto::PaymentResponse>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::TransactionsWrite)
+            .await?;
+        let req = request.into_inner().into_domain().map_err(status)?;
+        let tx = self
+            .0
+            .service
+            .transfer_as(&actor, req)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(tx.into()))
+    }
+
+    #[tracing::instrument(skip_all, fields(account_id = %request.get_ref().account_id))]
+    async fn list_transactions(
+        &self,
+        request: Request<proto::ListTransactionsRequest>,
+    ) -> Result<Response<proto::TransactionPage>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::TransactionsRead)
+            .await?;
+        let req = request.get_ref();
+        let account = convert::account_ref(&req.account_id).map_err(status)?;
+        let account_id = self
+            .0
+            .service
+            .resolve_account(&account)
+            .await
+            .map_err(status)?;
+        let page = self
+            .0
+            .service
+            .list_transactions(&actor, account_id, req.query())
+            .await
+            .map_err(status)?;
+        Ok(Response::new(page.into()))
+    }
+}
@@ -0,0 +1,70 @@
+//! `WebhookService` RPCs.
+
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use payments_types::{
+    ApiKeyScope, TransactionRepository, WebhookResponse, WebhookSignatureAlgorithm,
+    WebhookTimeouts, validate_event_patterns,
+};
+
+use super::proto::{self, webhook_service_server::WebhookService};
+use super::{GrpcState, status};
+
+pub(super) struct Webhooks<R: TransactionRepository>(pub(super) Arc<GrpcState<R>>);
+
+#[tonic::async_trait]
+impl<R: TransactionRepository> WebhookService for Webhooks<R> {
+    #[tracing::instrument(skip_all, fields(url = %request.get_ref().url))]
+    async fn register_webhook(
+        &self,
+        request: Request<proto::RegisterWebhookRequest>,
+    ) -> Result<Response<proto::Webhook>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::WebhooksWrite)
+            .await?;
+        let req = request.into_inner();
+        if req.url.is_empty() {
+            return Err(Status::invalid_argument("Webhook URL cannot be empty"));
+        }
+        validate_event_patterns(&req.events)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let timeouts = WebhookTimeouts::new(req.timeout_ms, req.connect_timeout_ms)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let signature_algorithm = req
+            .signature_algorithm
+            .as_deref()
+            .map(str::parse::<WebhookSignatureAlgorithm>)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+            .unwrap_or_default();
+
+        let endpoint = self
+            .0
+            .service
+            .register_webhook(&actor, &req.url, req.events, timeouts, signature_algorithm)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(WebhookResponse::from(endpoint).into()))
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn list_webhooks(
+        &self,
+        request: Request<proto::ListWebhooksRequest>,
+    ) -> Result<Response<proto::ListWebhooksResponse>, Status> {
+        let actor = self
+            .0
+            .authorize(&request, ApiKeyScope::WebhooksRead)
+            .await?;
+        let endpoints = self.0.service.list_webhooks(&actor).await.map_err(status)?;
+        Ok(Response::new(proto::ListWebhooksResponse {
+            webhooks: endpoints
+                .into_iter()
+                .map(|endpoint| WebhookResponse::from(endpoint).into())
+                .collect(),
+        }))
+    }
+}
@@ -4,21 +4,58 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Extension, Path, State},
-    http::StatusCode,
-    response::{IntoResponse, Response},
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{KeepAlive, Sse},
+    },
 };
 
 use payments_types::{
-    AccountId, ApiKey, AppError, CreateAccountRequest, DepositRequest, TransactionRepository,
-    TransferRequest, WithdrawRequest,
+    AccountId, AccountLimits, AccountListQuery, AccountRef, AccountSearchQuery,
+    AccountStatementQuery, ActorContext, AddAliasRequest, Alias, ApiKey, ApiKeyScope, AppError,
+    AssignFeeScheduleRequest, AuthorizeRequest, BalanceHistoryQuery, BalanceSnapshotResponse,
+    BatchItemResult, BatchItemStatus, BatchRequest, BatchResponse, BeneficiaryId, CaptureRequest,
+    ChangeAction, ChangeRequestId, ChangeRequestQuery, CreateAccountRequest,
+    CreateBeneficiaryRequest, CreateFeeScheduleRequest, CreateMemoKeyRequest, CreateOwnerRequest,
+    CreateQuoteRequest, CreateScheduledPaymentRequest, CreateSessionTokenRequest,
+    CreateSettlementBatchRequest, CurrencyCode, CurrencyRegistry, DeadLetterQuery,
+    DeadLetterRetryResponse, DepositRequest, Diagnostics, DomainError, EVENT_CATALOG,
+    EVENT_LOG_CURSOR_HEADER, EVENT_LOG_DAY_HEADER, EventLogQuery, EventStreamQuery, ExportId,
+    ExportRequest, ExposureQuery, FeeQuoteQuery, FeeScheduleId, FloatReportQuery, HoldId,
+    InboundPaymentRequest, IssueStatementsRequest, JournalExportQuery, MemoKeyId,
+    MergeAccountRequest, Mode, NewWebhookEndpoint, OwnerId, ProblemDetails, QuoteId,
+    ReadinessStatus, RegisterWebhooksRequest, RegisterWebhooksResponse, ScheduledPaymentId,
+    ScheduledPaymentQuery, ServiceHealth, ServiceStatus, SetApiKeyRateLimitRequest,
+    SetIncidentRequest, SetMaintenanceRequest, SettlementBatchId, SettlementExportQuery,
+    SpendingRules, StatementDownloadQuery, StatementEmail, StatementFormat, StatementId,
+    StatementPeriod, TransactionListQuery, TransactionRepository, TransactionSearchQuery,
+    TransferRequest, UpdateAccountRequest, UpdateOwnerRequest, UpdateSettlementBatchStatusRequest,
+    UpdateWebhookRequest, VerifyBeneficiaryRequest, WebhookDeliveriesQuery, WebhookEndpointId,
+    WebhookEventsQuery, WithdrawRequest, validate_event_patterns,
 };
 
-use crate::PaymentService;
+use super::diagnostics::problems;
+use super::extract::PaymentJson;
+use super::idempotency::IDEMPOTENCY_KEY_HEADER;
+use super::maintenance::MaintenanceMode;
+use super::rate_limit::RateLimiterState;
+use super::status::{IncidentBanner, STATUS_MAX_AGE_SECS};
+use super::stream::{STREAM_KEEP_ALIVE, StreamFilter, event_stream};
+use crate::metrics::MetricsRegistry;
+use crate::sessions::SessionClaims;
+use crate::statements::render_account_statement;
+use crate::{BatchOutcome, PaymentService};
 
 /// Application state shared across handlers.
 pub struct AppState<R: TransactionRepository> {
-    pub service: PaymentService<R>,
+    pub service: Arc<PaymentService<R>>,
+    pub maintenance: Arc<MaintenanceMode>,
+    pub incident: Arc<IncidentBanner>,
+    pub metrics: Arc<MetricsRegistry>,
+    /// Per-API-key limiter of the protected routes
+    pub rate_limiter: Arc<RateLimiterState>,
 }
 
 /// Wrapper to implement IntoResponse for AppError (orphan rule workaround).
@@ -32,141 +69,1441 @@ impl From<AppError> for ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, message) = match &self.0 {
-            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
-            AppError::InsufficientFunds {
-                available,
-                requested,
-            } => (
-                StatusCode::BAD_REQUEST,
-                format!(
-                    "Insufficient funds: available {}, requested {}",
-                    available, requested
-                ),
+        let (status, problem) = problem_details(&self.0);
+        (status, Json(problem)).into_response()
+    }
+}
+
+/// The status and body an error is reported with.
+fn problem_details(error: &AppError) -> (StatusCode, ProblemDetails) {
+    let (status, message) = match error {
+        AppError::BadRequest(msg) | AppError::InvalidAmount(msg) => {
+            (StatusCode::BAD_REQUEST, msg.clone())
+        }
+        AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
+        AppError::AccessDenied(_) => (StatusCode::BAD_REQUEST, error.to_string()),
+        AppError::AccountNotFound(_) => (StatusCode::NOT_FOUND, error.to_string()),
+        AppError::CurrencyMismatch { .. } | AppError::CrossCurrencyTransfer => {
+            (StatusCode::BAD_REQUEST, error.to_string())
+        }
+        AppError::InsufficientFunds {
+            available,
+            requested,
+        } => (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Insufficient funds: available {}, requested {}",
+                available, requested
+            ),
+        ),
+        AppError::AmountLimitExceeded { .. }
+        | AppError::BalanceLimitExceeded { .. }
+        | AppError::DailyDebitLimitExceeded { .. }
+        | AppError::HourlyTransactionLimitExceeded { .. }
+        | AppError::SpendingRuleViolation(_)
+        | AppError::AccountDormant(_)
+        | AppError::AccountFrozen(_)
+        | AppError::AccountClosed(_)
+        | AppError::RiskDenied(_)
+        | AppError::UnsupportedCurrency(_) => (StatusCode::UNPROCESSABLE_ENTITY, error.to_string()),
+        AppError::Conflict(msg) => (StatusCode::CONFLICT, format!("{}, please retry", msg)),
+        AppError::IdempotencyKeyConflict(_) => (StatusCode::CONFLICT, error.to_string()),
+        AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
+        AppError::ServiceUnavailable(msg) => {
+            tracing::warn!("Service unavailable: {}", msg);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Service temporarily unavailable, please retry".to_string(),
+            )
+        }
+    };
+    (
+        status,
+        ProblemDetails::new(status.as_u16(), error.error_code(), message),
+    )
+}
+
+/// The payment's idempotency key, from the body or the `Idempotency-Key`
+/// header; sending two different ones is rejected.
+fn idempotency_key(headers: &HeaderMap, body: Option<String>) -> Result<Option<String>, AppError> {
+    let Some(header) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(body);
+    };
+    let header = header.to_str().map_err(|_| {
+        AppError::BadRequest("Idempotency-Key header must be printable ASCII".into())
+    })?;
+    match body {
+        Some(body) if body != header => Err(AppError::BadRequest(
+            "Idempotency-Key header does not match idempotency_key in the body".into(),
+        )),
+        _ => Ok(Some(header.to_string())),
+    }
+}
+
+/// Resolves an `{id}` path segment, an account ID or `@alias`.
+async fn account_param<R: TransactionRepository>(
+    state: &AppState<R>,
+    id: &str,
+) -> Result<AccountId, AppError> {
+    let account: AccountRef = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid account ID".into()))?;
+    state.service.resolve_account(&account).await
+}
+
+/// Liveness probe: the process is up and serving. Checks no dependencies,
+/// so an orchestrator does not restart instances for a database outage.
+pub async fn health() -> impl IntoResponse {
+    Json(serde_json::json!({ "status": "healthy" }))
+}
+
+/// Public status page: coarse health and the incident banner, cacheable.
+pub async fn service_status<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+) -> impl IntoResponse {
+    (
+        [(
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", STATUS_MAX_AGE_SECS),
+        )],
+        Json(current_status(&state).await),
+    )
+}
+
+/// Readiness probe: each dependency's status and latency, with 503 while
+/// any of them is down.
+pub async fn ready<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+) -> impl IntoResponse {
+    let readiness = state.service.readiness().await;
+    let status = match readiness.status {
+        ReadinessStatus::Ready => StatusCode::OK,
+        ReadinessStatus::NotReady => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, Json(readiness))
+}
+
+/// Database latency, schema version, webhook backlog and rate limit
+/// saturation, with any that are past their limits (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn diagnostics<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+
+    let latency = state.service.database_latency().await;
+    let webhook_backlog = match latency {
+        Some(_) => Some(state.service.webhook_backlog().await?),
+        None => None,
+    };
+    let mut diagnostics = Diagnostics {
+        status: ServiceHealth::Operational,
+        database_latency_ms: latency.map(|latency| latency.as_secs_f64() * 1000.0),
+        schema_version: payments_repo::SCHEMA_VERSION,
+        webhook_backlog,
+        rate_limit_saturation: state.rate_limiter.saturation(),
+        problems: Vec::new(),
+    };
+    diagnostics.problems = problems(&diagnostics);
+    diagnostics.status = if !diagnostics.problems.is_empty() {
+        ServiceHealth::Degraded
+    } else if state.maintenance.is_enabled() {
+        ServiceHealth::Maintenance
+    } else {
+        ServiceHealth::Operational
+    };
+    Ok(Json(diagnostics))
+}
+
+async fn current_status<R: TransactionRepository>(state: &AppState<R>) -> ServiceStatus {
+    let status = if state.maintenance.is_enabled() {
+        ServiceHealth::Maintenance
+    } else if state.service.is_database_reachable().await {
+        ServiceHealth::Operational
+    } else {
+        ServiceHealth::Degraded
+    };
+    ServiceStatus {
+        status,
+        incident: state.incident.current(),
+    }
+}
+
+// #[tracing::instrument(skip(state), fields(owner = %req.name))]
+#[tracing::instrument(skip(state))]
+pub async fn create_account<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<CreateAccountRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::info!("👉 ENTERING create_account handler for {}", req.name);
+    // Owner-bound keys only open accounts for their owner
+    if actor.owner_id.is_some() {
+        actor.ensure_owner(req.owner_id)?;
+    }
+    // Accounts are opened in the mode of the key opening them
+    let mode = req.mode.unwrap_or(actor.mode);
+    if mode != actor.mode {
+        return Err(AppError::AccessDenied(format!(
+            "a {} API key cannot open {} accounts",
+            actor.mode, mode
+        ))
+        .into());
+    }
+    let req = CreateAccountRequest {
+        mode: Some(mode),
+        ..req
+    };
+    let account = state.service.create_account(req).await?;
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+/// List all accounts, or those of one owner.
+#[tracing::instrument(skip(state))]
+pub async fn list_accounts<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<AccountListQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    // If scoped key, filter to only that account
+    if let Some(account_id) = actor.account_id {
+        let mut accounts = vec![state.service.get_account(&actor, account_id).await?];
+        accounts.retain(|a| query.owner_id.is_none_or(|owner_id| a.owner_id == owner_id));
+        return Ok(Json(accounts));
+    }
+    // Owner-bound keys only ever list their owner's accounts
+    let owner_id = match (actor.owner_id, query.owner_id) {
+        (Some(own), Some(requested)) => {
+            actor.ensure_owner(requested)?;
+            Some(own)
+        }
+        (own, requested) => own.or(requested),
+    };
+    // Keys only ever see the accounts of their own mode
+    let accounts = match owner_id {
+        Some(owner_id) => {
+            state
+                .service
+                .list_owner_accounts(owner_id, actor.mode)
+                .await?
+        }
+        None => state.service.list_accounts(actor.mode).await?,
+    };
+    Ok(Json(accounts))
+}
+
+/// Search accounts by name fragment or ID prefix.
+#[tracing::instrument(skip(state))]
+pub async fn search_accounts<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<AccountSearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut accounts = state
+        .service
+        .search_accounts(&query.q, query.limit, actor.mode)
+        .await?;
+    // Scoped keys only ever see the accounts they may act on
+    accounts.retain(|account| actor.may_access(account.id));
+    Ok(Json(accounts))
+}
+
+/// Get account by ID.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn get_account<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let account = state.service.get_account(&actor, account_id).await?;
+    Ok(Json(account))
+}
+
+/// List an account's aliases.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn list_account_aliases<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let aliases = state
+        .service
+        .list_account_aliases(&actor, account_id)
+        .await?;
+    Ok(Json(aliases))
+}
+
+/// Register an alias for an account.
+#[tracing::instrument(skip(state), fields(account_id = %id, alias = %req.alias))]
+pub async fn add_account_alias<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<AddAliasRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let alias = state
+        .service
+        .add_account_alias(&actor, account_id, req)
+        .await?;
+    Ok((StatusCode::CREATED, Json(alias)))
+}
+
+/// Remove an alias from an account.
+#[tracing::instrument(skip(state), fields(account_id = %id, alias = %alias))]
+pub async fn remove_account_alias<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path((id, alias)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    state
+        .service
+        .remove_account_alias(&actor, account_id, &alias)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List an account's memo keys.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn list_memo_keys<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let keys = state.service.list_memo_keys(&actor, account_id).await?;
+    Ok(Json(keys))
+}
+
+/// Register a memo key for an account.
+#[tracing::instrument(skip(state, req), fields(account_id = %id, algorithm = %req.algorithm))]
+pub async fn add_memo_key<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateMemoKeyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let key = state.service.add_memo_key(&actor, account_id, req).await?;
+    Ok((StatusCode::CREATED, Json(key)))
+}
+
+/// Get one of an account's memo keys.
+#[tracing::instrument(skip(state), fields(account_id = %id, memo_key_id = %key_id))]
+pub async fn get_memo_key<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path((id, key_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+    let key_id = parse_memo_key_id(&key_id)?;
+
+    let key = state
+        .service
+        .get_memo_key(&actor, account_id, key_id)
+        .await?;
+    Ok(Json(key))
+}
+
+/// Retire one of an account's memo keys.
+#[tracing::instrument(skip(state), fields(account_id = %id, memo_key_id = %key_id))]
+pub async fn retire_memo_key<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path((id, key_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+    let key_id = parse_memo_key_id(&key_id)?;
+
+    let key = state
+        .service
+        .retire_memo_key(&actor, account_id, key_id)
+        .await?;
+    Ok(Json(key))
+}
+
+fn parse_memo_key_id(id: &str) -> Result<MemoKeyId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid memo key ID".into()))
+}
+
+/// List account owners (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn list_owners<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+) -> Result<impl IntoResponse, ApiError> {
+    let owners = state.service.list_owners(&actor).await?;
+    Ok(Json(owners))
+}
+
+/// Create an account owner (admin keys only).
+#[tracing::instrument(skip(state, actor, req), fields(name = %req.name))]
+pub async fn create_owner<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<CreateOwnerRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let owner = state.service.create_owner(&actor, req).await?;
+    Ok((StatusCode::CREATED, Json(owner)))
+}
+
+/// Get an account owner; keys bound to an owner may read their own.
+#[tracing::instrument(skip(state, actor), fields(owner_id = %id))]
+pub async fn get_owner<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let owner_id = parse_owner_id(&id)?;
+
+    let owner = state.service.get_owner(&actor, owner_id).await?;
+    Ok(Json(owner))
+}
+
+/// Rename an account owner or change its email (admin keys only).
+#[tracing::instrument(skip(state, actor, req), fields(owner_id = %id))]
+pub async fn update_owner<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateOwnerRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let owner_id = parse_owner_id(&id)?;
+
+    let owner = state.service.update_owner(&actor, owner_id, req).await?;
+    Ok(Json(owner))
+}
+
+/// Delete an account owner that holds no accounts (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(owner_id = %id))]
+pub async fn delete_owner<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let owner_id = parse_owner_id(&id)?;
+
+    state.service.delete_owner(&actor, owner_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn parse_owner_id(id: &str) -> Result<OwnerId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid owner ID".into()))
+}
+
+/// Look up the account an alias is registered to.
+#[tracing::instrument(skip(state), fields(alias = %alias))]
+pub async fn get_account_alias<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(alias): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let alias = Alias::parse(&alias).map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let entry = state.service.get_account_alias(&actor, &alias).await?;
+    Ok(Json(entry))
+}
+
+/// Deposit money into an account.
+#[tracing::instrument(skip(state, headers), fields(account_id = %req.account_id, amount = req.amount.get()))]
+pub async fn deposit<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    headers: HeaderMap,
+    PaymentJson(mut req): PaymentJson<DepositRequest<AccountRef>>,
+) -> Result<impl IntoResponse, ApiError> {
+    req.idempotency_key = idempotency_key(&headers, req.idempotency_key.take())?;
+    let tx = state.service.deposit_as(&actor, req).await?;
+    Ok(Json(tx))
+}
+
+/// Withdraw money from an account.
+#[tracing::instrument(skip(state, headers), fields(account_id = %req.account_id, amount = req.amount.get()))]
+pub async fn withdraw<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    headers: HeaderMap,
+    PaymentJson(mut req): PaymentJson<WithdrawRequest<AccountRef>>,
+) -> Result<impl IntoResponse, ApiError> {
+    req.idempotency_key = idempotency_key(&headers, req.idempotency_key.take())?;
+    let tx = state.service.withdraw_as(&actor, req).await?;
+    Ok(Json(tx))
+}
+
+/// Transfer money between accounts.
+#[tracing::instrument(skip(state, headers), fields(from = %req.from_account_id, to = %req.to_account_id, amount = req.amount.get()))]
+pub async fn transfer<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    headers: HeaderMap,
+    PaymentJson(mut req): PaymentJson<TransferRequest<AccountRef>>,
+) -> Result<impl IntoResponse, ApiError> {
+    req.idempotency_key = idempotency_key(&headers, req.idempotency_key.take())?;
+    let tx = state.service.transfer_as(&actor, req).await?;
+    Ok(Json(tx))
+}
+
+/// Make many deposits, withdrawals and transfers in one request.
+///
+/// Every account is resolved and access-checked before anything is made, so
+/// an unknown account or one the key may not use fails the whole request.
+#[tracing::instrument(skip(state, req), fields(mode = ?req.mode, operations = req.operations.len()))]
+pub async fn batch<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    PaymentJson(req): PaymentJson<BatchRequest<AccountRef>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mode = req.mode;
+    let outcomes = state.service.batch_as(&actor, req).await?;
+    let mut results = Vec::with_capacity(outcomes.len());
+    for (index, outcome) in outcomes.into_iter().enumerate() {
+        results.push(match outcome {
+            BatchOutcome::Succeeded(made) => BatchItemResult {
+                index,
+                status: BatchItemStatus::Succeeded,
+                transaction: Some(made.value),
+                warnings: made.warnings,
+                error: None,
+            },
+            BatchOutcome::Failed(e) => BatchItemResult {
+                index,
+                status: BatchItemStatus::Failed,
+                transaction: None,
+                warnings: Vec::new(),
+                error: Some(problem_details(&e).1),
+            },
+            BatchOutcome::RolledBack => BatchItemResult {
+                index,
+                status: BatchItemStatus::RolledBack,
+                transaction: None,
+                warnings: Vec::new(),
+                error: None,
+            },
+        });
+    }
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    Ok(Json(BatchResponse {
+        mode,
+        succeeded: count(BatchItemStatus::Succeeded),
+        failed: count(BatchItemStatus::Failed),
+        results,
+    }))
+}
+
+/// Record funds received over external rails; `201` when credited, `200`
+/// when the external reference was already recorded.
+#[tracing::instrument(skip(state), fields(account_id = %req.account_id, external_reference = %req.external_reference))]
+pub async fn receive_inbound_payment<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    PaymentJson(req): PaymentJson<InboundPaymentRequest<AccountRef>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let payment = state
+        .service
+        .receive_inbound_payment_as(&actor, req)
+        .await?;
+    let status = if payment.duplicate {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    Ok((status, Json(payment)))
+}
+
+/// Get the spending rules for an account.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn get_spending_rules<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let rules = state.service.spending_rules(&actor, account_id).await?;
+    Ok(Json(rules))
+}
+
+/// Replace the spending rules for an account (admin keys only).
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn set_spending_rules<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(rules): Json<SpendingRules>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let rules = state
+        .service
+        .set_spending_rules(&actor, account_id, rules)
+        .await?;
+    Ok(Json(rules))
+}
+
+/// Get the limits configured for an account.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn get_account_limits<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let limits = state.service.account_limits(&actor, account_id).await?;
+    Ok(Json(limits))
+}
+
+/// Replace the limits for an account (admin keys only).
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn set_account_limits<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(limits): Json<AccountLimits>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let limits = state
+        .service
+        .set_account_limits(&actor, account_id, limits)
+        .await?;
+    Ok(Json(limits))
+}
+
+/// Change an account's settings (admin keys only).
+#[tracing::instrument(skip(state, req), fields(account_id = %id))]
+pub async fn update_account<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateAccountRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let account = match req.overdraft_limit {
+        Some(limit) => {
+            let account = state
+                .service
+                .set_overdraft_limit(&actor, account_id, limit)
+                .await?;
+            tracing::info!(%account_id, limit, key = %actor.name, "Overdraft limit changed");
+            account
+        }
+        None => state.service.get_account(&actor, account_id).await?,
+    };
+    Ok(Json(account))
+}
+
+/// Return a dormant account to active use (admin keys only).
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn reactivate_account<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let account = state.service.reactivate_account(&actor, account_id).await?;
+    Ok(Json(account))
+}
+
+/// Stop money leaving an account (admin keys only).
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn freeze_account<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let account = state.service.freeze_account(&actor, account_id).await?;
+    Ok(Json(account))
+}
+
+/// Return a frozen account to active use (admin keys only).
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn unfreeze_account<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let account = state.service.unfreeze_account(&actor, account_id).await?;
+    Ok(Json(account))
+}
+
+/// Close an emptied account for good (admin keys only).
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn close_account<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let account = state.service.close_account(&actor, account_id).await?;
+    Ok(Json(account))
+}
+
+/// Merge a duplicate account into another and close it (admin keys only).
+#[tracing::instrument(skip(state, req), fields(account_id = %id))]
+pub async fn merge_account<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<MergeAccountRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let source = account_param(&state, &id).await?;
+    let target = state.service.resolve_account(&req.into).await?;
+
+    let merge = state.service.merge_account(&actor, source, target).await?;
+    tracing::warn!(%source, %target, key = %actor.name, "Account merged");
+    Ok(Json(merge))
+}
+
+/// Create a fee schedule (admin keys only).
+#[tracing::instrument(skip(state, req), fields(name = %req.name))]
+pub async fn create_fee_schedule<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<CreateFeeScheduleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let schedule = state.service.create_fee_schedule(&actor, req).await?;
+    Ok((StatusCode::CREATED, Json(schedule)))
+}
+
+/// List fee schedules.
+#[tracing::instrument(skip(state))]
+pub async fn list_fee_schedules<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let schedules = state.service.list_fee_schedules().await?;
+    Ok(Json(schedules))
+}
+
+/// Get a fee schedule by ID.
+#[tracing::instrument(skip(state), fields(fee_schedule_id = %id))]
+pub async fn get_fee_schedule<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let schedule_id: FeeScheduleId = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid fee schedule ID".into()))?;
+
+    let schedule = state.service.get_fee_schedule(schedule_id).await?;
+    Ok(Json(schedule))
+}
+
+/// Get an account's fee schedule assignments.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn get_account_fees<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let fees = state.service.account_fees(&actor, account_id).await?;
+    Ok(Json(fees))
+}
+
+/// Assign a fee schedule to an account (admin keys only).
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn assign_fee_schedule<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<AssignFeeScheduleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let assignment = state
+        .service
+        .assign_fee_schedule(&actor, account_id, req)
+        .await?;
+    Ok((StatusCode::CREATED, Json(assignment)))
+}
+
+/// Quote the fee for a payment from an account.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn quote_fee<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Query(query): Query<FeeQuoteQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let quote = state
+        .service
+        .quote_fee(&actor, account_id, query.amount)
+        .await?;
+    Ok(Json(quote))
+}
+
+/// Batch up the payouts made so far (admin keys only).
+#[tracing::instrument(skip(state, req))]
+pub async fn create_settlement_batch<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<CreateSettlementBatchRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let batch = state.service.create_settlement_batch(&actor, req).await?;
+    Ok((StatusCode::CREATED, Json(batch)))
+}
+
+/// List settlement batches (admin keys only).
+#[tracing::instrument(skip(state))]
+pub async fn list_settlement_batches<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+) -> Result<impl IntoResponse, ApiError> {
+    let batches = state.service.list_settlement_batches(&actor).await?;
+    Ok(Json(batches))
+}
+
+/// Get a settlement batch by ID (admin keys only).
+#[tracing::instrument(skip(state), fields(batch_id = %id))]
+pub async fn get_settlement_batch<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let batch_id = parse_settlement_batch_id(&id)?;
+
+    let batch = state.service.get_settlement_batch(&actor, batch_id).await?;
+    Ok(Json(batch))
+}
+
+/// Move a settlement batch along its lifecycle (admin keys only).
+#[tracing::instrument(skip(state, req), fields(batch_id = %id, status = %req.status))]
+pub async fn update_settlement_batch_status<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateSettlementBatchStatusRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let batch_id = parse_settlement_batch_id(&id)?;
+
+    let batch = state
+        .service
+        .update_settlement_batch_status(&actor, batch_id, req.status)
+        .await?;
+    Ok(Json(batch))
+}
+
+/// Download a settlement batch file (admin keys only).
+#[tracing::instrument(skip(state), fields(batch_id = %id))]
+pub async fn export_settlement_batch<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Query(query): Query<SettlementExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let batch_id = parse_settlement_batch_id(&id)?;
+
+    let body = state
+        .service
+        .export_settlement_batch(&actor, batch_id, query.format)
+        .await?;
+    let disposition = format!(
+        "attachment; filename=\"settlement-{}.{}\"",
+        batch_id,
+        query.format.extension()
+    );
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
             ),
-            AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        };
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
 
-        let body = serde_json::json!({
-            "error": message,
-            "code": status.as_u16()
-        });
+fn parse_settlement_batch_id(id: &str) -> Result<SettlementBatchId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid settlement batch ID".into()))
+}
 
-        (status, Json(body)).into_response()
-    }
+/// Download a period's transactions as journal entries (admin keys only).
+#[tracing::instrument(skip(state), fields(format = query.format.as_str(), from = %query.from, to = %query.to))]
+pub async fn export_journal<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<JournalExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let body = state
+        .service
+        .export_journal(&actor, query.format, query.from, query.to)
+        .await?;
+    let disposition = format!(
+        "attachment; filename=\"journal-{}-{}-{}.csv\"",
+        query.format.as_str(),
+        query.from,
+        query.to
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
 }
 
-/// Helper to ensure the authenticated API key has access to the target account.
-fn ensure_access(api_key: &ApiKey, target: AccountId) -> Result<(), AppError> {
-    match api_key.account_id {
-        Some(allowed_id) if allowed_id != target => Err(AppError::BadRequest(
-            "Access denied: API key not authorized for this account".into(),
-        )),
-        _ => Ok(()), // Admin (None) or Matching ID
+/// Export the event log as newline-delimited JSON, one UTC day per page
+/// (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn export_event_log<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<EventLogQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let page = state.service.event_log(&actor, query).await?;
+    let mut body = String::new();
+    for event in &page.events {
+        let line = serde_json::to_string(event).map_err(|e| AppError::Internal(e.to_string()))?;
+        body.push_str(&line);
+        body.push('\n');
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson"),
+    );
+    headers.insert(EVENT_LOG_CURSOR_HEADER, HeaderValue::from(page.cursor));
+    if let Some(day) = page.day
+        && let Ok(day) = HeaderValue::try_from(day.to_string())
+    {
+        headers.insert(EVENT_LOG_DAY_HEADER, day);
     }
+    Ok((headers, body))
 }
 
-/// Health check endpoint.
-pub async fn health() -> impl IntoResponse {
-    Json(serde_json::json!({ "status": "healthy" }))
+/// Queue a file export to be rendered in the background (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn create_export<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(request): Json<ExportRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let export = state.service.request_export(&actor, request).await?;
+    let service = state.service.clone();
+    let id = export.id;
+    tokio::spawn(async move {
+        if let Err(e) = service.run_export(id).await {
+            tracing::error!("Export {} could not be recorded: {}", id, e);
+        }
+    });
+    Ok((StatusCode::ACCEPTED, Json(export)))
 }
 
-// #[tracing::instrument(skip(state), fields(owner = %req.name))]
-#[tracing::instrument(skip(state))]
-pub async fn create_account<R: TransactionRepository>(
+/// Get an export, with a download link once it is ready (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(export_id = %id))]
+pub async fn get_export<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
-    Json(req): Json<CreateAccountRequest>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
-    tracing::info!("👉 ENTERING create_account handler for {}", req.name);
-    let account = state.service.create_account(req).await?;
-    Ok((StatusCode::CREATED, Json(account)))
+    let export_id: ExportId = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid export ID".into()))?;
+
+    let download = state.service.get_export(&actor, export_id).await?;
+    Ok(Json(download))
+}
+
+/// Download an export's file through a signed link (no API key).
+#[tracing::instrument(skip(state, token))]
+pub async fn download_export<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (export, body) = state.service.download_export(&token).await?;
+    let disposition = format!("attachment; filename=\"{}\"", export.request.filename());
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                export.request.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+/// Report balances per currency converted into a base currency (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn exposure_report<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<ExposureQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let base = query.base.unwrap_or(CurrencyCode::USD);
+    let report = state
+        .service
+        .exposure_report(&actor, base, query.as_of)
+        .await?;
+    Ok(Json(report))
+}
+
+/// Report the float per currency and daily external flows (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn float_report<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<FloatReportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let report = state.service.float_report(&actor, query).await?;
+    Ok(Json(report))
+}
+
+/// Set up a withdrawal or transfer from an account on a schedule.
+#[tracing::instrument(skip(state, req), fields(account_id = %req.from_account_id))]
+pub async fn create_scheduled_payment<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    PaymentJson(req): PaymentJson<CreateScheduledPaymentRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let payment = state.service.create_scheduled_payment(&actor, req).await?;
+    Ok((StatusCode::CREATED, Json(payment)))
 }
 
-/// List all accounts.
+/// List scheduled payments; scoped keys only see their own account's.
 #[tracing::instrument(skip(state))]
-pub async fn list_accounts<R: TransactionRepository>(
+pub async fn list_scheduled_payments<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
-    Extension(api_key): Extension<ApiKey>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<ScheduledPaymentQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    // If scoped key, filter to only that account
-    if let Some(account_id) = api_key.account_id {
-        let account = state.service.get_account(account_id).await?;
-        return Ok(Json(vec![account]));
-    }
-    // Otherwise return all
-    let accounts = state.service.list_accounts().await?;
-    Ok(Json(accounts))
+    let payments = state
+        .service
+        .list_scheduled_payments(&actor, query.account_id)
+        .await?;
+    Ok(Json(payments))
 }
 
-/// Get account by ID.
+/// Get a scheduled payment and how its last run went.
+#[tracing::instrument(skip(state), fields(scheduled_payment_id = %id))]
+pub async fn get_scheduled_payment<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let id = parse_scheduled_payment_id(&id)?;
+    let payment = state.service.get_scheduled_payment(&actor, id).await?;
+    Ok(Json(payment))
+}
+
+/// Stop a scheduled payment from running until it is resumed.
+#[tracing::instrument(skip(state), fields(scheduled_payment_id = %id))]
+pub async fn pause_scheduled_payment<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let id = parse_scheduled_payment_id(&id)?;
+    let payment = state.service.pause_scheduled_payment(&actor, id).await?;
+    Ok(Json(payment))
+}
+
+/// Let a paused payment run again from its next occurrence.
+#[tracing::instrument(skip(state), fields(scheduled_payment_id = %id))]
+pub async fn resume_scheduled_payment<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let id = parse_scheduled_payment_id(&id)?;
+    let payment = state.service.resume_scheduled_payment(&actor, id).await?;
+    Ok(Json(payment))
+}
+
+fn parse_scheduled_payment_id(id: &str) -> Result<ScheduledPaymentId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid scheduled payment ID".into()))
+}
+
+/// Reserve funds on an account until they are captured or voided.
+#[tracing::instrument(skip(state), fields(account_id = %req.account_id, amount = req.amount.get()))]
+pub async fn authorize<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    PaymentJson(req): PaymentJson<AuthorizeRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let hold = state.service.authorize(&actor, req).await?;
+    Ok((StatusCode::CREATED, Json(hold)))
+}
+
+/// Withdraw a hold's funds; the body is optional and defaults to the full amount.
+#[tracing::instrument(skip(state, req), fields(hold_id = %id))]
+pub async fn capture_hold<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    req: Option<PaymentJson<CaptureRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let id = parse_hold_id(&id)?;
+    let req = req.map(|PaymentJson(req)| req).unwrap_or_default();
+    let hold = state.service.capture_hold(&actor, id, req).await?;
+    Ok(Json(hold))
+}
+
+/// Release a hold without moving funds.
+#[tracing::instrument(skip(state), fields(hold_id = %id))]
+pub async fn void_hold<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let id = parse_hold_id(&id)?;
+    let hold = state.service.void_hold(&actor, id).await?;
+    Ok(Json(hold))
+}
+
+/// Get a hold by ID.
+#[tracing::instrument(skip(state), fields(hold_id = %id))]
+pub async fn get_hold<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let id = parse_hold_id(&id)?;
+    let hold = state.service.get_hold(&actor, id).await?;
+    Ok(Json(hold))
+}
+
+/// List an account's holds, newest first.
 #[tracing::instrument(skip(state), fields(account_id = %id))]
-pub async fn get_account<R: TransactionRepository>(
+pub async fn list_holds<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let holds = state.service.list_holds(&actor, account_id).await?;
+    Ok(Json(holds))
+}
+
+/// Lock the current exchange rate for a later transfer.
+#[tracing::instrument(skip(state, actor, req), fields(from = %req.from_currency, to = %req.to_currency))]
+pub async fn create_quote<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    PaymentJson(req): PaymentJson<CreateQuoteRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let quote = state.service.create_quote(&actor, req).await?;
+    Ok((StatusCode::CREATED, Json(quote)))
+}
+
+/// Get a quote by ID.
+#[tracing::instrument(skip(state, actor), fields(quote_id = %id))]
+pub async fn get_quote<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
-    Extension(api_key): Extension<ApiKey>,
+    Extension(actor): Extension<ActorContext>,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let account_id: AccountId = id
+    let id: QuoteId = id
         .parse()
-        .map_err(|_| AppError::BadRequest("Invalid account ID".into()))?;
+        .map_err(|_| AppError::BadRequest("Invalid quote ID".into()))?;
+    let quote = state.service.get_quote(&actor, id).await?;
+    Ok(Json(quote))
+}
 
-    ensure_access(&api_key, account_id).map_err(ApiError)?;
+/// Add a beneficiary and send it two micro-deposits to confirm.
+#[tracing::instrument(skip(state, req), fields(account_id = %req.account_id))]
+pub async fn create_beneficiary<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<CreateBeneficiaryRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let beneficiary = state.service.create_beneficiary(&actor, req).await?;
+    Ok((StatusCode::CREATED, Json(beneficiary)))
+}
 
-    let account = state.service.get_account(account_id).await?;
-    Ok(Json(account))
+/// Get a beneficiary by ID.
+#[tracing::instrument(skip(state), fields(beneficiary_id = %id))]
+pub async fn get_beneficiary<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let id = parse_beneficiary_id(&id)?;
+    let beneficiary = state.service.get_beneficiary(&actor, id).await?;
+    Ok(Json(beneficiary))
 }
 
-/// Deposit money into an account.
-#[tracing::instrument(skip(state), fields(account_id = %req.account_id, amount = req.amount))]
-pub async fn deposit<R: TransactionRepository>(
+/// List an account's beneficiaries, newest first.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn list_beneficiaries<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
-    Extension(api_key): Extension<ApiKey>,
-    Json(req): Json<DepositRequest>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
-    ensure_access(&api_key, req.account_id).map_err(ApiError)?;
-    let tx = state.service.deposit(req).await?;
-    Ok(Json(tx))
+    let account_id = account_param(&state, &id).await?;
+
+    let beneficiaries = state.service.list_beneficiaries(&actor, account_id).await?;
+    Ok(Json(beneficiaries))
 }
 
-/// Withdraw money from an account.
-#[tracing::instrument(skip(state), fields(account_id = %req.account_id, amount = req.amount))]
-pub async fn withdraw<R: TransactionRepository>(
+/// Confirm the micro-deposit amounts sent to a beneficiary.
+#[tracing::instrument(skip(state, req), fields(beneficiary_id = %id))]
+pub async fn verify_beneficiary<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
-    Extension(api_key): Extension<ApiKey>,
-    Json(req): Json<WithdrawRequest>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<VerifyBeneficiaryRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    ensure_access(&api_key, req.account_id).map_err(ApiError)?;
-    let tx = state.service.withdraw(req).await?;
-    Ok(Json(tx))
+    let id = parse_beneficiary_id(&id)?;
+    let beneficiary = state.service.verify_beneficiary(&actor, id, req).await?;
+    Ok(Json(beneficiary))
 }
 
-/// Transfer money between accounts.
-#[tracing::instrument(skip(state), fields(from = %req.from_account_id, to = %req.to_account_id, amount = req.amount))]
-pub async fn transfer<R: TransactionRepository>(
+fn parse_beneficiary_id(id: &str) -> Result<BeneficiaryId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid beneficiary ID".into()))
+}
+
+fn parse_hold_id(id: &str) -> Result<HoldId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid hold ID".into()))
+}
+
+/// Get the address an account's statements are emailed to.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn get_statement_email<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
-    Extension(api_key): Extension<ApiKey>,
-    Json(req): Json<TransferRequest>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
-    ensure_access(&api_key, req.from_account_id).map_err(ApiError)?;
-    let tx = state.service.transfer(req).await?;
-    Ok(Json(tx))
+    let account_id = account_param(&state, &id).await?;
+
+    let email = state.service.statement_email(&actor, account_id).await?;
+    Ok(Json(email))
+}
+
+/// Set or clear the address an account's statements are emailed to.
+#[tracing::instrument(skip(state, req), fields(account_id = %id))]
+pub async fn set_statement_email<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<StatementEmail>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let email = state
+        .service
+        .set_statement_email(&actor, account_id, req)
+        .await?;
+    Ok(Json(email))
+}
+
+/// List an account's statements, latest month first.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn list_statements<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let statements = state.service.list_statements(&actor, account_id).await?;
+    Ok(Json(statements))
+}
+
+/// Generate a statement for any range of days, as CSV or JSON.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn get_account_statement<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Query(query): Query<AccountStatementQuery>,
+) -> Result<Response, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let statement = state
+        .service
+        .account_statement(&actor, account_id, query.from, query.to)
+        .await?;
+    let (content_type, body) = match query.format {
+        StatementFormat::Csv => (
+            "text/csv; charset=utf-8",
+            render_account_statement(&statement, state.service.amount_format()),
+        ),
+        StatementFormat::Json => (
+            "application/json",
+            serde_json::to_string(&statement).map_err(|e| AppError::Internal(e.to_string()))?,
+        ),
+    };
+    let disposition = format!(
+        "attachment; filename=\"statement-{}-{}-{}.{}\"",
+        account_id,
+        query.from,
+        query.to,
+        query.format.as_str()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Get an account's recorded end-of-day balances for charting.
+#[tracing::instrument(skip(state), fields(account_id = %id))]
+pub async fn get_balance_history<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Query(query): Query<BalanceHistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let account_id = account_param(&state, &id).await?;
+
+    let history = state
+        .service
+        .balance_history(&actor, account_id, query)
+        .await?;
+    Ok(Json(history))
+}
+
+/// Record end-of-day balances now instead of waiting for the job (admin
+/// keys only).
+#[tracing::instrument(skip(state))]
+pub async fn record_balance_snapshots<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+
+    let recorded = state.service.record_daily_balances().await?;
+    Ok(Json(BalanceSnapshotResponse { recorded }))
+}
+
+/// Row counts of the tracked tables against their soft quotas (admin keys
+/// only).
+#[tracing::instrument(skip(state))]
+pub async fn storage_report<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+) -> Result<impl IntoResponse, ApiError> {
+    Ok(Json(state.service.storage_report(&actor).await?))
+}
+
+/// Get a statement with a fresh download link.
+#[tracing::instrument(skip(state), fields(statement_id = %id))]
+pub async fn get_statement<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let statement_id = parse_statement_id(&id)?;
+
+    let download = state.service.get_statement(&actor, statement_id).await?;
+    Ok(Json(download))
+}
+
+/// Issue statements for a month (admin keys only).
+#[tracing::instrument(skip(state, req))]
+pub async fn issue_statements<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<IssueStatementsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+
+    let statements = match req.month {
+        Some(month) => {
+            let period = StatementPeriod::parse(&month).ok_or_else(|| {
+                AppError::BadRequest(format!("Invalid month '{}': expected YYYY-MM", month))
+            })?;
+            state.service.issue_statements(period).await?
+        }
+        None => state.service.issue_monthly_statements().await?,
+    };
+    Ok((StatusCode::CREATED, Json(statements)))
+}
+
+/// Download a statement file through a signed link (no API key).
+#[tracing::instrument(skip(state, query), fields(statement_id = %id))]
+pub async fn download_statement<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(id): Path<String>,
+    Query(query): Query<StatementDownloadQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let statement_id = parse_statement_id(&id)?;
+
+    let (statement, body) = state
+        .service
+        .download_statement(statement_id, query.expires, &query.signature)
+        .await?;
+    let disposition = format!(
+        "attachment; filename=\"statement-{}-{}.csv\"",
+        statement.account_id,
+        statement.period()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+fn parse_statement_id(id: &str) -> Result<StatementId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid statement ID".into()))
 }
 
-/// List transactions for an account.
+/// List a page of an account's transactions, newest first.
 #[tracing::instrument(skip(state), fields(account_id = %id))]
 pub async fn list_transactions<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
-    Extension(api_key): Extension<ApiKey>,
+    Extension(actor): Extension<ActorContext>,
     Path(id): Path<String>,
+    Query(query): Query<TransactionListQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let account_id: AccountId = id
-        .parse()
-        .map_err(|_| AppError::BadRequest("Invalid account ID".into()))?;
+    let account_id = account_param(&state, &id).await?;
 
-    ensure_access(&api_key, account_id).map_err(ApiError)?;
+    let page = state
+        .service
+        .list_transactions(&actor, account_id, query)
+        .await?;
+    Ok(Json(page))
+}
 
-    let transactions = state.service.list_transactions(account_id).await?;
-    Ok(Json(transactions))
+/// Search transactions across accounts by reference, account, type,
+/// currency, amount or time.
+#[tracing::instrument(skip(state))]
+pub async fn search_transactions<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<TransactionSearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let page = state.service.search_transactions(&actor, query).await?;
+    Ok(Json(page))
 }
 
 /// Bootstrap endpoint - creates the first API key.
@@ -183,7 +1520,7 @@ pub struct BootstrapRequest {
 #[derive(serde::Serialize, utoipa::ToSchema)]
 pub struct BootstrapResponse {
     /// The generated API key (shown only once)
-    #[schema(example = "sk_abc123xyz...")]
+    #[schema(example = "sk_live_abc123xyz...")]
     pub api_key: String,
     /// Informational message
     pub message: String,
@@ -200,7 +1537,7 @@ pub async fn bootstrap<R: TransactionRepository>(
         .repo()
         .count_api_keys()
         .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .map_err(AppError::from)?;
 
     if key_count > 0 {
         return Err(AppError::BadRequest(
@@ -208,13 +1545,11 @@ pub async fn bootstrap<R: TransactionRepository>(
         ).into());
     }
 
-    // Create the first API key
+    // Create the first API key, a live one holding every scope
     let (_api_key, raw_key) = state
         .service
-        .repo()
-        .create_api_key(&req.name)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .create_api_key(&req.name, &ApiKeyScope::ALL, Mode::Live, None)
+        .await?;
 
     Ok((
         StatusCode::CREATED,
@@ -236,6 +1571,25 @@ pub struct CreateApiKeyRequest {
     /// Name for the API key
     #[schema(example = "production-key")]
     pub name: String,
+    /// Restricts the key to this account; omit for an unscoped (admin) key
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub account_id: Option<AccountId>,
+    /// Restricts the key to the accounts this owner holds; cannot be
+    /// combined with `account_id`
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub owner_id: Option<OwnerId>,
+    /// What the key may do; omit to grant every scope the caller holds, or
+    /// only `accounts:read` and `transactions:read` for a key bound to an
+    /// account or owner or in test mode
+    #[serde(default)]
+    #[schema(example = json!(["accounts:read", "transactions:read"]))]
+    pub scopes: Option<Vec<ApiKeyScope>>,
+    /// Mode of the key; omit for the caller's mode. A key bound to an
+    /// account is always in the account's mode
+    #[serde(default)]
+    pub mode: Option<Mode>,
 }
 
 /// Response containing API key info (without the raw key).
@@ -246,6 +1600,17 @@ pub struct ApiKeyInfo {
     pub id: payments_types::ApiKeyId,
     /// Name of the API key
     pub name: String,
+    /// Account the key is restricted to; absent for unscoped (admin) keys
+    #[schema(value_type = Option<String>)]
+    pub account_id: Option<AccountId>,
+    /// Owner whose accounts the key is restricted to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub owner_id: Option<OwnerId>,
+    /// What the key may do
+    pub scopes: Vec<ApiKeyScope>,
+    /// Whether the key works on test or live accounts
+    pub mode: Mode,
     /// Whether the key is active
     pub is_active: bool,
     /// When the key was created (ISO 8601)
@@ -254,20 +1619,87 @@ pub struct ApiKeyInfo {
     /// When the key was last used (ISO 8601)
     #[schema(value_type = Option<String>)]
     pub last_used_at: Option<String>,
+    /// Requests the key may make per minute; absent when it has the
+    /// server-wide default
+    pub rate_limit_per_minute: Option<u32>,
 }
 
-/// Create a new API key (requires authentication).
-#[tracing::instrument(skip(state), fields(key_name = %req.name))]
+impl From<ApiKey> for ApiKeyInfo {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            account_id: key.account_id,
+            owner_id: key.owner_id,
+            scopes: key.scopes,
+            mode: key.mode,
+            is_active: key.is_active,
+            created_at: key.created_at.to_rfc3339(),
+            last_used_at: key.last_used_at.map(|dt| dt.to_rfc3339()),
+            rate_limit_per_minute: key.rate_limit_per_minute,
+        }
+    }
+}
+
+/// Create a new API key, optionally restricted to one account or one owner
+/// and to some scopes (admin only).
+///
+/// A key can only grant scopes it holds itself, and a test key can only
+/// create test keys. Restricted keys created without scopes can only read.
+#[tracing::instrument(skip(state, actor), fields(key_name = %req.name))]
 pub async fn create_api_key<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
     Json(req): Json<CreateApiKeyRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let (_api_key, raw_key) = state
-        .service
-        .repo()
-        .create_api_key(&req.name)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+    actor.ensure_admin()?;
+    let mode = req.mode.unwrap_or(actor.mode);
+    if actor.mode == Mode::Test && mode == Mode::Live {
+        return Err(AppError::AccessDenied("a test API key cannot create live keys".into()).into());
+    }
+    let restricted = req.account_id.is_some() || req.owner_id.is_some() || mode == Mode::Test;
+    let scopes = match req.scopes {
+        Some(scopes) => scopes,
+        None if restricted => ApiKeyScope::READ
+            .into_iter()
+            .filter(|scope| actor.has_scope(*scope))
+            .collect(),
+        None => actor.scopes.clone(),
+    };
+    if let Some(scope) = scopes.iter().find(|scope| !actor.has_scope(**scope)) {
+        return Err(AppError::AccessDenied(format!(
+            "cannot grant the {} scope, which this API key does not hold",
+            scope
+        ))
+        .into());
+    }
+    let (_api_key, raw_key) = match (req.account_id, req.owner_id) {
+        (Some(_), Some(_)) => {
+            return Err(AppError::BadRequest(
+                "A key is bound to an account or to an owner, not both".into(),
+            )
+            .into());
+        }
+        (Some(account_id), None) => {
+            state.service.ensure_mode(&actor, account_id).await?;
+            state
+                .service
+                .create_scoped_api_key(&req.name, account_id, &scopes, Some(actor.api_key_id))
+                .await?
+        }
+        (None, Some(owner_id)) => {
+            state
+                .service
+                .create_owner_api_key(&req.name, owner_id, &scopes, mode, Some(actor.api_key_id))
+                .await?
+        }
+        (None, None) => {
+            state
+                .service
+                .create_api_key(&req.name, &scopes, mode, Some(actor.api_key_id))
+                .await?
+        }
+    };
 
     Ok((
         StatusCode::CREATED,
@@ -278,54 +1710,142 @@ pub async fn create_api_key<R: TransactionRepository>(
     ))
 }
 
-/// List all active API keys (without exposing raw keys).
-#[tracing::instrument(skip(state))]
+/// Parses an API key ID from the path. A key in another mode than the
+/// caller's is reported as not found.
+async fn api_key_param<R: TransactionRepository>(
+    state: &AppState<R>,
+    actor: &ActorContext,
+    id: &str,
+) -> Result<payments_types::ApiKeyId, AppError> {
+    let key_id: payments_types::ApiKeyId = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid API key ID".into()))?;
+    match state.service.repo().get_api_key(key_id).await? {
+        Some(key) if key.mode != actor.mode => {
+            Err(AppError::NotFound(format!("API key {}", key_id)))
+        }
+        _ => Ok(key_id),
+    }
+}
+
+/// List all active API keys (without exposing raw keys; admin only).
+#[tracing::instrument(skip(state, actor))]
 pub async fn list_api_keys<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
 ) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
     let keys = state
         .service
         .repo()
         .list_api_keys()
         .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .map_err(AppError::from)?;
 
+    // Keys only ever see the keys of their own mode
     let response: Vec<ApiKeyInfo> = keys
         .into_iter()
-        .map(|k| ApiKeyInfo {
-            id: k.id,
-            name: k.name,
-            is_active: k.is_active,
-            created_at: k.created_at.to_rfc3339(),
-            last_used_at: k.last_used_at.map(|dt| dt.to_rfc3339()),
-        })
+        .filter(|key| key.mode == actor.mode)
+        .map(ApiKeyInfo::from)
         .collect();
 
     Ok(Json(response))
 }
 
-/// Delete (deactivate) an API key.
-#[tracing::instrument(skip(state), fields(key_id = %id))]
+/// Give an API key its own quota, or return it to the server-wide default
+/// (admin only).
+#[tracing::instrument(skip(state, actor), fields(key_id = %id))]
+pub async fn set_api_key_rate_limit<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<SetApiKeyRateLimitRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+    let key_id = api_key_param(&state, &actor, &id).await?;
+
+    let key = state
+        .service
+        .set_api_key_rate_limit(key_id, req.requests_per_minute)
+        .await?;
+    Ok(Json(ApiKeyInfo::from(key)))
+}
+
+/// Request that an API key be deleted (deactivated); another admin key has
+/// to approve it (admin only).
+#[tracing::instrument(skip(state, actor), fields(key_id = %id))]
 pub async fn delete_api_key<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let key_id: payments_types::ApiKeyId = id
-        .parse()
-        .map_err(|_| AppError::BadRequest("Invalid API key ID".into()))?;
+    let key_id = api_key_param(&state, &actor, &id).await?;
 
-    let deleted = state
+    let change = state
         .service
-        .repo()
-        .delete_api_key(key_id)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .request_change(&actor, ChangeAction::DeleteApiKey { api_key_id: key_id })
+        .await?;
+    Ok((StatusCode::ACCEPTED, Json(change)))
+}
 
-    if deleted {
-        Ok(StatusCode::NO_CONTENT.into_response())
-    } else {
-        Err(AppError::NotFound("API key not found".into()).into())
+/// Report request and money-movement totals for an API key (admin keys, or
+/// the key itself).
+#[tracing::instrument(skip(state, actor), fields(key_id = %id))]
+pub async fn get_api_key_usage<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let key_id = api_key_param(&state, &actor, &id).await?;
+    if actor.api_key_id != key_id {
+        actor.ensure_admin()?;
+        if !actor.has_scope(ApiKeyScope::KeysAdmin) {
+            return Err(AppError::AccessDenied("keys:admin scope required".into()).into());
+        }
+    }
+
+    let usage = state.service.api_key_usage(key_id).await?;
+    Ok(Json(usage))
+}
+
+/// Show an API key, deleted or not, with the audit trail of its creation,
+/// deletion and use (admin only).
+#[tracing::instrument(skip(state, actor), fields(key_id = %id))]
+pub async fn get_api_key_audit<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+    let key_id = api_key_param(&state, &actor, &id).await?;
+
+    let audit = state.service.api_key_audit(key_id).await?;
+    Ok(Json(audit))
+}
+
+/// Exchange the caller's API key for a short-lived token that can only read
+/// one account, for embedding in dashboards.
+///
+/// The token holds whichever of `accounts:read` and `transactions:read` the
+/// key holds, and stops working once the key is deleted.
+#[tracing::instrument(skip(state, actor, session, req), fields(account_id = %req.account_id))]
+pub async fn create_session_token<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    session: Option<Extension<SessionClaims>>,
+    Json(req): Json<CreateSessionTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if session.is_some() {
+        return Err(
+            AppError::AccessDenied("session tokens cannot issue session tokens".into()).into(),
+        );
     }
+
+    let token = state
+        .service
+        .create_session_token(&actor, req.account_id, req.expires_in_secs)
+        .await?;
+    Ok((StatusCode::CREATED, Json(token)))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -333,57 +1853,307 @@ pub async fn delete_api_key<R: TransactionRepository>(
 // Webhooks
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Register a new webhook endpoint.
-#[tracing::instrument(skip(state), fields(url = %req.url))]
+/// Register a new webhook endpoint (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(url = %req.url))]
 pub async fn register_webhook<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
     Json(req): Json<payments_types::RegisterWebhookRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    // Validate URL
-    if req.url.is_empty() {
-        return Err(AppError::BadRequest("Webhook URL cannot be empty".into()).into());
-    }
-
+    let endpoint = NewWebhookEndpoint::try_from(req).map_err(AppError::from)?;
     let endpoint = state
         .service
-        .repo()
-        .register_webhook_endpoint(&req.url, req.events)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .register_webhook(
+            &actor,
+            &endpoint.url,
+            endpoint.events,
+            endpoint.timeouts,
+            endpoint.signature_algorithm,
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(payments_types::WebhookResponse::from(endpoint)),
+    ))
+}
+
+/// Register several webhook endpoints in one call (admin keys only).
+///
+/// Every endpoint is checked before any is registered, and they are
+/// registered together, so a bad item leaves nothing behind.
+#[tracing::instrument(skip(state, actor, req), fields(count = req.endpoints.len()))]
+pub async fn register_webhooks<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<RegisterWebhooksRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let endpoints = req
+        .endpoints
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            NewWebhookEndpoint::try_from(item).map_err(|e| match e {
+                DomainError::ValidationError(msg) => {
+                    AppError::BadRequest(format!("endpoints[{}]: {}", index, msg))
+                }
+                e => e.into(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
+    let registered = state.service.register_webhooks(&actor, endpoints).await?;
     Ok((
         StatusCode::CREATED,
-        Json(payments_types::WebhookResponse {
-            id: payments_types::WebhookEndpointId::from_uuid(endpoint.id),
-            url: endpoint.url,
-            secret: endpoint.secret,
-            events: endpoint.events,
-            is_active: endpoint.is_active,
+        Json(RegisterWebhooksResponse {
+            endpoints: registered.into_iter().map(Into::into).collect(),
         }),
     ))
 }
 
-/// List all active webhook endpoints.
-#[tracing::instrument(skip(state))]
-pub async fn list_webhooks<R: TransactionRepository>(
+/// Update a webhook endpoint (admin keys only).
+///
+/// A new URL is only requested: another admin key has to approve it, so the
+/// response is the pending change. Subscriptions, the active flag and the
+/// signature algorithm change at once and the response is the updated
+/// endpoint.
+#[tracing::instrument(skip(state, actor, req), fields(endpoint_id = %id))]
+pub async fn update_webhook<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateWebhookRequest>,
+) -> Result<Response, ApiError> {
+    let endpoint_id: WebhookEndpointId = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid webhook endpoint ID".into()))?;
+
+    let UpdateWebhookRequest {
+        url,
+        events,
+        is_active,
+        signature_algorithm,
+    } = req;
+    let Some(url) = url else {
+        let endpoint = state
+            .service
+            .update_webhook(&actor, endpoint_id, events, is_active, signature_algorithm)
+            .await?;
+        return Ok(Json(payments_types::WebhookResponse::from(endpoint)).into_response());
+    };
+    if events.is_some() || is_active.is_some() || signature_algorithm.is_some() {
+        return Err(AppError::BadRequest(
+            "A new url needs another admin's approval; send it on its own".into(),
+        )
+        .into());
+    }
+
+    let change = state
+        .service
+        .request_change(&actor, ChangeAction::SetWebhookUrl { endpoint_id, url })
+        .await?;
+    Ok((StatusCode::ACCEPTED, Json(change)).into_response())
+}
+
+/// Give a webhook endpoint a new signing secret (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(endpoint_id = %id))]
+pub async fn rotate_webhook_secret<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let endpoint_id: WebhookEndpointId = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid webhook endpoint ID".into()))?;
+
+    let endpoint = state
+        .service
+        .rotate_webhook_secret(&actor, endpoint_id)
+        .await?;
+    Ok(Json(payments_types::WebhookResponse::from(endpoint)))
+}
+
+/// Delete a webhook endpoint; its past events stay readable (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(endpoint_id = %id))]
+pub async fn delete_webhook<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let endpoint_id: WebhookEndpointId = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid webhook endpoint ID".into()))?;
+
+    state.service.delete_webhook(&actor, endpoint_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the event types webhooks can subscribe to, with their payload fields.
+#[tracing::instrument]
+pub async fn list_event_types() -> impl IntoResponse {
+    Json(EVENT_CATALOG)
+}
+
+/// Get a webhook event with its full payload (admin keys only).
+///
+/// Receivers of a `payload_truncated` stub fetch the payload here.
+#[tracing::instrument(skip(state, actor), fields(event_id = %id))]
+pub async fn get_webhook_event<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let event_id: uuid::Uuid = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid webhook event ID".into()))?;
+
+    let event = state.service.webhook_event(&actor, event_id).await?;
+    Ok(Json(payments_types::WebhookEventResponse::from(event)))
+}
+
+/// Export dead-lettered webhook events with their payloads (admin keys only).
+#[tracing::instrument(skip(state, actor, query))]
+pub async fn list_dead_lettered_webhooks<R: TransactionRepository>(
     State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<DeadLetterQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let endpoints = state
+    let events = state
         .service
-        .repo()
-        .list_webhook_endpoints()
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .dead_lettered_webhooks(&actor, query.into())
+        .await?;
+    Ok(Json(
+        events
+            .into_iter()
+            .map(payments_types::WebhookEventResponse::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Requeue dead-lettered webhook events in bulk (admin keys only).
+#[tracing::instrument(skip(state, actor, req))]
+pub async fn retry_dead_lettered_webhooks<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<DeadLetterQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let requeued = state
+        .service
+        .retry_dead_lettered_webhooks(&actor, req.into())
+        .await?;
+    Ok(Json(DeadLetterRetryResponse { requeued }))
+}
+
+/// List an endpoint's events after a delivery sequence (admin keys only).
+///
+/// Receivers that notice a gap in `X-Webhook-Delivery-Sequence` catch up
+/// here.
+#[tracing::instrument(skip(state, actor, query), fields(endpoint_id = %id))]
+pub async fn list_webhook_events<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Query(query): Query<WebhookEventsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let endpoint_id: uuid::Uuid = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid webhook endpoint ID".into()))?;
+
+    let events = state
+        .service
+        .webhook_events_after(
+            &actor,
+            WebhookEndpointId::from_uuid(endpoint_id),
+            query.after_sequence,
+            query.limit,
+        )
+        .await?;
+    Ok(Json(
+        events
+            .into_iter()
+            .map(payments_types::WebhookEventResponse::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// List an endpoint's delivery log, most recent first (admin keys only).
+#[tracing::instrument(skip(state, actor, query), fields(endpoint_id = %id))]
+pub async fn list_webhook_deliveries<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let endpoint_id: uuid::Uuid = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid webhook endpoint ID".into()))?;
+
+    let events = state
+        .service
+        .webhook_deliveries(&actor, WebhookEndpointId::from_uuid(endpoint_id), query)
+        .await?;
+    Ok(Json(
+        events
+            .into_iter()
+            .map(payments_types::WebhookDeliveryResponse::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Requeue one failed or dead-lettered webhook event (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(event_id = %id))]
+pub async fn redeliver_webhook_event<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let event_id: uuid::Uuid = id
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid webhook event ID".into()))?;
+
+    let event = state
+        .service
+        .redeliver_webhook_event(&actor, event_id)
+        .await?;
+    Ok(Json(payments_types::WebhookDeliveryResponse::from(event)))
+}
+
+/// Stream account and transaction events as Server-Sent Events. Keys scoped
+/// to an account only receive events about that account.
+#[tracing::instrument(skip(state, actor, query), fields(key = %actor.name))]
+pub async fn stream_events<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<EventStreamQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let patterns: Vec<String> = query
+        .events
+        .iter()
+        .flat_map(|events| events.split(','))
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(String::from)
+        .collect();
+    validate_event_patterns(&patterns).map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let events = event_stream(
+        state.service.subscribe_events(),
+        StreamFilter::new(&actor, patterns),
+    );
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(STREAM_KEEP_ALIVE)))
+}
+
+/// List the webhook endpoints in the key's mode (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn list_webhooks<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+) -> Result<impl IntoResponse, ApiError> {
+    let endpoints = state.service.list_webhooks(&actor).await?;
 
     let response: Vec<_> = endpoints
         .into_iter()
-        .map(|ep| payments_types::WebhookResponse {
-            id: payments_types::WebhookEndpointId::from_uuid(ep.id),
-            url: ep.url,
-            secret: ep.secret,
-            events: ep.events,
-            is_active: ep.is_active,
-        })
+        .map(payments_types::WebhookResponse::from)
         .collect();
 
     Ok(Json(response))
@@ -434,126 +2204,35 @@ pub struct ConvertResponse {
 
 /// Get exchange rates for a base currency.
 #[tracing::instrument]
-pub async fn get_rates(Path(base): Path<String>) -> Result<impl IntoResponse, ApiError> {
-    use exchange_rates::{EUR, GBP, INR, USD, get_rate};
-
+pub async fn get_rates(Path(base): Path<String>) -> Result<Json<ExchangeRateResponse>, ApiError> {
     let base_upper = base.to_uppercase();
-
-    // Build rates map based on base currency
-    let rates_map: std::collections::HashMap<String, f64> = match base_upper.as_str() {
-        "USD" => [
-            ("USD".to_string(), 1.0),
-            ("EUR".to_string(), get_rate::<USD, EUR>()),
-            ("GBP".to_string(), get_rate::<USD, GBP>()),
-            ("INR".to_string(), get_rate::<USD, INR>()),
-        ]
-        .into_iter()
-        .collect(),
-        "EUR" => [
-            ("USD".to_string(), get_rate::<EUR, USD>()),
-            ("EUR".to_string(), 1.0),
-            ("GBP".to_string(), get_rate::<EUR, GBP>()),
-            ("INR".to_string(), get_rate::<EUR, INR>()),
-        ]
-        .into_iter()
-        .collect(),
-        "GBP" => [
-            ("USD".to_string(), get_rate::<GBP, USD>()),
-            ("EUR".to_string(), get_rate::<GBP, EUR>()),
-            ("GBP".to_string(), 1.0),
-            ("INR".to_string(), get_rate::<GBP, INR>()),
-        ]
-        .into_iter()
-        .collect(),
-        "INR" => [
-            ("USD".to_string(), get_rate::<INR, USD>()),
-            ("EUR".to_string(), get_rate::<INR, EUR>()),
-            ("GBP".to_string(), get_rate::<INR, GBP>()),
-            ("INR".to_string(), 1.0),
-        ]
-        .into_iter()
-        .collect(),
-        _ => {
-            return Err(AppError::BadRequest(format!("Unsupported currency: {}", base)).into());
-        }
-    };
+    let rates = CurrencyRegistry::global()
+        .rates(&base_upper)
+        .map_err(|_| AppError::BadRequest(format!("Unsupported currency: {}", base)))?;
 
     Ok(Json(ExchangeRateResponse {
         base: base_upper,
-        rates: rates_map,
+        rates: rates.into_iter().collect(),
     }))
 }
 
 /// Convert an amount from one currency to another.
 #[tracing::instrument]
-pub async fn convert(Json(req): Json<ConvertRequest>) -> Result<impl IntoResponse, ApiError> {
-    use exchange_rates::{EUR, GBP, INR, Money, USD, convert as do_convert, get_rate};
-
+pub async fn convert(Json(req): Json<ConvertRequest>) -> Result<Json<ConvertResponse>, ApiError> {
     let from_upper = req.from.to_uppercase();
     let to_upper = req.to.to_uppercase();
 
-    // Runtime dispatch for type-safe conversion
-    let (rate, converted) = match (from_upper.as_str(), to_upper.as_str()) {
-        ("USD", "USD") => (1.0, req.amount),
-        ("USD", "EUR") => (
-            get_rate::<USD, EUR>(),
-            do_convert::<USD, EUR>(Money::<USD>::from_minor(req.amount)).minor_units(),
-        ),
-        ("USD", "GBP") => (
-            get_rate::<USD, GBP>(),
-            do_convert::<USD, GBP>(Money::<USD>::from_minor(req.amount)).minor_units(),
-        ),
-        ("USD", "INR") => (
-            get_rate::<USD, INR>(),
-            do_convert::<USD, INR>(Money::<USD>::from_minor(req.amount)).minor_units(),
-        ),
-        ("EUR", "USD") => (
-            get_rate::<EUR, USD>(),
-            do_convert::<EUR, USD>(Money::<EUR>::from_minor(req.amount)).minor_units(),
-        ),
-        ("EUR", "EUR") => (1.0, req.amount),
-        ("EUR", "GBP") => (
-            get_rate::<EUR, GBP>(),
-            do_convert::<EUR, GBP>(Money::<EUR>::from_minor(req.amount)).minor_units(),
-        ),
-        ("EUR", "INR") => (
-            get_rate::<EUR, INR>(),
-            do_convert::<EUR, INR>(Money::<EUR>::from_minor(req.amount)).minor_units(),
-        ),
-        ("GBP", "USD") => (
-            get_rate::<GBP, USD>(),
-            do_convert::<GBP, USD>(Money::<GBP>::from_minor(req.amount)).minor_units(),
-        ),
-        ("GBP", "EUR") => (
-            get_rate::<GBP, EUR>(),
-            do_convert::<GBP, EUR>(Money::<GBP>::from_minor(req.amount)).minor_units(),
-        ),
-        ("GBP", "GBP") => (1.0, req.amount),
-        ("GBP", "INR") => (
-            get_rate::<GBP, INR>(),
-            do_convert::<GBP, INR>(Money::<GBP>::from_minor(req.amount)).minor_units(),
-        ),
-        ("INR", "USD") => (
-            get_rate::<INR, USD>(),
-            do_convert::<INR, USD>(Money::<INR>::from_minor(req.amount)).minor_units(),
-        ),
-        ("INR", "EUR") => (
-            get_rate::<INR, EUR>(),
-            do_convert::<INR, EUR>(Money::<INR>::from_minor(req.amount)).minor_units(),
-        ),
-        ("INR", "GBP") => (
-            get_rate::<INR, GBP>(),
-            do_convert::<INR, GBP>(Money::<INR>::from_minor(req.amount)).minor_units(),
-        ),
-        ("INR", "INR") => (1.0, req.amount),
-        _ => {
-            return Err(AppError::BadRequest(format!(
-                "Unsupported currency pair: {} -> {}",
-                req.from, req.to
-            ))
-            .into());
-        }
+    let registry = CurrencyRegistry::global();
+    let unsupported = |_| {
+        AppError::BadRequest(format!(
+            "Unsupported currency pair: {} -> {}",
+            req.from, req.to
+        ))
     };
+    let rate = registry.rate(&from_upper, &to_upper).map_err(unsupported)?;
+    let converted = registry
+        .convert(req.amount, &from_upper, &to_upper)
+        .map_err(unsupported)?;
 
     Ok(Json(ConvertResponse {
         from: from_upper,
@@ -563,3 +2242,191 @@ pub async fn convert(Json(req): Json<ConvertRequest>) -> Result<impl IntoRespons
         rate,
     }))
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Admin
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Get the maintenance mode state.
+#[tracing::instrument(skip(state))]
+pub async fn get_maintenance<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+) -> impl IntoResponse {
+    Json(state.maintenance.status())
+}
+
+/// Switch maintenance mode on or off (admin keys only).
+#[tracing::instrument(skip(state))]
+pub async fn set_maintenance<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<SetMaintenanceRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+
+    if req.enabled {
+        tracing::warn!(reason = ?req.reason, key = %actor.name, "Maintenance mode enabled");
+        state.maintenance.enable(req.reason);
+    } else {
+        tracing::warn!(key = %actor.name, "Maintenance mode disabled");
+        state.maintenance.disable();
+    }
+    Ok(Json(state.maintenance.status()))
+}
+
+/// Prometheus scrape endpoint (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn metrics<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    ))
+}
+
+/// Post or clear the status page incident banner (admin keys only).
+#[tracing::instrument(skip(state))]
+pub async fn set_incident<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Json(req): Json<SetIncidentRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+
+    tracing::warn!(message = ?req.message, key = %actor.name, "Incident banner updated");
+    state.incident.set(req.message, state.service.clock().now());
+    Ok(Json(current_status(&state).await))
+}
+
+/// List security changes, optionally only those in one state (admin keys only).
+#[tracing::instrument(skip(state, actor))]
+pub async fn list_changes<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Query(query): Query<ChangeRequestQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    actor.ensure_admin()?;
+
+    let changes = state.service.list_change_requests(query.status).await?;
+    Ok(Json(changes))
+}
+
+/// Get a security change by ID (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(change_id = %id))]
+pub async fn get_change<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let change_id = parse_change_request_id(&id)?;
+    actor.ensure_admin()?;
+
+    let change = state.service.get_change_request(change_id).await?;
+    Ok(Json(change))
+}
+
+/// Approve and apply a pending change requested by another admin key
+/// (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(change_id = %id))]
+pub async fn approve_change<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let change_id = parse_change_request_id(&id)?;
+    let change = state.service.approve_change(change_id, &actor).await?;
+    Ok(Json(change))
+}
+
+/// Turn down a pending change without applying it (admin keys only).
+#[tracing::instrument(skip(state, actor), fields(change_id = %id))]
+pub async fn reject_change<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    Extension(actor): Extension<ActorContext>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let change_id = parse_change_request_id(&id)?;
+    let change = state.service.reject_change(change_id, &actor).await?;
+    Ok(Json(change))
+}
+
+fn parse_change_request_id(id: &str) -> Result<ChangeRequestId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest("Invalid change request ID".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, key.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_idempotency_key_from_header_or_body() {
+        let none = HeaderMap::new();
+        assert_eq!(idempotency_key(&none, None).unwrap(), None);
+        assert_eq!(
+            idempotency_key(&none, Some("body".into()))
+                .unwrap()
+                .as_deref(),
+            Some("body")
+        );
+        assert_eq!(
+            idempotency_key(&headers("header"), None)
+                .unwrap()
+                .as_deref(),
+            Some("header")
+        );
+        assert_eq!(
+            idempotency_key(&headers("same"), Some("same".into()))
+                .unwrap()
+                .as_deref(),
+            Some("same")
+        );
+        assert!(matches!(
+            idempotency_key(&headers("header"), Some("body".into())),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rates_and_conversions_cover_registered_currencies() {
+        // XTS is reserved for testing, so no built-in will ever claim it.
+        let test_currency = "XTS:¤:1:0.01".parse().unwrap();
+        CurrencyRegistry::global().register(test_currency).unwrap();
+
+        let Ok(Json(rates)) = get_rates(Path("usd".into())).await else {
+            panic!("rates for USD");
+        };
+        assert_eq!(rates.base, "USD");
+        assert_eq!(rates.rates["USD"], 1.0);
+        assert!(rates.rates.contains_key("XTS"));
+
+        let request = ConvertRequest {
+            from: "usd".into(),
+            to: "xts".into(),
+            amount: 250,
+        };
+        let Ok(Json(converted)) = convert(Json(request)).await else {
+            panic!("conversion to XTS");
+        };
+        assert_eq!(converted.to, "XTS");
+        assert_eq!(converted.converted, 250);
+
+        assert!(get_rates(Path("XXX".into())).await.is_err());
+        let unknown = ConvertRequest {
+            from: "USD".into(),
+            to: "XXX".into(),
+            amount: 100,
+        };
+        assert!(convert(Json(unknown)).await.is_err());
+    }
+}
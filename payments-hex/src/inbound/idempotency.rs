@@ -0,0 +1,348 @@
+//! Idempotent replay of POST requests.
+//!
+//! A POST sent with an `Idempotency-Key` header is answered once: its
+//! response is stored under the key and the matched route, and a retry with
+//! the same key gets exactly that response back, marked
+//! `Idempotent-Replayed: true`, without the handler running again. Reusing a
+//! key for a different request is refused with `409 Conflict` and
+//! `"error_code": "IDEMPOTENCY_KEY_CONFLICT"`. Only successful JSON (or empty)
+//! responses are stored, so a rejected request can be corrected and retried
+//! under the same key. Keys are scoped to the API key that sent them.
+//!
+//! The key is reserved before the handler runs, so a retry sent while the
+//! first request is still being answered gets `409 Conflict` with
+//! `"error_code": "CONFLICT"` instead of making the request a second time.
+//! A request dropped before it is answered, because the client went away or
+//! the handler panicked, gives the key up again; one lost with its server
+//! holds the key until the reservation lapses.
+//!
+//! Routes whose responses carry a credential (a new API key, session token or
+//! webhook secret) are not replayed: their responses would be stored in
+//! plain text, so the header is ignored there.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    body::{Body, to_bytes},
+    extract::{MatchedPath, State},
+    http::{HeaderValue, Method, Request, StatusCode, Uri, header, response::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use payments_repo::security::hash_request;
+use payments_types::{
+    ActorContext, AppError, ErrorCode, IdempotencyRecord, ProblemDetails, TransactionRepository,
+};
+
+use super::handlers::{ApiError, AppState};
+use crate::sessions::SESSION_TOKENS_PATH;
+
+/// Header a POST request's idempotency key is sent in.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header marking a response as replayed from an earlier request.
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// POST routes whose responses carry a credential, which are never stored.
+const CREDENTIAL_ROUTES: &[&str] = &[
+    "/api/keys",
+    SESSION_TOKENS_PATH,
+    "/api/webhooks",
+    "/api/webhooks/batch",
+    "/api/webhooks/{id}/rotate-secret",
+];
+
+/// Largest request body buffered for hashing, the same as the JSON
+/// extractor's default limit.
+const MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+
+/// Replays or stores the response to a POST sent with an `Idempotency-Key`.
+/// Runs inside auth middleware, which attaches the calling key.
+pub async fn idempotency_middleware<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let Ok(key) = key.to_str().map(str::to_string) else {
+        return ApiError(AppError::BadRequest(
+            "Idempotency-Key header must be printable ASCII".into(),
+        ))
+        .into_response();
+    };
+    let Some(route) = request.extensions().get::<MatchedPath>() else {
+        return next.run(request).await;
+    };
+    if CREDENTIAL_ROUTES.contains(&route.as_str()) {
+        return next.run(request).await;
+    }
+    let endpoint = format!("POST {}", route.as_str());
+    let scoped_key = match request.extensions().get::<ActorContext>() {
+        Some(actor) => format!("{}/{}", actor.api_key_id, key),
+        None => key.clone(),
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_REQUEST_BYTES).await else {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(ProblemDetails::new(
+                413,
+                ErrorCode::InvalidRequest,
+                "Request body is too large",
+            )),
+        )
+            .into_response();
+    };
+    let request_hash = request_hash(&parts.uri, &body);
+
+    // Reserve the key, unless another request already holds it. The guard
+    // is armed first, as the reservation may be stored even if this request
+    // is dropped while waiting for the store to answer.
+    let reservation = IdempotencyRecord::in_progress(
+        &endpoint,
+        &scoped_key,
+        &request_hash,
+        state.service.clock().now(),
+    );
+    let guard = ReservationGuard::new(state.clone(), reservation.clone());
+    match state.service.save_idempotency_record(&reservation).await {
+        // Stores may round the timestamp, so compare the reservation token
+        Ok(stored) if stored.response == reservation.response => {}
+        stored => {
+            guard.disarm();
+            return match stored {
+                Ok(stored) if !stored.matches(&request_hash) => {
+                    ApiError(AppError::IdempotencyKeyConflict(key)).into_response()
+                }
+                Ok(stored) if stored.is_in_progress() => ApiError(AppError::Conflict(format!(
+                    "A request with idempotency key {} is still in progress",
+                    key
+                )))
+                .into_response(),
+                Ok(stored) => replay(stored),
+                Err(e) => ApiError(e).into_response(),
+            };
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            guard.release().await;
+            return ApiError(AppError::Internal(format!(
+                "Failed to read response: {}",
+                e
+            )))
+            .into_response();
+        }
+    };
+    let stored = if storable(&parts, &body) {
+        match serde_json::from_slice(&body) {
+            Ok(value) => Some(value),
+            Err(_) if body.is_empty() => Some(serde_json::Value::Null),
+            Err(_) => None,
+        }
+    } else {
+        None
+    };
+    match stored {
+        Some(value) => {
+            let record = IdempotencyRecord {
+                status: parts.status.as_u16(),
+                response: value,
+                created_at: state.service.clock().now(),
+                ..reservation
+            };
+            if let Err(e) = state.service.replace_idempotency_record(&record).await {
+                tracing::error!(
+                    idempotency_key = record.key,
+                    endpoint = record.endpoint,
+                    "Failed to store idempotent response: {}",
+                    e
+                );
+            }
+            guard.disarm();
+        }
+        None => guard.release().await,
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// A key reserved by a request that has not been answered yet. Dropping it
+/// before [`disarm`](Self::disarm) or [`release`](Self::release) gives the
+/// key up in the background.
+struct ReservationGuard<R: TransactionRepository> {
+    state: Arc<AppState<R>>,
+    reservation: Option<IdempotencyRecord>,
+}
+
+impl<R: TransactionRepository> ReservationGuard<R> {
+    fn new(state: Arc<AppState<R>>, reservation: IdempotencyRecord) -> Self {
+        Self {
+            state,
+            reservation: Some(reservation),
+        }
+    }
+
+    /// Keeps whatever the key holds now.
+    fn disarm(mut self) {
+        self.reservation = None;
+    }
+
+    /// Gives up the reservation, so a request that was not stored can be
+    /// corrected and retried under its key.
+    async fn release(mut self) {
+        if let Some(reservation) = self.reservation.take() {
+            release(&self.state, &reservation).await;
+        }
+    }
+}
+
+impl<R: TransactionRepository> Drop for ReservationGuard<R> {
+    fn drop(&mut self) {
+        let Some(reservation) = self.reservation.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let state = self.state.clone();
+        runtime.spawn(async move { release(&state, &reservation).await });
+    }
+}
+
+/// Removes `reservation` if its key still holds it rather than a record
+/// stored since.
+async fn release<R: TransactionRepository>(state: &AppState<R>, reservation: &IdempotencyRecord) {
+    let (endpoint, key) = (reservation.endpoint.as_str(), reservation.key.as_str());
+    let held = match state.service.idempotency_record(endpoint, key).await {
+        Ok(stored) => stored.is_some_and(|stored| stored.response == reservation.response),
+        Err(e) => {
+            tracing::error!(
+                idempotency_key = key,
+                endpoint,
+                "Failed to release idempotency key: {}",
+                e
+            );
+            return;
+        }
+    };
+    if !held {
+        return;
+    }
+    if let Err(e) = state.service.remove_idempotency_record(endpoint, key).await {
+        tracing::error!(
+            idempotency_key = key,
+            endpoint,
+            "Failed to release idempotency key: {}",
+            e
+        );
+    }
+}
+
+/// Hash of a request's path, query and body. JSON bodies are hashed as
+/// parsed, so resending the same document formatted differently matches.
+fn request_hash(uri: &Uri, body: &[u8]) -> String {
+    let body = serde_json::from_slice::<serde_json::Value>(body)
+        .map(|value| value.to_string().into_bytes())
+        .unwrap_or_else(|_| body.to_vec());
+    let mut input = uri.to_string().into_bytes();
+    input.push(b'\n');
+    input.extend(body);
+    hash_request(&input)
+}
+
+/// Whether a response with these parts and body is kept for replays: a
+/// success with a JSON body, or with none at all.
+fn storable(parts: &Parts, body: &[u8]) -> bool {
+    if !parts.status.is_success() {
+        return false;
+    }
+    match parts.headers.get(header::CONTENT_TYPE) {
+        Some(content_type) => content_type
+            .to_str()
+            .is_ok_and(|value| value.starts_with("application/json")),
+        None => body.is_empty(),
+    }
+}
+
+/// The response stored in `record`, marked as replayed.
+fn replay(record: IdempotencyRecord) -> Response {
+    let status = StatusCode::from_u16(record.status).unwrap_or(StatusCode::OK);
+    let mut response = if record.response.is_null() {
+        status.into_response()
+    } else {
+        (status, Json(record.response)).into_response()
+    };
+    response
+        .headers_mut()
+        .insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_hash_ignores_json_formatting() {
+        let uri: Uri = "/api/accounts".parse().unwrap();
+        assert_eq!(
+            request_hash(&uri, br#"{"name":"Alice","currency":"USD"}"#),
+            request_hash(&uri, b"{ \"currency\": \"USD\",\n  \"name\": \"Alice\" }"),
+        );
+        assert_ne!(
+            request_hash(&uri, br#"{"name":"Alice"}"#),
+            request_hash(&uri, br#"{"name":"Bob"}"#),
+        );
+        assert_ne!(
+            request_hash(&uri, b""),
+            request_hash(&"/api/owners".parse().unwrap(), b""),
+        );
+    }
+
+    #[test]
+    fn test_only_successful_json_or_empty_responses_are_stored() {
+        let parts = |response: Response| response.into_parts().0;
+        assert!(storable(
+            &parts((StatusCode::CREATED, Json(serde_json::json!({"id": 1}))).into_response()),
+            br#"{"id":1}"#,
+        ));
+        assert!(storable(
+            &parts(StatusCode::NO_CONTENT.into_response()),
+            b""
+        ));
+        assert!(!storable(
+            &parts((StatusCode::BAD_REQUEST, Json(serde_json::json!({}))).into_response()),
+            b"{}",
+        ));
+        assert!(!storable(
+            &parts((StatusCode::OK, "csv,data").into_response()),
+            b"csv,data",
+        ));
+    }
+
+    #[test]
+    fn test_replay_restores_status_and_marks_response() {
+        let record = IdempotencyRecord {
+            endpoint: "POST /api/owners".into(),
+            key: "k".into(),
+            request_hash: String::new(),
+            status: 201,
+            response: serde_json::json!({"id": "o1"}),
+            created_at: chrono::Utc::now(),
+        };
+        let response = replay(record);
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[REPLAYED_HEADER], "true");
+    }
+}
@@ -0,0 +1,132 @@
+//! Read-only maintenance mode.
+//!
+//! While enabled, every request that could change state is rejected with
+//! `503 Service Unavailable` and `"error_code": "MAINTENANCE_MODE"`. Reads
+//! keep working, as do the admin endpoints that switch the mode off and post
+//! the status page's incident banner.
+
+use std::sync::{Arc, RwLock};
+
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use payments_types::{ErrorCode, MaintenanceStatus, ProblemDetails};
+
+use super::status::INCIDENT_PATH;
+use crate::sessions::SESSION_TOKENS_PATH;
+
+/// Path of the admin endpoint that toggles maintenance mode.
+pub const MAINTENANCE_PATH: &str = "/api/admin/maintenance";
+
+/// Admin endpoints that keep working during maintenance.
+const ALWAYS_ALLOWED: &[&str] = &[MAINTENANCE_PATH, INCIDENT_PATH];
+
+/// POST endpoints that do not change state.
+const READ_ONLY_POSTS: &[&str] = &["/api/convert", SESSION_TOKENS_PATH];
+
+/// Shared maintenance switch.
+#[derive(Debug, Default)]
+pub struct MaintenanceMode {
+    status: RwLock<MaintenanceStatus>,
+}
+
+impl MaintenanceMode {
+    /// Rejects mutations from now on.
+    pub fn enable(&self, reason: Option<String>) {
+        *self.status.write().unwrap() = MaintenanceStatus {
+            enabled: true,
+            reason,
+        };
+    }
+
+    /// Accepts mutations again.
+    pub fn disable(&self) {
+        *self.status.write().unwrap() = MaintenanceStatus {
+            enabled: false,
+            reason: None,
+        };
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        self.status.read().unwrap().clone()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.status.read().unwrap().enabled
+    }
+}
+
+/// Returns `true` if a request may change state.
+fn is_mutation(method: &Method, path: &str) -> bool {
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => false,
+        Method::POST => !READ_ONLY_POSTS.contains(&path),
+        _ => true,
+    }
+}
+
+/// Rejects mutations while maintenance mode is enabled.
+pub async fn maintenance_middleware(
+    State(mode): State<Arc<MaintenanceMode>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if ALWAYS_ALLOWED.contains(&path) || !is_mutation(request.method(), path) {
+        return next.run(request).await;
+    }
+
+    let status = mode.status();
+    if !status.enabled {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ProblemDetails {
+            reason: status.reason,
+            ..ProblemDetails::new(
+                503,
+                ErrorCode::MaintenanceMode,
+                "Service is in read-only maintenance mode, please retry later",
+            )
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_are_not_mutations() {
+        assert!(!is_mutation(&Method::GET, "/api/accounts"));
+        assert!(!is_mutation(&Method::POST, "/api/convert"));
+        assert!(is_mutation(&Method::POST, "/api/transactions/deposit"));
+        assert!(is_mutation(&Method::DELETE, "/api/keys/abc"));
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mode = MaintenanceMode::default();
+        assert!(!mode.is_enabled());
+
+        mode.enable(Some("migration".into()));
+        assert_eq!(
+            mode.status(),
+            MaintenanceStatus {
+                enabled: true,
+                reason: Some("migration".into()),
+            }
+        );
+
+        mode.disable();
+        assert!(!mode.is_enabled());
+    }
+}
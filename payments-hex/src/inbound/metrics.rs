@@ -0,0 +1,85 @@
+//! Request metrics middleware.
+//!
+//! Counts every request by method, matched route and status, and records how
+//! long it took and how many repository calls it made, into the registry
+//! `GET /metrics` renders. Routes are labelled
+//! by their pattern (`/api/accounts/{id}`), never the raw path, so IDs do not
+//! create a series each.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+
+use payments_repo::count_repo_calls;
+use payments_types::Metrics;
+use payments_types::ports::metrics::{
+    HTTP_REQUEST_DURATION_SECONDS, HTTP_REQUEST_REPO_CALLS_TOTAL, HTTP_REQUESTS_TOTAL,
+};
+
+use crate::metrics::MetricsRegistry;
+
+/// Path of the Prometheus scrape endpoint.
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Route label for requests that matched no route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Records the request count, latency and repository calls once the response
+/// is ready.
+///
+/// Calls are counted by a [`CountingRepo`](payments_repo::CountingRepo); with
+/// any other repository every request makes none.
+pub async fn metrics_middleware(
+    State(metrics): State<Arc<MetricsRegistry>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(UNMATCHED_ROUTE.to_string(), |path| {
+            path.as_str().to_string()
+        });
+    let started = Instant::now();
+
+    let (response, repo_calls) = count_repo_calls(next.run(request)).await;
+
+    let status = response.status().as_u16().to_string();
+    metrics.increment_counter(
+        HTTP_REQUESTS_TOTAL,
+        &[
+            ("method", method.as_str()),
+            ("route", route.as_str()),
+            ("status", status.as_str()),
+        ],
+        1,
+    );
+    metrics.observe_histogram(
+        HTTP_REQUEST_DURATION_SECONDS,
+        &[("method", method.as_str()), ("route", route.as_str())],
+        started.elapsed().as_secs_f64(),
+    );
+    let total = repo_calls.total();
+    metrics.increment_counter(
+        HTTP_REQUEST_REPO_CALLS_TOTAL,
+        &[("method", method.as_str()), ("route", route.as_str())],
+        total,
+    );
+    tracing::debug!(
+        method = %method,
+        route = %route,
+        repo_calls = total,
+        by_operation = ?repo_calls.by_operation(),
+        "Request made {} repository calls",
+        total
+    );
+    response
+}
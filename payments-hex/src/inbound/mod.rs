@@ -1,12 +1,34 @@
-//! HTTP Inbound Adapter
+//! Inbound Adapters
 //!
-//! Axum-based HTTP server that drives the application layer.
+//! Axum-based HTTP server that drives the application layer, and with the
+//! `grpc` feature a tonic-based gRPC server alongside it.
 
 pub mod auth;
+pub mod diagnostics;
+pub mod extract;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod handlers;
+pub mod idempotency;
+pub mod maintenance;
+pub mod metrics;
 pub mod rate_limit;
+pub mod scopes;
 mod server;
+pub mod status;
+pub mod stream;
+pub mod usage;
 
 pub use auth::auth_middleware;
-pub use rate_limit::{RateLimiterState, rate_limit_middleware};
+#[cfg(feature = "grpc")]
+pub use grpc::GrpcServer;
+pub use idempotency::idempotency_middleware;
+pub use maintenance::{MaintenanceMode, maintenance_middleware};
+pub use metrics::metrics_middleware;
+pub use rate_limit::{
+    InMemoryRateLimits, RateLimiterState, address_rate_limit_middleware, rate_limit_middleware,
+};
+pub use scopes::scope_middleware;
 pub use server::HttpServer;
+pub use status::IncidentBanner;
+pub use usage::usage_middleware;
@@ -1,30 +1,119 @@
 //! Rate limiting middleware using Governor.
 //!
-//! Implements per-API-key rate limiting with a token bucket algorithm.
+//! Implements per-API-key rate limiting with a token bucket algorithm, and
+//! per-address limiting for the public routes that take no key. Keys can
+//! carry a quota of their own, and every limited response reports the quota
+//! and what is left of it in `X-RateLimit-Limit` and `X-RateLimit-Remaining`.
+//!
+//! Requests are counted in this process unless a shared [`RateLimitStore`]
+//! is configured, so that replicas enforce one quota between them. When the
+//! shared store cannot be reached, each instance limits on its own until it
+//! is back.
 
+use async_trait::async_trait;
 use axum::{
     Json,
     body::Body,
-    extract::State,
-    http::{Request, StatusCode},
+    extract::{ConnectInfo, State},
+    http::{HeaderValue, Request, StatusCode, header},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use dashmap::DashMap;
 use governor::{
     Quota, RateLimiter,
-    clock::DefaultClock,
+    clock::{Clock, DefaultClock},
+    middleware::StateInformationMiddleware,
     state::{InMemoryState, NotKeyed},
 };
-use serde_json::json;
-use std::{num::NonZeroU32, sync::Arc, time::Duration};
+use payments_types::{ApiKey, ErrorCode, ProblemDetails, RateLimitError, RateLimitStore};
+use std::{
+    net::SocketAddr,
+    num::NonZeroU32,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+pub use payments_types::RateLimitDecision;
+
+/// Header carrying the requests a key may make per period.
+pub const LIMIT_HEADER: &str = "x-ratelimit-limit";
+
+/// Header carrying the requests a key has left before it is limited.
+pub const REMAINING_HEADER: &str = "x-ratelimit-remaining";
+
+/// One key's limiter.
+struct KeyLimiter {
+    limiter: RateLimiter<NotKeyed, InMemoryState, DefaultClock, StateInformationMiddleware>,
+    quota: Quota,
+}
+
+/// [`RateLimitStore`] that keeps every key's limiter in this process.
+#[derive(Default)]
+pub struct InMemoryRateLimits {
+    limiters: DashMap<String, Arc<KeyLimiter>>,
+}
+
+impl InMemoryRateLimits {
+    /// Counts one request from `key` against a burst of `requests`, one
+    /// replenished every `period`. A key whose quota changed starts afresh
+    /// under the new one.
+    pub fn check_now(&self, key: &str, requests: u32, period: Duration) -> RateLimitDecision {
+        let quota = Quota::with_period(period)
+            .expect("rate limit period must not be zero")
+            .allow_burst(NonZeroU32::new(requests).unwrap_or(NonZeroU32::MIN));
+        let limiter = {
+            let mut entry = self.limiters.entry(key.to_string()).or_insert_with(|| {
+                Arc::new(KeyLimiter {
+                    limiter: RateLimiter::direct(quota).with_middleware(),
+                    quota,
+                })
+            });
+            if entry.quota != quota {
+                *entry = Arc::new(KeyLimiter {
+                    limiter: RateLimiter::direct(quota).with_middleware(),
+                    quota,
+                });
+            }
+            entry.clone()
+        };
+
+        let outcome = limiter.limiter.check();
+        RateLimitDecision {
+            limit: quota.burst_size().get(),
+            remaining: outcome
+                .as_ref()
+                .map_or(0, |snapshot| snapshot.remaining_burst_capacity()),
+            retry_after: outcome
+                .err()
+                .map(|not_until| not_until.wait_time_from(limiter.limiter.clock().now())),
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimits {
+    async fn check(
+        &self,
+        key: &str,
+        requests: u32,
+        period: Duration,
+    ) -> Result<RateLimitDecision, RateLimitError> {
+        Ok(self.check_now(key, requests, period))
+    }
+}
 
 /// Rate limiter state shared across requests.
 pub struct RateLimiterState {
-    /// Per-key rate limiters
-    limiters: DashMap<String, Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>>,
-    /// Default quota for new keys
-    quota: Quota,
+    /// Per-key limiters in this process
+    local: InMemoryRateLimits,
+    /// Store shared between replicas; `None` to limit each on its own
+    shared: Option<Arc<dyn RateLimitStore>>,
+    /// Each key's last decision and when it was made
+    last_seen: DashMap<String, (RateLimitDecision, Instant)>,
+    /// Requests per period for keys without a quota of their own
+    requests: u32,
+    period: Duration,
 }
 
 impl Default for RateLimiterState {
@@ -40,25 +129,87 @@ impl RateLimiterState {
     /// * `requests` - Number of requests allowed per period
     /// * `period` - Time period for the quota
     pub fn new(requests: u32, period: Duration) -> Self {
-        let quota = Quota::with_period(period)
-            .unwrap()
-            .allow_burst(NonZeroU32::new(requests).unwrap());
-
         Self {
-            limiters: DashMap::new(),
-            quota,
+            local: InMemoryRateLimits::default(),
+            shared: None,
+            last_seen: DashMap::new(),
+            requests,
+            period,
         }
     }
 
+    /// Counts requests in `store`, e.g. one shared by every replica,
+    /// instead of in this process.
+    pub fn with_store(mut self, store: Arc<dyn RateLimitStore>) -> Self {
+        self.shared = Some(store);
+        self
+    }
+
     /// Checks if a request should be rate limited.
     /// Returns true if the request is allowed, false if rate limited.
     pub fn check(&self, key: &str) -> bool {
-        let limiter = self
-            .limiters
-            .entry(key.to_string())
-            .or_insert_with(|| Arc::new(RateLimiter::direct(self.quota)));
+        self.check_with_wait_time(key).is_ok()
+    }
+
+    /// Checks if a request should be rate limited, returning how long `key`
+    /// has to wait before its next request is allowed when it is.
+    pub fn check_with_wait_time(&self, key: &str) -> Result<(), Duration> {
+        match self.check_with_limit(key, None).retry_after {
+            Some(wait) => Err(wait),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks a request from `key` against `requests` per period, or the
+    /// default quota when `None`, in this process only. A key whose quota
+    /// changed starts afresh under the new one.
+    pub fn check_with_limit(&self, key: &str, requests: Option<u32>) -> RateLimitDecision {
+        let decision = self
+            .local
+            .check_now(key, requests.unwrap_or(self.requests), self.period);
+        self.record(key, decision)
+    }
+
+    /// Like [`check_with_limit`](Self::check_with_limit), but counted in the
+    /// shared store when there is one. Falls back to this process when the
+    /// store fails.
+    pub async fn check_request(&self, key: &str, requests: Option<u32>) -> RateLimitDecision {
+        let Some(shared) = &self.shared else {
+            return self.check_with_limit(key, requests);
+        };
+        match shared
+            .check(key, requests.unwrap_or(self.requests), self.period)
+            .await
+        {
+            Ok(decision) => self.record(key, decision),
+            Err(e) => {
+                tracing::warn!(error = %e, "Rate limiting in this process only");
+                self.check_with_limit(key, requests)
+            }
+        }
+    }
 
-        limiter.check().is_ok()
+    fn record(&self, key: &str, decision: RateLimitDecision) -> RateLimitDecision {
+        self.last_seen
+            .insert(key.to_string(), (decision, Instant::now()));
+        decision
+    }
+
+    /// Returns how much of its quota the busiest key has used, from 0.0
+    /// (idle) to 1.0 (being limited), counting capacity that has
+    /// replenished since the key's last request.
+    pub fn saturation(&self) -> f64 {
+        let interval = self.period.as_nanos().max(1);
+        self.last_seen
+            .iter()
+            .map(|entry| {
+                let (decision, at) = *entry.value();
+                let burst = u128::from(decision.limit.max(1));
+                let replenished = at.elapsed().as_nanos() / interval;
+                let available = (u128::from(decision.remaining) + replenished).min(burst);
+                1.0 - available as f64 / burst as f64
+            })
+            .fold(0.0, f64::max)
     }
 }
 
@@ -82,19 +233,68 @@ pub async fn rate_limit_middleware(
         .map(|s| s.trim_start_matches("Bearer ").to_string())
         .unwrap_or_else(|| "anonymous".to_string());
 
-    // Check rate limit
-    if !limiter.check(&key) {
-        return (
-            StatusCode::TOO_MANY_REQUESTS,
-            Json(json!({
-                "error": "Rate limit exceeded. Please try again later.",
-                "retry_after_seconds": 60
-            })),
-        )
-            .into_response();
-    }
+    // Keys with a quota of their own carry it from the auth middleware
+    let quota = request
+        .extensions()
+        .get::<ApiKey>()
+        .and_then(|api_key| api_key.rate_limit_per_minute);
+    let decision = limiter.check_request(&key, quota).await;
+    let response = match decision.retry_after {
+        Some(wait) => rate_limited(wait),
+        None => next.run(request).await,
+    };
+    with_rate_limit_headers(response, decision)
+}
+
+/// Rate limiting middleware for public routes, keyed by the caller's address.
+///
+/// The address comes from [`ConnectInfo`]; when the router is served without
+/// it, all callers share one quota.
+pub async fn address_rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiterState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let key = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "anonymous".to_string());
+
+    let decision = limiter.check_request(&key, None).await;
+    let response = match decision.retry_after {
+        Some(wait) => rate_limited(wait),
+        None => next.run(request).await,
+    };
+    with_rate_limit_headers(response, decision)
+}
 
-    next.run(request).await
+/// Tells the caller its quota and how much of it is left.
+fn with_rate_limit_headers(mut response: Response, decision: RateLimitDecision) -> Response {
+    let headers = response.headers_mut();
+    headers.insert(LIMIT_HEADER, HeaderValue::from(decision.limit));
+    headers.insert(REMAINING_HEADER, HeaderValue::from(decision.remaining));
+    response
+}
+
+/// A 429 telling the caller to retry after `wait`, rounded up to whole
+/// seconds so retrying on time never hits the limit again.
+fn rate_limited(wait: Duration) -> Response {
+    let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    let retry_after = retry_after.max(1);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after.to_string())],
+        Json(ProblemDetails {
+            retry_after_seconds: Some(retry_after),
+            ..ProblemDetails::new(
+                429,
+                ErrorCode::RateLimited,
+                "Rate limit exceeded. Please try again later.",
+            )
+        }),
+    )
+        .into_response()
 }
 
 #[cfg(test)]
@@ -147,6 +347,98 @@ mod tests {
         assert!(!limiter.check("key-b"), "Key B request 3 should be blocked");
     }
 
+    #[test]
+    fn test_per_key_quota_overrides_the_default() {
+        let limiter = RateLimiterState::new(2, Duration::from_secs(60));
+
+        let first = limiter.check_with_limit("premium-key", Some(5));
+        assert_eq!(
+            first,
+            RateLimitDecision {
+                limit: 5,
+                remaining: 4,
+                retry_after: None,
+            }
+        );
+        for remaining in (0..4).rev() {
+            let decision = limiter.check_with_limit("premium-key", Some(5));
+            assert_eq!(decision.remaining, remaining);
+        }
+        let refused = limiter.check_with_limit("premium-key", Some(5));
+        assert_eq!(refused.remaining, 0);
+        assert!(refused.retry_after.is_some());
+
+        // Changing the quota starts the key afresh under the new one.
+        let downgraded = limiter.check_with_limit("premium-key", None);
+        assert_eq!((downgraded.limit, downgraded.remaining), (2, 1));
+        assert!(downgraded.retry_after.is_none());
+    }
+
+    /// A store whose quotas are always used up, or that is unreachable.
+    struct StubStore {
+        reachable: bool,
+    }
+
+    #[async_trait]
+    impl RateLimitStore for StubStore {
+        async fn check(
+            &self,
+            _key: &str,
+            requests: u32,
+            period: Duration,
+        ) -> Result<RateLimitDecision, RateLimitError> {
+            if !self.reachable {
+                return Err(RateLimitError::Unavailable("connection refused".into()));
+            }
+            Ok(RateLimitDecision {
+                limit: requests,
+                remaining: 0,
+                retry_after: Some(period),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_store_decides_when_configured() {
+        let limiter = RateLimiterState::new(3, Duration::from_secs(60))
+            .with_store(Arc::new(StubStore { reachable: true }));
+
+        let decision = limiter.check_request("shared-key", None).await;
+        assert_eq!(decision.limit, 3);
+        assert_eq!(decision.retry_after, Some(Duration::from_secs(60)));
+        assert_eq!(limiter.saturation(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_store_falls_back_to_this_process() {
+        let limiter = RateLimiterState::new(2, Duration::from_secs(60))
+            .with_store(Arc::new(StubStore { reachable: false }));
+
+        for remaining in [1, 0] {
+            let decision = limiter.check_request("fallback-key", None).await;
+            assert_eq!(decision.remaining, remaining);
+            assert!(decision.retry_after.is_none());
+        }
+        let refused = limiter.check_request("fallback-key", None).await;
+        assert!(refused.retry_after.is_some());
+    }
+
+    #[test]
+    fn test_saturation_follows_the_busiest_key() {
+        let limiter = RateLimiterState::new(4, Duration::from_secs(60));
+        assert_eq!(limiter.saturation(), 0.0);
+
+        for _ in 0..3 {
+            assert!(limiter.check("busy-key"));
+        }
+        assert!(limiter.check("quiet-key"));
+        assert_eq!(limiter.saturation(), 0.75);
+
+        assert!(limiter.check("busy-key"));
+        assert!(!limiter.check("busy-key"));
+        assert_eq!(limiter.saturation(), 1.0);
+    }
+
     #[test]
     fn test_rate_limiter_default_config() {
         // Default is 100 requests per 60 seconds
@@ -188,6 +480,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rate_limiter_reports_wait_until_next_request() {
+        // One request replenishes every 10 seconds, with a burst of 2
+        let limiter = RateLimiterState::new(2, Duration::from_secs(10));
+
+        assert_eq!(limiter.check_with_wait_time("wait-key"), Ok(()));
+        assert_eq!(limiter.check_with_wait_time("wait-key"), Ok(()));
+        let wait = limiter.check_with_wait_time("wait-key").unwrap_err();
+        assert!(
+            wait > Duration::from_secs(9) && wait <= Duration::from_secs(10),
+            "Should wait for one request to replenish, got {:?}",
+            wait
+        );
+    }
+
+    #[test]
+    fn test_rate_limited_response_rounds_wait_up() {
+        let response = rate_limited(Duration::from_millis(2_100));
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "3");
+
+        let response = rate_limited(Duration::ZERO);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+    }
+
     #[test]
     fn test_rate_limiter_multiple_keys_independent() {
         let limiter = RateLimiterState::new(1, Duration::from_secs(60));
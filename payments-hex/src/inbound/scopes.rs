@@ -0,0 +1,231 @@
+//! API key scope enforcement.
+//!
+//! Each protected route requires at most one [`ApiKeyScope`]. A key without
+//! it is rejected with `403 Forbidden` and `"error_code": "INSUFFICIENT_SCOPE"`
+//! before the handler runs. Routes missing from [`required_scope`] need
+//! `keys:admin`, so a new route is never open to every key by accident.
+
+use axum::{
+    Json,
+    body::Body,
+    extract::MatchedPath,
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use payments_types::{ActorContext, ApiKeyScope, ErrorCode, ProblemDetails};
+
+use super::maintenance::MAINTENANCE_PATH;
+use super::stream::STREAM_PATH;
+use crate::sessions::{SESSION_TOKENS_PATH, SessionClaims};
+
+/// Route prefixes guarded by the `accounts:*` scopes.
+const ACCOUNT_ROUTES: &[&str] = &[
+    "/api/accounts",
+    "/api/aliases",
+    "/api/beneficiaries",
+    "/api/fee-schedules",
+    "/api/owners",
+    "/api/statements",
+];
+
+/// Route prefixes guarded by the `transactions:*` scopes.
+const TRANSACTION_ROUTES: &[&str] = &[
+    "/api/transactions",
+    "/api/holds",
+    "/api/quotes",
+    "/api/scheduled-payments",
+    "/api/settlement-batches",
+];
+
+/// Route prefixes that only read transactions, whatever the method.
+const TRANSACTION_READ_ROUTES: &[&str] = &[
+    "/api/accounts/{id}/transactions",
+    "/api/accounts/{id}/holds",
+    "/api/exports",
+    "/api/reports",
+];
+
+/// The scope a request to `route` (the matched route pattern) requires.
+fn required_scope(method: &Method, route: &str) -> Option<ApiKeyScope> {
+    let read = matches!(*method, Method::GET | Method::HEAD);
+    let by_method = |read_scope, write_scope| Some(if read { read_scope } else { write_scope });
+    let under = |prefixes: &[&str]| prefixes.iter().any(|p| route.starts_with(p));
+
+    match route {
+        // Any key may read its own usage; the handler guards other keys'.
+        "/api/keys/{id}/usage" => None,
+        MAINTENANCE_PATH => Some(ApiKeyScope::KeysAdmin),
+        // Tokens only carry read scopes the issuing key holds.
+        SESSION_TOKENS_PATH => Some(ApiKeyScope::AccountsRead),
+        // The stream itself filters events to the key's account.
+        STREAM_PATH => Some(ApiKeyScope::TransactionsRead),
+        "/api/inbound-payments" => Some(ApiKeyScope::InboundWrite),
+        _ if under(TRANSACTION_READ_ROUTES) => Some(ApiKeyScope::TransactionsRead),
+        _ if under(ACCOUNT_ROUTES) => {
+            by_method(ApiKeyScope::AccountsRead, ApiKeyScope::AccountsWrite)
+        }
+        _ if under(TRANSACTION_ROUTES) => by_method(
+            ApiKeyScope::TransactionsRead,
+            ApiKeyScope::TransactionsWrite,
+        ),
+        _ if route.starts_with("/api/webhooks") => {
+            by_method(ApiKeyScope::WebhooksRead, ApiKeyScope::WebhooksWrite)
+        }
+        _ => Some(ApiKeyScope::KeysAdmin),
+    }
+}
+
+/// Rejects requests whose API key lacks the route's scope.
+///
+/// Runs as a route layer after [`auth_middleware`](super::auth_middleware),
+/// which puts the caller's [`ActorContext`] in the request extensions.
+pub async fn scope_middleware(request: Request<Body>, next: Next) -> Response {
+    let Some(route) = request.extensions().get::<MatchedPath>() else {
+        return next.run(request).await;
+    };
+    let Some(scope) = required_scope(request.method(), route.as_str()) else {
+        // Routes open to every key stay closed to session tokens, which may
+        // only use what their read scopes cover.
+        if request.extensions().get::<SessionClaims>().is_some() {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ProblemDetails::new(
+                    403,
+                    ErrorCode::Forbidden,
+                    "Session tokens cannot use this endpoint",
+                )),
+            )
+                .into_response();
+        }
+        return next.run(request).await;
+    };
+    let allowed = request
+        .extensions()
+        .get::<ActorContext>()
+        .is_some_and(|key| key.has_scope(scope));
+    if allowed {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::FORBIDDEN,
+        Json(ProblemDetails {
+            required_scope: Some(scope),
+            ..ProblemDetails::new(
+                403,
+                ErrorCode::InsufficientScope,
+                format!("API key is missing the {} scope", scope),
+            )
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::diagnostics::DIAGNOSTICS_PATH;
+    use crate::inbound::metrics::METRICS_PATH;
+    use crate::inbound::status::INCIDENT_PATH;
+
+    #[test]
+    fn test_required_scopes() {
+        let cases = [
+            (Method::GET, "/api/accounts/{id}", ApiKeyScope::AccountsRead),
+            (Method::POST, "/api/accounts", ApiKeyScope::AccountsWrite),
+            (
+                Method::DELETE,
+                "/api/accounts/{id}/aliases/{alias}",
+                ApiKeyScope::AccountsWrite,
+            ),
+            (
+                Method::GET,
+                "/api/accounts/{id}/transactions",
+                ApiKeyScope::TransactionsRead,
+            ),
+            (
+                Method::POST,
+                "/api/transactions/deposit",
+                ApiKeyScope::TransactionsWrite,
+            ),
+            (
+                Method::POST,
+                "/api/transactions/batch",
+                ApiKeyScope::TransactionsWrite,
+            ),
+            (Method::POST, "/api/quotes", ApiKeyScope::TransactionsWrite),
+            (
+                Method::GET,
+                "/api/quotes/{id}",
+                ApiKeyScope::TransactionsRead,
+            ),
+            (
+                Method::POST,
+                "/api/beneficiaries/{id}/verify",
+                ApiKeyScope::AccountsWrite,
+            ),
+            (Method::GET, "/api/owners/{id}", ApiKeyScope::AccountsRead),
+            (
+                Method::PATCH,
+                "/api/owners/{id}",
+                ApiKeyScope::AccountsWrite,
+            ),
+            (Method::POST, "/api/exports", ApiKeyScope::TransactionsRead),
+            (Method::GET, "/api/webhooks", ApiKeyScope::WebhooksRead),
+            (
+                Method::POST,
+                "/api/webhooks/batch",
+                ApiKeyScope::WebhooksWrite,
+            ),
+            (
+                Method::DELETE,
+                "/api/webhooks/{id}",
+                ApiKeyScope::WebhooksWrite,
+            ),
+            (
+                Method::POST,
+                "/api/webhooks/{id}/rotate-secret",
+                ApiKeyScope::WebhooksWrite,
+            ),
+            (
+                Method::POST,
+                "/api/webhooks/deliveries/{id}/redeliver",
+                ApiKeyScope::WebhooksWrite,
+            ),
+            (Method::POST, "/api/keys", ApiKeyScope::KeysAdmin),
+            (Method::GET, "/api/keys", ApiKeyScope::KeysAdmin),
+            (
+                Method::PUT,
+                "/api/keys/{id}/rate-limit",
+                ApiKeyScope::KeysAdmin,
+            ),
+            (Method::PUT, MAINTENANCE_PATH, ApiKeyScope::KeysAdmin),
+            (Method::PUT, INCIDENT_PATH, ApiKeyScope::KeysAdmin),
+            (Method::GET, METRICS_PATH, ApiKeyScope::KeysAdmin),
+            (Method::GET, DIAGNOSTICS_PATH, ApiKeyScope::KeysAdmin),
+            (Method::GET, "/api/admin/storage", ApiKeyScope::KeysAdmin),
+            (
+                Method::POST,
+                "/api/admin/changes/{id}/approve",
+                ApiKeyScope::KeysAdmin,
+            ),
+            (Method::POST, SESSION_TOKENS_PATH, ApiKeyScope::AccountsRead),
+            (Method::GET, STREAM_PATH, ApiKeyScope::TransactionsRead),
+            (
+                Method::POST,
+                "/api/inbound-payments",
+                ApiKeyScope::InboundWrite,
+            ),
+            (Method::GET, "/api/unmapped", ApiKeyScope::KeysAdmin),
+        ];
+        for (method, route, scope) in cases {
+            assert_eq!(
+                required_scope(&method, route),
+                Some(scope),
+                "{method} {route}"
+            );
+        }
+        assert_eq!(required_scope(&Method::GET, "/api/keys/{id}/usage"), None);
+    }
+}
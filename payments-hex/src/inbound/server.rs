@@ -1,53 +1,127 @@
 //! HTTP Server configuration and startup.
 
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
     Router, middleware,
-    routing::{get, post},
+    routing::{get, patch, post, put},
 };
 use tower_http::trace::TraceLayer;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-use payments_types::TransactionRepository;
+use payments_types::{RateLimitStore, TransactionRepository};
 
 use super::auth::auth_middleware;
+use super::diagnostics::{DIAGNOSTICS_PATH, LIVE_PATH, READY_PATH, READYZ_PATH};
 use super::handlers::{self, AppState};
-use super::rate_limit::{RateLimiterState, rate_limit_middleware};
+use super::idempotency::idempotency_middleware;
+use super::maintenance::{MAINTENANCE_PATH, MaintenanceMode, maintenance_middleware};
+use super::metrics::{METRICS_PATH, metrics_middleware};
+use super::rate_limit::{RateLimiterState, address_rate_limit_middleware, rate_limit_middleware};
+use super::scopes::scope_middleware;
+use super::status::{INCIDENT_PATH, STATUS_PATH, STATUS_REQUESTS_PER_MINUTE};
+use super::stream::STREAM_PATH;
+use super::usage::usage_middleware;
 use crate::PaymentService;
+use crate::metrics::MetricsRegistry;
 use crate::openapi::ApiDoc;
+use crate::sessions::SESSION_TOKENS_PATH;
 
 /// HTTP Server for the Payments API.
 pub struct HttpServer<R: TransactionRepository> {
     state: Arc<AppState<R>>,
-    rate_limiter: Arc<RateLimiterState>,
+    status_limiter: Arc<RateLimiterState>,
+    /// Default quota of each API key
+    requests_per_minute: u32,
 }
 
 impl<R: TransactionRepository> HttpServer<R> {
     /// Creates a new HTTP server with the given service.
-    pub fn new(service: PaymentService<R>) -> Self {
-        Self {
-            state: Arc::new(AppState { service }),
-            rate_limiter: Arc::new(RateLimiterState::default()), // 100 req/min default
-        }
+    ///
+    /// Pass an `Arc` to share the service with background jobs.
+    pub fn new(service: impl Into<Arc<PaymentService<R>>>) -> Self {
+        Self::with_rate_limit(service, 100) // 100 req/min default
     }
 
     /// Creates a new HTTP server with custom rate limiting.
-    pub fn with_rate_limit(service: PaymentService<R>, requests_per_minute: u32) -> Self {
-        use std::time::Duration;
+    pub fn with_rate_limit(
+        service: impl Into<Arc<PaymentService<R>>>,
+        requests_per_minute: u32,
+    ) -> Self {
         Self {
-            state: Arc::new(AppState { service }),
-            rate_limiter: Arc::new(RateLimiterState::new(
-                requests_per_minute,
+            state: Arc::new(AppState {
+                service: service.into(),
+                maintenance: Arc::default(),
+                incident: Arc::default(),
+                metrics: Arc::default(),
+                rate_limiter: Arc::new(RateLimiterState::new(
+                    requests_per_minute,
+                    Duration::from_secs(60),
+                )),
+            }),
+            status_limiter: Arc::new(RateLimiterState::new(
+                STATUS_REQUESTS_PER_MINUTE,
                 Duration::from_secs(60),
             )),
+            requests_per_minute,
         }
     }
 
+    /// Returns the service behind the API, e.g. to share it with jobs.
+    pub fn service(&self) -> Arc<PaymentService<R>> {
+        self.state.service.clone()
+    }
+
+    /// Returns the maintenance switch, e.g. to start the server read-only.
+    pub fn maintenance(&self) -> Arc<MaintenanceMode> {
+        self.state.maintenance.clone()
+    }
+
+    /// Records request metrics into `metrics` and serves it at `/metrics`.
+    ///
+    /// Pass the registry the service was built with so one scrape covers
+    /// requests, transactions and webhook deliveries.
+    pub fn with_metrics(mut self, metrics: Arc<MetricsRegistry>) -> Self {
+        self.state = Arc::new(AppState {
+            service: self.state.service.clone(),
+            maintenance: self.state.maintenance.clone(),
+            incident: self.state.incident.clone(),
+            metrics,
+            rate_limiter: self.state.rate_limiter.clone(),
+        });
+        self
+    }
+
+    /// Counts API key quotas in `store`, e.g. Redis, so replicas enforce
+    /// them between them rather than each allowing the full quota. The
+    /// public status page stays limited per instance.
+    pub fn with_rate_limit_store(mut self, store: Arc<dyn RateLimitStore>) -> Self {
+        let rate_limiter = RateLimiterState::new(self.requests_per_minute, Duration::from_secs(60))
+            .with_store(store);
+        self.state = Arc::new(AppState {
+            service: self.state.service.clone(),
+            maintenance: self.state.maintenance.clone(),
+            incident: self.state.incident.clone(),
+            metrics: self.state.metrics.clone(),
+            rate_limiter: Arc::new(rate_limiter),
+        });
+        self
+    }
+
     /// Builds the Axum router with all routes.
     pub fn router(&self) -> Router {
-        // Protected API routes (require auth + rate limiting)
+        // Protected API routes (require auth + rate limiting; each route
+        // also requires its API key scope)
+        //
+        // Layers run outermost first: auth, usage, rate limit, scope, then
+        // idempotency. Middleware only reads headers and extensions and hands
+        // the body on unread, so uploads stream to the handler as they arrive
+        // and one refused by any layer is never read. The exception is a POST
+        // sent with an `Idempotency-Key`, whose body is buffered and hashed
+        // once every other layer has let it through.
         let protected_routes = Router::new()
             // API Key Management
             .route("/api/keys", post(handlers::create_api_key::<R>))
@@ -56,44 +130,349 @@ impl<R: TransactionRepository> HttpServer<R> {
                 "/api/keys/{id}",
                 axum::routing::delete(handlers::delete_api_key::<R>),
             )
+            .route(
+                "/api/keys/{id}/usage",
+                get(handlers::get_api_key_usage::<R>),
+            )
+            .route(
+                "/api/keys/{id}/audit",
+                get(handlers::get_api_key_audit::<R>),
+            )
+            .route(
+                "/api/keys/{id}/rate-limit",
+                put(handlers::set_api_key_rate_limit::<R>),
+            )
+            .route(
+                SESSION_TOKENS_PATH,
+                post(handlers::create_session_token::<R>),
+            )
             // Account Management
             .route("/api/accounts", post(handlers::create_account::<R>))
             .route("/api/accounts", get(handlers::list_accounts::<R>))
-            .route("/api/accounts/{id}", get(handlers::get_account::<R>))
+            .route("/api/accounts/search", get(handlers::search_accounts::<R>))
+            .route(
+                "/api/accounts/{id}",
+                get(handlers::get_account::<R>).patch(handlers::update_account::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/aliases",
+                get(handlers::list_account_aliases::<R>).post(handlers::add_account_alias::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/aliases/{alias}",
+                axum::routing::delete(handlers::remove_account_alias::<R>),
+            )
+            .route(
+                "/api/aliases/{alias}",
+                get(handlers::get_account_alias::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/memo-keys",
+                get(handlers::list_memo_keys::<R>).post(handlers::add_memo_key::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/memo-keys/{key_id}",
+                get(handlers::get_memo_key::<R>).delete(handlers::retire_memo_key::<R>),
+            )
             .route(
                 "/api/accounts/{id}/transactions",
                 get(handlers::list_transactions::<R>),
             )
+            // Account owners
+            .route(
+                "/api/owners",
+                get(handlers::list_owners::<R>).post(handlers::create_owner::<R>),
+            )
+            .route(
+                "/api/owners/{id}",
+                get(handlers::get_owner::<R>)
+                    .patch(handlers::update_owner::<R>)
+                    .delete(handlers::delete_owner::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/spending-rules",
+                get(handlers::get_spending_rules::<R>).put(handlers::set_spending_rules::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/limits",
+                get(handlers::get_account_limits::<R>).put(handlers::set_account_limits::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/reactivate",
+                post(handlers::reactivate_account::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/freeze",
+                post(handlers::freeze_account::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/unfreeze",
+                post(handlers::unfreeze_account::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/close",
+                post(handlers::close_account::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/merge",
+                post(handlers::merge_account::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/fees",
+                get(handlers::get_account_fees::<R>).post(handlers::assign_fee_schedule::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/fees/quote",
+                get(handlers::quote_fee::<R>),
+            )
+            // Fee schedules
+            .route(
+                "/api/fee-schedules",
+                get(handlers::list_fee_schedules::<R>).post(handlers::create_fee_schedule::<R>),
+            )
+            .route(
+                "/api/fee-schedules/{id}",
+                get(handlers::get_fee_schedule::<R>),
+            )
+            .route(
+                "/api/settlement-batches",
+                get(handlers::list_settlement_batches::<R>)
+                    .post(handlers::create_settlement_batch::<R>),
+            )
+            .route(
+                "/api/settlement-batches/{id}",
+                get(handlers::get_settlement_batch::<R>),
+            )
+            .route(
+                "/api/settlement-batches/{id}/status",
+                post(handlers::update_settlement_batch_status::<R>),
+            )
+            .route(
+                "/api/settlement-batches/{id}/export",
+                get(handlers::export_settlement_batch::<R>),
+            )
+            .route("/api/exports/journal", get(handlers::export_journal::<R>))
+            .route("/api/exports/events", get(handlers::export_event_log::<R>))
+            .route("/api/exports", post(handlers::create_export::<R>))
+            .route("/api/exports/{id}", get(handlers::get_export::<R>))
+            .route("/api/reports/exposure", get(handlers::exposure_report::<R>))
+            .route("/api/reports/float", get(handlers::float_report::<R>))
+            // Scheduled payments
+            .route(
+                "/api/scheduled-payments",
+                get(handlers::list_scheduled_payments::<R>)
+                    .post(handlers::create_scheduled_payment::<R>),
+            )
+            .route(
+                "/api/scheduled-payments/{id}",
+                get(handlers::get_scheduled_payment::<R>),
+            )
+            .route(
+                "/api/scheduled-payments/{id}/pause",
+                post(handlers::pause_scheduled_payment::<R>),
+            )
+            .route(
+                "/api/scheduled-payments/{id}/resume",
+                post(handlers::resume_scheduled_payment::<R>),
+            )
+            // Holds
+            .route(
+                "/api/transactions/authorize",
+                post(handlers::authorize::<R>),
+            )
+            .route(
+                "/api/transactions/{id}/capture",
+                post(handlers::capture_hold::<R>),
+            )
+            .route(
+                "/api/transactions/{id}/void",
+                post(handlers::void_hold::<R>),
+            )
+            .route("/api/holds/{id}", get(handlers::get_hold::<R>))
+            .route("/api/accounts/{id}/holds", get(handlers::list_holds::<R>))
+            // Quotes
+            .route("/api/quotes", post(handlers::create_quote::<R>))
+            .route("/api/quotes/{id}", get(handlers::get_quote::<R>))
+            // Beneficiaries
+            .route(
+                "/api/beneficiaries",
+                post(handlers::create_beneficiary::<R>),
+            )
+            .route(
+                "/api/beneficiaries/{id}",
+                get(handlers::get_beneficiary::<R>),
+            )
+            .route(
+                "/api/beneficiaries/{id}/verify",
+                post(handlers::verify_beneficiary::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/beneficiaries",
+                get(handlers::list_beneficiaries::<R>),
+            )
+            // Statements
+            .route(
+                "/api/accounts/{id}/statement-email",
+                get(handlers::get_statement_email::<R>).put(handlers::set_statement_email::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/statements",
+                get(handlers::list_statements::<R>),
+            )
+            .route(
+                "/api/accounts/{id}/statement",
+                get(handlers::get_account_statement::<R>),
+            )
+            .route(
+                "/api/statements/issue",
+                post(handlers::issue_statements::<R>),
+            )
+            .route("/api/statements/{id}", get(handlers::get_statement::<R>))
+            // Balance history
+            .route(
+                "/api/accounts/{id}/balance-history",
+                get(handlers::get_balance_history::<R>),
+            )
+            .route(
+                "/api/admin/balance-snapshots",
+                post(handlers::record_balance_snapshots::<R>),
+            )
             // Transactions
             .route("/api/transactions/deposit", post(handlers::deposit::<R>))
             .route("/api/transactions/withdraw", post(handlers::withdraw::<R>))
             .route("/api/transactions/transfer", post(handlers::transfer::<R>))
+            .route("/api/transactions/batch", post(handlers::batch::<R>))
+            .route(
+                "/api/transactions/search",
+                get(handlers::search_transactions::<R>),
+            )
+            .route(
+                "/api/inbound-payments",
+                post(handlers::receive_inbound_payment::<R>),
+            )
             // Webhooks
             .route("/api/webhooks", post(handlers::register_webhook::<R>))
             .route("/api/webhooks", get(handlers::list_webhooks::<R>))
+            .route(
+                "/api/webhooks/batch",
+                post(handlers::register_webhooks::<R>),
+            )
+            .route("/api/webhooks/events", get(handlers::list_event_types))
+            .route(
+                "/api/webhooks/events/dead-letters",
+                get(handlers::list_dead_lettered_webhooks::<R>),
+            )
+            .route(
+                "/api/webhooks/events/retry",
+                post(handlers::retry_dead_lettered_webhooks::<R>),
+            )
+            .route(
+                "/api/webhooks/events/{id}",
+                get(handlers::get_webhook_event::<R>),
+            )
+            .route("/api/webhooks/{id}", patch(handlers::update_webhook::<R>))
+            .route(
+                "/api/webhooks/{id}",
+                axum::routing::delete(handlers::delete_webhook::<R>),
+            )
+            .route(
+                "/api/webhooks/{id}/rotate-secret",
+                post(handlers::rotate_webhook_secret::<R>),
+            )
+            .route(
+                "/api/webhooks/{id}/events",
+                get(handlers::list_webhook_events::<R>),
+            )
+            .route(
+                "/api/webhooks/{id}/deliveries",
+                get(handlers::list_webhook_deliveries::<R>),
+            )
+            .route(
+                "/api/webhooks/deliveries/{id}/redeliver",
+                post(handlers::redeliver_webhook_event::<R>),
+            )
+            // Live events
+            .route(STREAM_PATH, get(handlers::stream_events::<R>))
+            // Admin
+            .route(MAINTENANCE_PATH, get(handlers::get_maintenance::<R>))
+            .route(MAINTENANCE_PATH, put(handlers::set_maintenance::<R>))
+            .route(INCIDENT_PATH, put(handlers::set_incident::<R>))
+            .route(DIAGNOSTICS_PATH, get(handlers::diagnostics::<R>))
+            .route("/api/admin/storage", get(handlers::storage_report::<R>))
+            .route(METRICS_PATH, get(handlers::metrics::<R>))
+            .route("/api/admin/changes", get(handlers::list_changes::<R>))
+            .route("/api/admin/changes/{id}", get(handlers::get_change::<R>))
+            .route(
+                "/api/admin/changes/{id}/approve",
+                post(handlers::approve_change::<R>),
+            )
+            .route(
+                "/api/admin/changes/{id}/reject",
+                post(handlers::reject_change::<R>),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                self.state.clone(),
+                idempotency_middleware::<R>,
+            ))
+            .route_layer(middleware::from_fn(scope_middleware))
             .layer(middleware::from_fn_with_state(
-                self.rate_limiter.clone(),
+                self.state.rate_limiter.clone(),
                 rate_limit_middleware,
             ))
+            .layer(middleware::from_fn_with_state(
+                self.state.clone(),
+                usage_middleware::<R>,
+            ))
             .layer(middleware::from_fn_with_state(
                 self.state.clone(),
                 auth_middleware::<R>,
             ))
             .with_state(self.state.clone());
 
+        // Public status page (no auth, rate limited per caller address)
+        let status_routes = Router::new()
+            .route(STATUS_PATH, get(handlers::service_status::<R>))
+            .layer(middleware::from_fn_with_state(
+                self.status_limiter.clone(),
+                address_rate_limit_middleware,
+            ))
+            .with_state(self.state.clone());
+
         // Public routes (no auth required)
         Router::new()
             // OpenAPI documentation (no auth)
             .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
-            // Health endpoint (no auth)
+            // Liveness and readiness probes (no auth)
+            .route(LIVE_PATH, get(handlers::health))
             .route("/health", get(handlers::health))
+            .route(READY_PATH, get(handlers::ready::<R>))
+            .route(READYZ_PATH, get(handlers::ready::<R>))
             // Bootstrap endpoint (no auth - for creating first API key)
             .route("/api/bootstrap", post(handlers::bootstrap::<R>))
             // Exchange Rates (public - no auth required)
             .route("/api/rates/{base}", get(handlers::get_rates))
             .route("/api/convert", post(handlers::convert))
+            // Statement downloads (public - the signed link is the credential)
+            .route(
+                "/api/statements/{id}/download",
+                get(handlers::download_statement::<R>),
+            )
+            // Export downloads (public - the signed token is the credential)
+            .route(
+                "/api/downloads/{token}",
+                get(handlers::download_export::<R>),
+            )
+            .merge(status_routes)
             // Merge protected routes
             .merge(protected_routes)
+            .layer(middleware::from_fn_with_state(
+                self.state.maintenance.clone(),
+                maintenance_middleware,
+            ))
+            .layer(middleware::from_fn_with_state(
+                self.state.metrics.clone(),
+                metrics_middleware,
+            ))
             .layer(TraceLayer::new_for_http())
             .with_state(self.state.clone())
     }
@@ -105,7 +484,11 @@ impl<R: TransactionRepository> HttpServer<R> {
         tracing::info!("Server listening on {}", local_addr);
         tracing::info!("API Docs: http://{}/swagger-ui", local_addr);
 
-        axum::serve(listener, self.router())
+        // Peer addresses key the status page's rate limit.
+        let app = self
+            .router()
+            .into_make_service_with_connect_info::<SocketAddr>();
+        axum::serve(listener, app)
             .with_graceful_shutdown(shutdown_signal())
             .await?;
 
@@ -113,7 +496,7 @@ impl<R: TransactionRepository> HttpServer<R> {
     }
 }
 
-async fn shutdown_signal() {
+pub(super) async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
             .await
@@ -0,0 +1,73 @@
+//! Public status page.
+//!
+//! `GET /status` needs no API key and reports coarse health plus the incident
+//! banner admins post, so integrators can build status checks without
+//! spending their authenticated quota. Responses may be cached by a CDN for
+//! [`STATUS_MAX_AGE_SECS`], and callers are rate limited by address.
+
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use payments_types::Incident;
+
+/// Path of the public status endpoint.
+pub const STATUS_PATH: &str = "/status";
+
+/// Path of the admin endpoint that posts or clears the incident banner.
+pub const INCIDENT_PATH: &str = "/api/admin/incident";
+
+/// How long shared caches may serve a status response.
+pub const STATUS_MAX_AGE_SECS: u32 = 30;
+
+/// Status requests allowed per caller address each minute.
+pub const STATUS_REQUESTS_PER_MINUTE: u32 = 60;
+
+/// The incident banner shown on the status page.
+#[derive(Debug, Default)]
+pub struct IncidentBanner {
+    incident: RwLock<Option<Incident>>,
+}
+
+impl IncidentBanner {
+    /// Shows `message` from `now` on, or clears the banner when it is `None`
+    /// or blank.
+    pub fn set(&self, message: Option<String>, now: DateTime<Utc>) {
+        let incident = message
+            .map(|message| message.trim().to_string())
+            .filter(|message| !message.is_empty())
+            .map(|message| Incident {
+                message,
+                posted_at: now,
+            });
+        *self.incident.write().unwrap() = incident;
+    }
+
+    pub fn current(&self) -> Option<Incident> {
+        self.incident.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_message_clears_the_banner() {
+        let banner = IncidentBanner::default();
+        let now = Utc::now();
+        banner.set(Some("  Deposits delayed ".into()), now);
+        assert_eq!(
+            banner.current(),
+            Some(Incident {
+                message: "Deposits delayed".into(),
+                posted_at: now,
+            })
+        );
+
+        banner.set(Some("   ".into()), now);
+        assert_eq!(banner.current(), None);
+        banner.set(Some("Back".into()), now);
+        banner.set(None, now);
+        assert_eq!(banner.current(), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Live stream of domain events.
+//!
+//! `GET /api/stream` holds the response open as Server-Sent Events and sends
+//! every event the service announces from then on, named by its webhook event
+//! type with the webhook payload as data, so dashboards can update without
+//! polling. A stream only sends events in its key's mode, and keys scoped to
+//! an account only see events about that account. A
+//! stream that falls more than [`LIVE_EVENT_CAPACITY`] events behind is sent a
+//! `lagged` event with how many it missed, and carries on from the oldest
+//! event still buffered.
+//!
+//! [`LIVE_EVENT_CAPACITY`]: crate::events::LIVE_EVENT_CAPACITY
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::response::sse::Event;
+use futures_util::{Stream, stream};
+use payments_types::{AccountId, ActorContext, Mode, event_pattern_matches};
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+use crate::events::LiveEvent;
+
+/// Path of the live event stream.
+pub const STREAM_PATH: &str = "/api/stream";
+
+/// How often an idle stream sends a comment, so proxies keep it open.
+pub const STREAM_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Which events a stream sends.
+#[derive(Debug, Clone)]
+pub struct StreamFilter {
+    /// Only events in the key's mode
+    mode: Mode,
+    /// Only events about these accounts; `None` for admin keys
+    accounts: Option<Vec<AccountId>>,
+    /// Only events matching one of these patterns; empty for every event
+    patterns: Vec<String>,
+}
+
+impl StreamFilter {
+    /// Events `actor` may see that match one of `patterns`, or all of them
+    /// when `patterns` is empty.
+    pub fn new(actor: &ActorContext, patterns: Vec<String>) -> Self {
+        Self {
+            mode: actor.mode,
+            accounts: actor.visible_accounts(),
+            patterns,
+        }
+    }
+
+    pub fn matches(&self, LiveEvent { mode, event }: &LiveEvent) -> bool {
+        let visible = *mode == self.mode
+            && self.accounts.as_ref().is_none_or(|accounts| {
+                event
+                    .account_ids()
+                    .iter()
+                    .any(|account_id| accounts.contains(account_id))
+            });
+        let subscribed = self.patterns.is_empty()
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| event_pattern_matches(pattern, event.event_type()));
+        visible && subscribed
+    }
+}
+
+/// The events from `receiver` that pass `filter`, as SSE frames. Ends when
+/// the service is dropped.
+pub fn event_stream(
+    receiver: Receiver<LiveEvent>,
+    filter: StreamFilter,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold((receiver, filter), |(mut receiver, filter)| async move {
+        loop {
+            let frame = match receiver.recv().await {
+                Ok(live) if filter.matches(&live) => Event::default()
+                    .event(live.event.event_type())
+                    .data(live.event.payload().to_string()),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(missed)) => Event::default()
+                    .event("lagged")
+                    .data(serde_json::json!({ "missed": missed }).to_string()),
+                Err(RecvError::Closed) => return None,
+            };
+            return Some((Ok(frame), (receiver, filter)));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use payments_types::{Account, ApiKey, CurrencyCode, DomainEvent, DynMoney, Transaction};
+    use tokio::sync::broadcast;
+
+    use super::*;
+
+    fn key(account_id: Option<AccountId>) -> ActorContext {
+        ActorContext::from(&ApiKey::new("dashboard".into(), "hash".into(), account_id))
+    }
+
+    fn live(event: DomainEvent) -> LiveEvent {
+        LiveEvent {
+            mode: Mode::Live,
+            event,
+        }
+    }
+
+    fn deposit(account_id: AccountId) -> LiveEvent {
+        let money = DynMoney::new(500, CurrencyCode::USD).unwrap();
+        live(DomainEvent::FundsDeposited(Transaction::deposit(
+            account_id, money, None, None,
+        )))
+    }
+
+    #[test]
+    fn test_scoped_keys_only_see_their_account() {
+        let mine = AccountId::new();
+        let theirs = AccountId::new();
+        let transfer = live(DomainEvent::TransferCompleted(Transaction::transfer(
+            theirs,
+            mine,
+            DynMoney::new(500, CurrencyCode::USD).unwrap(),
+            None,
+            None,
+        )));
+
+        let scoped = StreamFilter::new(&key(Some(mine)), vec![]);
+        assert!(scoped.matches(&deposit(mine)));
+        assert!(scoped.matches(&transfer));
+        assert!(!scoped.matches(&deposit(theirs)));
+
+        let admin = StreamFilter::new(&key(None), vec![]);
+        assert!(admin.matches(&deposit(theirs)));
+    }
+
+    #[test]
+    fn test_patterns_narrow_the_events() {
+        let account = Account::new("Alice".into(), CurrencyCode::USD).unwrap();
+        let filter = StreamFilter::new(&key(None), vec!["deposit.*".into()]);
+        assert!(filter.matches(&deposit(account.id)));
+        assert!(!filter.matches(&live(DomainEvent::AccountCreated(account))));
+    }
+
+    #[test]
+    fn test_streams_only_see_their_mode() {
+        let account = AccountId::new();
+        let mut test_admin = key(None);
+        test_admin.mode = Mode::Test;
+        let test_deposit = LiveEvent {
+            mode: Mode::Test,
+            ..deposit(account)
+        };
+
+        let test_stream = StreamFilter::new(&test_admin, vec![]);
+        assert!(test_stream.matches(&test_deposit));
+        assert!(!test_stream.matches(&deposit(account)));
+
+        let live_stream = StreamFilter::new(&key(None), vec![]);
+        assert!(live_stream.matches(&deposit(account)));
+        assert!(!live_stream.matches(&test_deposit));
+    }
+
+    #[tokio::test]
+    async fn test_stream_skips_filtered_events_and_reports_lag() {
+        let mine = AccountId::new();
+        let (sender, receiver) = broadcast::channel(2);
+        let mut events = Box::pin(event_stream(
+            receiver,
+            StreamFilter::new(&key(Some(mine)), vec![]),
+        ));
+
+        sender.send(deposit(AccountId::new())).unwrap();
+        sender.send(deposit(mine)).unwrap();
+        let frame = format!("{:?}", events.next().await.unwrap().unwrap());
+        assert!(frame.contains("deposit.success"), "{}", frame);
+
+        for _ in 0..3 {
+            sender.send(deposit(mine)).unwrap();
+        }
+        let frame = format!("{:?}", events.next().await.unwrap().unwrap());
+        assert!(frame.contains("lagged"), "{}", frame);
+
+        drop(sender);
+        assert!(events.next().await.is_some());
+        assert!(events.next().await.is_some());
+        assert!(events.next().await.is_none());
+    }
+}
@@ -0,0 +1,37 @@
+//! Usage metering middleware.
+//!
+//! Counts every authenticated request against its API key, including those
+//! the rate limiter rejects, for `GET /api/keys/{id}/usage`.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+
+use payments_types::{ActorContext, TransactionRepository};
+
+use super::handlers::AppState;
+
+/// Records one request for the calling key once the response is ready.
+/// Runs inside auth middleware, which attaches the key.
+pub async fn usage_middleware<R: TransactionRepository>(
+    State(state): State<Arc<AppState<R>>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let api_key_id = request
+        .extensions()
+        .get::<ActorContext>()
+        .map(|actor| actor.api_key_id);
+    let response = next.run(request).await;
+    if let Some(id) = api_key_id {
+        let rate_limited = response.status() == StatusCode::TOO_MANY_REQUESTS;
+        state.service.record_api_request(id, rate_limited).await;
+    }
+    response
+}
@@ -0,0 +1,62 @@
+//! Daily balance snapshots.
+//!
+//! Every `interval`, the recorder stores each account's closing balance for
+//! the UTC days that have ended since its last recorded one, backfilling
+//! accounts that have none from the day they were opened. Running it more
+//! often than daily only shortens how long after midnight yesterday's
+//! balances appear. While maintenance mode is on nothing is recorded.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use payments_types::TransactionRepository;
+use tokio::task::JoinHandle;
+
+use crate::PaymentService;
+use crate::inbound::MaintenanceMode;
+
+/// Periodically records end-of-day account balances.
+pub struct BalanceRecorder<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    interval: Duration,
+    maintenance: Option<Arc<MaintenanceMode>>,
+}
+
+impl<R: TransactionRepository> BalanceRecorder<R> {
+    pub fn new(service: Arc<PaymentService<R>>, interval: Duration) -> Self {
+        Self {
+            service,
+            interval,
+            maintenance: None,
+        }
+    }
+
+    /// Skips runs while `maintenance` mode is enabled.
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceMode>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    /// Runs the snapshot loop on a background task until it is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if self.maintenance.as_ref().is_some_and(|m| m.is_enabled()) {
+                    continue;
+                }
+                match self.service.record_daily_balances().await {
+                    Ok(0) => {}
+                    Ok(recorded) => {
+                        tracing::info!(target: "balances", recorded, "Recorded daily balances")
+                    }
+                    Err(e) => {
+                        tracing::warn!(target: "balances", "Balance snapshot failed: {}", e)
+                    }
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,63 @@
+//! Dormant account detection.
+//!
+//! Every `interval`, the detector flags active accounts that have had no
+//! transactions for the service's [`DormancyPolicy`](crate::DormancyPolicy)
+//! idle period, emitting `account.dormant` for each. Several instances can
+//! run it at once: an account is only flagged, and the event only emitted,
+//! by whichever gets to it first. While maintenance mode is on nothing is
+//! flagged.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use payments_types::TransactionRepository;
+use tokio::task::JoinHandle;
+
+use crate::PaymentService;
+use crate::inbound::MaintenanceMode;
+
+/// Periodically flags idle accounts as dormant.
+pub struct DormancyDetector<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    interval: Duration,
+    maintenance: Option<Arc<MaintenanceMode>>,
+}
+
+impl<R: TransactionRepository> DormancyDetector<R> {
+    pub fn new(service: Arc<PaymentService<R>>, interval: Duration) -> Self {
+        Self {
+            service,
+            interval,
+            maintenance: None,
+        }
+    }
+
+    /// Skips runs while `maintenance` mode is enabled.
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceMode>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    /// Runs the detection loop on a background task until it is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if self.maintenance.as_ref().is_some_and(|m| m.is_enabled()) {
+                    continue;
+                }
+                match self.service.flag_dormant_accounts().await {
+                    Ok(0) => {}
+                    Ok(flagged) => {
+                        tracing::info!(target: "dormancy", flagged, "Flagged dormant accounts")
+                    }
+                    Err(e) => {
+                        tracing::warn!(target: "dormancy", "Dormancy run failed: {}", e)
+                    }
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,64 @@
+//! Hold expiry.
+//!
+//! Every `interval`, the expirer releases authorized holds whose
+//! `expires_at` has passed, returning their funds to the available balance.
+//! A hold stays capturable until its expiry time even if it has not been
+//! released yet, so the interval only decides how long expired funds stay
+//! reserved. Several instances can run the expirer at once: a hold is only
+//! released, and `hold.expired` only emitted, by whichever gets to it first.
+//! While maintenance mode is on nothing is released.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use payments_types::TransactionRepository;
+use tokio::task::JoinHandle;
+
+use crate::PaymentService;
+use crate::inbound::MaintenanceMode;
+
+/// Periodically releases expired holds.
+pub struct HoldExpirer<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    interval: Duration,
+    maintenance: Option<Arc<MaintenanceMode>>,
+}
+
+impl<R: TransactionRepository> HoldExpirer<R> {
+    pub fn new(service: Arc<PaymentService<R>>, interval: Duration) -> Self {
+        Self {
+            service,
+            interval,
+            maintenance: None,
+        }
+    }
+
+    /// Skips runs while `maintenance` mode is enabled.
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceMode>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    /// Runs the expiry loop on a background task until it is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if self.maintenance.as_ref().is_some_and(|m| m.is_enabled()) {
+                    continue;
+                }
+                match self.service.expire_holds().await {
+                    Ok(0) => {}
+                    Ok(expired) => {
+                        tracing::info!(target: "hold_expiry", expired, "Released expired holds")
+                    }
+                    Err(e) => {
+                        tracing::warn!(target: "hold_expiry", "Hold expiry run failed: {}", e)
+                    }
+                }
+            }
+        })
+    }
+}
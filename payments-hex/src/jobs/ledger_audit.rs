@@ -0,0 +1,341 @@
+//! Ledger audit: an always-on canary for balance corruption.
+//!
+//! Every `interval`, the auditor samples up to `sample_size` random accounts,
+//! recomputes each balance from the account's transaction history and compares
+//! it with the stored balance. It also checks that customer balances and the
+//! float cancel out in every currency (see [`FloatPosition`]). A mismatch or
+//! an unbalanced currency is logged at `error` level under the `ledger_audit`
+//! target (alert on it) and counted in [`LedgerAuditStats`].
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use payments_types::{AccountId, FloatPosition, RepoError, Transaction, TransactionRepository};
+use rand::seq::IndexedRandom;
+use tokio::task::JoinHandle;
+
+/// How often and how widely the auditor samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerAuditConfig {
+    pub interval: Duration,
+    pub sample_size: usize,
+}
+
+impl Default for LedgerAuditConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(300),
+            sample_size: 20,
+        }
+    }
+}
+
+/// An account whose stored balance disagrees with its history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerMismatch {
+    pub account_id: AccountId,
+    pub stored: i64,
+    pub recomputed: i64,
+}
+
+/// Result of one audit pass.
+#[derive(Debug, Default)]
+pub struct AuditReport {
+    /// Accounts whose balance was compared.
+    pub checked: usize,
+    /// Accounts that moved while being checked; they are retried next pass.
+    pub skipped: usize,
+    pub mismatches: Vec<LedgerMismatch>,
+    /// Currencies whose customer balances and float do not sum to zero.
+    pub unbalanced: Vec<FloatPosition>,
+}
+
+/// Running totals across audit passes.
+#[derive(Debug, Default)]
+pub struct LedgerAuditStats {
+    runs: AtomicU64,
+    failed_runs: AtomicU64,
+    accounts_checked: AtomicU64,
+    mismatches: AtomicU64,
+    unbalanced_currencies: AtomicU64,
+}
+
+impl LedgerAuditStats {
+    pub fn runs(&self) -> u64 {
+        self.runs.load(Ordering::Relaxed)
+    }
+
+    /// Passes that could not complete because the repository failed.
+    pub fn failed_runs(&self) -> u64 {
+        self.failed_runs.load(Ordering::Relaxed)
+    }
+
+    pub fn accounts_checked(&self) -> u64 {
+        self.accounts_checked.load(Ordering::Relaxed)
+    }
+
+    pub fn mismatches(&self) -> u64 {
+        self.mismatches.load(Ordering::Relaxed)
+    }
+
+    /// Unbalanced currencies found, summed over passes.
+    pub fn unbalanced_currencies(&self) -> u64 {
+        self.unbalanced_currencies.load(Ordering::Relaxed)
+    }
+}
+
+/// Periodically checks that sampled balances match their transaction history.
+pub struct LedgerAuditor<R: TransactionRepository> {
+    repo: R,
+    config: LedgerAuditConfig,
+    stats: Arc<LedgerAuditStats>,
+}
+
+impl<R: TransactionRepository> LedgerAuditor<R> {
+    pub fn new(repo: R, config: LedgerAuditConfig) -> Self {
+        Self {
+            repo,
+            config,
+            stats: Arc::new(LedgerAuditStats::default()),
+        }
+    }
+
+    /// Returns a handle to the auditor's counters.
+    pub fn stats(&self) -> Arc<LedgerAuditStats> {
+        self.stats.clone()
+    }
+
+    /// Runs the audit loop on a background task until it is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.config.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run_once().await {
+                    tracing::warn!(target: "ledger_audit", "Ledger audit pass failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Runs a single audit pass and records it in the stats.
+    pub async fn run_once(&self) -> Result<AuditReport, RepoError> {
+        self.stats.runs.fetch_add(1, Ordering::Relaxed);
+        let report = match self.audit_sample().await {
+            Ok(report) => report,
+            Err(e) => {
+                self.stats.failed_runs.fetch_add(1, Ordering::Relaxed);
+                return Err(e);
+            }
+        };
+
+        self.stats
+            .accounts_checked
+            .fetch_add(report.checked as u64, Ordering::Relaxed);
+        self.stats
+            .mismatches
+            .fetch_add(report.mismatches.len() as u64, Ordering::Relaxed);
+        self.stats
+            .unbalanced_currencies
+            .fetch_add(report.unbalanced.len() as u64, Ordering::Relaxed);
+
+        for mismatch in &report.mismatches {
+            tracing::error!(
+                target: "ledger_audit",
+                account_id = %mismatch.account_id,
+                stored = mismatch.stored,
+                recomputed = mismatch.recomputed,
+                "Ledger mismatch: stored balance disagrees with transaction history"
+            );
+        }
+        for position in &report.unbalanced {
+            tracing::error!(
+                target: "ledger_audit",
+                currency = %position.currency,
+                customer_balance = position.customer_balance,
+                float_balance = position.float_balance,
+                "Ledger imbalance: customer balances and the float do not sum to zero"
+            );
+        }
+        tracing::debug!(
+            target: "ledger_audit",
+            checked = report.checked,
+            skipped = report.skipped,
+            "Ledger audit pass complete"
+        );
+
+        Ok(report)
+    }
+
+    async fn audit_sample(&self) -> Result<AuditReport, RepoError> {
+        let accounts = self.repo.list_accounts(None).await?;
+        let sample: Vec<AccountId> = accounts
+            .choose_multiple(&mut rand::rng(), self.config.sample_size)
+            .map(|account| account.id)
+            .collect();
+
+        let mut report = AuditReport::default();
+        for account_id in sample {
+            // Read the balance on both sides of the history so a transaction
+            // committing mid-check is not mistaken for corruption.
+            let Some(before) = self.repo.get_account(account_id).await? else {
+                continue;
+            };
+            let history = self.repo.list_transactions_for_account(account_id).await?;
+            let Some(after) = self.repo.get_account(account_id).await? else {
+                continue;
+            };
+            if before.balance.amount() != after.balance.amount() {
+                report.skipped += 1;
+                continue;
+            }
+
+            report.checked += 1;
+            let recomputed = recompute_balance(account_id, &history);
+            if recomputed != after.balance.amount() {
+                report.mismatches.push(LedgerMismatch {
+                    account_id,
+                    stored: after.balance.amount(),
+                    recomputed,
+                });
+            }
+        }
+
+        // One consistent read of both modes, so in-flight payments cannot
+        // skew it.
+        report.unbalanced = self
+            .repo
+            .float_positions(None)
+            .await?
+            .into_iter()
+            .filter(|position| !position.is_balanced())
+            .collect();
+
+        Ok(report)
+    }
+}
+
+/// Sums the signed effect of `history` on `account_id`.
+fn recompute_balance(account_id: AccountId, history: &[Transaction]) -> i64 {
+    history
+        .iter()
+        .map(|tx| tx.signed_amount_for(account_id))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use payments_types::{
+        CreateAccountRequest, CurrencyCode, DepositRequest, OwnerId, PositiveAmount,
+        TransferRequest,
+    };
+
+    use super::*;
+    use crate::service_tests::tests::MockRepo;
+
+    async fn seeded_repo() -> (Arc<MockRepo>, AccountId, AccountId) {
+        let repo = Arc::new(MockRepo::new());
+        let mut ids = Vec::new();
+        for name in ["Alice", "Bob"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.into(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        repo.deposit(DepositRequest {
+            account_id: ids[0],
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        repo.transfer(TransferRequest {
+            from_account_id: ids[0],
+            to_account_id: ids[1],
+            amount: PositiveAmount::new(300).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        (repo, ids[0], ids[1])
+    }
+
+    #[tokio::test]
+    async fn test_consistent_ledger_passes() {
+        let (repo, _, _) = seeded_repo().await;
+        let auditor = LedgerAuditor::new(repo, LedgerAuditConfig::default());
+
+        let report = auditor.run_once().await.unwrap();
+
+        assert_eq!(report.checked, 2);
+        assert!(report.mismatches.is_empty());
+        assert!(report.unbalanced.is_empty());
+        assert_eq!(auditor.stats().runs(), 1);
+        assert_eq!(auditor.stats().mismatches(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_balance_is_reported() {
+        let (repo, alice, _) = seeded_repo().await;
+        repo.set_balance(alice, 9999);
+        let auditor = LedgerAuditor::new(repo, LedgerAuditConfig::default());
+
+        let report = auditor.run_once().await.unwrap();
+
+        assert_eq!(
+            report.mismatches,
+            vec![LedgerMismatch {
+                account_id: alice,
+                stored: 9999,
+                recomputed: 700,
+            }]
+        );
+        assert_eq!(auditor.stats().mismatches(), 1);
+        // The 9299 that appeared on Alice has no counter-entry in the float.
+        assert_eq!(
+            report.unbalanced,
+            vec![FloatPosition {
+                currency: CurrencyCode::USD,
+                customer_balance: 10299,
+                float_balance: -1000,
+            }]
+        );
+        assert_eq!(auditor.stats().unbalanced_currencies(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sample_size_bounds_the_pass() {
+        let (repo, _, _) = seeded_repo().await;
+        let config = LedgerAuditConfig {
+            sample_size: 1,
+            ..LedgerAuditConfig::default()
+        };
+        let auditor = LedgerAuditor::new(repo, config);
+
+        assert_eq!(auditor.run_once().await.unwrap().checked, 1);
+        assert_eq!(auditor.stats().accounts_checked(), 1);
+    }
+}
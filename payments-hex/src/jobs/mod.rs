@@ -0,0 +1,21 @@
+//! Background jobs that run alongside the HTTP server.
+
+pub mod balances;
+pub mod dormancy;
+pub mod hold_expiry;
+pub mod ledger_audit;
+pub mod quotas;
+pub mod rates;
+pub mod scheduled_payments;
+pub mod statements;
+
+pub use balances::BalanceRecorder;
+pub use dormancy::DormancyDetector;
+pub use hold_expiry::HoldExpirer;
+pub use ledger_audit::{
+    AuditReport, LedgerAuditConfig, LedgerAuditStats, LedgerAuditor, LedgerMismatch,
+};
+pub use quotas::QuotaMonitor;
+pub use rates::RateRecorder;
+pub use scheduled_payments::PaymentScheduler;
+pub use statements::StatementScheduler;
@@ -0,0 +1,40 @@
+//! Storage quota monitoring.
+//!
+//! Every `interval`, the monitor counts the rows of each tracked table and
+//! emits `quota.warning` for those that have reached the service's
+//! [`StorageQuotas`](crate::StorageQuotas) warning threshold. Counting is
+//! read-only, so it keeps running in maintenance mode.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use payments_types::TransactionRepository;
+use tokio::task::JoinHandle;
+
+use crate::PaymentService;
+
+/// Periodically checks storage growth against soft quotas.
+pub struct QuotaMonitor<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    interval: Duration,
+}
+
+impl<R: TransactionRepository> QuotaMonitor<R> {
+    pub fn new(service: Arc<PaymentService<R>>, interval: Duration) -> Self {
+        Self { service, interval }
+    }
+
+    /// Runs the check loop on a background task until it is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.service.check_storage_quotas().await {
+                    tracing::warn!(target: "quotas", "Storage quota check failed: {}", e)
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,40 @@
+//! Exchange rate snapshots.
+//!
+//! Every `interval`, the recorder stores the current exchange rates so that
+//! exposure reports can later be rebuilt as of a past time. Reports as of a
+//! time use the latest snapshot taken at or before it, so the interval bounds
+//! how stale those rates can be.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use payments_types::TransactionRepository;
+use tokio::task::JoinHandle;
+
+use crate::PaymentService;
+
+/// Periodically records current exchange rates.
+pub struct RateRecorder<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    interval: Duration,
+}
+
+impl<R: TransactionRepository> RateRecorder<R> {
+    pub fn new(service: Arc<PaymentService<R>>, interval: Duration) -> Self {
+        Self { service, interval }
+    }
+
+    /// Runs the snapshot loop on a background task until it is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.service.record_exchange_rates().await {
+                    tracing::warn!(target: "rates", "Rate snapshot failed: {}", e);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,64 @@
+//! Scheduled payment runs.
+//!
+//! Every `interval`, the scheduler makes the scheduled payments that have
+//! come due. A payment therefore runs up to one interval after its due time,
+//! so the interval should be well below the shortest schedule (60 seconds).
+//! Several instances can run the scheduler at once: each run is keyed by the
+//! payment and its due time, so it is paid and announced only once. While
+//! maintenance mode is on no payments are made; runs that fall due in the
+//! meantime are made once it is switched off.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use payments_types::TransactionRepository;
+use tokio::task::JoinHandle;
+
+use crate::PaymentService;
+use crate::inbound::MaintenanceMode;
+
+/// Periodically makes due scheduled payments.
+pub struct PaymentScheduler<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    interval: Duration,
+    maintenance: Option<Arc<MaintenanceMode>>,
+}
+
+impl<R: TransactionRepository> PaymentScheduler<R> {
+    pub fn new(service: Arc<PaymentService<R>>, interval: Duration) -> Self {
+        Self {
+            service,
+            interval,
+            maintenance: None,
+        }
+    }
+
+    /// Skips runs while `maintenance` mode is enabled.
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceMode>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    /// Runs the scheduler loop on a background task until it is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if self.maintenance.as_ref().is_some_and(|m| m.is_enabled()) {
+                    continue;
+                }
+                match self.service.run_due_scheduled_payments().await {
+                    Ok(0) => {}
+                    Ok(ran) => {
+                        tracing::info!(target: "scheduled_payments", ran, "Ran scheduled payments")
+                    }
+                    Err(e) => {
+                        tracing::warn!(target: "scheduled_payments", "Scheduled payment run failed: {}", e)
+                    }
+                }
+            }
+        })
+    }
+}
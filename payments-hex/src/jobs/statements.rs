@@ -0,0 +1,46 @@
+//! Monthly statement run.
+//!
+//! Every `interval`, the scheduler asks the service to issue statements for
+//! the last full month. Issuing is idempotent per account and month, so the
+//! interval only bounds how soon after the month ends statements go out; an
+//! hourly tick sends them within an hour of midnight UTC on the 1st.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use payments_types::TransactionRepository;
+use tokio::task::JoinHandle;
+
+use crate::PaymentService;
+
+/// Periodically issues the previous month's statements.
+pub struct StatementScheduler<R: TransactionRepository> {
+    service: Arc<PaymentService<R>>,
+    interval: Duration,
+}
+
+impl<R: TransactionRepository> StatementScheduler<R> {
+    pub fn new(service: Arc<PaymentService<R>>, interval: Duration) -> Self {
+        Self { service, interval }
+    }
+
+    /// Runs the statement loop on a background task until it is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                match self.service.issue_monthly_statements().await {
+                    Ok(issued) if !issued.is_empty() => {
+                        tracing::info!(target: "statements", count = issued.len(), "Issued statements")
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!(target: "statements", "Statement run failed: {}", e)
+                    }
+                }
+            }
+        })
+    }
+}
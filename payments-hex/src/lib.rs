@@ -5,17 +5,77 @@
 //! ## Architecture
 //!
 //! - `service/` - Application service (orchestrates domain operations)
-//! - `inbound/` - HTTP adapter (Axum server)
+//! - `inbound/` - HTTP adapter (Axum server) and, with the `grpc` feature, a
+//!   gRPC adapter (tonic server)
+//! - `events` - Domain event publishers
+//! - `jobs/` - Background tasks (ledger audit, monthly statements, scheduled payments,
+//!   dormant accounts, daily balance snapshots, storage quotas)
+//! - `limits` - Global amount, balance, daily debit and hourly transaction caps
+//! - `metrics` - In-process metrics registry exported in Prometheus format
+//! - `dormancy` - When idle accounts become dormant and what that blocks
+//! - `quotas` - Soft row limits that raise `quota.warning` as storage grows
+//! - `settlement` - Settlement batch exports (CSV, pain.001)
+//! - `accounting` - Journal-entry exports (QuickBooks, Xero)
+//! - `downloads` - Signed, short-lived download links for exports
+//! - `statements` - Monthly statement files and signed download links
+//! - `warnings` - Built-in rules that flag unusual payments without blocking them
+//! - `risk` - Built-in risk checks that hold or deny payments before money moves
+//! - `payouts` - Built-in payout providers (simulated rails, stub bank)
+//! - `webhooks` - Cached list of the webhook endpoints payments announce to
+//! - `smtp` - SMTP notifier (`smtp` feature)
+//! - `redis_limits` - Rate limit store shared by replicas through Redis (`redis` feature)
+//! - `redis_idempotency` - Idempotent responses kept in Redis (`redis` feature)
 //!
 //! The service is generic over `R: TransactionRepository`, allowing
 //! different repository implementations to be injected.
 
+pub mod accounting;
+pub mod dormancy;
+pub mod downloads;
+pub mod events;
 pub mod inbound;
+pub mod jobs;
+pub mod limits;
+pub mod metrics;
 pub mod openapi;
+pub mod payouts;
+pub mod quotas;
+#[cfg(feature = "redis")]
+pub mod redis_idempotency;
+#[cfg(feature = "redis")]
+pub mod redis_limits;
+pub mod risk;
 pub mod service;
+pub mod sessions;
+pub mod settlement;
+#[cfg(feature = "smtp")]
+pub mod smtp;
+pub mod statements;
+pub mod warnings;
+pub mod webhooks;
 
 #[cfg(test)]
 mod service_tests;
 
+pub use accounting::{GlAccountCodes, GlMapping};
+pub use dormancy::DormancyPolicy;
+pub use downloads::DownloadLinks;
+pub use events::{BroadcastPublisher, LiveEvent};
+pub use limits::AmountLimits;
+pub use metrics::MetricsRegistry;
 pub use openapi::ApiDoc;
-pub use service::PaymentService;
+pub use payouts::{SimulatedPayouts, StubBankPayouts};
+pub use quotas::StorageQuotas;
+#[cfg(feature = "redis")]
+pub use redis_idempotency::RedisIdempotencyStore;
+#[cfg(feature = "redis")]
+pub use redis_limits::RedisRateLimits;
+pub use risk::{AllowAll, RiskRules};
+pub use service::{BatchOutcome, PaymentService, PaymentServiceBuilder};
+pub use sessions::SessionTokens;
+pub use settlement::SettlementDebtor;
+#[cfg(feature = "smtp")]
+pub use smtp::SmtpNotifier;
+pub use statements::StatementLinks;
+pub use warnings::{DormantAccountRule, LargeAmountRule};
+pub use webhooks::{WEBHOOK_ENDPOINT_CACHE_TTL, WebhookEndpointCache};
@@ -0,0 +1,264 @@
+//! Global sanity caps on amounts, balances and activity.
+//!
+//! Caps are in minor units and apply to every currency alike, unless set for
+//! one currency. They guard against fat-finger requests (an `i64`-scale
+//! deposit) and runaway clients; per-customer limits live in
+//! [`AccountLimits`] and can only tighten them. A payment refused by a cap is
+//! also announced as a `transaction.blocked` event.
+
+use std::collections::HashMap;
+
+use payments_types::{AccountLimits, AppError, CurrencyCode};
+
+/// Upper bounds enforced by `PaymentService` before touching the repository.
+///
+/// Every cap is disabled by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AmountLimits {
+    /// Largest amount a single deposit, withdrawal or transfer may move.
+    pub max_transaction_amount: Option<i64>,
+    /// Largest amount a single payment in one currency may move, where lower
+    /// than `max_transaction_amount`.
+    pub max_amount_per_currency: HashMap<CurrencyCode, i64>,
+    /// Largest balance a deposit or incoming transfer may leave an account with.
+    pub max_account_balance: Option<i64>,
+    /// Most any account may send out (withdrawals, transfers and holds) in
+    /// 24 hours.
+    pub daily_debit_limit: Option<i64>,
+    /// Most transactions an account may take part in over any hour. A deposit
+    /// counts against the receiving account, other payments against the
+    /// sending one.
+    pub max_transactions_per_hour: Option<i64>,
+}
+
+impl AmountLimits {
+    /// Returns these caps narrowed by an account's own limits.
+    ///
+    /// For each cap the lower of the two wins; an unset value on either side
+    /// defers to the other.
+    pub fn tightened(&self, account: &AccountLimits) -> Self {
+        let min = |a: Option<i64>, b: Option<i64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        Self {
+            max_transaction_amount: min(
+                self.max_transaction_amount,
+                account.max_transaction_amount,
+            ),
+            max_amount_per_currency: self.max_amount_per_currency.clone(),
+            max_account_balance: min(self.max_account_balance, account.max_balance),
+            daily_debit_limit: min(self.daily_debit_limit, account.daily_debit_limit),
+            max_transactions_per_hour: self.max_transactions_per_hour,
+        }
+    }
+
+    /// Rejects `amount` of `currency` if it is above `max_transaction_amount`
+    /// or the currency's own cap.
+    pub fn check_amount(&self, amount: i64, currency: CurrencyCode) -> Result<(), AppError> {
+        let max = [
+            self.max_transaction_amount,
+            self.max_amount_per_currency.get(&currency).copied(),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        match max {
+            Some(max) if amount > max => Err(AppError::AmountLimitExceeded { amount, max }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects crediting `amount` to an account holding `balance` if the
+    /// result would be above `max_account_balance`.
+    pub fn check_credit(&self, balance: i64, amount: i64) -> Result<(), AppError> {
+        let Some(max) = self.max_account_balance else {
+            return Ok(());
+        };
+        let resulting = balance.saturating_add(amount);
+        if resulting > max {
+            return Err(AppError::BalanceLimitExceeded {
+                balance: resulting,
+                max,
+            });
+        }
+        Ok(())
+    }
+
+    /// Rejects debiting `amount` when `spent` has already left the account
+    /// in the last 24 hours and the total would pass `daily_debit_limit`.
+    pub fn check_daily_debit(&self, spent: i64, amount: i64) -> Result<(), AppError> {
+        match self.daily_debit_limit {
+            Some(max) if spent.saturating_add(amount) > max => {
+                Err(AppError::DailyDebitLimitExceeded {
+                    spent,
+                    requested: amount,
+                    max,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects one more transaction on an account that took part in `count`
+    /// over the last hour, if that would pass `max_transactions_per_hour`.
+    pub fn check_hourly_transactions(&self, count: i64) -> Result<(), AppError> {
+        match self.max_transactions_per_hour {
+            Some(max) if count.saturating_add(1) > max => {
+                Err(AppError::HourlyTransactionLimitExceeded { count, max })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parses per-currency caps such as `USD:1000000,EUR:900000`.
+pub fn parse_max_amounts(spec: &str) -> Result<HashMap<CurrencyCode, i64>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (currency, max) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid cap '{}': expected CURRENCY:amount", entry))?;
+            let currency = currency.trim().parse::<CurrencyCode>()?;
+            let max = max
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .filter(|max| *max > 0)
+                .ok_or_else(|| format!("Invalid cap '{}': amount must be positive", entry))?;
+            Ok((currency, max))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_limits_accept_anything() {
+        let limits = AmountLimits::default();
+        assert!(limits.check_amount(i64::MAX, CurrencyCode::USD).is_ok());
+        assert!(limits.check_credit(i64::MAX, i64::MAX).is_ok());
+        assert!(limits.check_daily_debit(i64::MAX, 1).is_ok());
+        assert!(limits.check_hourly_transactions(i64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_caps_are_inclusive() {
+        let limits = AmountLimits {
+            max_transaction_amount: Some(1_000),
+            max_account_balance: Some(5_000),
+            daily_debit_limit: Some(1_000),
+            max_transactions_per_hour: Some(2),
+            ..AmountLimits::default()
+        };
+
+        assert!(limits.check_amount(1_000, CurrencyCode::USD).is_ok());
+        assert!(matches!(
+            limits.check_amount(1_001, CurrencyCode::USD),
+            Err(AppError::AmountLimitExceeded {
+                amount: 1_001,
+                max: 1_000
+            })
+        ));
+
+        assert!(limits.check_credit(4_000, 1_000).is_ok());
+        assert!(matches!(
+            limits.check_credit(4_000, 1_001),
+            Err(AppError::BalanceLimitExceeded {
+                balance: 5_001,
+                max: 5_000
+            })
+        ));
+
+        assert!(limits.check_daily_debit(600, 400).is_ok());
+        assert!(matches!(
+            limits.check_daily_debit(600, 401),
+            Err(AppError::DailyDebitLimitExceeded {
+                spent: 600,
+                requested: 401,
+                max: 1_000
+            })
+        ));
+
+        assert!(limits.check_hourly_transactions(1).is_ok());
+        assert!(matches!(
+            limits.check_hourly_transactions(2),
+            Err(AppError::HourlyTransactionLimitExceeded { count: 2, max: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_currency_caps_apply_to_their_currency_only() {
+        let limits = AmountLimits {
+            max_transaction_amount: Some(5_000),
+            max_amount_per_currency: HashMap::from([
+                (CurrencyCode::USD, 1_000),
+                (CurrencyCode::GBP, 90_000),
+            ]),
+            ..AmountLimits::default()
+        };
+
+        assert!(matches!(
+            limits.check_amount(1_001, CurrencyCode::USD),
+            Err(AppError::AmountLimitExceeded { max: 1_000, .. })
+        ));
+        assert!(limits.check_amount(5_000, CurrencyCode::EUR).is_ok());
+        // The lower of the two caps wins
+        assert!(matches!(
+            limits.check_amount(5_001, CurrencyCode::GBP),
+            Err(AppError::AmountLimitExceeded { max: 5_000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_account_limits_only_tighten() {
+        let global = AmountLimits {
+            max_transaction_amount: Some(1_000),
+            daily_debit_limit: Some(50_000),
+            max_transactions_per_hour: Some(20),
+            ..AmountLimits::default()
+        };
+        let account = AccountLimits {
+            max_transaction_amount: Some(5_000),
+            max_balance: Some(20_000),
+            daily_debit_limit: Some(10_000),
+        };
+
+        assert_eq!(
+            global.tightened(&account),
+            AmountLimits {
+                max_transaction_amount: Some(1_000),
+                max_account_balance: Some(20_000),
+                daily_debit_limit: Some(10_000),
+                max_transactions_per_hour: Some(20),
+                ..AmountLimits::default()
+            }
+        );
+        assert_eq!(global.tightened(&AccountLimits::default()), global);
+    }
+
+    #[test]
+    fn test_credit_overflow_is_rejected() {
+        let limits = AmountLimits {
+            max_account_balance: Some(i64::MAX - 1),
+            ..AmountLimits::default()
+        };
+        assert!(limits.check_credit(i64::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_max_amounts() {
+        assert_eq!(
+            parse_max_amounts("USD:1000, eur:900").unwrap(),
+            HashMap::from([(CurrencyCode::USD, 1_000), (CurrencyCode::EUR, 900)])
+        );
+        assert!(parse_max_amounts("").unwrap().is_empty());
+        assert!(parse_max_amounts("USD").is_err());
+        assert!(parse_max_amounts("USD:0").is_err());
+        assert!(parse_max_amounts("XYZ:100").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! In-process metrics registry with a Prometheus text exporter.
+//!
+//! The service, the HTTP layer and the webhook worker record into one
+//! [`MetricsRegistry`] through the [`Metrics`] port; `GET /metrics` renders
+//! it in the Prometheus exposition format. Values live in memory, so each
+//! instance reports its own counts from when it started.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use payments_types::ports::metrics::{
+    CONVERSIONS_TOTAL, HTTP_REQUEST_DURATION_SECONDS, HTTP_REQUEST_REPO_CALLS_TOTAL,
+    HTTP_REQUESTS_TOTAL, QUOTES_EXPIRED_TOTAL, QUOTES_ISSUED_TOTAL, TRANSACTION_VOLUME_TOTAL,
+    TRANSACTIONS_TOTAL, WEBHOOK_DELIVERIES_TOTAL,
+};
+use payments_types::{MetricLabels, Metrics};
+
+/// Upper bounds of the histogram buckets, in seconds.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// `# HELP` text for the metrics the service records.
+const HELP: &[(&str, &str)] = &[
+    (HTTP_REQUESTS_TOTAL, "HTTP requests handled."),
+    (
+        HTTP_REQUEST_DURATION_SECONDS,
+        "Time taken to handle HTTP requests.",
+    ),
+    (
+        HTTP_REQUEST_REPO_CALLS_TOTAL,
+        "Repository calls made while handling HTTP requests.",
+    ),
+    (TRANSACTIONS_TOTAL, "Completed transactions."),
+    (
+        TRANSACTION_VOLUME_TOTAL,
+        "Money moved by completed transactions, in minor units.",
+    ),
+    (
+        CONVERSIONS_TOTAL,
+        "Completed transfers converted between currencies.",
+    ),
+    (QUOTES_ISSUED_TOTAL, "Exchange-rate quotes issued."),
+    (
+        QUOTES_EXPIRED_TOTAL,
+        "Transfers refused because their quote had expired.",
+    ),
+    (WEBHOOK_DELIVERIES_TOTAL, "Webhook delivery attempts."),
+];
+
+type Series = Vec<(&'static str, String)>;
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    /// Observations at or below each of [`BUCKETS`]
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+/// Counters and histograms kept in memory until scraped.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    counters: Mutex<BTreeMap<&'static str, BTreeMap<Series, u64>>>,
+    histograms: Mutex<BTreeMap<&'static str, BTreeMap<Series, Histogram>>>,
+}
+
+impl MetricsRegistry {
+    /// Renders every series in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (name, series) in self.counters.lock().unwrap().iter() {
+            write_header(&mut out, name, "counter");
+            for (labels, value) in series {
+                let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+            }
+        }
+        for (name, series) in self.histograms.lock().unwrap().iter() {
+            write_header(&mut out, name, "histogram");
+            for (labels, histogram) in series {
+                for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+                    let le = bound.to_string();
+                    let _ = writeln!(
+                        out,
+                        "{}_bucket{} {}",
+                        name,
+                        format_labels(labels, Some(&le)),
+                        count
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some("+Inf")),
+                    histogram.count
+                );
+                let labels = format_labels(labels, None);
+                let _ = writeln!(out, "{}_sum{} {}", name, labels, histogram.sum);
+                let _ = writeln!(out, "{}_count{} {}", name, labels, histogram.count);
+            }
+        }
+        out
+    }
+
+    /// Current value of a counter series, e.g. for tests.
+    pub fn counter(&self, name: &str, labels: MetricLabels<'_>) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(name)
+            .and_then(|series| series.get(&to_series(labels)))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+impl Metrics for MetricsRegistry {
+    fn increment_counter(&self, name: &'static str, labels: MetricLabels<'_>, value: u64) {
+        *self
+            .counters
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .entry(to_series(labels))
+            .or_default() += value;
+    }
+
+    fn observe_histogram(&self, name: &'static str, labels: MetricLabels<'_>, value: f64) {
+        let mut histograms = self.histograms.lock().unwrap();
+        let histogram = histograms
+            .entry(name)
+            .or_default()
+            .entry(to_series(labels))
+            .or_default();
+        for (bound, count) in BUCKETS.iter().zip(histogram.buckets.iter_mut()) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        histogram.sum += value;
+        histogram.count += 1;
+    }
+}
+
+fn to_series(labels: MetricLabels<'_>) -> Series {
+    labels
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect()
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str) {
+    if let Some((_, help)) = HELP.iter().find(|(metric, _)| *metric == name) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+    }
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// `{name="value",...}`, with `le` appended for histogram buckets; empty when
+/// there are no labels.
+fn format_labels(labels: &Series, le: Option<&str>) -> String {
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .chain(le.map(|le| ("le", le)))
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_prometheus_text_format() {
+        let registry = MetricsRegistry::default();
+        let labels = [("type", "DEPOSIT"), ("currency", "USD")];
+        registry.increment_counter(TRANSACTIONS_TOTAL, &labels, 1);
+        registry.increment_counter(TRANSACTIONS_TOTAL, &labels, 2);
+        registry.observe_histogram(
+            HTTP_REQUEST_DURATION_SECONDS,
+            &[("route", "/api/\"x\"")],
+            0.02,
+        );
+        assert_eq!(registry.counter(TRANSACTIONS_TOTAL, &labels), 3);
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE payments_transactions_total counter\n"));
+        assert!(
+            text.contains("payments_transactions_total{type=\"DEPOSIT\",currency=\"USD\"} 3\n")
+        );
+        assert!(text.contains("# TYPE http_request_duration_seconds histogram\n"));
+        assert!(text.contains(
+            "http_request_duration_seconds_bucket{route=\"/api/\\\"x\\\"\",le=\"0.01\"} 0\n"
+        ));
+        assert!(text.contains(
+            "http_request_duration_seconds_bucket{route=\"/api/\\\"x\\\"\",le=\"0.025\"} 1\n"
+        ));
+        assert!(text.contains(
+            "http_request_duration_seconds_bucket{route=\"/api/\\\"x\\\"\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(text.contains("http_request_duration_seconds_count{route=\"/api/\\\"x\\\"\"} 1\n"));
+    }
+}
@@ -2,211 +2,2103 @@
 
 #![allow(dead_code)] // Path functions are only used by utoipa for documentation generation
 
-use payments_types::domain::{AccountId, CurrencyCode, TransactionId, WebhookEndpointId};
+use payments_types::domain::{
+    AccountAlias, AccountId, AccountLimits, AccountRef, AccountStatement, AccountStatus, Alias,
+    ApiKeyAudit, ApiKeyAuditAction, ApiKeyAuditEntry, ApiKeyScope, ApiKeyUsage, BalanceHistory,
+    Beneficiary, BeneficiaryId, BeneficiaryStatus, ChangeAction, ChangeRequest, ChangeRequestId,
+    ChangeStatus, Counterparty, CurrencyCode, CurrencyExposure, CurrencyTotal, DailyBalance,
+    EncryptedMemo, EventField, EventSpec, Export, ExportDownload, ExportId, ExportRequest,
+    ExportStatus, ExposureReport, FeeAssignment, FeeSchedule, FeeScheduleId, FeeTier, FloatFlow,
+    FloatPosition, FloatReport, FxConversion, Hold, HoldId, HoldStatus, JournalExportFormat,
+    LoggedEvent, MemoKey, MemoKeyId, Mode, Owner, OwnerId, PaymentSchedule, Quote, QuoteId,
+    RiskAssessment, RiskDecision, ScheduledPayment, ScheduledPaymentId, ScheduledPaymentStatus,
+    SessionToken, SettlementBatch, SettlementBatchId, SettlementBatchStatus,
+    SettlementExportFormat, SpendingRules, Statement, StatementDownload, StatementFormat,
+    StatementId, StatementLine, StorageReport, StorageResource, StorageUsage, TransactionId,
+    TransactionType, UsageWindow, VolumeTotal, WebhookEndpointId, WebhookSignatureAlgorithm,
+};
 
 use payments_types::dto::{
-    AccountResponse, CreateAccountRequest, DepositRequest, RegisterWebhookRequest,
-    TransactionResponse, TransactionStatus, TransferRequest, WebhookResponse, WithdrawRequest,
+    AccountFees, AccountListQuery, AccountMergeResponse, AccountResponse, AccountSearchQuery,
+    AccountStatementQuery, AddAliasRequest, AssignFeeScheduleRequest, AuthorizeRequest,
+    BalanceHistoryQuery, BalanceSnapshotResponse, BatchItemResult, BatchItemStatus, BatchMode,
+    BatchRequest, BatchResponse, CaptureRequest, ChangeRequestQuery, CreateAccountRequest,
+    CreateBeneficiaryRequest, CreateFeeScheduleRequest, CreateMemoKeyRequest, CreateOwnerRequest,
+    CreateQuoteRequest, CreateScheduledPaymentRequest, CreateSessionTokenRequest,
+    CreateSettlementBatchRequest, DeadLetterQuery, DeadLetterRetryResponse, DependencyCheck,
+    DependencyStatus, DepositRequest, Diagnostics, ErrorCode, EventLogQuery, EventStreamQuery,
+    ExposureQuery, FeeQuote, FeeQuoteQuery, FloatReportQuery, InboundPaymentRequest, Incident,
+    IssueStatementsRequest, JournalExportQuery, MaintenanceStatus, MergeAccountRequest,
+    ProblemDetails, Readiness, ReadinessStatus, RegisterWebhookRequest, RegisterWebhooksRequest,
+    RegisterWebhooksResponse, ScheduledPaymentQuery, ServiceHealth, ServiceStatus,
+    SetApiKeyRateLimitRequest, SetIncidentRequest, SetMaintenanceRequest, SettlementExportQuery,
+    StatementDownloadQuery, StatementEmail, TransactionListQuery, TransactionResponse,
+    TransactionSearchQuery, TransactionStatus, TransferRequest, UpdateAccountRequest,
+    UpdateOwnerRequest, UpdateSettlementBatchStatusRequest, UpdateWebhookRequest,
+    VerifyBeneficiaryRequest, WebhookDeliveriesQuery, WebhookDeliveryResponse,
+    WebhookEventResponse, WebhookEventsQuery, WebhookResponse, WithdrawRequest,
 };
+use payments_types::ports::Warning;
+use serde_json::{Value, json};
 use utoipa::{
     Modify, OpenApi,
-    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+    openapi::{
+        ContentBuilder, ObjectBuilder, PathItem, Ref, RefOr, Required, Response, Type,
+        example::ExampleBuilder,
+        header::HeaderBuilder,
+        path::{Operation, ParameterBuilder, ParameterIn},
+        security::{Http, HttpAuthScheme, SecurityScheme},
+    },
 };
 
+use crate::inbound::auth::MODE_HEADER;
 use crate::inbound::handlers::{
     ApiKeyInfo, BootstrapRequest, BootstrapResponse, ConvertRequest, ConvertResponse,
     CreateApiKeyRequest, ExchangeRateResponse,
 };
+use crate::inbound::status::STATUS_PATH;
 
 // Dummy functions to generate path documentation
 // These are not the actual handlers, just for OpenAPI path generation
 
-/// Health check endpoint
+/// Liveness probe
+///
+/// Needs no API key and checks no dependencies: it answers as long as the
+/// process serves requests, so an orchestrator only restarts instances that
+/// have stopped responding.
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses(
+        (status = 200, description = "The process is serving requests", body = inline(serde_json::Value), example = json!({"status": "healthy"}))
+    )
+)]
+async fn live() {}
+
+/// Liveness probe (older path)
+///
+/// The same as `GET /health/live`.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "The process is serving requests", body = inline(serde_json::Value), example = json!({"status": "healthy"}))
+    )
+)]
+async fn health() {}
+
+/// Public service status and incident banner
+///
+/// Needs no API key and does not count towards any key's quota. Responses
+/// carry `Cache-Control: public, max-age=30` and are limited to 60 requests
+/// per minute per caller address.
+#[utoipa::path(
+    get,
+    path = "/status",
+    tag = "health",
+    responses(
+        (status = 200, description = "Coarse service health and the current incident, if any", body = ServiceStatus),
+        (status = 429, description = "Too many status requests from this address")
+    )
+)]
+async fn service_status() {}
+
+/// Readiness probe
+///
+/// Needs no API key. Pings each dependency and reports its status and
+/// latency; answers 503 while any is down, so load balancers can take the
+/// instance out of rotation. Both statuses carry the same body.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to serve requests", body = Readiness, example = json!({"status": "ready", "checks": [{"name": "database", "status": "up", "latency_ms": 1.8}]})),
+        (status = 503, description = "A dependency is down", body = Readiness, example = json!({"status": "not_ready", "checks": [{"name": "database", "status": "down", "latency_ms": 5000.0}]}))
+    )
+)]
+async fn ready() {}
+
+/// Readiness probe (older path)
+///
+/// The same as `GET /health/ready`.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to serve requests", body = Readiness),
+        (status = 503, description = "A dependency is down", body = Readiness)
+    )
+)]
+async fn readyz() {}
+
+/// Bootstrap first API key
+#[utoipa::path(
+    post,
+    path = "/api/bootstrap",
+    tag = "auth",
+    request_body = BootstrapRequest,
+    responses(
+        (status = 201, description = "API key created successfully", body = BootstrapResponse),
+        (status = 400, description = "Bootstrap not allowed - API keys already exist")
+    )
+)]
+async fn bootstrap() {}
+
+/// Create a new API key, optionally scoped to one account (admin keys only)
+///
+/// `scopes` limits what the new key may do and defaults to every scope the
+/// calling key holds, or to `accounts:read` and `transactions:read` for a key
+/// bound to an account or owner or in test mode; a key cannot grant a scope
+/// it lacks. Requests with a key
+/// missing a route's scope are rejected with 403 and
+/// `"error_code": "INSUFFICIENT_SCOPE"`.
+#[utoipa::path(
+    post,
+    path = "/api/keys",
+    tag = "auth",
+    request_body = CreateApiKeyRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "API key created", body = BootstrapResponse),
+        (status = 400, description = "Not an admin key, no scopes, or a scope the caller lacks"),
+        (status = 403, description = "Key lacks the keys:admin scope"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_api_key() {}
+
+/// List all API keys without exposing raw keys (admin keys only)
+#[utoipa::path(
+    get,
+    path = "/api/keys",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "List of API keys", body = Vec<ApiKeyInfo>),
+        (status = 400, description = "Not an admin key"),
+        (status = 403, description = "Key lacks the keys:admin scope"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_api_keys() {}
+
+/// Request that an API key be deleted (admin keys only)
+///
+/// The key stays active until a different admin key approves the change
+/// through `POST /api/admin/changes/{id}/approve`. A deleted key stops
+/// authenticating but is kept, with when and by whom it was deleted, and
+/// stays visible through `GET /api/keys/{id}/audit`.
+#[utoipa::path(
+    delete,
+    path = "/api/keys/{id}",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "API key ID (UUID)")
+    ),
+    responses(
+        (status = 202, description = "Deletion requested, awaiting approval", body = ChangeRequest),
+        (status = 400, description = "Not an admin key"),
+        (status = 403, description = "Key lacks the keys:admin scope"),
+        (status = 404, description = "API key not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn delete_api_key() {}
+
+/// Give an API key its own quota (admin keys only)
+///
+/// Send `{"requests_per_minute": null}` to return the key to the server-wide
+/// default. The new quota applies from the key's next request, which starts
+/// with the full quota available.
+#[utoipa::path(
+    put,
+    path = "/api/keys/{id}/rate-limit",
+    tag = "auth",
+    request_body = SetApiKeyRateLimitRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "API key ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "The key with its new quota", body = ApiKeyInfo),
+        (status = 400, description = "Not an admin key, or the quota is out of range"),
+        (status = 403, description = "Key lacks the keys:admin scope"),
+        (status = 404, description = "API key not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn set_api_key_rate_limit() {}
+
+/// Get request and money-movement totals for an API key
+///
+/// Admin keys can read any key's usage; other keys only their own. Totals
+/// cover the current hour, the last 24 hours and the last 30 days.
+#[utoipa::path(
+    get,
+    path = "/api/keys/{id}/usage",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "API key ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Usage per window", body = ApiKeyUsage),
+        (status = 400, description = "Another key's usage requested with a non-admin key"),
+        (status = 404, description = "API key not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_api_key_usage() {}
+
+/// Get an API key's audit trail (admin keys only)
+///
+/// Works for deleted keys too. Entries record the key's creation and
+/// deletion with the admin key that made them, and its use: each time a
+/// request moves `last_used_at` on, which happens at most once a minute.
+#[utoipa::path(
+    get,
+    path = "/api/keys/{id}/audit",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "API key ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "The key and its audit trail, oldest first", body = ApiKeyAudit),
+        (status = 400, description = "Invalid ID or not an admin key"),
+        (status = 403, description = "Key lacks the keys:admin scope"),
+        (status = 404, description = "API key not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_api_key_audit() {}
+
+/// Exchange an API key for a short-lived, read-only session token
+///
+/// The token can only read the one account, holds whichever of
+/// `accounts:read` and `transactions:read` the key holds, and stops working
+/// when it expires or the key is deleted. Send it as a Bearer token in place
+/// of the key, e.g. from a customer-facing dashboard.
+#[utoipa::path(
+    post,
+    path = "/api/session-tokens",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    request_body = CreateSessionTokenRequest,
+    responses(
+        (status = 201, description = "Token issued (shown only once)", body = SessionToken),
+        (status = 400, description = "Invalid lifetime, key scoped to another account, or caller is a session token"),
+        (status = 403, description = "API key is missing the accounts:read scope"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_session_token() {}
+
+/// Create a new account
+///
+/// Every account belongs to an owner; keys bound to an owner may only open
+/// accounts for it.
+#[utoipa::path(
+    post,
+    path = "/api/accounts",
+    tag = "accounts",
+    request_body = CreateAccountRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Account created successfully", body = AccountResponse),
+        (status = 400, description = "Invalid request or no access to the owner"),
+        (status = 404, description = "Owner not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_account() {}
+
+/// List all accounts, or those of one owner
+#[utoipa::path(
+    get,
+    path = "/api/accounts",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(AccountListQuery),
+    responses(
+        (status = 200, description = "List of accounts", body = Vec<AccountResponse>),
+        (status = 400, description = "No access to the owner"),
+        (status = 404, description = "Owner not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_accounts() {}
+
+/// Search accounts by name fragment or ID prefix
+#[utoipa::path(
+    get,
+    path = "/api/accounts/search",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(AccountSearchQuery),
+    responses(
+        (status = 200, description = "Matching accounts, ordered by name", body = Vec<AccountResponse>),
+        (status = 400, description = "Empty query"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn search_accounts() {}
+
+/// Get account by ID
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Account details", body = AccountResponse),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_account() {}
+
+/// Change an account's settings (admin keys only)
+///
+/// Only the fields present are changed. Lowering the overdraft limit leaves
+/// a balance already below it as it is but stops further debits.
+#[utoipa::path(
+    patch,
+    path = "/api/accounts/{id}",
+    tag = "accounts",
+    request_body = UpdateAccountRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Account as updated", body = AccountResponse),
+        (status = 400, description = "Negative overdraft limit or not an admin API key"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn update_account() {}
+
+/// List an account's aliases, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/aliases",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "The account's aliases", body = Vec<AccountAlias>),
+        (status = 400, description = "Invalid ID or no access to the account"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_account_aliases() {}
+
+/// Register an alias for an account
+///
+/// Aliases are unique across accounts regardless of case and can then be
+/// used wherever an account ID is accepted. Adding an alias the account
+/// already has returns it unchanged; an account may have up to five.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/aliases",
+    tag = "accounts",
+    request_body = AddAliasRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 201, description = "Alias registered", body = AccountAlias),
+        (status = 400, description = "Invalid alias, alias taken by another account, or too many aliases"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn add_account_alias() {}
+
+/// Remove an alias from an account, freeing it for reuse
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{id}/aliases/{alias}",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias"),
+        ("alias" = Alias, Path, description = "Alias to remove, with or without the `@`")
+    ),
+    responses(
+        (status = 204, description = "Alias removed"),
+        (status = 400, description = "Invalid alias or no access to the account"),
+        (status = 404, description = "The account does not have this alias"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn remove_account_alias() {}
+
+/// Look up the account an alias belongs to
+#[utoipa::path(
+    get,
+    path = "/api/aliases/{alias}",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("alias" = Alias, Path, description = "Alias, with or without the `@`")
+    ),
+    responses(
+        (status = 200, description = "The alias and its account", body = AccountAlias),
+        (status = 400, description = "Invalid alias or no access to the account"),
+        (status = 404, description = "Alias not registered"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_account_alias() {}
+
+/// List an account's memo keys, retired ones included, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/memo-keys",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "The account's memo keys", body = Vec<MemoKey>),
+        (status = 400, description = "Invalid ID or no access to the account"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_memo_keys() {}
+
+/// Register a memo key for an account
+///
+/// Only the key's algorithm and fingerprint are stored, never the key.
+/// Payments may then carry an `encrypted_memo` under the key, which is stored
+/// and returned as sent. An account may have up to ten active keys.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/memo-keys",
+    tag = "accounts",
+    request_body = CreateMemoKeyRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 201, description = "Memo key registered", body = MemoKey),
+        (status = 400, description = "Invalid algorithm or fingerprint, or too many active keys"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn add_memo_key() {}
+
+/// Get one of an account's memo keys
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/memo-keys/{key_id}",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias"),
+        ("key_id" = MemoKeyId, Path, description = "Memo key ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "The memo key", body = MemoKey),
+        (status = 400, description = "Invalid ID or no access to the account"),
+        (status = 404, description = "The account does not hold this key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_memo_key() {}
+
+/// Retire a memo key
+///
+/// New memos may no longer use the key; memos already written with it are
+/// kept and still returned. Retiring a key again keeps the first time.
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{id}/memo-keys/{key_id}",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias"),
+        ("key_id" = MemoKeyId, Path, description = "Memo key ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "The retired memo key", body = MemoKey),
+        (status = 400, description = "Invalid ID or no access to the account"),
+        (status = 404, description = "The account does not hold this key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn retire_memo_key() {}
+
+/// List account owners, oldest first (admin keys only)
+#[utoipa::path(
+    get,
+    path = "/api/owners",
+    tag = "owners",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Every owner, the default one included", body = Vec<Owner>),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_owners() {}
+
+/// Create an account owner (admin keys only)
+#[utoipa::path(
+    post,
+    path = "/api/owners",
+    tag = "owners",
+    request_body = CreateOwnerRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Owner created", body = Owner),
+        (status = 400, description = "Invalid name or email, or not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_owner() {}
+
+/// Get an account owner
+///
+/// Keys bound to an owner may read their own.
+#[utoipa::path(
+    get,
+    path = "/api/owners/{id}",
+    tag = "owners",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = OwnerId, Path, description = "Owner ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "The owner", body = Owner),
+        (status = 400, description = "Invalid ID or no access to the owner"),
+        (status = 404, description = "Owner not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_owner() {}
+
+/// Rename an account owner or change its email (admin keys only)
+#[utoipa::path(
+    patch,
+    path = "/api/owners/{id}",
+    tag = "owners",
+    request_body = UpdateOwnerRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = OwnerId, Path, description = "Owner ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "The updated owner", body = Owner),
+        (status = 400, description = "Invalid name or email, or not an admin API key"),
+        (status = 404, description = "Owner not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn update_owner() {}
+
+/// Delete an account owner (admin keys only)
+///
+/// Owners still holding accounts, and the default owner, cannot be deleted.
+#[utoipa::path(
+    delete,
+    path = "/api/owners/{id}",
+    tag = "owners",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = OwnerId, Path, description = "Owner ID (UUID)")
+    ),
+    responses(
+        (status = 204, description = "Owner deleted"),
+        (status = 400, description = "The default owner, or not an admin API key"),
+        (status = 404, description = "Owner not found"),
+        (status = 409, description = "The owner still holds accounts"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn delete_owner() {}
+
+/// Get the spending rules for an account
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/spending-rules",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Allowed and denied purpose codes", body = SpendingRules),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_spending_rules() {}
+
+/// Replace the spending rules for an account (admin keys only)
+///
+/// Withdrawals and outgoing transfers whose `purpose_code` the rules do not
+/// permit are rejected with 422.
+#[utoipa::path(
+    put,
+    path = "/api/accounts/{id}/spending-rules",
+    tag = "accounts",
+    request_body = SpendingRules,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Normalized rules as stored", body = SpendingRules),
+        (status = 400, description = "Invalid purpose code or not an admin API key"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn set_spending_rules() {}
+
+/// Get the limits configured for an account
+///
+/// Only the account's own limits are returned; service-wide caps still apply
+/// on top of them.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/limits",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Account limits (null means unlimited)", body = AccountLimits),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_account_limits() {}
+
+/// Replace the limits for an account (admin keys only)
+///
+/// Omitted or null fields are cleared. Payments that would break a limit are
+/// rejected with 422.
+#[utoipa::path(
+    put,
+    path = "/api/accounts/{id}/limits",
+    tag = "accounts",
+    request_body = AccountLimits,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Limits as stored", body = AccountLimits),
+        (status = 400, description = "Non-positive limit or not an admin API key"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn set_account_limits() {}
+
+/// Reactivate a dormant account (admin keys only)
+///
+/// Accounts with no transactions for the dormancy period are flagged
+/// `DORMANT` by a background job. When the server blocks debits from dormant
+/// accounts, this is the only way to lift the block.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/reactivate",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Account, now active", body = AccountResponse),
+        (status = 400, description = "Account is not dormant or not an admin API key"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn reactivate_account() {}
+
+/// Freeze an account (admin keys only)
+///
+/// Withdrawals, transfers out, new holds and captures from a `FROZEN` account
+/// return 422 until it is unfrozen; deposits and incoming transfers still
+/// land. Emits `account.frozen`.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/freeze",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Account, now frozen", body = AccountResponse),
+        (status = 400, description = "Account is already frozen or not an admin API key"),
+        (status = 404, description = "Account not found"),
+        (status = 422, description = "Account is closed"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn freeze_account() {}
+
+/// Unfreeze an account (admin keys only)
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/unfreeze",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Account, now active", body = AccountResponse),
+        (status = 400, description = "Account is not frozen or not an admin API key"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn unfreeze_account() {}
+
+/// Close an account for good (admin keys only)
+///
+/// Only accounts with a zero balance and no open holds can be closed. Money
+/// can neither enter nor leave a `CLOSED` account, and it cannot be reopened.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/close",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Account, now closed", body = AccountResponse),
+        (status = 400, description = "Account holds money, is already closed, or not an admin API key"),
+        (status = 404, description = "Account not found"),
+        (status = 409, description = "Account changed status meanwhile, please retry"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn close_account() {}
+
+/// Merge a duplicate account into another (admin keys only)
+///
+/// Moves the whole balance of the account to `into` in one transfer, then
+/// closes it with `merged_into` pointing at the account kept. Its past
+/// transactions stay where they are. The transfer is exempt from amount caps,
+/// fees and the risk check. The account must have no open holds and may not
+/// be frozen. Emits `transfer.success` when money moved, then
+/// `account.merged`.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/merge",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias of the duplicate")
+    ),
+    request_body = MergeAccountRequest,
+    responses(
+        (status = 200, description = "Both accounts after the merge, and the transfer", body = AccountMergeResponse),
+        (status = 400, description = "Same account, currencies differ, open holds, or not an admin API key"),
+        (status = 404, description = "Account not found"),
+        (status = 409, description = "Account changed during the merge, please retry"),
+        (status = 422, description = "Either account is closed, or the duplicate is frozen"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn merge_account() {}
+
+/// Get an account's fee schedule assignments
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/fees",
+    tag = "fees",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Current assignment and full history", body = AccountFees),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_account_fees() {}
+
+/// Assign a fee schedule to an account (admin keys only)
+///
+/// The schedule applies from `effective_from` (default: now); past dates are
+/// rejected so fee changes only ever apply prospectively.
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/fees",
+    tag = "fees",
+    request_body = AssignFeeScheduleRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 201, description = "Assignment recorded", body = FeeAssignment),
+        (status = 400, description = "Back-dated assignment or not an admin API key"),
+        (status = 404, description = "Account or fee schedule not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn assign_fee_schedule() {}
+
+/// Quote the fee for a payment from an account
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/fees/quote",
+    tag = "fees",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias"),
+        FeeQuoteQuery
+    ),
+    responses(
+        (status = 200, description = "Fee under the schedule in force now", body = FeeQuote),
+        (status = 400, description = "Non-positive amount"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn quote_fee() {}
+
+/// Create a fee schedule (admin keys only)
+///
+/// Schedules cannot be edited; create a new one and assign it instead.
+#[utoipa::path(
+    post,
+    path = "/api/fee-schedules",
+    tag = "fees",
+    request_body = CreateFeeScheduleRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Fee schedule created", body = FeeSchedule),
+        (status = 400, description = "Invalid tiers or not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_fee_schedule() {}
+
+/// List fee schedules
+#[utoipa::path(
+    get,
+    path = "/api/fee-schedules",
+    tag = "fees",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Fee schedules, newest first", body = Vec<FeeSchedule>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_fee_schedules() {}
+
+/// Get a fee schedule by ID
+#[utoipa::path(
+    get,
+    path = "/api/fee-schedules/{id}",
+    tag = "fees",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = FeeScheduleId, Path, description = "Fee schedule ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Fee schedule", body = FeeSchedule),
+        (status = 404, description = "Fee schedule not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_fee_schedule() {}
+
+/// Batch up the payouts made so far (admin keys only)
+///
+/// Collects every withdrawal made up to `cutoff` (default: now) that is not
+/// in a batch yet and fixes the batch's per-currency totals.
+#[utoipa::path(
+    post,
+    path = "/api/settlement-batches",
+    tag = "settlement",
+    request_body = CreateSettlementBatchRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Batch created", body = SettlementBatch),
+        (status = 400, description = "Future cut-off, no unbatched payouts, or not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_settlement_batch() {}
+
+/// List settlement batches (admin keys only)
+#[utoipa::path(
+    get,
+    path = "/api/settlement-batches",
+    tag = "settlement",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Settlement batches, newest first", body = Vec<SettlementBatch>),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_settlement_batches() {}
+
+/// Get a settlement batch by ID (admin keys only)
+#[utoipa::path(
+    get,
+    path = "/api/settlement-batches/{id}",
+    tag = "settlement",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = SettlementBatchId, Path, description = "Settlement batch ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Settlement batch", body = SettlementBatch),
+        (status = 404, description = "Settlement batch not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_settlement_batch() {}
+
+/// Move a settlement batch along its lifecycle (admin keys only)
+///
+/// `PENDING -> SUBMITTED -> SETTLED`; `PENDING` and `SUBMITTED` batches can
+/// also be `CANCELLED`, which releases their payouts into the next batch.
+#[utoipa::path(
+    post,
+    path = "/api/settlement-batches/{id}/status",
+    tag = "settlement",
+    request_body = UpdateSettlementBatchStatusRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = SettlementBatchId, Path, description = "Settlement batch ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Batch after the change", body = SettlementBatch),
+        (status = 400, description = "Transition not allowed or not an admin API key"),
+        (status = 404, description = "Settlement batch not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn update_settlement_batch_status() {}
+
+/// Download a settlement batch file (admin keys only)
+///
+/// `csv` lists one payout per row in minor units. `pain001` is an ISO 20022
+/// pain.001.001.03 credit transfer file with one payment block per currency;
+/// it needs the debtor account to be configured and every payout to have a
+/// counterparty.
+#[utoipa::path(
+    get,
+    path = "/api/settlement-batches/{id}/export",
+    tag = "settlement",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = SettlementBatchId, Path, description = "Settlement batch ID (UUID)"),
+        SettlementExportQuery
+    ),
+    responses(
+        (status = 200, description = "Batch file", content(
+            (String = "text/csv"),
+            (String = "application/xml")
+        )),
+        (status = 400, description = "Cancelled batch, export not possible, or not an admin API key"),
+        (status = 404, description = "Settlement batch not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn export_settlement_batch() {}
+
+/// Download a period's transactions as journal entries (admin keys only)
+///
+/// Each transaction becomes a balanced debit/credit pair on the GL accounts
+/// configured for its type, in QuickBooks Online or Xero import layout.
+#[utoipa::path(
+    get,
+    path = "/api/exports/journal",
+    tag = "accounting",
+    security(("bearer_auth" = [])),
+    params(JournalExportQuery),
+    responses(
+        (status = 200, description = "Journal entries", content_type = "text/csv", body = String),
+        (status = 400, description = "Invalid period or not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn export_journal() {}
+
+/// Export the event log for a data warehouse (admin keys only)
+///
+/// Returns account and transaction events as newline-delimited JSON, one
+/// event per line in sequence order. Every event in a response was recorded
+/// on the same UTC day, named in `x-event-log-day`, so each response can be
+/// loaded into that day's partition. Pass `x-event-log-cursor` as
+/// `after_sequence` to continue; an empty response means there is nothing
+/// new yet.
+#[utoipa::path(
+    get,
+    path = "/api/exports/events",
+    tag = "events",
+    security(("bearer_auth" = [])),
+    params(EventLogQuery),
+    responses(
+        (status = 200, description = "Logged events, one JSON object per line",
+            content_type = "application/x-ndjson", body = LoggedEvent,
+            headers(
+                ("x-event-log-cursor" = i64, description = "`after_sequence` for the next page"),
+                ("x-event-log-day" = String, description = "UTC day the events were recorded on (YYYY-MM-DD); absent when there are none")
+            )
+        ),
+        (status = 400, description = "Negative after_sequence or not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn export_event_log() {}
+
+/// Queue a file export to be rendered in the background (admin keys only)
+///
+/// Returns right away with a `PENDING` export; poll it until it is `READY`
+/// and fetch the file from its download link.
+#[utoipa::path(
+    post,
+    path = "/api/exports",
+    tag = "exports",
+    request_body = ExportRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 202, description = "Export queued", body = Export),
+        (status = 400, description = "Invalid request or not an admin API key"),
+        (status = 404, description = "Settlement batch not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_export() {}
+
+/// Get an export, with a download link once it is ready (admin keys only)
+#[utoipa::path(
+    get,
+    path = "/api/exports/{id}",
+    tag = "exports",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = ExportId, Path, description = "Export ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Export and its download link", body = ExportDownload),
+        (status = 400, description = "Invalid ID or not an admin API key"),
+        (status = 404, description = "Export not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_export() {}
+
+/// Download an export's file through a signed link
+///
+/// Needs no API key: the token's signature and expiry are the credential.
+#[utoipa::path(
+    get,
+    path = "/api/downloads/{token}",
+    tag = "exports",
+    params(
+        ("token" = String, Path, description = "Signed token from the export's download URL")
+    ),
+    responses(
+        (status = 200, description = "Export file", content(
+            (String = "text/csv"),
+            (String = "application/xml")
+        )),
+        (status = 400, description = "Invalid or expired link"),
+        (status = 404, description = "Export not found")
+    )
+)]
+async fn download_export() {}
+
+/// Report balances per currency across all accounts (admin keys only)
+///
+/// Converts each currency's total into the base currency at current rates,
+/// or at the last rates recorded at or before `as_of`.
+#[utoipa::path(
+    get,
+    path = "/api/reports/exposure",
+    tag = "reports",
+    security(("bearer_auth" = [])),
+    params(ExposureQuery),
+    responses(
+        (status = 200, description = "Exposure per currency", body = ExposureReport),
+        (status = 400, description = "`as_of` in the future or not an admin API key"),
+        (status = 404, description = "No rates recorded at or before `as_of`"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn exposure_report() {}
+
+/// Report the float per currency and the money that entered or left the
+/// ledger each day (admin keys only)
+///
+/// The float takes the other side of every deposit, withdrawal and
+/// cross-currency conversion, so it and the customer balances sum to zero
+/// in each currency. Flows default to the 30 days up to today.
+#[utoipa::path(
+    get,
+    path = "/api/reports/float",
+    tag = "reports",
+    security(("bearer_auth" = [])),
+    params(FloatReportQuery),
+    responses(
+        (status = 200, description = "Float positions and daily external flows", body = FloatReport),
+        (status = 400, description = "Reversed or too long period, or not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn float_report() {}
+
+/// Make a withdrawal or transfer on a schedule
+///
+/// The schedule is either `{"type": "interval", "every_seconds": 86400}` or
+/// `{"type": "cron", "expression": "0 9 * * MON-FRI", "timezone":
+/// "Europe/Berlin"}`; cron expressions are read in the IANA time zone, so
+/// runs stay at the same local time across daylight saving changes. Each
+/// run emits `scheduled_payment.executed` or `scheduled_payment.failed`.
+#[utoipa::path(
+    post,
+    path = "/api/scheduled-payments",
+    tag = "scheduled-payments",
+    request_body = CreateScheduledPaymentRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Scheduled payment created", body = ScheduledPayment),
+        (status = 400, description = "Invalid schedule, accounts or currency, or no access to the source account"),
+        (status = 404, description = "Account not found"),
+        (status = 422, description = "Purpose code not allowed by the source account's spending rules"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_scheduled_payment() {}
+
+/// List scheduled payments, oldest first
+///
+/// Scoped keys only see payments drawing on their own account.
+#[utoipa::path(
+    get,
+    path = "/api/scheduled-payments",
+    tag = "scheduled-payments",
+    security(("bearer_auth" = [])),
+    params(ScheduledPaymentQuery),
+    responses(
+        (status = 200, description = "Scheduled payments", body = Vec<ScheduledPayment>),
+        (status = 400, description = "No access to the account"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_scheduled_payments() {}
+
+/// Get a scheduled payment and how its last run went
+#[utoipa::path(
+    get,
+    path = "/api/scheduled-payments/{id}",
+    tag = "scheduled-payments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = ScheduledPaymentId, Path, description = "Scheduled payment ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Scheduled payment", body = ScheduledPayment),
+        (status = 400, description = "Invalid ID or no access to the source account"),
+        (status = 404, description = "Scheduled payment not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_scheduled_payment() {}
+
+/// Pause a scheduled payment until it is resumed
+#[utoipa::path(
+    post,
+    path = "/api/scheduled-payments/{id}/pause",
+    tag = "scheduled-payments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = ScheduledPaymentId, Path, description = "Scheduled payment ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Paused scheduled payment", body = ScheduledPayment),
+        (status = 400, description = "Invalid ID or no access to the source account"),
+        (status = 404, description = "Scheduled payment not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn pause_scheduled_payment() {}
+
+/// Resume a paused scheduled payment
+///
+/// Runs that fell due while the payment was paused are skipped; it next
+/// runs at its first occurrence after now.
+#[utoipa::path(
+    post,
+    path = "/api/scheduled-payments/{id}/resume",
+    tag = "scheduled-payments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = ScheduledPaymentId, Path, description = "Scheduled payment ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Active scheduled payment", body = ScheduledPayment),
+        (status = 400, description = "Invalid ID or no access to the source account"),
+        (status = 404, description = "Scheduled payment not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn resume_scheduled_payment() {}
+
+/// Place a hold on an account's funds
+///
+/// The hold lowers the account's available balance without moving money.
+/// Capture it to withdraw the funds or void it to release them; holds that
+/// are neither expire after `expires_in_secs` (7 days by default).
+#[utoipa::path(
+    post,
+    path = "/api/transactions/authorize",
+    tag = "holds",
+    request_body = AuthorizeRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Hold placed", body = Hold),
+        (status = 400, description = "Invalid amount, currency or expiry, insufficient available funds, or no access to the account"),
+        (status = 404, description = "Account not found"),
+        (status = 422, description = "Purpose code not allowed by the account's spending rules, or account dormant, frozen or closed"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn authorize() {}
+
+/// Capture a hold
+///
+/// Withdraws `amount` (the whole hold when the body is omitted) and
+/// releases the rest of the reservation. Emits `hold.captured` and
+/// `withdraw.success`.
+#[utoipa::path(
+    post,
+    path = "/api/transactions/{id}/capture",
+    tag = "holds",
+    request_body(content = Option<CaptureRequest>, description = "Optional; captures the full hold when omitted"),
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = HoldId, Path, description = "Hold ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Captured hold", body = Hold),
+        (status = 400, description = "Invalid ID or amount, hold not open or expired, or no access to the account"),
+        (status = 404, description = "Hold not found"),
+        (status = 422, description = "Account dormant, frozen or closed"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn capture_hold() {}
+
+/// Void a hold, releasing its funds
+#[utoipa::path(
+    post,
+    path = "/api/transactions/{id}/void",
+    tag = "holds",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = HoldId, Path, description = "Hold ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Voided hold", body = Hold),
+        (status = 400, description = "Invalid ID, hold not open, or no access to the account"),
+        (status = 404, description = "Hold not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn void_hold() {}
+
+/// Get a hold
+#[utoipa::path(
+    get,
+    path = "/api/holds/{id}",
+    tag = "holds",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = HoldId, Path, description = "Hold ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Hold", body = Hold),
+        (status = 400, description = "Invalid ID or no access to the account"),
+        (status = 404, description = "Hold not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_hold() {}
+
+/// List an account's holds, newest first
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/holds",
+    tag = "holds",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Holds", body = Vec<Hold>),
+        (status = 400, description = "Invalid ID or no access to the account"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_holds() {}
+
+/// Lock an exchange rate
+///
+/// Quotes the current rate for converting `amount` and holds it until
+/// `expires_at` (`expires_in_secs`, 60 seconds by default). A transfer that
+/// names the quote in `quote_id` is converted at the quoted rate and spends
+/// the quote.
+#[utoipa::path(
+    post,
+    path = "/api/quotes",
+    tag = "rates",
+    request_body = CreateQuoteRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Quote issued", body = Quote),
+        (status = 400, description = "Invalid amount, currencies or expiry, or no rate between the currencies"),
+        (status = 401, description = "Unauthorized"),
+        (status = 503, description = "Exchange rate provider unavailable")
+    )
+)]
+async fn create_quote() {}
+
+/// Get a quote
+#[utoipa::path(
+    get,
+    path = "/api/quotes/{id}",
+    tag = "rates",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = QuoteId, Path, description = "Quote ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Quote", body = Quote),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Quote not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_quote() {}
+
+/// Add a beneficiary to pay out to
+///
+/// Sends the beneficiary two micro-deposits of random amounts (simulated:
+/// recorded, not sent over the rails). Payouts only go to the beneficiary
+/// once the customer confirms both amounts.
+#[utoipa::path(
+    post,
+    path = "/api/beneficiaries",
+    tag = "beneficiaries",
+    request_body = CreateBeneficiaryRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Beneficiary added, pending verification", body = Beneficiary),
+        (status = 400, description = "Empty name or external ID, or no access to the account"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn create_beneficiary() {}
+
+/// Get a beneficiary
+#[utoipa::path(
+    get,
+    path = "/api/beneficiaries/{id}",
+    tag = "beneficiaries",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = BeneficiaryId, Path, description = "Beneficiary ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Beneficiary", body = Beneficiary),
+        (status = 400, description = "Invalid ID or no access to the account"),
+        (status = 404, description = "Beneficiary not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_beneficiary() {}
+
+/// Confirm a beneficiary's micro-deposits
+///
+/// The two amounts may be given in either order. Wrong amounts are
+/// rejected and counted; after three the beneficiary fails verification
+/// and must be added again.
+#[utoipa::path(
+    post,
+    path = "/api/beneficiaries/{id}/verify",
+    tag = "beneficiaries",
+    request_body = VerifyBeneficiaryRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = BeneficiaryId, Path, description = "Beneficiary ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Beneficiary verified", body = Beneficiary),
+        (status = 400, description = "Wrong amounts, beneficiary not pending, invalid ID, or no access to the account"),
+        (status = 404, description = "Beneficiary not found"),
+        (status = 409, description = "Another confirmation was recorded at the same time; try again"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn verify_beneficiary() {}
+
+/// List an account's beneficiaries, newest first
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/beneficiaries",
+    tag = "beneficiaries",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Beneficiaries", body = Vec<Beneficiary>),
+        (status = 400, description = "Invalid ID or no access to the account"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_beneficiaries() {}
+
+/// Get the address an account's statements are emailed to
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/statement-email",
+    tag = "statements",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Statement email address", body = StatementEmail),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_statement_email() {}
+
+/// Set or clear the address an account's statements are emailed to
+///
+/// With an address and an email notifier configured, statements arrive as a
+/// CSV attachment; otherwise a `statement.ready` webhook carries a signed
+/// download link.
+#[utoipa::path(
+    put,
+    path = "/api/accounts/{id}/statement-email",
+    tag = "statements",
+    request_body = StatementEmail,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Updated statement email address", body = StatementEmail),
+        (status = 400, description = "Invalid email address"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn set_statement_email() {}
+
+/// List an account's statements, latest month first
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/statements",
+    tag = "statements",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias")
+    ),
+    responses(
+        (status = 200, description = "Statements", body = Vec<Statement>),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_statements() {}
+
+/// Generate a statement for any range of days
+///
+/// Opening and closing balances with every transaction in `from..=to` (UTC)
+/// and the running balance after each. `csv` uses the monthly statement
+/// layout; `json` returns an `AccountStatement` for rendering, e.g. to PDF.
+/// Nothing is stored, so a period that includes today reflects it so far.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/statement",
+    tag = "statements",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias"),
+        AccountStatementQuery
+    ),
+    responses(
+        (status = 200, description = "Statement file", content(
+            (String = "text/csv"),
+            (AccountStatement = "application/json")
+        )),
+        (status = 400, description = "Invalid ID or period, or no access to the account"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_account_statement() {}
+
+/// Get an account's end-of-day balances
+///
+/// Closing balances recorded for each UTC day in `from..=to`, oldest first,
+/// for charting. `to` defaults to yesterday and `from` to 30 days before it.
+/// Days are recorded by a background job once they end, so today and days
+/// the job has not reached yet are left out.
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/balance-history",
+    tag = "accounts",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias"),
+        BalanceHistoryQuery
+    ),
+    responses(
+        (status = 200, description = "Daily closing balances", body = BalanceHistory),
+        (status = 400, description = "Invalid ID or period, or no access to the account"),
+        (status = 404, description = "Account not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_balance_history() {}
+
+/// Get a statement with a fresh download link
+#[utoipa::path(
+    get,
+    path = "/api/statements/{id}",
+    tag = "statements",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = StatementId, Path, description = "Statement ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Statement and download link", body = StatementDownload),
+        (status = 404, description = "Statement not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_statement() {}
+
+/// Issue statements for a month (admin keys only)
+///
+/// Every account open before the month ended gets one statement per month;
+/// accounts that already have one are skipped. Returns the new statements.
+#[utoipa::path(
+    post,
+    path = "/api/statements/issue",
+    tag = "statements",
+    request_body = IssueStatementsRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Newly issued statements", body = Vec<Statement>),
+        (status = 400, description = "Invalid or unfinished month, or not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn issue_statements() {}
+
+/// Download a statement file through a signed link
+///
+/// Needs no API key: the link's signature and expiry are the credential.
+#[utoipa::path(
+    get,
+    path = "/api/statements/{id}/download",
+    tag = "statements",
+    params(
+        ("id" = StatementId, Path, description = "Statement ID (UUID)"),
+        StatementDownloadQuery
+    ),
+    responses(
+        (status = 200, description = "Statement file", content_type = "text/csv", body = String),
+        (status = 400, description = "Invalid or expired link"),
+        (status = 404, description = "Statement not found")
+    )
+)]
+async fn download_statement() {}
+
+/// Deposit money into an account
+///
+/// The account may be given by alias, e.g. `@alice-ops`. Repeating a request
+/// with the same idempotency key returns the first response unchanged.
+#[utoipa::path(
+    post,
+    path = "/api/transactions/deposit",
+    tag = "transactions",
+    request_body = DepositRequest<AccountRef>,
+    security(("bearer_auth" = [])),
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Idempotency key, instead of `idempotency_key` in the body")
+    ),
+    responses(
+        (status = 200, description = "Deposit successful", body = TransactionResponse),
+        (status = 400, description = "Invalid request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Idempotency key already used for a different request"),
+        (status = 422, description = "Amount or balance cap exceeded, account closed, denied by the risk check, or refused by a transaction rule")
+    )
+)]
+async fn deposit() {}
+
+/// Withdraw money from an account
+///
+/// The account may be given by alias, e.g. `@alice-ops`. Repeating a request
+/// with the same idempotency key returns the first response unchanged.
+#[utoipa::path(
+    post,
+    path = "/api/transactions/withdraw",
+    tag = "transactions",
+    request_body = WithdrawRequest<AccountRef>,
+    security(("bearer_auth" = [])),
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Idempotency key, instead of `idempotency_key` in the body")
+    ),
+    responses(
+        (status = 200, description = "Withdrawal successful", body = TransactionResponse),
+        (status = 400, description = "Insufficient funds or invalid request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Idempotency key already used for a different request"),
+        (status = 422, description = "Amount cap exceeded, purpose code not permitted, account dormant, frozen or closed, denied by the risk check, or refused by a transaction rule")
+    )
+)]
+async fn withdraw() {}
+
+/// Transfer money between accounts
+///
+/// Either account may be given by alias, e.g. `@alice-ops`. With `convert_currency`, a transfer into an account held in another
+/// currency is converted at the current exchange rate; the rate and credited
+/// amount are returned as `conversion`. With `quote_id`, it is converted at
+/// the rate locked by that quote instead. Repeating a request with the same
+/// idempotency key returns the first response unchanged.
+#[utoipa::path(
+    post,
+    path = "/api/transactions/transfer",
+    tag = "transactions",
+    request_body = TransferRequest<AccountRef>,
+    security(("bearer_auth" = [])),
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Idempotency key, instead of `idempotency_key` in the body")
+    ),
+    responses(
+        (status = 200, description = "Transfer successful", body = TransactionResponse),
+        (status = 400, description = "Insufficient funds, invalid accounts, or a quote that expired, was used, or does not match the transfer"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Quote not found"),
+        (status = 409, description = "Idempotency key already used for a different request"),
+        (status = 422, description = "Amount or balance cap exceeded, purpose code not permitted, account dormant, frozen or closed, denied by the risk check, or refused by a transaction rule"),
+        (status = 503, description = "Exchange rate provider unavailable")
+    )
+)]
+async fn transfer() {}
+
+/// Make many payments in one request
+///
+/// Each operation is a deposit, withdrawal or transfer tagged by `type`, with
+/// its own optional idempotency key; at most 1000 per batch. In `atomic` mode
+/// (the default) every operation is checked and then all are made together:
+/// if one fails it is reported as `failed` and the rest as `rolled_back`, and
+/// no money moves. In `best_effort` mode each operation is made on its own
+/// and failures do not stop the rest. Results come back in request order.
+#[utoipa::path(
+    post,
+    path = "/api/transactions/batch",
+    tag = "transactions",
+    request_body = BatchRequest<AccountRef>,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Per-operation results; check each item's `status`", body = BatchResponse),
+        (status = 400, description = "Empty or oversized batch, repeated idempotency key, or access denied to an account"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Account or alias not found")
+    )
+)]
+async fn batch() {}
+
+/// List a page of an account's transactions, newest first
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/transactions",
+    tag = "transactions",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = AccountRef, Path, description = "Account ID (UUID) or alias"),
+        TransactionListQuery
+    ),
+    responses(
+        (status = 200, description = "`{\"data\": [...], \"next_cursor\": ...}`; `next_cursor` is null on the last page"),
+        (status = 400, description = "Invalid cursor or filters"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Account not found")
+    )
+)]
+async fn list_transactions() {}
+
+/// Search transactions across accounts, newest first
+///
+/// Matches a case-insensitive fragment of the reference and any of the other
+/// filters; at least one must be set. Account-scoped keys only search their
+/// own account.
+#[utoipa::path(
+    get,
+    path = "/api/transactions/search",
+    tag = "transactions",
+    security(("bearer_auth" = [])),
+    params(TransactionSearchQuery),
+    responses(
+        (status = 200, description = "`{\"data\": [...], \"next_cursor\": ...}`; `next_cursor` is null on the last page"),
+        (status = 400, description = "No criteria, invalid cursor or filters, or access denied to the account"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Alias not found")
+    )
+)]
+async fn search_transactions() {}
+
+/// Record a payment received over external rails
+///
+/// Credits the account with a deposit for funds that arrived from outside,
+/// such as a bank transfer. Needs the `inbound:write` scope, held by
+/// integration keys. The payment is deduplicated on `external_reference`:
+/// reporting it again returns the original deposit with `duplicate: true`
+/// and credits nothing.
 #[utoipa::path(
-    get,
-    path = "/health",
-    tag = "health",
+    post,
+    path = "/api/inbound-payments",
+    tag = "transactions",
+    request_body = InboundPaymentRequest<AccountRef>,
+    security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "Service is healthy", body = inline(serde_json::Value), example = json!({"status": "healthy"}))
+        (status = 201, description = "`{\"transaction\": {...}, \"duplicate\": false}`; the account was credited"),
+        (status = 200, description = "`{\"transaction\": {...}, \"duplicate\": true}`; the payment was already recorded"),
+        (status = 400, description = "Missing or too long external reference, invalid request, or access denied to the account"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Key lacks the inbound:write scope"),
+        (status = 404, description = "Alias not found"),
+        (status = 409, description = "External reference already recorded with a different account, amount or currency"),
+        (status = 422, description = "Amount or balance cap exceeded, account closed, denied by the risk check, or refused by a transaction rule")
     )
 )]
-async fn health() {}
+async fn receive_inbound_payment() {}
 
-/// Bootstrap first API key
+/// Register a webhook endpoint (admin keys only)
 #[utoipa::path(
     post,
-    path = "/api/bootstrap",
-    tag = "auth",
-    request_body = BootstrapRequest,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    request_body = RegisterWebhookRequest,
+    security(("bearer_auth" = [])),
     responses(
-        (status = 201, description = "API key created successfully", body = BootstrapResponse),
-        (status = 400, description = "Bootstrap not allowed - API keys already exist")
+        (status = 201, description = "Webhook registered successfully", body = WebhookResponse),
+        (status = 400, description = "Invalid request or not an admin API key"),
+        (status = 401, description = "Unauthorized")
     )
 )]
-async fn bootstrap() {}
+async fn register_webhook() {}
 
-/// Create a new API key (requires authentication)
+/// Register several webhook endpoints at once (admin keys only)
+///
+/// Each endpoint has its own subscriptions, timeouts and signature algorithm
+/// and gets its own secret. Every item is checked first and the endpoints are
+/// registered in one transaction, so either all of them are registered or
+/// none is; a bad item is named by its index, as in `endpoints[2]: ...`.
 #[utoipa::path(
     post,
-    path = "/api/keys",
-    tag = "auth",
-    request_body = CreateApiKeyRequest,
+    path = "/api/webhooks/batch",
+    tag = "webhooks",
+    request_body = RegisterWebhooksRequest,
     security(("bearer_auth" = [])),
     responses(
-        (status = 201, description = "API key created", body = BootstrapResponse),
+        (status = 201, description = "Every endpoint registered, in request order", body = RegisterWebhooksResponse),
+        (status = 400, description = "Empty or oversized batch, an invalid item, or not an admin API key; nothing registered"),
         (status = 401, description = "Unauthorized")
     )
 )]
-async fn create_api_key() {}
+async fn register_webhooks() {}
 
-/// List all API keys (without exposing raw keys)
+/// Update a webhook endpoint (admin keys only)
+///
+/// `events` and `is_active` apply at once. A new `url` must be sent on its
+/// own: deliveries keep going to the current URL until a different admin key
+/// approves the change through `POST /api/admin/changes/{id}/approve`.
 #[utoipa::path(
-    get,
-    path = "/api/keys",
-    tag = "auth",
+    patch,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    request_body = UpdateWebhookRequest,
     security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Webhook endpoint ID (UUID)")
+    ),
     responses(
-        (status = 200, description = "List of API keys", body = Vec<ApiKeyInfo>),
-        (status = 401, description = "Unauthorized")
+        (status = 200, description = "Events, active flag or signature algorithm updated", body = WebhookResponse),
+        (status = 202, description = "URL change requested, awaiting approval", body = ChangeRequest),
+        (status = 400, description = "Empty URL, URL sent with other changes, nothing to update, invalid event pattern or not an admin API key"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Webhook endpoint not found")
     )
 )]
-async fn list_api_keys() {}
+async fn update_webhook() {}
 
-/// Delete (deactivate) an API key
+/// Delete a webhook endpoint (admin keys only)
+///
+/// Events already stored for the endpoint stay readable through
+/// `GET /api/webhooks/events/{id}`.
 #[utoipa::path(
     delete,
-    path = "/api/keys/{id}",
-    tag = "auth",
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
     security(("bearer_auth" = [])),
     params(
-        ("id" = String, Path, description = "API key ID (UUID)")
+        ("id" = String, Path, description = "Webhook endpoint ID (UUID)")
     ),
     responses(
-        (status = 204, description = "API key deleted"),
-        (status = 404, description = "API key not found"),
-        (status = 401, description = "Unauthorized")
+        (status = 204, description = "Webhook endpoint deleted"),
+        (status = 400, description = "Invalid ID or not an admin API key"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Webhook endpoint not found")
     )
 )]
-async fn delete_api_key() {}
-
-/// Create a new account
+async fn delete_webhook() {}
 
+/// Give a webhook endpoint a new signing secret (admin keys only)
+///
+/// Deliveries are signed with the new secret from now on; the old one stops
+/// working at once.
 #[utoipa::path(
     post,
-    path = "/api/accounts",
-    tag = "accounts",
-    request_body = CreateAccountRequest,
+    path = "/api/webhooks/{id}/rotate-secret",
+    tag = "webhooks",
     security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Webhook endpoint ID (UUID)")
+    ),
     responses(
-        (status = 201, description = "Account created successfully", body = AccountResponse),
-        (status = 400, description = "Invalid request"),
+        (status = 200, description = "Secret rotated", body = WebhookResponse),
+        (status = 400, description = "Invalid ID or not an admin API key"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Webhook endpoint not found")
+    )
+)]
+async fn rotate_webhook_secret() {}
+
+/// List all webhook endpoints (admin keys only)
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "List of webhook endpoints", body = Vec<WebhookResponse>),
+        (status = 400, description = "Not an admin API key"),
         (status = 401, description = "Unauthorized")
     )
 )]
-async fn create_account() {}
+async fn list_webhooks() {}
 
-/// List all accounts
+/// List the event types webhooks can subscribe to
 #[utoipa::path(
     get,
-    path = "/api/accounts",
-    tag = "accounts",
+    path = "/api/webhooks/events",
+    tag = "webhooks",
     security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "List of accounts", body = Vec<AccountResponse>),
+        (status = 200, description = "Event catalog with payload fields", body = Vec<EventSpec>),
         (status = 401, description = "Unauthorized")
     )
 )]
-async fn list_accounts() {}
+async fn list_event_types() {}
 
-/// Get account by ID
+/// Get a webhook event with its full payload (admin keys only)
+///
+/// Payloads over 64 KiB are delivered as a stub
+/// `{"payload_truncated": true, "event_id": ..., "payload_bytes": ...}`;
+/// receivers fetch the full payload here.
 #[utoipa::path(
     get,
-    path = "/api/accounts/{id}",
-    tag = "accounts",
+    path = "/api/webhooks/events/{id}",
+    tag = "webhooks",
     security(("bearer_auth" = [])),
     params(
-        ("id" = AccountId, Path, description = "Account ID (UUID)")
+        ("id" = String, Path, description = "Webhook event ID (UUID)")
     ),
     responses(
-        (status = 200, description = "Account details", body = AccountResponse),
-        (status = 404, description = "Account not found"),
+        (status = 200, description = "Webhook event", body = WebhookEventResponse),
+        (status = 400, description = "Invalid ID or not an admin key"),
+        (status = 404, description = "Webhook event not found"),
         (status = 401, description = "Unauthorized")
     )
 )]
-async fn get_account() {}
+async fn get_webhook_event() {}
 
-/// Deposit money into an account
+/// Export dead-lettered webhook events (admin keys only)
+///
+/// Returns every dead-lettered event matching the filter, oldest first, with
+/// its full payload, e.g. to replay by hand after a consumer outage.
 #[utoipa::path(
-    post,
-    path = "/api/transactions/deposit",
-    tag = "transactions",
-    request_body = DepositRequest,
+    get,
+    path = "/api/webhooks/events/dead-letters",
+    tag = "webhooks",
     security(("bearer_auth" = [])),
+    params(DeadLetterQuery),
     responses(
-        (status = 200, description = "Deposit successful", body = TransactionResponse),
-        (status = 400, description = "Invalid request"),
+        (status = 200, description = "Dead-lettered events, oldest first", body = Vec<WebhookEventResponse>),
+        (status = 400, description = "Invalid filter or not an admin key"),
         (status = 401, description = "Unauthorized")
     )
 )]
-async fn deposit() {}
+async fn list_dead_lettered_webhooks() {}
 
-/// Withdraw money from an account
+/// Retry dead-lettered webhook events in bulk (admin keys only)
+///
+/// Every dead-lettered event matching the filter is queued for delivery
+/// again, due now and with its attempts reset, so it gets the full retry
+/// budget. An empty filter `{}` requeues every dead letter.
 #[utoipa::path(
     post,
-    path = "/api/transactions/withdraw",
-    tag = "transactions",
-    request_body = WithdrawRequest,
+    path = "/api/webhooks/events/retry",
+    tag = "webhooks",
     security(("bearer_auth" = [])),
+    request_body = DeadLetterQuery,
     responses(
-        (status = 200, description = "Withdrawal successful", body = TransactionResponse),
-        (status = 400, description = "Insufficient funds or invalid request"),
+        (status = 200, description = "Number of events requeued", body = DeadLetterRetryResponse),
+        (status = 400, description = "Invalid filter or not an admin key"),
         (status = 401, description = "Unauthorized")
     )
 )]
-async fn withdraw() {}
+async fn retry_dead_lettered_webhooks() {}
 
-/// Transfer money between accounts
+/// Stream events live as Server-Sent Events
+///
+/// Holds the response open and sends each account and transaction event as
+/// it happens, named by its event type (as listed by `GET /api/webhooks/events`)
+/// with its webhook payload as data. Keys scoped to an account only receive
+/// events about that account. A client that falls too far behind receives a
+/// `lagged` event with the number of events it missed; reconcile through the
+/// list endpoints. Idle streams get a keep-alive comment every 15 seconds.
 #[utoipa::path(
-    post,
-    path = "/api/transactions/transfer",
-    tag = "transactions",
-    request_body = TransferRequest,
+    get,
+    path = "/api/stream",
+    tag = "events",
     security(("bearer_auth" = [])),
+    params(EventStreamQuery),
     responses(
-        (status = 200, description = "Transfer successful", body = TransactionResponse),
-        (status = 400, description = "Insufficient funds or invalid accounts"),
-        (status = 401, description = "Unauthorized")
+        (status = 200, description = "Stream of events", body = String, content_type = "text/event-stream"),
+        (status = 400, description = "Invalid event pattern"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Key lacks the transactions:read scope")
     )
 )]
-async fn transfer() {}
+async fn stream_events() {}
 
-/// Register a webhook endpoint
+/// List an endpoint's events after a delivery sequence (admin keys only)
+///
+/// Deliveries carry the endpoint's `X-Webhook-Delivery-Sequence`, counting
+/// from 1, and `X-Webhook-Delivery-Signature`, a signature of
+/// `{sequence}.{body}` made with the algorithm named in
+/// `X-Webhook-Signature-Algorithm`. Receivers that see a gap
+/// fetch the missed events here, lowest sequence first; a repeated sequence
+/// is a duplicate delivery.
 #[utoipa::path(
-    post,
-    path = "/api/webhooks",
+    get,
+    path = "/api/webhooks/{id}/events",
     tag = "webhooks",
-    request_body = RegisterWebhookRequest,
     security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Webhook endpoint ID (UUID)"),
+        WebhookEventsQuery
+    ),
     responses(
-        (status = 201, description = "Webhook registered successfully", body = WebhookResponse),
-        (status = 400, description = "Invalid request"),
+        (status = 200, description = "Events after the sequence, lowest first", body = Vec<WebhookEventResponse>),
+        (status = 400, description = "Invalid ID or not an admin key"),
+        (status = 404, description = "Webhook endpoint not found"),
         (status = 401, description = "Unauthorized")
     )
 )]
-async fn register_webhook() {}
+async fn list_webhook_events() {}
 
-/// List all webhook endpoints
+/// List an endpoint's delivery log (admin keys only)
+///
+/// Every event queued for the endpoint, most recent first, with its status,
+/// attempts so far and the error from the last failed attempt. Payloads are
+/// left out; fetch one with `GET /api/webhooks/events/{id}`. To page back,
+/// pass the last `delivery_sequence` seen as `before_sequence`.
 #[utoipa::path(
     get,
-    path = "/api/webhooks",
+    path = "/api/webhooks/{id}/deliveries",
     tag = "webhooks",
     security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Webhook endpoint ID (UUID)"),
+        WebhookDeliveriesQuery
+    ),
     responses(
-        (status = 200, description = "List of webhook endpoints", body = Vec<WebhookResponse>),
+        (status = 200, description = "Deliveries, most recent first", body = Vec<WebhookDeliveryResponse>),
+        (status = 400, description = "Invalid ID or status, or not an admin key"),
+        (status = 404, description = "Webhook endpoint not found"),
         (status = 401, description = "Unauthorized")
     )
 )]
-async fn list_webhooks() {}
+async fn list_webhook_deliveries() {}
+
+/// Redeliver a failed webhook event (admin keys only)
+///
+/// Queues a `FAILED` or `DEAD_LETTERED` event for delivery again, due now
+/// and with its attempts reset so it gets the full retry budget. Events
+/// that are pending, in flight or delivered are rejected.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/deliveries/{id}/redeliver",
+    tag = "webhooks",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Webhook event ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Event requeued", body = WebhookDeliveryResponse),
+        (status = 400, description = "Invalid ID, event not failed, or not an admin key"),
+        (status = 404, description = "Webhook event not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn redeliver_webhook_event() {}
 
 /// Get exchange rates for a base currency
 #[utoipa::path(
@@ -214,7 +2106,7 @@ async fn list_webhooks() {}
     path = "/api/rates/{base}",
     tag = "rates",
     params(
-        ("base" = String, Path, description = "Base currency (USD, EUR, GBP, INR)")
+        ("base" = String, Path, description = "Base currency: USD, EUR, GBP, INR or one registered via `CURRENCIES`")
     ),
     responses(
         (status = 200, description = "Exchange rates", body = ExchangeRateResponse),
@@ -236,6 +2128,202 @@ async fn get_rates() {}
 )]
 async fn convert() {}
 
+/// Get the maintenance mode state
+#[utoipa::path(
+    get,
+    path = "/api/admin/maintenance",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current maintenance mode state", body = MaintenanceStatus),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_maintenance() {}
+
+/// Switch read-only maintenance mode on or off (admin keys only)
+///
+/// While enabled, mutating requests return 503 with `"error_code": "MAINTENANCE_MODE"`.
+#[utoipa::path(
+    put,
+    path = "/api/admin/maintenance",
+    tag = "admin",
+    request_body = SetMaintenanceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated maintenance mode state", body = MaintenanceStatus),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn set_maintenance() {}
+
+/// Post or clear the incident banner shown on `/status` (admin keys only)
+///
+/// A missing or blank `message` clears the banner.
+#[utoipa::path(
+    put,
+    path = "/api/admin/incident",
+    tag = "admin",
+    request_body = SetIncidentRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Status page with the updated banner", body = ServiceStatus),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn set_incident() {}
+
+/// Prometheus metrics for this instance (admin keys only)
+///
+/// Request counts and latency histograms per route, completed transactions
+/// and volume per currency, and webhook deliveries by outcome, in the
+/// Prometheus text exposition format. Counts start at zero when the instance
+/// starts.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Metrics in the Prometheus text format", body = String, content_type = "text/plain"),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn metrics() {}
+
+/// Database latency, schema version, webhook backlog and rate limit
+/// saturation (admin keys only)
+///
+/// `status` is `degraded` and `problems` lists why when the database is not
+/// reachable, answers slower than 250 ms, more than 1000 webhook events are
+/// waiting, or an API key has used more than 90% of its quota.
+#[utoipa::path(
+    get,
+    path = "/api/admin/diagnostics",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Diagnostics for this instance", body = Diagnostics),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn diagnostics() {}
+
+/// Storage row counts against soft quotas (admin keys only)
+///
+/// Counts accounts, transactions and webhook events. A table with a quota
+/// reports the fraction used and whether it has reached the warning
+/// threshold; the quota job raises `quota.warning` when it does.
+#[utoipa::path(
+    get,
+    path = "/api/admin/storage",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Row counts and quota usage", body = StorageReport),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn storage_report() {}
+
+/// Record end-of-day balances now (admin keys only)
+///
+/// Runs the balance snapshot job once: every account gets a closing balance
+/// for each ended day since its last recorded one, back to the day it was
+/// opened when it has none.
+#[utoipa::path(
+    post,
+    path = "/api/admin/balance-snapshots",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Number of balances recorded", body = BalanceSnapshotResponse),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn record_balance_snapshots() {}
+
+/// List security changes (admin keys only)
+///
+/// Key deletions and webhook URL changes, newest first. Decided changes are
+/// kept, so this is also the audit log of who requested and who approved
+/// or rejected each one.
+#[utoipa::path(
+    get,
+    path = "/api/admin/changes",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    params(ChangeRequestQuery),
+    responses(
+        (status = 200, description = "Change requests", body = Vec<ChangeRequest>),
+        (status = 400, description = "Not an admin API key"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn list_changes() {}
+
+/// Get a security change (admin keys only)
+#[utoipa::path(
+    get,
+    path = "/api/admin/changes/{id}",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Change request ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Change request", body = ChangeRequest),
+        (status = 400, description = "Invalid ID or not an admin API key"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Change request not found")
+    )
+)]
+async fn get_change() {}
+
+/// Approve and apply a pending security change (admin keys only)
+///
+/// The approving key must differ from the key that requested the change.
+#[utoipa::path(
+    post,
+    path = "/api/admin/changes/{id}/approve",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Change request ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Change applied", body = ChangeRequest),
+        (status = 400, description = "Already decided, requested by the same key, or not an admin API key"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Change request or its target not found")
+    )
+)]
+async fn approve_change() {}
+
+/// Reject a pending security change without applying it (admin keys only)
+#[utoipa::path(
+    post,
+    path = "/api/admin/changes/{id}/reject",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Change request ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Change rejected", body = ChangeRequest),
+        (status = 400, description = "Already decided or not an admin API key"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Change request not found")
+    )
+)]
+async fn reject_change() {}
+
 /// OpenAPI documentation for the Payments API.
 #[derive(OpenApi)]
 #[openapi(
@@ -246,56 +2334,294 @@ async fn convert() {}
         license(name = "MIT"),
     ),
     paths(
+        live,
         health,
+        service_status,
+        ready,
+        readyz,
         bootstrap,
         create_api_key,
         list_api_keys,
         delete_api_key,
+        set_api_key_rate_limit,
+        get_api_key_audit,
+        get_api_key_usage,
+        create_session_token,
         create_account,
         list_accounts,
+        search_accounts,
         get_account,
+        update_account,
+        list_account_aliases,
+        add_account_alias,
+        remove_account_alias,
+        get_account_alias,
+        list_memo_keys,
+        add_memo_key,
+        get_memo_key,
+        retire_memo_key,
+        list_owners,
+        create_owner,
+        get_owner,
+        update_owner,
+        delete_owner,
+        get_spending_rules,
+        set_spending_rules,
+        get_account_limits,
+        set_account_limits,
+        reactivate_account,
+        freeze_account,
+        unfreeze_account,
+        close_account,
+        merge_account,
+        get_account_fees,
+        assign_fee_schedule,
+        quote_fee,
+        create_fee_schedule,
+        list_fee_schedules,
+        get_fee_schedule,
+        create_settlement_batch,
+        list_settlement_batches,
+        get_settlement_batch,
+        update_settlement_batch_status,
+        export_settlement_batch,
+        export_journal,
+        export_event_log,
+        create_export,
+        get_export,
+        download_export,
+        exposure_report,
+        float_report,
+        create_scheduled_payment,
+        list_scheduled_payments,
+        get_scheduled_payment,
+        pause_scheduled_payment,
+        resume_scheduled_payment,
+        authorize,
+        capture_hold,
+        void_hold,
+        get_hold,
+        list_holds,
+        create_quote,
+        get_quote,
+        create_beneficiary,
+        get_beneficiary,
+        verify_beneficiary,
+        list_beneficiaries,
+        get_statement_email,
+        set_statement_email,
+        list_statements,
+        get_account_statement,
+        get_balance_history,
+        get_statement,
+        issue_statements,
+        download_statement,
         deposit,
         withdraw,
         transfer,
+        batch,
+        list_transactions,
+        search_transactions,
+        receive_inbound_payment,
         register_webhook,
+        register_webhooks,
+        update_webhook,
+        delete_webhook,
+        rotate_webhook_secret,
         list_webhooks,
+        list_event_types,
+        get_webhook_event,
+        list_webhook_events,
+        list_webhook_deliveries,
+        redeliver_webhook_event,
+        list_dead_lettered_webhooks,
+        retry_dead_lettered_webhooks,
+        stream_events,
         get_rates,
         convert,
+        get_maintenance,
+        set_maintenance,
+        set_incident,
+        metrics,
+        diagnostics,
+        storage_report,
+        record_balance_snapshots,
+        list_changes,
+        get_change,
+        approve_change,
+        reject_change,
     ),
     components(
         schemas(
             CreateAccountRequest,
             AccountResponse,
+            MergeAccountRequest,
+            AccountMergeResponse,
+            AccountStatus,
             DepositRequest,
             WithdrawRequest,
             TransferRequest,
+            InboundPaymentRequest,
+            BatchMode,
+            BatchItemStatus,
+            BatchItemResult,
+            BatchResponse,
             TransactionResponse,
             TransactionStatus,
+            Warning,
+            RiskAssessment,
+            RiskDecision,
             RegisterWebhookRequest,
+            RegisterWebhooksRequest,
+            RegisterWebhooksResponse,
             WebhookResponse,
+            WebhookSignatureAlgorithm,
+            WebhookEventResponse,
+            WebhookDeliveryResponse,
+            DeadLetterQuery,
+            DeadLetterRetryResponse,
             CurrencyCode,
             AccountId,
-
+            AccountRef,
+            Alias,
+            AccountAlias,
+            AddAliasRequest,
+            MemoKeyId,
+            MemoKey,
+            CreateMemoKeyRequest,
+            OwnerId,
+            Owner,
+            CreateOwnerRequest,
+            UpdateOwnerRequest,
+            EncryptedMemo,
+            Counterparty,
+            FxConversion,
+            SpendingRules,
+            AccountLimits,
+            FeeTier,
+            FeeSchedule,
+            FeeScheduleId,
+            FeeAssignment,
+            CreateFeeScheduleRequest,
+            AssignFeeScheduleRequest,
+            AccountFees,
+            FeeQuote,
+            SettlementBatch,
+            SettlementBatchId,
+            SettlementBatchStatus,
+            CurrencyTotal,
+            CreateSettlementBatchRequest,
+            UpdateAccountRequest,
+            UpdateSettlementBatchStatusRequest,
+            SettlementExportFormat,
+            JournalExportFormat,
+            Export,
+            ExportId,
+            ExportRequest,
+            ExportStatus,
+            ExportDownload,
+            LoggedEvent,
+            ExposureReport,
+            FloatFlow,
+            FloatPosition,
+            FloatReport,
+            CurrencyExposure,
+            ScheduledPayment,
+            ScheduledPaymentId,
+            ScheduledPaymentStatus,
+            PaymentSchedule,
+            CreateScheduledPaymentRequest,
+            Hold,
+            HoldId,
+            HoldStatus,
+            AuthorizeRequest,
+            CaptureRequest,
+            Quote,
+            QuoteId,
+            CreateQuoteRequest,
+            Beneficiary,
+            BeneficiaryId,
+            BeneficiaryStatus,
+            CreateBeneficiaryRequest,
+            VerifyBeneficiaryRequest,
+            ApiKeyUsage,
+            ApiKeyAudit,
+            ApiKeyAuditEntry,
+            ApiKeyAuditAction,
+            UsageWindow,
+            VolumeTotal,
+            TransactionType,
+            Statement,
+            StatementId,
+            StatementDownload,
+            AccountStatement,
+            StatementLine,
+            StatementFormat,
+            StatementEmail,
+            IssueStatementsRequest,
+            BalanceHistory,
+            DailyBalance,
+            BalanceSnapshotResponse,
             TransactionId,
             WebhookEndpointId,
+            EventSpec,
+            EventField,
             BootstrapRequest,
             BootstrapResponse,
             CreateApiKeyRequest,
             ApiKeyInfo,
+            SetApiKeyRateLimitRequest,
+            ApiKeyScope,
+            Mode,
+            CreateSessionTokenRequest,
+            SessionToken,
             ExchangeRateResponse,
             ConvertRequest,
             ConvertResponse,
+            SetMaintenanceRequest,
+            MaintenanceStatus,
+            SetIncidentRequest,
+            ServiceHealth,
+            Incident,
+            ServiceStatus,
+            Diagnostics,
+            StorageReport,
+            StorageUsage,
+            StorageResource,
+            Readiness,
+            ReadinessStatus,
+            DependencyCheck,
+            DependencyStatus,
+            ChangeRequest,
+            ChangeRequestId,
+            ChangeAction,
+            ChangeStatus,
+            UpdateWebhookRequest,
+            ProblemDetails,
+            ErrorCode,
         )
     ),
 
-    modifiers(&SecurityAddon),
+    modifiers(&SecurityAddon, &IdempotencyAddon, &ModeAddon, &ProblemDetailsAddon),
     tags(
         (name = "health", description = "Health check endpoints"),
-        (name = "auth", description = "API key management"),
+        (name = "auth", description = "API key management and scopes"),
         (name = "accounts", description = "Account management operations"),
+        (name = "owners", description = "Customers that hold accounts"),
         (name = "transactions", description = "Deposit, withdraw, and transfer operations"),
         (name = "webhooks", description = "Webhook endpoint management"),
+        (name = "events", description = "Live stream and replayable log of account and transaction events"),
+        (name = "fees", description = "Fee schedules and their assignment to accounts"),
+        (name = "settlement", description = "Settlement batches of outgoing payouts (admin keys only)"),
+        (name = "accounting", description = "Journal exports for accounting tools (admin keys only)"),
+        (name = "exports", description = "Files rendered in the background and fetched through signed links (admin keys only)"),
+        (name = "reports", description = "Treasury reports across all accounts (admin keys only)"),
+        (name = "scheduled-payments", description = "Recurring withdrawals and transfers made by the scheduler"),
+        (name = "holds", description = "Two-phase payments: authorize a hold, then capture or void it"),
+        (name = "beneficiaries", description = "External accounts paid out to, verified by micro-deposits"),
+        (name = "statements", description = "Monthly account statements and their delivery"),
         (name = "rates", description = "Exchange rate operations"),
+        (name = "admin", description = "Operational controls (admin keys only)"),
     )
 )]
 pub struct ApiDoc;
@@ -313,3 +2639,341 @@ impl Modify for SecurityAddon {
         }
     }
 }
+
+/// Documents the `Idempotency-Key` header, and the `409` a reused key gets,
+/// on every authenticated `POST`.
+struct IdempotencyAddon;
+
+impl Modify for IdempotencyAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            let Some(operation) = item.post.as_mut() else {
+                continue;
+            };
+            if operation
+                .security
+                .as_ref()
+                .is_none_or(|security| security.is_empty())
+            {
+                continue;
+            }
+            let parameters = operation.parameters.get_or_insert_with(Vec::new);
+            if !parameters.iter().any(|p| p.name == "Idempotency-Key") {
+                parameters.push(
+                    ParameterBuilder::new()
+                        .name("Idempotency-Key")
+                        .parameter_in(ParameterIn::Header)
+                        .required(Required::False)
+                        .description(Some(
+                            "Replays the first successful response to a request sent with this key",
+                        ))
+                        .schema(Some(ObjectBuilder::new().schema_type(Type::String)))
+                        .build(),
+                );
+            }
+            operation
+                .responses
+                .responses
+                .entry("409".into())
+                .or_insert_with(|| {
+                    Response::new("Idempotency key reused for a different request").into()
+                });
+        }
+    }
+}
+
+/// Documents the `X-Payments-Mode` header on every response to an
+/// authenticated request.
+struct ModeAddon;
+
+impl Modify for ModeAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            for (_, operation) in operations(item) {
+                if operation
+                    .security
+                    .as_ref()
+                    .is_none_or(|security| security.is_empty())
+                {
+                    continue;
+                }
+                for (status, response) in operation.responses.responses.iter_mut() {
+                    let RefOr::T(response) = response else {
+                        continue;
+                    };
+                    if status == "401" {
+                        continue;
+                    }
+                    response.headers.insert(
+                        MODE_HEADER.into(),
+                        HeaderBuilder::new()
+                            .schema(Ref::from_schema_name("Mode"))
+                            .description(Some("Mode of the calling API key"))
+                            .build(),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// The schema every error response points at, an alias of
+/// [`ProblemDetails`] so clients share one error type.
+const ERROR_RESPONSE: &str = "ErrorResponse";
+
+/// Documents the [`ERROR_RESPONSE`] body, with examples, on every error
+/// response, and the `429` every rate-limited route can return.
+struct ProblemDetailsAddon;
+
+impl Modify for ProblemDetailsAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.schemas.insert(
+                ERROR_RESPONSE.into(),
+                Ref::from_schema_name("ProblemDetails").into(),
+            );
+        }
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            for (method, operation) in operations(item) {
+                let authenticated = operation
+                    .security
+                    .as_ref()
+                    .is_some_and(|security| !security.is_empty());
+                let rate_limited = path == STATUS_PATH || authenticated;
+                let responses = &mut operation.responses.responses;
+                if rate_limited {
+                    responses
+                        .entry("429".into())
+                        .or_insert_with(|| Response::new("Rate limit exceeded").into());
+                }
+                let moves_money = method == "post" && path.starts_with("/api/transactions/");
+                let idempotent = method == "post" && authenticated;
+                for (status, response) in responses.iter_mut() {
+                    let RefOr::T(response) = response else {
+                        continue;
+                    };
+                    if !(status.starts_with('4') || status.starts_with('5'))
+                        || !response.content.is_empty()
+                    {
+                        continue;
+                    }
+                    let examples = problem_examples(status, moves_money, idempotent)
+                        .into_iter()
+                        .map(|(name, summary, value)| {
+                            let example = ExampleBuilder::new()
+                                .summary(summary)
+                                .value(Some(value))
+                                .build();
+                            (name, example)
+                        });
+                    let content = ContentBuilder::new()
+                        .schema(Some(Ref::from_schema_name(ERROR_RESPONSE)))
+                        .examples_from_iter(examples)
+                        .build();
+                    response.content.insert("application/json".into(), content);
+                }
+            }
+        }
+    }
+}
+
+/// The operations of a path with their lowercase method names.
+fn operations(item: &mut PathItem) -> impl Iterator<Item = (&'static str, &mut Operation)> {
+    [
+        ("get", item.get.as_mut()),
+        ("put", item.put.as_mut()),
+        ("post", item.post.as_mut()),
+        ("delete", item.delete.as_mut()),
+        ("patch", item.patch.as_mut()),
+    ]
+    .into_iter()
+    .filter_map(|(method, operation)| operation.map(|operation| (method, operation)))
+}
+
+/// Named examples of the error bodies a response with `status` can carry.
+fn problem_examples(
+    status: &str,
+    moves_money: bool,
+    idempotent: bool,
+) -> Vec<(&'static str, &'static str, Value)> {
+    let mut examples = Vec::new();
+    match status {
+        "400" => {
+            examples.push((
+                "validation_error",
+                "Invalid request",
+                json!({"error": "Cannot transfer to the same account", "code": 400, "error_code": "INVALID_REQUEST"}),
+            ));
+            if moves_money {
+                examples.push((
+                    "invalid_amount",
+                    "Amount is zero or negative",
+                    json!({
+                        "error": "Invalid amount: Amount must be positive, got 0",
+                        "code": 400,
+                        "error_code": "INVALID_AMOUNT"
+                    }),
+                ));
+                examples.push((
+                    "insufficient_funds",
+                    "Not enough available balance",
+                    json!({
+                        "error": "Insufficient funds: available 500, requested 1000",
+                        "code": 400,
+                        "error_code": "INSUFFICIENT_FUNDS"
+                    }),
+                ));
+            }
+        }
+        "401" => examples.push((
+            "unauthorized",
+            "Missing or invalid API key",
+            json!({"error": "Invalid API key", "code": 401, "error_code": "UNAUTHORIZED"}),
+        )),
+        "403" => examples.push((
+            "insufficient_scope",
+            "API key lacks the route's scope",
+            json!({
+                "error": "API key is missing the transactions:write scope",
+                "code": 403,
+                "error_code": "INSUFFICIENT_SCOPE",
+                "required_scope": "transactions:write"
+            }),
+        )),
+        "404" => examples.push((
+            "not_found",
+            "No such resource",
+            json!({
+                "error": "Account not found: 6f2c9a4e-4f7b-4b0e-9a57-0d1e2c3b4a59",
+                "code": 404,
+                "error_code": "ACCOUNT_NOT_FOUND"
+            }),
+        )),
+        "409" => {
+            examples.push((
+                "conflict",
+                "Concurrent change; retry the request",
+                json!({
+                    "error": "Account balance changed, please retry",
+                    "code": 409,
+                    "error_code": "CONFLICT"
+                }),
+            ));
+            if idempotent {
+                examples.push((
+                    "idempotency_key_conflict",
+                    "Idempotency key reused for a different request",
+                    json!({
+                        "error": "Idempotency key conflict: key order-1 was already used with different parameters",
+                        "code": 409,
+                        "error_code": "IDEMPOTENCY_KEY_CONFLICT"
+                    }),
+                ));
+            }
+        }
+        "422" => {
+            examples.push((
+                "limit_exceeded",
+                "Refused by a limit, rule or account state",
+                json!({
+                    "error": "Amount 50000 exceeds the maximum transaction amount of 10000",
+                    "code": 422,
+                    "error_code": "AMOUNT_LIMIT_EXCEEDED"
+                }),
+            ));
+            if moves_money {
+                examples.push((
+                    "hourly_transaction_limit",
+                    "Refused by the hourly transaction limit; also sent as `transaction.blocked`",
+                    json!({
+                        "error": "Hourly transaction limit exceeded: 20 in the past hour, limit 20",
+                        "code": 422,
+                        "error_code": "HOURLY_TRANSACTION_LIMIT_EXCEEDED"
+                    }),
+                ));
+            }
+        }
+        "429" => examples.push((
+            "rate_limited",
+            "Too many requests",
+            json!({
+                "error": "Rate limit exceeded. Please try again later.",
+                "code": 429,
+                "error_code": "RATE_LIMITED",
+                "retry_after_seconds": 60
+            }),
+        )),
+        "503" => examples.push((
+            "maintenance_mode",
+            "Service is read-only",
+            json!({
+                "error": "Service is in read-only maintenance mode, please retry later",
+                "code": 503,
+                "error_code": "MAINTENANCE_MODE",
+                "reason": "database upgrade"
+            }),
+        )),
+        _ => {}
+    }
+    examples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_error_response_documents_the_error_response() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for (method, operation) in item.as_object().unwrap() {
+                let responses = operation["responses"].as_object().unwrap();
+                if operation.get("security").is_some() {
+                    assert!(responses.contains_key("429"), "{method} {path}");
+                }
+                for (status, response) in responses {
+                    let is_error = status.starts_with('4') || status.starts_with('5');
+                    // The readiness probe answers 503 with its own report
+                    if is_error && !["/health/ready", "/readyz"].contains(&path.as_str()) {
+                        assert_eq!(
+                            response["content"]["application/json"]["schema"]["$ref"],
+                            "#/components/schemas/ErrorResponse",
+                            "{method} {path} {status}"
+                        );
+                    }
+                }
+            }
+        }
+
+        let ready = &spec["paths"]["/health/ready"]["get"]["responses"];
+        assert_eq!(
+            ready["503"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Readiness"
+        );
+
+        let withdraw = &spec["paths"]["/api/transactions/withdraw"]["post"]["responses"];
+        let examples = &withdraw["400"]["content"]["application/json"]["examples"];
+        assert_eq!(examples["insufficient_funds"]["value"]["code"], json!(400));
+        assert_eq!(
+            examples["insufficient_funds"]["value"]["error_code"],
+            "INSUFFICIENT_FUNDS"
+        );
+        assert!(examples["validation_error"].is_object());
+
+        let schemas = &spec["components"]["schemas"];
+        assert_eq!(
+            schemas["ErrorResponse"]["$ref"],
+            "#/components/schemas/ProblemDetails"
+        );
+        assert_eq!(
+            schemas["ProblemDetails"]["properties"]["error_code"]["$ref"],
+            "#/components/schemas/ErrorCode"
+        );
+        assert!(
+            schemas["ErrorCode"]["enum"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("CROSS_CURRENCY_TRANSFER"))
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Built-in payout providers.
+//!
+//! [`PaymentService`](crate::PaymentService) sends payouts through
+//! [`SimulatedPayouts`] unless the builder is given another
+//! [`PayoutProvider`]. [`StubBankPayouts`] marks where a real bank client
+//! plugs in.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::TimeDelta;
+use payments_types::{Clock, Payout, PayoutError, PayoutInstruction, PayoutProvider, PayoutStatus};
+
+use crate::settlement::looks_like_iban;
+
+/// How long a simulated payout stays pending unless configured otherwise.
+pub const DEFAULT_SIMULATED_SETTLEMENT: TimeDelta = TimeDelta::minutes(1);
+
+/// Pays out in memory: every payout completes once it has been pending for
+/// the settlement delay, unless it is cancelled first.
+///
+/// Payouts are forgotten on restart; meant for development and tests.
+pub struct SimulatedPayouts {
+    clock: Arc<dyn Clock>,
+    settle_after: TimeDelta,
+    payouts: Mutex<HashMap<String, Payout>>,
+}
+
+impl SimulatedPayouts {
+    /// Settles payouts [`DEFAULT_SIMULATED_SETTLEMENT`] after `clock` says
+    /// they were made.
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            settle_after: DEFAULT_SIMULATED_SETTLEMENT,
+            payouts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how long payouts stay pending; zero completes them at once.
+    pub fn with_settle_after(mut self, settle_after: TimeDelta) -> Self {
+        self.settle_after = settle_after;
+        self
+    }
+
+    /// Completes `payout` if it has been pending for the settlement delay.
+    fn settle(&self, payout: &mut Payout) {
+        if payout.status == PayoutStatus::Pending
+            && self.clock.now() - payout.initiated_at >= self.settle_after
+        {
+            payout.status = PayoutStatus::Completed;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PayoutProvider for SimulatedPayouts {
+    async fn initiate(&self, instruction: &PayoutInstruction) -> Result<Payout, PayoutError> {
+        if instruction.beneficiary.external_id.is_none() {
+            return Err(PayoutError::Rejected(
+                "Beneficiary has no account to pay into".into(),
+            ));
+        }
+        let reference = format!("sim_{}", instruction.transaction_id);
+        let mut payouts = self.payouts.lock().unwrap();
+        let payout = payouts.entry(reference.clone()).or_insert_with(|| Payout {
+            provider_reference: reference,
+            transaction_id: instruction.transaction_id,
+            status: PayoutStatus::Pending,
+            initiated_at: self.clock.now(),
+        });
+        self.settle(payout);
+        Ok(payout.clone())
+    }
+
+    async fn status(&self, provider_reference: &str) -> Result<Payout, PayoutError> {
+        let mut payouts = self.payouts.lock().unwrap();
+        let payout = payouts
+            .get_mut(provider_reference)
+            .ok_or_else(|| PayoutError::NotFound(provider_reference.to_string()))?;
+        self.settle(payout);
+        Ok(payout.clone())
+    }
+
+    async fn cancel(&self, provider_reference: &str) -> Result<Payout, PayoutError> {
+        let mut payouts = self.payouts.lock().unwrap();
+        let payout = payouts
+            .get_mut(provider_reference)
+            .ok_or_else(|| PayoutError::NotFound(provider_reference.to_string()))?;
+        self.settle(payout);
+        match payout.status {
+            PayoutStatus::Pending => payout.status = PayoutStatus::Cancelled,
+            PayoutStatus::Cancelled => {}
+            _ => {
+                return Err(PayoutError::NotCancellable(provider_reference.to_string()));
+            }
+        }
+        Ok(payout.clone())
+    }
+}
+
+/// Stand-in for a bank's payout API.
+///
+/// Checks instructions the way the bank would, paying only to IBANs, then
+/// reports the bank unavailable: it is the place to write the bank client,
+/// and lets a deployment be wired to it before that client exists.
+#[derive(Debug, Clone)]
+pub struct StubBankPayouts {
+    bank: String,
+}
+
+impl StubBankPayouts {
+    /// A stub for the bank named `bank`, used in error messages.
+    pub fn new(bank: impl Into<String>) -> Self {
+        Self { bank: bank.into() }
+    }
+
+    fn unavailable(&self) -> PayoutError {
+        PayoutError::Unavailable(format!("{} payout API is not connected", self.bank))
+    }
+}
+
+#[async_trait::async_trait]
+impl PayoutProvider for StubBankPayouts {
+    async fn initiate(&self, instruction: &PayoutInstruction) -> Result<Payout, PayoutError> {
+        match instruction.beneficiary.external_id.as_deref() {
+            Some(id) if looks_like_iban(id) => Err(self.unavailable()),
+            _ => Err(PayoutError::Rejected(format!(
+                "{} only pays out to IBANs",
+                self.bank
+            ))),
+        }
+    }
+
+    async fn status(&self, _provider_reference: &str) -> Result<Payout, PayoutError> {
+        Err(self.unavailable())
+    }
+
+    async fn cancel(&self, _provider_reference: &str) -> Result<Payout, PayoutError> {
+        Err(self.unavailable())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use payments_types::{Counterparty, CurrencyCode, DynMoney, FixedClock, TransactionId};
+
+    use super::*;
+
+    fn instruction(external_id: Option<&str>) -> PayoutInstruction {
+        PayoutInstruction {
+            transaction_id: TransactionId::new(),
+            amount: DynMoney::new(2_500, CurrencyCode::EUR).unwrap(),
+            beneficiary: Counterparty {
+                name: "ACME Supplies".into(),
+                external_id: external_id.map(Into::into),
+            },
+            reference: Some("INV-42".into()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulated_payout_settles_after_delay() {
+        let clock = Arc::new(FixedClock::new(
+            Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+        ));
+        let payouts = SimulatedPayouts::new(clock.clone());
+        let instruction = instruction(Some("DE89370400440532013000"));
+
+        let payout = payouts.initiate(&instruction).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Pending);
+        // Resending is idempotent.
+        assert_eq!(payouts.initiate(&instruction).await.unwrap(), payout);
+
+        clock.advance(DEFAULT_SIMULATED_SETTLEMENT);
+        let settled = payouts.status(&payout.provider_reference).await.unwrap();
+        assert_eq!(settled.status, PayoutStatus::Completed);
+        assert!(settled.status.is_final());
+        assert!(matches!(
+            payouts.cancel(&payout.provider_reference).await,
+            Err(PayoutError::NotCancellable(_))
+        ));
+        assert!(matches!(
+            payouts.status("sim_unknown").await,
+            Err(PayoutError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_simulated_payout_cancels_while_pending() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let payouts = SimulatedPayouts::new(clock.clone());
+        let payout = payouts
+            .initiate(&instruction(Some("12345678")))
+            .await
+            .unwrap();
+
+        let cancelled = payouts.cancel(&payout.provider_reference).await.unwrap();
+        assert_eq!(cancelled.status, PayoutStatus::Cancelled);
+        clock.advance(DEFAULT_SIMULATED_SETTLEMENT);
+        let later = payouts.status(&payout.provider_reference).await.unwrap();
+        assert_eq!(later.status, PayoutStatus::Cancelled);
+
+        assert!(matches!(
+            payouts.initiate(&instruction(None)).await,
+            Err(PayoutError::Rejected(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stub_bank_rejects_non_iban_and_is_unavailable() {
+        let bank = StubBankPayouts::new("Example Bank");
+        assert!(matches!(
+            bank.initiate(&instruction(Some("12345678"))).await,
+            Err(PayoutError::Rejected(_))
+        ));
+        assert!(matches!(
+            bank.initiate(&instruction(Some("DE89370400440532013000")))
+                .await,
+            Err(PayoutError::Unavailable(_))
+        ));
+        assert!(matches!(
+            bank.status("anything").await,
+            Err(PayoutError::Unavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+//! Soft storage quotas.
+//!
+//! The [`QuotaMonitor`](crate::jobs::QuotaMonitor) counts the rows of each
+//! tracked table and emits `quota.warning` when one reaches
+//! [`warn_at`](StorageQuotas::warn_at) of its limit. Quotas never block
+//! writes; they only give operators time to plan capacity.
+
+use payments_types::{StorageCounts, StorageResource, StorageUsage};
+
+/// Row limits per table and when to warn about them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StorageQuotas {
+    pub accounts: Option<i64>,
+    pub transactions: Option<i64>,
+    pub webhook_events: Option<i64>,
+    /// Fraction of a limit that raises a warning; 0.8 by default.
+    pub warn_at: f64,
+}
+
+impl Default for StorageQuotas {
+    fn default() -> Self {
+        Self {
+            accounts: None,
+            transactions: None,
+            webhook_events: None,
+            warn_at: 0.8,
+        }
+    }
+}
+
+impl StorageQuotas {
+    /// The limit of `resource`, if it has one.
+    pub fn limit(&self, resource: StorageResource) -> Option<i64> {
+        match resource {
+            StorageResource::Accounts => self.accounts,
+            StorageResource::Transactions => self.transactions,
+            StorageResource::WebhookEvents => self.webhook_events,
+        }
+    }
+
+    /// Whether any table has a limit.
+    pub fn is_enabled(&self) -> bool {
+        StorageResource::ALL
+            .into_iter()
+            .any(|r| self.limit(r).is_some())
+    }
+
+    /// Usage of every tracked table, in [`StorageResource::ALL`] order.
+    pub fn usage(&self, counts: &StorageCounts) -> Vec<StorageUsage> {
+        StorageResource::ALL
+            .into_iter()
+            .map(|r| StorageUsage::new(r, counts.rows(r), self.limit(r), self.warn_at))
+            .collect()
+    }
+}
@@ -0,0 +1,114 @@
+//! Redis idempotency store adapter.
+//!
+//! Keeps idempotent responses in Redis rather than the database, each under
+//! its endpoint and key and expiring after a while, since retries of a
+//! request come within minutes rather than months. The first record stored
+//! under a key wins: later saves are dropped with `SET NX`, and only the
+//! request that reserved the key replaces its record with the response. A
+//! reservation expires after [`IDEMPOTENCY_RESERVATION_TTL`], so a request
+//! that never finished stops holding the key.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
+
+use payments_types::{IDEMPOTENCY_RESERVATION_TTL, IdempotencyRecord, IdempotencyStore, RepoError};
+
+/// Prefix of the Redis keys responses are stored under.
+const KEY_PREFIX: &str = "payments:idempotency";
+
+/// How long a request waits on Redis before failing.
+const RESPONSE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// How long a response is kept by default.
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// [`IdempotencyStore`] shared by every instance through Redis.
+pub struct RedisIdempotencyStore {
+    connection: ConnectionManager,
+    ttl: Duration,
+}
+
+impl RedisIdempotencyStore {
+    /// Connects to the server at `url` (e.g. `redis://cache:6379`), keeping
+    /// each response for `ttl`.
+    pub async fn connect(url: &str, ttl: Duration) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let config = ConnectionManagerConfig::new()
+            .set_connection_timeout(RESPONSE_TIMEOUT)
+            .set_response_timeout(RESPONSE_TIMEOUT);
+        let connection = ConnectionManager::new_with_config(client, config).await?;
+        Ok(Self { connection, ttl })
+    }
+}
+
+/// The Redis key the response to `key` sent to `endpoint` is stored under.
+fn redis_key(endpoint: &str, key: &str) -> String {
+    format!("{}:{}:{}", KEY_PREFIX, endpoint, key)
+}
+
+fn unavailable(e: impl std::fmt::Display) -> RepoError {
+    RepoError::Database(format!("Idempotency store unavailable: {}", e))
+}
+
+#[async_trait]
+impl IdempotencyStore for RedisIdempotencyStore {
+    async fn get(&self, endpoint: &str, key: &str) -> Result<Option<IdempotencyRecord>, RepoError> {
+        let stored: Option<String> = self
+            .connection
+            .clone()
+            .get(redis_key(endpoint, key))
+            .await
+            .map_err(unavailable)?;
+        stored
+            .map(|json| serde_json::from_str(&json).map_err(|e| RepoError::Database(e.to_string())))
+            .transpose()
+    }
+
+    async fn save(&self, record: &IdempotencyRecord) -> Result<IdempotencyRecord, RepoError> {
+        let json = serde_json::to_string(record).map_err(|e| RepoError::Database(e.to_string()))?;
+        let ttl = match IDEMPOTENCY_RESERVATION_TTL.to_std() {
+            Ok(reservation_ttl) if record.is_in_progress() => self.ttl.min(reservation_ttl),
+            _ => self.ttl,
+        };
+        let options = redis::SetOptions::default()
+            .conditional_set(redis::ExistenceCheck::NX)
+            .with_expiration(redis::SetExpiry::EX(ttl.as_secs().max(1)));
+        let _: Option<String> = self
+            .connection
+            .clone()
+            .set_options(redis_key(&record.endpoint, &record.key), json, options)
+            .await
+            .map_err(unavailable)?;
+        self.get(&record.endpoint, &record.key)
+            .await?
+            .ok_or_else(|| RepoError::Database("Idempotency record vanished".into()))
+    }
+
+    async fn replace(&self, record: &IdempotencyRecord) -> Result<(), RepoError> {
+        let json = serde_json::to_string(record).map_err(|e| RepoError::Database(e.to_string()))?;
+        let _: () = self
+            .connection
+            .clone()
+            .set_ex(
+                redis_key(&record.endpoint, &record.key),
+                json,
+                self.ttl.as_secs().max(1),
+            )
+            .await
+            .map_err(unavailable)?;
+        Ok(())
+    }
+
+    async fn remove(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        let _: () = self
+            .connection
+            .clone()
+            .del(redis_key(endpoint, key))
+            .await
+            .map_err(unavailable)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! Redis rate limit store adapter.
+//!
+//! Counts requests in Redis so every replica enforces one quota per key. Each
+//! key holds the time its quota is next fully used up (the generic cell rate
+//! algorithm), updated by a script so concurrent requests from different
+//! instances never race, and expires once the quota has fully replenished.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::Script;
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
+
+use payments_repo::security::hash_api_key;
+use payments_types::{RateLimitDecision, RateLimitError, RateLimitStore};
+
+/// Prefix of the Redis keys quotas are counted under.
+const KEY_PREFIX: &str = "payments:rate-limit";
+
+/// How long a request waits on Redis before being limited in this process.
+const RESPONSE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Takes a burst size and the milliseconds one request replenishes in, and
+/// returns `{1, remaining}` when the request is allowed or `{0, wait_ms}`
+/// when it is not. Redis's clock is used so replicas agree on the time.
+const CHECK_SCRIPT: &str = r"
+local burst = tonumber(ARGV[1])
+local interval = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local tat = math.max(tonumber(redis.call('GET', KEYS[1])) or now, now)
+local allow_at = tat + interval - burst * interval
+if allow_at > now then
+  return {0, allow_at - now}
+end
+redis.call('SET', KEYS[1], tat + interval, 'PX', tat + interval - now)
+return {1, math.floor((now - allow_at) / interval)}
+";
+
+/// [`RateLimitStore`] shared by every instance through Redis.
+pub struct RedisRateLimits {
+    connection: ConnectionManager,
+    script: Script,
+}
+
+impl RedisRateLimits {
+    /// Connects to the server at `url` (e.g. `redis://cache:6379`),
+    /// reconnecting whenever the connection drops.
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let config = ConnectionManagerConfig::new()
+            .set_connection_timeout(RESPONSE_TIMEOUT)
+            .set_response_timeout(RESPONSE_TIMEOUT);
+        let connection = ConnectionManager::new_with_config(client, config).await?;
+        Ok(Self {
+            connection,
+            script: Script::new(CHECK_SCRIPT),
+        })
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RedisRateLimits {
+    async fn check(
+        &self,
+        key: &str,
+        requests: u32,
+        period: Duration,
+    ) -> Result<RateLimitDecision, RateLimitError> {
+        let requests = requests.max(1);
+        let interval_ms = u64::try_from(period.as_millis().max(1)).unwrap_or(u64::MAX);
+        // Hashed so bearer tokens never reach Redis
+        let (allowed, value): (i64, i64) = self
+            .script
+            .key(format!("{}:{}", KEY_PREFIX, hash_api_key(key)))
+            .arg(requests)
+            .arg(interval_ms)
+            .invoke_async(&mut self.connection.clone())
+            .await
+            .map_err(|e| RateLimitError::Unavailable(e.to_string()))?;
+        let value = u64::try_from(value).unwrap_or(0);
+        Ok(if allowed == 1 {
+            RateLimitDecision {
+                limit: requests,
+                remaining: u32::try_from(value).unwrap_or(u32::MAX),
+                retry_after: None,
+            }
+        } else {
+            RateLimitDecision {
+                limit: requests,
+                remaining: 0,
+                retry_after: Some(Duration::from_millis(value)),
+            }
+        })
+    }
+}
@@ -0,0 +1,232 @@
+//! Built-in risk checks.
+//!
+//! [`PaymentService`](crate::PaymentService) runs [`AllowAll`] unless the
+//! builder is given another [`RiskCheck`]. [`RiskRules`] is a simple
+//! rules-based check for deployments without a fraud scoring service.
+
+use chrono::TimeDelta;
+use payments_types::{PaymentCheck, RiskAssessment, RiskCheck, TransactionType};
+
+use crate::warnings::money;
+
+/// Lets every payment through.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAll;
+
+#[async_trait::async_trait]
+impl RiskCheck for AllowAll {
+    async fn assess(&self, _payment: &PaymentCheck) -> RiskAssessment {
+        RiskAssessment::allow()
+    }
+}
+
+/// Velocity, amount and new-beneficiary heuristics.
+///
+/// Rules only see each account's latest 20 transactions, so velocity limits
+/// above that are never reached and a beneficiary last paid before them
+/// counts as new.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiskRules {
+    /// Debits from the paying account in the past hour after which further
+    /// ones are held; 5 by default.
+    pub hourly_debits_to_hold: usize,
+    /// Debits in the past hour after which further ones are denied; 10 by
+    /// default.
+    pub hourly_debits_to_deny: usize,
+    /// Amount in minor units from which any payment is held; 1,000,000 by
+    /// default.
+    pub hold_amount: i64,
+    /// Amount from which a transfer to an account the payer has not paid
+    /// recently is held; 500,000 by default.
+    pub new_beneficiary_amount: i64,
+}
+
+impl Default for RiskRules {
+    fn default() -> Self {
+        Self {
+            hourly_debits_to_hold: 5,
+            hourly_debits_to_deny: 10,
+            hold_amount: 1_000_000,
+            new_beneficiary_amount: 500_000,
+        }
+    }
+}
+
+impl RiskRules {
+    fn velocity(&self, payment: &PaymentCheck) -> RiskAssessment {
+        let Some(source) = &payment.source else {
+            return RiskAssessment::allow();
+        };
+        let since = payment.now - TimeDelta::hours(1);
+        let debits = source
+            .recent
+            .iter()
+            .filter(|tx| tx.source_account_id == Some(source.account.id) && tx.created_at > since)
+            .count();
+        let reason = format!("{} debits from this account in the past hour", debits);
+        if debits >= self.hourly_debits_to_deny {
+            RiskAssessment::deny(reason)
+        } else if debits >= self.hourly_debits_to_hold {
+            RiskAssessment::hold(reason)
+        } else {
+            RiskAssessment::allow()
+        }
+    }
+
+    fn amount(&self, payment: &PaymentCheck) -> RiskAssessment {
+        if payment.amount < self.hold_amount {
+            return RiskAssessment::allow();
+        }
+        RiskAssessment::hold(format!(
+            "Amount {} is at or above the review threshold of {}",
+            money(payment.amount, payment.currency),
+            money(self.hold_amount, payment.currency)
+        ))
+    }
+
+    fn new_beneficiary(&self, payment: &PaymentCheck) -> RiskAssessment {
+        let (Some(source), Some(destination)) = (&payment.source, &payment.destination) else {
+            return RiskAssessment::allow();
+        };
+        if payment.transaction_type != TransactionType::Transfer
+            || payment.amount < self.new_beneficiary_amount
+        {
+            return RiskAssessment::allow();
+        }
+        let paid_before = source
+            .recent
+            .iter()
+            .any(|tx| tx.destination_account_id == Some(destination.account.id));
+        if paid_before {
+            return RiskAssessment::allow();
+        }
+        RiskAssessment::hold(format!(
+            "First transfer of {} or more to this beneficiary",
+            money(self.new_beneficiary_amount, payment.currency)
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl RiskCheck for RiskRules {
+    async fn assess(&self, payment: &PaymentCheck) -> RiskAssessment {
+        self.velocity(payment)
+            .and(self.amount(payment))
+            .and(self.new_beneficiary(payment))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use payments_types::{
+        Account, AccountActivity, CurrencyCode, DynMoney, RiskDecision, Transaction,
+    };
+
+    use super::*;
+
+    fn account(name: &str) -> Account {
+        Account::new(name.into(), CurrencyCode::USD).unwrap()
+    }
+
+    fn transfer(amount: i64, source: AccountActivity, destination: Account) -> PaymentCheck {
+        PaymentCheck {
+            transaction_type: TransactionType::Transfer,
+            amount,
+            currency: CurrencyCode::USD,
+            source: Some(source),
+            destination: Some(AccountActivity {
+                account: destination,
+                recent: Vec::new(),
+            }),
+            now: Utc::now(),
+        }
+    }
+
+    /// `source` with `count` recent withdrawals, `minutes_ago` each.
+    fn with_debits(source: Account, count: usize, minutes_ago: i64) -> AccountActivity {
+        let recent = (0..count)
+            .map(|_| {
+                let mut tx = Transaction::withdrawal(
+                    source.id,
+                    DynMoney::new(100, CurrencyCode::USD).unwrap(),
+                    None,
+                    None,
+                );
+                tx.created_at = Utc::now() - TimeDelta::minutes(minutes_ago);
+                tx
+            })
+            .collect();
+        AccountActivity {
+            account: source,
+            recent,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_velocity_holds_then_denies() {
+        let rules = RiskRules::default();
+        let assess = |count, minutes_ago| {
+            let payment = transfer(
+                100,
+                with_debits(account("A"), count, minutes_ago),
+                account("B"),
+            );
+            async move { rules.assess(&payment).await }
+        };
+
+        assert_eq!(assess(4, 10).await.decision, RiskDecision::Allow);
+        let held = assess(5, 10).await;
+        assert_eq!(held.decision, RiskDecision::Hold);
+        assert_eq!(
+            held.reasons,
+            ["5 debits from this account in the past hour"]
+        );
+        assert_eq!(assess(10, 10).await.decision, RiskDecision::Deny);
+        // Older debits do not count.
+        assert_eq!(assess(10, 90).await.decision, RiskDecision::Allow);
+    }
+
+    #[tokio::test]
+    async fn test_large_amounts_and_new_beneficiaries_are_held() {
+        let rules = RiskRules::default();
+        let payer = account("Payer");
+        let payee = account("Payee");
+        let mut source = with_debits(payer.clone(), 0, 0);
+
+        let held = rules
+            .assess(&transfer(500_000, source.clone(), payee.clone()))
+            .await;
+        assert_eq!(
+            held,
+            RiskAssessment::hold("First transfer of $5000.00 or more to this beneficiary")
+        );
+        assert_eq!(
+            rules
+                .assess(&transfer(499_999, source.clone(), payee.clone()))
+                .await,
+            RiskAssessment::allow()
+        );
+
+        source.recent.push(Transaction::transfer(
+            payer.id,
+            payee.id,
+            DynMoney::new(100, CurrencyCode::USD).unwrap(),
+            None,
+            None,
+        ));
+        assert_eq!(
+            rules
+                .assess(&transfer(500_000, source.clone(), payee.clone()))
+                .await,
+            RiskAssessment::allow()
+        );
+        let large = rules.assess(&transfer(1_000_000, source, payee)).await;
+        assert_eq!(
+            large,
+            RiskAssessment::hold(
+                "Amount $10000.00 is at or above the review threshold of $10000.00"
+            )
+        );
+    }
+}
@@ -3,11 +3,325 @@
 //! Orchestrates domain operations through the repository port.
 //! Contains NO infrastructure logic - pure business orchestration.
 
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, SubsecRound, TimeDelta, Utc};
+use payments_repo::security::hash_request;
+use rand::Rng;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::broadcast;
+
+use payments_types::ports::metrics;
 use payments_types::{
-    Account, AccountId, AppError, CreateAccountRequest, DepositRequest, Transaction, TransactionId,
-    TransactionRepository, TransferRequest, WithdrawRequest,
+    Account, AccountActivity, AccountAlias, AccountFees, AccountId, AccountLimits, AccountMerge,
+    AccountRef, AccountStatement, AccountStatus, ActorContext, AddAliasRequest, Alias,
+    AmountFormat, ApiKey, ApiKeyAudit, ApiKeyAuditAction, ApiKeyAuditEntry, ApiKeyId, ApiKeyScope,
+    ApiKeyUsage, ApiKeyUsageBucket, ApiKeyVolumeBucket, AppError, AssignFeeScheduleRequest,
+    Attachment, AuthorizeRequest, BalanceHistory, BalanceHistoryQuery, BatchMode, BatchOperation,
+    BatchRequest, Beneficiary, BeneficiaryId, BeneficiaryStatus, BlockedTransaction,
+    CaptureRequest, ChangeAction, ChangeRequest, ChangeRequestId, ChangeStatus, Clock,
+    Counterparty, CreateAccountRequest, CreateBeneficiaryRequest, CreateFeeScheduleRequest,
+    CreateMemoKeyRequest, CreateOwnerRequest, CreateQuoteRequest, CreateScheduledPaymentRequest,
+    CreateSettlementBatchRequest, CurrencyCode, DEFAULT_HOLD_TTL_SECS, DEFAULT_QUOTE_TTL_SECS,
+    DEFAULT_SESSION_TTL_SECS, DailyBalance, DeadLetterFilter, DependencyCheck, DependencyStatus,
+    DepositRequest, DomainEvent, DynMoney, EncryptedMemo, EventLogPage, EventLogQuery,
+    EventPublisher, ExchangeError, ExchangeRateProvider, Export, ExportDownload, ExportId,
+    ExportRequest, ExportStatus, ExposureReport, FeeAssignment, FeeQuote, FeeSchedule,
+    FeeScheduleId, FloatFlow, FloatReport, FloatReportQuery, FxConversion, Hold, HoldId,
+    HoldStatus, IdGenerator, IdempotencyRecord, IdempotencyStore, InboundPayment,
+    InboundPaymentRequest, JournalExportFormat, LAST_USED_RESOLUTION_SECS, LedgerOperation,
+    MAX_ALIASES_PER_ACCOUNT, MAX_BATCH_OPERATIONS, MAX_EXTERNAL_REFERENCE_LEN, MAX_HOLD_TTL_SECS,
+    MAX_MEMO_KEYS_PER_ACCOUNT, MAX_METADATA_KEY_LEN, MAX_METADATA_KEYS, MAX_METADATA_VALUE_LEN,
+    MAX_MICRO_DEPOSIT, MAX_QUOTE_TTL_SECS, MAX_RATE_LIMIT_PER_MINUTE, MAX_SESSION_TTL_SECS,
+    MAX_VERIFICATION_ATTEMPTS, MAX_WEBHOOK_BATCH, MemoKey, MemoKeyId, Metrics, Mode,
+    NewWebhookEndpoint, NoopMetrics, Notification, Notifier, Owner, OwnerId,
+    PAYMENTS_IDEMPOTENCY_ENDPOINT, PaymentCheck, Payout, PayoutError, PayoutInstruction,
+    PayoutProvider, PositiveAmount, QuotaWarning, Quote, QuoteId, RandomIdGenerator, RateSnapshot,
+    Readiness, ReadinessStatus, RepoError, RiskAssessment, RiskCheck, RiskDecision,
+    ScheduledPayment, ScheduledPaymentId, ScheduledPaymentStatus, SessionToken, SettlementBatch,
+    SettlementBatchId, SettlementBatchStatus, SettlementExportFormat, SpendingRules, Statement,
+    StatementDownload, StatementEmail, StatementId, StatementPeriod, StorageReport,
+    StorageResource, StorageUsage, SystemClock, Transaction, TransactionCursor, TransactionFilter,
+    TransactionId, TransactionListQuery, TransactionPage, TransactionRepository, TransactionSearch,
+    TransactionSearchQuery, TransactionType, TransferRequest, USAGE_WINDOW_HOURS, UnitOfWork,
+    UpdateOwnerRequest, VerifyBeneficiaryRequest, Warning, WarningRule, WebhookDeliveriesQuery,
+    WebhookDeliveryFilter, WebhookEndpoint, WebhookEndpointId, WebhookEvent,
+    WebhookSignatureAlgorithm, WebhookStatus, WebhookTimeouts, WithWarnings, WithdrawRequest,
+    normalize_purpose_code, usage_hour, usage_window_start, validate_event_patterns,
 };
 
+use crate::accounting::{GlAccountCodes, render_quickbooks, render_xero};
+use crate::dormancy::DormancyPolicy;
+use crate::downloads::DownloadLinks;
+use crate::events::{LIVE_EVENT_CAPACITY, LiveEvent};
+use crate::limits::AmountLimits;
+use crate::payouts::SimulatedPayouts;
+use crate::quotas::StorageQuotas;
+use crate::risk::AllowAll;
+use crate::sessions::{SessionClaims, SessionTokens, session_scopes};
+use crate::settlement::{SettlementDebtor, render_csv, render_pain001};
+use crate::statements::{StatementLinks, render_statement};
+use crate::webhooks::{WEBHOOK_ENDPOINT_CACHE_TTL, WebhookEndpointCache};
+
+/// Results returned by `search_accounts` when the caller gives no limit.
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+/// Upper bound on `search_accounts` results.
+const MAX_SEARCH_LIMIT: usize = 100;
+/// Transactions per page when the caller gives no limit.
+const DEFAULT_TRANSACTION_PAGE: usize = 50;
+/// Upper bound on transactions per page.
+const MAX_TRANSACTION_PAGE: usize = 200;
+/// Webhook events returned by a catch-up or delivery log request when the
+/// caller gives no limit.
+const DEFAULT_WEBHOOK_EVENT_PAGE: usize = 100;
+/// Upper bound on webhook events per catch-up or delivery log request.
+const MAX_WEBHOOK_EVENT_PAGE: usize = 500;
+/// Logged events per event log page when the caller gives no limit.
+const DEFAULT_EVENT_LOG_PAGE: usize = 1000;
+/// Upper bound on logged events per event log page.
+const MAX_EVENT_LOG_PAGE: usize = 10_000;
+/// Trailing window the daily debit limit is measured over.
+const DAILY_DEBIT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+/// Trailing window the hourly transaction limit is measured over.
+const HOURLY_TRANSACTION_WINDOW: Duration = Duration::from_secs(60 * 60);
+/// Longest period a journal export, account statement or balance history may
+/// cover, in days.
+const MAX_PERIOD_DAYS: i64 = 366;
+/// How long exports and their files are kept before being pruned.
+const EXPORT_RETENTION: TimeDelta = TimeDelta::hours(24);
+/// Due scheduled payments made per scheduler run; the rest wait for the next.
+const SCHEDULED_PAYMENT_BATCH: usize = 100;
+/// Expired holds released per expiry run; the rest wait for the next.
+const HOLD_EXPIRY_BATCH: usize = 100;
+/// Idle accounts flagged dormant per detection run; the rest wait for the next.
+const DORMANCY_BATCH: usize = 100;
+/// Latest transactions per account shown to warning rules and risk checks.
+const WARNING_HISTORY: usize = 20;
+/// Days in a balance history when the caller gives no start.
+const DEFAULT_BALANCE_HISTORY_DAYS: u64 = 30;
+/// Days of external flows in a float report when the caller gives no start.
+const DEFAULT_FLOAT_REPORT_DAYS: u64 = 30;
+/// Longest window in an API key usage report, in hours.
+const MAX_USAGE_WINDOW_HOURS: i64 = USAGE_WINDOW_HOURS[USAGE_WINDOW_HOURS.len() - 1];
+
+/// Rejects a counterparty without a usable name.
+fn validate_counterparty(counterparty: Option<&Counterparty>) -> Result<(), AppError> {
+    match counterparty {
+        Some(c) if c.name.trim().is_empty() => Err(AppError::BadRequest(
+            "Counterparty name cannot be empty".into(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Rejects metadata with too many keys, or keys or values that are too long.
+fn validate_metadata(metadata: &HashMap<String, String>) -> Result<(), AppError> {
+    if metadata.len() > MAX_METADATA_KEYS {
+        return Err(AppError::BadRequest(format!(
+            "Metadata can have at most {} keys",
+            MAX_METADATA_KEYS
+        )));
+    }
+    for (key, value) in metadata {
+        if key.trim().is_empty() || key.chars().count() > MAX_METADATA_KEY_LEN {
+            return Err(AppError::BadRequest(format!(
+                "Metadata keys must be 1 to {} characters",
+                MAX_METADATA_KEY_LEN
+            )));
+        }
+        if value.chars().count() > MAX_METADATA_VALUE_LEN {
+            return Err(AppError::BadRequest(format!(
+                "Metadata value for {} is longer than {} characters",
+                key, MAX_METADATA_VALUE_LEN
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Rejects a negative overdraft limit.
+fn check_overdraft_limit(limit: i64) -> Result<(), AppError> {
+    if limit < 0 {
+        return Err(AppError::BadRequest(
+            "Overdraft limit cannot be negative".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// The API error a payout provider's error is reported as.
+fn payout_error(err: PayoutError) -> AppError {
+    match err {
+        PayoutError::Rejected(msg) => AppError::BadRequest(msg),
+        PayoutError::NotFound(reference) => AppError::NotFound(format!("Payout {}", reference)),
+        e @ PayoutError::NotCancellable(_) => AppError::Conflict(e.to_string()),
+        PayoutError::Unavailable(msg) => AppError::ServiceUnavailable(msg),
+    }
+}
+
+/// Rejects a journal export, statement or balance history period that is
+/// reversed or too long.
+fn check_period(from: NaiveDate, to: NaiveDate) -> Result<(), AppError> {
+    if from > to {
+        return Err(AppError::BadRequest("from must not be after to".into()));
+    }
+    if (to - from).num_days() >= MAX_PERIOD_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "A period covers at most {} days",
+            MAX_PERIOD_DAYS
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects transaction filter bounds that are the wrong way round.
+fn check_transaction_filter(filter: &TransactionFilter) -> Result<(), AppError> {
+    if let (Some(from), Some(to)) = (filter.from, filter.to)
+        && from >= to
+    {
+        return Err(AppError::BadRequest("`from` must be before `to`".into()));
+    }
+    if let (Some(min), Some(max)) = (filter.min_amount, filter.max_amount)
+        && min > max
+    {
+        return Err(AppError::BadRequest(
+            "`min_amount` cannot exceed `max_amount`".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Parses a transaction page cursor and clamps the page size.
+fn transaction_page_bounds(
+    cursor: Option<&str>,
+    limit: Option<usize>,
+) -> Result<(Option<TransactionCursor>, usize), AppError> {
+    let after = cursor
+        .map(str::parse::<TransactionCursor>)
+        .transpose()
+        .map_err(AppError::BadRequest)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_TRANSACTION_PAGE)
+        .clamp(1, MAX_TRANSACTION_PAGE);
+    Ok((after, limit))
+}
+
+/// Pages `data`, fetched with one row more than `limit` to learn whether
+/// another page follows.
+fn transaction_page(mut data: Vec<Transaction>, limit: usize) -> TransactionPage {
+    let next_cursor = if data.len() > limit {
+        data.truncate(limit);
+        data.last()
+            .map(|tx| TransactionCursor::after(tx).to_string())
+    } else {
+        None
+    };
+    TransactionPage { data, next_cursor }
+}
+
+/// Upper-cases a request's purpose code, rejecting malformed ones.
+fn normalize_purpose(purpose_code: Option<String>) -> Result<Option<String>, AppError> {
+    purpose_code
+        .map(|code| normalize_purpose_code(&code).map_err(|e| AppError::BadRequest(e.to_string())))
+        .transpose()
+}
+
+/// Sorts and de-duplicates API key scopes, rejecting an empty list.
+fn normalize_scopes(scopes: &[ApiKeyScope]) -> Result<Vec<ApiKeyScope>, AppError> {
+    if scopes.is_empty() {
+        return Err(AppError::BadRequest(
+            "An API key needs at least one scope".into(),
+        ));
+    }
+    let mut scopes = scopes.to_vec();
+    scopes.sort();
+    scopes.dedup();
+    Ok(scopes)
+}
+
+/// Hash of a payment request sent with an idempotency key.
+fn request_hash(operation: &str, req: &impl Serialize) -> Result<String, AppError> {
+    let body =
+        serde_json::to_vec(&(operation, req)).map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(hash_request(&body))
+}
+
+/// The response stored in `record`, refusing it if the key was first used
+/// for a request other than the one hashing to `request_hash`.
+fn replay<T: DeserializeOwned>(
+    record: IdempotencyRecord,
+    request_hash: &str,
+) -> Result<T, AppError> {
+    if !record.matches(request_hash) {
+        return Err(AppError::IdempotencyKeyConflict(record.key));
+    }
+    serde_json::from_value(record.response).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// The accounts a payment debits and credits.
+fn payment_accounts(operation: &LedgerOperation) -> (Option<AccountId>, Option<AccountId>) {
+    match operation {
+        LedgerOperation::Deposit(req) => (None, Some(req.account_id)),
+        LedgerOperation::Withdraw(req) => (Some(req.account_id), None),
+        LedgerOperation::Transfer(req, _) => (Some(req.from_account_id), Some(req.to_account_id)),
+    }
+}
+
+/// The idempotency key an operation of a batch was sent with.
+fn batch_idempotency_key(operation: &BatchOperation) -> Option<&str> {
+    match operation {
+        BatchOperation::Deposit(req) => req.idempotency_key.as_deref(),
+        BatchOperation::Withdraw(req) => req.idempotency_key.as_deref(),
+        BatchOperation::Transfer(req) => req.idempotency_key.as_deref(),
+    }
+}
+
+/// What happened to one operation of a [`PaymentService::batch`].
+#[derive(Debug)]
+pub enum BatchOutcome {
+    /// The payment was made, or replayed by its idempotency key
+    Succeeded(Box<WithWarnings<Transaction>>),
+    /// The payment was refused
+    Failed(AppError),
+    /// Not made because another operation of an atomic batch failed
+    RolledBack,
+}
+
+/// An operation of an atomic batch after its checks.
+enum PreparedOperation {
+    /// Already made under its idempotency key
+    Replayed(WithWarnings<Transaction>),
+    Ready(ReadyOperation),
+}
+
+/// What the checks made before a payment's unit of work decided about it.
+#[derive(Debug, Clone, Default)]
+struct Cleared {
+    /// Risk assessment to record if the payment is held
+    risk: Option<RiskAssessment>,
+    /// Caps of the account the payment counts against, tightened by its own
+    /// limits; the daily debit and hourly transaction limits are checked
+    /// inside the unit of work
+    limits: AmountLimits,
+    /// Mode of the accounts the payment moves money between
+    mode: Mode,
+}
+
+/// An operation of an atomic batch that passed its checks, waiting to be
+/// applied with the rest.
+struct ReadyOperation {
+    operation: LedgerOperation,
+    warnings: Vec<Warning>,
+    cleared: Cleared,
+    /// The payment as checked, to announce if a limit refuses it
+    payment: Box<PaymentCheck>,
+    /// Idempotency key and request hash to store the response under
+    idempotency: Option<(String, String)>,
+}
+
 /// Application service for payment operations.
 ///
 /// Generic over `R: TransactionRepository` - the adapter is injected at compile time.
@@ -15,14 +329,254 @@ use payments_types::{
 /// - Swapping repositories without code changes
 /// - Testing with in-memory repo
 /// - Compile-time checks for port implementation
+///
+/// Optional collaborators are wired through [`PaymentService::builder`].
 pub struct PaymentService<R: TransactionRepository> {
     repo: R,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+    exchange: Option<Arc<dyn ExchangeRateProvider>>,
+    publishers: Vec<Arc<dyn EventPublisher>>,
+    limits: AmountLimits,
+    settlement_debtor: Option<SettlementDebtor>,
+    gl_codes: GlAccountCodes,
+    amount_format: AmountFormat,
+    notifier: Option<Arc<dyn Notifier>>,
+    statement_links: StatementLinks,
+    download_links: DownloadLinks,
+    session_tokens: SessionTokens,
+    warning_rules: Vec<Arc<dyn WarningRule>>,
+    risk_check: Arc<dyn RiskCheck>,
+    payouts: Arc<dyn PayoutProvider>,
+    dormancy: DormancyPolicy,
+    metrics: Arc<dyn Metrics>,
+    idempotency: Option<Arc<dyn IdempotencyStore>>,
+    webhook_endpoints: WebhookEndpointCache,
+    live_events: broadcast::Sender<LiveEvent>,
+    storage_quotas: StorageQuotas,
+    /// Tables over their warning threshold at the last quota check
+    quota_warnings: Mutex<HashSet<StorageResource>>,
+}
+
+/// Builder for [`PaymentService`].
+///
+/// Every collaborator besides the repository has a default, so new ones can be
+/// added here without breaking existing callers.
+pub struct PaymentServiceBuilder<R: TransactionRepository> {
+    repo: R,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+    exchange: Option<Arc<dyn ExchangeRateProvider>>,
+    publishers: Vec<Arc<dyn EventPublisher>>,
+    limits: AmountLimits,
+    settlement_debtor: Option<SettlementDebtor>,
+    gl_codes: GlAccountCodes,
+    amount_format: AmountFormat,
+    notifier: Option<Arc<dyn Notifier>>,
+    statement_links: StatementLinks,
+    download_links: DownloadLinks,
+    session_tokens: SessionTokens,
+    warning_rules: Vec<Arc<dyn WarningRule>>,
+    risk_check: Arc<dyn RiskCheck>,
+    payouts: Option<Arc<dyn PayoutProvider>>,
+    dormancy: DormancyPolicy,
+    metrics: Arc<dyn Metrics>,
+    idempotency: Option<Arc<dyn IdempotencyStore>>,
+    webhook_endpoint_cache_ttl: Duration,
+    storage_quotas: StorageQuotas,
+}
+
+impl<R: TransactionRepository> PaymentServiceBuilder<R> {
+    /// Replaces the service clock (share it with the repo to freeze time in tests).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Replaces the service ID generator.
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Sets the provider used for currency conversion.
+    pub fn with_exchange_provider(mut self, provider: Arc<dyn ExchangeRateProvider>) -> Self {
+        self.exchange = Some(provider);
+        self
+    }
+
+    /// Sets the global amount and balance caps.
+    pub fn with_limits(mut self, limits: AmountLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets the account payouts are paid from, required for pain.001 exports.
+    pub fn with_settlement_debtor(mut self, debtor: SettlementDebtor) -> Self {
+        self.settlement_debtor = Some(debtor);
+        self
+    }
+
+    /// Sets the GL accounts journal exports post each transaction type to.
+    pub fn with_gl_codes(mut self, codes: GlAccountCodes) -> Self {
+        self.gl_codes = codes;
+        self
+    }
+
+    /// Sets how amounts in statements and their emails are written.
+    pub fn with_amount_format(mut self, format: AmountFormat) -> Self {
+        self.amount_format = format;
+        self
+    }
+
+    /// Sets the notifier statements are emailed through. Without one, every
+    /// statement is announced with a `statement.ready` webhook instead.
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Sets how statement download links are built and signed.
+    pub fn with_statement_links(mut self, links: StatementLinks) -> Self {
+        self.statement_links = links;
+        self
+    }
+
+    /// Sets how export download links are built and signed.
+    pub fn with_download_links(mut self, links: DownloadLinks) -> Self {
+        self.download_links = links;
+        self
+    }
+
+    /// Sets how dashboard session tokens are signed.
+    pub fn with_session_tokens(mut self, tokens: SessionTokens) -> Self {
+        self.session_tokens = tokens;
+        self
+    }
+
+    /// Replaces the rules that flag unusual payments, by default
+    /// [`default_rules`](crate::warnings::default_rules). Pass none to
+    /// disable warnings.
+    pub fn with_warning_rules(mut self, rules: Vec<Arc<dyn WarningRule>>) -> Self {
+        self.warning_rules = rules;
+        self
+    }
+
+    /// Sets the check that may hold or deny payments before money moves, by
+    /// default [`AllowAll`](crate::risk::AllowAll).
+    pub fn with_risk_check(mut self, check: Arc<dyn RiskCheck>) -> Self {
+        self.risk_check = check;
+        self
+    }
+
+    /// Sets the rails payouts are sent over, by default
+    /// [`SimulatedPayouts`](crate::payouts::SimulatedPayouts) on the service
+    /// clock.
+    pub fn with_payout_provider(mut self, provider: Arc<dyn PayoutProvider>) -> Self {
+        self.payouts = Some(provider);
+        self
+    }
+
+    /// Sets when accounts become dormant and whether that blocks debits.
+    pub fn with_dormancy_policy(mut self, policy: DormancyPolicy) -> Self {
+        self.dormancy = policy;
+        self
+    }
+
+    /// Adds a publisher that receives every domain event, after webhooks are queued.
+    pub fn with_event_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publishers.push(publisher);
+        self
+    }
+
+    /// Counts transactions into `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Stores idempotent responses in `store` instead of the repository.
+    pub fn with_idempotency_store(mut self, store: Arc<dyn IdempotencyStore>) -> Self {
+        self.idempotency = Some(store);
+        self
+    }
+
+    /// Sets how long the list of webhook endpoints is cached (default
+    /// [`WEBHOOK_ENDPOINT_CACHE_TTL`]); zero lists them on every payment.
+    pub fn with_webhook_endpoint_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.webhook_endpoint_cache_ttl = ttl;
+        self
+    }
+
+    /// Sets the soft row limits storage is checked against; none by default.
+    pub fn with_storage_quotas(mut self, quotas: StorageQuotas) -> Self {
+        self.storage_quotas = quotas;
+        self
+    }
+
+    pub fn build(self) -> PaymentService<R> {
+        let payouts = self
+            .payouts
+            .unwrap_or_else(|| Arc::new(SimulatedPayouts::new(self.clock.clone())));
+        PaymentService {
+            repo: self.repo,
+            clock: self.clock,
+            ids: self.ids,
+            exchange: self.exchange,
+            publishers: self.publishers,
+            limits: self.limits,
+            settlement_debtor: self.settlement_debtor,
+            gl_codes: self.gl_codes,
+            amount_format: self.amount_format,
+            notifier: self.notifier,
+            statement_links: self.statement_links,
+            download_links: self.download_links,
+            session_tokens: self.session_tokens,
+            warning_rules: self.warning_rules,
+            risk_check: self.risk_check,
+            payouts,
+            dormancy: self.dormancy,
+            metrics: self.metrics,
+            idempotency: self.idempotency,
+            webhook_endpoints: WebhookEndpointCache::new(self.webhook_endpoint_cache_ttl),
+            live_events: broadcast::channel(LIVE_EVENT_CAPACITY).0,
+            storage_quotas: self.storage_quotas,
+            quota_warnings: Mutex::default(),
+        }
+    }
 }
 
 impl<R: TransactionRepository> PaymentService<R> {
-    /// Creates a new payment service with the given repository.
+    /// Creates a new payment service with the given repository and default collaborators.
     pub fn new(repo: R) -> Self {
-        Self { repo }
+        Self::builder(repo).build()
+    }
+
+    /// Starts building a service around `repo`.
+    pub fn builder(repo: R) -> PaymentServiceBuilder<R> {
+        PaymentServiceBuilder {
+            repo,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+            exchange: None,
+            publishers: Vec::new(),
+            limits: AmountLimits::default(),
+            settlement_debtor: None,
+            gl_codes: GlAccountCodes::default(),
+            amount_format: AmountFormat::default(),
+            notifier: None,
+            statement_links: StatementLinks::default(),
+            download_links: DownloadLinks::default(),
+            session_tokens: SessionTokens::default(),
+            warning_rules: crate::warnings::default_rules(),
+            risk_check: Arc::new(AllowAll),
+            payouts: None,
+            dormancy: DormancyPolicy::default(),
+            metrics: Arc::new(NoopMetrics),
+            idempotency: None,
+            webhook_endpoint_cache_ttl: WEBHOOK_ENDPOINT_CACHE_TTL,
+            storage_quotas: StorageQuotas::default(),
+        }
     }
 
     /// Returns a reference to the underlying repository.
@@ -30,6 +584,81 @@ impl<R: TransactionRepository> PaymentService<R> {
         &self.repo
     }
 
+    /// Returns a receiver for every domain event the service announces from
+    /// now on, for live streams to clients.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<LiveEvent> {
+        self.live_events.subscribe()
+    }
+
+    /// Returns the clock the service reads the current time from.
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Returns the generator the service draws new IDs from.
+    pub fn ids(&self) -> &dyn IdGenerator {
+        self.ids.as_ref()
+    }
+
+    /// Returns the caps applied to incoming transactions.
+    pub fn limits(&self) -> &AmountLimits {
+        &self.limits
+    }
+
+    /// Returns how amounts in statements are written.
+    pub fn amount_format(&self) -> &AmountFormat {
+        &self.amount_format
+    }
+
+    /// Whether the repository answers a ping, for coarse health checks.
+    pub async fn is_database_reachable(&self) -> bool {
+        self.database_latency().await.is_some()
+    }
+
+    /// How long the repository takes to answer a ping, or `None` when it
+    /// fails.
+    pub async fn database_latency(&self) -> Option<Duration> {
+        let started = std::time::Instant::now();
+        match self.repo.ping().await {
+            Ok(()) => Some(started.elapsed()),
+            Err(e) => {
+                tracing::warn!("Health check ping failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Pings each dependency the service needs to serve requests. Failures
+    /// are logged rather than reported, as readiness is public.
+    pub async fn readiness(&self) -> Readiness {
+        let started = std::time::Instant::now();
+        let database = self.database_latency().await;
+        let checks = vec![DependencyCheck {
+            name: "database".to_string(),
+            status: match database {
+                Some(_) => DependencyStatus::Up,
+                None => DependencyStatus::Down,
+            },
+            latency_ms: database.unwrap_or_else(|| started.elapsed()).as_secs_f64() * 1000.0,
+        }];
+        let status = if checks.iter().all(|c| c.status == DependencyStatus::Up) {
+            ReadinessStatus::Ready
+        } else {
+            ReadinessStatus::NotReady
+        };
+        Readiness { status, checks }
+    }
+
+    /// Counts webhook events still waiting to be delivered.
+    pub async fn webhook_backlog(&self) -> Result<i64, AppError> {
+        Ok(self.repo.count_webhook_backlog().await?)
+    }
+
+    /// Returns the configured exchange rate provider, if any.
+    pub fn exchange_provider(&self) -> Option<&dyn ExchangeRateProvider> {
+        self.exchange.as_deref()
+    }
+
     // ─────────────────────────────────────────────────────────────────────────────
     // Account Operations
     // ─────────────────────────────────────────────────────────────────────────────
@@ -40,194 +669,4676 @@ impl<R: TransactionRepository> PaymentService<R> {
         if req.name.trim().is_empty() {
             return Err(AppError::BadRequest("Account name cannot be empty".into()));
         }
+        check_overdraft_limit(req.overdraft_limit)?;
+        self.load_owner(req.owner_id).await?;
+
+        let account = self
+            .repo
+            .create_account(req)
+            .await
+            .map_err(AppError::from)?;
+        self.emit(DomainEvent::AccountCreated(account.clone()))
+            .await;
+
+        Ok(account)
+    }
 
-        self.repo.create_account(req).await.map_err(Into::into)
+    /// Gets an account `actor` has access to.
+    pub async fn get_account(
+        &self,
+        actor: &ActorContext,
+        id: AccountId,
+    ) -> Result<Account, AppError> {
+        actor.ensure_access(id)?;
+        self.account_in_mode(actor, id).await
     }
 
-    /// Gets an account by ID.
-    pub async fn get_account(&self, id: AccountId) -> Result<Account, AppError> {
+    async fn load_account(&self, id: AccountId) -> Result<Account, AppError> {
         self.repo
             .get_account(id)
             .await
             .map_err(Into::into)
-            .and_then(|opt| opt.ok_or_else(|| AppError::NotFound(format!("Account {}", id))))
+            .and_then(|opt| opt.ok_or(AppError::AccountNotFound(id)))
     }
 
-    /// Lists all accounts.
-    pub async fn list_accounts(&self) -> Result<Vec<Account>, AppError> {
-        self.repo.list_accounts().await.map_err(Into::into)
+    /// Lists the accounts in `mode`, newest first.
+    pub async fn list_accounts(&self, mode: Mode) -> Result<Vec<Account>, AppError> {
+        self.repo
+            .list_accounts(Some(mode))
+            .await
+            .map_err(Into::into)
     }
 
-    // ─────────────────────────────────────────────────────────────────────────────
-    // Transaction Operations
-    // ─────────────────────────────────────────────────────────────────────────────
+    /// Lists the accounts an owner holds in `mode`, newest first.
+    pub async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Mode,
+    ) -> Result<Vec<Account>, AppError> {
+        self.load_owner(owner_id).await?;
+        self.repo
+            .list_owner_accounts(owner_id, Some(mode))
+            .await
+            .map_err(Into::into)
+    }
 
-    /// Deposits money into an account.
-    pub async fn deposit(&self, req: DepositRequest) -> Result<Transaction, AppError> {
-        // Business validation
-        if req.amount <= 0 {
-            return Err(AppError::BadRequest("Amount must be positive".into()));
+    /// Finds accounts in `mode` by name fragment or ID prefix.
+    pub async fn search_accounts(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        mode: Mode,
+    ) -> Result<Vec<Account>, AppError> {
+        if query.trim().is_empty() {
+            return Err(AppError::BadRequest("Search query cannot be empty".into()));
         }
+        let limit = limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
 
-        let transaction = self.repo.deposit(req).await.map_err(AppError::from)?;
+        self.repo
+            .search_accounts(query, limit, mode)
+            .await
+            .map_err(Into::into)
+    }
 
-        // Trigger webhook
-        let payload = serde_json::json!({
-            "transaction_id": transaction.id,
-            "account_id": transaction.destination_account_id,
-            "amount": transaction.amount.amount(),
-            "currency": transaction.amount.currency(),
-            "reference": transaction.reference,
-        });
-        self.trigger_webhook("deposit.success", payload).await;
+    /// Gets the spending rules applied to the outgoing payments of an account
+    /// `actor` has access to.
+    pub async fn spending_rules(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<SpendingRules, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo
+            .get_spending_rules(account_id)
+            .await
+            .map_err(Into::into)
+    }
 
-        Ok(transaction)
+    /// Replaces an account's spending rules on behalf of `actor`, an admin
+    /// key, returning them normalized.
+    pub async fn set_spending_rules(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        rules: SpendingRules,
+    ) -> Result<SpendingRules, AppError> {
+        actor.ensure_admin()?;
+        let rules = rules
+            .normalized()
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        self.ensure_mode(actor, account_id).await?;
+
+        self.repo
+            .set_spending_rules(account_id, &rules)
+            .await
+            .map_err(AppError::from)?;
+        Ok(rules)
     }
 
-    /// Withdraws money from an account.
-    pub async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, AppError> {
-        if req.amount <= 0 {
-            return Err(AppError::BadRequest("Amount must be positive".into()));
-        }
+    /// Gets the limits configured for an account `actor` has access to
+    /// (global caps not included).
+    pub async fn account_limits(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<AccountLimits, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo
+            .get_account_limits(account_id)
+            .await
+            .map_err(Into::into)
+    }
 
-        let transaction = self.repo.withdraw(req).await.map_err(AppError::from)?;
+    /// Replaces an account's limits on behalf of `actor`, an admin key;
+    /// all-`None` limits clear them.
+    pub async fn set_account_limits(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        limits: AccountLimits,
+    ) -> Result<AccountLimits, AppError> {
+        actor.ensure_admin()?;
+        limits
+            .validate()
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        self.ensure_mode(actor, account_id).await?;
 
-        // Trigger webhook
-        let payload = serde_json::json!({
-            "transaction_id": transaction.id,
-            "account_id": transaction.source_account_id,
-            "amount": transaction.amount.amount(),
-            "currency": transaction.amount.currency(),
-            "reference": transaction.reference,
-        });
-        self.trigger_webhook("withdraw.success", payload).await;
+        self.repo
+            .set_account_limits(account_id, &limits)
+            .await
+            .map_err(AppError::from)?;
+        Ok(limits)
+    }
 
-        Ok(transaction)
+    /// Sets how far below zero an account's balance may go, on behalf of
+    /// `actor`, an admin key. Lowering the limit does not touch a balance
+    /// already below the new one; it only stops further debits.
+    pub async fn set_overdraft_limit(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        limit: i64,
+    ) -> Result<Account, AppError> {
+        self.ensure_admin_of(actor, account_id).await?;
+        check_overdraft_limit(limit)?;
+        self.repo
+            .set_overdraft_limit(account_id, limit)
+            .await
+            .map_err(AppError::from)?
+            .ok_or(AppError::AccountNotFound(account_id))
     }
 
-    /// Transfers money between accounts.
-    pub async fn transfer(&self, req: TransferRequest) -> Result<Transaction, AppError> {
-        if req.amount <= 0 {
-            return Err(AppError::BadRequest("Amount must be positive".into()));
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Dormancy
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Returns the policy deciding when accounts become dormant.
+    pub fn dormancy_policy(&self) -> &DormancyPolicy {
+        &self.dormancy
+    }
+
+    /// Flags active accounts with no transactions for the policy's idle
+    /// period as dormant, emitting `account.dormant` for each. Returns how
+    /// many were flagged.
+    ///
+    /// Each account is flagged once: another instance flagging it first, or
+    /// its reactivation in between, leaves it alone.
+    pub async fn flag_dormant_accounts(&self) -> Result<usize, AppError> {
+        let now = self.clock.now();
+        let idle = self
+            .repo
+            .list_idle_accounts(now - self.dormancy.idle_for, DORMANCY_BATCH)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut flagged = 0;
+        for account in idle {
+            match self
+                .repo
+                .update_account_status(
+                    account.id,
+                    AccountStatus::Active,
+                    AccountStatus::Dormant,
+                    now,
+                )
+                .await
+            {
+                Ok(Some(account)) => {
+                    flagged += 1;
+                    self.emit(DomainEvent::AccountDormant(account)).await;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(account_id = %account.id, "Account will be flagged on the next run: {}", e)
+                }
+            }
         }
+        Ok(flagged)
+    }
 
-        if req.from_account_id == req.to_account_id {
-            return Err(AppError::BadRequest(
-                "Cannot transfer to the same account".into(),
+    /// Counts the rows of each tracked table against its soft quota, for
+    /// `actor`, a live admin key: the counts span both modes.
+    pub async fn storage_report(&self, actor: &ActorContext) -> Result<StorageReport, AppError> {
+        actor.ensure_admin()?;
+        if actor.mode != Mode::Live {
+            return Err(AppError::AccessDenied(
+                "storage quotas span both modes; use a live API key".into(),
             ));
         }
+        self.storage_usage().await
+    }
+
+    async fn storage_usage(&self) -> Result<StorageReport, AppError> {
+        let counts = self.repo.storage_counts().await?;
+        Ok(StorageReport {
+            checked_at: self.clock.now(),
+            warn_at: self.storage_quotas.warn_at,
+            resources: self.storage_quotas.usage(&counts),
+        })
+    }
 
-        let transaction = self.repo.transfer(req).await.map_err(AppError::from)?;
+    /// Emits `quota.warning` for each table that has reached its warning
+    /// threshold since the last check. Returns how many were raised.
+    ///
+    /// A table warns once per crossing: it must drop back below the
+    /// threshold before it warns again. Crossings are remembered per
+    /// instance, so each instance running the check raises its own.
+    pub async fn check_storage_quotas(&self) -> Result<usize, AppError> {
+        let report = self.storage_usage().await?;
+        let crossed: Vec<StorageUsage> = {
+            let mut warned = self.quota_warnings.lock().unwrap();
+            report
+                .resources
+                .iter()
+                .filter(|usage| {
+                    if usage.warning {
+                        warned.insert(usage.resource)
+                    } else {
+                        warned.remove(&usage.resource);
+                        false
+                    }
+                })
+                .copied()
+                .collect()
+        };
 
-        // Trigger webhook
-        let payload = serde_json::json!({
-            "transaction_id": transaction.id,
-            "from_account_id": transaction.source_account_id,
-            "to_account_id": transaction.destination_account_id,
-            "amount": transaction.amount.amount(),
-            "currency": transaction.amount.currency(),
-            "reference": transaction.reference,
-        });
-        self.trigger_webhook("transfer.success", payload).await;
+        for usage in &crossed {
+            tracing::warn!(
+                resource = %usage.resource,
+                rows = usage.rows,
+                limit = usage.limit,
+                "Storage is nearing its quota"
+            );
+            self.emit(DomainEvent::QuotaWarning(QuotaWarning {
+                usage: *usage,
+                warn_at: report.warn_at,
+                checked_at: report.checked_at,
+            }))
+            .await;
+        }
+        Ok(crossed.len())
+    }
 
-        Ok(transaction)
+    /// Returns a dormant account to active use, on behalf of `actor`, an
+    /// admin key.
+    pub async fn reactivate_account(
+        &self,
+        actor: &ActorContext,
+        id: AccountId,
+    ) -> Result<Account, AppError> {
+        self.ensure_admin_of(actor, id).await?;
+        self.repo
+            .update_account_status(
+                id,
+                AccountStatus::Dormant,
+                AccountStatus::Active,
+                self.clock.now(),
+            )
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::BadRequest(format!("Account {} is not dormant", id)))
     }
 
     // ─────────────────────────────────────────────────────────────────────────────
-    // Transaction History
+    // Freezing & Closing
     // ─────────────────────────────────────────────────────────────────────────────
 
-    /// Gets a transaction by ID.
-    pub async fn get_transaction(&self, id: TransactionId) -> Result<Transaction, AppError> {
+    /// Stops money leaving an active or dormant account until it is
+    /// unfrozen, emitting `account.frozen`. `actor` must be an admin key.
+    pub async fn freeze_account(
+        &self,
+        actor: &ActorContext,
+        id: AccountId,
+    ) -> Result<Account, AppError> {
+        self.ensure_admin_of(actor, id).await?;
+        let account = self.load_account(id).await?;
+        match account.status {
+            AccountStatus::Active | AccountStatus::Dormant => {}
+            AccountStatus::Frozen => {
+                return Err(AppError::BadRequest(format!(
+                    "Account {} is already frozen",
+                    id
+                )));
+            }
+            AccountStatus::Closed => return Err(AppError::AccountClosed(id)),
+        }
+        let account = self
+            .change_status(id, account.status, AccountStatus::Frozen)
+            .await?;
+        self.emit(DomainEvent::AccountFrozen(account.clone())).await;
+        Ok(account)
+    }
+
+    /// Returns a frozen account to active use, on behalf of `actor`, an
+    /// admin key.
+    pub async fn unfreeze_account(
+        &self,
+        actor: &ActorContext,
+        id: AccountId,
+    ) -> Result<Account, AppError> {
+        self.ensure_admin_of(actor, id).await?;
         self.repo
-            .get_transaction(id)
+            .update_account_status(
+                id,
+                AccountStatus::Frozen,
+                AccountStatus::Active,
+                self.clock.now(),
+            )
             .await
-            .map_err(Into::into)
-            .and_then(|opt| opt.ok_or_else(|| AppError::NotFound(format!("Transaction {}", id))))
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::BadRequest(format!("Account {} is not frozen", id)))
     }
 
-    /// Lists transactions for an account.
-    pub async fn list_transactions(
+    /// Closes an account for good, on behalf of `actor`, an admin key. Its
+    /// balance must be zero and nothing may be held on it.
+    pub async fn close_account(
         &self,
-        account_id: AccountId,
-    ) -> Result<Vec<Transaction>, AppError> {
-        // Verify account exists first
-        let _ = self.get_account(account_id).await?;
+        actor: &ActorContext,
+        id: AccountId,
+    ) -> Result<Account, AppError> {
+        self.ensure_admin_of(actor, id).await?;
+        let account = self.load_account(id).await?;
+        if account.status == AccountStatus::Closed {
+            return Err(AppError::BadRequest(format!(
+                "Account {} is already closed",
+                id
+            )));
+        }
+        if account.balance.amount() != 0 || account.held != 0 {
+            return Err(AppError::BadRequest(format!(
+                "Account {} must have a zero balance and no open holds to be closed",
+                id
+            )));
+        }
+        self.change_status(id, account.status, AccountStatus::Closed)
+            .await
+    }
 
+    /// Moves an account out of `from`, which it was just read in.
+    async fn change_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+    ) -> Result<Account, AppError> {
         self.repo
-            .list_transactions_for_account(account_id)
+            .update_account_status(id, from, to, self.clock.now())
             .await
-            .map_err(Into::into)
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::Conflict(format!("Account {} changed status", id)))
     }
 
-    // ─────────────────────────────────────────────────────────────────────────────
-    // Webhook Logic
-    // ─────────────────────────────────────────────────────────────────────────────
-
-    async fn trigger_webhook(&self, event_type: &str, payload: serde_json::Value) {
-        use payments_types::WebhookEndpointId;
-
-        // 1. List all endpoints (naive approach, better would be to filter in DB)
-        let endpoints = match self.repo.list_webhook_endpoints().await {
-            Ok(eps) => eps,
-            Err(e) => {
-                tracing::error!("Failed to list webhooks for trigger: {}", e);
-                return;
+    /// Merges a duplicate account into another, emitting `account.merged`.
+    ///
+    /// The whole balance of `source` moves to `target` in one transfer and
+    /// `source` is closed, linked to `target` through `merged_into`, in a
+    /// single unit of work. Its past transactions stay as they were. The
+    /// transfer skips amount caps, fees and the risk check, since the money
+    /// stays with the same customer.
+    ///
+    /// Both accounts must be open, in the same currency and in the mode of
+    /// `actor`, an admin key, and `source` may be neither frozen, overdrawn
+    /// nor have open holds.
+    pub async fn merge_account(
+        &self,
+        actor: &ActorContext,
+        source: AccountId,
+        target: AccountId,
+    ) -> Result<AccountMerge, AppError> {
+        self.ensure_admin_of(actor, source).await?;
+        self.ensure_mode(actor, target).await?;
+        if source == target {
+            return Err(AppError::BadRequest(
+                "An account cannot be merged into itself".into(),
+            ));
+        }
+        let from = self.load_account(source).await?;
+        let into = self.load_account(target).await?;
+        for account in [&from, &into] {
+            if account.status == AccountStatus::Closed {
+                return Err(AppError::AccountClosed(account.id));
             }
-        };
+        }
+        if from.status == AccountStatus::Frozen {
+            return Err(AppError::AccountFrozen(source));
+        }
+        if from.currency() != into.currency() {
+            return Err(AppError::CurrencyMismatch {
+                expected: into.currency(),
+                got: from.currency(),
+            });
+        }
+        if from.held != 0 {
+            return Err(AppError::BadRequest(format!(
+                "Account {} has open holds; capture or void them before merging",
+                source
+            )));
+        }
+        if from.balance.amount() < 0 {
+            return Err(AppError::BadRequest(format!(
+                "Account {} is overdrawn; settle its balance before merging",
+                source
+            )));
+        }
 
-        // 2. Filter interesting endpoints
-        // Note: For demo simplicity, we match exact string. A regex or wildcard system is better.
-        let targets: Vec<_> = endpoints
+        let mode = from.mode;
+        let endpoints = self.active_webhook_endpoints().await?;
+        let mut work = self.repo.begin().await?;
+        let moved = if from.balance.amount() > 0 {
+            let transfer = TransferRequest {
+                from_account_id: source,
+                to_account_id: target,
+                amount: PositiveAmount::new(from.balance.amount())?,
+                currency: from.currency(),
+                idempotency_key: None,
+                reference: Some(format!("Merge of account {}", source)),
+                purpose_code: None,
+                convert_currency: false,
+                quote_id: None,
+                metadata: HashMap::new(),
+                encrypted_memo: None,
+            };
+            Some(
+                self.stage_payment(
+                    work.as_mut(),
+                    &endpoints,
+                    LedgerOperation::Transfer(transfer, None),
+                    Cleared {
+                        mode,
+                        ..Cleared::default()
+                    },
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
+        let closed = work
+            .close_merged_account(source, target, self.clock.now())
+            .await?
+            .ok_or_else(|| {
+                AppError::Conflict(format!("Account {} changed during the merge", source))
+            })?;
+        let (transaction, transferred) = moved.unzip();
+        // account.merged carries nothing about the target's balance, so its
+        // webhook events are queued before the target is read back
+        let mut merge = AccountMerge {
+            source: closed,
+            target: into,
+            transaction,
+        };
+        let merged = DomainEvent::AccountMerged(Box::new(merge.clone()));
+        stage_event(work.as_mut(), &endpoints, &merged, mode).await?;
+        work.commit().await?;
+
+        if let Some(transferred) = transferred {
+            self.announce(transferred).await;
+        }
+        self.announce(LiveEvent {
+            mode,
+            event: merged,
+        })
+        .await;
+        merge.target = self.load_account(target).await?;
+        Ok(merge)
+    }
+
+    /// Rejects a debit from a frozen or closed account, or from a dormant
+    /// one when the policy blocks them. Returns the account otherwise.
+    async fn check_can_debit(&self, account_id: AccountId) -> Result<Account, AppError> {
+        let account = self.load_account(account_id).await?;
+        match account.status {
+            AccountStatus::Active => Ok(account),
+            AccountStatus::Dormant if !self.dormancy.block_withdrawals => Ok(account),
+            AccountStatus::Dormant => Err(AppError::AccountDormant(account_id)),
+            AccountStatus::Frozen => Err(AppError::AccountFrozen(account_id)),
+            AccountStatus::Closed => Err(AppError::AccountClosed(account_id)),
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Account Aliases
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Returns the account an ID or alias refers to.
+    ///
+    /// IDs are returned as given; whether the account exists is left to the
+    /// operation that uses it.
+    pub async fn resolve_account(&self, account: &AccountRef) -> Result<AccountId, AppError> {
+        match account {
+            AccountRef::Id(id) => Ok(*id),
+            AccountRef::Alias(alias) => Ok(self.load_account_alias(alias).await?.account_id),
+        }
+    }
+
+    /// Gets the account an alias is registered to, which `actor` must have
+    /// access to.
+    pub async fn get_account_alias(
+        &self,
+        actor: &ActorContext,
+        alias: &Alias,
+    ) -> Result<AccountAlias, AppError> {
+        let entry = self.load_account_alias(alias).await?;
+        self.ensure_account_access(actor, entry.account_id).await?;
+        Ok(entry)
+    }
+
+    async fn load_account_alias(&self, alias: &Alias) -> Result<AccountAlias, AppError> {
+        self.repo
+            .get_account_alias(alias)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Alias {}", alias)))
+    }
+
+    /// Lists the aliases of an account `actor` has access to, oldest first.
+    pub async fn list_account_aliases(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo
+            .list_account_aliases(account_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Registers an alias for an account `actor` has access to.
+    ///
+    /// Adding an alias the account already holds is a no-op. An alias held
+    /// by another account is rejected, as is going over
+    /// [`MAX_ALIASES_PER_ACCOUNT`].
+    pub async fn add_account_alias(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        req: AddAliasRequest,
+    ) -> Result<AccountAlias, AppError> {
+        let alias = Alias::parse(&req.alias).map_err(|e| AppError::BadRequest(e.to_string()))?;
+        let held = self.list_account_aliases(actor, account_id).await?;
+        if let Some(existing) = held.iter().find(|a| a.alias == alias) {
+            return Ok(existing.clone());
+        }
+        if held.len() >= MAX_ALIASES_PER_ACCOUNT {
+            return Err(AppError::BadRequest(format!(
+                "An account may have at most {} aliases",
+                MAX_ALIASES_PER_ACCOUNT
+            )));
+        }
+
+        self.repo
+            .add_account_alias(&AccountAlias {
+                alias,
+                account_id,
+                created_at: self.clock.now(),
+            })
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Removes an alias from an account `actor` has access to, freeing it
+    /// for reuse.
+    pub async fn remove_account_alias(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        alias: &str,
+    ) -> Result<(), AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        let alias = Alias::parse(alias).map_err(|e| AppError::BadRequest(e.to_string()))?;
+        let removed = self
+            .repo
+            .remove_account_alias(account_id, &alias)
+            .await
+            .map_err(AppError::from)?;
+        if !removed {
+            return Err(AppError::NotFound(format!(
+                "Alias {} on account {}",
+                alias, account_id
+            )));
+        }
+        Ok(())
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Owners
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Creates an account owner on behalf of `actor`, an admin key.
+    pub async fn create_owner(
+        &self,
+        actor: &ActorContext,
+        req: CreateOwnerRequest,
+    ) -> Result<Owner, AppError> {
+        actor.ensure_admin()?;
+        let mut owner = Owner::new(&req.name, req.email.as_deref(), self.clock.now())
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        owner.id = OwnerId::from_uuid(self.ids.new_id());
+
+        self.repo.create_owner(&owner).await.map_err(AppError::from)
+    }
+
+    /// Gets an owner by ID for `actor`, an admin key or one bound to that
+    /// owner.
+    pub async fn get_owner(&self, actor: &ActorContext, id: OwnerId) -> Result<Owner, AppError> {
+        actor.ensure_owner(id)?;
+        self.load_owner(id).await
+    }
+
+    async fn load_owner(&self, id: OwnerId) -> Result<Owner, AppError> {
+        self.repo
+            .get_owner(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Owner {}", id)))
+    }
+
+    /// Lists every owner, oldest first, for `actor`, an admin key.
+    pub async fn list_owners(&self, actor: &ActorContext) -> Result<Vec<Owner>, AppError> {
+        actor.ensure_admin()?;
+        self.repo.list_owners().await.map_err(Into::into)
+    }
+
+    /// Renames an owner or changes its email, on behalf of `actor`, an
+    /// admin key.
+    pub async fn update_owner(
+        &self,
+        actor: &ActorContext,
+        id: OwnerId,
+        req: UpdateOwnerRequest,
+    ) -> Result<Owner, AppError> {
+        actor.ensure_admin()?;
+        let mut owner = self.load_owner(id).await?;
+        if let Some(name) = &req.name {
+            owner
+                .rename(name)
+                .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        }
+        if let Some(email) = &req.email {
+            owner
+                .set_email(Some(email))
+                .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        }
+
+        self.repo
+            .update_owner(&owner)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Owner {}", id)))
+    }
+
+    /// Deletes an owner that holds no accounts, on behalf of `actor`, an
+    /// admin key. The default owner is never deleted.
+    pub async fn delete_owner(&self, actor: &ActorContext, id: OwnerId) -> Result<(), AppError> {
+        actor.ensure_admin()?;
+        if id == OwnerId::DEFAULT {
+            return Err(AppError::BadRequest(
+                "The default owner cannot be deleted".into(),
+            ));
+        }
+        self.load_owner(id).await?;
+        let accounts = self.repo.list_owner_accounts(id, None).await?;
+        if !accounts.is_empty() {
+            return Err(AppError::Conflict(format!(
+                "Owner {} still holds accounts",
+                id
+            )));
+        }
+
+        if self.repo.delete_owner(id).await.map_err(AppError::from)? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("Owner {}", id)))
+        }
+    }
+
+    /// The identity `api_key` acts with, including the accounts its owner
+    /// holds in the key's mode when it is bound to one.
+    pub async fn actor_for(&self, api_key: &ApiKey) -> Result<ActorContext, RepoError> {
+        let actor = ActorContext::from(api_key);
+        match api_key.owner_id {
+            Some(owner_id) => {
+                let accounts = self
+                    .repo
+                    .list_owner_accounts(owner_id, Some(api_key.mode))
+                    .await?;
+                Ok(actor.with_owned_accounts(accounts.into_iter().map(|a| a.id).collect()))
+            }
+            None => Ok(actor),
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Memo Keys
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Registers the metadata of a key an account `actor` has access to
+    /// encrypts memos with.
+    ///
+    /// The key itself never reaches the service. An account may hold at
+    /// most [`MAX_MEMO_KEYS_PER_ACCOUNT`] active keys.
+    pub async fn add_memo_key(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        req: CreateMemoKeyRequest,
+    ) -> Result<MemoKey, AppError> {
+        let key = MemoKey::new(
+            account_id,
+            &req.algorithm,
+            &req.fingerprint,
+            self.clock.now(),
+        )
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        let active = self
+            .list_memo_keys(actor, account_id)
+            .await?
+            .iter()
+            .filter(|k| k.is_active())
+            .count();
+        if active >= MAX_MEMO_KEYS_PER_ACCOUNT {
+            return Err(AppError::BadRequest(format!(
+                "An account may have at most {} active memo keys",
+                MAX_MEMO_KEYS_PER_ACCOUNT
+            )));
+        }
+
+        self.repo.add_memo_key(&key).await.map_err(AppError::from)
+    }
+
+    /// Lists the memo keys of an account `actor` has access to, retired ones
+    /// included, oldest first.
+    pub async fn list_memo_keys(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<Vec<MemoKey>, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo
+            .list_memo_keys(account_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Gets one of the memo keys of an account `actor` has access to.
+    pub async fn get_memo_key(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        id: MemoKeyId,
+    ) -> Result<MemoKey, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo
+            .get_memo_key(id)
+            .await
+            .map_err(AppError::from)?
+            .filter(|k| k.account_id == account_id)
+            .ok_or_else(|| AppError::NotFound(format!("Memo key {} on account {}", id, account_id)))
+    }
+
+    /// Retires a memo key of an account `actor` has access to, so new memos
+    /// can no longer use it. Memos already written with it are kept;
+    /// retiring a key twice keeps the first time.
+    pub async fn retire_memo_key(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        id: MemoKeyId,
+    ) -> Result<MemoKey, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo
+            .retire_memo_key(account_id, id, self.clock.now())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Memo key {} on account {}", id, account_id)))
+    }
+
+    /// Rejects a memo that is not base64 or whose key is unknown, retired,
+    /// or held by none of `parties`.
+    async fn check_memo(
+        &self,
+        memo: Option<&EncryptedMemo>,
+        parties: &[AccountId],
+    ) -> Result<(), AppError> {
+        let Some(memo) = memo else {
+            return Ok(());
+        };
+        memo.validate()
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        let key = self
+            .repo
+            .get_memo_key(memo.key_id)
+            .await
+            .map_err(AppError::from)?
+            .filter(|k| parties.contains(&k.account_id))
+            .ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "Memo key {} is not held by an account in this payment",
+                    memo.key_id
+                ))
+            })?;
+        if !key.is_active() {
+            return Err(AppError::BadRequest(format!(
+                "Memo key {} is retired",
+                key.id
+            )));
+        }
+        Ok(())
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // API Keys
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Issues a new API key in `mode` holding `scopes`, returning it with the
+    /// raw key (shown only once). `created_by` is the admin key issuing it,
+    /// if any.
+    pub async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+        created_by: Option<ApiKeyId>,
+    ) -> Result<(ApiKey, String), AppError> {
+        let scopes = normalize_scopes(scopes)?;
+        let (api_key, raw_key) = self
+            .repo
+            .create_api_key(name, &scopes, mode)
+            .await
+            .map_err(AppError::from)?;
+        self.audit_api_key(api_key.id, ApiKeyAuditAction::Created, created_by)
+            .await?;
+        self.emit(DomainEvent::ApiKeyCreated(api_key.clone())).await;
+
+        Ok((api_key, raw_key))
+    }
+
+    /// Issues a new API key holding `scopes` that can only operate on
+    /// `account_id`, in the account's mode.
+    pub async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[ApiKeyScope],
+        created_by: Option<ApiKeyId>,
+    ) -> Result<(ApiKey, String), AppError> {
+        let scopes = normalize_scopes(scopes)?;
+        let account = self.load_account(account_id).await?;
+        let (api_key, raw_key) = self
+            .repo
+            .create_scoped_api_key(name, account_id, &scopes, account.mode)
+            .await
+            .map_err(AppError::from)?;
+        self.audit_api_key(api_key.id, ApiKeyAuditAction::Created, created_by)
+            .await?;
+        self.emit(DomainEvent::ApiKeyCreated(api_key.clone())).await;
+
+        Ok((api_key, raw_key))
+    }
+
+    /// Issues a new API key in `mode` holding `scopes` that can only operate
+    /// on the accounts `owner_id` holds in that mode.
+    pub async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+        created_by: Option<ApiKeyId>,
+    ) -> Result<(ApiKey, String), AppError> {
+        let scopes = normalize_scopes(scopes)?;
+        self.load_owner(owner_id).await?;
+        let (api_key, raw_key) = self
+            .repo
+            .create_owner_api_key(name, owner_id, &scopes, mode)
+            .await
+            .map_err(AppError::from)?;
+        self.audit_api_key(api_key.id, ApiKeyAuditAction::Created, created_by)
+            .await?;
+        self.emit(DomainEvent::ApiKeyCreated(api_key.clone())).await;
+
+        Ok((api_key, raw_key))
+    }
+
+    /// A key, deleted or not, with its audit trail.
+    pub async fn api_key_audit(&self, id: ApiKeyId) -> Result<ApiKeyAudit, AppError> {
+        let key = self
+            .repo
+            .get_api_key(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("API key {}", id)))?;
+        let entries = self
+            .repo
+            .list_api_key_audit(id)
+            .await
+            .map_err(AppError::from)?;
+        Ok(ApiKeyAudit {
+            api_key_id: key.id,
+            name: key.name,
+            is_active: key.is_active,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+            deactivated_at: key.deactivated_at,
+            deactivated_by: key.deactivated_by,
+            entries,
+        })
+    }
+
+    /// Sets how many requests per minute a key may make, or restores the
+    /// server-wide default when `None`. The rate limiter picks it up on the
+    /// key's next request.
+    pub async fn set_api_key_rate_limit(
+        &self,
+        id: ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<ApiKey, AppError> {
+        if let Some(limit) = rate_limit_per_minute
+            && !(1..=MAX_RATE_LIMIT_PER_MINUTE).contains(&limit)
+        {
+            return Err(AppError::BadRequest(format!(
+                "Rate limit must be between 1 and {} requests per minute",
+                MAX_RATE_LIMIT_PER_MINUTE
+            )));
+        }
+        let not_found = || AppError::NotFound(format!("API key {}", id));
+        if !self
+            .repo
+            .set_api_key_rate_limit(id, rate_limit_per_minute)
+            .await?
+        {
+            return Err(not_found());
+        }
+        self.repo.get_api_key(id).await?.ok_or_else(not_found)
+    }
+
+    /// Notes that `api_key` authenticated a request: moves its
+    /// `last_used_at` on and audits the use, at most once every
+    /// [`LAST_USED_RESOLUTION_SECS`].
+    ///
+    /// Like [`record_api_request`](Self::record_api_request), errors are only
+    /// logged.
+    pub async fn record_api_key_use(&self, api_key: &ApiKey) {
+        let now = self.clock.now();
+        let resolution = TimeDelta::seconds(LAST_USED_RESOLUTION_SECS);
+        if api_key
+            .last_used_at
+            .is_some_and(|last_used| now - last_used < resolution)
+        {
+            return;
+        }
+        let recorded = match self.repo.touch_api_key(api_key.id, now).await {
+            Ok(()) => self
+                .audit_api_key(api_key.id, ApiKeyAuditAction::Used, None)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record use of API key {}: {}", api_key.id, e);
+        }
+    }
+
+    /// Appends `action` by `actor` to a key's audit trail.
+    async fn audit_api_key(
+        &self,
+        api_key_id: ApiKeyId,
+        action: ApiKeyAuditAction,
+        actor: Option<ApiKeyId>,
+    ) -> Result<(), AppError> {
+        let entry = ApiKeyAuditEntry {
+            api_key_id,
+            action,
+            actor,
+            at: self.clock.now(),
+        };
+        self.repo
+            .insert_api_key_audit(&entry)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Issues a token that lets a dashboard read `account_id` on behalf of
+    /// `actor`, which must have access to it, for `ttl_secs` (by default
+    /// [`DEFAULT_SESSION_TTL_SECS`]).
+    ///
+    /// The token holds whichever of [`SessionToken::SCOPES`] the key holds.
+    pub async fn create_session_token(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        ttl_secs: Option<u64>,
+    ) -> Result<SessionToken, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        let ttl = ttl_secs.unwrap_or(DEFAULT_SESSION_TTL_SECS);
+        if ttl == 0 || ttl > MAX_SESSION_TTL_SECS {
+            return Err(AppError::BadRequest(format!(
+                "expires_in_secs must be between 1 and {}",
+                MAX_SESSION_TTL_SECS
+            )));
+        }
+        let scopes = session_scopes(&actor.scopes);
+        if scopes.is_empty() {
+            return Err(AppError::BadRequest(
+                "API key holds no read scopes to pass on to a session token".into(),
+            ));
+        }
+
+        // Tokens carry their expiry in whole seconds.
+        let expires_at = (self.clock.now() + TimeDelta::seconds(ttl as i64)).trunc_subsecs(0);
+        Ok(SessionToken {
+            token: self
+                .session_tokens
+                .issue(actor.api_key_id, account_id, expires_at),
+            account_id,
+            scopes,
+            expires_at,
+        })
+    }
+
+    /// Resolves a session token to the API key its requests act as: the
+    /// issuing key, restricted to the token's account and read scopes.
+    /// Returns `None` if the token is invalid or expired, or its key has been
+    /// deleted since.
+    pub async fn verify_session_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<(ApiKey, SessionClaims)>, RepoError> {
+        let Some(claims) = self.session_tokens.verify(token, self.clock.now()) else {
+            return Ok(None);
+        };
+        let issuer = self.repo.get_api_key(claims.api_key_id).await?;
+        Ok(issuer
+            .filter(|key| key.is_active)
+            .map(|key| (claims.restrict(key), claims)))
+    }
+
+    /// Request and money-movement totals for a key, revoked or not, over
+    /// each of [`USAGE_WINDOW_HOURS`](payments_types::USAGE_WINDOW_HOURS).
+    pub async fn api_key_usage(&self, id: ApiKeyId) -> Result<ApiKeyUsage, AppError> {
+        self.repo
+            .get_api_key(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("API key {}", id)))?;
+        let now = self.clock.now();
+        let since = usage_window_start(now, MAX_USAGE_WINDOW_HOURS);
+        let usage = self
+            .repo
+            .list_api_key_usage(id, since)
+            .await
+            .map_err(AppError::from)?;
+        let volume = self
+            .repo
+            .list_api_key_volume(id, since)
+            .await
+            .map_err(AppError::from)?;
+        Ok(ApiKeyUsage::summarize(id, now, &usage, &volume))
+    }
+
+    /// Counts one request made with `api_key_id`.
+    ///
+    /// Metering never fails the request it meters, so errors are only logged.
+    pub async fn record_api_request(&self, api_key_id: ApiKeyId, rate_limited: bool) {
+        let bucket = ApiKeyUsageBucket {
+            api_key_id,
+            hour: usage_hour(self.clock.now()),
+            requests: 1,
+            rate_limited: i64::from(rate_limited),
+        };
+        if let Err(e) = self.repo.add_api_key_usage(&bucket).await {
+            tracing::warn!("Failed to record usage for API key {}: {}", api_key_id, e);
+        }
+    }
+
+    /// Adds a completed transaction to `api_key_id`'s money-movement totals.
+    ///
+    /// Like [`record_api_request`](Self::record_api_request), errors are only
+    /// logged.
+    pub async fn record_api_key_transaction(&self, api_key_id: ApiKeyId, tx: &Transaction) {
+        let bucket = ApiKeyVolumeBucket {
+            api_key_id,
+            hour: usage_hour(self.clock.now()),
+            transaction_type: tx.transaction_type,
+            currency: tx.amount.currency(),
+            count: 1,
+            amount: tx.amount.amount(),
+        };
+        if let Err(e) = self.repo.add_api_key_volume(&bucket).await {
+            tracing::warn!("Failed to record volume for API key {}: {}", api_key_id, e);
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Change Approval
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Records `actor`'s request to apply `action`, to be applied once a
+    /// different admin key approves it. Only admin keys may request changes.
+    pub async fn request_change(
+        &self,
+        actor: &ActorContext,
+        action: ChangeAction,
+    ) -> Result<ChangeRequest, AppError> {
+        actor.ensure_admin()?;
+        let requested_by = actor.api_key_id;
+        match &action {
+            ChangeAction::DeleteApiKey { api_key_id } => {
+                let key = self
+                    .repo
+                    .get_api_key(*api_key_id)
+                    .await
+                    .map_err(AppError::from)?;
+                if !key.is_some_and(|key| key.is_active) {
+                    return Err(AppError::NotFound(format!("API key {}", api_key_id)));
+                }
+            }
+            ChangeAction::SetWebhookUrl { endpoint_id, url } => {
+                if url.trim().is_empty() {
+                    return Err(AppError::BadRequest("Webhook URL cannot be empty".into()));
+                }
+                self.webhook_endpoint(actor, *endpoint_id).await?;
+            }
+        }
+
+        let request = ChangeRequest::new(action, requested_by, self.clock.now());
+        self.repo
+            .insert_change_request(&request)
+            .await
+            .map_err(AppError::from)?;
+        tracing::info!(
+            change_id = %request.id,
+            requested_by = %requested_by,
+            "Change requested: {}",
+            request.action
+        );
+        Ok(request)
+    }
+
+    /// Gets a change request by ID.
+    pub async fn get_change_request(&self, id: ChangeRequestId) -> Result<ChangeRequest, AppError> {
+        self.repo
+            .get_change_request(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Change request {}", id)))
+    }
+
+    /// Lists change requests, optionally only those in `status`, newest first.
+    pub async fn list_change_requests(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, AppError> {
+        self.repo
+            .list_change_requests(status)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Applies a pending change on behalf of `actor`, an admin key other
+    /// than the one that requested it.
+    pub async fn approve_change(
+        &self,
+        id: ChangeRequestId,
+        actor: &ActorContext,
+    ) -> Result<ChangeRequest, AppError> {
+        actor.ensure_admin()?;
+        let approver = actor.api_key_id;
+        let request = self.pending_change(id).await?;
+        if request.requested_by == approver {
+            return Err(AppError::BadRequest(
+                "A change must be approved by a different admin key than the one that requested it"
+                    .into(),
+            ));
+        }
+
+        match &request.action {
+            // A key deleted since the request was made needs nothing more.
+            ChangeAction::DeleteApiKey { api_key_id } => {
+                let deleted = self
+                    .repo
+                    .delete_api_key(*api_key_id, approver, self.clock.now())
+                    .await
+                    .map_err(AppError::from)?;
+                if deleted {
+                    self.audit_api_key(*api_key_id, ApiKeyAuditAction::Deactivated, Some(approver))
+                        .await?;
+                }
+            }
+            ChangeAction::SetWebhookUrl { endpoint_id, url } => {
+                self.webhook_endpoint(actor, *endpoint_id).await?;
+                self.repo
+                    .update_webhook_endpoint_url(*endpoint_id, url)
+                    .await
+                    .map_err(AppError::from)?
+                    .ok_or_else(|| {
+                        AppError::NotFound(format!("Webhook endpoint {}", endpoint_id))
+                    })?;
+                self.webhook_endpoints.invalidate();
+            }
+        }
+
+        let approved = self
+            .decide_change(id, ChangeStatus::Approved, approver)
+            .await?;
+        tracing::info!(
+            change_id = %id,
+            requested_by = %approved.requested_by,
+            approved_by = %approver,
+            "Change approved: {}",
+            approved.action
+        );
+        Ok(approved)
+    }
+
+    /// Turns down a pending change without applying it. Any admin key may
+    /// reject, including the one that requested it.
+    pub async fn reject_change(
+        &self,
+        id: ChangeRequestId,
+        actor: &ActorContext,
+    ) -> Result<ChangeRequest, AppError> {
+        actor.ensure_admin()?;
+        let rejected_by = actor.api_key_id;
+        self.pending_change(id).await?;
+        let rejected = self
+            .decide_change(id, ChangeStatus::Rejected, rejected_by)
+            .await?;
+        tracing::info!(
+            change_id = %id,
+            requested_by = %rejected.requested_by,
+            rejected_by = %rejected_by,
+            "Change rejected: {}",
+            rejected.action
+        );
+        Ok(rejected)
+    }
+
+    /// Gets a change request that has not been decided yet.
+    async fn pending_change(&self, id: ChangeRequestId) -> Result<ChangeRequest, AppError> {
+        let request = self.get_change_request(id).await?;
+        if request.status != ChangeStatus::Pending {
+            return Err(AppError::BadRequest(format!(
+                "Change request {} is already {}",
+                id, request.status
+            )));
+        }
+        Ok(request)
+    }
+
+    /// Records a decision, failing if another admin decided first.
+    async fn decide_change(
+        &self,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: ApiKeyId,
+    ) -> Result<ChangeRequest, AppError> {
+        self.repo
+            .decide_change_request(id, status, decided_by, self.clock.now())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| {
+                AppError::BadRequest(format!("Change request {} was already decided", id))
+            })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Fee Schedules
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Creates a fee schedule on behalf of `actor`, an admin key. Schedules
+    /// cannot be edited afterwards.
+    pub async fn create_fee_schedule(
+        &self,
+        actor: &ActorContext,
+        req: CreateFeeScheduleRequest,
+    ) -> Result<FeeSchedule, AppError> {
+        actor.ensure_admin()?;
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest(
+                "Fee schedule name cannot be empty".into(),
+            ));
+        }
+        FeeSchedule::validate_tiers(&req.tiers).map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        self.repo
+            .create_fee_schedule(name, &req.tiers)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Gets a fee schedule by ID.
+    pub async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<FeeSchedule, AppError> {
+        self.repo
+            .get_fee_schedule(id)
+            .await
+            .map_err(Into::into)
+            .and_then(|opt| opt.ok_or_else(|| AppError::NotFound(format!("Fee schedule {}", id))))
+    }
+
+    /// Lists all fee schedules, newest first.
+    pub async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, AppError> {
+        self.repo.list_fee_schedules().await.map_err(Into::into)
+    }
+
+    /// Applies a fee schedule to an account from `effective_from` (default: now).
+    ///
+    /// Back-dated assignments are rejected so that payments already made keep
+    /// the pricing that applied at the time.
+    pub async fn assign_fee_schedule(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        req: AssignFeeScheduleRequest,
+    ) -> Result<FeeAssignment, AppError> {
+        self.ensure_admin_of(actor, account_id).await?;
+        let now = self.clock.now();
+        let effective_from = req.effective_from.unwrap_or(now);
+        if effective_from < now {
+            return Err(AppError::BadRequest(
+                "effective_from cannot be in the past: fee changes apply prospectively".into(),
+            ));
+        }
+        self.load_account(account_id).await?;
+        self.get_fee_schedule(req.fee_schedule_id).await?;
+
+        self.repo
+            .assign_fee_schedule(account_id, req.fee_schedule_id, effective_from)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Gets the fee schedule history of an account `actor` has access to and
+    /// the assignment in force now.
+    pub async fn account_fees(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<AccountFees, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        let assignments = self
+            .repo
+            .list_fee_assignments(account_id)
+            .await
+            .map_err(AppError::from)?;
+        let current = FeeAssignment::effective_at(&assignments, self.clock.now()).cloned();
+
+        Ok(AccountFees {
+            current,
+            assignments,
+        })
+    }
+
+    /// Quotes the fee the current schedule of an account `actor` has access
+    /// to puts on a payment of `amount`.
+    pub async fn quote_fee(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        amount: PositiveAmount,
+    ) -> Result<FeeQuote, AppError> {
+        let Some(current) = self.account_fees(actor, account_id).await?.current else {
+            return Ok(FeeQuote {
+                account_id,
+                amount: amount.get(),
+                fee: 0,
+                fee_schedule_id: None,
+            });
+        };
+        let schedule = self.get_fee_schedule(current.fee_schedule_id).await?;
+
+        Ok(FeeQuote {
+            account_id,
+            amount: amount.get(),
+            fee: schedule.fee_for(amount.get()),
+            fee_schedule_id: Some(schedule.id),
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Settlement Batches
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Batches every unbatched payout from an account in `actor`'s mode made
+    /// up to the cut-off (default: now).
+    ///
+    /// Future cut-offs are rejected: payouts made after the batch is created
+    /// would fall before its cut-off yet never be in it.
+    pub async fn create_settlement_batch(
+        &self,
+        actor: &ActorContext,
+        req: CreateSettlementBatchRequest,
+    ) -> Result<SettlementBatch, AppError> {
+        actor.ensure_admin()?;
+        let now = self.clock.now();
+        let cutoff = req.cutoff.unwrap_or(now);
+        if cutoff > now {
+            return Err(AppError::BadRequest(
+                "cutoff cannot be in the future".into(),
+            ));
+        }
+
+        self.repo
+            .create_settlement_batch(cutoff, actor.mode)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "No unbatched payouts up to {}",
+                    cutoff.to_rfc3339()
+                ))
+            })
+    }
+
+    /// Gets a settlement batch by ID for `actor`, an admin key.
+    pub async fn get_settlement_batch(
+        &self,
+        actor: &ActorContext,
+        id: SettlementBatchId,
+    ) -> Result<SettlementBatch, AppError> {
+        actor.ensure_admin()?;
+        self.load_settlement_batch(id, actor.mode).await
+    }
+
+    /// Gets settlement batch `id`; batches in the other mode than `mode` are
+    /// not found.
+    async fn load_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+        mode: Mode,
+    ) -> Result<SettlementBatch, AppError> {
+        self.repo
+            .get_settlement_batch(id)
+            .await
+            .map_err(AppError::from)?
+            .filter(|batch| batch.mode == mode)
+            .ok_or_else(|| AppError::NotFound(format!("Settlement batch {}", id)))
+    }
+
+    /// Lists the settlement batches in `actor`'s mode, newest first, for
+    /// `actor`, an admin key.
+    pub async fn list_settlement_batches(
+        &self,
+        actor: &ActorContext,
+    ) -> Result<Vec<SettlementBatch>, AppError> {
+        actor.ensure_admin()?;
+        self.repo
+            .list_settlement_batches(Some(actor.mode))
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Moves a settlement batch to `status`; cancelling releases its payouts
+    /// into the next batch.
+    pub async fn update_settlement_batch_status(
+        &self,
+        actor: &ActorContext,
+        id: SettlementBatchId,
+        status: SettlementBatchStatus,
+    ) -> Result<SettlementBatch, AppError> {
+        actor.ensure_admin()?;
+        let batch = self.load_settlement_batch(id, actor.mode).await?;
+        if !batch.status.can_transition_to(status) {
+            return Err(AppError::BadRequest(format!(
+                "Settlement batch {} is {} and cannot become {}",
+                id, batch.status, status
+            )));
+        }
+
+        self.repo
+            .update_settlement_batch_status(id, batch.status, status)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "Settlement batch {} changed status concurrently, retry",
+                    id
+                ))
+            })
+    }
+
+    /// Renders a settlement batch's payouts as a file for the bank, for
+    /// `actor`, an admin key.
+    pub async fn export_settlement_batch(
+        &self,
+        actor: &ActorContext,
+        id: SettlementBatchId,
+        format: SettlementExportFormat,
+    ) -> Result<String, AppError> {
+        actor.ensure_admin()?;
+        self.settlement_file(id, format, actor.mode).await
+    }
+
+    async fn settlement_file(
+        &self,
+        id: SettlementBatchId,
+        format: SettlementExportFormat,
+        mode: Mode,
+    ) -> Result<String, AppError> {
+        let batch = self.load_settlement_batch(id, mode).await?;
+        if batch.status == SettlementBatchStatus::Cancelled {
+            return Err(AppError::BadRequest(format!(
+                "Settlement batch {} was cancelled and has no payouts",
+                id
+            )));
+        }
+        let payouts = self
+            .repo
+            .list_settlement_batch_payouts(id)
+            .await
+            .map_err(AppError::from)?;
+
+        match format {
+            SettlementExportFormat::Csv => Ok(render_csv(&payouts)),
+            SettlementExportFormat::Pain001 => {
+                let debtor = self.settlement_debtor.as_ref().ok_or_else(|| {
+                    AppError::BadRequest(
+                        "pain.001 export needs a settlement debtor account to be configured".into(),
+                    )
+                })?;
+                render_pain001(&batch, &payouts, debtor).map_err(AppError::BadRequest)
+            }
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Accounting Export
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Renders every transaction in `actor`'s mode made on the UTC days
+    /// `from..=to` as journal entries for an accounting tool, for `actor`, an
+    /// admin key.
+    pub async fn export_journal(
+        &self,
+        actor: &ActorContext,
+        format: JournalExportFormat,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<String, AppError> {
+        actor.ensure_admin()?;
+        self.journal_file(format, from, to, actor.mode).await
+    }
+
+    async fn journal_file(
+        &self,
+        format: JournalExportFormat,
+        from: NaiveDate,
+        to: NaiveDate,
+        mode: Mode,
+    ) -> Result<String, AppError> {
+        check_period(from, to)?;
+
+        let start = from.and_time(NaiveTime::MIN).and_utc();
+        let end = (to + TimeDelta::days(1)).and_time(NaiveTime::MIN).and_utc();
+        let transactions = self
+            .repo
+            .list_transactions_between(start, end, Some(mode))
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(match format {
+            JournalExportFormat::Quickbooks => render_quickbooks(&transactions, &self.gl_codes),
+            JournalExportFormat::Xero => render_xero(&transactions, &self.gl_codes),
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Event Log
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Lists the logged events after `query.after_sequence` that were recorded
+    /// on the same UTC day as the first of them, for loading into a data
+    /// warehouse one day's partition at a time. `actor` must be an admin key.
+    pub async fn event_log(
+        &self,
+        actor: &ActorContext,
+        query: EventLogQuery,
+    ) -> Result<EventLogPage, AppError> {
+        actor.ensure_admin()?;
+        if query.after_sequence < 0 {
+            return Err(AppError::BadRequest(
+                "after_sequence must not be negative".into(),
+            ));
+        }
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_EVENT_LOG_PAGE)
+            .clamp(1, MAX_EVENT_LOG_PAGE);
+        let events = self
+            .repo
+            .list_logged_events(query.after_sequence, limit, Some(actor.mode))
+            .await
+            .map_err(AppError::from)?;
+        Ok(EventLogPage::new(query.after_sequence, events))
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Exports
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Queues an export of `actor`'s mode to be rendered by
+    /// [`run_export`](Self::run_export).
+    ///
+    /// The request is checked here so that obvious mistakes fail right away
+    /// instead of in the background. Exports older than a day are pruned
+    /// along the way. `actor` must be an admin key.
+    pub async fn request_export(
+        &self,
+        actor: &ActorContext,
+        request: ExportRequest,
+    ) -> Result<Export, AppError> {
+        actor.ensure_admin()?;
+        match request {
+            ExportRequest::Journal { from, to, .. } => check_period(from, to)?,
+            ExportRequest::SettlementBatch { batch_id, .. } => {
+                self.load_settlement_batch(batch_id, actor.mode).await?;
+            }
+        }
+
+        let now = self.clock.now();
+        if let Err(e) = self
+            .repo
+            .delete_exports_before(now - EXPORT_RETENTION)
+            .await
+        {
+            tracing::warn!("Failed to prune old exports: {}", e);
+        }
+        let export =
+            Export::new(ExportId::from_uuid(self.ids.new_id()), request, now).with_mode(actor.mode);
+        self.repo
+            .insert_export(&export)
+            .await
+            .map_err(AppError::from)?;
+        Ok(export)
+    }
+
+    /// Renders a pending export and records whether it succeeded.
+    ///
+    /// Rendering errors mark the export `FAILED` rather than being returned;
+    /// exports that already finished are returned unchanged.
+    pub async fn run_export(&self, id: ExportId) -> Result<Export, AppError> {
+        let mut export = self.load_export(id).await?;
+        if export.status != ExportStatus::Pending {
+            return Ok(export);
+        }
+
+        let rendered = match export.request {
+            ExportRequest::Journal { format, from, to } => {
+                self.journal_file(format, from, to, export.mode).await
+            }
+            ExportRequest::SettlementBatch { batch_id, format } => {
+                self.settlement_file(batch_id, format, export.mode).await
+            }
+        };
+        export.completed_at = Some(self.clock.now());
+        let content = match rendered {
+            Ok(content) => {
+                export.status = ExportStatus::Ready;
+                Some(content)
+            }
+            Err(e) => {
+                tracing::warn!("Export {} failed: {}", id, e);
+                export.status = ExportStatus::Failed;
+                export.error = Some(e.to_string());
+                None
+            }
+        };
+        self.repo
+            .finish_export(&export, content.as_deref())
+            .await
+            .map_err(AppError::from)?;
+        Ok(export)
+    }
+
+    /// Gets an export, with a fresh download link once it is ready, for
+    /// `actor`, an admin key. Exports of the other mode are not found.
+    pub async fn get_export(
+        &self,
+        actor: &ActorContext,
+        id: ExportId,
+    ) -> Result<ExportDownload, AppError> {
+        actor.ensure_admin()?;
+        let export = self.load_export(id).await?;
+        if export.mode != actor.mode {
+            return Err(AppError::NotFound(format!("Export {}", id)));
+        }
+        let link = (export.status == ExportStatus::Ready)
+            .then(|| self.download_links.url_for(export.id, self.clock.now()));
+        Ok(ExportDownload {
+            export,
+            download_url: link.as_ref().map(|(url, _)| url.clone()),
+            url_expires_at: link.map(|(_, expires_at)| expires_at),
+        })
+    }
+
+    /// Returns an export and its file if `token` is a valid, unexpired
+    /// download token for it.
+    pub async fn download_export(&self, token: &str) -> Result<(Export, String), AppError> {
+        let id = self
+            .download_links
+            .verify(token, self.clock.now())
+            .ok_or_else(|| AppError::BadRequest("Invalid or expired download link".into()))?;
+        let export = self.load_export(id).await?;
+        let content = self
+            .repo
+            .get_export_content(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Export {}", id)))?;
+        Ok((export, content))
+    }
+
+    async fn load_export(&self, id: ExportId) -> Result<Export, AppError> {
+        self.repo
+            .get_export(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Export {}", id)))
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Scheduled Payments
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Sets up a withdrawal or transfer to be made on a schedule from an
+    /// account `actor` has access to, into one in its mode.
+    ///
+    /// Accounts, currencies, the schedule and the purpose code are checked
+    /// up front; limits and balances are checked on each run. The first run
+    /// is the first time the schedule is due at or after `start_at`.
+    pub async fn create_scheduled_payment(
+        &self,
+        actor: &ActorContext,
+        req: CreateScheduledPaymentRequest,
+    ) -> Result<ScheduledPayment, AppError> {
+        self.ensure_account_access(actor, req.from_account_id)
+            .await?;
+        if let Some(to) = req.to_account_id {
+            self.ensure_mode(actor, to).await?;
+        }
+        match (req.transaction_type, req.to_account_id) {
+            (TransactionType::Withdrawal, None) => {}
+            (TransactionType::Withdrawal, Some(_)) => {
+                return Err(AppError::BadRequest(
+                    "Scheduled withdrawals cannot have a to_account_id".into(),
+                ));
+            }
+            (TransactionType::Transfer, None) => {
+                return Err(AppError::BadRequest(
+                    "Scheduled transfers need a to_account_id".into(),
+                ));
+            }
+            (TransactionType::Transfer, Some(to)) if to == req.from_account_id => {
+                return Err(AppError::BadRequest(
+                    "Cannot transfer to the same account".into(),
+                ));
+            }
+            (TransactionType::Transfer, Some(_)) => {}
+            (TransactionType::Deposit, _) => {
+                return Err(AppError::BadRequest(
+                    "Only withdrawals and transfers can be scheduled".into(),
+                ));
+            }
+        }
+        req.schedule
+            .validate()
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        for account_id in std::iter::once(req.from_account_id).chain(req.to_account_id) {
+            let account = self.load_account(account_id).await?;
+            if account.currency() != req.currency {
+                return Err(AppError::BadRequest(format!(
+                    "Account {} holds {}, not {}",
+                    account_id,
+                    account.currency(),
+                    req.currency
+                )));
+            }
+        }
+        let purpose_code = normalize_purpose(req.purpose_code)?;
+        self.check_purpose(req.from_account_id, purpose_code.as_deref())
+            .await?;
+
+        let now = self.clock.now();
+        let start_at = req.start_at.unwrap_or(now);
+        if start_at < now {
+            return Err(AppError::BadRequest("`start_at` is in the past".into()));
+        }
+        let payment = ScheduledPayment {
+            id: ScheduledPaymentId::from_uuid(self.ids.new_id()),
+            transaction_type: req.transaction_type,
+            from_account_id: req.from_account_id,
+            to_account_id: req.to_account_id,
+            amount: req.amount.get(),
+            currency: req.currency,
+            reference: req.reference,
+            purpose_code,
+            next_run_at: req
+                .schedule
+                .first_run(start_at)
+                .map_err(|e| AppError::BadRequest(e.to_string()))?,
+            schedule: req.schedule,
+            status: ScheduledPaymentStatus::Active,
+            last_run_at: None,
+            last_transaction_id: None,
+            last_error: None,
+            created_at: now,
+        };
+        self.repo
+            .insert_scheduled_payment(&payment)
+            .await
+            .map_err(AppError::from)?;
+        Ok(payment)
+    }
+
+    /// Gets a scheduled payment out of an account `actor` has access to.
+    pub async fn get_scheduled_payment(
+        &self,
+        actor: &ActorContext,
+        id: ScheduledPaymentId,
+    ) -> Result<ScheduledPayment, AppError> {
+        let payment = self.load_scheduled_payment(id).await?;
+        self.ensure_account_access(actor, payment.from_account_id)
+            .await?;
+        Ok(payment)
+    }
+
+    async fn load_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<ScheduledPayment, AppError> {
+        self.repo
+            .get_scheduled_payment(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Scheduled payment {}", id)))
+    }
+
+    /// Lists the scheduled payments drawing on the accounts `actor` has
+    /// access to, oldest first; only those drawing on `account_id` when it is
+    /// set.
+    pub async fn list_scheduled_payments(
+        &self,
+        actor: &ActorContext,
+        account_id: Option<AccountId>,
+    ) -> Result<Vec<ScheduledPayment>, AppError> {
+        if let Some(account_id) = account_id {
+            self.ensure_account_access(actor, account_id).await?;
+        }
+        // Keys only ever see the payments out of accounts in their own mode
+        let mut payments = self
+            .repo
+            .list_scheduled_payments(account_id.or(actor.account_id), Some(actor.mode))
+            .await
+            .map_err(AppError::from)?;
+        // Owner-bound keys see the payments of every account their owner holds
+        payments.retain(|p| actor.may_access(p.from_account_id));
+        Ok(payments)
+    }
+
+    /// Stops a scheduled payment out of an account `actor` has access to from
+    /// running until it is resumed.
+    pub async fn pause_scheduled_payment(
+        &self,
+        actor: &ActorContext,
+        id: ScheduledPaymentId,
+    ) -> Result<ScheduledPayment, AppError> {
+        let mut payment = self.get_scheduled_payment(actor, id).await?;
+        if payment.status == ScheduledPaymentStatus::Paused {
+            return Ok(payment);
+        }
+        payment.status = ScheduledPaymentStatus::Paused;
+        self.repo
+            .update_scheduled_payment_status(&payment)
+            .await
+            .map_err(AppError::from)?;
+        Ok(payment)
+    }
+
+    /// Lets a paused payment out of an account `actor` has access to run
+    /// again. Runs that fell due while it was paused are skipped, not made up.
+    pub async fn resume_scheduled_payment(
+        &self,
+        actor: &ActorContext,
+        id: ScheduledPaymentId,
+    ) -> Result<ScheduledPayment, AppError> {
+        let mut payment = self.get_scheduled_payment(actor, id).await?;
+        if payment.status == ScheduledPaymentStatus::Active {
+            return Ok(payment);
+        }
+        payment
+            .resume(self.clock.now())
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        self.repo
+            .update_scheduled_payment_status(&payment)
+            .await
+            .map_err(AppError::from)?;
+        Ok(payment)
+    }
+
+    /// Makes every scheduled payment that is due, returning how many runs
+    /// were recorded.
+    ///
+    /// A run whose transaction is rejected (insufficient funds, a limit, a
+    /// spending rule) is recorded as failed and the payment moves on to its
+    /// next occurrence. A run that hits an internal or availability error is
+    /// left due and retried on the next call; its idempotency key keeps the
+    /// retry from paying twice.
+    pub async fn run_due_scheduled_payments(&self) -> Result<usize, AppError> {
+        let due = self
+            .repo
+            .list_due_scheduled_payments(self.clock.now(), SCHEDULED_PAYMENT_BATCH)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut recorded = 0;
+        for payment in due {
+            let id = payment.id;
+            match self.run_scheduled_payment(payment).await {
+                Ok(true) => recorded += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(scheduled_payment_id = %id, "Scheduled payment will be retried: {}", e)
+                }
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Makes the run of `payment` due at its `next_run_at` and records it.
+    /// Returns `false` if another scheduler recorded that run first.
+    async fn run_scheduled_payment(&self, mut payment: ScheduledPayment) -> Result<bool, AppError> {
+        let due_at = payment.next_run_at;
+        let idempotency_key = Some(payment.idempotency_key(due_at));
+        let result = match payment.to_account_id {
+            Some(to_account_id) => {
+                self.transfer(TransferRequest {
+                    from_account_id: payment.from_account_id,
+                    to_account_id,
+                    amount: PositiveAmount::new(payment.amount)?,
+                    currency: payment.currency,
+                    idempotency_key,
+                    reference: payment.reference.clone(),
+                    purpose_code: payment.purpose_code.clone(),
+                    convert_currency: false,
+                    quote_id: None,
+                    metadata: HashMap::new(),
+                    encrypted_memo: None,
+                })
+                .await
+            }
+            None => {
+                self.withdraw(WithdrawRequest {
+                    account_id: payment.from_account_id,
+                    amount: PositiveAmount::new(payment.amount)?,
+                    currency: payment.currency,
+                    idempotency_key,
+                    reference: payment.reference.clone(),
+                    counterparty: None,
+                    purpose_code: payment.purpose_code.clone(),
+                    metadata: HashMap::new(),
+                    encrypted_memo: None,
+                })
+                .await
+            }
+        };
+        let outcome = match result {
+            Ok(tx) => Ok(tx.id),
+            Err(
+                e @ (AppError::Conflict(_)
+                | AppError::Internal(_)
+                | AppError::ServiceUnavailable(_)),
+            ) => return Err(e),
+            Err(e) => Err(e.to_string()),
+        };
+
+        payment
+            .record_run(self.clock.now(), outcome)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let recorded = self
+            .repo
+            .record_scheduled_payment_run(&payment, due_at)
+            .await
+            .map_err(AppError::from)?;
+        if recorded {
+            let event = match &payment.last_error {
+                None => DomainEvent::ScheduledPaymentExecuted(payment),
+                Some(error) => {
+                    tracing::info!(scheduled_payment_id = %payment.id, "Scheduled payment failed: {}", error);
+                    DomainEvent::ScheduledPaymentFailed(payment)
+                }
+            };
+            self.emit(event).await;
+        }
+        Ok(recorded)
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Holds
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Reserves funds on an account `actor` has access to without moving
+    /// them.
+    ///
+    /// The amount is checked against the same limits and spending rules as a
+    /// withdrawal; the funds leave the account only when the hold is captured.
+    pub async fn authorize(
+        &self,
+        actor: &ActorContext,
+        mut req: AuthorizeRequest,
+    ) -> Result<Hold, AppError> {
+        self.ensure_account_access(actor, req.account_id).await?;
+        let ttl = req.expires_in_secs.unwrap_or(DEFAULT_HOLD_TTL_SECS);
+        if ttl == 0 || ttl > MAX_HOLD_TTL_SECS {
+            return Err(AppError::BadRequest(format!(
+                "expires_in_secs must be between 1 and {}",
+                MAX_HOLD_TTL_SECS
+            )));
+        }
+        let limits = self.limits_for(req.account_id).await?;
+        limits.check_amount(req.amount.get(), req.currency)?;
+        req.purpose_code = normalize_purpose(req.purpose_code)?;
+        let mode = self.check_can_debit(req.account_id).await?.mode;
+        self.check_purpose(req.account_id, req.purpose_code.as_deref())
+            .await?;
+
+        let now = self.clock.now();
+        let hold = Hold {
+            id: HoldId::from_uuid(self.ids.new_id()),
+            account_id: req.account_id,
+            amount: req.amount.get(),
+            currency: req.currency,
+            reference: req.reference,
+            purpose_code: req.purpose_code,
+            status: HoldStatus::Authorized,
+            expires_at: now + TimeDelta::seconds(ttl as i64),
+            captured_amount: None,
+            transaction_id: None,
+            created_at: now,
+            resolved_at: None,
+        };
+        let endpoints = self.active_webhook_endpoints().await?;
+        let mut work = self.repo.begin().await?;
+        self.check_daily_debit(work.as_mut(), req.account_id, &limits, hold.amount)
+            .await?;
+        work.create_hold(&hold).await?;
+        let event = DomainEvent::HoldAuthorized(hold.clone());
+        stage_event(work.as_mut(), &endpoints, &event, mode).await?;
+        work.commit().await?;
+        self.announce(LiveEvent { mode, event }).await;
+        Ok(hold)
+    }
+
+    /// Gets a hold on an account `actor` has access to.
+    pub async fn get_hold(&self, actor: &ActorContext, id: HoldId) -> Result<Hold, AppError> {
+        let hold = self
+            .repo
+            .get_hold(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Hold {}", id)))?;
+        self.ensure_account_access(actor, hold.account_id).await?;
+        Ok(hold)
+    }
+
+    /// Lists the holds on an account `actor` has access to, newest first.
+    pub async fn list_holds(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<Vec<Hold>, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo.list_holds(account_id).await.map_err(Into::into)
+    }
+
+    /// Withdraws `amount` (the whole hold when unset) from a hold on an
+    /// account `actor` has access to and releases the rest of the
+    /// reservation.
+    pub async fn capture_hold(
+        &self,
+        actor: &ActorContext,
+        id: HoldId,
+        req: CaptureRequest,
+    ) -> Result<Hold, AppError> {
+        let hold = self.get_hold(actor, id).await?;
+        let mode = self.check_can_debit(hold.account_id).await?.mode;
+        let amount = req.amount.map_or(hold.amount, PositiveAmount::get);
+        let endpoints = self.active_webhook_endpoints().await?;
+        let mut work = self.repo.begin().await?;
+        let (hold, transaction) = work.capture_hold(id, amount, self.clock.now()).await?;
+        let withdrawn = DomainEvent::FundsWithdrawn(transaction);
+        let captured = DomainEvent::HoldCaptured(hold.clone());
+        for event in [&withdrawn, &captured] {
+            stage_event(work.as_mut(), &endpoints, event, mode).await?;
+        }
+        work.commit().await?;
+        for event in [withdrawn, captured] {
+            self.announce(LiveEvent { mode, event }).await;
+        }
+        Ok(hold)
+    }
+
+    /// Releases a hold on an account `actor` has access to without moving
+    /// any funds.
+    pub async fn void_hold(&self, actor: &ActorContext, id: HoldId) -> Result<Hold, AppError> {
+        self.get_hold(actor, id).await?;
+        self.release_hold(id, HoldStatus::Voided, actor.mode, self.clock.now())
+            .await
+    }
+
+    /// Releases every hold past its expiry, returning how many were expired.
+    ///
+    /// A hold captured or voided between listing and release is skipped.
+    pub async fn expire_holds(&self) -> Result<usize, AppError> {
+        let now = self.clock.now();
+        let expired = self
+            .repo
+            .list_expired_holds(now, HOLD_EXPIRY_BATCH)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut released = 0;
+        for hold in expired {
+            let expire = async {
+                let mode = self.load_account(hold.account_id).await?.mode;
+                self.release_hold(hold.id, HoldStatus::Expired, mode, now)
+                    .await
+            };
+            match expire.await {
+                Ok(_) => released += 1,
+                Err(AppError::BadRequest(_)) => {}
+                Err(e) => {
+                    tracing::warn!(hold_id = %hold.id, "Hold will be expired on the next run: {}", e)
+                }
+            }
+        }
+        Ok(released)
+    }
+
+    /// Releases hold `id` on an account in `mode` as `status` (voided or
+    /// expired), staging the event announcing it with the release.
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        mode: Mode,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, AppError> {
+        let endpoints = self.active_webhook_endpoints().await?;
+        let mut work = self.repo.begin().await?;
+        let hold = work.release_hold(id, status, now).await?;
+        let event = match status {
+            HoldStatus::Expired => DomainEvent::HoldExpired(hold.clone()),
+            _ => DomainEvent::HoldVoided(hold.clone()),
+        };
+        stage_event(work.as_mut(), &endpoints, &event, mode).await?;
+        work.commit().await?;
+        self.announce(LiveEvent { mode, event }).await;
+        Ok(hold)
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Quotes
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Locks the current exchange rate for converting an amount, in
+    /// `actor`'s mode.
+    ///
+    /// A transfer in that mode naming the quote before it expires is
+    /// converted at the quoted rate, whatever the rate is by then.
+    pub async fn create_quote(
+        &self,
+        actor: &ActorContext,
+        req: CreateQuoteRequest,
+    ) -> Result<Quote, AppError> {
+        let ttl = req.expires_in_secs.unwrap_or(DEFAULT_QUOTE_TTL_SECS);
+        if ttl == 0 || ttl > MAX_QUOTE_TTL_SECS {
+            return Err(AppError::BadRequest(format!(
+                "expires_in_secs must be between 1 and {}",
+                MAX_QUOTE_TTL_SECS
+            )));
+        }
+        if req.from_currency == req.to_currency {
+            return Err(AppError::BadRequest(format!(
+                "Cannot quote {} against itself",
+                req.from_currency
+            )));
+        }
+        let conversion = self
+            .convert(req.amount, req.from_currency, req.to_currency)
+            .await?;
+
+        let now = self.clock.now();
+        let quote = Quote {
+            id: QuoteId::from_uuid(self.ids.new_id()),
+            from_currency: req.from_currency,
+            to_currency: req.to_currency,
+            amount: req.amount.get(),
+            rate: conversion.rate,
+            converted_amount: conversion.converted_amount.amount(),
+            created_at: now,
+            expires_at: now + TimeDelta::seconds(ttl as i64),
+            transaction_id: None,
+            mode: actor.mode,
+        };
+        self.repo
+            .insert_quote(&quote)
+            .await
+            .map_err(AppError::from)?;
+        self.count_quote(metrics::QUOTES_ISSUED_TOTAL, &quote);
+        Ok(quote)
+    }
+
+    /// Gets a quote by ID. Quotes of the other mode than `actor`'s are not
+    /// found.
+    pub async fn get_quote(&self, actor: &ActorContext, id: QuoteId) -> Result<Quote, AppError> {
+        self.load_quote(id, actor.mode).await
+    }
+
+    /// Loads quote `id`, treating a quote of another mode than `mode` as
+    /// missing.
+    async fn load_quote(&self, id: QuoteId, mode: Mode) -> Result<Quote, AppError> {
+        self.repo
+            .get_quote(id)
+            .await
+            .map_err(AppError::from)?
+            .filter(|quote| quote.mode == mode)
+            .ok_or_else(|| AppError::NotFound(format!("Quote {}", id)))
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Exposure
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Sums balances per currency across the accounts in `actor`'s mode and
+    /// converts them into `base`.
+    ///
+    /// Without `as_of` the current rates are used. With it, the latest rate
+    /// snapshot taken at or before `as_of` is used, so the snapshot job must
+    /// have been running by then. `actor` must be an admin key.
+    pub async fn exposure_report(
+        &self,
+        actor: &ActorContext,
+        base: CurrencyCode,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<ExposureReport, AppError> {
+        actor.ensure_admin()?;
+        let rates = match as_of {
+            Some(at) if at > self.clock.now() => {
+                return Err(AppError::BadRequest("`as_of` is in the future".into()));
+            }
+            Some(at) => self
+                .repo
+                .rate_snapshot_at(at)
+                .await
+                .map_err(AppError::from)?
+                .ok_or_else(|| AppError::NotFound(format!("Exchange rates as of {}", at)))?,
+            None => self.current_rates().await?,
+        };
+        let balances = self
+            .repo
+            .currency_balances(Some(actor.mode))
+            .await
+            .map_err(AppError::from)?;
+        ExposureReport::build(base, &balances, &rates).map_err(|currency| {
+            AppError::Internal(format!(
+                "No {} exchange rate as of {}",
+                currency.code(),
+                rates.taken_at
+            ))
+        })
+    }
+
+    /// Reports the float in every currency and the money that entered or
+    /// left the ledger on each day of `from..=to`, in `actor`'s mode.
+    ///
+    /// Without `to` the report runs up to today, and without `from` it covers
+    /// the 30 days up to `to`. `actor` must be an admin key.
+    pub async fn float_report(
+        &self,
+        actor: &ActorContext,
+        query: FloatReportQuery,
+    ) -> Result<FloatReport, AppError> {
+        actor.ensure_admin()?;
+        let to = query.to.unwrap_or_else(|| self.clock.now().date_naive());
+        let from = query.from.unwrap_or_else(|| {
+            to.checked_sub_days(Days::new(DEFAULT_FLOAT_REPORT_DAYS - 1))
+                .unwrap_or(to)
+        });
+        check_period(from, to)?;
+
+        let positions = self
+            .repo
+            .float_positions(Some(actor.mode))
+            .await
+            .map_err(AppError::from)?;
+        let start = from.and_time(NaiveTime::MIN).and_utc();
+        let end = (to + TimeDelta::days(1)).and_time(NaiveTime::MIN).and_utc();
+        let transactions = self
+            .repo
+            .list_transactions_between(start, end, Some(actor.mode))
+            .await
+            .map_err(AppError::from)?;
+        Ok(FloatReport {
+            positions,
+            flows: FloatFlow::tally(&transactions),
+        })
+    }
+
+    /// Takes a snapshot of current exchange rates for later as-of reports.
+    pub async fn record_exchange_rates(&self) -> Result<RateSnapshot, AppError> {
+        let snapshot = self.current_rates().await?;
+        self.repo
+            .insert_rate_snapshot(&snapshot)
+            .await
+            .map_err(AppError::from)?;
+        Ok(snapshot)
+    }
+
+    /// Current rates from the exchange provider, or the built-in table when
+    /// none is configured.
+    async fn current_rates(&self) -> Result<RateSnapshot, AppError> {
+        let mut usd_rates = HashMap::new();
+        for &currency in CurrencyCode::all() {
+            let rate = match &self.exchange {
+                Some(provider) => provider
+                    .get_rate(currency, CurrencyCode::USD)
+                    .await
+                    .map_err(|e| {
+                        AppError::Internal(format!("Exchange rate lookup failed: {}", e))
+                    })?,
+                None => currency.to_usd_rate(),
+            };
+            usd_rates.insert(currency, rate);
+        }
+        Ok(RateSnapshot {
+            taken_at: self.clock.now(),
+            usd_rates,
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Balance History
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Records the closing balance of every account for each UTC day that
+    /// has ended since its last recorded one. Returns how many were stored.
+    ///
+    /// Accounts without any recorded days are backfilled from the day they
+    /// were opened, so the first run covers history that predates the job.
+    /// Days are replayed from the transaction history rather than read off
+    /// the current balance, so a late run still records the right amounts.
+    pub async fn record_daily_balances(&self) -> Result<usize, AppError> {
+        let Some(last_closed) = self.clock.now().date_naive().pred_opt() else {
+            return Ok(0);
+        };
+        let accounts = self
+            .repo
+            .list_accounts(None)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut recorded = 0;
+        for account in accounts {
+            match self.record_account_balances(&account, last_closed).await {
+                Ok(count) => recorded += count,
+                Err(e) => {
+                    tracing::warn!(account_id = %account.id, "Balances will be recorded on the next run: {}", e)
+                }
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Records an account's closing balances for the days after its last
+    /// recorded one up to `last_closed`.
+    async fn record_account_balances(
+        &self,
+        account: &Account,
+        last_closed: NaiveDate,
+    ) -> Result<usize, AppError> {
+        let from = match self
+            .repo
+            .latest_daily_balance_date(account.id)
+            .await
+            .map_err(AppError::from)?
+        {
+            Some(latest) => latest.succ_opt(),
+            None => Some(account.created_at.date_naive()),
+        };
+        let Some(from) = from.filter(|from| *from <= last_closed) else {
+            return Ok(0);
+        };
+
+        let history = self
+            .repo
+            .list_transactions_for_account(account.id)
+            .await
+            .map_err(AppError::from)?;
+        let balances = DailyBalance::from_history(account.id, from, last_closed, &history);
+        self.repo
+            .save_daily_balances(account.id, &balances)
+            .await
+            .map_err(AppError::from)?;
+        Ok(balances.len())
+    }
+
+    /// Gets the recorded closing balances of an account `actor` has access
+    /// to over the UTC days in `query`, oldest first. Days not recorded yet,
+    /// such as today, are left out.
+    pub async fn balance_history(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        query: BalanceHistoryQuery,
+    ) -> Result<BalanceHistory, AppError> {
+        let account = self.get_account(actor, account_id).await?;
+        let today = self.clock.now().date_naive();
+        let to = query
+            .to
+            .unwrap_or_else(|| today.pred_opt().unwrap_or(today));
+        let from = query.from.unwrap_or_else(|| {
+            to.checked_sub_days(Days::new(DEFAULT_BALANCE_HISTORY_DAYS - 1))
+                .unwrap_or(to)
+        });
+        check_period(from, to)?;
+        let balances = self
+            .repo
+            .list_daily_balances(account_id, from, to)
+            .await
+            .map_err(AppError::from)?;
+        Ok(BalanceHistory {
+            account_id,
+            currency: account.currency(),
+            balances,
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Statements
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Gets the address the statements of an account `actor` has access to
+    /// are emailed to.
+    pub async fn statement_email(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<StatementEmail, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        let email = self
+            .repo
+            .get_statement_email(account_id)
+            .await
+            .map_err(AppError::from)?;
+        Ok(StatementEmail { email })
+    }
+
+    /// Sets or clears the address the statements of an account `actor` has
+    /// access to are emailed to.
+    pub async fn set_statement_email(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        req: StatementEmail,
+    ) -> Result<StatementEmail, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        let email = req.email.map(|e| e.trim().to_string());
+        if let Some(email) = &email {
+            let valid = email
+                .split_once('@')
+                .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'))
+                && !email.contains(char::is_whitespace);
+            if !valid {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid email address: {}",
+                    email
+                )));
+            }
+        }
+        self.repo
+            .set_statement_email(account_id, email.as_deref())
+            .await
+            .map_err(AppError::from)?;
+        Ok(StatementEmail { email })
+    }
+
+    /// Issues statements for `period` to every account open before it ended.
+    ///
+    /// Accounts that already have a statement for the period are skipped, so
+    /// re-running a month is safe. Each new statement is emailed when a
+    /// notifier is configured and the account has an address, and announced
+    /// with a `statement.ready` webhook otherwise. Returns the new statements.
+    pub async fn issue_statements(
+        &self,
+        period: StatementPeriod,
+    ) -> Result<Vec<Statement>, AppError> {
+        let now = self.clock.now();
+        if period.ends_at() > now {
+            return Err(AppError::BadRequest(format!(
+                "Statements for {} can be issued once the month has ended",
+                period
+            )));
+        }
+
+        let accounts = self
+            .repo
+            .list_accounts(None)
+            .await
+            .map_err(AppError::from)?;
+        let mut issued = Vec::new();
+        for account in accounts.iter().filter(|a| a.created_at < period.ends_at()) {
+            let history = self
+                .repo
+                .list_transactions_for_account(account.id)
+                .await
+                .map_err(AppError::from)?;
+            let (statement, lines) = Statement::from_history(
+                StatementId::from_uuid(self.ids.new_id()),
+                account,
+                period,
+                &history,
+                now,
+            );
+            let content = render_statement(&statement, &lines, &self.amount_format);
+            let inserted = self
+                .repo
+                .insert_statement(&statement, &content)
+                .await
+                .map_err(AppError::from)?;
+            if inserted {
+                self.deliver_statement(account, &statement, &content).await;
+                issued.push(statement);
+            }
+        }
+        Ok(issued)
+    }
+
+    /// Summarises the activity of an account `actor` has access to over the
+    /// UTC days `from..=to`.
+    ///
+    /// Unlike monthly statements this is computed on request and not
+    /// stored, so a period that includes today reflects it so far.
+    pub async fn account_statement(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<AccountStatement, AppError> {
+        let account = self.get_account(actor, account_id).await?;
+        check_period(from, to)?;
+        let history = self
+            .repo
+            .list_transactions_for_account(account_id)
+            .await
+            .map_err(AppError::from)?;
+        Ok(AccountStatement::from_history(
+            &account,
+            from,
+            to,
+            &history,
+            self.clock.now(),
+        ))
+    }
+
+    /// Issues statements for the last full month.
+    pub async fn issue_monthly_statements(&self) -> Result<Vec<Statement>, AppError> {
+        self.issue_statements(StatementPeriod::previous(self.clock.now()))
+            .await
+    }
+
+    /// Lists the statements of an account `actor` has access to, latest
+    /// period first.
+    pub async fn list_statements(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<Vec<Statement>, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo
+            .list_statements(account_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Gets a statement of an account `actor` has access to, with a fresh
+    /// download link.
+    pub async fn get_statement(
+        &self,
+        actor: &ActorContext,
+        id: StatementId,
+    ) -> Result<StatementDownload, AppError> {
+        let statement = self.load_statement(id).await?;
+        self.ensure_account_access(actor, statement.account_id)
+            .await?;
+        Ok(self.statement_download(statement))
+    }
+
+    async fn load_statement(&self, id: StatementId) -> Result<Statement, AppError> {
+        self.repo
+            .get_statement(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Statement {}", id)))
+    }
+
+    /// Returns a statement and its file if `signature` is a valid, unexpired
+    /// link signature for it.
+    pub async fn download_statement(
+        &self,
+        id: StatementId,
+        expires: i64,
+        signature: &str,
+    ) -> Result<(Statement, String), AppError> {
+        if !self
+            .statement_links
+            .verify(id, expires, signature, self.clock.now())
+        {
+            return Err(AppError::BadRequest(
+                "Invalid or expired download link".into(),
+            ));
+        }
+        let statement = self.load_statement(id).await?;
+        let content = self
+            .repo
+            .get_statement_content(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Statement {}", id)))?;
+        Ok((statement, content))
+    }
+
+    fn statement_download(&self, statement: Statement) -> StatementDownload {
+        let (download_url, url_expires_at) =
+            self.statement_links.url_for(statement.id, self.clock.now());
+        StatementDownload {
+            statement,
+            download_url,
+            url_expires_at,
+        }
+    }
+
+    /// Emails a new statement, falling back to the webhook when there is no
+    /// notifier or address, or the email cannot be sent.
+    async fn deliver_statement(&self, account: &Account, statement: &Statement, content: &str) {
+        if let Some(notifier) = &self.notifier {
+            match self.repo.get_statement_email(account.id).await {
+                Ok(Some(to)) => {
+                    let period = statement.period();
+                    let notification = Notification {
+                        to,
+                        subject: format!("Your {} statement", period),
+                        body: format!(
+                            "Hello {},\n\nYour statement for {} is attached.\nOpening balance: {} {}\nClosing balance: {} {}\n",
+                            account.name,
+                            period,
+                            self.amount_format
+                                .format(statement.opening_balance, statement.currency),
+                            statement.currency,
+                            self.amount_format
+                                .format(statement.closing_balance, statement.currency),
+                            statement.currency,
+                        ),
+                        attachment: Some(Attachment {
+                            filename: format!("statement-{}.csv", period),
+                            content_type: "text/csv".into(),
+                            content: content.as_bytes().to_vec(),
+                        }),
+                    };
+                    match notifier.send(&notification).await {
+                        Ok(()) => return,
+                        Err(e) => tracing::warn!(
+                            statement_id = %statement.id,
+                            "Failed to email statement, sending webhook instead: {}",
+                            e
+                        ),
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(
+                    statement_id = %statement.id,
+                    "Failed to look up statement email: {}",
+                    e
+                ),
+            }
+        }
+        self.emit(DomainEvent::StatementReady(
+            self.statement_download(statement.clone()),
+        ))
+        .await;
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Transaction Operations
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Deposits money into an account.
+    pub async fn deposit(&self, req: DepositRequest) -> Result<Transaction, AppError> {
+        self.deposit_with_warnings(req).await.map(|made| made.value)
+    }
+
+    /// Withdraws money from an account.
+    pub async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, AppError> {
+        self.withdraw_with_warnings(req)
+            .await
+            .map(|made| made.value)
+    }
+
+    /// Transfers money between accounts.
+    pub async fn transfer(&self, req: TransferRequest) -> Result<Transaction, AppError> {
+        self.transfer_with_warnings(req)
+            .await
+            .map(|made| made.value)
+    }
+
+    /// Deposits into `req`'s account on behalf of `actor`, counting the
+    /// deposit towards the key's volume.
+    pub async fn deposit_as(
+        &self,
+        actor: &ActorContext,
+        req: DepositRequest<AccountRef>,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let account_id = self.resolve_account_for(actor, &req.account_id).await?;
+        let made = self
+            .deposit_with_warnings(req.with_account(account_id))
+            .await?;
+        self.record_api_key_transaction(actor.api_key_id, &made.value)
+            .await;
+        Ok(made)
+    }
+
+    /// Withdraws from `req`'s account on behalf of `actor`, counting the
+    /// withdrawal towards the key's volume.
+    pub async fn withdraw_as(
+        &self,
+        actor: &ActorContext,
+        req: WithdrawRequest<AccountRef>,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let account_id = self.resolve_account_for(actor, &req.account_id).await?;
+        let made = self
+            .withdraw_with_warnings(req.with_account(account_id))
+            .await?;
+        self.record_api_key_transaction(actor.api_key_id, &made.value)
+            .await;
+        Ok(made)
+    }
+
+    /// Transfers on behalf of `actor`, who must have access to the source
+    /// account but not the destination, counting the transfer towards the
+    /// key's volume.
+    pub async fn transfer_as(
+        &self,
+        actor: &ActorContext,
+        req: TransferRequest<AccountRef>,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let from = self
+            .resolve_account_for(actor, &req.from_account_id)
+            .await?;
+        let to = self
+            .resolve_destination_for(actor, &req.to_account_id)
+            .await?;
+        let made = self
+            .transfer_with_warnings(req.with_accounts(from, to))
+            .await?;
+        self.record_api_key_transaction(actor.api_key_id, &made.value)
+            .await;
+        Ok(made)
+    }
+
+    /// [`batch`](Self::batch) on behalf of `actor`.
+    ///
+    /// Every account is resolved and access-checked before anything is made,
+    /// so an unknown account or one the key may not use fails the whole
+    /// batch. Payments made count towards the key's volume.
+    pub async fn batch_as(
+        &self,
+        actor: &ActorContext,
+        req: BatchRequest<AccountRef>,
+    ) -> Result<Vec<BatchOutcome>, AppError> {
+        let mut operations = Vec::with_capacity(req.operations.len());
+        for operation in req.operations {
+            operations.push(match operation {
+                BatchOperation::Deposit(req) => {
+                    let account_id = self.resolve_account_for(actor, &req.account_id).await?;
+                    BatchOperation::Deposit(req.with_account(account_id))
+                }
+                BatchOperation::Withdraw(req) => {
+                    let account_id = self.resolve_account_for(actor, &req.account_id).await?;
+                    BatchOperation::Withdraw(req.with_account(account_id))
+                }
+                BatchOperation::Transfer(req) => {
+                    let from = self
+                        .resolve_account_for(actor, &req.from_account_id)
+                        .await?;
+                    let to = self
+                        .resolve_destination_for(actor, &req.to_account_id)
+                        .await?;
+                    BatchOperation::Transfer(req.with_accounts(from, to))
+                }
+            });
+        }
+
+        let outcomes = self
+            .batch(BatchRequest {
+                mode: req.mode,
+                operations,
+            })
+            .await?;
+        for outcome in &outcomes {
+            if let BatchOutcome::Succeeded(made) = outcome {
+                self.record_api_key_transaction(actor.api_key_id, &made.value)
+                    .await;
+            }
+        }
+        Ok(outcomes)
+    }
+
+    /// Resolves `account` and checks that `actor` may act on it.
+    async fn resolve_account_for(
+        &self,
+        actor: &ActorContext,
+        account: &AccountRef,
+    ) -> Result<AccountId, AppError> {
+        let account_id = self.resolve_account(account).await?;
+        self.ensure_account_access(actor, account_id).await?;
+        Ok(account_id)
+    }
+
+    /// Resolves `account`, which `actor` may pay into whether or not it has
+    /// access to it, as long as it is in the key's mode.
+    async fn resolve_destination_for(
+        &self,
+        actor: &ActorContext,
+        account: &AccountRef,
+    ) -> Result<AccountId, AppError> {
+        let account_id = self.resolve_account(account).await?;
+        self.ensure_mode(actor, account_id).await?;
+        Ok(account_id)
+    }
+
+    /// Checks that `actor` may act on `account_id` and that the account is
+    /// in the key's mode.
+    async fn ensure_account_access(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<(), AppError> {
+        actor.ensure_access(account_id)?;
+        self.ensure_mode(actor, account_id).await
+    }
+
+    /// Checks that `actor` is an admin key and that `account_id` is in its
+    /// mode.
+    async fn ensure_admin_of(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<(), AppError> {
+        actor.ensure_admin()?;
+        self.ensure_mode(actor, account_id).await
+    }
+
+    /// Checks that `account_id` is in `actor`'s mode. An account in the other
+    /// mode is reported as not found, so a test key cannot tell which live
+    /// accounts exist.
+    pub async fn ensure_mode(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<(), AppError> {
+        self.account_in_mode(actor, account_id).await.map(drop)
+    }
+
+    /// Loads `account_id` if it is in `actor`'s mode, as
+    /// [`ensure_mode`](Self::ensure_mode) checks.
+    async fn account_in_mode(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<Account, AppError> {
+        Some(self.load_account(account_id).await?)
+            .filter(|account| account.mode == actor.mode)
+            .ok_or(AppError::AccountNotFound(account_id))
+    }
+
+    /// [`deposit`](Self::deposit), with the warnings raised about it.
+    pub async fn deposit_with_warnings(
+        &self,
+        req: DepositRequest,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let key = req.idempotency_key.as_deref();
+        self.idempotent(key, "deposit", &req, self.deposit_once(req.clone()))
+            .await
+    }
+
+    /// [`withdraw`](Self::withdraw), with the warnings raised about it.
+    pub async fn withdraw_with_warnings(
+        &self,
+        req: WithdrawRequest,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let key = req.idempotency_key.as_deref();
+        self.idempotent(key, "withdraw", &req, self.withdraw_once(req.clone()))
+            .await
+    }
+
+    /// [`transfer`](Self::transfer), with the warnings raised about it.
+    pub async fn transfer_with_warnings(
+        &self,
+        req: TransferRequest,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let key = req.idempotency_key.as_deref();
+        self.idempotent(key, "transfer", &req, self.transfer_once(req.clone()))
+            .await
+    }
+
+    /// Makes many payments in one request.
+    ///
+    /// In [`BatchMode::BestEffort`] each operation is made exactly as if it
+    /// had been sent on its own, and a failure does not stop the rest. In
+    /// [`BatchMode::Atomic`] every operation is checked first and then all are
+    /// applied in one repository transaction, so either every payment is made
+    /// or none is. Limits and balance caps are checked against balances from
+    /// before the batch, so an atomic batch relies on the repository to refuse
+    /// overdrafts between its own operations.
+    ///
+    /// Operations keep their per-item idempotency keys: one already made is
+    /// replayed rather than made again, in either mode. Returns one outcome
+    /// per operation, in request order.
+    pub async fn batch(&self, req: BatchRequest) -> Result<Vec<BatchOutcome>, AppError> {
+        if req.operations.is_empty() {
+            return Err(AppError::BadRequest(
+                "A batch needs at least one operation".into(),
+            ));
+        }
+        if req.operations.len() > MAX_BATCH_OPERATIONS {
+            return Err(AppError::BadRequest(format!(
+                "A batch can have at most {} operations",
+                MAX_BATCH_OPERATIONS
+            )));
+        }
+        let mut keys = HashSet::new();
+        for operation in &req.operations {
+            if let Some(key) = batch_idempotency_key(operation)
+                && !keys.insert(key)
+            {
+                return Err(AppError::BadRequest(format!(
+                    "Idempotency key {} is used by more than one operation",
+                    key
+                )));
+            }
+        }
+
+        match req.mode {
+            BatchMode::BestEffort => {
+                let mut outcomes = Vec::with_capacity(req.operations.len());
+                for operation in req.operations {
+                    let made = match operation {
+                        BatchOperation::Deposit(req) => self.deposit_with_warnings(req).await,
+                        BatchOperation::Withdraw(req) => self.withdraw_with_warnings(req).await,
+                        BatchOperation::Transfer(req) => self.transfer_with_warnings(req).await,
+                    };
+                    outcomes.push(match made {
+                        Ok(made) => BatchOutcome::Succeeded(Box::new(made)),
+                        Err(e) => BatchOutcome::Failed(e),
+                    });
+                }
+                Ok(outcomes)
+            }
+            BatchMode::Atomic => self.atomic_batch(req.operations).await,
+        }
+    }
+
+    async fn atomic_batch(
+        &self,
+        operations: Vec<BatchOperation>,
+    ) -> Result<Vec<BatchOutcome>, AppError> {
+        let mut outcomes: Vec<Option<BatchOutcome>> = operations.iter().map(|_| None).collect();
+        let mut pending = Vec::new();
+        for (index, operation) in operations.into_iter().enumerate() {
+            match self.prepare_operation(operation).await {
+                Ok(PreparedOperation::Replayed(made)) => {
+                    outcomes[index] = Some(BatchOutcome::Succeeded(Box::new(made)));
+                }
+                Ok(PreparedOperation::Ready(ready)) => pending.push((index, ready)),
+                Err(e) => outcomes[index] = Some(BatchOutcome::Failed(e)),
+            }
+        }
+
+        let refused = outcomes
+            .iter()
+            .any(|outcome| matches!(outcome, Some(BatchOutcome::Failed(_))));
+        if !refused && !pending.is_empty() {
+            let endpoints = self.active_webhook_endpoints().await?;
+            let mut work = self.repo.begin().await?;
+            let mut staged = Vec::with_capacity(pending.len());
+            for (index, ready) in &pending {
+                match self
+                    .stage_payment(
+                        work.as_mut(),
+                        &endpoints,
+                        ready.operation.clone(),
+                        ready.cleared.clone(),
+                    )
+                    .await
+                {
+                    Ok(made) => staged.push(made),
+                    // Dropping the unit of work undoes the operations
+                    // staged so far
+                    Err(e) => {
+                        let (from, to) = payment_accounts(&ready.operation);
+                        let e = self
+                            .report_blocked::<()>(&ready.payment, from, to, Err(e))
+                            .await
+                            .unwrap_err();
+                        outcomes[*index] = Some(BatchOutcome::Failed(e));
+                        break;
+                    }
+                }
+            }
+            if staged.len() == pending.len() {
+                work.commit().await?;
+                for ((index, ready), (transaction, event)) in pending.into_iter().zip(staged) {
+                    self.announce(event).await;
+                    let made = self.complete_operation(ready, transaction).await;
+                    outcomes[index] = Some(BatchOutcome::Succeeded(Box::new(made)));
+                }
+            }
+        }
+
+        Ok(outcomes
+            .into_iter()
+            .map(|outcome| outcome.unwrap_or(BatchOutcome::RolledBack))
+            .collect())
+    }
+
+    /// Replays an operation of an atomic batch already made under its
+    /// idempotency key, or runs its checks without moving any money.
+    async fn prepare_operation(
+        &self,
+        operation: BatchOperation,
+    ) -> Result<PreparedOperation, AppError> {
+        let idempotency = match &operation {
+            BatchOperation::Deposit(req) => req
+                .idempotency_key
+                .clone()
+                .map(|key| Ok::<_, AppError>((key, request_hash("deposit", req)?)))
+                .transpose()?,
+            BatchOperation::Withdraw(req) => req
+                .idempotency_key
+                .clone()
+                .map(|key| Ok::<_, AppError>((key, request_hash("withdraw", req)?)))
+                .transpose()?,
+            BatchOperation::Transfer(req) => req
+                .idempotency_key
+                .clone()
+                .map(|key| Ok::<_, AppError>((key, request_hash("transfer", req)?)))
+                .transpose()?,
+        };
+        if let Some((key, request_hash)) = &idempotency
+            && let Some(record) = self
+                .idempotency_record(PAYMENTS_IDEMPOTENCY_ENDPOINT, key)
+                .await?
+        {
+            return replay(record, request_hash).map(PreparedOperation::Replayed);
+        }
+
+        let (operation, payment, cleared) = match operation {
+            BatchOperation::Deposit(req) => {
+                let payment = self.deposit_check(&req).await;
+                let checked = self.check_deposit(&req, &payment).await;
+                let cleared = self
+                    .report_blocked(&payment, None, Some(req.account_id), checked)
+                    .await?;
+                (LedgerOperation::Deposit(req), payment, cleared)
+            }
+            BatchOperation::Withdraw(mut req) => {
+                let payment = self.withdrawal_check(&req).await;
+                let checked = self.check_withdrawal(&mut req, &payment).await;
+                let cleared = self
+                    .report_blocked(&payment, Some(req.account_id), None, checked)
+                    .await?;
+                (LedgerOperation::Withdraw(req), payment, cleared)
+            }
+            BatchOperation::Transfer(mut req) => {
+                let payment = self.transfer_check(&req).await;
+                let checked = self.check_transfer(&mut req, &payment).await;
+                let (from, to) = (Some(req.from_account_id), Some(req.to_account_id));
+                let (conversion, cleared) =
+                    self.report_blocked(&payment, from, to, checked).await?;
+                (LedgerOperation::Transfer(req, conversion), payment, cleared)
+            }
+        };
+        Ok(PreparedOperation::Ready(ReadyOperation {
+            operation,
+            warnings: self.payment_warnings(&payment),
+            cleared,
+            payment: Box::new(payment),
+            idempotency,
+        }))
+    }
+
+    /// Finishes an operation of an atomic batch once its unit of work is
+    /// committed.
+    async fn complete_operation(
+        &self,
+        ready: ReadyOperation,
+        transaction: Transaction,
+    ) -> WithWarnings<Transaction> {
+        let made = WithWarnings::new(transaction, ready.warnings);
+        match ready.idempotency {
+            // The money has moved, so report it even if storing the
+            // response for replays fails.
+            Some((key, request_hash)) => self
+                .store_response(&key, request_hash, made.clone())
+                .await
+                .unwrap_or(made),
+            None => made,
+        }
+    }
+
+    /// Gets the response stored for `key` sent to `endpoint`, from the
+    /// idempotency store if one is set and the repository otherwise.
+    pub async fn idempotency_record(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, AppError> {
+        let record = match &self.idempotency {
+            Some(store) => store.get(endpoint, key).await?,
+            None => self.repo.get_idempotency_record(endpoint, key).await?,
+        };
+        Ok(record)
+    }
+
+    /// Stores `record` unless its endpoint and key already have a response,
+    /// and returns the response they hold afterwards. A lapsed reservation
+    /// is taken over.
+    pub async fn save_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, AppError> {
+        let stored = match &self.idempotency {
+            Some(store) => store.save(record).await?,
+            None => self.repo.save_idempotency_record(record).await?,
+        };
+        Ok(stored)
+    }
+
+    /// Stores `record` in place of whatever its endpoint and key hold, such
+    /// as the in-progress record reserving the key.
+    pub async fn replace_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<(), AppError> {
+        match &self.idempotency {
+            Some(store) => store.replace(record).await?,
+            None => self.repo.replace_idempotency_record(record).await?,
+        }
+        Ok(())
+    }
+
+    /// Forgets the record stored for `key` sent to `endpoint`, so the key can
+    /// be used again.
+    pub async fn remove_idempotency_record(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<(), AppError> {
+        match &self.idempotency {
+            Some(store) => store.remove(endpoint, key).await?,
+            None => self.repo.remove_idempotency_record(endpoint, key).await?,
+        }
+        Ok(())
+    }
+
+    /// Makes a payment once per idempotency key.
+    ///
+    /// Without a key `make` simply runs. With one, the first successful
+    /// response is stored alongside a hash of `operation` and `req`: repeating
+    /// the request returns the stored response without running `make` again,
+    /// and reusing the key for a different request is refused. Failures are
+    /// not stored, so a rejected payment can be corrected and retried under
+    /// the same key.
+    async fn idempotent<T>(
+        &self,
+        key: Option<&str>,
+        operation: &str,
+        req: &impl Serialize,
+        make: impl Future<Output = Result<T, AppError>>,
+    ) -> Result<T, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let Some(key) = key else {
+            return make.await;
+        };
+        let request_hash = request_hash(operation, req)?;
+        if let Some(record) = self
+            .idempotency_record(PAYMENTS_IDEMPOTENCY_ENDPOINT, key)
+            .await?
+        {
+            return replay(record, &request_hash);
+        }
+
+        let response = make.await?;
+        self.store_response(key, request_hash, response).await
+    }
+
+    /// Stores the response to a payment made under an idempotency key.
+    async fn store_response<T>(
+        &self,
+        key: &str,
+        request_hash: String,
+        response: T,
+    ) -> Result<T, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let record = IdempotencyRecord {
+            endpoint: PAYMENTS_IDEMPOTENCY_ENDPOINT.to_string(),
+            key: key.to_string(),
+            request_hash,
+            status: 200,
+            response: serde_json::to_value(&response)
+                .map_err(|e| AppError::Internal(e.to_string()))?,
+            created_at: self.clock.now(),
+        };
+        // A concurrent request with the same key may have stored its response
+        // first; answer with that one so both callers see the same result.
+        match self.save_idempotency_record(&record).await {
+            Ok(stored) => replay(stored, &record.request_hash),
+            Err(e) => {
+                tracing::error!(
+                    idempotency_key = key,
+                    "Failed to store idempotent response: {}",
+                    e
+                );
+                Ok(response)
+            }
+        }
+    }
+
+    async fn deposit_once(
+        &self,
+        req: DepositRequest,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let payment = self.deposit_check(&req).await;
+        let warnings = self.payment_warnings(&payment);
+        let transaction = self.make_deposit(req, &payment).await?;
+        Ok(WithWarnings::new(transaction, warnings))
+    }
+
+    async fn withdraw_once(
+        &self,
+        req: WithdrawRequest,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let payment = self.withdrawal_check(&req).await;
+        let warnings = self.payment_warnings(&payment);
+        let transaction = self.make_withdrawal(req, &payment).await?;
+        Ok(WithWarnings::new(transaction, warnings))
+    }
+
+    async fn transfer_once(
+        &self,
+        req: TransferRequest,
+    ) -> Result<WithWarnings<Transaction>, AppError> {
+        let payment = self.transfer_check(&req).await;
+        let warnings = self.payment_warnings(&payment);
+        let transaction = self.make_transfer(req, &payment).await?;
+        Ok(WithWarnings::new(transaction, warnings))
+    }
+
+    async fn make_deposit(
+        &self,
+        req: DepositRequest,
+        payment: &PaymentCheck,
+    ) -> Result<Transaction, AppError> {
+        let account_id = req.account_id;
+        let made = async {
+            let cleared = self.check_deposit(&req, payment).await?;
+            self.commit_payment(LedgerOperation::Deposit(req), cleared)
+                .await
+        }
+        .await;
+        self.report_blocked(payment, None, Some(account_id), made)
+            .await
+    }
+
+    async fn make_withdrawal(
+        &self,
+        mut req: WithdrawRequest,
+        payment: &PaymentCheck,
+    ) -> Result<Transaction, AppError> {
+        let account_id = req.account_id;
+        let made = async {
+            let cleared = self.check_withdrawal(&mut req, payment).await?;
+            self.commit_payment(LedgerOperation::Withdraw(req), cleared)
+                .await
+        }
+        .await;
+        self.report_blocked(payment, Some(account_id), None, made)
+            .await
+    }
+
+    async fn make_transfer(
+        &self,
+        mut req: TransferRequest,
+        payment: &PaymentCheck,
+    ) -> Result<Transaction, AppError> {
+        let (from, to) = (Some(req.from_account_id), Some(req.to_account_id));
+        let made = async {
+            let (conversion, cleared) = self.check_transfer(&mut req, payment).await?;
+            self.commit_payment(LedgerOperation::Transfer(req, conversion), cleared)
+                .await
+        }
+        .await;
+        self.report_blocked(payment, from, to, made).await
+    }
+
+    /// Makes a payment that passed its checks in one unit of work, then
+    /// announces it.
+    async fn commit_payment(
+        &self,
+        operation: LedgerOperation,
+        cleared: Cleared,
+    ) -> Result<Transaction, AppError> {
+        let endpoints = self.active_webhook_endpoints().await?;
+        let mut work = self.repo.begin().await?;
+        let (transaction, event) = self
+            .stage_payment(work.as_mut(), &endpoints, operation, cleared)
+            .await?;
+        work.commit().await?;
+        self.announce(event).await;
+        Ok(transaction)
+    }
+
+    /// Stages a payment, its risk assessment, its event log entry and the
+    /// webhook events announcing it in `work`, so that either all of them are
+    /// stored or none are.
+    async fn stage_payment(
+        &self,
+        work: &mut dyn UnitOfWork,
+        endpoints: &[WebhookEndpoint],
+        operation: LedgerOperation,
+        cleared: Cleared,
+    ) -> Result<(Transaction, LiveEvent), AppError> {
+        let event: fn(Transaction) -> DomainEvent = match &operation {
+            LedgerOperation::Deposit(_) => DomainEvent::FundsDeposited,
+            LedgerOperation::Withdraw(_) => DomainEvent::FundsWithdrawn,
+            LedgerOperation::Transfer(..) => DomainEvent::TransferCompleted,
+        };
+        // A deposit counts against the account it credits, other payments
+        // against the account they debit
+        let (account_id, debit, accounts) = match &operation {
+            LedgerOperation::Deposit(req) => (req.account_id, None, vec![req.account_id]),
+            LedgerOperation::Withdraw(req) => {
+                (req.account_id, Some(req.amount.get()), vec![req.account_id])
+            }
+            LedgerOperation::Transfer(req, _) => (
+                req.from_account_id,
+                Some(req.amount.get()),
+                vec![req.from_account_id, req.to_account_id],
+            ),
+        };
+        // Both of a transfer's accounts are locked before its limits are
+        // counted, so a transfer the other way cannot hold one of them
+        work.lock_accounts(&accounts).await?;
+        if let Some(amount) = debit {
+            self.check_daily_debit(work, account_id, &cleared.limits, amount)
+                .await?;
+        }
+        self.check_hourly_transactions(work, account_id, &cleared.limits)
+            .await?;
+        let quote_id = match &operation {
+            LedgerOperation::Transfer(req, _) => req.quote_id,
+            _ => None,
+        };
+        let mut transaction = work.apply(operation).await?;
+        // A replayed transfer already spent its quote; spending it again is a
+        // no-op, while a new transfer fails here if the quote was used
+        if let Some(quote_id) = quote_id {
+            work.spend_quote(quote_id, transaction.id).await?;
+        }
+        // A transaction replayed by its idempotency key keeps the assessment
+        // it was first recorded with
+        if let Some(risk) = cleared.risk
+            && transaction.risk.is_none()
+        {
+            work.record_transaction_risk(transaction.id, &risk).await?;
+            transaction = transaction.with_risk(Some(risk));
+        }
+
+        let event = event(transaction.clone());
+        stage_event(work, endpoints, &event, cleared.mode).await?;
+        Ok((
+            transaction,
+            LiveEvent {
+                mode: cleared.mode,
+                event,
+            },
+        ))
+    }
+
+    /// Business validation for a deposit.
+    async fn check_deposit(
+        &self,
+        req: &DepositRequest,
+        payment: &PaymentCheck,
+    ) -> Result<Cleared, AppError> {
+        let limits = self.limits_for(req.account_id).await?;
+        limits.check_amount(req.amount.get(), req.currency)?;
+        validate_counterparty(req.counterparty.as_ref())?;
+        validate_metadata(&req.metadata)?;
+        self.check_memo(req.encrypted_memo.as_ref(), &[req.account_id])
+            .await?;
+        let mode = self
+            .check_credit(req.account_id, req.amount.get(), &limits)
+            .await?
+            .mode;
+        let risk = self.assess_risk(payment).await?;
+        Ok(Cleared { risk, limits, mode })
+    }
+
+    /// Business validation for a withdrawal, normalizing its purpose code.
+    async fn check_withdrawal(
+        &self,
+        req: &mut WithdrawRequest,
+        payment: &PaymentCheck,
+    ) -> Result<Cleared, AppError> {
+        let limits = self.limits_for(req.account_id).await?;
+        limits.check_amount(req.amount.get(), req.currency)?;
+        validate_counterparty(req.counterparty.as_ref())?;
+        validate_metadata(&req.metadata)?;
+        self.check_memo(req.encrypted_memo.as_ref(), &[req.account_id])
+            .await?;
+        req.purpose_code = normalize_purpose(req.purpose_code.take())?;
+        let mode = self.check_can_debit(req.account_id).await?.mode;
+        self.check_purpose(req.account_id, req.purpose_code.as_deref())
+            .await?;
+        let risk = self.assess_risk(payment).await?;
+        Ok(Cleared { risk, limits, mode })
+    }
+
+    /// Business validation for a transfer, normalizing its purpose code.
+    /// Returns the currency conversion to apply along with the outcome.
+    async fn check_transfer(
+        &self,
+        req: &mut TransferRequest,
+        payment: &PaymentCheck,
+    ) -> Result<(Option<FxConversion>, Cleared), AppError> {
+        if req.from_account_id == req.to_account_id {
+            return Err(AppError::BadRequest(
+                "Cannot transfer to the same account".into(),
+            ));
+        }
+        let limits = self.limits_for(req.from_account_id).await?;
+        limits.check_amount(req.amount.get(), req.currency)?;
+        validate_metadata(&req.metadata)?;
+        self.check_memo(
+            req.encrypted_memo.as_ref(),
+            &[req.from_account_id, req.to_account_id],
+        )
+        .await?;
+        req.purpose_code = normalize_purpose(req.purpose_code.take())?;
+        let mode = self.check_can_debit(req.from_account_id).await?.mode;
+        self.check_purpose(req.from_account_id, req.purpose_code.as_deref())
+            .await?;
+        let conversion = self.transfer_conversion(req).await?;
+        let credited = conversion.map_or(req.amount.get(), |c| c.converted_amount.amount());
+        let destination_limits = self.limits_for(req.to_account_id).await?;
+        self.check_credit(req.to_account_id, credited, &destination_limits)
+            .await?;
+        let risk = self.assess_risk(payment).await?;
+        Ok((conversion, Cleared { risk, limits, mode }))
+    }
+
+    async fn deposit_check(&self, req: &DepositRequest) -> PaymentCheck {
+        self.payment_check(
+            TransactionType::Deposit,
+            req.amount.get(),
+            req.currency,
+            None,
+            Some(req.account_id),
+        )
+        .await
+    }
+
+    async fn withdrawal_check(&self, req: &WithdrawRequest) -> PaymentCheck {
+        self.payment_check(
+            TransactionType::Withdrawal,
+            req.amount.get(),
+            req.currency,
+            Some(req.account_id),
+            None,
+        )
+        .await
+    }
+
+    async fn transfer_check(&self, req: &TransferRequest) -> PaymentCheck {
+        self.payment_check(
+            TransactionType::Transfer,
+            req.amount.get(),
+            req.currency,
+            Some(req.from_account_id),
+            Some(req.to_account_id),
+        )
+        .await
+    }
+
+    /// A payment about to be made, as shown to warning rules and the risk
+    /// check.
+    async fn payment_check(
+        &self,
+        transaction_type: TransactionType,
+        amount: i64,
+        currency: CurrencyCode,
+        source: Option<AccountId>,
+        destination: Option<AccountId>,
+    ) -> PaymentCheck {
+        PaymentCheck {
+            transaction_type,
+            amount,
+            currency,
+            source: self.account_activity(source).await,
+            destination: self.account_activity(destination).await,
+            now: self.clock.now(),
+        }
+    }
+
+    /// Runs the warning rules over a payment about to be made.
+    fn payment_warnings(&self, payment: &PaymentCheck) -> Vec<Warning> {
+        self.warning_rules
+            .iter()
+            .flat_map(|rule| rule.check(payment))
+            .collect()
+    }
+
+    /// Passes `result` through, first announcing the payment as
+    /// `transaction.blocked` if a limit refused it.
+    async fn report_blocked<T>(
+        &self,
+        payment: &PaymentCheck,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        result: Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let Err(error) = &result else {
+            return result;
+        };
+        if !error.is_limit_exceeded() {
+            return result;
+        }
+        tracing::info!(
+            transaction_type = %payment.transaction_type,
+            code = ?error.error_code(),
+            "Payment blocked: {}",
+            error
+        );
+        self.emit(DomainEvent::TransactionBlocked(BlockedTransaction {
+            transaction_type: payment.transaction_type,
+            from_account_id: from,
+            to_account_id: to,
+            amount: payment.amount,
+            currency: payment.currency,
+            error_code: error.error_code(),
+            reason: error.to_string(),
+            blocked_at: payment.now,
+        }))
+        .await;
+        result
+    }
+
+    /// Runs the risk check over a payment about to be made. Fails if it is
+    /// denied; returns the assessment to record if it is held.
+    async fn assess_risk(
+        &self,
+        payment: &PaymentCheck,
+    ) -> Result<Option<RiskAssessment>, AppError> {
+        let assessment = self.risk_check.assess(payment).await;
+        match assessment.decision {
+            RiskDecision::Allow => Ok(None),
+            RiskDecision::Hold => Ok(Some(assessment)),
+            RiskDecision::Deny => {
+                let reasons = assessment.reasons.join("; ");
+                tracing::info!(
+                    transaction_type = %payment.transaction_type,
+                    amount = payment.amount,
+                    "Payment denied by risk check: {}",
+                    reasons
+                );
+                Err(AppError::RiskDenied(reasons))
+            }
+        }
+    }
+
+    /// An account and its latest transactions, for warning rules and the
+    /// risk check.
+    async fn account_activity(&self, id: Option<AccountId>) -> Option<AccountActivity> {
+        let id = id?;
+        let loaded = async {
+            let Some(account) = self.repo.get_account(id).await? else {
+                return Ok(None);
+            };
+            let recent = self
+                .repo
+                .list_transactions_page(id, &TransactionFilter::default(), None, WARNING_HISTORY)
+                .await?;
+            Ok::<_, RepoError>(Some(AccountActivity { account, recent }))
+        };
+        loaded.await.unwrap_or_else(|e| {
+            tracing::warn!("Skipping payment checks for account {}: {}", id, e);
+            None
+        })
+    }
+
+    /// Conversion for a `convert_currency` transfer into an account held in
+    /// another currency, or `None` if the transfer is made as-is. A transfer
+    /// naming a quote is converted at the quoted rate.
+    async fn transfer_conversion(
+        &self,
+        req: &TransferRequest,
+    ) -> Result<Option<FxConversion>, AppError> {
+        if let Some(quote_id) = req.quote_id {
+            return self.quoted_conversion(req, quote_id).await.map(Some);
+        }
+        if !req.convert_currency {
+            return Ok(None);
+        }
+        let to = self.load_account(req.to_account_id).await?.currency();
+        if to == req.currency {
+            return Ok(None);
+        }
+        self.convert(req.amount, req.currency, to).await.map(Some)
+    }
+
+    /// Conversion locked by a quote for `req`.
+    ///
+    /// Whether a spent quote may be used again is left to the unit of work:
+    /// only the transfer that spent it, replayed by its idempotency key, may.
+    async fn quoted_conversion(
+        &self,
+        req: &TransferRequest,
+        quote_id: QuoteId,
+    ) -> Result<FxConversion, AppError> {
+        let mode = self.load_account(req.from_account_id).await?.mode;
+        let quote = self.load_quote(quote_id, mode).await?;
+        let to = self.load_account(req.to_account_id).await?.currency();
+        let usable = match quote.transaction_id {
+            Some(_) => Ok(()),
+            None => quote
+                .check_unexpired(self.clock.now())
+                .inspect_err(|_| self.count_quote(metrics::QUOTES_EXPIRED_TOTAL, &quote)),
+        };
+        usable
+            .and_then(|()| quote.check_covers(req.amount.get(), req.currency, to))
+            .and_then(|()| quote.conversion())
+            .map_err(|e| AppError::BadRequest(e.to_string()))
+    }
+
+    /// Adds one to the quote counter `name` for the quote's currency pair.
+    fn count_quote(&self, name: &'static str, quote: &Quote) {
+        let (from, to) = (
+            quote.from_currency.to_string(),
+            quote.to_currency.to_string(),
+        );
+        self.metrics
+            .increment_counter(name, &[("from", from.as_str()), ("to", to.as_str())], 1);
+    }
+
+    /// Converts `amount` of `from` into `to` at the current rate.
+    async fn convert(
+        &self,
+        amount: PositiveAmount,
+        from: CurrencyCode,
+        to: CurrencyCode,
+    ) -> Result<FxConversion, AppError> {
+        let amount = DynMoney::positive(amount, from);
+        let rate = match &self.exchange {
+            Some(provider) => provider.get_rate(from, to).await.map_err(|e| match e {
+                ExchangeError::ServiceUnavailable(msg) => AppError::ServiceUnavailable(msg),
+                e => AppError::BadRequest(e.to_string()),
+            })?,
+            None => amount.rate_to(to),
+        };
+        FxConversion::at_rate(amount, to, rate).map_err(|e| AppError::BadRequest(e.to_string()))
+    }
+
+    /// Rejects crediting `amount` to `account_id` if it is closed or the credit
+    /// would breach the balance cap. Returns the account otherwise.
+    ///
+    /// The check reads the balance outside the write transaction, so concurrent
+    /// credits can overshoot the cap slightly; it is a sanity guard, not a hard limit.
+    async fn check_credit(
+        &self,
+        account_id: AccountId,
+        amount: i64,
+        limits: &AmountLimits,
+    ) -> Result<Account, AppError> {
+        let account = self.load_account(account_id).await?;
+        if account.status == AccountStatus::Closed {
+            return Err(AppError::AccountClosed(account_id));
+        }
+        if limits.max_account_balance.is_some() {
+            limits.check_credit(account.balance.amount(), amount)?;
+        }
+        Ok(account)
+    }
+
+    /// Loads the limits stored for `account_id`.
+    async fn load_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, AppError> {
+        self.repo
+            .get_account_limits(account_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Returns the global caps tightened by `account_id`'s own limits.
+    async fn limits_for(&self, account_id: AccountId) -> Result<AmountLimits, AppError> {
+        let account_limits = self.load_account_limits(account_id).await?;
+        Ok(self.limits.tightened(&account_limits))
+    }
+
+    /// Rejects debiting `amount` from `account_id` if the account's outgoing
+    /// payments over the last 24 hours would pass its daily debit limit.
+    ///
+    /// The debits are summed inside `work`, so the ones it staged already
+    /// count and a debit racing this one is not missed.
+    async fn check_daily_debit(
+        &self,
+        work: &mut dyn UnitOfWork,
+        account_id: AccountId,
+        limits: &AmountLimits,
+        amount: i64,
+    ) -> Result<(), AppError> {
+        if limits.daily_debit_limit.is_none() {
+            return Ok(());
+        }
+        let since = self.clock.now() - DAILY_DEBIT_WINDOW;
+        let spent = work.debited_since(account_id, since).await?;
+        limits.check_daily_debit(spent, amount)
+    }
+
+    /// Rejects one more transaction on `account_id` if the ones it took part
+    /// in over the last hour already reach its hourly limit.
+    ///
+    /// Like the daily debit limit, the count is taken inside `work`.
+    async fn check_hourly_transactions(
+        &self,
+        work: &mut dyn UnitOfWork,
+        account_id: AccountId,
+        limits: &AmountLimits,
+    ) -> Result<(), AppError> {
+        if limits.max_transactions_per_hour.is_none() {
+            return Ok(());
+        }
+        let since = self.clock.now() - HOURLY_TRANSACTION_WINDOW;
+        let count = work.transactions_since(account_id, since).await?;
+        limits.check_hourly_transactions(count)
+    }
+
+    /// Rejects a debit from `account_id` that its spending rules do not permit.
+    async fn check_purpose(
+        &self,
+        account_id: AccountId,
+        purpose_code: Option<&str>,
+    ) -> Result<(), AppError> {
+        let rules = self
+            .repo
+            .get_spending_rules(account_id)
+            .await
+            .map_err(AppError::from)?;
+        if rules.permits(purpose_code) {
+            return Ok(());
+        }
+
+        Err(AppError::SpendingRuleViolation(match purpose_code {
+            Some(code) => format!(
+                "purpose code {} is not allowed for account {}",
+                code, account_id
+            ),
+            None => format!("account {} requires a purpose code", account_id),
+        }))
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Inbound Payments
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// [`receive_inbound_payment`](Self::receive_inbound_payment) into an
+    /// account `actor` has access to, attributed to its API key unless the
+    /// payment was already recorded.
+    pub async fn receive_inbound_payment_as(
+        &self,
+        actor: &ActorContext,
+        req: InboundPaymentRequest<AccountRef>,
+    ) -> Result<InboundPayment, AppError> {
+        let account_id = self.resolve_account_for(actor, &req.account_id).await?;
+        let payment = self
+            .receive_inbound_payment(req.with_account(account_id))
+            .await?;
+        if !payment.duplicate {
+            self.record_api_key_transaction(actor.api_key_id, &payment.transaction)
+                .await;
+        }
+        Ok(payment)
+    }
+
+    /// Records funds received over external rails as a deposit, deduplicated
+    /// on the rail's external reference.
+    ///
+    /// A reference already recorded credits nothing: the original deposit is
+    /// returned marked as a duplicate, or a conflict if it was for another
+    /// account, amount or currency.
+    pub async fn receive_inbound_payment(
+        &self,
+        req: InboundPaymentRequest,
+    ) -> Result<InboundPayment, AppError> {
+        let external_reference = req.external_reference.trim();
+        if external_reference.is_empty() {
+            return Err(AppError::BadRequest(
+                "An inbound payment needs an external reference".into(),
+            ));
+        }
+        if external_reference.chars().count() > MAX_EXTERNAL_REFERENCE_LEN {
+            return Err(AppError::BadRequest(format!(
+                "External reference must be at most {} characters",
+                MAX_EXTERNAL_REFERENCE_LEN
+            )));
+        }
+        let key = format!("inbound:{}", external_reference);
+
+        if let Some(existing) = self.repo.find_by_idempotency_key(&key).await? {
+            if existing.transaction_type != TransactionType::Deposit
+                || existing.destination_account_id != Some(req.account_id)
+                || existing.amount.amount() != req.amount
+                || existing.amount.currency() != req.currency
+            {
+                return Err(AppError::Conflict(format!(
+                    "Inbound payment {} was already recorded with different details",
+                    external_reference
+                )));
+            }
+            return Ok(InboundPayment {
+                transaction: existing,
+                duplicate: true,
+            });
+        }
+
+        let transaction = self
+            .deposit(DepositRequest {
+                account_id: req.account_id,
+                amount: req.amount,
+                currency: req.currency,
+                idempotency_key: Some(key),
+                reference: req.reference,
+                counterparty: req.counterparty,
+                metadata: HashMap::new(),
+                encrypted_memo: None,
+            })
+            .await?;
+        Ok(InboundPayment {
+            transaction,
+            duplicate: false,
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Transaction History
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Gets a transaction into or out of an account `actor` has access to.
+    pub async fn get_transaction(
+        &self,
+        actor: &ActorContext,
+        id: TransactionId,
+    ) -> Result<Transaction, AppError> {
+        let tx = self.load_transaction(id).await?;
+        // Either party to a transfer may see it
+        let account_id = tx
+            .source_account_id
             .into_iter()
-            .filter(|ep| ep.is_active && ep.events.contains(&event_type.to_string()))
-            .collect();
+            .chain(tx.destination_account_id)
+            .find(|party| actor.may_access(*party))
+            .ok_or_else(|| {
+                AppError::AccessDenied("API key not authorized for this transaction".into())
+            })?;
+        self.ensure_mode(actor, account_id).await?;
+        Ok(tx)
+    }
+
+    async fn load_transaction(&self, id: TransactionId) -> Result<Transaction, AppError> {
+        self.repo
+            .get_transaction(id)
+            .await
+            .map_err(Into::into)
+            .and_then(|opt| opt.ok_or_else(|| AppError::NotFound(format!("Transaction {}", id))))
+    }
+
+    /// Lists a page of the transactions of an account `actor` has access to,
+    /// newest first.
+    ///
+    /// `next_cursor` is set when more transactions match; pass it back as
+    /// `cursor` with the same filters to get the next page.
+    pub async fn list_transactions(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+        query: TransactionListQuery,
+    ) -> Result<TransactionPage, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+
+        let (after, limit) = transaction_page_bounds(query.cursor.as_deref(), query.limit)?;
+        let filter = TransactionFilter {
+            transaction_type: query.transaction_type,
+            from: query.from,
+            to: query.to,
+            min_amount: query.min_amount,
+            max_amount: query.max_amount,
+        };
+        check_transaction_filter(&filter)?;
+
+        // Fetch one extra row to learn whether another page follows.
+        let data = self
+            .repo
+            .list_transactions_page(account_id, &filter, after, limit + 1)
+            .await
+            .map_err(AppError::from)?;
+        Ok(transaction_page(data, limit))
+    }
+
+    /// Searches transactions touching accounts in `actor`'s mode, newest
+    /// first.
+    ///
+    /// At least one criterion must be set, so a search never pages through
+    /// the whole ledger. Keys bound to an account only ever search it, and
+    /// keys bound to an owner must name one of the owner's accounts. Paged
+    /// like [`list_transactions`](Self::list_transactions).
+    pub async fn search_transactions(
+        &self,
+        actor: &ActorContext,
+        query: TransactionSearchQuery,
+    ) -> Result<TransactionPage, AppError> {
+        let (after, limit) = transaction_page_bounds(query.cursor.as_deref(), query.limit)?;
+        let account_id = match &query.account {
+            Some(account) if actor.is_admin() => Some(self.resolve_account(account).await?),
+            Some(account) => Some(self.resolve_account_for(actor, account).await?),
+            None if actor.owner_id.is_some() => {
+                return Err(AppError::BadRequest(
+                    "Keys bound to an owner must name an account to search".into(),
+                ));
+            }
+            None => None,
+        };
+        // Keys bound to an account search nothing else
+        let account_id = actor.account_id.or(account_id);
+        let search = TransactionSearch {
+            reference: query
+                .reference
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty()),
+            account_id,
+            currency: query.currency,
+            metadata_key: query.metadata_key,
+            metadata_value: query.metadata_value,
+            filter: TransactionFilter {
+                transaction_type: query.transaction_type,
+                from: query.from,
+                to: query.to,
+                min_amount: query.min_amount,
+                max_amount: query.max_amount,
+            },
+            mode: None,
+        };
+        if search.is_empty() {
+            return Err(AppError::BadRequest(
+                "Set at least one search criterion".into(),
+            ));
+        }
+        if search.metadata_value.is_some() && search.metadata_key.is_none() {
+            return Err(AppError::BadRequest(
+                "metadata_value needs a metadata_key".into(),
+            ));
+        }
+        check_transaction_filter(&search.filter)?;
+        let search = TransactionSearch {
+            mode: Some(actor.mode),
+            ..search
+        };
+
+        let data = self
+            .repo
+            .search_transactions(&search, after, limit + 1)
+            .await
+            .map_err(AppError::from)?;
+        Ok(transaction_page(data, limit))
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Beneficiaries
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Adds an external account for `req.account_id`, which `actor` must
+    /// have access to, to pay out to and sends it two micro-deposits of
+    /// random amounts. It can be paid once the customer confirms the amounts
+    /// with [`verify_beneficiary`](Self::verify_beneficiary).
+    ///
+    /// The micro-deposits are simulated: their amounts are recorded and
+    /// logged, but nothing is sent over the rails.
+    pub async fn create_beneficiary(
+        &self,
+        actor: &ActorContext,
+        req: CreateBeneficiaryRequest,
+    ) -> Result<Beneficiary, AppError> {
+        let account = self.get_account(actor, req.account_id).await?;
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest(
+                "Beneficiary name cannot be empty".into(),
+            ));
+        }
+        let external_id = req.external_id.trim();
+        if external_id.is_empty() {
+            return Err(AppError::BadRequest(
+                "Beneficiary external_id cannot be empty".into(),
+            ));
+        }
+
+        let micro_deposits = {
+            let mut rng = rand::rng();
+            [
+                rng.random_range(1..=MAX_MICRO_DEPOSIT),
+                rng.random_range(1..=MAX_MICRO_DEPOSIT),
+            ]
+        };
+        let beneficiary = Beneficiary {
+            id: BeneficiaryId::from_uuid(self.ids.new_id()),
+            account_id: account.id,
+            name: name.to_string(),
+            external_id: external_id.to_string(),
+            currency: account.currency(),
+            status: BeneficiaryStatus::Pending,
+            failed_attempts: 0,
+            micro_deposits,
+            created_at: self.clock.now(),
+            verified_at: None,
+        };
+        self.repo
+            .create_beneficiary(&beneficiary)
+            .await
+            .map_err(AppError::from)?;
+        let [first, second] = beneficiary.micro_deposits;
+        tracing::info!(
+            beneficiary_id = %beneficiary.id,
+            "Simulated micro-deposits of {} and {} ({} minor units) to {}",
+            first,
+            second,
+            beneficiary.currency,
+            beneficiary.external_id
+        );
+        Ok(beneficiary)
+    }
+
+    /// Gets a beneficiary of an account `actor` has access to.
+    pub async fn get_beneficiary(
+        &self,
+        actor: &ActorContext,
+        id: BeneficiaryId,
+    ) -> Result<Beneficiary, AppError> {
+        let beneficiary = self
+            .repo
+            .get_beneficiary(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Beneficiary {}", id)))?;
+        self.ensure_account_access(actor, beneficiary.account_id)
+            .await?;
+        Ok(beneficiary)
+    }
+
+    /// Lists the beneficiaries of an account `actor` has access to, newest
+    /// first.
+    pub async fn list_beneficiaries(
+        &self,
+        actor: &ActorContext,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, AppError> {
+        self.ensure_account_access(actor, account_id).await?;
+        self.repo
+            .list_beneficiaries(account_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Confirms the two micro-deposit amounts sent to a beneficiary of an
+    /// account `actor` has access to, in either order, verifying it for
+    /// payouts.
+    ///
+    /// Wrong amounts are rejected and counted; after
+    /// [`MAX_VERIFICATION_ATTEMPTS`] the beneficiary fails for good.
+    pub async fn verify_beneficiary(
+        &self,
+        actor: &ActorContext,
+        id: BeneficiaryId,
+        req: VerifyBeneficiaryRequest,
+    ) -> Result<Beneficiary, AppError> {
+        let mut beneficiary = self.get_beneficiary(actor, id).await?;
+        let amounts: [i64; 2] = req.amounts.as_slice().try_into().map_err(|_| {
+            AppError::BadRequest("Give exactly the two micro-deposit amounts".into())
+        })?;
+        let failed_attempts = beneficiary.failed_attempts;
+        let confirmed = beneficiary
+            .confirm(amounts, self.clock.now())
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        if !self
+            .repo
+            .record_beneficiary_attempt(&beneficiary, failed_attempts)
+            .await
+            .map_err(AppError::from)?
+        {
+            return Err(AppError::Conflict(format!(
+                "Beneficiary {} changed while verifying; try again",
+                id
+            )));
+        }
+        if !confirmed {
+            let left = MAX_VERIFICATION_ATTEMPTS - beneficiary.failed_attempts;
+            return Err(AppError::BadRequest(if left == 0 {
+                "Micro-deposit amounts do not match; the beneficiary failed verification"
+                    .to_string()
+            } else {
+                format!(
+                    "Micro-deposit amounts do not match; {} attempt(s) left",
+                    left
+                )
+            }));
+        }
+        Ok(beneficiary)
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Payouts
+    // ─────────────────────────────────────────────────────────────────────────────
 
-        for endpoint in targets {
+    /// Sends a withdrawal on to its counterparty through the payout provider.
+    /// The counterparty must be a verified beneficiary of the account, matched
+    /// by `external_id`.
+    ///
+    /// Sending the same withdrawal again returns the payout already made.
+    pub async fn send_payout(&self, transaction_id: TransactionId) -> Result<Payout, AppError> {
+        let tx = self.load_transaction(transaction_id).await?;
+        if tx.transaction_type != TransactionType::Withdrawal {
+            return Err(AppError::BadRequest(format!(
+                "Transaction {} is not a withdrawal",
+                transaction_id
+            )));
+        }
+        let beneficiary = tx.counterparty.ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Withdrawal {} has no counterparty to pay",
+                transaction_id
+            ))
+        })?;
+        self.check_verified_beneficiary(tx.source_account_id, &beneficiary)
+            .await?;
+        let instruction = PayoutInstruction {
+            transaction_id,
+            amount: tx.amount,
+            beneficiary,
+            reference: tx.reference,
+        };
+        self.payouts
+            .initiate(&instruction)
+            .await
+            .map_err(payout_error)
+    }
+
+    /// Fails unless `beneficiary` is verified for payouts from `account_id`.
+    async fn check_verified_beneficiary(
+        &self,
+        account_id: Option<AccountId>,
+        beneficiary: &Counterparty,
+    ) -> Result<(), AppError> {
+        let verified = match (account_id, beneficiary.external_id.as_deref()) {
+            (Some(account_id), Some(external_id)) => self
+                .repo
+                .list_beneficiaries(account_id)
+                .await
+                .map_err(AppError::from)?
+                .iter()
+                .any(|b| b.is_verified() && b.external_id == external_id),
+            _ => false,
+        };
+        if !verified {
+            return Err(AppError::BadRequest(format!(
+                "{} is not a verified beneficiary of the account",
+                beneficiary.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Gets a payout by the provider's reference for it.
+    pub async fn payout_status(&self, provider_reference: &str) -> Result<Payout, AppError> {
+        self.payouts
+            .status(provider_reference)
+            .await
+            .map_err(payout_error)
+    }
+
+    /// Stops a payout that is still pending.
+    pub async fn cancel_payout(&self, provider_reference: &str) -> Result<Payout, AppError> {
+        self.payouts
+            .cancel(provider_reference)
+            .await
+            .map_err(payout_error)
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Event Fan-out
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Registers a webhook endpoint in `actor`'s mode, an admin key; payments
+    /// in that mode made from now on queue events for it.
+    pub async fn register_webhook(
+        &self,
+        actor: &ActorContext,
+        url: &str,
+        events: Vec<String>,
+        timeouts: WebhookTimeouts,
+        signature_algorithm: WebhookSignatureAlgorithm,
+    ) -> Result<WebhookEndpoint, AppError> {
+        let mut registered = self
+            .register_webhooks(
+                actor,
+                vec![NewWebhookEndpoint {
+                    url: url.to_string(),
+                    events,
+                    timeouts,
+                    signature_algorithm,
+                    mode: actor.mode,
+                }],
+            )
+            .await?;
+        Ok(registered.remove(0))
+    }
+
+    /// Registers several webhook endpoints in `actor`'s mode at once, all of
+    /// them or none. `actor` must be an admin key.
+    pub async fn register_webhooks(
+        &self,
+        actor: &ActorContext,
+        mut endpoints: Vec<NewWebhookEndpoint>,
+    ) -> Result<Vec<WebhookEndpoint>, AppError> {
+        actor.ensure_admin()?;
+        if endpoints.is_empty() {
+            return Err(AppError::BadRequest(
+                "A webhook batch needs at least one endpoint".into(),
+            ));
+        }
+        if endpoints.len() > MAX_WEBHOOK_BATCH {
+            return Err(AppError::BadRequest(format!(
+                "A webhook batch may register at most {} endpoints",
+                MAX_WEBHOOK_BATCH
+            )));
+        }
+        for endpoint in &mut endpoints {
+            endpoint.mode = actor.mode;
+        }
+        let registered = self.repo.register_webhook_endpoints(endpoints).await?;
+        self.webhook_endpoints.invalidate();
+        tracing::info!(count = registered.len(), "Registered webhook endpoints");
+        Ok(registered)
+    }
+
+    /// Lists the webhook endpoints in `actor`'s mode, an admin key.
+    pub async fn list_webhooks(
+        &self,
+        actor: &ActorContext,
+    ) -> Result<Vec<WebhookEndpoint>, AppError> {
+        actor.ensure_admin()?;
+        self.repo
+            .list_webhook_endpoints(Some(actor.mode))
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Gets webhook endpoint `id` for `actor`, an admin key; endpoints in the
+    /// other mode are not found.
+    async fn webhook_endpoint(
+        &self,
+        actor: &ActorContext,
+        id: WebhookEndpointId,
+    ) -> Result<WebhookEndpoint, AppError> {
+        actor.ensure_admin()?;
+        self.repo
+            .get_webhook_endpoint(id)
+            .await
+            .map_err(AppError::from)?
+            .filter(|endpoint| endpoint.mode == actor.mode)
+            .ok_or_else(|| AppError::NotFound(format!("Webhook endpoint {}", id)))
+    }
+
+    /// Replaces a webhook endpoint's subscriptions, pauses or resumes its
+    /// deliveries and/or changes how they are signed.
+    pub async fn update_webhook(
+        &self,
+        actor: &ActorContext,
+        id: WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<WebhookSignatureAlgorithm>,
+    ) -> Result<WebhookEndpoint, AppError> {
+        if events.is_none() && is_active.is_none() && signature_algorithm.is_none() {
+            return Err(AppError::BadRequest(
+                "Nothing to update: set events, is_active or signature_algorithm".into(),
+            ));
+        }
+        if let Some(events) = &events {
+            validate_event_patterns(events).map_err(|e| AppError::BadRequest(e.to_string()))?;
+        }
+        self.webhook_endpoint(actor, id).await?;
+        let endpoint = self
+            .repo
+            .update_webhook_endpoint(id, events, is_active, signature_algorithm)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Webhook endpoint {}", id)))?;
+        self.webhook_endpoints.invalidate();
+        tracing::info!(
+            endpoint_id = %id,
+            is_active = endpoint.is_active,
+            signature_algorithm = %endpoint.signature_algorithm,
+            "Updated webhook endpoint"
+        );
+        Ok(endpoint)
+    }
+
+    /// Gives a webhook endpoint a new signing secret, used for deliveries
+    /// from now on.
+    pub async fn rotate_webhook_secret(
+        &self,
+        actor: &ActorContext,
+        id: WebhookEndpointId,
+    ) -> Result<WebhookEndpoint, AppError> {
+        self.webhook_endpoint(actor, id).await?;
+        let endpoint = self
+            .repo
+            .rotate_webhook_secret(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Webhook endpoint {}", id)))?;
+        self.webhook_endpoints.invalidate();
+        tracing::info!(endpoint_id = %id, "Rotated webhook secret");
+        Ok(endpoint)
+    }
+
+    /// Deletes a webhook endpoint. Its past events stay readable.
+    pub async fn delete_webhook(
+        &self,
+        actor: &ActorContext,
+        id: WebhookEndpointId,
+    ) -> Result<(), AppError> {
+        self.webhook_endpoint(actor, id).await?;
+        if !self.repo.delete_webhook_endpoint(id).await? {
+            return Err(AppError::NotFound(format!("Webhook endpoint {}", id)));
+        }
+        self.webhook_endpoints.invalidate();
+        tracing::info!(endpoint_id = %id, "Deleted webhook endpoint");
+        Ok(())
+    }
+
+    /// Active webhook endpoints, listed from the repository when the cached
+    /// list has expired or was invalidated.
+    async fn active_webhook_endpoints(&self) -> Result<Arc<[WebhookEndpoint]>, RepoError> {
+        let now = self.clock.now();
+        match self.webhook_endpoints.get(now) {
+            Ok(endpoints) => Ok(endpoints),
+            Err(generation) => {
+                let endpoints = self.repo.list_webhook_endpoints(None).await?;
+                Ok(self.webhook_endpoints.store(generation, now, endpoints))
+            }
+        }
+    }
+
+    /// Gets a webhook event with its full payload, including payloads too
+    /// large to have been delivered inline. Events queued for endpoints in
+    /// the other mode than `actor`'s are not found.
+    pub async fn webhook_event(
+        &self,
+        actor: &ActorContext,
+        id: uuid::Uuid,
+    ) -> Result<WebhookEvent, AppError> {
+        actor.ensure_admin()?;
+        self.repo
+            .get_webhook_event(id)
+            .await
+            .map_err(AppError::from)?
+            .filter(|event| event.mode == actor.mode)
+            .ok_or_else(|| AppError::NotFound(format!("Webhook event {}", id)))
+    }
+
+    /// Lists dead-lettered events in `actor`'s mode matching `filter`, oldest
+    /// first, with their full payloads.
+    pub async fn dead_lettered_webhooks(
+        &self,
+        actor: &ActorContext,
+        filter: DeadLetterFilter,
+    ) -> Result<Vec<WebhookEvent>, AppError> {
+        actor.ensure_admin()?;
+        let filter = DeadLetterFilter {
+            mode: Some(actor.mode),
+            ..filter
+        };
+        self.repo
+            .list_dead_lettered_webhooks(&filter)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Queues dead-lettered events in `actor`'s mode matching `filter` for
+    /// delivery again, e.g. once a consumer is back from an outage. Returns
+    /// how many were requeued.
+    pub async fn retry_dead_lettered_webhooks(
+        &self,
+        actor: &ActorContext,
+        filter: DeadLetterFilter,
+    ) -> Result<u64, AppError> {
+        actor.ensure_admin()?;
+        let filter = DeadLetterFilter {
+            mode: Some(actor.mode),
+            ..filter
+        };
+        let requeued = self
+            .repo
+            .requeue_dead_lettered_webhooks(&filter)
+            .await
+            .map_err(AppError::from)?;
+        tracing::info!(requeued, "Requeued dead-lettered webhooks");
+        Ok(requeued)
+    }
+
+    /// Lists an endpoint's events with a delivery sequence above
+    /// `after_sequence`, lowest first, so a receiver can fill gaps it has
+    /// seen in the sequence.
+    pub async fn webhook_events_after(
+        &self,
+        actor: &ActorContext,
+        endpoint_id: payments_types::WebhookEndpointId,
+        after_sequence: i64,
+        limit: Option<usize>,
+    ) -> Result<Vec<WebhookEvent>, AppError> {
+        self.webhook_endpoint(actor, endpoint_id).await?;
+        let limit = limit
+            .unwrap_or(DEFAULT_WEBHOOK_EVENT_PAGE)
+            .clamp(1, MAX_WEBHOOK_EVENT_PAGE);
+        self.repo
+            .list_webhook_events_after(endpoint_id, after_sequence, limit)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Lists an endpoint's delivery log, most recent event first, so
+    /// operators can see which deliveries failed and why.
+    pub async fn webhook_deliveries(
+        &self,
+        actor: &ActorContext,
+        endpoint_id: payments_types::WebhookEndpointId,
+        query: WebhookDeliveriesQuery,
+    ) -> Result<Vec<WebhookEvent>, AppError> {
+        let status = query
+            .status
+            .as_deref()
+            .map(str::parse::<WebhookStatus>)
+            .transpose()
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        self.webhook_endpoint(actor, endpoint_id).await?;
+        let filter = WebhookDeliveryFilter {
+            status,
+            before_sequence: query.before_sequence,
+        };
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_WEBHOOK_EVENT_PAGE)
+            .clamp(1, MAX_WEBHOOK_EVENT_PAGE);
+        self.repo
+            .list_webhook_deliveries(endpoint_id, &filter, limit)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Queues a failed or dead-lettered event for delivery again, with a
+    /// fresh retry budget.
+    pub async fn redeliver_webhook_event(
+        &self,
+        actor: &ActorContext,
+        id: uuid::Uuid,
+    ) -> Result<WebhookEvent, AppError> {
+        let event = self.webhook_event(actor, id).await?;
+        let requeued = event.status.is_failed()
+            && self
+                .repo
+                .requeue_webhook_event(id)
+                .await
+                .map_err(AppError::from)?;
+        if !requeued {
+            return Err(AppError::BadRequest(format!(
+                "Webhook event {} is {}; only FAILED or DEAD_LETTERED events can be redelivered",
+                id, event.status
+            )));
+        }
+        tracing::info!(event_id = %id, "Requeued webhook event");
+        self.webhook_event(actor, id).await
+    }
+
+    /// Logs `event`, stores its webhook events and announces it.
+    async fn emit(&self, event: DomainEvent) {
+        let mode = self.event_mode(&event).await;
+        if event.is_logged()
+            && let Err(e) = self.repo.append_event(&event, mode).await
+        {
+            tracing::error!("Failed to append event to the event log: {}", e);
+        }
+        self.trigger_webhook(&event, mode).await;
+        self.announce(LiveEvent { mode, event }).await;
+    }
+
+    /// The mode `event` happened in: its own, or that of the first account
+    /// it concerns. Events about no account, such as quota warnings, are
+    /// live.
+    async fn event_mode(&self, event: &DomainEvent) -> Mode {
+        if let Some(mode) = event.mode() {
+            return mode;
+        }
+        let Some(&account_id) = event.account_ids().first() else {
+            return Mode::Live;
+        };
+        match self.repo.get_account(account_id).await {
+            Ok(account) => account.map_or(Mode::Live, |account| account.mode),
+            Err(e) => {
+                tracing::error!("Failed to look up the mode of an event: {}", e);
+                Mode::Live
+            }
+        }
+    }
+
+    /// Counts a domain event and hands it to every registered publisher and
+    /// live stream. Its webhook events are already stored; the webhook worker
+    /// sends them.
+    async fn announce(&self, live: LiveEvent) {
+        let event = &live.event;
+        if let DomainEvent::FundsDeposited(tx)
+        | DomainEvent::FundsWithdrawn(tx)
+        | DomainEvent::TransferCompleted(tx) = event
+        {
+            let transaction_type = tx.transaction_type.to_string();
+            let currency = tx.amount.currency().to_string();
+            let labels = [
+                ("type", transaction_type.as_str()),
+                ("currency", currency.as_str()),
+            ];
+            self.metrics
+                .increment_counter(metrics::TRANSACTIONS_TOTAL, &labels, 1);
+            self.metrics.increment_counter(
+                metrics::TRANSACTION_VOLUME_TOTAL,
+                &labels,
+                tx.amount.amount().unsigned_abs(),
+            );
+            if let Some(conversion) = &tx.conversion {
+                let to = conversion.converted_amount.currency().to_string();
+                self.metrics.increment_counter(
+                    metrics::CONVERSIONS_TOTAL,
+                    &[("from", currency.as_str()), ("to", to.as_str())],
+                    1,
+                );
+            }
+        }
+
+        for publisher in &self.publishers {
+            publisher.publish(event).await;
+        }
+        // An error only means no stream is open right now.
+        let _ = self.live_events.send(live);
+    }
+
+    /// Stores a webhook event for each endpoint in `mode` subscribed to
+    /// `event`, for events that do not come from a unit of work. Failures
+    /// are logged: the event has already happened.
+    async fn trigger_webhook(&self, event: &DomainEvent, mode: Mode) {
+        let endpoints = match self.active_webhook_endpoints().await {
+            Ok(eps) => eps,
+            Err(e) => {
+                tracing::error!("Failed to list webhooks for trigger: {}", e);
+                return;
+            }
+        };
+
+        for endpoint in subscribed(&endpoints, event.event_type(), mode) {
             let endpoint_id = WebhookEndpointId::from_uuid(endpoint.id);
-            // 3. Create event in DB
             if let Err(e) = self
                 .repo
-                .create_webhook_event(endpoint_id, event_type, payload.clone())
+                .create_webhook_event(endpoint_id, event.event_type(), event.payload())
                 .await
             {
                 tracing::error!("Failed to persist webhook event: {}", e);
-                continue;
-            }
-
-            // 4. Send event (Fire and forget via tokio spawn)
-            let url = endpoint.url.clone();
-            let payload = payload.clone();
-            let event_type = event_type.to_string();
-
-            tokio::spawn(async move {
-                let client = reqwest::Client::new();
-                // Construct standard wrapper if needed, or just send payload
-                // Usually webhooks wrap: { "event": "type", "data": payload }
-                let body = serde_json::json!({
-                    "event": event_type,
-                    "data": payload
-                });
-
-                tracing::info!("Sending webhook {} to {}", event_type, url);
-
-                match client.post(&url).json(&body).send().await {
-                    Ok(resp) => {
-                        if !resp.status().is_success() {
-                            tracing::warn!(
-                                "Webhook to {} failed with status {}",
-                                url,
-                                resp.status()
-                            );
-                        } else {
-                            tracing::info!("Webhook sent to {}", url);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to send webhook request to {}: {}", url, e);
-                    }
-                }
-            });
+            }
         }
     }
 }
+
+/// Appends `event`, which happened in `mode`, to the event log in `work` and
+/// queues a webhook event for each endpoint in that mode subscribed to it, so
+/// both are stored if and only if the writes behind it are. The webhook
+/// worker delivers them once committed.
+async fn stage_event(
+    work: &mut dyn UnitOfWork,
+    endpoints: &[WebhookEndpoint],
+    event: &DomainEvent,
+    mode: Mode,
+) -> Result<(), RepoError> {
+    if event.is_logged() {
+        work.append_event(event, mode).await?;
+    }
+    for endpoint in subscribed(endpoints, event.event_type(), mode) {
+        work.create_webhook_event(
+            WebhookEndpointId::from_uuid(endpoint.id),
+            event.event_type(),
+            event.payload(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Active endpoints in `mode` subscribed to `event_type`.
+fn subscribed<'a>(
+    endpoints: &'a [WebhookEndpoint],
+    event_type: &'a str,
+    mode: Mode,
+) -> impl Iterator<Item = &'a WebhookEndpoint> {
+    endpoints
+        .iter()
+        .filter(move |ep| ep.is_active && ep.mode == mode && ep.subscribes_to(event_type))
+}
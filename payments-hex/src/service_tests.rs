@@ -2,23 +2,76 @@
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use std::collections::HashMap;
-    use std::sync::Mutex;
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::{Arc, Mutex};
 
     use async_trait::async_trait;
+    use chrono::{DateTime, Duration, NaiveDate, Utc};
 
     use payments_types::{
-        Account, AccountId, AppError, CreateAccountRequest, CurrencyCode, DepositRequest,
-        DomainError, DynMoney, RepoError, Transaction, TransactionId, TransactionRepository,
-        TransferRequest, WithdrawRequest,
+        Account, AccountAlias, AccountId, AccountLimits, AccountRef, AccountStatus, ActorContext,
+        AddAliasRequest, Alias, ApiKeyId, ApiKeyScope, AppError, AssignFeeScheduleRequest,
+        AuthorizeRequest, BalanceHistoryQuery, BatchMode, BatchOperation, BatchRequest,
+        Beneficiary, BeneficiaryId, BeneficiaryStatus, CaptureRequest, ChangeAction, ChangeRequest,
+        ChangeRequestId, ChangeStatus, Clock, Counterparty, CreateAccountRequest,
+        CreateBeneficiaryRequest, CreateFeeScheduleRequest, CreateMemoKeyRequest,
+        CreateOwnerRequest, CreateQuoteRequest, CreateScheduledPaymentRequest,
+        CreateSettlementBatchRequest, CurrencyBalance, CurrencyCode, DEFAULT_HOLD_TTL_SECS,
+        DailyBalance, DepositRequest, DomainError, DomainEvent, DynMoney, EncryptedMemo, ErrorCode,
+        EventLogPage, EventLogQuery, ExchangeError, ExchangeRateProvider, Export, ExportId,
+        ExportRequest, ExportStatus, FeeAssignment, FeeSchedule, FeeScheduleId, FeeTier,
+        FixedClock, FloatPosition, FxConversion, Hold, HoldId, HoldStatus, IdempotencyRecord,
+        IdempotencyStore, InboundPaymentRequest, JournalExportFormat, LedgerOperation, LoggedEvent,
+        MAX_ALIASES_PER_ACCOUNT, MAX_EXTERNAL_REFERENCE_LEN, MAX_HOLD_TTL_SECS,
+        MAX_MEMO_KEYS_PER_ACCOUNT, MAX_METADATA_KEY_LEN, MAX_METADATA_KEYS, MAX_METADATA_VALUE_LEN,
+        MAX_MICRO_DEPOSIT, MAX_QUOTE_TTL_SECS, MAX_VERIFICATION_ATTEMPTS, MemoKey, MemoKeyId, Mode,
+        Notification, Notifier, NotifyError, Owner, OwnerId, PAYMENTS_IDEMPOTENCY_ENDPOINT,
+        PaymentCheck, PaymentSchedule, PayoutStatus, PositiveAmount, Quote, QuoteId, RateSnapshot,
+        RepoError, RiskAssessment, RiskCheck, RiskDecision, ScheduledPayment, ScheduledPaymentId,
+        ScheduledPaymentStatus, SettlementBatch, SettlementBatchId, SettlementBatchStatus,
+        SettlementExportFormat, SpendingRules, Statement, StatementEmail, StatementId,
+        StatementPeriod, StorageCounts, StorageResource, SystemClock, Transaction,
+        TransactionCursor, TransactionFilter, TransactionId, TransactionListQuery,
+        TransactionRepository, TransactionSearch, TransactionSearchQuery, TransactionType,
+        TransferRequest, UnitOfWork, UpdateOwnerRequest, VerifyBeneficiaryRequest, Warning,
+        WarningRule, WithdrawRequest,
     };
 
-    use crate::PaymentService;
+    use crate::{
+        AmountLimits, BatchOutcome, BroadcastPublisher, DormancyPolicy, DownloadLinks,
+        GlAccountCodes, MetricsRegistry, PaymentService, SettlementDebtor, StatementLinks,
+        StorageQuotas, StubBankPayouts,
+    };
+    use payments_repo::{CountingRepo, RepoCalls, count_repo_calls};
+    use payments_types::ports::metrics::{
+        CONVERSIONS_TOTAL, QUOTES_EXPIRED_TOTAL, QUOTES_ISSUED_TOTAL, TRANSACTION_VOLUME_TOTAL,
+        TRANSACTIONS_TOTAL,
+    };
 
     /// Simple in-memory repository for testing the service layer.
     pub struct MockRepo {
         accounts: Mutex<HashMap<AccountId, Account>>,
         transactions: Mutex<Vec<Transaction>>,
+        spending_rules: Mutex<HashMap<AccountId, SpendingRules>>,
+        account_limits: Mutex<HashMap<AccountId, AccountLimits>>,
+        account_aliases: Mutex<Vec<AccountAlias>>,
+        owners: Mutex<Vec<Owner>>,
+        memo_keys: Mutex<Vec<MemoKey>>,
+        fee_schedules: Mutex<Vec<FeeSchedule>>,
+        fee_assignments: Mutex<Vec<FeeAssignment>>,
+        settlement_batches: Mutex<Vec<SettlementBatch>>,
+        settlement_payouts: Mutex<HashMap<TransactionId, SettlementBatchId>>,
+        statement_emails: Mutex<HashMap<AccountId, String>>,
+        statements: Mutex<Vec<(Statement, String)>>,
+        exports: Mutex<HashMap<ExportId, (Export, Option<String>)>>,
+        scheduled_payments: Mutex<Vec<ScheduledPayment>>,
+        holds: Mutex<Vec<Hold>>,
+        beneficiaries: Mutex<Vec<Beneficiary>>,
+        rate_snapshots: Mutex<Vec<RateSnapshot>>,
+        quotes: Mutex<HashMap<QuoteId, Quote>>,
+        daily_balances: Mutex<HashMap<AccountId, BTreeMap<NaiveDate, i64>>>,
+        idempotency_records: Mutex<HashMap<(String, String), IdempotencyRecord>>,
+        event_log: Mutex<Vec<LoggedEvent>>,
     }
 
     impl MockRepo {
@@ -26,6 +79,211 @@ pub(crate) mod tests {
             Self {
                 accounts: Mutex::new(HashMap::new()),
                 transactions: Mutex::new(Vec::new()),
+                spending_rules: Mutex::new(HashMap::new()),
+                account_limits: Mutex::new(HashMap::new()),
+                account_aliases: Mutex::new(Vec::new()),
+                owners: Mutex::new(vec![Owner {
+                    id: OwnerId::DEFAULT,
+                    name: "Default owner".into(),
+                    email: None,
+                    created_at: DateTime::UNIX_EPOCH,
+                }]),
+                memo_keys: Mutex::new(Vec::new()),
+                fee_schedules: Mutex::new(Vec::new()),
+                fee_assignments: Mutex::new(Vec::new()),
+                settlement_batches: Mutex::new(Vec::new()),
+                settlement_payouts: Mutex::new(HashMap::new()),
+                statement_emails: Mutex::new(HashMap::new()),
+                statements: Mutex::new(Vec::new()),
+                exports: Mutex::new(HashMap::new()),
+                scheduled_payments: Mutex::new(Vec::new()),
+                holds: Mutex::new(Vec::new()),
+                beneficiaries: Mutex::new(Vec::new()),
+                rate_snapshots: Mutex::new(Vec::new()),
+                quotes: Mutex::new(HashMap::new()),
+                daily_balances: Mutex::new(HashMap::new()),
+                idempotency_records: Mutex::new(HashMap::new()),
+                event_log: Mutex::new(Vec::new()),
+            }
+        }
+
+        /// Overwrites a stored balance without recording a transaction.
+        pub fn set_balance(&self, id: AccountId, amount: i64) {
+            let mut accounts = self.accounts.lock().unwrap();
+            let account = accounts.get_mut(&id).expect("account exists");
+            account.balance = DynMoney::new(amount, account.currency()).unwrap();
+        }
+    }
+
+    /// Applies writes straight to the mock and puts the accounts and
+    /// transactions back when dropped uncommitted.
+    struct MockUnitOfWork<'a> {
+        repo: &'a MockRepo,
+        accounts: HashMap<AccountId, Account>,
+        made_before: usize,
+        logged_before: usize,
+        committed: bool,
+    }
+
+    #[async_trait]
+    impl UnitOfWork for MockUnitOfWork<'_> {
+        async fn apply(&mut self, operation: LedgerOperation) -> Result<Transaction, RepoError> {
+            match operation {
+                LedgerOperation::Deposit(req) => self.repo.deposit(req).await,
+                LedgerOperation::Withdraw(req) => self.repo.withdraw(req).await,
+                LedgerOperation::Transfer(req, None) => self.repo.transfer(req).await,
+                LedgerOperation::Transfer(req, Some(conversion)) => {
+                    self.repo.transfer_with_conversion(req, conversion).await
+                }
+            }
+        }
+
+        async fn lock_accounts(&mut self, _account_ids: &[AccountId]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn debited_since(
+            &mut self,
+            account_id: AccountId,
+            since: DateTime<Utc>,
+        ) -> Result<i64, RepoError> {
+            let sent = self
+                .repo
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|tx| tx.source_account_id == Some(account_id) && tx.created_at > since)
+                .fold(0i64, |total, tx| total.saturating_add(tx.amount.amount()));
+            let held = self
+                .repo
+                .holds
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|hold| {
+                    hold.account_id == account_id
+                        && hold.status == HoldStatus::Authorized
+                        && hold.created_at > since
+                })
+                .fold(0i64, |total, hold| total.saturating_add(hold.amount));
+            Ok(sent.saturating_add(held))
+        }
+
+        async fn transactions_since(
+            &mut self,
+            account_id: AccountId,
+            since: DateTime<Utc>,
+        ) -> Result<i64, RepoError> {
+            Ok(self
+                .repo
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|tx| {
+                    (tx.source_account_id == Some(account_id)
+                        || tx.destination_account_id == Some(account_id))
+                        && tx.created_at > since
+                })
+                .count() as i64)
+        }
+
+        async fn create_hold(&mut self, hold: &Hold) -> Result<(), RepoError> {
+            self.repo.create_hold(hold).await
+        }
+
+        async fn record_transaction_risk(
+            &mut self,
+            id: TransactionId,
+            risk: &RiskAssessment,
+        ) -> Result<(), RepoError> {
+            self.repo.record_transaction_risk(id, risk).await
+        }
+
+        async fn create_webhook_event(
+            &mut self,
+            endpoint_id: payments_types::WebhookEndpointId,
+            event_type: &str,
+            payload: serde_json::Value,
+        ) -> Result<payments_types::WebhookEvent, RepoError> {
+            self.repo
+                .create_webhook_event(endpoint_id, event_type, payload)
+                .await
+        }
+
+        async fn append_event(&mut self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+            self.repo.append_event(event, mode).await
+        }
+
+        async fn capture_hold(
+            &mut self,
+            id: HoldId,
+            amount: i64,
+            now: DateTime<Utc>,
+        ) -> Result<(Hold, Transaction), RepoError> {
+            self.repo.capture_hold(id, amount, now).await
+        }
+
+        async fn release_hold(
+            &mut self,
+            id: HoldId,
+            status: HoldStatus,
+            now: DateTime<Utc>,
+        ) -> Result<Hold, RepoError> {
+            self.repo.release_hold(id, status, now).await
+        }
+
+        async fn spend_quote(
+            &mut self,
+            id: QuoteId,
+            transaction_id: TransactionId,
+        ) -> Result<(), RepoError> {
+            let mut quotes = self.repo.quotes.lock().unwrap();
+            let quote = quotes.get_mut(&id).ok_or(RepoError::NotFound)?;
+            quote.spend(transaction_id).map_err(RepoError::Domain)
+        }
+
+        async fn close_merged_account(
+            &mut self,
+            id: AccountId,
+            into: AccountId,
+            now: DateTime<Utc>,
+        ) -> Result<Option<Account>, RepoError> {
+            let mut accounts = self.repo.accounts.lock().unwrap();
+            Ok(accounts
+                .get_mut(&id)
+                .filter(|a| {
+                    a.status != AccountStatus::Closed && a.balance.amount() == 0 && a.held == 0
+                })
+                .map(|a| {
+                    a.status = AccountStatus::Closed;
+                    a.status_changed_at = Some(now);
+                    a.merged_into = Some(into);
+                    a.clone()
+                }))
+        }
+
+        async fn commit(mut self: Box<Self>) -> Result<(), RepoError> {
+            self.committed = true;
+            Ok(())
+        }
+    }
+
+    impl Drop for MockUnitOfWork<'_> {
+        fn drop(&mut self) {
+            if !self.committed {
+                *self.repo.accounts.lock().unwrap() = std::mem::take(&mut self.accounts);
+                self.repo
+                    .transactions
+                    .lock()
+                    .unwrap()
+                    .truncate(self.made_before);
+                self.repo
+                    .event_log
+                    .lock()
+                    .unwrap()
+                    .truncate(self.logged_before);
             }
         }
     }
@@ -33,7 +291,11 @@ pub(crate) mod tests {
     #[async_trait]
     impl TransactionRepository for MockRepo {
         async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
-            let account = Account::new(req.name, req.currency).map_err(RepoError::Domain)?;
+            let account = Account::new(req.name, req.currency)
+                .map_err(RepoError::Domain)?
+                .with_overdraft_limit(req.overdraft_limit)
+                .with_owner(req.owner_id)
+                .with_mode(req.mode.unwrap_or_default());
             self.accounts
                 .lock()
                 .unwrap()
@@ -45,8 +307,249 @@ pub(crate) mod tests {
             Ok(self.accounts.lock().unwrap().get(&id).cloned())
         }
 
-        async fn list_accounts(&self) -> Result<Vec<Account>, RepoError> {
-            Ok(self.accounts.lock().unwrap().values().cloned().collect())
+        async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError> {
+            let accounts = self.accounts.lock().unwrap();
+            Ok(accounts
+                .values()
+                .filter(|a| mode.is_none_or(|mode| a.mode == mode))
+                .cloned()
+                .collect())
+        }
+
+        async fn search_accounts(
+            &self,
+            query: &str,
+            limit: usize,
+            mode: Mode,
+        ) -> Result<Vec<Account>, RepoError> {
+            let mut accounts: Vec<Account> = self
+                .accounts
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|a| a.mode == mode && a.matches_search(query))
+                .cloned()
+                .collect();
+            accounts.sort_by_key(|a| a.name.to_lowercase());
+            accounts.truncate(limit);
+            Ok(accounts)
+        }
+
+        async fn get_spending_rules(
+            &self,
+            account_id: AccountId,
+        ) -> Result<SpendingRules, RepoError> {
+            let rules = self.spending_rules.lock().unwrap();
+            Ok(rules.get(&account_id).cloned().unwrap_or_default())
+        }
+
+        async fn set_spending_rules(
+            &self,
+            account_id: AccountId,
+            rules: &SpendingRules,
+        ) -> Result<(), RepoError> {
+            self.spending_rules
+                .lock()
+                .unwrap()
+                .insert(account_id, rules.clone());
+            Ok(())
+        }
+
+        async fn get_account_limits(
+            &self,
+            account_id: AccountId,
+        ) -> Result<AccountLimits, RepoError> {
+            let limits = self.account_limits.lock().unwrap();
+            Ok(limits.get(&account_id).copied().unwrap_or_default())
+        }
+
+        async fn set_account_limits(
+            &self,
+            account_id: AccountId,
+            limits: &AccountLimits,
+        ) -> Result<(), RepoError> {
+            self.account_limits
+                .lock()
+                .unwrap()
+                .insert(account_id, *limits);
+            Ok(())
+        }
+
+        async fn list_idle_accounts(
+            &self,
+            idle_since: DateTime<Utc>,
+            limit: usize,
+        ) -> Result<Vec<Account>, RepoError> {
+            let transactions = self.transactions.lock().unwrap();
+            let mut idle: Vec<Account> = self
+                .accounts
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|a| {
+                    a.status == AccountStatus::Active
+                        && a.created_at < idle_since
+                        && !transactions.iter().any(|t| {
+                            t.created_at >= idle_since
+                                && (t.source_account_id == Some(a.id)
+                                    || t.destination_account_id == Some(a.id))
+                        })
+                })
+                .cloned()
+                .collect();
+            idle.sort_by_key(|a| a.created_at);
+            idle.truncate(limit);
+            Ok(idle)
+        }
+
+        async fn update_account_status(
+            &self,
+            id: AccountId,
+            from: AccountStatus,
+            to: AccountStatus,
+            now: DateTime<Utc>,
+        ) -> Result<Option<Account>, RepoError> {
+            let mut accounts = self.accounts.lock().unwrap();
+            Ok(accounts.get_mut(&id).filter(|a| a.status == from).map(|a| {
+                a.status = to;
+                a.status_changed_at = Some(now);
+                a.clone()
+            }))
+        }
+
+        async fn set_overdraft_limit(
+            &self,
+            id: AccountId,
+            limit: i64,
+        ) -> Result<Option<Account>, RepoError> {
+            let mut accounts = self.accounts.lock().unwrap();
+            Ok(accounts.get_mut(&id).map(|a| {
+                a.overdraft_limit = limit;
+                a.clone()
+            }))
+        }
+
+        async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError> {
+            let mut aliases = self.account_aliases.lock().unwrap();
+            match aliases.iter().find(|a| a.alias == alias.alias) {
+                Some(stored) if stored.account_id == alias.account_id => Ok(stored.clone()),
+                Some(_) => Err(RepoError::Domain(DomainError::ValidationError(format!(
+                    "Alias {} is already taken",
+                    alias.alias
+                )))),
+                None => {
+                    aliases.push(alias.clone());
+                    Ok(alias.clone())
+                }
+            }
+        }
+
+        async fn get_account_alias(
+            &self,
+            alias: &Alias,
+        ) -> Result<Option<AccountAlias>, RepoError> {
+            let aliases = self.account_aliases.lock().unwrap();
+            Ok(aliases.iter().find(|a| &a.alias == alias).cloned())
+        }
+
+        async fn list_account_aliases(
+            &self,
+            account_id: AccountId,
+        ) -> Result<Vec<AccountAlias>, RepoError> {
+            let aliases = self.account_aliases.lock().unwrap();
+            Ok(aliases
+                .iter()
+                .filter(|a| a.account_id == account_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn remove_account_alias(
+            &self,
+            account_id: AccountId,
+            alias: &Alias,
+        ) -> Result<bool, RepoError> {
+            let mut aliases = self.account_aliases.lock().unwrap();
+            let before = aliases.len();
+            aliases.retain(|a| !(a.account_id == account_id && &a.alias == alias));
+            Ok(aliases.len() < before)
+        }
+
+        async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError> {
+            self.owners.lock().unwrap().push(owner.clone());
+            Ok(owner.clone())
+        }
+
+        async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError> {
+            let owners = self.owners.lock().unwrap();
+            Ok(owners.iter().find(|o| o.id == id).cloned())
+        }
+
+        async fn list_owners(&self) -> Result<Vec<Owner>, RepoError> {
+            Ok(self.owners.lock().unwrap().clone())
+        }
+
+        async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError> {
+            let mut owners = self.owners.lock().unwrap();
+            Ok(owners.iter_mut().find(|o| o.id == owner.id).map(|stored| {
+                *stored = owner.clone();
+                stored.clone()
+            }))
+        }
+
+        async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError> {
+            let mut owners = self.owners.lock().unwrap();
+            let before = owners.len();
+            owners.retain(|o| o.id != id);
+            Ok(owners.len() < before)
+        }
+
+        async fn list_owner_accounts(
+            &self,
+            owner_id: OwnerId,
+            mode: Option<Mode>,
+        ) -> Result<Vec<Account>, RepoError> {
+            let accounts = self.accounts.lock().unwrap();
+            Ok(accounts
+                .values()
+                .filter(|a| a.owner_id == owner_id && mode.is_none_or(|mode| a.mode == mode))
+                .cloned()
+                .collect())
+        }
+
+        async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError> {
+            self.memo_keys.lock().unwrap().push(key.clone());
+            Ok(key.clone())
+        }
+
+        async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError> {
+            let keys = self.memo_keys.lock().unwrap();
+            Ok(keys.iter().find(|k| k.id == id).cloned())
+        }
+
+        async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError> {
+            let keys = self.memo_keys.lock().unwrap();
+            Ok(keys
+                .iter()
+                .filter(|k| k.account_id == account_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn retire_memo_key(
+            &self,
+            account_id: AccountId,
+            id: MemoKeyId,
+            at: DateTime<Utc>,
+        ) -> Result<Option<MemoKey>, RepoError> {
+            let mut keys = self.memo_keys.lock().unwrap();
+            Ok(keys
+                .iter_mut()
+                .find(|k| k.id == id && k.account_id == account_id)
+                .map(|key| {
+                    key.retired_at.get_or_insert(at);
+                    key.clone()
+                }))
         }
 
         async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
@@ -54,10 +557,13 @@ pub(crate) mod tests {
             let account = accounts
                 .get_mut(&req.account_id)
                 .ok_or(RepoError::NotFound)?;
-            let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
+            let money = DynMoney::positive(req.amount, req.currency);
             account.deposit(money).map_err(RepoError::Domain)?;
             let tx =
-                Transaction::deposit(req.account_id, money, req.idempotency_key, req.reference);
+                Transaction::deposit(req.account_id, money, req.idempotency_key, req.reference)
+                    .with_counterparty(req.counterparty)
+                    .with_metadata(req.metadata)
+                    .with_encrypted_memo(req.encrypted_memo);
             self.transactions.lock().unwrap().push(tx.clone());
             Ok(tx)
         }
@@ -67,10 +573,14 @@ pub(crate) mod tests {
             let account = accounts
                 .get_mut(&req.account_id)
                 .ok_or(RepoError::NotFound)?;
-            let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
+            let money = DynMoney::positive(req.amount, req.currency);
             account.withdraw(money).map_err(RepoError::Domain)?;
             let tx =
-                Transaction::withdrawal(req.account_id, money, req.idempotency_key, req.reference);
+                Transaction::withdrawal(req.account_id, money, req.idempotency_key, req.reference)
+                    .with_counterparty(req.counterparty)
+                    .with_purpose_code(req.purpose_code)
+                    .with_metadata(req.metadata)
+                    .with_encrypted_memo(req.encrypted_memo);
             self.transactions.lock().unwrap().push(tx.clone());
             Ok(tx)
         }
@@ -88,7 +598,7 @@ pub(crate) mod tests {
                 return Err(RepoError::Domain(DomainError::CrossCurrencyTransfer));
             }
 
-            let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
+            let money = DynMoney::positive(req.amount, req.currency);
 
             let from = accounts.get_mut(&req.from_account_id).unwrap();
             from.withdraw(money).map_err(RepoError::Domain)?;
@@ -102,16 +612,80 @@ pub(crate) mod tests {
                 money,
                 req.idempotency_key,
                 req.reference,
-            );
+            )
+            .with_purpose_code(req.purpose_code)
+            .with_metadata(req.metadata)
+            .with_encrypted_memo(req.encrypted_memo);
+            self.transactions.lock().unwrap().push(tx.clone());
+            Ok(tx)
+        }
+
+        async fn transfer_with_conversion(
+            &self,
+            req: TransferRequest,
+            conversion: FxConversion,
+        ) -> Result<Transaction, RepoError> {
+            let mut accounts = self.accounts.lock().unwrap();
+            let money = DynMoney::positive(req.amount, req.currency);
+            accounts
+                .get_mut(&req.from_account_id)
+                .ok_or(RepoError::NotFound)?
+                .withdraw(money)
+                .map_err(RepoError::Domain)?;
+            accounts
+                .get_mut(&req.to_account_id)
+                .ok_or(RepoError::NotFound)?
+                .deposit(conversion.converted_amount)
+                .map_err(RepoError::Domain)?;
+
+            let tx = Transaction::transfer(
+                req.from_account_id,
+                req.to_account_id,
+                money,
+                req.idempotency_key,
+                req.reference,
+            )
+            .with_conversion(Some(conversion));
             self.transactions.lock().unwrap().push(tx.clone());
             Ok(tx)
         }
 
+        async fn begin(&self) -> Result<Box<dyn UnitOfWork + '_>, RepoError> {
+            Ok(Box::new(MockUnitOfWork {
+                accounts: self.accounts.lock().unwrap().clone(),
+                made_before: self.transactions.lock().unwrap().len(),
+                logged_before: self.event_log.lock().unwrap().len(),
+                repo: self,
+                committed: false,
+            }))
+        }
+
+        async fn record_transaction_risk(
+            &self,
+            id: TransactionId,
+            risk: &RiskAssessment,
+        ) -> Result<(), RepoError> {
+            let mut transactions = self.transactions.lock().unwrap();
+            if let Some(tx) = transactions
+                .iter_mut()
+                .find(|t| t.id == id && t.risk.is_none())
+            {
+                tx.risk = Some(risk.clone());
+            }
+            Ok(())
+        }
+
         async fn find_by_idempotency_key(
             &self,
-            _key: &str,
+            key: &str,
         ) -> Result<Option<Transaction>, RepoError> {
-            Ok(None)
+            Ok(self
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|t| t.idempotency_key.as_deref() == Some(key))
+                .cloned())
         }
 
         async fn get_transaction(
@@ -144,6 +718,66 @@ pub(crate) mod tests {
                 .collect())
         }
 
+        async fn list_transactions_page(
+            &self,
+            account_id: AccountId,
+            filter: &TransactionFilter,
+            after: Option<TransactionCursor>,
+            limit: usize,
+        ) -> Result<Vec<Transaction>, RepoError> {
+            let mut transactions: Vec<Transaction> = self
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| {
+                    (t.source_account_id == Some(account_id)
+                        || t.destination_account_id == Some(account_id))
+                        && filter.matches(t)
+                        && after.is_none_or(|cursor| cursor.precedes(t))
+                })
+                .cloned()
+                .collect();
+            transactions.sort_by_key(|t| std::cmp::Reverse((t.created_at, *t.id.as_uuid())));
+            transactions.truncate(limit);
+            Ok(transactions)
+        }
+
+        async fn search_transactions(
+            &self,
+            search: &TransactionSearch,
+            after: Option<TransactionCursor>,
+            limit: usize,
+        ) -> Result<Vec<Transaction>, RepoError> {
+            let mut transactions: Vec<Transaction> = self
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| search.matches(t) && after.is_none_or(|cursor| cursor.precedes(t)))
+                .cloned()
+                .collect();
+            transactions.sort_by_key(|t| std::cmp::Reverse((t.created_at, *t.id.as_uuid())));
+            transactions.truncate(limit);
+            Ok(transactions)
+        }
+
+        async fn list_transactions_between(
+            &self,
+            from: DateTime<Utc>,
+            to: DateTime<Utc>,
+            _mode: Option<Mode>,
+        ) -> Result<Vec<Transaction>, RepoError> {
+            Ok(self
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| t.created_at >= from && t.created_at < to)
+                .cloned()
+                .collect())
+        }
+
         async fn verify_api_key_hash(
             &self,
             _key_hash: &str,
@@ -154,10 +788,42 @@ pub(crate) mod tests {
 
         async fn create_api_key(
             &self,
-            _name: &str,
+            name: &str,
+            scopes: &[payments_types::ApiKeyScope],
+            mode: Mode,
         ) -> Result<(payments_types::ApiKey, String), RepoError> {
-            // Mock - not implemented for unit tests
-            unimplemented!("create_api_key not implemented in MockRepo")
+            let key = payments_types::ApiKey::new(name.to_string(), "mock-hash".into(), None)
+                .with_scopes(scopes.to_vec())
+                .with_mode(mode);
+            Ok((key, "sk_mock".into()))
+        }
+
+        async fn create_scoped_api_key(
+            &self,
+            name: &str,
+            account_id: AccountId,
+            scopes: &[payments_types::ApiKeyScope],
+            mode: Mode,
+        ) -> Result<(payments_types::ApiKey, String), RepoError> {
+            let key =
+                payments_types::ApiKey::new(name.to_string(), "mock-hash".into(), Some(account_id))
+                    .with_scopes(scopes.to_vec())
+                    .with_mode(mode);
+            Ok((key, "sk_mock".into()))
+        }
+
+        async fn create_owner_api_key(
+            &self,
+            name: &str,
+            owner_id: OwnerId,
+            scopes: &[payments_types::ApiKeyScope],
+            mode: Mode,
+        ) -> Result<(payments_types::ApiKey, String), RepoError> {
+            let key = payments_types::ApiKey::new(name.to_string(), "mock-hash".into(), None)
+                .with_owner(owner_id)
+                .with_scopes(scopes.to_vec())
+                .with_mode(mode);
+            Ok((key, "sk_mock".into()))
         }
 
         async fn count_api_keys(&self) -> Result<i64, RepoError> {
@@ -170,29 +836,150 @@ pub(crate) mod tests {
             Ok(vec![])
         }
 
-        async fn delete_api_key(&self, _id: payments_types::ApiKeyId) -> Result<bool, RepoError> {
+        async fn delete_api_key(
+            &self,
+            _id: payments_types::ApiKeyId,
+            _deactivated_by: payments_types::ApiKeyId,
+            _at: DateTime<Utc>,
+        ) -> Result<bool, RepoError> {
             // Mock always returns not found
             Ok(false)
         }
 
+        async fn touch_api_key(
+            &self,
+            _id: payments_types::ApiKeyId,
+            _at: DateTime<Utc>,
+        ) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn set_api_key_rate_limit(
+            &self,
+            _id: payments_types::ApiKeyId,
+            _rate_limit_per_minute: Option<u32>,
+        ) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+
+        async fn get_api_key(
+            &self,
+            _id: payments_types::ApiKeyId,
+        ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+            Ok(None)
+        }
+
+        async fn insert_api_key_audit(
+            &self,
+            _entry: &payments_types::ApiKeyAuditEntry,
+        ) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn list_api_key_audit(
+            &self,
+            _id: payments_types::ApiKeyId,
+        ) -> Result<Vec<payments_types::ApiKeyAuditEntry>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn add_api_key_usage(
+            &self,
+            _usage: &payments_types::ApiKeyUsageBucket,
+        ) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn add_api_key_volume(
+            &self,
+            _volume: &payments_types::ApiKeyVolumeBucket,
+        ) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn list_api_key_usage(
+            &self,
+            _id: payments_types::ApiKeyId,
+            _since: DateTime<Utc>,
+        ) -> Result<Vec<payments_types::ApiKeyUsageBucket>, RepoError> {
+            Ok(Vec::new())
+        }
+
+        async fn list_api_key_volume(
+            &self,
+            _id: payments_types::ApiKeyId,
+            _since: DateTime<Utc>,
+        ) -> Result<Vec<payments_types::ApiKeyVolumeBucket>, RepoError> {
+            Ok(Vec::new())
+        }
+
         async fn register_webhook_endpoint(
             &self,
             _url: &str,
             _events: Vec<String>,
+            _timeouts: payments_types::WebhookTimeouts,
+            _signature_algorithm: payments_types::WebhookSignatureAlgorithm,
         ) -> Result<payments_types::WebhookEndpoint, RepoError> {
             unimplemented!("register_webhook_endpoint not implemented in MockRepo")
         }
 
+        async fn register_webhook_endpoints(
+            &self,
+            _endpoints: Vec<payments_types::NewWebhookEndpoint>,
+        ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+            unimplemented!("register_webhook_endpoints not implemented in MockRepo")
+        }
+
         async fn list_webhook_endpoints(
             &self,
+            _mode: Option<Mode>,
         ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
             Ok(vec![])
         }
 
-        async fn create_webhook_event(
+        async fn get_webhook_endpoint(
             &self,
-            _endpoint_id: payments_types::WebhookEndpointId,
-            _event_type: &str,
+            _id: payments_types::WebhookEndpointId,
+        ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+            Ok(None)
+        }
+
+        async fn update_webhook_endpoint_url(
+            &self,
+            _id: payments_types::WebhookEndpointId,
+            _url: &str,
+        ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+            Ok(None)
+        }
+
+        async fn update_webhook_endpoint(
+            &self,
+            _id: payments_types::WebhookEndpointId,
+            _events: Option<Vec<String>>,
+            _is_active: Option<bool>,
+            _signature_algorithm: Option<payments_types::WebhookSignatureAlgorithm>,
+        ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+            Ok(None)
+        }
+
+        async fn rotate_webhook_secret(
+            &self,
+            _id: payments_types::WebhookEndpointId,
+        ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+            Ok(None)
+        }
+
+        async fn delete_webhook_endpoint(
+            &self,
+            _id: payments_types::WebhookEndpointId,
+        ) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+
+        async fn create_webhook_event(
+            &self,
+            _endpoint_id: payments_types::WebhookEndpointId,
+            _event_type: &str,
             _payload: serde_json::Value,
         ) -> Result<payments_types::WebhookEvent, RepoError> {
             // Return a dummy event
@@ -202,184 +989,5670 @@ pub(crate) mod tests {
                 _payload,
             ))
         }
+
+        async fn get_webhook_event(
+            &self,
+            _id: uuid::Uuid,
+        ) -> Result<Option<payments_types::WebhookEvent>, RepoError> {
+            Ok(None)
+        }
+
+        async fn list_dead_lettered_webhooks(
+            &self,
+            _filter: &payments_types::DeadLetterFilter,
+        ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn requeue_dead_lettered_webhooks(
+            &self,
+            _filter: &payments_types::DeadLetterFilter,
+        ) -> Result<u64, RepoError> {
+            Ok(0)
+        }
+
+        async fn count_webhook_backlog(&self) -> Result<i64, RepoError> {
+            Ok(0)
+        }
+
+        async fn storage_counts(&self) -> Result<StorageCounts, RepoError> {
+            Ok(StorageCounts {
+                accounts: self.accounts.lock().unwrap().len() as i64,
+                transactions: self.transactions.lock().unwrap().len() as i64,
+                webhook_events: 0,
+            })
+        }
+
+        async fn ping(&self) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn list_webhook_events_after(
+            &self,
+            _endpoint_id: payments_types::WebhookEndpointId,
+            _after_sequence: i64,
+            _limit: usize,
+        ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn list_webhook_deliveries(
+            &self,
+            _endpoint_id: payments_types::WebhookEndpointId,
+            _filter: &payments_types::WebhookDeliveryFilter,
+            _limit: usize,
+        ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn requeue_webhook_event(&self, _id: uuid::Uuid) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+
+        async fn append_event(&self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+            let mut log = self.event_log.lock().unwrap();
+            let sequence = log.len() as i64 + 1;
+            log.push(LoggedEvent {
+                sequence,
+                event_type: event.event_type().to_string(),
+                occurred_at: event.occurred_at(),
+                recorded_at: Utc::now(),
+                data: event.payload(),
+                mode,
+            });
+            Ok(())
+        }
+
+        async fn list_logged_events(
+            &self,
+            after: i64,
+            limit: usize,
+            mode: Option<Mode>,
+        ) -> Result<Vec<LoggedEvent>, RepoError> {
+            Ok(self
+                .event_log
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|e| e.sequence > after && mode.is_none_or(|mode| e.mode == mode))
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        async fn create_fee_schedule(
+            &self,
+            name: &str,
+            tiers: &[FeeTier],
+        ) -> Result<FeeSchedule, RepoError> {
+            let schedule = FeeSchedule {
+                id: FeeScheduleId::new(),
+                name: name.to_string(),
+                tiers: tiers.to_vec(),
+                created_at: Utc::now(),
+            };
+            self.fee_schedules.lock().unwrap().push(schedule.clone());
+            Ok(schedule)
+        }
+
+        async fn get_fee_schedule(
+            &self,
+            id: FeeScheduleId,
+        ) -> Result<Option<FeeSchedule>, RepoError> {
+            let schedules = self.fee_schedules.lock().unwrap();
+            Ok(schedules.iter().find(|s| s.id == id).cloned())
+        }
+
+        async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError> {
+            Ok(self.fee_schedules.lock().unwrap().clone())
+        }
+
+        async fn assign_fee_schedule(
+            &self,
+            account_id: AccountId,
+            fee_schedule_id: FeeScheduleId,
+            effective_from: DateTime<Utc>,
+        ) -> Result<FeeAssignment, RepoError> {
+            let assignment = FeeAssignment {
+                account_id,
+                fee_schedule_id,
+                effective_from,
+                assigned_at: Utc::now(),
+            };
+            self.fee_assignments
+                .lock()
+                .unwrap()
+                .push(assignment.clone());
+            Ok(assignment)
+        }
+
+        async fn list_fee_assignments(
+            &self,
+            account_id: AccountId,
+        ) -> Result<Vec<FeeAssignment>, RepoError> {
+            let assignments = self.fee_assignments.lock().unwrap();
+            Ok(assignments
+                .iter()
+                .filter(|a| a.account_id == account_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn create_settlement_batch(
+            &self,
+            cutoff: DateTime<Utc>,
+            mode: Mode,
+        ) -> Result<Option<SettlementBatch>, RepoError> {
+            let mut batched = self.settlement_payouts.lock().unwrap();
+            let payouts: Vec<Transaction> = self
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| {
+                    t.transaction_type == TransactionType::Withdrawal
+                        && t.created_at <= cutoff
+                        && !batched.contains_key(&t.id)
+                })
+                .cloned()
+                .collect();
+            if payouts.is_empty() {
+                return Ok(None);
+            }
+
+            let now = Utc::now();
+            let batch = SettlementBatch {
+                id: SettlementBatchId::new(),
+                status: SettlementBatchStatus::Pending,
+                cutoff,
+                payout_count: payouts.len() as i64,
+                totals: SettlementBatch::totals_for(&payouts),
+                created_at: now,
+                updated_at: now,
+                mode,
+            };
+            for payout in &payouts {
+                batched.insert(payout.id, batch.id);
+            }
+            self.settlement_batches.lock().unwrap().push(batch.clone());
+            Ok(Some(batch))
+        }
+
+        async fn get_settlement_batch(
+            &self,
+            id: SettlementBatchId,
+        ) -> Result<Option<SettlementBatch>, RepoError> {
+            let batches = self.settlement_batches.lock().unwrap();
+            Ok(batches.iter().find(|b| b.id == id).cloned())
+        }
+
+        async fn list_settlement_batches(
+            &self,
+            mode: Option<Mode>,
+        ) -> Result<Vec<SettlementBatch>, RepoError> {
+            Ok(self
+                .settlement_batches
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|b| mode.is_none_or(|mode| b.mode == mode))
+                .cloned()
+                .collect())
+        }
+
+        async fn update_settlement_batch_status(
+            &self,
+            id: SettlementBatchId,
+            from: SettlementBatchStatus,
+            to: SettlementBatchStatus,
+        ) -> Result<Option<SettlementBatch>, RepoError> {
+            let mut batches = self.settlement_batches.lock().unwrap();
+            let Some(batch) = batches.iter_mut().find(|b| b.id == id && b.status == from) else {
+                return Ok(None);
+            };
+            batch.status = to;
+            batch.updated_at = Utc::now();
+            if to == SettlementBatchStatus::Cancelled {
+                self.settlement_payouts
+                    .lock()
+                    .unwrap()
+                    .retain(|_, batch_id| *batch_id != id);
+            }
+            Ok(Some(batch.clone()))
+        }
+
+        async fn list_settlement_batch_payouts(
+            &self,
+            id: SettlementBatchId,
+        ) -> Result<Vec<Transaction>, RepoError> {
+            let batched = self.settlement_payouts.lock().unwrap();
+            Ok(self
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| batched.get(&t.id) == Some(&id))
+                .cloned()
+                .collect())
+        }
+
+        async fn get_statement_email(
+            &self,
+            account_id: AccountId,
+        ) -> Result<Option<String>, RepoError> {
+            Ok(self
+                .statement_emails
+                .lock()
+                .unwrap()
+                .get(&account_id)
+                .cloned())
+        }
+
+        async fn set_statement_email(
+            &self,
+            account_id: AccountId,
+            email: Option<&str>,
+        ) -> Result<(), RepoError> {
+            let mut emails = self.statement_emails.lock().unwrap();
+            match email {
+                Some(email) => emails.insert(account_id, email.to_string()),
+                None => emails.remove(&account_id),
+            };
+            Ok(())
+        }
+
+        async fn insert_statement(
+            &self,
+            statement: &Statement,
+            content: &str,
+        ) -> Result<bool, RepoError> {
+            let mut statements = self.statements.lock().unwrap();
+            if statements.iter().any(|(s, _)| {
+                s.account_id == statement.account_id && s.period_start == statement.period_start
+            }) {
+                return Ok(false);
+            }
+            statements.push((statement.clone(), content.to_string()));
+            Ok(true)
+        }
+
+        async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError> {
+            let statements = self.statements.lock().unwrap();
+            Ok(statements
+                .iter()
+                .find(|(s, _)| s.id == id)
+                .map(|(s, _)| s.clone()))
+        }
+
+        async fn get_statement_content(
+            &self,
+            id: StatementId,
+        ) -> Result<Option<String>, RepoError> {
+            let statements = self.statements.lock().unwrap();
+            Ok(statements
+                .iter()
+                .find(|(s, _)| s.id == id)
+                .map(|(_, content)| content.clone()))
+        }
+
+        async fn list_statements(
+            &self,
+            account_id: AccountId,
+        ) -> Result<Vec<Statement>, RepoError> {
+            let mut statements: Vec<Statement> = self
+                .statements
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(s, _)| s.account_id == account_id)
+                .map(|(s, _)| s.clone())
+                .collect();
+            statements.sort_by_key(|s| std::cmp::Reverse(s.period_start));
+            Ok(statements)
+        }
+
+        async fn insert_export(&self, export: &Export) -> Result<(), RepoError> {
+            self.exports
+                .lock()
+                .unwrap()
+                .insert(export.id, (export.clone(), None));
+            Ok(())
+        }
+
+        async fn finish_export(
+            &self,
+            export: &Export,
+            content: Option<&str>,
+        ) -> Result<(), RepoError> {
+            if let Some(stored) = self.exports.lock().unwrap().get_mut(&export.id) {
+                *stored = (export.clone(), content.map(str::to_string));
+            }
+            Ok(())
+        }
+
+        async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError> {
+            let exports = self.exports.lock().unwrap();
+            Ok(exports.get(&id).map(|(export, _)| export.clone()))
+        }
+
+        async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError> {
+            let exports = self.exports.lock().unwrap();
+            Ok(exports.get(&id).and_then(|(_, content)| content.clone()))
+        }
+
+        async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError> {
+            let mut exports = self.exports.lock().unwrap();
+            let before = exports.len();
+            exports.retain(|_, (export, _)| export.created_at >= cutoff);
+            Ok((before - exports.len()) as u64)
+        }
+
+        async fn insert_scheduled_payment(
+            &self,
+            payment: &ScheduledPayment,
+        ) -> Result<(), RepoError> {
+            self.scheduled_payments
+                .lock()
+                .unwrap()
+                .push(payment.clone());
+            Ok(())
+        }
+
+        async fn get_scheduled_payment(
+            &self,
+            id: ScheduledPaymentId,
+        ) -> Result<Option<ScheduledPayment>, RepoError> {
+            Ok(self
+                .scheduled_payments
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|p| p.id == id)
+                .cloned())
+        }
+
+        async fn list_scheduled_payments(
+            &self,
+            account_id: Option<AccountId>,
+            mode: Option<Mode>,
+        ) -> Result<Vec<ScheduledPayment>, RepoError> {
+            let accounts = self.accounts.lock().unwrap();
+            Ok(self
+                .scheduled_payments
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|p| account_id.is_none_or(|id| p.from_account_id == id))
+                .filter(|p| {
+                    mode.is_none_or(|mode| {
+                        accounts
+                            .get(&p.from_account_id)
+                            .is_some_and(|a| a.mode == mode)
+                    })
+                })
+                .cloned()
+                .collect())
+        }
+
+        async fn list_due_scheduled_payments(
+            &self,
+            now: DateTime<Utc>,
+            limit: usize,
+        ) -> Result<Vec<ScheduledPayment>, RepoError> {
+            let mut due: Vec<ScheduledPayment> = self
+                .scheduled_payments
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|p| p.status == ScheduledPaymentStatus::Active && p.next_run_at <= now)
+                .cloned()
+                .collect();
+            due.sort_by_key(|p| (p.next_run_at, *p.id.as_uuid()));
+            due.truncate(limit);
+            Ok(due)
+        }
+
+        async fn record_scheduled_payment_run(
+            &self,
+            payment: &ScheduledPayment,
+            due_at: DateTime<Utc>,
+        ) -> Result<bool, RepoError> {
+            let mut payments = self.scheduled_payments.lock().unwrap();
+            let Some(stored) = payments
+                .iter_mut()
+                .find(|p| p.id == payment.id && p.next_run_at == due_at)
+            else {
+                return Ok(false);
+            };
+            stored.next_run_at = payment.next_run_at;
+            stored.last_run_at = payment.last_run_at;
+            stored.last_transaction_id = payment.last_transaction_id;
+            stored.last_error = payment.last_error.clone();
+            Ok(true)
+        }
+
+        async fn update_scheduled_payment_status(
+            &self,
+            payment: &ScheduledPayment,
+        ) -> Result<(), RepoError> {
+            if let Some(stored) = self
+                .scheduled_payments
+                .lock()
+                .unwrap()
+                .iter_mut()
+                .find(|p| p.id == payment.id)
+            {
+                stored.status = payment.status;
+                stored.next_run_at = payment.next_run_at;
+            }
+            Ok(())
+        }
+
+        async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError> {
+            let money = DynMoney::new(hold.amount, hold.currency).map_err(RepoError::Domain)?;
+            self.accounts
+                .lock()
+                .unwrap()
+                .get_mut(&hold.account_id)
+                .ok_or(RepoError::NotFound)?
+                .place_hold(money)
+                .map_err(RepoError::Domain)?;
+            self.holds.lock().unwrap().push(hold.clone());
+            Ok(())
+        }
+
+        async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError> {
+            Ok(self
+                .holds
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|h| h.id == id)
+                .cloned())
+        }
+
+        async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError> {
+            let mut holds: Vec<Hold> = self
+                .holds
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|h| h.account_id == account_id)
+                .cloned()
+                .collect();
+            holds.reverse();
+            Ok(holds)
+        }
+
+        async fn list_expired_holds(
+            &self,
+            now: DateTime<Utc>,
+            limit: usize,
+        ) -> Result<Vec<Hold>, RepoError> {
+            Ok(self
+                .holds
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|h| h.is_open() && h.expires_at <= now)
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        async fn capture_hold(
+            &self,
+            id: HoldId,
+            amount: i64,
+            now: DateTime<Utc>,
+        ) -> Result<(Hold, Transaction), RepoError> {
+            let mut holds = self.holds.lock().unwrap();
+            let hold = holds
+                .iter_mut()
+                .find(|h| h.id == id)
+                .ok_or(RepoError::NotFound)?;
+            hold.check_capture(amount, now).map_err(RepoError::Domain)?;
+            let money = DynMoney::new(amount, hold.currency).map_err(RepoError::Domain)?;
+            let mut accounts = self.accounts.lock().unwrap();
+            let account = accounts.get_mut(&hold.account_id).unwrap();
+            account.release_hold(hold.amount);
+            account.withdraw(money).map_err(RepoError::Domain)?;
+            let tx = Transaction::withdrawal(hold.account_id, money, None, hold.reference.clone())
+                .with_purpose_code(hold.purpose_code.clone());
+            self.transactions.lock().unwrap().push(tx.clone());
+            hold.capture(amount, tx.id, now);
+            Ok((hold.clone(), tx))
+        }
+
+        async fn release_hold(
+            &self,
+            id: HoldId,
+            status: HoldStatus,
+            now: DateTime<Utc>,
+        ) -> Result<Hold, RepoError> {
+            let mut holds = self.holds.lock().unwrap();
+            let hold = holds
+                .iter_mut()
+                .find(|h| h.id == id)
+                .ok_or(RepoError::NotFound)?;
+            hold.check_open().map_err(RepoError::Domain)?;
+            hold.release(status, now);
+            self.accounts
+                .lock()
+                .unwrap()
+                .get_mut(&hold.account_id)
+                .unwrap()
+                .release_hold(hold.amount);
+            Ok(hold.clone())
+        }
+
+        async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError> {
+            self.beneficiaries.lock().unwrap().push(beneficiary.clone());
+            Ok(())
+        }
+
+        async fn get_beneficiary(
+            &self,
+            id: BeneficiaryId,
+        ) -> Result<Option<Beneficiary>, RepoError> {
+            Ok(self
+                .beneficiaries
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|b| b.id == id)
+                .cloned())
+        }
+
+        async fn list_beneficiaries(
+            &self,
+            account_id: AccountId,
+        ) -> Result<Vec<Beneficiary>, RepoError> {
+            Ok(self
+                .beneficiaries
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .filter(|b| b.account_id == account_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn record_beneficiary_attempt(
+            &self,
+            beneficiary: &Beneficiary,
+            failed_attempts: u32,
+        ) -> Result<bool, RepoError> {
+            let mut beneficiaries = self.beneficiaries.lock().unwrap();
+            let Some(stored) = beneficiaries.iter_mut().find(|b| {
+                b.id == beneficiary.id
+                    && b.status == BeneficiaryStatus::Pending
+                    && b.failed_attempts == failed_attempts
+            }) else {
+                return Ok(false);
+            };
+            *stored = beneficiary.clone();
+            Ok(true)
+        }
+
+        async fn currency_balances(
+            &self,
+            mode: Option<Mode>,
+        ) -> Result<Vec<CurrencyBalance>, RepoError> {
+            Ok(CurrencyBalance::tally(
+                self.accounts
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .filter(|a| mode.is_none_or(|mode| a.mode == mode)),
+            ))
+        }
+
+        async fn float_positions(
+            &self,
+            _mode: Option<Mode>,
+        ) -> Result<Vec<FloatPosition>, RepoError> {
+            Ok(FloatPosition::tally(
+                self.accounts.lock().unwrap().values(),
+                self.transactions.lock().unwrap().iter(),
+            ))
+        }
+
+        async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError> {
+            self.rate_snapshots.lock().unwrap().push(snapshot.clone());
+            Ok(())
+        }
+
+        async fn rate_snapshot_at(
+            &self,
+            at: DateTime<Utc>,
+        ) -> Result<Option<RateSnapshot>, RepoError> {
+            Ok(self
+                .rate_snapshots
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|s| s.taken_at <= at)
+                .max_by_key(|s| s.taken_at)
+                .cloned())
+        }
+
+        async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError> {
+            self.quotes.lock().unwrap().insert(quote.id, quote.clone());
+            Ok(())
+        }
+
+        async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError> {
+            Ok(self.quotes.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn save_daily_balances(
+            &self,
+            account_id: AccountId,
+            balances: &[DailyBalance],
+        ) -> Result<(), RepoError> {
+            let mut stored = self.daily_balances.lock().unwrap();
+            let stored = stored.entry(account_id).or_default();
+            for daily in balances {
+                stored.insert(daily.date, daily.balance);
+            }
+            Ok(())
+        }
+
+        async fn list_daily_balances(
+            &self,
+            account_id: AccountId,
+            from: NaiveDate,
+            to: NaiveDate,
+        ) -> Result<Vec<DailyBalance>, RepoError> {
+            Ok(self
+                .daily_balances
+                .lock()
+                .unwrap()
+                .get(&account_id)
+                .into_iter()
+                .flat_map(|stored| stored.range(from..=to))
+                .map(|(&date, &balance)| DailyBalance { date, balance })
+                .collect())
+        }
+
+        async fn latest_daily_balance_date(
+            &self,
+            account_id: AccountId,
+        ) -> Result<Option<NaiveDate>, RepoError> {
+            Ok(self
+                .daily_balances
+                .lock()
+                .unwrap()
+                .get(&account_id)
+                .and_then(|stored| stored.keys().next_back().copied()))
+        }
+
+        async fn insert_change_request(&self, _request: &ChangeRequest) -> Result<(), RepoError> {
+            unimplemented!("insert_change_request not implemented in MockRepo")
+        }
+
+        async fn get_change_request(
+            &self,
+            _id: ChangeRequestId,
+        ) -> Result<Option<ChangeRequest>, RepoError> {
+            Ok(None)
+        }
+
+        async fn list_change_requests(
+            &self,
+            _status: Option<ChangeStatus>,
+        ) -> Result<Vec<ChangeRequest>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn decide_change_request(
+            &self,
+            _id: ChangeRequestId,
+            _status: ChangeStatus,
+            _decided_by: payments_types::ApiKeyId,
+            _decided_at: DateTime<Utc>,
+        ) -> Result<Option<ChangeRequest>, RepoError> {
+            Ok(None)
+        }
+
+        async fn get_idempotency_record(
+            &self,
+            endpoint: &str,
+            key: &str,
+        ) -> Result<Option<IdempotencyRecord>, RepoError> {
+            Ok(self
+                .idempotency_records
+                .lock()
+                .unwrap()
+                .get(&(endpoint.to_string(), key.to_string()))
+                .cloned())
+        }
+
+        async fn save_idempotency_record(
+            &self,
+            record: &IdempotencyRecord,
+        ) -> Result<IdempotencyRecord, RepoError> {
+            let mut records = self.idempotency_records.lock().unwrap();
+            let stored = records
+                .entry((record.endpoint.clone(), record.key.clone()))
+                .or_insert_with(|| record.clone());
+            if stored.is_expired_reservation(record.created_at) {
+                *stored = record.clone();
+            }
+            Ok(stored.clone())
+        }
+
+        async fn replace_idempotency_record(
+            &self,
+            record: &IdempotencyRecord,
+        ) -> Result<(), RepoError> {
+            self.idempotency_records.lock().unwrap().insert(
+                (record.endpoint.clone(), record.key.clone()),
+                record.clone(),
+            );
+            Ok(())
+        }
+
+        async fn remove_idempotency_record(
+            &self,
+            endpoint: &str,
+            key: &str,
+        ) -> Result<(), RepoError> {
+            self.idempotency_records
+                .lock()
+                .unwrap()
+                .remove(&(endpoint.to_string(), key.to_string()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_account_success() {
+        let service = PaymentService::new(MockRepo::new());
+
+        let req = CreateAccountRequest {
+            name: "Test Account".to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+
+        let account = service.create_account(req).await.unwrap();
+
+        assert_eq!(account.name, "Test Account");
+        assert_eq!(account.balance.amount(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_account_empty_name_fails() {
+        let service = PaymentService::new(MockRepo::new());
+
+        let req = CreateAccountRequest {
+            name: "   ".to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+
+        let result = service.create_account(req).await;
+
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_deposit_success() {
+        let service = PaymentService::new(MockRepo::new());
+
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+
+        let tx = service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(tx.amount.amount(), 1000);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_replays_the_stored_response() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let deposit = |amount| DepositRequest {
+            account_id: account.id,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: Some("order-1".into()),
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        // MockRepo does not deduplicate by key, so only the service stands
+        // between a retry and a second deposit.
+        let first = service
+            .deposit(deposit(PositiveAmount::new(1000).unwrap()))
+            .await
+            .unwrap();
+        let replay = service
+            .deposit_with_warnings(deposit(PositiveAmount::new(1000).unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(replay.value.id, first.id);
+        assert_eq!(replay.value.created_at, first.created_at);
+        assert_eq!(service.repo().transactions.lock().unwrap().len(), 1);
+
+        let reused = service
+            .deposit(deposit(PositiveAmount::new(2000).unwrap()))
+            .await;
+        assert!(matches!(reused, Err(AppError::IdempotencyKeyConflict(key)) if key == "order-1"));
+        let as_withdrawal = service
+            .withdraw(WithdrawRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: Some("order-1".into()),
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await;
+        assert!(matches!(
+            as_withdrawal,
+            Err(AppError::IdempotencyKeyConflict(_))
+        ));
+    }
+
+    /// Idempotency store keeping responses in memory.
+    #[derive(Default)]
+    struct MemoryIdempotencyStore {
+        records: Mutex<HashMap<(String, String), IdempotencyRecord>>,
+    }
+
+    #[async_trait]
+    impl IdempotencyStore for MemoryIdempotencyStore {
+        async fn get(
+            &self,
+            endpoint: &str,
+            key: &str,
+        ) -> Result<Option<IdempotencyRecord>, RepoError> {
+            let records = self.records.lock().unwrap();
+            Ok(records
+                .get(&(endpoint.to_string(), key.to_string()))
+                .cloned())
+        }
+
+        async fn save(&self, record: &IdempotencyRecord) -> Result<IdempotencyRecord, RepoError> {
+            let mut records = self.records.lock().unwrap();
+            let stored = records
+                .entry((record.endpoint.clone(), record.key.clone()))
+                .or_insert_with(|| record.clone());
+            if stored.is_expired_reservation(record.created_at) {
+                *stored = record.clone();
+            }
+            Ok(stored.clone())
+        }
+
+        async fn replace(&self, record: &IdempotencyRecord) -> Result<(), RepoError> {
+            let mut records = self.records.lock().unwrap();
+            records.insert(
+                (record.endpoint.clone(), record.key.clone()),
+                record.clone(),
+            );
+            Ok(())
+        }
+
+        async fn remove(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+            let mut records = self.records.lock().unwrap();
+            records.remove(&(endpoint.to_string(), key.to_string()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_store_replaces_the_repository() {
+        let store = Arc::new(MemoryIdempotencyStore::default());
+        let service = PaymentService::builder(MockRepo::new())
+            .with_idempotency_store(store.clone())
+            .build();
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let deposit = DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: Some("order-1".into()),
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        let first = service.deposit(deposit.clone()).await.unwrap();
+        let replay = service.deposit(deposit).await.unwrap();
+        assert_eq!(replay.id, first.id);
+        assert_eq!(service.repo().transactions.lock().unwrap().len(), 1);
+
+        assert!(
+            service
+                .repo()
+                .idempotency_records
+                .lock()
+                .unwrap()
+                .is_empty()
+        );
+        let stored = service
+            .idempotency_record(PAYMENTS_IDEMPOTENCY_ENDPOINT, "order-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, 200);
+        assert_eq!(store.records.lock().unwrap().len(), 1);
+    }
+
+    fn batch_deposit(account_id: AccountId, amount: i64, key: Option<&str>) -> BatchOperation {
+        BatchOperation::Deposit(DepositRequest {
+            account_id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: key.map(str::to_string),
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+    }
+
+    fn batch_withdraw(account_id: AccountId, amount: i64) -> BatchOperation {
+        BatchOperation::Withdraw(WithdrawRequest {
+            account_id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+    }
+
+    fn batch_transfer(from: AccountId, to: AccountId, amount: i64) -> BatchOperation {
+        BatchOperation::Transfer(TransferRequest {
+            from_account_id: from,
+            to_account_id: to,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+    }
+
+    async fn funded<R: TransactionRepository>(
+        service: &PaymentService<R>,
+        name: &str,
+        amount: i64,
+    ) -> AccountId {
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: name.to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        if amount > 0 {
+            service
+                .deposit(DepositRequest {
+                    account_id: account.id,
+                    amount: PositiveAmount::new(amount).unwrap(),
+                    currency: CurrencyCode::USD,
+                    idempotency_key: None,
+                    reference: None,
+                    counterparty: None,
+                    metadata: Default::default(),
+                    encrypted_memo: None,
+                })
+                .await
+                .unwrap();
+        }
+        account.id
+    }
+
+    #[tokio::test]
+    async fn test_atomic_batch_makes_nothing_when_one_operation_fails() {
+        let service = PaymentService::new(MockRepo::new());
+        let payer = funded(&service, "Payer", 1000).await;
+        let payee = funded(&service, "Payee", 0).await;
+        let made_before = service.repo().transactions.lock().unwrap().len();
+
+        // Passes the service's checks but overdraws in the repository.
+        let outcomes = service
+            .batch(BatchRequest {
+                mode: BatchMode::Atomic,
+                operations: vec![
+                    batch_transfer(payer, payee, 600),
+                    batch_withdraw(payer, 600),
+                ],
+            })
+            .await
+            .unwrap();
+        assert!(matches!(outcomes[0], BatchOutcome::RolledBack));
+        assert!(matches!(outcomes[1], BatchOutcome::Failed(_)));
+        assert_eq!(
+            service.repo().transactions.lock().unwrap().len(),
+            made_before
+        );
+        assert_eq!(
+            service
+                .get_account(&actor(None), payer)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            1000
+        );
+
+        // Refused by the service's checks: the repository is never asked.
+        let outcomes = service
+            .batch(BatchRequest {
+                mode: BatchMode::Atomic,
+                operations: vec![
+                    batch_deposit(payee, 100, None),
+                    batch_transfer(payee, payee, 100),
+                ],
+            })
+            .await
+            .unwrap();
+        assert!(matches!(outcomes[0], BatchOutcome::RolledBack));
+        assert!(matches!(
+            &outcomes[1],
+            BatchOutcome::Failed(AppError::BadRequest(_))
+        ));
+        assert_eq!(
+            service
+                .get_account(&actor(None), payee)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_atomic_batch_debits_share_the_daily_debit_limit() {
+        let service = PaymentService::new(MockRepo::new());
+        let payer = funded(&service, "Payer", 1000).await;
+        let payee = funded(&service, "Payee", 0).await;
+        service
+            .set_account_limits(
+                &actor(None),
+                payer,
+                AccountLimits {
+                    daily_debit_limit: Some(500),
+                    ..AccountLimits::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // Each fits the limit on its own; together they do not.
+        let outcomes = service
+            .batch(BatchRequest {
+                mode: BatchMode::Atomic,
+                operations: vec![
+                    batch_transfer(payer, payee, 300),
+                    batch_withdraw(payer, 300),
+                ],
+            })
+            .await
+            .unwrap();
+        assert!(matches!(outcomes[0], BatchOutcome::RolledBack));
+        assert!(matches!(
+            outcomes[1],
+            BatchOutcome::Failed(AppError::DailyDebitLimitExceeded {
+                spent: 300,
+                requested: 300,
+                max: 500
+            })
+        ));
+        assert_eq!(
+            service
+                .get_account(&actor(None), payer)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            1000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_atomic_batch_payments_share_the_hourly_limit() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_limits(AmountLimits {
+                max_transactions_per_hour: Some(2),
+                ..AmountLimits::default()
+            })
+            .build();
+        let account = funded(&service, "Payee", 0).await;
+
+        // Each fits the limit on its own; the third does not once the first
+        // two are staged.
+        let outcomes = service
+            .batch(BatchRequest {
+                mode: BatchMode::Atomic,
+                operations: vec![
+                    batch_deposit(account, 100, None),
+                    batch_deposit(account, 100, None),
+                    batch_deposit(account, 100, None),
+                ],
+            })
+            .await
+            .unwrap();
+        assert!(matches!(outcomes[0], BatchOutcome::RolledBack));
+        assert!(matches!(
+            outcomes[2],
+            BatchOutcome::Failed(AppError::HourlyTransactionLimitExceeded { count: 2, max: 2 })
+        ));
+        assert_eq!(
+            service
+                .get_account(&actor(None), account)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_best_effort_batch_carries_on_past_failures() {
+        let service = PaymentService::new(MockRepo::new());
+        let payer = funded(&service, "Payer", 1000).await;
+        let payee = funded(&service, "Payee", 0).await;
+
+        let outcomes = service
+            .batch(BatchRequest {
+                mode: BatchMode::BestEffort,
+                operations: vec![
+                    batch_transfer(payer, payee, 600),
+                    batch_withdraw(payer, 600),
+                    batch_deposit(payee, 50, None),
+                ],
+            })
+            .await
+            .unwrap();
+        assert!(matches!(outcomes[0], BatchOutcome::Succeeded(_)));
+        assert!(matches!(outcomes[1], BatchOutcome::Failed(_)));
+        assert!(matches!(outcomes[2], BatchOutcome::Succeeded(_)));
+        assert_eq!(
+            service
+                .get_account(&actor(None), payer)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            400
+        );
+        assert_eq!(
+            service
+                .get_account(&actor(None), payee)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            650
+        );
+    }
+
+    #[tokio::test]
+    async fn test_atomic_batch_replays_operations_by_idempotency_key() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = funded(&service, "Payroll", 0).await;
+        let batch = || BatchRequest {
+            mode: BatchMode::Atomic,
+            operations: vec![
+                batch_deposit(account, 100, Some("run-1-a")),
+                batch_deposit(account, 200, Some("run-1-b")),
+            ],
+        };
+
+        let first = service.batch(batch()).await.unwrap();
+        let retried = service.batch(batch()).await.unwrap();
+        for (first, retried) in first.iter().zip(&retried) {
+            let (BatchOutcome::Succeeded(first), BatchOutcome::Succeeded(retried)) =
+                (first, retried)
+            else {
+                panic!("expected both runs to succeed");
+            };
+            assert_eq!(retried.value.id, first.value.id);
+        }
+        assert_eq!(
+            service
+                .get_account(&actor(None), account)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            300
+        );
+
+        let repeated_key = service
+            .batch(BatchRequest {
+                mode: BatchMode::BestEffort,
+                operations: vec![
+                    batch_deposit(account, 100, Some("run-2")),
+                    batch_deposit(account, 100, Some("run-2")),
+                ],
+            })
+            .await;
+        assert!(matches!(repeated_key, Err(AppError::BadRequest(_))));
+        let empty = service
+            .batch(BatchRequest {
+                mode: BatchMode::Atomic,
+                operations: Vec::new(),
+            })
+            .await;
+        assert!(matches!(empty, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failed_payment_can_be_retried_under_its_key() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let withdrawal = WithdrawRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(500).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: Some("payout-1".into()),
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        let rejected = service.withdraw(withdrawal.clone()).await;
+        assert!(matches!(rejected, Err(AppError::InsufficientFunds { .. })));
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(500).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let made = service.withdraw(withdrawal).await.unwrap();
+        assert_eq!(made.amount.amount(), 500);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_to_same_account_fails() {
+        let service = PaymentService::new(MockRepo::new());
+
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+
+        let result = service
+            .transfer(TransferRequest {
+                from_account_id: account.id,
+                to_account_id: account.id,
+                amount: PositiveAmount::new(100).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                purpose_code: None,
+                convert_currency: false,
+                quote_id: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_account_not_found() {
+        let service = PaymentService::new(MockRepo::new());
+
+        let result = service.get_account(&actor(None), AccountId::new()).await;
+
+        assert!(matches!(result, Err(AppError::AccountNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_transactions() {
+        let service = PaymentService::new(MockRepo::new());
+
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+
+        let page = service
+            .list_transactions(&actor(None), account.id, TransactionListQuery::default())
+            .await
+            .unwrap();
+
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_list_transactions_pages_and_filters() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        for amount in [100, 200, 300, 400, 500] {
+            service
+                .deposit(DepositRequest {
+                    account_id: account.id,
+                    amount: PositiveAmount::new(amount).unwrap(),
+                    currency: CurrencyCode::USD,
+                    idempotency_key: None,
+                    reference: None,
+                    counterparty: None,
+                    metadata: Default::default(),
+                    encrypted_memo: None,
+                })
+                .await
+                .unwrap();
+        }
+        service
+            .withdraw(WithdrawRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(250).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+
+        // Walk the deposits two at a time.
+        let mut query = TransactionListQuery {
+            limit: Some(2),
+            transaction_type: Some(TransactionType::Deposit),
+            ..Default::default()
+        };
+        let mut seen = Vec::new();
+        loop {
+            let page = service
+                .list_transactions(&actor(None), account.id, query.clone())
+                .await
+                .unwrap();
+            assert!(page.data.len() <= 2);
+            seen.extend(page.data);
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(seen.len(), 5);
+        assert!(seen.windows(2).all(
+            |w| (w[0].created_at, w[0].id.into_uuid()) > (w[1].created_at, w[1].id.into_uuid())
+        ));
+        assert!(
+            seen.iter()
+                .all(|tx| tx.transaction_type == TransactionType::Deposit)
+        );
+
+        let page = service
+            .list_transactions(
+                &actor(None),
+                account.id,
+                TransactionListQuery {
+                    min_amount: Some(250),
+                    max_amount: Some(400),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let mut amounts: Vec<i64> = page.data.iter().map(|tx| tx.amount.amount()).collect();
+        amounts.sort();
+        assert_eq!(amounts, vec![250, 300, 400]);
+
+        for query in [
+            TransactionListQuery {
+                cursor: Some("bogus".into()),
+                ..Default::default()
+            },
+            TransactionListQuery {
+                min_amount: Some(500),
+                max_amount: Some(100),
+                ..Default::default()
+            },
+            TransactionListQuery {
+                from: Some(Utc::now()),
+                to: Some(Utc::now() - Duration::hours(1)),
+                ..Default::default()
+            },
+        ] {
+            let result = service
+                .list_transactions(&actor(None), account.id, query)
+                .await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_transactions_across_accounts() {
+        let service = PaymentService::new(MockRepo::new());
+        let mut accounts = Vec::new();
+        for name in ["Alice", "Bob"] {
+            let account = service
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            accounts.push(account);
+        }
+        for (account, amount, reference) in [
+            (&accounts[0], 100, "INV-001"),
+            (&accounts[1], 200, "inv-002"),
+            (&accounts[1], 300, "payroll"),
+        ] {
+            service
+                .deposit(DepositRequest {
+                    account_id: account.id,
+                    amount: PositiveAmount::new(amount).unwrap(),
+                    currency: CurrencyCode::USD,
+                    idempotency_key: None,
+                    reference: Some(reference.to_string()),
+                    counterparty: None,
+                    metadata: Default::default(),
+                    encrypted_memo: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let page = service
+            .search_transactions(
+                &actor(None),
+                TransactionSearchQuery {
+                    reference: Some("  Inv ".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let mut amounts: Vec<i64> = page.data.iter().map(|tx| tx.amount.amount()).collect();
+        amounts.sort();
+        assert_eq!(amounts, vec![100, 200]);
+        assert_eq!(page.next_cursor, None);
+
+        let page = service
+            .search_transactions(
+                &actor(None),
+                TransactionSearchQuery {
+                    account: Some(accounts[1].id.into()),
+                    limit: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.data.len(), 1);
+        assert!(page.next_cursor.is_some());
+
+        // A search must narrow the ledger down somehow.
+        for query in [
+            TransactionSearchQuery::default(),
+            TransactionSearchQuery {
+                reference: Some("   ".into()),
+                limit: Some(10),
+                ..Default::default()
+            },
+            TransactionSearchQuery {
+                min_amount: Some(500),
+                max_amount: Some(100),
+                ..Default::default()
+            },
+        ] {
+            let result = service.search_transactions(&actor(None), query).await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_is_validated_and_searchable() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let deposit = |metadata: HashMap<String, String>| DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata,
+            encrypted_memo: None,
+        };
+
+        let tx = service
+            .deposit(deposit(HashMap::from([(
+                "order_id".to_string(),
+                "ord_1042".to_string(),
+            )])))
+            .await
+            .unwrap();
+        assert_eq!(tx.metadata["order_id"], "ord_1042");
+
+        let too_many = (0..=MAX_METADATA_KEYS)
+            .map(|i| (format!("key_{}", i), "v".to_string()))
+            .collect();
+        let long_key = HashMap::from([("k".repeat(MAX_METADATA_KEY_LEN + 1), "v".to_string())]);
+        let long_value =
+            HashMap::from([("note".to_string(), "v".repeat(MAX_METADATA_VALUE_LEN + 1))]);
+        let empty_key = HashMap::from([(" ".to_string(), "v".to_string())]);
+        for metadata in [too_many, long_key, long_value, empty_key] {
+            let result = service.deposit(deposit(metadata)).await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+
+        let page = service
+            .search_transactions(
+                &actor(None),
+                TransactionSearchQuery {
+                    metadata_key: Some("order_id".into()),
+                    metadata_value: Some("ord_1042".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(page.data[0].id, tx.id);
+
+        let result = service
+            .search_transactions(
+                &actor(None),
+                TransactionSearchQuery {
+                    metadata_value: Some("ord_1042".into()),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_inbound_payments_are_deduplicated_by_external_reference() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = funded(&service, "Merchant", 0).await;
+        let payment = |amount, external_reference: &str| InboundPaymentRequest {
+            account_id: account,
+            amount,
+            currency: CurrencyCode::USD,
+            external_reference: external_reference.to_string(),
+            reference: Some("INV-7".to_string()),
+            counterparty: Some(Counterparty {
+                name: "ACME Ltd".to_string(),
+                external_id: Some("DE89370400440532013000".to_string()),
+            }),
+        };
+
+        let first = service
+            .receive_inbound_payment(payment(PositiveAmount::new(250).unwrap(), "SEPA-1"))
+            .await
+            .unwrap();
+        assert!(!first.duplicate);
+        assert_eq!(first.transaction.transaction_type, TransactionType::Deposit);
+        assert_eq!(
+            first.transaction.idempotency_key.as_deref(),
+            Some("inbound:SEPA-1")
+        );
+
+        let again = service
+            .receive_inbound_payment(payment(PositiveAmount::new(250).unwrap(), " SEPA-1 "))
+            .await
+            .unwrap();
+        assert!(again.duplicate);
+        assert_eq!(again.transaction.id, first.transaction.id);
+        assert_eq!(
+            service
+                .get_account(&actor(None), account)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            250
+        );
+
+        assert!(matches!(
+            service
+                .receive_inbound_payment(payment(PositiveAmount::new(300).unwrap(), "SEPA-1"))
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+        for reference in ["  ".to_string(), "X".repeat(MAX_EXTERNAL_REFERENCE_LEN + 1)] {
+            assert!(matches!(
+                service
+                    .receive_inbound_payment(payment(PositiveAmount::new(250).unwrap(), &reference))
+                    .await,
+                Err(AppError::BadRequest(_))
+            ));
+        }
+    }
+
+    /// Adds Globex as a beneficiary of `account` and confirms its
+    /// micro-deposits.
+    async fn verified_beneficiary(
+        service: &PaymentService<MockRepo>,
+        account: AccountId,
+    ) -> Beneficiary {
+        let beneficiary = service
+            .create_beneficiary(
+                &actor(None),
+                CreateBeneficiaryRequest {
+                    account_id: account,
+                    name: "Globex".to_string(),
+                    external_id: "GB33BUKB20201555555555".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        let [first, second] = beneficiary.micro_deposits;
+        service
+            .verify_beneficiary(
+                &actor(None),
+                beneficiary.id,
+                VerifyBeneficiaryRequest {
+                    amounts: vec![second, first],
+                },
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_beneficiary_micro_deposit_verification() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = funded(&service, "Payer", 0).await;
+        let create = |name: &str, external_id: &str| CreateBeneficiaryRequest {
+            account_id: account,
+            name: name.to_string(),
+            external_id: external_id.to_string(),
+        };
+
+        let beneficiary = service
+            .create_beneficiary(&actor(None), create(" Globex ", "GB33BUKB20201555555555"))
+            .await
+            .unwrap();
+        assert_eq!(beneficiary.name, "Globex");
+        assert_eq!(beneficiary.currency, CurrencyCode::USD);
+        assert_eq!(beneficiary.status, BeneficiaryStatus::Pending);
+        assert!(
+            beneficiary
+                .micro_deposits
+                .iter()
+                .all(|amount| (1..=MAX_MICRO_DEPOSIT).contains(amount))
+        );
+        let [first, second] = beneficiary.micro_deposits;
+        let admin = actor(None);
+        let verify = |amounts: Vec<i64>| {
+            service.verify_beneficiary(&admin, beneficiary.id, VerifyBeneficiaryRequest { amounts })
+        };
+
+        assert!(matches!(
+            verify(vec![first]).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            verify(vec![first + 100, second]).await,
+            Err(AppError::BadRequest(_))
+        ));
+        let stored = service
+            .get_beneficiary(&actor(None), beneficiary.id)
+            .await
+            .unwrap();
+        assert_eq!(stored.failed_attempts, 1);
+        let verified = verify(vec![second, first]).await.unwrap();
+        assert!(verified.is_verified());
+        assert!(verified.verified_at.is_some());
+        assert!(matches!(
+            verify(vec![first, second]).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        // Too many wrong amounts fail the beneficiary for good.
+        let guessed = service
+            .create_beneficiary(&actor(None), create("Initech", "12345678"))
+            .await
+            .unwrap();
+        for _ in 0..MAX_VERIFICATION_ATTEMPTS {
+            assert!(matches!(
+                service
+                    .verify_beneficiary(
+                        &actor(None),
+                        guessed.id,
+                        VerifyBeneficiaryRequest {
+                            amounts: vec![0, 0]
+                        }
+                    )
+                    .await,
+                Err(AppError::BadRequest(_))
+            ));
+        }
+        let failed = service
+            .get_beneficiary(&actor(None), guessed.id)
+            .await
+            .unwrap();
+        assert_eq!(failed.status, BeneficiaryStatus::Failed);
+        assert!(matches!(
+            service
+                .verify_beneficiary(
+                    &actor(None),
+                    guessed.id,
+                    VerifyBeneficiaryRequest {
+                        amounts: guessed.micro_deposits.to_vec()
+                    }
+                )
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let listed = service
+            .list_beneficiaries(&actor(None), account)
+            .await
+            .unwrap();
+        let ids: Vec<_> = listed.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![guessed.id, beneficiary.id]);
+        for (name, external_id) in [("  ", "12345678"), ("Globex", " ")] {
+            assert!(matches!(
+                service
+                    .create_beneficiary(&actor(None), create(name, external_id))
+                    .await,
+                Err(AppError::BadRequest(_))
+            ));
+        }
+        assert!(
+            service
+                .create_beneficiary(
+                    &actor(None),
+                    CreateBeneficiaryRequest {
+                        account_id: AccountId::new(),
+                        ..create("Globex", "12345678")
+                    }
+                )
+                .await
+                .is_err()
+        );
+        assert!(matches!(
+            service
+                .get_beneficiary(&actor(None), BeneficiaryId::new())
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_payouts_go_through_the_provider() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = funded(&service, "Payer", 1000).await;
+        let withdraw = |counterparty| WithdrawRequest {
+            account_id: account,
+            amount: PositiveAmount::new(400).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: Some("INV-42".to_string()),
+            counterparty,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        let paid = service
+            .withdraw(withdraw(Some(Counterparty {
+                name: "Globex".to_string(),
+                external_id: Some("GB33BUKB20201555555555".to_string()),
+            })))
+            .await
+            .unwrap();
+
+        // Only verified beneficiaries are paid.
+        assert!(matches!(
+            service.send_payout(paid.id).await,
+            Err(AppError::BadRequest(_))
+        ));
+        verified_beneficiary(&service, account).await;
+
+        let payout = service.send_payout(paid.id).await.unwrap();
+        assert_eq!(payout.transaction_id, paid.id);
+        assert_eq!(payout.status, PayoutStatus::Pending);
+        assert_eq!(service.send_payout(paid.id).await.unwrap(), payout);
+        let cancelled = service
+            .cancel_payout(&payout.provider_reference)
+            .await
+            .unwrap();
+        assert_eq!(cancelled.status, PayoutStatus::Cancelled);
+        assert_eq!(
+            service
+                .payout_status(&payout.provider_reference)
+                .await
+                .unwrap(),
+            cancelled
+        );
+        assert!(matches!(
+            service.payout_status("sim_unknown").await,
+            Err(AppError::NotFound(_))
+        ));
+
+        // Only withdrawals with someone to pay can be paid out.
+        let anonymous = service.withdraw(withdraw(None)).await.unwrap();
+        let deposit = service
+            .list_transactions(&actor(None), account, TransactionListQuery::default())
+            .await
+            .unwrap()
+            .data
+            .pop()
+            .unwrap();
+        for id in [anonymous.id, deposit.id] {
+            assert!(matches!(
+                service.send_payout(id).await,
+                Err(AppError::BadRequest(_))
+            ));
+        }
+
+        let bank = PaymentService::builder(MockRepo::new())
+            .with_payout_provider(Arc::new(StubBankPayouts::new("Example Bank")))
+            .build();
+        let account = funded(&bank, "Payer", 1000).await;
+        verified_beneficiary(&bank, account).await;
+        let paid = bank
+            .withdraw(WithdrawRequest {
+                account_id: account,
+                ..withdraw(Some(Counterparty {
+                    name: "Globex".to_string(),
+                    external_id: Some("GB33BUKB20201555555555".to_string()),
+                }))
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            bank.send_payout(paid.id).await,
+            Err(AppError::ServiceUnavailable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_operations_publish_domain_events() {
+        let publisher = Arc::new(BroadcastPublisher::new(16));
+        let mut events = publisher.subscribe();
+        let service = PaymentService::builder(MockRepo::new())
+            .with_event_publisher(publisher)
+            .build();
+
+        let alice = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let bob = service
+            .create_account(CreateAccountRequest {
+                name: "Bob".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        service
+            .deposit(DepositRequest {
+                account_id: alice.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let transfer = service
+            .transfer(TransferRequest {
+                from_account_id: alice.id,
+                to_account_id: bob.id,
+                amount: PositiveAmount::new(400).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                purpose_code: None,
+                convert_currency: false,
+                quote_id: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        // Failed operations publish nothing.
+        service
+            .withdraw(WithdrawRequest {
+                account_id: bob.id,
+                amount: PositiveAmount::new(5000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap_err();
+
+        let mut types = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            types.push(event.event_type());
+            if let DomainEvent::TransferCompleted(tx) = &event {
+                assert_eq!(tx.id, transfer.id);
+            }
+        }
+        assert_eq!(
+            types,
+            vec![
+                "account.created",
+                "account.created",
+                "deposit.success",
+                "transfer.success"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_api_key_publishes_event() {
+        let publisher = Arc::new(BroadcastPublisher::new(4));
+        let mut events = publisher.subscribe();
+        let service = PaymentService::builder(MockRepo::new())
+            .with_event_publisher(publisher)
+            .build();
+
+        let (key, _raw) = service
+            .create_api_key("ci", &ApiKeyScope::ALL, Mode::Live, None)
+            .await
+            .unwrap();
+
+        match events.try_recv().unwrap() {
+            DomainEvent::ApiKeyCreated(published) => assert_eq!(published.id, key.id),
+            other => panic!("unexpected event {:?}", other.event_type()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_log_records_account_and_money_events() {
+        let service = PaymentService::new(MockRepo::new());
+        let alice = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        // Key creations stay out of the log
+        service
+            .create_api_key("ci", &ApiKeyScope::ALL, Mode::Live, None)
+            .await
+            .unwrap();
+        let deposit = service
+            .deposit(DepositRequest {
+                account_id: alice.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        // So do operations that failed
+        service
+            .withdraw(WithdrawRequest {
+                account_id: alice.id,
+                amount: PositiveAmount::new(5000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap_err();
+
+        let page = service
+            .event_log(&actor(None), EventLogQuery::default())
+            .await
+            .unwrap();
+        let logged: Vec<_> = page
+            .events
+            .iter()
+            .map(|e| (e.sequence, e.event_type.as_str()))
+            .collect();
+        assert_eq!(logged, vec![(1, "account.created"), (2, "deposit.success")]);
+        assert_eq!(
+            page.events[1].data["transaction_id"],
+            serde_json::json!(deposit.id)
+        );
+        assert_eq!(page.day, Some(Utc::now().date_naive()));
+        assert_eq!(page.cursor, 2);
+
+        let first = service
+            .event_log(
+                &actor(None),
+                EventLogQuery {
+                    after_sequence: 0,
+                    limit: Some(1),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(first.cursor, 1);
+        let rest = service
+            .event_log(
+                &actor(None),
+                EventLogQuery {
+                    after_sequence: first.cursor,
+                    limit: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(rest.events, page.events[1..]);
+
+        // Caught up: the cursor stays put until something new is logged
+        let caught_up = service
+            .event_log(
+                &actor(None),
+                EventLogQuery {
+                    after_sequence: page.cursor,
+                    limit: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert!(caught_up.events.is_empty());
+        assert_eq!((caught_up.day, caught_up.cursor), (None, 2));
+
+        let result = service
+            .event_log(
+                &actor(None),
+                EventLogQuery {
+                    after_sequence: -1,
+                    limit: None,
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_event_log_pages_stop_at_the_day_boundary() {
+        let logged = |sequence, recorded_at: &str| LoggedEvent {
+            sequence,
+            event_type: "deposit.success".to_string(),
+            occurred_at: recorded_at.parse().unwrap(),
+            recorded_at: recorded_at.parse().unwrap(),
+            data: serde_json::json!({}),
+            mode: Mode::Live,
+        };
+        let page = EventLogPage::new(
+            4,
+            vec![
+                logged(5, "2026-03-01T23:59:58Z"),
+                logged(6, "2026-03-01T23:59:59Z"),
+                logged(7, "2026-03-02T00:00:00Z"),
+            ],
+        );
+        assert_eq!(page.day, NaiveDate::from_ymd_opt(2026, 3, 1));
+        assert_eq!(page.events.len(), 2);
+        assert_eq!(page.cursor, 6);
+    }
+
+    #[tokio::test]
+    async fn test_create_api_key_normalizes_scopes() {
+        let service = PaymentService::new(MockRepo::new());
+
+        let (key, _raw) = service
+            .create_api_key(
+                "reporting",
+                &[
+                    ApiKeyScope::TransactionsRead,
+                    ApiKeyScope::AccountsRead,
+                    ApiKeyScope::TransactionsRead,
+                ],
+                Mode::Live,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            key.scopes,
+            vec![ApiKeyScope::AccountsRead, ApiKeyScope::TransactionsRead]
+        );
+
+        let result = service
+            .create_api_key("nothing", &[], Mode::Live, None)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_spending_rules_gate_outgoing_payments() {
+        let service = PaymentService::new(MockRepo::new());
+        let mut ids = Vec::new();
+        for name in ["Sub", "Vendor"] {
+            let account = service
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        let (sub, vendor) = (ids[0], ids[1]);
+        service
+            .deposit(DepositRequest {
+                account_id: sub,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+
+        let rules = service
+            .set_spending_rules(
+                &actor(None),
+                sub,
+                SpendingRules {
+                    allowed: vec!["suppliers".into()],
+                    denied: vec![],
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(rules.allowed, vec!["SUPPLIERS"]);
+
+        let transfer = |purpose_code: Option<&str>| TransferRequest {
+            from_account_id: sub,
+            to_account_id: vendor,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: purpose_code.map(String::from),
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        let tx = service.transfer(transfer(Some("Suppliers"))).await.unwrap();
+        assert_eq!(tx.purpose_code.as_deref(), Some("SUPPLIERS"));
+
+        for purpose in [None, Some("PAYROLL")] {
+            let result = service.transfer(transfer(purpose)).await;
+            assert!(matches!(result, Err(AppError::SpendingRuleViolation(_))));
+        }
+        let result = service.transfer(transfer(Some("not valid!"))).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        // Rules only restrict the account they are set on.
+        let result = service
+            .withdraw(WithdrawRequest {
+                account_id: vendor,
+                amount: PositiveAmount::new(50).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_set_spending_rules_rejects_bad_codes_and_unknown_accounts() {
+        let service = PaymentService::new(MockRepo::new());
+        let bad = SpendingRules {
+            allowed: vec!["".into()],
+            denied: vec![],
+        };
+        let result = service
+            .set_spending_rules(&actor(None), AccountId::new(), bad)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let result = service
+            .set_spending_rules(&actor(None), AccountId::new(), SpendingRules::default())
+            .await;
+        assert!(matches!(result, Err(AppError::AccountNotFound(_))));
+    }
+
+    struct FlatRates;
+
+    #[async_trait]
+    impl ExchangeRateProvider for FlatRates {
+        async fn get_rate(&self, _: CurrencyCode, _: CurrencyCode) -> Result<f64, ExchangeError> {
+            Ok(1.0)
+        }
+
+        async fn convert(
+            &self,
+            amount: i64,
+            _: CurrencyCode,
+            _: CurrencyCode,
+        ) -> Result<i64, ExchangeError> {
+            Ok(amount)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_wires_optional_collaborators() {
+        let frozen = SystemClock.now();
+        let service = PaymentService::builder(MockRepo::new())
+            .with_clock(Arc::new(FixedClock::new(frozen)))
+            .with_exchange_provider(Arc::new(FlatRates))
+            .build();
+
+        assert_eq!(service.clock().now(), frozen);
+        let rates = service.exchange_provider().expect("provider is set");
+        assert_eq!(
+            rates
+                .convert(250, CurrencyCode::USD, CurrencyCode::EUR)
+                .await
+                .unwrap(),
+            250
+        );
+
+        assert!(
+            PaymentService::new(MockRepo::new())
+                .exchange_provider()
+                .is_none()
+        );
+    }
+
+    /// Warns about every payment into an account, naming its balance then.
+    struct EveryDeposit;
+
+    impl WarningRule for EveryDeposit {
+        fn check(&self, payment: &PaymentCheck) -> Vec<Warning> {
+            payment
+                .destination
+                .iter()
+                .map(|to| Warning::new("seen", format!("balance {}", to.account.balance.amount())))
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warning_rules_annotate_without_blocking() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_warning_rules(vec![Arc::new(EveryDeposit)])
+            .build();
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let deposit = |amount| DepositRequest {
+            account_id: account.id,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        // Rules see the accounts as they were before the payment.
+        let result = service
+            .deposit_with_warnings(deposit(PositiveAmount::new(500).unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(result.value.amount.amount(), 500);
+        assert_eq!(result.warnings, vec![Warning::new("seen", "balance 0")]);
+        let result = service
+            .deposit_with_warnings(deposit(PositiveAmount::new(500).unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(result.warnings[0].message, "balance 500");
+
+        // A payment that fails still fails; warnings never replace errors.
+        assert!(
+            service
+                .deposit_with_warnings(DepositRequest {
+                    currency: CurrencyCode::EUR,
+                    ..deposit(PositiveAmount::new(500).unwrap())
+                })
+                .await
+                .is_err()
+        );
+
+        let quiet = PaymentService::builder(MockRepo::new())
+            .with_warning_rules(Vec::new())
+            .build();
+        let account = quiet
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let result = quiet
+            .deposit_with_warnings(DepositRequest {
+                account_id: account.id,
+                ..deposit(PositiveAmount::new(500).unwrap())
+            })
+            .await
+            .unwrap();
+        assert!(result.warnings.is_empty());
+    }
+
+    /// Holds deposits over 1,000 and denies those over 10,000.
+    struct DepositLimits;
+
+    #[async_trait]
+    impl RiskCheck for DepositLimits {
+        async fn assess(&self, payment: &PaymentCheck) -> RiskAssessment {
+            match payment.amount {
+                ..=1_000 => RiskAssessment::allow(),
+                1_001..=10_000 => RiskAssessment::hold("large deposit"),
+                _ => RiskAssessment::deny("deposit too large"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_risk_check_holds_and_denies_payments() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_risk_check(Arc::new(DepositLimits))
+            .build();
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let deposit = |amount| DepositRequest {
+            account_id: account.id,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        let allowed = service
+            .deposit(deposit(PositiveAmount::new(1_000).unwrap()))
+            .await
+            .unwrap();
+        assert!(allowed.risk.is_none());
+
+        let held = service
+            .deposit(deposit(PositiveAmount::new(5_000).unwrap()))
+            .await
+            .unwrap();
+        let risk = held
+            .risk
+            .clone()
+            .expect("held deposits record the decision");
+        assert_eq!(risk.decision, RiskDecision::Hold);
+        assert_eq!(risk.reasons, ["large deposit"]);
+        let stored = service
+            .get_transaction(&actor(None), held.id)
+            .await
+            .unwrap();
+        assert_eq!(stored.risk, Some(risk));
+
+        let denied = service
+            .deposit(deposit(PositiveAmount::new(50_000).unwrap()))
+            .await;
+        assert!(
+            matches!(denied, Err(AppError::RiskDenied(reason)) if reason == "deposit too large")
+        );
+        assert_eq!(
+            service
+                .get_account(&actor(None), account.id)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            6_000
+        );
+    }
+
+    /// Quotes USD -> EUR at 0.5, or fails as if the rate service were down.
+    struct HalfRates {
+        down: bool,
+    }
+
+    #[async_trait]
+    impl ExchangeRateProvider for HalfRates {
+        async fn get_rate(
+            &self,
+            from: CurrencyCode,
+            to: CurrencyCode,
+        ) -> Result<f64, ExchangeError> {
+            match (from, to) {
+                _ if self.down => Err(ExchangeError::ServiceUnavailable("rates down".into())),
+                (CurrencyCode::USD, CurrencyCode::EUR) => Ok(0.5),
+                _ => Err(ExchangeError::RateNotAvailable(from, to)),
+            }
+        }
+
+        async fn convert(
+            &self,
+            amount: i64,
+            from: CurrencyCode,
+            to: CurrencyCode,
+        ) -> Result<i64, ExchangeError> {
+            let rate = self.get_rate(from, to).await?;
+            Ok((amount as f64 * rate).round() as i64)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_convert_currency_transfer_uses_exchange_provider() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_exchange_provider(Arc::new(HalfRates { down: false }))
+            .build();
+        let alice = funded_account(&service, "Alice").await;
+        let bob = service
+            .create_account(CreateAccountRequest {
+                name: "Bob".to_string(),
+                currency: CurrencyCode::EUR,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let transfer = |convert_currency| TransferRequest {
+            from_account_id: alice,
+            to_account_id: bob.id,
+            amount: PositiveAmount::new(400).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        let result = service.transfer(transfer(false)).await;
+        assert!(matches!(result, Err(AppError::CrossCurrencyTransfer)));
+
+        let tx = service.transfer(transfer(true)).await.unwrap();
+        let conversion = tx.conversion.expect("transfer was converted");
+        assert_eq!(conversion.rate, 0.5);
+        assert_eq!(conversion.converted_amount.amount(), 200);
+        assert_eq!(conversion.converted_amount.currency(), CurrencyCode::EUR);
+        assert_eq!(tx.amount.amount(), 400);
+        let bob = service.get_account(&actor(None), bob.id).await.unwrap();
+        assert_eq!(bob.balance.amount(), 200);
+
+        let down = PaymentService::builder(MockRepo::new())
+            .with_exchange_provider(Arc::new(HalfRates { down: true }))
+            .build();
+        let alice = funded_account(&down, "Alice").await;
+        let bob = down
+            .create_account(CreateAccountRequest {
+                name: "Bob".to_string(),
+                currency: CurrencyCode::EUR,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let mut req = transfer(true);
+        req.from_account_id = alice;
+        req.to_account_id = bob.id;
+        let result = down.transfer(req).await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+    }
+
+    /// Quotes USD -> EUR at whatever rate was last set.
+    struct MovingRates(Mutex<f64>);
+
+    #[async_trait]
+    impl ExchangeRateProvider for MovingRates {
+        async fn get_rate(
+            &self,
+            from: CurrencyCode,
+            to: CurrencyCode,
+        ) -> Result<f64, ExchangeError> {
+            match (from, to) {
+                (CurrencyCode::USD, CurrencyCode::EUR) => Ok(*self.0.lock().unwrap()),
+                _ => Err(ExchangeError::RateNotAvailable(from, to)),
+            }
+        }
+
+        async fn convert(
+            &self,
+            amount: i64,
+            from: CurrencyCode,
+            to: CurrencyCode,
+        ) -> Result<i64, ExchangeError> {
+            let rate = self.get_rate(from, to).await?;
+            Ok((amount as f64 * rate).round() as i64)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quoted_transfer_converts_at_the_locked_rate_once() {
+        let clock = Arc::new(FixedClock::new(SystemClock.now()));
+        let rates = Arc::new(MovingRates(Mutex::new(0.5)));
+        let metrics = Arc::new(MetricsRegistry::default());
+        let service = PaymentService::builder(MockRepo::new())
+            .with_clock(clock.clone())
+            .with_exchange_provider(rates.clone())
+            .with_metrics(metrics.clone())
+            .build();
+        let alice = funded_account(&service, "Alice").await;
+        let bob = service
+            .create_account(CreateAccountRequest {
+                name: "Bob".to_string(),
+                currency: CurrencyCode::EUR,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let quote_request = |expires_in_secs| CreateQuoteRequest {
+            from_currency: CurrencyCode::USD,
+            to_currency: CurrencyCode::EUR,
+            amount: PositiveAmount::new(400).unwrap(),
+            expires_in_secs,
+        };
+        let quote = service
+            .create_quote(&actor(None), quote_request(None))
+            .await
+            .unwrap();
+        assert_eq!(quote.converted_amount, 200);
+        assert_eq!(quote.expires_at - quote.created_at, Duration::seconds(60));
+        let transfer = |amount, quote_id| TransferRequest {
+            from_account_id: alice,
+            to_account_id: bob.id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: Some(quote_id),
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        // The rate moves after the quote was issued
+        *rates.0.lock().unwrap() = 0.25;
+        let result = service.transfer(transfer(300, quote.id)).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let tx = service.transfer(transfer(400, quote.id)).await.unwrap();
+        let conversion = tx.conversion.expect("transfer was converted");
+        assert_eq!(conversion.rate, 0.5);
+        assert_eq!(conversion.converted_amount.amount(), 200);
+        let spent = service.get_quote(&actor(None), quote.id).await.unwrap();
+        assert_eq!(spent.transaction_id, Some(tx.id));
+
+        let result = service.transfer(transfer(400, quote.id)).await;
+        assert!(matches!(result, Err(AppError::BadRequest(msg)) if msg.contains("already used")));
+
+        // A test key's quote is neither visible to nor spendable by live keys
+        let test = ActorContext {
+            mode: Mode::Test,
+            ..actor(None)
+        };
+        let sandbox = service
+            .create_quote(&test, quote_request(None))
+            .await
+            .unwrap();
+        assert_eq!(sandbox.mode, Mode::Test);
+        service.get_quote(&test, sandbox.id).await.unwrap();
+        let result = service.get_quote(&actor(None), sandbox.id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        let result = service.transfer(transfer(400, sandbox.id)).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        let expiring = service
+            .create_quote(&actor(None), quote_request(Some(5)))
+            .await
+            .unwrap();
+        assert_eq!(expiring.converted_amount, 100);
+        clock.advance(Duration::seconds(5));
+        let result = service.transfer(transfer(400, expiring.id)).await;
+        assert!(matches!(result, Err(AppError::BadRequest(msg)) if msg.contains("expired")));
+
+        let bob = service.get_account(&actor(None), bob.id).await.unwrap();
+        assert_eq!(bob.balance.amount(), 200);
+        for bad in [Some(0), Some(MAX_QUOTE_TTL_SECS + 1)] {
+            let result = service.create_quote(&actor(None), quote_request(bad)).await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+
+        let pair = [("from", "USD"), ("to", "EUR")];
+        assert_eq!(metrics.counter(QUOTES_ISSUED_TOTAL, &pair), 3);
+        assert_eq!(metrics.counter(QUOTES_EXPIRED_TOTAL, &pair), 1);
+        assert_eq!(metrics.counter(CONVERSIONS_TOTAL, &pair), 1);
+    }
+
+    #[tokio::test]
+    async fn test_amount_caps_reject_before_touching_balances() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_limits(AmountLimits {
+                max_transaction_amount: Some(1_000),
+                max_account_balance: Some(1_500),
+                ..AmountLimits::default()
+            })
+            .build();
+        let alice = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let deposit = |amount| DepositRequest {
+            account_id: alice.id,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        let result = service
+            .deposit(deposit(PositiveAmount::new(i64::MAX).unwrap()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::AmountLimitExceeded { max: 1_000, .. })
+        ));
+
+        service
+            .deposit(deposit(PositiveAmount::new(1_000).unwrap()))
+            .await
+            .unwrap();
+        let result = service
+            .deposit(deposit(PositiveAmount::new(600).unwrap()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::BalanceLimitExceeded {
+                balance: 1_600,
+                max: 1_500
+            })
+        ));
+
+        let account = service.get_account(&actor(None), alice.id).await.unwrap();
+        assert_eq!(account.balance.amount(), 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_account_limits_tighten_global_caps() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_limits(AmountLimits {
+                max_transaction_amount: Some(1_000),
+                max_account_balance: None,
+                ..AmountLimits::default()
+            })
+            .build();
+        let mut ids = Vec::new();
+        for name in ["Alice", "Bob"] {
+            let account = service
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        let (alice, bob) = (ids[0], ids[1]);
+        let deposit = |account_id, amount| DepositRequest {
+            account_id,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        service
+            .deposit(deposit(alice, PositiveAmount::new(1_000).unwrap()))
+            .await
+            .unwrap();
+
+        service
+            .set_account_limits(
+                &actor(None),
+                alice,
+                AccountLimits {
+                    max_transaction_amount: Some(5_000),
+                    max_balance: None,
+                    daily_debit_limit: Some(500),
+                },
+            )
+            .await
+            .unwrap();
+        service
+            .set_account_limits(
+                &actor(None),
+                bob,
+                AccountLimits {
+                    max_balance: Some(300),
+                    ..AccountLimits::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // A looser account limit does not lift the global cap.
+        let result = service
+            .deposit(deposit(alice, PositiveAmount::new(1_001).unwrap()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::AmountLimitExceeded { max: 1_000, .. })
+        ));
+
+        let transfer = |amount| TransferRequest {
+            from_account_id: alice,
+            to_account_id: bob,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        service
+            .transfer(transfer(PositiveAmount::new(300).unwrap()))
+            .await
+            .unwrap();
+        let result = service
+            .transfer(transfer(PositiveAmount::new(1).unwrap()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::BalanceLimitExceeded {
+                balance: 301,
+                max: 300
+            })
+        ));
+
+        // Withdrawals and outgoing transfers share the daily debit limit.
+        let withdraw = |amount| WithdrawRequest {
+            account_id: alice,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        service
+            .withdraw(withdraw(PositiveAmount::new(200).unwrap()))
+            .await
+            .unwrap();
+        let result = service
+            .withdraw(withdraw(PositiveAmount::new(1).unwrap()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::DailyDebitLimitExceeded {
+                spent: 500,
+                requested: 1,
+                max: 500
+            })
+        ));
+
+        service
+            .set_account_limits(&actor(None), alice, AccountLimits::default())
+            .await
+            .unwrap();
+        assert!(
+            service
+                .withdraw(withdraw(PositiveAmount::new(1).unwrap()))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_account_limits_validates_input() {
+        let service = PaymentService::new(MockRepo::new());
+        let result = service
+            .set_account_limits(
+                &actor(None),
+                AccountId::new(),
+                AccountLimits {
+                    max_balance: Some(-1),
+                    ..AccountLimits::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let result = service.account_limits(&actor(None), AccountId::new()).await;
+        assert!(matches!(result, Err(AppError::AccountNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_overdraft_limit_lets_the_balance_go_negative() {
+        let service = PaymentService::new(MockRepo::new());
+        let open = |name: &str, overdraft_limit| CreateAccountRequest {
+            name: name.to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+        assert!(matches!(
+            service.create_account(open("Alice", -1)).await,
+            Err(AppError::BadRequest(_))
+        ));
+        let alice = service.create_account(open("Alice", 0)).await.unwrap();
+        let bob = service.create_account(open("Bob", 0)).await.unwrap();
+        let withdraw = |amount| WithdrawRequest {
+            account_id: alice.id,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        assert!(matches!(
+            service
+                .withdraw(withdraw(PositiveAmount::new(100).unwrap()))
+                .await,
+            Err(AppError::InsufficientFunds { .. })
+        ));
+
+        assert!(matches!(
+            service
+                .set_overdraft_limit(&actor(None), alice.id, -1)
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service
+                .set_overdraft_limit(&actor(None), AccountId::new(), 100)
+                .await,
+            Err(AppError::AccountNotFound(_))
+        ));
+        let updated = service
+            .set_overdraft_limit(&actor(None), alice.id, 100)
+            .await
+            .unwrap();
+        assert_eq!(updated.overdraft_limit, 100);
+        service
+            .withdraw(withdraw(PositiveAmount::new(100).unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .get_account(&actor(None), alice.id)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            -100
+        );
+        assert!(matches!(
+            service
+                .withdraw(withdraw(PositiveAmount::new(1).unwrap()))
+                .await,
+            Err(AppError::InsufficientFunds { .. })
+        ));
+
+        // An overdrawn account cannot be merged away.
+        assert!(matches!(
+            service.merge_account(&actor(None), alice.id, bob.id).await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_memo_keys_are_capped_and_retired() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Ops".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let add = |i: usize| CreateMemoKeyRequest {
+            algorithm: "xchacha20poly1305".to_string(),
+            fingerprint: format!("sha256:{:08}", i),
+        };
+
+        let mut keys = Vec::new();
+        for i in 0..MAX_MEMO_KEYS_PER_ACCOUNT {
+            keys.push(
+                service
+                    .add_memo_key(&actor(None), account.id, add(i))
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert!(matches!(
+            service
+                .add_memo_key(&actor(None), account.id, add(99))
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+        // Retired keys stay listed but no longer count towards the cap.
+        let retired = service
+            .retire_memo_key(&actor(None), account.id, keys[0].id)
+            .await
+            .unwrap();
+        assert!(!retired.is_active());
+        service
+            .add_memo_key(&actor(None), account.id, add(99))
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .list_memo_keys(&actor(None), account.id)
+                .await
+                .unwrap()
+                .len(),
+            MAX_MEMO_KEYS_PER_ACCOUNT + 1
+        );
+        assert_eq!(
+            service
+                .get_memo_key(&actor(None), account.id, keys[1].id)
+                .await
+                .unwrap(),
+            keys[1]
+        );
+
+        assert!(matches!(
+            service
+                .add_memo_key(
+                    &actor(None),
+                    account.id,
+                    CreateMemoKeyRequest {
+                        algorithm: "AES GCM".to_string(),
+                        fingerprint: "sha256:00000000".to_string(),
+                    }
+                )
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+        let other = funded(&service, "Bob", 0).await;
+        assert!(matches!(
+            service.get_memo_key(&actor(None), other, keys[1].id).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            service
+                .retire_memo_key(&actor(None), other, keys[1].id)
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_owners_hold_accounts_and_are_deleted_once_empty() {
+        let service = PaymentService::new(MockRepo::new());
+        let owner = service
+            .create_owner(
+                &actor(None),
+                CreateOwnerRequest {
+                    name: " Acme Ltd ".to_string(),
+                    email: Some("finance@acme.example".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(owner.name, "Acme Ltd");
+        assert_eq!(
+            service.get_owner(&actor(None), owner.id).await.unwrap(),
+            owner
+        );
+        assert!(matches!(
+            service
+                .create_owner(
+                    &actor(None),
+                    CreateOwnerRequest {
+                        name: "  ".to_string(),
+                        email: None,
+                    }
+                )
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let updated = service
+            .update_owner(
+                &actor(None),
+                owner.id,
+                UpdateOwnerRequest {
+                    name: None,
+                    email: Some(String::new()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!((updated.name.as_str(), updated.email), ("Acme Ltd", None));
+
+        let open = |owner_id| CreateAccountRequest {
+            owner_id,
+            name: "Payroll".to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            mode: None,
+        };
+        assert!(matches!(
+            service.create_account(open(OwnerId::new())).await,
+            Err(AppError::NotFound(_))
+        ));
+        let account = service.create_account(open(owner.id)).await.unwrap();
+        assert_eq!(account.owner_id, owner.id);
+        let held = service
+            .list_owner_accounts(owner.id, Mode::Live)
+            .await
+            .unwrap();
+        assert_eq!(
+            held.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![account.id]
+        );
+
+        // Owner-bound keys act on the accounts the owner holds
+        let key =
+            payments_types::ApiKey::new("acme".into(), "hash".into(), None).with_owner(owner.id);
+        let acme = service.actor_for(&key).await.unwrap();
+        assert!(acme.ensure_access(account.id).is_ok());
+        assert_eq!(acme.visible_accounts(), Some(vec![account.id]));
+        // and may read their owner, but only admin keys may change owners
+        assert_eq!(
+            service.get_owner(&acme, owner.id).await.unwrap().id,
+            owner.id
+        );
+        assert!(matches!(
+            service.delete_owner(&acme, owner.id).await,
+            Err(AppError::AccessDenied(_))
+        ));
+
+        assert!(matches!(
+            service.delete_owner(&actor(None), owner.id).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            service.delete_owner(&actor(None), OwnerId::DEFAULT).await,
+            Err(AppError::BadRequest(_))
+        ));
+        let empty = service
+            .create_owner(
+                &actor(None),
+                CreateOwnerRequest {
+                    name: "Globex".to_string(),
+                    email: None,
+                },
+            )
+            .await
+            .unwrap();
+        service.delete_owner(&actor(None), empty.id).await.unwrap();
+        assert!(matches!(
+            service.get_owner(&actor(None), empty.id).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_memo_needs_an_active_key_of_a_party() {
+        let service = PaymentService::new(MockRepo::new());
+        let mut accounts = Vec::new();
+        for name in ["Alice", "Bob", "Carol"] {
+            let account = service
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            accounts.push(account);
+        }
+        let (alice, bob, carol) = (&accounts[0], &accounts[1], &accounts[2]);
+        let key_of = |account_id| {
+            let service = &service;
+            async move {
+                service
+                    .add_memo_key(
+                        &actor(None),
+                        account_id,
+                        CreateMemoKeyRequest {
+                            algorithm: "aes-256-gcm".to_string(),
+                            fingerprint: format!("sha256:{}", account_id),
+                        },
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+        let (alice_key, bob_key, carol_key) = (
+            key_of(alice.id).await,
+            key_of(bob.id).await,
+            key_of(carol.id).await,
+        );
+        let memo = |key: &MemoKey, ciphertext: &str| EncryptedMemo {
+            key_id: key.id,
+            ciphertext: ciphertext.to_string(),
+        };
+        let deposit = |memo| DepositRequest {
+            account_id: alice.id,
+            amount: PositiveAmount::new(1_000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: Some(memo),
+        };
+        let transfer = |memo| TransferRequest {
+            from_account_id: alice.id,
+            to_account_id: bob.id,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            convert_currency: false,
+            quote_id: None,
+            encrypted_memo: Some(memo),
+        };
+
+        let tx = service
+            .deposit(deposit(memo(&alice_key, "3q2+7w==")))
+            .await
+            .unwrap();
+        assert_eq!(tx.encrypted_memo, Some(memo(&alice_key, "3q2+7w==")));
+        // Either side of a transfer may hold the key.
+        let tx = service
+            .transfer(transfer(memo(&bob_key, "AAAA")))
+            .await
+            .unwrap();
+        assert_eq!(tx.encrypted_memo.unwrap().key_id, bob_key.id);
+
+        for rejected in [
+            deposit(memo(&alice_key, "not base64!")),
+            deposit(memo(&bob_key, "AAAA")),
+        ] {
+            assert!(matches!(
+                service.deposit(rejected).await,
+                Err(AppError::BadRequest(_))
+            ));
+        }
+        assert!(matches!(
+            service.transfer(transfer(memo(&carol_key, "AAAA"))).await,
+            Err(AppError::BadRequest(_))
+        ));
+        service
+            .retire_memo_key(&actor(None), alice.id, alice_key.id)
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.deposit(deposit(memo(&alice_key, "AAAA"))).await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_account_aliases_resolve_and_are_capped() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Ops".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let add = |alias: &str| AddAliasRequest {
+            alias: alias.to_string(),
+        };
+
+        let ops = service
+            .add_account_alias(&actor(None), account.id, add("@Ops"))
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .resolve_account(&AccountRef::Alias(ops.alias.clone()))
+                .await
+                .unwrap(),
+            account.id
+        );
+        assert_eq!(
+            service
+                .resolve_account(&AccountRef::Id(account.id))
+                .await
+                .unwrap(),
+            account.id
+        );
+        let unknown = AccountRef::Alias(Alias::parse("@nobody").unwrap());
+        assert!(matches!(
+            service.resolve_account(&unknown).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        for i in 1..MAX_ALIASES_PER_ACCOUNT {
+            service
+                .add_account_alias(&actor(None), account.id, add(&format!("ops-{}", i)))
+                .await
+                .unwrap();
+        }
+        // Re-adding a held alias is fine at the cap; a new one is not.
+        assert!(
+            service
+                .add_account_alias(&actor(None), account.id, add("ops"))
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            service
+                .add_account_alias(&actor(None), account.id, add("ops-extra"))
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service
+                .add_account_alias(&actor(None), account.id, add("not valid"))
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service
+                .add_account_alias(&actor(None), AccountId::new(), add("ghost"))
+                .await,
+            Err(AppError::AccountNotFound(_))
+        ));
+
+        service
+            .remove_account_alias(&actor(None), account.id, "@ops")
+            .await
+            .unwrap();
+        assert!(matches!(
+            service
+                .remove_account_alias(&actor(None), account.id, "@ops")
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        assert_eq!(
+            service
+                .list_account_aliases(&actor(None), account.id)
+                .await
+                .unwrap()
+                .len(),
+            MAX_ALIASES_PER_ACCOUNT - 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fee_schedules_apply_prospectively() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Merchant".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let schedule = |name: &str, tiers| CreateFeeScheduleRequest {
+            name: name.to_string(),
+            tiers,
+        };
+        let standard = service
+            .create_fee_schedule(
+                &actor(None),
+                schedule(
+                    "Standard",
+                    vec![
+                        FeeTier {
+                            up_to: Some(10_000),
+                            flat_fee: 25,
+                            ..FeeTier::default()
+                        },
+                        FeeTier {
+                            percentage_bps: 100,
+                            min_fee: Some(150),
+                            ..FeeTier::default()
+                        },
+                    ],
+                ),
+            )
+            .await
+            .unwrap();
+        let discounted = service
+            .create_fee_schedule(
+                &actor(None),
+                schedule("Discounted", vec![FeeTier::default()]),
+            )
+            .await
+            .unwrap();
+
+        let quote = service
+            .quote_fee(
+                &actor(None),
+                account.id,
+                PositiveAmount::new(5_000).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!((quote.fee, quote.fee_schedule_id), (0, None));
+
+        let assign = |fee_schedule_id, effective_from| AssignFeeScheduleRequest {
+            fee_schedule_id,
+            effective_from,
+        };
+        service
+            .assign_fee_schedule(&actor(None), account.id, assign(standard.id, None))
+            .await
+            .unwrap();
+        let next_week = Some(Utc::now() + Duration::days(7));
+        service
+            .assign_fee_schedule(&actor(None), account.id, assign(discounted.id, next_week))
+            .await
+            .unwrap();
+
+        // The discount is recorded but only applies from next week.
+        let fees = service
+            .account_fees(&actor(None), account.id)
+            .await
+            .unwrap();
+        assert_eq!(fees.assignments.len(), 2);
+        assert_eq!(fees.current.unwrap().fee_schedule_id, standard.id);
+        assert_eq!(
+            service
+                .quote_fee(
+                    &actor(None),
+                    account.id,
+                    PositiveAmount::new(5_000).unwrap()
+                )
+                .await
+                .unwrap()
+                .fee,
+            25
+        );
+        let quote = service
+            .quote_fee(
+                &actor(None),
+                account.id,
+                PositiveAmount::new(50_000).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!((quote.fee, quote.fee_schedule_id), (500, Some(standard.id)));
+
+        let yesterday = Some(Utc::now() - Duration::days(1));
+        let result = service
+            .assign_fee_schedule(&actor(None), account.id, assign(discounted.id, yesterday))
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        let result = service
+            .assign_fee_schedule(&actor(None), account.id, assign(FeeScheduleId::new(), None))
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_fee_schedule_validates_tiers() {
+        let service = PaymentService::new(MockRepo::new());
+        let bounded_only = CreateFeeScheduleRequest {
+            name: "Gappy".to_string(),
+            tiers: vec![FeeTier {
+                up_to: Some(1_000),
+                ..FeeTier::default()
+            }],
+        };
+        let result = service
+            .create_fee_schedule(&actor(None), bounded_only)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let unnamed = CreateFeeScheduleRequest {
+            name: " ".to_string(),
+            tiers: vec![FeeTier::default()],
+        };
+        let result = service.create_fee_schedule(&actor(None), unnamed).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        assert!(service.list_fee_schedules().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_counterparty_is_recorded_and_validated() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let payer = Counterparty {
+            name: "ACME Payroll Ltd".to_string(),
+            external_id: Some("GB33BUKB20201555555555".to_string()),
+        };
+
+        let tx = service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: Some("March salary".to_string()),
+                counterparty: Some(payer.clone()),
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(tx.counterparty, Some(payer));
+
+        let result = service
+            .withdraw(WithdrawRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(100).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: Some(Counterparty {
+                    name: "  ".to_string(),
+                    external_id: None,
+                }),
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_settlement_batch_lifecycle() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_settlement_debtor(SettlementDebtor {
+                name: "Payments Ltd".to_string(),
+                iban: "DE89370400440532013000".to_string(),
+                bic: None,
+            })
+            .build();
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        for amount in [100, 250] {
+            service
+                .withdraw(WithdrawRequest {
+                    account_id: account.id,
+                    amount: PositiveAmount::new(amount).unwrap(),
+                    currency: CurrencyCode::USD,
+                    idempotency_key: None,
+                    reference: Some("Invoice 42".to_string()),
+                    counterparty: Some(Counterparty {
+                        name: "Globex".to_string(),
+                        external_id: Some("GB33BUKB20201555555555".to_string()),
+                    }),
+                    purpose_code: None,
+                    metadata: Default::default(),
+                    encrypted_memo: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let future = CreateSettlementBatchRequest {
+            cutoff: Some(Utc::now() + Duration::hours(1)),
+        };
+        let result = service.create_settlement_batch(&actor(None), future).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let batch = service
+            .create_settlement_batch(&actor(None), CreateSettlementBatchRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(batch.payout_count, 2);
+        assert_eq!(batch.totals[0].amount, 350);
+        let result = service
+            .create_settlement_batch(&actor(None), CreateSettlementBatchRequest::default())
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let result = service
+            .update_settlement_batch_status(&actor(None), batch.id, SettlementBatchStatus::Settled)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let csv = service
+            .export_settlement_batch(&actor(None), batch.id, SettlementExportFormat::Csv)
+            .await
+            .unwrap();
+        assert_eq!(csv.lines().count(), 3);
+        let xml = service
+            .export_settlement_batch(&actor(None), batch.id, SettlementExportFormat::Pain001)
+            .await
+            .unwrap();
+        assert!(xml.contains("<CtrlSum>3.50</CtrlSum>"));
+
+        let cancelled = service
+            .update_settlement_batch_status(
+                &actor(None),
+                batch.id,
+                SettlementBatchStatus::Cancelled,
+            )
+            .await
+            .unwrap();
+        assert_eq!(cancelled.status, SettlementBatchStatus::Cancelled);
+        let result = service
+            .export_settlement_batch(&actor(None), batch.id, SettlementExportFormat::Csv)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        // Cancelling released the payouts into the next batch.
+        let rebatched = service
+            .create_settlement_batch(&actor(None), CreateSettlementBatchRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(rebatched.payout_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_pain001_export_needs_debtor() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(500).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        service
+            .withdraw(WithdrawRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(200).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+
+        let batch = service
+            .create_settlement_batch(&actor(None), CreateSettlementBatchRequest::default())
+            .await
+            .unwrap();
+        let result = service
+            .export_settlement_batch(&actor(None), batch.id, SettlementExportFormat::Pain001)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_export_journal() {
+        let codes: GlAccountCodes = "deposit=1010/2100".parse().unwrap();
+        let service = PaymentService::builder(MockRepo::new())
+            .with_gl_codes(codes)
+            .build();
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1250).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+
+        let today = Utc::now().date_naive();
+        let csv = service
+            .export_journal(&actor(None), JournalExportFormat::Xero, today, today)
+            .await
+            .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].ends_with(",1010,Tax Exempt,12.50"));
+        assert!(lines[2].ends_with(",2100,Tax Exempt,-12.50"));
+
+        let yesterday = today - Duration::days(1);
+        let csv = service
+            .export_journal(
+                &actor(None),
+                JournalExportFormat::Quickbooks,
+                yesterday,
+                yesterday,
+            )
+            .await
+            .unwrap();
+        assert_eq!(csv.lines().count(), 1);
+
+        let result = service
+            .export_journal(&actor(None), JournalExportFormat::Xero, today, yesterday)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        let result = service
+            .export_journal(
+                &actor(None),
+                JournalExportFormat::Xero,
+                today - Duration::days(366),
+                today,
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_exports_render_in_background_and_download_by_link() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_download_links(DownloadLinks::new("https://pay.example.com", "secret"))
+            .build();
+
+        let today = Utc::now().date_naive();
+        let result = service
+            .request_export(
+                &actor(None),
+                ExportRequest::Journal {
+                    format: JournalExportFormat::Xero,
+                    from: today,
+                    to: today - Duration::days(1),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let export = service
+            .request_export(
+                &actor(None),
+                ExportRequest::Journal {
+                    format: JournalExportFormat::Xero,
+                    from: today,
+                    to: today,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(export.status, ExportStatus::Pending);
+        let pending = service.get_export(&actor(None), export.id).await.unwrap();
+        assert_eq!(pending.download_url, None);
+
+        let ready = service.run_export(export.id).await.unwrap();
+        assert_eq!(ready.status, ExportStatus::Ready);
+        let download = service.get_export(&actor(None), export.id).await.unwrap();
+        assert_eq!(download.export, ready);
+        let url = download.download_url.unwrap();
+        let token = url
+            .strip_prefix("https://pay.example.com/api/downloads/")
+            .unwrap();
+        let (downloaded, csv) = service.download_export(token).await.unwrap();
+        assert_eq!(downloaded.id, export.id);
+        assert!(csv.starts_with("*Narration,"));
+
+        let forged = format!("{}0", token);
+        let result = service.download_export(&forged).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failed_exports_record_the_error() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(500).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        service
+            .withdraw(WithdrawRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(200).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let batch = service
+            .create_settlement_batch(&actor(None), CreateSettlementBatchRequest::default())
+            .await
+            .unwrap();
+
+        let result = service
+            .request_export(
+                &actor(None),
+                ExportRequest::SettlementBatch {
+                    batch_id: SettlementBatchId::new(),
+                    format: SettlementExportFormat::Csv,
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        // No debtor is configured, so the pain.001 file cannot be rendered.
+        let export = service
+            .request_export(
+                &actor(None),
+                ExportRequest::SettlementBatch {
+                    batch_id: batch.id,
+                    format: SettlementExportFormat::Pain001,
+                },
+            )
+            .await
+            .unwrap();
+        let failed = service.run_export(export.id).await.unwrap();
+        assert_eq!(failed.status, ExportStatus::Failed);
+        assert!(failed.error.is_some());
+        assert!(failed.completed_at.is_some());
+        let download = service.get_export(&actor(None), export.id).await.unwrap();
+        assert_eq!(download.download_url, None);
+    }
+
+    #[tokio::test]
+    async fn test_exposure_report_uses_current_or_recorded_rates() {
+        let start = SystemClock.now();
+        let clock = Arc::new(FixedClock::new(start));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_clock(clock.clone())
+            .with_exchange_provider(Arc::new(FlatRates))
+            .build();
+        for (currency, amount) in [(CurrencyCode::USD, 1000), (CurrencyCode::EUR, 500)] {
+            let account = service
+                .create_account(CreateAccountRequest {
+                    name: currency.code().to_string(),
+                    currency,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            service
+                .deposit(DepositRequest {
+                    account_id: account.id,
+                    amount: PositiveAmount::new(amount).unwrap(),
+                    currency,
+                    idempotency_key: None,
+                    reference: None,
+                    counterparty: None,
+                    metadata: Default::default(),
+                    encrypted_memo: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let report = service
+            .exposure_report(&actor(None), CurrencyCode::EUR, None)
+            .await
+            .unwrap();
+        assert_eq!(report.base_currency, CurrencyCode::EUR);
+        assert_eq!(report.rates_as_of, start);
+        assert_eq!(report.total, 1500);
+        assert_eq!(report.currencies[0].currency, CurrencyCode::USD);
+        assert_eq!(report.currencies[0].account_count, 1);
+
+        let result = service
+            .exposure_report(&actor(None), CurrencyCode::USD, Some(start))
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        service.record_exchange_rates().await.unwrap();
+        clock.advance(Duration::hours(1));
+        let report = service
+            .exposure_report(
+                &actor(None),
+                CurrencyCode::USD,
+                Some(start + Duration::minutes(30)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(report.rates_as_of, start);
+        assert_eq!(report.total, 1500);
+
+        let result = service
+            .exposure_report(
+                &actor(None),
+                CurrencyCode::USD,
+                Some(clock.now() + Duration::minutes(1)),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_payments_run_when_due() {
+        let start = SystemClock.now();
+        let clock = Arc::new(FixedClock::new(start));
+        let publisher = Arc::new(BroadcastPublisher::new(32));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_clock(clock.clone())
+            .with_event_publisher(publisher.clone())
+            .build();
+        let mut ids = Vec::new();
+        for name in ["Alice", "Bob"] {
+            let account = service
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        service
+            .deposit(DepositRequest {
+                account_id: ids[0],
+                amount: PositiveAmount::new(150).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let mut events = publisher.subscribe();
+
+        let payment = service
+            .create_scheduled_payment(
+                &actor(None),
+                CreateScheduledPaymentRequest {
+                    transaction_type: TransactionType::Transfer,
+                    from_account_id: ids[0],
+                    to_account_id: Some(ids[1]),
+                    amount: PositiveAmount::new(100).unwrap(),
+                    currency: CurrencyCode::USD,
+                    reference: Some("Allowance".into()),
+                    purpose_code: None,
+                    schedule: PaymentSchedule::Interval {
+                        every_seconds: 3600,
+                    },
+                    start_at: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(payment.next_run_at, start);
+
+        // The first run pays; the second is rejected for lack of funds and
+        // recorded as failed rather than retried.
+        assert_eq!(service.run_due_scheduled_payments().await.unwrap(), 1);
+        assert_eq!(service.run_due_scheduled_payments().await.unwrap(), 0);
+        clock.advance(Duration::hours(1));
+        assert_eq!(service.run_due_scheduled_payments().await.unwrap(), 1);
+
+        let bob = service.get_account(&actor(None), ids[1]).await.unwrap();
+        assert_eq!(bob.balance.amount(), 100);
+        let failed = service
+            .get_scheduled_payment(&actor(None), payment.id)
+            .await
+            .unwrap();
+        assert_eq!(failed.next_run_at, start + Duration::hours(2));
+        assert_eq!(failed.last_transaction_id, None);
+        assert!(failed.last_error.is_some());
+
+        let mut outcomes = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            match event {
+                DomainEvent::ScheduledPaymentExecuted(p) => {
+                    outcomes.push(p.last_transaction_id.is_some())
+                }
+                DomainEvent::ScheduledPaymentFailed(p) => outcomes.push(p.last_error.is_none()),
+                _ => {}
+            }
+        }
+        assert_eq!(outcomes, vec![true, false]);
+
+        // Paused payments are skipped, and resuming skips what was missed.
+        service
+            .pause_scheduled_payment(&actor(None), payment.id)
+            .await
+            .unwrap();
+        clock.advance(Duration::hours(5));
+        assert_eq!(service.run_due_scheduled_payments().await.unwrap(), 0);
+        let resumed = service
+            .resume_scheduled_payment(&actor(None), payment.id)
+            .await
+            .unwrap();
+        assert_eq!(resumed.status, ScheduledPaymentStatus::Active);
+        assert_eq!(resumed.next_run_at, start + Duration::hours(7));
+    }
+
+    #[tokio::test]
+    async fn test_create_scheduled_payment_validates_request() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let request = |transaction_type, to_account_id, schedule| CreateScheduledPaymentRequest {
+            transaction_type,
+            from_account_id: account.id,
+            to_account_id,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            schedule,
+            start_at: None,
+        };
+        let daily = PaymentSchedule::Interval {
+            every_seconds: 86400,
+        };
+
+        for req in [
+            request(TransactionType::Deposit, None, daily.clone()),
+            request(TransactionType::Transfer, None, daily.clone()),
+            request(TransactionType::Withdrawal, Some(account.id), daily.clone()),
+            request(
+                TransactionType::Withdrawal,
+                None,
+                PaymentSchedule::Cron {
+                    expression: "0 9 * * *".into(),
+                    timezone: "Nowhere/Special".into(),
+                },
+            ),
+            CreateScheduledPaymentRequest {
+                start_at: Some(Utc::now() - Duration::days(1)),
+                ..request(TransactionType::Withdrawal, None, daily.clone())
+            },
+            CreateScheduledPaymentRequest {
+                currency: CurrencyCode::EUR,
+                ..request(TransactionType::Withdrawal, None, daily.clone())
+            },
+        ] {
+            let result = service.create_scheduled_payment(&actor(None), req).await;
+            assert!(
+                matches!(result, Err(AppError::BadRequest(_))),
+                "{:?}",
+                result
+            );
+        }
+
+        let result = service
+            .create_scheduled_payment(
+                &actor(None),
+                request(TransactionType::Transfer, Some(AccountId::new()), daily),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::AccountNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_holds_authorize_capture_void_and_expire() {
+        let clock = Arc::new(FixedClock::new(SystemClock.now()));
+        let publisher = Arc::new(BroadcastPublisher::new(32));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_clock(clock.clone())
+            .with_event_publisher(publisher.clone())
+            .build();
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let mut events = publisher.subscribe();
+        let authorize = |amount, expires_in_secs| AuthorizeRequest {
+            account_id: account.id,
+            amount,
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            expires_in_secs,
+        };
+
+        for req in [
+            authorize(PositiveAmount::new(100).unwrap(), Some(0)),
+            authorize(
+                PositiveAmount::new(100).unwrap(),
+                Some(MAX_HOLD_TTL_SECS + 1),
+            ),
+            authorize(PositiveAmount::new(1001).unwrap(), None),
+        ] {
+            let result = service.authorize(&actor(None), req).await;
+            assert!(
+                matches!(
+                    result,
+                    Err(AppError::BadRequest(_) | AppError::InsufficientFunds { .. })
+                ),
+                "{:?}",
+                result
+            );
+        }
+
+        let captured = service
+            .authorize(
+                &actor(None),
+                authorize(PositiveAmount::new(600).unwrap(), None),
+            )
+            .await
+            .unwrap();
+        let voided = service
+            .authorize(
+                &actor(None),
+                authorize(PositiveAmount::new(200).unwrap(), Some(60)),
+            )
+            .await
+            .unwrap();
+        let expiring = service
+            .authorize(
+                &actor(None),
+                authorize(PositiveAmount::new(100).unwrap(), Some(60)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            captured.expires_at,
+            clock.now() + Duration::seconds(DEFAULT_HOLD_TTL_SECS as i64)
+        );
+        let alice = service.get_account(&actor(None), account.id).await.unwrap();
+        assert_eq!((alice.balance.amount(), alice.available()), (1000, 100));
+
+        let captured = service
+            .capture_hold(
+                &actor(None),
+                captured.id,
+                CaptureRequest {
+                    amount: Some(PositiveAmount::new(500).unwrap()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(captured.captured_amount, Some(500));
+        assert!(
+            service
+                .capture_hold(&actor(None), captured.id, CaptureRequest::default())
+                .await
+                .is_err()
+        );
+        service.void_hold(&actor(None), voided.id).await.unwrap();
+        assert!(matches!(
+            service.void_hold(&actor(None), HoldId::new()).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        // Expired holds can no longer be captured and are released by the job.
+        clock.advance(Duration::seconds(60));
+        assert!(
+            service
+                .capture_hold(&actor(None), expiring.id, CaptureRequest::default())
+                .await
+                .is_err()
+        );
+        assert_eq!(service.expire_holds().await.unwrap(), 1);
+        assert_eq!(service.expire_holds().await.unwrap(), 0);
+        let alice = service.get_account(&actor(None), account.id).await.unwrap();
+        assert_eq!((alice.balance.amount(), alice.available()), (500, 500));
+        let statuses: Vec<_> = service
+            .list_holds(&actor(None), account.id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|hold| hold.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                HoldStatus::Expired,
+                HoldStatus::Voided,
+                HoldStatus::Captured
+            ]
+        );
+
+        let mut types = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            types.push(event.event_type());
+        }
+        assert_eq!(
+            types,
+            vec![
+                "hold.authorized",
+                "hold.authorized",
+                "hold.authorized",
+                "withdraw.success",
+                "hold.captured",
+                "hold.voided",
+                "hold.expired"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hold_events_are_logged_with_the_hold_change() {
+        let clock = Arc::new(FixedClock::new(SystemClock.now()));
+        let service = PaymentService::builder(CountingRepo::new(MockRepo::new()))
+            .with_clock(clock.clone())
+            .build();
+        let account = funded(&service, "Alice", 1000).await;
+        let authorize = || AuthorizeRequest {
+            account_id: account,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            expires_in_secs: Some(60),
+        };
+        let appends = |calls: &RepoCalls| {
+            let calls = calls.by_operation();
+            (
+                calls.get("unit_of_work.append_event").copied(),
+                calls.get("append_event").copied(),
+            )
+        };
+
+        let (voided, calls) = count_repo_calls(service.authorize(&actor(None), authorize())).await;
+        let voided = voided.unwrap();
+        assert_eq!(appends(&calls), (Some(1), None));
+        service.authorize(&actor(None), authorize()).await.unwrap();
+
+        let (result, calls) = count_repo_calls(service.void_hold(&actor(None), voided.id)).await;
+        result.unwrap();
+        assert_eq!(appends(&calls), (Some(1), None));
+
+        clock.advance(Duration::seconds(60));
+        let (expired, calls) = count_repo_calls(service.expire_holds()).await;
+        assert_eq!(expired.unwrap(), 1);
+        assert_eq!(appends(&calls), (Some(1), None));
+    }
+
+    #[tokio::test]
+    async fn test_holds_count_towards_the_daily_debit_limit() {
+        let service = PaymentService::builder(MockRepo::new())
+            .with_limits(AmountLimits {
+                daily_debit_limit: Some(500),
+                ..AmountLimits::default()
+            })
+            .build();
+        let account = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        service
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(2_000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let authorize = |amount| AuthorizeRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            expires_in_secs: None,
+        };
+
+        let first = service
+            .authorize(&actor(None), authorize(300))
+            .await
+            .unwrap();
+        let result = service.authorize(&actor(None), authorize(300)).await;
+        assert!(
+            matches!(
+                result,
+                Err(AppError::DailyDebitLimitExceeded {
+                    spent: 300,
+                    requested: 300,
+                    max: 500
+                })
+            ),
+            "{:?}",
+            result
+        );
+
+        // Captured, the hold counts once, as the withdrawal it became
+        service
+            .capture_hold(&actor(None), first.id, CaptureRequest::default())
+            .await
+            .unwrap();
+        service
+            .authorize(&actor(None), authorize(200))
+            .await
+            .unwrap();
+        let result = service.authorize(&actor(None), authorize(1)).await;
+        assert!(
+            matches!(
+                result,
+                Err(AppError::DailyDebitLimitExceeded { spent: 500, .. })
+            ),
+            "{:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_limits_block_and_announce_payments() {
+        let publisher = Arc::new(BroadcastPublisher::new(16));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_event_publisher(publisher.clone())
+            .with_limits(AmountLimits {
+                max_amount_per_currency: HashMap::from([(CurrencyCode::USD, 5_000)]),
+                daily_debit_limit: Some(1_000),
+                max_transactions_per_hour: Some(4),
+                ..AmountLimits::default()
+            })
+            .build();
+        let alice = service
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let mut events = publisher.subscribe();
+        let deposit = |amount| DepositRequest {
+            account_id: alice.id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        let withdrawal = |amount| WithdrawRequest {
+            account_id: alice.id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        let result = service.deposit(deposit(5_001)).await;
+        assert!(
+            matches!(
+                result,
+                Err(AppError::AmountLimitExceeded {
+                    amount: 5_001,
+                    max: 5_000
+                })
+            ),
+            "{:?}",
+            result
+        );
+
+        service.deposit(deposit(5_000)).await.unwrap();
+        service.withdraw(withdrawal(600)).await.unwrap();
+        let result = service.withdraw(withdrawal(500)).await;
+        assert!(
+            matches!(
+                result,
+                Err(AppError::DailyDebitLimitExceeded {
+                    spent: 600,
+                    requested: 500,
+                    max: 1_000
+                })
+            ),
+            "{:?}",
+            result
+        );
+
+        // Deposits count against the hourly limit of the account they credit
+        service.withdraw(withdrawal(400)).await.unwrap();
+        service.deposit(deposit(100)).await.unwrap();
+        let result = service.deposit(deposit(100)).await;
+        assert!(
+            matches!(
+                result,
+                Err(AppError::HourlyTransactionLimitExceeded { count: 4, max: 4 })
+            ),
+            "{:?}",
+            result
+        );
+
+        let account = service.get_account(&actor(None), alice.id).await.unwrap();
+        assert_eq!(account.balance.amount(), 4_100);
+
+        let mut blocked = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let DomainEvent::TransactionBlocked(payment) = event {
+                blocked.push(payment);
+            }
+        }
+        assert_eq!(blocked.len(), 3);
+        assert_eq!(blocked[1].transaction_type, TransactionType::Withdrawal);
+        assert_eq!(blocked[1].from_account_id, Some(alice.id));
+        assert_eq!(blocked[1].amount, 500);
+        assert_eq!(blocked[1].error_code, ErrorCode::DailyDebitLimitExceeded);
+        assert_eq!(
+            blocked[2].error_code,
+            ErrorCode::HourlyTransactionLimitExceeded
+        );
+        assert_eq!(blocked[2].to_account_id, Some(alice.id));
+    }
+
+    #[tokio::test]
+    async fn test_quota_warnings_are_raised_once_per_crossing() {
+        let publisher = Arc::new(BroadcastPublisher::new(16));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_event_publisher(publisher.clone())
+            .with_storage_quotas(StorageQuotas {
+                accounts: Some(4),
+                transactions: None,
+                webhook_events: None,
+                warn_at: 0.5,
+            })
+            .build();
+        let mut events = publisher.subscribe();
+        let open = |name: &str| CreateAccountRequest {
+            name: name.to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+
+        service.create_account(open("Alice")).await.unwrap();
+        assert_eq!(service.check_storage_quotas().await.unwrap(), 0);
+
+        service.create_account(open("Bob")).await.unwrap();
+        let report = service.storage_report(&actor(None)).await.unwrap();
+        let accounts = report.resources[0];
+        assert_eq!(accounts.resource, StorageResource::Accounts);
+        assert_eq!((accounts.rows, accounts.used), (2, Some(0.5)));
+        assert!(accounts.warning);
+        assert!(!report.resources[1].warning);
+
+        assert_eq!(service.check_storage_quotas().await.unwrap(), 1);
+        // Still over the threshold, so no second warning.
+        assert_eq!(service.check_storage_quotas().await.unwrap(), 0);
+
+        let mut warnings = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let DomainEvent::QuotaWarning(warning) = event {
+                warnings.push(warning);
+            }
+        }
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].usage.resource, StorageResource::Accounts);
+        assert_eq!(warnings[0].usage.limit, Some(4));
+        assert_eq!(warnings[0].warn_at, 0.5);
     }
 
     #[tokio::test]
-    async fn test_create_account_success() {
-        let service = PaymentService::new(MockRepo::new());
+    async fn test_dormant_accounts_are_flagged_blocked_and_reactivated() {
+        let clock = Arc::new(FixedClock::new(SystemClock.now()));
+        let publisher = Arc::new(BroadcastPublisher::new(16));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_clock(clock.clone())
+            .with_event_publisher(publisher.clone())
+            .with_dormancy_policy(DormancyPolicy {
+                idle_for: Duration::days(30),
+                block_withdrawals: true,
+            })
+            .build();
+        let open = |name: &str| CreateAccountRequest {
+            name: name.to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+        let alice = service.create_account(open("Alice")).await.unwrap();
+        let bob = service.create_account(open("Bob")).await.unwrap();
+        let deposit = |account_id| DepositRequest {
+            account_id,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        let withdraw = WithdrawRequest {
+            account_id: alice.id,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        service.deposit(deposit(alice.id)).await.unwrap();
+        let mut events = publisher.subscribe();
 
-        let req = CreateAccountRequest {
-            name: "Test Account".to_string(),
+        assert_eq!(service.flag_dormant_accounts().await.unwrap(), 0);
+        clock.advance(Duration::days(31));
+        assert_eq!(service.flag_dormant_accounts().await.unwrap(), 2);
+        assert_eq!(service.flag_dormant_accounts().await.unwrap(), 0);
+        let dormant = service.get_account(&actor(None), alice.id).await.unwrap();
+        assert_eq!(dormant.status, AccountStatus::Dormant);
+        assert_eq!(dormant.status_changed_at, Some(clock.now()));
+
+        // Money may still come in, but not go out.
+        service.deposit(deposit(alice.id)).await.unwrap();
+        assert!(matches!(
+            service.withdraw(withdraw.clone()).await,
+            Err(AppError::AccountDormant(id)) if id == alice.id
+        ));
+        let transfer = TransferRequest {
+            from_account_id: alice.id,
+            to_account_id: bob.id,
+            amount: PositiveAmount::new(100).unwrap(),
             currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         };
+        assert!(matches!(
+            service.transfer(transfer).await,
+            Err(AppError::AccountDormant(_))
+        ));
 
-        let account = service.create_account(req).await.unwrap();
+        let reactivated = service
+            .reactivate_account(&actor(None), alice.id)
+            .await
+            .unwrap();
+        assert_eq!(reactivated.status, AccountStatus::Active);
+        service.withdraw(withdraw).await.unwrap();
+        assert!(matches!(
+            service.reactivate_account(&actor(None), alice.id).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service
+                .reactivate_account(&actor(None), AccountId::new())
+                .await,
+            Err(AppError::AccountNotFound(_))
+        ));
 
-        assert_eq!(account.name, "Test Account");
-        assert_eq!(account.balance.amount(), 0);
+        let mut dormant_events = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let DomainEvent::AccountDormant(account) = event {
+                dormant_events.push(account.id);
+            }
+        }
+        dormant_events.sort_by_key(|id| id.into_uuid());
+        let mut expected = vec![alice.id, bob.id];
+        expected.sort_by_key(|id| id.into_uuid());
+        assert_eq!(dormant_events, expected);
     }
 
     #[tokio::test]
-    async fn test_create_account_empty_name_fails() {
-        let service = PaymentService::new(MockRepo::new());
-
-        let req = CreateAccountRequest {
-            name: "   ".to_string(),
+    async fn test_frozen_and_closed_accounts_block_money_movement() {
+        let publisher = Arc::new(BroadcastPublisher::new(16));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_event_publisher(publisher.clone())
+            .build();
+        let open = |name: &str| CreateAccountRequest {
+            name: name.to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+        let alice = service.create_account(open("Alice")).await.unwrap();
+        let bob = service.create_account(open("Bob")).await.unwrap();
+        let deposit = |account_id| DepositRequest {
+            account_id,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        let withdraw = WithdrawRequest {
+            account_id: alice.id,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        let transfer = TransferRequest {
+            from_account_id: bob.id,
+            to_account_id: alice.id,
+            amount: PositiveAmount::new(1000).unwrap(),
             currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         };
+        service.deposit(deposit(bob.id)).await.unwrap();
+        let mut events = publisher.subscribe();
 
-        let result = service.create_account(req).await;
+        let frozen = service
+            .freeze_account(&actor(None), alice.id)
+            .await
+            .unwrap();
+        assert_eq!(frozen.status, AccountStatus::Frozen);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DomainEvent::AccountFrozen(account)) if account.id == alice.id
+        ));
+        assert!(matches!(
+            service.freeze_account(&actor(None), alice.id).await,
+            Err(AppError::BadRequest(_))
+        ));
 
-        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        // Money may still come in, but not go out.
+        service.transfer(transfer.clone()).await.unwrap();
+        assert!(matches!(
+            service.withdraw(withdraw.clone()).await,
+            Err(AppError::AccountFrozen(id)) if id == alice.id
+        ));
+        assert!(matches!(
+            service.close_account(&actor(None), alice.id).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        service
+            .unfreeze_account(&actor(None), alice.id)
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.unfreeze_account(&actor(None), alice.id).await,
+            Err(AppError::BadRequest(_))
+        ));
+        service.withdraw(withdraw.clone()).await.unwrap();
+
+        let closed = service.close_account(&actor(None), alice.id).await.unwrap();
+        assert_eq!(closed.status, AccountStatus::Closed);
+        assert!(matches!(
+            service.deposit(deposit(alice.id)).await,
+            Err(AppError::AccountClosed(_))
+        ));
+        assert!(matches!(
+            service.transfer(transfer).await,
+            Err(AppError::AccountClosed(_))
+        ));
+        assert!(matches!(
+            service.withdraw(withdraw).await,
+            Err(AppError::AccountClosed(_))
+        ));
+        assert!(matches!(
+            service.freeze_account(&actor(None), alice.id).await,
+            Err(AppError::AccountClosed(_))
+        ));
+        assert!(matches!(
+            service.close_account(&actor(None), alice.id).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert_eq!(
+            service
+                .get_account(&actor(None), bob.id)
+                .await
+                .unwrap()
+                .balance
+                .amount(),
+            0
+        );
     }
 
     #[tokio::test]
-    async fn test_deposit_success() {
-        let service = PaymentService::new(MockRepo::new());
-
-        let account = service
-            .create_account(CreateAccountRequest {
-                name: "Test".to_string(),
-                currency: CurrencyCode::USD,
-            })
+    async fn test_merge_moves_the_balance_and_closes_the_duplicate() {
+        let publisher = Arc::new(BroadcastPublisher::new(16));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_event_publisher(publisher.clone())
+            .build();
+        let open = |name: &str, currency| CreateAccountRequest {
+            name: name.to_string(),
+            currency,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+        let alice = service
+            .create_account(open("Alice", CurrencyCode::USD))
             .await
             .unwrap();
-
-        let tx = service
+        let duplicate = service
+            .create_account(open("Alice", CurrencyCode::USD))
+            .await
+            .unwrap();
+        let euros = service
+            .create_account(open("Alice EUR", CurrencyCode::EUR))
+            .await
+            .unwrap();
+        service
             .deposit(DepositRequest {
-                account_id: account.id,
-                amount: 1000,
+                account_id: duplicate.id,
+                amount: PositiveAmount::new(700).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await
             .unwrap();
 
-        assert_eq!(tx.amount.amount(), 1000);
+        assert!(matches!(
+            service
+                .merge_account(&actor(None), alice.id, alice.id)
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service
+                .merge_account(&actor(None), duplicate.id, euros.id)
+                .await,
+            Err(AppError::CurrencyMismatch { .. })
+        ));
+        service
+            .freeze_account(&actor(None), duplicate.id)
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.merge_account(&actor(None), duplicate.id, alice.id).await,
+            Err(AppError::AccountFrozen(id)) if id == duplicate.id
+        ));
+        service
+            .unfreeze_account(&actor(None), duplicate.id)
+            .await
+            .unwrap();
+
+        let mut events = publisher.subscribe();
+        let merge = service
+            .merge_account(&actor(None), duplicate.id, alice.id)
+            .await
+            .unwrap();
+        assert_eq!(merge.source.status, AccountStatus::Closed);
+        assert_eq!(merge.source.merged_into, Some(alice.id));
+        assert_eq!(merge.source.balance.amount(), 0);
+        assert_eq!(merge.target.balance.amount(), 700);
+        let transaction = merge.transaction.unwrap();
+        assert_eq!(transaction.source_account_id, Some(duplicate.id));
+        assert_eq!(transaction.amount.amount(), 700);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DomainEvent::TransferCompleted(tx)) if tx.id == transaction.id
+        ));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DomainEvent::AccountMerged(merged)) if merged.source.id == duplicate.id
+        ));
+
+        // The duplicate is gone for good; an empty account merges without a transfer.
+        assert!(matches!(
+            service.merge_account(&actor(None), duplicate.id, alice.id).await,
+            Err(AppError::AccountClosed(id)) if id == duplicate.id
+        ));
+        let empty = service
+            .create_account(open("Alice", CurrencyCode::USD))
+            .await
+            .unwrap();
+        let merge = service
+            .merge_account(&actor(None), empty.id, alice.id)
+            .await
+            .unwrap();
+        assert!(merge.transaction.is_none());
+        assert_eq!(merge.target.balance.amount(), 700);
     }
 
     #[tokio::test]
-    async fn test_deposit_zero_amount_fails() {
+    async fn test_api_key_usage_requires_known_key() {
         let service = PaymentService::new(MockRepo::new());
+        let id = payments_types::ApiKeyId::new();
+
+        // Metering an unknown key is logged, not surfaced.
+        service.record_api_request(id, false).await;
+        let result = service.api_key_usage(id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    fn actor(account_id: Option<AccountId>) -> ActorContext {
+        ActorContext {
+            api_key_id: ApiKeyId::new(),
+            name: "test".into(),
+            account_id,
+            owner_id: None,
+            owned_accounts: Vec::new(),
+            scopes: ApiKeyScope::all(),
+            mode: Mode::Live,
+        }
+    }
 
+    #[tokio::test]
+    async fn test_accounts_in_another_mode_are_not_found() {
+        let service = PaymentService::new(MockRepo::new());
         let account = service
             .create_account(CreateAccountRequest {
-                name: "Test".to_string(),
+                name: "Sandbox".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: Some(Mode::Test),
             })
             .await
             .unwrap();
+        let live = actor(None);
+        let test = ActorContext {
+            mode: Mode::Test,
+            ..actor(None)
+        };
 
-        let result = service
-            .deposit(DepositRequest {
-                account_id: account.id,
-                amount: 0,
-                currency: CurrencyCode::USD,
-                idempotency_key: None,
-                reference: None,
-            })
-            .await;
+        service.ensure_mode(&test, account.id).await.unwrap();
+        let result = service.get_account(&live, account.id).await;
+        assert!(matches!(result, Err(AppError::AccountNotFound(id)) if id == account.id));
+    }
+
+    #[tokio::test]
+    async fn test_admin_operations_refuse_scoped_keys_and_other_modes() {
+        let service = PaymentService::new(MockRepo::new());
+        let account = funded(&service, "Alice", 0).await;
+        let scoped = actor(Some(account));
+        let test = ActorContext {
+            mode: Mode::Test,
+            ..actor(None)
+        };
+
+        // A key bound to the account still may not administer it
+        let denied = |error: Option<AppError>| matches!(error, Some(AppError::AccessDenied(_)));
+        assert!(denied(service.freeze_account(&scoped, account).await.err()));
+        assert!(denied(
+            service
+                .set_account_limits(&scoped, account, AccountLimits::default())
+                .await
+                .err()
+        ));
+        assert!(denied(
+            service
+                .exposure_report(&scoped, CurrencyCode::USD, None)
+                .await
+                .err()
+        ));
+        assert!(denied(service.list_settlement_batches(&scoped).await.err()));
+        assert!(denied(service.list_owners(&scoped).await.err()));
+
+        // An admin key in the other mode cannot see the account at all
+        let result = service.freeze_account(&test, account).await;
+        assert!(matches!(result, Err(AppError::AccountNotFound(id)) if id == account));
+        let account = service.get_account(&actor(None), account).await.unwrap();
+        assert_eq!(account.status, AccountStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_account_resources_refuse_keys_without_access() {
+        let service = PaymentService::new(MockRepo::new());
+        let alice = funded(&service, "Alice", 1000).await;
+        let bob = funded(&service, "Bob", 1000).await;
+        let admin = actor(None);
+        let hold = service
+            .authorize(
+                &admin,
+                AuthorizeRequest {
+                    account_id: bob,
+                    amount: PositiveAmount::new(100).unwrap(),
+                    currency: CurrencyCode::USD,
+                    reference: None,
+                    purpose_code: None,
+                    expires_in_secs: None,
+                },
+            )
+            .await
+            .unwrap();
+        let beneficiary = service
+            .create_beneficiary(
+                &admin,
+                CreateBeneficiaryRequest {
+                    account_id: bob,
+                    name: "Globex".to_string(),
+                    external_id: "GB33BUKB20201555555555".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        let deposit = service
+            .list_transactions(&admin, bob, Default::default())
+            .await
+            .unwrap()
+            .data
+            .remove(0);
+
+        // A key bound to Alice's account cannot reach Bob's through the service
+        let scoped = actor(Some(alice));
+        let denied = |error: Option<AppError>| matches!(error, Some(AppError::AccessDenied(_)));
+        assert!(denied(service.get_hold(&scoped, hold.id).await.err()));
+        assert!(denied(service.void_hold(&scoped, hold.id).await.err()));
+        assert!(denied(
+            service
+                .capture_hold(&scoped, hold.id, CaptureRequest::default())
+                .await
+                .err()
+        ));
+        assert!(denied(service.list_holds(&scoped, bob).await.err()));
+        assert!(denied(
+            service.get_beneficiary(&scoped, beneficiary.id).await.err()
+        ));
+        assert!(denied(
+            service.get_transaction(&scoped, deposit.id).await.err()
+        ));
+        let search = TransactionSearchQuery {
+            account: Some(bob.into()),
+            ..Default::default()
+        };
+        assert!(denied(
+            service.search_transactions(&scoped, search).await.err()
+        ));
+        let hold = service.get_hold(&admin, hold.id).await.unwrap();
+        assert_eq!(hold.status, HoldStatus::Authorized);
+
+        // Bob's key sees his deposit
+        let own = service.get_transaction(&actor(Some(bob)), deposit.id).await;
+        assert_eq!(own.unwrap().id, deposit.id);
+    }
+
+    #[tokio::test]
+    async fn test_change_requests_need_a_known_target() {
+        let service = PaymentService::new(MockRepo::new());
+        let admin = actor(None);
 
+        // Nothing is stored when the target does not exist.
+        let delete = ChangeAction::DeleteApiKey {
+            api_key_id: ApiKeyId::new(),
+        };
+        let result = service.request_change(&admin, delete).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        let blank = ChangeAction::SetWebhookUrl {
+            endpoint_id: payments_types::WebhookEndpointId::new(),
+            url: " ".into(),
+        };
+        let result = service.request_change(&admin, blank).await;
         assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let result = service.approve_change(ChangeRequestId::new(), &admin).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
     }
 
     #[tokio::test]
-    async fn test_deposit_negative_amount_fails() {
+    async fn test_only_admin_keys_request_and_decide_changes() {
         let service = PaymentService::new(MockRepo::new());
+        let scoped = actor(Some(AccountId::new()));
 
-        let account = service
-            .create_account(CreateAccountRequest {
-                name: "Test".to_string(),
+        let delete = ChangeAction::DeleteApiKey {
+            api_key_id: ApiKeyId::new(),
+        };
+        let result = service.request_change(&scoped, delete).await;
+        assert!(matches!(result, Err(AppError::AccessDenied(_))));
+        let result = service
+            .approve_change(ChangeRequestId::new(), &scoped)
+            .await;
+        assert!(matches!(result, Err(AppError::AccessDenied(_))));
+        let result = service.reject_change(ChangeRequestId::new(), &scoped).await;
+        assert!(matches!(result, Err(AppError::AccessDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_actors_move_money_only_from_their_own_account() {
+        let service = PaymentService::new(MockRepo::new());
+        let open = |name: &str| {
+            service.create_account(CreateAccountRequest {
+                name: name.to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
+        };
+        let alice = open("Alice").await.unwrap();
+        let bob = open("Bob").await.unwrap();
+        let as_alice = actor(Some(alice.id));
+        let deposit = |account_id| DepositRequest {
+            account_id: AccountRef::Id(account_id),
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        let transfer = |from, to| TransferRequest {
+            from_account_id: AccountRef::Id(from),
+            to_account_id: AccountRef::Id(to),
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+
+        service
+            .deposit_as(&as_alice, deposit(alice.id))
+            .await
+            .unwrap();
+        let result = service.deposit_as(&as_alice, deposit(bob.id)).await;
+        assert!(matches!(result, Err(AppError::AccessDenied(_))));
+
+        // Paying someone else needs no access to their account
+        service
+            .transfer_as(&as_alice, transfer(alice.id, bob.id))
             .await
             .unwrap();
+        let result = service
+            .transfer_as(&as_alice, transfer(bob.id, alice.id))
+            .await;
+        assert!(matches!(result, Err(AppError::AccessDenied(_))));
 
+        // One operation on another account refuses the whole batch
         let result = service
-            .deposit(DepositRequest {
-                account_id: account.id,
-                amount: -100,
-                currency: CurrencyCode::USD,
-                idempotency_key: None,
-                reference: None,
-            })
+            .batch_as(
+                &as_alice,
+                BatchRequest {
+                    mode: BatchMode::BestEffort,
+                    operations: vec![
+                        BatchOperation::Deposit(deposit(alice.id)),
+                        BatchOperation::Deposit(deposit(bob.id)),
+                    ],
+                },
+            )
             .await;
+        assert!(matches!(result, Err(AppError::AccessDenied(_))));
+        let alice = service.get_account(&actor(None), alice.id).await.unwrap();
+        assert_eq!(alice.balance.amount(), 900);
 
-        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        // Admin keys may act on any account
+        service
+            .deposit_as(&actor(None), deposit(bob.id))
+            .await
+            .unwrap();
     }
 
-    #[tokio::test]
-    async fn test_transfer_to_same_account_fails() {
-        let service = PaymentService::new(MockRepo::new());
+    /// Records notifications instead of sending them.
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    /// A service whose clock is just past the end of the current month, so
+    /// that month's statements can be issued.
+    fn statement_service(
+        notifier: Option<Arc<RecordingNotifier>>,
+    ) -> (
+        PaymentService<MockRepo>,
+        StatementPeriod,
+        tokio::sync::broadcast::Receiver<DomainEvent>,
+    ) {
+        let period = StatementPeriod::containing(Utc::now().date_naive());
+        let publisher = Arc::new(BroadcastPublisher::new(16));
+        let events = publisher.subscribe();
+        let mut builder = PaymentService::builder(MockRepo::new())
+            .with_clock(Arc::new(FixedClock::new(
+                period.ends_at() + Duration::hours(1),
+            )))
+            .with_statement_links(StatementLinks::new("https://pay.example.com", "secret"))
+            .with_event_publisher(publisher);
+        if let Some(notifier) = notifier {
+            builder = builder.with_notifier(notifier);
+        }
+        (builder.build(), period, events)
+    }
 
+    async fn funded_account(service: &PaymentService<MockRepo>, name: &str) -> AccountId {
         let account = service
             .create_account(CreateAccountRequest {
-                name: "Test".to_string(),
+                name: name.to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
-
         service
             .deposit(DepositRequest {
                 account_id: account.id,
-                amount: 1000,
+                amount: PositiveAmount::new(1250).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await
             .unwrap();
+        account.id
+    }
+
+    #[tokio::test]
+    async fn test_statements_are_announced_by_webhook() {
+        let (service, period, mut events) = statement_service(None);
+        let account_id = funded_account(&service, "Alice").await;
+
+        let issued = service.issue_statements(period).await.unwrap();
+        assert_eq!(issued.len(), 1);
+        assert_eq!(issued[0].account_id, account_id);
+        assert_eq!(issued[0].opening_balance, 0);
+        assert_eq!(issued[0].closing_balance, 1250);
+        // Re-running the month issues nothing new.
+        assert!(service.issue_statements(period).await.unwrap().is_empty());
 
+        let mut ready = None;
+        while let Ok(event) = events.try_recv() {
+            if let DomainEvent::StatementReady(download) = event {
+                assert!(ready.is_none(), "announced twice");
+                ready = Some(download);
+            }
+        }
+        let ready = ready.expect("statement.ready published");
+        assert_eq!(ready.statement.id, issued[0].id);
+        let query = ready.download_url.split_once('?').unwrap().1;
+        let (expires, signature) = query.split_once("&signature=").unwrap();
+        let expires: i64 = expires.trim_start_matches("expires=").parse().unwrap();
+
+        let (statement, csv) = service
+            .download_statement(ready.statement.id, expires, signature)
+            .await
+            .unwrap();
+        assert_eq!(statement, issued[0]);
+        assert!(
+            csv.lines()
+                .last()
+                .unwrap()
+                .contains("CLOSING BALANCE,,,12.50,USD")
+        );
         let result = service
-            .transfer(TransferRequest {
-                from_account_id: account.id,
-                to_account_id: account.id,
-                amount: 100,
-                currency: CurrencyCode::USD,
-                idempotency_key: None,
-                reference: None,
-            })
+            .download_statement(ready.statement.id, expires, "forged")
             .await;
-
         assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let listed = service
+            .list_statements(&actor(None), account_id)
+            .await
+            .unwrap();
+        assert_eq!(listed, issued);
     }
 
     #[tokio::test]
-    async fn test_get_account_not_found() {
-        let service = PaymentService::new(MockRepo::new());
+    async fn test_statements_are_emailed_when_address_is_set() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let (service, period, mut events) = statement_service(Some(notifier.clone()));
+        let alice = funded_account(&service, "Alice").await;
+        let bob = funded_account(&service, "Bob").await;
+
+        let result = service
+            .set_statement_email(
+                &actor(None),
+                alice,
+                StatementEmail {
+                    email: Some("not an email".into()),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        service
+            .set_statement_email(
+                &actor(None),
+                alice,
+                StatementEmail {
+                    email: Some(" alice@example.com ".into()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .statement_email(&actor(None), alice)
+                .await
+                .unwrap()
+                .email
+                .as_deref(),
+            Some("alice@example.com")
+        );
 
-        let result = service.get_account(AccountId::new()).await;
+        service.issue_statements(period).await.unwrap();
 
-        assert!(matches!(result, Err(AppError::NotFound(_))));
+        let sent = notifier.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "alice@example.com");
+        let attachment = sent[0].attachment.as_ref().unwrap();
+        assert_eq!(attachment.filename, format!("statement-{}.csv", period));
+        assert!(String::from_utf8_lossy(&attachment.content).contains("OPENING BALANCE"));
+
+        // Bob has no address, so only his statement goes out by webhook.
+        let mut announced = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let DomainEvent::StatementReady(download) = event {
+                announced.push(download.statement.account_id);
+            }
+        }
+        assert_eq!(announced, vec![bob]);
     }
 
     #[tokio::test]
-    async fn test_list_transactions() {
+    async fn test_statements_wait_for_month_end() {
         let service = PaymentService::new(MockRepo::new());
+        let current = StatementPeriod::containing(Utc::now().date_naive());
+        let result = service.issue_statements(current).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
 
+    #[tokio::test]
+    async fn test_account_statement_covers_today_so_far() {
+        let service = PaymentService::new(MockRepo::new());
         let account = service
             .create_account(CreateAccountRequest {
-                name: "Test".to_string(),
+                name: "Alice".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
-
         service
             .deposit(DepositRequest {
                 account_id: account.id,
-                amount: 1000,
+                amount: PositiveAmount::new(1200).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let today = Utc::now().date_naive();
+
+        let statement = service
+            .account_statement(&actor(None), account.id, today - Duration::days(30), today)
+            .await
+            .unwrap();
+        assert_eq!(statement.opening_balance, 0);
+        assert_eq!(statement.closing_balance, 1200);
+        assert_eq!(statement.lines.len(), 1);
+
+        for (from, to) in [
+            (today, today - Duration::days(1)),
+            (today - Duration::days(366), today),
+        ] {
+            let result = service
+                .account_statement(&actor(None), account.id, from, to)
+                .await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+        let result = service
+            .account_statement(&actor(None), AccountId::new(), today, today)
+            .await;
+        assert!(matches!(result, Err(AppError::AccountNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_daily_balances_are_backfilled_and_recorded_once() {
+        let clock = Arc::new(FixedClock::new(SystemClock.now()));
+        let service = PaymentService::builder(MockRepo::new())
+            .with_clock(clock.clone())
+            .build();
+        let opened_on = clock.now().date_naive();
+        let account_id = funded_account(&service, "Alice").await;
+
+        // Today has not ended yet.
+        assert_eq!(service.record_daily_balances().await.unwrap(), 0);
+        clock.advance(Duration::days(3));
+        assert_eq!(service.record_daily_balances().await.unwrap(), 3);
+        assert_eq!(service.record_daily_balances().await.unwrap(), 0);
+
+        let history = service
+            .balance_history(&actor(None), account_id, BalanceHistoryQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(history.currency, CurrencyCode::USD);
+        let expected: Vec<DailyBalance> = opened_on
+            .iter_days()
+            .take(3)
+            .map(|date| DailyBalance {
+                date,
+                balance: 1250,
             })
+            .collect();
+        assert_eq!(history.balances, expected);
+
+        let first_day = service
+            .balance_history(
+                &actor(None),
+                account_id,
+                BalanceHistoryQuery {
+                    from: None,
+                    to: Some(opened_on),
+                },
+            )
             .await
             .unwrap();
+        assert_eq!(first_day.balances, expected[..1]);
+
+        let reversed = BalanceHistoryQuery {
+            from: Some(opened_on),
+            to: Some(opened_on - Duration::days(1)),
+        };
+        let result = service
+            .balance_history(&actor(None), account_id, reversed)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        let result = service
+            .balance_history(
+                &actor(None),
+                AccountId::new(),
+                BalanceHistoryQuery::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::AccountNotFound(_))));
+    }
 
-        let transactions = service.list_transactions(account.id).await.unwrap();
+    #[tokio::test]
+    async fn test_completed_transactions_are_counted_per_currency() {
+        let metrics = Arc::new(MetricsRegistry::default());
+        let service = PaymentService::builder(MockRepo::new())
+            .with_metrics(metrics.clone())
+            .build();
+        let open = |name: &str| CreateAccountRequest {
+            name: name.to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+        let alice = service.create_account(open("Alice")).await.unwrap();
+        let bob = service.create_account(open("Bob")).await.unwrap();
+        service
+            .deposit(DepositRequest {
+                account_id: alice.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let transfer = |amount| TransferRequest {
+            from_account_id: alice.id,
+            to_account_id: bob.id,
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        service
+            .transfer(transfer(PositiveAmount::new(300).unwrap()))
+            .await
+            .unwrap();
+        assert!(
+            service
+                .transfer(transfer(PositiveAmount::new(5000).unwrap()))
+                .await
+                .is_err()
+        );
 
-        assert_eq!(transactions.len(), 1);
+        let usd = |kind| [("type", kind), ("currency", "USD")];
+        assert_eq!(metrics.counter(TRANSACTIONS_TOTAL, &usd("DEPOSIT")), 1);
+        assert_eq!(metrics.counter(TRANSACTIONS_TOTAL, &usd("TRANSFER")), 1);
+        assert_eq!(
+            metrics.counter(TRANSACTION_VOLUME_TOTAL, &usd("TRANSFER")),
+            300
+        );
+        assert_eq!(metrics.counter(TRANSACTIONS_TOTAL, &usd("WITHDRAWAL")), 0);
     }
 }
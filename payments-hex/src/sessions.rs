@@ -0,0 +1,175 @@
+//! Short-lived session tokens for dashboards.
+//!
+//! A token is `st_{api_key_id}.{account_id}.{expires}.{signature}`: the
+//! signature is an HMAC over the rest, so tokens need no storage and cannot
+//! be pointed at another account or kept alive past their expiry. Requests
+//! made with one act as the issuing key, limited to reading that account, and
+//! stop working as soon as the key is deleted.
+
+use chrono::{DateTime, Utc};
+use payments_repo::security::{sign_webhook, verify_webhook_signature};
+use payments_types::{AccountId, ApiKey, ApiKeyId, SessionToken};
+use rand::Rng;
+use rand::distr::Alphanumeric;
+
+/// Path of the endpoint that issues session tokens.
+pub const SESSION_TOKENS_PATH: &str = "/api/session-tokens";
+
+/// Prefix telling session tokens apart from API keys (`sk_`).
+pub const SESSION_TOKEN_PREFIX: &str = "st_";
+
+/// What a valid session token was issued for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionClaims {
+    pub api_key_id: ApiKeyId,
+    pub account_id: AccountId,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl SessionClaims {
+    /// The key requests with this token act as: `issuer`, restricted to the
+    /// token's account and to the read scopes it holds.
+    pub fn restrict(&self, issuer: ApiKey) -> ApiKey {
+        ApiKey {
+            account_id: Some(self.account_id),
+            scopes: session_scopes(&issuer.scopes),
+            ..issuer
+        }
+    }
+}
+
+/// The read scopes a token issued by a key holding `scopes` holds.
+pub fn session_scopes(scopes: &[payments_types::ApiKeyScope]) -> Vec<payments_types::ApiKeyScope> {
+    SessionToken::SCOPES
+        .into_iter()
+        .filter(|scope| scopes.contains(scope))
+        .collect()
+}
+
+/// Signs and checks session tokens.
+#[derive(Debug, Clone)]
+pub struct SessionTokens {
+    secret: String,
+}
+
+impl SessionTokens {
+    /// Tokens signed with `secret`.
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    /// Returns a token for `api_key_id` to read `account_id` until
+    /// `expires_at` (whole seconds).
+    pub fn issue(
+        &self,
+        api_key_id: ApiKeyId,
+        account_id: AccountId,
+        expires_at: DateTime<Utc>,
+    ) -> String {
+        let expires = expires_at.timestamp();
+        format!(
+            "{}{}.{}.{}.{}",
+            SESSION_TOKEN_PREFIX,
+            api_key_id,
+            account_id,
+            expires,
+            self.sign(api_key_id, account_id, expires)
+        )
+    }
+
+    /// Returns what `token` was issued for, if it is ours and has not
+    /// expired.
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> Option<SessionClaims> {
+        let mut parts = token.strip_prefix(SESSION_TOKEN_PREFIX)?.splitn(4, '.');
+        let api_key_id: ApiKeyId = parts.next()?.parse().ok()?;
+        let account_id: AccountId = parts.next()?.parse().ok()?;
+        let expires: i64 = parts.next()?.parse().ok()?;
+        let signature = parts.next()?;
+        let valid = expires > now.timestamp()
+            && verify_webhook_signature(
+                Self::payload(api_key_id, account_id, expires).as_bytes(),
+                signature,
+                &self.secret,
+            );
+        valid.then_some(SessionClaims {
+            api_key_id,
+            account_id,
+            expires_at: DateTime::from_timestamp(expires, 0)?,
+        })
+    }
+
+    fn sign(&self, api_key_id: ApiKeyId, account_id: AccountId, expires: i64) -> String {
+        sign_webhook(
+            Self::payload(api_key_id, account_id, expires).as_bytes(),
+            &self.secret,
+        )
+    }
+
+    fn payload(api_key_id: ApiKeyId, account_id: AccountId, expires: i64) -> String {
+        format!("session:{}:{}:{}", api_key_id, account_id, expires)
+    }
+}
+
+/// Tokens signed with a per-process secret.
+///
+/// Tokens stop working when the process restarts and are only accepted by
+/// the instance that issued them; set a shared secret when running several.
+impl Default for SessionTokens {
+    fn default() -> Self {
+        let secret: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        Self::new(secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone};
+    use payments_types::ApiKeyScope;
+
+    use super::*;
+
+    #[test]
+    fn test_tokens_expire_and_reject_tampering() {
+        let tokens = SessionTokens::new("secret");
+        let (key, account) = (ApiKeyId::new(), AccountId::new());
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let expires_at = now + TimeDelta::minutes(15);
+        let token = tokens.issue(key, account, expires_at);
+        assert!(token.starts_with(SESSION_TOKEN_PREFIX));
+
+        let claims = tokens.verify(&token, now).unwrap();
+        assert_eq!(claims.api_key_id, key);
+        assert_eq!(claims.account_id, account);
+        assert_eq!(claims.expires_at, expires_at);
+        assert_eq!(tokens.verify(&token, expires_at), None);
+
+        let other_account = token.replace(&account.to_string(), &AccountId::new().to_string());
+        assert_eq!(tokens.verify(&other_account, now), None);
+        assert_eq!(SessionTokens::new("other").verify(&token, now), None);
+        assert_eq!(tokens.verify(token.trim_start_matches("st_"), now), None);
+    }
+
+    #[test]
+    fn test_restricted_key_only_reads_its_account() {
+        let issuer = ApiKey::new("dashboard".into(), "hash".into(), None).with_scopes(vec![
+            ApiKeyScope::AccountsRead,
+            ApiKeyScope::AccountsWrite,
+            ApiKeyScope::KeysAdmin,
+        ]);
+        let claims = SessionClaims {
+            api_key_id: issuer.id,
+            account_id: AccountId::new(),
+            expires_at: Utc::now(),
+        };
+        let key = claims.restrict(issuer.clone());
+        assert_eq!(key.id, issuer.id);
+        assert_eq!(key.account_id, Some(claims.account_id));
+        assert_eq!(key.scopes, [ApiKeyScope::AccountsRead]);
+    }
+}
@@ -0,0 +1,314 @@
+//! Settlement batch files for the bank.
+//!
+//! Renders a batch's payouts as CSV (one row per payout) or as an ISO 20022
+//! `pain.001.001.03` credit transfer initiation with one payment block per
+//! currency. Amounts in the CSV stay in minor units like the rest of the
+//! API; pain.001 needs them in major units with the currency's decimals.
+
+use std::fmt::Write;
+
+use payments_types::{AmountFormat, CurrencyCode, CurrencyTotal, SettlementBatch, Transaction};
+
+/// The service's own account that payouts are paid from, named in pain.001 files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementDebtor {
+    /// Account holder name
+    pub name: String,
+    pub iban: String,
+    /// BIC of the debtor's bank; omitted from the file when unknown
+    pub bic: Option<String>,
+}
+
+/// Renders a batch's payouts as CSV.
+///
+/// Text fields starting with `=`, `+`, `-` or `@` get a leading `'` so that
+/// spreadsheet tools do not evaluate customer-supplied names as formulas.
+pub fn render_csv(payouts: &[Transaction]) -> String {
+    let mut out = String::from(
+        "transaction_id,account_id,amount,currency,counterparty_name,counterparty_external_id,reference,purpose_code,created_at\n",
+    );
+    for tx in payouts {
+        let counterparty = tx.counterparty.as_ref();
+        let fields = [
+            tx.id.to_string(),
+            tx.source_account_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            tx.amount.amount().to_string(),
+            tx.amount.currency().to_string(),
+            csv_field(counterparty.map(|c| c.name.as_str())),
+            csv_field(counterparty.and_then(|c| c.external_id.as_deref())),
+            csv_field(tx.reference.as_deref()),
+            csv_field(tx.purpose_code.as_deref()),
+            tx.created_at.to_rfc3339(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Renders `batch` as a pain.001.001.03 document paid from `debtor`.
+///
+/// Fails with a message naming the payout if any payout has no counterparty,
+/// since the bank needs a creditor for every transfer.
+pub fn render_pain001(
+    batch: &SettlementBatch,
+    payouts: &[Transaction],
+    debtor: &SettlementDebtor,
+) -> Result<String, String> {
+    if let Some(tx) = payouts.iter().find(|tx| tx.counterparty.is_none()) {
+        return Err(format!(
+            "Payout {} has no counterparty; pain.001 needs a creditor for every payout",
+            tx.id
+        ));
+    }
+
+    let msg_id = batch.id.as_uuid().simple().to_string();
+    let mut xml = String::new();
+    // Writing to a String cannot fail.
+    let _ = write!(
+        xml,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:pain.001.001.03">
+  <CstmrCdtTrfInitn>
+    <GrpHdr>
+      <MsgId>{msg_id}</MsgId>
+      <CreDtTm>{created}</CreDtTm>
+      <NbOfTxs>{count}</NbOfTxs>
+      <CtrlSum>{ctrl_sum}</CtrlSum>
+      <InitgPty><Nm>{name}</Nm></InitgPty>
+    </GrpHdr>
+"#,
+        created = batch.created_at.format("%Y-%m-%dT%H:%M:%S"),
+        count = payouts.len(),
+        ctrl_sum = group_control_sum(&batch.totals),
+        name = xml_escape(&debtor.name),
+    );
+
+    for total in &batch.totals {
+        let _ = write!(
+            xml,
+            r#"    <PmtInf>
+      <PmtInfId>{msg_id}-{currency}</PmtInfId>
+      <PmtMtd>TRF</PmtMtd>
+      <NbOfTxs>{count}</NbOfTxs>
+      <CtrlSum>{ctrl_sum}</CtrlSum>
+      <ReqdExctnDt>{date}</ReqdExctnDt>
+      <Dbtr><Nm>{name}</Nm></Dbtr>
+      <DbtrAcct><Id><IBAN>{iban}</IBAN></Id><Ccy>{currency}</Ccy></DbtrAcct>
+      <DbtrAgt><FinInstnId>{agent}</FinInstnId></DbtrAgt>
+"#,
+            currency = total.currency,
+            count = total.payout_count,
+            ctrl_sum = major_units(total.amount, total.currency),
+            date = batch.created_at.format("%Y-%m-%d"),
+            name = xml_escape(&debtor.name),
+            iban = xml_escape(&debtor.iban),
+            agent = match &debtor.bic {
+                Some(bic) => format!("<BIC>{}</BIC>", xml_escape(bic)),
+                None => "<Othr><Id>NOTPROVIDED</Id></Othr>".to_string(),
+            },
+        );
+
+        for tx in payouts
+            .iter()
+            .filter(|tx| tx.amount.currency() == total.currency)
+        {
+            // Checked above.
+            let Some(counterparty) = tx.counterparty.as_ref() else {
+                continue;
+            };
+            let creditor_account = match counterparty.external_id.as_deref() {
+                Some(id) if looks_like_iban(id) => format!("<IBAN>{}</IBAN>", xml_escape(id)),
+                Some(id) => format!("<Othr><Id>{}</Id></Othr>", xml_escape(id)),
+                None => "<Othr><Id>NOTPROVIDED</Id></Othr>".to_string(),
+            };
+            let remittance = tx
+                .reference
+                .as_deref()
+                .map(|r| {
+                    format!(
+                        "        <RmtInf><Ustrd>{}</Ustrd></RmtInf>\n",
+                        xml_escape(r)
+                    )
+                })
+                .unwrap_or_default();
+            let _ = write!(
+                xml,
+                r#"      <CdtTrfTxInf>
+        <PmtId><EndToEndId>{end_to_end}</EndToEndId></PmtId>
+        <Amt><InstdAmt Ccy="{currency}">{amount}</InstdAmt></Amt>
+        <Cdtr><Nm>{creditor}</Nm></Cdtr>
+        <CdtrAcct><Id>{creditor_account}</Id></CdtrAcct>
+{remittance}      </CdtTrfTxInf>
+"#,
+                end_to_end = tx.id.as_uuid().simple(),
+                currency = total.currency,
+                amount = major_units(tx.amount.amount(), total.currency),
+                creditor = xml_escape(&counterparty.name),
+            );
+        }
+        xml.push_str("    </PmtInf>\n");
+    }
+    xml.push_str("  </CstmrCdtTrfInitn>\n</Document>\n");
+    Ok(xml)
+}
+
+/// Sum of every payout amount in major units, across currencies, as pain.001
+/// defines the group header's control sum.
+fn group_control_sum(totals: &[CurrencyTotal]) -> String {
+    let mut cents: i128 = 0;
+    let mut decimals = 0;
+    for total in totals {
+        let digits = AmountFormat::currency_decimals(total.currency);
+        // Scale everything to the finest precision seen so far.
+        if digits > decimals {
+            cents *= 10i128.pow(digits - decimals);
+            decimals = digits;
+        }
+        cents += total.amount as i128 * 10i128.pow(decimals - digits);
+    }
+    format_decimal(cents, decimals)
+}
+
+/// Formats a minor-unit amount in major units with the currency's own
+/// decimals, e.g. `1234` USD as `12.34`, as files other systems import need.
+pub(crate) fn major_units(minor: i64, currency: CurrencyCode) -> String {
+    AmountFormat::default().format(minor, currency)
+}
+
+fn format_decimal(value: i128, decimals: u32) -> String {
+    if decimals == 0 {
+        return value.to_string();
+    }
+    let scale = 10i128.pow(decimals);
+    let sign = if value < 0 { "-" } else { "" };
+    let value = value.abs();
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        value / scale,
+        value % scale,
+        width = decimals as usize
+    )
+}
+
+/// Two letters, two check digits, then up to 30 alphanumerics.
+pub(crate) fn looks_like_iban(id: &str) -> bool {
+    let bytes = id.as_bytes();
+    (15..=34).contains(&bytes.len())
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes[4..].iter().all(u8::is_ascii_alphanumeric)
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Quotes a CSV field, defusing leading formula characters.
+pub(crate) fn csv_field(value: Option<&str>) -> String {
+    let Some(value) = value else {
+        return String::new();
+    };
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use payments_types::{
+        AccountId, Counterparty, DynMoney, SettlementBatchId, SettlementBatchStatus,
+    };
+
+    use super::*;
+
+    fn payout(amount: i64, currency: CurrencyCode, name: &str) -> Transaction {
+        Transaction::withdrawal(
+            AccountId::new(),
+            DynMoney::new(amount, currency).unwrap(),
+            None,
+            Some("INV-7".into()),
+        )
+        .with_counterparty(Some(Counterparty {
+            name: name.into(),
+            external_id: Some("GB33BUKB20201555555555".into()),
+        }))
+    }
+
+    fn batch_of(payouts: &[Transaction]) -> SettlementBatch {
+        let now = Utc::now();
+        SettlementBatch {
+            id: SettlementBatchId::new(),
+            status: SettlementBatchStatus::Pending,
+            cutoff: now,
+            payout_count: payouts.len() as i64,
+            totals: SettlementBatch::totals_for(payouts),
+            created_at: now,
+            updated_at: now,
+            mode: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_csv_quotes_and_defuses_fields() {
+        let csv = render_csv(&[payout(1_250, CurrencyCode::USD, "=SUM(A1), Inc")]);
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.contains(",1250,USD,\"'=SUM(A1), Inc\",GB33BUKB20201555555555,INV-7,"));
+    }
+
+    #[test]
+    fn test_pain001_groups_by_currency() {
+        let payouts = [
+            payout(1_250, CurrencyCode::USD, "Acme & Sons"),
+            payout(99, CurrencyCode::EUR, "Globex"),
+            payout(5, CurrencyCode::USD, "Initech"),
+        ];
+        let debtor = SettlementDebtor {
+            name: "Payments Ltd".into(),
+            iban: "DE89370400440532013000".into(),
+            bic: None,
+        };
+        let xml = render_pain001(&batch_of(&payouts), &payouts, &debtor).unwrap();
+
+        assert!(xml.contains("<NbOfTxs>3</NbOfTxs>\n      <CtrlSum>13.54</CtrlSum>"));
+        assert!(xml.contains("<NbOfTxs>2</NbOfTxs>\n      <CtrlSum>12.55</CtrlSum>"));
+        assert!(xml.contains(r#"<InstdAmt Ccy="EUR">0.99</InstdAmt>"#));
+        assert!(xml.contains("<Cdtr><Nm>Acme &amp; Sons</Nm></Cdtr>"));
+        assert!(xml.contains("<IBAN>GB33BUKB20201555555555</IBAN>"));
+        assert_eq!(xml.matches("<PmtInf>").count(), 2);
+    }
+
+    #[test]
+    fn test_pain001_requires_counterparties() {
+        let mut payouts = vec![payout(100, CurrencyCode::USD, "Acme")];
+        payouts[0].counterparty = None;
+        let debtor = SettlementDebtor {
+            name: "Payments Ltd".into(),
+            iban: "DE89370400440532013000".into(),
+            bic: Some("COBADEFFXXX".into()),
+        };
+        assert!(render_pain001(&batch_of(&payouts), &payouts, &debtor).is_err());
+    }
+}
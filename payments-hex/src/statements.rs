@@ -0,0 +1,236 @@
+//! Statement files and their download links.
+//!
+//! Statements, monthly or on demand, are rendered as CSV: an opening balance
+//! row, one row per transaction with the running balance after it, and a
+//! closing balance row.
+//! Amounts are in major units, signed from the account's point of view and
+//! written with the service's [`AmountFormat`].
+//!
+//! Download links carry an expiry and an HMAC over the statement ID and that
+//! expiry, so they can be handed to a customer (or a webhook consumer) and
+//! used without an API key.
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use payments_repo::security::{sign_webhook, verify_webhook_signature};
+use payments_types::{
+    AccountStatement, AmountFormat, CurrencyCode, Statement, StatementId, StatementLine,
+    Transaction,
+};
+use rand::Rng;
+use rand::distr::Alphanumeric;
+
+use crate::settlement::csv_field;
+
+/// How long a download link stays valid by default.
+const DEFAULT_LINK_TTL: TimeDelta = TimeDelta::days(7);
+
+/// Signs and checks statement download links.
+#[derive(Debug, Clone)]
+pub struct StatementLinks {
+    /// Public origin links point at, e.g. `https://payments.example.com`
+    base_url: String,
+    secret: String,
+    ttl: TimeDelta,
+}
+
+impl StatementLinks {
+    /// Links under `base_url`, signed with `secret`.
+    pub fn new(base_url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            secret: secret.into(),
+            ttl: DEFAULT_LINK_TTL,
+        }
+    }
+
+    /// Sets how long new links stay valid.
+    pub fn with_ttl(mut self, ttl: TimeDelta) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns a signed download URL for `id` and when it expires.
+    pub fn url_for(&self, id: StatementId, now: DateTime<Utc>) -> (String, DateTime<Utc>) {
+        let expires_at = now + self.ttl;
+        let expires = expires_at.timestamp();
+        let url = format!(
+            "{}/api/statements/{}/download?expires={}&signature={}",
+            self.base_url,
+            id,
+            expires,
+            self.sign(id, expires)
+        );
+        (url, expires_at)
+    }
+
+    /// Checks that a link for `id` was signed by us and has not expired.
+    pub fn verify(
+        &self,
+        id: StatementId,
+        expires: i64,
+        signature: &str,
+        now: DateTime<Utc>,
+    ) -> bool {
+        expires > now.timestamp()
+            && verify_webhook_signature(
+                Self::payload(id, expires).as_bytes(),
+                signature,
+                &self.secret,
+            )
+    }
+
+    fn sign(&self, id: StatementId, expires: i64) -> String {
+        sign_webhook(Self::payload(id, expires).as_bytes(), &self.secret)
+    }
+
+    fn payload(id: StatementId, expires: i64) -> String {
+        format!("{}:{}", id, expires)
+    }
+}
+
+/// Local links signed with a per-process secret.
+///
+/// Links stop working when the process restarts; configure a base URL and
+/// secret for anything beyond local use.
+impl Default for StatementLinks {
+    fn default() -> Self {
+        let secret: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        Self::new("http://localhost:3000", secret)
+    }
+}
+
+/// Renders `statement` with its period's transactions, oldest first.
+pub fn render_statement(
+    statement: &Statement,
+    transactions: &[Transaction],
+    format: &AmountFormat,
+) -> String {
+    render_lines(
+        format,
+        statement.currency,
+        (statement.period_start, statement.opening_balance),
+        &StatementLine::running(
+            statement.account_id,
+            statement.opening_balance,
+            transactions,
+        ),
+        (statement.period_end, statement.closing_balance),
+    )
+}
+
+/// Renders an on-demand statement in the monthly statement layout.
+pub fn render_account_statement(statement: &AccountStatement, format: &AmountFormat) -> String {
+    render_lines(
+        format,
+        statement.currency,
+        (statement.from, statement.opening_balance),
+        &statement.lines,
+        (statement.to, statement.closing_balance),
+    )
+}
+
+/// Writes the opening row, one row per line and the closing row, each
+/// `(date, balance)` pair giving the date and balance of the outer rows.
+fn render_lines(
+    format: &AmountFormat,
+    currency: CurrencyCode,
+    opening: (NaiveDate, i64),
+    lines: &[StatementLine],
+    closing: (NaiveDate, i64),
+) -> String {
+    let mut out = String::from("date,transaction_id,type,description,amount,balance,currency\n");
+    out.push_str(&format!(
+        "{},,OPENING BALANCE,,,{},{}\n",
+        opening.0,
+        format.format(opening.1, currency),
+        currency
+    ));
+    for line in lines {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            line.created_at.format("%Y-%m-%d"),
+            line.transaction_id,
+            line.transaction_type,
+            csv_field(Some(&line.description)),
+            format.format(line.amount, currency),
+            format.format(line.balance, currency),
+            currency
+        ));
+    }
+    out.push_str(&format!(
+        "{},,CLOSING BALANCE,,,{},{}\n",
+        closing.0,
+        format.format(closing.1, currency),
+        currency
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use payments_types::{Account, CurrencyCode, DynMoney, StatementPeriod};
+
+    use super::*;
+
+    #[test]
+    fn test_links_expire_and_reject_tampering() {
+        let links = StatementLinks::new("https://pay.example.com/", "secret");
+        let id = StatementId::new();
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let (url, expires_at) = links.url_for(id, now);
+        assert!(url.starts_with(&format!(
+            "https://pay.example.com/api/statements/{}/download?",
+            id
+        )));
+        assert_eq!(expires_at, now + DEFAULT_LINK_TTL);
+
+        let signature = url.rsplit_once("signature=").unwrap().1;
+        let expires = expires_at.timestamp();
+        assert!(links.verify(id, expires, signature, now));
+        assert!(!links.verify(id, expires, signature, expires_at));
+        assert!(!links.verify(id, expires + 60, signature, now));
+        assert!(!links.verify(StatementId::new(), expires, signature, now));
+        assert!(
+            !StatementLinks::new("https://pay.example.com", "other")
+                .verify(id, expires, signature, now)
+        );
+    }
+
+    #[test]
+    fn test_render_keeps_running_balance() {
+        let account = Account::new("Alice".into(), CurrencyCode::USD).unwrap();
+        let period = StatementPeriod::parse("2026-02").unwrap();
+        let money = |amount| DynMoney::new(amount, CurrencyCode::USD).unwrap();
+        let mut deposit =
+            Transaction::deposit(account.id, money(2_500), None, Some("Pay, Feb".into()));
+        deposit.created_at = period.starts_at() + TimeDelta::days(2);
+        let mut withdrawal = Transaction::withdrawal(account.id, money(1_000), None, None);
+        withdrawal.created_at = period.starts_at() + TimeDelta::days(9);
+        let mut before = Transaction::deposit(account.id, money(500), None, None);
+        before.created_at = period.starts_at() - TimeDelta::days(1);
+
+        let (statement, lines) = Statement::from_history(
+            StatementId::new(),
+            &account,
+            period,
+            &[before, deposit, withdrawal],
+            Utc::now(),
+        );
+        let csv = render_statement(&statement, &lines, &AmountFormat::default());
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[1], "2026-02-01,,OPENING BALANCE,,,5.00,USD");
+        assert!(rows[2].ends_with(",DEPOSIT,\"Pay, Feb\",25.00,30.00,USD"));
+        assert!(rows[3].ends_with(",WITHDRAWAL,,-10.00,20.00,USD"));
+        assert_eq!(rows[4], "2026-02-28,,CLOSING BALANCE,,,20.00,USD");
+
+        let whole = "decimals=0".parse().unwrap();
+        let csv = render_statement(&statement, &lines, &whole);
+        assert!(csv.lines().nth(3).unwrap().ends_with(",-10,20,USD"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Built-in payment warning rules.
+//!
+//! [`default_rules`] is what [`PaymentService`](crate::PaymentService) runs
+//! unless the builder is given other rules. Warnings never block a payment;
+//! they are returned next to it for the caller to act on.
+
+use std::sync::Arc;
+
+use chrono::TimeDelta;
+use payments_types::{AccountActivity, CurrencyCode, DynMoney, PaymentCheck, Warning, WarningRule};
+
+/// The rules a service runs by default: [`DormantAccountRule`] and
+/// [`LargeAmountRule`], both with their default thresholds.
+pub fn default_rules() -> Vec<Arc<dyn WarningRule>> {
+    vec![
+        Arc::new(DormantAccountRule::default()),
+        Arc::new(LargeAmountRule::default()),
+    ]
+}
+
+/// Formats minor units of `currency` for a message, e.g. `$12.50`.
+pub(crate) fn money(amount: i64, currency: CurrencyCode) -> String {
+    DynMoney::new(amount, currency).map_or_else(|_| amount.to_string(), |m| m.to_string())
+}
+
+/// Warns when money moves through an account that has had no transactions
+/// for a long time, e.g. a transfer into an account abandoned a year ago.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DormantAccountRule {
+    /// How long an account must have been idle; 365 days by default.
+    pub idle_for: TimeDelta,
+}
+
+impl Default for DormantAccountRule {
+    fn default() -> Self {
+        Self {
+            idle_for: TimeDelta::days(365),
+        }
+    }
+}
+
+impl WarningRule for DormantAccountRule {
+    fn check(&self, payment: &PaymentCheck) -> Vec<Warning> {
+        let accounts = [
+            ("Source", payment.source.as_ref()),
+            ("Destination", payment.destination.as_ref()),
+        ];
+        accounts
+            .into_iter()
+            .filter_map(|(role, activity)| {
+                let idle = payment.now - activity?.last_active_at();
+                (idle >= self.idle_for).then(|| {
+                    Warning::new(
+                        "dormant_account",
+                        format!(
+                            "{} account has been dormant for {} months",
+                            role,
+                            idle.num_days() / 30
+                        ),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Warns when a payment is far larger than the account's recent ones.
+///
+/// Compares against the paying account, or the credited one for deposits,
+/// using only its recent transactions in the payment's currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LargeAmountRule {
+    /// How many times the recent average an amount must exceed; 10 by default.
+    pub factor: i64,
+    /// Fewest recent transactions needed to judge; 5 by default.
+    pub min_history: usize,
+}
+
+impl Default for LargeAmountRule {
+    fn default() -> Self {
+        Self {
+            factor: 10,
+            min_history: 5,
+        }
+    }
+}
+
+impl LargeAmountRule {
+    /// Average of the account's recent amounts in `currency`, if it has
+    /// enough of them.
+    fn recent_average(&self, activity: &AccountActivity, currency: CurrencyCode) -> Option<i64> {
+        let amounts: Vec<i64> = activity
+            .recent
+            .iter()
+            .filter(|tx| tx.amount.currency() == currency)
+            .map(|tx| tx.amount.amount())
+            .collect();
+        if amounts.len() < self.min_history.max(1) {
+            return None;
+        }
+        Some(amounts.iter().sum::<i64>() / amounts.len() as i64)
+    }
+}
+
+impl WarningRule for LargeAmountRule {
+    fn check(&self, payment: &PaymentCheck) -> Vec<Warning> {
+        let Some(activity) = payment.source.as_ref().or(payment.destination.as_ref()) else {
+            return Vec::new();
+        };
+        let Some(average) = self.recent_average(activity, payment.currency) else {
+            return Vec::new();
+        };
+        if payment.amount <= average.saturating_mul(self.factor) {
+            return Vec::new();
+        }
+        vec![Warning::new(
+            "unusual_amount",
+            format!(
+                "Amount {} is more than {} times this account's recent average of {}",
+                money(payment.amount, payment.currency),
+                self.factor,
+                money(average, payment.currency)
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use payments_types::{Account, Transaction, TransactionType};
+
+    use super::*;
+
+    fn activity(days_since_last: Option<i64>, amounts: &[i64]) -> AccountActivity {
+        let mut account = Account::new("Alice".into(), CurrencyCode::USD).unwrap();
+        account.created_at = Utc::now() - TimeDelta::days(1_000);
+        let recent = amounts
+            .iter()
+            .map(|amount| {
+                let mut tx = Transaction::deposit(
+                    account.id,
+                    DynMoney::new(*amount, CurrencyCode::USD).unwrap(),
+                    None,
+                    None,
+                );
+                tx.created_at = Utc::now() - TimeDelta::days(days_since_last.unwrap_or(0));
+                tx
+            })
+            .collect();
+        AccountActivity { account, recent }
+    }
+
+    fn transfer(amount: i64, to: AccountActivity) -> PaymentCheck {
+        PaymentCheck {
+            transaction_type: TransactionType::Transfer,
+            amount,
+            currency: CurrencyCode::USD,
+            source: Some(activity(Some(0), &[100; 5])),
+            destination: Some(to),
+            now: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_dormant_destination_is_flagged() {
+        let rule = DormantAccountRule::default();
+
+        let warnings = rule.check(&transfer(100, activity(Some(400), &[100])));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "dormant_account");
+        assert_eq!(
+            warnings[0].message,
+            "Destination account has been dormant for 13 months"
+        );
+
+        assert!(
+            rule.check(&transfer(100, activity(Some(30), &[100])))
+                .is_empty()
+        );
+        // Never used since it was opened 1,000 days ago.
+        assert_eq!(rule.check(&transfer(100, activity(None, &[]))).len(), 1);
+    }
+
+    #[test]
+    fn test_unusually_large_amount_is_flagged() {
+        let rule = LargeAmountRule::default();
+        let to = || activity(Some(0), &[]);
+
+        let warnings = rule.check(&transfer(1_001, to()));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "unusual_amount");
+        assert_eq!(
+            warnings[0].message,
+            "Amount $10.01 is more than 10 times this account's recent average of $1.00"
+        );
+        assert!(rule.check(&transfer(1_000, to())).is_empty());
+
+        // Too little history to judge.
+        let mut payment = transfer(1_000_000, to());
+        payment.source = Some(activity(Some(0), &[100; 4]));
+        assert!(rule.check(&payment).is_empty());
+    }
+}
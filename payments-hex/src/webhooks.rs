@@ -0,0 +1,138 @@
+//! Cached view of the active webhook endpoints.
+//!
+//! Every payment queues webhook events for the endpoints subscribed to it, so
+//! the service keeps the active endpoints in memory instead of listing them
+//! on each payment. Registering an endpoint or changing its URL through the
+//! service drops the cached list; changes made by another instance show up
+//! once the cached list expires.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use payments_types::WebhookEndpoint;
+
+/// How long a listing of endpoints is served before it is read again.
+pub const WEBHOOK_ENDPOINT_CACHE_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct Cached {
+    /// Bumped on every invalidation, so a listing read before one is not
+    /// stored after it
+    generation: u64,
+    endpoints: Option<(DateTime<Utc>, Arc<[WebhookEndpoint]>)>,
+}
+
+/// Active webhook endpoints, kept for [`WEBHOOK_ENDPOINT_CACHE_TTL`] or until
+/// invalidated.
+#[derive(Debug)]
+pub struct WebhookEndpointCache {
+    ttl: Duration,
+    cached: Mutex<Cached>,
+}
+
+impl Default for WebhookEndpointCache {
+    fn default() -> Self {
+        Self::new(WEBHOOK_ENDPOINT_CACHE_TTL)
+    }
+}
+
+impl WebhookEndpointCache {
+    /// A cache serving each listing for `ttl`; zero disables caching.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cached: Mutex::default(),
+        }
+    }
+
+    /// The cached endpoints if still fresh at `now`, otherwise the generation
+    /// to pass to [`store`](Self::store) with a fresh listing.
+    pub fn get(&self, now: DateTime<Utc>) -> Result<Arc<[WebhookEndpoint]>, u64> {
+        let cached = self.cached.lock().unwrap();
+        match &cached.endpoints {
+            Some((listed_at, endpoints))
+                if (now - *listed_at).to_std().is_ok_and(|age| age < self.ttl) =>
+            {
+                Ok(endpoints.clone())
+            }
+            _ => Err(cached.generation),
+        }
+    }
+
+    /// Keeps the active ones of `endpoints`, listed at `now`, unless the cache
+    /// was invalidated since `generation` was handed out.
+    pub fn store(
+        &self,
+        generation: u64,
+        now: DateTime<Utc>,
+        endpoints: Vec<WebhookEndpoint>,
+    ) -> Arc<[WebhookEndpoint]> {
+        let active: Arc<[WebhookEndpoint]> =
+            endpoints.into_iter().filter(|ep| ep.is_active).collect();
+        let mut cached = self.cached.lock().unwrap();
+        if cached.generation == generation {
+            cached.endpoints = Some((now, active.clone()));
+        }
+        active
+    }
+
+    /// Drops the cached endpoints after one is registered or changed.
+    pub fn invalidate(&self) {
+        let mut cached = self.cached.lock().unwrap();
+        cached.generation += 1;
+        cached.endpoints = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use payments_types::WebhookTimeouts;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn endpoint(is_active: bool) -> WebhookEndpoint {
+        WebhookEndpoint {
+            id: Uuid::new_v4(),
+            url: "https://example.com/hook".into(),
+            secret: "secret".into(),
+            events: vec![],
+            is_active,
+            created_at: Utc::now(),
+            timeouts: WebhookTimeouts::default(),
+            signature_algorithm: Default::default(),
+            public_key: None,
+            signing_key: String::new(),
+            mode: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_serves_active_endpoints_until_expired_or_invalidated() {
+        let cache = WebhookEndpointCache::new(Duration::from_secs(30));
+        let now = Utc::now();
+        let generation = cache.get(now).unwrap_err();
+        let stored = cache.store(generation, now, vec![endpoint(true), endpoint(false)]);
+        assert_eq!(stored.len(), 1);
+
+        let later = now + chrono::Duration::seconds(29);
+        assert_eq!(cache.get(later).unwrap()[0].id, stored[0].id);
+        assert!(cache.get(now + chrono::Duration::seconds(30)).is_err());
+
+        cache.invalidate();
+        assert!(cache.get(now).is_err());
+    }
+
+    #[test]
+    fn test_listing_read_before_an_invalidation_is_not_kept() {
+        let cache = WebhookEndpointCache::default();
+        let now = Utc::now();
+        let generation = cache.get(now).unwrap_err();
+        cache.invalidate();
+
+        let stored = cache.store(generation, now, vec![endpoint(true)]);
+        assert_eq!(stored.len(), 1);
+        assert!(cache.get(now).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Integration tests for request bodies passing through the middleware stack.
+//!
+//! Middleware only looks at headers and extensions, so bodies stream through
+//! to the handler untouched, and authentication runs before any of the body
+//! is read. These tests send bodies that count how many chunks were pulled
+//! from them.
+//!
+//! This test requires the `sqlite` feature flag.
+
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    http::{Method, Request, StatusCode, header},
+};
+use futures_util::stream;
+use tower::ServiceExt;
+
+use common::test_app;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A streamed body of `chunks`, without a `Content-Length`, and the number of
+/// chunks read from it so far.
+fn counted_body(chunks: Vec<Bytes>) -> (Body, Arc<AtomicUsize>) {
+    let read = Arc::new(AtomicUsize::new(0));
+    let counter = read.clone();
+    let body = Body::from_stream(stream::iter(chunks.into_iter().map(move |chunk| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok::<_, std::io::Error>(chunk)
+    })));
+    (body, read)
+}
+
+/// `len` bytes of padding split into chunks.
+fn padding(len: usize) -> Vec<Bytes> {
+    (0..len.div_ceil(CHUNK_SIZE))
+        .map(|i| Bytes::from(vec![b' '; CHUNK_SIZE.min(len - i * CHUNK_SIZE)]))
+        .collect()
+}
+
+/// A create-account request followed by `padding` bytes of whitespace.
+fn padded_account_request(padding_len: usize) -> Vec<Bytes> {
+    let mut chunks = vec![Bytes::from_static(
+        br#"{"owner_id": "00000000-0000-0000-0000-000000000000", "name": "Streamed", "currency": "USD"}"#,
+    )];
+    chunks.extend(padding(padding_len));
+    chunks
+}
+
+fn request(method: Method, uri: &str, api_key: Option<&str>, body: Body) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(key) = api_key {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", key));
+    }
+    builder.body(body).unwrap()
+}
+
+#[tokio::test]
+async fn test_unauthenticated_upload_is_refused_before_body_is_read() {
+    let (app, _) = test_app().await;
+
+    for api_key in [None, Some("sk_not_a_real_key")] {
+        let (body, read) = counted_body(padded_account_request(4 * 1024 * 1024));
+        let response = app
+            .clone()
+            .oneshot(request(Method::POST, "/api/accounts", api_key, body))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(read.load(Ordering::SeqCst), 0, "key {:?}", api_key);
+    }
+}
+
+#[tokio::test]
+async fn test_get_with_body_is_authenticated_without_reading_it() {
+    let (app, api_key) = test_app().await;
+
+    let (body, read) = counted_body(padding(CHUNK_SIZE * 4));
+    let response = app
+        .clone()
+        .oneshot(request(
+            Method::GET,
+            "/api/accounts",
+            Some("sk_wrong"),
+            body,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(read.load(Ordering::SeqCst), 0);
+
+    let (body, _) = counted_body(padding(CHUNK_SIZE * 4));
+    let response = app
+        .oneshot(request(Method::GET, "/api/accounts", Some(&api_key), body))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_large_upload_streams_through_middleware_to_handler() {
+    let (app, api_key) = test_app().await;
+
+    // Every chunk reaches the handler, which parses the whole body.
+    let chunks = padded_account_request(1024 * 1024);
+    let sent = chunks.len();
+    let (body, read) = counted_body(chunks);
+    let response = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/accounts", Some(&api_key), body))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(read.load(Ordering::SeqCst), sent);
+
+    // Past the JSON extractor's limit the handler refuses the body itself,
+    // after reading only as much as the limit allows.
+    let chunks = padded_account_request(8 * 1024 * 1024);
+    let sent = chunks.len();
+    let (body, read) = counted_body(chunks);
+    let response = app
+        .oneshot(request(Method::POST, "/api/accounts", Some(&api_key), body))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(read.load(Ordering::SeqCst) < sent);
+}
@@ -0,0 +1,30 @@
+//! Setup shared by the HTTP integration tests.
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use http_body_util::BodyExt;
+use payments_hex::{PaymentService, inbound::HttpServer};
+use payments_repo::SqliteRepo;
+use tower::ServiceExt;
+
+/// A router over an in-memory database, and the live admin key bootstrapped
+/// on it.
+pub async fn test_app() -> (Router, String) {
+    let repo = SqliteRepo::new("sqlite::memory:").await.unwrap();
+    let app = HttpServer::with_rate_limit(PaymentService::new(repo), 1_000).router();
+
+    let bootstrap = Request::builder()
+        .method(Method::POST)
+        .uri("/api/bootstrap")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(r#"{"name": "admin"}"#))
+        .unwrap();
+    let response = app.clone().oneshot(bootstrap).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    (app, json["api_key"].as_str().unwrap().to_string())
+}
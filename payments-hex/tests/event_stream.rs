@@ -0,0 +1,177 @@
+//! Integration tests for the live event stream at `GET /api/stream`.
+//!
+//! Opens a stream, makes payments through the API and reads the Server-Sent
+//! Events frames that arrive.
+//!
+//! This test requires the `sqlite` feature flag.
+
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use http_body_util::BodyExt;
+use payments_types::OwnerId;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use common::test_app;
+
+async fn send(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    api_key: Option<&str>,
+    body: Value,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(key) = api_key {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", key));
+    }
+    let response = app
+        .clone()
+        .oneshot(builder.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn create_account(app: &Router, api_key: &str, name: &str) -> String {
+    let (status, json) = send(
+        app,
+        Method::POST,
+        "/api/accounts",
+        Some(api_key),
+        json!({"owner_id": OwnerId::DEFAULT, "name": name, "currency": "USD"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    json["id"].as_str().unwrap().to_string()
+}
+
+async fn deposit(app: &Router, api_key: &str, account_id: &str, amount: i64) {
+    let (status, _) = send(
+        app,
+        Method::POST,
+        "/api/transactions/deposit",
+        Some(api_key),
+        json!({"account_id": account_id, "amount": amount, "currency": "USD"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+/// Opens the stream, returning its body once the response headers arrive.
+async fn open_stream(app: &Router, api_key: &str, query: &str) -> Body {
+    let request = Request::builder()
+        .uri(format!("/api/stream{}", query))
+        .header(header::AUTHORIZATION, format!("Bearer {}", api_key))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+    response.into_body()
+}
+
+/// The next event on the stream as `(name, data)`.
+async fn next_event(body: &mut Body) -> (String, Value) {
+    let frame = tokio::time::timeout(Duration::from_secs(5), body.frame())
+        .await
+        .expect("no event within 5s")
+        .unwrap()
+        .unwrap();
+    let text = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name))
+            .unwrap_or_else(|| panic!("no {name} in {text:?}"))
+            .to_string()
+    };
+    (
+        field("event: "),
+        serde_json::from_str(&field("data: ")).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_admin_stream_receives_every_event() {
+    let (app, admin) = test_app().await;
+    let mut stream = open_stream(&app, &admin, "").await;
+
+    let account_id = create_account(&app, &admin, "Alice").await;
+    deposit(&app, &admin, &account_id, 500).await;
+
+    let (name, data) = next_event(&mut stream).await;
+    assert_eq!(name, "account.created");
+    assert_eq!(data["account_id"], account_id);
+    let (name, data) = next_event(&mut stream).await;
+    assert_eq!(name, "deposit.success");
+    assert_eq!(data["amount"], 500);
+}
+
+#[tokio::test]
+async fn test_scoped_stream_only_receives_its_accounts_events() {
+    let (app, admin) = test_app().await;
+    let mine = create_account(&app, &admin, "Mine").await;
+    let theirs = create_account(&app, &admin, "Theirs").await;
+    let (status, json) = send(
+        &app,
+        Method::POST,
+        "/api/keys",
+        Some(&admin),
+        json!({"name": "dashboard", "account_id": mine, "scopes": ["transactions:read"]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let scoped = json["api_key"].as_str().unwrap().to_string();
+
+    let mut stream = open_stream(&app, &scoped, "?events=deposit.*").await;
+    deposit(&app, &admin, &theirs, 100).await;
+    deposit(&app, &admin, &mine, 200).await;
+
+    let (name, data) = next_event(&mut stream).await;
+    assert_eq!(name, "deposit.success");
+    assert_eq!(data["amount"], 200);
+}
+
+#[tokio::test]
+async fn test_stream_refuses_invalid_patterns_and_missing_scope() {
+    let (app, admin) = test_app().await;
+    let (status, _) = send(
+        &app,
+        Method::GET,
+        "/api/stream?events=de*posit",
+        Some(&admin),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, json) = send(
+        &app,
+        Method::POST,
+        "/api/keys",
+        Some(&admin),
+        json!({"name": "writer", "scopes": ["accounts:write"]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let writer = json["api_key"].as_str().unwrap();
+    let (status, _) = send(&app, Method::GET, "/api/stream", Some(writer), Value::Null).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
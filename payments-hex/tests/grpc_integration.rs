@@ -0,0 +1,451 @@
+//! Integration tests for the gRPC adapter.
+//!
+//! These tests start a real gRPC server on a local port and call it through
+//! the generated clients, checking that it applies the same API keys,
+//! scopes and maintenance mode as the HTTP adapter.
+//!
+//! This test requires the `grpc` and `sqlite` feature flags.
+
+#![cfg(all(feature = "grpc", feature = "sqlite"))]
+
+use std::sync::Arc;
+
+use payments_hex::PaymentService;
+use payments_hex::inbound::{
+    GrpcServer, MaintenanceMode,
+    grpc::proto::{
+        self, account_service_client::AccountServiceClient,
+        transaction_service_client::TransactionServiceClient,
+        webhook_service_client::WebhookServiceClient,
+    },
+};
+use payments_repo::SqliteRepo;
+use payments_types::{AccountId, ApiKeyScope, Mode, OwnerId, TransactionRepository};
+use tonic::{Code, Request, transport::server::TcpIncoming};
+
+/// A running server and an API key with every scope.
+struct TestServer {
+    url: String,
+    service: Arc<PaymentService<SqliteRepo>>,
+    admin_key: String,
+}
+
+impl TestServer {
+    async fn start() -> Self {
+        Self::start_with(Arc::default()).await
+    }
+
+    async fn start_with(maintenance: Arc<MaintenanceMode>) -> Self {
+        let repo = SqliteRepo::new("sqlite::memory:").await.unwrap();
+        let service = Arc::new(PaymentService::new(repo));
+        let (_, admin_key) = service
+            .repo()
+            .create_api_key("admin", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let router = GrpcServer::new(service.clone())
+            .with_maintenance(maintenance)
+            .router();
+        tokio::spawn(router.serve_with_incoming(TcpIncoming::from(listener)));
+
+        Self {
+            url,
+            service,
+            admin_key,
+        }
+    }
+
+    async fn accounts(&self) -> AccountServiceClient<tonic::transport::Channel> {
+        AccountServiceClient::connect(self.url.clone())
+            .await
+            .unwrap()
+    }
+
+    async fn transactions(&self) -> TransactionServiceClient<tonic::transport::Channel> {
+        TransactionServiceClient::connect(self.url.clone())
+            .await
+            .unwrap()
+    }
+
+    async fn webhooks(&self) -> WebhookServiceClient<tonic::transport::Channel> {
+        WebhookServiceClient::connect(self.url.clone())
+            .await
+            .unwrap()
+    }
+
+    async fn create_account(&self, name: &str) -> proto::Account {
+        self.accounts()
+            .await
+            .create_account(authed(
+                &self.admin_key,
+                proto::CreateAccountRequest {
+                    name: name.into(),
+                    currency: "USD".into(),
+                    owner_id: OwnerId::DEFAULT.to_string(),
+                    mode: None,
+                },
+            ))
+            .await
+            .unwrap()
+            .into_inner()
+    }
+}
+
+/// `message` with `key` in the `authorization` metadata.
+fn authed<T>(key: &str, message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("authorization", format!("Bearer {}", key).parse().unwrap());
+    request
+}
+
+fn deposit(account_id: &str, amount: i64) -> proto::DepositRequest {
+    proto::DepositRequest {
+        account_id: account_id.into(),
+        amount,
+        currency: "USD".into(),
+        idempotency_key: None,
+        reference: None,
+        metadata: Default::default(),
+        encrypted_memo: None,
+    }
+}
+
+#[tokio::test]
+async fn test_accounts_and_payments_round_trip() {
+    let server = TestServer::start().await;
+    let alice = server.create_account("Alice").await;
+    let bob = server.create_account("Bob").await;
+    assert_eq!(alice.balance, 0);
+    assert_eq!(alice.status, "ACTIVE");
+
+    let mut transactions = server.transactions().await;
+    let deposited = transactions
+        .deposit(authed(&server.admin_key, deposit(&alice.id, 10_000)))
+        .await
+        .unwrap()
+        .into_inner();
+    let tx = deposited.transaction.unwrap();
+    assert_eq!(tx.transaction_type, "DEPOSIT");
+    assert_eq!(
+        tx.destination_account_id.as_deref(),
+        Some(alice.id.as_str())
+    );
+
+    transactions
+        .transfer(authed(
+            &server.admin_key,
+            proto::TransferRequest {
+                from_account_id: alice.id.clone(),
+                to_account_id: bob.id.clone(),
+                amount: 2_500,
+                currency: "USD".into(),
+                idempotency_key: None,
+                reference: Some("Rent".into()),
+                purpose_code: None,
+                convert_currency: false,
+                metadata: [("lease".to_string(), "L-7".to_string())].into(),
+                encrypted_memo: None,
+            },
+        ))
+        .await
+        .unwrap();
+
+    let mut accounts = server.accounts().await;
+    let alice = accounts
+        .get_account(authed(
+            &server.admin_key,
+            proto::GetAccountRequest { id: alice.id },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(alice.balance, 7_500);
+
+    let page = transactions
+        .list_transactions(authed(
+            &server.admin_key,
+            proto::ListTransactionsRequest {
+                account_id: alice.id.clone(),
+                limit: Some(1),
+                cursor: None,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(page.transactions.len(), 1);
+    assert_eq!(page.transactions[0].reference.as_deref(), Some("Rent"));
+    assert_eq!(page.transactions[0].metadata["lease"], "L-7");
+    assert!(page.next_cursor.is_some());
+
+    let listed = accounts
+        .list_accounts(authed(&server.admin_key, proto::ListAccountsRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.accounts.len(), 2);
+}
+
+#[tokio::test]
+async fn test_errors_map_to_status_codes() {
+    let server = TestServer::start().await;
+    let alice = server.create_account("Alice").await;
+    let mut transactions = server.transactions().await;
+
+    let overdrawn = transactions
+        .withdraw(authed(
+            &server.admin_key,
+            proto::WithdrawRequest {
+                account_id: alice.id.clone(),
+                amount: 100,
+                currency: "USD".into(),
+                idempotency_key: None,
+                reference: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(overdrawn.code(), Code::FailedPrecondition);
+
+    let unknown = transactions
+        .deposit(authed(
+            &server.admin_key,
+            deposit(&AccountId::new().to_string(), 100),
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(unknown.code(), Code::NotFound);
+
+    let invalid = transactions
+        .deposit(authed(&server.admin_key, deposit("not-an-id", 100)))
+        .await
+        .unwrap_err();
+    assert_eq!(invalid.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_api_keys_and_scopes_are_enforced() {
+    let server = TestServer::start().await;
+    let alice = server.create_account("Alice").await;
+    let bob = server.create_account("Bob").await;
+    let mut transactions = server.transactions().await;
+
+    let missing = transactions
+        .deposit(Request::new(deposit(&alice.id, 100)))
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), Code::Unauthenticated);
+    let invalid = transactions
+        .deposit(authed("sk_not_a_key", deposit(&alice.id, 100)))
+        .await
+        .unwrap_err();
+    assert_eq!(invalid.code(), Code::Unauthenticated);
+
+    let (_, read_only) = server
+        .service
+        .repo()
+        .create_api_key("reader", &[ApiKeyScope::TransactionsRead], Mode::Live)
+        .await
+        .unwrap();
+    let denied = transactions
+        .deposit(authed(&read_only, deposit(&alice.id, 100)))
+        .await
+        .unwrap_err();
+    assert_eq!(denied.code(), Code::PermissionDenied);
+    assert!(denied.message().contains("transactions:write"));
+
+    // A key scoped to Alice's account cannot touch Bob's
+    let (_, alice_key) = server
+        .service
+        .repo()
+        .create_scoped_api_key(
+            "alice",
+            alice.id.parse().unwrap(),
+            &ApiKeyScope::ALL,
+            Mode::Live,
+        )
+        .await
+        .unwrap();
+    transactions
+        .deposit(authed(&alice_key, deposit(&alice.id, 100)))
+        .await
+        .unwrap();
+    let other = transactions
+        .deposit(authed(&alice_key, deposit(&bob.id, 100)))
+        .await
+        .unwrap_err();
+    assert_eq!(other.code(), Code::PermissionDenied);
+
+    let listed = server
+        .accounts()
+        .await
+        .list_accounts(authed(&alice_key, proto::ListAccountsRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.accounts.len(), 1);
+    assert_eq!(listed.accounts[0].id, alice.id);
+}
+
+#[tokio::test]
+async fn test_accounts_are_opened_in_the_mode_of_the_key() {
+    let server = TestServer::start().await;
+    let live = server.create_account("Alice").await;
+    assert_eq!(live.mode, "live");
+
+    let (_, test_key) = server
+        .service
+        .repo()
+        .create_api_key("test", &ApiKeyScope::ALL, Mode::Test)
+        .await
+        .unwrap();
+    let mut accounts = server.accounts().await;
+    let request = |key: &str, mode: Option<&str>| {
+        authed(
+            key,
+            proto::CreateAccountRequest {
+                name: "Bob".into(),
+                currency: String::new(),
+                owner_id: OwnerId::DEFAULT.to_string(),
+                mode: mode.map(Into::into),
+            },
+        )
+    };
+    let test = accounts
+        .create_account(request(&test_key, None))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(test.mode, "test");
+
+    let denied = accounts
+        .create_account(request(&server.admin_key, Some("test")))
+        .await
+        .unwrap_err();
+    assert_eq!(denied.code(), Code::PermissionDenied);
+    let invalid = accounts
+        .create_account(request(&test_key, Some("staging")))
+        .await
+        .unwrap_err();
+    assert_eq!(invalid.code(), Code::InvalidArgument);
+
+    let listed = accounts
+        .list_accounts(authed(&test_key, proto::ListAccountsRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.accounts.len(), 1);
+    assert_eq!(listed.accounts[0].id, test.id);
+}
+
+#[tokio::test]
+async fn test_maintenance_mode_rejects_writes() {
+    let maintenance = Arc::new(MaintenanceMode::default());
+    let server = TestServer::start_with(maintenance.clone()).await;
+    let alice = server.create_account("Alice").await;
+
+    maintenance.enable(Some("Database upgrade".into()));
+    let mut accounts = server.accounts().await;
+    let rejected = accounts
+        .create_account(authed(
+            &server.admin_key,
+            proto::CreateAccountRequest {
+                name: "Bob".into(),
+                currency: String::new(),
+                owner_id: OwnerId::DEFAULT.to_string(),
+                mode: None,
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(rejected.code(), Code::Unavailable);
+    assert!(rejected.message().contains("Database upgrade"));
+
+    // Reads keep working
+    accounts
+        .get_account(authed(
+            &server.admin_key,
+            proto::GetAccountRequest { id: alice.id },
+        ))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_register_and_list_webhooks() {
+    let server = TestServer::start().await;
+    let mut webhooks = server.webhooks().await;
+
+    let registered = webhooks
+        .register_webhook(authed(
+            &server.admin_key,
+            proto::RegisterWebhookRequest {
+                url: "https://example.com/hook".into(),
+                events: vec!["transaction.created".into()],
+                timeout_ms: Some(5_000),
+                connect_timeout_ms: None,
+                signature_algorithm: None,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(registered.is_active);
+    assert_eq!(registered.timeout_ms, 5_000);
+    assert!(!registered.secret.is_empty());
+    assert_eq!(registered.signature_algorithm, "hmac-sha256");
+    assert_eq!(registered.public_key, None);
+
+    let ed25519 = webhooks
+        .register_webhook(authed(
+            &server.admin_key,
+            proto::RegisterWebhookRequest {
+                url: "https://example.com/signed".into(),
+                signature_algorithm: Some("ed25519".into()),
+                ..Default::default()
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(ed25519.signature_algorithm, "ed25519");
+    assert_eq!(ed25519.public_key.map(|key| key.len()), Some(64));
+
+    let listed = webhooks
+        .list_webhooks(authed(&server.admin_key, proto::ListWebhooksRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.webhooks.len(), 2);
+    assert!(listed.webhooks.iter().any(|w| w.id == registered.id));
+
+    let empty_url = webhooks
+        .register_webhook(authed(
+            &server.admin_key,
+            proto::RegisterWebhookRequest::default(),
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(empty_url.code(), Code::InvalidArgument);
+
+    let unknown_algorithm = webhooks
+        .register_webhook(authed(
+            &server.admin_key,
+            proto::RegisterWebhookRequest {
+                url: "https://example.com/hook".into(),
+                signature_algorithm: Some("md5".into()),
+                ..Default::default()
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(unknown_algorithm.code(), Code::InvalidArgument);
+}
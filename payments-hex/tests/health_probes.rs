@@ -0,0 +1,57 @@
+//! Integration tests for the liveness and readiness probes.
+//!
+//! This test requires the `sqlite` feature flag.
+
+#![cfg(feature = "sqlite")]
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use payments_hex::{PaymentService, inbound::HttpServer};
+use payments_repo::SqliteRepo;
+use tower::ServiceExt;
+
+/// Sends an unauthenticated GET to `path`, returning its status and body.
+async fn probe(app: &Router, path: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_readiness_follows_the_database_and_liveness_does_not() {
+    let repo = SqliteRepo::new("sqlite::memory:").await.unwrap();
+    let pool = repo.pool().clone();
+    let app = HttpServer::with_rate_limit(PaymentService::new(repo), 1_000).router();
+
+    for path in ["/health/ready", "/readyz"] {
+        let (status, body) = probe(&app, path).await;
+        assert_eq!(status, StatusCode::OK, "{path}");
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["checks"][0]["name"], "database");
+        assert_eq!(body["checks"][0]["status"], "up");
+        assert!(body["checks"][0]["latency_ms"].as_f64().unwrap() >= 0.0);
+    }
+
+    pool.close().await;
+
+    for path in ["/health/ready", "/readyz"] {
+        let (status, body) = probe(&app, path).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{path}");
+        assert_eq!(body["status"], "not_ready");
+        assert_eq!(body["checks"][0]["status"], "down");
+    }
+    for path in ["/health/live", "/health"] {
+        let (status, body) = probe(&app, path).await;
+        assert_eq!(status, StatusCode::OK, "{path}");
+        assert_eq!(body["status"], "healthy");
+    }
+}
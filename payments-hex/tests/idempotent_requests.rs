@@ -0,0 +1,370 @@
+//! Integration tests for replaying POST requests sent with an
+//! `Idempotency-Key` header.
+//!
+//! This test requires the `sqlite` feature flag.
+
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use chrono::Utc;
+use http_body_util::BodyExt;
+use payments_hex::{PaymentService, inbound::HttpServer};
+use payments_repo::SqliteRepo;
+use payments_types::{
+    ApiKeyScope, IDEMPOTENCY_RESERVATION_TTL, IdempotencyRecord, IdempotencyStore, Mode, RepoError,
+    TransactionRepository,
+};
+use serde_json::Value;
+use tokio::sync::Notify;
+use tower::ServiceExt;
+
+use common::test_app;
+
+/// Idempotency records kept in memory. Once armed, the request making the
+/// next reservation stalls right after storing it.
+#[derive(Default)]
+struct StallingStore {
+    records: Mutex<HashMap<(String, String), IdempotencyRecord>>,
+    stall: AtomicBool,
+    reserved: Notify,
+}
+
+#[async_trait]
+impl IdempotencyStore for StallingStore {
+    async fn get(&self, endpoint: &str, key: &str) -> Result<Option<IdempotencyRecord>, RepoError> {
+        let records = self.records.lock().unwrap();
+        Ok(records
+            .get(&(endpoint.to_string(), key.to_string()))
+            .cloned())
+    }
+
+    async fn save(&self, record: &IdempotencyRecord) -> Result<IdempotencyRecord, RepoError> {
+        let stored = self
+            .records
+            .lock()
+            .unwrap()
+            .entry((record.endpoint.clone(), record.key.clone()))
+            .or_insert_with(|| record.clone())
+            .clone();
+        if self.stall.swap(false, Ordering::SeqCst) {
+            self.reserved.notify_one();
+            std::future::pending::<()>().await;
+        }
+        Ok(stored)
+    }
+
+    async fn replace(&self, record: &IdempotencyRecord) -> Result<(), RepoError> {
+        self.records.lock().unwrap().insert(
+            (record.endpoint.clone(), record.key.clone()),
+            record.clone(),
+        );
+        Ok(())
+    }
+
+    async fn remove(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        self.records
+            .lock()
+            .unwrap()
+            .remove(&(endpoint.to_string(), key.to_string()));
+        Ok(())
+    }
+}
+
+/// Sends `request`, returning its status, whether it was replayed and its body.
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, bool, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let replayed = response.headers().get("idempotent-replayed").is_some();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    (status, replayed, body)
+}
+
+fn post(uri: &str, api_key: &str, idempotency_key: Option<&str>, body: &str) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", api_key))
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(key) = idempotency_key {
+        builder = builder.header("Idempotency-Key", key);
+    }
+    builder.body(Body::from(body.to_string())).unwrap()
+}
+
+async fn owner_count(app: &Router, api_key: &str) -> usize {
+    let request = Request::get("/api/owners")
+        .header(header::AUTHORIZATION, format!("Bearer {}", api_key))
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, body) = send(app, request).await;
+    assert_eq!(status, StatusCode::OK);
+    body.as_array().unwrap().len()
+}
+
+#[tokio::test]
+async fn test_retry_replays_the_first_response() {
+    let (app, api_key) = test_app().await;
+    let owners = owner_count(&app, &api_key).await;
+
+    let (status, replayed, first) = send(
+        &app,
+        post(
+            "/api/owners",
+            &api_key,
+            Some("owner-1"),
+            r#"{"name": "Acme Ltd"}"#,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+
+    // The same document, formatted differently, is the same request.
+    let (status, replayed, second) = send(
+        &app,
+        post(
+            "/api/owners",
+            &api_key,
+            Some("owner-1"),
+            "{\n  \"name\": \"Acme Ltd\"\n}",
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(replayed);
+    assert_eq!(second, first);
+    assert_eq!(owner_count(&app, &api_key).await, owners + 1);
+
+    // Without the header every request is made.
+    send(
+        &app,
+        post("/api/owners", &api_key, None, r#"{"name": "Acme Ltd"}"#),
+    )
+    .await;
+    assert_eq!(owner_count(&app, &api_key).await, owners + 2);
+}
+
+#[tokio::test]
+async fn test_reused_key_with_another_body_conflicts() {
+    let (app, api_key) = test_app().await;
+    let owners = owner_count(&app, &api_key).await;
+
+    send(
+        &app,
+        post(
+            "/api/owners",
+            &api_key,
+            Some("owner-1"),
+            r#"{"name": "Acme Ltd"}"#,
+        ),
+    )
+    .await;
+    let (status, _, body) = send(
+        &app,
+        post(
+            "/api/owners",
+            &api_key,
+            Some("owner-1"),
+            r#"{"name": "Other Ltd"}"#,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error_code"], "IDEMPOTENCY_KEY_CONFLICT");
+    assert_eq!(owner_count(&app, &api_key).await, owners + 1);
+}
+
+#[tokio::test]
+async fn test_failures_are_not_stored() {
+    let (app, api_key) = test_app().await;
+
+    let (status, _, _) = send(
+        &app,
+        post("/api/owners", &api_key, Some("owner-1"), r#"{"name": ""}"#),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Corrected and resent under the same key, the request is made.
+    let (status, replayed, _) = send(
+        &app,
+        post(
+            "/api/owners",
+            &api_key,
+            Some("owner-1"),
+            r#"{"name": "Acme Ltd"}"#,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+}
+
+#[tokio::test]
+async fn test_keys_are_scoped_to_the_api_key_and_route() {
+    let (app, api_key) = test_app().await;
+    let (status, _, created) = send(
+        &app,
+        post("/api/keys", &api_key, None, r#"{"name": "second"}"#),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let other_key = created["api_key"].as_str().unwrap();
+    let owners = owner_count(&app, &api_key).await;
+
+    let body = r#"{"name": "Acme Ltd"}"#;
+    let (_, _, owner) = send(&app, post("/api/owners", &api_key, Some("shared"), body)).await;
+    let (status, replayed, _) =
+        send(&app, post("/api/owners", other_key, Some("shared"), body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+    assert_eq!(owner_count(&app, &api_key).await, owners + 2);
+
+    let (status, replayed, _) = send(
+        &app,
+        post(
+            "/api/accounts",
+            &api_key,
+            Some("shared"),
+            &format!(
+                r#"{{"owner_id": "{}", "name": "Main", "currency": "USD"}}"#,
+                owner["id"].as_str().unwrap()
+            ),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+}
+
+#[tokio::test]
+async fn test_credentials_are_not_replayed() {
+    let (app, api_key) = test_app().await;
+    let body = r#"{"name": "partner"}"#;
+
+    let (status, replayed, first) =
+        send(&app, post("/api/keys", &api_key, Some("key-1"), body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+
+    // The raw key is never stored, so a retry is answered by the handler.
+    let (status, replayed, second) =
+        send(&app, post("/api/keys", &api_key, Some("key-1"), body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+    assert_ne!(second["api_key"], first["api_key"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_retries_make_the_request_once() {
+    let (app, api_key) = test_app().await;
+    let owners = owner_count(&app, &api_key).await;
+
+    let mut requests = tokio::task::JoinSet::new();
+    for _ in 0..8 {
+        let app = app.clone();
+        let request = post(
+            "/api/owners",
+            &api_key,
+            Some("owner-1"),
+            r#"{"name": "Acme Ltd"}"#,
+        );
+        requests.spawn(async move { send(&app, request).await });
+    }
+    let mut made = 0;
+    while let Some(result) = requests.join_next().await {
+        let (status, replayed, body) = result.unwrap();
+        match status {
+            StatusCode::CREATED if !replayed => made += 1,
+            StatusCode::CREATED => {}
+            StatusCode::CONFLICT => assert_eq!(body["error_code"], "CONFLICT"),
+            status => panic!("unexpected status {}", status),
+        }
+    }
+    assert_eq!(made, 1);
+    assert_eq!(owner_count(&app, &api_key).await, owners + 1);
+}
+
+#[tokio::test]
+async fn test_dropped_request_gives_up_its_key() {
+    let store = Arc::new(StallingStore::default());
+    let repo = SqliteRepo::new("sqlite::memory:").await.unwrap();
+    let service = PaymentService::builder(repo)
+        .with_idempotency_store(store.clone())
+        .build();
+    let (_, api_key) = service
+        .repo()
+        .create_api_key("admin", &ApiKeyScope::ALL, Mode::Live)
+        .await
+        .unwrap();
+    let app = HttpServer::with_rate_limit(service, 1_000).router();
+    let body = r#"{"name": "Acme Ltd"}"#;
+
+    // The first request is dropped while it holds the key, as happens when
+    // the client disconnects.
+    store.stall.store(true, Ordering::SeqCst);
+    let request = post("/api/owners", &api_key, Some("owner-1"), body);
+    let first = tokio::spawn({
+        let app = app.clone();
+        async move { send(&app, request).await }
+    });
+    store.reserved.notified().await;
+    first.abort();
+    assert!(first.await.unwrap_err().is_cancelled());
+
+    for _ in 0..100 {
+        if store.records.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let (status, replayed, _) =
+        send(&app, post("/api/owners", &api_key, Some("owner-1"), body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+}
+
+#[tokio::test]
+async fn test_lapsed_reservation_is_taken_over() {
+    let repo = SqliteRepo::new("sqlite::memory:").await.unwrap();
+    let server = HttpServer::with_rate_limit(PaymentService::new(repo), 1_000);
+    let service = server.service();
+    let app = server.router();
+    let (key, api_key) = service
+        .repo()
+        .create_api_key("admin", &ApiKeyScope::ALL, Mode::Live)
+        .await
+        .unwrap();
+    let body = r#"{"name": "Acme Ltd"}"#;
+
+    // Left behind by a request on a server that went away
+    let abandoned = IdempotencyRecord::in_progress(
+        "POST /api/owners",
+        format!("{}/owner-1", key.id),
+        "abandoned",
+        Utc::now() - IDEMPOTENCY_RESERVATION_TTL,
+    );
+    service.save_idempotency_record(&abandoned).await.unwrap();
+
+    let (status, replayed, _) =
+        send(&app, post("/api/owners", &api_key, Some("owner-1"), body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+    let (status, replayed, _) =
+        send(&app, post("/api/owners", &api_key, Some("owner-1"), body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(replayed);
+}
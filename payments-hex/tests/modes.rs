@@ -0,0 +1,394 @@
+//! Integration tests for test and live API keys, and the accounts, webhooks
+//! and events each can see.
+//!
+//! This test requires the `sqlite` feature flag.
+
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use http_body_util::BodyExt;
+use payments_types::OwnerId;
+use serde_json::Value;
+use tower::ServiceExt;
+
+/// A server with a live admin key and a test admin key.
+async fn test_app() -> (Router, String, String) {
+    let (app, live_key) = common::test_app().await;
+
+    let (status, _, body) = send(
+        &app,
+        request(
+            Method::POST,
+            "/api/keys",
+            &live_key,
+            r#"{"name": "test-key", "mode": "test", "scopes": ["accounts:read", "accounts:write", "transactions:read", "transactions:write", "webhooks:read", "webhooks:write", "keys:admin"]}"#,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let test_key = body["api_key"].as_str().unwrap().to_string();
+    (app, live_key, test_key)
+}
+
+/// Sends `request`, returning its status, `X-Payments-Mode` header and body.
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Option<String>, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let mode = response
+        .headers()
+        .get("x-payments-mode")
+        .map(|mode| mode.to_str().unwrap().to_string());
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    (status, mode, body)
+}
+
+fn request(method: Method, uri: &str, api_key: &str, body: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", api_key))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+async fn create_account(app: &Router, api_key: &str, name: &str) -> String {
+    let (status, _, body) = send(
+        app,
+        request(
+            Method::POST,
+            "/api/accounts",
+            api_key,
+            &format!(
+                r#"{{"owner_id": "{}", "name": "{}", "currency": "USD"}}"#,
+                OwnerId::DEFAULT,
+                name
+            ),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["id"].as_str().unwrap().to_string()
+}
+
+async fn account_ids(app: &Router, api_key: &str) -> Vec<String> {
+    let (status, _, body) = send(app, request(Method::GET, "/api/accounts", api_key, "")).await;
+    assert_eq!(status, StatusCode::OK);
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|account| account["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_keys_carry_their_mode_in_prefix_and_header() {
+    let (app, live_key, test_key) = test_app().await;
+    assert!(live_key.starts_with("sk_live_"));
+    assert!(test_key.starts_with("sk_test_"));
+
+    let (_, mode, _) = send(&app, request(Method::GET, "/api/accounts", &live_key, "")).await;
+    assert_eq!(mode.as_deref(), Some("live"));
+    let (_, mode, _) = send(&app, request(Method::GET, "/api/accounts", &test_key, "")).await;
+    assert_eq!(mode.as_deref(), Some("test"));
+
+    let (status, _, keys) = send(&app, request(Method::GET, "/api/keys", &test_key, "")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(keys.as_array().unwrap().len(), 1);
+    assert_eq!(keys[0]["mode"], "test");
+}
+
+#[tokio::test]
+async fn test_each_mode_only_sees_its_own_accounts() {
+    let (app, live_key, test_key) = test_app().await;
+    let live = create_account(&app, &live_key, "Live").await;
+    let test = create_account(&app, &test_key, "Test").await;
+
+    let (_, _, account) = send(
+        &app,
+        request(
+            Method::GET,
+            &format!("/api/accounts/{}", test),
+            &test_key,
+            "",
+        ),
+    )
+    .await;
+    assert_eq!(account["mode"], "test");
+    assert!(account_ids(&app, &test_key).await.contains(&test));
+    assert!(!account_ids(&app, &test_key).await.contains(&live));
+    assert!(!account_ids(&app, &live_key).await.contains(&test));
+
+    let (status, _, _) = send(
+        &app,
+        request(
+            Method::GET,
+            &format!("/api/accounts/{}", live),
+            &test_key,
+            "",
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = send(
+        &app,
+        request(
+            Method::POST,
+            "/api/transactions/deposit",
+            &test_key,
+            &format!(
+                r#"{{"account_id": "{}", "amount": 100, "currency": "USD"}}"#,
+                live
+            ),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_money_does_not_move_between_modes() {
+    let (app, live_key, test_key) = test_app().await;
+    let live = create_account(&app, &live_key, "Live").await;
+    let test = create_account(&app, &test_key, "Test").await;
+    let (status, _, _) = send(
+        &app,
+        request(
+            Method::POST,
+            "/api/transactions/deposit",
+            &test_key,
+            &format!(
+                r#"{{"account_id": "{}", "amount": 1000, "currency": "USD"}}"#,
+                test
+            ),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _, _) = send(
+        &app,
+        request(
+            Method::POST,
+            "/api/transactions/transfer",
+            &test_key,
+            &format!(
+                r#"{{"from_account_id": "{}", "to_account_id": "{}", "amount": 100, "currency": "USD"}}"#,
+                test, live
+            ),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Searches only turn up the transactions of the caller's mode.
+    let search = "/api/transactions/search?currency=USD";
+    let (_, _, page) = send(&app, request(Method::GET, search, &test_key, "")).await;
+    assert_eq!(page["data"].as_array().unwrap().len(), 1);
+    let (_, _, page) = send(&app, request(Method::GET, search, &live_key, "")).await;
+    assert!(page["data"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_test_keys_cannot_reach_live_mode() {
+    let (app, _, test_key) = test_app().await;
+
+    let (status, _, _) = send(
+        &app,
+        request(
+            Method::POST,
+            "/api/keys",
+            &test_key,
+            r#"{"name": "escalate", "mode": "live"}"#,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _, _) = send(
+        &app,
+        request(
+            Method::POST,
+            "/api/accounts",
+            &test_key,
+            &format!(
+                r#"{{"owner_id": "{}", "name": "Live", "currency": "USD", "mode": "live"}}"#,
+                OwnerId::DEFAULT
+            ),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+async fn register_webhook(app: &Router, api_key: &str, url: &str) -> String {
+    let (status, _, body) = send(
+        app,
+        request(
+            Method::POST,
+            "/api/webhooks",
+            api_key,
+            &format!(r#"{{"url": "{}", "events": []}}"#, url),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_webhooks_and_event_log_stay_in_their_mode() {
+    let (app, live_key, test_key) = test_app().await;
+    let live = register_webhook(&app, &live_key, "https://example.com/live").await;
+    let test = register_webhook(&app, &test_key, "https://example.com/test").await;
+    create_account(&app, &live_key, "Live").await;
+    create_account(&app, &test_key, "Test").await;
+
+    let (status, _, webhooks) =
+        send(&app, request(Method::GET, "/api/webhooks", &test_key, "")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(webhooks.as_array().unwrap().len(), 1);
+    assert_eq!(webhooks[0]["id"], test.as_str());
+    assert_eq!(webhooks[0]["mode"], "test");
+    let (status, _, _) = send(
+        &app,
+        request(
+            Method::GET,
+            &format!("/api/webhooks/{}/deliveries", live),
+            &test_key,
+            "",
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = send(
+        &app,
+        request(
+            Method::DELETE,
+            &format!("/api/webhooks/{}", live),
+            &test_key,
+            "",
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Each endpoint only gets its own mode's account.created event.
+    for (key, id) in [(&live_key, &live), (&test_key, &test)] {
+        let (_, _, deliveries) = send(
+            &app,
+            request(
+                Method::GET,
+                &format!("/api/webhooks/{}/deliveries", id),
+                key,
+                "",
+            ),
+        )
+        .await;
+        assert_eq!(deliveries.as_array().unwrap().len(), 1);
+    }
+
+    // The event log is newline-delimited JSON.
+    let response = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/exports/events", &test_key, ""))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let events: Vec<Value> = std::str::from_utf8(&body)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(!events.is_empty());
+    assert!(events.iter().all(|event| event["mode"] == "test"));
+}
+
+#[tokio::test]
+async fn test_reports_exports_and_settlement_stay_in_their_mode() {
+    let (app, live_key, test_key) = test_app().await;
+    let live = create_account(&app, &live_key, "Live").await;
+    create_account(&app, &test_key, "Test").await;
+    for (path, amount) in [("deposit", 1000), ("withdraw", 300)] {
+        let (status, _, _) = send(
+            &app,
+            request(
+                Method::POST,
+                &format!("/api/transactions/{}", path),
+                &live_key,
+                &format!(
+                    r#"{{"account_id": "{}", "amount": {}, "currency": "USD"}}"#,
+                    live, amount
+                ),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let exposure = "/api/reports/exposure?base=USD";
+    let (_, _, report) = send(&app, request(Method::GET, exposure, &test_key, "")).await;
+    assert_eq!(report["total"], 0);
+    let (_, _, report) = send(&app, request(Method::GET, exposure, &live_key, "")).await;
+    assert_eq!(report["total"], 700);
+
+    // The live payout is not the test key's to settle.
+    let (status, _, _) = send(
+        &app,
+        request(Method::POST, "/api/settlement-batches", &test_key, "{}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _, batch) = send(
+        &app,
+        request(Method::POST, "/api/settlement-batches", &live_key, "{}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(batch["mode"], "live");
+    let batch_path = format!("/api/settlement-batches/{}", batch["id"].as_str().unwrap());
+    let (status, _, _) = send(&app, request(Method::GET, &batch_path, &test_key, "")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, _, batches) = send(
+        &app,
+        request(Method::GET, "/api/settlement-batches", &test_key, ""),
+    )
+    .await;
+    assert!(batches.as_array().unwrap().is_empty());
+
+    let (status, _, export) = send(
+        &app,
+        request(
+            Method::POST,
+            "/api/exports",
+            &live_key,
+            r#"{"kind": "journal", "from": "2026-01-01", "to": "2026-01-31"}"#,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let export_path = format!("/api/exports/{}", export["id"].as_str().unwrap());
+    let (status, _, _) = send(&app, request(Method::GET, &export_path, &test_key, "")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Storage quotas count both modes' rows.
+    let (status, _, _) = send(
+        &app,
+        request(Method::GET, "/api/admin/storage", &test_key, ""),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _, _) = send(
+        &app,
+        request(Method::GET, "/api/admin/storage", &live_key, ""),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
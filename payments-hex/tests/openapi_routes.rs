@@ -0,0 +1,238 @@
+//! Checks the OpenAPI spec against the routes the HTTP server really serves.
+//!
+//! Every documented operation is sent to the router with an admin key. The
+//! router answers paths it has no route for with an empty 404, and methods a
+//! path does not take with an empty 405; handlers always send a body with
+//! their errors, so an empty 404 or 405 means the spec documents a route
+//! that does not exist.
+//!
+//! The other way round, Axum cannot list a router's routes, so the
+//! `.route(...)` calls are read from the server's source and each must be
+//! documented.
+//!
+//! This test requires the `sqlite` feature flag.
+
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use http_body_util::BodyExt;
+use payments_hex::ApiDoc;
+use tower::ServiceExt;
+use utoipa::OpenApi;
+
+use common::test_app;
+
+/// `path` with each `{param}` filled in with a value of the right shape.
+fn concrete_path(path: &str) -> String {
+    let mut concrete = String::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}').unwrap() + start;
+        concrete.push_str(&rest[..start]);
+        concrete.push_str(match &rest[start + 1..end] {
+            "base" => "USD",
+            _ => "00000000-0000-0000-0000-000000000000",
+        });
+        rest = &rest[end + 1..];
+    }
+    concrete.push_str(rest);
+    concrete
+}
+
+/// Whether the router answered as it does for a route it does not have.
+async fn is_unrouted(app: &Router, api_key: &str, method: Method, path: &str) -> bool {
+    let request = Request::builder()
+        .method(method)
+        .uri(path)
+        .header(header::AUTHORIZATION, format!("Bearer {}", api_key))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from("{}"))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    if !matches!(
+        response.status(),
+        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+    ) {
+        return false;
+    }
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    body.is_empty()
+}
+
+#[tokio::test]
+async fn test_every_documented_operation_is_routed() {
+    let (app, api_key) = test_app().await;
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+    let mut unrouted = Vec::new();
+    for (path, item) in spec["paths"].as_object().unwrap() {
+        for method in item.as_object().unwrap().keys() {
+            let method: Method = method.to_uppercase().parse().unwrap();
+            if is_unrouted(&app, &api_key, method.clone(), &concrete_path(path)).await {
+                unrouted.push(format!("{} {}", method, path));
+            }
+        }
+    }
+    assert!(
+        unrouted.is_empty(),
+        "documented but not routed: {unrouted:?}"
+    );
+
+    // The probe itself tells routes apart.
+    assert!(is_unrouted(&app, &api_key, Method::GET, "/api/not-a-route").await);
+    assert!(is_unrouted(&app, &api_key, Method::DELETE, "/api/transactions/deposit").await);
+    assert!(!is_unrouted(&app, &api_key, Method::GET, "/api/accounts").await);
+}
+
+/// Reads `src/{path}` of this crate.
+fn source(path: &str) -> String {
+    std::fs::read_to_string(format!("{}/src/{}", env!("CARGO_MANIFEST_DIR"), path)).unwrap()
+}
+
+/// The value of the string constant `name` declared anywhere in `src/`.
+fn path_constant(name: &str) -> String {
+    let declaration = format!("pub const {}: &str = \"", name);
+    let mut dirs = vec![std::path::PathBuf::from(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src"
+    ))];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let code = std::fs::read_to_string(&path).unwrap();
+            if let Some(start) = code.find(&declaration) {
+                let value = &code[start + declaration.len()..];
+                return value[..value.find('"').unwrap()].to_string();
+            }
+        }
+    }
+    panic!("no path constant {name}");
+}
+
+/// `METHOD path` of every route the HTTP server registers.
+fn routed_operations() -> BTreeSet<String> {
+    let code = source("inbound/server.rs");
+    let mut operations = BTreeSet::new();
+    for call in code.split(".route(").skip(1) {
+        // The call ends at the parenthesis that closes `.route(`.
+        let mut depth = 1;
+        let end = call
+            .char_indices()
+            .find(|&(_, c)| {
+                depth += match c {
+                    '(' => 1,
+                    ')' => -1,
+                    _ => 0,
+                };
+                depth == 0
+            })
+            .unwrap()
+            .0;
+        let (path, handlers) = call[..end].split_once(',').unwrap();
+        let path = path.trim();
+        let path = match path.strip_prefix('"') {
+            Some(literal) => literal.trim_end_matches('"').to_string(),
+            None => path_constant(path),
+        };
+        for method in ["get", "post", "put", "patch", "delete"] {
+            let routed = handlers
+                .match_indices(&format!("{method}("))
+                .any(|(at, _)| {
+                    !handlers[..at].ends_with(|c: char| c.is_alphanumeric() || c == '_')
+                });
+            if routed {
+                operations.insert(format!("{} {}", method.to_uppercase(), path));
+            }
+        }
+    }
+    operations
+}
+
+#[test]
+fn test_every_routed_operation_is_documented() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let routed = routed_operations();
+
+    let undocumented: Vec<&String> = routed
+        .iter()
+        .filter(|operation| {
+            let (method, path) = operation.split_once(' ').unwrap();
+            !spec["paths"][path][method.to_lowercase()].is_object()
+        })
+        .collect();
+    assert!(
+        undocumented.is_empty(),
+        "routed but not documented: {undocumented:?}"
+    );
+
+    // The source reader finds literal, constant and chained routes.
+    for operation in [
+        "POST /api/keys",
+        "GET /api/stream",
+        "PATCH /api/owners/{id}",
+        "DELETE /api/webhooks/{id}",
+        "GET /health/live",
+    ] {
+        assert!(routed.contains(operation), "{operation}");
+    }
+}
+
+#[test]
+fn test_error_responses_share_the_error_response_schema() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(
+        schemas["ErrorResponse"]["$ref"],
+        "#/components/schemas/ProblemDetails"
+    );
+    let required = schemas["ProblemDetails"]["required"].as_array().unwrap();
+    for field in ["error", "code", "error_code"] {
+        assert!(required.contains(&field.into()), "{field}");
+    }
+
+    let mut errors = 0;
+    for (path, item) in spec["paths"].as_object().unwrap() {
+        for (method, operation) in item.as_object().unwrap() {
+            for (status, response) in operation["responses"].as_object().unwrap() {
+                let is_error = status.starts_with('4') || status.starts_with('5');
+                // The readiness probe answers 503 with its own report
+                if !is_error || ["/health/ready", "/readyz"].contains(&path.as_str()) {
+                    continue;
+                }
+                assert_eq!(
+                    response["content"]["application/json"]["schema"]["$ref"],
+                    "#/components/schemas/ErrorResponse",
+                    "{method} {path} {status}"
+                );
+                errors += 1;
+            }
+        }
+    }
+    assert!(errors > 0);
+}
+
+#[test]
+fn test_spec_documents_exchange_and_history_routes() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let paths = &spec["paths"];
+    for (path, method) in [
+        ("/api/rates/{base}", "get"),
+        ("/api/convert", "post"),
+        ("/api/accounts/{id}/transactions", "get"),
+        ("/api/transactions/search", "get"),
+    ] {
+        assert!(paths[path][method].is_object(), "{method} {path}");
+    }
+}
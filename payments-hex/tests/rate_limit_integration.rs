@@ -32,6 +32,14 @@ fn health_request() -> Request<Body> {
         .unwrap()
 }
 
+/// Helper to make a public status page request.
+fn status_request() -> Request<Body> {
+    Request::builder()
+        .uri("/status")
+        .body(Body::empty())
+        .unwrap()
+}
+
 /// Helper to bootstrap and get API key.
 fn bootstrap_request() -> Request<Body> {
     Request::builder()
@@ -91,6 +99,14 @@ async fn test_rate_limiting_returns_429_when_exceeded() {
         "Request should be rate limited after exceeding quota"
     );
 
+    // The quota is 3 per minute, so the next request frees up within a minute
+    let retry_after: u64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(retry_after, 60);
+
     // Verify the response body contains the expected error
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
@@ -101,7 +117,7 @@ async fn test_rate_limiting_returns_429_when_exceeded() {
             .unwrap()
             .contains("Rate limit exceeded")
     );
-    assert_eq!(json["retry_after_seconds"], 60);
+    assert_eq!(json["retry_after_seconds"], retry_after);
 }
 
 #[tokio::test]
@@ -123,6 +139,159 @@ async fn test_rate_limiting_health_endpoint_bypassed() {
     }
 }
 
+#[tokio::test]
+async fn test_status_endpoint_has_its_own_limit() {
+    let server = create_test_server(2).await;
+    let app = server.router();
+    let api_key = bootstrap_api_key(app.clone()).await;
+
+    let response = app.clone().oneshot(status_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["cache-control"],
+        "public, max-age=30",
+        "Status responses should be cacheable by a CDN"
+    );
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "operational");
+    assert!(json["incident"].is_null());
+
+    // Status checks are limited per address, 60 a minute, not by the API key limit.
+    for _ in 1..60 {
+        let response = app.clone().oneshot(status_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = app.clone().oneshot(status_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // ...and leave the authenticated quota untouched.
+    for _ in 0..2 {
+        let response = app.clone().oneshot(api_request(&api_key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_readiness_and_diagnostics_report_quota_use() {
+    let server = create_test_server(4).await;
+    let app = server.router();
+    let api_key = bootstrap_api_key(app.clone()).await;
+
+    // The readiness probe needs no key and is not limited.
+    for _ in 0..10 {
+        let response = app
+            .clone()
+            .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app.clone().oneshot(api_request(&api_key)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::get("/api/admin/diagnostics")
+                .header("Authorization", format!("Bearer {}", api_key))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "operational");
+    assert_eq!(json["schema_version"], payments_repo::SCHEMA_VERSION);
+    assert_eq!(json["webhook_backlog"], 0);
+    // Two of the key's four requests are used, counting this one.
+    assert_eq!(json["rate_limit_saturation"], 0.5);
+    assert!(json["database_latency_ms"].as_f64().is_some());
+    assert_eq!(json["problems"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_responses_report_the_quota_left() {
+    let server = create_test_server(3).await;
+    let app = server.router();
+    let api_key = bootstrap_api_key(app.clone()).await;
+
+    for remaining in ["2", "1", "0"] {
+        let response = app.clone().oneshot(api_request(&api_key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-ratelimit-limit"], "3");
+        assert_eq!(response.headers()["x-ratelimit-remaining"], remaining);
+        assert!(response.headers().get("retry-after").is_none());
+    }
+
+    let response = app.clone().oneshot(api_request(&api_key)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers()["x-ratelimit-limit"], "3");
+    assert_eq!(response.headers()["x-ratelimit-remaining"], "0");
+    assert_eq!(response.headers()["retry-after"], "60");
+}
+
+#[tokio::test]
+async fn test_api_key_quota_override_raises_the_limit() {
+    let server = create_test_server(2).await;
+    let app = server.router();
+    let api_key = bootstrap_api_key(app.clone()).await;
+
+    // Look up the key's ID, using one of its two default requests.
+    let response = app
+        .clone()
+        .oneshot(
+            Request::get("/api/keys")
+                .header("Authorization", format!("Bearer {}", api_key))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let keys: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let id = keys[0]["id"].as_str().unwrap().to_string();
+    assert_eq!(keys[0]["rate_limit_per_minute"], serde_json::Value::Null);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::put(format!("/api/keys/{}/rate-limit", id))
+                .header("Authorization", format!("Bearer {}", api_key))
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"requests_per_minute": 5}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let key: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(key["rate_limit_per_minute"], 5);
+
+    // The new quota applies from the next request, past the default of two.
+    for _ in 0..3 {
+        let response = app.clone().oneshot(api_request(&api_key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-ratelimit-limit"], "5");
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::put(format!("/api/keys/{}/rate-limit", id))
+                .header("Authorization", format!("Bearer {}", api_key))
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"requests_per_minute": 0}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_rate_limiting_per_key_isolation() {
     // Create server with 3 requests per key (1 for bootstrap each + 1 for test + 1 to hit limit)
@@ -187,4 +356,5 @@ async fn test_rate_limiting_response_format() {
         json.get("retry_after_seconds").is_some(),
         "Response should have 'retry_after_seconds' field"
     );
+    assert_eq!(json["code"], 429, "Response should repeat the status code");
 }
@@ -0,0 +1,372 @@
+//! Payments Load Tester
+//!
+//! Drives a configurable mix of deposits, withdrawals and transfers against a
+//! running payments API and reports throughput and latency percentiles per
+//! operation, so regressions in the repository layer show up as numbers.
+//!
+//! ```bash
+//! payments-loadtest --requests 5000 --concurrency 32 --mix deposit=50,withdraw=25,transfer=25
+//! ```
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clap::Parser;
+use rand::Rng;
+
+use payments_client::{ClientError, PaymentsClient};
+use payments_types::{AccountId, CurrencyCode, OwnerId, PositiveAmount};
+
+#[derive(Parser)]
+#[command(name = "payments-loadtest")]
+#[command(author, version, about = "Load-testing harness for the Payments API", long_about = None)]
+struct Cli {
+    /// Base URL of the Payments API
+    #[arg(
+        long,
+        env = "PAYMENTS_API_URL",
+        default_value = "http://localhost:3000"
+    )]
+    api_url: String,
+
+    /// API key for authentication (bootstraps a new key when omitted)
+    #[arg(long, env = "PAYMENTS_API_KEY")]
+    api_key: Option<String>,
+
+    /// Total number of operations to run
+    #[arg(long, default_value_t = 1000)]
+    requests: usize,
+
+    /// Number of operations in flight at the same time
+    #[arg(long, default_value_t = 16)]
+    concurrency: usize,
+
+    /// Number of accounts to spread the load across
+    #[arg(long, default_value_t = 10)]
+    accounts: usize,
+
+    /// Initial balance deposited into each account (smallest currency unit)
+    #[arg(long, default_value = "10000000")]
+    initial_balance: PositiveAmount,
+
+    /// Amount moved by each operation (smallest currency unit)
+    #[arg(long, default_value = "100")]
+    amount: PositiveAmount,
+
+    /// Currency for all accounts and operations
+    #[arg(long, default_value = "USD")]
+    currency: String,
+
+    /// Operation mix as weights, e.g. `deposit=40,withdraw=30,transfer=30`
+    #[arg(long, default_value = "deposit=40,withdraw=30,transfer=30")]
+    mix: Mix,
+}
+
+/// A money-movement operation issued by the load tester.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Deposit,
+    Withdraw,
+    Transfer,
+}
+
+impl Operation {
+    const ALL: [Operation; 3] = [Operation::Deposit, Operation::Withdraw, Operation::Transfer];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Operation::Deposit => "deposit",
+            Operation::Withdraw => "withdraw",
+            Operation::Transfer => "transfer",
+        }
+    }
+}
+
+/// Relative weights of each operation type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Mix {
+    deposit: u32,
+    withdraw: u32,
+    transfer: u32,
+}
+
+impl Mix {
+    fn total(&self) -> u32 {
+        self.deposit + self.withdraw + self.transfer
+    }
+
+    /// Maps a roll in `0..total()` to an operation.
+    fn pick(&self, roll: u32) -> Operation {
+        if roll < self.deposit {
+            Operation::Deposit
+        } else if roll < self.deposit + self.withdraw {
+            Operation::Withdraw
+        } else {
+            Operation::Transfer
+        }
+    }
+}
+
+impl FromStr for Mix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mix = Mix {
+            deposit: 0,
+            withdraw: 0,
+            transfer: 0,
+        };
+
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, weight) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid mix entry '{}', expected name=weight", part))?;
+            let weight: u32 = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid weight in '{}'", part))?;
+            match name.trim() {
+                "deposit" => mix.deposit = weight,
+                "withdraw" => mix.withdraw = weight,
+                "transfer" => mix.transfer = weight,
+                other => return Err(format!("Unknown operation '{}'", other)),
+            }
+        }
+
+        if mix.total() == 0 {
+            return Err("Operation mix must have at least one non-zero weight".into());
+        }
+        Ok(mix)
+    }
+}
+
+/// Outcome of a single operation.
+struct Sample {
+    operation: Operation,
+    latency: Duration,
+    /// Short error label (e.g. `HTTP 429`) when the operation failed.
+    error: Option<String>,
+}
+
+/// Collapses a client error into a label suitable for grouping.
+fn error_label(err: &ClientError) -> String {
+    match err {
+        ClientError::Api { status, .. } => format!("HTTP {}", status),
+        ClientError::Http(_) => "connection error".to_string(),
+        ClientError::Json(_) => "invalid response body".to_string(),
+    }
+}
+
+/// Returns the `p`-th percentile (0.0..=100.0) of an ascending-sorted slice.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    let cli = Cli::parse();
+    let currency: CurrencyCode = cli
+        .currency
+        .parse()
+        .map_err(|e: String| anyhow::anyhow!(e))?;
+
+    if cli.accounts < 2 && cli.mix.transfer > 0 {
+        anyhow::bail!("Transfers need at least 2 accounts");
+    }
+
+    // Single attempts, so failures and latencies are the server's own
+    let mut client = PaymentsClient::builder(&cli.api_url)
+        .max_retries(0)
+        .build()?;
+    let api_key = match cli.api_key {
+        Some(key) => key,
+        None => client.bootstrap("loadtest").await?,
+    };
+    client = client.with_api_key(api_key);
+    let client = Arc::new(client);
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Setup: create and fund accounts
+    // ─────────────────────────────────────────────────────────────────────────
+
+    println!(
+        "Setting up {} accounts with {} {} each...",
+        cli.accounts, cli.initial_balance, currency
+    );
+    let mut accounts: Vec<AccountId> = Vec::with_capacity(cli.accounts);
+    for i in 0..cli.accounts {
+        let account = client
+            .create_account(OwnerId::DEFAULT, &format!("loadtest-{}", i), currency)
+            .await?;
+        client
+            .deposit(account.id, cli.initial_balance, currency, None, None)
+            .await?;
+        accounts.push(account.id);
+    }
+    let accounts = Arc::new(accounts);
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Run: workers pull from a shared counter until all requests are issued
+    // ─────────────────────────────────────────────────────────────────────────
+
+    println!(
+        "Running {} operations with concurrency {}...",
+        cli.requests, cli.concurrency
+    );
+    let remaining = Arc::new(AtomicUsize::new(cli.requests));
+    let started = Instant::now();
+
+    let mut workers = Vec::with_capacity(cli.concurrency);
+    for _ in 0..cli.concurrency.max(1) {
+        let client = client.clone();
+        let accounts = accounts.clone();
+        let remaining = remaining.clone();
+        let mix = cli.mix;
+        let amount = cli.amount;
+
+        workers.push(tokio::spawn(async move {
+            let mut samples = Vec::new();
+            while remaining
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                let (operation, from, to) = {
+                    let mut rng = rand::rng();
+                    let operation = mix.pick(rng.random_range(0..mix.total()));
+                    let from = rng.random_range(0..accounts.len());
+                    let mut to = rng.random_range(0..accounts.len());
+                    if accounts.len() > 1 {
+                        while to == from {
+                            to = rng.random_range(0..accounts.len());
+                        }
+                    }
+                    (operation, accounts[from], accounts[to])
+                };
+
+                let start = Instant::now();
+                let result = match operation {
+                    Operation::Deposit => client
+                        .deposit(from, amount, currency, None, None)
+                        .await
+                        .map(|_| ()),
+                    Operation::Withdraw => client
+                        .withdraw(from, amount, currency, None, None)
+                        .await
+                        .map(|_| ()),
+                    Operation::Transfer => client
+                        .transfer(from, to, amount, currency, None, None)
+                        .await
+                        .map(|_| ()),
+                };
+                samples.push(Sample {
+                    operation,
+                    latency: start.elapsed(),
+                    error: result.err().map(|e| error_label(&e)),
+                });
+            }
+            samples
+        }));
+    }
+
+    let mut samples = Vec::with_capacity(cli.requests);
+    for worker in workers {
+        samples.extend(worker.await?);
+    }
+    let elapsed = started.elapsed();
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Report
+    // ─────────────────────────────────────────────────────────────────────────
+
+    println!();
+    println!(
+        "{:<10} {:>8} {:>8} {:>10} {:>10} {:>10} {:>10}",
+        "operation", "count", "errors", "p50 ms", "p90 ms", "p99 ms", "max ms"
+    );
+    for operation in Operation::ALL {
+        let mut latencies: Vec<Duration> = samples
+            .iter()
+            .filter(|s| s.operation == operation)
+            .map(|s| s.latency)
+            .collect();
+        if latencies.is_empty() {
+            continue;
+        }
+        latencies.sort();
+        let errors = samples
+            .iter()
+            .filter(|s| s.operation == operation && s.error.is_some())
+            .count();
+
+        println!(
+            "{:<10} {:>8} {:>8} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+            operation.name(),
+            latencies.len(),
+            errors,
+            millis(percentile(&latencies, 50.0)),
+            millis(percentile(&latencies, 90.0)),
+            millis(percentile(&latencies, 99.0)),
+            millis(*latencies.last().unwrap()),
+        );
+    }
+
+    let mut errors: BTreeMap<&str, usize> = BTreeMap::new();
+    for label in samples.iter().filter_map(|s| s.error.as_deref()) {
+        *errors.entry(label).or_default() += 1;
+    }
+    println!();
+    println!(
+        "{} operations in {:.2}s ({:.1} ops/s), {} errors",
+        samples.len(),
+        elapsed.as_secs_f64(),
+        samples.len() as f64 / elapsed.as_secs_f64(),
+        errors.values().sum::<usize>()
+    );
+    for (label, count) in &errors {
+        println!("  {:<24} {}", label, count);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mix() {
+        let mix: Mix = "deposit=50,withdraw=25,transfer=25".parse().unwrap();
+        assert_eq!(mix.total(), 100);
+        assert_eq!(mix.pick(0), Operation::Deposit);
+        assert_eq!(mix.pick(50), Operation::Withdraw);
+        assert_eq!(mix.pick(99), Operation::Transfer);
+    }
+
+    #[test]
+    fn test_parse_mix_rejects_unknown_and_empty() {
+        assert!("refund=10".parse::<Mix>().is_err());
+        assert!("deposit=0".parse::<Mix>().is_err());
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50.0), Duration::from_millis(51));
+        assert_eq!(percentile(&sorted, 100.0), Duration::from_millis(100));
+        assert_eq!(percentile(&[], 99.0), Duration::ZERO);
+    }
+}
@@ -0,0 +1,159 @@
+//! Shared deposit/transfer benchmarks, run against any `TransactionRepository`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use payments_types::{
+    AccountId, CreateAccountRequest, CurrencyCode, DepositRequest, OwnerId, PositiveAmount,
+    TransactionRepository, TransferRequest,
+};
+use tokio::runtime::Runtime;
+use tokio::task::JoinSet;
+
+/// Balance given to every account before transfers so they never run dry.
+const FUNDING: i64 = 1_000_000_000_000;
+
+/// Numbers of operations issued at once in the concurrent benchmarks.
+const CONCURRENCY: [usize; 3] = [1, 4, 16];
+
+async fn funded_account<R: TransactionRepository>(repo: &R, name: &str) -> AccountId {
+    let account = repo
+        .create_account(CreateAccountRequest {
+            name: name.to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        })
+        .await
+        .expect("create bench account");
+    repo.deposit(deposit_request(account.id, FUNDING))
+        .await
+        .expect("fund bench account");
+    account.id
+}
+
+fn deposit_request(account_id: AccountId, amount: i64) -> DepositRequest {
+    DepositRequest {
+        account_id,
+        amount: PositiveAmount::new(amount).unwrap(),
+        currency: CurrencyCode::USD,
+        idempotency_key: None,
+        reference: None,
+        counterparty: None,
+        metadata: Default::default(),
+        encrypted_memo: None,
+    }
+}
+
+fn transfer_request(from: AccountId, to: AccountId) -> TransferRequest {
+    TransferRequest {
+        from_account_id: from,
+        to_account_id: to,
+        amount: PositiveAmount::new(1).unwrap(),
+        currency: CurrencyCode::USD,
+        idempotency_key: None,
+        reference: None,
+        purpose_code: None,
+        convert_currency: false,
+        quote_id: None,
+        metadata: Default::default(),
+        encrypted_memo: None,
+    }
+}
+
+/// Counts attempted and failed operations for one benchmark.
+///
+/// Backends that serialize writers (SQLite) reject some concurrent operations
+/// with lock errors; those are reported rather than aborting the run.
+#[derive(Default)]
+struct Outcomes {
+    attempted: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+impl Outcomes {
+    fn record<T, E>(&self, result: Result<Result<T, E>, tokio::task::JoinError>) {
+        self.attempted.fetch_add(1, Ordering::Relaxed);
+        if !matches!(result, Ok(Ok(_))) {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn report(&self, name: &str) {
+        let failed = self.failed.load(Ordering::Relaxed);
+        if failed > 0 {
+            eprintln!(
+                "{}: {} of {} operations failed",
+                name,
+                failed,
+                self.attempted.load(Ordering::Relaxed)
+            );
+        }
+    }
+}
+
+/// Registers the deposit and transfer benchmark groups under `backend`.
+pub fn bench_repo<R>(c: &mut Criterion, rt: &Runtime, backend: &str, repo: R)
+where
+    R: TransactionRepository + 'static,
+{
+    let repo = Arc::new(repo);
+    let (a, b) = rt.block_on(async {
+        (
+            funded_account(repo.as_ref(), "bench-a").await,
+            funded_account(repo.as_ref(), "bench-b").await,
+        )
+    });
+
+    let mut group = c.benchmark_group(format!("{}/deposit", backend));
+    for n in CONCURRENCY {
+        group.throughput(Throughput::Elements(n as u64));
+        let outcomes = Arc::new(Outcomes::default());
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |bench, &n| {
+            bench.to_async(rt).iter(|| {
+                let repo = repo.clone();
+                let outcomes = outcomes.clone();
+                async move {
+                    let mut tasks = JoinSet::new();
+                    for _ in 0..n {
+                        let repo = repo.clone();
+                        tasks.spawn(async move { repo.deposit(deposit_request(a, 1)).await });
+                    }
+                    while let Some(result) = tasks.join_next().await {
+                        outcomes.record(result);
+                    }
+                }
+            })
+        });
+        outcomes.report(&format!("{}/deposit/{}", backend, n));
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group(format!("{}/transfer", backend));
+    for n in CONCURRENCY {
+        group.throughput(Throughput::Elements(n as u64));
+        let outcomes = Arc::new(Outcomes::default());
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |bench, &n| {
+            bench.to_async(rt).iter(|| {
+                let repo = repo.clone();
+                let outcomes = outcomes.clone();
+                async move {
+                    let mut tasks = JoinSet::new();
+                    for i in 0..n {
+                        let repo = repo.clone();
+                        // Alternate direction so both sides see lock contention.
+                        let (from, to) = if i % 2 == 0 { (a, b) } else { (b, a) };
+                        tasks.spawn(async move { repo.transfer(transfer_request(from, to)).await });
+                    }
+                    while let Some(result) = tasks.join_next().await {
+                        outcomes.record(result);
+                    }
+                }
+            })
+        });
+        outcomes.report(&format!("{}/transfer/{}", backend, n));
+    }
+    group.finish();
+}
@@ -0,0 +1,57 @@
+//! Benchmarks for API key hashing and webhook signing.
+//!
+//! ```bash
+//! cargo bench -p payments-repo --features sqlite --bench security
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use payments_repo::security::{
+    hash_api_key, sign_webhook, sign_webhook_with, verify_api_key, verify_webhook_signature,
+};
+use payments_types::WebhookSignatureAlgorithm;
+
+const SECRET: &str = "whsec_0123456789abcdef0123456789abcdef";
+
+fn bench_webhook_signing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("webhook_signing");
+
+    for size in [256usize, 4 * 1024, 64 * 1024] {
+        let payload = vec![b'x'; size];
+        let signature = sign_webhook(&payload, SECRET);
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("sign", size), &payload, |b, payload| {
+            b.iter(|| sign_webhook(black_box(payload), SECRET))
+        });
+        group.bench_with_input(BenchmarkId::new("verify", size), &payload, |b, payload| {
+            b.iter(|| verify_webhook_signature(black_box(payload), &signature, SECRET))
+        });
+        for algorithm in [
+            WebhookSignatureAlgorithm::HmacSha512,
+            WebhookSignatureAlgorithm::Ed25519,
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("sign_{}", algorithm), size),
+                &payload,
+                |b, payload| b.iter(|| sign_webhook_with(algorithm, black_box(payload), SECRET)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_api_key_hashing(c: &mut Criterion) {
+    let key = "sk_0123456789abcdef0123456789abcdef";
+    let stored = hash_api_key(key);
+
+    c.bench_function("api_key/hash", |b| b.iter(|| hash_api_key(black_box(key))));
+    c.bench_function("api_key/verify", |b| {
+        b.iter(|| verify_api_key(black_box(key), &stored))
+    });
+}
+
+criterion_group!(benches, bench_webhook_signing, bench_api_key_hashing);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+//! SQLite deposit/transfer throughput.
+//!
+//! Uses a throwaway database file in the system temp directory so the numbers
+//! include real disk I/O and connection pooling.
+//!
+//! ```bash
+//! cargo bench -p payments-repo --no-default-features --features sqlite --bench sqlite_repo
+//! ```
+
+mod common;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use payments_repo::SqliteRepo;
+
+fn bench_sqlite(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().expect("tokio runtime");
+    let path = std::env::temp_dir().join(format!("payments-bench-{}.db", uuid::Uuid::new_v4()));
+    let url = format!("sqlite://{}?mode=rwc", path.display());
+
+    let repo = rt
+        .block_on(SqliteRepo::new(&url))
+        .expect("open SQLite bench database");
+    common::bench_repo(c, &rt, "sqlite", repo);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+criterion_group!(benches, bench_sqlite);
+criterion_main!(benches);
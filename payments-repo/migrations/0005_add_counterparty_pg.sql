@@ -0,0 +1,2 @@
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS counterparty_name TEXT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS counterparty_external_id TEXT;
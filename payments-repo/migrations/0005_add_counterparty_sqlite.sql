@@ -0,0 +1,4 @@
+-- Counterparty details for deposits and withdrawals.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE transactions ADD COLUMN counterparty_name TEXT;
+ALTER TABLE transactions ADD COLUMN counterparty_external_id TEXT;
@@ -0,0 +1,5 @@
+-- Indexes for GET /api/accounts/search.
+-- pg_trgm is a trusted extension (PG13+), so the database owner can install it.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE INDEX IF NOT EXISTS idx_accounts_name_trgm ON accounts USING gin (lower(name) gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_accounts_id_prefix ON accounts ((id::text) text_pattern_ops);
@@ -0,0 +1,8 @@
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS purpose_code TEXT;
+
+CREATE TABLE IF NOT EXISTS account_spending_rules (
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    purpose_code TEXT NOT NULL,
+    effect TEXT NOT NULL CHECK (effect IN ('ALLOW', 'DENY')),
+    PRIMARY KEY (account_id, purpose_code, effect)
+);
@@ -0,0 +1,9 @@
+-- Purpose codes on transactions and per-account allow/deny rules.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+CREATE TABLE IF NOT EXISTS account_spending_rules (
+    account_id TEXT NOT NULL REFERENCES accounts(id),
+    purpose_code TEXT NOT NULL,
+    effect TEXT NOT NULL CHECK (effect IN ('ALLOW', 'DENY')),
+    PRIMARY KEY (account_id, purpose_code, effect)
+);
+ALTER TABLE transactions ADD COLUMN purpose_code TEXT;
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS account_limits (
+    account_id UUID PRIMARY KEY REFERENCES accounts(id),
+    max_transaction_amount BIGINT,
+    max_balance BIGINT,
+    daily_debit_limit BIGINT
+);
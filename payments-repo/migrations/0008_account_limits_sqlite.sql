@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS account_limits (
+    account_id TEXT PRIMARY KEY REFERENCES accounts(id),
+    max_transaction_amount INTEGER,
+    max_balance INTEGER,
+    daily_debit_limit INTEGER
+);
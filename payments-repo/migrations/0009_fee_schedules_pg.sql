@@ -0,0 +1,19 @@
+-- Fee schedules are never updated and assignments are append-only, so the
+-- history of an account's pricing stays auditable.
+CREATE TABLE IF NOT EXISTS fee_schedules (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    tiers JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS account_fee_assignments (
+    id BIGSERIAL PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    fee_schedule_id UUID NOT NULL REFERENCES fee_schedules(id),
+    effective_from TIMESTAMPTZ NOT NULL,
+    assigned_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_fee_assignments_account
+    ON account_fee_assignments(account_id, effective_from);
@@ -0,0 +1,19 @@
+-- Fee schedules are never updated and assignments are append-only, so the
+-- history of an account's pricing stays auditable.
+CREATE TABLE IF NOT EXISTS fee_schedules (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    tiers TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS account_fee_assignments (
+    id INTEGER PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id),
+    fee_schedule_id TEXT NOT NULL REFERENCES fee_schedules(id),
+    effective_from TEXT NOT NULL,
+    assigned_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_fee_assignments_account
+    ON account_fee_assignments(account_id);
@@ -0,0 +1,20 @@
+-- Totals are fixed when a batch is created. A payout sits in at most one live
+-- batch: cancelling a batch deletes its payout rows so the next batch picks
+-- them up again.
+CREATE TABLE IF NOT EXISTS settlement_batches (
+    id UUID PRIMARY KEY,
+    status TEXT NOT NULL,
+    cutoff TIMESTAMPTZ NOT NULL,
+    payout_count BIGINT NOT NULL,
+    totals JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS settlement_batch_payouts (
+    transaction_id UUID PRIMARY KEY REFERENCES transactions(id),
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlement_batch_payouts_batch
+    ON settlement_batch_payouts(batch_id);
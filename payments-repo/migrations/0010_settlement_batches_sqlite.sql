@@ -0,0 +1,20 @@
+-- Totals are fixed when a batch is created. A payout sits in at most one live
+-- batch: cancelling a batch deletes its payout rows so the next batch picks
+-- them up again.
+CREATE TABLE IF NOT EXISTS settlement_batches (
+    id TEXT PRIMARY KEY,
+    status TEXT NOT NULL,
+    cutoff TEXT NOT NULL,
+    payout_count INTEGER NOT NULL,
+    totals TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS settlement_batch_payouts (
+    transaction_id TEXT PRIMARY KEY REFERENCES transactions(id),
+    batch_id TEXT NOT NULL REFERENCES settlement_batches(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlement_batch_payouts_batch
+    ON settlement_batch_payouts(batch_id);
@@ -0,0 +1,20 @@
+-- One statement per account and month. The rendered file is stored with it
+-- so downloads never depend on history that could be re-read differently.
+CREATE TABLE IF NOT EXISTS statements (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    currency TEXT NOT NULL,
+    opening_balance BIGINT NOT NULL,
+    closing_balance BIGINT NOT NULL,
+    transaction_count BIGINT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (account_id, period_start)
+);
+
+CREATE TABLE IF NOT EXISTS statement_emails (
+    account_id UUID PRIMARY KEY REFERENCES accounts(id),
+    email TEXT NOT NULL
+);
@@ -0,0 +1,20 @@
+-- One statement per account and month. The rendered file is stored with it
+-- so downloads never depend on history that could be re-read differently.
+CREATE TABLE IF NOT EXISTS statements (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id),
+    period_start TEXT NOT NULL,
+    period_end TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    opening_balance INTEGER NOT NULL,
+    closing_balance INTEGER NOT NULL,
+    transaction_count INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    UNIQUE (account_id, period_start)
+);
+
+CREATE TABLE IF NOT EXISTS statement_emails (
+    account_id TEXT PRIMARY KEY REFERENCES accounts(id),
+    email TEXT NOT NULL
+);
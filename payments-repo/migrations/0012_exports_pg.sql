@@ -0,0 +1,13 @@
+-- Files rendered in the background. The file is filled in once rendering
+-- finishes.
+CREATE TABLE IF NOT EXISTS exports (
+    id UUID PRIMARY KEY,
+    request JSONB NOT NULL,
+    status TEXT NOT NULL,
+    error TEXT,
+    content TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_exports_created_at ON exports(created_at);
@@ -0,0 +1,13 @@
+-- Files rendered in the background. The request is stored as JSON and the
+-- file is filled in once rendering finishes.
+CREATE TABLE IF NOT EXISTS exports (
+    id TEXT PRIMARY KEY,
+    request TEXT NOT NULL,
+    status TEXT NOT NULL,
+    error TEXT,
+    content TEXT,
+    created_at TEXT NOT NULL,
+    completed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_exports_created_at ON exports(created_at);
@@ -0,0 +1,3 @@
+-- Indexes for paging GET /api/accounts/{id}/transactions newest first.
+CREATE INDEX IF NOT EXISTS idx_transactions_source_page ON transactions(source_account_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_transactions_dest_page ON transactions(destination_account_id, created_at DESC, id DESC);
@@ -0,0 +1,6 @@
+-- Exchange rates recorded over time, so exposure reports can use the rates
+-- that applied at a past moment.
+CREATE TABLE IF NOT EXISTS rate_snapshots (
+    taken_at TIMESTAMPTZ PRIMARY KEY,
+    usd_rates JSONB NOT NULL
+);
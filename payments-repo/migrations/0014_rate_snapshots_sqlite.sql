@@ -0,0 +1,7 @@
+-- Exchange rates recorded over time, so exposure reports can use the rates
+-- that applied at a past moment. `taken_at` is fixed-width UTC RFC 3339 with
+-- microseconds, so it sorts as text.
+CREATE TABLE IF NOT EXISTS rate_snapshots (
+    taken_at TEXT PRIMARY KEY,
+    usd_rates TEXT NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Retry schedule for webhook deliveries.
+-- Events queued before this column existed become due right away.
+ALTER TABLE webhook_events ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMPTZ;
+
+UPDATE webhook_events SET next_attempt_at = created_at
+WHERE status = 'PENDING' AND next_attempt_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_next_attempt
+    ON webhook_events(next_attempt_at)
+    WHERE status IN ('PENDING', 'FAILED');
@@ -0,0 +1,6 @@
+-- Retry schedule for webhook deliveries.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+-- Events queued before this column existed become due right away.
+ALTER TABLE webhook_events ADD COLUMN next_attempt_at TEXT;
+UPDATE webhook_events SET next_attempt_at = created_at WHERE status = 'PENDING';
+CREATE INDEX IF NOT EXISTS idx_webhook_next_attempt ON webhook_events(status, next_attempt_at);
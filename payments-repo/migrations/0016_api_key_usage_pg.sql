@@ -0,0 +1,19 @@
+-- Per-key usage in UTC hour buckets, summed for the key usage report.
+CREATE TABLE IF NOT EXISTS api_key_usage (
+    api_key_id UUID NOT NULL REFERENCES api_keys(id),
+    hour TIMESTAMPTZ NOT NULL,
+    requests BIGINT NOT NULL DEFAULT 0,
+    rate_limited BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, hour)
+);
+
+-- Money moved per key, hour, transaction type and currency.
+CREATE TABLE IF NOT EXISTS api_key_volume (
+    api_key_id UUID NOT NULL REFERENCES api_keys(id),
+    hour TIMESTAMPTZ NOT NULL,
+    transaction_type VARCHAR(20) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+    amount BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, hour, transaction_type, currency)
+);
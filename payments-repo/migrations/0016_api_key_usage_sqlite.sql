@@ -0,0 +1,20 @@
+-- Per-key usage in UTC hour buckets, summed for the key usage report. `hour`
+-- is fixed-width UTC RFC 3339 with microseconds, so it sorts as text.
+CREATE TABLE IF NOT EXISTS api_key_usage (
+    api_key_id TEXT NOT NULL REFERENCES api_keys(id),
+    hour TEXT NOT NULL,
+    requests INTEGER NOT NULL DEFAULT 0,
+    rate_limited INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, hour)
+);
+
+-- Money moved per key, hour, transaction type and currency.
+CREATE TABLE IF NOT EXISTS api_key_volume (
+    api_key_id TEXT NOT NULL REFERENCES api_keys(id),
+    hour TEXT NOT NULL,
+    transaction_type TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    amount INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, hour, transaction_type, currency)
+);
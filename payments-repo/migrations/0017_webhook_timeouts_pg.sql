@@ -0,0 +1,4 @@
+-- Per-endpoint delivery timeouts, in milliseconds.
+-- Existing endpoints get the defaults.
+ALTER TABLE webhook_endpoints ADD COLUMN IF NOT EXISTS timeout_ms INTEGER NOT NULL DEFAULT 10000;
+ALTER TABLE webhook_endpoints ADD COLUMN IF NOT EXISTS connect_timeout_ms INTEGER NOT NULL DEFAULT 3000;
@@ -0,0 +1,5 @@
+-- Per-endpoint delivery timeouts, in milliseconds.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+-- Existing endpoints get the defaults.
+ALTER TABLE webhook_endpoints ADD COLUMN timeout_ms INTEGER NOT NULL DEFAULT 10000;
+ALTER TABLE webhook_endpoints ADD COLUMN connect_timeout_ms INTEGER NOT NULL DEFAULT 3000;
@@ -0,0 +1,5 @@
+-- Conversion applied to cross-currency transfers: the rate and the amount
+-- credited in the destination currency. NULL for all other transactions.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS fx_rate DOUBLE PRECISION;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS converted_amount BIGINT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS converted_currency TEXT;
@@ -0,0 +1,6 @@
+-- Conversion applied to cross-currency transfers: the rate and the amount
+-- credited in the destination currency. NULL for all other transactions.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE transactions ADD COLUMN fx_rate REAL;
+ALTER TABLE transactions ADD COLUMN converted_amount INTEGER;
+ALTER TABLE transactions ADD COLUMN converted_currency TEXT;
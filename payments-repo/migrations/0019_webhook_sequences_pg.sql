@@ -0,0 +1,23 @@
+-- Per-endpoint delivery sequence numbers, so receivers can spot gaps and
+-- duplicates. The counter table hands out the next number for each endpoint.
+-- Events queued before this column existed are numbered in creation order.
+ALTER TABLE webhook_events ADD COLUMN IF NOT EXISTS delivery_sequence BIGINT;
+
+UPDATE webhook_events SET delivery_sequence = (
+    SELECT COUNT(*) FROM webhook_events earlier
+    WHERE earlier.endpoint_id = webhook_events.endpoint_id
+      AND (earlier.created_at, earlier.id) <= (webhook_events.created_at, webhook_events.id)
+)
+WHERE delivery_sequence IS NULL;
+
+CREATE TABLE IF NOT EXISTS webhook_delivery_sequences (
+    endpoint_id UUID PRIMARY KEY,
+    last_sequence BIGINT NOT NULL
+);
+
+INSERT INTO webhook_delivery_sequences (endpoint_id, last_sequence)
+SELECT endpoint_id, MAX(delivery_sequence) FROM webhook_events GROUP BY endpoint_id
+ON CONFLICT (endpoint_id) DO NOTHING;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_events_endpoint_sequence
+    ON webhook_events(endpoint_id, delivery_sequence);
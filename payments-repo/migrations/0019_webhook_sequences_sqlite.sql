@@ -0,0 +1,17 @@
+-- Per-endpoint delivery sequence numbers, so receivers can spot gaps and
+-- duplicates. The counter table hands out the next number for each endpoint.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+-- Events queued before this column existed are numbered in insertion order.
+ALTER TABLE webhook_events ADD COLUMN delivery_sequence INTEGER;
+UPDATE webhook_events SET delivery_sequence = (
+    SELECT COUNT(*) FROM webhook_events earlier
+    WHERE earlier.endpoint_id = webhook_events.endpoint_id
+      AND earlier.rowid <= webhook_events.rowid
+);
+CREATE TABLE IF NOT EXISTS webhook_delivery_sequences (
+    endpoint_id TEXT PRIMARY KEY,
+    last_sequence INTEGER NOT NULL
+);
+INSERT OR IGNORE INTO webhook_delivery_sequences (endpoint_id, last_sequence)
+SELECT endpoint_id, MAX(delivery_sequence) FROM webhook_events GROUP BY endpoint_id;
+CREATE INDEX IF NOT EXISTS idx_webhook_events_endpoint_sequence ON webhook_events(endpoint_id, delivery_sequence);
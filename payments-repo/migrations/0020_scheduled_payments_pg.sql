@@ -0,0 +1,26 @@
+-- Withdrawals and transfers made on a schedule. The schedule is stored as
+-- JSON (an interval or a cron expression with its time zone). The scheduler
+-- claims a run by moving next_run_at on from the value it read.
+CREATE TABLE IF NOT EXISTS scheduled_payments (
+    id UUID PRIMARY KEY,
+    transaction_type TEXT NOT NULL,
+    from_account_id UUID NOT NULL REFERENCES accounts(id),
+    to_account_id UUID REFERENCES accounts(id),
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL,
+    reference TEXT,
+    purpose_code TEXT,
+    schedule JSONB NOT NULL,
+    status TEXT NOT NULL,
+    next_run_at TIMESTAMPTZ NOT NULL,
+    last_run_at TIMESTAMPTZ,
+    last_transaction_id UUID,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_payments_due
+    ON scheduled_payments(status, next_run_at);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_payments_from_account
+    ON scheduled_payments(from_account_id);
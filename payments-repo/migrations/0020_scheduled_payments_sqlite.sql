@@ -0,0 +1,26 @@
+-- Withdrawals and transfers made on a schedule. The schedule is stored as
+-- JSON (an interval or a cron expression with its time zone). next_run_at is
+-- written in a fixed-width UTC format so that it sorts and compares as text.
+CREATE TABLE IF NOT EXISTS scheduled_payments (
+    id TEXT PRIMARY KEY,
+    transaction_type TEXT NOT NULL,
+    from_account_id TEXT NOT NULL REFERENCES accounts(id),
+    to_account_id TEXT REFERENCES accounts(id),
+    amount INTEGER NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL,
+    reference TEXT,
+    purpose_code TEXT,
+    schedule TEXT NOT NULL,
+    status TEXT NOT NULL,
+    next_run_at TEXT NOT NULL,
+    last_run_at TEXT,
+    last_transaction_id TEXT,
+    last_error TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_payments_due
+    ON scheduled_payments(status, next_run_at);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_payments_from_account
+    ON scheduled_payments(from_account_id);
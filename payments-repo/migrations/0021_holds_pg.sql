@@ -0,0 +1,23 @@
+-- Funds reserved by authorizations until they are captured, voided or expire.
+-- accounts.held is the sum of the account's AUTHORIZED holds and is updated
+-- in the same transaction as the hold.
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS held BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS holds (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL,
+    reference TEXT,
+    purpose_code TEXT,
+    status TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    captured_amount BIGINT,
+    transaction_id UUID REFERENCES transactions(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_holds_account ON holds(account_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_holds_expiry ON holds(status, expires_at);
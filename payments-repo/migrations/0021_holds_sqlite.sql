@@ -0,0 +1,21 @@
+-- Funds reserved by authorizations until they are captured, voided or expire.
+-- accounts.held is the sum of the account's AUTHORIZED holds and is updated
+-- in the same transaction as the hold.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+CREATE TABLE IF NOT EXISTS holds (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id),
+    amount INTEGER NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL,
+    reference TEXT,
+    purpose_code TEXT,
+    status TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    captured_amount INTEGER,
+    transaction_id TEXT REFERENCES transactions(id),
+    created_at TEXT NOT NULL,
+    resolved_at TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_holds_account ON holds(account_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_holds_expiry ON holds(status, expires_at);
+ALTER TABLE accounts ADD COLUMN held INTEGER NOT NULL DEFAULT 0;
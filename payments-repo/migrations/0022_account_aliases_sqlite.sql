@@ -0,0 +1,10 @@
+-- Human-friendly names for accounts. Aliases are stored lower-case without
+-- their leading '@', so the primary key makes them unique regardless of case.
+CREATE TABLE IF NOT EXISTS account_aliases (
+    alias TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id),
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_aliases_account
+    ON account_aliases(account_id);
@@ -0,0 +1,3 @@
+-- Permissions granted to each API key, as scope names separated by spaces.
+-- Keys created before scopes existed keep full access.
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS scopes TEXT NOT NULL DEFAULT 'accounts:read accounts:write transactions:read transactions:write webhooks:read webhooks:write keys:admin';
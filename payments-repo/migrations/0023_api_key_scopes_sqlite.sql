@@ -0,0 +1,4 @@
+-- Permissions granted to each API key, as scope names separated by spaces.
+-- Keys created before scopes existed keep full access.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE api_keys ADD COLUMN scopes TEXT NOT NULL DEFAULT 'accounts:read accounts:write transactions:read transactions:write webhooks:read webhooks:write keys:admin';
@@ -0,0 +1,6 @@
+-- Account lifecycle status (ACTIVE or DORMANT) and when it last changed.
+-- Existing accounts start out active.
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'ACTIVE';
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS status_changed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_accounts_status ON accounts(status, created_at);
@@ -0,0 +1,5 @@
+-- Account lifecycle status (ACTIVE or DORMANT) and when it last changed.
+-- Existing accounts start out active.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE accounts ADD COLUMN status TEXT NOT NULL DEFAULT 'ACTIVE';
+ALTER TABLE accounts ADD COLUMN status_changed_at TEXT;
@@ -0,0 +1,4 @@
+-- Risk check decision a payment was held for review under, with the
+-- reasons as a JSON array. NULL for payments the risk check allowed.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS risk_decision TEXT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS risk_reasons TEXT;
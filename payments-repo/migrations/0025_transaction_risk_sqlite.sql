@@ -0,0 +1,5 @@
+-- Risk check decision a payment was held for review under, with the
+-- reasons as a JSON array. NULL for payments the risk check allowed.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE transactions ADD COLUMN risk_decision TEXT;
+ALTER TABLE transactions ADD COLUMN risk_reasons TEXT;
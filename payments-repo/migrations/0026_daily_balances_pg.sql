@@ -0,0 +1,8 @@
+-- Closing balance of every account at the end of each UTC day, recorded once
+-- the day is over so balance charts don't replay the transaction history.
+CREATE TABLE IF NOT EXISTS daily_balances (
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    date DATE NOT NULL,
+    balance BIGINT NOT NULL,
+    PRIMARY KEY (account_id, date)
+);
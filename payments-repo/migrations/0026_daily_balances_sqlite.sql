@@ -0,0 +1,9 @@
+-- Closing balance of every account at the end of each UTC day, recorded once
+-- the day is over so balance charts don't replay the transaction history.
+-- `date` is `YYYY-MM-DD`, so it sorts as text.
+CREATE TABLE IF NOT EXISTS daily_balances (
+    account_id TEXT NOT NULL REFERENCES accounts(id),
+    date TEXT NOT NULL,
+    balance INTEGER NOT NULL,
+    PRIMARY KEY (account_id, date)
+);
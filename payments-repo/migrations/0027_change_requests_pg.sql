@@ -0,0 +1,15 @@
+-- Security changes (API key deletion, webhook URL changes) waiting for or
+-- given a second admin's approval. Rows are never deleted, so the table is
+-- also the audit log of who requested and who decided each change.
+CREATE TABLE IF NOT EXISTS change_requests (
+    id UUID PRIMARY KEY,
+    action JSONB NOT NULL,
+    status TEXT NOT NULL,
+    requested_by UUID NOT NULL,
+    requested_at TIMESTAMPTZ NOT NULL,
+    decided_by UUID,
+    decided_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_requests_status
+    ON change_requests(status, requested_at);
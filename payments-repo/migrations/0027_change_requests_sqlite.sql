@@ -0,0 +1,16 @@
+-- Security changes (API key deletion, webhook URL changes) waiting for or
+-- given a second admin's approval. Rows are never deleted, so the table is
+-- also the audit log of who requested and who decided each change.
+-- `action` is the JSON-encoded change; timestamps are sortable RFC 3339.
+CREATE TABLE IF NOT EXISTS change_requests (
+    id TEXT PRIMARY KEY,
+    action TEXT NOT NULL,
+    status TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+    requested_at TEXT NOT NULL,
+    decided_by TEXT,
+    decided_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_requests_status
+    ON change_requests(status, requested_at);
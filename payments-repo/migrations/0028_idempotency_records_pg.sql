@@ -0,0 +1,9 @@
+-- Responses to payment requests made with an idempotency key, so a repeated
+-- request gets the stored response and a key reused for a different request
+-- is refused.
+CREATE TABLE IF NOT EXISTS idempotency_records (
+    idempotency_key TEXT PRIMARY KEY,
+    request_hash TEXT NOT NULL,
+    response JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Responses to payment requests made with an idempotency key, so a repeated
+-- request gets the stored response and a key reused for a different request
+-- is refused. `response` is the JSON-encoded response body; `created_at` is
+-- sortable RFC 3339.
+CREATE TABLE IF NOT EXISTS idempotency_records (
+    idempotency_key TEXT PRIMARY KEY,
+    request_hash TEXT NOT NULL,
+    response TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
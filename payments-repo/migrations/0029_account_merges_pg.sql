@@ -0,0 +1,2 @@
+-- The account a closed account was merged into, if it was closed by a merge.
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS merged_into UUID;
@@ -0,0 +1,3 @@
+-- The account a closed account was merged into, if it was closed by a merge.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE accounts ADD COLUMN merged_into TEXT;
@@ -0,0 +1,2 @@
+-- How far below zero an account's balance may go, in minor units.
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS overdraft_limit BIGINT NOT NULL DEFAULT 0;
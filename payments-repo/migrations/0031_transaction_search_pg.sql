@@ -0,0 +1,4 @@
+-- Indexes for GET /api/transactions/search.
+-- pg_trgm is installed by 0006; it serves reference fragments of any length.
+CREATE INDEX IF NOT EXISTS idx_transactions_reference_trgm ON transactions USING gin (lower(reference) gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_transactions_page ON transactions(created_at DESC, id DESC);
@@ -0,0 +1,7 @@
+-- Keys holding keys:admin also get inbound:write, so existing admin keys
+-- (including the bootstrap key) can create integration keys.
+-- Safe to run repeatedly: keys that already hold the scope are skipped.
+UPDATE api_keys
+SET scopes = scopes || ' inbound:write'
+WHERE ' ' || scopes || ' ' LIKE '% keys:admin %'
+  AND ' ' || scopes || ' ' NOT LIKE '% inbound:write %';
@@ -0,0 +1,13 @@
+-- Deleted keys are kept, with who deleted them and when, and every key's
+-- creation, deletion and use is appended to audit_log.
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMPTZ;
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS deactivated_by UUID;
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    api_key_id UUID NOT NULL REFERENCES api_keys(id),
+    action VARCHAR(20) NOT NULL,
+    actor_id UUID,
+    occurred_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_audit_log_key ON audit_log(api_key_id, id);
@@ -0,0 +1,13 @@
+-- Deleted keys are kept, with who deleted them and when, and every key's
+-- creation, deletion and use is appended to audit_log.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    api_key_id TEXT NOT NULL REFERENCES api_keys(id),
+    action TEXT NOT NULL,
+    actor_id TEXT,
+    occurred_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_audit_log_key ON audit_log(api_key_id, id);
+ALTER TABLE api_keys ADD COLUMN deactivated_at TEXT;
+ALTER TABLE api_keys ADD COLUMN deactivated_by TEXT;
@@ -0,0 +1,16 @@
+-- External accounts that an account pays out to. Each is sent two
+-- micro-deposits and only paid once the customer confirms their amounts.
+CREATE TABLE IF NOT EXISTS beneficiaries (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id),
+    name TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    status TEXT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    micro_deposit_1 INTEGER NOT NULL,
+    micro_deposit_2 INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    verified_at TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_beneficiaries_account ON beneficiaries(account_id, created_at);
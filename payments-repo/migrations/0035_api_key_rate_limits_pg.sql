@@ -0,0 +1,3 @@
+-- Per-key quota overrides, e.g. for premium integrations. NULL keeps the
+-- server-wide default.
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS rate_limit_per_minute INTEGER;
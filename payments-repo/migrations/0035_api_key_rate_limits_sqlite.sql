@@ -0,0 +1,4 @@
+-- Per-key quota overrides, e.g. for premium integrations. NULL keeps the
+-- server-wide default.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE api_keys ADD COLUMN rate_limit_per_minute INTEGER;
@@ -0,0 +1,18 @@
+-- Append-only log of domain events, exported for analytics. The counter row
+-- hands out sequence numbers and stays locked until commit, so events become
+-- visible in sequence order and an export cursor never skips one.
+CREATE TABLE IF NOT EXISTS event_log (
+    sequence BIGINT PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS event_log_sequence (
+    id SMALLINT PRIMARY KEY CHECK (id = 1),
+    last_sequence BIGINT NOT NULL
+);
+
+INSERT INTO event_log_sequence (id, last_sequence) VALUES (1, 0)
+ON CONFLICT (id) DO NOTHING;
@@ -0,0 +1,14 @@
+-- Append-only log of domain events, exported for analytics. The counter row
+-- hands out sequence numbers, so events are numbered in commit order.
+CREATE TABLE IF NOT EXISTS event_log (
+    sequence INTEGER PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS event_log_sequence (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_sequence INTEGER NOT NULL
+);
+INSERT OR IGNORE INTO event_log_sequence (id, last_sequence) VALUES (1, 0);
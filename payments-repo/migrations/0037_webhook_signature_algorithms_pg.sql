@@ -0,0 +1,3 @@
+-- How each endpoint's deliveries are signed: hmac-sha256, hmac-sha512 or ed25519.
+-- Existing endpoints keep signing with HMAC-SHA256.
+ALTER TABLE webhook_endpoints ADD COLUMN IF NOT EXISTS signature_algorithm TEXT NOT NULL DEFAULT 'hmac-sha256';
@@ -0,0 +1,4 @@
+-- How each endpoint's deliveries are signed: hmac-sha256, hmac-sha512 or ed25519.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+-- Existing endpoints keep signing with HMAC-SHA256.
+ALTER TABLE webhook_endpoints ADD COLUMN signature_algorithm TEXT NOT NULL DEFAULT 'hmac-sha256';
@@ -0,0 +1,3 @@
+-- Key-value metadata integrators attach to a payment, as a JSON object.
+-- NULL for transactions without any.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS metadata TEXT;
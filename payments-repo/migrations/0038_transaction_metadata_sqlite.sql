@@ -0,0 +1,4 @@
+-- Key-value metadata integrators attach to a payment, as a JSON object.
+-- NULL for transactions without any.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE transactions ADD COLUMN metadata TEXT;
@@ -0,0 +1,13 @@
+-- Metadata of the keys customers encrypt transaction memos with. The keys
+-- themselves never reach the service; retired keys are kept so memos
+-- written with them still name a known key.
+CREATE TABLE IF NOT EXISTS memo_keys (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id),
+    algorithm TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    retired_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_memo_keys_account ON memo_keys(account_id, created_at);
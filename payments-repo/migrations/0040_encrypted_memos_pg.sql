@@ -0,0 +1,3 @@
+-- Memo a customer encrypted under one of its memo keys, as a JSON object of
+-- key_id and ciphertext. NULL for transactions without one.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS encrypted_memo TEXT;
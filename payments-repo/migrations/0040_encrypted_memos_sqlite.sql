@@ -0,0 +1,4 @@
+-- Memo a customer encrypted under one of its memo keys, as a JSON object of
+-- key_id and ciphertext. NULL for transactions without one.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE transactions ADD COLUMN encrypted_memo TEXT;
@@ -0,0 +1,14 @@
+-- Wakes the webhook worker as soon as new webhook events commit.
+-- Notifications are sent on commit and identical ones in a transaction are
+-- folded into one, so a batch of payments wakes the worker once.
+CREATE OR REPLACE FUNCTION notify_webhook_events() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('webhook_events', '');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS webhook_events_notify ON webhook_events;
+CREATE TRIGGER webhook_events_notify
+    AFTER INSERT ON webhook_events
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_webhook_events();
@@ -0,0 +1,17 @@
+-- The customers accounts belong to. Accounts opened before owners existed
+-- belong to the default owner; an API key may be bound to an owner instead
+-- of a single account.
+CREATE TABLE IF NOT EXISTS owners (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+INSERT INTO owners (id, name)
+VALUES ('00000000-0000-0000-0000-000000000000', 'Default owner')
+ON CONFLICT (id) DO NOTHING;
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS owner_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES owners(id);
+ALTER TABLE accounts ALTER COLUMN owner_id DROP DEFAULT;
+CREATE INDEX IF NOT EXISTS idx_accounts_owner ON accounts(owner_id);
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS owner_id UUID REFERENCES owners(id);
@@ -0,0 +1,15 @@
+-- The customers accounts belong to. Accounts opened before owners existed
+-- belong to the default owner; an API key may be bound to an owner instead
+-- of a single account.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+CREATE TABLE IF NOT EXISTS owners (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT,
+    created_at TEXT NOT NULL
+);
+INSERT OR IGNORE INTO owners (id, name, created_at)
+VALUES ('00000000-0000-0000-0000-000000000000', 'Default owner', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+ALTER TABLE accounts ADD COLUMN owner_id TEXT NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000';
+CREATE INDEX IF NOT EXISTS idx_accounts_owner ON accounts(owner_id);
+ALTER TABLE api_keys ADD COLUMN owner_id TEXT;
@@ -0,0 +1,13 @@
+-- Exchange rates locked for a later transfer. A quote is spent by the
+-- transfer it paid for, which it then names.
+CREATE TABLE IF NOT EXISTS fx_quotes (
+    id UUID PRIMARY KEY,
+    from_currency TEXT NOT NULL,
+    to_currency TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    rate DOUBLE PRECISION NOT NULL,
+    converted_amount BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    transaction_id UUID REFERENCES transactions(id)
+);
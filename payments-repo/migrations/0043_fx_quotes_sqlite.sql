@@ -0,0 +1,13 @@
+-- Exchange rates locked for a later transfer. A quote is spent by the
+-- transfer it paid for, which it then names.
+CREATE TABLE IF NOT EXISTS fx_quotes (
+    id TEXT PRIMARY KEY,
+    from_currency TEXT NOT NULL,
+    to_currency TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    rate REAL NOT NULL,
+    converted_amount INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    transaction_id TEXT REFERENCES transactions(id)
+);
@@ -0,0 +1,21 @@
+-- Responses stored by idempotency key, now for every POST endpoint. A key is
+-- scoped to the endpoint it was sent to and keeps the response's status next
+-- to its body. Records kept by 0028 were all for payments; they are moved
+-- over and the old table dropped.
+CREATE TABLE IF NOT EXISTS idempotency_responses (
+    endpoint TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    response JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (endpoint, idempotency_key)
+);
+
+INSERT INTO idempotency_responses
+    (endpoint, idempotency_key, request_hash, status, response, created_at)
+SELECT 'payments', idempotency_key, request_hash, 200, response, created_at
+FROM idempotency_records
+ON CONFLICT (endpoint, idempotency_key) DO NOTHING;
+
+DROP TABLE IF EXISTS idempotency_records;
@@ -0,0 +1,5 @@
+-- Test and live modes. A test API key only sees and moves money between
+-- test accounts; keys and accounts recorded before modes existed are live.
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'live';
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'live';
+CREATE INDEX IF NOT EXISTS idx_accounts_mode ON accounts(mode);
@@ -0,0 +1,6 @@
+-- Test and live modes. A test API key only sees and moves money between
+-- test accounts; keys and accounts recorded before modes existed are live.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE accounts ADD COLUMN mode TEXT NOT NULL DEFAULT 'live';
+CREATE INDEX IF NOT EXISTS idx_accounts_mode ON accounts(mode);
+ALTER TABLE api_keys ADD COLUMN mode TEXT NOT NULL DEFAULT 'live';
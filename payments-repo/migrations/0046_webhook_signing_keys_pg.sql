@@ -0,0 +1,8 @@
+-- Ed25519 endpoints signed with a key derived from their secret, which
+-- receivers are given. Each endpoint now gets a random signing key that
+-- stays on the server; existing endpoints are given one here, which changes
+-- the public key of those that sign with Ed25519.
+ALTER TABLE webhook_endpoints ADD COLUMN IF NOT EXISTS signing_key TEXT NOT NULL DEFAULT '';
+UPDATE webhook_endpoints
+SET signing_key = replace(gen_random_uuid()::text || gen_random_uuid()::text, '-', '')
+WHERE signing_key = '';
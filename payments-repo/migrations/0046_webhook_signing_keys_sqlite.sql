@@ -0,0 +1,7 @@
+-- Ed25519 endpoints signed with a key derived from their secret, which
+-- receivers are given. Each endpoint now gets a random signing key that
+-- stays on the server; existing endpoints are given one here, which changes
+-- the public key of those that sign with Ed25519.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE webhook_endpoints ADD COLUMN signing_key TEXT NOT NULL DEFAULT '';
+UPDATE webhook_endpoints SET signing_key = lower(hex(randomblob(32))) WHERE signing_key = '';
@@ -0,0 +1,7 @@
+-- The event log, webhook endpoints and their events belong to a mode, so a
+-- test key neither receives nor reads live events. Everything recorded
+-- before this is live.
+ALTER TABLE event_log ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'live';
+CREATE INDEX IF NOT EXISTS idx_event_log_mode ON event_log(mode, sequence);
+ALTER TABLE webhook_endpoints ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'live';
+ALTER TABLE webhook_events ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'live';
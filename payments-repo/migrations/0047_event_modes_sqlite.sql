@@ -0,0 +1,8 @@
+-- The event log, webhook endpoints and their events belong to a mode, so a
+-- test key neither receives nor reads live events. Everything recorded
+-- before this is live.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE event_log ADD COLUMN mode TEXT NOT NULL DEFAULT 'live';
+CREATE INDEX IF NOT EXISTS idx_event_log_mode ON event_log(mode, sequence);
+ALTER TABLE webhook_endpoints ADD COLUMN mode TEXT NOT NULL DEFAULT 'live';
+ALTER TABLE webhook_events ADD COLUMN mode TEXT NOT NULL DEFAULT 'live';
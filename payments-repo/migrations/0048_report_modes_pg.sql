@@ -0,0 +1,5 @@
+-- Settlement batches and exports belong to a mode, so test payouts never
+-- reach a live bank file and a test key cannot read live exports. Everything
+-- created before this is live.
+ALTER TABLE settlement_batches ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'live';
+ALTER TABLE exports ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'live';
@@ -0,0 +1,6 @@
+-- Settlement batches and exports belong to a mode, so test payouts never
+-- reach a live bank file and a test key cannot read live exports. Everything
+-- created before this is live.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE settlement_batches ADD COLUMN mode TEXT NOT NULL DEFAULT 'live';
+ALTER TABLE exports ADD COLUMN mode TEXT NOT NULL DEFAULT 'live';
@@ -0,0 +1,3 @@
+-- Quotes belong to the mode of the key that asked for them, so a test key
+-- cannot read or spend a live quote. Everything created before this is live.
+ALTER TABLE fx_quotes ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'live';
@@ -0,0 +1,4 @@
+-- Quotes belong to the mode of the key that asked for them, so a test key
+-- cannot read or spend a live quote. Everything created before this is live.
+-- SQLite has no ADD COLUMN IF NOT EXISTS; the adapter skips this file once applied.
+ALTER TABLE fx_quotes ADD COLUMN mode TEXT NOT NULL DEFAULT 'live';
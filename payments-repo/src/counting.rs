@@ -0,0 +1,1163 @@
+//! Repository call counting.
+//!
+//! `CountingRepo` wraps any `TransactionRepository` and tallies each call it
+//! forwards into the calls of the current task, scoped with
+//! [`count_repo_calls`]. The HTTP layer scopes every request this way, so a
+//! handler that queries once per row (an N+1 pattern) shows up in its call
+//! count. Calls made outside a scope, e.g. by background jobs or tasks a
+//! request spawns, are not counted.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use payments_types::{
+    Account, AccountAlias, AccountId, AccountLimits, AccountStatus, Alias, ApiKey,
+    ApiKeyAuditEntry, ApiKeyId, ApiKeyScope, ApiKeyUsageBucket, ApiKeyVolumeBucket, Beneficiary,
+    BeneficiaryId, ChangeRequest, ChangeRequestId, ChangeStatus, CreateAccountRequest,
+    CurrencyBalance, DailyBalance, DeadLetterFilter, DepositRequest, DomainEvent, Export, ExportId,
+    FeeAssignment, FeeSchedule, FeeScheduleId, FeeTier, FloatPosition, FxConversion, Hold, HoldId,
+    HoldStatus, IdempotencyRecord, LedgerOperation, LoggedEvent, MemoKey, MemoKeyId, Mode,
+    NewWebhookEndpoint, Owner, OwnerId, Quote, QuoteId, RateSnapshot, RepoError, RiskAssessment,
+    ScheduledPayment, ScheduledPaymentId, SettlementBatch, SettlementBatchId,
+    SettlementBatchStatus, SpendingRules, Statement, StatementId, StorageCounts, Transaction,
+    TransactionCursor, TransactionFilter, TransactionId, TransactionRepository, TransactionSearch,
+    TransferRequest, UnitOfWork, WebhookDeliveryFilter, WebhookEndpoint, WebhookEndpointId,
+    WebhookEvent, WebhookSignatureAlgorithm, WebhookTimeouts, WithdrawRequest,
+};
+
+tokio::task_local! {
+    static CALLS: Arc<RepoCalls>;
+}
+
+/// Repository calls made within one [`count_repo_calls`] scope.
+#[derive(Debug, Default)]
+pub struct RepoCalls {
+    by_operation: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+impl RepoCalls {
+    /// Total calls, across every operation.
+    pub fn total(&self) -> u64 {
+        self.by_operation.lock().unwrap().values().sum()
+    }
+
+    /// Calls per repository method, e.g. `get_account`.
+    pub fn by_operation(&self) -> BTreeMap<&'static str, u64> {
+        self.by_operation.lock().unwrap().clone()
+    }
+
+    fn add(&self, operation: &'static str) {
+        *self
+            .by_operation
+            .lock()
+            .unwrap()
+            .entry(operation)
+            .or_default() += 1;
+    }
+}
+
+/// Runs `future`, counting the calls it makes through a [`CountingRepo`].
+pub async fn count_repo_calls<F: Future>(future: F) -> (F::Output, Arc<RepoCalls>) {
+    let calls = Arc::new(RepoCalls::default());
+    let output = CALLS.scope(calls.clone(), future).await;
+    (output, calls)
+}
+
+fn count(operation: &'static str) {
+    let _ = CALLS.try_with(|calls| calls.add(operation));
+}
+
+/// Repository decorator that counts the calls made through it.
+pub struct CountingRepo<R> {
+    inner: R,
+}
+
+impl<R: TransactionRepository> CountingRepo<R> {
+    /// Wraps `inner`.
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+/// Counts the writes staged in a unit of work like any other call.
+struct CountingUnitOfWork<'a> {
+    inner: Box<dyn UnitOfWork + 'a>,
+}
+
+#[async_trait]
+impl UnitOfWork for CountingUnitOfWork<'_> {
+    async fn apply(&mut self, operation: LedgerOperation) -> Result<Transaction, RepoError> {
+        count("unit_of_work.apply");
+        self.inner.apply(operation).await
+    }
+
+    async fn lock_accounts(&mut self, account_ids: &[AccountId]) -> Result<(), RepoError> {
+        count("unit_of_work.lock_accounts");
+        self.inner.lock_accounts(account_ids).await
+    }
+
+    async fn debited_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        count("unit_of_work.debited_since");
+        self.inner.debited_since(account_id, since).await
+    }
+
+    async fn transactions_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        count("unit_of_work.transactions_since");
+        self.inner.transactions_since(account_id, since).await
+    }
+
+    async fn create_hold(&mut self, hold: &Hold) -> Result<(), RepoError> {
+        count("unit_of_work.create_hold");
+        self.inner.create_hold(hold).await
+    }
+
+    async fn record_transaction_risk(
+        &mut self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        count("unit_of_work.record_transaction_risk");
+        self.inner.record_transaction_risk(id, risk).await
+    }
+
+    async fn create_webhook_event(
+        &mut self,
+        endpoint_id: WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<WebhookEvent, RepoError> {
+        count("unit_of_work.create_webhook_event");
+        self.inner
+            .create_webhook_event(endpoint_id, event_type, payload)
+            .await
+    }
+
+    async fn append_event(&mut self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        count("unit_of_work.append_event");
+        self.inner.append_event(event, mode).await
+    }
+
+    async fn capture_hold(
+        &mut self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        count("unit_of_work.capture_hold");
+        self.inner.capture_hold(id, amount, now).await
+    }
+
+    async fn release_hold(
+        &mut self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        count("unit_of_work.release_hold");
+        self.inner.release_hold(id, status, now).await
+    }
+
+    async fn spend_quote(
+        &mut self,
+        id: QuoteId,
+        transaction_id: TransactionId,
+    ) -> Result<(), RepoError> {
+        count("unit_of_work.spend_quote");
+        self.inner.spend_quote(id, transaction_id).await
+    }
+
+    async fn close_merged_account(
+        &mut self,
+        id: AccountId,
+        into: AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        count("unit_of_work.close_merged_account");
+        self.inner.close_merged_account(id, into, now).await
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepoError> {
+        count("unit_of_work.commit");
+        self.inner.commit().await
+    }
+}
+
+#[async_trait]
+impl<R: TransactionRepository> TransactionRepository for CountingRepo<R> {
+    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
+        count("create_account");
+        self.inner.create_account(req).await
+    }
+
+    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
+        count("get_account");
+        self.inner.get_account(id).await
+    }
+
+    async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError> {
+        count("list_accounts");
+        self.inner.list_accounts(mode).await
+    }
+
+    async fn search_accounts(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: Mode,
+    ) -> Result<Vec<Account>, RepoError> {
+        count("search_accounts");
+        self.inner.search_accounts(query, limit, mode).await
+    }
+
+    async fn get_spending_rules(&self, account_id: AccountId) -> Result<SpendingRules, RepoError> {
+        count("get_spending_rules");
+        self.inner.get_spending_rules(account_id).await
+    }
+
+    async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<(), RepoError> {
+        count("set_spending_rules");
+        self.inner.set_spending_rules(account_id, rules).await
+    }
+
+    async fn get_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, RepoError> {
+        count("get_account_limits");
+        self.inner.get_account_limits(account_id).await
+    }
+
+    async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<(), RepoError> {
+        count("set_account_limits");
+        self.inner.set_account_limits(account_id, limits).await
+    }
+
+    async fn list_idle_accounts(
+        &self,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Account>, RepoError> {
+        count("list_idle_accounts");
+        self.inner.list_idle_accounts(idle_since, limit).await
+    }
+
+    async fn update_account_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        count("update_account_status");
+        self.inner.update_account_status(id, from, to, now).await
+    }
+
+    async fn set_overdraft_limit(
+        &self,
+        id: AccountId,
+        limit: i64,
+    ) -> Result<Option<Account>, RepoError> {
+        count("set_overdraft_limit");
+        self.inner.set_overdraft_limit(id, limit).await
+    }
+
+    async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError> {
+        count("add_account_alias");
+        self.inner.add_account_alias(alias).await
+    }
+
+    async fn get_account_alias(&self, alias: &Alias) -> Result<Option<AccountAlias>, RepoError> {
+        count("get_account_alias");
+        self.inner.get_account_alias(alias).await
+    }
+
+    async fn list_account_aliases(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, RepoError> {
+        count("list_account_aliases");
+        self.inner.list_account_aliases(account_id).await
+    }
+
+    async fn remove_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<bool, RepoError> {
+        count("remove_account_alias");
+        self.inner.remove_account_alias(account_id, alias).await
+    }
+
+    async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError> {
+        count("create_owner");
+        self.inner.create_owner(owner).await
+    }
+
+    async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError> {
+        count("get_owner");
+        self.inner.get_owner(id).await
+    }
+
+    async fn list_owners(&self) -> Result<Vec<Owner>, RepoError> {
+        count("list_owners");
+        self.inner.list_owners().await
+    }
+
+    async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError> {
+        count("update_owner");
+        self.inner.update_owner(owner).await
+    }
+
+    async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError> {
+        count("delete_owner");
+        self.inner.delete_owner(id).await
+    }
+
+    async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Account>, RepoError> {
+        count("list_owner_accounts");
+        self.inner.list_owner_accounts(owner_id, mode).await
+    }
+
+    async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError> {
+        count("add_memo_key");
+        self.inner.add_memo_key(key).await
+    }
+
+    async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError> {
+        count("get_memo_key");
+        self.inner.get_memo_key(id).await
+    }
+
+    async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError> {
+        count("list_memo_keys");
+        self.inner.list_memo_keys(account_id).await
+    }
+
+    async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        id: MemoKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<MemoKey>, RepoError> {
+        count("retire_memo_key");
+        self.inner.retire_memo_key(account_id, id, at).await
+    }
+
+    async fn create_fee_schedule(
+        &self,
+        name: &str,
+        tiers: &[FeeTier],
+    ) -> Result<FeeSchedule, RepoError> {
+        count("create_fee_schedule");
+        self.inner.create_fee_schedule(name, tiers).await
+    }
+
+    async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<Option<FeeSchedule>, RepoError> {
+        count("get_fee_schedule");
+        self.inner.get_fee_schedule(id).await
+    }
+
+    async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError> {
+        count("list_fee_schedules");
+        self.inner.list_fee_schedules().await
+    }
+
+    async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: DateTime<Utc>,
+    ) -> Result<FeeAssignment, RepoError> {
+        count("assign_fee_schedule");
+        self.inner
+            .assign_fee_schedule(account_id, fee_schedule_id, effective_from)
+            .await
+    }
+
+    async fn list_fee_assignments(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<FeeAssignment>, RepoError> {
+        count("list_fee_assignments");
+        self.inner.list_fee_assignments(account_id).await
+    }
+
+    async fn create_settlement_batch(
+        &self,
+        cutoff: DateTime<Utc>,
+        mode: Mode,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        count("create_settlement_batch");
+        self.inner.create_settlement_batch(cutoff, mode).await
+    }
+
+    async fn get_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        count("get_settlement_batch");
+        self.inner.get_settlement_batch(id).await
+    }
+
+    async fn list_settlement_batches(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<SettlementBatch>, RepoError> {
+        count("list_settlement_batches");
+        self.inner.list_settlement_batches(mode).await
+    }
+
+    async fn update_settlement_batch_status(
+        &self,
+        id: SettlementBatchId,
+        from: SettlementBatchStatus,
+        to: SettlementBatchStatus,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        count("update_settlement_batch_status");
+        self.inner
+            .update_settlement_batch_status(id, from, to)
+            .await
+    }
+
+    async fn list_settlement_batch_payouts(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        count("list_settlement_batch_payouts");
+        self.inner.list_settlement_batch_payouts(id).await
+    }
+
+    async fn get_statement_email(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<String>, RepoError> {
+        count("get_statement_email");
+        self.inner.get_statement_email(account_id).await
+    }
+
+    async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<(), RepoError> {
+        count("set_statement_email");
+        self.inner.set_statement_email(account_id, email).await
+    }
+
+    async fn insert_statement(
+        &self,
+        statement: &Statement,
+        content: &str,
+    ) -> Result<bool, RepoError> {
+        count("insert_statement");
+        self.inner.insert_statement(statement, content).await
+    }
+
+    async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError> {
+        count("get_statement");
+        self.inner.get_statement(id).await
+    }
+
+    async fn get_statement_content(&self, id: StatementId) -> Result<Option<String>, RepoError> {
+        count("get_statement_content");
+        self.inner.get_statement_content(id).await
+    }
+
+    async fn list_statements(&self, account_id: AccountId) -> Result<Vec<Statement>, RepoError> {
+        count("list_statements");
+        self.inner.list_statements(account_id).await
+    }
+
+    async fn insert_export(&self, export: &Export) -> Result<(), RepoError> {
+        count("insert_export");
+        self.inner.insert_export(export).await
+    }
+
+    async fn finish_export(&self, export: &Export, content: Option<&str>) -> Result<(), RepoError> {
+        count("finish_export");
+        self.inner.finish_export(export, content).await
+    }
+
+    async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError> {
+        count("get_export");
+        self.inner.get_export(id).await
+    }
+
+    async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError> {
+        count("get_export_content");
+        self.inner.get_export_content(id).await
+    }
+
+    async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError> {
+        count("delete_exports_before");
+        self.inner.delete_exports_before(cutoff).await
+    }
+
+    async fn insert_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<(), RepoError> {
+        count("insert_scheduled_payment");
+        self.inner.insert_scheduled_payment(payment).await
+    }
+
+    async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<Option<ScheduledPayment>, RepoError> {
+        count("get_scheduled_payment");
+        self.inner.get_scheduled_payment(id).await
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        count("list_scheduled_payments");
+        self.inner.list_scheduled_payments(account_id, mode).await
+    }
+
+    async fn list_due_scheduled_payments(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        count("list_due_scheduled_payments");
+        self.inner.list_due_scheduled_payments(now, limit).await
+    }
+
+    async fn record_scheduled_payment_run(
+        &self,
+        payment: &ScheduledPayment,
+        due_at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        count("record_scheduled_payment_run");
+        self.inner
+            .record_scheduled_payment_run(payment, due_at)
+            .await
+    }
+
+    async fn update_scheduled_payment_status(
+        &self,
+        payment: &ScheduledPayment,
+    ) -> Result<(), RepoError> {
+        count("update_scheduled_payment_status");
+        self.inner.update_scheduled_payment_status(payment).await
+    }
+
+    async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError> {
+        count("create_hold");
+        self.inner.create_hold(hold).await
+    }
+
+    async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError> {
+        count("get_hold");
+        self.inner.get_hold(id).await
+    }
+
+    async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError> {
+        count("list_holds");
+        self.inner.list_holds(account_id).await
+    }
+
+    async fn list_expired_holds(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Hold>, RepoError> {
+        count("list_expired_holds");
+        self.inner.list_expired_holds(now, limit).await
+    }
+
+    async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        count("capture_hold");
+        self.inner.capture_hold(id, amount, now).await
+    }
+
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        count("release_hold");
+        self.inner.release_hold(id, status, now).await
+    }
+
+    async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError> {
+        count("create_beneficiary");
+        self.inner.create_beneficiary(beneficiary).await
+    }
+
+    async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Option<Beneficiary>, RepoError> {
+        count("get_beneficiary");
+        self.inner.get_beneficiary(id).await
+    }
+
+    async fn list_beneficiaries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, RepoError> {
+        count("list_beneficiaries");
+        self.inner.list_beneficiaries(account_id).await
+    }
+
+    async fn record_beneficiary_attempt(
+        &self,
+        beneficiary: &Beneficiary,
+        failed_attempts: u32,
+    ) -> Result<bool, RepoError> {
+        count("record_beneficiary_attempt");
+        self.inner
+            .record_beneficiary_attempt(beneficiary, failed_attempts)
+            .await
+    }
+
+    async fn currency_balances(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<CurrencyBalance>, RepoError> {
+        count("currency_balances");
+        self.inner.currency_balances(mode).await
+    }
+
+    async fn float_positions(&self, mode: Option<Mode>) -> Result<Vec<FloatPosition>, RepoError> {
+        count("float_positions");
+        self.inner.float_positions(mode).await
+    }
+
+    async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError> {
+        count("insert_rate_snapshot");
+        self.inner.insert_rate_snapshot(snapshot).await
+    }
+
+    async fn rate_snapshot_at(&self, at: DateTime<Utc>) -> Result<Option<RateSnapshot>, RepoError> {
+        count("rate_snapshot_at");
+        self.inner.rate_snapshot_at(at).await
+    }
+
+    async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError> {
+        count("insert_quote");
+        self.inner.insert_quote(quote).await
+    }
+
+    async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError> {
+        count("get_quote");
+        self.inner.get_quote(id).await
+    }
+
+    async fn save_daily_balances(
+        &self,
+        account_id: AccountId,
+        balances: &[DailyBalance],
+    ) -> Result<(), RepoError> {
+        count("save_daily_balances");
+        self.inner.save_daily_balances(account_id, balances).await
+    }
+
+    async fn list_daily_balances(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyBalance>, RepoError> {
+        count("list_daily_balances");
+        self.inner.list_daily_balances(account_id, from, to).await
+    }
+
+    async fn latest_daily_balance_date(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<NaiveDate>, RepoError> {
+        count("latest_daily_balance_date");
+        self.inner.latest_daily_balance_date(account_id).await
+    }
+
+    async fn insert_change_request(&self, request: &ChangeRequest) -> Result<(), RepoError> {
+        count("insert_change_request");
+        self.inner.insert_change_request(request).await
+    }
+
+    async fn get_change_request(
+        &self,
+        id: ChangeRequestId,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        count("get_change_request");
+        self.inner.get_change_request(id).await
+    }
+
+    async fn list_change_requests(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, RepoError> {
+        count("list_change_requests");
+        self.inner.list_change_requests(status).await
+    }
+
+    async fn decide_change_request(
+        &self,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: ApiKeyId,
+        decided_at: DateTime<Utc>,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        count("decide_change_request");
+        self.inner
+            .decide_change_request(id, status, decided_by, decided_at)
+            .await
+    }
+
+    async fn get_idempotency_record(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, RepoError> {
+        count("get_idempotency_record");
+        self.inner.get_idempotency_record(endpoint, key).await
+    }
+
+    async fn save_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, RepoError> {
+        count("save_idempotency_record");
+        self.inner.save_idempotency_record(record).await
+    }
+
+    async fn replace_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<(), RepoError> {
+        count("replace_idempotency_record");
+        self.inner.replace_idempotency_record(record).await
+    }
+
+    async fn remove_idempotency_record(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        count("remove_idempotency_record");
+        self.inner.remove_idempotency_record(endpoint, key).await
+    }
+
+    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+        count("deposit");
+        self.inner.deposit(req).await
+    }
+
+    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+        count("withdraw");
+        self.inner.withdraw(req).await
+    }
+
+    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+        count("transfer");
+        self.inner.transfer(req).await
+    }
+
+    async fn transfer_with_conversion(
+        &self,
+        req: TransferRequest,
+        conversion: FxConversion,
+    ) -> Result<Transaction, RepoError> {
+        count("transfer_with_conversion");
+        self.inner.transfer_with_conversion(req, conversion).await
+    }
+
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork + '_>, RepoError> {
+        count("begin");
+        let work = self.inner.begin().await?;
+        Ok(Box::new(CountingUnitOfWork { inner: work }))
+    }
+
+    async fn record_transaction_risk(
+        &self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        count("record_transaction_risk");
+        self.inner.record_transaction_risk(id, risk).await
+    }
+
+    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
+        count("find_by_idempotency_key");
+        self.inner.find_by_idempotency_key(key).await
+    }
+
+    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
+        count("get_transaction");
+        self.inner.get_transaction(id).await
+    }
+
+    async fn list_transactions_for_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        count("list_transactions_for_account");
+        self.inner.list_transactions_for_account(account_id).await
+    }
+
+    async fn list_transactions_page(
+        &self,
+        account_id: AccountId,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        count("list_transactions_page");
+        self.inner
+            .list_transactions_page(account_id, filter, after, limit)
+            .await
+    }
+
+    async fn search_transactions(
+        &self,
+        search: &TransactionSearch,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        count("search_transactions");
+        self.inner.search_transactions(search, after, limit).await
+    }
+
+    async fn list_transactions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        count("list_transactions_between");
+        self.inner.list_transactions_between(from, to, mode).await
+    }
+
+    async fn verify_api_key_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, RepoError> {
+        count("verify_api_key_hash");
+        self.inner.verify_api_key_hash(key_hash).await
+    }
+
+    async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(ApiKey, String), RepoError> {
+        count("create_api_key");
+        self.inner.create_api_key(name, scopes, mode).await
+    }
+
+    async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(ApiKey, String), RepoError> {
+        count("create_scoped_api_key");
+        self.inner
+            .create_scoped_api_key(name, account_id, scopes, mode)
+            .await
+    }
+
+    async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(ApiKey, String), RepoError> {
+        count("create_owner_api_key");
+        self.inner
+            .create_owner_api_key(name, owner_id, scopes, mode)
+            .await
+    }
+
+    async fn count_api_keys(&self) -> Result<i64, RepoError> {
+        count("count_api_keys");
+        self.inner.count_api_keys().await
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<ApiKey>, RepoError> {
+        count("list_api_keys");
+        self.inner.list_api_keys().await
+    }
+
+    async fn delete_api_key(
+        &self,
+        id: ApiKeyId,
+        deactivated_by: ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        count("delete_api_key");
+        self.inner.delete_api_key(id, deactivated_by, at).await
+    }
+
+    async fn touch_api_key(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<(), RepoError> {
+        count("touch_api_key");
+        self.inner.touch_api_key(id, at).await
+    }
+
+    async fn set_api_key_rate_limit(
+        &self,
+        id: ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<bool, RepoError> {
+        count("set_api_key_rate_limit");
+        self.inner
+            .set_api_key_rate_limit(id, rate_limit_per_minute)
+            .await
+    }
+
+    async fn insert_api_key_audit(&self, entry: &ApiKeyAuditEntry) -> Result<(), RepoError> {
+        count("insert_api_key_audit");
+        self.inner.insert_api_key_audit(entry).await
+    }
+
+    async fn list_api_key_audit(&self, id: ApiKeyId) -> Result<Vec<ApiKeyAuditEntry>, RepoError> {
+        count("list_api_key_audit");
+        self.inner.list_api_key_audit(id).await
+    }
+
+    async fn get_api_key(&self, id: ApiKeyId) -> Result<Option<ApiKey>, RepoError> {
+        count("get_api_key");
+        self.inner.get_api_key(id).await
+    }
+
+    async fn add_api_key_usage(&self, usage: &ApiKeyUsageBucket) -> Result<(), RepoError> {
+        count("add_api_key_usage");
+        self.inner.add_api_key_usage(usage).await
+    }
+
+    async fn add_api_key_volume(&self, volume: &ApiKeyVolumeBucket) -> Result<(), RepoError> {
+        count("add_api_key_volume");
+        self.inner.add_api_key_volume(volume).await
+    }
+
+    async fn list_api_key_usage(
+        &self,
+        id: ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyUsageBucket>, RepoError> {
+        count("list_api_key_usage");
+        self.inner.list_api_key_usage(id, since).await
+    }
+
+    async fn list_api_key_volume(
+        &self,
+        id: ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyVolumeBucket>, RepoError> {
+        count("list_api_key_volume");
+        self.inner.list_api_key_volume(id, since).await
+    }
+
+    async fn register_webhook_endpoint(
+        &self,
+        url: &str,
+        events: Vec<String>,
+        timeouts: WebhookTimeouts,
+        signature_algorithm: WebhookSignatureAlgorithm,
+    ) -> Result<WebhookEndpoint, RepoError> {
+        count("register_webhook_endpoint");
+        self.inner
+            .register_webhook_endpoint(url, events, timeouts, signature_algorithm)
+            .await
+    }
+
+    async fn register_webhook_endpoints(
+        &self,
+        endpoints: Vec<NewWebhookEndpoint>,
+    ) -> Result<Vec<WebhookEndpoint>, RepoError> {
+        count("register_webhook_endpoints");
+        self.inner.register_webhook_endpoints(endpoints).await
+    }
+
+    async fn list_webhook_endpoints(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<WebhookEndpoint>, RepoError> {
+        count("list_webhook_endpoints");
+        self.inner.list_webhook_endpoints(mode).await
+    }
+
+    async fn get_webhook_endpoint(
+        &self,
+        id: WebhookEndpointId,
+    ) -> Result<Option<WebhookEndpoint>, RepoError> {
+        count("get_webhook_endpoint");
+        self.inner.get_webhook_endpoint(id).await
+    }
+
+    async fn update_webhook_endpoint_url(
+        &self,
+        id: WebhookEndpointId,
+        url: &str,
+    ) -> Result<Option<WebhookEndpoint>, RepoError> {
+        count("update_webhook_endpoint_url");
+        self.inner.update_webhook_endpoint_url(id, url).await
+    }
+
+    async fn update_webhook_endpoint(
+        &self,
+        id: WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<WebhookSignatureAlgorithm>,
+    ) -> Result<Option<WebhookEndpoint>, RepoError> {
+        count("update_webhook_endpoint");
+        self.inner
+            .update_webhook_endpoint(id, events, is_active, signature_algorithm)
+            .await
+    }
+
+    async fn rotate_webhook_secret(
+        &self,
+        id: WebhookEndpointId,
+    ) -> Result<Option<WebhookEndpoint>, RepoError> {
+        count("rotate_webhook_secret");
+        self.inner.rotate_webhook_secret(id).await
+    }
+
+    async fn delete_webhook_endpoint(&self, id: WebhookEndpointId) -> Result<bool, RepoError> {
+        count("delete_webhook_endpoint");
+        self.inner.delete_webhook_endpoint(id).await
+    }
+
+    async fn create_webhook_event(
+        &self,
+        endpoint_id: WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<WebhookEvent, RepoError> {
+        count("create_webhook_event");
+        self.inner
+            .create_webhook_event(endpoint_id, event_type, payload)
+            .await
+    }
+
+    async fn get_webhook_event(&self, id: uuid::Uuid) -> Result<Option<WebhookEvent>, RepoError> {
+        count("get_webhook_event");
+        self.inner.get_webhook_event(id).await
+    }
+
+    async fn list_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        count("list_dead_lettered_webhooks");
+        self.inner.list_dead_lettered_webhooks(filter).await
+    }
+
+    async fn requeue_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<u64, RepoError> {
+        count("requeue_dead_lettered_webhooks");
+        self.inner.requeue_dead_lettered_webhooks(filter).await
+    }
+
+    async fn count_webhook_backlog(&self) -> Result<i64, RepoError> {
+        count("count_webhook_backlog");
+        self.inner.count_webhook_backlog().await
+    }
+
+    async fn storage_counts(&self) -> Result<StorageCounts, RepoError> {
+        count("storage_counts");
+        self.inner.storage_counts().await
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        count("ping");
+        self.inner.ping().await
+    }
+
+    async fn list_webhook_events_after(
+        &self,
+        endpoint_id: WebhookEndpointId,
+        after_sequence: i64,
+        limit: usize,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        count("list_webhook_events_after");
+        self.inner
+            .list_webhook_events_after(endpoint_id, after_sequence, limit)
+            .await
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        endpoint_id: WebhookEndpointId,
+        filter: &WebhookDeliveryFilter,
+        limit: usize,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        count("list_webhook_deliveries");
+        self.inner
+            .list_webhook_deliveries(endpoint_id, filter, limit)
+            .await
+    }
+
+    async fn requeue_webhook_event(&self, id: uuid::Uuid) -> Result<bool, RepoError> {
+        count("requeue_webhook_event");
+        self.inner.requeue_webhook_event(id).await
+    }
+
+    async fn append_event(&self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        count("append_event");
+        self.inner.append_event(event, mode).await
+    }
+
+    async fn list_logged_events(
+        &self,
+        after: i64,
+        limit: usize,
+        mode: Option<Mode>,
+    ) -> Result<Vec<LoggedEvent>, RepoError> {
+        count("list_logged_events");
+        self.inner.list_logged_events(after, limit, mode).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_counts_only_within_a_scope() {
+        count("get_account");
+        let ((), calls) = count_repo_calls(async {
+            count("get_account");
+            count("list_accounts");
+            count("get_account");
+        })
+        .await;
+
+        assert_eq!(calls.total(), 3);
+        assert_eq!(
+            calls.by_operation(),
+            BTreeMap::from([("get_account", 2), ("list_accounts", 1)])
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Mapping from sqlx errors to `RepoError`.
+
+use payments_types::RepoError;
+
+/// Returns `true` for errors that mean the database could not be reached,
+/// as opposed to the query itself failing.
+fn is_unavailable(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::WorkerCrashed => true,
+        // Postgres SQLSTATE class 08 (connection exception), plus server
+        // shutdown/startup (57P01-57P03).
+        sqlx::Error::Database(db) => db.code().is_some_and(|code| {
+            code.starts_with("08") || matches!(&*code, "57P01" | "57P02" | "57P03")
+        }),
+        _ => false,
+    }
+}
+
+/// Returns `true` for errors that mean another writer got there first and
+/// the statement was not applied.
+fn is_conflict(err: &sqlx::Error) -> bool {
+    match err {
+        // SQLite SQLITE_BUSY and SQLITE_LOCKED (plus their extended codes),
+        // Postgres serialization failure and deadlock.
+        sqlx::Error::Database(db) => db
+            .code()
+            .is_some_and(|code| matches!(&*code, "5" | "6" | "262" | "517" | "40001" | "40P01")),
+        _ => false,
+    }
+}
+
+/// Maps a failed query to `RepoError::Unavailable`, `RepoError::Conflict` or
+/// `RepoError::Database`.
+pub(crate) fn db_error(err: sqlx::Error) -> RepoError {
+    if is_unavailable(&err) {
+        RepoError::Unavailable(err.to_string())
+    } else if is_conflict(&err) {
+        RepoError::Conflict(err.to_string())
+    } else {
+        RepoError::Database(err.to_string())
+    }
+}
+
+/// Maps a failed begin/commit to `RepoError::Unavailable`,
+/// `RepoError::Conflict` or `RepoError::Transaction`.
+pub(crate) fn tx_error(err: sqlx::Error) -> RepoError {
+    if is_unavailable(&err) {
+        RepoError::Unavailable(err.to_string())
+    } else if is_conflict(&err) {
+        RepoError::Conflict(err.to_string())
+    } else {
+        RepoError::Transaction(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_failures_are_unavailable() {
+        assert!(matches!(
+            db_error(sqlx::Error::PoolTimedOut),
+            RepoError::Unavailable(_)
+        ));
+        let reset = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+        assert!(matches!(
+            tx_error(sqlx::Error::Io(reset)),
+            RepoError::Unavailable(_)
+        ));
+    }
+
+    #[test]
+    fn test_query_failures_are_not_retried() {
+        assert!(matches!(
+            db_error(sqlx::Error::RowNotFound),
+            RepoError::Database(_)
+        ));
+        assert!(matches!(
+            tx_error(sqlx::Error::RowNotFound),
+            RepoError::Transaction(_)
+        ));
+    }
+}
@@ -6,10 +6,20 @@
 #[cfg(not(any(feature = "postgres", feature = "sqlite")))]
 compile_error!("Enable a repo feature: `postgres` or `sqlite`.");
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use payments_types::{
-    Account, AccountId, CreateAccountRequest, DepositRequest, RepoError, Transaction,
-    TransactionId, TransactionRepository, TransferRequest, WithdrawRequest,
+    Account, AccountAlias, AccountId, AccountLimits, AccountStatus, Alias, ApiKeyId,
+    ApiKeyUsageBucket, ApiKeyVolumeBucket, Beneficiary, BeneficiaryId, ChangeRequest,
+    ChangeRequestId, ChangeStatus, Clock, CreateAccountRequest, CurrencyBalance, DailyBalance,
+    DepositRequest, Export, ExportId, FeeAssignment, FeeSchedule, FeeScheduleId, FeeTier,
+    FloatPosition, Hold, HoldId, HoldStatus, IdGenerator, IdempotencyRecord, MemoKey, MemoKeyId,
+    Mode, Owner, OwnerId, Quote, QuoteId, RateSnapshot, RepoError, ScheduledPayment,
+    ScheduledPaymentId, SettlementBatch, SettlementBatchId, SettlementBatchStatus, SpendingRules,
+    Statement, StatementId, StorageCounts, Transaction, TransactionCursor, TransactionFilter,
+    TransactionId, TransactionRepository, TransactionSearch, TransferRequest, WithdrawRequest,
 };
 
 #[cfg(feature = "postgres")]
@@ -17,9 +27,13 @@ pub mod postgres;
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
 
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+mod error;
 #[cfg(any(feature = "postgres", feature = "sqlite"))]
 mod types;
 
+pub mod counting;
+pub mod retry;
 pub mod security;
 pub mod webhooks;
 
@@ -27,6 +41,19 @@ pub mod webhooks;
 #[cfg(test)]
 mod sqlite_tests;
 
+// The shared row types decode IDs as TEXT whenever `sqlite` is on, so the
+// Postgres adapter is only exercised in postgres-only builds.
+#[cfg(all(feature = "postgres", not(feature = "sqlite")))]
+#[cfg(test)]
+mod postgres_tests;
+
+#[cfg(test)]
+mod stress_tests;
+
+/// Number of the latest migration in `migrations/`; both adapters bring a
+/// database up to it when they connect.
+pub const SCHEMA_VERSION: u32 = 49;
+
 /// Unified repository wrapper that handles both SQLite and PostgreSQL.
 pub struct Repo {
     #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
@@ -68,6 +95,32 @@ impl Repo {
         Ok(Self { inner })
     }
 
+    /// Replaces the clock used by the underlying adapter.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.inner = self.inner.with_clock(clock);
+        self
+    }
+
+    /// Replaces the ID generator used by the underlying adapter.
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.inner = self.inner.with_id_generator(ids);
+        self
+    }
+
+    /// Returns what wakes the webhook worker: notifications of new events
+    /// on Postgres, a fixed poll on SQLite.
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    pub async fn webhook_event_source(&self) -> Box<dyn webhooks::WebhookEventSource> {
+        Box::new(webhooks::PollingSource::default())
+    }
+
+    /// Returns what wakes the webhook worker: notifications of new events
+    /// on Postgres, a fixed poll on SQLite.
+    #[cfg(feature = "postgres")]
+    pub async fn webhook_event_source(&self) -> Box<dyn webhooks::WebhookEventSource> {
+        self.inner.webhook_event_source().await
+    }
+
     pub async fn get_pending_webhooks(
         &self,
         limit: i64,
@@ -75,6 +128,19 @@ impl Repo {
         self.inner.get_pending_webhooks(limit).await
     }
 
+    pub async fn get_due_webhook_deliveries(
+        &self,
+        limit: i64,
+    ) -> Result<
+        Vec<(
+            payments_types::WebhookEvent,
+            Option<payments_types::WebhookEndpoint>,
+        )>,
+        RepoError,
+    > {
+        self.inner.get_due_webhook_deliveries(limit).await
+    }
+
     pub async fn update_webhook_status(
         &self,
         id: uuid::Uuid,
@@ -85,8 +151,20 @@ impl Repo {
             .update_webhook_status(id, status, last_error)
             .await
     }
+
+    pub async fn reschedule_webhook(
+        &self,
+        id: uuid::Uuid,
+        last_error: String,
+        delay: std::time::Duration,
+    ) -> Result<(), RepoError> {
+        self.inner.reschedule_webhook(id, last_error, delay).await
+    }
 }
 
+pub use counting::{CountingRepo, RepoCalls, count_repo_calls};
+pub use retry::{RetryPolicy, RetryRepo};
+
 // Re-export individual repos for direct use if needed
 #[cfg(feature = "postgres")]
 pub use postgres::PostgresRepo;
@@ -108,179 +186,1673 @@ impl TransactionRepository for Repo {
         self.inner.get_account(id).await
     }
 
-    async fn list_accounts(&self) -> Result<Vec<Account>, RepoError> {
-        self.inner.list_accounts().await
+    async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError> {
+        self.inner.list_accounts(mode).await
     }
 
-    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
-        self.inner.deposit(req).await
+    async fn search_accounts(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: Mode,
+    ) -> Result<Vec<Account>, RepoError> {
+        self.inner.search_accounts(query, limit, mode).await
     }
 
-    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
-        self.inner.withdraw(req).await
+    async fn get_spending_rules(&self, account_id: AccountId) -> Result<SpendingRules, RepoError> {
+        self.inner.get_spending_rules(account_id).await
     }
 
-    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
-        self.inner.transfer(req).await
+    async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<(), RepoError> {
+        self.inner.set_spending_rules(account_id, rules).await
     }
 
-    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
-        self.inner.find_by_idempotency_key(key).await
+    async fn get_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, RepoError> {
+        self.inner.get_account_limits(account_id).await
     }
 
-    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
-        self.inner.get_transaction(id).await
+    async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<(), RepoError> {
+        self.inner.set_account_limits(account_id, limits).await
     }
 
-    async fn list_transactions_for_account(
+    async fn list_idle_accounts(
+        &self,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Account>, RepoError> {
+        self.inner.list_idle_accounts(idle_since, limit).await
+    }
+
+    async fn update_account_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        self.inner.update_account_status(id, from, to, now).await
+    }
+
+    async fn set_overdraft_limit(
+        &self,
+        id: AccountId,
+        limit: i64,
+    ) -> Result<Option<Account>, RepoError> {
+        self.inner.set_overdraft_limit(id, limit).await
+    }
+
+    async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError> {
+        self.inner.add_account_alias(alias).await
+    }
+
+    async fn get_account_alias(&self, alias: &Alias) -> Result<Option<AccountAlias>, RepoError> {
+        self.inner.get_account_alias(alias).await
+    }
+
+    async fn list_account_aliases(
         &self,
         account_id: AccountId,
-    ) -> Result<Vec<Transaction>, RepoError> {
-        self.inner.list_transactions_for_account(account_id).await
+    ) -> Result<Vec<AccountAlias>, RepoError> {
+        self.inner.list_account_aliases(account_id).await
     }
 
-    async fn verify_api_key_hash(
+    async fn remove_account_alias(
         &self,
-        key_hash: &str,
-    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
-        self.inner.verify_api_key_hash(key_hash).await
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<bool, RepoError> {
+        self.inner.remove_account_alias(account_id, alias).await
     }
 
-    async fn create_api_key(
+    async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError> {
+        self.inner.create_owner(owner).await
+    }
+
+    async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError> {
+        self.inner.get_owner(id).await
+    }
+
+    async fn list_owners(&self) -> Result<Vec<Owner>, RepoError> {
+        self.inner.list_owners().await
+    }
+
+    async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError> {
+        self.inner.update_owner(owner).await
+    }
+
+    async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError> {
+        self.inner.delete_owner(id).await
+    }
+
+    async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Account>, RepoError> {
+        self.inner.list_owner_accounts(owner_id, mode).await
+    }
+
+    async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError> {
+        self.inner.add_memo_key(key).await
+    }
+
+    async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError> {
+        self.inner.get_memo_key(id).await
+    }
+
+    async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError> {
+        self.inner.list_memo_keys(account_id).await
+    }
+
+    async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        id: MemoKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<MemoKey>, RepoError> {
+        self.inner.retire_memo_key(account_id, id, at).await
+    }
+
+    async fn create_fee_schedule(
         &self,
         name: &str,
-    ) -> Result<(payments_types::ApiKey, String), RepoError> {
-        self.inner.create_api_key(name).await
+        tiers: &[FeeTier],
+    ) -> Result<FeeSchedule, RepoError> {
+        self.inner.create_fee_schedule(name, tiers).await
     }
 
-    async fn count_api_keys(&self) -> Result<i64, RepoError> {
-        self.inner.count_api_keys().await
+    async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<Option<FeeSchedule>, RepoError> {
+        self.inner.get_fee_schedule(id).await
     }
 
-    async fn list_api_keys(&self) -> Result<Vec<payments_types::ApiKey>, RepoError> {
-        self.inner.list_api_keys().await
+    async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError> {
+        self.inner.list_fee_schedules().await
+    }
+
+    async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: DateTime<Utc>,
+    ) -> Result<FeeAssignment, RepoError> {
+        self.inner
+            .assign_fee_schedule(account_id, fee_schedule_id, effective_from)
+            .await
     }
 
-    async fn delete_api_key(&self, id: payments_types::ApiKeyId) -> Result<bool, RepoError> {
-        self.inner.delete_api_key(id).await
+    async fn list_fee_assignments(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<FeeAssignment>, RepoError> {
+        self.inner.list_fee_assignments(account_id).await
     }
 
-    async fn register_webhook_endpoint(
+    async fn create_settlement_batch(
         &self,
-        url: &str,
-        events: Vec<String>,
-    ) -> Result<payments_types::WebhookEndpoint, RepoError> {
-        self.inner.register_webhook_endpoint(url, events).await
+        cutoff: DateTime<Utc>,
+        mode: Mode,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        self.inner.create_settlement_batch(cutoff, mode).await
     }
 
-    async fn list_webhook_endpoints(
+    async fn get_settlement_batch(
         &self,
-    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
-        self.inner.list_webhook_endpoints().await
+        id: SettlementBatchId,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        self.inner.get_settlement_batch(id).await
     }
 
-    async fn create_webhook_event(
+    async fn list_settlement_batches(
         &self,
-        endpoint_id: payments_types::WebhookEndpointId,
-        event_type: &str,
-        payload: serde_json::Value,
-    ) -> Result<payments_types::WebhookEvent, RepoError> {
+        mode: Option<Mode>,
+    ) -> Result<Vec<SettlementBatch>, RepoError> {
+        self.inner.list_settlement_batches(mode).await
+    }
+
+    async fn update_settlement_batch_status(
+        &self,
+        id: SettlementBatchId,
+        from: SettlementBatchStatus,
+        to: SettlementBatchStatus,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
         self.inner
-            .create_webhook_event(endpoint_id, event_type, payload)
+            .update_settlement_batch_status(id, from, to)
             .await
     }
-}
 
-#[cfg(feature = "postgres")]
-#[async_trait]
-impl TransactionRepository for Repo {
-    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
-        self.inner.create_account(req).await
+    async fn list_settlement_batch_payouts(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner.list_settlement_batch_payouts(id).await
     }
 
-    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
-        self.inner.get_account(id).await
+    async fn get_statement_email(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<String>, RepoError> {
+        self.inner.get_statement_email(account_id).await
     }
 
-    async fn list_accounts(&self) -> Result<Vec<Account>, RepoError> {
-        self.inner.list_accounts().await
+    async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<(), RepoError> {
+        self.inner.set_statement_email(account_id, email).await
     }
 
-    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
-        self.inner.deposit(req).await
+    async fn insert_statement(
+        &self,
+        statement: &Statement,
+        content: &str,
+    ) -> Result<bool, RepoError> {
+        self.inner.insert_statement(statement, content).await
     }
 
-    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
-        self.inner.withdraw(req).await
+    async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError> {
+        self.inner.get_statement(id).await
     }
 
-    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
-        self.inner.transfer(req).await
+    async fn get_statement_content(&self, id: StatementId) -> Result<Option<String>, RepoError> {
+        self.inner.get_statement_content(id).await
     }
 
-    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
-        self.inner.find_by_idempotency_key(key).await
+    async fn list_statements(&self, account_id: AccountId) -> Result<Vec<Statement>, RepoError> {
+        self.inner.list_statements(account_id).await
     }
 
-    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
-        self.inner.get_transaction(id).await
+    async fn insert_export(&self, export: &Export) -> Result<(), RepoError> {
+        self.inner.insert_export(export).await
     }
 
-    async fn list_transactions_for_account(
+    async fn finish_export(&self, export: &Export, content: Option<&str>) -> Result<(), RepoError> {
+        self.inner.finish_export(export, content).await
+    }
+
+    async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError> {
+        self.inner.get_export(id).await
+    }
+
+    async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError> {
+        self.inner.get_export_content(id).await
+    }
+
+    async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError> {
+        self.inner.delete_exports_before(cutoff).await
+    }
+
+    async fn insert_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<(), RepoError> {
+        self.inner.insert_scheduled_payment(payment).await
+    }
+
+    async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<Option<ScheduledPayment>, RepoError> {
+        self.inner.get_scheduled_payment(id).await
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        self.inner.list_scheduled_payments(account_id, mode).await
+    }
+
+    async fn list_due_scheduled_payments(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        self.inner.list_due_scheduled_payments(now, limit).await
+    }
+
+    async fn record_scheduled_payment_run(
+        &self,
+        payment: &ScheduledPayment,
+        due_at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        self.inner
+            .record_scheduled_payment_run(payment, due_at)
+            .await
+    }
+
+    async fn update_scheduled_payment_status(
+        &self,
+        payment: &ScheduledPayment,
+    ) -> Result<(), RepoError> {
+        self.inner.update_scheduled_payment_status(payment).await
+    }
+
+    async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError> {
+        self.inner.create_hold(hold).await
+    }
+
+    async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError> {
+        self.inner.get_hold(id).await
+    }
+
+    async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError> {
+        self.inner.list_holds(account_id).await
+    }
+
+    async fn list_expired_holds(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Hold>, RepoError> {
+        self.inner.list_expired_holds(now, limit).await
+    }
+
+    async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        self.inner.capture_hold(id, amount, now).await
+    }
+
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        self.inner.release_hold(id, status, now).await
+    }
+
+    async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError> {
+        self.inner.create_beneficiary(beneficiary).await
+    }
+
+    async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Option<Beneficiary>, RepoError> {
+        self.inner.get_beneficiary(id).await
+    }
+
+    async fn list_beneficiaries(
         &self,
         account_id: AccountId,
-    ) -> Result<Vec<Transaction>, RepoError> {
-        self.inner.list_transactions_for_account(account_id).await
+    ) -> Result<Vec<Beneficiary>, RepoError> {
+        self.inner.list_beneficiaries(account_id).await
     }
 
-    async fn verify_api_key_hash(
+    async fn record_beneficiary_attempt(
         &self,
-        key_hash: &str,
-    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
-        self.inner.verify_api_key_hash(key_hash).await
+        beneficiary: &Beneficiary,
+        failed_attempts: u32,
+    ) -> Result<bool, RepoError> {
+        self.inner
+            .record_beneficiary_attempt(beneficiary, failed_attempts)
+            .await
     }
 
-    async fn create_api_key(
+    async fn currency_balances(
         &self,
-        name: &str,
-    ) -> Result<(payments_types::ApiKey, String), RepoError> {
-        self.inner.create_api_key(name).await
+        mode: Option<Mode>,
+    ) -> Result<Vec<CurrencyBalance>, RepoError> {
+        self.inner.currency_balances(mode).await
     }
 
-    async fn count_api_keys(&self) -> Result<i64, RepoError> {
-        self.inner.count_api_keys().await
+    async fn float_positions(&self, mode: Option<Mode>) -> Result<Vec<FloatPosition>, RepoError> {
+        self.inner.float_positions(mode).await
     }
 
-    async fn list_api_keys(&self) -> Result<Vec<payments_types::ApiKey>, RepoError> {
-        self.inner.list_api_keys().await
+    async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError> {
+        self.inner.insert_rate_snapshot(snapshot).await
     }
 
-    async fn delete_api_key(&self, id: payments_types::ApiKeyId) -> Result<bool, RepoError> {
-        self.inner.delete_api_key(id).await
+    async fn rate_snapshot_at(&self, at: DateTime<Utc>) -> Result<Option<RateSnapshot>, RepoError> {
+        self.inner.rate_snapshot_at(at).await
     }
 
-    async fn register_webhook_endpoint(
+    async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError> {
+        self.inner.insert_quote(quote).await
+    }
+
+    async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError> {
+        self.inner.get_quote(id).await
+    }
+
+    async fn save_daily_balances(
         &self,
-        url: &str,
-        events: Vec<String>,
-    ) -> Result<payments_types::WebhookEndpoint, RepoError> {
-        self.inner.register_webhook_endpoint(url, events).await
+        account_id: AccountId,
+        balances: &[DailyBalance],
+    ) -> Result<(), RepoError> {
+        self.inner.save_daily_balances(account_id, balances).await
     }
 
-    async fn list_webhook_endpoints(
+    async fn list_daily_balances(
         &self,
-    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
-        self.inner.list_webhook_endpoints().await
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyBalance>, RepoError> {
+        self.inner.list_daily_balances(account_id, from, to).await
     }
 
-    async fn create_webhook_event(
+    async fn latest_daily_balance_date(
         &self,
-        endpoint_id: payments_types::WebhookEndpointId,
-        event_type: &str,
-        payload: serde_json::Value,
-    ) -> Result<payments_types::WebhookEvent, RepoError> {
+        account_id: AccountId,
+    ) -> Result<Option<NaiveDate>, RepoError> {
+        self.inner.latest_daily_balance_date(account_id).await
+    }
+
+    async fn insert_change_request(&self, request: &ChangeRequest) -> Result<(), RepoError> {
+        self.inner.insert_change_request(request).await
+    }
+
+    async fn get_change_request(
+        &self,
+        id: ChangeRequestId,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        self.inner.get_change_request(id).await
+    }
+
+    async fn list_change_requests(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, RepoError> {
+        self.inner.list_change_requests(status).await
+    }
+
+    async fn decide_change_request(
+        &self,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: ApiKeyId,
+        decided_at: DateTime<Utc>,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
         self.inner
-            .create_webhook_event(endpoint_id, event_type, payload)
+            .decide_change_request(id, status, decided_by, decided_at)
             .await
     }
+
+    async fn get_idempotency_record(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, RepoError> {
+        self.inner.get_idempotency_record(endpoint, key).await
+    }
+
+    async fn save_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, RepoError> {
+        self.inner.save_idempotency_record(record).await
+    }
+
+    async fn replace_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<(), RepoError> {
+        self.inner.replace_idempotency_record(record).await
+    }
+
+    async fn remove_idempotency_record(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        self.inner.remove_idempotency_record(endpoint, key).await
+    }
+
+    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+        self.inner.deposit(req).await
+    }
+
+    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+        self.inner.withdraw(req).await
+    }
+
+    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+        self.inner.transfer(req).await
+    }
+
+    async fn transfer_with_conversion(
+        &self,
+        req: TransferRequest,
+        conversion: payments_types::FxConversion,
+    ) -> Result<Transaction, RepoError> {
+        self.inner.transfer_with_conversion(req, conversion).await
+    }
+
+    async fn begin(&self) -> Result<Box<dyn payments_types::UnitOfWork + '_>, RepoError> {
+        self.inner.begin().await
+    }
+
+    async fn record_transaction_risk(
+        &self,
+        id: TransactionId,
+        risk: &payments_types::RiskAssessment,
+    ) -> Result<(), RepoError> {
+        self.inner.record_transaction_risk(id, risk).await
+    }
+
+    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
+        self.inner.find_by_idempotency_key(key).await
+    }
+
+    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
+        self.inner.get_transaction(id).await
+    }
+
+    async fn list_transactions_for_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner.list_transactions_for_account(account_id).await
+    }
+
+    async fn list_transactions_page(
+        &self,
+        account_id: AccountId,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner
+            .list_transactions_page(account_id, filter, after, limit)
+            .await
+    }
+
+    async fn search_transactions(
+        &self,
+        search: &TransactionSearch,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner.search_transactions(search, after, limit).await
+    }
+
+    async fn list_transactions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner.list_transactions_between(from, to, mode).await
+    }
+
+    async fn verify_api_key_hash(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+        self.inner.verify_api_key_hash(key_hash).await
+    }
+
+    async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.inner.create_api_key(name, scopes, mode).await
+    }
+
+    async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.inner
+            .create_scoped_api_key(name, account_id, scopes, mode)
+            .await
+    }
+
+    async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.inner
+            .create_owner_api_key(name, owner_id, scopes, mode)
+            .await
+    }
+
+    async fn count_api_keys(&self) -> Result<i64, RepoError> {
+        self.inner.count_api_keys().await
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<payments_types::ApiKey>, RepoError> {
+        self.inner.list_api_keys().await
+    }
+
+    async fn delete_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+        deactivated_by: payments_types::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        self.inner.delete_api_key(id, deactivated_by, at).await
+    }
+
+    async fn touch_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<(), RepoError> {
+        self.inner.touch_api_key(id, at).await
+    }
+
+    async fn set_api_key_rate_limit(
+        &self,
+        id: payments_types::ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<bool, RepoError> {
+        self.inner
+            .set_api_key_rate_limit(id, rate_limit_per_minute)
+            .await
+    }
+
+    async fn insert_api_key_audit(
+        &self,
+        entry: &payments_types::ApiKeyAuditEntry,
+    ) -> Result<(), RepoError> {
+        self.inner.insert_api_key_audit(entry).await
+    }
+
+    async fn list_api_key_audit(
+        &self,
+        id: payments_types::ApiKeyId,
+    ) -> Result<Vec<payments_types::ApiKeyAuditEntry>, RepoError> {
+        self.inner.list_api_key_audit(id).await
+    }
+
+    async fn get_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+        self.inner.get_api_key(id).await
+    }
+
+    async fn add_api_key_usage(&self, usage: &ApiKeyUsageBucket) -> Result<(), RepoError> {
+        self.inner.add_api_key_usage(usage).await
+    }
+
+    async fn add_api_key_volume(&self, volume: &ApiKeyVolumeBucket) -> Result<(), RepoError> {
+        self.inner.add_api_key_volume(volume).await
+    }
+
+    async fn list_api_key_usage(
+        &self,
+        id: payments_types::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyUsageBucket>, RepoError> {
+        self.inner.list_api_key_usage(id, since).await
+    }
+
+    async fn list_api_key_volume(
+        &self,
+        id: payments_types::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyVolumeBucket>, RepoError> {
+        self.inner.list_api_key_volume(id, since).await
+    }
+
+    async fn register_webhook_endpoint(
+        &self,
+        url: &str,
+        events: Vec<String>,
+        timeouts: payments_types::WebhookTimeouts,
+        signature_algorithm: payments_types::WebhookSignatureAlgorithm,
+    ) -> Result<payments_types::WebhookEndpoint, RepoError> {
+        self.inner
+            .register_webhook_endpoint(url, events, timeouts, signature_algorithm)
+            .await
+    }
+
+    async fn register_webhook_endpoints(
+        &self,
+        endpoints: Vec<payments_types::NewWebhookEndpoint>,
+    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.register_webhook_endpoints(endpoints).await
+    }
+
+    async fn list_webhook_endpoints(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.list_webhook_endpoints(mode).await
+    }
+
+    async fn get_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.get_webhook_endpoint(id).await
+    }
+
+    async fn update_webhook_endpoint_url(
+        &self,
+        id: payments_types::WebhookEndpointId,
+        url: &str,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.update_webhook_endpoint_url(id, url).await
+    }
+
+    async fn update_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<payments_types::WebhookSignatureAlgorithm>,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner
+            .update_webhook_endpoint(id, events, is_active, signature_algorithm)
+            .await
+    }
+
+    async fn rotate_webhook_secret(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.rotate_webhook_secret(id).await
+    }
+
+    async fn delete_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<bool, RepoError> {
+        self.inner.delete_webhook_endpoint(id).await
+    }
+
+    async fn create_webhook_event(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<payments_types::WebhookEvent, RepoError> {
+        self.inner
+            .create_webhook_event(endpoint_id, event_type, payload)
+            .await
+    }
+
+    async fn get_webhook_event(
+        &self,
+        id: uuid::Uuid,
+    ) -> Result<Option<payments_types::WebhookEvent>, RepoError> {
+        self.inner.get_webhook_event(id).await
+    }
+
+    async fn list_dead_lettered_webhooks(
+        &self,
+        filter: &payments_types::DeadLetterFilter,
+    ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+        self.inner.list_dead_lettered_webhooks(filter).await
+    }
+
+    async fn requeue_dead_lettered_webhooks(
+        &self,
+        filter: &payments_types::DeadLetterFilter,
+    ) -> Result<u64, RepoError> {
+        self.inner.requeue_dead_lettered_webhooks(filter).await
+    }
+
+    async fn count_webhook_backlog(&self) -> Result<i64, RepoError> {
+        self.inner.count_webhook_backlog().await
+    }
+
+    async fn storage_counts(&self) -> Result<StorageCounts, RepoError> {
+        self.inner.storage_counts().await
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        self.inner.ping().await
+    }
+
+    async fn list_webhook_events_after(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        after_sequence: i64,
+        limit: usize,
+    ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+        self.inner
+            .list_webhook_events_after(endpoint_id, after_sequence, limit)
+            .await
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        filter: &payments_types::WebhookDeliveryFilter,
+        limit: usize,
+    ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+        self.inner
+            .list_webhook_deliveries(endpoint_id, filter, limit)
+            .await
+    }
+
+    async fn requeue_webhook_event(&self, id: uuid::Uuid) -> Result<bool, RepoError> {
+        self.inner.requeue_webhook_event(id).await
+    }
+
+    async fn append_event(
+        &self,
+        event: &payments_types::DomainEvent,
+        mode: Mode,
+    ) -> Result<(), RepoError> {
+        self.inner.append_event(event, mode).await
+    }
+
+    async fn list_logged_events(
+        &self,
+        after: i64,
+        limit: usize,
+        mode: Option<Mode>,
+    ) -> Result<Vec<payments_types::LoggedEvent>, RepoError> {
+        self.inner.list_logged_events(after, limit, mode).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl TransactionRepository for Repo {
+    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
+        self.inner.create_account(req).await
+    }
+
+    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
+        self.inner.get_account(id).await
+    }
+
+    async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError> {
+        self.inner.list_accounts(mode).await
+    }
+
+    async fn search_accounts(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: Mode,
+    ) -> Result<Vec<Account>, RepoError> {
+        self.inner.search_accounts(query, limit, mode).await
+    }
+
+    async fn get_spending_rules(&self, account_id: AccountId) -> Result<SpendingRules, RepoError> {
+        self.inner.get_spending_rules(account_id).await
+    }
+
+    async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<(), RepoError> {
+        self.inner.set_spending_rules(account_id, rules).await
+    }
+
+    async fn get_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, RepoError> {
+        self.inner.get_account_limits(account_id).await
+    }
+
+    async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<(), RepoError> {
+        self.inner.set_account_limits(account_id, limits).await
+    }
+
+    async fn list_idle_accounts(
+        &self,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Account>, RepoError> {
+        self.inner.list_idle_accounts(idle_since, limit).await
+    }
+
+    async fn update_account_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        self.inner.update_account_status(id, from, to, now).await
+    }
+
+    async fn set_overdraft_limit(
+        &self,
+        id: AccountId,
+        limit: i64,
+    ) -> Result<Option<Account>, RepoError> {
+        self.inner.set_overdraft_limit(id, limit).await
+    }
+
+    async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError> {
+        self.inner.add_account_alias(alias).await
+    }
+
+    async fn get_account_alias(&self, alias: &Alias) -> Result<Option<AccountAlias>, RepoError> {
+        self.inner.get_account_alias(alias).await
+    }
+
+    async fn list_account_aliases(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, RepoError> {
+        self.inner.list_account_aliases(account_id).await
+    }
+
+    async fn remove_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<bool, RepoError> {
+        self.inner.remove_account_alias(account_id, alias).await
+    }
+
+    async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError> {
+        self.inner.create_owner(owner).await
+    }
+
+    async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError> {
+        self.inner.get_owner(id).await
+    }
+
+    async fn list_owners(&self) -> Result<Vec<Owner>, RepoError> {
+        self.inner.list_owners().await
+    }
+
+    async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError> {
+        self.inner.update_owner(owner).await
+    }
+
+    async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError> {
+        self.inner.delete_owner(id).await
+    }
+
+    async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Account>, RepoError> {
+        self.inner.list_owner_accounts(owner_id, mode).await
+    }
+
+    async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError> {
+        self.inner.add_memo_key(key).await
+    }
+
+    async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError> {
+        self.inner.get_memo_key(id).await
+    }
+
+    async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError> {
+        self.inner.list_memo_keys(account_id).await
+    }
+
+    async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        id: MemoKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<MemoKey>, RepoError> {
+        self.inner.retire_memo_key(account_id, id, at).await
+    }
+
+    async fn create_fee_schedule(
+        &self,
+        name: &str,
+        tiers: &[FeeTier],
+    ) -> Result<FeeSchedule, RepoError> {
+        self.inner.create_fee_schedule(name, tiers).await
+    }
+
+    async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<Option<FeeSchedule>, RepoError> {
+        self.inner.get_fee_schedule(id).await
+    }
+
+    async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError> {
+        self.inner.list_fee_schedules().await
+    }
+
+    async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: DateTime<Utc>,
+    ) -> Result<FeeAssignment, RepoError> {
+        self.inner
+            .assign_fee_schedule(account_id, fee_schedule_id, effective_from)
+            .await
+    }
+
+    async fn list_fee_assignments(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<FeeAssignment>, RepoError> {
+        self.inner.list_fee_assignments(account_id).await
+    }
+
+    async fn create_settlement_batch(
+        &self,
+        cutoff: DateTime<Utc>,
+        mode: Mode,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        self.inner.create_settlement_batch(cutoff, mode).await
+    }
+
+    async fn get_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        self.inner.get_settlement_batch(id).await
+    }
+
+    async fn list_settlement_batches(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<SettlementBatch>, RepoError> {
+        self.inner.list_settlement_batches(mode).await
+    }
+
+    async fn update_settlement_batch_status(
+        &self,
+        id: SettlementBatchId,
+        from: SettlementBatchStatus,
+        to: SettlementBatchStatus,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        self.inner
+            .update_settlement_batch_status(id, from, to)
+            .await
+    }
+
+    async fn list_settlement_batch_payouts(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner.list_settlement_batch_payouts(id).await
+    }
+
+    async fn get_statement_email(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<String>, RepoError> {
+        self.inner.get_statement_email(account_id).await
+    }
+
+    async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<(), RepoError> {
+        self.inner.set_statement_email(account_id, email).await
+    }
+
+    async fn insert_statement(
+        &self,
+        statement: &Statement,
+        content: &str,
+    ) -> Result<bool, RepoError> {
+        self.inner.insert_statement(statement, content).await
+    }
+
+    async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError> {
+        self.inner.get_statement(id).await
+    }
+
+    async fn get_statement_content(&self, id: StatementId) -> Result<Option<String>, RepoError> {
+        self.inner.get_statement_content(id).await
+    }
+
+    async fn list_statements(&self, account_id: AccountId) -> Result<Vec<Statement>, RepoError> {
+        self.inner.list_statements(account_id).await
+    }
+
+    async fn insert_export(&self, export: &Export) -> Result<(), RepoError> {
+        self.inner.insert_export(export).await
+    }
+
+    async fn finish_export(&self, export: &Export, content: Option<&str>) -> Result<(), RepoError> {
+        self.inner.finish_export(export, content).await
+    }
+
+    async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError> {
+        self.inner.get_export(id).await
+    }
+
+    async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError> {
+        self.inner.get_export_content(id).await
+    }
+
+    async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError> {
+        self.inner.delete_exports_before(cutoff).await
+    }
+
+    async fn insert_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<(), RepoError> {
+        self.inner.insert_scheduled_payment(payment).await
+    }
+
+    async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<Option<ScheduledPayment>, RepoError> {
+        self.inner.get_scheduled_payment(id).await
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        self.inner.list_scheduled_payments(account_id, mode).await
+    }
+
+    async fn list_due_scheduled_payments(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        self.inner.list_due_scheduled_payments(now, limit).await
+    }
+
+    async fn record_scheduled_payment_run(
+        &self,
+        payment: &ScheduledPayment,
+        due_at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        self.inner
+            .record_scheduled_payment_run(payment, due_at)
+            .await
+    }
+
+    async fn update_scheduled_payment_status(
+        &self,
+        payment: &ScheduledPayment,
+    ) -> Result<(), RepoError> {
+        self.inner.update_scheduled_payment_status(payment).await
+    }
+
+    async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError> {
+        self.inner.create_hold(hold).await
+    }
+
+    async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError> {
+        self.inner.get_hold(id).await
+    }
+
+    async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError> {
+        self.inner.list_holds(account_id).await
+    }
+
+    async fn list_expired_holds(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Hold>, RepoError> {
+        self.inner.list_expired_holds(now, limit).await
+    }
+
+    async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        self.inner.capture_hold(id, amount, now).await
+    }
+
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        self.inner.release_hold(id, status, now).await
+    }
+
+    async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError> {
+        self.inner.create_beneficiary(beneficiary).await
+    }
+
+    async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Option<Beneficiary>, RepoError> {
+        self.inner.get_beneficiary(id).await
+    }
+
+    async fn list_beneficiaries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, RepoError> {
+        self.inner.list_beneficiaries(account_id).await
+    }
+
+    async fn record_beneficiary_attempt(
+        &self,
+        beneficiary: &Beneficiary,
+        failed_attempts: u32,
+    ) -> Result<bool, RepoError> {
+        self.inner
+            .record_beneficiary_attempt(beneficiary, failed_attempts)
+            .await
+    }
+
+    async fn currency_balances(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<CurrencyBalance>, RepoError> {
+        self.inner.currency_balances(mode).await
+    }
+
+    async fn float_positions(&self, mode: Option<Mode>) -> Result<Vec<FloatPosition>, RepoError> {
+        self.inner.float_positions(mode).await
+    }
+
+    async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError> {
+        self.inner.insert_rate_snapshot(snapshot).await
+    }
+
+    async fn rate_snapshot_at(&self, at: DateTime<Utc>) -> Result<Option<RateSnapshot>, RepoError> {
+        self.inner.rate_snapshot_at(at).await
+    }
+
+    async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError> {
+        self.inner.insert_quote(quote).await
+    }
+
+    async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError> {
+        self.inner.get_quote(id).await
+    }
+
+    async fn save_daily_balances(
+        &self,
+        account_id: AccountId,
+        balances: &[DailyBalance],
+    ) -> Result<(), RepoError> {
+        self.inner.save_daily_balances(account_id, balances).await
+    }
+
+    async fn list_daily_balances(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyBalance>, RepoError> {
+        self.inner.list_daily_balances(account_id, from, to).await
+    }
+
+    async fn latest_daily_balance_date(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<NaiveDate>, RepoError> {
+        self.inner.latest_daily_balance_date(account_id).await
+    }
+
+    async fn insert_change_request(&self, request: &ChangeRequest) -> Result<(), RepoError> {
+        self.inner.insert_change_request(request).await
+    }
+
+    async fn get_change_request(
+        &self,
+        id: ChangeRequestId,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        self.inner.get_change_request(id).await
+    }
+
+    async fn list_change_requests(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, RepoError> {
+        self.inner.list_change_requests(status).await
+    }
+
+    async fn decide_change_request(
+        &self,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: ApiKeyId,
+        decided_at: DateTime<Utc>,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        self.inner
+            .decide_change_request(id, status, decided_by, decided_at)
+            .await
+    }
+
+    async fn get_idempotency_record(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, RepoError> {
+        self.inner.get_idempotency_record(endpoint, key).await
+    }
+
+    async fn save_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, RepoError> {
+        self.inner.save_idempotency_record(record).await
+    }
+
+    async fn replace_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<(), RepoError> {
+        self.inner.replace_idempotency_record(record).await
+    }
+
+    async fn remove_idempotency_record(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        self.inner.remove_idempotency_record(endpoint, key).await
+    }
+
+    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+        self.inner.deposit(req).await
+    }
+
+    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+        self.inner.withdraw(req).await
+    }
+
+    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+        self.inner.transfer(req).await
+    }
+
+    async fn transfer_with_conversion(
+        &self,
+        req: TransferRequest,
+        conversion: payments_types::FxConversion,
+    ) -> Result<Transaction, RepoError> {
+        self.inner.transfer_with_conversion(req, conversion).await
+    }
+
+    async fn begin(&self) -> Result<Box<dyn payments_types::UnitOfWork + '_>, RepoError> {
+        self.inner.begin().await
+    }
+
+    async fn record_transaction_risk(
+        &self,
+        id: TransactionId,
+        risk: &payments_types::RiskAssessment,
+    ) -> Result<(), RepoError> {
+        self.inner.record_transaction_risk(id, risk).await
+    }
+
+    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
+        self.inner.find_by_idempotency_key(key).await
+    }
+
+    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
+        self.inner.get_transaction(id).await
+    }
+
+    async fn list_transactions_for_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner.list_transactions_for_account(account_id).await
+    }
+
+    async fn list_transactions_page(
+        &self,
+        account_id: AccountId,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner
+            .list_transactions_page(account_id, filter, after, limit)
+            .await
+    }
+
+    async fn search_transactions(
+        &self,
+        search: &TransactionSearch,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner.search_transactions(search, after, limit).await
+    }
+
+    async fn list_transactions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        self.inner.list_transactions_between(from, to, mode).await
+    }
+
+    async fn verify_api_key_hash(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+        self.inner.verify_api_key_hash(key_hash).await
+    }
+
+    async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.inner.create_api_key(name, scopes, mode).await
+    }
+
+    async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.inner
+            .create_scoped_api_key(name, account_id, scopes, mode)
+            .await
+    }
+
+    async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.inner
+            .create_owner_api_key(name, owner_id, scopes, mode)
+            .await
+    }
+
+    async fn count_api_keys(&self) -> Result<i64, RepoError> {
+        self.inner.count_api_keys().await
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<payments_types::ApiKey>, RepoError> {
+        self.inner.list_api_keys().await
+    }
+
+    async fn delete_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+        deactivated_by: payments_types::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        self.inner.delete_api_key(id, deactivated_by, at).await
+    }
+
+    async fn touch_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<(), RepoError> {
+        self.inner.touch_api_key(id, at).await
+    }
+
+    async fn set_api_key_rate_limit(
+        &self,
+        id: payments_types::ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<bool, RepoError> {
+        self.inner
+            .set_api_key_rate_limit(id, rate_limit_per_minute)
+            .await
+    }
+
+    async fn insert_api_key_audit(
+        &self,
+        entry: &payments_types::ApiKeyAuditEntry,
+    ) -> Result<(), RepoError> {
+        self.inner.insert_api_key_audit(entry).await
+    }
+
+    async fn list_api_key_audit(
+        &self,
+        id: payments_types::ApiKeyId,
+    ) -> Result<Vec<payments_types::ApiKeyAuditEntry>, RepoError> {
+        self.inner.list_api_key_audit(id).await
+    }
+
+    async fn get_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+        self.inner.get_api_key(id).await
+    }
+
+    async fn add_api_key_usage(&self, usage: &ApiKeyUsageBucket) -> Result<(), RepoError> {
+        self.inner.add_api_key_usage(usage).await
+    }
+
+    async fn add_api_key_volume(&self, volume: &ApiKeyVolumeBucket) -> Result<(), RepoError> {
+        self.inner.add_api_key_volume(volume).await
+    }
+
+    async fn list_api_key_usage(
+        &self,
+        id: payments_types::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyUsageBucket>, RepoError> {
+        self.inner.list_api_key_usage(id, since).await
+    }
+
+    async fn list_api_key_volume(
+        &self,
+        id: payments_types::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyVolumeBucket>, RepoError> {
+        self.inner.list_api_key_volume(id, since).await
+    }
+
+    async fn register_webhook_endpoint(
+        &self,
+        url: &str,
+        events: Vec<String>,
+        timeouts: payments_types::WebhookTimeouts,
+        signature_algorithm: payments_types::WebhookSignatureAlgorithm,
+    ) -> Result<payments_types::WebhookEndpoint, RepoError> {
+        self.inner
+            .register_webhook_endpoint(url, events, timeouts, signature_algorithm)
+            .await
+    }
+
+    async fn register_webhook_endpoints(
+        &self,
+        endpoints: Vec<payments_types::NewWebhookEndpoint>,
+    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.register_webhook_endpoints(endpoints).await
+    }
+
+    async fn list_webhook_endpoints(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.list_webhook_endpoints(mode).await
+    }
+
+    async fn get_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.get_webhook_endpoint(id).await
+    }
+
+    async fn update_webhook_endpoint_url(
+        &self,
+        id: payments_types::WebhookEndpointId,
+        url: &str,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.update_webhook_endpoint_url(id, url).await
+    }
+
+    async fn update_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<payments_types::WebhookSignatureAlgorithm>,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner
+            .update_webhook_endpoint(id, events, is_active, signature_algorithm)
+            .await
+    }
+
+    async fn rotate_webhook_secret(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        self.inner.rotate_webhook_secret(id).await
+    }
+
+    async fn delete_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<bool, RepoError> {
+        self.inner.delete_webhook_endpoint(id).await
+    }
+
+    async fn create_webhook_event(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<payments_types::WebhookEvent, RepoError> {
+        self.inner
+            .create_webhook_event(endpoint_id, event_type, payload)
+            .await
+    }
+
+    async fn get_webhook_event(
+        &self,
+        id: uuid::Uuid,
+    ) -> Result<Option<payments_types::WebhookEvent>, RepoError> {
+        self.inner.get_webhook_event(id).await
+    }
+
+    async fn list_dead_lettered_webhooks(
+        &self,
+        filter: &payments_types::DeadLetterFilter,
+    ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+        self.inner.list_dead_lettered_webhooks(filter).await
+    }
+
+    async fn requeue_dead_lettered_webhooks(
+        &self,
+        filter: &payments_types::DeadLetterFilter,
+    ) -> Result<u64, RepoError> {
+        self.inner.requeue_dead_lettered_webhooks(filter).await
+    }
+
+    async fn count_webhook_backlog(&self) -> Result<i64, RepoError> {
+        self.inner.count_webhook_backlog().await
+    }
+
+    async fn storage_counts(&self) -> Result<StorageCounts, RepoError> {
+        self.inner.storage_counts().await
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        self.inner.ping().await
+    }
+
+    async fn list_webhook_events_after(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        after_sequence: i64,
+        limit: usize,
+    ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+        self.inner
+            .list_webhook_events_after(endpoint_id, after_sequence, limit)
+            .await
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        filter: &payments_types::WebhookDeliveryFilter,
+        limit: usize,
+    ) -> Result<Vec<payments_types::WebhookEvent>, RepoError> {
+        self.inner
+            .list_webhook_deliveries(endpoint_id, filter, limit)
+            .await
+    }
+
+    async fn requeue_webhook_event(&self, id: uuid::Uuid) -> Result<bool, RepoError> {
+        self.inner.requeue_webhook_event(id).await
+    }
+
+    async fn append_event(
+        &self,
+        event: &payments_types::DomainEvent,
+        mode: Mode,
+    ) -> Result<(), RepoError> {
+        self.inner.append_event(event, mode).await
+    }
+
+    async fn list_logged_events(
+        &self,
+        after: i64,
+        limit: usize,
+        mode: Option<Mode>,
+    ) -> Result<Vec<payments_types::LoggedEvent>, RepoError> {
+        self.inner.list_logged_events(after, limit, mode).await
+    }
 }
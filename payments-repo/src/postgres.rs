@@ -1,18 +1,42 @@
 //! PostgreSQL repository adapter.
 #![allow(clippy::collapsible_if)]
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use chrono::Utc;
-use sqlx::PgPool;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::postgres::PgListener;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use tracing::warn;
 use uuid::Uuid;
 
+use payments_types::domain::quote;
 use payments_types::{
-    Account, AccountId, CreateAccountRequest, DepositRequest, DomainError, DynMoney, RepoError,
-    Transaction, TransactionId, TransactionRepository, TransferRequest, WebhookEvent,
-    WebhookStatus, WithdrawRequest,
+    Account, AccountAlias, AccountId, AccountLimits, AccountStatus, Alias, ApiKeyAuditEntry,
+    ApiKeyId, ApiKeyUsageBucket, ApiKeyVolumeBucket, Beneficiary, BeneficiaryId, ChangeRequest,
+    ChangeRequestId, ChangeStatus, Clock, CreateAccountRequest, CurrencyBalance, DailyBalance,
+    DeadLetterFilter, DepositRequest, DomainError, DomainEvent, DynMoney, Export, ExportId,
+    FeeAssignment, FeeSchedule, FeeScheduleId, FeeTier, FloatPosition, FxConversion, Hold, HoldId,
+    HoldStatus, IDEMPOTENCY_RESERVATION_TTL, IdGenerator, IdempotencyRecord, LedgerOperation,
+    LoggedEvent, MemoKey, MemoKeyId, Mode, NewWebhookEndpoint, Owner, OwnerId, Quote, QuoteId,
+    RandomIdGenerator, RateSnapshot, RepoError, RiskAssessment, ScheduledPayment,
+    ScheduledPaymentId, SettlementBatch, SettlementBatchId, SettlementBatchStatus, SpendingRules,
+    Statement, StatementId, StorageCounts, SystemClock, Transaction, TransactionCursor,
+    TransactionFilter, TransactionId, TransactionRepository, TransactionSearch, TransactionType,
+    TransferRequest, UnitOfWork, WebhookDeliveryFilter, WebhookEvent, WebhookSignatureAlgorithm,
+    WebhookStatus, WebhookTimeouts, WithdrawRequest,
 };
 
-use crate::types::{DbAccount, DbAccountBalance, DbAccountCurrency, DbTransaction};
+use crate::error::{db_error, tx_error};
+use crate::types::{
+    DbAccount, DbAccountBalance, DbAccountCurrency, DbTransaction, check_currency, escape_like,
+    memo_json, metadata_json, parse_currency, parse_transaction_type, retry_delay,
+    spending_rule_rows, spending_rules_from_rows,
+};
+use crate::webhooks::{
+    NOTIFY_FALLBACK_INTERVAL, POLL_INTERVAL, PollingSource, WEBHOOK_EVENTS_CHANNEL,
+    WebhookEventSource,
+};
 
 // ─────────────────────────────────────────────────────────────────────────────
 // PostgreSQL Repository
@@ -21,11 +45,13 @@ use crate::types::{DbAccount, DbAccountBalance, DbAccountCurrency, DbTransaction
 /// PostgreSQL repository with row-level locking.
 pub struct PostgresRepo {
     pool: PgPool,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
 }
 
 /// Executes SQL statements from a migration file, splitting by semicolons.
 async fn execute_migration(pool: &PgPool, sql: &str, name: &str) -> Result<(), anyhow::Error> {
-    for statement in sql.split(';') {
+    for statement in split_statements(sql) {
         let stmt = statement.trim();
         if !stmt.is_empty() {
             sqlx::query(stmt)
@@ -37,6 +63,37 @@ async fn execute_migration(pool: &PgPool, sql: &str, name: &str) -> Result<(), a
     Ok(())
 }
 
+/// Splits `sql` at the semicolons that end statements, leaving those inside
+/// `--` comments and `$$`-quoted function bodies alone.
+pub(crate) fn split_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut in_body = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"$$") {
+            in_body = !in_body;
+            i += 2;
+            continue;
+        }
+        if !in_body && bytes[i..].starts_with(b"--") {
+            i += bytes[i..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .unwrap_or(bytes.len() - i);
+            continue;
+        }
+        if !in_body && bytes[i] == b';' {
+            statements.push(&sql[start..i]);
+            start = i + 1;
+        }
+        i += 1;
+    }
+    statements.push(&sql[start..]);
+    statements
+}
+
 /// Runs all database migrations.
 async fn run_migrations(pool: &PgPool) -> Result<(), anyhow::Error> {
     execute_migration(
@@ -67,6 +124,284 @@ async fn run_migrations(pool: &PgPool) -> Result<(), anyhow::Error> {
     )
     .await?;
 
+    execute_migration(
+        pool,
+        include_str!("../migrations/0005_add_counterparty_pg.sql"),
+        "0005",
+    )
+    .await?;
+
+    execute_migration(
+        pool,
+        include_str!("../migrations/0006_account_search_pg.sql"),
+        "0006",
+    )
+    .await?;
+
+    execute_migration(
+        pool,
+        include_str!("../migrations/0007_spending_controls_pg.sql"),
+        "0007",
+    )
+    .await?;
+
+    execute_migration(
+        pool,
+        include_str!("../migrations/0008_account_limits_pg.sql"),
+        "0008",
+    )
+    .await?;
+
+    execute_migration(
+        pool,
+        include_str!("../migrations/0009_fee_schedules_pg.sql"),
+        "0009",
+    )
+    .await?;
+
+    execute_migration(
+        pool,
+        include_str!("../migrations/0010_settlement_batches_pg.sql"),
+        "0010",
+    )
+    .await?;
+
+    execute_migration(
+        pool,
+        include_str!("../migrations/0011_statements_pg.sql"),
+        "0011",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0012_exports_pg.sql"),
+        "0012",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0013_transaction_pages_pg.sql"),
+        "0013",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0014_rate_snapshots_pg.sql"),
+        "0014",
+    )
+    .await?;
+
+    execute_migration(
+        pool,
+        include_str!("../migrations/0015_webhook_retries_pg.sql"),
+        "0015",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0016_api_key_usage_pg.sql"),
+        "0016",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0017_webhook_timeouts_pg.sql"),
+        "0017",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0018_fx_transfers_pg.sql"),
+        "0018",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0019_webhook_sequences_pg.sql"),
+        "0019",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0020_scheduled_payments_pg.sql"),
+        "0020",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0021_holds_pg.sql"),
+        "0021",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0022_account_aliases_pg.sql"),
+        "0022",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0023_api_key_scopes_pg.sql"),
+        "0023",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0024_account_status_pg.sql"),
+        "0024",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0025_transaction_risk_pg.sql"),
+        "0025",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0026_daily_balances_pg.sql"),
+        "0026",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0027_change_requests_pg.sql"),
+        "0027",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0028_idempotency_records_pg.sql"),
+        "0028",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0029_account_merges_pg.sql"),
+        "0029",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0030_overdraft_limits_pg.sql"),
+        "0030",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0031_transaction_search_pg.sql"),
+        "0031",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0032_inbound_scope.sql"),
+        "0032",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0033_api_key_audit_pg.sql"),
+        "0033",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0034_beneficiaries_pg.sql"),
+        "0034",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0035_api_key_rate_limits_pg.sql"),
+        "0035",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0036_event_log_pg.sql"),
+        "0036",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0037_webhook_signature_algorithms_pg.sql"),
+        "0037",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0038_transaction_metadata_pg.sql"),
+        "0038",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0039_memo_keys_pg.sql"),
+        "0039",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0040_encrypted_memos_pg.sql"),
+        "0040",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0041_webhook_notify_pg.sql"),
+        "0041",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0042_account_owners_pg.sql"),
+        "0042",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0043_fx_quotes_pg.sql"),
+        "0043",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0044_idempotency_responses_pg.sql"),
+        "0044",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0045_modes_pg.sql"),
+        "0045",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0046_webhook_signing_keys_pg.sql"),
+        "0046",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0047_event_modes_pg.sql"),
+        "0047",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0048_report_modes_pg.sql"),
+        "0048",
+    )
+    .await?;
+    execute_migration(
+        pool,
+        include_str!("../migrations/0049_quote_modes_pg.sql"),
+        "0049",
+    )
+    .await?;
+
     Ok(())
 }
 
@@ -75,7 +410,23 @@ impl PostgresRepo {
     pub async fn new(database_url: &str) -> anyhow::Result<Self> {
         let pool = PgPool::connect(database_url).await?;
         run_migrations(&pool).await?;
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+        })
+    }
+
+    /// Replaces the clock used for `created_at`/`processed_at` timestamps.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Replaces the generator used for new row IDs.
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
     }
 
     /// Returns a reference to the connection pool.
@@ -83,73 +434,108 @@ impl PostgresRepo {
         &self.pool
     }
 
+    /// Listens for new webhook events, or polls when the listener cannot
+    /// connect.
+    pub async fn webhook_event_source(&self) -> Box<dyn WebhookEventSource> {
+        match PgNotifySource::connect(&self.pool).await {
+            Ok(source) => Box::new(source),
+            Err(e) => {
+                warn!("Cannot listen for webhook events, polling instead: {}", e);
+                Box::new(PollingSource::default())
+            }
+        }
+    }
+
     /// Creates the database schema (for testing with existing pool).
     pub async fn create_schema(&self) -> Result<(), RepoError> {
         run_migrations(&self.pool)
             .await
             .map_err(|e| RepoError::Database(e.to_string()))
     }
-}
 
-// ─────────────────────────────────────────────────────────────────────────────
-// Repository implementation
-// ─────────────────────────────────────────────────────────────────────────────
+    /// Stores a new key, optionally scoped to one account or to the accounts
+    /// of one owner, and returns it with the raw key.
+    async fn insert_api_key(
+        &self,
+        name: &str,
+        account_id: Option<AccountId>,
+        owner_id: Option<OwnerId>,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        use rand::Rng;
+        use rand::distr::Alphanumeric;
 
-#[async_trait]
-impl TransactionRepository for PostgresRepo {
-    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
-        // Validate first
-        let _ = Account::new(req.name.clone(), req.currency).map_err(RepoError::Domain)?;
+        // Generate a secure random API key
+        let raw_key: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        let prefixed_key = format!("{}{}", mode.key_prefix(), raw_key);
 
-        let id = Uuid::new_v4();
-        let currency_str = req.currency.to_string();
-        let now = Utc::now();
+        let key_hash = crate::security::hash_api_key(&prefixed_key);
+        let id = self.ids.new_id();
+        let now = self.clock.now();
 
         sqlx::query(
-            r#"INSERT INTO accounts (id, name, balance, currency, created_at) VALUES ($1, $2, 0, $3, $4)"#,
+            r#"
+            INSERT INTO api_keys (id, name, key_hash, account_id, owner_id, scopes, is_active, created_at, mode)
+            VALUES ($1, $2, $3, $4, $5, $6, TRUE, $7, $8)
+            "#,
         )
         .bind(id)
-        .bind(&req.name)
-        .bind(&currency_str)
+        .bind(name)
+        .bind(&key_hash)
+        .bind(account_id.map(|id| id.into_uuid()))
+        .bind(owner_id.map(|id| id.into_uuid()))
+        .bind(crate::types::scopes_column(scopes))
         .bind(now)
+        .bind(mode.as_str())
         .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        Ok(Account::from_parts(
-            AccountId::from_uuid(id),
-            req.name,
-            DynMoney::zero(req.currency),
-            now,
-        ))
-    }
+        let api_key = payments_types::ApiKey {
+            id: payments_types::ApiKeyId::from_uuid(id),
+            name: name.to_string(),
+            key_hash,
+            account_id,
+            owner_id,
+            scopes: scopes.to_vec(),
+            is_active: true,
+            created_at: now,
+            last_used_at: None,
+            deactivated_at: None,
+            deactivated_by: None,
+            rate_limit_per_minute: None,
+            mode,
+        };
 
-    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
-        let row: Option<DbAccount> = sqlx::query_as(
-            r#"SELECT id, name, balance, currency, created_at FROM accounts WHERE id = $1"#,
-        )
-        .bind(id.into_uuid())
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        Ok((api_key, prefixed_key))
+    }
 
-        row.map(DbAccount::into_domain).transpose()
-    }
-
-    async fn list_accounts(&self) -> Result<Vec<Account>, RepoError> {
-        let rows: Vec<DbAccount> = sqlx::query_as(
-            r#"SELECT id, name, balance, currency, created_at FROM accounts ORDER BY created_at DESC"#,
-        )
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        rows.into_iter().map(DbAccount::into_domain).collect()
+    /// Debits `req.amount` from the source account and credits the
+    /// destination, converted when `conversion` is set.
+    async fn transfer_between(
+        &self,
+        req: TransferRequest,
+        conversion: Option<FxConversion>,
+    ) -> Result<Transaction, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let transaction = self.transfer_in(&mut db_tx, req, conversion).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(transaction)
     }
 
-    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+    /// Deposits within `conn`'s transaction.
+    async fn deposit_in(
+        &self,
+        conn: &mut PgConnection,
+        req: DepositRequest,
+    ) -> Result<Transaction, RepoError> {
         if let Some(key) = &req.idempotency_key {
-            if let Some(tx) = self.find_by_idempotency_key(key).await? {
+            if let Some(tx) = find_by_idempotency_key(&mut *conn, key).await? {
                 if tx.amount.amount() != req.amount
                     || tx.amount.currency() != req.currency
                     || tx.source_account_id.as_ref().map(|a| a.as_uuid()).is_some()
@@ -164,33 +550,27 @@ impl TransactionRepository for PostgresRepo {
             }
         }
 
-        let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
-
-        let mut db_tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
+        let money = DynMoney::positive(req.amount, req.currency);
 
         let result = sqlx::query(
             r#"UPDATE accounts SET balance = balance + $1 WHERE id = $2 RETURNING balance"#,
         )
         .bind(money.amount())
         .bind(req.account_id.into_uuid())
-        .fetch_optional(&mut *db_tx)
+        .fetch_optional(&mut *conn)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
         if result.is_none() {
             return Err(RepoError::NotFound);
         }
 
-        let tx_id = Uuid::new_v4();
-        let now = Utc::now();
+        let tx_id = self.ids.new_id();
+        let now = self.clock.now();
 
         sqlx::query(
-            r#"INSERT INTO transactions (id, direction, amount, currency, destination_account_id, idempotency_key, reference, created_at)
-               VALUES ($1, 'DEPOSIT', $2, $3, $4, $5, $6, $7)"#,
+            r#"INSERT INTO transactions (id, direction, amount, currency, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, metadata, encrypted_memo, created_at)
+               VALUES ($1, 'DEPOSIT', $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
         )
         .bind(tx_id)
         .bind(money.amount())
@@ -198,27 +578,38 @@ impl TransactionRepository for PostgresRepo {
         .bind(req.account_id.into_uuid())
         .bind(&req.idempotency_key)
         .bind(&req.reference)
+        .bind(req.counterparty.as_ref().map(|c| &c.name))
+        .bind(req.counterparty.as_ref().and_then(|c| c.external_id.as_ref()))
+        .bind(metadata_json(&req.metadata)?)
+        .bind(memo_json(req.encrypted_memo.as_ref())?)
         .bind(now)
-        .execute(&mut *db_tx)
+        .execute(&mut *conn)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        db_tx
-            .commit()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
-
-        Ok(Transaction::deposit(
-            req.account_id,
+        Ok(Transaction::from_parts(
+            TransactionId::from_uuid(tx_id),
+            TransactionType::Deposit,
             money,
+            None,
+            Some(req.account_id),
             req.idempotency_key,
             req.reference,
-        ))
+            now,
+        )
+        .with_counterparty(req.counterparty)
+        .with_metadata(req.metadata)
+        .with_encrypted_memo(req.encrypted_memo))
     }
 
-    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+    /// Withdraws within `conn`'s transaction.
+    async fn withdraw_in(
+        &self,
+        conn: &mut PgConnection,
+        req: WithdrawRequest,
+    ) -> Result<Transaction, RepoError> {
         if let Some(key) = &req.idempotency_key {
-            if let Some(tx) = self.find_by_idempotency_key(key).await? {
+            if let Some(tx) = find_by_idempotency_key(&mut *conn, key).await? {
                 if tx.amount.amount() != req.amount
                     || tx.amount.currency() != req.currency
                     || tx.source_account_id.as_ref().map(|a| a.as_uuid())
@@ -237,44 +628,34 @@ impl TransactionRepository for PostgresRepo {
             }
         }
 
-        let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
-
-        let mut db_tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
+        let money = DynMoney::positive(req.amount, req.currency);
 
         // Lock the account with FOR UPDATE
-        let row: Option<DbAccountBalance> =
-            sqlx::query_as(r#"SELECT balance, currency FROM accounts WHERE id = $1 FOR UPDATE"#)
-                .bind(req.account_id.into_uuid())
-                .fetch_optional(&mut *db_tx)
-                .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
+        let row: Option<DbAccountBalance> = sqlx::query_as(
+            r#"SELECT balance, held, currency, overdraft_limit FROM accounts WHERE id = $1 FOR UPDATE"#,
+        )
+        .bind(req.account_id.into_uuid())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(db_error)?;
 
         let account = row.ok_or(RepoError::NotFound)?;
 
-        if account.balance < money.amount() {
-            return Err(RepoError::Domain(DomainError::InsufficientFunds {
-                available: account.balance,
-                requested: money.amount(),
-            }));
-        }
+        account.check_available(money.amount())?;
 
         sqlx::query(r#"UPDATE accounts SET balance = balance - $1 WHERE id = $2"#)
             .bind(money.amount())
             .bind(req.account_id.into_uuid())
-            .execute(&mut *db_tx)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+            .map_err(db_error)?;
 
-        let tx_id = Uuid::new_v4();
-        let now = Utc::now();
+        let tx_id = self.ids.new_id();
+        let now = self.clock.now();
 
         sqlx::query(
-            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, idempotency_key, reference, created_at)
-               VALUES ($1, 'WITHDRAWAL', $2, $3, $4, $5, $6, $7)"#,
+            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, metadata, encrypted_memo, created_at)
+               VALUES ($1, 'WITHDRAWAL', $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"#,
         )
         .bind(tx_id)
         .bind(money.amount())
@@ -282,27 +663,42 @@ impl TransactionRepository for PostgresRepo {
         .bind(req.account_id.into_uuid())
         .bind(&req.idempotency_key)
         .bind(&req.reference)
+        .bind(req.counterparty.as_ref().map(|c| &c.name))
+        .bind(req.counterparty.as_ref().and_then(|c| c.external_id.as_ref()))
+        .bind(&req.purpose_code)
+        .bind(metadata_json(&req.metadata)?)
+        .bind(memo_json(req.encrypted_memo.as_ref())?)
         .bind(now)
-        .execute(&mut *db_tx)
+        .execute(&mut *conn)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        db_tx
-            .commit()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
+        .map_err(db_error)?;
 
-        Ok(Transaction::withdrawal(
-            req.account_id,
+        Ok(Transaction::from_parts(
+            TransactionId::from_uuid(tx_id),
+            TransactionType::Withdrawal,
             money,
+            Some(req.account_id),
+            None,
             req.idempotency_key,
             req.reference,
-        ))
+            now,
+        )
+        .with_counterparty(req.counterparty)
+        .with_purpose_code(req.purpose_code)
+        .with_metadata(req.metadata)
+        .with_encrypted_memo(req.encrypted_memo))
     }
 
-    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+    /// Transfers within `conn`'s transaction, converting when `conversion`
+    /// is set.
+    async fn transfer_in(
+        &self,
+        conn: &mut PgConnection,
+        req: TransferRequest,
+        conversion: Option<FxConversion>,
+    ) -> Result<Transaction, RepoError> {
         if let Some(key) = &req.idempotency_key {
-            if let Some(tx) = self.find_by_idempotency_key(key).await? {
+            if let Some(tx) = find_by_idempotency_key(&mut *conn, key).await? {
                 if tx.amount.amount() != req.amount
                     || tx.amount.currency() != req.currency
                     || tx.source_account_id.as_ref().map(|a| a.as_uuid())
@@ -318,13 +714,7 @@ impl TransactionRepository for PostgresRepo {
             }
         }
 
-        let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
-
-        let mut db_tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
+        let money = DynMoney::positive(req.amount, req.currency);
 
         // Lock accounts in consistent order to prevent deadlocks
         let (first_id, second_id) = if req.from_account_id.as_uuid() < req.to_account_id.as_uuid() {
@@ -334,78 +724,84 @@ impl TransactionRepository for PostgresRepo {
         };
 
         // Lock first account
-        let first: Option<DbAccountBalance> =
-            sqlx::query_as(r#"SELECT balance, currency FROM accounts WHERE id = $1 FOR UPDATE"#)
-                .bind(first_id.into_uuid())
-                .fetch_optional(&mut *db_tx)
-                .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
+        let first: Option<DbAccountBalance> = sqlx::query_as(
+            r#"SELECT balance, held, currency, overdraft_limit FROM accounts WHERE id = $1 FOR UPDATE"#,
+        )
+        .bind(first_id.into_uuid())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(db_error)?;
 
         if first.is_none() {
             return Err(RepoError::NotFound);
         }
 
         // Lock second account
-        let second: Option<DbAccountBalance> =
-            sqlx::query_as(r#"SELECT balance, currency FROM accounts WHERE id = $1 FOR UPDATE"#)
-                .bind(second_id.into_uuid())
-                .fetch_optional(&mut *db_tx)
-                .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
+        let second: Option<DbAccountBalance> = sqlx::query_as(
+            r#"SELECT balance, held, currency, overdraft_limit FROM accounts WHERE id = $1 FOR UPDATE"#,
+        )
+        .bind(second_id.into_uuid())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(db_error)?;
 
         if second.is_none() {
             return Err(RepoError::NotFound);
         }
 
         // Get source balance and currency
-        let source: DbAccountBalance =
-            sqlx::query_as(r#"SELECT balance, currency FROM accounts WHERE id = $1"#)
-                .bind(req.from_account_id.into_uuid())
-                .fetch_one(&mut *db_tx)
-                .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
+        let source: DbAccountBalance = sqlx::query_as(
+            r#"SELECT balance, held, currency, overdraft_limit FROM accounts WHERE id = $1"#,
+        )
+        .bind(req.from_account_id.into_uuid())
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(db_error)?;
 
-        if source.balance < money.amount() {
-            return Err(RepoError::Domain(DomainError::InsufficientFunds {
-                available: source.balance,
-                requested: money.amount(),
-            }));
-        }
+        source.check_available(money.amount())?;
 
         // Get destination currency
         let dest: DbAccountCurrency =
             sqlx::query_as(r#"SELECT currency FROM accounts WHERE id = $1"#)
                 .bind(req.to_account_id.into_uuid())
-                .fetch_one(&mut *db_tx)
+                .fetch_one(&mut *conn)
                 .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
+                .map_err(db_error)?;
 
-        if source.currency != dest.currency {
-            return Err(RepoError::Domain(DomainError::CrossCurrencyTransfer));
-        }
+        let credit = match &conversion {
+            None if source.currency != dest.currency => {
+                return Err(RepoError::Domain(DomainError::CrossCurrencyTransfer));
+            }
+            None => money,
+            Some(conversion) => {
+                check_currency(&source.currency, money.currency())?;
+                check_currency(&dest.currency, conversion.converted_amount.currency())?;
+                conversion.converted_amount
+            }
+        };
 
         // Debit source
         sqlx::query(r#"UPDATE accounts SET balance = balance - $1 WHERE id = $2"#)
             .bind(money.amount())
             .bind(req.from_account_id.into_uuid())
-            .execute(&mut *db_tx)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+            .map_err(db_error)?;
 
         // Credit destination
         sqlx::query(r#"UPDATE accounts SET balance = balance + $1 WHERE id = $2"#)
-            .bind(money.amount())
+            .bind(credit.amount())
             .bind(req.to_account_id.into_uuid())
-            .execute(&mut *db_tx)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+            .map_err(db_error)?;
 
-        let tx_id = Uuid::new_v4();
-        let now = Utc::now();
+        let tx_id = self.ids.new_id();
+        let now = self.clock.now();
 
         sqlx::query(
-            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, created_at)
-               VALUES ($1, 'TRANSFER', $2, $3, $4, $5, $6, $7, $8)"#,
+            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, purpose_code, fx_rate, converted_amount, converted_currency, metadata, encrypted_memo, created_at)
+               VALUES ($1, 'TRANSFER', $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)"#,
         )
         .bind(tx_id)
         .bind(money.amount())
@@ -414,348 +810,3666 @@ impl TransactionRepository for PostgresRepo {
         .bind(req.to_account_id.into_uuid())
         .bind(&req.idempotency_key)
         .bind(&req.reference)
+        .bind(&req.purpose_code)
+        .bind(conversion.map(|c| c.rate))
+        .bind(conversion.map(|c| c.converted_amount.amount()))
+        .bind(conversion.map(|c| c.converted_amount.currency().to_string()))
+        .bind(metadata_json(&req.metadata)?)
+        .bind(memo_json(req.encrypted_memo.as_ref())?)
         .bind(now)
-        .execute(&mut *db_tx)
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        Ok(Transaction::from_parts(
+            TransactionId::from_uuid(tx_id),
+            TransactionType::Transfer,
+            money,
+            Some(req.from_account_id),
+            Some(req.to_account_id),
+            req.idempotency_key,
+            req.reference,
+            now,
+        )
+        .with_purpose_code(req.purpose_code)
+        .with_metadata(req.metadata)
+        .with_encrypted_memo(req.encrypted_memo)
+        .with_conversion(conversion))
+    }
+
+    /// Captures hold `id` within `conn`'s transaction.
+    async fn capture_hold_in(
+        &self,
+        conn: &mut PgConnection,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        // Lock the hold before its account, as release_hold does
+        let row: Option<HoldRow> = sqlx::query_as(
+            r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at
+               FROM holds WHERE id = $1 FOR UPDATE"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        let mut hold = hold_from_row(row.ok_or(RepoError::NotFound)?)?;
+        hold.check_capture(amount, now).map_err(RepoError::Domain)?;
+        let money = DynMoney::new(amount, hold.currency).map_err(RepoError::Domain)?;
+
+        sqlx::query(
+            r#"UPDATE accounts SET balance = balance - $1, held = held - $2 WHERE id = $3"#,
+        )
+        .bind(money.amount())
+        .bind(hold.amount)
+        .bind(hold.account_id.into_uuid())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        let tx_id = self.ids.new_id();
+        sqlx::query(
+            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, reference, purpose_code, created_at)
+               VALUES ($1, 'WITHDRAWAL', $2, $3, $4, $5, $6, $7)"#,
+        )
+        .bind(tx_id)
+        .bind(money.amount())
+        .bind(money.currency().to_string())
+        .bind(hold.account_id.into_uuid())
+        .bind(&hold.reference)
+        .bind(&hold.purpose_code)
+        .bind(now)
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        hold.capture(amount, TransactionId::from_uuid(tx_id), now);
+        sqlx::query(
+            r#"UPDATE holds SET status = $1, captured_amount = $2, transaction_id = $3, resolved_at = $4
+               WHERE id = $5"#,
+        )
+        .bind(hold.status.as_ref())
+        .bind(hold.captured_amount)
+        .bind(tx_id)
+        .bind(hold.resolved_at)
+        .bind(hold.id.into_uuid())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        let transaction = Transaction::from_parts(
+            TransactionId::from_uuid(tx_id),
+            TransactionType::Withdrawal,
+            money,
+            Some(hold.account_id),
+            None,
+            None,
+            hold.reference.clone(),
+            now,
+        )
+        .with_purpose_code(hold.purpose_code.clone());
+        Ok((hold, transaction))
+    }
+
+    /// Releases hold `id` within `conn`'s transaction.
+    async fn release_hold_in(
+        &self,
+        conn: &mut PgConnection,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        let row: Option<HoldRow> = sqlx::query_as(
+            r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at
+               FROM holds WHERE id = $1 FOR UPDATE"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        let mut hold = hold_from_row(row.ok_or(RepoError::NotFound)?)?;
+        hold.check_open().map_err(RepoError::Domain)?;
+
+        sqlx::query(r#"UPDATE accounts SET held = held - $1 WHERE id = $2"#)
+            .bind(hold.amount)
+            .bind(hold.account_id.into_uuid())
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+
+        hold.release(status, now);
+        sqlx::query(r#"UPDATE holds SET status = $1, resolved_at = $2 WHERE id = $3"#)
+            .bind(hold.status.as_ref())
+            .bind(hold.resolved_at)
+            .bind(hold.id.into_uuid())
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+
+        Ok(hold)
+    }
+
+    /// Queues a webhook event within `conn`'s transaction.
+    async fn create_webhook_event_in(
+        &self,
+        conn: &mut PgConnection,
+        endpoint_id: payments_types::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<payments_types::WebhookEvent, RepoError> {
+        let event_id = self.ids.new_id();
+        let now = self.clock.now();
+        let payload_json =
+            serde_json::to_value(payload).map_err(|e| RepoError::Database(e.to_string()))?;
+
+        // The counter row stays locked until commit, so concurrent events
+        // for one endpoint get consecutive numbers in commit order
+        let delivery_sequence: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO webhook_delivery_sequences (endpoint_id, last_sequence)
+            VALUES ($1, 1)
+            ON CONFLICT (endpoint_id) DO UPDATE
+                SET last_sequence = webhook_delivery_sequences.last_sequence + 1
+            RETURNING last_sequence
+            "#,
+        )
+        .bind(endpoint_id.0)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        let mode: String = sqlx::query_scalar(
+            r#"
+            INSERT INTO webhook_events
+                (id, endpoint_id, event_type, payload, status, created_at, next_attempt_at, delivery_sequence, mode)
+            VALUES ($1, $2, $3, $4, $5, $6, $6, $7,
+                    COALESCE((SELECT mode FROM webhook_endpoints WHERE id = $2), 'live'))
+            RETURNING mode
+            "#,
+        )
+        .bind(event_id)
+        .bind(endpoint_id.0)
+        .bind(event_type)
+        .bind(payload_json)
+        .bind("PENDING")
+        .bind(now)
+        .bind(delivery_sequence)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        Ok(payments_types::WebhookEvent {
+            id: event_id,
+            endpoint_id: endpoint_id.0,
+            event_type: event_type.to_string(),
+            payload: serde_json::Value::Null,
+            status: payments_types::WebhookStatus::Pending,
+            created_at: now,
+            processed_at: None,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: Some(now),
+            delivery_sequence,
+            mode: mode.parse().map_err(RepoError::Database)?,
+        })
+    }
+
+    /// Appends `event`, which happened in `mode`, to the event log within
+    /// `conn`'s transaction.
+    async fn append_event_in(
+        &self,
+        conn: &mut PgConnection,
+        event: &DomainEvent,
+        mode: Mode,
+    ) -> Result<(), RepoError> {
+        // The counter row stays locked until commit, so events are numbered
+        // in commit order
+        let sequence: i64 = sqlx::query_scalar(
+            r#"UPDATE event_log_sequence SET last_sequence = last_sequence + 1
+               WHERE id = 1 RETURNING last_sequence"#,
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        sqlx::query(
+            r#"INSERT INTO event_log (sequence, event_type, payload, occurred_at, recorded_at, mode)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(sequence)
+        .bind(event.event_type())
+        .bind(event.payload())
+        .bind(event.occurred_at())
+        .bind(self.clock.now())
+        .bind(mode.as_str())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+/// Looks up the transaction made under an idempotency key, on `conn` so a
+/// unit of work sees the transactions it has made so far.
+async fn find_by_idempotency_key(
+    conn: &mut PgConnection,
+    key: &str,
+) -> Result<Option<Transaction>, RepoError> {
+    let row: Option<DbTransaction> = sqlx::query_as(
+        r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+           FROM transactions WHERE idempotency_key = $1"#,
+    )
+    .bind(key)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(db_error)?;
+
+    row.map(DbTransaction::into_domain).transpose()
+}
+
+/// Locks `account_ids` within `conn`'s transaction in ID order, the order
+/// transfers lock their two accounts in.
+async fn lock_accounts(
+    conn: &mut PgConnection,
+    account_ids: &[AccountId],
+) -> Result<(), RepoError> {
+    let mut ids: Vec<Uuid> = account_ids.iter().map(|id| id.into_uuid()).collect();
+    ids.sort();
+    ids.dedup();
+    for id in ids {
+        sqlx::query(r#"SELECT id FROM accounts WHERE id = $1 FOR UPDATE"#)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+/// Sums the amounts sent out of or held on `account_id` after `since`
+/// within `conn`'s transaction, first locking the account so that concurrent debits of it
+/// wait for this transaction to end.
+async fn debited_since(
+    conn: &mut PgConnection,
+    account_id: AccountId,
+    since: DateTime<Utc>,
+) -> Result<i64, RepoError> {
+    sqlx::query(r#"SELECT id FROM accounts WHERE id = $1 FOR UPDATE"#)
+        .bind(account_id.into_uuid())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+    // A captured hold is counted once, by the withdrawal it became
+    let (debited,): (i64,) = sqlx::query_as(
+        r#"SELECT (
+               (SELECT COALESCE(SUM(amount), 0) FROM transactions
+                WHERE source_account_id = $1 AND created_at > $2)
+             + (SELECT COALESCE(SUM(amount), 0) FROM holds
+                WHERE account_id = $1 AND status = $3 AND created_at > $2)
+           )::BIGINT"#,
+    )
+    .bind(account_id.into_uuid())
+    .bind(since)
+    .bind(HoldStatus::Authorized.as_ref())
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    Ok(debited)
+}
+
+/// Counts the transactions `account_id` sent or received after `since`
+/// within `conn`'s transaction, first locking the account like
+/// [`debited_since`].
+async fn transactions_since(
+    conn: &mut PgConnection,
+    account_id: AccountId,
+    since: DateTime<Utc>,
+) -> Result<i64, RepoError> {
+    sqlx::query(r#"SELECT id FROM accounts WHERE id = $1 FOR UPDATE"#)
+        .bind(account_id.into_uuid())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+    let (count,): (i64,) = sqlx::query_as(
+        r#"SELECT COUNT(*) FROM transactions
+           WHERE (source_account_id = $1 OR destination_account_id = $1)
+             AND created_at > $2"#,
+    )
+    .bind(account_id.into_uuid())
+    .bind(since)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    Ok(count)
+}
+
+/// Stores an authorized hold within `conn`'s transaction, reserving its
+/// amount on the account.
+async fn create_hold(conn: &mut PgConnection, hold: &Hold) -> Result<(), RepoError> {
+    let money = DynMoney::new(hold.amount, hold.currency).map_err(RepoError::Domain)?;
+
+    let row: Option<DbAccountBalance> = sqlx::query_as(
+        r#"SELECT balance, held, currency, overdraft_limit FROM accounts WHERE id = $1 FOR UPDATE"#,
+    )
+    .bind(hold.account_id.into_uuid())
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(db_error)?;
+
+    let account = row.ok_or(RepoError::NotFound)?;
+    check_currency(&account.currency, money.currency())?;
+    account.check_available(money.amount())?;
+
+    sqlx::query(r#"UPDATE accounts SET held = held + $1 WHERE id = $2"#)
+        .bind(money.amount())
+        .bind(hold.account_id.into_uuid())
+        .execute(&mut *conn)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        db_tx
-            .commit()
+    sqlx::query(
+        r#"INSERT INTO holds (id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"#,
+    )
+    .bind(hold.id.into_uuid())
+    .bind(hold.account_id.into_uuid())
+    .bind(hold.amount)
+    .bind(hold.currency.to_string())
+    .bind(hold.reference.as_deref())
+    .bind(hold.purpose_code.as_deref())
+    .bind(hold.status.as_ref())
+    .bind(hold.expires_at)
+    .bind(hold.captured_amount)
+    .bind(hold.transaction_id.map(TransactionId::into_uuid))
+    .bind(hold.created_at)
+    .bind(hold.resolved_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Records a risk assessment within `conn`'s transaction.
+async fn record_transaction_risk(
+    conn: &mut PgConnection,
+    id: TransactionId,
+    risk: &RiskAssessment,
+) -> Result<(), RepoError> {
+    let reasons =
+        serde_json::to_string(&risk.reasons).map_err(|e| RepoError::Database(e.to_string()))?;
+    sqlx::query(
+        r#"UPDATE transactions SET risk_decision = $1, risk_reasons = $2
+           WHERE id = $3 AND risk_decision IS NULL"#,
+    )
+    .bind(risk.decision.as_ref())
+    .bind(reasons)
+    .bind(id.into_uuid())
+    .execute(conn)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// A [`UnitOfWork`] over one PostgreSQL transaction, rolled back if dropped
+/// uncommitted.
+struct PostgresUnitOfWork<'a> {
+    repo: &'a PostgresRepo,
+    tx: sqlx::Transaction<'static, sqlx::Postgres>,
+}
+
+#[async_trait]
+impl UnitOfWork for PostgresUnitOfWork<'_> {
+    async fn apply(&mut self, operation: LedgerOperation) -> Result<Transaction, RepoError> {
+        match operation {
+            LedgerOperation::Deposit(req) => self.repo.deposit_in(&mut self.tx, req).await,
+            LedgerOperation::Withdraw(req) => self.repo.withdraw_in(&mut self.tx, req).await,
+            LedgerOperation::Transfer(req, conversion) => {
+                self.repo.transfer_in(&mut self.tx, req, conversion).await
+            }
+        }
+    }
+
+    async fn lock_accounts(&mut self, account_ids: &[AccountId]) -> Result<(), RepoError> {
+        lock_accounts(&mut self.tx, account_ids).await
+    }
+
+    async fn debited_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        debited_since(&mut self.tx, account_id, since).await
+    }
+
+    async fn transactions_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        transactions_since(&mut self.tx, account_id, since).await
+    }
+
+    async fn create_hold(&mut self, hold: &Hold) -> Result<(), RepoError> {
+        create_hold(&mut self.tx, hold).await
+    }
+
+    async fn record_transaction_risk(
+        &mut self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        record_transaction_risk(&mut self.tx, id, risk).await
+    }
+
+    async fn create_webhook_event(
+        &mut self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<WebhookEvent, RepoError> {
+        self.repo
+            .create_webhook_event_in(&mut self.tx, endpoint_id, event_type, payload)
             .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
+    }
+
+    async fn append_event(&mut self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        self.repo.append_event_in(&mut self.tx, event, mode).await
+    }
+
+    async fn capture_hold(
+        &mut self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        self.repo
+            .capture_hold_in(&mut self.tx, id, amount, now)
+            .await
+    }
+
+    async fn release_hold(
+        &mut self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        self.repo
+            .release_hold_in(&mut self.tx, id, status, now)
+            .await
+    }
+
+    async fn spend_quote(
+        &mut self,
+        id: QuoteId,
+        transaction_id: TransactionId,
+    ) -> Result<(), RepoError> {
+        let spent = sqlx::query(
+            r#"UPDATE fx_quotes SET transaction_id = $1
+               WHERE id = $2 AND (transaction_id IS NULL OR transaction_id = $1)"#,
+        )
+        .bind(transaction_id.into_uuid())
+        .bind(id.into_uuid())
+        .execute(&mut *self.tx)
+        .await
+        .map_err(db_error)?;
+        if spent.rows_affected() == 0 {
+            return Err(RepoError::Domain(quote::already_used(id)));
+        }
+        Ok(())
+    }
+
+    async fn close_merged_account(
+        &mut self,
+        id: AccountId,
+        into: AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        let row: Option<DbAccount> = sqlx::query_as(
+            r#"UPDATE accounts SET status = $1, status_changed_at = $2, merged_into = $3
+               WHERE id = $4 AND status <> $1 AND balance = 0 AND held = 0
+               RETURNING id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode"#,
+        )
+        .bind(AccountStatus::Closed.as_ref())
+        .bind(now)
+        .bind(into.into_uuid())
+        .bind(id.into_uuid())
+        .fetch_optional(&mut *self.tx)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbAccount::into_domain).transpose()
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepoError> {
+        self.tx.commit().await.map_err(tx_error)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Repository implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[async_trait]
+impl TransactionRepository for PostgresRepo {
+    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
+        // Validate first
+        let _ = Account::new(req.name.clone(), req.currency).map_err(RepoError::Domain)?;
+
+        let mode = req.mode.unwrap_or_default();
+        let id = self.ids.new_id();
+        let currency_str = req.currency.to_string();
+        let now = self.clock.now();
+
+        sqlx::query(
+            r#"INSERT INTO accounts (id, name, balance, currency, created_at, overdraft_limit, owner_id, mode) VALUES ($1, $2, 0, $3, $4, $5, $6, $7)"#,
+        )
+        .bind(id)
+        .bind(&req.name)
+        .bind(&currency_str)
+        .bind(now)
+        .bind(req.overdraft_limit)
+        .bind(req.owner_id.into_uuid())
+        .bind(mode.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(Account::from_parts(
+            AccountId::from_uuid(id),
+            req.name,
+            DynMoney::zero(req.currency),
+            now,
+        )
+        .with_overdraft_limit(req.overdraft_limit)
+        .with_owner(req.owner_id)
+        .with_mode(mode))
+    }
+
+    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
+        let row: Option<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbAccount::into_domain).transpose()
+    }
+
+    async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError> {
+        let rows: Vec<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts
+               WHERE ($1::text IS NULL OR mode = $1) ORDER BY created_at DESC"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbAccount::into_domain).collect()
+    }
+
+    async fn search_accounts(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: Mode,
+    ) -> Result<Vec<Account>, RepoError> {
+        let rows: Vec<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts
+               WHERE (lower(name) LIKE $1 ESCAPE '\' OR id::text LIKE $2) AND mode = $4
+               ORDER BY lower(name), id
+               LIMIT $3"#,
+        )
+        .bind(format!("%{}%", escape_like(&query.trim().to_lowercase())))
+        .bind(AccountId::search_prefix(query).map(|prefix| format!("{}%", prefix)))
+        .bind(limit as i64)
+        .bind(mode.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbAccount::into_domain).collect()
+    }
+
+    async fn get_spending_rules(&self, account_id: AccountId) -> Result<SpendingRules, RepoError> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"SELECT purpose_code, effect FROM account_spending_rules
+               WHERE account_id = $1 ORDER BY purpose_code"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(spending_rules_from_rows(rows))
+    }
+
+    async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<(), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+
+        sqlx::query("DELETE FROM account_spending_rules WHERE account_id = $1")
+            .bind(account_id.into_uuid())
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
+
+        for (code, effect) in spending_rule_rows(rules) {
+            sqlx::query(
+                "INSERT INTO account_spending_rules (account_id, purpose_code, effect) VALUES ($1, $2, $3)",
+            )
+            .bind(account_id.into_uuid())
+            .bind(code)
+            .bind(effect)
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
+        }
+
+        db_tx.commit().await.map_err(tx_error)
+    }
+
+    async fn get_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, RepoError> {
+        let row: Option<(Option<i64>, Option<i64>, Option<i64>)> = sqlx::query_as(
+            r#"SELECT max_transaction_amount, max_balance, daily_debit_limit
+               FROM account_limits WHERE account_id = $1"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(row
+            .map(
+                |(max_transaction_amount, max_balance, daily_debit_limit)| AccountLimits {
+                    max_transaction_amount,
+                    max_balance,
+                    daily_debit_limit,
+                },
+            )
+            .unwrap_or_default())
+    }
+
+    async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<(), RepoError> {
+        if limits.is_empty() {
+            sqlx::query("DELETE FROM account_limits WHERE account_id = $1")
+                .bind(account_id.into_uuid())
+                .execute(&self.pool)
+                .await
+                .map_err(db_error)?;
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"INSERT INTO account_limits
+                   (account_id, max_transaction_amount, max_balance, daily_debit_limit)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (account_id) DO UPDATE SET
+                   max_transaction_amount = excluded.max_transaction_amount,
+                   max_balance = excluded.max_balance,
+                   daily_debit_limit = excluded.daily_debit_limit"#,
+        )
+        .bind(account_id.into_uuid())
+        .bind(limits.max_transaction_amount)
+        .bind(limits.max_balance)
+        .bind(limits.daily_debit_limit)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn list_idle_accounts(
+        &self,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Account>, RepoError> {
+        let rows: Vec<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts a
+               WHERE status = 'ACTIVE' AND created_at < $1
+                 AND NOT EXISTS (
+                     SELECT 1 FROM transactions t
+                     WHERE (t.source_account_id = a.id OR t.destination_account_id = a.id)
+                       AND t.created_at >= $1
+                 )
+               ORDER BY created_at, id
+               LIMIT $2"#,
+        )
+        .bind(idle_since)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbAccount::into_domain).collect()
+    }
+
+    async fn update_account_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        let row: Option<DbAccount> = sqlx::query_as(
+            r#"UPDATE accounts SET status = $1, status_changed_at = $2
+               WHERE id = $3 AND status = $4
+               RETURNING id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode"#,
+        )
+        .bind(to.as_ref())
+        .bind(now)
+        .bind(id.into_uuid())
+        .bind(from.as_ref())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbAccount::into_domain).transpose()
+    }
+
+    async fn set_overdraft_limit(
+        &self,
+        id: AccountId,
+        limit: i64,
+    ) -> Result<Option<Account>, RepoError> {
+        let row: Option<DbAccount> = sqlx::query_as(
+            r#"UPDATE accounts SET overdraft_limit = $1 WHERE id = $2
+               RETURNING id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode"#,
+        )
+        .bind(limit)
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbAccount::into_domain).transpose()
+    }
+
+    async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError> {
+        sqlx::query(
+            r#"INSERT INTO account_aliases (alias, account_id, created_at) VALUES ($1, $2, $3)
+               ON CONFLICT (alias) DO NOTHING"#,
+        )
+        .bind(alias.alias.as_str())
+        .bind(alias.account_id.into_uuid())
+        .bind(alias.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let stored = self
+            .get_account_alias(&alias.alias)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+        if stored.account_id != alias.account_id {
+            return Err(RepoError::Domain(DomainError::ValidationError(format!(
+                "Alias {} is already taken",
+                alias.alias
+            ))));
+        }
+        Ok(stored)
+    }
+
+    async fn get_account_alias(&self, alias: &Alias) -> Result<Option<AccountAlias>, RepoError> {
+        let row: Option<AccountAliasRow> = sqlx::query_as(
+            "SELECT alias, account_id, created_at FROM account_aliases WHERE alias = $1",
+        )
+        .bind(alias.as_str())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(account_alias_from_row).transpose()
+    }
+
+    async fn list_account_aliases(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, RepoError> {
+        let rows: Vec<AccountAliasRow> = sqlx::query_as(
+            r#"SELECT alias, account_id, created_at FROM account_aliases
+               WHERE account_id = $1 ORDER BY created_at"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(account_alias_from_row).collect()
+    }
+
+    async fn remove_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<bool, RepoError> {
+        let removed =
+            sqlx::query("DELETE FROM account_aliases WHERE alias = $1 AND account_id = $2")
+                .bind(alias.as_str())
+                .bind(account_id.into_uuid())
+                .execute(&self.pool)
+                .await
+                .map_err(db_error)?;
+        Ok(removed.rows_affected() > 0)
+    }
+
+    async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError> {
+        sqlx::query(r#"INSERT INTO owners (id, name, email, created_at) VALUES ($1, $2, $3, $4)"#)
+            .bind(owner.id.into_uuid())
+            .bind(&owner.name)
+            .bind(&owner.email)
+            .bind(owner.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(owner.clone())
+    }
+
+    async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError> {
+        let row: Option<OwnerRow> =
+            sqlx::query_as(r#"SELECT id, name, email, created_at FROM owners WHERE id = $1"#)
+                .bind(id.into_uuid())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        Ok(row.map(owner_from_row))
+    }
+
+    async fn list_owners(&self) -> Result<Vec<Owner>, RepoError> {
+        let rows: Vec<OwnerRow> = sqlx::query_as(
+            r#"SELECT id, name, email, created_at FROM owners ORDER BY created_at, id"#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows.into_iter().map(owner_from_row).collect())
+    }
+
+    async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError> {
+        let row: Option<OwnerRow> = sqlx::query_as(
+            r#"UPDATE owners SET name = $1, email = $2 WHERE id = $3
+               RETURNING id, name, email, created_at"#,
+        )
+        .bind(&owner.name)
+        .bind(&owner.email)
+        .bind(owner.id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(row.map(owner_from_row))
+    }
+
+    async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError> {
+        let deleted = sqlx::query("DELETE FROM owners WHERE id = $1")
+            .bind(id.into_uuid())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Account>, RepoError> {
+        let rows: Vec<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts
+               WHERE owner_id = $1 AND ($2::text IS NULL OR mode = $2) ORDER BY created_at DESC"#,
+        )
+        .bind(owner_id.into_uuid())
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbAccount::into_domain).collect()
+    }
+
+    async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError> {
+        sqlx::query(
+            r#"INSERT INTO memo_keys (id, account_id, algorithm, fingerprint, created_at, retired_at)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(key.id.into_uuid())
+        .bind(key.account_id.into_uuid())
+        .bind(&key.algorithm)
+        .bind(&key.fingerprint)
+        .bind(key.created_at)
+        .bind(key.retired_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(key.clone())
+    }
+
+    async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError> {
+        let row: Option<MemoKeyRow> = sqlx::query_as(
+            r#"SELECT id, account_id, algorithm, fingerprint, created_at, retired_at
+               FROM memo_keys WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(row.map(memo_key_from_row))
+    }
+
+    async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError> {
+        let rows: Vec<MemoKeyRow> = sqlx::query_as(
+            r#"SELECT id, account_id, algorithm, fingerprint, created_at, retired_at
+               FROM memo_keys WHERE account_id = $1 ORDER BY created_at"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows.into_iter().map(memo_key_from_row).collect())
+    }
+
+    async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        id: MemoKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<MemoKey>, RepoError> {
+        let row: Option<MemoKeyRow> = sqlx::query_as(
+            r#"UPDATE memo_keys SET retired_at = COALESCE(retired_at, $1)
+               WHERE id = $2 AND account_id = $3
+               RETURNING id, account_id, algorithm, fingerprint, created_at, retired_at"#,
+        )
+        .bind(at)
+        .bind(id.into_uuid())
+        .bind(account_id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(row.map(memo_key_from_row))
+    }
+
+    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let transaction = self.deposit_in(&mut db_tx, req).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(transaction)
+    }
+
+    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let transaction = self.withdraw_in(&mut db_tx, req).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(transaction)
+    }
+
+    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+        self.transfer_between(req, None).await
+    }
+
+    async fn transfer_with_conversion(
+        &self,
+        req: TransferRequest,
+        conversion: FxConversion,
+    ) -> Result<Transaction, RepoError> {
+        self.transfer_between(req, Some(conversion)).await
+    }
+
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork + '_>, RepoError> {
+        let tx = self.pool.begin().await.map_err(tx_error)?;
+        Ok(Box::new(PostgresUnitOfWork { repo: self, tx }))
+    }
+
+    async fn record_transaction_risk(
+        &self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        let mut conn = self.pool.acquire().await.map_err(db_error)?;
+        record_transaction_risk(&mut conn, id, risk).await
+    }
+
+    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
+        let mut conn = self.pool.acquire().await.map_err(db_error)?;
+        find_by_idempotency_key(&mut conn, key).await
+    }
+
+    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
+        let row: Option<DbTransaction> = sqlx::query_as(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbTransaction::into_domain).transpose()
+    }
+
+    async fn list_transactions_for_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions WHERE source_account_id = $1 OR destination_account_id = $1
+               ORDER BY created_at DESC"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbTransaction::into_domain).collect()
+    }
+
+    async fn list_transactions_page(
+        &self,
+        account_id: AccountId,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions
+               WHERE (source_account_id = $1 OR destination_account_id = $1)
+                 AND ($2::text IS NULL OR direction = $2)
+                 AND ($3::timestamptz IS NULL OR created_at >= $3)
+                 AND ($4::timestamptz IS NULL OR created_at < $4)
+                 AND ($5::bigint IS NULL OR amount >= $5)
+                 AND ($6::bigint IS NULL OR amount <= $6)
+                 AND ($7::timestamptz IS NULL OR (created_at, id) < ($7, $8))
+               ORDER BY created_at DESC, id DESC
+               LIMIT $9"#,
+        )
+        .bind(account_id.into_uuid())
+        .bind(filter.transaction_type.map(|t| t.to_string()))
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.min_amount)
+        .bind(filter.max_amount)
+        .bind(after.map(|cursor| cursor.created_at))
+        .bind(after.map(|cursor| cursor.id.into_uuid()))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbTransaction::into_domain).collect()
+    }
+
+    async fn search_transactions(
+        &self,
+        search: &TransactionSearch,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions WHERE TRUE"#,
+        );
+        if let Some(reference) = &search.reference {
+            query
+                .push(" AND lower(reference) LIKE ")
+                .push_bind(format!("%{}%", escape_like(&reference.to_lowercase())))
+                .push(r" ESCAPE '\'");
+        }
+        if let Some(account_id) = search.account_id {
+            query
+                .push(" AND (source_account_id = ")
+                .push_bind(account_id.into_uuid())
+                .push(" OR destination_account_id = ")
+                .push_bind(account_id.into_uuid())
+                .push(")");
+        }
+        if let Some(currency) = search.currency {
+            query
+                .push(" AND currency = ")
+                .push_bind(currency.code().to_string());
+        }
+        if let Some(mode) = search.mode {
+            query
+                .push(" AND EXISTS (SELECT 1 FROM accounts WHERE accounts.id IN (source_account_id, destination_account_id) AND accounts.mode = ")
+                .push_bind(mode.as_str().to_string())
+                .push(")");
+        }
+        if let Some(key) = &search.metadata_key {
+            query
+                .push(" AND metadata::jsonb ->> ")
+                .push_bind(key.clone());
+            match &search.metadata_value {
+                Some(value) => query.push(" = ").push_bind(value.clone()),
+                None => query.push(" IS NOT NULL"),
+            };
+        }
+        if let Some(transaction_type) = search.filter.transaction_type {
+            query
+                .push(" AND direction = ")
+                .push_bind(transaction_type.to_string());
+        }
+        if let Some(from) = search.filter.from {
+            query.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = search.filter.to {
+            query.push(" AND created_at < ").push_bind(to);
+        }
+        if let Some(min) = search.filter.min_amount {
+            query.push(" AND amount >= ").push_bind(min);
+        }
+        if let Some(max) = search.filter.max_amount {
+            query.push(" AND amount <= ").push_bind(max);
+        }
+        if let Some(cursor) = after {
+            query
+                .push(" AND (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id.into_uuid())
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit as i64);
+        let rows: Vec<DbTransaction> = query
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        rows.into_iter().map(DbTransaction::into_domain).collect()
+    }
+
+    async fn list_transactions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions WHERE created_at >= $1 AND created_at < $2
+                 AND ($3::text IS NULL OR EXISTS (SELECT 1 FROM accounts WHERE accounts.id IN (source_account_id, destination_account_id) AND accounts.mode = $3))
+               ORDER BY created_at, id"#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbTransaction::into_domain).collect()
+    }
+
+    async fn verify_api_key_hash(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+        let row: Option<DbApiKey> = sqlx::query_as(
+            r#"
+            SELECT id, name, key_hash, account_id, owner_id, scopes, is_active, created_at, last_used_at, deactivated_at, deactivated_by, rate_limit_per_minute, mode
+            FROM api_keys
+            WHERE key_hash = $1 AND is_active = TRUE
+            "#,
+        )
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbApiKey::into_domain).transpose()
+    }
+
+    async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.insert_api_key(name, None, None, scopes, mode).await
+    }
+
+    async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.insert_api_key(name, Some(account_id), None, scopes, mode)
+            .await
+    }
+
+    async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.insert_api_key(name, None, Some(owner_id), scopes, mode)
+            .await
+    }
+
+    async fn count_api_keys(&self) -> Result<i64, RepoError> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM api_keys WHERE is_active = TRUE")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(row.0)
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<payments_types::ApiKey>, RepoError> {
+        let rows: Vec<DbApiKey> = sqlx::query_as(
+            "SELECT id, name, key_hash, account_id, owner_id, scopes, is_active, created_at, last_used_at, deactivated_at, deactivated_by, rate_limit_per_minute, mode FROM api_keys WHERE is_active = TRUE ORDER BY created_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbApiKey::into_domain).collect()
+    }
+
+    async fn delete_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+        deactivated_by: payments_types::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"UPDATE api_keys SET is_active = FALSE, deactivated_at = $1, deactivated_by = $2
+               WHERE id = $3 AND is_active = TRUE"#,
+        )
+        .bind(at)
+        .bind(deactivated_by.into_uuid())
+        .bind(id.into_uuid())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<(), RepoError> {
+        sqlx::query("UPDATE api_keys SET last_used_at = $1 WHERE id = $2")
+            .bind(at)
+            .bind(id.into_uuid())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn set_api_key_rate_limit(
+        &self,
+        id: payments_types::ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<bool, RepoError> {
+        let rate_limit_per_minute = rate_limit_per_minute
+            .map(i32::try_from)
+            .transpose()
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        let result = sqlx::query(
+            "UPDATE api_keys SET rate_limit_per_minute = $1 WHERE id = $2 AND is_active = TRUE",
+        )
+        .bind(rate_limit_per_minute)
+        .bind(id.into_uuid())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+        let row: Option<DbApiKey> = sqlx::query_as(
+            "SELECT id, name, key_hash, account_id, owner_id, scopes, is_active, created_at, last_used_at, deactivated_at, deactivated_by, rate_limit_per_minute, mode FROM api_keys WHERE id = $1",
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbApiKey::into_domain).transpose()
+    }
+
+    async fn insert_api_key_audit(&self, entry: &ApiKeyAuditEntry) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"INSERT INTO audit_log (api_key_id, action, actor_id, occurred_at)
+               VALUES ($1, $2, $3, $4)"#,
+        )
+        .bind(entry.api_key_id.into_uuid())
+        .bind(entry.action.as_ref())
+        .bind(entry.actor.map(ApiKeyId::into_uuid))
+        .bind(entry.at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_api_key_audit(
+        &self,
+        id: payments_types::ApiKeyId,
+    ) -> Result<Vec<ApiKeyAuditEntry>, RepoError> {
+        let rows: Vec<AuditRow> = sqlx::query_as(
+            r#"SELECT api_key_id, action, actor_id, occurred_at
+               FROM audit_log WHERE api_key_id = $1 ORDER BY id"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(audit_entry_from_row).collect()
+    }
+
+    async fn add_api_key_usage(&self, usage: &ApiKeyUsageBucket) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"INSERT INTO api_key_usage (api_key_id, hour, requests, rate_limited)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (api_key_id, hour) DO UPDATE SET
+                   requests = api_key_usage.requests + excluded.requests,
+                   rate_limited = api_key_usage.rate_limited + excluded.rate_limited"#,
+        )
+        .bind(usage.api_key_id.into_uuid())
+        .bind(usage.hour)
+        .bind(usage.requests)
+        .bind(usage.rate_limited)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn add_api_key_volume(&self, volume: &ApiKeyVolumeBucket) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"INSERT INTO api_key_volume
+                   (api_key_id, hour, transaction_type, currency, count, amount)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (api_key_id, hour, transaction_type, currency) DO UPDATE SET
+                   count = api_key_volume.count + excluded.count,
+                   amount = api_key_volume.amount + excluded.amount"#,
+        )
+        .bind(volume.api_key_id.into_uuid())
+        .bind(volume.hour)
+        .bind(volume.transaction_type.to_string())
+        .bind(volume.currency.to_string())
+        .bind(volume.count)
+        .bind(volume.amount)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_api_key_usage(
+        &self,
+        id: payments_types::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyUsageBucket>, RepoError> {
+        let rows: Vec<(DateTime<Utc>, i64, i64)> = sqlx::query_as(
+            r#"SELECT hour, requests, rate_limited FROM api_key_usage
+               WHERE api_key_id = $1 AND hour >= $2 ORDER BY hour"#,
+        )
+        .bind(id.into_uuid())
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(hour, requests, rate_limited)| ApiKeyUsageBucket {
+                api_key_id: id,
+                hour,
+                requests,
+                rate_limited,
+            })
+            .collect())
+    }
+
+    async fn list_api_key_volume(
+        &self,
+        id: payments_types::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyVolumeBucket>, RepoError> {
+        let rows: Vec<(DateTime<Utc>, String, String, i64, i64)> = sqlx::query_as(
+            r#"SELECT hour, transaction_type, currency, count, amount FROM api_key_volume
+               WHERE api_key_id = $1 AND hour >= $2 ORDER BY hour"#,
+        )
+        .bind(id.into_uuid())
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(|(hour, transaction_type, currency, count, amount)| {
+                Ok(ApiKeyVolumeBucket {
+                    api_key_id: id,
+                    hour,
+                    transaction_type: parse_transaction_type(&transaction_type)?,
+                    currency: parse_currency(&currency)?,
+                    count,
+                    amount,
+                })
+            })
+            .collect()
+    }
+
+    async fn register_webhook_endpoint(
+        &self,
+        url: &str,
+        events: Vec<String>,
+        timeouts: WebhookTimeouts,
+        signature_algorithm: WebhookSignatureAlgorithm,
+    ) -> Result<payments_types::WebhookEndpoint, RepoError> {
+        let mut registered = self
+            .register_webhook_endpoints(vec![NewWebhookEndpoint {
+                url: url.to_string(),
+                events,
+                timeouts,
+                signature_algorithm,
+                mode: Mode::Live,
+            }])
+            .await?;
+        Ok(registered.remove(0))
+    }
+
+    async fn register_webhook_endpoints(
+        &self,
+        endpoints: Vec<NewWebhookEndpoint>,
+    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+        let now = self.clock.now();
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let mut registered = Vec::with_capacity(endpoints.len());
+        for endpoint in endpoints {
+            let id = self.ids.new_id();
+            let secret = crate::security::generate_webhook_secret();
+            let signing_key = crate::security::generate_webhook_signing_key();
+            let events_json = serde_json::to_value(&endpoint.events)
+                .map_err(|e| RepoError::Database(e.to_string()))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO webhook_endpoints
+                    (id, url, secret, events, is_active, created_at, timeout_ms,
+                     connect_timeout_ms, signature_algorithm, signing_key, mode)
+                VALUES ($1, $2, $3, $4, TRUE, $5, $6, $7, $8, $9, $10)
+                "#,
+            )
+            .bind(id)
+            .bind(&endpoint.url)
+            .bind(&secret)
+            .bind(&events_json)
+            .bind(now)
+            .bind(timeout_column(endpoint.timeouts.timeout_ms)?)
+            .bind(timeout_column(endpoint.timeouts.connect_timeout_ms)?)
+            .bind(endpoint.signature_algorithm.as_ref())
+            .bind(&signing_key)
+            .bind(endpoint.mode.as_str())
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
+
+            registered.push(payments_types::WebhookEndpoint {
+                id,
+                url: endpoint.url,
+                public_key: crate::security::webhook_public_key(
+                    endpoint.signature_algorithm,
+                    &signing_key,
+                ),
+                secret,
+                events: endpoint.events,
+                is_active: true,
+                created_at: now,
+                timeouts: endpoint.timeouts,
+                signature_algorithm: endpoint.signature_algorithm,
+                signing_key,
+                mode: endpoint.mode,
+            });
+        }
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(registered)
+    }
+
+    async fn list_webhook_endpoints(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+        let rows: Vec<WebhookEndpointRow> = sqlx::query_as(
+            r#"
+            SELECT id, url, secret, events, is_active, created_at, timeout_ms, connect_timeout_ms,
+                   signature_algorithm, signing_key, mode
+            FROM webhook_endpoints
+            WHERE is_active = TRUE AND ($1::text IS NULL OR mode = $1)
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(webhook_endpoint_from_row).collect()
+    }
+
+    async fn get_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        let row: Option<WebhookEndpointRow> = sqlx::query_as(
+            r#"
+            SELECT id, url, secret, events, is_active, created_at, timeout_ms, connect_timeout_ms,
+                   signature_algorithm, signing_key, mode
+            FROM webhook_endpoints
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(webhook_endpoint_from_row).transpose()
+    }
+
+    async fn update_webhook_endpoint_url(
+        &self,
+        id: payments_types::WebhookEndpointId,
+        url: &str,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        sqlx::query("UPDATE webhook_endpoints SET url = $1 WHERE id = $2")
+            .bind(url)
+            .bind(id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        self.get_webhook_endpoint(id).await
+    }
+
+    async fn update_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<WebhookSignatureAlgorithm>,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        let events_json = events
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"
+            UPDATE webhook_endpoints
+            SET events = COALESCE($1, events), is_active = COALESCE($2, is_active),
+                signature_algorithm = COALESCE($3, signature_algorithm)
+            WHERE id = $4
+            "#,
+        )
+        .bind(events_json)
+        .bind(is_active)
+        .bind(signature_algorithm.as_ref().map(AsRef::as_ref))
+        .bind(id.0)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        self.get_webhook_endpoint(id).await
+    }
+
+    async fn rotate_webhook_secret(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        sqlx::query("UPDATE webhook_endpoints SET secret = $1, signing_key = $2 WHERE id = $3")
+            .bind(crate::security::generate_webhook_secret())
+            .bind(crate::security::generate_webhook_signing_key())
+            .bind(id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        self.get_webhook_endpoint(id).await
+    }
+
+    async fn delete_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query("DELETE FROM webhook_endpoints WHERE id = $1")
+            .bind(id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn create_webhook_event(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<payments_types::WebhookEvent, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let event = self
+            .create_webhook_event_in(&mut db_tx, endpoint_id, event_type, payload)
+            .await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(event)
+    }
+
+    async fn get_webhook_event(&self, id: Uuid) -> Result<Option<WebhookEvent>, RepoError> {
+        let row = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
+            r#"
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
+            FROM webhook_events
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(|row| row.into_domain()).transpose()
+    }
+
+    async fn list_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let rows = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
+            r#"
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
+            FROM webhook_events
+            WHERE status = 'DEAD_LETTERED'
+              AND ($1::uuid IS NULL OR endpoint_id = $1)
+              AND ($2::text IS NULL OR event_type = $2)
+              AND ($3::timestamptz IS NULL OR processed_at >= $3)
+              AND ($4::text IS NULL OR mode = $4)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(filter.endpoint_id.map(|id| id.0))
+        .bind(filter.event_type.as_deref())
+        .bind(filter.failed_since)
+        .bind(filter.mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(|row| row.into_domain()).collect()
+    }
+
+    async fn requeue_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<u64, RepoError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_events
+            SET status = 'PENDING', attempts = 0, processed_at = NULL, next_attempt_at = $1
+            WHERE status = 'DEAD_LETTERED'
+              AND ($2::uuid IS NULL OR endpoint_id = $2)
+              AND ($3::text IS NULL OR event_type = $3)
+              AND ($4::timestamptz IS NULL OR processed_at >= $4)
+              AND ($5::text IS NULL OR mode = $5)
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(filter.endpoint_id.map(|id| id.0))
+        .bind(filter.event_type.as_deref())
+        .bind(filter.failed_since)
+        .bind(filter.mode.map(|mode| mode.as_str()))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn count_webhook_backlog(&self) -> Result<i64, RepoError> {
+        let row: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM webhook_events WHERE status IN ('PENDING', 'FAILED')",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(row.0)
+    }
+
+    async fn storage_counts(&self) -> Result<StorageCounts, RepoError> {
+        let row: (i64, i64, i64) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM accounts), (SELECT COUNT(*) FROM transactions), \
+             (SELECT COUNT(*) FROM webhook_events)",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(StorageCounts {
+            accounts: row.0,
+            transactions: row.1,
+            webhook_events: row.2,
+        })
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_webhook_events_after(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        after_sequence: i64,
+        limit: usize,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let rows = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
+            r#"
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
+            FROM webhook_events
+            WHERE endpoint_id = $1 AND delivery_sequence > $2
+            ORDER BY delivery_sequence ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(endpoint_id.0)
+        .bind(after_sequence)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(|row| row.into_domain()).collect()
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        filter: &WebhookDeliveryFilter,
+        limit: usize,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let rows = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
+            r#"
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
+            FROM webhook_events
+            WHERE endpoint_id = $1
+              AND ($2::text IS NULL OR status = $2)
+              AND ($3::bigint IS NULL OR delivery_sequence < $3)
+            ORDER BY delivery_sequence DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(endpoint_id.0)
+        .bind(filter.status.as_ref().map(|s| s.to_string()))
+        .bind(filter.before_sequence)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(|row| row.into_domain()).collect()
+    }
+
+    async fn requeue_webhook_event(&self, id: Uuid) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_events
+            SET status = 'PENDING', attempts = 0, processed_at = NULL, next_attempt_at = $1
+            WHERE id = $2 AND status IN ('FAILED', 'DEAD_LETTERED')
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn append_event(&self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        self.append_event_in(&mut db_tx, event, mode).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(())
+    }
+
+    async fn list_logged_events(
+        &self,
+        after: i64,
+        limit: usize,
+        mode: Option<Mode>,
+    ) -> Result<Vec<LoggedEvent>, RepoError> {
+        let rows: Vec<LoggedEventRow> = sqlx::query_as(
+            r#"SELECT sequence, event_type, payload, occurred_at, recorded_at, mode
+               FROM event_log WHERE sequence > $1 AND ($3::text IS NULL OR mode = $3)
+               ORDER BY sequence LIMIT $2"#,
+        )
+        .bind(after)
+        .bind(limit as i64)
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(
+                |(sequence, event_type, data, occurred_at, recorded_at, mode)| {
+                    Ok(LoggedEvent {
+                        sequence,
+                        event_type,
+                        occurred_at,
+                        recorded_at,
+                        data,
+                        mode: mode.parse().map_err(RepoError::Database)?,
+                    })
+                },
+            )
+            .collect()
+    }
+
+    async fn create_fee_schedule(
+        &self,
+        name: &str,
+        tiers: &[FeeTier],
+    ) -> Result<FeeSchedule, RepoError> {
+        let id = FeeScheduleId::from_uuid(self.ids.new_id());
+        let tiers_json =
+            serde_json::to_value(tiers).map_err(|e| RepoError::Database(e.to_string()))?;
+
+        let (created_at,): (DateTime<Utc>,) = sqlx::query_as(
+            r#"INSERT INTO fee_schedules (id, name, tiers, created_at)
+               VALUES ($1, $2, $3, $4)
+               RETURNING created_at"#,
+        )
+        .bind(id.into_uuid())
+        .bind(name)
+        .bind(tiers_json)
+        .bind(self.clock.now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(FeeSchedule {
+            id,
+            name: name.to_string(),
+            tiers: tiers.to_vec(),
+            created_at,
+        })
+    }
+
+    async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<Option<FeeSchedule>, RepoError> {
+        let row: Option<FeeScheduleRow> =
+            sqlx::query_as("SELECT id, name, tiers, created_at FROM fee_schedules WHERE id = $1")
+                .bind(id.into_uuid())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        row.map(fee_schedule_from_row).transpose()
+    }
+
+    async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError> {
+        let rows: Vec<FeeScheduleRow> = sqlx::query_as(
+            "SELECT id, name, tiers, created_at FROM fee_schedules ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(fee_schedule_from_row).collect()
+    }
+
+    async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: DateTime<Utc>,
+    ) -> Result<FeeAssignment, RepoError> {
+        // Read the timestamps back so callers see them at the stored
+        // (microsecond) precision, matching later listings.
+        let (effective_from, assigned_at): (DateTime<Utc>, DateTime<Utc>) = sqlx::query_as(
+            r#"INSERT INTO account_fee_assignments
+                   (account_id, fee_schedule_id, effective_from, assigned_at)
+               VALUES ($1, $2, $3, $4)
+               RETURNING effective_from, assigned_at"#,
+        )
+        .bind(account_id.into_uuid())
+        .bind(fee_schedule_id.into_uuid())
+        .bind(effective_from)
+        .bind(self.clock.now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(FeeAssignment {
+            account_id,
+            fee_schedule_id,
+            effective_from,
+            assigned_at,
+        })
+    }
+
+    async fn list_fee_assignments(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<FeeAssignment>, RepoError> {
+        let rows: Vec<(Uuid, DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(
+            r#"SELECT fee_schedule_id, effective_from, assigned_at
+               FROM account_fee_assignments WHERE account_id = $1
+               ORDER BY effective_from, assigned_at, id"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(fee_schedule_id, effective_from, assigned_at)| FeeAssignment {
+                    account_id,
+                    fee_schedule_id: FeeScheduleId::from_uuid(fee_schedule_id),
+                    effective_from,
+                    assigned_at,
+                },
+            )
+            .collect())
+    }
+
+    async fn create_settlement_batch(
+        &self,
+        cutoff: DateTime<Utc>,
+        mode: Mode,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let id = self.ids.new_id();
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+
+        sqlx::query(
+            r#"INSERT INTO settlement_batches (id, status, cutoff, payout_count, totals, created_at, updated_at, mode)
+               VALUES ($1, $2, $3, 0, '[]', $4, $4, $5)"#,
+        )
+        .bind(id)
+        .bind(SettlementBatchStatus::Pending.as_ref())
+        .bind(cutoff)
+        .bind(self.clock.now())
+        .bind(mode.as_str())
+        .execute(&mut *db_tx)
+        .await
+        .map_err(db_error)?;
+
+        // ON CONFLICT skips payouts a concurrent batch claimed first.
+        sqlx::query(
+            r#"INSERT INTO settlement_batch_payouts (transaction_id, batch_id)
+               SELECT t.id, $1 FROM transactions t
+               WHERE t.direction = 'WITHDRAWAL' AND t.created_at <= $2
+                 AND NOT EXISTS (SELECT 1 FROM settlement_batch_payouts p WHERE p.transaction_id = t.id)
+                 AND t.source_account_id IN (SELECT id FROM accounts WHERE mode = $3)
+               ON CONFLICT (transaction_id) DO NOTHING"#,
+        )
+        .bind(id)
+        .bind(cutoff)
+        .bind(mode.as_str())
+        .execute(&mut *db_tx)
+        .await
+        .map_err(db_error)?;
+
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT t.id, t.direction, t.amount, t.currency, t.source_account_id, t.destination_account_id, t.idempotency_key, t.reference, t.counterparty_name, t.counterparty_external_id, t.purpose_code, t.fx_rate, t.converted_amount, t.converted_currency, t.risk_decision, t.risk_reasons, t.metadata, t.encrypted_memo, t.created_at
+               FROM transactions t
+               JOIN settlement_batch_payouts p ON p.transaction_id = t.id
+               WHERE p.batch_id = $1"#,
+        )
+        .bind(id)
+        .fetch_all(&mut *db_tx)
+        .await
+        .map_err(db_error)?;
+        if rows.is_empty() {
+            db_tx.rollback().await.map_err(tx_error)?;
+            return Ok(None);
+        }
+
+        let payouts = rows
+            .into_iter()
+            .map(DbTransaction::into_domain)
+            .collect::<Result<Vec<_>, _>>()?;
+        let totals = serde_json::to_value(SettlementBatch::totals_for(&payouts))
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+
+        let row: SettlementBatchRow = sqlx::query_as(
+            r#"UPDATE settlement_batches SET payout_count = $2, totals = $3 WHERE id = $1
+               RETURNING id, status, cutoff, payout_count, totals, created_at, updated_at, mode"#,
+        )
+        .bind(id)
+        .bind(payouts.len() as i64)
+        .bind(totals)
+        .fetch_one(&mut *db_tx)
+        .await
+        .map_err(db_error)?;
+
+        db_tx.commit().await.map_err(tx_error)?;
+        settlement_batch_from_row(row).map(Some)
+    }
+
+    async fn get_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let row: Option<SettlementBatchRow> = sqlx::query_as(
+            r#"SELECT id, status, cutoff, payout_count, totals, created_at, updated_at, mode
+               FROM settlement_batches WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(settlement_batch_from_row).transpose()
+    }
+
+    async fn list_settlement_batches(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<SettlementBatch>, RepoError> {
+        let rows: Vec<SettlementBatchRow> = sqlx::query_as(
+            r#"SELECT id, status, cutoff, payout_count, totals, created_at, updated_at, mode
+               FROM settlement_batches WHERE $1::text IS NULL OR mode = $1
+               ORDER BY created_at DESC"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(settlement_batch_from_row).collect()
+    }
+
+    async fn update_settlement_batch_status(
+        &self,
+        id: SettlementBatchId,
+        from: SettlementBatchStatus,
+        to: SettlementBatchStatus,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+
+        let row: Option<SettlementBatchRow> = sqlx::query_as(
+            r#"UPDATE settlement_batches SET status = $3, updated_at = $4
+               WHERE id = $1 AND status = $2
+               RETURNING id, status, cutoff, payout_count, totals, created_at, updated_at, mode"#,
+        )
+        .bind(id.into_uuid())
+        .bind(from.as_ref())
+        .bind(to.as_ref())
+        .bind(self.clock.now())
+        .fetch_optional(&mut *db_tx)
+        .await
+        .map_err(db_error)?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        if to == SettlementBatchStatus::Cancelled {
+            sqlx::query("DELETE FROM settlement_batch_payouts WHERE batch_id = $1")
+                .bind(id.into_uuid())
+                .execute(&mut *db_tx)
+                .await
+                .map_err(db_error)?;
+        }
+
+        db_tx.commit().await.map_err(tx_error)?;
+        settlement_batch_from_row(row).map(Some)
+    }
+
+    async fn list_settlement_batch_payouts(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT t.id, t.direction, t.amount, t.currency, t.source_account_id, t.destination_account_id, t.idempotency_key, t.reference, t.counterparty_name, t.counterparty_external_id, t.purpose_code, t.fx_rate, t.converted_amount, t.converted_currency, t.risk_decision, t.risk_reasons, t.metadata, t.encrypted_memo, t.created_at
+               FROM transactions t
+               JOIN settlement_batch_payouts p ON p.transaction_id = t.id
+               WHERE p.batch_id = $1
+               ORDER BY t.created_at, t.id"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbTransaction::into_domain).collect()
+    }
+
+    async fn get_statement_email(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<String>, RepoError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT email FROM statement_emails WHERE account_id = $1")
+                .bind(account_id.into_uuid())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+        Ok(row.map(|(email,)| email))
+    }
+
+    async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<(), RepoError> {
+        let query = match email {
+            Some(email) => sqlx::query(
+                r#"INSERT INTO statement_emails (account_id, email) VALUES ($1, $2)
+                   ON CONFLICT (account_id) DO UPDATE SET email = EXCLUDED.email"#,
+            )
+            .bind(account_id.into_uuid())
+            .bind(email),
+            None => sqlx::query("DELETE FROM statement_emails WHERE account_id = $1")
+                .bind(account_id.into_uuid()),
+        };
+        query.execute(&self.pool).await.map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn insert_statement(
+        &self,
+        statement: &Statement,
+        content: &str,
+    ) -> Result<bool, RepoError> {
+        let inserted = sqlx::query(
+            r#"INSERT INTO statements (id, account_id, period_start, period_end, currency, opening_balance, closing_balance, transaction_count, content, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               ON CONFLICT (account_id, period_start) DO NOTHING"#,
+        )
+        .bind(statement.id.into_uuid())
+        .bind(statement.account_id.into_uuid())
+        .bind(statement.period_start)
+        .bind(statement.period_end)
+        .bind(statement.currency.to_string())
+        .bind(statement.opening_balance)
+        .bind(statement.closing_balance)
+        .bind(statement.transaction_count)
+        .bind(content)
+        .bind(statement.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError> {
+        let row: Option<StatementRow> = sqlx::query_as(
+            r#"SELECT id, account_id, period_start, period_end, currency, opening_balance, closing_balance, transaction_count, created_at
+               FROM statements WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(statement_from_row).transpose()
+    }
+
+    async fn get_statement_content(&self, id: StatementId) -> Result<Option<String>, RepoError> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT content FROM statements WHERE id = $1")
+            .bind(id.into_uuid())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|(content,)| content))
+    }
+
+    async fn list_statements(&self, account_id: AccountId) -> Result<Vec<Statement>, RepoError> {
+        let rows: Vec<StatementRow> = sqlx::query_as(
+            r#"SELECT id, account_id, period_start, period_end, currency, opening_balance, closing_balance, transaction_count, created_at
+               FROM statements WHERE account_id = $1
+               ORDER BY period_start DESC"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(statement_from_row).collect()
+    }
+
+    async fn insert_export(&self, export: &Export) -> Result<(), RepoError> {
+        let request =
+            serde_json::to_value(export.request).map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"INSERT INTO exports (id, request, status, error, created_at, completed_at, mode)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+        )
+        .bind(export.id.into_uuid())
+        .bind(request)
+        .bind(export.status.as_ref())
+        .bind(export.error.as_deref())
+        .bind(export.created_at)
+        .bind(export.completed_at)
+        .bind(export.mode.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn finish_export(&self, export: &Export, content: Option<&str>) -> Result<(), RepoError> {
+        sqlx::query(
+            "UPDATE exports SET status = $1, error = $2, content = $3, completed_at = $4 WHERE id = $5",
+        )
+        .bind(export.status.as_ref())
+        .bind(export.error.as_deref())
+        .bind(content)
+        .bind(export.completed_at)
+        .bind(export.id.into_uuid())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError> {
+        let row: Option<ExportRow> = sqlx::query_as(
+            "SELECT id, request, status, error, created_at, completed_at, mode FROM exports WHERE id = $1",
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(export_from_row).transpose()
+    }
+
+    async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError> {
+        let row: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT content FROM exports WHERE id = $1")
+                .bind(id.into_uuid())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+        Ok(row.and_then(|(content,)| content))
+    }
+
+    async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError> {
+        let result = sqlx::query("DELETE FROM exports WHERE created_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(result.rows_affected())
+    }
+
+    async fn insert_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<(), RepoError> {
+        let schedule = serde_json::to_value(&payment.schedule)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"INSERT INTO scheduled_payments (id, transaction_type, from_account_id, to_account_id, amount, currency, reference, purpose_code, schedule, status, next_run_at, last_run_at, last_transaction_id, last_error, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)"#,
+        )
+        .bind(payment.id.into_uuid())
+        .bind(payment.transaction_type.to_string())
+        .bind(payment.from_account_id.into_uuid())
+        .bind(payment.to_account_id.map(AccountId::into_uuid))
+        .bind(payment.amount)
+        .bind(payment.currency.to_string())
+        .bind(payment.reference.as_deref())
+        .bind(payment.purpose_code.as_deref())
+        .bind(schedule)
+        .bind(payment.status.as_ref())
+        .bind(payment.next_run_at)
+        .bind(payment.last_run_at)
+        .bind(payment.last_transaction_id.map(TransactionId::into_uuid))
+        .bind(payment.last_error.as_deref())
+        .bind(payment.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<Option<ScheduledPayment>, RepoError> {
+        let row: Option<ScheduledPaymentRow> = sqlx::query_as(
+            r#"SELECT id, transaction_type, from_account_id, to_account_id, amount, currency, reference, purpose_code, schedule, status, next_run_at, last_run_at, last_transaction_id, last_error, created_at
+               FROM scheduled_payments WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(scheduled_payment_from_row).transpose()
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        let rows: Vec<ScheduledPaymentRow> = sqlx::query_as(
+            r#"SELECT id, transaction_type, from_account_id, to_account_id, amount, currency, reference, purpose_code, schedule, status, next_run_at, last_run_at, last_transaction_id, last_error, created_at
+               FROM scheduled_payments
+               WHERE ($1::uuid IS NULL OR from_account_id = $1)
+                 AND ($2::text IS NULL
+                      OR from_account_id IN (SELECT id FROM accounts WHERE mode = $2))
+               ORDER BY created_at, id"#,
+        )
+        .bind(account_id.map(AccountId::into_uuid))
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(scheduled_payment_from_row).collect()
+    }
+
+    async fn list_due_scheduled_payments(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        let rows: Vec<ScheduledPaymentRow> = sqlx::query_as(
+            r#"SELECT id, transaction_type, from_account_id, to_account_id, amount, currency, reference, purpose_code, schedule, status, next_run_at, last_run_at, last_transaction_id, last_error, created_at
+               FROM scheduled_payments
+               WHERE status = 'ACTIVE' AND next_run_at <= $1
+               ORDER BY next_run_at, id
+               LIMIT $2"#,
+        )
+        .bind(now)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(scheduled_payment_from_row).collect()
+    }
+
+    async fn record_scheduled_payment_run(
+        &self,
+        payment: &ScheduledPayment,
+        due_at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"UPDATE scheduled_payments
+               SET next_run_at = $1, last_run_at = $2, last_transaction_id = $3, last_error = $4
+               WHERE id = $5 AND next_run_at = $6"#,
+        )
+        .bind(payment.next_run_at)
+        .bind(payment.last_run_at)
+        .bind(payment.last_transaction_id.map(TransactionId::into_uuid))
+        .bind(payment.last_error.as_deref())
+        .bind(payment.id.into_uuid())
+        .bind(due_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update_scheduled_payment_status(
+        &self,
+        payment: &ScheduledPayment,
+    ) -> Result<(), RepoError> {
+        sqlx::query("UPDATE scheduled_payments SET status = $1, next_run_at = $2 WHERE id = $3")
+            .bind(payment.status.as_ref())
+            .bind(payment.next_run_at)
+            .bind(payment.id.into_uuid())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        create_hold(&mut db_tx, hold).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(())
+    }
+
+    async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError> {
+        let row: Option<HoldRow> = sqlx::query_as(
+            r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at
+               FROM holds WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(hold_from_row).transpose()
+    }
+
+    async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError> {
+        let rows: Vec<HoldRow> = sqlx::query_as(
+            r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at
+               FROM holds WHERE account_id = $1
+               ORDER BY created_at DESC, id DESC"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(hold_from_row).collect()
+    }
+
+    async fn list_expired_holds(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Hold>, RepoError> {
+        let rows: Vec<HoldRow> = sqlx::query_as(
+            r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at
+               FROM holds
+               WHERE status = 'AUTHORIZED' AND expires_at <= $1
+               ORDER BY expires_at, id
+               LIMIT $2"#,
+        )
+        .bind(now)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(hold_from_row).collect()
+    }
+
+    async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let captured = self.capture_hold_in(&mut db_tx, id, amount, now).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(captured)
+    }
+
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let hold = self.release_hold_in(&mut db_tx, id, status, now).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(hold)
+    }
+
+    async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError> {
+        let [first, second] = beneficiary.micro_deposits;
+        sqlx::query(
+            r#"INSERT INTO beneficiaries (id, account_id, name, external_id, currency, status, failed_attempts, micro_deposit_1, micro_deposit_2, created_at, verified_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+        )
+        .bind(beneficiary.id.into_uuid())
+        .bind(beneficiary.account_id.into_uuid())
+        .bind(&beneficiary.name)
+        .bind(&beneficiary.external_id)
+        .bind(beneficiary.currency.to_string())
+        .bind(beneficiary.status.as_ref())
+        .bind(attempts_column(beneficiary.failed_attempts)?)
+        .bind(first)
+        .bind(second)
+        .bind(beneficiary.created_at)
+        .bind(beneficiary.verified_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Option<Beneficiary>, RepoError> {
+        let row: Option<BeneficiaryRow> = sqlx::query_as(
+            r#"SELECT id, account_id, name, external_id, currency, status, failed_attempts, micro_deposit_1, micro_deposit_2, created_at, verified_at
+               FROM beneficiaries WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(beneficiary_from_row).transpose()
+    }
+
+    async fn list_beneficiaries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, RepoError> {
+        let rows: Vec<BeneficiaryRow> = sqlx::query_as(
+            r#"SELECT id, account_id, name, external_id, currency, status, failed_attempts, micro_deposit_1, micro_deposit_2, created_at, verified_at
+               FROM beneficiaries WHERE account_id = $1
+               ORDER BY created_at DESC, id DESC"#,
+        )
+        .bind(account_id.into_uuid())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(beneficiary_from_row).collect()
+    }
+
+    async fn record_beneficiary_attempt(
+        &self,
+        beneficiary: &Beneficiary,
+        failed_attempts: u32,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"UPDATE beneficiaries SET status = $1, failed_attempts = $2, verified_at = $3
+               WHERE id = $4 AND status = 'PENDING' AND failed_attempts = $5"#,
+        )
+        .bind(beneficiary.status.as_ref())
+        .bind(attempts_column(beneficiary.failed_attempts)?)
+        .bind(beneficiary.verified_at)
+        .bind(beneficiary.id.into_uuid())
+        .bind(attempts_column(failed_attempts)?)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn currency_balances(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<CurrencyBalance>, RepoError> {
+        let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+            r#"SELECT currency, COUNT(*), COALESCE(SUM(balance), 0)::BIGINT FROM accounts
+               WHERE $1::text IS NULL OR mode = $1
+               GROUP BY currency ORDER BY currency"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(|(currency, account_count, balance)| {
+                Ok(CurrencyBalance {
+                    currency: parse_currency(&currency)?,
+                    account_count,
+                    balance,
+                })
+            })
+            .collect()
+    }
+
+    async fn float_positions(&self, mode: Option<Mode>) -> Result<Vec<FloatPosition>, RepoError> {
+        let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+            r#"WITH moded AS (
+                   SELECT * FROM transactions
+                   WHERE $1::text IS NULL OR EXISTS (SELECT 1 FROM accounts WHERE accounts.id IN (source_account_id, destination_account_id) AND accounts.mode = $1)
+               )
+               SELECT currency, COALESCE(SUM(customer_balance), 0)::BIGINT, COALESCE(SUM(float_balance), 0)::BIGINT FROM (
+                   SELECT currency, balance AS customer_balance, 0 AS float_balance FROM accounts
+                   WHERE $1::text IS NULL OR mode = $1
+                   UNION ALL
+                   SELECT currency, 0, -amount FROM moded WHERE direction = 'DEPOSIT'
+                   UNION ALL
+                   SELECT currency, 0, amount FROM moded WHERE direction = 'WITHDRAWAL'
+                   UNION ALL
+                   SELECT currency, 0, amount FROM moded WHERE converted_currency IS NOT NULL
+                   UNION ALL
+                   SELECT converted_currency, 0, -converted_amount FROM moded
+                   WHERE converted_currency IS NOT NULL
+               ) AS ledger
+               GROUP BY currency ORDER BY currency"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(|(currency, customer_balance, float_balance)| {
+                Ok(FloatPosition {
+                    currency: parse_currency(&currency)?,
+                    customer_balance,
+                    float_balance,
+                })
+            })
+            .collect()
+    }
+
+    async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError> {
+        let usd_rates = serde_json::to_value(&snapshot.usd_rates)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"INSERT INTO rate_snapshots (taken_at, usd_rates) VALUES ($1, $2)
+               ON CONFLICT (taken_at) DO UPDATE SET usd_rates = EXCLUDED.usd_rates"#,
+        )
+        .bind(snapshot.taken_at)
+        .bind(usd_rates)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn rate_snapshot_at(&self, at: DateTime<Utc>) -> Result<Option<RateSnapshot>, RepoError> {
+        let row: Option<(DateTime<Utc>, serde_json::Value)> = sqlx::query_as(
+            r#"SELECT taken_at, usd_rates FROM rate_snapshots
+               WHERE taken_at <= $1 ORDER BY taken_at DESC LIMIT 1"#,
+        )
+        .bind(at)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(|(taken_at, usd_rates)| {
+            Ok(RateSnapshot {
+                taken_at,
+                usd_rates: serde_json::from_value(usd_rates)
+                    .map_err(|e| RepoError::Database(e.to_string()))?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"INSERT INTO fx_quotes (id, from_currency, to_currency, amount, rate, converted_amount, created_at, expires_at, transaction_id, mode)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+        )
+        .bind(quote.id.into_uuid())
+        .bind(quote.from_currency.to_string())
+        .bind(quote.to_currency.to_string())
+        .bind(quote.amount)
+        .bind(quote.rate)
+        .bind(quote.converted_amount)
+        .bind(quote.created_at)
+        .bind(quote.expires_at)
+        .bind(quote.transaction_id.map(TransactionId::into_uuid))
+        .bind(quote.mode.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError> {
+        let row: Option<QuoteRow> = sqlx::query_as(
+            r#"SELECT id, from_currency, to_currency, amount, rate, converted_amount, created_at, expires_at, transaction_id, mode
+               FROM fx_quotes WHERE id = $1"#,
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(quote_from_row).transpose()
+    }
+
+    async fn save_daily_balances(
+        &self,
+        account_id: AccountId,
+        balances: &[DailyBalance],
+    ) -> Result<(), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        for daily in balances {
+            sqlx::query(
+                r#"INSERT INTO daily_balances (account_id, date, balance) VALUES ($1, $2, $3)
+                   ON CONFLICT (account_id, date) DO UPDATE SET balance = EXCLUDED.balance"#,
+            )
+            .bind(account_id.into_uuid())
+            .bind(daily.date)
+            .bind(daily.balance)
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
+        }
+        db_tx.commit().await.map_err(tx_error)
+    }
+
+    async fn list_daily_balances(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyBalance>, RepoError> {
+        let rows: Vec<(NaiveDate, i64)> = sqlx::query_as(
+            r#"SELECT date, balance FROM daily_balances
+               WHERE account_id = $1 AND date BETWEEN $2 AND $3
+               ORDER BY date"#,
+        )
+        .bind(account_id.into_uuid())
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(date, balance)| DailyBalance { date, balance })
+            .collect())
+    }
 
-        Ok(Transaction::transfer(
-            req.from_account_id,
-            req.to_account_id,
-            money,
-            req.idempotency_key,
-            req.reference,
-        ))
+    async fn latest_daily_balance_date(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<NaiveDate>, RepoError> {
+        let row: (Option<NaiveDate>,) =
+            sqlx::query_as("SELECT MAX(date) FROM daily_balances WHERE account_id = $1")
+                .bind(account_id.into_uuid())
+                .fetch_one(&self.pool)
+                .await
+                .map_err(db_error)?;
+        Ok(row.0)
     }
 
-    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
-        let row: Option<DbTransaction> = sqlx::query_as(
-            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, created_at
-               FROM transactions WHERE idempotency_key = $1"#,
+    async fn insert_change_request(&self, request: &ChangeRequest) -> Result<(), RepoError> {
+        let action = serde_json::to_value(&request.action)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"INSERT INTO change_requests (id, action, status, requested_by, requested_at, decided_by, decided_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
         )
-        .bind(key)
-        .fetch_optional(&self.pool)
+        .bind(request.id.into_uuid())
+        .bind(action)
+        .bind(request.status.as_ref())
+        .bind(request.requested_by.into_uuid())
+        .bind(request.requested_at)
+        .bind(request.decided_by.map(ApiKeyId::into_uuid))
+        .bind(request.decided_at)
+        .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        row.map(DbTransaction::into_domain).transpose()
+        .map_err(db_error)?;
+        Ok(())
     }
 
-    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
-        let row: Option<DbTransaction> = sqlx::query_as(
-            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, created_at
-               FROM transactions WHERE id = $1"#,
+    async fn get_change_request(
+        &self,
+        id: ChangeRequestId,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        let row: Option<ChangeRequestRow> = sqlx::query_as(
+            r#"SELECT id, action, status, requested_by, requested_at, decided_by, decided_at
+               FROM change_requests WHERE id = $1"#,
         )
         .bind(id.into_uuid())
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        row.map(DbTransaction::into_domain).transpose()
+        row.map(change_request_from_row).transpose()
     }
 
-    async fn list_transactions_for_account(
+    async fn list_change_requests(
         &self,
-        account_id: AccountId,
-    ) -> Result<Vec<Transaction>, RepoError> {
-        let rows: Vec<DbTransaction> = sqlx::query_as(
-            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, created_at
-               FROM transactions WHERE source_account_id = $1 OR destination_account_id = $1
-               ORDER BY created_at DESC"#,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, RepoError> {
+        let rows: Vec<ChangeRequestRow> = sqlx::query_as(
+            r#"SELECT id, action, status, requested_by, requested_at, decided_by, decided_at
+               FROM change_requests
+               WHERE $1::TEXT IS NULL OR status = $1
+               ORDER BY requested_at DESC, id DESC"#,
         )
-        .bind(account_id.into_uuid())
+        .bind(status.as_ref().map(AsRef::<str>::as_ref))
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        rows.into_iter().map(DbTransaction::into_domain).collect()
+        rows.into_iter().map(change_request_from_row).collect()
     }
 
-    async fn verify_api_key_hash(
+    async fn decide_change_request(
         &self,
-        key_hash: &str,
-    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
-        let row: Option<crate::types::DbApiKey> = sqlx::query_as(
-            r#"
-            SELECT id, name, key_hash, account_id, is_active, created_at, last_used_at
-            FROM api_keys
-            WHERE key_hash = $1 AND is_active = TRUE
-            "#,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: ApiKeyId,
+        decided_at: DateTime<Utc>,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        let row: Option<ChangeRequestRow> = sqlx::query_as(
+            r#"UPDATE change_requests SET status = $1, decided_by = $2, decided_at = $3
+               WHERE id = $4 AND status = $5
+               RETURNING id, action, status, requested_by, requested_at, decided_by, decided_at"#,
         )
-        .bind(key_hash)
+        .bind(status.as_ref())
+        .bind(decided_by.into_uuid())
+        .bind(decided_at)
+        .bind(id.into_uuid())
+        .bind(ChangeStatus::Pending.as_ref())
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        row.map(|r| r.into_domain()).transpose()
+        row.map(change_request_from_row).transpose()
     }
 
-    async fn create_api_key(
+    async fn get_idempotency_record(
         &self,
-        name: &str,
-    ) -> Result<(payments_types::ApiKey, String), RepoError> {
-        use rand::Rng;
-        use rand::distr::Alphanumeric;
-
-        // Generate a secure random API key
-        let raw_key: String = rand::rng()
-            .sample_iter(&Alphanumeric)
-            .take(32)
-            .map(char::from)
-            .collect();
-        let prefixed_key = format!("sk_{}", raw_key);
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, RepoError> {
+        let row: Option<IdempotencyRow> = sqlx::query_as(
+            r#"SELECT endpoint, idempotency_key, request_hash, status, response, created_at
+               FROM idempotency_responses WHERE endpoint = $1 AND idempotency_key = $2"#,
+        )
+        .bind(endpoint)
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
 
-        let key_hash = crate::security::hash_api_key(&prefixed_key);
-        let id = Uuid::new_v4();
-        let now = Utc::now();
+        row.map(idempotency_record_from_row).transpose()
+    }
 
+    async fn save_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, RepoError> {
+        // Only a lapsed reservation gives way to the new record
         sqlx::query(
-            r#"
-            INSERT INTO api_keys (id, name, key_hash, is_active, created_at)
-            VALUES ($1, $2, $3, TRUE, $4)
-            "#,
+            r#"INSERT INTO idempotency_responses
+                   (endpoint, idempotency_key, request_hash, status, response, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (endpoint, idempotency_key) DO UPDATE SET
+                   request_hash = EXCLUDED.request_hash,
+                   status = EXCLUDED.status,
+                   response = EXCLUDED.response,
+                   created_at = EXCLUDED.created_at
+               WHERE idempotency_responses.status = $7
+                 AND idempotency_responses.created_at <= $8"#,
         )
-        .bind(id)
-        .bind(name)
-        .bind(&key_hash)
-        .bind(now)
+        .bind(&record.endpoint)
+        .bind(&record.key)
+        .bind(&record.request_hash)
+        .bind(i32::from(record.status))
+        .bind(&record.response)
+        .bind(record.created_at)
+        .bind(i32::from(IdempotencyRecord::IN_PROGRESS_STATUS))
+        .bind(record.created_at - IDEMPOTENCY_RESERVATION_TTL)
         .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        let api_key = payments_types::ApiKey {
-            id: payments_types::ApiKeyId::from_uuid(id),
-            name: name.to_string(),
-            key_hash,
-            account_id: None,
-            is_active: true,
-            created_at: now,
-            last_used_at: None,
-        };
+        self.get_idempotency_record(&record.endpoint, &record.key)
+            .await?
+            .ok_or_else(|| RepoError::Database("Idempotency record vanished".into()))
+    }
 
-        Ok((api_key, prefixed_key))
+    async fn replace_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"INSERT INTO idempotency_responses
+                   (endpoint, idempotency_key, request_hash, status, response, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (endpoint, idempotency_key) DO UPDATE SET
+                   request_hash = EXCLUDED.request_hash,
+                   status = EXCLUDED.status,
+                   response = EXCLUDED.response,
+                   created_at = EXCLUDED.created_at"#,
+        )
+        .bind(&record.endpoint)
+        .bind(&record.key)
+        .bind(&record.request_hash)
+        .bind(i32::from(record.status))
+        .bind(&record.response)
+        .bind(record.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
     }
 
-    async fn count_api_keys(&self) -> Result<i64, RepoError> {
-        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM api_keys WHERE is_active = TRUE")
-            .fetch_one(&self.pool)
-            .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+    async fn remove_idempotency_record(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        sqlx::query(
+            "DELETE FROM idempotency_responses WHERE endpoint = $1 AND idempotency_key = $2",
+        )
+        .bind(endpoint)
+        .bind(key)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+}
 
-        Ok(row.0)
+/// `(id, action, status, requested_by, requested_at, decided_by, decided_at)`
+/// as stored in `change_requests`.
+type ChangeRequestRow = (
+    Uuid,
+    serde_json::Value,
+    String,
+    Uuid,
+    DateTime<Utc>,
+    Option<Uuid>,
+    Option<DateTime<Utc>>,
+);
+
+fn change_request_from_row(
+    (id, action, status, requested_by, requested_at, decided_by, decided_at): ChangeRequestRow,
+) -> Result<ChangeRequest, RepoError> {
+    Ok(ChangeRequest {
+        id: ChangeRequestId::from_uuid(id),
+        action: serde_json::from_value(action).map_err(|e| RepoError::Database(e.to_string()))?,
+        status: status.parse().map_err(RepoError::Database)?,
+        requested_by: ApiKeyId::from_uuid(requested_by),
+        requested_at,
+        decided_by: decided_by.map(ApiKeyId::from_uuid),
+        decided_at,
+    })
+}
+
+/// `(endpoint, idempotency_key, request_hash, status, response, created_at)`
+/// as stored in `idempotency_responses`.
+type IdempotencyRow = (
+    String,
+    String,
+    String,
+    i32,
+    serde_json::Value,
+    DateTime<Utc>,
+);
+
+fn idempotency_record_from_row(
+    (endpoint, key, request_hash, status, response, created_at): IdempotencyRow,
+) -> Result<IdempotencyRecord, RepoError> {
+    Ok(IdempotencyRecord {
+        endpoint,
+        key,
+        request_hash,
+        status: u16::try_from(status).map_err(|e| RepoError::Database(e.to_string()))?,
+        response,
+        created_at,
+    })
+}
+
+/// `(id, request, status, error, created_at, completed_at, mode)` as stored
+/// in `exports`.
+type ExportRow = (
+    Uuid,
+    serde_json::Value,
+    String,
+    Option<String>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    String,
+);
+
+fn export_from_row(
+    (id, request, status, error, created_at, completed_at, mode): ExportRow,
+) -> Result<Export, RepoError> {
+    Ok(Export {
+        id: ExportId::from_uuid(id),
+        request: serde_json::from_value(request).map_err(|e| RepoError::Database(e.to_string()))?,
+        status: status.parse().map_err(RepoError::Database)?,
+        error,
+        created_at,
+        completed_at,
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
+
+/// `(id, transaction_type, from_account_id, to_account_id, amount, currency,
+/// reference, purpose_code, schedule, status, next_run_at, last_run_at,
+/// last_transaction_id, last_error, created_at)` as stored in
+/// `scheduled_payments`.
+type ScheduledPaymentRow = (
+    Uuid,
+    String,
+    Uuid,
+    Option<Uuid>,
+    i64,
+    String,
+    Option<String>,
+    Option<String>,
+    serde_json::Value,
+    String,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    Option<Uuid>,
+    Option<String>,
+    DateTime<Utc>,
+);
+
+fn scheduled_payment_from_row(
+    (
+        id,
+        transaction_type,
+        from_account_id,
+        to_account_id,
+        amount,
+        currency,
+        reference,
+        purpose_code,
+        schedule,
+        status,
+        next_run_at,
+        last_run_at,
+        last_transaction_id,
+        last_error,
+        created_at,
+    ): ScheduledPaymentRow,
+) -> Result<ScheduledPayment, RepoError> {
+    Ok(ScheduledPayment {
+        id: ScheduledPaymentId::from_uuid(id),
+        transaction_type: parse_transaction_type(&transaction_type)?,
+        from_account_id: AccountId::from_uuid(from_account_id),
+        to_account_id: to_account_id.map(AccountId::from_uuid),
+        amount,
+        currency: parse_currency(&currency)?,
+        reference,
+        purpose_code,
+        schedule: serde_json::from_value(schedule)
+            .map_err(|e| RepoError::Database(e.to_string()))?,
+        status: status.parse().map_err(RepoError::Database)?,
+        next_run_at,
+        last_run_at,
+        last_transaction_id: last_transaction_id.map(TransactionId::from_uuid),
+        last_error,
+        created_at,
+    })
+}
+
+/// `(id, account_id, amount, currency, reference, purpose_code, status,
+/// expires_at, captured_amount, transaction_id, created_at, resolved_at)` as
+/// stored in `holds`.
+type HoldRow = (
+    Uuid,
+    Uuid,
+    i64,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    DateTime<Utc>,
+    Option<i64>,
+    Option<Uuid>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+fn hold_from_row(
+    (
+        id,
+        account_id,
+        amount,
+        currency,
+        reference,
+        purpose_code,
+        status,
+        expires_at,
+        captured_amount,
+        transaction_id,
+        created_at,
+        resolved_at,
+    ): HoldRow,
+) -> Result<Hold, RepoError> {
+    Ok(Hold {
+        id: HoldId::from_uuid(id),
+        account_id: AccountId::from_uuid(account_id),
+        amount,
+        currency: parse_currency(&currency)?,
+        reference,
+        purpose_code,
+        status: status.parse().map_err(RepoError::Database)?,
+        expires_at,
+        captured_amount,
+        transaction_id: transaction_id.map(TransactionId::from_uuid),
+        created_at,
+        resolved_at,
+    })
+}
+
+/// `(id, from_currency, to_currency, amount, rate, converted_amount,
+/// created_at, expires_at, transaction_id, mode)` as stored in `fx_quotes`.
+type QuoteRow = (
+    Uuid,
+    String,
+    String,
+    i64,
+    f64,
+    i64,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<Uuid>,
+    String,
+);
+
+fn quote_from_row(
+    (
+        id,
+        from_currency,
+        to_currency,
+        amount,
+        rate,
+        converted_amount,
+        created_at,
+        expires_at,
+        transaction_id,
+        mode,
+    ): QuoteRow,
+) -> Result<Quote, RepoError> {
+    Ok(Quote {
+        id: QuoteId::from_uuid(id),
+        from_currency: parse_currency(&from_currency)?,
+        to_currency: parse_currency(&to_currency)?,
+        amount,
+        rate,
+        converted_amount,
+        created_at,
+        expires_at,
+        transaction_id: transaction_id.map(TransactionId::from_uuid),
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
+
+/// `(id, account_id, name, external_id, currency, status, failed_attempts,
+/// micro_deposit_1, micro_deposit_2, created_at, verified_at)` as stored in
+/// `beneficiaries`.
+type BeneficiaryRow = (
+    Uuid,
+    Uuid,
+    String,
+    String,
+    String,
+    String,
+    i32,
+    i64,
+    i64,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+fn beneficiary_from_row(
+    (
+        id,
+        account_id,
+        name,
+        external_id,
+        currency,
+        status,
+        failed_attempts,
+        first,
+        second,
+        created_at,
+        verified_at,
+    ): BeneficiaryRow,
+) -> Result<Beneficiary, RepoError> {
+    Ok(Beneficiary {
+        id: BeneficiaryId::from_uuid(id),
+        account_id: AccountId::from_uuid(account_id),
+        name,
+        external_id,
+        currency: parse_currency(&currency)?,
+        status: status.parse().map_err(RepoError::Database)?,
+        failed_attempts: u32::try_from(failed_attempts)
+            .map_err(|e| RepoError::Database(e.to_string()))?,
+        micro_deposits: [first, second],
+        created_at,
+        verified_at,
+    })
+}
+
+/// A count of verification attempts as the `INTEGER` column stores it.
+fn attempts_column(attempts: u32) -> Result<i32, RepoError> {
+    i32::try_from(attempts).map_err(|e| RepoError::Database(e.to_string()))
+}
+
+/// `(id, account_id, period_start, period_end, currency, opening_balance,
+/// closing_balance, transaction_count, created_at)` as stored in `statements`.
+type StatementRow = (
+    Uuid,
+    Uuid,
+    NaiveDate,
+    NaiveDate,
+    String,
+    i64,
+    i64,
+    i64,
+    DateTime<Utc>,
+);
+
+fn statement_from_row(
+    (
+        id,
+        account_id,
+        period_start,
+        period_end,
+        currency,
+        opening_balance,
+        closing_balance,
+        transaction_count,
+        created_at,
+    ): StatementRow,
+) -> Result<Statement, RepoError> {
+    Ok(Statement {
+        id: StatementId::from_uuid(id),
+        account_id: AccountId::from_uuid(account_id),
+        period_start,
+        period_end,
+        currency: parse_currency(&currency)?,
+        opening_balance,
+        closing_balance,
+        transaction_count,
+        created_at,
+    })
+}
+
+/// `(alias, account_id, created_at)` as stored in `account_aliases`.
+type AccountAliasRow = (String, Uuid, DateTime<Utc>);
+
+fn account_alias_from_row(
+    (alias, account_id, created_at): AccountAliasRow,
+) -> Result<AccountAlias, RepoError> {
+    Ok(AccountAlias {
+        alias: Alias::parse(&alias).map_err(|e| RepoError::Database(e.to_string()))?,
+        account_id: AccountId::from_uuid(account_id),
+        created_at,
+    })
+}
+
+/// Wakes the webhook worker on the notifications migration 0041 sends when
+/// webhook events are inserted.
+///
+/// Retries fall due without an insert, so the source still wakes every
+/// [`NOTIFY_FALLBACK_INTERVAL`]; that also covers notifications missed while
+/// the listener reconnects.
+pub struct PgNotifySource {
+    listener: PgListener,
+}
+
+impl PgNotifySource {
+    /// Starts listening on its own connection from `pool`.
+    pub async fn connect(pool: &PgPool) -> Result<Self, sqlx::Error> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(WEBHOOK_EVENTS_CHANNEL).await?;
+        Ok(Self { listener })
     }
+}
 
-    async fn list_api_keys(&self) -> Result<Vec<payments_types::ApiKey>, RepoError> {
-        #[derive(sqlx::FromRow)]
-        struct DbApiKey {
-            id: Uuid,
-            name: String,
-            key_hash: String,
-            account_id: Option<Uuid>,
-            is_active: bool,
-            created_at: chrono::DateTime<Utc>,
-            last_used_at: Option<chrono::DateTime<Utc>>,
+#[async_trait]
+impl WebhookEventSource for PgNotifySource {
+    async fn wait(&mut self) {
+        match tokio::time::timeout(NOTIFY_FALLBACK_INTERVAL, self.listener.recv()).await {
+            Ok(Ok(_)) | Err(_) => {}
+            Ok(Err(e)) => {
+                warn!("Lost the webhook event listener, reconnecting: {}", e);
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
         }
+    }
+}
 
-        let rows: Vec<DbApiKey> = sqlx::query_as(
-            "SELECT id, name, key_hash, account_id, is_active, created_at, last_used_at FROM api_keys WHERE is_active = TRUE ORDER BY created_at DESC"
-        )
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+/// `(id, name, email, created_at)` as stored in `owners`.
+type OwnerRow = (Uuid, String, Option<String>, DateTime<Utc>);
 
-        Ok(rows
-            .into_iter()
-            .map(|row| payments_types::ApiKey {
-                id: payments_types::ApiKeyId::from_uuid(row.id),
-                name: row.name,
-                key_hash: row.key_hash,
-                account_id: row.account_id.map(payments_types::AccountId::from_uuid),
-                is_active: row.is_active,
-                created_at: row.created_at,
-                last_used_at: row.last_used_at,
-            })
-            .collect())
+fn owner_from_row((id, name, email, created_at): OwnerRow) -> Owner {
+    Owner {
+        id: OwnerId::from_uuid(id),
+        name,
+        email,
+        created_at,
     }
+}
 
-    async fn delete_api_key(&self, id: payments_types::ApiKeyId) -> Result<bool, RepoError> {
-        let result =
-            sqlx::query("UPDATE api_keys SET is_active = FALSE WHERE id = $1 AND is_active = TRUE")
-                .bind(id.into_uuid())
-                .execute(&self.pool)
-                .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
+/// `(id, account_id, algorithm, fingerprint, created_at, retired_at)` as
+/// stored in `memo_keys`.
+type MemoKeyRow = (
+    Uuid,
+    Uuid,
+    String,
+    String,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
 
-        Ok(result.rows_affected() > 0)
+fn memo_key_from_row(
+    (id, account_id, algorithm, fingerprint, created_at, retired_at): MemoKeyRow,
+) -> MemoKey {
+    MemoKey {
+        id: MemoKeyId::from_uuid(id),
+        account_id: AccountId::from_uuid(account_id),
+        algorithm,
+        fingerprint,
+        created_at,
+        retired_at,
     }
+}
 
-    async fn register_webhook_endpoint(
-        &self,
-        url: &str,
-        events: Vec<String>,
-    ) -> Result<payments_types::WebhookEndpoint, RepoError> {
-        use rand::Rng;
-        use rand::distr::Alphanumeric;
+/// `(id, name, tiers, created_at)` as stored in `fee_schedules`.
+type FeeScheduleRow = (Uuid, String, serde_json::Value, DateTime<Utc>);
 
-        let id = Uuid::new_v4();
-        let now = Utc::now();
+/// `(id, status, cutoff, payout_count, totals, created_at, updated_at, mode)`
+/// as stored in `settlement_batches`.
+type SettlementBatchRow = (
+    Uuid,
+    String,
+    DateTime<Utc>,
+    i64,
+    serde_json::Value,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    String,
+);
 
-        // Generate a random secret for HMAC signing
-        let secret: String = rand::rng()
-            .sample_iter(&Alphanumeric)
-            .take(32)
-            .map(char::from)
-            .collect();
-        let secret = format!("whsec_{}", secret);
+fn settlement_batch_from_row(
+    (id, status, cutoff, payout_count, totals, created_at, updated_at, mode): SettlementBatchRow,
+) -> Result<SettlementBatch, RepoError> {
+    Ok(SettlementBatch {
+        id: SettlementBatchId::from_uuid(id),
+        status: status.parse().map_err(RepoError::Database)?,
+        cutoff,
+        payout_count,
+        totals: serde_json::from_value(totals).map_err(|e| RepoError::Database(e.to_string()))?,
+        created_at,
+        updated_at,
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
 
-        let events_json =
-            serde_json::to_value(&events).map_err(|e| RepoError::Database(e.to_string()))?;
+fn fee_schedule_from_row(
+    (id, name, tiers, created_at): FeeScheduleRow,
+) -> Result<FeeSchedule, RepoError> {
+    Ok(FeeSchedule {
+        id: FeeScheduleId::from_uuid(id),
+        name,
+        tiers: serde_json::from_value(tiers).map_err(|e| RepoError::Database(e.to_string()))?,
+        created_at,
+    })
+}
 
-        sqlx::query(
-            r#"
-            INSERT INTO webhook_endpoints (id, url, secret, events, is_active, created_at)
-            VALUES ($1, $2, $3, $4, TRUE, $5)
-            "#,
-        )
-        .bind(id)
-        .bind(url)
-        .bind(&secret)
-        .bind(&events_json)
-        .bind(now)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+/// `(id, url, secret, events, is_active, created_at, timeout_ms,
+/// connect_timeout_ms, signature_algorithm, signing_key, mode)` as stored in
+/// `webhook_endpoints`.
+type WebhookEndpointRow = (
+    Uuid,
+    String,
+    String,
+    serde_json::Value,
+    bool,
+    DateTime<Utc>,
+    i32,
+    i32,
+    String,
+    String,
+    String,
+);
 
-        Ok(payments_types::WebhookEndpoint {
-            id,
-            url: url.to_string(),
-            secret,
-            events,
-            is_active: true,
-            created_at: now,
-        })
-    }
+fn webhook_endpoint_from_row(
+    (
+        id,
+        url,
+        secret,
+        events,
+        is_active,
+        created_at,
+        timeout_ms,
+        connect_timeout_ms,
+        signature_algorithm,
+        signing_key,
+        mode,
+    ): WebhookEndpointRow,
+) -> Result<payments_types::WebhookEndpoint, RepoError> {
+    let millis = |value: i32| u32::try_from(value).map_err(|e| RepoError::Database(e.to_string()));
+    let signature_algorithm: WebhookSignatureAlgorithm = signature_algorithm
+        .parse()
+        .map_err(|e: DomainError| RepoError::Database(e.to_string()))?;
+    Ok(payments_types::WebhookEndpoint {
+        id,
+        url,
+        public_key: crate::security::webhook_public_key(signature_algorithm, &signing_key),
+        secret,
+        events: serde_json::from_value(events).unwrap_or_default(),
+        is_active,
+        created_at,
+        timeouts: WebhookTimeouts {
+            timeout_ms: millis(timeout_ms)?,
+            connect_timeout_ms: millis(connect_timeout_ms)?,
+        },
+        signature_algorithm,
+        signing_key,
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
 
-    async fn list_webhook_endpoints(
-        &self,
-    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
-        let rows: Vec<(
-            Uuid,
-            String,
-            String,
-            serde_json::Value,
-            bool,
-            chrono::DateTime<Utc>,
-        )> = sqlx::query_as(
-            r#"
-            SELECT id, url, secret, events, is_active, created_at
-            FROM webhook_endpoints
-            WHERE is_active = TRUE
-            ORDER BY created_at DESC
-            "#,
-        )
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+/// A due webhook event joined with its endpoint's columns, all `NULL` when
+/// the endpoint has been deleted.
+#[derive(sqlx::FromRow)]
+struct DbDueWebhook {
+    #[sqlx(flatten)]
+    event: crate::types::DbWebhookEvent,
+    endpoint_url: Option<String>,
+    endpoint_secret: Option<String>,
+    endpoint_events: Option<serde_json::Value>,
+    endpoint_is_active: Option<bool>,
+    endpoint_created_at: Option<DateTime<Utc>>,
+    endpoint_timeout_ms: Option<i32>,
+    endpoint_connect_timeout_ms: Option<i32>,
+    endpoint_signature_algorithm: Option<String>,
+    endpoint_signing_key: Option<String>,
+}
 
-        rows.into_iter()
-            .map(|(id, url, secret, events, is_active, created_at)| {
-                let events: Vec<String> = serde_json::from_value(events).unwrap_or_default();
-                Ok(payments_types::WebhookEndpoint {
-                    id,
-                    url,
-                    secret,
-                    events,
-                    is_active,
-                    created_at,
-                })
-            })
-            .collect()
+impl DbDueWebhook {
+    fn into_domain(
+        self,
+    ) -> Result<(WebhookEvent, Option<payments_types::WebhookEndpoint>), RepoError> {
+        let event = self.event.into_domain()?;
+        let endpoint = match (
+            self.endpoint_url,
+            self.endpoint_secret,
+            self.endpoint_events,
+            self.endpoint_is_active,
+            self.endpoint_created_at,
+            self.endpoint_timeout_ms,
+            self.endpoint_connect_timeout_ms,
+            self.endpoint_signature_algorithm,
+            self.endpoint_signing_key,
+        ) {
+            (
+                Some(url),
+                Some(secret),
+                Some(events),
+                Some(is_active),
+                Some(created_at),
+                Some(timeout_ms),
+                Some(connect_timeout_ms),
+                Some(signature_algorithm),
+                Some(signing_key),
+            ) => Some(webhook_endpoint_from_row((
+                event.endpoint_id,
+                url,
+                secret,
+                events,
+                is_active,
+                created_at,
+                timeout_ms,
+                connect_timeout_ms,
+                signature_algorithm,
+                signing_key,
+                event.mode.as_str().to_string(),
+            ))?),
+            _ => None,
+        };
+        Ok((event, endpoint))
     }
+}
 
-    async fn create_webhook_event(
-        &self,
-        endpoint_id: payments_types::WebhookEndpointId,
-        event_type: &str,
-        payload: serde_json::Value,
-    ) -> Result<payments_types::WebhookEvent, RepoError> {
-        let event_id = Uuid::new_v4();
-        let now = Utc::now();
-        let payload_json =
-            serde_json::to_value(payload).map_err(|e| RepoError::Database(e.to_string()))?;
+/// Converts a delivery timeout for an `INTEGER` column.
+fn timeout_column(millis: u32) -> Result<i32, RepoError> {
+    i32::try_from(millis).map_err(|e| RepoError::Database(e.to_string()))
+}
 
-        sqlx::query(
-            r#"
-            INSERT INTO webhook_events (id, endpoint_id, event_type, payload, status, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            "#,
-        )
-        .bind(event_id)
-        .bind(endpoint_id.0)
-        .bind(event_type)
-        .bind(payload_json)
-        .bind("PENDING")
-        .bind(now)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+#[derive(sqlx::FromRow)]
+struct DbApiKey {
+    id: Uuid,
+    name: String,
+    key_hash: String,
+    account_id: Option<Uuid>,
+    owner_id: Option<Uuid>,
+    scopes: String,
+    is_active: bool,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    deactivated_at: Option<DateTime<Utc>>,
+    deactivated_by: Option<Uuid>,
+    rate_limit_per_minute: Option<i32>,
+    mode: String,
+}
 
-        Ok(payments_types::WebhookEvent {
-            id: event_id,
-            endpoint_id: endpoint_id.0,
-            event_type: event_type.to_string(),
-            payload: serde_json::Value::Null,
-            status: payments_types::WebhookStatus::Pending,
-            created_at: now,
-            processed_at: None,
-            attempts: 0,
-            last_error: None,
+impl DbApiKey {
+    fn into_domain(self) -> Result<payments_types::ApiKey, RepoError> {
+        Ok(payments_types::ApiKey {
+            id: payments_types::ApiKeyId::from_uuid(self.id),
+            name: self.name,
+            key_hash: self.key_hash,
+            account_id: self.account_id.map(AccountId::from_uuid),
+            owner_id: self.owner_id.map(OwnerId::from_uuid),
+            scopes: crate::types::parse_scopes(&self.scopes)?,
+            is_active: self.is_active,
+            created_at: self.created_at,
+            last_used_at: self.last_used_at,
+            deactivated_at: self.deactivated_at,
+            deactivated_by: self.deactivated_by.map(payments_types::ApiKeyId::from_uuid),
+            rate_limit_per_minute: self
+                .rate_limit_per_minute
+                .map(u32::try_from)
+                .transpose()
+                .map_err(|e| RepoError::Database(e.to_string()))?,
+            mode: self.mode.parse().map_err(RepoError::Database)?,
         })
     }
 }
 
+/// `(api_key_id, action, actor_id, occurred_at)` as stored in `audit_log`.
+type LoggedEventRow = (
+    i64,
+    String,
+    serde_json::Value,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    String,
+);
+
+type AuditRow = (Uuid, String, Option<Uuid>, DateTime<Utc>);
+
+fn audit_entry_from_row(
+    (api_key_id, action, actor_id, occurred_at): AuditRow,
+) -> Result<ApiKeyAuditEntry, RepoError> {
+    Ok(ApiKeyAuditEntry {
+        api_key_id: ApiKeyId::from_uuid(api_key_id),
+        action: action.parse().map_err(RepoError::Database)?,
+        actor: actor_id.map(ApiKeyId::from_uuid),
+        at: occurred_at,
+    })
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Webhook Extension (Internal)
 // ─────────────────────────────────────────────────────────────────────────────
 impl PostgresRepo {
+    /// Returns up to `limit` pending or failed events whose next attempt is
+    /// due, oldest first.
     pub async fn get_pending_webhooks(&self, limit: i64) -> Result<Vec<WebhookEvent>, RepoError> {
         // We use SKIP LOCKED to allow multiple workers (Postgres feature)
         let rows = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
             r#"
-            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
             FROM webhook_events
-            WHERE status = 'PENDING'
+            WHERE status IN ('PENDING', 'FAILED') AND next_attempt_at <= $1
             ORDER BY created_at ASC
-            LIMIT $1
+            LIMIT $2
             FOR UPDATE SKIP LOCKED
             "#,
         )
+        .bind(self.clock.now())
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
         rows.into_iter().map(|row| row.into_domain()).collect()
     }
 
+    /// Like [`get_pending_webhooks`](Self::get_pending_webhooks), with the
+    /// endpoint each event is for, or `None` when it has been deleted.
+    pub async fn get_due_webhook_deliveries(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<(WebhookEvent, Option<payments_types::WebhookEndpoint>)>, RepoError> {
+        let rows = sqlx::query_as::<_, DbDueWebhook>(
+            r#"
+            SELECT w.id, w.endpoint_id, w.event_type, w.payload, w.status, w.created_at, w.processed_at,
+                   w.attempts, w.last_error, w.next_attempt_at, w.delivery_sequence, w.mode,
+                   e.url AS endpoint_url, e.secret AS endpoint_secret, e.events AS endpoint_events,
+                   e.is_active AS endpoint_is_active, e.created_at AS endpoint_created_at,
+                   e.timeout_ms AS endpoint_timeout_ms,
+                   e.connect_timeout_ms AS endpoint_connect_timeout_ms,
+                   e.signature_algorithm AS endpoint_signature_algorithm,
+                   e.signing_key AS endpoint_signing_key
+            FROM webhook_events w
+            LEFT JOIN webhook_endpoints e ON e.id = w.endpoint_id
+            WHERE w.status IN ('PENDING', 'FAILED') AND w.next_attempt_at <= $1
+            ORDER BY w.created_at ASC
+            LIMIT $2
+            FOR UPDATE OF w SKIP LOCKED
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbDueWebhook::into_domain).collect()
+    }
+
+    /// Records an attempt with a final outcome; the event is not picked up
+    /// again.
     pub async fn update_webhook_status(
         &self,
         id: Uuid,
         status: WebhookStatus,
         last_error: Option<String>,
     ) -> Result<(), RepoError> {
-        let now = Utc::now();
+        let now = self.clock.now();
         let status_str = status.to_string();
 
         sqlx::query(
             r#"
             UPDATE webhook_events
-            SET status = $1, processed_at = $2, last_error = $3, attempts = attempts + 1
+            SET status = $1, processed_at = $2, last_error = $3, attempts = attempts + 1,
+                next_attempt_at = NULL
             WHERE id = $4
             "#,
         )
@@ -765,7 +4479,35 @@ impl PostgresRepo {
         .bind(id)
         .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    /// Records a failed attempt and makes the event due again after `delay`.
+    pub async fn reschedule_webhook(
+        &self,
+        id: Uuid,
+        last_error: String,
+        delay: std::time::Duration,
+    ) -> Result<(), RepoError> {
+        let now = self.clock.now();
+
+        sqlx::query(
+            r#"
+            UPDATE webhook_events
+            SET status = 'FAILED', processed_at = $1, last_error = $2, attempts = attempts + 1,
+                next_attempt_at = $3
+            WHERE id = $4
+            "#,
+        )
+        .bind(now)
+        .bind(last_error)
+        .bind(now + retry_delay(delay)?)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
 
         Ok(())
     }
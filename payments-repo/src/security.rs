@@ -1,6 +1,9 @@
 //! Security utilities for API key hashing and webhook signing.
 
-use sha2::{Digest, Sha256};
+use ed25519_dalek::{Signer, SigningKey};
+use hmac::{Hmac, Mac};
+use payments_types::WebhookSignatureAlgorithm;
+use sha2::{Digest, Sha256, Sha512};
 use subtle::ConstantTimeEq;
 
 /// Hashes an API key using SHA-256.
@@ -9,16 +12,42 @@ pub fn hash_api_key(key: &str) -> String {
     hex::encode(hash)
 }
 
+/// Hashes a request body using SHA-256, to tell apart requests sent with the
+/// same idempotency key.
+pub fn hash_request(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
 /// Verifies an API key against a stored hash using constant-time comparison.
 pub fn verify_api_key(input: &str, stored_hash: &str) -> bool {
     let input_hash = hash_api_key(input);
     input_hash.as_bytes().ct_eq(stored_hash.as_bytes()).into()
 }
 
+/// Generates a random `whsec_`-prefixed secret for signing an endpoint's
+/// webhooks.
+pub fn generate_webhook_secret() -> String {
+    use rand::Rng;
+    use rand::distr::Alphanumeric;
+
+    let secret: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    format!("whsec_{}", secret)
+}
+
+/// Generates the random hex seed of an endpoint's Ed25519 signing key. It
+/// is kept apart from the secret, which receivers are given.
+pub fn generate_webhook_signing_key() -> String {
+    use rand::Rng;
+
+    hex::encode(rand::rng().random::<[u8; 32]>())
+}
+
 /// Signs a webhook payload using HMAC-SHA256.
 pub fn sign_webhook(payload: &[u8], secret: &str) -> String {
-    use hmac::{Hmac, Mac};
-
     type HmacSha256 = Hmac<Sha256>;
 
     let mut mac =
@@ -27,12 +56,81 @@ pub fn sign_webhook(payload: &[u8], secret: &str) -> String {
     hex::encode(mac.finalize().into_bytes())
 }
 
+/// Signs a webhook payload using `algorithm`, returning the signature in
+/// hex. `key` is the endpoint's secret for HMAC algorithms and its signing
+/// key for Ed25519, as given by `WebhookEndpoint::signature_key`.
+pub fn sign_webhook_with(
+    algorithm: WebhookSignatureAlgorithm,
+    payload: &[u8],
+    key: &str,
+) -> String {
+    match algorithm {
+        WebhookSignatureAlgorithm::HmacSha256 => sign_webhook(payload, key),
+        WebhookSignatureAlgorithm::HmacSha512 => {
+            type HmacSha512 = Hmac<Sha512>;
+
+            let mut mac =
+                HmacSha512::new_from_slice(key.as_bytes()).expect("HMAC can take key of any size");
+            mac.update(payload);
+            hex::encode(mac.finalize().into_bytes())
+        }
+        WebhookSignatureAlgorithm::Ed25519 => {
+            hex::encode(webhook_signing_key(key).sign(payload).to_bytes())
+        }
+    }
+}
+
+/// The Ed25519 key an endpoint signs with, seeded by the SHA-256 of its
+/// server-held signing key.
+fn webhook_signing_key(signing_key: &str) -> SigningKey {
+    SigningKey::from_bytes(&Sha256::digest(signing_key.as_bytes()).into())
+}
+
+/// The hex public key receivers verify an endpoint's signatures with, when
+/// it signs with Ed25519; HMAC signatures are checked with the secret.
+pub fn webhook_public_key(
+    algorithm: WebhookSignatureAlgorithm,
+    signing_key: &str,
+) -> Option<String> {
+    (algorithm == WebhookSignatureAlgorithm::Ed25519)
+        .then(|| hex::encode(webhook_signing_key(signing_key).verifying_key().to_bytes()))
+}
+
 /// Verifies a webhook signature using constant-time comparison.
 pub fn verify_webhook_signature(payload: &[u8], signature: &str, secret: &str) -> bool {
     let expected = sign_webhook(payload, secret);
     expected.as_bytes().ct_eq(signature.as_bytes()).into()
 }
 
+/// Signs a webhook delivery together with its sequence number, with the
+/// same `key` as [`sign_webhook_with`].
+///
+/// The signed message is `{sequence}.{payload}`, so a receiver can trust the
+/// `X-Webhook-Delivery-Sequence` header it uses to spot gaps and duplicates.
+pub fn sign_webhook_delivery(
+    algorithm: WebhookSignatureAlgorithm,
+    sequence: i64,
+    payload: &[u8],
+    key: &str,
+) -> String {
+    let mut message = format!("{}.", sequence).into_bytes();
+    message.extend_from_slice(payload);
+    sign_webhook_with(algorithm, &message, key)
+}
+
+/// Verifies a signature made by [`sign_webhook_delivery`]. Every algorithm
+/// signs deterministically, so the signature is made again and compared.
+pub fn verify_webhook_delivery_signature(
+    algorithm: WebhookSignatureAlgorithm,
+    sequence: i64,
+    payload: &[u8],
+    signature: &str,
+    key: &str,
+) -> bool {
+    let expected = sign_webhook_delivery(algorithm, sequence, payload, key);
+    expected.as_bytes().ct_eq(signature.as_bytes()).into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,4 +167,82 @@ mod tests {
         ));
         assert!(!verify_webhook_signature(b"tampered", &signature, secret));
     }
+
+    #[test]
+    fn test_webhook_delivery_signature_covers_sequence() {
+        let payload = br#"{"event":"deposit.success"}"#;
+        let secret = "webhook_secret_123";
+
+        for algorithm in [
+            WebhookSignatureAlgorithm::HmacSha256,
+            WebhookSignatureAlgorithm::HmacSha512,
+            WebhookSignatureAlgorithm::Ed25519,
+        ] {
+            let signature = sign_webhook_delivery(algorithm, 7, payload, secret);
+            assert!(verify_webhook_delivery_signature(
+                algorithm, 7, payload, &signature, secret
+            ));
+            assert!(!verify_webhook_delivery_signature(
+                algorithm, 8, payload, &signature, secret
+            ));
+            assert!(!verify_webhook_delivery_signature(
+                algorithm,
+                7,
+                b"tampered",
+                &signature,
+                secret
+            ));
+            assert_ne!(signature, sign_webhook_with(algorithm, payload, secret));
+        }
+    }
+
+    #[test]
+    fn test_webhook_signature_algorithms() {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+        let payload = br#"{"event":"deposit.success"}"#;
+        let secret = "webhook_secret_123";
+
+        assert_eq!(
+            sign_webhook_with(WebhookSignatureAlgorithm::HmacSha256, payload, secret),
+            sign_webhook(payload, secret)
+        );
+        assert_eq!(
+            sign_webhook_with(WebhookSignatureAlgorithm::HmacSha512, payload, secret).len(),
+            128
+        );
+        assert_eq!(
+            webhook_public_key(WebhookSignatureAlgorithm::HmacSha512, secret),
+            None
+        );
+
+        // Ed25519 signatures check out against the public key alone, and
+        // the secret receivers are given cannot make them
+        let signing_key = generate_webhook_signing_key();
+        assert_eq!(signing_key.len(), 64);
+        let public_key =
+            webhook_public_key(WebhookSignatureAlgorithm::Ed25519, &signing_key).unwrap();
+        let key_bytes: [u8; 32] = hex::decode(&public_key).unwrap().try_into().unwrap();
+        let key = VerifyingKey::from_bytes(&key_bytes).unwrap();
+        let forged = sign_webhook_with(WebhookSignatureAlgorithm::Ed25519, payload, secret);
+        let forged_bytes: [u8; 64] = hex::decode(&forged).unwrap().try_into().unwrap();
+        assert!(
+            key.verify(payload, &Signature::from_bytes(&forged_bytes))
+                .is_err()
+        );
+        let signature =
+            sign_webhook_with(WebhookSignatureAlgorithm::Ed25519, payload, &signing_key);
+        let signature_bytes: [u8; 64] = hex::decode(&signature).unwrap().try_into().unwrap();
+        let signature = Signature::from_bytes(&signature_bytes);
+        assert!(key.verify(payload, &signature).is_ok());
+        assert!(key.verify(b"tampered", &signature).is_err());
+        assert_ne!(
+            webhook_public_key(
+                WebhookSignatureAlgorithm::Ed25519,
+                &generate_webhook_signing_key()
+            )
+            .unwrap(),
+            public_key
+        );
+    }
 }
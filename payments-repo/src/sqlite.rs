@@ -2,18 +2,37 @@
 #![allow(clippy::collapsible_if)]
 
 use async_trait::async_trait;
-use sqlx::SqlitePool;
+use chrono::{DateTime, NaiveDate, Utc};
 use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use payments_types::domain::quote;
 use payments_types::{
-    Account, AccountId, CreateAccountRequest, DepositRequest, DomainError, DynMoney, RepoError,
-    Transaction, TransactionRepository, TransferRequest, WebhookEvent, WebhookStatus,
-    WithdrawRequest,
+    Account, AccountAlias, AccountId, AccountLimits, AccountStatus, Alias, ApiKeyAuditEntry,
+    ApiKeyId, ApiKeyUsageBucket, ApiKeyVolumeBucket, Beneficiary, BeneficiaryId, ChangeRequest,
+    ChangeRequestId, ChangeStatus, Clock, CreateAccountRequest, CurrencyBalance, DailyBalance,
+    DeadLetterFilter, DepositRequest, DomainError, DomainEvent, DynMoney, Export, ExportId,
+    FeeAssignment, FeeSchedule, FeeScheduleId, FeeTier, FloatPosition, FxConversion, Hold, HoldId,
+    HoldStatus, IDEMPOTENCY_RESERVATION_TTL, IdGenerator, IdempotencyRecord, LedgerOperation,
+    LoggedEvent, MemoKey, MemoKeyId, Mode, NewWebhookEndpoint, Owner, OwnerId, Quote, QuoteId,
+    RandomIdGenerator, RateSnapshot, RepoError, RiskAssessment, ScheduledPayment,
+    ScheduledPaymentId, SettlementBatch, SettlementBatchId, SettlementBatchStatus, SpendingRules,
+    Statement, StatementId, StorageCounts, SystemClock, Transaction, TransactionCursor,
+    TransactionFilter, TransactionId, TransactionRepository, TransactionSearch, TransactionType,
+    TransferRequest, UnitOfWork, WebhookDeliveryFilter, WebhookEvent, WebhookSignatureAlgorithm,
+    WebhookStatus, WebhookTimeouts, WithdrawRequest,
 };
 
-use crate::types::{DbAccount, DbAccountBalance, DbAccountCurrency, DbBalance, DbTransaction};
+use crate::error::{db_error, tx_error};
+use crate::types::{
+    DbAccount, DbAccountBalance, DbAccountCurrency, DbTransaction, check_currency, escape_like,
+    memo_json, metadata_json, parse_currency, parse_transaction_type, retry_delay,
+    spending_rule_rows, spending_rules_from_rows,
+};
 
 // ─────────────────────────────────────────────────────────────────────────────
 // SQLite Repository
@@ -22,6 +41,8 @@ use crate::types::{DbAccount, DbAccountBalance, DbAccountCurrency, DbBalance, Db
 /// SQLite repository implementation.
 pub struct SqliteRepo {
     pool: SqlitePool,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
 }
 
 impl SqliteRepo {
@@ -58,7 +79,84 @@ impl SqliteRepo {
             include_str!("../migrations/0004_create_webhook_endpoints_sqlite.sql");
         sqlx::query(ddl_webhook_endpoints).execute(&pool).await?;
 
-        Ok(Self { pool })
+        add_missing_columns(&pool, COLUMN_MIGRATIONS).await?;
+
+        let ddl_account_limits = include_str!("../migrations/0008_account_limits_sqlite.sql");
+        sqlx::query(ddl_account_limits).execute(&pool).await?;
+
+        let ddl_fee_schedules = include_str!("../migrations/0009_fee_schedules_sqlite.sql");
+        sqlx::query(ddl_fee_schedules).execute(&pool).await?;
+
+        let ddl_settlement = include_str!("../migrations/0010_settlement_batches_sqlite.sql");
+        sqlx::query(ddl_settlement).execute(&pool).await?;
+
+        let ddl_statements = include_str!("../migrations/0011_statements_sqlite.sql");
+        sqlx::query(ddl_statements).execute(&pool).await?;
+
+        let ddl_exports = include_str!("../migrations/0012_exports_sqlite.sql");
+        sqlx::query(ddl_exports).execute(&pool).await?;
+
+        let ddl_rates = include_str!("../migrations/0014_rate_snapshots_sqlite.sql");
+        sqlx::query(ddl_rates).execute(&pool).await?;
+
+        let ddl_usage = include_str!("../migrations/0016_api_key_usage_sqlite.sql");
+        sqlx::query(ddl_usage).execute(&pool).await?;
+
+        let ddl_scheduled = include_str!("../migrations/0020_scheduled_payments_sqlite.sql");
+        sqlx::query(ddl_scheduled).execute(&pool).await?;
+
+        let ddl_aliases = include_str!("../migrations/0022_account_aliases_sqlite.sql");
+        sqlx::query(ddl_aliases).execute(&pool).await?;
+
+        let ddl_balances = include_str!("../migrations/0026_daily_balances_sqlite.sql");
+        sqlx::query(ddl_balances).execute(&pool).await?;
+
+        let ddl_changes = include_str!("../migrations/0027_change_requests_sqlite.sql");
+        sqlx::query(ddl_changes).execute(&pool).await?;
+
+        let ddl_idempotency = include_str!("../migrations/0028_idempotency_records_sqlite.sql");
+        sqlx::query(ddl_idempotency).execute(&pool).await?;
+
+        let ddl_inbound_scope = include_str!("../migrations/0032_inbound_scope.sql");
+        sqlx::query(ddl_inbound_scope).execute(&pool).await?;
+
+        let ddl_beneficiaries = include_str!("../migrations/0034_beneficiaries_sqlite.sql");
+        sqlx::query(ddl_beneficiaries).execute(&pool).await?;
+
+        let ddl_event_log = include_str!("../migrations/0036_event_log_sqlite.sql");
+        sqlx::query(ddl_event_log).execute(&pool).await?;
+
+        let ddl_memo_keys = include_str!("../migrations/0039_memo_keys_sqlite.sql");
+        sqlx::query(ddl_memo_keys).execute(&pool).await?;
+
+        let ddl_quotes = include_str!("../migrations/0043_fx_quotes_sqlite.sql");
+        sqlx::query(ddl_quotes).execute(&pool).await?;
+
+        let ddl_idempotency_responses =
+            include_str!("../migrations/0044_idempotency_responses_sqlite.sql");
+        sqlx::query(ddl_idempotency_responses)
+            .execute(&pool)
+            .await?;
+
+        add_missing_columns(&pool, LATE_COLUMN_MIGRATIONS).await?;
+
+        Ok(Self {
+            pool,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+        })
+    }
+
+    /// Replaces the clock used for `created_at`/`processed_at` timestamps.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Replaces the generator used for new row IDs.
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
     }
 
     /// Returns a reference to the connection pool.
@@ -72,88 +170,228 @@ impl SqliteRepo {
         sqlx::query(ddl)
             .execute(&self.pool)
             .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+            .map_err(db_error)?;
 
         let ddl_webhooks = include_str!("../migrations/0002_create_webhook_events.sql");
         sqlx::query(ddl_webhooks)
             .execute(&self.pool)
             .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+            .map_err(db_error)?;
 
         let ddl_api_keys = include_str!("../migrations/0003_create_api_keys.sql");
         sqlx::query(ddl_api_keys)
             .execute(&self.pool)
             .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+            .map_err(db_error)?;
+
+        add_missing_columns(&self.pool, COLUMN_MIGRATIONS)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_account_limits = include_str!("../migrations/0008_account_limits_sqlite.sql");
+        sqlx::query(ddl_account_limits)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_fee_schedules = include_str!("../migrations/0009_fee_schedules_sqlite.sql");
+        sqlx::query(ddl_fee_schedules)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_settlement = include_str!("../migrations/0010_settlement_batches_sqlite.sql");
+        sqlx::query(ddl_settlement)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_statements = include_str!("../migrations/0011_statements_sqlite.sql");
+        sqlx::query(ddl_statements)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_exports = include_str!("../migrations/0012_exports_sqlite.sql");
+        sqlx::query(ddl_exports)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_rates = include_str!("../migrations/0014_rate_snapshots_sqlite.sql");
+        sqlx::query(ddl_rates)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_usage = include_str!("../migrations/0016_api_key_usage_sqlite.sql");
+        sqlx::query(ddl_usage)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_scheduled = include_str!("../migrations/0020_scheduled_payments_sqlite.sql");
+        sqlx::query(ddl_scheduled)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_aliases = include_str!("../migrations/0022_account_aliases_sqlite.sql");
+        sqlx::query(ddl_aliases)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_balances = include_str!("../migrations/0026_daily_balances_sqlite.sql");
+        sqlx::query(ddl_balances)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_changes = include_str!("../migrations/0027_change_requests_sqlite.sql");
+        sqlx::query(ddl_changes)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_idempotency = include_str!("../migrations/0028_idempotency_records_sqlite.sql");
+        sqlx::query(ddl_idempotency)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_inbound_scope = include_str!("../migrations/0032_inbound_scope.sql");
+        sqlx::query(ddl_inbound_scope)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_beneficiaries = include_str!("../migrations/0034_beneficiaries_sqlite.sql");
+        sqlx::query(ddl_beneficiaries)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_event_log = include_str!("../migrations/0036_event_log_sqlite.sql");
+        sqlx::query(ddl_event_log)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_memo_keys = include_str!("../migrations/0039_memo_keys_sqlite.sql");
+        sqlx::query(ddl_memo_keys)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_quotes = include_str!("../migrations/0043_fx_quotes_sqlite.sql");
+        sqlx::query(ddl_quotes)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let ddl_idempotency_responses =
+            include_str!("../migrations/0044_idempotency_responses_sqlite.sql");
+        sqlx::query(ddl_idempotency_responses)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        add_missing_columns(&self.pool, LATE_COLUMN_MIGRATIONS)
+            .await
+            .map_err(db_error)?;
 
         Ok(())
     }
-}
 
-// ─────────────────────────────────────────────────────────────────────────────
-// Repository implementation
-// ─────────────────────────────────────────────────────────────────────────────
+    /// Stores a new key, optionally scoped to one account or to the accounts
+    /// of one owner, and returns it with the raw key.
+    async fn insert_api_key(
+        &self,
+        name: &str,
+        account_id: Option<AccountId>,
+        owner_id: Option<OwnerId>,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        use rand::Rng;
+        use rand::distr::Alphanumeric;
 
-#[async_trait]
-impl TransactionRepository for SqliteRepo {
-    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
-        // Validate first
-        let _ = Account::new(req.name.clone(), req.currency).map_err(RepoError::Domain)?;
+        // Generate a secure random API key
+        let raw_key: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        let prefixed_key = format!("{}{}", mode.key_prefix(), raw_key);
 
-        let id = Uuid::new_v4();
-        let now = chrono::Utc::now();
-        let id_str = id.to_string();
-        let currency_str = req.currency.to_string();
-        let created_at_str = now.to_rfc3339();
+        let key_hash = crate::security::hash_api_key(&prefixed_key);
+        let id = self.ids.new_id();
+        let now = self.clock.now().to_rfc3339();
 
         sqlx::query(
-            r#"INSERT INTO accounts (id, name, balance, currency, created_at) VALUES (?, ?, 0, ?, ?)"#,
+            r#"
+            INSERT INTO api_keys (id, name, key_hash, account_id, owner_id, scopes, is_active, created_at, mode)
+            VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?)
+            "#,
         )
-        .bind(&id_str)
-        .bind(&req.name)
-        .bind(&currency_str)
-        .bind(&created_at_str)
+        .bind(id.to_string())
+        .bind(name)
+        .bind(&key_hash)
+        .bind(account_id.map(|id| id.to_string()))
+        .bind(owner_id.map(|id| id.to_string()))
+        .bind(crate::types::scopes_column(scopes))
+        .bind(&now)
+        .bind(mode.as_str())
         .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        Ok(Account::from_parts(
-            AccountId::from_uuid(id),
-            req.name,
-            DynMoney::zero(req.currency),
-            now,
-        ))
-    }
+        .map_err(db_error)?;
 
-    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
-        let id_str = id.to_string();
+        let created_at = chrono::DateTime::parse_from_rfc3339(&now)
+            .map_err(|e| RepoError::Database(e.to_string()))?
+            .with_timezone(&chrono::Utc);
 
-        let row: Option<DbAccount> = sqlx::query_as(
-            r#"SELECT id, name, balance, currency, created_at FROM accounts WHERE id = ?"#,
-        )
-        .bind(&id_str)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        let api_key = payments_types::ApiKey {
+            id: payments_types::ApiKeyId::from_uuid(id),
+            name: name.to_string(),
+            key_hash,
+            account_id,
+            owner_id,
+            scopes: scopes.to_vec(),
+            is_active: true,
+            created_at,
+            last_used_at: None,
+            deactivated_at: None,
+            deactivated_by: None,
+            rate_limit_per_minute: None,
+            mode,
+        };
 
-        row.map(DbAccount::into_domain).transpose()
+        Ok((api_key, prefixed_key))
     }
 
-    async fn list_accounts(&self) -> Result<Vec<Account>, RepoError> {
-        let rows: Vec<DbAccount> = sqlx::query_as(
-            r#"SELECT id, name, balance, currency, created_at FROM accounts ORDER BY created_at DESC"#,
-        )
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        rows.into_iter().map(DbAccount::into_domain).collect()
+    /// Debits `req.amount` from the source account and credits the
+    /// destination, converted when `conversion` is set.
+    async fn transfer_between(
+        &self,
+        req: TransferRequest,
+        conversion: Option<FxConversion>,
+    ) -> Result<Transaction, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let transaction = self.transfer_in(&mut db_tx, req, conversion).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(transaction)
     }
 
-    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+    /// Deposits within `conn`'s transaction.
+    async fn deposit_in(
+        &self,
+        conn: &mut SqliteConnection,
+        req: DepositRequest,
+    ) -> Result<Transaction, RepoError> {
         // Check idempotency
         if let Some(key) = &req.idempotency_key {
-            if let Some(tx) = self.find_by_idempotency_key(key).await? {
+            if let Some(tx) = find_by_idempotency_key(&mut *conn, key).await? {
                 if tx.amount.amount() != req.amount
                     || tx.amount.currency() != req.currency
                     || tx.source_account_id.as_ref().map(|a| a.as_uuid()).is_some()
@@ -168,32 +406,26 @@ impl TransactionRepository for SqliteRepo {
             }
         }
 
-        let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
+        let money = DynMoney::positive(req.amount, req.currency);
         let account_id_str = req.account_id.to_string();
 
-        let mut db_tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
-
         let result = sqlx::query(r#"UPDATE accounts SET balance = balance + ? WHERE id = ?"#)
             .bind(money.amount())
             .bind(&account_id_str)
-            .execute(&mut *db_tx)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+            .map_err(db_error)?;
 
         if result.rows_affected() == 0 {
             return Err(RepoError::NotFound);
         }
 
-        let tx_id = Uuid::new_v4();
-        let now = chrono::Utc::now().to_rfc3339();
+        let tx_id = self.ids.new_id();
+        let now = self.clock.now();
 
         sqlx::query(
-            r#"INSERT INTO transactions (id, direction, amount, currency, destination_account_id, idempotency_key, reference, created_at)
-               VALUES (?, 'DEPOSIT', ?, ?, ?, ?, ?, ?)"#,
+            r#"INSERT INTO transactions (id, direction, amount, currency, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, metadata, encrypted_memo, created_at)
+               VALUES (?, 'DEPOSIT', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
         .bind(tx_id.to_string())
         .bind(money.amount())
@@ -201,27 +433,38 @@ impl TransactionRepository for SqliteRepo {
         .bind(&account_id_str)
         .bind(&req.idempotency_key)
         .bind(&req.reference)
-        .bind(&now)
-        .execute(&mut *db_tx)
+        .bind(req.counterparty.as_ref().map(|c| &c.name))
+        .bind(req.counterparty.as_ref().and_then(|c| c.external_id.as_ref()))
+        .bind(metadata_json(&req.metadata)?)
+        .bind(memo_json(req.encrypted_memo.as_ref())?)
+        .bind(now.to_rfc3339())
+        .execute(&mut *conn)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        db_tx
-            .commit()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
+        .map_err(db_error)?;
 
-        Ok(Transaction::deposit(
-            req.account_id,
+        Ok(Transaction::from_parts(
+            TransactionId::from_uuid(tx_id),
+            TransactionType::Deposit,
             money,
+            None,
+            Some(req.account_id),
             req.idempotency_key,
             req.reference,
-        ))
+            now,
+        )
+        .with_counterparty(req.counterparty)
+        .with_metadata(req.metadata)
+        .with_encrypted_memo(req.encrypted_memo))
     }
 
-    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+    /// Withdraws within `conn`'s transaction.
+    async fn withdraw_in(
+        &self,
+        conn: &mut SqliteConnection,
+        req: WithdrawRequest,
+    ) -> Result<Transaction, RepoError> {
         if let Some(key) = &req.idempotency_key {
-            if let Some(tx) = self.find_by_idempotency_key(key).await? {
+            if let Some(tx) = find_by_idempotency_key(&mut *conn, key).await? {
                 if tx.amount.amount() != req.amount
                     || tx.amount.currency() != req.currency
                     || tx.source_account_id.as_ref().map(|a| a.as_uuid())
@@ -240,43 +483,23 @@ impl TransactionRepository for SqliteRepo {
             }
         }
 
-        let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
+        let money = DynMoney::positive(req.amount, req.currency);
         let account_id_str = req.account_id.to_string();
 
-        let mut db_tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
-
-        let row: Option<DbBalance> = sqlx::query_as(r#"SELECT balance FROM accounts WHERE id = ?"#)
-            .bind(&account_id_str)
-            .fetch_optional(&mut *db_tx)
-            .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        let account = row.ok_or(RepoError::NotFound)?;
-
-        if account.balance < money.amount() {
-            return Err(RepoError::Domain(DomainError::InsufficientFunds {
-                available: account.balance,
-                requested: money.amount(),
-            }));
-        }
-
-        sqlx::query(r#"UPDATE accounts SET balance = balance - ? WHERE id = ?"#)
-            .bind(money.amount())
-            .bind(&account_id_str)
-            .execute(&mut *db_tx)
-            .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+        claim_available(
+            conn,
+            &account_id_str,
+            "balance = balance - ?",
+            money.amount(),
+        )
+        .await?;
 
-        let tx_id = Uuid::new_v4();
-        let now = chrono::Utc::now().to_rfc3339();
+        let tx_id = self.ids.new_id();
+        let now = self.clock.now();
 
         sqlx::query(
-            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, idempotency_key, reference, created_at)
-               VALUES (?, 'WITHDRAWAL', ?, ?, ?, ?, ?, ?)"#,
+            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, metadata, encrypted_memo, created_at)
+               VALUES (?, 'WITHDRAWAL', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
         .bind(tx_id.to_string())
         .bind(money.amount())
@@ -284,27 +507,42 @@ impl TransactionRepository for SqliteRepo {
         .bind(&account_id_str)
         .bind(&req.idempotency_key)
         .bind(&req.reference)
-        .bind(&now)
-        .execute(&mut *db_tx)
+        .bind(req.counterparty.as_ref().map(|c| &c.name))
+        .bind(req.counterparty.as_ref().and_then(|c| c.external_id.as_ref()))
+        .bind(&req.purpose_code)
+        .bind(metadata_json(&req.metadata)?)
+        .bind(memo_json(req.encrypted_memo.as_ref())?)
+        .bind(now.to_rfc3339())
+        .execute(&mut *conn)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        db_tx
-            .commit()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
+        .map_err(db_error)?;
 
-        Ok(Transaction::withdrawal(
-            req.account_id,
+        Ok(Transaction::from_parts(
+            TransactionId::from_uuid(tx_id),
+            TransactionType::Withdrawal,
             money,
+            Some(req.account_id),
+            None,
             req.idempotency_key,
             req.reference,
-        ))
+            now,
+        )
+        .with_counterparty(req.counterparty)
+        .with_purpose_code(req.purpose_code)
+        .with_metadata(req.metadata)
+        .with_encrypted_memo(req.encrypted_memo))
     }
 
-    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+    /// Transfers within `conn`'s transaction, converting when `conversion`
+    /// is set.
+    async fn transfer_in(
+        &self,
+        conn: &mut SqliteConnection,
+        req: TransferRequest,
+        conversion: Option<FxConversion>,
+    ) -> Result<Transaction, RepoError> {
         if let Some(key) = &req.idempotency_key {
-            if let Some(tx) = self.find_by_idempotency_key(key).await? {
+            if let Some(tx) = find_by_idempotency_key(&mut *conn, key).await? {
                 if tx.amount.amount() != req.amount
                     || tx.amount.currency() != req.currency
                     || tx.source_account_id.as_ref().map(|a| a.as_uuid())
@@ -320,69 +558,51 @@ impl TransactionRepository for SqliteRepo {
             }
         }
 
-        let money = DynMoney::new(req.amount, req.currency).map_err(RepoError::Domain)?;
+        let money = DynMoney::positive(req.amount, req.currency);
         let from_id_str = req.from_account_id.to_string();
         let to_id_str = req.to_account_id.to_string();
 
-        let mut db_tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
-
-        // Check source
-        let source: Option<DbAccountBalance> =
-            sqlx::query_as(r#"SELECT balance, currency FROM accounts WHERE id = ?"#)
-                .bind(&from_id_str)
-                .fetch_optional(&mut *db_tx)
-                .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        let source = source.ok_or(RepoError::NotFound)?;
-
-        if source.balance < money.amount() {
-            return Err(RepoError::Domain(DomainError::InsufficientFunds {
-                available: source.balance,
-                requested: money.amount(),
-            }));
-        }
+        // Debit source first, so the transaction holds the write lock from
+        // its first statement on. A currency failure below rolls it back.
+        let source =
+            claim_available(conn, &from_id_str, "balance = balance - ?", money.amount()).await?;
 
         // Check destination
         let dest: Option<DbAccountCurrency> =
             sqlx::query_as(r#"SELECT currency FROM accounts WHERE id = ?"#)
                 .bind(&to_id_str)
-                .fetch_optional(&mut *db_tx)
+                .fetch_optional(&mut *conn)
                 .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
+                .map_err(db_error)?;
 
         let dest = dest.ok_or(RepoError::NotFound)?;
 
-        if source.currency != dest.currency {
-            return Err(RepoError::Domain(DomainError::CrossCurrencyTransfer));
-        }
-
-        // Debit source
-        sqlx::query(r#"UPDATE accounts SET balance = balance - ? WHERE id = ?"#)
-            .bind(money.amount())
-            .bind(&from_id_str)
-            .execute(&mut *db_tx)
-            .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+        let credit = match &conversion {
+            None if source.currency != dest.currency => {
+                return Err(RepoError::Domain(DomainError::CrossCurrencyTransfer));
+            }
+            None => money,
+            Some(conversion) => {
+                check_currency(&source.currency, money.currency())?;
+                check_currency(&dest.currency, conversion.converted_amount.currency())?;
+                conversion.converted_amount
+            }
+        };
 
         // Credit destination
         sqlx::query(r#"UPDATE accounts SET balance = balance + ? WHERE id = ?"#)
-            .bind(money.amount())
+            .bind(credit.amount())
             .bind(&to_id_str)
-            .execute(&mut *db_tx)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+            .map_err(db_error)?;
 
-        let tx_id = Uuid::new_v4();
-        let now = chrono::Utc::now().to_rfc3339();
+        let tx_id = self.ids.new_id();
+        let now = self.clock.now();
 
         sqlx::query(
-            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, created_at)
-               VALUES (?, 'TRANSFER', ?, ?, ?, ?, ?, ?, ?)"#,
+            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, purpose_code, fx_rate, converted_amount, converted_currency, metadata, encrypted_memo, created_at)
+               VALUES (?, 'TRANSFER', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
         .bind(tx_id.to_string())
         .bind(money.amount())
@@ -391,342 +611,3851 @@ impl TransactionRepository for SqliteRepo {
         .bind(&to_id_str)
         .bind(&req.idempotency_key)
         .bind(&req.reference)
-        .bind(&now)
-        .execute(&mut *db_tx)
+        .bind(&req.purpose_code)
+        .bind(conversion.map(|c| c.rate))
+        .bind(conversion.map(|c| c.converted_amount.amount()))
+        .bind(conversion.map(|c| c.converted_amount.currency().to_string()))
+        .bind(metadata_json(&req.metadata)?)
+        .bind(memo_json(req.encrypted_memo.as_ref())?)
+        .bind(now.to_rfc3339())
+        .execute(&mut *conn)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        db_tx
-            .commit()
-            .await
-            .map_err(|e| RepoError::Transaction(e.to_string()))?;
+        .map_err(db_error)?;
 
-        Ok(Transaction::transfer(
-            req.from_account_id,
-            req.to_account_id,
+        Ok(Transaction::from_parts(
+            TransactionId::from_uuid(tx_id),
+            TransactionType::Transfer,
             money,
+            Some(req.from_account_id),
+            Some(req.to_account_id),
             req.idempotency_key,
             req.reference,
-        ))
-    }
-
-    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
-        let row: Option<DbTransaction> = sqlx::query_as(
-            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, created_at
-               FROM transactions WHERE idempotency_key = ?"#,
+            now,
         )
-        .bind(key)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        row.map(DbTransaction::into_domain).transpose()
+        .with_purpose_code(req.purpose_code)
+        .with_metadata(req.metadata)
+        .with_encrypted_memo(req.encrypted_memo)
+        .with_conversion(conversion))
     }
 
-    async fn get_transaction(
+    /// Captures hold `id` within `conn`'s transaction.
+    async fn capture_hold_in(
         &self,
-        id: payments_types::TransactionId,
-    ) -> Result<Option<Transaction>, RepoError> {
-        let id_str = id.to_string();
+        conn: &mut SqliteConnection,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        let row: Option<HoldRow> =
+            sqlx::query_as(r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at FROM holds WHERE id = ?"#)
+                .bind(id.to_string())
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(db_error)?;
 
-        let row: Option<DbTransaction> = sqlx::query_as(
-            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, created_at
-               FROM transactions WHERE id = ?"#,
+        let mut hold = hold_from_row(row.ok_or(RepoError::NotFound)?)?;
+        hold.check_capture(amount, now).map_err(RepoError::Domain)?;
+        let money = DynMoney::new(amount, hold.currency).map_err(RepoError::Domain)?;
+        let tx_id = self.ids.new_id();
+        hold.capture(amount, TransactionId::from_uuid(tx_id), now);
+
+        let account_id_str = hold.account_id.to_string();
+        sqlx::query(r#"UPDATE accounts SET balance = balance - ?, held = held - ? WHERE id = ?"#)
+            .bind(money.amount())
+            .bind(hold.amount)
+            .bind(&account_id_str)
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+
+        sqlx::query(
+            r#"INSERT INTO transactions (id, direction, amount, currency, source_account_id, reference, purpose_code, created_at)
+               VALUES (?, 'WITHDRAWAL', ?, ?, ?, ?, ?, ?)"#,
         )
-        .bind(&id_str)
-        .fetch_optional(&self.pool)
+        .bind(tx_id.to_string())
+        .bind(money.amount())
+        .bind(money.currency().to_string())
+        .bind(&account_id_str)
+        .bind(&hold.reference)
+        .bind(&hold.purpose_code)
+        .bind(now.to_rfc3339())
+        .execute(&mut *conn)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
+
+        // Only one writer moves the hold out of AUTHORIZED; a loser's
+        // withdrawal is rolled back with the rest of the transaction
+        let claimed = sqlx::query(
+            r#"UPDATE holds SET status = ?, captured_amount = ?, transaction_id = ?, resolved_at = ?
+               WHERE id = ? AND status = 'AUTHORIZED'"#,
+        )
+        .bind(hold.status.as_ref())
+        .bind(hold.captured_amount)
+        .bind(tx_id.to_string())
+        .bind(hold.resolved_at.map(|at| at.to_rfc3339()))
+        .bind(hold.id.to_string())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+        if claimed.rows_affected() == 0 {
+            return Err(RepoError::Domain(DomainError::ValidationError(
+                "Hold is no longer AUTHORIZED".into(),
+            )));
+        }
+
+        let transaction = Transaction::from_parts(
+            TransactionId::from_uuid(tx_id),
+            TransactionType::Withdrawal,
+            money,
+            Some(hold.account_id),
+            None,
+            None,
+            hold.reference.clone(),
+            now,
+        )
+        .with_purpose_code(hold.purpose_code.clone());
+        Ok((hold, transaction))
+    }
+
+    /// Releases hold `id` within `conn`'s transaction.
+    async fn release_hold_in(
+        &self,
+        conn: &mut SqliteConnection,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        let row: Option<HoldRow> =
+            sqlx::query_as(r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at FROM holds WHERE id = ?"#)
+                .bind(id.to_string())
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(db_error)?;
+
+        let mut hold = hold_from_row(row.ok_or(RepoError::NotFound)?)?;
+        hold.check_open().map_err(RepoError::Domain)?;
+        hold.release(status, now);
+
+        let claimed = sqlx::query(
+            r#"UPDATE holds SET status = ?, resolved_at = ? WHERE id = ? AND status = 'AUTHORIZED'"#,
+        )
+        .bind(hold.status.as_ref())
+        .bind(hold.resolved_at.map(|at| at.to_rfc3339()))
+        .bind(hold.id.to_string())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+        if claimed.rows_affected() == 0 {
+            return Err(RepoError::Domain(DomainError::ValidationError(
+                "Hold is no longer AUTHORIZED".into(),
+            )));
+        }
+
+        sqlx::query(r#"UPDATE accounts SET held = held - ? WHERE id = ?"#)
+            .bind(hold.amount)
+            .bind(hold.account_id.to_string())
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+
+        Ok(hold)
+    }
+
+    /// Queues a webhook event within `conn`'s transaction.
+    async fn create_webhook_event_in(
+        &self,
+        conn: &mut SqliteConnection,
+        endpoint_id: payments_types::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<payments_types::WebhookEvent, RepoError> {
+        let event_id = self.ids.new_id();
+        let now_dt = self.clock.now();
+        let now = now_dt.to_rfc3339();
+        let payload_json =
+            serde_json::to_string(&payload).map_err(|e| RepoError::Database(e.to_string()))?;
+
+        let delivery_sequence: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO webhook_delivery_sequences (endpoint_id, last_sequence)
+            VALUES (?, 1)
+            ON CONFLICT (endpoint_id) DO UPDATE
+                SET last_sequence = webhook_delivery_sequences.last_sequence + 1
+            RETURNING last_sequence
+            "#,
+        )
+        .bind(endpoint_id.0.to_string())
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        let mode: String = sqlx::query_scalar(
+            r#"
+            INSERT INTO webhook_events
+                (id, endpoint_id, event_type, payload, status, created_at, next_attempt_at, delivery_sequence, mode)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
+                    COALESCE((SELECT mode FROM webhook_endpoints WHERE id = ?2), 'live'))
+            RETURNING mode
+            "#,
+        )
+        .bind(event_id.to_string())
+        .bind(endpoint_id.0.to_string())
+        .bind(event_type)
+        .bind(payload_json)
+        .bind("PENDING")
+        .bind(&now)
+        .bind(sortable_timestamp(now_dt))
+        .bind(delivery_sequence)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        Ok(payments_types::WebhookEvent {
+            id: event_id,
+            endpoint_id: endpoint_id.0,
+            event_type: event_type.to_string(),
+            payload: serde_json::Value::Null,
+            status: payments_types::WebhookStatus::Pending,
+            created_at: now_dt,
+            processed_at: None,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: Some(now_dt),
+            delivery_sequence,
+            mode: mode.parse().map_err(RepoError::Database)?,
+        })
+    }
+
+    /// Appends `event`, which happened in `mode`, to the event log within
+    /// `conn`'s transaction.
+    async fn append_event_in(
+        &self,
+        conn: &mut SqliteConnection,
+        event: &DomainEvent,
+        mode: Mode,
+    ) -> Result<(), RepoError> {
+        let payload = serde_json::to_string(&event.payload())
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        let sequence: i64 = sqlx::query_scalar(
+            r#"UPDATE event_log_sequence SET last_sequence = last_sequence + 1
+               WHERE id = 1 RETURNING last_sequence"#,
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        sqlx::query(
+            r#"INSERT INTO event_log (sequence, event_type, payload, occurred_at, recorded_at, mode)
+               VALUES (?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(sequence)
+        .bind(event.event_type())
+        .bind(payload)
+        .bind(sortable_timestamp(event.occurred_at()))
+        .bind(sortable_timestamp(self.clock.now()))
+        .bind(mode.as_str())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+/// Sums the amounts sent out of `account_id` after `since`, on `conn` so a
+/// unit of work counts the debits it has made so far.
+///
+/// RFC 3339 text does not order reliably, so timestamps are compared as
+/// Julian days.
+async fn debited_since(
+    conn: &mut SqliteConnection,
+    account_id: AccountId,
+    since: DateTime<Utc>,
+) -> Result<i64, RepoError> {
+    // A captured hold is counted once, by the withdrawal it became
+    let (debited,): (i64,) = sqlx::query_as(
+        r#"SELECT
+               (SELECT COALESCE(SUM(amount), 0) FROM transactions
+                WHERE source_account_id = ?1 AND julianday(created_at) > julianday(?2))
+             + (SELECT COALESCE(SUM(amount), 0) FROM holds
+                WHERE account_id = ?1 AND status = ?3
+                  AND julianday(created_at) > julianday(?2))"#,
+    )
+    .bind(account_id.to_string())
+    .bind(since.to_rfc3339())
+    .bind(HoldStatus::Authorized.as_ref())
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    Ok(debited)
+}
+
+/// Counts the transactions `account_id` sent or received after `since`, on
+/// `conn` so a unit of work counts the ones it has made so far.
+async fn transactions_since(
+    conn: &mut SqliteConnection,
+    account_id: AccountId,
+    since: DateTime<Utc>,
+) -> Result<i64, RepoError> {
+    let account_id = account_id.to_string();
+    let (count,): (i64,) = sqlx::query_as(
+        r#"SELECT COUNT(*) FROM transactions
+           WHERE (source_account_id = ? OR destination_account_id = ?)
+             AND julianday(created_at) > julianday(?)"#,
+    )
+    .bind(&account_id)
+    .bind(&account_id)
+    .bind(since.to_rfc3339())
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    Ok(count)
+}
+
+/// Stores an authorized hold within `conn`'s transaction, reserving its
+/// amount on the account.
+async fn create_hold(conn: &mut SqliteConnection, hold: &Hold) -> Result<(), RepoError> {
+    let money = DynMoney::new(hold.amount, hold.currency).map_err(RepoError::Domain)?;
+    let account_id_str = hold.account_id.to_string();
+
+    let account = claim_available(conn, &account_id_str, "held = held + ?", money.amount()).await?;
+    check_currency(&account.currency, money.currency())?;
+
+    sqlx::query(
+        r#"INSERT INTO holds (id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(hold.id.to_string())
+    .bind(&account_id_str)
+    .bind(hold.amount)
+    .bind(hold.currency.to_string())
+    .bind(hold.reference.as_deref())
+    .bind(hold.purpose_code.as_deref())
+    .bind(hold.status.as_ref())
+    .bind(sortable_timestamp(hold.expires_at))
+    .bind(hold.captured_amount)
+    .bind(hold.transaction_id.map(|id| id.to_string()))
+    .bind(hold.created_at.to_rfc3339())
+    .bind(hold.resolved_at.map(|at| at.to_rfc3339()))
+    .execute(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Looks up the transaction made under an idempotency key, on `conn` so a
+/// unit of work sees the transactions it has made so far.
+async fn find_by_idempotency_key(
+    conn: &mut SqliteConnection,
+    key: &str,
+) -> Result<Option<Transaction>, RepoError> {
+    let row: Option<DbTransaction> = sqlx::query_as(
+        r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+           FROM transactions WHERE idempotency_key = ?"#,
+    )
+    .bind(key)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(db_error)?;
+
+    row.map(DbTransaction::into_domain).transpose()
+}
+
+/// Records a risk assessment within `conn`'s transaction.
+async fn record_transaction_risk(
+    conn: &mut SqliteConnection,
+    id: TransactionId,
+    risk: &RiskAssessment,
+) -> Result<(), RepoError> {
+    let reasons =
+        serde_json::to_string(&risk.reasons).map_err(|e| RepoError::Database(e.to_string()))?;
+    sqlx::query(
+        r#"UPDATE transactions SET risk_decision = ?, risk_reasons = ?
+           WHERE id = ? AND risk_decision IS NULL"#,
+    )
+    .bind(risk.decision.as_ref())
+    .bind(reasons)
+    .bind(id.to_string())
+    .execute(conn)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// A [`UnitOfWork`] over one SQLite transaction, rolled back if dropped
+/// uncommitted.
+struct SqliteUnitOfWork<'a> {
+    repo: &'a SqliteRepo,
+    tx: sqlx::Transaction<'static, sqlx::Sqlite>,
+}
+
+#[async_trait]
+impl UnitOfWork for SqliteUnitOfWork<'_> {
+    async fn apply(&mut self, operation: LedgerOperation) -> Result<Transaction, RepoError> {
+        match operation {
+            LedgerOperation::Deposit(req) => self.repo.deposit_in(&mut self.tx, req).await,
+            LedgerOperation::Withdraw(req) => self.repo.withdraw_in(&mut self.tx, req).await,
+            LedgerOperation::Transfer(req, conversion) => {
+                self.repo.transfer_in(&mut self.tx, req, conversion).await
+            }
+        }
+    }
+
+    async fn lock_accounts(&mut self, _account_ids: &[AccountId]) -> Result<(), RepoError> {
+        // SQLite locks the whole database for a writing transaction
+        Ok(())
+    }
+
+    async fn debited_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        debited_since(&mut self.tx, account_id, since).await
+    }
+
+    async fn transactions_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        transactions_since(&mut self.tx, account_id, since).await
+    }
+
+    async fn create_hold(&mut self, hold: &Hold) -> Result<(), RepoError> {
+        create_hold(&mut self.tx, hold).await
+    }
+
+    async fn record_transaction_risk(
+        &mut self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        record_transaction_risk(&mut self.tx, id, risk).await
+    }
+
+    async fn create_webhook_event(
+        &mut self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<WebhookEvent, RepoError> {
+        self.repo
+            .create_webhook_event_in(&mut self.tx, endpoint_id, event_type, payload)
+            .await
+    }
+
+    async fn append_event(&mut self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        self.repo.append_event_in(&mut self.tx, event, mode).await
+    }
+
+    async fn capture_hold(
+        &mut self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        self.repo
+            .capture_hold_in(&mut self.tx, id, amount, now)
+            .await
+    }
+
+    async fn release_hold(
+        &mut self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        self.repo
+            .release_hold_in(&mut self.tx, id, status, now)
+            .await
+    }
+
+    async fn spend_quote(
+        &mut self,
+        id: QuoteId,
+        transaction_id: TransactionId,
+    ) -> Result<(), RepoError> {
+        let spent = sqlx::query(
+            r#"UPDATE fx_quotes SET transaction_id = ?1
+               WHERE id = ?2 AND (transaction_id IS NULL OR transaction_id = ?1)"#,
+        )
+        .bind(transaction_id.to_string())
+        .bind(id.to_string())
+        .execute(&mut *self.tx)
+        .await
+        .map_err(db_error)?;
+        if spent.rows_affected() == 0 {
+            return Err(RepoError::Domain(quote::already_used(id)));
+        }
+        Ok(())
+    }
+
+    async fn close_merged_account(
+        &mut self,
+        id: AccountId,
+        into: AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        let row: Option<DbAccount> = sqlx::query_as(
+            r#"UPDATE accounts SET status = ?, status_changed_at = ?, merged_into = ?
+               WHERE id = ? AND status <> ? AND balance = 0 AND held = 0
+               RETURNING id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode"#,
+        )
+        .bind(AccountStatus::Closed.as_ref())
+        .bind(sortable_timestamp(now))
+        .bind(into.to_string())
+        .bind(id.to_string())
+        .bind(AccountStatus::Closed.as_ref())
+        .fetch_optional(&mut *self.tx)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbAccount::into_domain).transpose()
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepoError> {
+        self.tx.commit().await.map_err(tx_error)
+    }
+}
+
+/// Applies `set` (e.g. `balance = balance - ?`, bound to `amount`) to the
+/// account only if its available balance still covers `amount`, returning
+/// the updated row.
+///
+/// The check and the write are one statement, so two concurrent debits can
+/// never both spend the same money. When nothing matched, the account is read
+/// back to say why: it is missing, short of funds, or was changed by another
+/// writer in between, which is a retryable `RepoError::Conflict`.
+async fn claim_available(
+    conn: &mut SqliteConnection,
+    account_id: &str,
+    set: &str,
+    amount: i64,
+) -> Result<DbAccountBalance, RepoError> {
+    let claimed: Option<DbAccountBalance> = sqlx::query_as(&format!(
+        "UPDATE accounts SET {} WHERE id = ? AND balance - held + overdraft_limit >= ? RETURNING balance, held, currency, overdraft_limit",
+        set
+    ))
+    .bind(amount)
+    .bind(account_id)
+    .bind(amount)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    if let Some(account) = claimed {
+        return Ok(account);
+    }
+
+    let account: DbAccountBalance = sqlx::query_as(
+        r#"SELECT balance, held, currency, overdraft_limit FROM accounts WHERE id = ?"#,
+    )
+    .bind(account_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(db_error)?
+    .ok_or(RepoError::NotFound)?;
+    account.check_available(amount)?;
+    Err(RepoError::Conflict(format!(
+        "Balance of account {} changed concurrently",
+        account_id
+    )))
+}
+
+/// Column-adding migrations, each keyed by its table and the first column it
+/// adds.
+const COLUMN_MIGRATIONS: &[(&str, &str, &str)] = &[
+    (
+        "transactions",
+        "counterparty_name",
+        include_str!("../migrations/0005_add_counterparty_sqlite.sql"),
+    ),
+    (
+        "transactions",
+        "purpose_code",
+        include_str!("../migrations/0007_spending_controls_sqlite.sql"),
+    ),
+    (
+        "webhook_events",
+        "next_attempt_at",
+        include_str!("../migrations/0015_webhook_retries_sqlite.sql"),
+    ),
+    (
+        "webhook_endpoints",
+        "timeout_ms",
+        include_str!("../migrations/0017_webhook_timeouts_sqlite.sql"),
+    ),
+    (
+        "transactions",
+        "fx_rate",
+        include_str!("../migrations/0018_fx_transfers_sqlite.sql"),
+    ),
+    (
+        "webhook_events",
+        "delivery_sequence",
+        include_str!("../migrations/0019_webhook_sequences_sqlite.sql"),
+    ),
+    (
+        "accounts",
+        "held",
+        include_str!("../migrations/0021_holds_sqlite.sql"),
+    ),
+    (
+        "api_keys",
+        "scopes",
+        include_str!("../migrations/0023_api_key_scopes_sqlite.sql"),
+    ),
+    (
+        "accounts",
+        "status",
+        include_str!("../migrations/0024_account_status_sqlite.sql"),
+    ),
+    (
+        "transactions",
+        "risk_decision",
+        include_str!("../migrations/0025_transaction_risk_sqlite.sql"),
+    ),
+    (
+        "accounts",
+        "merged_into",
+        include_str!("../migrations/0029_account_merges_sqlite.sql"),
+    ),
+    (
+        "accounts",
+        "overdraft_limit",
+        include_str!("../migrations/0030_overdraft_limits_sqlite.sql"),
+    ),
+    (
+        "api_keys",
+        "deactivated_at",
+        include_str!("../migrations/0033_api_key_audit_sqlite.sql"),
+    ),
+    (
+        "api_keys",
+        "rate_limit_per_minute",
+        include_str!("../migrations/0035_api_key_rate_limits_sqlite.sql"),
+    ),
+    (
+        "webhook_endpoints",
+        "signature_algorithm",
+        include_str!("../migrations/0037_webhook_signature_algorithms_sqlite.sql"),
+    ),
+    (
+        "transactions",
+        "metadata",
+        include_str!("../migrations/0038_transaction_metadata_sqlite.sql"),
+    ),
+    (
+        "transactions",
+        "encrypted_memo",
+        include_str!("../migrations/0040_encrypted_memos_sqlite.sql"),
+    ),
+    (
+        "accounts",
+        "owner_id",
+        include_str!("../migrations/0042_account_owners_sqlite.sql"),
+    ),
+    (
+        "accounts",
+        "mode",
+        include_str!("../migrations/0045_modes_sqlite.sql"),
+    ),
+    (
+        "webhook_endpoints",
+        "signing_key",
+        include_str!("../migrations/0046_webhook_signing_keys_sqlite.sql"),
+    ),
+];
+
+/// Column-adding migrations for tables created after [`COLUMN_MIGRATIONS`]
+/// are applied, so they run once every table exists.
+const LATE_COLUMN_MIGRATIONS: &[(&str, &str, &str)] = &[
+    (
+        "event_log",
+        "mode",
+        include_str!("../migrations/0047_event_modes_sqlite.sql"),
+    ),
+    (
+        "settlement_batches",
+        "mode",
+        include_str!("../migrations/0048_report_modes_sqlite.sql"),
+    ),
+    (
+        "fx_quotes",
+        "mode",
+        include_str!("../migrations/0049_quote_modes_sqlite.sql"),
+    ),
+];
+
+/// Applies each of `migrations` unless its column already exists.
+async fn add_missing_columns(
+    pool: &SqlitePool,
+    migrations: &[(&str, &str, &str)],
+) -> Result<(), sqlx::Error> {
+    for (table, column, ddl) in migrations {
+        let applied: Option<(String,)> =
+            sqlx::query_as(r#"SELECT name FROM pragma_table_info(?) WHERE name = ?"#)
+                .bind(table)
+                .bind(column)
+                .fetch_optional(pool)
+                .await?;
+
+        if applied.is_none() {
+            sqlx::query(ddl).execute(pool).await?;
+        }
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Repository implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[async_trait]
+impl TransactionRepository for SqliteRepo {
+    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
+        // Validate first
+        let _ = Account::new(req.name.clone(), req.currency).map_err(RepoError::Domain)?;
+
+        let mode = req.mode.unwrap_or_default();
+        let id = self.ids.new_id();
+        let now = self.clock.now();
+        let id_str = id.to_string();
+        let currency_str = req.currency.to_string();
+        let created_at_str = now.to_rfc3339();
+
+        sqlx::query(
+            r#"INSERT INTO accounts (id, name, balance, currency, created_at, overdraft_limit, owner_id, mode) VALUES (?, ?, 0, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(&id_str)
+        .bind(&req.name)
+        .bind(&currency_str)
+        .bind(&created_at_str)
+        .bind(req.overdraft_limit)
+        .bind(req.owner_id.to_string())
+        .bind(mode.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(Account::from_parts(
+            AccountId::from_uuid(id),
+            req.name,
+            DynMoney::zero(req.currency),
+            now,
+        )
+        .with_overdraft_limit(req.overdraft_limit)
+        .with_owner(req.owner_id)
+        .with_mode(mode))
+    }
+
+    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
+        let id_str = id.to_string();
+
+        let row: Option<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts WHERE id = ?"#,
+        )
+        .bind(&id_str)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbAccount::into_domain).transpose()
+    }
+
+    async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError> {
+        let rows: Vec<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts
+               WHERE ? IS NULL OR mode = ? ORDER BY created_at DESC"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbAccount::into_domain).collect()
+    }
+
+    async fn search_accounts(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: Mode,
+    ) -> Result<Vec<Account>, RepoError> {
+        // LIKE is ASCII case-insensitive in SQLite; GLOB keeps the ID prefix
+        // match case-sensitive so it can use the primary key index.
+        let rows: Vec<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts
+               WHERE (name LIKE ? ESCAPE '\' OR id GLOB ?) AND mode = ?
+               ORDER BY name COLLATE NOCASE, id
+               LIMIT ?"#,
+        )
+        .bind(format!("%{}%", escape_like(query.trim())))
+        .bind(AccountId::search_prefix(query).map(|prefix| format!("{}*", prefix)))
+        .bind(mode.as_str())
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbAccount::into_domain).collect()
+    }
+
+    async fn get_spending_rules(&self, account_id: AccountId) -> Result<SpendingRules, RepoError> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"SELECT purpose_code, effect FROM account_spending_rules
+               WHERE account_id = ? ORDER BY purpose_code"#,
+        )
+        .bind(account_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(spending_rules_from_rows(rows))
+    }
+
+    async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<(), RepoError> {
+        let account_id_str = account_id.to_string();
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+
+        sqlx::query("DELETE FROM account_spending_rules WHERE account_id = ?")
+            .bind(&account_id_str)
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
+
+        for (code, effect) in spending_rule_rows(rules) {
+            sqlx::query(
+                "INSERT INTO account_spending_rules (account_id, purpose_code, effect) VALUES (?, ?, ?)",
+            )
+            .bind(&account_id_str)
+            .bind(code)
+            .bind(effect)
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
+        }
+
+        db_tx.commit().await.map_err(tx_error)
+    }
+
+    async fn get_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, RepoError> {
+        let row: Option<(Option<i64>, Option<i64>, Option<i64>)> = sqlx::query_as(
+            r#"SELECT max_transaction_amount, max_balance, daily_debit_limit
+               FROM account_limits WHERE account_id = ?"#,
+        )
+        .bind(account_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(row
+            .map(
+                |(max_transaction_amount, max_balance, daily_debit_limit)| AccountLimits {
+                    max_transaction_amount,
+                    max_balance,
+                    daily_debit_limit,
+                },
+            )
+            .unwrap_or_default())
+    }
+
+    async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<(), RepoError> {
+        if limits.is_empty() {
+            sqlx::query("DELETE FROM account_limits WHERE account_id = ?")
+                .bind(account_id.to_string())
+                .execute(&self.pool)
+                .await
+                .map_err(db_error)?;
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"INSERT INTO account_limits
+                   (account_id, max_transaction_amount, max_balance, daily_debit_limit)
+               VALUES (?, ?, ?, ?)
+               ON CONFLICT (account_id) DO UPDATE SET
+                   max_transaction_amount = excluded.max_transaction_amount,
+                   max_balance = excluded.max_balance,
+                   daily_debit_limit = excluded.daily_debit_limit"#,
+        )
+        .bind(account_id.to_string())
+        .bind(limits.max_transaction_amount)
+        .bind(limits.max_balance)
+        .bind(limits.daily_debit_limit)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn list_idle_accounts(
+        &self,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Account>, RepoError> {
+        let rows: Vec<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts
+               WHERE status = 'ACTIVE'"#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+        let activity: Vec<(Option<String>, Option<String>, String)> = sqlx::query_as(
+            r#"SELECT source_account_id, destination_account_id, created_at FROM transactions"#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        // Compare parsed timestamps: RFC 3339 text does not order reliably.
+        let mut last_active: HashMap<String, DateTime<Utc>> = HashMap::new();
+        for (source, destination, created_at) in activity {
+            let at = parse_timestamp(&created_at)?;
+            for id in source.into_iter().chain(destination) {
+                let latest = last_active.entry(id).or_insert(at);
+                *latest = (*latest).max(at);
+            }
+        }
+        let mut idle = rows
+            .into_iter()
+            .map(DbAccount::into_domain)
+            .collect::<Result<Vec<_>, _>>()?;
+        idle.retain(|account| {
+            account.created_at < idle_since
+                && last_active
+                    .get(&account.id.to_string())
+                    .is_none_or(|at| *at < idle_since)
+        });
+        idle.sort_by_key(|account| account.created_at);
+        idle.truncate(limit);
+        Ok(idle)
+    }
+
+    async fn update_account_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        let updated = sqlx::query(
+            r#"UPDATE accounts SET status = ?, status_changed_at = ? WHERE id = ? AND status = ?"#,
+        )
+        .bind(to.as_ref())
+        .bind(sortable_timestamp(now))
+        .bind(id.to_string())
+        .bind(from.as_ref())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get_account(id).await
+    }
+
+    async fn set_overdraft_limit(
+        &self,
+        id: AccountId,
+        limit: i64,
+    ) -> Result<Option<Account>, RepoError> {
+        let updated = sqlx::query(r#"UPDATE accounts SET overdraft_limit = ? WHERE id = ?"#)
+            .bind(limit)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get_account(id).await
+    }
+
+    async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError> {
+        sqlx::query(
+            r#"INSERT INTO account_aliases (alias, account_id, created_at) VALUES (?, ?, ?)
+               ON CONFLICT (alias) DO NOTHING"#,
+        )
+        .bind(alias.alias.as_str())
+        .bind(alias.account_id.to_string())
+        .bind(alias.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let stored = self
+            .get_account_alias(&alias.alias)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+        if stored.account_id != alias.account_id {
+            return Err(RepoError::Domain(DomainError::ValidationError(format!(
+                "Alias {} is already taken",
+                alias.alias
+            ))));
+        }
+        Ok(stored)
+    }
+
+    async fn get_account_alias(&self, alias: &Alias) -> Result<Option<AccountAlias>, RepoError> {
+        let row: Option<AccountAliasRow> = sqlx::query_as(
+            "SELECT alias, account_id, created_at FROM account_aliases WHERE alias = ?",
+        )
+        .bind(alias.as_str())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(account_alias_from_row).transpose()
+    }
+
+    async fn list_account_aliases(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, RepoError> {
+        let rows: Vec<AccountAliasRow> = sqlx::query_as(
+            "SELECT alias, account_id, created_at FROM account_aliases WHERE account_id = ?",
+        )
+        .bind(account_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        // Sort parsed timestamps: RFC 3339 text does not order reliably.
+        let mut aliases = rows
+            .into_iter()
+            .map(account_alias_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        aliases.sort_by_key(|a| a.created_at);
+        Ok(aliases)
+    }
+
+    async fn remove_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<bool, RepoError> {
+        let removed = sqlx::query("DELETE FROM account_aliases WHERE alias = ? AND account_id = ?")
+            .bind(alias.as_str())
+            .bind(account_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(removed.rows_affected() > 0)
+    }
+
+    async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError> {
+        sqlx::query(r#"INSERT INTO owners (id, name, email, created_at) VALUES (?, ?, ?, ?)"#)
+            .bind(owner.id.to_string())
+            .bind(&owner.name)
+            .bind(&owner.email)
+            .bind(owner.created_at.to_rfc3339())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(owner.clone())
+    }
+
+    async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError> {
+        let row: Option<OwnerRow> =
+            sqlx::query_as(r#"SELECT id, name, email, created_at FROM owners WHERE id = ?"#)
+                .bind(id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        row.map(owner_from_row).transpose()
+    }
+
+    async fn list_owners(&self) -> Result<Vec<Owner>, RepoError> {
+        let rows: Vec<OwnerRow> =
+            sqlx::query_as(r#"SELECT id, name, email, created_at FROM owners"#)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        // Sort parsed timestamps: RFC 3339 text does not order reliably.
+        let mut owners = rows
+            .into_iter()
+            .map(owner_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        owners.sort_by_key(|o| o.created_at);
+        Ok(owners)
+    }
+
+    async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError> {
+        let updated = sqlx::query(r#"UPDATE owners SET name = ?, email = ? WHERE id = ?"#)
+            .bind(&owner.name)
+            .bind(&owner.email)
+            .bind(owner.id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get_owner(owner.id).await
+    }
+
+    async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError> {
+        let deleted = sqlx::query("DELETE FROM owners WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Account>, RepoError> {
+        let rows: Vec<DbAccount> = sqlx::query_as(
+            r#"SELECT id, name, balance, held, currency, created_at, status, status_changed_at, merged_into, overdraft_limit, owner_id, mode FROM accounts
+               WHERE owner_id = ? AND (? IS NULL OR mode = ?) ORDER BY created_at DESC"#,
+        )
+        .bind(owner_id.to_string())
+        .bind(mode.map(|mode| mode.as_str()))
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbAccount::into_domain).collect()
+    }
+
+    async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError> {
+        sqlx::query(
+            r#"INSERT INTO memo_keys (id, account_id, algorithm, fingerprint, created_at, retired_at)
+               VALUES (?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(key.id.to_string())
+        .bind(key.account_id.to_string())
+        .bind(&key.algorithm)
+        .bind(&key.fingerprint)
+        .bind(key.created_at.to_rfc3339())
+        .bind(key.retired_at.map(|at| at.to_rfc3339()))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(key.clone())
+    }
+
+    async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError> {
+        let row: Option<MemoKeyRow> = sqlx::query_as(
+            r#"SELECT id, account_id, algorithm, fingerprint, created_at, retired_at
+               FROM memo_keys WHERE id = ?"#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(memo_key_from_row).transpose()
+    }
+
+    async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError> {
+        let rows: Vec<MemoKeyRow> = sqlx::query_as(
+            r#"SELECT id, account_id, algorithm, fingerprint, created_at, retired_at
+               FROM memo_keys WHERE account_id = ?"#,
+        )
+        .bind(account_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        // Sort parsed timestamps: RFC 3339 text does not order reliably.
+        let mut keys = rows
+            .into_iter()
+            .map(memo_key_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        keys.sort_by_key(|k| k.created_at);
+        Ok(keys)
+    }
+
+    async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        id: MemoKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<MemoKey>, RepoError> {
+        sqlx::query(
+            r#"UPDATE memo_keys SET retired_at = ?
+               WHERE id = ? AND account_id = ? AND retired_at IS NULL"#,
+        )
+        .bind(at.to_rfc3339())
+        .bind(id.to_string())
+        .bind(account_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(self
+            .get_memo_key(id)
+            .await?
+            .filter(|key| key.account_id == account_id))
+    }
+
+    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let transaction = self.deposit_in(&mut db_tx, req).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(transaction)
+    }
+
+    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let transaction = self.withdraw_in(&mut db_tx, req).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(transaction)
+    }
+
+    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+        self.transfer_between(req, None).await
+    }
+
+    async fn transfer_with_conversion(
+        &self,
+        req: TransferRequest,
+        conversion: FxConversion,
+    ) -> Result<Transaction, RepoError> {
+        self.transfer_between(req, Some(conversion)).await
+    }
+
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork + '_>, RepoError> {
+        let tx = self.pool.begin().await.map_err(tx_error)?;
+        Ok(Box::new(SqliteUnitOfWork { repo: self, tx }))
+    }
+
+    async fn record_transaction_risk(
+        &self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        let mut conn = self.pool.acquire().await.map_err(db_error)?;
+        record_transaction_risk(&mut conn, id, risk).await
+    }
+
+    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
+        let mut conn = self.pool.acquire().await.map_err(db_error)?;
+        find_by_idempotency_key(&mut conn, key).await
+    }
+
+    async fn get_transaction(
+        &self,
+        id: payments_types::TransactionId,
+    ) -> Result<Option<Transaction>, RepoError> {
+        let id_str = id.to_string();
+
+        let row: Option<DbTransaction> = sqlx::query_as(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions WHERE id = ?"#,
+        )
+        .bind(&id_str)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
 
         row.map(DbTransaction::into_domain).transpose()
     }
 
-    async fn list_transactions_for_account(
+    async fn list_transactions_for_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let account_id_str = account_id.to_string();
+
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions WHERE source_account_id = ? OR destination_account_id = ?
+               ORDER BY created_at DESC"#,
+        )
+        .bind(&account_id_str)
+        .bind(&account_id_str)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbTransaction::into_domain).collect()
+    }
+
+    async fn list_transactions_page(
+        &self,
+        account_id: AccountId,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions
+               WHERE (source_account_id = ?1 OR destination_account_id = ?1)
+                 AND (?2 IS NULL OR direction = ?2)
+                 AND (?3 IS NULL OR amount >= ?3)
+                 AND (?4 IS NULL OR amount <= ?4)"#,
+        )
+        .bind(account_id.to_string())
+        .bind(filter.transaction_type.map(|t| t.to_string()))
+        .bind(filter.min_amount)
+        .bind(filter.max_amount)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let mut transactions = rows
+            .into_iter()
+            .map(DbTransaction::into_domain)
+            .collect::<Result<Vec<_>, _>>()?;
+        // Compare parsed timestamps: RFC 3339 text does not order reliably.
+        transactions
+            .retain(|tx| filter.matches(tx) && after.is_none_or(|cursor| cursor.precedes(tx)));
+        transactions.sort_by_key(|tx| std::cmp::Reverse((tx.created_at, *tx.id.as_uuid())));
+        transactions.truncate(limit);
+        Ok(transactions)
+    }
+
+    async fn search_transactions(
+        &self,
+        search: &TransactionSearch,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let mut query = QueryBuilder::<Sqlite>::new(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions WHERE 1 = 1"#,
+        );
+        // LIKE is ASCII case-insensitive in SQLite.
+        if let Some(reference) = &search.reference {
+            query
+                .push(" AND reference LIKE ")
+                .push_bind(format!("%{}%", escape_like(reference)))
+                .push(r" ESCAPE '\'");
+        }
+        if let Some(account_id) = search.account_id {
+            query
+                .push(" AND (source_account_id = ")
+                .push_bind(account_id.to_string())
+                .push(" OR destination_account_id = ")
+                .push_bind(account_id.to_string())
+                .push(")");
+        }
+        if let Some(currency) = search.currency {
+            query
+                .push(" AND currency = ")
+                .push_bind(currency.code().to_string());
+        }
+        if let Some(mode) = search.mode {
+            query
+                .push(" AND EXISTS (SELECT 1 FROM accounts WHERE accounts.id IN (source_account_id, destination_account_id) AND accounts.mode = ")
+                .push_bind(mode.as_str().to_string())
+                .push(")");
+        }
+        if let Some(key) = &search.metadata_key {
+            query
+                .push(" AND EXISTS (SELECT 1 FROM json_each(metadata) WHERE key = ")
+                .push_bind(key.clone());
+            if let Some(value) = &search.metadata_value {
+                query.push(" AND value = ").push_bind(value.clone());
+            }
+            query.push(")");
+        }
+        if let Some(transaction_type) = search.filter.transaction_type {
+            query
+                .push(" AND direction = ")
+                .push_bind(transaction_type.to_string());
+        }
+        if let Some(min) = search.filter.min_amount {
+            query.push(" AND amount >= ").push_bind(min);
+        }
+        if let Some(max) = search.filter.max_amount {
+            query.push(" AND amount <= ").push_bind(max);
+        }
+        let rows: Vec<DbTransaction> = query
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let mut transactions = rows
+            .into_iter()
+            .map(DbTransaction::into_domain)
+            .collect::<Result<Vec<_>, _>>()?;
+        // Compare parsed timestamps: RFC 3339 text does not order reliably.
+        transactions.retain(|tx| {
+            search.filter.matches(tx) && after.is_none_or(|cursor| cursor.precedes(tx))
+        });
+        transactions.sort_by_key(|tx| std::cmp::Reverse((tx.created_at, *tx.id.as_uuid())));
+        transactions.truncate(limit);
+        Ok(transactions)
+    }
+
+    async fn list_transactions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions
+               WHERE ?1 IS NULL OR EXISTS (SELECT 1 FROM accounts WHERE accounts.id IN (source_account_id, destination_account_id) AND accounts.mode = ?1)"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let mut transactions = rows
+            .into_iter()
+            .map(DbTransaction::into_domain)
+            .collect::<Result<Vec<_>, _>>()?;
+        // Compare parsed timestamps: RFC 3339 text does not order reliably.
+        transactions.retain(|tx| tx.created_at >= from && tx.created_at < to);
+        transactions.sort_by_key(|tx| tx.created_at);
+        Ok(transactions)
+    }
+
+    async fn verify_api_key_hash(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+        let row: Option<DbApiKey> = sqlx::query_as(
+            r#"
+            SELECT id, name, key_hash, account_id, owner_id, scopes, is_active, created_at, last_used_at, deactivated_at, deactivated_by, rate_limit_per_minute, mode
+            FROM api_keys
+            WHERE key_hash = ? AND is_active = 1
+            "#,
+        )
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbApiKey::into_domain).transpose()
+    }
+
+    async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.insert_api_key(name, None, None, scopes, mode).await
+    }
+
+    async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.insert_api_key(name, Some(account_id), None, scopes, mode)
+            .await
+    }
+
+    async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[payments_types::ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(payments_types::ApiKey, String), RepoError> {
+        self.insert_api_key(name, None, Some(owner_id), scopes, mode)
+            .await
+    }
+
+    async fn count_api_keys(&self) -> Result<i64, RepoError> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM api_keys WHERE is_active = 1")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(row.0)
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<payments_types::ApiKey>, RepoError> {
+        let rows: Vec<DbApiKey> = sqlx::query_as(
+            "SELECT id, name, key_hash, account_id, owner_id, scopes, is_active, created_at, last_used_at, deactivated_at, deactivated_by, rate_limit_per_minute, mode FROM api_keys WHERE is_active = 1 ORDER BY created_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbApiKey::into_domain).collect()
+    }
+
+    async fn delete_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+        deactivated_by: payments_types::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"UPDATE api_keys SET is_active = 0, deactivated_at = ?, deactivated_by = ?
+               WHERE id = ? AND is_active = 1"#,
+        )
+        .bind(sortable_timestamp(at))
+        .bind(deactivated_by.to_string())
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<(), RepoError> {
+        sqlx::query("UPDATE api_keys SET last_used_at = ? WHERE id = ?")
+            .bind(sortable_timestamp(at))
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn set_api_key_rate_limit(
+        &self,
+        id: payments_types::ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            "UPDATE api_keys SET rate_limit_per_minute = ? WHERE id = ? AND is_active = 1",
+        )
+        .bind(rate_limit_per_minute)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_api_key(
+        &self,
+        id: payments_types::ApiKeyId,
+    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
+        let row: Option<DbApiKey> = sqlx::query_as(
+            "SELECT id, name, key_hash, account_id, owner_id, scopes, is_active, created_at, last_used_at, deactivated_at, deactivated_by, rate_limit_per_minute, mode FROM api_keys WHERE id = ?",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(DbApiKey::into_domain).transpose()
+    }
+
+    async fn insert_api_key_audit(&self, entry: &ApiKeyAuditEntry) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"INSERT INTO audit_log (api_key_id, action, actor_id, occurred_at)
+               VALUES (?, ?, ?, ?)"#,
+        )
+        .bind(entry.api_key_id.to_string())
+        .bind(entry.action.as_ref())
+        .bind(entry.actor.map(|id| id.to_string()))
+        .bind(sortable_timestamp(entry.at))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_api_key_audit(
+        &self,
+        id: payments_types::ApiKeyId,
+    ) -> Result<Vec<ApiKeyAuditEntry>, RepoError> {
+        let rows: Vec<AuditRow> = sqlx::query_as(
+            r#"SELECT api_key_id, action, actor_id, occurred_at
+               FROM audit_log WHERE api_key_id = ? ORDER BY id"#,
+        )
+        .bind(id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(audit_entry_from_row).collect()
+    }
+
+    async fn add_api_key_usage(&self, usage: &ApiKeyUsageBucket) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"INSERT INTO api_key_usage (api_key_id, hour, requests, rate_limited)
+               VALUES (?, ?, ?, ?)
+               ON CONFLICT (api_key_id, hour) DO UPDATE SET
+                   requests = requests + excluded.requests,
+                   rate_limited = rate_limited + excluded.rate_limited"#,
+        )
+        .bind(usage.api_key_id.to_string())
+        .bind(sortable_timestamp(usage.hour))
+        .bind(usage.requests)
+        .bind(usage.rate_limited)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn add_api_key_volume(&self, volume: &ApiKeyVolumeBucket) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"INSERT INTO api_key_volume
+                   (api_key_id, hour, transaction_type, currency, count, amount)
+               VALUES (?, ?, ?, ?, ?, ?)
+               ON CONFLICT (api_key_id, hour, transaction_type, currency) DO UPDATE SET
+                   count = count + excluded.count,
+                   amount = amount + excluded.amount"#,
+        )
+        .bind(volume.api_key_id.to_string())
+        .bind(sortable_timestamp(volume.hour))
+        .bind(volume.transaction_type.to_string())
+        .bind(volume.currency.to_string())
+        .bind(volume.count)
+        .bind(volume.amount)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_api_key_usage(
+        &self,
+        id: payments_types::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyUsageBucket>, RepoError> {
+        let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+            r#"SELECT hour, requests, rate_limited FROM api_key_usage
+               WHERE api_key_id = ? AND hour >= ? ORDER BY hour"#,
+        )
+        .bind(id.to_string())
+        .bind(sortable_timestamp(since))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(|(hour, requests, rate_limited)| {
+                Ok(ApiKeyUsageBucket {
+                    api_key_id: id,
+                    hour: parse_timestamp(&hour)?,
+                    requests,
+                    rate_limited,
+                })
+            })
+            .collect()
+    }
+
+    async fn list_api_key_volume(
+        &self,
+        id: payments_types::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyVolumeBucket>, RepoError> {
+        let rows: Vec<(String, String, String, i64, i64)> = sqlx::query_as(
+            r#"SELECT hour, transaction_type, currency, count, amount FROM api_key_volume
+               WHERE api_key_id = ? AND hour >= ? ORDER BY hour"#,
+        )
+        .bind(id.to_string())
+        .bind(sortable_timestamp(since))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(|(hour, transaction_type, currency, count, amount)| {
+                Ok(ApiKeyVolumeBucket {
+                    api_key_id: id,
+                    hour: parse_timestamp(&hour)?,
+                    transaction_type: parse_transaction_type(&transaction_type)?,
+                    currency: parse_currency(&currency)?,
+                    count,
+                    amount,
+                })
+            })
+            .collect()
+    }
+
+    async fn register_webhook_endpoint(
+        &self,
+        url: &str,
+        events: Vec<String>,
+        timeouts: WebhookTimeouts,
+        signature_algorithm: WebhookSignatureAlgorithm,
+    ) -> Result<payments_types::WebhookEndpoint, RepoError> {
+        let mut registered = self
+            .register_webhook_endpoints(vec![NewWebhookEndpoint {
+                url: url.to_string(),
+                events,
+                timeouts,
+                signature_algorithm,
+                mode: Mode::Live,
+            }])
+            .await?;
+        Ok(registered.remove(0))
+    }
+
+    async fn register_webhook_endpoints(
+        &self,
+        endpoints: Vec<NewWebhookEndpoint>,
+    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+        let now = self.clock.now();
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let mut registered = Vec::with_capacity(endpoints.len());
+        for endpoint in endpoints {
+            let id = self.ids.new_id();
+            let secret = crate::security::generate_webhook_secret();
+            let signing_key = crate::security::generate_webhook_signing_key();
+            let events_json = serde_json::to_string(&endpoint.events)
+                .map_err(|e| RepoError::Database(e.to_string()))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO webhook_endpoints
+                    (id, url, secret, events, is_active, created_at, timeout_ms,
+                     connect_timeout_ms, signature_algorithm, signing_key, mode)
+                VALUES (?, ?, ?, ?, 1, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(id.to_string())
+            .bind(&endpoint.url)
+            .bind(&secret)
+            .bind(&events_json)
+            .bind(now.to_rfc3339())
+            .bind(endpoint.timeouts.timeout_ms)
+            .bind(endpoint.timeouts.connect_timeout_ms)
+            .bind(endpoint.signature_algorithm.as_ref())
+            .bind(&signing_key)
+            .bind(endpoint.mode.as_str())
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
+
+            registered.push(payments_types::WebhookEndpoint {
+                id,
+                url: endpoint.url,
+                public_key: crate::security::webhook_public_key(
+                    endpoint.signature_algorithm,
+                    &signing_key,
+                ),
+                secret,
+                events: endpoint.events,
+                is_active: true,
+                created_at: now,
+                timeouts: endpoint.timeouts,
+                signature_algorithm: endpoint.signature_algorithm,
+                signing_key,
+                mode: endpoint.mode,
+            });
+        }
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(registered)
+    }
+
+    async fn list_webhook_endpoints(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
+        let rows: Vec<WebhookEndpointRow> = sqlx::query_as(
+            r#"
+            SELECT id, url, secret, events, is_active, created_at, timeout_ms, connect_timeout_ms,
+                   signature_algorithm, signing_key, mode
+            FROM webhook_endpoints
+            WHERE is_active = 1 AND (? IS NULL OR mode = ?)
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(webhook_endpoint_from_row).collect()
+    }
+
+    async fn get_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        let row: Option<WebhookEndpointRow> = sqlx::query_as(
+            r#"
+            SELECT id, url, secret, events, is_active, created_at, timeout_ms, connect_timeout_ms,
+                   signature_algorithm, signing_key, mode
+            FROM webhook_endpoints
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(webhook_endpoint_from_row).transpose()
+    }
+
+    async fn update_webhook_endpoint_url(
+        &self,
+        id: payments_types::WebhookEndpointId,
+        url: &str,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        sqlx::query("UPDATE webhook_endpoints SET url = ? WHERE id = ?")
+            .bind(url)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        self.get_webhook_endpoint(id).await
+    }
+
+    async fn update_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<WebhookSignatureAlgorithm>,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        let events_json = events
+            .map(|events| serde_json::to_string(&events))
+            .transpose()
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"
+            UPDATE webhook_endpoints
+            SET events = COALESCE(?, events), is_active = COALESCE(?, is_active),
+                signature_algorithm = COALESCE(?, signature_algorithm)
+            WHERE id = ?
+            "#,
+        )
+        .bind(events_json)
+        .bind(is_active)
+        .bind(signature_algorithm.as_ref().map(AsRef::as_ref))
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        self.get_webhook_endpoint(id).await
+    }
+
+    async fn rotate_webhook_secret(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<Option<payments_types::WebhookEndpoint>, RepoError> {
+        sqlx::query("UPDATE webhook_endpoints SET secret = ?, signing_key = ? WHERE id = ?")
+            .bind(crate::security::generate_webhook_secret())
+            .bind(crate::security::generate_webhook_signing_key())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        self.get_webhook_endpoint(id).await
+    }
+
+    async fn delete_webhook_endpoint(
+        &self,
+        id: payments_types::WebhookEndpointId,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query("DELETE FROM webhook_endpoints WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn create_webhook_event(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<payments_types::WebhookEvent, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let event = self
+            .create_webhook_event_in(&mut db_tx, endpoint_id, event_type, payload)
+            .await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(event)
+    }
+
+    async fn get_webhook_event(&self, id: Uuid) -> Result<Option<WebhookEvent>, RepoError> {
+        let row = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
+            r#"
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
+            FROM webhook_events
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(|row| row.into_domain()).transpose()
+    }
+
+    async fn list_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let rows = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
+            r#"
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
+            FROM webhook_events
+            WHERE status = 'DEAD_LETTERED'
+              AND (?1 IS NULL OR endpoint_id = ?1)
+              AND (?2 IS NULL OR event_type = ?2)
+              AND (?3 IS NULL OR mode = ?3)
+            "#,
+        )
+        .bind(filter.endpoint_id.map(|id| id.to_string()))
+        .bind(filter.event_type.as_deref())
+        .bind(filter.mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let mut events = rows
+            .into_iter()
+            .map(|row| row.into_domain())
+            .collect::<Result<Vec<_>, _>>()?;
+        // Compare parsed timestamps: RFC 3339 text does not order reliably.
+        events.retain(|event| filter.matches(event));
+        events.sort_by_key(|event| (event.created_at, event.id));
+        Ok(events)
+    }
+
+    async fn requeue_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<u64, RepoError> {
+        let events = self.list_dead_lettered_webhooks(filter).await?;
+        let now = sortable_timestamp(self.clock.now());
+
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let mut requeued = 0;
+        for event in events {
+            // Skip events another caller requeued since they were listed
+            requeued += sqlx::query(
+                r#"
+                UPDATE webhook_events
+                SET status = 'PENDING', attempts = 0, processed_at = NULL, next_attempt_at = ?
+                WHERE id = ? AND status = 'DEAD_LETTERED'
+                "#,
+            )
+            .bind(&now)
+            .bind(event.id.to_string())
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?
+            .rows_affected();
+        }
+        db_tx.commit().await.map_err(tx_error)?;
+
+        Ok(requeued)
+    }
+
+    async fn count_webhook_backlog(&self) -> Result<i64, RepoError> {
+        let row: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM webhook_events WHERE status IN ('PENDING', 'FAILED')",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(row.0)
+    }
+
+    async fn storage_counts(&self) -> Result<StorageCounts, RepoError> {
+        let row: (i64, i64, i64) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM accounts), (SELECT COUNT(*) FROM transactions), \
+             (SELECT COUNT(*) FROM webhook_events)",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(StorageCounts {
+            accounts: row.0,
+            transactions: row.1,
+            webhook_events: row.2,
+        })
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_webhook_events_after(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        after_sequence: i64,
+        limit: usize,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let rows = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
+            r#"
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
+            FROM webhook_events
+            WHERE endpoint_id = ? AND delivery_sequence > ?
+            ORDER BY delivery_sequence ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(endpoint_id.0.to_string())
+        .bind(after_sequence)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(|row| row.into_domain()).collect()
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        endpoint_id: payments_types::WebhookEndpointId,
+        filter: &WebhookDeliveryFilter,
+        limit: usize,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let rows = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
+            r#"
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
+            FROM webhook_events
+            WHERE endpoint_id = ?1
+              AND (?2 IS NULL OR status = ?2)
+              AND (?3 IS NULL OR delivery_sequence < ?3)
+            ORDER BY delivery_sequence DESC
+            LIMIT ?4
+            "#,
+        )
+        .bind(endpoint_id.0.to_string())
+        .bind(filter.status.as_ref().map(|s| s.to_string()))
+        .bind(filter.before_sequence)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(|row| row.into_domain()).collect()
+    }
+
+    async fn requeue_webhook_event(&self, id: Uuid) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_events
+            SET status = 'PENDING', attempts = 0, processed_at = NULL, next_attempt_at = ?
+            WHERE id = ? AND status IN ('FAILED', 'DEAD_LETTERED')
+            "#,
+        )
+        .bind(sortable_timestamp(self.clock.now()))
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn append_event(&self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        self.append_event_in(&mut db_tx, event, mode).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(())
+    }
+
+    async fn list_logged_events(
+        &self,
+        after: i64,
+        limit: usize,
+        mode: Option<Mode>,
+    ) -> Result<Vec<LoggedEvent>, RepoError> {
+        let rows: Vec<LoggedEventRow> = sqlx::query_as(
+            r#"SELECT sequence, event_type, payload, occurred_at, recorded_at, mode
+               FROM event_log WHERE sequence > ? AND (? IS NULL OR mode = ?)
+               ORDER BY sequence LIMIT ?"#,
+        )
+        .bind(after)
+        .bind(mode.map(|mode| mode.as_str()))
+        .bind(mode.map(|mode| mode.as_str()))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(logged_event_from_row).collect()
+    }
+
+    async fn create_fee_schedule(
+        &self,
+        name: &str,
+        tiers: &[FeeTier],
+    ) -> Result<FeeSchedule, RepoError> {
+        let schedule = FeeSchedule {
+            id: FeeScheduleId::from_uuid(self.ids.new_id()),
+            name: name.to_string(),
+            tiers: tiers.to_vec(),
+            created_at: self.clock.now(),
+        };
+        let tiers_json = serde_json::to_string(&schedule.tiers)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+
+        sqlx::query("INSERT INTO fee_schedules (id, name, tiers, created_at) VALUES (?, ?, ?, ?)")
+            .bind(schedule.id.to_string())
+            .bind(&schedule.name)
+            .bind(tiers_json)
+            .bind(schedule.created_at.to_rfc3339())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(schedule)
+    }
+
+    async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<Option<FeeSchedule>, RepoError> {
+        let row: Option<FeeScheduleRow> =
+            sqlx::query_as("SELECT id, name, tiers, created_at FROM fee_schedules WHERE id = ?")
+                .bind(id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        row.map(fee_schedule_from_row).transpose()
+    }
+
+    async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError> {
+        let rows: Vec<FeeScheduleRow> = sqlx::query_as(
+            "SELECT id, name, tiers, created_at FROM fee_schedules ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(fee_schedule_from_row).collect()
+    }
+
+    async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: DateTime<Utc>,
+    ) -> Result<FeeAssignment, RepoError> {
+        let assignment = FeeAssignment {
+            account_id,
+            fee_schedule_id,
+            effective_from,
+            assigned_at: self.clock.now(),
+        };
+
+        sqlx::query(
+            r#"INSERT INTO account_fee_assignments
+                   (account_id, fee_schedule_id, effective_from, assigned_at)
+               VALUES (?, ?, ?, ?)"#,
+        )
+        .bind(account_id.to_string())
+        .bind(fee_schedule_id.to_string())
+        .bind(effective_from.to_rfc3339())
+        .bind(assignment.assigned_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(assignment)
+    }
+
+    async fn list_fee_assignments(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<FeeAssignment>, RepoError> {
+        let rows: Vec<(String, String, String)> = sqlx::query_as(
+            r#"SELECT fee_schedule_id, effective_from, assigned_at
+               FROM account_fee_assignments WHERE account_id = ? ORDER BY id"#,
+        )
+        .bind(account_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let mut assignments = rows
+            .into_iter()
+            .map(|(fee_schedule_id, effective_from, assigned_at)| {
+                Ok(FeeAssignment {
+                    account_id,
+                    fee_schedule_id: fee_schedule_id
+                        .parse()
+                        .map_err(|e: uuid::Error| RepoError::Database(e.to_string()))?,
+                    effective_from: parse_timestamp(&effective_from)?,
+                    assigned_at: parse_timestamp(&assigned_at)?,
+                })
+            })
+            .collect::<Result<Vec<_>, RepoError>>()?;
+        // RFC 3339 text does not sort chronologically when fractional
+        // seconds vary, so order here rather than in SQL.
+        assignments.sort_by_key(|a| (a.effective_from, a.assigned_at));
+        Ok(assignments)
+    }
+
+    async fn create_settlement_batch(
         &self,
-        account_id: AccountId,
-    ) -> Result<Vec<Transaction>, RepoError> {
-        let account_id_str = account_id.to_string();
+        cutoff: DateTime<Utc>,
+        mode: Mode,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
 
         let rows: Vec<DbTransaction> = sqlx::query_as(
-            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, created_at
-               FROM transactions WHERE source_account_id = ? OR destination_account_id = ?
-               ORDER BY created_at DESC"#,
+            r#"SELECT id, direction, amount, currency, source_account_id, destination_account_id, idempotency_key, reference, counterparty_name, counterparty_external_id, purpose_code, fx_rate, converted_amount, converted_currency, risk_decision, risk_reasons, metadata, encrypted_memo, created_at
+               FROM transactions
+               WHERE direction = 'WITHDRAWAL'
+                 AND id NOT IN (SELECT transaction_id FROM settlement_batch_payouts)
+                 AND source_account_id IN (SELECT id FROM accounts WHERE mode = ?)"#,
         )
-        .bind(&account_id_str)
-        .bind(&account_id_str)
+        .bind(mode.as_str())
+        .fetch_all(&mut *db_tx)
+        .await
+        .map_err(db_error)?;
+
+        let mut payouts = rows
+            .into_iter()
+            .map(DbTransaction::into_domain)
+            .collect::<Result<Vec<_>, _>>()?;
+        // Compare parsed timestamps: RFC 3339 text does not order reliably.
+        payouts.retain(|tx| tx.created_at <= cutoff);
+        if payouts.is_empty() {
+            return Ok(None);
+        }
+
+        let now = self.clock.now();
+        let batch = SettlementBatch {
+            id: SettlementBatchId::from_uuid(self.ids.new_id()),
+            status: SettlementBatchStatus::Pending,
+            cutoff,
+            payout_count: payouts.len() as i64,
+            totals: SettlementBatch::totals_for(&payouts),
+            created_at: now,
+            updated_at: now,
+            mode,
+        };
+        let totals_json =
+            serde_json::to_string(&batch.totals).map_err(|e| RepoError::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"INSERT INTO settlement_batches (id, status, cutoff, payout_count, totals, created_at, updated_at, mode)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(batch.id.to_string())
+        .bind(batch.status.as_ref())
+        .bind(cutoff.to_rfc3339())
+        .bind(batch.payout_count)
+        .bind(totals_json)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .bind(mode.as_str())
+        .execute(&mut *db_tx)
+        .await
+        .map_err(db_error)?;
+
+        for payout in &payouts {
+            sqlx::query(
+                "INSERT INTO settlement_batch_payouts (transaction_id, batch_id) VALUES (?, ?)",
+            )
+            .bind(payout.id.to_string())
+            .bind(batch.id.to_string())
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
+        }
+
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(Some(batch))
+    }
+
+    async fn get_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let row: Option<SettlementBatchRow> = sqlx::query_as(
+            r#"SELECT id, status, cutoff, payout_count, totals, created_at, updated_at, mode
+               FROM settlement_batches WHERE id = ?"#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(settlement_batch_from_row).transpose()
+    }
+
+    async fn list_settlement_batches(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<SettlementBatch>, RepoError> {
+        let rows: Vec<SettlementBatchRow> = sqlx::query_as(
+            r#"SELECT id, status, cutoff, payout_count, totals, created_at, updated_at, mode
+               FROM settlement_batches WHERE ?1 IS NULL OR mode = ?1"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        rows.into_iter().map(DbTransaction::into_domain).collect()
+        let mut batches = rows
+            .into_iter()
+            .map(settlement_batch_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        batches.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+        Ok(batches)
     }
 
-    async fn verify_api_key_hash(
+    async fn update_settlement_batch_status(
         &self,
-        key_hash: &str,
-    ) -> Result<Option<payments_types::ApiKey>, RepoError> {
-        let row: Option<crate::types::DbApiKey> = sqlx::query_as(
-            r#"
-            SELECT id, name, key_hash, account_id, is_active, created_at, last_used_at
-            FROM api_keys
-            WHERE key_hash = ? AND is_active = 1
-            "#,
+        id: SettlementBatchId,
+        from: SettlementBatchStatus,
+        to: SettlementBatchStatus,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let id_str = id.to_string();
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+
+        let updated = sqlx::query(
+            "UPDATE settlement_batches SET status = ?, updated_at = ? WHERE id = ? AND status = ?",
+        )
+        .bind(to.as_ref())
+        .bind(self.clock.now().to_rfc3339())
+        .bind(&id_str)
+        .bind(from.as_ref())
+        .execute(&mut *db_tx)
+        .await
+        .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        if to == SettlementBatchStatus::Cancelled {
+            sqlx::query("DELETE FROM settlement_batch_payouts WHERE batch_id = ?")
+                .bind(&id_str)
+                .execute(&mut *db_tx)
+                .await
+                .map_err(db_error)?;
+        }
+
+        db_tx.commit().await.map_err(tx_error)?;
+        self.get_settlement_batch(id).await
+    }
+
+    async fn list_settlement_batch_payouts(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let rows: Vec<DbTransaction> = sqlx::query_as(
+            r#"SELECT t.id, t.direction, t.amount, t.currency, t.source_account_id, t.destination_account_id, t.idempotency_key, t.reference, t.counterparty_name, t.counterparty_external_id, t.purpose_code, t.fx_rate, t.converted_amount, t.converted_currency, t.risk_decision, t.risk_reasons, t.metadata, t.encrypted_memo, t.created_at
+               FROM transactions t
+               JOIN settlement_batch_payouts p ON p.transaction_id = t.id
+               WHERE p.batch_id = ?"#,
+        )
+        .bind(id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let mut payouts = rows
+            .into_iter()
+            .map(DbTransaction::into_domain)
+            .collect::<Result<Vec<_>, _>>()?;
+        payouts.sort_by_key(|tx| tx.created_at);
+        Ok(payouts)
+    }
+
+    async fn get_statement_email(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<String>, RepoError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT email FROM statement_emails WHERE account_id = ?")
+                .bind(account_id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+        Ok(row.map(|(email,)| email))
+    }
+
+    async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<(), RepoError> {
+        let query = match email {
+            Some(email) => sqlx::query(
+                r#"INSERT INTO statement_emails (account_id, email) VALUES (?, ?)
+                   ON CONFLICT (account_id) DO UPDATE SET email = excluded.email"#,
+            )
+            .bind(account_id.to_string())
+            .bind(email),
+            None => sqlx::query("DELETE FROM statement_emails WHERE account_id = ?")
+                .bind(account_id.to_string()),
+        };
+        query.execute(&self.pool).await.map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn insert_statement(
+        &self,
+        statement: &Statement,
+        content: &str,
+    ) -> Result<bool, RepoError> {
+        let inserted = sqlx::query(
+            r#"INSERT INTO statements (id, account_id, period_start, period_end, currency, opening_balance, closing_balance, transaction_count, content, created_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+               ON CONFLICT (account_id, period_start) DO NOTHING"#,
+        )
+        .bind(statement.id.to_string())
+        .bind(statement.account_id.to_string())
+        .bind(statement.period_start.to_string())
+        .bind(statement.period_end.to_string())
+        .bind(statement.currency.to_string())
+        .bind(statement.opening_balance)
+        .bind(statement.closing_balance)
+        .bind(statement.transaction_count)
+        .bind(content)
+        .bind(statement.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError> {
+        let row: Option<StatementRow> = sqlx::query_as(
+            r#"SELECT id, account_id, period_start, period_end, currency, opening_balance, closing_balance, transaction_count, created_at
+               FROM statements WHERE id = ?"#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(statement_from_row).transpose()
+    }
+
+    async fn get_statement_content(&self, id: StatementId) -> Result<Option<String>, RepoError> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT content FROM statements WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|(content,)| content))
+    }
+
+    async fn list_statements(&self, account_id: AccountId) -> Result<Vec<Statement>, RepoError> {
+        // ISO dates sort chronologically as text.
+        let rows: Vec<StatementRow> = sqlx::query_as(
+            r#"SELECT id, account_id, period_start, period_end, currency, opening_balance, closing_balance, transaction_count, created_at
+               FROM statements WHERE account_id = ?
+               ORDER BY period_start DESC"#,
+        )
+        .bind(account_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(statement_from_row).collect()
+    }
+
+    async fn insert_export(&self, export: &Export) -> Result<(), RepoError> {
+        let request = serde_json::to_string(&export.request)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"INSERT INTO exports (id, request, status, error, created_at, completed_at, mode)
+               VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(export.id.to_string())
+        .bind(request)
+        .bind(export.status.as_ref())
+        .bind(export.error.as_deref())
+        .bind(export.created_at.to_rfc3339())
+        .bind(export.completed_at.map(|at| at.to_rfc3339()))
+        .bind(export.mode.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn finish_export(&self, export: &Export, content: Option<&str>) -> Result<(), RepoError> {
+        sqlx::query(
+            "UPDATE exports SET status = ?, error = ?, content = ?, completed_at = ? WHERE id = ?",
+        )
+        .bind(export.status.as_ref())
+        .bind(export.error.as_deref())
+        .bind(content)
+        .bind(export.completed_at.map(|at| at.to_rfc3339()))
+        .bind(export.id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError> {
+        let row: Option<ExportRow> = sqlx::query_as(
+            "SELECT id, request, status, error, created_at, completed_at, mode FROM exports WHERE id = ?",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(export_from_row).transpose()
+    }
+
+    async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError> {
+        let row: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT content FROM exports WHERE id = ?")
+                .bind(id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+        Ok(row.and_then(|(content,)| content))
+    }
+
+    async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError> {
+        // Compare parsed timestamps: RFC 3339 text does not order reliably.
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT id, created_at FROM exports")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?;
+        let mut deleted = 0;
+        for (id, created_at) in rows {
+            if parse_timestamp(&created_at)? < cutoff {
+                deleted += sqlx::query("DELETE FROM exports WHERE id = ?")
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(db_error)?
+                    .rows_affected();
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn insert_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<(), RepoError> {
+        let schedule = serde_json::to_string(&payment.schedule)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"INSERT INTO scheduled_payments (id, transaction_type, from_account_id, to_account_id, amount, currency, reference, purpose_code, schedule, status, next_run_at, last_run_at, last_transaction_id, last_error, created_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(payment.id.to_string())
+        .bind(payment.transaction_type.to_string())
+        .bind(payment.from_account_id.to_string())
+        .bind(payment.to_account_id.map(|id| id.to_string()))
+        .bind(payment.amount)
+        .bind(payment.currency.to_string())
+        .bind(payment.reference.as_deref())
+        .bind(payment.purpose_code.as_deref())
+        .bind(schedule)
+        .bind(payment.status.as_ref())
+        .bind(sortable_timestamp(payment.next_run_at))
+        .bind(payment.last_run_at.map(|at| at.to_rfc3339()))
+        .bind(payment.last_transaction_id.map(|id| id.to_string()))
+        .bind(payment.last_error.as_deref())
+        .bind(payment.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<Option<ScheduledPayment>, RepoError> {
+        let row: Option<ScheduledPaymentRow> = sqlx::query_as(
+            r#"SELECT id, transaction_type, from_account_id, to_account_id, amount, currency, reference, purpose_code, schedule, status, next_run_at, last_run_at, last_transaction_id, last_error, created_at
+               FROM scheduled_payments WHERE id = ?"#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(scheduled_payment_from_row).transpose()
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        let rows: Vec<ScheduledPaymentRow> = sqlx::query_as(
+            r#"SELECT id, transaction_type, from_account_id, to_account_id, amount, currency, reference, purpose_code, schedule, status, next_run_at, last_run_at, last_transaction_id, last_error, created_at
+               FROM scheduled_payments
+               WHERE (? IS NULL OR from_account_id = ?)
+                 AND (? IS NULL OR from_account_id IN (SELECT id FROM accounts WHERE mode = ?))"#,
+        )
+        .bind(account_id.map(|id| id.to_string()))
+        .bind(account_id.map(|id| id.to_string()))
+        .bind(mode.map(|mode| mode.as_str()))
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        // Sort parsed timestamps: RFC 3339 text does not order reliably.
+        let mut payments = rows
+            .into_iter()
+            .map(scheduled_payment_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        payments.sort_by_key(|p| (p.created_at, *p.id.as_uuid()));
+        Ok(payments)
+    }
+
+    async fn list_due_scheduled_payments(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        let rows: Vec<ScheduledPaymentRow> = sqlx::query_as(
+            r#"SELECT id, transaction_type, from_account_id, to_account_id, amount, currency, reference, purpose_code, schedule, status, next_run_at, last_run_at, last_transaction_id, last_error, created_at
+               FROM scheduled_payments
+               WHERE status = 'ACTIVE' AND next_run_at <= ?
+               ORDER BY next_run_at, id
+               LIMIT ?"#,
+        )
+        .bind(sortable_timestamp(now))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(scheduled_payment_from_row).collect()
+    }
+
+    async fn record_scheduled_payment_run(
+        &self,
+        payment: &ScheduledPayment,
+        due_at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"UPDATE scheduled_payments
+               SET next_run_at = ?, last_run_at = ?, last_transaction_id = ?, last_error = ?
+               WHERE id = ? AND next_run_at = ?"#,
+        )
+        .bind(sortable_timestamp(payment.next_run_at))
+        .bind(payment.last_run_at.map(|at| at.to_rfc3339()))
+        .bind(payment.last_transaction_id.map(|id| id.to_string()))
+        .bind(payment.last_error.as_deref())
+        .bind(payment.id.to_string())
+        .bind(sortable_timestamp(due_at))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update_scheduled_payment_status(
+        &self,
+        payment: &ScheduledPayment,
+    ) -> Result<(), RepoError> {
+        sqlx::query("UPDATE scheduled_payments SET status = ?, next_run_at = ? WHERE id = ?")
+            .bind(payment.status.as_ref())
+            .bind(sortable_timestamp(payment.next_run_at))
+            .bind(payment.id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        create_hold(&mut db_tx, hold).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(())
+    }
+
+    async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError> {
+        let row: Option<HoldRow> =
+            sqlx::query_as(r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at FROM holds WHERE id = ?"#)
+                .bind(id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        row.map(hold_from_row).transpose()
+    }
+
+    async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError> {
+        let rows: Vec<HoldRow> =
+            sqlx::query_as(r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at FROM holds WHERE account_id = ?"#)
+                .bind(account_id.to_string())
+                .fetch_all(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        // Sort parsed timestamps: RFC 3339 text does not order reliably.
+        let mut holds = rows
+            .into_iter()
+            .map(hold_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        holds.sort_by_key(|h| std::cmp::Reverse((h.created_at, *h.id.as_uuid())));
+        Ok(holds)
+    }
+
+    async fn list_expired_holds(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Hold>, RepoError> {
+        let rows: Vec<HoldRow> = sqlx::query_as(
+            r#"SELECT id, account_id, amount, currency, reference, purpose_code, status, expires_at, captured_amount, transaction_id, created_at, resolved_at
+               FROM holds
+               WHERE status = 'AUTHORIZED' AND expires_at <= ?
+               ORDER BY expires_at, id
+               LIMIT ?"#,
+        )
+        .bind(sortable_timestamp(now))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(hold_from_row).collect()
+    }
+
+    async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let captured = self.capture_hold_in(&mut db_tx, id, amount, now).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(captured)
+    }
+
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        let hold = self.release_hold_in(&mut db_tx, id, status, now).await?;
+        db_tx.commit().await.map_err(tx_error)?;
+        Ok(hold)
+    }
+
+    async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError> {
+        let [first, second] = beneficiary.micro_deposits;
+        sqlx::query(
+            r#"INSERT INTO beneficiaries (id, account_id, name, external_id, currency, status, failed_attempts, micro_deposit_1, micro_deposit_2, created_at, verified_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(beneficiary.id.to_string())
+        .bind(beneficiary.account_id.to_string())
+        .bind(&beneficiary.name)
+        .bind(&beneficiary.external_id)
+        .bind(beneficiary.currency.to_string())
+        .bind(beneficiary.status.as_ref())
+        .bind(beneficiary.failed_attempts)
+        .bind(first)
+        .bind(second)
+        .bind(beneficiary.created_at.to_rfc3339())
+        .bind(beneficiary.verified_at.map(|at| at.to_rfc3339()))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Option<Beneficiary>, RepoError> {
+        let row: Option<BeneficiaryRow> =
+            sqlx::query_as(r#"SELECT id, account_id, name, external_id, currency, status, failed_attempts, micro_deposit_1, micro_deposit_2, created_at, verified_at FROM beneficiaries WHERE id = ?"#)
+                .bind(id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        row.map(beneficiary_from_row).transpose()
+    }
+
+    async fn list_beneficiaries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, RepoError> {
+        let rows: Vec<BeneficiaryRow> =
+            sqlx::query_as(r#"SELECT id, account_id, name, external_id, currency, status, failed_attempts, micro_deposit_1, micro_deposit_2, created_at, verified_at FROM beneficiaries WHERE account_id = ?"#)
+                .bind(account_id.to_string())
+                .fetch_all(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        // Sort parsed timestamps: RFC 3339 text does not order reliably.
+        let mut beneficiaries = rows
+            .into_iter()
+            .map(beneficiary_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        beneficiaries.sort_by_key(|b| std::cmp::Reverse((b.created_at, *b.id.as_uuid())));
+        Ok(beneficiaries)
+    }
+
+    async fn record_beneficiary_attempt(
+        &self,
+        beneficiary: &Beneficiary,
+        failed_attempts: u32,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"UPDATE beneficiaries SET status = ?, failed_attempts = ?, verified_at = ?
+               WHERE id = ? AND status = 'PENDING' AND failed_attempts = ?"#,
+        )
+        .bind(beneficiary.status.as_ref())
+        .bind(beneficiary.failed_attempts)
+        .bind(beneficiary.verified_at.map(|at| at.to_rfc3339()))
+        .bind(beneficiary.id.to_string())
+        .bind(failed_attempts)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn currency_balances(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<CurrencyBalance>, RepoError> {
+        let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+            r#"SELECT currency, COUNT(*), COALESCE(SUM(balance), 0) FROM accounts
+               WHERE ?1 IS NULL OR mode = ?1
+               GROUP BY currency ORDER BY currency"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(|(currency, account_count, balance)| {
+                Ok(CurrencyBalance {
+                    currency: parse_currency(&currency)?,
+                    account_count,
+                    balance,
+                })
+            })
+            .collect()
+    }
+
+    async fn float_positions(&self, mode: Option<Mode>) -> Result<Vec<FloatPosition>, RepoError> {
+        let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+            r#"WITH moded AS (
+                   SELECT * FROM transactions
+                   WHERE ?1 IS NULL OR EXISTS (SELECT 1 FROM accounts WHERE accounts.id IN (source_account_id, destination_account_id) AND accounts.mode = ?1)
+               )
+               SELECT currency, COALESCE(SUM(customer_balance), 0), COALESCE(SUM(float_balance), 0) FROM (
+                   SELECT currency, balance AS customer_balance, 0 AS float_balance FROM accounts
+                   WHERE ?1 IS NULL OR mode = ?1
+                   UNION ALL
+                   SELECT currency, 0, -amount FROM moded WHERE direction = 'DEPOSIT'
+                   UNION ALL
+                   SELECT currency, 0, amount FROM moded WHERE direction = 'WITHDRAWAL'
+                   UNION ALL
+                   SELECT currency, 0, amount FROM moded WHERE converted_currency IS NOT NULL
+                   UNION ALL
+                   SELECT converted_currency, 0, -converted_amount FROM moded
+                   WHERE converted_currency IS NOT NULL
+               ) AS ledger
+               GROUP BY currency ORDER BY currency"#,
+        )
+        .bind(mode.map(|mode| mode.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(|(currency, customer_balance, float_balance)| {
+                Ok(FloatPosition {
+                    currency: parse_currency(&currency)?,
+                    customer_balance,
+                    float_balance,
+                })
+            })
+            .collect()
+    }
+
+    async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError> {
+        let usd_rates = serde_json::to_string(&snapshot.usd_rates)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"INSERT INTO rate_snapshots (taken_at, usd_rates) VALUES (?, ?)
+               ON CONFLICT (taken_at) DO UPDATE SET usd_rates = excluded.usd_rates"#,
+        )
+        .bind(sortable_timestamp(snapshot.taken_at))
+        .bind(usd_rates)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn rate_snapshot_at(&self, at: DateTime<Utc>) -> Result<Option<RateSnapshot>, RepoError> {
+        let row: Option<(String, String)> = sqlx::query_as(
+            r#"SELECT taken_at, usd_rates FROM rate_snapshots
+               WHERE taken_at <= ? ORDER BY taken_at DESC LIMIT 1"#,
         )
-        .bind(key_hash)
+        .bind(sortable_timestamp(at))
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        row.map(|r| r.into_domain()).transpose()
+        row.map(|(taken_at, usd_rates)| {
+            Ok(RateSnapshot {
+                taken_at: parse_timestamp(&taken_at)?,
+                usd_rates: serde_json::from_str(&usd_rates)
+                    .map_err(|e| RepoError::Database(e.to_string()))?,
+            })
+        })
+        .transpose()
     }
 
-    async fn create_api_key(
-        &self,
-        name: &str,
-    ) -> Result<(payments_types::ApiKey, String), RepoError> {
-        use rand::Rng;
-        use rand::distr::Alphanumeric;
-
-        // Generate a secure random API key
-        let raw_key: String = rand::rng()
-            .sample_iter(&Alphanumeric)
-            .take(32)
-            .map(char::from)
-            .collect();
-        let prefixed_key = format!("sk_{}", raw_key);
-
-        let key_hash = crate::security::hash_api_key(&prefixed_key);
-        let id = uuid::Uuid::new_v4();
-        let now = chrono::Utc::now().to_rfc3339();
-
+    async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError> {
         sqlx::query(
-            r#"
-            INSERT INTO api_keys (id, name, key_hash, is_active, created_at)
-            VALUES (?, ?, ?, 1, ?)
-            "#,
+            r#"INSERT INTO fx_quotes (id, from_currency, to_currency, amount, rate, converted_amount, created_at, expires_at, transaction_id, mode)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
-        .bind(id.to_string())
-        .bind(name)
-        .bind(&key_hash)
-        .bind(&now)
+        .bind(quote.id.to_string())
+        .bind(quote.from_currency.to_string())
+        .bind(quote.to_currency.to_string())
+        .bind(quote.amount)
+        .bind(quote.rate)
+        .bind(quote.converted_amount)
+        .bind(sortable_timestamp(quote.created_at))
+        .bind(sortable_timestamp(quote.expires_at))
+        .bind(quote.transaction_id.map(|id| id.to_string()))
+        .bind(quote.mode.to_string())
         .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        let created_at = chrono::DateTime::parse_from_rfc3339(&now)
-            .map_err(|e| RepoError::Database(e.to_string()))?
-            .with_timezone(&chrono::Utc);
-
-        let api_key = payments_types::ApiKey {
-            id: payments_types::ApiKeyId::from_uuid(id),
-            name: name.to_string(),
-            key_hash,
-            account_id: None,
-            is_active: true,
-            created_at,
-            last_used_at: None,
-        };
-
-        Ok((api_key, prefixed_key))
+        .map_err(db_error)?;
+        Ok(())
     }
 
-    async fn count_api_keys(&self) -> Result<i64, RepoError> {
-        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM api_keys WHERE is_active = 1")
-            .fetch_one(&self.pool)
-            .await
-            .map_err(|e| RepoError::Database(e.to_string()))?;
+    async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError> {
+        let row: Option<QuoteRow> = sqlx::query_as(
+            r#"SELECT id, from_currency, to_currency, amount, rate, converted_amount, created_at, expires_at, transaction_id, mode
+               FROM fx_quotes WHERE id = ?"#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
 
-        Ok(row.0)
+        row.map(quote_from_row).transpose()
     }
 
-    async fn list_api_keys(&self) -> Result<Vec<payments_types::ApiKey>, RepoError> {
-        #[derive(sqlx::FromRow)]
-        struct DbApiKey {
-            id: String,
-            name: String,
-            key_hash: String,
-            account_id: Option<String>,
-            is_active: bool,
-            created_at: String,
-            last_used_at: Option<String>,
+    async fn save_daily_balances(
+        &self,
+        account_id: AccountId,
+        balances: &[DailyBalance],
+    ) -> Result<(), RepoError> {
+        let mut db_tx = self.pool.begin().await.map_err(tx_error)?;
+        for daily in balances {
+            sqlx::query(
+                r#"INSERT INTO daily_balances (account_id, date, balance) VALUES (?, ?, ?)
+                   ON CONFLICT (account_id, date) DO UPDATE SET balance = excluded.balance"#,
+            )
+            .bind(account_id.to_string())
+            .bind(daily.date.to_string())
+            .bind(daily.balance)
+            .execute(&mut *db_tx)
+            .await
+            .map_err(db_error)?;
         }
+        db_tx.commit().await.map_err(tx_error)
+    }
 
-        let rows: Vec<DbApiKey> = sqlx::query_as(
-            "SELECT id, name, key_hash, account_id, is_active, created_at, last_used_at FROM api_keys WHERE is_active = 1 ORDER BY created_at DESC"
+    async fn list_daily_balances(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyBalance>, RepoError> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            r#"SELECT date, balance FROM daily_balances
+               WHERE account_id = ? AND date >= ? AND date <= ?
+               ORDER BY date"#,
         )
+        .bind(account_id.to_string())
+        .bind(from.to_string())
+        .bind(to.to_string())
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
         rows.into_iter()
-            .map(|row| {
-                let id = uuid::Uuid::parse_str(&row.id)
-                    .map_err(|e| RepoError::Database(e.to_string()))?;
-                let created_at = chrono::DateTime::parse_from_rfc3339(&row.created_at)
-                    .map_err(|e| RepoError::Database(e.to_string()))?
-                    .with_timezone(&chrono::Utc);
-                let last_used_at = row
-                    .last_used_at
-                    .map(|s| {
-                        chrono::DateTime::parse_from_rfc3339(&s)
-                            .map(|dt| dt.with_timezone(&chrono::Utc))
-                    })
-                    .transpose()
-                    .map_err(|e| RepoError::Database(e.to_string()))?;
-                let account_id = row
-                    .account_id
-                    .map(|s| uuid::Uuid::parse_str(&s).map(payments_types::AccountId::from_uuid))
-                    .transpose()
-                    .map_err(|e| RepoError::Database(e.to_string()))?;
-
-                Ok(payments_types::ApiKey {
-                    id: payments_types::ApiKeyId::from_uuid(id),
-                    name: row.name,
-                    key_hash: row.key_hash,
-                    account_id,
-                    is_active: row.is_active,
-                    created_at,
-                    last_used_at,
+            .map(|(date, balance)| {
+                Ok(DailyBalance {
+                    date: parse_date(&date)?,
+                    balance,
                 })
             })
             .collect()
     }
 
-    async fn delete_api_key(&self, id: payments_types::ApiKeyId) -> Result<bool, RepoError> {
-        let result =
-            sqlx::query("UPDATE api_keys SET is_active = 0 WHERE id = ? AND is_active = 1")
-                .bind(id.to_string())
-                .execute(&self.pool)
+    async fn latest_daily_balance_date(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<NaiveDate>, RepoError> {
+        let row: (Option<String>,) =
+            sqlx::query_as("SELECT MAX(date) FROM daily_balances WHERE account_id = ?")
+                .bind(account_id.to_string())
+                .fetch_one(&self.pool)
                 .await
-                .map_err(|e| RepoError::Database(e.to_string()))?;
+                .map_err(db_error)?;
+        row.0.as_deref().map(parse_date).transpose()
+    }
 
-        Ok(result.rows_affected() > 0)
+    async fn insert_change_request(&self, request: &ChangeRequest) -> Result<(), RepoError> {
+        let action = serde_json::to_string(&request.action)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        sqlx::query(
+            r#"INSERT INTO change_requests (id, action, status, requested_by, requested_at, decided_by, decided_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(request.id.to_string())
+        .bind(action)
+        .bind(request.status.as_ref())
+        .bind(request.requested_by.to_string())
+        .bind(sortable_timestamp(request.requested_at))
+        .bind(request.decided_by.map(|id| id.to_string()))
+        .bind(request.decided_at.map(sortable_timestamp))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
     }
 
-    async fn register_webhook_endpoint(
+    async fn get_change_request(
         &self,
-        url: &str,
-        events: Vec<String>,
-    ) -> Result<payments_types::WebhookEndpoint, RepoError> {
-        use rand::Rng;
-        use rand::distr::Alphanumeric;
+        id: ChangeRequestId,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        let row: Option<ChangeRequestRow> = sqlx::query_as(
+            r#"SELECT id, action, status, requested_by, requested_at, decided_by, decided_at
+               FROM change_requests WHERE id = ?"#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
 
-        let id = uuid::Uuid::new_v4();
-        let now = chrono::Utc::now();
+        row.map(change_request_from_row).transpose()
+    }
 
-        // Generate a random secret for HMAC signing
-        let secret: String = rand::rng()
-            .sample_iter(&Alphanumeric)
-            .take(32)
-            .map(char::from)
-            .collect();
-        let secret = format!("whsec_{}", secret);
+    async fn list_change_requests(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, RepoError> {
+        let status = status.as_ref().map(AsRef::<str>::as_ref);
+        let rows: Vec<ChangeRequestRow> = sqlx::query_as(
+            r#"SELECT id, action, status, requested_by, requested_at, decided_by, decided_at
+               FROM change_requests
+               WHERE ? IS NULL OR status = ?
+               ORDER BY requested_at DESC, id DESC"#,
+        )
+        .bind(status)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
 
-        let events_json =
-            serde_json::to_string(&events).map_err(|e| RepoError::Database(e.to_string()))?;
+        rows.into_iter().map(change_request_from_row).collect()
+    }
 
-        sqlx::query(
-            r#"
-            INSERT INTO webhook_endpoints (id, url, secret, events, is_active, created_at)
-            VALUES (?, ?, ?, ?, 1, ?)
-            "#,
+    async fn decide_change_request(
+        &self,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: ApiKeyId,
+        decided_at: DateTime<Utc>,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        let updated = sqlx::query(
+            r#"UPDATE change_requests SET status = ?, decided_by = ?, decided_at = ?
+               WHERE id = ? AND status = ?"#,
         )
+        .bind(status.as_ref())
+        .bind(decided_by.to_string())
+        .bind(sortable_timestamp(decided_at))
         .bind(id.to_string())
-        .bind(url)
-        .bind(&secret)
-        .bind(&events_json)
-        .bind(now.to_rfc3339())
+        .bind(ChangeStatus::Pending.as_ref())
         .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
-
-        Ok(payments_types::WebhookEndpoint {
-            id,
-            url: url.to_string(),
-            secret,
-            events,
-            is_active: true,
-            created_at: now,
-        })
+        .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get_change_request(id).await
     }
 
-    async fn list_webhook_endpoints(
+    async fn get_idempotency_record(
         &self,
-    ) -> Result<Vec<payments_types::WebhookEndpoint>, RepoError> {
-        let rows: Vec<(String, String, String, String, i32, String)> = sqlx::query_as(
-            r#"
-            SELECT id, url, secret, events, is_active, created_at
-            FROM webhook_endpoints
-            WHERE is_active = 1
-            ORDER BY created_at DESC
-            "#,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, RepoError> {
+        let row: Option<IdempotencyRow> = sqlx::query_as(
+            r#"SELECT endpoint, idempotency_key, request_hash, status, response, created_at
+               FROM idempotency_responses WHERE endpoint = ? AND idempotency_key = ?"#,
         )
-        .fetch_all(&self.pool)
+        .bind(endpoint)
+        .bind(key)
+        .fetch_optional(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
-        rows.into_iter()
-            .map(|(id, url, secret, events, is_active, created_at)| {
-                let id =
-                    uuid::Uuid::parse_str(&id).map_err(|e| RepoError::Database(e.to_string()))?;
-                let events: Vec<String> = serde_json::from_str(&events).unwrap_or_default();
-                let created_at = chrono::DateTime::parse_from_rfc3339(&created_at)
-                    .map_err(|e| RepoError::Database(e.to_string()))?
-                    .with_timezone(&chrono::Utc);
-                Ok(payments_types::WebhookEndpoint {
-                    id,
-                    url,
-                    secret,
-                    events,
-                    is_active: is_active == 1,
-                    created_at,
-                })
-            })
-            .collect()
+        row.map(idempotency_record_from_row).transpose()
     }
 
-    async fn create_webhook_event(
+    async fn save_idempotency_record(
         &self,
-        endpoint_id: payments_types::WebhookEndpointId,
-        event_type: &str,
-        payload: serde_json::Value,
-    ) -> Result<payments_types::WebhookEvent, RepoError> {
-        let event_id = uuid::Uuid::new_v4();
-        let now_dt = chrono::Utc::now();
-        let now = now_dt.to_rfc3339();
-        let payload_json =
-            serde_json::to_string(&payload).map_err(|e| RepoError::Database(e.to_string()))?;
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, RepoError> {
+        let response = serde_json::to_string(&record.response)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
+        // Only a lapsed reservation gives way to the new record
+        sqlx::query(
+            r#"INSERT INTO idempotency_responses
+                   (endpoint, idempotency_key, request_hash, status, response, created_at)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+               ON CONFLICT (endpoint, idempotency_key) DO UPDATE SET
+                   request_hash = excluded.request_hash,
+                   status = excluded.status,
+                   response = excluded.response,
+                   created_at = excluded.created_at
+               WHERE idempotency_responses.status = ?7
+                 AND idempotency_responses.created_at <= ?8"#,
+        )
+        .bind(&record.endpoint)
+        .bind(&record.key)
+        .bind(&record.request_hash)
+        .bind(i64::from(record.status))
+        .bind(response)
+        .bind(sortable_timestamp(record.created_at))
+        .bind(i64::from(IdempotencyRecord::IN_PROGRESS_STATUS))
+        .bind(sortable_timestamp(
+            record.created_at - IDEMPOTENCY_RESERVATION_TTL,
+        ))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        self.get_idempotency_record(&record.endpoint, &record.key)
+            .await?
+            .ok_or_else(|| RepoError::Database("Idempotency record vanished".into()))
+    }
 
+    async fn replace_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<(), RepoError> {
+        let response = serde_json::to_string(&record.response)
+            .map_err(|e| RepoError::Database(e.to_string()))?;
         sqlx::query(
-            r#"
-            INSERT INTO webhook_events (id, endpoint_id, event_type, payload, status, created_at)
-            VALUES (?, ?, ?, ?, ?, ?)
-            "#,
+            r#"INSERT INTO idempotency_responses
+                   (endpoint, idempotency_key, request_hash, status, response, created_at)
+               VALUES (?, ?, ?, ?, ?, ?)
+               ON CONFLICT (endpoint, idempotency_key) DO UPDATE SET
+                   request_hash = excluded.request_hash,
+                   status = excluded.status,
+                   response = excluded.response,
+                   created_at = excluded.created_at"#,
         )
-        .bind(event_id.to_string())
-        .bind(endpoint_id.0.to_string())
-        .bind(event_type)
-        .bind(payload_json)
-        .bind("PENDING")
-        .bind(&now)
+        .bind(&record.endpoint)
+        .bind(&record.key)
+        .bind(&record.request_hash)
+        .bind(i64::from(record.status))
+        .bind(response)
+        .bind(sortable_timestamp(record.created_at))
         .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
+        Ok(())
+    }
 
-        Ok(payments_types::WebhookEvent {
-            id: event_id,
-            endpoint_id: endpoint_id.0,
-            event_type: event_type.to_string(),
-            payload: serde_json::Value::Null,
-            status: payments_types::WebhookStatus::Pending,
-            created_at: now_dt,
-            processed_at: None,
-            attempts: 0,
-            last_error: None,
+    async fn remove_idempotency_record(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        sqlx::query("DELETE FROM idempotency_responses WHERE endpoint = ? AND idempotency_key = ?")
+            .bind(endpoint)
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+/// `(alias, account_id, created_at)` as stored in `account_aliases`.
+type AccountAliasRow = (String, String, String);
+
+fn account_alias_from_row(
+    (alias, account_id, created_at): AccountAliasRow,
+) -> Result<AccountAlias, RepoError> {
+    Ok(AccountAlias {
+        alias: Alias::parse(&alias).map_err(|e| RepoError::Database(e.to_string()))?,
+        account_id: AccountId::from_uuid(
+            Uuid::parse_str(&account_id).map_err(|e| RepoError::Database(e.to_string()))?,
+        ),
+        created_at: parse_timestamp(&created_at)?,
+    })
+}
+
+/// `(id, name, email, created_at)` as stored in `owners`.
+type OwnerRow = (String, String, Option<String>, String);
+
+fn owner_from_row((id, name, email, created_at): OwnerRow) -> Result<Owner, RepoError> {
+    Ok(Owner {
+        id: OwnerId::from_uuid(
+            Uuid::parse_str(&id).map_err(|e| RepoError::Database(e.to_string()))?,
+        ),
+        name,
+        email,
+        created_at: parse_timestamp(&created_at)?,
+    })
+}
+
+/// `(id, account_id, algorithm, fingerprint, created_at, retired_at)` as
+/// stored in `memo_keys`.
+type MemoKeyRow = (String, String, String, String, String, Option<String>);
+
+fn memo_key_from_row(
+    (id, account_id, algorithm, fingerprint, created_at, retired_at): MemoKeyRow,
+) -> Result<MemoKey, RepoError> {
+    Ok(MemoKey {
+        id: MemoKeyId::from_uuid(
+            Uuid::parse_str(&id).map_err(|e| RepoError::Database(e.to_string()))?,
+        ),
+        account_id: AccountId::from_uuid(
+            Uuid::parse_str(&account_id).map_err(|e| RepoError::Database(e.to_string()))?,
+        ),
+        algorithm,
+        fingerprint,
+        created_at: parse_timestamp(&created_at)?,
+        retired_at: retired_at.as_deref().map(parse_timestamp).transpose()?,
+    })
+}
+
+/// `(id, name, tiers, created_at)` as stored in `fee_schedules`.
+type FeeScheduleRow = (String, String, String, String);
+
+fn fee_schedule_from_row(
+    (id, name, tiers, created_at): FeeScheduleRow,
+) -> Result<FeeSchedule, RepoError> {
+    Ok(FeeSchedule {
+        id: id
+            .parse()
+            .map_err(|e: uuid::Error| RepoError::Database(e.to_string()))?,
+        name,
+        tiers: serde_json::from_str(&tiers).map_err(|e| RepoError::Database(e.to_string()))?,
+        created_at: parse_timestamp(&created_at)?,
+    })
+}
+
+/// `(id, status, cutoff, payout_count, totals, created_at, updated_at, mode)`
+/// as stored in `settlement_batches`.
+type SettlementBatchRow = (String, String, String, i64, String, String, String, String);
+
+fn settlement_batch_from_row(
+    (id, status, cutoff, payout_count, totals, created_at, updated_at, mode): SettlementBatchRow,
+) -> Result<SettlementBatch, RepoError> {
+    Ok(SettlementBatch {
+        id: id
+            .parse()
+            .map_err(|e: uuid::Error| RepoError::Database(e.to_string()))?,
+        status: status.parse().map_err(RepoError::Database)?,
+        cutoff: parse_timestamp(&cutoff)?,
+        payout_count,
+        totals: serde_json::from_str(&totals).map_err(|e| RepoError::Database(e.to_string()))?,
+        created_at: parse_timestamp(&created_at)?,
+        updated_at: parse_timestamp(&updated_at)?,
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
+
+/// `(id, request, status, error, created_at, completed_at, mode)` as stored
+/// in `exports`.
+type ExportRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    Option<String>,
+    String,
+);
+
+fn export_from_row(
+    (id, request, status, error, created_at, completed_at, mode): ExportRow,
+) -> Result<Export, RepoError> {
+    Ok(Export {
+        id: ExportId::from_uuid(
+            Uuid::parse_str(&id).map_err(|e| RepoError::Database(e.to_string()))?,
+        ),
+        request: serde_json::from_str(&request).map_err(|e| RepoError::Database(e.to_string()))?,
+        status: status.parse().map_err(RepoError::Database)?,
+        error,
+        created_at: parse_timestamp(&created_at)?,
+        completed_at: completed_at.as_deref().map(parse_timestamp).transpose()?,
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
+
+/// `(id, from_currency, to_currency, amount, rate, converted_amount,
+/// created_at, expires_at, transaction_id, mode)` as stored in `fx_quotes`.
+type QuoteRow = (
+    String,
+    String,
+    String,
+    i64,
+    f64,
+    i64,
+    String,
+    String,
+    Option<String>,
+    String,
+);
+
+fn quote_from_row(
+    (
+        id,
+        from_currency,
+        to_currency,
+        amount,
+        rate,
+        converted_amount,
+        created_at,
+        expires_at,
+        transaction_id,
+        mode,
+    ): QuoteRow,
+) -> Result<Quote, RepoError> {
+    let uuid = |s: &str| Uuid::parse_str(s).map_err(|e| RepoError::Database(e.to_string()));
+    Ok(Quote {
+        id: QuoteId::from_uuid(uuid(&id)?),
+        from_currency: parse_currency(&from_currency)?,
+        to_currency: parse_currency(&to_currency)?,
+        amount,
+        rate,
+        converted_amount,
+        created_at: parse_timestamp(&created_at)?,
+        expires_at: parse_timestamp(&expires_at)?,
+        transaction_id: transaction_id
+            .as_deref()
+            .map(uuid)
+            .transpose()?
+            .map(TransactionId::from_uuid),
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
+
+/// `(id, account_id, amount, currency, reference, purpose_code, status,
+/// expires_at, captured_amount, transaction_id, created_at, resolved_at)` as
+/// stored in `holds`.
+type HoldRow = (
+    String,
+    String,
+    i64,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+    Option<i64>,
+    Option<String>,
+    String,
+    Option<String>,
+);
+
+fn hold_from_row(
+    (
+        id,
+        account_id,
+        amount,
+        currency,
+        reference,
+        purpose_code,
+        status,
+        expires_at,
+        captured_amount,
+        transaction_id,
+        created_at,
+        resolved_at,
+    ): HoldRow,
+) -> Result<Hold, RepoError> {
+    let uuid = |s: &str| Uuid::parse_str(s).map_err(|e| RepoError::Database(e.to_string()));
+    Ok(Hold {
+        id: HoldId::from_uuid(uuid(&id)?),
+        account_id: AccountId::from_uuid(uuid(&account_id)?),
+        amount,
+        currency: parse_currency(&currency)?,
+        reference,
+        purpose_code,
+        status: status.parse().map_err(RepoError::Database)?,
+        expires_at: parse_timestamp(&expires_at)?,
+        captured_amount,
+        transaction_id: transaction_id
+            .as_deref()
+            .map(uuid)
+            .transpose()?
+            .map(TransactionId::from_uuid),
+        created_at: parse_timestamp(&created_at)?,
+        resolved_at: resolved_at.as_deref().map(parse_timestamp).transpose()?,
+    })
+}
+
+/// `(id, account_id, name, external_id, currency, status, failed_attempts,
+/// micro_deposit_1, micro_deposit_2, created_at, verified_at)` as stored in
+/// `beneficiaries`.
+type BeneficiaryRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    i64,
+    i64,
+    i64,
+    String,
+    Option<String>,
+);
+
+fn beneficiary_from_row(
+    (
+        id,
+        account_id,
+        name,
+        external_id,
+        currency,
+        status,
+        failed_attempts,
+        first,
+        second,
+        created_at,
+        verified_at,
+    ): BeneficiaryRow,
+) -> Result<Beneficiary, RepoError> {
+    let uuid = |s: &str| Uuid::parse_str(s).map_err(|e| RepoError::Database(e.to_string()));
+    Ok(Beneficiary {
+        id: BeneficiaryId::from_uuid(uuid(&id)?),
+        account_id: AccountId::from_uuid(uuid(&account_id)?),
+        name,
+        external_id,
+        currency: parse_currency(&currency)?,
+        status: status.parse().map_err(RepoError::Database)?,
+        failed_attempts: u32::try_from(failed_attempts)
+            .map_err(|e| RepoError::Database(e.to_string()))?,
+        micro_deposits: [first, second],
+        created_at: parse_timestamp(&created_at)?,
+        verified_at: verified_at.as_deref().map(parse_timestamp).transpose()?,
+    })
+}
+
+/// `(id, transaction_type, from_account_id, to_account_id, amount, currency,
+/// reference, purpose_code, schedule, status, next_run_at, last_run_at,
+/// last_transaction_id, last_error, created_at)` as stored in
+/// `scheduled_payments`.
+type ScheduledPaymentRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    i64,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+);
+
+fn scheduled_payment_from_row(
+    (
+        id,
+        transaction_type,
+        from_account_id,
+        to_account_id,
+        amount,
+        currency,
+        reference,
+        purpose_code,
+        schedule,
+        status,
+        next_run_at,
+        last_run_at,
+        last_transaction_id,
+        last_error,
+        created_at,
+    ): ScheduledPaymentRow,
+) -> Result<ScheduledPayment, RepoError> {
+    let uuid = |s: &str| Uuid::parse_str(s).map_err(|e| RepoError::Database(e.to_string()));
+    Ok(ScheduledPayment {
+        id: ScheduledPaymentId::from_uuid(uuid(&id)?),
+        transaction_type: parse_transaction_type(&transaction_type)?,
+        from_account_id: AccountId::from_uuid(uuid(&from_account_id)?),
+        to_account_id: to_account_id
+            .as_deref()
+            .map(uuid)
+            .transpose()?
+            .map(AccountId::from_uuid),
+        amount,
+        currency: parse_currency(&currency)?,
+        reference,
+        purpose_code,
+        schedule: serde_json::from_str(&schedule)
+            .map_err(|e| RepoError::Database(e.to_string()))?,
+        status: status.parse().map_err(RepoError::Database)?,
+        next_run_at: parse_timestamp(&next_run_at)?,
+        last_run_at: last_run_at.as_deref().map(parse_timestamp).transpose()?,
+        last_transaction_id: last_transaction_id
+            .as_deref()
+            .map(uuid)
+            .transpose()?
+            .map(TransactionId::from_uuid),
+        last_error,
+        created_at: parse_timestamp(&created_at)?,
+    })
+}
+
+/// `(id, account_id, period_start, period_end, currency, opening_balance,
+/// closing_balance, transaction_count, created_at)` as stored in `statements`.
+type StatementRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    i64,
+    i64,
+    i64,
+    String,
+);
+
+/// `(id, action, status, requested_by, requested_at, decided_by, decided_at)`
+/// as stored in `change_requests`.
+type ChangeRequestRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+);
+
+fn change_request_from_row(
+    (id, action, status, requested_by, requested_at, decided_by, decided_at): ChangeRequestRow,
+) -> Result<ChangeRequest, RepoError> {
+    let uuid = |s: &str| Uuid::parse_str(s).map_err(|e| RepoError::Database(e.to_string()));
+    Ok(ChangeRequest {
+        id: ChangeRequestId::from_uuid(uuid(&id)?),
+        action: serde_json::from_str(&action).map_err(|e| RepoError::Database(e.to_string()))?,
+        status: status.parse().map_err(RepoError::Database)?,
+        requested_by: ApiKeyId::from_uuid(uuid(&requested_by)?),
+        requested_at: parse_timestamp(&requested_at)?,
+        decided_by: decided_by
+            .as_deref()
+            .map(uuid)
+            .transpose()?
+            .map(ApiKeyId::from_uuid),
+        decided_at: decided_at.as_deref().map(parse_timestamp).transpose()?,
+    })
+}
+
+/// `(endpoint, idempotency_key, request_hash, status, response, created_at)`
+/// as stored in `idempotency_responses`.
+type IdempotencyRow = (String, String, String, i64, String, String);
+
+fn idempotency_record_from_row(
+    (endpoint, key, request_hash, status, response, created_at): IdempotencyRow,
+) -> Result<IdempotencyRecord, RepoError> {
+    Ok(IdempotencyRecord {
+        endpoint,
+        key,
+        request_hash,
+        status: u16::try_from(status).map_err(|e| RepoError::Database(e.to_string()))?,
+        response: serde_json::from_str(&response)
+            .map_err(|e| RepoError::Database(e.to_string()))?,
+        created_at: parse_timestamp(&created_at)?,
+    })
+}
+
+/// Parses a `YYYY-MM-DD` date column.
+fn parse_date(value: &str) -> Result<NaiveDate, RepoError> {
+    NaiveDate::from_str(value).map_err(|e| RepoError::Database(e.to_string()))
+}
+
+fn statement_from_row(
+    (
+        id,
+        account_id,
+        period_start,
+        period_end,
+        currency,
+        opening_balance,
+        closing_balance,
+        transaction_count,
+        created_at,
+    ): StatementRow,
+) -> Result<Statement, RepoError> {
+    let uuid = |value: &str| Uuid::parse_str(value).map_err(|e| RepoError::Database(e.to_string()));
+    Ok(Statement {
+        id: StatementId::from_uuid(uuid(&id)?),
+        account_id: AccountId::from_uuid(uuid(&account_id)?),
+        period_start: parse_date(&period_start)?,
+        period_end: parse_date(&period_end)?,
+        currency: parse_currency(&currency)?,
+        opening_balance,
+        closing_balance,
+        transaction_count,
+        created_at: parse_timestamp(&created_at)?,
+    })
+}
+
+/// `(id, url, secret, events, is_active, created_at, timeout_ms,
+/// connect_timeout_ms, signature_algorithm, signing_key, mode)` as stored in
+/// `webhook_endpoints`.
+type WebhookEndpointRow = (
+    String,
+    String,
+    String,
+    String,
+    i32,
+    String,
+    u32,
+    u32,
+    String,
+    String,
+    String,
+);
+
+fn webhook_endpoint_from_row(
+    (
+        id,
+        url,
+        secret,
+        events,
+        is_active,
+        created_at,
+        timeout_ms,
+        connect_timeout_ms,
+        signature_algorithm,
+        signing_key,
+        mode,
+    ): WebhookEndpointRow,
+) -> Result<payments_types::WebhookEndpoint, RepoError> {
+    let signature_algorithm: WebhookSignatureAlgorithm = signature_algorithm
+        .parse()
+        .map_err(|e: DomainError| RepoError::Database(e.to_string()))?;
+    Ok(payments_types::WebhookEndpoint {
+        id: Uuid::parse_str(&id).map_err(|e| RepoError::Database(e.to_string()))?,
+        url,
+        public_key: crate::security::webhook_public_key(signature_algorithm, &signing_key),
+        secret,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        is_active: is_active == 1,
+        created_at: parse_timestamp(&created_at)?,
+        timeouts: WebhookTimeouts {
+            timeout_ms,
+            connect_timeout_ms,
+        },
+        signature_algorithm,
+        signing_key,
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
+
+/// A due webhook event joined with its endpoint's columns, all `NULL` when
+/// the endpoint has been deleted.
+#[derive(sqlx::FromRow)]
+struct DbDueWebhook {
+    #[sqlx(flatten)]
+    event: crate::types::DbWebhookEvent,
+    endpoint_url: Option<String>,
+    endpoint_secret: Option<String>,
+    endpoint_events: Option<String>,
+    endpoint_is_active: Option<i32>,
+    endpoint_created_at: Option<String>,
+    endpoint_timeout_ms: Option<u32>,
+    endpoint_connect_timeout_ms: Option<u32>,
+    endpoint_signature_algorithm: Option<String>,
+    endpoint_signing_key: Option<String>,
+}
+
+impl DbDueWebhook {
+    fn into_domain(
+        self,
+    ) -> Result<(WebhookEvent, Option<payments_types::WebhookEndpoint>), RepoError> {
+        let endpoint = match (
+            self.endpoint_url,
+            self.endpoint_secret,
+            self.endpoint_events,
+            self.endpoint_is_active,
+            self.endpoint_created_at,
+            self.endpoint_timeout_ms,
+            self.endpoint_connect_timeout_ms,
+            self.endpoint_signature_algorithm,
+            self.endpoint_signing_key,
+        ) {
+            (
+                Some(url),
+                Some(secret),
+                Some(events),
+                Some(is_active),
+                Some(created_at),
+                Some(timeout_ms),
+                Some(connect_timeout_ms),
+                Some(signature_algorithm),
+                Some(signing_key),
+            ) => Some(webhook_endpoint_from_row((
+                self.event.endpoint_id.clone(),
+                url,
+                secret,
+                events,
+                is_active,
+                created_at,
+                timeout_ms,
+                connect_timeout_ms,
+                signature_algorithm,
+                signing_key,
+                self.event.mode.clone(),
+            ))?),
+            _ => None,
+        };
+        Ok((self.event.into_domain()?, endpoint))
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DbApiKey {
+    id: String,
+    name: String,
+    key_hash: String,
+    account_id: Option<String>,
+    owner_id: Option<String>,
+    scopes: String,
+    is_active: bool,
+    created_at: String,
+    last_used_at: Option<String>,
+    deactivated_at: Option<String>,
+    deactivated_by: Option<String>,
+    rate_limit_per_minute: Option<i64>,
+    mode: String,
+}
+
+impl DbApiKey {
+    fn into_domain(self) -> Result<payments_types::ApiKey, RepoError> {
+        let uuid =
+            |value: &str| Uuid::parse_str(value).map_err(|e| RepoError::Database(e.to_string()));
+        Ok(payments_types::ApiKey {
+            id: payments_types::ApiKeyId::from_uuid(uuid(&self.id)?),
+            name: self.name,
+            key_hash: self.key_hash,
+            account_id: self
+                .account_id
+                .as_deref()
+                .map(uuid)
+                .transpose()?
+                .map(AccountId::from_uuid),
+            owner_id: self
+                .owner_id
+                .as_deref()
+                .map(uuid)
+                .transpose()?
+                .map(OwnerId::from_uuid),
+            scopes: crate::types::parse_scopes(&self.scopes)?,
+            is_active: self.is_active,
+            created_at: parse_timestamp(&self.created_at)?,
+            last_used_at: self
+                .last_used_at
+                .as_deref()
+                .map(parse_timestamp)
+                .transpose()?,
+            deactivated_at: self
+                .deactivated_at
+                .as_deref()
+                .map(parse_timestamp)
+                .transpose()?,
+            deactivated_by: self
+                .deactivated_by
+                .as_deref()
+                .map(uuid)
+                .transpose()?
+                .map(payments_types::ApiKeyId::from_uuid),
+            rate_limit_per_minute: self
+                .rate_limit_per_minute
+                .map(u32::try_from)
+                .transpose()
+                .map_err(|e| RepoError::Database(e.to_string()))?,
+            mode: self.mode.parse().map_err(RepoError::Database)?,
         })
     }
 }
 
+/// `(api_key_id, action, actor_id, occurred_at)` as stored in `audit_log`.
+type AuditRow = (String, String, Option<String>, String);
+
+fn audit_entry_from_row(
+    (api_key_id, action, actor_id, occurred_at): AuditRow,
+) -> Result<ApiKeyAuditEntry, RepoError> {
+    let uuid = |s: &str| Uuid::parse_str(s).map_err(|e| RepoError::Database(e.to_string()));
+    Ok(ApiKeyAuditEntry {
+        api_key_id: ApiKeyId::from_uuid(uuid(&api_key_id)?),
+        action: action.parse().map_err(RepoError::Database)?,
+        actor: actor_id
+            .as_deref()
+            .map(uuid)
+            .transpose()?
+            .map(ApiKeyId::from_uuid),
+        at: parse_timestamp(&occurred_at)?,
+    })
+}
+
+/// Fixed-width UTC timestamp that orders correctly as text.
+type LoggedEventRow = (i64, String, String, String, String, String);
+
+fn logged_event_from_row(
+    (sequence, event_type, payload, occurred_at, recorded_at, mode): LoggedEventRow,
+) -> Result<LoggedEvent, RepoError> {
+    Ok(LoggedEvent {
+        sequence,
+        event_type,
+        occurred_at: parse_timestamp(&occurred_at)?,
+        recorded_at: parse_timestamp(&recorded_at)?,
+        data: serde_json::from_str(&payload).map_err(|e| RepoError::Database(e.to_string()))?,
+        mode: mode.parse().map_err(RepoError::Database)?,
+    })
+}
+
+fn sortable_timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, RepoError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| RepoError::Database(e.to_string()))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Webhook Extension (Internal)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -734,38 +4463,76 @@ impl TransactionRepository for SqliteRepo {
 // Webhook Extension (Internal)
 // ─────────────────────────────────────────────────────────────────────────────
 impl SqliteRepo {
+    /// Returns up to `limit` pending or failed events whose next attempt is
+    /// due, oldest first.
     pub async fn get_pending_webhooks(&self, limit: i64) -> Result<Vec<WebhookEvent>, RepoError> {
         let rows = sqlx::query_as::<_, crate::types::DbWebhookEvent>(
             r#"
-            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error
+            SELECT id, endpoint_id, event_type, payload, status, created_at, processed_at, attempts, last_error, next_attempt_at, delivery_sequence, mode
             FROM webhook_events
-            WHERE status = 'PENDING'
+            WHERE status IN ('PENDING', 'FAILED') AND next_attempt_at <= ?
             ORDER BY created_at ASC
             LIMIT ?
             "#,
         )
+        .bind(sortable_timestamp(self.clock.now()))
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
 
         rows.into_iter().map(|row| row.into_domain()).collect()
     }
 
+    /// Like [`get_pending_webhooks`](Self::get_pending_webhooks), with the
+    /// endpoint each event is for, or `None` when it has been deleted.
+    pub async fn get_due_webhook_deliveries(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<(WebhookEvent, Option<payments_types::WebhookEndpoint>)>, RepoError> {
+        let rows = sqlx::query_as::<_, DbDueWebhook>(
+            r#"
+            SELECT w.id, w.endpoint_id, w.event_type, w.payload, w.status, w.created_at, w.processed_at,
+                   w.attempts, w.last_error, w.next_attempt_at, w.delivery_sequence, w.mode,
+                   e.url AS endpoint_url, e.secret AS endpoint_secret, e.events AS endpoint_events,
+                   e.is_active AS endpoint_is_active, e.created_at AS endpoint_created_at,
+                   e.timeout_ms AS endpoint_timeout_ms,
+                   e.connect_timeout_ms AS endpoint_connect_timeout_ms,
+                   e.signature_algorithm AS endpoint_signature_algorithm,
+                   e.signing_key AS endpoint_signing_key
+            FROM webhook_events w
+            LEFT JOIN webhook_endpoints e ON e.id = w.endpoint_id
+            WHERE w.status IN ('PENDING', 'FAILED') AND w.next_attempt_at <= ?
+            ORDER BY w.created_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(sortable_timestamp(self.clock.now()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(DbDueWebhook::into_domain).collect()
+    }
+
+    /// Records an attempt with a final outcome; the event is not picked up
+    /// again.
     pub async fn update_webhook_status(
         &self,
         id: Uuid,
         status: WebhookStatus,
         last_error: Option<String>,
     ) -> Result<(), RepoError> {
-        let now = chrono::Utc::now().to_rfc3339();
+        let now = self.clock.now().to_rfc3339();
         let status_str = status.to_string();
         let id_str = id.to_string();
 
         sqlx::query(
             r#"
             UPDATE webhook_events
-            SET status = ?, processed_at = ?, last_error = ?, attempts = attempts + 1
+            SET status = ?, processed_at = ?, last_error = ?, attempts = attempts + 1,
+                next_attempt_at = NULL
             WHERE id = ?
             "#,
         )
@@ -775,7 +4542,36 @@ impl SqliteRepo {
         .bind(id_str)
         .execute(&self.pool)
         .await
-        .map_err(|e| RepoError::Database(e.to_string()))?;
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    /// Records a failed attempt and makes the event due again after `delay`.
+    pub async fn reschedule_webhook(
+        &self,
+        id: Uuid,
+        last_error: String,
+        delay: std::time::Duration,
+    ) -> Result<(), RepoError> {
+        let now = self.clock.now();
+        let next_attempt_at = now + retry_delay(delay)?;
+
+        sqlx::query(
+            r#"
+            UPDATE webhook_events
+            SET status = 'FAILED', processed_at = ?, last_error = ?, attempts = attempts + 1,
+                next_attempt_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(now.to_rfc3339())
+        .bind(last_error)
+        .bind(sortable_timestamp(next_attempt_at))
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
 
         Ok(())
     }
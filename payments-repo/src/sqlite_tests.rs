@@ -2,9 +2,26 @@
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use chrono::{Duration, NaiveDate, TimeZone, Utc};
     use payments_types::{
-        AccountId, CreateAccountRequest, CurrencyCode, DepositRequest, DomainError, RepoError,
-        TransactionRepository, TransferRequest, WebhookEndpointId, WithdrawRequest,
+        AccountAlias, AccountId, AccountLimits, AccountStatus, Alias, ApiKeyAuditAction,
+        ApiKeyAuditEntry, ApiKeyId, ApiKeyScope, ApiKeyUsageBucket, ApiKeyVolumeBucket,
+        Beneficiary, BeneficiaryId, BeneficiaryStatus, ChangeAction, ChangeRequest, ChangeStatus,
+        Counterparty, CreateAccountRequest, CurrencyBalance, CurrencyCode, CurrencyTotal,
+        DailyBalance, DeadLetterFilter, DepositRequest, DomainError, DomainEvent, DynMoney,
+        EncryptedMemo, Export, ExportId, ExportRequest, ExportStatus, FeeScheduleId, FeeTier,
+        FixedClock, FloatPosition, FxConversion, Hold, HoldId, HoldStatus, IdempotencyRecord,
+        JournalExportFormat, LedgerOperation, MemoKey, MemoKeyId, Mode, NewWebhookEndpoint, Owner,
+        OwnerId, PaymentSchedule, PositiveAmount, Quote, QuoteId, RateSnapshot, RepoError,
+        RiskAssessment, RiskDecision, ScheduledPayment, ScheduledPaymentId, ScheduledPaymentStatus,
+        SettlementBatchStatus, SpendingRules, Statement, StatementId, StatementPeriod, Transaction,
+        TransactionCursor, TransactionFilter, TransactionId, TransactionRepository,
+        TransactionSearch, TransactionType, TransferRequest, WebhookDeliveryFilter,
+        WebhookEndpointId, WebhookEvent, WebhookSignatureAlgorithm, WebhookStatus, WebhookTimeouts,
+        WithdrawRequest,
     };
 
     use uuid::Uuid;
@@ -15,6 +32,47 @@ mod tests {
         SqliteRepo::new("sqlite::memory:").await.unwrap()
     }
 
+    /// A repository over a database file, removed again on drop.
+    ///
+    /// In-memory connections share one cache and queue up for locks, so
+    /// tests of concurrent writers need a file to see real lock contention.
+    struct FileRepo {
+        repo: Arc<SqliteRepo>,
+        path: std::path::PathBuf,
+    }
+
+    impl FileRepo {
+        async fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("payments-{}.db", Uuid::new_v4()));
+            let repo = SqliteRepo::new(&format!("sqlite://{}", path.display()))
+                .await
+                .unwrap();
+            Self {
+                repo: Arc::new(repo),
+                path,
+            }
+        }
+    }
+
+    impl Drop for FileRepo {
+        fn drop(&mut self) {
+            for suffix in ["", "-wal", "-shm"] {
+                let mut path = self.path.clone().into_os_string();
+                path.push(suffix);
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    async fn balance(repo: &SqliteRepo, account_id: AccountId) -> i64 {
+        repo.get_account(account_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .balance
+            .amount()
+    }
+
     #[tokio::test]
     async fn test_create_account() {
         let repo = setup_repo().await;
@@ -22,6 +80,9 @@ mod tests {
         let req = CreateAccountRequest {
             name: "Test Account".to_string(),
             currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
         };
 
         let account = repo.create_account(req).await.unwrap();
@@ -38,6 +99,9 @@ mod tests {
         let req = CreateAccountRequest {
             name: "Test".to_string(),
             currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
         };
         let created = repo.create_account(req).await.unwrap();
 
@@ -63,6 +127,9 @@ mod tests {
         repo.create_account(CreateAccountRequest {
             name: "Alice".to_string(),
             currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
         })
         .await
         .unwrap();
@@ -70,15 +137,142 @@ mod tests {
         repo.create_account(CreateAccountRequest {
             name: "Bob".to_string(),
             currency: CurrencyCode::EUR,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
         })
         .await
         .unwrap();
 
-        let accounts = repo.list_accounts().await.unwrap();
+        let accounts = repo.list_accounts(None).await.unwrap();
 
         assert_eq!(accounts.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_search_accounts() {
+        let repo = setup_repo().await;
+        let mut ids = Vec::new();
+        for name in ["Alice Smith", "bob smithers", "Carol 100%"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        let names = |accounts: Vec<payments_types::Account>| {
+            accounts.into_iter().map(|a| a.name).collect::<Vec<_>>()
+        };
+
+        let found = repo.search_accounts("SMITH", 10, Mode::Live).await.unwrap();
+        assert_eq!(names(found), vec!["Alice Smith", "bob smithers"]);
+
+        let found = repo.search_accounts("SMITH", 1, Mode::Live).await.unwrap();
+        assert_eq!(names(found), vec!["Alice Smith"]);
+
+        // LIKE wildcards in the query match literally
+        let found = repo.search_accounts("0%", 10, Mode::Live).await.unwrap();
+        assert_eq!(names(found), vec!["Carol 100%"]);
+        assert!(
+            repo.search_accounts("_", 10, Mode::Live)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let prefix = ids[1].to_string()[..8].to_uppercase();
+        let found = repo.search_accounts(&prefix, 10, Mode::Live).await.unwrap();
+        assert!(found.iter().any(|a| a.id == ids[1]));
+    }
+
+    #[tokio::test]
+    async fn test_accounts_keys_and_searches_keep_to_their_mode() {
+        let repo = setup_repo().await;
+        let account = |name: &str, mode| CreateAccountRequest {
+            name: name.to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode,
+        };
+        let live = repo.create_account(account("Live Co", None)).await.unwrap();
+        let test = repo
+            .create_account(account("Test Co", Some(Mode::Test)))
+            .await
+            .unwrap();
+        assert_eq!(live.mode, Mode::Live);
+        assert_eq!(
+            repo.get_account(test.id).await.unwrap().unwrap().mode,
+            Mode::Test
+        );
+
+        let found = repo.search_accounts("co", 10, Mode::Test).await.unwrap();
+        assert_eq!(
+            found.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![test.id]
+        );
+        let listed = repo.list_accounts(Some(Mode::Test)).await.unwrap();
+        assert_eq!(
+            listed.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![test.id]
+        );
+        let held = repo
+            .list_owner_accounts(OwnerId::DEFAULT, Some(Mode::Live))
+            .await
+            .unwrap();
+        assert_eq!(held.iter().map(|a| a.id).collect::<Vec<_>>(), vec![live.id]);
+        assert_eq!(repo.list_accounts(None).await.unwrap().len(), 2);
+
+        for (account_id, amount) in [(live.id, 100), (test.id, 200)] {
+            repo.deposit(DepositRequest {
+                account_id,
+                amount: PositiveAmount::new(amount).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: Some("mode".to_string()),
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        }
+        for (mode, expected) in [(Mode::Live, 100), (Mode::Test, 200)] {
+            let search = TransactionSearch {
+                reference: Some("mode".into()),
+                mode: Some(mode),
+                ..Default::default()
+            };
+            let found = repo.search_transactions(&search, None, 10).await.unwrap();
+            assert_eq!(
+                found
+                    .iter()
+                    .map(|tx| tx.amount.amount())
+                    .collect::<Vec<_>>(),
+                vec![expected]
+            );
+        }
+
+        let (api_key, raw_key) = repo
+            .create_api_key("sandbox", &ApiKeyScope::ALL, Mode::Test)
+            .await
+            .unwrap();
+        assert!(raw_key.starts_with("sk_test_"));
+        assert_eq!(api_key.mode, Mode::Test);
+        let verified = repo
+            .verify_api_key_hash(&crate::security::hash_api_key(&raw_key))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(verified.mode, Mode::Test);
+    }
+
     #[tokio::test]
     async fn test_deposit() {
         let repo = setup_repo().await;
@@ -87,6 +281,9 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Test".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
@@ -94,10 +291,13 @@ mod tests {
         let tx = repo
             .deposit(DepositRequest {
                 account_id: account.id,
-                amount: 1000,
+                amount: PositiveAmount::new(1000).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: Some("Initial deposit".to_string()),
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await
             .unwrap();
@@ -115,10 +315,13 @@ mod tests {
         let result = repo
             .deposit(DepositRequest {
                 account_id: AccountId::new(),
-                amount: 1000,
+                amount: PositiveAmount::new(1000).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await;
 
@@ -133,16 +336,22 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Test".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
 
         repo.deposit(DepositRequest {
             account_id: account.id,
-            amount: 1000,
+            amount: PositiveAmount::new(1000).unwrap(),
             currency: CurrencyCode::USD,
             idempotency_key: None,
             reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         })
         .await
         .unwrap();
@@ -150,10 +359,14 @@ mod tests {
         let tx = repo
             .withdraw(WithdrawRequest {
                 account_id: account.id,
-                amount: 300,
+                amount: PositiveAmount::new(300).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await
             .unwrap();
@@ -172,16 +385,22 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Test".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
 
         repo.deposit(DepositRequest {
             account_id: account.id,
-            amount: 100,
+            amount: PositiveAmount::new(100).unwrap(),
             currency: CurrencyCode::USD,
             idempotency_key: None,
             reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         })
         .await
         .unwrap();
@@ -189,10 +408,14 @@ mod tests {
         let result = repo
             .withdraw(WithdrawRequest {
                 account_id: account.id,
-                amount: 200,
+                amount: PositiveAmount::new(200).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await;
 
@@ -202,6 +425,110 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_withdraw_and_transfer_into_overdraft() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 300,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(account.overdraft_limit, 300);
+        let other = repo
+            .create_account(CreateAccountRequest {
+                name: "Other".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        repo.deposit(DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        let withdraw = |amount| WithdrawRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        let transfer = |amount| TransferRequest {
+            from_account_id: account.id,
+            to_account_id: other.id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        repo.withdraw(withdraw(250)).await.unwrap();
+        assert_eq!(balance(&repo, account.id).await, -150);
+
+        let result = repo.transfer(transfer(250)).await;
+        assert!(matches!(
+            result,
+            Err(RepoError::Domain(DomainError::InsufficientFunds { .. }))
+        ));
+        repo.transfer(transfer(150)).await.unwrap();
+        assert_eq!(balance(&repo, account.id).await, -300);
+        assert_eq!(balance(&repo, other.id).await, 150);
+    }
+
+    #[tokio::test]
+    async fn test_set_overdraft_limit() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+
+        let updated = repo
+            .set_overdraft_limit(account.id, 1000)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.overdraft_limit, 1000);
+        let stored = repo.get_account(account.id).await.unwrap().unwrap();
+        assert_eq!(stored.overdraft_limit, 1000);
+
+        let missing = repo
+            .set_overdraft_limit(AccountId::new(), 1000)
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+    }
+
     #[tokio::test]
     async fn test_transfer() {
         let repo = setup_repo().await;
@@ -210,6 +537,9 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Alice".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
@@ -218,16 +548,22 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Bob".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
 
         repo.deposit(DepositRequest {
             account_id: alice.id,
-            amount: 1000,
+            amount: PositiveAmount::new(1000).unwrap(),
             currency: CurrencyCode::USD,
             idempotency_key: None,
             reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         })
         .await
         .unwrap();
@@ -236,10 +572,15 @@ mod tests {
             .transfer(TransferRequest {
                 from_account_id: alice.id,
                 to_account_id: bob.id,
-                amount: 400,
+                amount: PositiveAmount::new(400).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: None,
+                purpose_code: None,
+                convert_currency: false,
+                quote_id: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await
             .unwrap();
@@ -261,6 +602,9 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Alice".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
@@ -269,16 +613,22 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Bob".to_string(),
                 currency: CurrencyCode::EUR,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
 
         repo.deposit(DepositRequest {
             account_id: alice.id,
-            amount: 1000,
+            amount: PositiveAmount::new(1000).unwrap(),
             currency: CurrencyCode::USD,
             idempotency_key: None,
             reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         })
         .await
         .unwrap();
@@ -287,10 +637,15 @@ mod tests {
             .transfer(TransferRequest {
                 from_account_id: alice.id,
                 to_account_id: bob.id,
-                amount: 400,
+                amount: PositiveAmount::new(400).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: None,
                 reference: None,
+                purpose_code: None,
+                convert_currency: false,
+                quote_id: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await;
 
@@ -300,6 +655,89 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_transfer_with_conversion_credits_converted_amount() {
+        let repo = setup_repo().await;
+
+        let alice = repo
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let bob = repo
+            .create_account(CreateAccountRequest {
+                name: "Bob".to_string(),
+                currency: CurrencyCode::EUR,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        repo.deposit(DepositRequest {
+            account_id: alice.id,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        let request = |currency| TransferRequest {
+            from_account_id: alice.id,
+            to_account_id: bob.id,
+            amount: PositiveAmount::new(400).unwrap(),
+            currency,
+            idempotency_key: Some("fx-1".into()),
+            reference: None,
+            purpose_code: None,
+            convert_currency: true,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        let amount = DynMoney::new(400, CurrencyCode::USD).unwrap();
+        let conversion = FxConversion::at_rate(amount, CurrencyCode::EUR, 0.9).unwrap();
+        let tx = repo
+            .transfer_with_conversion(request(CurrencyCode::USD), conversion)
+            .await
+            .unwrap();
+        assert_eq!(tx.conversion, Some(conversion));
+
+        assert_eq!(balance(&repo, alice.id).await, 600);
+        assert_eq!(balance(&repo, bob.id).await, 360);
+
+        let stored = repo.get_transaction(tx.id).await.unwrap().unwrap();
+        assert_eq!(stored.conversion, Some(conversion));
+        assert_eq!(stored.signed_amount_for(bob.id), 360);
+
+        // Replays return the recorded conversion without moving money again
+        let replay = repo
+            .transfer_with_conversion(request(CurrencyCode::USD), conversion)
+            .await
+            .unwrap();
+        assert_eq!(replay.id, tx.id);
+        assert_eq!(balance(&repo, bob.id).await, 360);
+
+        let mut wrong = request(CurrencyCode::USD);
+        wrong.idempotency_key = None;
+        let to_gbp = FxConversion::at_rate(amount, CurrencyCode::GBP, 0.8).unwrap();
+        assert!(matches!(
+            repo.transfer_with_conversion(wrong, to_gbp).await,
+            Err(RepoError::Domain(DomainError::CurrencyMismatch { .. }))
+        ));
+        assert_eq!(balance(&repo, alice.id).await, 600);
+    }
+
     #[tokio::test]
     async fn test_idempotency_deposit() {
         let repo = setup_repo().await;
@@ -308,6 +746,9 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Test".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
@@ -318,10 +759,13 @@ mod tests {
         let _tx1 = repo
             .deposit(DepositRequest {
                 account_id: account.id,
-                amount: 1000,
+                amount: PositiveAmount::new(1000).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: Some(key.clone()),
                 reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await
             .unwrap();
@@ -330,10 +774,13 @@ mod tests {
         let tx2 = repo
             .deposit(DepositRequest {
                 account_id: account.id,
-                amount: 1000,
+                amount: PositiveAmount::new(1000).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: Some(key.clone()),
                 reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await
             .unwrap();
@@ -356,6 +803,9 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Test Mismatch".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
@@ -365,10 +815,13 @@ mod tests {
         // 1. Initial Deposit $10
         repo.deposit(DepositRequest {
             account_id: account.id,
-            amount: 1000,
+            amount: PositiveAmount::new(1000).unwrap(),
             currency: CurrencyCode::USD,
             idempotency_key: Some(key.clone()),
             reference: Some("Initial".to_string()),
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         })
         .await
         .unwrap();
@@ -378,10 +831,13 @@ mod tests {
         let result = repo
             .deposit(DepositRequest {
                 account_id: account.id,
-                amount: 2000,
+                amount: PositiveAmount::new(2000).unwrap(),
                 currency: CurrencyCode::USD,
                 idempotency_key: Some(key.clone()),
                 reference: Some("Changed Amount".to_string()),
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
             })
             .await;
 
@@ -405,6 +861,373 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_unit_of_work_is_all_or_nothing() {
+        let repo = setup_repo().await;
+        let mut ids = Vec::new();
+        for name in ["Alice", "Bob"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        let (alice, bob) = (ids[0], ids[1]);
+        let deposit = LedgerOperation::Deposit(DepositRequest {
+            account_id: alice,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        });
+        let transfer = |amount| {
+            LedgerOperation::Transfer(
+                TransferRequest {
+                    from_account_id: alice,
+                    to_account_id: bob,
+                    amount: PositiveAmount::new(amount).unwrap(),
+                    currency: CurrencyCode::USD,
+                    idempotency_key: None,
+                    reference: None,
+                    purpose_code: None,
+                    convert_currency: false,
+                    quote_id: None,
+                    metadata: Default::default(),
+                    encrypted_memo: None,
+                },
+                None,
+            )
+        };
+
+        let mut work = repo.begin().await.unwrap();
+        work.apply(deposit.clone()).await.unwrap();
+        let made = work.apply(transfer(400)).await.unwrap();
+        assert_eq!(made.transaction_type, TransactionType::Transfer);
+        work.commit().await.unwrap();
+        assert_eq!(balance(&repo, alice).await, 600);
+        assert_eq!(balance(&repo, bob).await, 400);
+
+        let endpoint = repo
+            .register_webhook_endpoint(
+                "https://example.com/hook",
+                vec![],
+                WebhookTimeouts::default(),
+                WebhookSignatureAlgorithm::default(),
+            )
+            .await
+            .unwrap();
+        let mut work = repo.begin().await.unwrap();
+        let made = work.apply(deposit).await.unwrap();
+        let event = work
+            .create_webhook_event(
+                WebhookEndpointId::from_uuid(endpoint.id),
+                "deposit.success",
+                serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+        let failed = work.apply(transfer(5000)).await;
+        assert!(matches!(
+            failed,
+            Err(RepoError::Domain(DomainError::InsufficientFunds { .. }))
+        ));
+        drop(work);
+
+        assert_eq!(balance(&repo, alice).await, 600);
+        assert_eq!(balance(&repo, bob).await, 400);
+        assert!(repo.get_transaction(made.id).await.unwrap().is_none());
+        assert!(repo.get_webhook_event(event.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unit_of_work_closes_merged_account() {
+        let repo = setup_repo().await;
+        let mut ids = Vec::new();
+        for name in ["Alice", "Alice again"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        let (kept, duplicate) = (ids[0], ids[1]);
+        repo.deposit(DepositRequest {
+            account_id: duplicate,
+            amount: PositiveAmount::new(500).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+
+        // An account still holding money is not closed.
+        let mut work = repo.begin().await.unwrap();
+        assert!(
+            work.close_merged_account(duplicate, kept, now)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        drop(work);
+
+        let mut work = repo.begin().await.unwrap();
+        work.apply(LedgerOperation::Transfer(
+            TransferRequest {
+                from_account_id: duplicate,
+                to_account_id: kept,
+                amount: PositiveAmount::new(500).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                purpose_code: None,
+                convert_currency: false,
+                quote_id: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            },
+            None,
+        ))
+        .await
+        .unwrap();
+        let closed = work
+            .close_merged_account(duplicate, kept, now)
+            .await
+            .unwrap()
+            .expect("account was emptied");
+        assert_eq!(closed.status, AccountStatus::Closed);
+        assert_eq!(closed.merged_into, Some(kept));
+        work.commit().await.unwrap();
+
+        let stored = repo.get_account(duplicate).await.unwrap().unwrap();
+        assert_eq!(stored.status, AccountStatus::Closed);
+        assert_eq!(stored.status_changed_at, Some(now));
+        assert_eq!(stored.merged_into, Some(kept));
+        assert_eq!(balance(&repo, kept).await, 500);
+        assert_eq!(
+            repo.get_account(kept).await.unwrap().unwrap().merged_into,
+            None
+        );
+
+        let mut work = repo.begin().await.unwrap();
+        assert!(
+            work.close_merged_account(duplicate, kept, now)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unit_of_work_sums_debits_since_a_time() {
+        let repo = setup_repo().await;
+        let mut ids = Vec::new();
+        for name in ["Spender", "Payee"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.into(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        let (spender, payee) = (ids[0], ids[1]);
+        let since = Utc::now() - Duration::hours(1);
+        repo.deposit(DepositRequest {
+            account_id: spender,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        let withdraw = |amount| WithdrawRequest {
+            account_id: spender,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        repo.withdraw(withdraw(300)).await.unwrap();
+        repo.transfer(TransferRequest {
+            from_account_id: spender,
+            to_account_id: payee,
+            amount: PositiveAmount::new(150).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        // Deposits and incoming transfers are not debits; staged ones are.
+        let mut work = repo.begin().await.unwrap();
+        assert_eq!(work.debited_since(spender, since).await.unwrap(), 450);
+        assert_eq!(work.debited_since(payee, since).await.unwrap(), 0);
+        work.apply(LedgerOperation::Withdraw(withdraw(50)))
+            .await
+            .unwrap();
+        assert_eq!(work.debited_since(spender, since).await.unwrap(), 500);
+
+        // So is money held for a capture still to come
+        let now = Utc::now();
+        work.create_hold(&Hold {
+            id: HoldId::new(),
+            account_id: spender,
+            amount: 120,
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            status: HoldStatus::Authorized,
+            expires_at: now + Duration::hours(1),
+            captured_amount: None,
+            transaction_id: None,
+            created_at: now,
+            resolved_at: None,
+        })
+        .await
+        .unwrap();
+        assert_eq!(work.debited_since(spender, since).await.unwrap(), 620);
+        assert_eq!(
+            work.debited_since(spender, Utc::now() + Duration::hours(1))
+                .await
+                .unwrap(),
+            0
+        );
+
+        // Every transaction counts, whichever side the account was on
+        assert_eq!(work.transactions_since(spender, since).await.unwrap(), 4);
+        assert_eq!(work.transactions_since(payee, since).await.unwrap(), 1);
+        assert_eq!(
+            work.transactions_since(spender, Utc::now() + Duration::hours(1))
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unit_of_work_captures_hold_with_its_webhook_events() {
+        let repo = setup_repo().await;
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Card".into(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        repo.deposit(DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        let hold = Hold {
+            id: HoldId::new(),
+            account_id: account.id,
+            amount: 600,
+            currency: CurrencyCode::USD,
+            reference: Some("Hotel".into()),
+            purpose_code: None,
+            status: HoldStatus::Authorized,
+            expires_at: now + Duration::hours(1),
+            captured_amount: None,
+            transaction_id: None,
+            created_at: now,
+            resolved_at: None,
+        };
+        repo.create_hold(&hold).await.unwrap();
+        let endpoint = repo
+            .register_webhook_endpoint(
+                "https://example.com/hook",
+                vec![],
+                WebhookTimeouts::default(),
+                WebhookSignatureAlgorithm::default(),
+            )
+            .await
+            .unwrap();
+        let endpoint_id = WebhookEndpointId::from_uuid(endpoint.id);
+
+        // Dropped uncommitted, neither the capture nor its event is kept.
+        let mut work = repo.begin().await.unwrap();
+        let (_, tx) = work.capture_hold(hold.id, 400, now).await.unwrap();
+        let event = work
+            .create_webhook_event(endpoint_id, "hold.captured", serde_json::json!({}))
+            .await
+            .unwrap();
+        drop(work);
+        assert_eq!(repo.get_hold(hold.id).await.unwrap(), Some(hold.clone()));
+        assert!(repo.get_transaction(tx.id).await.unwrap().is_none());
+        assert!(repo.get_webhook_event(event.id).await.unwrap().is_none());
+
+        let mut work = repo.begin().await.unwrap();
+        let (captured, tx) = work.capture_hold(hold.id, 400, now).await.unwrap();
+        let event = work
+            .create_webhook_event(endpoint_id, "hold.captured", serde_json::json!({}))
+            .await
+            .unwrap();
+        work.commit().await.unwrap();
+        assert_eq!(captured.status, HoldStatus::Captured);
+        assert_eq!(repo.get_hold(hold.id).await.unwrap(), Some(captured));
+        assert!(repo.get_transaction(tx.id).await.unwrap().is_some());
+        assert_eq!(
+            repo.get_webhook_event(event.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .status,
+            WebhookStatus::Pending
+        );
+        let after = repo.get_account(account.id).await.unwrap().unwrap();
+        assert_eq!((after.balance.amount(), after.held), (600, 0));
+    }
+
     #[tokio::test]
     async fn test_list_transactions_for_account() {
         let repo = setup_repo().await;
@@ -413,26 +1236,36 @@ mod tests {
             .create_account(CreateAccountRequest {
                 name: "Test".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
 
         repo.deposit(DepositRequest {
             account_id: account.id,
-            amount: 1000,
+            amount: PositiveAmount::new(1000).unwrap(),
             currency: CurrencyCode::USD,
             idempotency_key: None,
             reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         })
         .await
         .unwrap();
 
         repo.withdraw(WithdrawRequest {
             account_id: account.id,
-            amount: 200,
+            amount: PositiveAmount::new(200).unwrap(),
             currency: CurrencyCode::USD,
             idempotency_key: None,
             reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
         })
         .await
         .unwrap();
@@ -446,51 +1279,2769 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_webhook_generation() {
+    async fn test_list_transactions_page() {
         let repo = setup_repo().await;
-
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let repo = repo.with_clock(clock.clone());
         let account = repo
             .create_account(CreateAccountRequest {
-                name: "Webhook Test".to_string(),
+                name: "Pages".to_string(),
                 currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
             })
             .await
             .unwrap();
-
-        // Manually create a webhook event (simulating PaymentService behavior)
-        let endpoint_id = WebhookEndpointId(Uuid::new_v4());
-        let payload = serde_json::json!({
-            "type": "DEPOSIT",
+        // Two deposits share a timestamp, so paging has to break the tie by ID.
+        for amount in [100, 200] {
+            repo.deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(amount).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        }
+        clock.advance(Duration::seconds(1));
+        repo.deposit(DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(300).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        clock.advance(Duration::milliseconds(1500));
+        repo.withdraw(WithdrawRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(50).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        let all = TransactionFilter::default();
+        let amounts =
+            |txs: &[Transaction]| txs.iter().map(|tx| tx.amount.amount()).collect::<Vec<_>>();
+        let first = repo
+            .list_transactions_page(account.id, &all, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(amounts(&first), vec![50, 300]);
+        let cursor = TransactionCursor::after(&first[1]);
+        let second = repo
+            .list_transactions_page(account.id, &all, Some(cursor), 2)
+            .await
+            .unwrap();
+        let mut tied = amounts(&second);
+        tied.sort();
+        assert_eq!(tied, vec![100, 200]);
+        assert!(second[0].id.into_uuid() > second[1].id.into_uuid());
+        let cursor = TransactionCursor::after(&second[1]);
+        assert!(
+            repo.list_transactions_page(account.id, &all, Some(cursor), 2)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let large_deposits = TransactionFilter {
+            transaction_type: Some(TransactionType::Deposit),
+            min_amount: Some(150),
+            ..Default::default()
+        };
+        let page = repo
+            .list_transactions_page(account.id, &large_deposits, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(amounts(&page), vec![300, 200]);
+        let recent = TransactionFilter {
+            from: Some(start + Duration::seconds(1)),
+            to: Some(start + Duration::seconds(3)),
+            max_amount: Some(1000),
+            ..Default::default()
+        };
+        let page = repo
+            .list_transactions_page(account.id, &recent, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(amounts(&page), vec![50, 300]);
+    }
+
+    #[tokio::test]
+    async fn test_search_transactions_across_accounts() {
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let repo = setup_repo().await.with_clock(clock.clone());
+        let account = |name: &str, currency| CreateAccountRequest {
+            name: name.to_string(),
+            currency,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+        let alice = repo
+            .create_account(account("Alice", CurrencyCode::USD))
+            .await
+            .unwrap();
+        let bob = repo
+            .create_account(account("Bob", CurrencyCode::USD))
+            .await
+            .unwrap();
+        let carol = repo
+            .create_account(account("Carol", CurrencyCode::EUR))
+            .await
+            .unwrap();
+        let deposit = |account_id, amount, currency, reference: &str| DepositRequest {
+            account_id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency,
+            idempotency_key: None,
+            reference: Some(reference.to_string()),
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        repo.deposit(deposit(alice.id, 1000, CurrencyCode::USD, "INV-2026-0042"))
+            .await
+            .unwrap();
+        clock.advance(Duration::seconds(1));
+        repo.transfer(TransferRequest {
+            from_account_id: alice.id,
+            to_account_id: bob.id,
+            amount: PositiveAmount::new(250).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: Some("inv-2026-0043".to_string()),
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        clock.advance(Duration::seconds(1));
+        repo.deposit(deposit(carol.id, 500, CurrencyCode::EUR, "INV-2026-0044"))
+            .await
+            .unwrap();
+        clock.advance(Duration::seconds(1));
+        repo.deposit(deposit(bob.id, 100, CurrencyCode::USD, "payroll"))
+            .await
+            .unwrap();
+
+        let amounts =
+            |txs: &[Transaction]| txs.iter().map(|tx| tx.amount.amount()).collect::<Vec<_>>();
+        let by_reference = TransactionSearch {
+            reference: Some("INV-2026".into()),
+            ..Default::default()
+        };
+        let first = repo
+            .search_transactions(&by_reference, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(amounts(&first), vec![500, 250]);
+        let cursor = TransactionCursor::after(&first[1]);
+        let second = repo
+            .search_transactions(&by_reference, Some(cursor), 2)
+            .await
+            .unwrap();
+        assert_eq!(amounts(&second), vec![1000]);
+
+        let searches = [
+            (
+                TransactionSearch {
+                    account_id: Some(bob.id),
+                    ..Default::default()
+                },
+                vec![100, 250],
+            ),
+            (
+                TransactionSearch {
+                    reference: Some("inv".into()),
+                    currency: Some(CurrencyCode::USD),
+                    ..Default::default()
+                },
+                vec![250, 1000],
+            ),
+            (
+                TransactionSearch {
+                    filter: TransactionFilter {
+                        transaction_type: Some(TransactionType::Transfer),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                vec![250],
+            ),
+            (
+                TransactionSearch {
+                    filter: TransactionFilter {
+                        min_amount: Some(300),
+                        from: Some(start + Duration::seconds(1)),
+                        to: Some(start + Duration::seconds(3)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                vec![500],
+            ),
+            // LIKE wildcards in the fragment match literally.
+            (
+                TransactionSearch {
+                    reference: Some("INV%44".into()),
+                    ..Default::default()
+                },
+                vec![],
+            ),
+        ];
+        for (search, expected) in searches {
+            let found = repo.search_transactions(&search, None, 10).await.unwrap();
+            assert_eq!(amounts(&found), expected, "{:?}", search);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_round_trips_and_is_searchable() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let deposit = |amount, metadata: &[(&str, &str)]| DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            encrypted_memo: None,
+        };
+        let tx = repo
+            .deposit(deposit(100, &[("order_id", "ord_1"), ("channel", "web")]))
+            .await
+            .unwrap();
+        assert_eq!(tx.metadata["order_id"], "ord_1");
+        repo.deposit(deposit(200, &[("order_id", "ord_2")]))
+            .await
+            .unwrap();
+        repo.deposit(deposit(300, &[])).await.unwrap();
+
+        let stored = repo.get_transaction(tx.id).await.unwrap().unwrap();
+        assert_eq!(stored.metadata, tx.metadata);
+
+        let search = |key: &str, value: Option<&str>| TransactionSearch {
+            metadata_key: Some(key.into()),
+            metadata_value: value.map(Into::into),
+            ..Default::default()
+        };
+        let amounts = |txs: Vec<Transaction>| {
+            let mut amounts: Vec<i64> = txs.iter().map(|tx| tx.amount.amount()).collect();
+            amounts.sort();
+            amounts
+        };
+        let cases = [
+            (search("order_id", None), vec![100, 200]),
+            (search("order_id", Some("ord_2")), vec![200]),
+            (search("channel", Some("web")), vec![100]),
+            (search("missing", None), vec![]),
+        ];
+        for (search, expected) in cases {
+            let found = repo.search_transactions(&search, None, 10).await.unwrap();
+            assert_eq!(amounts(found), expected, "{:?}", search);
+        }
+    }
+
+    /// Rows written by a release with more currencies fail with a typed
+    /// error instead of a generic database one.
+    #[tokio::test]
+    async fn test_unknown_stored_currency_is_unsupported() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        sqlx::query("UPDATE accounts SET currency = 'XTS' WHERE id = ?")
+            .bind(account.id.to_string())
+            .execute(repo.pool())
+            .await
+            .unwrap();
+
+        let result = repo.get_account(account.id).await;
+        assert!(matches!(result, Err(RepoError::UnsupportedCurrency(code)) if code == "XTS"));
+    }
+
+    #[tokio::test]
+    async fn test_counterparty_round_trips() {
+        let repo = setup_repo().await;
+        // The counterparty migration must be safe to re-run on every start.
+        repo.create_schema().await.unwrap();
+
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let payer = Counterparty {
+            name: "ACME Payroll Ltd".to_string(),
+            external_id: None,
+        };
+
+        repo.deposit(DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: Some(payer.clone()),
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        repo.deposit(DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(500).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        let mut counterparties: Vec<_> = repo
+            .list_transactions_for_account(account.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tx| tx.counterparty)
+            .collect();
+        counterparties.sort_by_key(|c| c.is_none());
+
+        assert_eq!(counterparties, vec![Some(payer), None]);
+    }
+
+    #[tokio::test]
+    async fn test_spending_rules_and_purpose_code_round_trip() {
+        let repo = setup_repo().await;
+        // The spending-controls migration must be safe to re-run on every start.
+        repo.create_schema().await.unwrap();
+
+        let mut ids = Vec::new();
+        for name in ["Alice", "Bob"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        assert!(repo.get_spending_rules(ids[0]).await.unwrap().is_empty());
+
+        let rules = SpendingRules {
+            allowed: vec!["PAYROLL".into()],
+            denied: vec!["GAMBLING".into()],
+        };
+        repo.set_spending_rules(ids[0], &rules).await.unwrap();
+        assert_eq!(repo.get_spending_rules(ids[0]).await.unwrap(), rules);
+        assert!(repo.get_spending_rules(ids[1]).await.unwrap().is_empty());
+
+        // Setting replaces the previous rules entirely.
+        repo.set_spending_rules(ids[0], &SpendingRules::default())
+            .await
+            .unwrap();
+        assert!(repo.get_spending_rules(ids[0]).await.unwrap().is_empty());
+
+        repo.deposit(DepositRequest {
+            account_id: ids[0],
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        let tx = repo
+            .transfer(TransferRequest {
+                from_account_id: ids[0],
+                to_account_id: ids[1],
+                amount: PositiveAmount::new(100).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                purpose_code: Some("PAYROLL".into()),
+                convert_currency: false,
+                quote_id: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(tx.purpose_code.as_deref(), Some("PAYROLL"));
+
+        let stored = repo.get_transaction(tx.id).await.unwrap().unwrap();
+        assert_eq!(stored.purpose_code.as_deref(), Some("PAYROLL"));
+    }
+
+    #[tokio::test]
+    async fn test_account_limits_round_trip() {
+        let repo = setup_repo().await;
+        repo.create_schema().await.unwrap();
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Limited".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        assert!(
+            repo.get_account_limits(account.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let limits = AccountLimits {
+            max_transaction_amount: Some(500),
+            max_balance: None,
+            daily_debit_limit: Some(2_000),
+        };
+        repo.set_account_limits(account.id, &limits).await.unwrap();
+        assert_eq!(repo.get_account_limits(account.id).await.unwrap(), limits);
+
+        // Setting replaces every field, including clearing ones left unset.
+        let limits = AccountLimits {
+            max_balance: Some(10_000),
+            ..AccountLimits::default()
+        };
+        repo.set_account_limits(account.id, &limits).await.unwrap();
+        assert_eq!(repo.get_account_limits(account.id).await.unwrap(), limits);
+
+        repo.set_account_limits(account.id, &AccountLimits::default())
+            .await
+            .unwrap();
+        assert!(
+            repo.get_account_limits(account.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fee_schedules_and_assignments_round_trip() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Merchant".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let tiers = vec![
+            FeeTier {
+                up_to: Some(10_000),
+                flat_fee: 25,
+                ..FeeTier::default()
+            },
+            FeeTier {
+                percentage_bps: 150,
+                min_fee: Some(100),
+                max_fee: Some(2_500),
+                ..FeeTier::default()
+            },
+        ];
+        let standard = repo.create_fee_schedule("Standard", &tiers).await.unwrap();
+        assert_eq!(
+            repo.get_fee_schedule(standard.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .tiers,
+            tiers
+        );
+        assert!(
+            repo.get_fee_schedule(FeeScheduleId::new())
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(repo.list_fee_schedules().await.unwrap().len(), 1);
+
+        // Recorded out of order; listed by effective date.
+        let now = Utc::now();
+        let later = repo
+            .assign_fee_schedule(account.id, standard.id, now + Duration::days(30))
+            .await
+            .unwrap();
+        let sooner = repo
+            .assign_fee_schedule(account.id, standard.id, now)
+            .await
+            .unwrap();
+        let history = repo.list_fee_assignments(account.id).await.unwrap();
+        let effective: Vec<_> = history.iter().map(|a| a.effective_from).collect();
+        assert_eq!(effective, vec![sooner.effective_from, later.effective_from]);
+    }
+
+    async fn payout(repo: &SqliteRepo, account_id: AccountId, amount: i64) -> Transaction {
+        repo.withdraw(WithdrawRequest {
+            account_id,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_settlement_batch_lifecycle() {
+        let repo = &setup_repo().await;
+        let account_id = repo
+            .create_account(CreateAccountRequest {
+                name: "Merchant".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap()
+            .id;
+        repo.deposit(DepositRequest {
+            account_id,
+            amount: PositiveAmount::new(1_000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        let before_payouts = Utc::now() - Duration::seconds(1);
+        let first = payout(repo, account_id, 300).await;
+        let second = payout(repo, account_id, 200).await;
+        assert!(
+            repo.create_settlement_batch(before_payouts, Mode::Live)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // The payouts are live, so a test batch has none.
+        assert!(
+            repo.create_settlement_batch(Utc::now(), Mode::Test)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let batch = repo
+            .create_settlement_batch(Utc::now(), Mode::Live)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.status, SettlementBatchStatus::Pending);
+        assert_eq!(batch.mode, Mode::Live);
+        assert_eq!(
+            batch.totals,
+            vec![CurrencyTotal {
+                currency: CurrencyCode::USD,
+                payout_count: 2,
+                amount: 500
+            }]
+        );
+        assert_eq!(
+            repo.get_settlement_batch(batch.id).await.unwrap(),
+            Some(batch.clone())
+        );
+        // Already batched payouts are not picked up again.
+        assert!(
+            repo.create_settlement_batch(Utc::now(), Mode::Live)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let payouts = repo.list_settlement_batch_payouts(batch.id).await.unwrap();
+        let ids: Vec<_> = payouts.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, vec![first.id, second.id]);
+
+        // Status changes are compare-and-set.
+        assert!(
+            repo.update_settlement_batch_status(
+                batch.id,
+                SettlementBatchStatus::Submitted,
+                SettlementBatchStatus::Settled
+            )
+            .await
+            .unwrap()
+            .is_none()
+        );
+        let cancelled = repo
+            .update_settlement_batch_status(
+                batch.id,
+                SettlementBatchStatus::Pending,
+                SettlementBatchStatus::Cancelled,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cancelled.status, SettlementBatchStatus::Cancelled);
+        assert_eq!(cancelled.totals, batch.totals);
+
+        // Cancelling released the payouts into the next batch.
+        assert!(
+            repo.list_settlement_batch_payouts(batch.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let next = repo
+            .create_settlement_batch(Utc::now(), Mode::Live)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.payout_count, 2);
+        let listed: Vec<_> = repo
+            .list_settlement_batches(None)
+            .await
+            .unwrap()
+            .iter()
+            .map(|b| b.id)
+            .collect();
+        assert_eq!(listed, vec![next.id, batch.id]);
+        assert!(
+            repo.list_settlement_batches(Some(Mode::Test))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_transactions_between() {
+        let repo = &setup_repo().await;
+        let account_id = repo
+            .create_account(CreateAccountRequest {
+                name: "Merchant".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap()
+            .id;
+        let start = Utc::now() - Duration::seconds(1);
+        let deposit = repo
+            .deposit(DepositRequest {
+                account_id,
+                amount: PositiveAmount::new(1_000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        let withdrawal = payout(repo, account_id, 300).await;
+
+        let all = repo
+            .list_transactions_between(start, Utc::now() + Duration::seconds(1), None)
+            .await
+            .unwrap();
+        let ids: Vec<_> = all.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, vec![deposit.id, withdrawal.id]);
+        assert!(
+            repo.list_transactions_between(start, Utc::now(), Some(Mode::Test))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        // The end of the range is exclusive.
+        let before_withdrawal = repo
+            .list_transactions_between(start, all[1].created_at, None)
+            .await
+            .unwrap();
+        assert!(before_withdrawal.iter().all(|tx| tx.id != withdrawal.id));
+        assert!(
+            repo.list_transactions_between(start - Duration::days(1), start, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idle_accounts_and_status_changes() {
+        let opened = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(opened));
+        let repo = setup_repo().await.with_clock(clock.clone());
+        let open = |name: &str| CreateAccountRequest {
+            name: name.to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+        let idle = repo.create_account(open("Idle")).await.unwrap();
+        let busy = repo.create_account(open("Busy")).await.unwrap();
+        clock.advance(Duration::days(10));
+        repo.deposit(DepositRequest {
+            account_id: busy.id,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        let ids = |accounts: Vec<payments_types::Account>| -> Vec<AccountId> {
+            accounts.into_iter().map(|a| a.id).collect()
+        };
+        let idle_since = opened + Duration::days(5);
+        assert_eq!(
+            ids(repo.list_idle_accounts(idle_since, 10).await.unwrap()),
+            vec![idle.id]
+        );
+        // Accounts opened after the cut-off are not idle yet.
+        assert!(
+            repo.list_idle_accounts(opened, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let now = opened + Duration::days(10);
+        let dormant = repo
+            .update_account_status(idle.id, AccountStatus::Active, AccountStatus::Dormant, now)
+            .await
+            .unwrap()
+            .expect("account was active");
+        assert_eq!(dormant.status, AccountStatus::Dormant);
+        assert_eq!(dormant.status_changed_at, Some(now));
+        assert!(
+            repo.update_account_status(idle.id, AccountStatus::Active, AccountStatus::Dormant, now)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            repo.list_idle_accounts(idle_since, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let stored = repo.get_account(idle.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, AccountStatus::Dormant);
+        assert_eq!(stored.status_changed_at, Some(now));
+
+        let active = repo
+            .update_account_status(idle.id, AccountStatus::Dormant, AccountStatus::Active, now)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(active.status, AccountStatus::Active);
+        assert!(
+            repo.update_account_status(
+                AccountId::new(),
+                AccountStatus::Dormant,
+                AccountStatus::Active,
+                now
+            )
+            .await
+            .unwrap()
+            .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transaction_risk_is_recorded_once() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Held".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let tx = repo
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(100).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        assert!(tx.risk.is_none());
+
+        let held = RiskAssessment {
+            decision: RiskDecision::Hold,
+            reasons: vec!["large amount".into(), "new beneficiary".into()],
+        };
+        repo.record_transaction_risk(tx.id, &held).await.unwrap();
+        repo.record_transaction_risk(tx.id, &RiskAssessment::hold("later"))
+            .await
+            .unwrap();
+
+        let stored = repo.get_transaction(tx.id).await.unwrap().unwrap();
+        assert_eq!(stored.risk, Some(held.clone()));
+        let listed = repo
+            .list_transactions_for_account(account.id)
+            .await
+            .unwrap();
+        assert_eq!(listed[0].risk, Some(held));
+    }
+
+    #[tokio::test]
+    async fn test_memo_keys_and_encrypted_memos_round_trip() {
+        let repo = setup_repo().await;
+        let mut accounts = Vec::new();
+        for name in ["Alice", "Bob"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            accounts.push(account);
+        }
+        let (alice, bob) = (&accounts[0], &accounts[1]);
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let old = MemoKey::new(alice.id, "aes-256-gcm", "sha256:00000001", now).unwrap();
+        let new = MemoKey::new(
+            alice.id,
+            "xchacha20poly1305",
+            "sha256:00000002",
+            now + Duration::minutes(1),
+        )
+        .unwrap();
+        assert_eq!(repo.add_memo_key(&new).await.unwrap(), new);
+        repo.add_memo_key(&old).await.unwrap();
+
+        assert_eq!(repo.get_memo_key(new.id).await.unwrap(), Some(new.clone()));
+        assert_eq!(repo.get_memo_key(MemoKeyId::new()).await.unwrap(), None);
+        assert_eq!(
+            repo.list_memo_keys(alice.id).await.unwrap(),
+            vec![old.clone(), new.clone()]
+        );
+        assert!(repo.list_memo_keys(bob.id).await.unwrap().is_empty());
+
+        let retired_at = now + Duration::hours(1);
+        assert_eq!(
+            repo.retire_memo_key(bob.id, old.id, retired_at)
+                .await
+                .unwrap(),
+            None
+        );
+        let retired = repo
+            .retire_memo_key(alice.id, old.id, retired_at)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(retired.retired_at, Some(retired_at));
+        // Retiring again keeps the first time.
+        let again = repo
+            .retire_memo_key(alice.id, old.id, retired_at + Duration::hours(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(again.retired_at, Some(retired_at));
+
+        let memo = EncryptedMemo {
+            key_id: new.id,
+            ciphertext: "3q2+7wAAAAB0aGlz".to_string(),
+        };
+        let tx = repo
+            .deposit(DepositRequest {
+                account_id: alice.id,
+                amount: PositiveAmount::new(1_000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: Some(memo.clone()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(tx.encrypted_memo, Some(memo.clone()));
+        let stored = repo.get_transaction(tx.id).await.unwrap().unwrap();
+        assert_eq!(stored.encrypted_memo, Some(memo.clone()));
+        let listed = repo.list_transactions_for_account(alice.id).await.unwrap();
+        assert_eq!(listed[0].encrypted_memo, Some(memo));
+    }
+
+    #[tokio::test]
+    async fn test_owners_round_trip_and_hold_accounts() {
+        let repo = setup_repo().await;
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let acme = Owner::new("Acme Ltd", Some("finance@acme.example"), now).unwrap();
+        let globex = Owner::new("Globex", None, now + Duration::minutes(1)).unwrap();
+        assert_eq!(repo.create_owner(&globex).await.unwrap(), globex);
+        repo.create_owner(&acme).await.unwrap();
+
+        // The migration seeds the default owner
+        let (default, created): (Vec<_>, Vec<_>) = repo
+            .list_owners()
+            .await
+            .unwrap()
+            .into_iter()
+            .partition(|o| o.id == OwnerId::DEFAULT);
+        assert_eq!(default.len(), 1);
+        assert_eq!(created, vec![acme.clone(), globex.clone()]);
+        assert_eq!(repo.get_owner(acme.id).await.unwrap(), Some(acme.clone()));
+        assert_eq!(repo.get_owner(OwnerId::new()).await.unwrap(), None);
+
+        let mut renamed = acme.clone();
+        renamed.rename("Acme Holdings").unwrap();
+        renamed.set_email(None).unwrap();
+        assert_eq!(
+            repo.update_owner(&renamed).await.unwrap(),
+            Some(renamed.clone())
+        );
+        let unknown = Owner::new("Nobody", None, now).unwrap();
+        assert_eq!(repo.update_owner(&unknown).await.unwrap(), None);
+
+        let account = repo
+            .create_account(CreateAccountRequest {
+                owner_id: acme.id,
+                name: "Acme payroll".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(account.owner_id, acme.id);
+        assert_eq!(
+            repo.get_account(account.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .owner_id,
+            acme.id
+        );
+        let held = repo.list_owner_accounts(acme.id, None).await.unwrap();
+        assert_eq!(
+            held.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![account.id]
+        );
+        assert!(
+            repo.list_owner_accounts(globex.id, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let (key, raw) = repo
+            .create_owner_api_key("acme", acme.id, &[ApiKeyScope::AccountsRead], Mode::Live)
+            .await
+            .unwrap();
+        assert_eq!(key.owner_id, Some(acme.id));
+        assert_eq!(key.account_id, None);
+        let verified = repo
+            .verify_api_key_hash(&crate::security::hash_api_key(&raw))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(verified.owner_id, Some(acme.id));
+
+        assert!(repo.delete_owner(globex.id).await.unwrap());
+        assert!(!repo.delete_owner(globex.id).await.unwrap());
+        assert_eq!(repo.get_owner(globex.id).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_account_aliases_round_trip() {
+        let repo = setup_repo().await;
+        let mut accounts = Vec::new();
+        for name in ["Alice", "Bob"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.to_string(),
+                    currency: CurrencyCode::USD,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            accounts.push(account);
+        }
+        let (alice, bob) = (&accounts[0], &accounts[1]);
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let alias = |name: &str, account_id, minutes| AccountAlias {
+            alias: Alias::parse(name).unwrap(),
+            account_id,
+            created_at: now + Duration::minutes(minutes),
+        };
+
+        let ops = alias("@alice-ops", alice.id, 1);
+        assert_eq!(repo.add_account_alias(&ops).await.unwrap(), ops);
+        repo.add_account_alias(&alias("@alice", alice.id, 0))
+            .await
+            .unwrap();
+        // Re-adding to the same account returns the stored alias unchanged.
+        assert_eq!(
+            repo.add_account_alias(&alias("@alice-ops", alice.id, 5))
+                .await
+                .unwrap(),
+            ops
+        );
+        let taken = repo
+            .add_account_alias(&alias("@Alice-Ops", bob.id, 2))
+            .await;
+        assert!(matches!(
+            taken,
+            Err(RepoError::Domain(DomainError::ValidationError(_)))
+        ));
+
+        assert_eq!(
+            repo.get_account_alias(&ops.alias).await.unwrap(),
+            Some(ops.clone())
+        );
+        let names: Vec<String> = repo
+            .list_account_aliases(alice.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| a.alias.to_string())
+            .collect();
+        assert_eq!(names, vec!["@alice", "@alice-ops"]);
+
+        assert!(!repo.remove_account_alias(bob.id, &ops.alias).await.unwrap());
+        assert!(
+            repo.remove_account_alias(alice.id, &ops.alias)
+                .await
+                .unwrap()
+        );
+        assert!(repo.get_account_alias(&ops.alias).await.unwrap().is_none());
+        // A removed alias is free for any account.
+        repo.add_account_alias(&alias("@alice-ops", bob.id, 3))
+            .await
+            .unwrap();
+        assert_eq!(repo.list_account_aliases(bob.id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_statements_round_trip() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(repo.get_statement_email(account.id).await.unwrap(), None);
+        repo.set_statement_email(account.id, Some("a@example.com"))
+            .await
+            .unwrap();
+        repo.set_statement_email(account.id, Some("alice@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_statement_email(account.id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("alice@example.com")
+        );
+        repo.set_statement_email(account.id, None).await.unwrap();
+        assert_eq!(repo.get_statement_email(account.id).await.unwrap(), None);
+
+        let statement = |month: &str| {
+            let period = StatementPeriod::parse(month).unwrap();
+            Statement::from_history(StatementId::new(), &account, period, &[], period.ends_at()).0
+        };
+        let (january, february) = (statement("2026-01"), statement("2026-02"));
+        assert!(repo.insert_statement(&january, "jan.csv").await.unwrap());
+        assert!(repo.insert_statement(&february, "feb.csv").await.unwrap());
+        // One statement per account and month.
+        assert!(
+            !repo
+                .insert_statement(&statement("2026-01"), "again")
+                .await
+                .unwrap()
+        );
+
+        assert_eq!(
+            repo.get_statement(january.id).await.unwrap(),
+            Some(january.clone())
+        );
+        assert_eq!(
+            repo.get_statement_content(january.id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("jan.csv")
+        );
+        assert_eq!(
+            repo.list_statements(account.id).await.unwrap(),
+            vec![february, january]
+        );
+        assert!(
+            repo.get_statement(StatementId::new())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_daily_balances_round_trip() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Alice".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let daily = |d, balance| DailyBalance {
+            date: day(d),
+            balance,
+        };
+
+        assert_eq!(
+            repo.latest_daily_balance_date(account.id).await.unwrap(),
+            None
+        );
+        repo.save_daily_balances(account.id, &[daily(1, 100), daily(2, 250), daily(3, 50)])
+            .await
+            .unwrap();
+        // Saving a day again replaces its balance.
+        repo.save_daily_balances(account.id, &[daily(3, 75), daily(10, 0)])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.list_daily_balances(account.id, day(2), day(9))
+                .await
+                .unwrap(),
+            vec![daily(2, 250), daily(3, 75)]
+        );
+        assert_eq!(
+            repo.latest_daily_balance_date(account.id).await.unwrap(),
+            Some(day(10))
+        );
+        assert!(
+            repo.list_daily_balances(AccountId::new(), day(1), day(31))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_record_keeps_the_first_response() {
+        let repo = setup_repo().await;
+        let at = |hour: u32| Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap();
+        let first = IdempotencyRecord {
+            endpoint: "POST /api/transactions/deposit".into(),
+            key: "order-1".into(),
+            request_hash: "abc".into(),
+            status: 201,
+            response: serde_json::json!({"id": "tx-1", "amount": 500, "warnings": []}),
+            created_at: at(9),
+        };
+        assert_eq!(
+            repo.get_idempotency_record(&first.endpoint, "order-1")
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(repo.save_idempotency_record(&first).await.unwrap(), first);
+
+        // A later save under the same key leaves the first record in place.
+        let second = IdempotencyRecord {
+            request_hash: "def".into(),
+            response: serde_json::json!({"id": "tx-2"}),
+            created_at: at(10),
+            ..first.clone()
+        };
+        assert_eq!(repo.save_idempotency_record(&second).await.unwrap(), first);
+        assert_eq!(
+            repo.get_idempotency_record(&first.endpoint, "order-1")
+                .await
+                .unwrap(),
+            Some(first.clone())
+        );
+
+        // The same key sent to another endpoint stores its own response.
+        let other = IdempotencyRecord {
+            endpoint: "POST /api/owners".into(),
+            status: 200,
+            ..second.clone()
+        };
+        assert_eq!(repo.save_idempotency_record(&other).await.unwrap(), other);
+        assert_eq!(
+            repo.get_idempotency_record(&first.endpoint, "order-1")
+                .await
+                .unwrap(),
+            Some(first.clone())
+        );
+
+        // Replacing overwrites the record; removing frees the key.
+        repo.replace_idempotency_record(&second).await.unwrap();
+        assert_eq!(
+            repo.get_idempotency_record(&first.endpoint, "order-1")
+                .await
+                .unwrap(),
+            Some(second)
+        );
+        repo.remove_idempotency_record(&first.endpoint, "order-1")
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_idempotency_record(&first.endpoint, "order-1")
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            repo.get_idempotency_record(&other.endpoint, "order-1")
+                .await
+                .unwrap(),
+            Some(other)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lapsed_reservation_is_taken_over() {
+        let repo = setup_repo().await;
+        let at = |minute: u32| Utc.with_ymd_and_hms(2026, 3, 1, 9, minute, 0).unwrap();
+        let reservation =
+            IdempotencyRecord::in_progress("POST /api/owners", "order-1", "abc", at(0));
+        assert_eq!(
+            repo.save_idempotency_record(&reservation).await.unwrap(),
+            reservation
+        );
+
+        // A reservation still in time keeps the key
+        let retry = IdempotencyRecord::in_progress("POST /api/owners", "order-1", "abc", at(4));
+        assert_eq!(
+            repo.save_idempotency_record(&retry).await.unwrap(),
+            reservation
+        );
+
+        // One that lapsed gives way, but a stored response never does
+        let retry = IdempotencyRecord::in_progress("POST /api/owners", "order-1", "abc", at(5));
+        assert_eq!(repo.save_idempotency_record(&retry).await.unwrap(), retry);
+        let response = IdempotencyRecord {
+            status: 201,
+            response: serde_json::json!({"id": "o1"}),
+            ..retry
+        };
+        repo.replace_idempotency_record(&response).await.unwrap();
+        let late = IdempotencyRecord::in_progress("POST /api/owners", "order-1", "abc", at(30));
+        assert_eq!(repo.save_idempotency_record(&late).await.unwrap(), response);
+    }
+
+    /// Responses stored before keys were scoped to endpoints are kept as the
+    /// payment operations' responses.
+    #[tokio::test]
+    async fn test_idempotency_records_move_to_the_payments_endpoint() {
+        let repo = setup_repo().await;
+        sqlx::query(include_str!(
+            "../migrations/0028_idempotency_records_sqlite.sql"
+        ))
+        .execute(repo.pool())
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO idempotency_records (idempotency_key, request_hash, response, created_at) VALUES ('order-1', 'abc', '{\"id\":\"tx-1\"}', ?)",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .execute(repo.pool())
+        .await
+        .unwrap();
+        repo.create_schema().await.unwrap();
+
+        let record = repo
+            .get_idempotency_record(payments_types::PAYMENTS_IDEMPOTENCY_ENDPOINT, "order-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.request_hash, "abc");
+        assert_eq!(record.status, 200);
+        assert_eq!(record.response, serde_json::json!({"id": "tx-1"}));
+    }
+
+    #[tokio::test]
+    async fn test_change_requests_round_trip() {
+        let repo = setup_repo().await;
+        let at = |hour: u32| Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap();
+        let (requester, approver) = (ApiKeyId::new(), ApiKeyId::new());
+        let endpoint = repo
+            .register_webhook_endpoint(
+                "https://example.com/old",
+                vec![],
+                WebhookTimeouts::default(),
+                WebhookSignatureAlgorithm::default(),
+            )
+            .await
+            .unwrap();
+        let endpoint_id = WebhookEndpointId::from_uuid(endpoint.id);
+
+        let old = ChangeRequest::new(
+            ChangeAction::DeleteApiKey {
+                api_key_id: ApiKeyId::new(),
+            },
+            requester,
+            at(9),
+        );
+        let recent = ChangeRequest::new(
+            ChangeAction::SetWebhookUrl {
+                endpoint_id,
+                url: "https://example.com/new".into(),
+            },
+            requester,
+            at(10),
+        );
+        repo.insert_change_request(&old).await.unwrap();
+        repo.insert_change_request(&recent).await.unwrap();
+        assert_eq!(
+            repo.get_change_request(recent.id).await.unwrap(),
+            Some(recent.clone())
+        );
+
+        let approved = repo
+            .decide_change_request(recent.id, ChangeStatus::Approved, approver, at(11))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(approved.status, ChangeStatus::Approved);
+        assert_eq!(approved.decided_by, Some(approver));
+        assert_eq!(approved.decided_at, Some(at(11)));
+        // Only pending requests can be decided.
+        assert!(
+            repo.decide_change_request(recent.id, ChangeStatus::Rejected, approver, at(12))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let ids =
+            |requests: Vec<ChangeRequest>| -> Vec<_> { requests.iter().map(|r| r.id).collect() };
+        assert_eq!(
+            ids(repo.list_change_requests(None).await.unwrap()),
+            [recent.id, old.id]
+        );
+        assert_eq!(
+            ids(repo
+                .list_change_requests(Some(ChangeStatus::Pending))
+                .await
+                .unwrap()),
+            [old.id]
+        );
+
+        let updated = repo
+            .update_webhook_endpoint_url(endpoint_id, "https://example.com/new")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.url, "https://example.com/new");
+        assert!(
+            repo.update_webhook_endpoint_url(WebhookEndpointId::new(), "https://example.com")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exports_round_trip() {
+        let repo = setup_repo().await;
+        let at = |day: u32| Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        let request = ExportRequest::Journal {
+            format: JournalExportFormat::Xero,
+            from: at(1).date_naive(),
+            to: at(2).date_naive(),
+        };
+        let old = Export::new(ExportId::new(), request, at(1));
+        let mut recent = Export::new(ExportId::new(), request, at(3));
+        repo.insert_export(&old).await.unwrap();
+        repo.insert_export(&recent).await.unwrap();
+        assert_eq!(
+            repo.get_export(recent.id).await.unwrap(),
+            Some(recent.clone())
+        );
+        assert_eq!(repo.get_export_content(recent.id).await.unwrap(), None);
+
+        recent.status = ExportStatus::Ready;
+        recent.completed_at = Some(at(3) + Duration::seconds(5));
+        repo.finish_export(&recent, Some("journal.csv"))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_export(recent.id).await.unwrap(),
+            Some(recent.clone())
+        );
+        assert_eq!(
+            repo.get_export_content(recent.id).await.unwrap().as_deref(),
+            Some("journal.csv")
+        );
+
+        assert_eq!(repo.delete_exports_before(at(2)).await.unwrap(), 1);
+        assert!(repo.get_export(old.id).await.unwrap().is_none());
+        assert!(repo.get_export(recent.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_payments_round_trip() {
+        let repo = setup_repo().await;
+        let at = |day: u32| Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        let mut ids = Vec::new();
+        for name in ["Payer", "Payee"] {
+            let account = repo
+                .create_account(CreateAccountRequest {
+                    name: name.into(),
+                    currency: CurrencyCode::EUR,
+                    overdraft_limit: 0,
+                    owner_id: OwnerId::DEFAULT,
+                    mode: None,
+                })
+                .await
+                .unwrap();
+            ids.push(account.id);
+        }
+        let transfer = ScheduledPayment {
+            id: ScheduledPaymentId::new(),
+            transaction_type: TransactionType::Transfer,
+            from_account_id: ids[0],
+            to_account_id: Some(ids[1]),
+            amount: 2500,
+            currency: CurrencyCode::EUR,
+            reference: Some("Rent".into()),
+            purpose_code: None,
+            schedule: PaymentSchedule::Interval {
+                every_seconds: 3600,
+            },
+            status: ScheduledPaymentStatus::Active,
+            next_run_at: at(1),
+            last_run_at: None,
+            last_transaction_id: None,
+            last_error: None,
+            created_at: at(1),
+        };
+        let withdrawal = ScheduledPayment {
+            id: ScheduledPaymentId::new(),
+            transaction_type: TransactionType::Withdrawal,
+            from_account_id: ids[1],
+            to_account_id: None,
+            reference: None,
+            purpose_code: Some("PAYROLL".into()),
+            schedule: PaymentSchedule::Cron {
+                expression: "0 9 * * MON-FRI".into(),
+                timezone: "Europe/Berlin".into(),
+            },
+            next_run_at: at(5),
+            created_at: at(1) + Duration::seconds(1),
+            ..transfer.clone()
+        };
+        repo.insert_scheduled_payment(&transfer).await.unwrap();
+        repo.insert_scheduled_payment(&withdrawal).await.unwrap();
+
+        assert_eq!(
+            repo.get_scheduled_payment(withdrawal.id).await.unwrap(),
+            Some(withdrawal.clone())
+        );
+        assert_eq!(
+            repo.list_scheduled_payments(None, None).await.unwrap(),
+            vec![transfer.clone(), withdrawal.clone()]
+        );
+        assert_eq!(
+            repo.list_scheduled_payments(Some(ids[1]), None)
+                .await
+                .unwrap(),
+            vec![withdrawal.clone()]
+        );
+        assert_eq!(
+            repo.list_scheduled_payments(None, Some(Mode::Live))
+                .await
+                .unwrap(),
+            vec![transfer.clone(), withdrawal.clone()]
+        );
+        assert!(
+            repo.list_scheduled_payments(None, Some(Mode::Test))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            repo.list_due_scheduled_payments(at(2), 10).await.unwrap(),
+            vec![transfer.clone()]
+        );
+        assert_eq!(
+            repo.list_due_scheduled_payments(at(6), 1).await.unwrap(),
+            vec![transfer.clone()]
+        );
+
+        // Only the first scheduler to record a run wins it.
+        let mut ran = transfer.clone();
+        ran.record_run(at(1) + Duration::minutes(1), Ok(TransactionId::new()))
+            .unwrap();
+        assert!(
+            repo.record_scheduled_payment_run(&ran, at(1))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !repo
+                .record_scheduled_payment_run(&ran, at(1))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            repo.get_scheduled_payment(ran.id).await.unwrap(),
+            Some(ran.clone())
+        );
+
+        let mut paused = withdrawal.clone();
+        paused.status = ScheduledPaymentStatus::Paused;
+        repo.update_scheduled_payment_status(&paused).await.unwrap();
+        assert_eq!(
+            repo.list_due_scheduled_payments(at(6), 10).await.unwrap(),
+            vec![ran]
+        );
+        assert_eq!(
+            repo.get_scheduled_payment(paused.id).await.unwrap(),
+            Some(paused)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_holds_reserve_capture_and_release() {
+        let repo = setup_repo().await;
+        let at = |day: u32| Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Card".into(),
+                currency: CurrencyCode::EUR,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        repo.deposit(DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(10_000).unwrap(),
+            currency: CurrencyCode::EUR,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        let hotel = Hold {
+            id: HoldId::new(),
+            account_id: account.id,
+            amount: 6000,
+            currency: CurrencyCode::EUR,
+            reference: Some("Hotel".into()),
+            purpose_code: None,
+            status: HoldStatus::Authorized,
+            expires_at: at(3),
+            captured_amount: None,
+            transaction_id: None,
+            created_at: at(1),
+            resolved_at: None,
+        };
+        let fuel = Hold {
+            id: HoldId::new(),
+            amount: 3000,
+            reference: None,
+            expires_at: at(10),
+            created_at: at(1) + Duration::seconds(1),
+            ..hotel.clone()
+        };
+        repo.create_hold(&hotel).await.unwrap();
+        repo.create_hold(&fuel).await.unwrap();
+
+        // Held funds stay in the balance but cannot be spent or held again.
+        let held = repo.get_account(account.id).await.unwrap().unwrap();
+        assert_eq!((held.balance.amount(), held.held), (10_000, 9000));
+        assert_eq!(held.available(), 1000);
+        let overdrawn = repo
+            .withdraw(WithdrawRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(2000).unwrap(),
+                currency: CurrencyCode::EUR,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await;
+        assert!(matches!(
+            overdrawn,
+            Err(RepoError::Domain(DomainError::InsufficientFunds { .. }))
+        ));
+        let too_much = Hold {
+            id: HoldId::new(),
+            amount: 2000,
+            ..fuel.clone()
+        };
+        assert!(repo.create_hold(&too_much).await.is_err());
+
+        assert_eq!(repo.get_hold(hotel.id).await.unwrap(), Some(hotel.clone()));
+        assert_eq!(
+            repo.list_holds(account.id).await.unwrap(),
+            vec![fuel.clone(), hotel.clone()]
+        );
+        assert_eq!(
+            repo.list_expired_holds(at(5), 10).await.unwrap(),
+            vec![hotel.clone()]
+        );
+
+        // A partial capture withdraws what was captured and releases the rest.
+        let (captured, tx) = repo.capture_hold(hotel.id, 4000, at(2)).await.unwrap();
+        assert_eq!(captured.status, HoldStatus::Captured);
+        assert_eq!(captured.captured_amount, Some(4000));
+        assert_eq!(captured.transaction_id, Some(tx.id));
+        assert_eq!(tx.amount.amount(), 4000);
+        assert_eq!(tx.source_account_id, Some(account.id));
+        assert_eq!(repo.get_hold(hotel.id).await.unwrap(), Some(captured));
+        let after = repo.get_account(account.id).await.unwrap().unwrap();
+        assert_eq!((after.balance.amount(), after.held), (6000, 3000));
+        assert!(repo.capture_hold(hotel.id, 1000, at(2)).await.is_err());
+        assert!(repo.list_expired_holds(at(5), 10).await.unwrap().is_empty());
+
+        let voided = repo
+            .release_hold(fuel.id, HoldStatus::Voided, at(2))
+            .await
+            .unwrap();
+        assert_eq!(voided.status, HoldStatus::Voided);
+        assert_eq!(voided.resolved_at, Some(at(2)));
+        assert!(
+            repo.release_hold(fuel.id, HoldStatus::Expired, at(11))
+                .await
+                .is_err()
+        );
+        let released = repo.get_account(account.id).await.unwrap().unwrap();
+        assert_eq!((released.balance.amount(), released.held), (6000, 0));
+        assert!(matches!(
+            repo.release_hold(HoldId::new(), HoldStatus::Voided, at(2))
+                .await,
+            Err(RepoError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_beneficiary_attempts_are_recorded_once() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Payer".into(),
+                currency: CurrencyCode::GBP,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let at = |hour: u32| Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap();
+        let beneficiary = |name: &str, hour: u32| Beneficiary {
+            id: BeneficiaryId::new(),
+            account_id: account.id,
+            name: name.into(),
+            external_id: "GB33BUKB20201555555555".into(),
+            currency: CurrencyCode::GBP,
+            status: BeneficiaryStatus::Pending,
+            failed_attempts: 0,
+            micro_deposits: [12, 47],
+            created_at: at(hour),
+            verified_at: None,
+        };
+        let mut first = beneficiary("Globex", 9);
+        let second = beneficiary("Initech", 10);
+        repo.create_beneficiary(&first).await.unwrap();
+        repo.create_beneficiary(&second).await.unwrap();
+        assert_eq!(
+            repo.get_beneficiary(first.id).await.unwrap().as_ref(),
+            Some(&first)
+        );
+        assert!(
+            repo.get_beneficiary(BeneficiaryId::new())
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let listed = repo.list_beneficiaries(account.id).await.unwrap();
+        assert_eq!(listed, vec![second.clone(), first.clone()]);
+
+        // A wrong guess counts once; a racing attempt that saw the old
+        // count is not recorded.
+        assert!(!first.confirm([12, 48], at(11)).unwrap());
+        assert!(repo.record_beneficiary_attempt(&first, 0).await.unwrap());
+        assert!(!repo.record_beneficiary_attempt(&first, 0).await.unwrap());
+        assert!(first.confirm([47, 12], at(12)).unwrap());
+        assert!(repo.record_beneficiary_attempt(&first, 1).await.unwrap());
+        let stored = repo.get_beneficiary(first.id).await.unwrap().unwrap();
+        assert_eq!(stored, first);
+        assert_eq!(stored.status, BeneficiaryStatus::Verified);
+        assert_eq!(stored.verified_at, Some(at(12)));
+        // Verified beneficiaries take no more attempts.
+        assert!(!repo.record_beneficiary_attempt(&first, 1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_exposure_balances_and_rate_snapshots() {
+        let repo = setup_repo().await;
+        let usd = repo
+            .create_account(CreateAccountRequest {
+                name: "Dollars".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        repo.create_account(CreateAccountRequest {
+            name: "Empty".to_string(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: Some(Mode::Test),
+        })
+        .await
+        .unwrap();
+        let eur = repo
+            .create_account(CreateAccountRequest {
+                name: "Euros".to_string(),
+                currency: CurrencyCode::EUR,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        for (account_id, amount, currency) in [
+            (usd.id, 1500, CurrencyCode::USD),
+            (eur.id, 300, CurrencyCode::EUR),
+        ] {
+            repo.deposit(DepositRequest {
+                account_id,
+                amount: PositiveAmount::new(amount).unwrap(),
+                currency,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            repo.currency_balances(None).await.unwrap(),
+            vec![
+                CurrencyBalance {
+                    currency: CurrencyCode::EUR,
+                    account_count: 1,
+                    balance: 300,
+                },
+                CurrencyBalance {
+                    currency: CurrencyCode::USD,
+                    account_count: 2,
+                    balance: 1500,
+                },
+            ]
+        );
+        assert_eq!(
+            repo.currency_balances(Some(Mode::Test)).await.unwrap(),
+            vec![CurrencyBalance {
+                currency: CurrencyCode::USD,
+                account_count: 1,
+                balance: 0,
+            }]
+        );
+
+        let at = |hour: u32| Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap();
+        let snapshot = |hour: u32, eur: f64| RateSnapshot {
+            taken_at: at(hour),
+            usd_rates: HashMap::from([(CurrencyCode::USD, 1.0), (CurrencyCode::EUR, eur)]),
+        };
+        repo.insert_rate_snapshot(&snapshot(9, 1.08)).await.unwrap();
+        repo.insert_rate_snapshot(&snapshot(12, 1.1)).await.unwrap();
+        repo.insert_rate_snapshot(&snapshot(12, 1.12))
+            .await
+            .unwrap();
+
+        assert_eq!(repo.rate_snapshot_at(at(8)).await.unwrap(), None);
+        assert_eq!(
+            repo.rate_snapshot_at(at(11)).await.unwrap(),
+            Some(snapshot(9, 1.08))
+        );
+        assert_eq!(
+            repo.rate_snapshot_at(at(12)).await.unwrap(),
+            Some(snapshot(12, 1.12))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quotes_round_trip_and_are_spent_once() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Dollars".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        let mut payments = Vec::new();
+        for _ in 0..2 {
+            let tx = repo
+                .deposit(DepositRequest {
+                    account_id: account.id,
+                    amount: PositiveAmount::new(100).unwrap(),
+                    currency: CurrencyCode::USD,
+                    idempotency_key: None,
+                    reference: None,
+                    counterparty: None,
+                    metadata: Default::default(),
+                    encrypted_memo: None,
+                })
+                .await
+                .unwrap();
+            payments.push(tx.id);
+        }
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let quote = Quote {
+            id: QuoteId::new(),
+            from_currency: CurrencyCode::USD,
+            to_currency: CurrencyCode::EUR,
+            amount: 10_000,
+            rate: 0.92,
+            converted_amount: 9_200,
+            created_at: now,
+            expires_at: now + Duration::seconds(60),
+            transaction_id: None,
+            mode: Mode::Test,
+        };
+        repo.insert_quote(&quote).await.unwrap();
+        assert_eq!(repo.get_quote(quote.id).await.unwrap(), Some(quote.clone()));
+        assert_eq!(repo.get_quote(QuoteId::new()).await.unwrap(), None);
+
+        // Dropped uncommitted, the quote stays unspent.
+        let mut work = repo.begin().await.unwrap();
+        work.spend_quote(quote.id, payments[0]).await.unwrap();
+        drop(work);
+        assert_eq!(repo.get_quote(quote.id).await.unwrap(), Some(quote.clone()));
+
+        let mut work = repo.begin().await.unwrap();
+        work.spend_quote(quote.id, payments[0]).await.unwrap();
+        work.commit().await.unwrap();
+        let mut work = repo.begin().await.unwrap();
+        // The same transaction may spend it again, as a replay does.
+        work.spend_quote(quote.id, payments[0]).await.unwrap();
+        assert!(matches!(
+            work.spend_quote(quote.id, payments[1]).await,
+            Err(RepoError::Domain(DomainError::ValidationError(_)))
+        ));
+        drop(work);
+        assert_eq!(
+            repo.get_quote(quote.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .transaction_id,
+            Some(payments[0])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_float_positions_offset_external_money() {
+        let repo = setup_repo().await;
+        let open = |name: &str, currency| CreateAccountRequest {
+            name: name.to_string(),
+            currency,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        };
+        let alice = repo
+            .create_account(open("Alice", CurrencyCode::USD))
+            .await
+            .unwrap();
+        let bob = repo
+            .create_account(open("Bob", CurrencyCode::EUR))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.float_positions(None).await.unwrap(),
+            vec![
+                FloatPosition {
+                    currency: CurrencyCode::EUR,
+                    customer_balance: 0,
+                    float_balance: 0,
+                },
+                FloatPosition {
+                    currency: CurrencyCode::USD,
+                    customer_balance: 0,
+                    float_balance: 0,
+                },
+            ]
+        );
+        repo.deposit(DepositRequest {
+            account_id: alice.id,
+            amount: PositiveAmount::new(1000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        let withdraw = WithdrawRequest {
+            account_id: alice.id,
+            amount: PositiveAmount::new(250).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        repo.withdraw(withdraw).await.unwrap();
+        let amount = DynMoney::new(400, CurrencyCode::USD).unwrap();
+        let conversion = FxConversion::at_rate(amount, CurrencyCode::EUR, 0.9).unwrap();
+        let transfer = TransferRequest {
+            from_account_id: alice.id,
+            to_account_id: bob.id,
+            amount: PositiveAmount::new(400).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: true,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        };
+        repo.transfer_with_conversion(transfer, conversion)
+            .await
+            .unwrap();
+
+        let positions = repo.float_positions(None).await.unwrap();
+        assert_eq!(
+            positions,
+            vec![
+                FloatPosition {
+                    currency: CurrencyCode::EUR,
+                    customer_balance: 360,
+                    float_balance: -360,
+                },
+                FloatPosition {
+                    currency: CurrencyCode::USD,
+                    customer_balance: 350,
+                    float_balance: -350,
+                },
+            ]
+        );
+        assert!(positions.iter().all(FloatPosition::is_balanced));
+        // Neither account nor their payments are in test mode.
+        assert!(
+            repo.float_positions(Some(Mode::Test))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_generation() {
+        let repo = setup_repo().await;
+
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Webhook Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+
+        // Manually create a webhook event (simulating PaymentService behavior)
+        let endpoint_id = WebhookEndpointId(Uuid::new_v4());
+        let payload = serde_json::json!({
+            "type": "DEPOSIT",
             "amount": 500,
             "currency": "USD",
             "account_id": account.id,
         });
 
-        repo.create_webhook_event(endpoint_id, "DEPOSIT_COMPLETED", payload)
+        repo.create_webhook_event(endpoint_id, "DEPOSIT_COMPLETED", payload)
+            .await
+            .unwrap();
+
+        // 2. Fetch pending webhooks
+        let events = repo.get_pending_webhooks(10).await.unwrap();
+        assert_eq!(events.len(), 1);
+
+        let event = &events[0];
+        assert_eq!(event.event_type, "DEPOSIT_COMPLETED");
+        assert_eq!(event.status, payments_types::WebhookStatus::Pending);
+
+        // Check payload
+        let payload = &event.payload;
+        assert_eq!(payload["type"], "DEPOSIT");
+        assert_eq!(payload["amount"], 500);
+
+        // 3. Update status
+        repo.update_webhook_status(event.id, payments_types::WebhookStatus::Completed, None)
+            .await
+            .unwrap();
+
+        // 4. Verify no pending webhooks
+        let events_after = repo.get_pending_webhooks(10).await.unwrap();
+        assert!(events_after.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_api_key_usage_buckets_accumulate() {
+        let repo = setup_repo().await;
+        let (key, _) = repo
+            .create_api_key("partner", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
+        let hour = |h: u32| Utc.with_ymd_and_hms(2026, 3, 1, h, 0, 0).unwrap();
+        for (at, rate_limited) in [(hour(9), 0), (hour(9), 1), (hour(10), 0)] {
+            repo.add_api_key_usage(&ApiKeyUsageBucket {
+                api_key_id: key.id,
+                hour: at,
+                requests: 1,
+                rate_limited,
+            })
+            .await
+            .unwrap();
+        }
+        for amount in [500, 250] {
+            repo.add_api_key_volume(&ApiKeyVolumeBucket {
+                api_key_id: key.id,
+                hour: hour(9),
+                transaction_type: TransactionType::Deposit,
+                currency: CurrencyCode::USD,
+                count: 1,
+                amount,
+            })
+            .await
+            .unwrap();
+        }
+
+        let usage = repo.list_api_key_usage(key.id, hour(9)).await.unwrap();
+        let usage: Vec<_> = usage
+            .iter()
+            .map(|b| (b.hour, b.requests, b.rate_limited))
+            .collect();
+        assert_eq!(usage, vec![(hour(9), 2, 1), (hour(10), 1, 0)]);
+        assert_eq!(
+            repo.list_api_key_usage(key.id, hour(10))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let volume = repo.list_api_key_volume(key.id, hour(0)).await.unwrap();
+        assert_eq!(volume.len(), 1);
+        assert_eq!((volume[0].count, volume[0].amount), (2, 750));
+        assert_eq!(volume[0].transaction_type, TransactionType::Deposit);
+
+        // Usage stays readable after the key is revoked
+        assert!(
+            repo.delete_api_key(key.id, key.id, Utc::now())
+                .await
+                .unwrap()
+        );
+        let revoked = repo.get_api_key(key.id).await.unwrap().unwrap();
+        assert!(!revoked.is_active);
+        assert!(
+            repo.get_api_key(payments_types::ApiKeyId::new())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_wait_until_due() {
+        let repo = setup_repo().await;
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let repo = repo.with_clock(clock.clone());
+        let endpoint_id = WebhookEndpointId(Uuid::new_v4());
+        let retried = repo
+            .create_webhook_event(endpoint_id, "deposit.success", serde_json::json!({}))
+            .await
+            .unwrap();
+        let dead = repo
+            .create_webhook_event(endpoint_id, "deposit.success", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(repo.get_pending_webhooks(10).await.unwrap().len(), 2);
+        assert_eq!(repo.count_webhook_backlog().await.unwrap(), 2);
+
+        repo.reschedule_webhook(
+            retried.id,
+            "HTTP 503".to_string(),
+            std::time::Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+        repo.update_webhook_status(
+            dead.id,
+            WebhookStatus::DeadLettered,
+            Some("HTTP 410".to_string()),
+        )
+        .await
+        .unwrap();
+        assert!(repo.get_pending_webhooks(10).await.unwrap().is_empty());
+        // The retry is not due yet but still waiting to go out.
+        assert_eq!(repo.count_webhook_backlog().await.unwrap(), 1);
+
+        clock.advance(Duration::seconds(60));
+        let due = repo.get_pending_webhooks(10).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, retried.id);
+        assert_eq!(due[0].status, WebhookStatus::Failed);
+        assert_eq!(due[0].attempts, 1);
+        assert_eq!(due[0].last_error.as_deref(), Some("HTTP 503"));
+        assert_eq!(due[0].next_attempt_at, Some(start + Duration::seconds(60)));
+    }
+
+    #[tokio::test]
+    async fn test_storage_counts_cover_each_tracked_table() {
+        let repo = setup_repo().await;
+        assert_eq!(
+            repo.storage_counts().await.unwrap(),
+            payments_types::StorageCounts::default()
+        );
+
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            repo.deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(1000).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        }
+        repo.create_webhook_event(
+            WebhookEndpointId(Uuid::new_v4()),
+            "deposit.success",
+            serde_json::json!({}),
+        )
+        .await
+        .unwrap();
+
+        let counts = repo.storage_counts().await.unwrap();
+        assert_eq!(counts.accounts, 1);
+        assert_eq!(counts.transactions, 2);
+        assert_eq!(counts.webhook_events, 1);
+    }
+
+    #[tokio::test]
+    async fn test_dead_lettered_webhooks_filter_and_requeue() {
+        let repo = setup_repo().await;
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let repo = &repo.with_clock(clock.clone());
+        let first = WebhookEndpointId(Uuid::new_v4());
+        let second = WebhookEndpointId(Uuid::new_v4());
+        let mut events = Vec::new();
+        for (endpoint_id, event_type) in [
+            (first, "deposit.success"),
+            (first, "withdraw.success"),
+            (second, "deposit.success"),
+            (first, "deposit.success"),
+        ] {
+            let event = repo
+                .create_webhook_event(endpoint_id, event_type, serde_json::json!({}))
+                .await
+                .unwrap();
+            events.push(event.id);
+        }
+        let dead_letter = |id| repo.update_webhook_status(id, WebhookStatus::DeadLettered, None);
+        dead_letter(events[0]).await.unwrap();
+        clock.advance(Duration::hours(1));
+        dead_letter(events[1]).await.unwrap();
+        dead_letter(events[2]).await.unwrap();
+        let listed = |filter: DeadLetterFilter| async move {
+            let mut ids: Vec<_> = repo
+                .list_dead_lettered_webhooks(&filter)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|e| e.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        let sorted = |mut ids: Vec<Uuid>| {
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(
+            listed(DeadLetterFilter::default()).await,
+            sorted(events[..3].to_vec())
+        );
+        // Events for unregistered endpoints are live.
+        let test = DeadLetterFilter {
+            mode: Some(Mode::Test),
+            ..Default::default()
+        };
+        assert!(listed(test).await.is_empty());
+        let recent = DeadLetterFilter {
+            failed_since: Some(start + Duration::minutes(30)),
+            ..Default::default()
+        };
+        assert_eq!(listed(recent.clone()).await, sorted(events[1..3].to_vec()));
+        let first_deposits = DeadLetterFilter {
+            endpoint_id: Some(first),
+            event_type: Some("deposit.success".into()),
+            ..Default::default()
+        };
+        assert_eq!(listed(first_deposits).await, vec![events[0]]);
+
+        let recent_first = DeadLetterFilter {
+            endpoint_id: Some(first),
+            ..recent
+        };
+        assert_eq!(
+            repo.requeue_dead_lettered_webhooks(&recent_first)
+                .await
+                .unwrap(),
+            1
+        );
+        let requeued = repo.get_webhook_event(events[1]).await.unwrap().unwrap();
+        assert_eq!(requeued.status, WebhookStatus::Pending);
+        assert_eq!(requeued.attempts, 0);
+        assert_eq!(requeued.processed_at, None);
+        assert_eq!(requeued.next_attempt_at, Some(start + Duration::hours(1)));
+        let due: Vec<_> = repo
+            .get_pending_webhooks(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(sorted(due), sorted(vec![events[1], events[3]]));
+        assert_eq!(
+            repo.requeue_dead_lettered_webhooks(&recent_first)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            listed(DeadLetterFilter::default()).await,
+            sorted(vec![events[0], events[2]])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_webhook_event_returns_full_payload() {
+        let repo = setup_repo().await;
+        let reference = "x".repeat(payments_types::MAX_WEBHOOK_PAYLOAD_BYTES);
+        let created = repo
+            .create_webhook_event(
+                WebhookEndpointId(Uuid::new_v4()),
+                "deposit.success",
+                serde_json::json!({ "reference": reference }),
+            )
             .await
             .unwrap();
 
-        // 2. Fetch pending webhooks
-        let events = repo.get_pending_webhooks(10).await.unwrap();
-        assert_eq!(events.len(), 1);
+        let event = repo.get_webhook_event(created.id).await.unwrap().unwrap();
+        assert_eq!(event.event_type, "deposit.success");
+        assert_eq!(event.status, WebhookStatus::Pending);
+        assert_eq!(event.payload["reference"], reference.as_str());
+        assert!(event.payload_truncated());
+        assert!(
+            repo.get_webhook_event(Uuid::new_v4())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
 
-        let event = &events[0];
-        assert_eq!(event.event_type, "DEPOSIT_COMPLETED");
-        assert_eq!(event.status, payments_types::WebhookStatus::Pending);
+    #[tokio::test]
+    async fn test_webhook_delivery_sequences_count_per_endpoint() {
+        let repo = setup_repo().await;
+        let first = WebhookEndpointId(Uuid::new_v4());
+        let second = WebhookEndpointId(Uuid::new_v4());
+        let mut sequences = Vec::new();
+        for endpoint_id in [first, second, first, first] {
+            let event = repo
+                .create_webhook_event(endpoint_id, "deposit.success", serde_json::json!({}))
+                .await
+                .unwrap();
+            sequences.push(event.delivery_sequence);
+        }
+        assert_eq!(sequences, vec![1, 1, 2, 3]);
 
-        // Check payload
-        let payload = &event.payload;
-        assert_eq!(payload["type"], "DEPOSIT");
-        assert_eq!(payload["amount"], 500);
+        let after = repo.list_webhook_events_after(first, 1, 10).await.unwrap();
+        let listed: Vec<_> = after.iter().map(|e| e.delivery_sequence).collect();
+        assert_eq!(listed, vec![2, 3]);
+        assert!(after.iter().all(|e| e.endpoint_id == first.0));
+        assert_eq!(
+            repo.list_webhook_events_after(first, 0, 1).await.unwrap()[0].delivery_sequence,
+            1
+        );
+        let fetched = repo.get_webhook_event(after[1].id).await.unwrap().unwrap();
+        assert_eq!(fetched.delivery_sequence, 3);
+        assert!(
+            repo.list_webhook_events_after(second, 1, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
 
-        // 3. Update status
-        repo.update_webhook_status(event.id, payments_types::WebhookStatus::Completed, None)
+    #[tokio::test]
+    async fn test_event_log_sequences_follow_commits() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Logged".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
             .await
             .unwrap();
+        let event = DomainEvent::AccountCreated(account.clone());
 
-        // 4. Verify no pending webhooks
-        let events_after = repo.get_pending_webhooks(10).await.unwrap();
-        assert!(events_after.is_empty());
+        repo.append_event(&event, Mode::Live).await.unwrap();
+        {
+            // Rolled back with its unit of work, leaving no gap
+            let mut work = repo.begin().await.unwrap();
+            work.append_event(&event, Mode::Live).await.unwrap();
+        }
+        let mut work = repo.begin().await.unwrap();
+        work.append_event(&DomainEvent::AccountFrozen(account.clone()), Mode::Test)
+            .await
+            .unwrap();
+        work.commit().await.unwrap();
+
+        let logged = repo.list_logged_events(0, 10, None).await.unwrap();
+        let listed: Vec<_> = logged
+            .iter()
+            .map(|e| (e.sequence, e.event_type.as_str()))
+            .collect();
+        assert_eq!(listed, vec![(1, "account.created"), (2, "account.frozen")]);
+        assert_eq!(logged[0].data["account_id"], serde_json::json!(account.id));
+        assert_eq!(
+            repo.list_logged_events(0, 10, Some(Mode::Test))
+                .await
+                .unwrap(),
+            logged[1..]
+        );
+        assert_eq!(
+            logged[0].occurred_at.timestamp_micros(),
+            account.created_at.timestamp_micros()
+        );
+
+        let after = repo.list_logged_events(1, 10, None).await.unwrap();
+        assert_eq!(after, logged[1..]);
+        assert_eq!(repo.list_logged_events(0, 1, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_delivery_log_and_redelivery() {
+        let repo = setup_repo().await;
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let repo = repo.with_clock(clock.clone());
+        let endpoint_id = WebhookEndpointId(Uuid::new_v4());
+        let mut events = Vec::new();
+        for _ in 0..4 {
+            let event = repo
+                .create_webhook_event(endpoint_id, "deposit.success", serde_json::json!({}))
+                .await
+                .unwrap();
+            events.push(event.id);
+        }
+        repo.create_webhook_event(
+            WebhookEndpointId(Uuid::new_v4()),
+            "deposit.success",
+            serde_json::json!({}),
+        )
+        .await
+        .unwrap();
+        repo.update_webhook_status(events[0], WebhookStatus::Completed, None)
+            .await
+            .unwrap();
+        repo.reschedule_webhook(
+            events[1],
+            "HTTP 503".to_string(),
+            std::time::Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+        repo.update_webhook_status(
+            events[2],
+            WebhookStatus::DeadLettered,
+            Some("HTTP 410".to_string()),
+        )
+        .await
+        .unwrap();
+
+        let sequences = |events: Vec<WebhookEvent>| -> Vec<i64> {
+            events.iter().map(|e| e.delivery_sequence).collect()
+        };
+        let all = WebhookDeliveryFilter::default();
+        assert_eq!(
+            sequences(
+                repo.list_webhook_deliveries(endpoint_id, &all, 10)
+                    .await
+                    .unwrap()
+            ),
+            vec![4, 3, 2, 1]
+        );
+        let older = WebhookDeliveryFilter {
+            before_sequence: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            sequences(
+                repo.list_webhook_deliveries(endpoint_id, &older, 1)
+                    .await
+                    .unwrap()
+            ),
+            vec![2]
+        );
+        let failed = WebhookDeliveryFilter {
+            status: Some(WebhookStatus::Failed),
+            ..Default::default()
+        };
+        let listed = repo
+            .list_webhook_deliveries(endpoint_id, &failed, 10)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].last_error.as_deref(), Some("HTTP 503"));
+
+        // Only failed and dead-lettered events are requeued.
+        clock.advance(Duration::minutes(5));
+        assert!(!repo.requeue_webhook_event(events[0]).await.unwrap());
+        assert!(!repo.requeue_webhook_event(events[3]).await.unwrap());
+        assert!(!repo.requeue_webhook_event(Uuid::new_v4()).await.unwrap());
+        for id in [events[1], events[2]] {
+            assert!(repo.requeue_webhook_event(id).await.unwrap());
+            let event = repo.get_webhook_event(id).await.unwrap().unwrap();
+            assert_eq!(event.status, WebhookStatus::Pending);
+            assert_eq!(event.attempts, 0);
+            assert_eq!(event.processed_at, None);
+            assert_eq!(event.next_attempt_at, Some(start + Duration::minutes(5)));
+            assert!(!repo.requeue_webhook_event(id).await.unwrap());
+        }
+        assert_eq!(repo.get_pending_webhooks(10).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_endpoint_timeouts_round_trip() {
+        let repo = setup_repo().await;
+        let timeouts = WebhookTimeouts::new(Some(2_500), Some(500)).unwrap();
+
+        let endpoint = repo
+            .register_webhook_endpoint(
+                "https://example.com/slow",
+                vec![],
+                timeouts,
+                WebhookSignatureAlgorithm::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(endpoint.timeouts, timeouts);
+
+        let fetched = repo
+            .get_webhook_endpoint(WebhookEndpointId::from_uuid(endpoint.id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.timeouts, timeouts);
+        assert_eq!(fetched.url, "https://example.com/slow");
+        assert_eq!(
+            repo.list_webhook_endpoints(None).await.unwrap()[0].timeouts,
+            timeouts
+        );
+        assert!(
+            repo.get_webhook_endpoint(WebhookEndpointId(Uuid::new_v4()))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_endpoints_register_together_or_not_at_all() {
+        let endpoint = |url: &str| NewWebhookEndpoint {
+            url: url.to_string(),
+            events: vec!["deposit.success".into()],
+            timeouts: WebhookTimeouts::default(),
+            signature_algorithm: WebhookSignatureAlgorithm::default(),
+            mode: Mode::Live,
+        };
+        let repo = setup_repo().await;
+        let registered = repo
+            .register_webhook_endpoints(vec![
+                endpoint("https://example.com/a"),
+                NewWebhookEndpoint {
+                    mode: Mode::Test,
+                    ..endpoint("https://example.com/b")
+                },
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            registered
+                .iter()
+                .map(|e| e.url.as_str())
+                .collect::<Vec<_>>(),
+            ["https://example.com/a", "https://example.com/b"]
+        );
+        assert_eq!(repo.list_webhook_endpoints(None).await.unwrap().len(), 2);
+        let test = repo.list_webhook_endpoints(Some(Mode::Test)).await.unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].url, "https://example.com/b");
+
+        // Events queued for an endpoint take its mode.
+        let event = repo
+            .create_webhook_event(
+                WebhookEndpointId(test[0].id),
+                "deposit.success",
+                serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(event.mode, Mode::Test);
+        assert_eq!(
+            repo.get_webhook_event(event.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .mode,
+            Mode::Test
+        );
+
+        // Handing out the same ID twice makes the second insert fail, which
+        // takes the first down with it.
+        struct SameId(Uuid);
+        impl payments_types::IdGenerator for SameId {
+            fn new_id(&self) -> Uuid {
+                self.0
+            }
+        }
+        let repo = setup_repo()
+            .await
+            .with_id_generator(Arc::new(SameId(Uuid::new_v4())));
+        assert!(
+            repo.register_webhook_endpoints(vec![
+                endpoint("https://example.com/a"),
+                endpoint("https://example.com/b"),
+            ])
+            .await
+            .is_err()
+        );
+        assert!(repo.list_webhook_endpoints(None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_endpoint_update_rotate_and_delete() {
+        let repo = setup_repo().await;
+        let endpoint = repo
+            .register_webhook_endpoint(
+                "https://example.com/hook",
+                vec!["deposit.success".into()],
+                WebhookTimeouts::default(),
+                WebhookSignatureAlgorithm::default(),
+            )
+            .await
+            .unwrap();
+        let id = WebhookEndpointId::from_uuid(endpoint.id);
+
+        let paused = repo
+            .update_webhook_endpoint(id, None, Some(false), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!paused.is_active);
+        assert_eq!(paused.events, vec!["deposit.success"]);
+        let resubscribed = repo
+            .update_webhook_endpoint(id, Some(vec!["hold.*".into()]), None, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!resubscribed.is_active);
+        assert_eq!(resubscribed.events, vec!["hold.*"]);
+        assert_eq!(
+            resubscribed.signature_algorithm,
+            WebhookSignatureAlgorithm::HmacSha256
+        );
+        assert_eq!(resubscribed.public_key, None);
+
+        let ed25519 = repo
+            .update_webhook_endpoint(id, None, None, Some(WebhookSignatureAlgorithm::Ed25519))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ed25519.signature_algorithm,
+            WebhookSignatureAlgorithm::Ed25519
+        );
+        assert_eq!(ed25519.events, vec!["hold.*"]);
+        let public_key = ed25519.public_key.clone().unwrap();
+        // The key pair is not derived from the secret receivers are given
+        assert_eq!(ed25519.signature_key(), ed25519.signing_key);
+        assert_ne!(
+            crate::security::webhook_public_key(
+                WebhookSignatureAlgorithm::Ed25519,
+                &ed25519.secret
+            ),
+            Some(public_key.clone())
+        );
+
+        let rotated = repo.rotate_webhook_secret(id).await.unwrap().unwrap();
+        assert_ne!(rotated.secret, endpoint.secret);
+        assert_ne!(rotated.public_key.as_ref(), Some(&public_key));
+        let fetched = repo.get_webhook_endpoint(id).await.unwrap().unwrap();
+        assert_eq!(fetched.secret, rotated.secret);
+        assert_eq!(fetched.public_key, rotated.public_key);
+        assert_eq!(fetched.signing_key, rotated.signing_key);
+
+        assert!(repo.delete_webhook_endpoint(id).await.unwrap());
+        assert!(!repo.delete_webhook_endpoint(id).await.unwrap());
+        assert!(repo.get_webhook_endpoint(id).await.unwrap().is_none());
+        assert!(repo.rotate_webhook_secret(id).await.unwrap().is_none());
+        assert!(
+            repo.update_webhook_endpoint(id, None, Some(true), None)
+                .await
+                .unwrap()
+                .is_none()
+        );
     }
 
     // ─────────────────────────────────────────────────────────────────────────────
@@ -506,12 +4057,15 @@ mod tests {
         assert_eq!(count_before, 0);
 
         // Create an API key
-        let (api_key, raw_key) = repo.create_api_key("test-key").await.unwrap();
+        let (api_key, raw_key) = repo
+            .create_api_key("test-key", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
 
         assert_eq!(api_key.name, "test-key");
         assert!(api_key.is_active);
-        assert!(raw_key.starts_with("sk_"));
-        assert_eq!(raw_key.len(), 35); // "sk_" + 32 chars
+        assert!(raw_key.starts_with("sk_live_"));
+        assert_eq!(raw_key.len(), 40); // "sk_live_" + 32 chars
 
         // Count should be 1 now
         let count_after = repo.count_api_keys().await.unwrap();
@@ -523,9 +4077,15 @@ mod tests {
         let repo = setup_repo().await;
 
         // Create multiple API keys
-        repo.create_api_key("key-1").await.unwrap();
-        repo.create_api_key("key-2").await.unwrap();
-        repo.create_api_key("key-3").await.unwrap();
+        repo.create_api_key("key-1", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
+        repo.create_api_key("key-2", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
+        repo.create_api_key("key-3", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
 
         // List all keys
         let keys = repo.list_api_keys().await.unwrap();
@@ -544,15 +4104,24 @@ mod tests {
         let repo = setup_repo().await;
 
         // Create an API key
-        let (api_key, _raw_key) = repo.create_api_key("to-delete").await.unwrap();
+        let (api_key, _raw_key) = repo
+            .create_api_key("to-delete", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
 
         // Verify it exists
         let count_before = repo.count_api_keys().await.unwrap();
         assert_eq!(count_before, 1);
 
-        // Delete the key
-        let deleted = repo.delete_api_key(api_key.id).await.unwrap();
+        // Delete the key, recording who did it
+        let admin = ApiKeyId::new();
+        let at = Utc.with_ymd_and_hms(2026, 4, 1, 9, 30, 0).unwrap();
+        let deleted = repo.delete_api_key(api_key.id, admin, at).await.unwrap();
         assert!(deleted);
+        let deactivated = repo.get_api_key(api_key.id).await.unwrap().unwrap();
+        assert!(!deactivated.is_active);
+        assert_eq!(deactivated.deactivated_at, Some(at));
+        assert_eq!(deactivated.deactivated_by, Some(admin));
 
         // Verify it no longer exists in active keys
         let count_after = repo.count_api_keys().await.unwrap();
@@ -563,13 +4132,83 @@ mod tests {
         assert!(keys.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_scoped_api_key_keeps_account() {
+        let repo = setup_repo().await;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Alice".into(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+
+        let (api_key, raw_key) = repo
+            .create_scoped_api_key("alice-app", account.id, &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
+        assert_eq!(api_key.account_id, Some(account.id));
+
+        let hash = crate::security::hash_api_key(&raw_key);
+        let verified = repo.verify_api_key_hash(&hash).await.unwrap().unwrap();
+        assert_eq!(verified.id, api_key.id);
+        assert_eq!(verified.account_id, Some(account.id));
+        let listed = repo.list_api_keys().await.unwrap();
+        assert_eq!(listed[0].account_id, Some(account.id));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_scopes_round_trip() {
+        let repo = setup_repo().await;
+        let scopes = [ApiKeyScope::AccountsRead, ApiKeyScope::TransactionsWrite];
+
+        let (api_key, raw_key) = repo
+            .create_api_key("reporting", &scopes, Mode::Live)
+            .await
+            .unwrap();
+        assert_eq!(api_key.scopes, scopes);
+
+        let hash = crate::security::hash_api_key(&raw_key);
+        let verified = repo.verify_api_key_hash(&hash).await.unwrap().unwrap();
+        assert_eq!(verified.scopes, scopes);
+        let fetched = repo.get_api_key(api_key.id).await.unwrap().unwrap();
+        assert_eq!(fetched.scopes, scopes);
+        assert!(!fetched.has_scope(ApiKeyScope::KeysAdmin));
+    }
+
+    /// Keys stored before the scopes column existed keep full access.
+    #[tokio::test]
+    async fn test_api_keys_without_scopes_hold_all_of_them() {
+        let repo = setup_repo().await;
+        let id = payments_types::ApiKeyId::new();
+        sqlx::query(
+            "INSERT INTO api_keys (id, name, key_hash, is_active, created_at) VALUES (?, 'legacy', 'hash', 1, ?)",
+        )
+        .bind(id.to_string())
+        .bind(Utc::now().to_rfc3339())
+        .execute(repo.pool())
+        .await
+        .unwrap();
+        // Scopes added since then are granted at startup.
+        repo.create_schema().await.unwrap();
+
+        let fetched = repo.get_api_key(id).await.unwrap().unwrap();
+        assert_eq!(fetched.scopes, ApiKeyScope::ALL);
+    }
+
     #[tokio::test]
     async fn test_delete_api_key_not_found() {
         let repo = setup_repo().await;
 
         // Try to delete a non-existent key
         let fake_id = payments_types::ApiKeyId::new();
-        let deleted = repo.delete_api_key(fake_id).await.unwrap();
+        let deleted = repo
+            .delete_api_key(fake_id, fake_id, Utc::now())
+            .await
+            .unwrap();
 
         assert!(!deleted);
     }
@@ -579,14 +4218,271 @@ mod tests {
         let repo = setup_repo().await;
 
         // Create an API key
-        let (api_key, _raw_key) = repo.create_api_key("double-delete").await.unwrap();
+        let (api_key, _raw_key) = repo
+            .create_api_key("double-delete", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
 
         // First delete should succeed
-        let deleted_first = repo.delete_api_key(api_key.id).await.unwrap();
+        let deleted_first = repo
+            .delete_api_key(api_key.id, api_key.id, Utc::now())
+            .await
+            .unwrap();
         assert!(deleted_first);
 
-        // Second delete should fail (key already inactive)
-        let deleted_second = repo.delete_api_key(api_key.id).await.unwrap();
+        // Second delete should fail (key already inactive) and keep the
+        // first deactivation time
+        let first = repo.get_api_key(api_key.id).await.unwrap().unwrap();
+        let deleted_second = repo
+            .delete_api_key(api_key.id, api_key.id, Utc::now() + Duration::hours(1))
+            .await
+            .unwrap();
         assert!(!deleted_second);
+        let second = repo.get_api_key(api_key.id).await.unwrap().unwrap();
+        assert_eq!(second.deactivated_at, first.deactivated_at);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_rate_limit_round_trip() {
+        let repo = setup_repo().await;
+        let (api_key, raw_key) = repo
+            .create_api_key("premium", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
+        assert_eq!(api_key.rate_limit_per_minute, None);
+
+        assert!(
+            repo.set_api_key_rate_limit(api_key.id, Some(1000))
+                .await
+                .unwrap()
+        );
+        let hash = crate::security::hash_api_key(&raw_key);
+        let verified = repo.verify_api_key_hash(&hash).await.unwrap().unwrap();
+        assert_eq!(verified.rate_limit_per_minute, Some(1000));
+
+        assert!(repo.set_api_key_rate_limit(api_key.id, None).await.unwrap());
+        let fetched = repo.get_api_key(api_key.id).await.unwrap().unwrap();
+        assert_eq!(fetched.rate_limit_per_minute, None);
+
+        // Deleted and unknown keys are left alone.
+        repo.delete_api_key(api_key.id, api_key.id, Utc::now())
+            .await
+            .unwrap();
+        assert!(
+            !repo
+                .set_api_key_rate_limit(api_key.id, Some(10))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !repo
+                .set_api_key_rate_limit(payments_types::ApiKeyId::new(), Some(10))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_key_touch_and_audit_round_trip() {
+        let repo = setup_repo().await;
+        let (api_key, _raw_key) = repo
+            .create_api_key("audited", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
+        assert!(api_key.last_used_at.is_none());
+
+        let used_at = Utc.with_ymd_and_hms(2026, 4, 1, 10, 0, 0).unwrap();
+        repo.touch_api_key(api_key.id, used_at).await.unwrap();
+        let touched = repo.get_api_key(api_key.id).await.unwrap().unwrap();
+        assert_eq!(touched.last_used_at, Some(used_at));
+
+        let admin = ApiKeyId::new();
+        let entries = [
+            ApiKeyAuditEntry {
+                api_key_id: api_key.id,
+                action: ApiKeyAuditAction::Created,
+                actor: Some(admin),
+                at: Utc.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap(),
+            },
+            ApiKeyAuditEntry {
+                api_key_id: api_key.id,
+                action: ApiKeyAuditAction::Used,
+                actor: None,
+                at: used_at,
+            },
+        ];
+        for entry in &entries {
+            repo.insert_api_key_audit(entry).await.unwrap();
+        }
+        assert_eq!(repo.list_api_key_audit(api_key.id).await.unwrap(), entries);
+        assert!(
+            repo.list_api_key_audit(ApiKeyId::new())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_injected_clock_and_ids() {
+        use std::sync::Arc;
+
+        use chrono::{DateTime, Utc};
+        use payments_types::{FixedClock, SequentialIdGenerator};
+
+        let frozen = DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let repo = setup_repo()
+            .await
+            .with_clock(Arc::new(FixedClock::new(frozen)))
+            .with_id_generator(Arc::new(SequentialIdGenerator::new()));
+
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Frozen".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            account.id.to_string(),
+            "00000000-0000-0000-0000-000000000001"
+        );
+        assert_eq!(account.created_at, frozen);
+
+        let tx = repo
+            .deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(100).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(tx.id.to_string(), "00000000-0000-0000-0000-000000000002");
+        assert_eq!(tx.created_at, frozen);
+
+        // The returned transaction is the one that was stored.
+        let stored = repo.get_transaction(tx.id).await.unwrap().unwrap();
+        assert_eq!(stored.created_at, frozen);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_transfer_storm() {
+        let repo = std::sync::Arc::new(setup_repo().await);
+
+        let report = crate::stress_tests::transfer_storm(repo, 4, 300).await;
+
+        // SQLite allows one writer at a time, so some transfers may lose the
+        // race. They must fail cleanly with a retryable conflict (the storm
+        // checks nothing was half-applied), and the rest must go through.
+        assert!(report.other_errors.is_empty(), "{:?}", report.other_errors);
+        assert!(report.succeeded > 0, "{:?}", report);
+    }
+
+    /// Many tasks draining one account: the conditional debit must let
+    /// through exactly the withdrawals the balance covers.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_withdrawals_never_overdraw() {
+        let db = FileRepo::new().await;
+
+        let report = crate::stress_tests::withdrawal_storm(db.repo.clone(), 80, 100).await;
+
+        assert_eq!(report.insufficient_funds, 30, "{:?}", report);
+    }
+
+    #[tokio::test]
+    async fn test_lost_debit_race_is_a_retryable_conflict() {
+        let db = FileRepo::new().await;
+        let repo = &db.repo;
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Racy".to_string(),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        repo.deposit(DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+
+        // Another connection holds the write lock, as a concurrent withdrawal
+        // would; once the busy timeout runs out the debit reports a conflict.
+        let mut writer = repo.pool().begin().await.unwrap();
+        sqlx::query("UPDATE accounts SET balance = balance WHERE id = ?")
+            .bind(account.id.to_string())
+            .execute(&mut *writer)
+            .await
+            .unwrap();
+
+        let result = repo
+            .withdraw(WithdrawRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(60).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await;
+        assert!(
+            matches!(&result, Err(e @ RepoError::Conflict(_)) if e.is_transient()),
+            "{:?}",
+            result
+        );
+        writer.rollback().await.unwrap();
+
+        assert_eq!(balance(repo, account.id).await, 100);
+    }
+
+    #[tokio::test]
+    async fn test_closed_pool_is_unavailable() {
+        let repo = setup_repo().await;
+        repo.ping().await.unwrap();
+        repo.pool().close().await;
+
+        let result = repo.get_account(AccountId::new()).await;
+        assert!(matches!(result, Err(RepoError::Unavailable(_))));
+        assert!(matches!(repo.ping().await, Err(RepoError::Unavailable(_))));
+
+        let retrying = crate::RetryRepo::new(repo, crate::RetryPolicy::default());
+        let result = retrying.list_accounts(None).await;
+        assert!(matches!(result, Err(RepoError::Unavailable(_))));
+    }
+
+    #[test]
+    fn test_schema_version_is_the_latest_migration() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
+        let latest = std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|entry| {
+                let name = entry.unwrap().file_name().into_string().unwrap();
+                name.split('_').next()?.parse::<u32>().ok()
+            })
+            .max();
+        assert_eq!(latest, Some(crate::SCHEMA_VERSION));
     }
 }
@@ -0,0 +1,269 @@
+//! Concurrent stress harnesses shared by the adapter test suites.
+//!
+//! [`transfer_storm`] fires a burst of transfers between a handful of
+//! accounts all at once, then checks the invariants every locking scheme has
+//! to preserve:
+//!
+//! - the total balance across accounts is unchanged,
+//! - no balance is negative,
+//! - each balance equals its opening deposit plus the ledger of transfers that
+//!   reported success (so a failed transfer left nothing half-applied).
+//!
+//! [`withdrawal_storm`] hammers a single account with withdrawals, retrying
+//! the ones that lose a race, and checks that exactly as many go through as
+//! the balance covers.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use payments_types::{
+    AccountId, CreateAccountRequest, CurrencyCode, DepositRequest, DomainError, OwnerId,
+    PositiveAmount, RepoError, TransactionRepository, TransactionType, TransferRequest,
+    WithdrawRequest,
+};
+
+/// Opening balance of every account in the storm.
+pub const OPENING_BALANCE: i64 = 5_000;
+
+/// How a storm went, for backend-specific assertions.
+#[derive(Debug, Default)]
+pub struct StormReport {
+    pub succeeded: usize,
+    pub insufficient_funds: usize,
+    /// Attempts that lost a race with another writer (`RepoError::Conflict`)
+    pub conflicts: usize,
+    /// Anything else
+    pub other_errors: Vec<String>,
+}
+
+/// Runs `transfers` concurrent transfers among `accounts` fresh USD accounts
+/// and asserts the invariants listed in the module docs.
+pub async fn transfer_storm<R: TransactionRepository + 'static>(
+    repo: Arc<R>,
+    accounts: usize,
+    transfers: usize,
+) -> StormReport {
+    assert!(accounts >= 2, "a storm needs at least two accounts");
+
+    let mut ids = Vec::with_capacity(accounts);
+    for i in 0..accounts {
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: format!("Storm {}", i),
+                currency: CurrencyCode::USD,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        repo.deposit(DepositRequest {
+            account_id: account.id,
+            amount: PositiveAmount::new(OPENING_BALANCE).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap();
+        ids.push(account.id);
+    }
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for i in 0..transfers {
+        // Deterministic but well-mixed pairs and amounts; some transfers are
+        // large enough to hit insufficient funds once balances drift.
+        let from = ids[i % accounts];
+        let mut to = ids[(i * 7 + 1) % accounts];
+        if to == from {
+            to = ids[(i + 1) % accounts];
+        }
+        let amount = 1 + (i as i64 * 37) % 900;
+        let repo = repo.clone();
+        tasks.spawn(async move {
+            repo.transfer(TransferRequest {
+                from_account_id: from,
+                to_account_id: to,
+                amount: PositiveAmount::new(amount).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                purpose_code: None,
+                convert_currency: false,
+                quote_id: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+        });
+    }
+
+    let mut report = StormReport::default();
+    while let Some(result) = tasks.join_next().await {
+        match result.expect("transfer task panicked") {
+            Ok(_) => report.succeeded += 1,
+            Err(RepoError::Domain(DomainError::InsufficientFunds { .. })) => {
+                report.insufficient_funds += 1
+            }
+            Err(RepoError::Conflict(_)) => report.conflicts += 1,
+            Err(e) => report.other_errors.push(e.to_string()),
+        }
+    }
+
+    let mut balances = HashMap::new();
+    for id in &ids {
+        let account = repo.get_account(*id).await.unwrap().unwrap();
+        assert!(
+            account.balance.amount() >= 0,
+            "account {} went negative: {}",
+            id,
+            account.balance.amount()
+        );
+        balances.insert(*id, account.balance.amount());
+    }
+
+    let total: i64 = balances.values().sum();
+    assert_eq!(
+        total,
+        OPENING_BALANCE * accounts as i64,
+        "money was created or destroyed"
+    );
+
+    let mut recorded_transfers = 0;
+    for id in &ids {
+        let history = repo.list_transactions_for_account(*id).await.unwrap();
+        let ledger: i64 = history.iter().map(|tx| ledger_effect(*id, tx)).sum();
+        assert_eq!(
+            balances[id], ledger,
+            "balance of {} disagrees with its transaction history",
+            id
+        );
+        recorded_transfers += history
+            .iter()
+            .filter(|tx| {
+                tx.transaction_type == TransactionType::Transfer
+                    && tx.source_account_id == Some(*id)
+            })
+            .count();
+    }
+    assert_eq!(
+        recorded_transfers, report.succeeded,
+        "a failed transfer left a transaction behind, or a successful one did not"
+    );
+
+    report
+}
+
+/// Runs `withdrawals` concurrent withdrawals of `amount` from one fresh USD
+/// account holding [`OPENING_BALANCE`], retrying each while it fails with a
+/// transient error. Asserts that exactly the withdrawals the balance covers
+/// succeed and that the account ends at what is left over.
+pub async fn withdrawal_storm<R: TransactionRepository + 'static>(
+    repo: Arc<R>,
+    withdrawals: usize,
+    amount: i64,
+) -> StormReport {
+    let account = repo
+        .create_account(CreateAccountRequest {
+            name: "Withdrawal storm".into(),
+            currency: CurrencyCode::USD,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: None,
+        })
+        .await
+        .unwrap();
+    repo.deposit(DepositRequest {
+        account_id: account.id,
+        amount: PositiveAmount::new(OPENING_BALANCE).unwrap(),
+        currency: CurrencyCode::USD,
+        idempotency_key: None,
+        reference: None,
+        counterparty: None,
+        metadata: Default::default(),
+        encrypted_memo: None,
+    })
+    .await
+    .unwrap();
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for _ in 0..withdrawals {
+        let repo = repo.clone();
+        tasks.spawn(async move {
+            let mut conflicts = 0;
+            loop {
+                let result = repo
+                    .withdraw(WithdrawRequest {
+                        account_id: account.id,
+                        amount: PositiveAmount::new(amount).unwrap(),
+                        currency: CurrencyCode::USD,
+                        idempotency_key: None,
+                        reference: None,
+                        counterparty: None,
+                        purpose_code: None,
+                        metadata: Default::default(),
+                        encrypted_memo: None,
+                    })
+                    .await;
+                match result {
+                    Err(e) if e.is_transient() && conflicts < MAX_RETRIES => {
+                        conflicts += 1;
+                        tokio::task::yield_now().await;
+                    }
+                    result => return (result, conflicts),
+                }
+            }
+        });
+    }
+
+    let mut report = StormReport::default();
+    while let Some(result) = tasks.join_next().await {
+        let (result, conflicts) = result.expect("withdrawal task panicked");
+        report.conflicts += conflicts;
+        match result {
+            Ok(_) => report.succeeded += 1,
+            Err(RepoError::Domain(DomainError::InsufficientFunds { .. })) => {
+                report.insufficient_funds += 1
+            }
+            Err(e) => report.other_errors.push(e.to_string()),
+        }
+    }
+    assert!(
+        report.other_errors.is_empty(),
+        "withdrawals failed: {:?}",
+        report.other_errors
+    );
+
+    let covered = (OPENING_BALANCE / amount) as usize;
+    assert_eq!(
+        report.succeeded,
+        covered.min(withdrawals),
+        "withdrawals went through that the balance did not cover, or the other way round"
+    );
+    let account = repo.get_account(account.id).await.unwrap().unwrap();
+    assert_eq!(
+        account.balance.amount(),
+        OPENING_BALANCE - amount * report.succeeded as i64
+    );
+
+    report
+}
+
+/// How often [`withdrawal_storm`] retries one withdrawal before giving up.
+const MAX_RETRIES: usize = 100;
+
+/// Signed effect of `tx` on `account`'s balance.
+fn ledger_effect(account: AccountId, tx: &payments_types::Transaction) -> i64 {
+    let amount = tx.amount.amount();
+    let mut effect = 0;
+    if tx.destination_account_id == Some(account) {
+        effect += amount;
+    }
+    if tx.source_account_id == Some(account) {
+        effect -= amount;
+    }
+    effect
+}
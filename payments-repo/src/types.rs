@@ -1,10 +1,13 @@
 //! Shared database types with feature-gated fields for SQLite and PostgreSQL.
 
+use std::collections::HashMap;
+
 use sqlx::FromRow;
 
 use payments_types::{
-    Account, AccountId, CurrencyCode, DynMoney, RepoError, Transaction, TransactionId,
-    TransactionType, WebhookEvent, WebhookStatus,
+    Account, AccountId, AccountStatus, ApiKeyScope, Counterparty, CurrencyCode, DomainError,
+    DynMoney, EncryptedMemo, FxConversion, Mode, OwnerId, RepoError, RiskAssessment, RiskDecision,
+    SpendingRules, Transaction, TransactionId, TransactionType, WebhookEvent, WebhookStatus,
 };
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -30,12 +33,34 @@ pub struct DbAccount {
 
     pub name: String,
     pub balance: i64,
+    pub held: i64,
     pub currency: String,
 
     #[cfg(not(feature = "sqlite"))]
     pub created_at: DateTime<Utc>,
     #[cfg(feature = "sqlite")]
     pub created_at: String,
+
+    pub status: String,
+
+    #[cfg(not(feature = "sqlite"))]
+    pub status_changed_at: Option<DateTime<Utc>>,
+    #[cfg(feature = "sqlite")]
+    pub status_changed_at: Option<String>,
+
+    #[cfg(not(feature = "sqlite"))]
+    pub merged_into: Option<Uuid>,
+    #[cfg(feature = "sqlite")]
+    pub merged_into: Option<String>,
+
+    pub overdraft_limit: i64,
+
+    #[cfg(not(feature = "sqlite"))]
+    pub owner_id: Uuid,
+    #[cfg(feature = "sqlite")]
+    pub owner_id: String,
+
+    pub mode: String,
 }
 
 /// Transaction row from database.
@@ -62,6 +87,19 @@ pub struct DbTransaction {
 
     pub idempotency_key: Option<String>,
     pub reference: Option<String>,
+    pub counterparty_name: Option<String>,
+    pub counterparty_external_id: Option<String>,
+    pub purpose_code: Option<String>,
+    pub fx_rate: Option<f64>,
+    pub converted_amount: Option<i64>,
+    pub converted_currency: Option<String>,
+    pub risk_decision: Option<String>,
+    /// JSON array of reasons
+    pub risk_reasons: Option<String>,
+    /// JSON object of metadata, NULL when there is none
+    pub metadata: Option<String>,
+    /// JSON object of the encrypted memo, NULL when there is none
+    pub encrypted_memo: Option<String>,
 
     #[cfg(not(feature = "sqlite"))]
     pub created_at: DateTime<Utc>,
@@ -103,6 +141,16 @@ pub struct DbWebhookEvent {
 
     pub attempts: i32,
     pub last_error: Option<String>,
+
+    #[cfg(not(feature = "sqlite"))]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[cfg(feature = "sqlite")]
+    pub next_attempt_at: Option<String>,
+
+    /// Nullable in the schema, but backfilled for rows older than the column
+    pub delivery_sequence: Option<i64>,
+
+    pub mode: String,
 }
 
 impl DbWebhookEvent {
@@ -112,20 +160,22 @@ impl DbWebhookEvent {
             "PROCESSING" => WebhookStatus::Processing,
             "COMPLETED" => WebhookStatus::Completed,
             "FAILED" => WebhookStatus::Failed,
+            "DEAD_LETTERED" => WebhookStatus::DeadLettered,
             _ => WebhookStatus::Pending,
         };
 
         #[cfg(not(feature = "sqlite"))]
-        let (id, endpoint_id, payload, created_at, processed_at) = (
+        let (id, endpoint_id, payload, created_at, processed_at, next_attempt_at) = (
             self.id,
             self.endpoint_id,
             self.payload,
             self.created_at,
             self.processed_at,
+            self.next_attempt_at,
         );
 
         #[cfg(feature = "sqlite")]
-        let (id, endpoint_id, payload, created_at, processed_at) = {
+        let (id, endpoint_id, payload, created_at, processed_at, next_attempt_at) = {
             let uuid =
                 uuid::Uuid::parse_str(&self.id).map_err(|e| RepoError::Database(e.to_string()))?;
 
@@ -139,16 +189,23 @@ impl DbWebhookEvent {
                 .map_err(|e| RepoError::Database(e.to_string()))?
                 .with_timezone(&chrono::Utc);
 
-            let processed_at = match self.processed_at {
-                Some(s) => Some(
-                    chrono::DateTime::parse_from_rfc3339(&s)
-                        .map_err(|e| RepoError::Database(e.to_string()))?
-                        .with_timezone(&chrono::Utc),
-                ),
-                None => None,
+            let parse_optional = |value: Option<String>| match value {
+                Some(s) => chrono::DateTime::parse_from_rfc3339(&s)
+                    .map(|t| Some(t.with_timezone(&chrono::Utc)))
+                    .map_err(|e| RepoError::Database(e.to_string())),
+                None => Ok(None),
             };
+            let processed_at = parse_optional(self.processed_at)?;
+            let next_attempt_at = parse_optional(self.next_attempt_at)?;
 
-            (uuid, endpoint_uuid, payload, created_at, processed_at)
+            (
+                uuid,
+                endpoint_uuid,
+                payload,
+                created_at,
+                processed_at,
+                next_attempt_at,
+            )
         };
 
         Ok(WebhookEvent {
@@ -161,22 +218,35 @@ impl DbWebhookEvent {
             processed_at,
             attempts: self.attempts,
             last_error: self.last_error,
+            next_attempt_at,
+            delivery_sequence: self.delivery_sequence.unwrap_or(0),
+            mode: self.mode.parse().map_err(RepoError::Database)?,
         })
     }
 }
 
-/// Balance-only row for queries.
-#[cfg(feature = "sqlite")]
-#[derive(FromRow)]
-pub struct DbBalance {
-    pub balance: i64,
-}
-
-/// Balance and currency row for queries.
+/// Balance, held amount and currency row for queries.
 #[derive(FromRow)]
 pub struct DbAccountBalance {
     pub balance: i64,
+    pub held: i64,
     pub currency: String,
+    pub overdraft_limit: i64,
+}
+
+impl DbAccountBalance {
+    /// Fails with `InsufficientFunds` unless the unheld balance and the
+    /// overdraft limit cover `amount`.
+    pub fn check_available(&self, amount: i64) -> Result<(), RepoError> {
+        let available = self.balance - self.held + self.overdraft_limit;
+        if available < amount {
+            return Err(RepoError::Domain(DomainError::InsufficientFunds {
+                available,
+                requested: amount,
+            }));
+        }
+        Ok(())
+    }
 }
 
 /// Currency-only row for queries.
@@ -185,50 +255,132 @@ pub struct DbAccountCurrency {
     pub currency: String,
 }
 
-/// API key row from database.
-#[derive(FromRow)]
-pub struct DbApiKey {
-    #[cfg(not(feature = "sqlite"))]
-    pub id: Uuid,
-    #[cfg(feature = "sqlite")]
-    pub id: String,
+// ─────────────────────────────────────────────────────────────────────────────
+// Parsing helpers
+// ─────────────────────────────────────────────────────────────────────────────
 
-    pub name: String,
-    pub key_hash: String,
+/// Rejects a transfer leg in `got` against an account stored in `stored`.
+pub fn check_currency(stored: &str, got: CurrencyCode) -> Result<(), RepoError> {
+    let expected = parse_currency(stored)?;
+    if expected != got {
+        return Err(RepoError::Domain(DomainError::CurrencyMismatch {
+            expected,
+            got,
+        }));
+    }
+    Ok(())
+}
 
-    #[cfg(not(feature = "sqlite"))]
-    pub account_id: Option<Uuid>,
-    #[cfg(feature = "sqlite")]
-    pub account_id: Option<String>,
+/// Parses a stored currency code. Codes this build does not know, e.g. ones
+/// written by a newer release, are reported as unsupported rather than as a
+/// database failure.
+pub fn parse_currency(s: &str) -> Result<CurrencyCode, RepoError> {
+    s.parse()
+        .map_err(|_| RepoError::UnsupportedCurrency(s.to_string()))
+}
 
-    #[cfg(not(feature = "sqlite"))]
-    pub is_active: bool,
-    #[cfg(feature = "sqlite")]
-    pub is_active: i64,
+/// Escapes `%`, `_` and `\` so user input matches literally in a
+/// `LIKE ... ESCAPE '\'` pattern.
+pub fn escape_like(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
 
-    #[cfg(not(feature = "sqlite"))]
-    pub created_at: DateTime<Utc>,
-    #[cfg(feature = "sqlite")]
-    pub created_at: String,
+/// Builds `SpendingRules` from `(purpose_code, effect)` rows of
+/// `account_spending_rules`.
+pub fn spending_rules_from_rows(rows: Vec<(String, String)>) -> SpendingRules {
+    let mut rules = SpendingRules::default();
+    for (code, effect) in rows {
+        match effect.as_str() {
+            "DENY" => rules.denied.push(code),
+            _ => rules.allowed.push(code),
+        }
+    }
+    rules
+}
 
-    #[cfg(not(feature = "sqlite"))]
-    pub last_used_at: Option<DateTime<Utc>>,
-    #[cfg(feature = "sqlite")]
-    pub last_used_at: Option<String>,
+/// `(purpose_code, effect)` rows to store for `rules`.
+pub fn spending_rule_rows(rules: &SpendingRules) -> impl Iterator<Item = (&str, &'static str)> {
+    let allowed = rules.allowed.iter().map(|c| (c.as_str(), "ALLOW"));
+    let denied = rules.denied.iter().map(|c| (c.as_str(), "DENY"));
+    allowed.chain(denied)
 }
 
-// ─────────────────────────────────────────────────────────────────────────────
-// Parsing helpers
-// ─────────────────────────────────────────────────────────────────────────────
+/// The `api_keys.scopes` column: scope names separated by spaces.
+pub fn scopes_column(scopes: &[ApiKeyScope]) -> String {
+    let names: Vec<&str> = scopes.iter().map(ApiKeyScope::as_str).collect();
+    names.join(" ")
+}
 
-pub fn parse_currency(s: &str) -> Result<CurrencyCode, RepoError> {
-    match s {
-        "USD" => Ok(CurrencyCode::USD),
-        "EUR" => Ok(CurrencyCode::EUR),
-        "GBP" => Ok(CurrencyCode::GBP),
-        "INR" => Ok(CurrencyCode::INR),
-        _ => Err(RepoError::Database(format!("Unknown currency: {}", s))),
+/// Parses the `api_keys.scopes` column in canonical order, since migrations
+/// that grant new scopes append them.
+pub fn parse_scopes(s: &str) -> Result<Vec<ApiKeyScope>, RepoError> {
+    let mut scopes = s
+        .split_whitespace()
+        .map(|name| {
+            name.parse()
+                .map_err(|_| RepoError::Database(format!("Unknown API key scope: {}", name)))
+        })
+        .collect::<Result<Vec<ApiKeyScope>, _>>()?;
+    scopes.sort();
+    scopes.dedup();
+    Ok(scopes)
+}
+
+/// Converts a webhook retry delay for adding to a timestamp.
+pub fn retry_delay(delay: std::time::Duration) -> Result<chrono::TimeDelta, RepoError> {
+    chrono::TimeDelta::from_std(delay)
+        .map_err(|e| RepoError::Database(format!("Invalid retry delay: {}", e)))
+}
+
+/// The `transactions.metadata` column: a JSON object, or NULL when empty.
+pub fn metadata_json(metadata: &HashMap<String, String>) -> Result<Option<String>, RepoError> {
+    if metadata.is_empty() {
+        return Ok(None);
     }
+    serde_json::to_string(metadata)
+        .map(Some)
+        .map_err(|e| RepoError::Database(e.to_string()))
+}
+
+/// The `transactions.encrypted_memo` column: a JSON object, or NULL.
+pub fn memo_json(memo: Option<&EncryptedMemo>) -> Result<Option<String>, RepoError> {
+    memo.map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| RepoError::Database(e.to_string()))
+}
+
+/// Parses the `transactions.encrypted_memo` column.
+fn parse_memo(memo: Option<&str>) -> Result<Option<EncryptedMemo>, RepoError> {
+    memo.map(serde_json::from_str)
+        .transpose()
+        .map_err(|e| RepoError::Database(e.to_string()))
+}
+
+/// Parses the `transactions.metadata` column.
+fn parse_metadata(metadata: Option<&str>) -> Result<HashMap<String, String>, RepoError> {
+    metadata
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|e| RepoError::Database(e.to_string()))
+        .map(Option::unwrap_or_default)
+}
+
+/// Parses a stored risk decision and its JSON array of reasons.
+fn parse_risk(decision: &str, reasons: Option<&str>) -> Result<RiskAssessment, RepoError> {
+    let decision: RiskDecision = decision.parse().map_err(RepoError::Database)?;
+    let reasons = reasons
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|e| RepoError::Database(e.to_string()))?
+        .unwrap_or_default();
+    Ok(RiskAssessment { decision, reasons })
 }
 
 pub fn parse_transaction_type(s: &str) -> Result<TransactionType, RepoError> {
@@ -251,22 +403,57 @@ impl DbAccount {
     /// Convert database row to domain Account.
     pub fn into_domain(self) -> Result<Account, RepoError> {
         let currency = parse_currency(&self.currency)?;
-        let money = DynMoney::new(self.balance, currency).map_err(RepoError::Domain)?;
+        let money = DynMoney::balance(self.balance, currency);
+
+        let status: AccountStatus = self.status.parse().map_err(RepoError::Database)?;
+        let mode: Mode = self.mode.parse().map_err(RepoError::Database)?;
 
         #[cfg(not(feature = "sqlite"))]
-        let (id, created_at) = (AccountId::from_uuid(self.id), self.created_at);
+        let (id, created_at, status_changed_at, merged_into, owner_id) = (
+            AccountId::from_uuid(self.id),
+            self.created_at,
+            self.status_changed_at,
+            self.merged_into.map(AccountId::from_uuid),
+            OwnerId::from_uuid(self.owner_id),
+        );
 
         #[cfg(feature = "sqlite")]
-        let (id, created_at) = {
+        let (id, created_at, status_changed_at, merged_into, owner_id) = {
+            let parse = |value: &str| {
+                chrono::DateTime::parse_from_rfc3339(value)
+                    .map(|dt| dt.with_timezone(&chrono::Utc))
+                    .map_err(|e| RepoError::Database(e.to_string()))
+            };
             let uuid =
                 uuid::Uuid::parse_str(&self.id).map_err(|e| RepoError::Database(e.to_string()))?;
-            let dt = chrono::DateTime::parse_from_rfc3339(&self.created_at)
-                .map_err(|e| RepoError::Database(e.to_string()))?
-                .with_timezone(&chrono::Utc);
-            (AccountId::from_uuid(uuid), dt)
+            let changed_at = self.status_changed_at.as_deref().map(parse).transpose()?;
+            let merged_into = self
+                .merged_into
+                .as_deref()
+                .map(|id| {
+                    uuid::Uuid::parse_str(id)
+                        .map(AccountId::from_uuid)
+                        .map_err(|e| RepoError::Database(e.to_string()))
+                })
+                .transpose()?;
+            let owner_id = uuid::Uuid::parse_str(&self.owner_id)
+                .map_err(|e| RepoError::Database(e.to_string()))?;
+            (
+                AccountId::from_uuid(uuid),
+                parse(&self.created_at)?,
+                changed_at,
+                merged_into,
+                OwnerId::from_uuid(owner_id),
+            )
         };
 
-        Ok(Account::from_parts(id, self.name, money, created_at))
+        Ok(Account::from_parts(id, self.name, money, created_at)
+            .with_held(self.held)
+            .with_status(status, status_changed_at)
+            .with_merged_into(merged_into)
+            .with_overdraft_limit(self.overdraft_limit)
+            .with_owner(owner_id)
+            .with_mode(mode))
     }
 }
 
@@ -311,6 +498,27 @@ impl DbTransaction {
             (TransactionId::from_uuid(uuid), source, dest, dt)
         };
 
+        let counterparty = self.counterparty_name.map(|name| Counterparty {
+            name,
+            external_id: self.counterparty_external_id,
+        });
+
+        let conversion = match (self.fx_rate, self.converted_amount, self.converted_currency) {
+            (Some(rate), Some(amount), Some(currency)) => Some(FxConversion {
+                rate,
+                converted_amount: DynMoney::new(amount, parse_currency(&currency)?)
+                    .map_err(RepoError::Domain)?,
+            }),
+            _ => None,
+        };
+
+        let risk = self
+            .risk_decision
+            .map(|decision| parse_risk(&decision, self.risk_reasons.as_deref()))
+            .transpose()?;
+        let metadata = parse_metadata(self.metadata.as_deref())?;
+        let encrypted_memo = parse_memo(self.encrypted_memo.as_deref())?;
+
         Ok(Transaction::from_parts(
             id,
             tx_type,
@@ -320,64 +528,12 @@ impl DbTransaction {
             self.idempotency_key,
             self.reference,
             created_at,
-        ))
-    }
-}
-
-impl DbApiKey {
-    /// Convert database row to domain ApiKey.
-    pub fn into_domain(self) -> Result<payments_types::ApiKey, RepoError> {
-        #[cfg(not(feature = "sqlite"))]
-        let (id, account_id, is_active, created_at, last_used_at) = (
-            payments_types::ApiKeyId::from_uuid(self.id),
-            self.account_id.map(payments_types::AccountId::from_uuid),
-            self.is_active,
-            self.created_at,
-            self.last_used_at,
-        );
-
-        #[cfg(feature = "sqlite")]
-        let (id, account_id, is_active, created_at, last_used_at) = {
-            let uuid =
-                uuid::Uuid::parse_str(&self.id).map_err(|e| RepoError::Database(e.to_string()))?;
-
-            let account_id = self
-                .account_id
-                .map(|s| uuid::Uuid::parse_str(&s))
-                .transpose()
-                .map_err(|e| RepoError::Database(e.to_string()))?
-                .map(payments_types::AccountId::from_uuid);
-
-            let is_active = self.is_active != 0;
-
-            let created_at = chrono::DateTime::parse_from_rfc3339(&self.created_at)
-                .map_err(|e| RepoError::Database(e.to_string()))?
-                .with_timezone(&chrono::Utc);
-
-            let last_used_at = self
-                .last_used_at
-                .map(|s| chrono::DateTime::parse_from_rfc3339(&s))
-                .transpose()
-                .map_err(|e| RepoError::Database(e.to_string()))?
-                .map(|dt| dt.with_timezone(&chrono::Utc));
-
-            (
-                payments_types::ApiKeyId::from_uuid(uuid),
-                account_id,
-                is_active,
-                created_at,
-                last_used_at,
-            )
-        };
-
-        Ok(payments_types::ApiKey {
-            id,
-            name: self.name,
-            key_hash: self.key_hash,
-            account_id,
-            is_active,
-            created_at,
-            last_used_at,
-        })
+        )
+        .with_counterparty(counterparty)
+        .with_purpose_code(self.purpose_code)
+        .with_conversion(conversion)
+        .with_risk(risk)
+        .with_metadata(metadata)
+        .with_encrypted_memo(encrypted_memo))
     }
 }
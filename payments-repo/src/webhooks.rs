@@ -1,131 +1,561 @@
 use crate::Repo;
-use crate::security::sign_webhook;
-use payments_types::{WebhookEvent, WebhookStatus};
+use crate::security::{sign_webhook_delivery, sign_webhook_with};
+use async_trait::async_trait;
+use futures_util::{StreamExt, stream};
+use payments_types::ports::metrics::WEBHOOK_DELIVERIES_TOTAL;
+use payments_types::{
+    Metrics, NoopMetrics, WebhookEndpoint, WebhookEvent, WebhookStatus, WebhookTimeouts,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
-use tracing::{error, info, instrument};
+use tracing::{error, info, instrument, warn};
 
-/// Worker that processes pending webhook events and sends them to the target URL.
+/// Endpoints delivered to at once unless configured otherwise.
+pub const DEFAULT_WEBHOOK_CONCURRENCY: usize = 4;
+
+/// Due events fetched per poll for each endpoint delivered to at once.
+const EVENTS_PER_SLOT: usize = 10;
+
+/// How often the worker looks for due events when nothing wakes it sooner.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the worker looks for due events when new ones wake it, to pick
+/// up retries falling due.
+pub const NOTIFY_FALLBACK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Postgres channel notified when webhook events are inserted.
+pub const WEBHOOK_EVENTS_CHANNEL: &str = "webhook_events";
+
+/// Tells the webhook worker when to look for due events again.
+#[async_trait]
+pub trait WebhookEventSource: Send + Sync {
+    /// Returns once due events may be waiting: when new events are
+    /// announced, or after an interval that picks up retries falling due.
+    async fn wait(&mut self);
+}
+
+/// Wakes the worker on a fixed interval; used where the database cannot
+/// announce new events, as with SQLite.
+#[derive(Debug, Clone, Copy)]
+pub struct PollingSource {
+    interval: Duration,
+}
+
+impl PollingSource {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+}
+
+impl Default for PollingSource {
+    fn default() -> Self {
+        Self::new(POLL_INTERVAL)
+    }
+}
+
+#[async_trait]
+impl WebhookEventSource for PollingSource {
+    async fn wait(&mut self) {
+        sleep(self.interval).await;
+    }
+}
+
+/// Backoff settings for redelivering webhooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebhookRetryPolicy {
+    /// Total delivery attempts, including the first one, before an event is
+    /// dead-lettered. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after each failed retry.
+    pub base_delay: Duration,
+    /// Upper bound for any single delay.
+    pub max_delay: Duration,
+}
+
+impl Default for WebhookRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl WebhookRetryPolicy {
+    /// Delay to wait after failed attempt number `attempt` (1-based).
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Worker that delivers queued webhook events to their endpoints.
 ///
-/// Webhooks are signed using HMAC-SHA256 for security. The signature is included
-/// in the `X-Webhook-Signature` header.
+/// The service only stores webhook events, in the same database transaction
+/// as the writes they announce; this worker is the one place they are sent
+/// from, so an event is never lost to a crash after commit. Deliveries are
+/// at least once: a crash between sending and recording the outcome sends
+/// the event again, with the same `X-Webhook-Event-Id`.
+///
+/// Each delivery is signed with its endpoint's secret using the endpoint's
+/// algorithm, named in `X-Webhook-Signature-Algorithm`. The signature is
+/// included in the `X-Webhook-Signature` header;
+/// `X-Webhook-Delivery-Signature` also covers the endpoint's
+/// `X-Webhook-Delivery-Sequence`. Failed deliveries are retried with
+/// exponential backoff until the retry policy's attempts are used up, after
+/// which the event is dead-lettered, as are events whose endpoint was deleted.
+///
+/// Each poll fetches due events together with their endpoints and delivers
+/// to several endpoints at once, each event to its own endpoint's URL. One
+/// endpoint's events go out one at a time, oldest first, so its delivery
+/// sequence arrives in order. Each delivery uses its endpoint's timeouts, so
+/// one slow consumer cannot hold the worker for longer than it asked for.
 pub struct WebhookWorker {
-    repo: Repo,
+    repo: Arc<Repo>,
     client: reqwest::Client,
-    target_url: String,
-    webhook_secret: String,
+    retry: WebhookRetryPolicy,
+    metrics: Arc<dyn Metrics>,
+    /// Endpoints delivered to at once
+    concurrency: usize,
+    /// Wakes the worker; the repository's own source when `None`
+    source: Option<Box<dyn WebhookEventSource>>,
 }
 
 impl WebhookWorker {
     /// Creates a new webhook worker.
     ///
     /// # Arguments
-    /// * `repo` - Repository for fetching and updating webhook events
-    /// * `target_url` - URL to send webhooks to
-    /// * `webhook_secret` - Secret key for HMAC-SHA256 signing
-    pub fn new(repo: Repo, target_url: String, webhook_secret: String) -> Self {
+    /// * `repo` - Repository for fetching and updating webhook events and
+    ///   their endpoints
+    pub fn new(repo: Arc<Repo>) -> Self {
         Self {
             repo,
-            client: reqwest::Client::new(),
-            target_url,
-            webhook_secret,
+            client: build_client(WebhookTimeouts::default()).unwrap_or_default(),
+            retry: WebhookRetryPolicy::default(),
+            metrics: Arc::new(NoopMetrics),
+            concurrency: DEFAULT_WEBHOOK_CONCURRENCY,
+            source: None,
+        }
+    }
+
+    /// Replaces the default retry policy.
+    pub fn with_retry_policy(mut self, retry: WebhookRetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Counts delivery attempts into `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Delivers to up to `concurrency` endpoints at once (at least one).
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Wakes the worker from `source` instead of the repository's own.
+    pub fn with_event_source(mut self, source: Box<dyn WebhookEventSource>) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Returns a client honouring `endpoint`'s timeouts.
+    fn client_for(&self, endpoint: &WebhookEndpoint) -> reqwest::Client {
+        if endpoint.timeouts == WebhookTimeouts::default() {
+            return self.client.clone();
         }
+        build_client(endpoint.timeouts).unwrap_or_else(|e| {
+            warn!(
+                "Failed to build webhook client, using default timeouts: {}",
+                e
+            );
+            self.client.clone()
+        })
     }
 
     /// Runs the webhook worker loop.
     ///
-    /// This method runs indefinitely, polling for pending webhooks every second
-    /// and processing them.
+    /// This method runs indefinitely, delivering the webhooks whose next
+    /// attempt is due each time its event source wakes it: straight after
+    /// new events commit on Postgres, every second on SQLite.
     #[instrument(skip(self))]
-    pub async fn run(self) {
-        info!("Starting webhook worker sending to {}", self.target_url);
+    pub async fn run(mut self) {
+        info!("Starting webhook worker");
+        let mut source = match self.source.take() {
+            Some(source) => source,
+            None => self.repo.webhook_event_source().await,
+        };
         loop {
-            match self.repo.get_pending_webhooks(10).await {
-                Ok(events) => {
-                    if !events.is_empty() {
-                        info!("Processing {} pending webhooks", events.len());
-                        for event in events {
-                            self.process_event(event).await;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to fetch webhooks: {}", e);
-                }
+            self.deliver_due().await;
+            source.wait().await;
+        }
+    }
+
+    /// Delivers the events that are due, up to `concurrency` endpoints at a
+    /// time and each endpoint's events in order. Once an event has to be
+    /// retried, its endpoint's later events wait for a later tick rather
+    /// than overtake it.
+    async fn deliver_due(&self) {
+        let limit = i64::try_from(self.concurrency * EVENTS_PER_SLOT).unwrap_or(i64::MAX);
+        let due = match self.repo.get_due_webhook_deliveries(limit).await {
+            Ok(due) => due,
+            Err(e) => {
+                error!("Failed to fetch webhooks: {}", e);
+                return;
             }
-            sleep(Duration::from_secs(1)).await;
+        };
+        if due.is_empty() {
+            return;
+        }
+        info!("Processing {} due webhooks", due.len());
+
+        // Group by endpoint, keeping each endpoint's events oldest first
+        let mut slots: HashMap<uuid::Uuid, usize> = HashMap::new();
+        let mut queues: Vec<Vec<(WebhookEvent, Option<WebhookEndpoint>)>> = Vec::new();
+        for (event, endpoint) in due {
+            let slot = *slots.entry(event.endpoint_id).or_insert_with(|| {
+                queues.push(Vec::new());
+                queues.len() - 1
+            });
+            queues[slot].push((event, endpoint));
         }
+        stream::iter(queues)
+            .for_each_concurrent(self.concurrency, |queue| async move {
+                for (event, endpoint) in queue {
+                    if !self.process_event(event, endpoint).await {
+                        break;
+                    }
+                }
+            })
+            .await;
     }
 
-    /// Processes a single webhook event by sending it to the target URL.
+    /// Processes a single webhook event by sending it to `endpoint`, the
+    /// one it was created for (`None` once that has been deleted).
     ///
-    /// The payload is signed using HMAC-SHA256 and the signature is included
-    /// in the `X-Webhook-Signature` header.
-    #[instrument(skip(self, event), fields(event_id = %event.id))]
-    async fn process_event(&self, event: WebhookEvent) {
-        info!(
-            "Sending webhook {} to {}",
-            event.event_type, self.target_url
-        );
+    /// The payload is signed with the endpoint's secret using its signature
+    /// algorithm and the signature is included in the `X-Webhook-Signature`
+    /// header, and again with the delivery sequence in
+    /// `X-Webhook-Delivery-Signature`.
+    ///
+    /// Returns whether the event is settled, i.e. delivered or given up on,
+    /// rather than rescheduled for a retry.
+    #[instrument(skip(self, event, endpoint), fields(event_id = %event.id))]
+    async fn process_event(&self, event: WebhookEvent, endpoint: Option<WebhookEndpoint>) -> bool {
+        let Some(endpoint) = endpoint else {
+            warn!("Dead-lettering webhook for deleted endpoint");
+            self.dead_letter(&event, "Webhook endpoint no longer exists".into())
+                .await;
+            return true;
+        };
+        info!("Sending webhook {} to {}", event.event_type, endpoint.url);
 
-        // Serialize the payload
-        let payload_bytes = match serde_json::to_vec(&event.payload) {
+        // Serialize the payload, or its stub when it is too large to send
+        let payload_bytes = match serde_json::to_vec(&event.delivery_payload()) {
             Ok(bytes) => bytes,
             Err(e) => {
                 error!("Failed to serialize webhook payload: {}", e);
-                if let Err(e) = self
-                    .repo
-                    .update_webhook_status(
-                        event.id,
-                        WebhookStatus::Failed,
-                        Some(format!("Serialization error: {}", e)),
-                    )
-                    .await
-                {
-                    error!("Failed to update webhook status: {}", e);
-                }
-                return;
+                self.dead_letter(&event, format!("Serialization error: {}", e))
+                    .await;
+                return true;
             }
         };
 
         // Sign the payload
-        let signature = sign_webhook(&payload_bytes, &self.webhook_secret);
+        let algorithm = endpoint.signature_algorithm;
+        let signature = sign_webhook_with(algorithm, &payload_bytes, endpoint.signature_key());
+        let delivery_signature = sign_webhook_delivery(
+            algorithm,
+            event.delivery_sequence,
+            &payload_bytes,
+            endpoint.signature_key(),
+        );
 
         // Send the webhook with signature header
         let result = self
-            .client
-            .post(&self.target_url)
+            .client_for(&endpoint)
+            .post(&endpoint.url)
             .header("Content-Type", "application/json")
             .header("X-Webhook-Signature", &signature)
+            .header("X-Webhook-Signature-Algorithm", algorithm.as_ref())
             .header("X-Webhook-Event-Id", event.id.to_string())
             .header("X-Webhook-Event-Type", &event.event_type)
+            .header(
+                "X-Webhook-Delivery-Sequence",
+                event.delivery_sequence.to_string(),
+            )
+            .header("X-Webhook-Delivery-Signature", &delivery_signature)
             .body(payload_bytes)
             .send()
             .await;
 
-        let (status, last_error) = match result {
+        let last_error = match result {
+            Ok(resp) if resp.status().is_success() => {
+                info!("Webhook delivered successfully");
+                None
+            }
             Ok(resp) => {
-                if resp.status().is_success() {
-                    info!("Webhook delivered successfully");
-                    (WebhookStatus::Completed, None)
-                } else {
-                    let status_code = resp.status();
-                    error!("Webhook delivery failed with HTTP {}", status_code);
-                    (WebhookStatus::Failed, Some(format!("HTTP {}", status_code)))
-                }
+                let status_code = resp.status();
+                error!("Webhook delivery failed with HTTP {}", status_code);
+                Some(format!("HTTP {}", status_code))
             }
             Err(e) => {
                 error!("Webhook delivery failed: {}", e);
-                (WebhookStatus::Failed, Some(e.to_string()))
+                Some(e.to_string())
             }
         };
 
+        let outcome = if last_error.is_none() {
+            "success"
+        } else {
+            "failure"
+        };
+        self.metrics
+            .increment_counter(WEBHOOK_DELIVERIES_TOTAL, &[("outcome", outcome)], 1);
+
+        let attempt = u32::try_from(event.attempts).unwrap_or(0) + 1;
+        let settled = last_error.is_none() || attempt >= self.retry.max_attempts;
+        let updated = match last_error {
+            None => {
+                self.repo
+                    .update_webhook_status(event.id, WebhookStatus::Completed, None)
+                    .await
+            }
+            Some(last_error) if attempt >= self.retry.max_attempts => {
+                warn!(
+                    "Dead-lettering webhook after {} attempts: {}",
+                    attempt, last_error
+                );
+                self.repo
+                    .update_webhook_status(event.id, WebhookStatus::DeadLettered, Some(last_error))
+                    .await
+            }
+            Some(last_error) => {
+                let delay = self.retry.delay_for(attempt);
+                info!(
+                    "Retrying webhook in {:?} (attempt {}/{})",
+                    delay, attempt, self.retry.max_attempts
+                );
+                self.repo
+                    .reschedule_webhook(event.id, last_error, delay)
+                    .await
+            }
+        };
+        if let Err(e) = updated {
+            error!("Failed to update webhook status: {}", e);
+        }
+        settled
+    }
+
+    /// Gives up on `event` for good.
+    async fn dead_letter(&self, event: &WebhookEvent, reason: String) {
         if let Err(e) = self
             .repo
-            .update_webhook_status(event.id, status, last_error)
+            .update_webhook_status(event.id, WebhookStatus::DeadLettered, Some(reason))
             .await
         {
             error!("Failed to update webhook status: {}", e);
         }
     }
 }
+
+/// HTTP client that gives up on connecting and on whole deliveries per
+/// `timeouts`.
+fn build_client(timeouts: WebhookTimeouts) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .connect_timeout(timeouts.connect_timeout())
+        .timeout(timeouts.timeout())
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    use tokio::net::TcpListener;
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    use tokio::sync::oneshot;
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    /// A URL that answers one request with `200 OK`, and the raw request it
+    /// received.
+    async fn receiver() -> (String, oneshot::Receiver<String>) {
+        receiver_answering("200 OK").await
+    }
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    /// A URL that answers one request with `status`, and the raw request it
+    /// received.
+    async fn receiver_answering(status: &'static str) -> (String, oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, received) = oneshot::channel();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            // Read the headers, then as much body as they announce
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_lowercase();
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length: "))
+                        .and_then(|len| len.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if n == 0 || request.len() >= end + 4 + length {
+                        break;
+                    }
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                status
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            let _ = sender.send(String::from_utf8(request).unwrap());
+        });
+        (url, received)
+    }
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    #[tokio::test]
+    async fn test_delivers_each_event_to_its_endpoint_signed_with_its_secret() {
+        use payments_types::{TransactionRepository, WebhookEndpointId, WebhookSignatureAlgorithm};
+
+        let repo = Arc::new(Repo::new("sqlite::memory:").await.unwrap());
+        let worker = WebhookWorker::new(repo.clone()).with_concurrency(2);
+        let mut deliveries = Vec::new();
+        for (amount, algorithm) in [
+            (500, WebhookSignatureAlgorithm::HmacSha256),
+            (700, WebhookSignatureAlgorithm::Ed25519),
+        ] {
+            let (url, received) = receiver().await;
+            let endpoint = repo
+                .register_webhook_endpoint(&url, vec![], WebhookTimeouts::default(), algorithm)
+                .await
+                .unwrap();
+            let event = repo
+                .create_webhook_event(
+                    WebhookEndpointId::from_uuid(endpoint.id),
+                    "deposit.success",
+                    serde_json::json!({ "amount": amount }),
+                )
+                .await
+                .unwrap();
+            deliveries.push((endpoint, event, received));
+        }
+
+        worker.deliver_due().await;
+        for (endpoint, event, received) in deliveries {
+            let request = received.await.unwrap().to_lowercase();
+            assert!(request.starts_with("post /hook "), "{}", request);
+            let delivered = repo.get_webhook_event(event.id).await.unwrap().unwrap();
+            assert_eq!(delivered.status, WebhookStatus::Completed);
+            let body = serde_json::to_vec(&delivered.delivery_payload()).unwrap();
+            let algorithm = endpoint.signature_algorithm;
+            let signature = sign_webhook_with(algorithm, &body, endpoint.signature_key());
+            assert!(
+                request.contains(&format!("x-webhook-signature: {}", signature)),
+                "{}",
+                request
+            );
+            assert!(
+                request.contains(&format!("x-webhook-signature-algorithm: {}", algorithm)),
+                "{}",
+                request
+            );
+        }
+
+        // Events left behind by a deleted endpoint have nowhere to go.
+        let (url, _received) = receiver().await;
+        let endpoint = repo
+            .register_webhook_endpoint(
+                &url,
+                vec![],
+                WebhookTimeouts::default(),
+                WebhookSignatureAlgorithm::default(),
+            )
+            .await
+            .unwrap();
+        let endpoint_id = WebhookEndpointId::from_uuid(endpoint.id);
+        let orphan = repo
+            .create_webhook_event(endpoint_id, "deposit.success", serde_json::json!({}))
+            .await
+            .unwrap();
+        repo.delete_webhook_endpoint(endpoint_id).await.unwrap();
+        worker.deliver_due().await;
+        let orphan = repo.get_webhook_event(orphan.id).await.unwrap().unwrap();
+        assert_eq!(orphan.status, WebhookStatus::DeadLettered);
+        assert_eq!(
+            orphan.last_error.as_deref(),
+            Some("Webhook endpoint no longer exists")
+        );
+    }
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    #[tokio::test]
+    async fn test_failed_event_holds_back_its_endpoints_later_events() {
+        use payments_types::{TransactionRepository, WebhookEndpointId, WebhookSignatureAlgorithm};
+
+        let repo = Arc::new(Repo::new("sqlite::memory:").await.unwrap());
+        let worker = WebhookWorker::new(repo.clone());
+        let (url, received) = receiver_answering("500 Internal Server Error").await;
+        let endpoint = repo
+            .register_webhook_endpoint(
+                &url,
+                vec![],
+                WebhookTimeouts::default(),
+                WebhookSignatureAlgorithm::default(),
+            )
+            .await
+            .unwrap();
+        let endpoint_id = WebhookEndpointId::from_uuid(endpoint.id);
+        let mut events = Vec::new();
+        for amount in [500, 700] {
+            let event = repo
+                .create_webhook_event(
+                    endpoint_id,
+                    "deposit.success",
+                    serde_json::json!({ "amount": amount }),
+                )
+                .await
+                .unwrap();
+            events.push(event.id);
+        }
+
+        worker.deliver_due().await;
+        received.await.unwrap();
+        let failed = repo.get_webhook_event(events[0]).await.unwrap().unwrap();
+        assert_eq!(failed.status, WebhookStatus::Failed);
+        let held_back = repo.get_webhook_event(events[1]).await.unwrap().unwrap();
+        assert_eq!(
+            (held_back.status, held_back.attempts),
+            (WebhookStatus::Pending, 0)
+        );
+    }
+
+    #[test]
+    fn test_delay_doubles_up_to_the_cap() {
+        let policy = WebhookRetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(300),
+        };
+        assert_eq!(policy.delay_for(1), Duration::from_secs(30));
+        assert_eq!(policy.delay_for(2), Duration::from_secs(60));
+        assert_eq!(policy.delay_for(4), Duration::from_secs(240));
+        assert_eq!(policy.delay_for(5), Duration::from_secs(300));
+        assert_eq!(policy.delay_for(40), Duration::from_secs(300));
+    }
+}
@@ -0,0 +1,218 @@
+//! Builders for domain fixtures.
+//!
+//! Every field has a sensible default so tests only spell out what they care
+//! about:
+//!
+//! ```ignore
+//! let alice = AccountBuilder::new().name("Alice").balance(10_000).build();
+//! let tx = TransactionBuilder::deposit(alice.id).amount(500).build();
+//! ```
+
+use chrono::{DateTime, Utc};
+
+use payments_types::{
+    Account, AccountId, CurrencyCode, DynMoney, Transaction, TransactionId, TransactionType,
+};
+
+/// Builds an [`Account`] with defaults: "Test Account", USD, zero balance.
+#[derive(Debug, Clone)]
+pub struct AccountBuilder {
+    id: AccountId,
+    name: String,
+    currency: CurrencyCode,
+    balance: i64,
+    created_at: DateTime<Utc>,
+}
+
+impl Default for AccountBuilder {
+    fn default() -> Self {
+        Self {
+            id: AccountId::new(),
+            name: "Test Account".to_string(),
+            currency: CurrencyCode::USD,
+            balance: 0,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl AccountBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(mut self, id: AccountId) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn currency(mut self, currency: CurrencyCode) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Sets the balance in the smallest currency unit.
+    pub fn balance(mut self, balance: i64) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    pub fn created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    /// Builds the account.
+    ///
+    /// # Panics
+    /// If the balance is negative.
+    pub fn build(self) -> Account {
+        let balance = DynMoney::new(self.balance, self.currency).expect("balance must be >= 0");
+        Account::from_parts(self.id, self.name, balance, self.created_at)
+    }
+}
+
+/// Builds a [`Transaction`] with defaults: 1000 USD, no key or reference.
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    id: TransactionId,
+    transaction_type: TransactionType,
+    amount: i64,
+    currency: CurrencyCode,
+    source_account_id: Option<AccountId>,
+    destination_account_id: Option<AccountId>,
+    idempotency_key: Option<String>,
+    reference: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl TransactionBuilder {
+    fn new(
+        transaction_type: TransactionType,
+        source_account_id: Option<AccountId>,
+        destination_account_id: Option<AccountId>,
+    ) -> Self {
+        Self {
+            id: TransactionId::new(),
+            transaction_type,
+            amount: 1000,
+            currency: CurrencyCode::USD,
+            source_account_id,
+            destination_account_id,
+            idempotency_key: None,
+            reference: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Starts a deposit into `destination`.
+    pub fn deposit(destination: AccountId) -> Self {
+        Self::new(TransactionType::Deposit, None, Some(destination))
+    }
+
+    /// Starts a withdrawal from `source`.
+    pub fn withdrawal(source: AccountId) -> Self {
+        Self::new(TransactionType::Withdrawal, Some(source), None)
+    }
+
+    /// Starts a transfer from `source` to `destination`.
+    pub fn transfer(source: AccountId, destination: AccountId) -> Self {
+        Self::new(TransactionType::Transfer, Some(source), Some(destination))
+    }
+
+    pub fn id(mut self, id: TransactionId) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Sets the amount in the smallest currency unit.
+    pub fn amount(mut self, amount: i64) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    pub fn currency(mut self, currency: CurrencyCode) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    pub fn reference(mut self, reference: impl Into<String>) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    pub fn created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    /// Builds the transaction.
+    ///
+    /// # Panics
+    /// If the amount is negative.
+    pub fn build(self) -> Transaction {
+        let amount = DynMoney::new(self.amount, self.currency).expect("amount must be >= 0");
+        Transaction::from_parts(
+            self.id,
+            self.transaction_type,
+            amount,
+            self.source_account_id,
+            self.destination_account_id,
+            self.idempotency_key,
+            self.reference,
+            self.created_at,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_defaults_and_overrides() {
+        let account = AccountBuilder::new().build();
+        assert_eq!(account.name, "Test Account");
+        assert_eq!(account.balance.amount(), 0);
+        assert_eq!(account.currency(), CurrencyCode::USD);
+
+        let account = AccountBuilder::new()
+            .name("Alice")
+            .currency(CurrencyCode::EUR)
+            .balance(2500)
+            .build();
+        assert_eq!(account.name, "Alice");
+        assert_eq!(account.balance.amount(), 2500);
+        assert_eq!(account.currency(), CurrencyCode::EUR);
+    }
+
+    #[test]
+    fn test_transaction_directions() {
+        let alice = AccountId::new();
+        let bob = AccountId::new();
+
+        let tx = TransactionBuilder::transfer(alice, bob)
+            .amount(42)
+            .idempotency_key("key-1")
+            .build();
+        assert_eq!(tx.transaction_type, TransactionType::Transfer);
+        assert_eq!(tx.source_account_id, Some(alice));
+        assert_eq!(tx.destination_account_id, Some(bob));
+        assert_eq!(tx.amount.amount(), 42);
+        assert_eq!(tx.idempotency_key.as_deref(), Some("key-1"));
+
+        let tx = TransactionBuilder::withdrawal(alice).build();
+        assert_eq!(tx.source_account_id, Some(alice));
+        assert!(tx.destination_account_id.is_none());
+    }
+}
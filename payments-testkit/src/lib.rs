@@ -0,0 +1,23 @@
+//! # Payments Testkit
+//!
+//! Shared fixtures for testing code that talks to the payments service.
+//!
+//! - [`InMemoryRepo`] - a `TransactionRepository` backed by plain collections
+//! - [`AccountBuilder`] / [`TransactionBuilder`] - terse domain fixtures
+//! - [`spawn_test_server`] - a real HTTP server on a random port, with an API key
+//!
+//! ```ignore
+//! let server = payments_testkit::spawn_test_server().await;
+//! let client = server.client();
+//! let account = client.create_account(OwnerId::DEFAULT, "Alice", CurrencyCode::USD).await?;
+//! ```
+//!
+//! Add it as a dev-dependency only; nothing here is meant for production.
+
+mod builders;
+mod memory;
+mod server;
+
+pub use builders::{AccountBuilder, TransactionBuilder};
+pub use memory::InMemoryRepo;
+pub use server::{TestServer, in_memory_service, spawn_test_server, spawn_test_server_with};
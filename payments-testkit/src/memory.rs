@@ -0,0 +1,2616 @@
+//! In-memory repository adapter.
+//!
+//! Mirrors the behaviour of the SQL adapters (idempotency checks, currency
+//! rules, active-only listings) without touching a database, so service and
+//! HTTP tests run fast and in parallel.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use rand::Rng;
+use rand::distr::Alphanumeric;
+
+use payments_repo::security::{
+    generate_webhook_secret, generate_webhook_signing_key, webhook_public_key,
+};
+use payments_types::{
+    Account, AccountAlias, AccountId, AccountLimits, AccountStatus, Alias, ApiKey,
+    ApiKeyAuditEntry, ApiKeyId, ApiKeyScope, ApiKeyUsageBucket, ApiKeyVolumeBucket, Beneficiary,
+    BeneficiaryId, BeneficiaryStatus, ChangeRequest, ChangeRequestId, ChangeStatus, Clock,
+    CreateAccountRequest, CurrencyBalance, DailyBalance, DeadLetterFilter, DepositRequest,
+    DomainError, DomainEvent, DynMoney, Export, ExportId, FeeAssignment, FeeSchedule,
+    FeeScheduleId, FeeTier, FloatPosition, FxConversion, Hold, HoldId, HoldStatus, IdGenerator,
+    IdempotencyRecord, LedgerOperation, LoggedEvent, MemoKey, MemoKeyId, Mode, NewWebhookEndpoint,
+    Owner, OwnerId, Quote, QuoteId, RandomIdGenerator, RateSnapshot, RepoError, RiskAssessment,
+    ScheduledPayment, ScheduledPaymentId, ScheduledPaymentStatus, SettlementBatch,
+    SettlementBatchId, SettlementBatchStatus, SpendingRules, Statement, StatementId, StorageCounts,
+    SystemClock, Transaction, TransactionCursor, TransactionFilter, TransactionId,
+    TransactionRepository, TransactionSearch, TransactionType, TransferRequest, UnitOfWork,
+    WebhookDeliveryFilter, WebhookEndpoint, WebhookEndpointId, WebhookEvent,
+    WebhookSignatureAlgorithm, WebhookStatus, WebhookTimeouts, WithdrawRequest,
+};
+
+#[derive(Default, Clone)]
+struct State {
+    accounts: HashMap<AccountId, Account>,
+    transactions: Vec<Transaction>,
+    api_keys: Vec<ApiKey>,
+    webhook_endpoints: Vec<WebhookEndpoint>,
+    webhook_events: Vec<WebhookEvent>,
+    spending_rules: HashMap<AccountId, SpendingRules>,
+    account_limits: HashMap<AccountId, AccountLimits>,
+    account_aliases: Vec<AccountAlias>,
+    owners: Vec<Owner>,
+    memo_keys: Vec<MemoKey>,
+    fee_schedules: Vec<FeeSchedule>,
+    fee_assignments: Vec<FeeAssignment>,
+    settlement_batches: Vec<SettlementBatch>,
+    settlement_payouts: HashMap<TransactionId, SettlementBatchId>,
+    statement_emails: HashMap<AccountId, String>,
+    statements: Vec<(Statement, String)>,
+    exports: HashMap<ExportId, (Export, Option<String>)>,
+    scheduled_payments: Vec<ScheduledPayment>,
+    holds: Vec<Hold>,
+    beneficiaries: Vec<Beneficiary>,
+    rate_snapshots: Vec<RateSnapshot>,
+    quotes: Vec<Quote>,
+    daily_balances: HashMap<AccountId, BTreeMap<NaiveDate, i64>>,
+    change_requests: Vec<ChangeRequest>,
+    idempotency_records: HashMap<(String, String), IdempotencyRecord>,
+    api_key_usage: Vec<ApiKeyUsageBucket>,
+    api_key_volume: Vec<ApiKeyVolumeBucket>,
+    api_key_audit: Vec<ApiKeyAuditEntry>,
+    event_log: Vec<LoggedEvent>,
+}
+
+impl State {
+    /// Returns the transaction previously recorded under `key`, or a conflict
+    /// error when it was recorded with different parameters.
+    fn replay(
+        &self,
+        key: Option<&String>,
+        same_request: impl Fn(&Transaction) -> bool,
+    ) -> Result<Option<Transaction>, RepoError> {
+        let Some(key) = key else {
+            return Ok(None);
+        };
+        match self
+            .transactions
+            .iter()
+            .find(|t| t.idempotency_key.as_ref() == Some(key))
+        {
+            Some(tx) if same_request(tx) => Ok(Some(tx.clone())),
+            Some(_) => Err(RepoError::Domain(DomainError::IdempotencyKeyConflict(
+                key.clone(),
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Records a held transaction's risk assessment unless it has one.
+    fn record_risk(&mut self, id: TransactionId, risk: &RiskAssessment) {
+        if let Some(tx) = self
+            .transactions
+            .iter_mut()
+            .find(|t| t.id == id && t.risk.is_none())
+        {
+            tx.risk = Some(risk.clone());
+        }
+    }
+
+    /// Sums the amounts sent out of or held on an account after `since`.
+    fn debited_since(&self, account_id: AccountId, since: DateTime<Utc>) -> i64 {
+        let sent = self
+            .transactions
+            .iter()
+            .filter(|tx| tx.source_account_id == Some(account_id) && tx.created_at > since)
+            .map(|tx| tx.amount.amount());
+        let held = self
+            .holds
+            .iter()
+            .filter(|hold| {
+                hold.account_id == account_id
+                    && hold.status == HoldStatus::Authorized
+                    && hold.created_at > since
+            })
+            .map(|hold| hold.amount);
+        sent.chain(held)
+            .fold(0i64, |total, amount| total.saturating_add(amount))
+    }
+
+    /// Counts the transactions an account took part in after `since`.
+    fn transactions_since(&self, account_id: AccountId, since: DateTime<Utc>) -> i64 {
+        self.transactions
+            .iter()
+            .filter(|tx| {
+                (tx.source_account_id == Some(account_id)
+                    || tx.destination_account_id == Some(account_id))
+                    && tx.created_at > since
+            })
+            .count() as i64
+    }
+
+    /// Whether `tx` moved money in or out of an account in `mode`, or `mode`
+    /// is `None`.
+    fn in_mode(&self, tx: &Transaction, mode: Option<Mode>) -> bool {
+        mode.is_none_or(|mode| {
+            [tx.source_account_id, tx.destination_account_id]
+                .into_iter()
+                .flatten()
+                .any(|id| self.accounts.get(&id).is_some_and(|a| a.mode == mode))
+        })
+    }
+
+    /// Stores an authorized hold, reserving its amount on the account.
+    fn place_hold(&mut self, hold: &Hold) -> Result<(), RepoError> {
+        let money = DynMoney::new(hold.amount, hold.currency).map_err(RepoError::Domain)?;
+        self.account_mut(hold.account_id)?
+            .place_hold(money)
+            .map_err(RepoError::Domain)?;
+        self.holds.push(hold.clone());
+        Ok(())
+    }
+
+    /// Highest delivery sequence handed out for an endpoint's events.
+    fn last_delivery_sequence(&self, endpoint_id: uuid::Uuid) -> i64 {
+        last_delivery_sequence(&self.webhook_events, endpoint_id)
+    }
+
+    fn account_mut(&mut self, id: AccountId) -> Result<&mut Account, RepoError> {
+        self.accounts.get_mut(&id).ok_or(RepoError::NotFound)
+    }
+
+    fn hold_mut(&mut self, id: HoldId) -> Result<&mut Hold, RepoError> {
+        self.holds
+            .iter_mut()
+            .find(|h| h.id == id)
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn quote_mut(&mut self, id: QuoteId) -> Result<&mut Quote, RepoError> {
+        self.quotes
+            .iter_mut()
+            .find(|q| q.id == id)
+            .ok_or(RepoError::NotFound)
+    }
+}
+
+fn last_delivery_sequence(events: &[WebhookEvent], endpoint_id: uuid::Uuid) -> i64 {
+    events
+        .iter()
+        .filter(|e| e.endpoint_id == endpoint_id)
+        .map(|e| e.delivery_sequence)
+        .max()
+        .unwrap_or(0)
+}
+
+/// What a unit of work checks an account against on commit.
+type AccountVersion = (i64, i64, AccountStatus);
+
+fn account_version(account: &Account) -> AccountVersion {
+    (account.balance.amount(), account.held, account.status)
+}
+
+/// A [`UnitOfWork`] staged on a copy of the state and merged in on commit.
+///
+/// Commit changes nothing and fails with a conflict if an account the unit
+/// touched, or an endpoint it queued events for, changed in the meantime, as
+/// a database transaction losing a race would.
+struct InMemoryUnitOfWork<'a> {
+    repo: &'a InMemoryRepo,
+    staged: State,
+    /// Accounts touched, as they were before this unit touched them
+    accounts_before: HashMap<AccountId, Option<AccountVersion>>,
+    transactions_before: usize,
+    holds_before: usize,
+    events_before: usize,
+    logged_before: usize,
+    risks: Vec<(TransactionId, RiskAssessment)>,
+    resolved_holds: Vec<HoldId>,
+    spent_quotes: Vec<(QuoteId, TransactionId)>,
+}
+
+impl InMemoryUnitOfWork<'_> {
+    fn touch(&mut self, id: AccountId) {
+        let before = self.staged.accounts.get(&id).map(account_version);
+        self.accounts_before.entry(id).or_insert(before);
+    }
+}
+
+#[async_trait]
+impl UnitOfWork for InMemoryUnitOfWork<'_> {
+    async fn apply(&mut self, operation: LedgerOperation) -> Result<Transaction, RepoError> {
+        match operation {
+            LedgerOperation::Deposit(req) => {
+                self.touch(req.account_id);
+                self.repo.deposit_in(&mut self.staged, req)
+            }
+            LedgerOperation::Withdraw(req) => {
+                self.touch(req.account_id);
+                self.repo.withdraw_in(&mut self.staged, req)
+            }
+            LedgerOperation::Transfer(req, conversion) => {
+                self.touch(req.from_account_id);
+                self.touch(req.to_account_id);
+                self.repo.transfer_in(&mut self.staged, req, conversion)
+            }
+        }
+    }
+
+    async fn lock_accounts(&mut self, account_ids: &[AccountId]) -> Result<(), RepoError> {
+        for &account_id in account_ids {
+            self.touch(account_id);
+        }
+        Ok(())
+    }
+
+    async fn debited_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        // Touching the account makes the commit fail if another debit
+        // landed in the meantime
+        self.touch(account_id);
+        Ok(self.staged.debited_since(account_id, since))
+    }
+
+    async fn transactions_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        self.touch(account_id);
+        Ok(self.staged.transactions_since(account_id, since))
+    }
+
+    async fn create_hold(&mut self, hold: &Hold) -> Result<(), RepoError> {
+        self.touch(hold.account_id);
+        self.staged.place_hold(hold)
+    }
+
+    async fn record_transaction_risk(
+        &mut self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        self.staged.record_risk(id, risk);
+        self.risks.push((id, risk.clone()));
+        Ok(())
+    }
+
+    async fn create_webhook_event(
+        &mut self,
+        endpoint_id: WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<WebhookEvent, RepoError> {
+        Ok(self
+            .repo
+            .queue_webhook_event(&mut self.staged, endpoint_id, event_type, payload))
+    }
+
+    async fn append_event(&mut self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        self.repo.log_event(&mut self.staged, event, mode);
+        Ok(())
+    }
+
+    async fn capture_hold(
+        &mut self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        let account_id = self.staged.hold_mut(id)?.account_id;
+        self.touch(account_id);
+        let captured = self
+            .repo
+            .capture_hold_in(&mut self.staged, id, amount, now)?;
+        self.resolved_holds.push(id);
+        Ok(captured)
+    }
+
+    async fn release_hold(
+        &mut self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        let account_id = self.staged.hold_mut(id)?.account_id;
+        self.touch(account_id);
+        let released = self
+            .repo
+            .release_hold_in(&mut self.staged, id, status, now)?;
+        self.resolved_holds.push(id);
+        Ok(released)
+    }
+
+    async fn spend_quote(
+        &mut self,
+        id: QuoteId,
+        transaction_id: TransactionId,
+    ) -> Result<(), RepoError> {
+        self.staged
+            .quote_mut(id)?
+            .spend(transaction_id)
+            .map_err(RepoError::Domain)?;
+        self.spent_quotes.push((id, transaction_id));
+        Ok(())
+    }
+
+    async fn close_merged_account(
+        &mut self,
+        id: AccountId,
+        into: AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        self.touch(id);
+        match self.staged.accounts.get_mut(&id) {
+            Some(account)
+                if account.status != AccountStatus::Closed
+                    && account.balance.amount() == 0
+                    && account.held == 0 =>
+            {
+                account.status = AccountStatus::Closed;
+                account.status_changed_at = Some(now);
+                account.merged_into = Some(into);
+                Ok(Some(account.clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepoError> {
+        let mut work = *self;
+        let mut state = work.repo.state.lock().unwrap();
+        for (id, before) in &work.accounts_before {
+            if state.accounts.get(id).map(account_version) != *before {
+                return Err(RepoError::Conflict(format!(
+                    "Balance of account {} changed concurrently",
+                    id
+                )));
+            }
+        }
+        let (earlier, queued) = work.staged.webhook_events.split_at(work.events_before);
+        for event in queued {
+            if state.last_delivery_sequence(event.endpoint_id)
+                != last_delivery_sequence(earlier, event.endpoint_id)
+            {
+                return Err(RepoError::Conflict(format!(
+                    "Webhook events for endpoint {} changed concurrently",
+                    event.endpoint_id
+                )));
+            }
+        }
+        if work.staged.event_log.len() > work.logged_before
+            && state.event_log.len() != work.logged_before
+        {
+            return Err(RepoError::Conflict(
+                "Event log changed concurrently".to_string(),
+            ));
+        }
+        for id in &work.resolved_holds {
+            if state.hold_mut(*id)?.status != HoldStatus::Authorized {
+                return Err(RepoError::Conflict(format!(
+                    "Hold {} changed concurrently",
+                    id
+                )));
+            }
+        }
+
+        for (id, transaction_id) in &work.spent_quotes {
+            state
+                .quote_mut(*id)?
+                .clone()
+                .spend(*transaction_id)
+                .map_err(RepoError::Domain)?;
+        }
+
+        for id in work.accounts_before.keys() {
+            if let Some(account) = work.staged.accounts.remove(id) {
+                state.accounts.insert(*id, account);
+            }
+        }
+        state
+            .transactions
+            .extend(work.staged.transactions.drain(work.transactions_before..));
+        state
+            .holds
+            .extend(work.staged.holds.drain(work.holds_before..));
+        for (id, risk) in &work.risks {
+            state.record_risk(*id, risk);
+        }
+        for (id, transaction_id) in &work.spent_quotes {
+            state.quote_mut(*id)?.transaction_id = Some(*transaction_id);
+        }
+        for id in &work.resolved_holds {
+            *state.hold_mut(*id)? = work.staged.hold_mut(*id)?.clone();
+        }
+        state
+            .webhook_events
+            .extend(work.staged.webhook_events.drain(work.events_before..));
+        state
+            .event_log
+            .extend(work.staged.event_log.drain(work.logged_before..));
+        Ok(())
+    }
+}
+
+/// A `TransactionRepository` that keeps everything in memory.
+///
+/// All operations take a single lock, so balance changes are atomic just like
+/// the database-backed adapters.
+pub struct InMemoryRepo {
+    state: Mutex<State>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl Default for InMemoryRepo {
+    fn default() -> Self {
+        // Every store holds the owner of accounts opened before owners
+        // existed, as the SQL migrations seed it.
+        let default_owner = Owner {
+            id: OwnerId::DEFAULT,
+            name: "Default owner".to_string(),
+            email: None,
+            created_at: DateTime::UNIX_EPOCH,
+        };
+        Self {
+            state: Mutex::new(State {
+                owners: vec![default_owner],
+                ..State::default()
+            }),
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+        }
+    }
+}
+
+impl InMemoryRepo {
+    /// Creates an empty repository.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the clock used for `created_at` timestamps.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Replaces the generator used for new IDs.
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    fn new_transaction(
+        &self,
+        transaction_type: TransactionType,
+        amount: DynMoney,
+        source_account_id: Option<AccountId>,
+        destination_account_id: Option<AccountId>,
+        idempotency_key: Option<String>,
+        reference: Option<String>,
+    ) -> Transaction {
+        Transaction::from_parts(
+            TransactionId::from_uuid(self.ids.new_id()),
+            transaction_type,
+            amount,
+            source_account_id,
+            destination_account_id,
+            idempotency_key,
+            reference,
+            self.clock.now(),
+        )
+    }
+
+    fn insert_api_key(
+        &self,
+        name: &str,
+        account_id: Option<AccountId>,
+        owner_id: Option<OwnerId>,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+    ) -> (ApiKey, String) {
+        let raw_key: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        let prefixed_key = format!("{}{}", mode.key_prefix(), raw_key);
+        let key_hash = payments_repo::security::hash_api_key(&prefixed_key);
+
+        let api_key = ApiKey {
+            id: ApiKeyId::from_uuid(self.ids.new_id()),
+            name: name.to_string(),
+            key_hash,
+            account_id,
+            owner_id,
+            scopes: scopes.to_vec(),
+            is_active: true,
+            created_at: self.clock.now(),
+            last_used_at: None,
+            deactivated_at: None,
+            deactivated_by: None,
+            rate_limit_per_minute: None,
+            mode,
+        };
+        self.state.lock().unwrap().api_keys.push(api_key.clone());
+        (api_key, prefixed_key)
+    }
+
+    /// Inserts an account as-is, e.g. one built with [`crate::AccountBuilder`].
+    pub fn insert_account(&self, account: Account) {
+        self.state
+            .lock()
+            .unwrap()
+            .accounts
+            .insert(account.id, account);
+    }
+
+    /// Records a transaction without touching any balance.
+    pub fn insert_transaction(&self, transaction: Transaction) {
+        self.state.lock().unwrap().transactions.push(transaction);
+    }
+
+    /// Returns every webhook event created so far, oldest first.
+    pub fn webhook_events(&self) -> Vec<WebhookEvent> {
+        self.state.lock().unwrap().webhook_events.clone()
+    }
+
+    /// Marks a webhook event dead-lettered now, as the delivery worker does
+    /// once its retries are used up.
+    pub fn dead_letter_webhook(&self, id: uuid::Uuid, last_error: &str) {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        if let Some(event) = state.webhook_events.iter_mut().find(|e| e.id == id) {
+            event.status = WebhookStatus::DeadLettered;
+            event.attempts += 1;
+            event.last_error = Some(last_error.to_string());
+            event.processed_at = Some(now);
+            event.next_attempt_at = None;
+        }
+    }
+
+    /// Debits `req.amount` from the source account and credits the
+    /// destination, converted when `conversion` is set.
+    fn capture_hold_in(
+        &self,
+        state: &mut State,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        let hold = state.hold_mut(id)?.clone();
+        hold.check_capture(amount, now).map_err(RepoError::Domain)?;
+        let money = DynMoney::new(amount, hold.currency).map_err(RepoError::Domain)?;
+
+        let account = state.account_mut(hold.account_id)?;
+        account.release_hold(hold.amount);
+        account.withdraw(money).map_err(RepoError::Domain)?;
+
+        let tx = Transaction::from_parts(
+            TransactionId::from_uuid(self.ids.new_id()),
+            TransactionType::Withdrawal,
+            money,
+            Some(hold.account_id),
+            None,
+            None,
+            hold.reference.clone(),
+            now,
+        )
+        .with_purpose_code(hold.purpose_code.clone());
+        state.transactions.push(tx.clone());
+
+        let stored = state.hold_mut(id)?;
+        stored.capture(amount, tx.id, now);
+        Ok((stored.clone(), tx))
+    }
+
+    /// Releases hold `id` in `state` without moving funds.
+    fn release_hold_in(
+        &self,
+        state: &mut State,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        let hold = state.hold_mut(id)?;
+        hold.check_open().map_err(RepoError::Domain)?;
+        hold.release(status, now);
+        let hold = hold.clone();
+        state
+            .account_mut(hold.account_id)?
+            .release_hold(hold.amount);
+        Ok(hold)
+    }
+
+    fn deposit_in(&self, state: &mut State, req: DepositRequest) -> Result<Transaction, RepoError> {
+        if let Some(tx) = state.replay(req.idempotency_key.as_ref(), |tx| {
+            tx.amount.amount() == req.amount
+                && tx.amount.currency() == req.currency
+                && tx.source_account_id.is_none()
+                && tx.destination_account_id == Some(req.account_id)
+        })? {
+            return Ok(tx);
+        }
+
+        let money = DynMoney::positive(req.amount, req.currency);
+        state
+            .account_mut(req.account_id)?
+            .deposit(money)
+            .map_err(RepoError::Domain)?;
+
+        let tx = self
+            .new_transaction(
+                TransactionType::Deposit,
+                money,
+                None,
+                Some(req.account_id),
+                req.idempotency_key,
+                req.reference,
+            )
+            .with_counterparty(req.counterparty)
+            .with_metadata(req.metadata)
+            .with_encrypted_memo(req.encrypted_memo);
+        state.transactions.push(tx.clone());
+        Ok(tx)
+    }
+
+    fn withdraw_in(
+        &self,
+        state: &mut State,
+        req: WithdrawRequest,
+    ) -> Result<Transaction, RepoError> {
+        if let Some(tx) = state.replay(req.idempotency_key.as_ref(), |tx| {
+            tx.amount.amount() == req.amount
+                && tx.amount.currency() == req.currency
+                && tx.source_account_id == Some(req.account_id)
+                && tx.destination_account_id.is_none()
+        })? {
+            return Ok(tx);
+        }
+
+        let money = DynMoney::positive(req.amount, req.currency);
+        state
+            .account_mut(req.account_id)?
+            .withdraw(money)
+            .map_err(RepoError::Domain)?;
+
+        let tx = self
+            .new_transaction(
+                TransactionType::Withdrawal,
+                money,
+                Some(req.account_id),
+                None,
+                req.idempotency_key,
+                req.reference,
+            )
+            .with_counterparty(req.counterparty)
+            .with_purpose_code(req.purpose_code)
+            .with_metadata(req.metadata)
+            .with_encrypted_memo(req.encrypted_memo);
+        state.transactions.push(tx.clone());
+        Ok(tx)
+    }
+
+    fn transfer_in(
+        &self,
+        state: &mut State,
+        req: TransferRequest,
+        conversion: Option<FxConversion>,
+    ) -> Result<Transaction, RepoError> {
+        if let Some(tx) = state.replay(req.idempotency_key.as_ref(), |tx| {
+            tx.amount.amount() == req.amount
+                && tx.amount.currency() == req.currency
+                && tx.source_account_id == Some(req.from_account_id)
+                && tx.destination_account_id == Some(req.to_account_id)
+        })? {
+            return Ok(tx);
+        }
+
+        let money = DynMoney::positive(req.amount, req.currency);
+        let from_currency = state.account_mut(req.from_account_id)?.currency();
+        let to_currency = state.account_mut(req.to_account_id)?.currency();
+        if conversion.is_none() && from_currency != to_currency {
+            return Err(RepoError::Domain(DomainError::CrossCurrencyTransfer));
+        }
+        let credit = conversion.map_or(money, |c| c.converted_amount);
+        if credit.currency() != to_currency {
+            return Err(RepoError::Domain(DomainError::CurrencyMismatch {
+                expected: to_currency,
+                got: credit.currency(),
+            }));
+        }
+
+        // Debit first: a failed withdrawal leaves both balances untouched.
+        state
+            .account_mut(req.from_account_id)?
+            .withdraw(money)
+            .map_err(RepoError::Domain)?;
+        state
+            .account_mut(req.to_account_id)?
+            .deposit(credit)
+            .map_err(RepoError::Domain)?;
+
+        let tx = self
+            .new_transaction(
+                TransactionType::Transfer,
+                money,
+                Some(req.from_account_id),
+                Some(req.to_account_id),
+                req.idempotency_key,
+                req.reference,
+            )
+            .with_purpose_code(req.purpose_code)
+            .with_metadata(req.metadata)
+            .with_encrypted_memo(req.encrypted_memo)
+            .with_conversion(conversion);
+        state.transactions.push(tx.clone());
+        Ok(tx)
+    }
+
+    /// Queues a webhook event for `endpoint_id` with the next delivery
+    /// sequence.
+    fn queue_webhook_event(
+        &self,
+        state: &mut State,
+        endpoint_id: WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> WebhookEvent {
+        let now = self.clock.now();
+        let mode = state
+            .webhook_endpoints
+            .iter()
+            .find(|e| e.id == endpoint_id.0)
+            .map_or(Mode::Live, |e| e.mode);
+        let event = WebhookEvent {
+            id: self.ids.new_id(),
+            endpoint_id: endpoint_id.0,
+            event_type: event_type.to_string(),
+            payload,
+            status: WebhookStatus::Pending,
+            created_at: now,
+            processed_at: None,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: Some(now),
+            delivery_sequence: state.last_delivery_sequence(endpoint_id.0) + 1,
+            mode,
+        };
+        state.webhook_events.push(event.clone());
+        event
+    }
+
+    fn log_event(&self, state: &mut State, event: &DomainEvent, mode: Mode) {
+        let sequence = state.event_log.last().map_or(0, |e| e.sequence) + 1;
+        state.event_log.push(LoggedEvent {
+            sequence,
+            event_type: event.event_type().to_string(),
+            occurred_at: event.occurred_at(),
+            recorded_at: self.clock.now(),
+            data: event.payload(),
+            mode,
+        });
+    }
+}
+
+#[async_trait]
+impl TransactionRepository for InMemoryRepo {
+    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
+        // Validate first
+        let _ = Account::new(req.name.clone(), req.currency).map_err(RepoError::Domain)?;
+
+        let account = Account::from_parts(
+            AccountId::from_uuid(self.ids.new_id()),
+            req.name,
+            DynMoney::zero(req.currency),
+            self.clock.now(),
+        )
+        .with_overdraft_limit(req.overdraft_limit)
+        .with_owner(req.owner_id)
+        .with_mode(req.mode.unwrap_or_default());
+        self.insert_account(account.clone());
+        Ok(account)
+    }
+
+    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
+        Ok(self.state.lock().unwrap().accounts.get(&id).cloned())
+    }
+
+    async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError> {
+        let mut accounts: Vec<Account> = self
+            .state
+            .lock()
+            .unwrap()
+            .accounts
+            .values()
+            .filter(|a| mode.is_none_or(|mode| a.mode == mode))
+            .cloned()
+            .collect();
+        accounts.sort_by_key(|a| Reverse(a.created_at));
+        Ok(accounts)
+    }
+
+    async fn search_accounts(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: Mode,
+    ) -> Result<Vec<Account>, RepoError> {
+        let mut accounts: Vec<Account> = self
+            .state
+            .lock()
+            .unwrap()
+            .accounts
+            .values()
+            .filter(|a| a.mode == mode && a.matches_search(query))
+            .cloned()
+            .collect();
+        accounts.sort_by_key(|a| (a.name.to_lowercase(), a.id.to_string()));
+        accounts.truncate(limit);
+        Ok(accounts)
+    }
+
+    async fn get_spending_rules(&self, account_id: AccountId) -> Result<SpendingRules, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .spending_rules
+            .get(&account_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        if !state.accounts.contains_key(&account_id) {
+            return Err(RepoError::NotFound);
+        }
+        state.spending_rules.insert(account_id, rules.clone());
+        Ok(())
+    }
+
+    async fn get_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .account_limits
+            .get(&account_id)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        if !state.accounts.contains_key(&account_id) {
+            return Err(RepoError::NotFound);
+        }
+        state.account_limits.insert(account_id, *limits);
+        Ok(())
+    }
+
+    async fn list_idle_accounts(
+        &self,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Account>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let active_since = |id: AccountId| {
+            state.transactions.iter().any(|tx| {
+                tx.created_at >= idle_since
+                    && (tx.source_account_id == Some(id) || tx.destination_account_id == Some(id))
+            })
+        };
+        let mut idle: Vec<Account> = state
+            .accounts
+            .values()
+            .filter(|a| {
+                a.status == AccountStatus::Active
+                    && a.created_at < idle_since
+                    && !active_since(a.id)
+            })
+            .cloned()
+            .collect();
+        idle.sort_by_key(|a| (a.created_at, a.id.into_uuid()));
+        idle.truncate(limit);
+        Ok(idle)
+    }
+
+    async fn update_account_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        match state.accounts.get_mut(&id) {
+            Some(account) if account.status == from => {
+                account.status = to;
+                account.status_changed_at = Some(now);
+                Ok(Some(account.clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn set_overdraft_limit(
+        &self,
+        id: AccountId,
+        limit: i64,
+    ) -> Result<Option<Account>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.accounts.get_mut(&id).map(|account| {
+            account.overdraft_limit = limit;
+            account.clone()
+        }))
+    }
+
+    async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        match state
+            .account_aliases
+            .iter()
+            .find(|a| a.alias == alias.alias)
+        {
+            Some(stored) if stored.account_id == alias.account_id => Ok(stored.clone()),
+            Some(_) => Err(RepoError::Domain(DomainError::ValidationError(format!(
+                "Alias {} is already taken",
+                alias.alias
+            )))),
+            None => {
+                state.account_aliases.push(alias.clone());
+                Ok(alias.clone())
+            }
+        }
+    }
+
+    async fn get_account_alias(&self, alias: &Alias) -> Result<Option<AccountAlias>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .account_aliases
+            .iter()
+            .find(|a| &a.alias == alias)
+            .cloned())
+    }
+
+    async fn list_account_aliases(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut aliases: Vec<_> = state
+            .account_aliases
+            .iter()
+            .filter(|a| a.account_id == account_id)
+            .cloned()
+            .collect();
+        aliases.sort_by_key(|a| a.created_at);
+        Ok(aliases)
+    }
+
+    async fn remove_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<bool, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.account_aliases.len();
+        state
+            .account_aliases
+            .retain(|a| !(a.account_id == account_id && &a.alias == alias));
+        Ok(state.account_aliases.len() < before)
+    }
+
+    async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError> {
+        self.state.lock().unwrap().owners.push(owner.clone());
+        Ok(owner.clone())
+    }
+
+    async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.owners.iter().find(|o| o.id == id).cloned())
+    }
+
+    async fn list_owners(&self) -> Result<Vec<Owner>, RepoError> {
+        let mut owners = self.state.lock().unwrap().owners.clone();
+        owners.sort_by_key(|o| o.created_at);
+        Ok(owners)
+    }
+
+    async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state
+            .owners
+            .iter_mut()
+            .find(|o| o.id == owner.id)
+            .map(|stored| {
+                stored.name = owner.name.clone();
+                stored.email = owner.email.clone();
+                stored.clone()
+            }))
+    }
+
+    async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.owners.len();
+        state.owners.retain(|o| o.id != id);
+        Ok(state.owners.len() < before)
+    }
+
+    async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Account>, RepoError> {
+        let mut accounts: Vec<Account> = self
+            .state
+            .lock()
+            .unwrap()
+            .accounts
+            .values()
+            .filter(|a| a.owner_id == owner_id && mode.is_none_or(|mode| a.mode == mode))
+            .cloned()
+            .collect();
+        accounts.sort_by_key(|a| Reverse(a.created_at));
+        Ok(accounts)
+    }
+
+    async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError> {
+        self.state.lock().unwrap().memo_keys.push(key.clone());
+        Ok(key.clone())
+    }
+
+    async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.memo_keys.iter().find(|k| k.id == id).cloned())
+    }
+
+    async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut keys: Vec<_> = state
+            .memo_keys
+            .iter()
+            .filter(|k| k.account_id == account_id)
+            .cloned()
+            .collect();
+        keys.sort_by_key(|k| k.created_at);
+        Ok(keys)
+    }
+
+    async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        id: MemoKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<MemoKey>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state
+            .memo_keys
+            .iter_mut()
+            .find(|k| k.id == id && k.account_id == account_id)
+            .map(|key| {
+                key.retired_at.get_or_insert(at);
+                key.clone()
+            }))
+    }
+
+    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+        self.deposit_in(&mut self.state.lock().unwrap(), req)
+    }
+
+    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+        self.withdraw_in(&mut self.state.lock().unwrap(), req)
+    }
+
+    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+        self.transfer_in(&mut self.state.lock().unwrap(), req, None)
+    }
+
+    async fn transfer_with_conversion(
+        &self,
+        req: TransferRequest,
+        conversion: FxConversion,
+    ) -> Result<Transaction, RepoError> {
+        self.transfer_in(&mut self.state.lock().unwrap(), req, Some(conversion))
+    }
+
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork + '_>, RepoError> {
+        let staged = self.state.lock().unwrap().clone();
+        Ok(Box::new(InMemoryUnitOfWork {
+            repo: self,
+            transactions_before: staged.transactions.len(),
+            holds_before: staged.holds.len(),
+            events_before: staged.webhook_events.len(),
+            logged_before: staged.event_log.len(),
+            staged,
+            accounts_before: HashMap::new(),
+            risks: Vec::new(),
+            resolved_holds: Vec::new(),
+            spent_quotes: Vec::new(),
+        }))
+    }
+
+    async fn record_transaction_risk(
+        &self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        self.state.lock().unwrap().record_risk(id, risk);
+        Ok(())
+    }
+
+    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .transactions
+            .iter()
+            .find(|t| t.idempotency_key.as_deref() == Some(key))
+            .cloned())
+    }
+
+    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .transactions
+            .iter()
+            .find(|t| t.id == id)
+            .cloned())
+    }
+
+    async fn list_transactions_for_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let mut transactions: Vec<Transaction> = self
+            .state
+            .lock()
+            .unwrap()
+            .transactions
+            .iter()
+            .filter(|t| {
+                t.source_account_id == Some(account_id)
+                    || t.destination_account_id == Some(account_id)
+            })
+            .cloned()
+            .collect();
+        transactions.sort_by_key(|a| Reverse(a.created_at));
+        Ok(transactions)
+    }
+
+    async fn list_transactions_page(
+        &self,
+        account_id: AccountId,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let mut transactions: Vec<Transaction> = self
+            .state
+            .lock()
+            .unwrap()
+            .transactions
+            .iter()
+            .filter(|t| {
+                (t.source_account_id == Some(account_id)
+                    || t.destination_account_id == Some(account_id))
+                    && filter.matches(t)
+                    && after.is_none_or(|cursor| cursor.precedes(t))
+            })
+            .cloned()
+            .collect();
+        transactions.sort_by_key(|t| Reverse((t.created_at, *t.id.as_uuid())));
+        transactions.truncate(limit);
+        Ok(transactions)
+    }
+
+    async fn search_transactions(
+        &self,
+        search: &TransactionSearch,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let in_mode = |id: Option<AccountId>, mode: Mode| {
+            id.and_then(|id| state.accounts.get(&id))
+                .is_some_and(|account| account.mode == mode)
+        };
+        let mut transactions: Vec<Transaction> = state
+            .transactions
+            .iter()
+            .filter(|t| search.matches(t) && after.is_none_or(|cursor| cursor.precedes(t)))
+            .filter(|t| {
+                search.mode.is_none_or(|mode| {
+                    in_mode(t.source_account_id, mode) || in_mode(t.destination_account_id, mode)
+                })
+            })
+            .cloned()
+            .collect();
+        transactions.sort_by_key(|t| Reverse((t.created_at, *t.id.as_uuid())));
+        transactions.truncate(limit);
+        Ok(transactions)
+    }
+
+    async fn list_transactions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut transactions: Vec<Transaction> = state
+            .transactions
+            .iter()
+            .filter(|t| t.created_at >= from && t.created_at < to && state.in_mode(t, mode))
+            .cloned()
+            .collect();
+        transactions.sort_by_key(|t| t.created_at);
+        Ok(transactions)
+    }
+
+    async fn verify_api_key_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, RepoError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .api_keys
+            .iter()
+            .find(|k| k.is_active && k.key_hash == key_hash)
+            .cloned())
+    }
+
+    async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(ApiKey, String), RepoError> {
+        Ok(self.insert_api_key(name, None, None, scopes, mode))
+    }
+
+    async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(ApiKey, String), RepoError> {
+        Ok(self.insert_api_key(name, Some(account_id), None, scopes, mode))
+    }
+
+    async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[ApiKeyScope],
+        mode: Mode,
+    ) -> Result<(ApiKey, String), RepoError> {
+        Ok(self.insert_api_key(name, None, Some(owner_id), scopes, mode))
+    }
+
+    async fn count_api_keys(&self) -> Result<i64, RepoError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .api_keys
+            .iter()
+            .filter(|k| k.is_active)
+            .count() as i64)
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<ApiKey>, RepoError> {
+        let mut keys: Vec<ApiKey> = self
+            .state
+            .lock()
+            .unwrap()
+            .api_keys
+            .iter()
+            .filter(|k| k.is_active)
+            .cloned()
+            .collect();
+        keys.sort_by_key(|a| Reverse(a.created_at));
+        Ok(keys)
+    }
+
+    async fn delete_api_key(
+        &self,
+        id: ApiKeyId,
+        deactivated_by: ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        match state
+            .api_keys
+            .iter_mut()
+            .find(|k| k.id == id && k.is_active)
+        {
+            Some(key) => {
+                key.is_active = false;
+                key.deactivated_at = Some(at);
+                key.deactivated_by = Some(deactivated_by);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn touch_api_key(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(key) = state.api_keys.iter_mut().find(|k| k.id == id) {
+            key.last_used_at = Some(at);
+        }
+        Ok(())
+    }
+
+    async fn set_api_key_rate_limit(
+        &self,
+        id: ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<bool, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        match state
+            .api_keys
+            .iter_mut()
+            .find(|k| k.id == id && k.is_active)
+        {
+            Some(key) => {
+                key.rate_limit_per_minute = rate_limit_per_minute;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn get_api_key(&self, id: ApiKeyId) -> Result<Option<ApiKey>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.api_keys.iter().find(|k| k.id == id).cloned())
+    }
+
+    async fn insert_api_key_audit(&self, entry: &ApiKeyAuditEntry) -> Result<(), RepoError> {
+        self.state.lock().unwrap().api_key_audit.push(entry.clone());
+        Ok(())
+    }
+
+    async fn list_api_key_audit(&self, id: ApiKeyId) -> Result<Vec<ApiKeyAuditEntry>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .api_key_audit
+            .iter()
+            .filter(|entry| entry.api_key_id == id)
+            .cloned()
+            .collect())
+    }
+
+    async fn add_api_key_usage(&self, usage: &ApiKeyUsageBucket) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        match state
+            .api_key_usage
+            .iter_mut()
+            .find(|b| b.api_key_id == usage.api_key_id && b.hour == usage.hour)
+        {
+            Some(bucket) => {
+                bucket.requests += usage.requests;
+                bucket.rate_limited += usage.rate_limited;
+            }
+            None => state.api_key_usage.push(*usage),
+        }
+        Ok(())
+    }
+
+    async fn add_api_key_volume(&self, volume: &ApiKeyVolumeBucket) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        match state.api_key_volume.iter_mut().find(|b| {
+            b.api_key_id == volume.api_key_id
+                && b.hour == volume.hour
+                && b.transaction_type == volume.transaction_type
+                && b.currency == volume.currency
+        }) {
+            Some(bucket) => {
+                bucket.count += volume.count;
+                bucket.amount += volume.amount;
+            }
+            None => state.api_key_volume.push(*volume),
+        }
+        Ok(())
+    }
+
+    async fn list_api_key_usage(
+        &self,
+        id: ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyUsageBucket>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut buckets: Vec<ApiKeyUsageBucket> = state
+            .api_key_usage
+            .iter()
+            .filter(|b| b.api_key_id == id && b.hour >= since)
+            .copied()
+            .collect();
+        buckets.sort_by_key(|b| b.hour);
+        Ok(buckets)
+    }
+
+    async fn list_api_key_volume(
+        &self,
+        id: ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyVolumeBucket>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut buckets: Vec<ApiKeyVolumeBucket> = state
+            .api_key_volume
+            .iter()
+            .filter(|b| b.api_key_id == id && b.hour >= since)
+            .copied()
+            .collect();
+        buckets.sort_by_key(|b| b.hour);
+        Ok(buckets)
+    }
+
+    async fn register_webhook_endpoint(
+        &self,
+        url: &str,
+        events: Vec<String>,
+        timeouts: WebhookTimeouts,
+        signature_algorithm: WebhookSignatureAlgorithm,
+    ) -> Result<WebhookEndpoint, RepoError> {
+        let mut registered = self
+            .register_webhook_endpoints(vec![NewWebhookEndpoint {
+                url: url.to_string(),
+                events,
+                timeouts,
+                signature_algorithm,
+                mode: Mode::Live,
+            }])
+            .await?;
+        Ok(registered.remove(0))
+    }
+
+    async fn register_webhook_endpoints(
+        &self,
+        endpoints: Vec<NewWebhookEndpoint>,
+    ) -> Result<Vec<WebhookEndpoint>, RepoError> {
+        let registered: Vec<WebhookEndpoint> = endpoints
+            .into_iter()
+            .map(|endpoint| {
+                let signing_key = generate_webhook_signing_key();
+                WebhookEndpoint {
+                    id: self.ids.new_id(),
+                    url: endpoint.url,
+                    public_key: webhook_public_key(endpoint.signature_algorithm, &signing_key),
+                    secret: generate_webhook_secret(),
+                    events: endpoint.events,
+                    is_active: true,
+                    created_at: self.clock.now(),
+                    timeouts: endpoint.timeouts,
+                    signature_algorithm: endpoint.signature_algorithm,
+                    signing_key,
+                    mode: endpoint.mode,
+                }
+            })
+            .collect();
+        self.state
+            .lock()
+            .unwrap()
+            .webhook_endpoints
+            .extend(registered.iter().cloned());
+        Ok(registered)
+    }
+
+    async fn list_webhook_endpoints(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<WebhookEndpoint>, RepoError> {
+        let mut endpoints: Vec<WebhookEndpoint> = self
+            .state
+            .lock()
+            .unwrap()
+            .webhook_endpoints
+            .iter()
+            .filter(|e| e.is_active && mode.is_none_or(|mode| e.mode == mode))
+            .cloned()
+            .collect();
+        endpoints.sort_by_key(|a| Reverse(a.created_at));
+        Ok(endpoints)
+    }
+
+    async fn get_webhook_endpoint(
+        &self,
+        id: WebhookEndpointId,
+    ) -> Result<Option<WebhookEndpoint>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .webhook_endpoints
+            .iter()
+            .find(|e| e.id == id.0)
+            .cloned())
+    }
+
+    async fn update_webhook_endpoint_url(
+        &self,
+        id: WebhookEndpointId,
+        url: &str,
+    ) -> Result<Option<WebhookEndpoint>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state
+            .webhook_endpoints
+            .iter_mut()
+            .find(|e| e.id == id.0)
+            .map(|endpoint| {
+                endpoint.url = url.to_string();
+                endpoint.clone()
+            }))
+    }
+
+    async fn update_webhook_endpoint(
+        &self,
+        id: WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<WebhookSignatureAlgorithm>,
+    ) -> Result<Option<WebhookEndpoint>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state
+            .webhook_endpoints
+            .iter_mut()
+            .find(|e| e.id == id.0)
+            .map(|endpoint| {
+                if let Some(events) = events {
+                    endpoint.events = events;
+                }
+                if let Some(is_active) = is_active {
+                    endpoint.is_active = is_active;
+                }
+                if let Some(signature_algorithm) = signature_algorithm {
+                    endpoint.signature_algorithm = signature_algorithm;
+                    endpoint.public_key =
+                        webhook_public_key(signature_algorithm, &endpoint.signing_key);
+                }
+                endpoint.clone()
+            }))
+    }
+
+    async fn rotate_webhook_secret(
+        &self,
+        id: WebhookEndpointId,
+    ) -> Result<Option<WebhookEndpoint>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state
+            .webhook_endpoints
+            .iter_mut()
+            .find(|e| e.id == id.0)
+            .map(|endpoint| {
+                endpoint.secret = generate_webhook_secret();
+                endpoint.signing_key = generate_webhook_signing_key();
+                endpoint.public_key =
+                    webhook_public_key(endpoint.signature_algorithm, &endpoint.signing_key);
+                endpoint.clone()
+            }))
+    }
+
+    async fn delete_webhook_endpoint(&self, id: WebhookEndpointId) -> Result<bool, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.webhook_endpoints.len();
+        state.webhook_endpoints.retain(|e| e.id != id.0);
+        Ok(state.webhook_endpoints.len() < before)
+    }
+
+    async fn create_webhook_event(
+        &self,
+        endpoint_id: WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<WebhookEvent, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(self.queue_webhook_event(&mut state, endpoint_id, event_type, payload))
+    }
+
+    async fn get_webhook_event(&self, id: uuid::Uuid) -> Result<Option<WebhookEvent>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.webhook_events.iter().find(|e| e.id == id).cloned())
+    }
+
+    async fn list_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut events: Vec<_> = state
+            .webhook_events
+            .iter()
+            .filter(|e| filter.matches(e))
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| (e.created_at, e.id));
+        Ok(events)
+    }
+
+    async fn requeue_dead_lettered_webhooks(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<u64, RepoError> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let mut requeued = 0;
+        for event in state
+            .webhook_events
+            .iter_mut()
+            .filter(|e| filter.matches(e))
+        {
+            event.status = WebhookStatus::Pending;
+            event.attempts = 0;
+            event.processed_at = None;
+            event.next_attempt_at = Some(now);
+            requeued += 1;
+        }
+        Ok(requeued)
+    }
+
+    async fn count_webhook_backlog(&self) -> Result<i64, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .webhook_events
+            .iter()
+            .filter(|e| matches!(e.status, WebhookStatus::Pending | WebhookStatus::Failed))
+            .count() as i64)
+    }
+
+    async fn storage_counts(&self) -> Result<StorageCounts, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(StorageCounts {
+            accounts: state.accounts.len() as i64,
+            transactions: state.transactions.len() as i64,
+            webhook_events: state.webhook_events.len() as i64,
+        })
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        Ok(())
+    }
+
+    async fn list_webhook_events_after(
+        &self,
+        endpoint_id: WebhookEndpointId,
+        after_sequence: i64,
+        limit: usize,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut events: Vec<_> = state
+            .webhook_events
+            .iter()
+            .filter(|e| e.endpoint_id == endpoint_id.0 && e.delivery_sequence > after_sequence)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.delivery_sequence);
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        endpoint_id: WebhookEndpointId,
+        filter: &WebhookDeliveryFilter,
+        limit: usize,
+    ) -> Result<Vec<WebhookEvent>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut events: Vec<_> = state
+            .webhook_events
+            .iter()
+            .filter(|e| e.endpoint_id == endpoint_id.0 && filter.matches(e))
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| Reverse(e.delivery_sequence));
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    async fn requeue_webhook_event(&self, id: uuid::Uuid) -> Result<bool, RepoError> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let Some(event) = state
+            .webhook_events
+            .iter_mut()
+            .find(|e| e.id == id && e.status.is_failed())
+        else {
+            return Ok(false);
+        };
+        event.status = WebhookStatus::Pending;
+        event.attempts = 0;
+        event.processed_at = None;
+        event.next_attempt_at = Some(now);
+        Ok(true)
+    }
+
+    async fn append_event(&self, event: &DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        self.log_event(&mut self.state.lock().unwrap(), event, mode);
+        Ok(())
+    }
+
+    async fn list_logged_events(
+        &self,
+        after: i64,
+        limit: usize,
+        mode: Option<Mode>,
+    ) -> Result<Vec<LoggedEvent>, RepoError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .event_log
+            .iter()
+            .filter(|e| e.sequence > after && mode.is_none_or(|mode| e.mode == mode))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn create_fee_schedule(
+        &self,
+        name: &str,
+        tiers: &[FeeTier],
+    ) -> Result<FeeSchedule, RepoError> {
+        let schedule = FeeSchedule {
+            id: FeeScheduleId::from_uuid(self.ids.new_id()),
+            name: name.to_string(),
+            tiers: tiers.to_vec(),
+            created_at: self.clock.now(),
+        };
+        self.state
+            .lock()
+            .unwrap()
+            .fee_schedules
+            .push(schedule.clone());
+        Ok(schedule)
+    }
+
+    async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<Option<FeeSchedule>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.fee_schedules.iter().find(|s| s.id == id).cloned())
+    }
+
+    async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut schedules = state.fee_schedules.clone();
+        schedules.reverse();
+        Ok(schedules)
+    }
+
+    async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: DateTime<Utc>,
+    ) -> Result<FeeAssignment, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let known_schedule = state.fee_schedules.iter().any(|s| s.id == fee_schedule_id);
+        if !state.accounts.contains_key(&account_id) || !known_schedule {
+            return Err(RepoError::NotFound);
+        }
+        let assignment = FeeAssignment {
+            account_id,
+            fee_schedule_id,
+            effective_from,
+            assigned_at: self.clock.now(),
+        };
+        state.fee_assignments.push(assignment.clone());
+        Ok(assignment)
+    }
+
+    async fn list_fee_assignments(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<FeeAssignment>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut assignments: Vec<FeeAssignment> = state
+            .fee_assignments
+            .iter()
+            .filter(|a| a.account_id == account_id)
+            .cloned()
+            .collect();
+        assignments.sort_by_key(|a| (a.effective_from, a.assigned_at));
+        Ok(assignments)
+    }
+
+    async fn create_settlement_batch(
+        &self,
+        cutoff: DateTime<Utc>,
+        mode: Mode,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let payouts: Vec<Transaction> = state
+            .transactions
+            .iter()
+            .filter(|t| {
+                t.transaction_type == TransactionType::Withdrawal
+                    && t.created_at <= cutoff
+                    && !state.settlement_payouts.contains_key(&t.id)
+                    && t.source_account_id
+                        .and_then(|id| state.accounts.get(&id))
+                        .is_some_and(|account| account.mode == mode)
+            })
+            .cloned()
+            .collect();
+        if payouts.is_empty() {
+            return Ok(None);
+        }
+
+        let now = self.clock.now();
+        let batch = SettlementBatch {
+            id: SettlementBatchId::from_uuid(self.ids.new_id()),
+            status: SettlementBatchStatus::Pending,
+            cutoff,
+            payout_count: payouts.len() as i64,
+            totals: SettlementBatch::totals_for(&payouts),
+            created_at: now,
+            updated_at: now,
+            mode,
+        };
+        for payout in &payouts {
+            state.settlement_payouts.insert(payout.id, batch.id);
+        }
+        state.settlement_batches.push(batch.clone());
+        Ok(Some(batch))
+    }
+
+    async fn get_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .settlement_batches
+            .iter()
+            .find(|b| b.id == id)
+            .cloned())
+    }
+
+    async fn list_settlement_batches(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<SettlementBatch>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut batches: Vec<SettlementBatch> = state
+            .settlement_batches
+            .iter()
+            .filter(|b| mode.is_none_or(|mode| b.mode == mode))
+            .cloned()
+            .collect();
+        batches.reverse();
+        Ok(batches)
+    }
+
+    async fn update_settlement_batch_status(
+        &self,
+        id: SettlementBatchId,
+        from: SettlementBatchStatus,
+        to: SettlementBatchStatus,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let Some(batch) = state
+            .settlement_batches
+            .iter_mut()
+            .find(|b| b.id == id && b.status == from)
+        else {
+            return Ok(None);
+        };
+        batch.status = to;
+        batch.updated_at = self.clock.now();
+        let batch = batch.clone();
+
+        if to == SettlementBatchStatus::Cancelled {
+            state
+                .settlement_payouts
+                .retain(|_, batch_id| *batch_id != id);
+        }
+        Ok(Some(batch))
+    }
+
+    async fn list_settlement_batch_payouts(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut payouts: Vec<Transaction> = state
+            .transactions
+            .iter()
+            .filter(|t| state.settlement_payouts.get(&t.id) == Some(&id))
+            .cloned()
+            .collect();
+        payouts.sort_by_key(|t| t.created_at);
+        Ok(payouts)
+    }
+
+    async fn get_statement_email(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<String>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.statement_emails.get(&account_id).cloned())
+    }
+
+    async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        match email {
+            Some(email) => state.statement_emails.insert(account_id, email.to_string()),
+            None => state.statement_emails.remove(&account_id),
+        };
+        Ok(())
+    }
+
+    async fn insert_statement(
+        &self,
+        statement: &Statement,
+        content: &str,
+    ) -> Result<bool, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        if state.statements.iter().any(|(s, _)| {
+            s.account_id == statement.account_id && s.period_start == statement.period_start
+        }) {
+            return Ok(false);
+        }
+        state
+            .statements
+            .push((statement.clone(), content.to_string()));
+        Ok(true)
+    }
+
+    async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .statements
+            .iter()
+            .find(|(s, _)| s.id == id)
+            .map(|(s, _)| s.clone()))
+    }
+
+    async fn get_statement_content(&self, id: StatementId) -> Result<Option<String>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .statements
+            .iter()
+            .find(|(s, _)| s.id == id)
+            .map(|(_, content)| content.clone()))
+    }
+
+    async fn list_statements(&self, account_id: AccountId) -> Result<Vec<Statement>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut statements: Vec<Statement> = state
+            .statements
+            .iter()
+            .filter(|(s, _)| s.account_id == account_id)
+            .map(|(s, _)| s.clone())
+            .collect();
+        statements.sort_by_key(|s| Reverse(s.period_start));
+        Ok(statements)
+    }
+
+    async fn insert_export(&self, export: &Export) -> Result<(), RepoError> {
+        self.state
+            .lock()
+            .unwrap()
+            .exports
+            .insert(export.id, (export.clone(), None));
+        Ok(())
+    }
+
+    async fn finish_export(&self, export: &Export, content: Option<&str>) -> Result<(), RepoError> {
+        if let Some(stored) = self.state.lock().unwrap().exports.get_mut(&export.id) {
+            *stored = (export.clone(), content.map(str::to_string));
+        }
+        Ok(())
+    }
+
+    async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.exports.get(&id).map(|(export, _)| export.clone()))
+    }
+
+    async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .exports
+            .get(&id)
+            .and_then(|(_, content)| content.clone()))
+    }
+
+    async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.exports.len();
+        state
+            .exports
+            .retain(|_, (export, _)| export.created_at >= cutoff);
+        Ok((before - state.exports.len()) as u64)
+    }
+
+    async fn insert_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<(), RepoError> {
+        self.state
+            .lock()
+            .unwrap()
+            .scheduled_payments
+            .push(payment.clone());
+        Ok(())
+    }
+
+    async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<Option<ScheduledPayment>, RepoError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .scheduled_payments
+            .iter()
+            .find(|p| p.id == id)
+            .cloned())
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let in_mode = |id: &AccountId| {
+            mode.is_none_or(|mode| state.accounts.get(id).is_some_and(|a| a.mode == mode))
+        };
+        Ok(state
+            .scheduled_payments
+            .iter()
+            .filter(|p| account_id.is_none_or(|id| p.from_account_id == id))
+            .filter(|p| in_mode(&p.from_account_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn list_due_scheduled_payments(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        let mut due: Vec<ScheduledPayment> = self
+            .state
+            .lock()
+            .unwrap()
+            .scheduled_payments
+            .iter()
+            .filter(|p| p.status == ScheduledPaymentStatus::Active && p.next_run_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|p| (p.next_run_at, *p.id.as_uuid()));
+        due.truncate(limit);
+        Ok(due)
+    }
+
+    async fn record_scheduled_payment_run(
+        &self,
+        payment: &ScheduledPayment,
+        due_at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let Some(stored) = state
+            .scheduled_payments
+            .iter_mut()
+            .find(|p| p.id == payment.id && p.next_run_at == due_at)
+        else {
+            return Ok(false);
+        };
+        stored.next_run_at = payment.next_run_at;
+        stored.last_run_at = payment.last_run_at;
+        stored.last_transaction_id = payment.last_transaction_id;
+        stored.last_error = payment.last_error.clone();
+        Ok(true)
+    }
+
+    async fn update_scheduled_payment_status(
+        &self,
+        payment: &ScheduledPayment,
+    ) -> Result<(), RepoError> {
+        if let Some(stored) = self
+            .state
+            .lock()
+            .unwrap()
+            .scheduled_payments
+            .iter_mut()
+            .find(|p| p.id == payment.id)
+        {
+            stored.status = payment.status;
+            stored.next_run_at = payment.next_run_at;
+        }
+        Ok(())
+    }
+
+    async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError> {
+        self.state.lock().unwrap().place_hold(hold)
+    }
+
+    async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.holds.iter().find(|h| h.id == id).cloned())
+    }
+
+    async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut holds: Vec<Hold> = state
+            .holds
+            .iter()
+            .filter(|h| h.account_id == account_id)
+            .cloned()
+            .collect();
+        holds.sort_by_key(|h| Reverse((h.created_at, *h.id.as_uuid())));
+        Ok(holds)
+    }
+
+    async fn list_expired_holds(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Hold>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut expired: Vec<Hold> = state
+            .holds
+            .iter()
+            .filter(|h| h.is_open() && h.expires_at <= now)
+            .cloned()
+            .collect();
+        expired.sort_by_key(|h| (h.expires_at, *h.id.as_uuid()));
+        expired.truncate(limit);
+        Ok(expired)
+    }
+
+    async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        self.capture_hold_in(&mut state, id, amount, now)
+    }
+
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        self.release_hold_in(&mut state, id, status, now)
+    }
+
+    async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        state.account_mut(beneficiary.account_id)?;
+        state.beneficiaries.push(beneficiary.clone());
+        Ok(())
+    }
+
+    async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Option<Beneficiary>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.beneficiaries.iter().find(|b| b.id == id).cloned())
+    }
+
+    async fn list_beneficiaries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut beneficiaries: Vec<Beneficiary> = state
+            .beneficiaries
+            .iter()
+            .filter(|b| b.account_id == account_id)
+            .cloned()
+            .collect();
+        beneficiaries.sort_by_key(|b| Reverse((b.created_at, *b.id.as_uuid())));
+        Ok(beneficiaries)
+    }
+
+    async fn record_beneficiary_attempt(
+        &self,
+        beneficiary: &Beneficiary,
+        failed_attempts: u32,
+    ) -> Result<bool, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let Some(stored) = state.beneficiaries.iter_mut().find(|b| {
+            b.id == beneficiary.id
+                && b.status == BeneficiaryStatus::Pending
+                && b.failed_attempts == failed_attempts
+        }) else {
+            return Ok(false);
+        };
+        stored.status = beneficiary.status;
+        stored.failed_attempts = beneficiary.failed_attempts;
+        stored.verified_at = beneficiary.verified_at;
+        Ok(true)
+    }
+
+    async fn currency_balances(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<CurrencyBalance>, RepoError> {
+        Ok(CurrencyBalance::tally(
+            self.state
+                .lock()
+                .unwrap()
+                .accounts
+                .values()
+                .filter(|a| mode.is_none_or(|mode| a.mode == mode)),
+        ))
+    }
+
+    async fn float_positions(&self, mode: Option<Mode>) -> Result<Vec<FloatPosition>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(FloatPosition::tally(
+            state
+                .accounts
+                .values()
+                .filter(|a| mode.is_none_or(|mode| a.mode == mode)),
+            state.transactions.iter().filter(|t| state.in_mode(t, mode)),
+        ))
+    }
+
+    async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .rate_snapshots
+            .retain(|s| s.taken_at != snapshot.taken_at);
+        state.rate_snapshots.push(snapshot.clone());
+        Ok(())
+    }
+
+    async fn rate_snapshot_at(&self, at: DateTime<Utc>) -> Result<Option<RateSnapshot>, RepoError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .rate_snapshots
+            .iter()
+            .filter(|s| s.taken_at <= at)
+            .max_by_key(|s| s.taken_at)
+            .cloned())
+    }
+
+    async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError> {
+        self.state.lock().unwrap().quotes.push(quote.clone());
+        Ok(())
+    }
+
+    async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.quotes.iter().find(|q| q.id == id).cloned())
+    }
+
+    async fn save_daily_balances(
+        &self,
+        account_id: AccountId,
+        balances: &[DailyBalance],
+    ) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let stored = state.daily_balances.entry(account_id).or_default();
+        for daily in balances {
+            stored.insert(daily.date, daily.balance);
+        }
+        Ok(())
+    }
+
+    async fn list_daily_balances(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyBalance>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .daily_balances
+            .get(&account_id)
+            .into_iter()
+            .flat_map(|stored| stored.range(from..=to))
+            .map(|(&date, &balance)| DailyBalance { date, balance })
+            .collect())
+    }
+
+    async fn latest_daily_balance_date(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<NaiveDate>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .daily_balances
+            .get(&account_id)
+            .and_then(|stored| stored.keys().next_back().copied()))
+    }
+
+    async fn insert_change_request(&self, request: &ChangeRequest) -> Result<(), RepoError> {
+        self.state
+            .lock()
+            .unwrap()
+            .change_requests
+            .push(request.clone());
+        Ok(())
+    }
+
+    async fn get_change_request(
+        &self,
+        id: ChangeRequestId,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.change_requests.iter().find(|r| r.id == id).cloned())
+    }
+
+    async fn list_change_requests(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, RepoError> {
+        let state = self.state.lock().unwrap();
+        let mut requests: Vec<ChangeRequest> = state
+            .change_requests
+            .iter()
+            .filter(|r| status.is_none_or(|status| r.status == status))
+            .cloned()
+            .collect();
+        requests.sort_by_key(|r| Reverse((r.requested_at, *r.id.as_uuid())));
+        Ok(requests)
+    }
+
+    async fn decide_change_request(
+        &self,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: ApiKeyId,
+        decided_at: DateTime<Utc>,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state
+            .change_requests
+            .iter_mut()
+            .find(|r| r.id == id && r.status == ChangeStatus::Pending)
+            .map(|request| {
+                request.status = status;
+                request.decided_by = Some(decided_by);
+                request.decided_at = Some(decided_at);
+                request.clone()
+            }))
+    }
+
+    async fn get_idempotency_record(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, RepoError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .idempotency_records
+            .get(&(endpoint.to_string(), key.to_string()))
+            .cloned())
+    }
+
+    async fn save_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, RepoError> {
+        let mut state = self.state.lock().unwrap();
+        let stored = state
+            .idempotency_records
+            .entry((record.endpoint.clone(), record.key.clone()))
+            .or_insert_with(|| record.clone());
+        if stored.is_expired_reservation(record.created_at) {
+            *stored = record.clone();
+        }
+        Ok(stored.clone())
+    }
+
+    async fn replace_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        state.idempotency_records.insert(
+            (record.endpoint.clone(), record.key.clone()),
+            record.clone(),
+        );
+        Ok(())
+    }
+
+    async fn remove_idempotency_record(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .idempotency_records
+            .remove(&(endpoint.to_string(), key.to_string()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use payments_types::{CurrencyCode, PositiveAmount};
+
+    use super::*;
+
+    async fn funded(repo: &InMemoryRepo, currency: CurrencyCode, amount: i64) -> AccountId {
+        let account = repo
+            .create_account(CreateAccountRequest {
+                name: "Test".to_string(),
+                currency,
+                overdraft_limit: 0,
+                owner_id: OwnerId::DEFAULT,
+                mode: None,
+            })
+            .await
+            .unwrap();
+        if amount > 0 {
+            repo.deposit(DepositRequest {
+                account_id: account.id,
+                amount: PositiveAmount::new(amount).unwrap(),
+                currency,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+        }
+        account.id
+    }
+
+    fn transfer(from: AccountId, to: AccountId, amount: i64, key: Option<&str>) -> TransferRequest {
+        TransferRequest {
+            from_account_id: from,
+            to_account_id: to,
+            amount: PositiveAmount::new(amount).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: key.map(String::from),
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transfer_moves_balance() {
+        let repo = InMemoryRepo::new();
+        let alice = funded(&repo, CurrencyCode::USD, 1000).await;
+        let bob = funded(&repo, CurrencyCode::USD, 0).await;
+
+        repo.transfer(transfer(alice, bob, 300, None))
+            .await
+            .unwrap();
+
+        let alice = repo.get_account(alice).await.unwrap().unwrap();
+        let bob = repo.get_account(bob).await.unwrap().unwrap();
+        assert_eq!(alice.balance.amount(), 700);
+        assert_eq!(bob.balance.amount(), 300);
+    }
+
+    #[tokio::test]
+    async fn test_failed_transfer_leaves_balances_untouched() {
+        let repo = InMemoryRepo::new();
+        let alice = funded(&repo, CurrencyCode::USD, 100).await;
+        let bob = funded(&repo, CurrencyCode::USD, 0).await;
+
+        let result = repo.transfer(transfer(alice, bob, 500, None)).await;
+        assert!(matches!(
+            result,
+            Err(RepoError::Domain(DomainError::InsufficientFunds { .. }))
+        ));
+
+        let euro = funded(&repo, CurrencyCode::EUR, 0).await;
+        let result = repo.transfer(transfer(alice, euro, 50, None)).await;
+        assert!(matches!(
+            result,
+            Err(RepoError::Domain(DomainError::CrossCurrencyTransfer))
+        ));
+
+        let alice = repo.get_account(alice).await.unwrap().unwrap();
+        assert_eq!(alice.balance.amount(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_replay_and_conflict() {
+        let repo = InMemoryRepo::new();
+        let alice = funded(&repo, CurrencyCode::USD, 1000).await;
+        let bob = funded(&repo, CurrencyCode::USD, 0).await;
+
+        let first = repo
+            .transfer(transfer(alice, bob, 100, Some("key-1")))
+            .await
+            .unwrap();
+        let replay = repo
+            .transfer(transfer(alice, bob, 100, Some("key-1")))
+            .await
+            .unwrap();
+        assert_eq!(first.id, replay.id);
+
+        let conflict = repo
+            .transfer(transfer(alice, bob, 200, Some("key-1")))
+            .await;
+        assert!(matches!(
+            conflict,
+            Err(RepoError::Domain(DomainError::IdempotencyKeyConflict(_)))
+        ));
+
+        let bob = repo.get_account(bob).await.unwrap().unwrap();
+        assert_eq!(bob.balance.amount(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_lifecycle() {
+        let repo = InMemoryRepo::new();
+        let (key, raw) = repo
+            .create_api_key("test", &ApiKeyScope::ALL, Mode::Live)
+            .await
+            .unwrap();
+
+        let hash = payments_repo::security::hash_api_key(&raw);
+        assert!(repo.verify_api_key_hash(&hash).await.unwrap().is_some());
+        assert_eq!(repo.count_api_keys().await.unwrap(), 1);
+
+        let admin = ApiKeyId::new();
+        let now = chrono::Utc::now();
+        assert!(repo.delete_api_key(key.id, admin, now).await.unwrap());
+        assert!(repo.verify_api_key_hash(&hash).await.unwrap().is_none());
+        assert!(!repo.delete_api_key(key.id, admin, now).await.unwrap());
+        let deleted = repo.get_api_key(key.id).await.unwrap().unwrap();
+        assert_eq!(deleted.deactivated_by, Some(admin));
+        assert_eq!(deleted.deactivated_at, Some(now));
+    }
+
+    #[tokio::test]
+    async fn test_injected_clock_and_ids() {
+        use chrono::{DateTime, Duration, Utc};
+        use payments_types::{FixedClock, SequentialIdGenerator};
+
+        let start = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let clock = Arc::new(FixedClock::new(start));
+        let repo = InMemoryRepo::new()
+            .with_clock(clock.clone())
+            .with_id_generator(Arc::new(SequentialIdGenerator::new()));
+
+        let alice = funded(&repo, CurrencyCode::USD, 0).await;
+        clock.advance(Duration::days(1));
+        let tx = repo
+            .deposit(DepositRequest {
+                account_id: alice,
+                amount: PositiveAmount::new(10).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(alice.to_string(), "00000000-0000-0000-0000-000000000001");
+        assert_eq!(tx.id.to_string(), "00000000-0000-0000-0000-000000000002");
+        assert_eq!(tx.created_at, start + Duration::days(1));
+    }
+}
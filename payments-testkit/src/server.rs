@@ -0,0 +1,93 @@
+//! A real HTTP server for integration tests.
+
+use payments_client::PaymentsClient;
+use std::sync::Arc;
+
+use payments_hex::inbound::HttpServer;
+use payments_hex::{MetricsRegistry, PaymentService};
+use payments_repo::CountingRepo;
+use payments_types::{ApiKeyScope, Mode, TransactionRepository};
+use tokio::task::JoinHandle;
+
+use crate::InMemoryRepo;
+
+/// Rate limit for test servers: high enough that no test trips it by accident.
+const TEST_RATE_LIMIT: u32 = 1_000_000;
+
+/// Returns a `PaymentService` over a fresh [`InMemoryRepo`].
+pub fn in_memory_service() -> PaymentService<InMemoryRepo> {
+    PaymentService::new(InMemoryRepo::new())
+}
+
+/// A payments server listening on a random local port.
+///
+/// The server task is aborted when this value is dropped.
+pub struct TestServer {
+    /// Base URL, e.g. `http://127.0.0.1:54321`
+    pub base_url: String,
+    /// An unscoped API key created before the server started
+    pub api_key: String,
+    handle: JoinHandle<()>,
+}
+
+impl TestServer {
+    /// Returns a client authenticated with the server's API key.
+    pub fn client(&self) -> PaymentsClient {
+        PaymentsClient::new(&self.base_url).with_api_key(&self.api_key)
+    }
+
+    /// Returns an absolute URL for `path` (which should start with `/`).
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Spawns a server backed by a fresh [`InMemoryRepo`].
+pub async fn spawn_test_server() -> TestServer {
+    spawn_test_server_with(InMemoryRepo::new()).await
+}
+
+/// Spawns a server backed by `repo`, e.g. a pre-seeded [`InMemoryRepo`] or a
+/// real database adapter.
+///
+/// # Panics
+/// If the API key cannot be created or no local port can be bound.
+pub async fn spawn_test_server_with<R: TransactionRepository>(repo: R) -> TestServer {
+    let (_, api_key) = repo
+        .create_api_key("testkit", &ApiKeyScope::ALL, Mode::Live)
+        .await
+        .expect("create testkit API key");
+
+    let metrics = Arc::new(MetricsRegistry::default());
+    let service = PaymentService::builder(CountingRepo::new(repo))
+        .with_metrics(metrics.clone())
+        .build();
+    let router = HttpServer::with_rate_limit(service, TEST_RATE_LIMIT)
+        .with_metrics(metrics)
+        .router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind test server");
+    let addr = listener.local_addr().expect("test server address");
+
+    let handle = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("test server failed");
+    });
+
+    TestServer {
+        base_url: format!("http://{}", addr),
+        api_key,
+        handle,
+    }
+}
@@ -0,0 +1,3445 @@
+//! Contract tests: every `PaymentsClient` method against the real router.
+//!
+//! The client and server share DTOs through `payments-types`, but several
+//! response shapes (webhooks, API keys, bootstrap) are declared separately on
+//! each side. These tests fail when the two drift apart.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use payments_client::{ClientError, PaymentsClient};
+use payments_testkit::{
+    AccountBuilder, InMemoryRepo, TestServer, TransactionBuilder, spawn_test_server,
+    spawn_test_server_with,
+};
+use payments_types::{
+    Account, AccountId, AccountLimits, AccountRef, AccountStatus, Alias, ApiKeyAuditAction,
+    ApiKeyScope, AuthorizeRequest, BatchItemStatus, BatchMode, BatchOperation, BatchRequest,
+    BeneficiaryId, BeneficiaryStatus, ChangeAction, ChangeStatus, Counterparty,
+    CreateBeneficiaryRequest, CreateQuoteRequest, CreateScheduledPaymentRequest, CurrencyCode,
+    DeadLetterQuery, DepositRequest, EncryptedMemo, ErrorCode, ExportId, ExportRequest,
+    ExportStatus, ExposureQuery, FeeScheduleId, FeeTier, FloatPosition, FloatReportQuery, HoldId,
+    HoldStatus, InboundPaymentRequest, JournalExportFormat, MAX_WEBHOOK_PAYLOAD_BYTES, Mode,
+    OwnerId, PaymentSchedule, PositiveAmount, QuoteId, RegisterWebhookRequest, ScheduledPaymentId,
+    ScheduledPaymentStatus, ServiceHealth, SettlementBatchId, SettlementBatchStatus,
+    SettlementExportFormat, SpendingRules, StatementFormat, StatementPeriod, TransactionListQuery,
+    TransactionRepository, TransactionSearchQuery, TransactionType, TransferRequest,
+    UpdateWebhookRequest, WebhookDeliveriesQuery, WebhookEventsQuery, WebhookSignatureAlgorithm,
+    WebhookStatus, WithdrawRequest,
+};
+
+fn assert_api_error<T: std::fmt::Debug>(result: Result<T, ClientError>, expected: u16) {
+    match result {
+        Err(ClientError::Api { status, .. }) => assert_eq!(status, expected),
+        other => panic!("expected API error {}, got {:?}", expected, other),
+    }
+}
+
+/// A key bound to `account_id` holding every scope, so that only its
+/// account binding limits it.
+async fn account_key(client: &PaymentsClient, name: &str, account_id: AccountId) -> String {
+    client
+        .create_api_key_with_scopes(name, Some(account_id), &ApiKeyScope::ALL)
+        .await
+        .unwrap()
+}
+
+async fn funded_account(server: &TestServer, name: &str, amount: i64) -> AccountId {
+    let client = server.client();
+    let account = client
+        .create_account(OwnerId::DEFAULT, name, CurrencyCode::USD)
+        .await
+        .unwrap();
+    if amount > 0 {
+        client
+            .deposit(
+                account.id,
+                PositiveAmount::new(amount).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    account.id
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Health & Bootstrap
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_health() {
+    let server = spawn_test_server().await;
+    assert!(
+        PaymentsClient::new(&server.base_url)
+            .health()
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_bootstrap_issues_usable_key_once() {
+    let repo = Arc::new(InMemoryRepo::new());
+    let server = spawn_test_server_with(repo.clone()).await;
+    let client = server.client();
+
+    // Retire the testkit key so the system has no active keys. Deleting
+    // through the API needs a second admin, so go to the repository.
+    let keys = client.list_api_keys().await.unwrap();
+    let key_id = keys[0].id.parse().unwrap();
+    repo.delete_api_key(key_id, key_id, Utc::now())
+        .await
+        .unwrap();
+
+    let anonymous = PaymentsClient::new(&server.base_url);
+    let api_key = anonymous.bootstrap("first").await.unwrap();
+    assert!(api_key.starts_with("sk_"));
+
+    let bootstrapped = PaymentsClient::new(&server.base_url).with_api_key(&api_key);
+    assert!(bootstrapped.list_accounts().await.unwrap().is_empty());
+
+    assert_api_error(anonymous.bootstrap("second").await, 400);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Accounts
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_account_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+
+    let created = client
+        .create_account(OwnerId::DEFAULT, "Alice", CurrencyCode::EUR)
+        .await
+        .unwrap();
+    assert_eq!(created.name, "Alice");
+    assert_eq!(created.currency(), CurrencyCode::EUR);
+    assert_eq!(created.balance.amount(), 0);
+
+    let fetched = client.get_account(created.id).await.unwrap();
+    assert_eq!(fetched.id, created.id);
+    assert_eq!(fetched.created_at, created.created_at);
+
+    let listed = client.list_accounts().await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, created.id);
+}
+
+#[tokio::test]
+async fn test_account_search() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice Smith", 0).await;
+    funded_account(&server, "Bob Jones", 0).await;
+
+    let found = client.search_accounts("smith", None).await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, alice);
+
+    let prefix = &alice.to_string()[..8];
+    let found = client.search_accounts(prefix, Some(5)).await.unwrap();
+    assert!(found.iter().any(|a| a.id == alice));
+
+    assert_api_error(client.search_accounts(" ", None).await, 400);
+}
+
+#[tokio::test]
+async fn test_account_errors() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+
+    assert_api_error(client.get_account(AccountId::new()).await, 404);
+    assert_api_error(
+        client
+            .create_account(OwnerId::DEFAULT, "   ", CurrencyCode::USD)
+            .await,
+        400,
+    );
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Transactions
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_money_movement_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 0).await;
+    let bob = funded_account(&server, "Bob", 0).await;
+
+    let deposit = client
+        .deposit(
+            alice,
+            PositiveAmount::new(10_000).unwrap(),
+            CurrencyCode::USD,
+            None,
+            Some("payroll".into()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(deposit.transaction_type, TransactionType::Deposit);
+    assert_eq!(deposit.destination_account_id, Some(alice));
+    assert_eq!(deposit.reference.as_deref(), Some("payroll"));
+
+    let withdrawal = client
+        .withdraw(
+            alice,
+            PositiveAmount::new(1_000).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(withdrawal.transaction_type, TransactionType::Withdrawal);
+    assert_eq!(withdrawal.source_account_id, Some(alice));
+
+    let transfer = client
+        .transfer(
+            alice,
+            bob,
+            PositiveAmount::new(4_000).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(transfer.transaction_type, TransactionType::Transfer);
+    assert_eq!(transfer.source_account_id, Some(alice));
+    assert_eq!(transfer.destination_account_id, Some(bob));
+    assert_eq!(transfer.amount.amount(), 4_000);
+
+    assert_eq!(
+        client.get_account(alice).await.unwrap().balance.amount(),
+        5_000
+    );
+    assert_eq!(
+        client.get_account(bob).await.unwrap().balance.amount(),
+        4_000
+    );
+
+    let history = client
+        .list_transactions(alice, &TransactionListQuery::default())
+        .await
+        .unwrap()
+        .data;
+    let ids: Vec<_> = history.iter().map(|tx| tx.id).collect();
+    assert_eq!(history.len(), 3);
+    assert!(ids.contains(&deposit.id));
+    assert!(ids.contains(&withdrawal.id));
+    assert!(ids.contains(&transfer.id));
+}
+
+#[tokio::test]
+async fn test_transaction_history_pages() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 0).await;
+    let started = Utc::now();
+    for amount in [100, 200, 300] {
+        client
+            .deposit(
+                alice,
+                PositiveAmount::new(amount).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    client
+        .withdraw(
+            alice,
+            PositiveAmount::new(50).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let deposits = TransactionListQuery {
+        limit: Some(2),
+        transaction_type: Some(TransactionType::Deposit),
+        from: Some(started - Duration::minutes(1)),
+        ..Default::default()
+    };
+    let first = client.list_transactions(alice, &deposits).await.unwrap();
+    assert_eq!(first.data.len(), 2);
+    let second = client
+        .list_transactions(
+            alice,
+            &TransactionListQuery {
+                cursor: first.next_cursor.clone(),
+                ..deposits.clone()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(second.data.len(), 1);
+    assert_eq!(second.next_cursor, None);
+
+    let all = client
+        .list_all_transactions(alice, &deposits)
+        .await
+        .unwrap();
+    let mut amounts: Vec<i64> = all.iter().map(|tx| tx.amount.amount()).collect();
+    amounts.sort();
+    assert_eq!(amounts, vec![100, 200, 300]);
+
+    let small = TransactionListQuery {
+        max_amount: Some(100),
+        ..Default::default()
+    };
+    let page = client.list_transactions(alice, &small).await.unwrap();
+    let mut amounts: Vec<i64> = page.data.iter().map(|tx| tx.amount.amount()).collect();
+    amounts.sort();
+    assert_eq!(amounts, vec![50, 100]);
+
+    assert_api_error(
+        client
+            .list_transactions(
+                alice,
+                &TransactionListQuery {
+                    cursor: Some("nope".into()),
+                    ..Default::default()
+                },
+            )
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_search_transactions() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 0).await;
+    let bob = funded_account(&server, "Bob", 0).await;
+    for (account, amount, reference) in [
+        (alice, 100, "INV-2026-0001"),
+        (bob, 200, "INV-2026-0002"),
+        (bob, 300, "payroll"),
+    ] {
+        client
+            .deposit(
+                account,
+                PositiveAmount::new(amount).unwrap(),
+                CurrencyCode::USD,
+                None,
+                Some(reference.into()),
+            )
+            .await
+            .unwrap();
+    }
+
+    let invoices = TransactionSearchQuery {
+        reference: Some("inv-2026".into()),
+        limit: Some(1),
+        ..Default::default()
+    };
+    let first = client.search_transactions(&invoices).await.unwrap();
+    assert_eq!(first.data.len(), 1);
+    let second = client
+        .search_transactions(&TransactionSearchQuery {
+            cursor: first.next_cursor.clone(),
+            ..invoices.clone()
+        })
+        .await
+        .unwrap();
+    assert_eq!(second.data.len(), 1);
+    assert_eq!(second.next_cursor, None);
+    let mut amounts: Vec<i64> = first
+        .data
+        .iter()
+        .chain(&second.data)
+        .map(|tx| tx.amount.amount())
+        .collect();
+    amounts.sort();
+    assert_eq!(amounts, vec![100, 200]);
+
+    let large = TransactionSearchQuery {
+        min_amount: Some(150),
+        currency: Some(CurrencyCode::USD),
+        ..Default::default()
+    };
+    let page = client.search_transactions(&large).await.unwrap();
+    assert!(
+        page.data
+            .iter()
+            .all(|tx| tx.destination_account_id == Some(bob))
+    );
+    assert_eq!(page.data.len(), 2);
+
+    // Scoped keys only ever see their own account's transactions.
+    let raw = account_key(&client, "alice-app", alice).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    let own = alice_client.search_transactions(&invoices).await.unwrap();
+    assert_eq!(own.data.len(), 1);
+    assert_eq!(own.data[0].destination_account_id, Some(alice));
+    assert!(
+        alice_client
+            .search_transactions(&large)
+            .await
+            .unwrap()
+            .data
+            .is_empty()
+    );
+    assert_api_error(
+        alice_client
+            .search_transactions(&TransactionSearchQuery {
+                account: Some(bob.into()),
+                ..Default::default()
+            })
+            .await,
+        400,
+    );
+
+    assert_api_error(
+        client
+            .search_transactions(&TransactionSearchQuery::default())
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_metadata_round_trips_and_is_searchable() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 0).await;
+    let metadata = HashMap::from([("order_id".to_string(), "ord_1042".to_string())]);
+
+    let deposit = client
+        .send_deposit(&DepositRequest {
+            account_id: alice,
+            amount: PositiveAmount::new(5_000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: metadata.clone(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(deposit.metadata, metadata);
+    client
+        .deposit(
+            alice,
+            PositiveAmount::new(100).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let page = client
+        .search_transactions(&TransactionSearchQuery {
+            metadata_key: Some("order_id".into()),
+            metadata_value: Some("ord_1042".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.data.len(), 1);
+    assert_eq!(page.data[0].id, deposit.id);
+    assert_eq!(page.data[0].metadata, metadata);
+}
+
+#[tokio::test]
+async fn test_encrypted_memo_round_trips_under_a_memo_key() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 0).await;
+
+    let key = client
+        .add_memo_key(alice, "xchacha20poly1305", "sha256:9f86d081884c7d65")
+        .await
+        .unwrap();
+    assert_eq!(
+        client.list_memo_keys(alice).await.unwrap(),
+        vec![key.clone()]
+    );
+    assert_eq!(client.get_memo_key(alice, key.id).await.unwrap(), key);
+
+    let memo = EncryptedMemo {
+        key_id: key.id,
+        ciphertext: "3q2+7wAAAAB0aGlz".to_string(),
+    };
+    let deposit = client
+        .send_deposit(&DepositRequest {
+            account_id: alice,
+            amount: PositiveAmount::new(5_000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: Some(memo.clone()),
+        })
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(deposit.encrypted_memo, Some(memo.clone()));
+    let page = client
+        .list_transactions(alice, &TransactionListQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(page.data[0].encrypted_memo, Some(memo.clone()));
+
+    let retired = client.retire_memo_key(alice, key.id).await.unwrap();
+    assert!(retired.retired_at.is_some());
+    let err = client
+        .send_deposit(&DepositRequest {
+            account_id: alice,
+            amount: PositiveAmount::new(100).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            metadata: Default::default(),
+            encrypted_memo: Some(memo),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.error_code(), Some(ErrorCode::InvalidRequest));
+}
+
+#[tokio::test]
+async fn test_receive_inbound_payment() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let merchant = funded_account(&server, "Merchant", 1_000).await;
+    let other = funded_account(&server, "Other", 1_000).await;
+
+    let raw = client
+        .create_api_key_with_scopes("bank-feed", None, &[ApiKeyScope::InboundWrite])
+        .await
+        .unwrap();
+    let integration = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    let payment = |amount| InboundPaymentRequest {
+        account_id: AccountRef::from(merchant),
+        amount,
+        currency: CurrencyCode::USD,
+        external_reference: "SEPA-2026-0001".to_string(),
+        reference: Some("Invoice 17".to_string()),
+        counterparty: Some(Counterparty {
+            name: "ACME Ltd".to_string(),
+            external_id: Some("DE89370400440532013000".to_string()),
+        }),
+    };
+
+    let received = integration
+        .receive_inbound_payment(&payment(PositiveAmount::new(250).unwrap()))
+        .await
+        .unwrap();
+    assert!(!received.duplicate);
+    assert_eq!(
+        received.transaction.transaction_type,
+        TransactionType::Deposit
+    );
+    assert_eq!(received.transaction.destination_account_id, Some(merchant));
+
+    // The rail reporting the payment again credits nothing.
+    let again = integration
+        .receive_inbound_payment(&payment(PositiveAmount::new(250).unwrap()))
+        .await
+        .unwrap();
+    assert!(again.duplicate);
+    assert_eq!(again.transaction.id, received.transaction.id);
+    assert_eq!(
+        client.get_account(merchant).await.unwrap().balance.amount(),
+        1_250
+    );
+    assert_api_error(
+        integration
+            .receive_inbound_payment(&payment(PositiveAmount::new(300).unwrap()))
+            .await,
+        409,
+    );
+
+    // Integration keys only record inbound payments.
+    assert_api_error(
+        integration
+            .deposit(
+                merchant,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        403,
+    );
+    let raw = client
+        .create_api_key_with_scopes("payments", None, &[ApiKeyScope::TransactionsWrite])
+        .await
+        .unwrap();
+    let payments = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        payments
+            .receive_inbound_payment(&payment(PositiveAmount::new(250).unwrap()))
+            .await,
+        403,
+    );
+
+    // A key scoped to one account cannot credit another.
+    let raw = account_key(&client, "other-feed", other).await;
+    let scoped = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        scoped
+            .receive_inbound_payment(&payment(PositiveAmount::new(250).unwrap()))
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_beneficiary_verification() {
+    let repo = Arc::new(InMemoryRepo::new());
+    let server = spawn_test_server_with(repo.clone()).await;
+    let client = server.client();
+    let payer = funded_account(&server, "Payer", 0).await;
+    let other = funded_account(&server, "Other", 0).await;
+
+    let created = client
+        .create_beneficiary(&CreateBeneficiaryRequest {
+            account_id: payer,
+            name: "Globex".to_string(),
+            external_id: "GB33BUKB20201555555555".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.status, BeneficiaryStatus::Pending);
+    assert_eq!(created.currency, CurrencyCode::USD);
+
+    // The amounts reach the customer through their bank, never the API.
+    let [first, second] = repo
+        .get_beneficiary(created.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .micro_deposits;
+    assert_api_error(
+        client
+            .verify_beneficiary(created.id, [first + 100, second])
+            .await,
+        400,
+    );
+    let fetched = client.get_beneficiary(created.id).await.unwrap();
+    assert_eq!(fetched.failed_attempts, 1);
+
+    // A key scoped to another account cannot verify it.
+    let raw = account_key(&client, "other-app", other).await;
+    let scoped = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        scoped.verify_beneficiary(created.id, [first, second]).await,
+        400,
+    );
+
+    let verified = client
+        .verify_beneficiary(created.id, [second, first])
+        .await
+        .unwrap();
+    assert_eq!(verified.status, BeneficiaryStatus::Verified);
+    assert!(verified.verified_at.is_some());
+
+    let listed = client.list_beneficiaries(payer).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, created.id);
+    assert!(client.list_beneficiaries(other).await.unwrap().is_empty());
+    assert_api_error(client.get_beneficiary(BeneficiaryId::new()).await, 404);
+}
+
+#[tokio::test]
+async fn test_transaction_errors() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 500).await;
+    let bob = funded_account(&server, "Bob", 0).await;
+
+    assert_api_error(
+        client
+            .withdraw(
+                alice,
+                PositiveAmount::new(501).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        400,
+    );
+    assert_api_error(
+        client
+            .transfer(
+                alice,
+                bob,
+                PositiveAmount::new(501).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        400,
+    );
+    assert_api_error(
+        client
+            .transfer(
+                alice,
+                alice,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        400,
+    );
+    assert_api_error(
+        client
+            .deposit(
+                AccountId::new(),
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        404,
+    );
+    assert_api_error(
+        client
+            .list_transactions(AccountId::new(), &TransactionListQuery::default())
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_idempotent_replay_returns_original() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 0).await;
+
+    let first = client
+        .deposit(
+            alice,
+            PositiveAmount::new(700).unwrap(),
+            CurrencyCode::USD,
+            Some("dep-1".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    let replay = client
+        .deposit(
+            alice,
+            PositiveAmount::new(700).unwrap(),
+            CurrencyCode::USD,
+            Some("dep-1".into()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(first.id, replay.id);
+    assert_eq!(first.created_at, replay.created_at);
+    assert_eq!(
+        client.get_account(alice).await.unwrap().balance.amount(),
+        700
+    );
+
+    // Reusing the key for a different payment is refused.
+    assert_api_error(
+        client
+            .deposit(
+                alice,
+                PositiveAmount::new(900).unwrap(),
+                CurrencyCode::USD,
+                Some("dep-1".into()),
+                None,
+            )
+            .await,
+        409,
+    );
+    assert_api_error(
+        client
+            .withdraw(
+                alice,
+                PositiveAmount::new(700).unwrap(),
+                CurrencyCode::USD,
+                Some("dep-1".into()),
+                None,
+            )
+            .await,
+        409,
+    );
+}
+
+#[tokio::test]
+async fn test_counterparty_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 0).await;
+    let payer = Counterparty {
+        name: "ACME Payroll Ltd".into(),
+        external_id: Some("GB33BUKB20201555555555".into()),
+    };
+
+    let deposit = client
+        .send_deposit(&DepositRequest {
+            account_id: alice,
+            amount: PositiveAmount::new(5_000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: Some(payer.clone()),
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(deposit.counterparty.as_ref(), Some(&payer));
+
+    let withdrawal = client
+        .send_withdrawal(&WithdrawRequest {
+            account_id: alice,
+            amount: PositiveAmount::new(1_000).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            counterparty: None,
+            purpose_code: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(withdrawal.counterparty, None);
+
+    let history = client
+        .list_transactions(alice, &TransactionListQuery::default())
+        .await
+        .unwrap()
+        .data;
+    let stored = history.iter().find(|tx| tx.id == deposit.id).unwrap();
+    assert_eq!(stored.counterparty, Some(payer));
+}
+
+#[tokio::test]
+async fn test_payment_warnings_round_trip() {
+    let repo = InMemoryRepo::new();
+    let dormant = AccountBuilder::new()
+        .created_at(Utc::now() - Duration::days(400))
+        .build();
+    repo.insert_account(dormant.clone());
+    let server = spawn_test_server_with(repo).await;
+    let client = server.client();
+    let deposit = DepositRequest {
+        account_id: dormant.id,
+        amount: PositiveAmount::new(500).unwrap(),
+        currency: CurrencyCode::USD,
+        idempotency_key: None,
+        reference: None,
+        counterparty: None,
+        metadata: Default::default(),
+        encrypted_memo: None,
+    };
+
+    let first = client.send_deposit(&deposit).await.unwrap();
+    assert_eq!(first.value.amount.amount(), 500);
+    let codes: Vec<_> = first.warnings.iter().map(|w| w.code.as_str()).collect();
+    assert_eq!(codes, ["dormant_account"]);
+
+    // The deposit itself woke the account up.
+    let second = client.send_deposit(&deposit).await.unwrap();
+    assert!(second.warnings.is_empty());
+    assert_eq!(
+        client
+            .get_account(dormant.id)
+            .await
+            .unwrap()
+            .balance
+            .amount(),
+        1_000
+    );
+}
+
+#[tokio::test]
+async fn test_reactivate_dormant_account() {
+    let repo = InMemoryRepo::new();
+    let dormant = AccountBuilder::new()
+        .build()
+        .with_status(AccountStatus::Dormant, Some(Utc::now()));
+    repo.insert_account(dormant.clone());
+    let server = spawn_test_server_with(repo).await;
+    let client = server.client();
+
+    let fetched = client.get_account(dormant.id).await.unwrap();
+    assert_eq!(fetched.status, AccountStatus::Dormant);
+
+    let reactivated = client.reactivate_account(dormant.id).await.unwrap();
+    assert_eq!(reactivated.status, AccountStatus::Active);
+    assert_api_error(client.reactivate_account(dormant.id).await, 400);
+    assert_api_error(client.reactivate_account(AccountId::new()).await, 404);
+}
+
+#[tokio::test]
+async fn test_freeze_and_close_account() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+
+    let frozen = client.freeze_account(alice).await.unwrap();
+    assert_eq!(frozen.status, AccountStatus::Frozen);
+    assert_api_error(client.freeze_account(alice).await, 400);
+    assert_api_error(
+        client
+            .withdraw(
+                alice,
+                PositiveAmount::new(1000).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        422,
+    );
+    client
+        .deposit(
+            alice,
+            PositiveAmount::new(100).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let raw = account_key(&client, "alice-app", alice).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(alice_client.unfreeze_account(alice).await, 400);
+
+    let active = client.unfreeze_account(alice).await.unwrap();
+    assert_eq!(active.status, AccountStatus::Active);
+    assert_api_error(client.unfreeze_account(alice).await, 400);
+    assert_api_error(client.close_account(alice).await, 400);
+    client
+        .withdraw(
+            alice,
+            PositiveAmount::new(1100).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let closed = client.close_account(alice).await.unwrap();
+    assert_eq!(closed.status, AccountStatus::Closed);
+    assert_api_error(
+        client
+            .deposit(
+                alice,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        422,
+    );
+    assert_api_error(client.close_account(alice).await, 400);
+    assert_api_error(client.freeze_account(AccountId::new()).await, 404);
+}
+
+#[tokio::test]
+async fn test_merge_duplicate_account() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+    let duplicate = funded_account(&server, "Alice (duplicate)", 250).await;
+
+    let raw = account_key(&client, "alice-app", duplicate).await;
+    let scoped = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(scoped.merge_account(duplicate, alice).await, 400);
+    assert_api_error(client.merge_account(alice, alice).await, 400);
+    assert_api_error(client.merge_account(duplicate, AccountId::new()).await, 404);
+
+    let merge = client.merge_account(duplicate, alice).await.unwrap();
+    assert_eq!(merge.source.status, AccountStatus::Closed);
+    assert_eq!(merge.source.merged_into, Some(alice));
+    assert_eq!(merge.source.balance.amount(), 0);
+    assert_eq!(merge.target.balance.amount(), 1250);
+    assert_eq!(merge.transaction.unwrap().amount.amount(), 250);
+    let source = client.get_account(duplicate).await.unwrap();
+    assert_eq!(source.merged_into, Some(alice));
+
+    assert_api_error(client.merge_account(duplicate, alice).await, 422);
+}
+
+#[tokio::test]
+async fn test_overdraft_limit() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    assert_api_error(
+        client
+            .create_account_with_overdraft(OwnerId::DEFAULT, "Overdrawn", CurrencyCode::USD, -1)
+            .await,
+        400,
+    );
+    let account = client
+        .create_account_with_overdraft(OwnerId::DEFAULT, "Overdrawn", CurrencyCode::USD, 500)
+        .await
+        .unwrap();
+    assert_eq!(account.overdraft_limit, 500);
+
+    client
+        .withdraw(
+            account.id,
+            PositiveAmount::new(400).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .get_account(account.id)
+            .await
+            .unwrap()
+            .balance
+            .amount(),
+        -400
+    );
+    assert_api_error(
+        client
+            .withdraw(
+                account.id,
+                PositiveAmount::new(200).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        400,
+    );
+
+    let raw = account_key(&client, "overdrawn-app", account.id).await;
+    let scoped = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(scoped.set_overdraft_limit(account.id, 1000).await, 400);
+    assert_api_error(client.set_overdraft_limit(account.id, -1).await, 400);
+    assert_api_error(client.set_overdraft_limit(AccountId::new(), 0).await, 404);
+
+    let updated = client.set_overdraft_limit(account.id, 1000).await.unwrap();
+    assert_eq!(updated.overdraft_limit, 1000);
+    client
+        .withdraw(
+            account.id,
+            PositiveAmount::new(200).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_spending_rules_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let sub = funded_account(&server, "Sub", 1000).await;
+    let vendor = funded_account(&server, "Vendor", 0).await;
+
+    assert!(client.spending_rules(sub).await.unwrap().is_empty());
+    let rules = SpendingRules {
+        allowed: vec!["suppliers".into()],
+        denied: vec![],
+    };
+    let stored = client.set_spending_rules(sub, &rules).await.unwrap();
+    assert_eq!(stored.allowed, vec!["SUPPLIERS"]);
+    assert_eq!(client.spending_rules(sub).await.unwrap(), stored);
+
+    let mut req = TransferRequest {
+        from_account_id: sub,
+        to_account_id: vendor,
+        amount: PositiveAmount::new(100).unwrap(),
+        currency: CurrencyCode::USD,
+        idempotency_key: None,
+        reference: None,
+        purpose_code: Some("PAYROLL".into()),
+        convert_currency: false,
+        quote_id: None,
+        metadata: Default::default(),
+        encrypted_memo: None,
+    };
+    assert_api_error(client.send_transfer(&req).await, 422);
+
+    req.purpose_code = Some("SUPPLIERS".into());
+    let tx = client.send_transfer(&req).await.unwrap().value;
+    assert_eq!(tx.purpose_code.as_deref(), Some("SUPPLIERS"));
+
+    assert_api_error(
+        client
+            .set_spending_rules(AccountId::new(), &SpendingRules::default())
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_cross_currency_transfer_is_converted_on_request() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+    let bob = client
+        .create_account(OwnerId::DEFAULT, "Bob", CurrencyCode::EUR)
+        .await
+        .unwrap();
+
+    let mut req = TransferRequest {
+        from_account_id: alice,
+        to_account_id: bob.id,
+        amount: PositiveAmount::new(400).unwrap(),
+        currency: CurrencyCode::USD,
+        idempotency_key: None,
+        reference: None,
+        purpose_code: None,
+        convert_currency: false,
+        quote_id: None,
+        metadata: Default::default(),
+        encrypted_memo: None,
+    };
+    assert_api_error(client.send_transfer(&req).await, 400);
+
+    req.convert_currency = true;
+    let tx = client.send_transfer(&req).await.unwrap().value;
+    let conversion = tx.conversion.expect("transfer was converted");
+    let rate = CurrencyCode::USD.to_usd_rate() / CurrencyCode::EUR.to_usd_rate();
+    assert!((conversion.rate - rate).abs() < 1e-9);
+    assert_eq!(conversion.converted_amount.currency(), CurrencyCode::EUR);
+    assert_eq!(
+        conversion.converted_amount.amount(),
+        (400.0 * rate).round() as i64
+    );
+
+    let bob = client.get_account(bob.id).await.unwrap();
+    assert_eq!(bob.balance.amount(), conversion.converted_amount.amount());
+    let alice = client.get_account(alice).await.unwrap();
+    assert_eq!(alice.balance.amount(), 600);
+
+    let history = client
+        .list_transactions(bob.id, &TransactionListQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(history.data[0].conversion, Some(conversion));
+}
+
+#[tokio::test]
+async fn test_quoted_transfer_spends_the_quote() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+    let bob = client
+        .create_account(OwnerId::DEFAULT, "Bob", CurrencyCode::EUR)
+        .await
+        .unwrap();
+
+    let quote = client
+        .create_quote(&CreateQuoteRequest {
+            from_currency: CurrencyCode::USD,
+            to_currency: CurrencyCode::EUR,
+            amount: PositiveAmount::new(400).unwrap(),
+            expires_in_secs: Some(300),
+        })
+        .await
+        .unwrap();
+    assert_eq!(client.get_quote(quote.id).await.unwrap(), quote);
+
+    let req = TransferRequest {
+        from_account_id: alice,
+        to_account_id: bob.id,
+        amount: PositiveAmount::new(400).unwrap(),
+        currency: CurrencyCode::USD,
+        idempotency_key: Some("quoted-transfer".into()),
+        reference: None,
+        purpose_code: None,
+        convert_currency: false,
+        quote_id: Some(quote.id),
+        metadata: Default::default(),
+        encrypted_memo: None,
+    };
+    let tx = client.send_transfer(&req).await.unwrap().value;
+    let conversion = tx.conversion.expect("transfer was converted");
+    assert_eq!(conversion.rate, quote.rate);
+    assert_eq!(conversion.converted_amount.amount(), quote.converted_amount);
+    assert_eq!(
+        client.get_quote(quote.id).await.unwrap().transaction_id,
+        Some(tx.id)
+    );
+
+    // Replaying the transfer returns it again; a new one cannot reuse the quote.
+    let replayed = client.send_transfer(&req).await.unwrap().value;
+    assert_eq!(replayed.id, tx.id);
+    assert_api_error(
+        client
+            .send_transfer(&TransferRequest {
+                idempotency_key: None,
+                ..req
+            })
+            .await,
+        400,
+    );
+    let bob = client.get_account(bob.id).await.unwrap();
+    assert_eq!(bob.balance.amount(), quote.converted_amount);
+    assert_api_error(client.get_quote(QuoteId::new()).await, 404);
+}
+
+#[tokio::test]
+async fn test_errors_carry_machine_readable_codes() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 100).await;
+    let bob = client
+        .create_account(OwnerId::DEFAULT, "Bob", CurrencyCode::EUR)
+        .await
+        .unwrap();
+
+    let code = |err: ClientError| err.error_code();
+    let overdraft = client
+        .withdraw(
+            alice,
+            PositiveAmount::new(500).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(code(overdraft), Some(ErrorCode::InsufficientFunds));
+    let missing = client.get_account(AccountId::new()).await.unwrap_err();
+    assert_eq!(code(missing), Some(ErrorCode::AccountNotFound));
+    let req = TransferRequest {
+        from_account_id: alice,
+        to_account_id: bob.id,
+        amount: PositiveAmount::new(50).unwrap(),
+        currency: CurrencyCode::USD,
+        idempotency_key: None,
+        reference: None,
+        purpose_code: None,
+        convert_currency: false,
+        quote_id: None,
+        metadata: Default::default(),
+        encrypted_memo: None,
+    };
+    let cross = client.send_transfer(&req).await.unwrap_err();
+    assert_eq!(code(cross), Some(ErrorCode::CrossCurrencyTransfer));
+    let invalid = client.register_webhook("", vec![]).await.unwrap_err();
+    assert_eq!(code(invalid), Some(ErrorCode::InvalidRequest));
+    let unauthorized = PaymentsClient::new(&server.base_url)
+        .with_api_key("sk_not_a_key")
+        .list_accounts()
+        .await
+        .unwrap_err();
+    assert_eq!(code(unauthorized), Some(ErrorCode::Unauthorized));
+}
+
+#[tokio::test]
+async fn test_account_limits_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+
+    assert!(client.account_limits(alice).await.unwrap().is_empty());
+    let limits = AccountLimits {
+        max_transaction_amount: Some(400),
+        max_balance: None,
+        daily_debit_limit: Some(500),
+    };
+    assert_eq!(
+        client.set_account_limits(alice, &limits).await.unwrap(),
+        limits
+    );
+    assert_eq!(client.account_limits(alice).await.unwrap(), limits);
+
+    assert_api_error(
+        client
+            .withdraw(
+                alice,
+                PositiveAmount::new(401).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        422,
+    );
+    client
+        .withdraw(
+            alice,
+            PositiveAmount::new(400).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_api_error(
+        client
+            .withdraw(
+                alice,
+                PositiveAmount::new(101).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        422,
+    );
+
+    let invalid = AccountLimits {
+        max_balance: Some(0),
+        ..AccountLimits::default()
+    };
+    assert_api_error(client.set_account_limits(alice, &invalid).await, 400);
+    assert_api_error(
+        client
+            .set_account_limits(AccountId::new(), &AccountLimits::default())
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_account_alias_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1_000).await;
+    let bob = funded_account(&server, "Bob", 0).await;
+
+    let ops = client.add_account_alias(alice, "@Alice-Ops").await.unwrap();
+    assert_eq!(ops.alias.to_string(), "@alice-ops");
+    assert_eq!(ops.account_id, alice);
+    // Adding it again is a no-op; another account cannot take it.
+    assert_eq!(
+        client.add_account_alias(alice, "alice-ops").await.unwrap(),
+        ops
+    );
+    assert_api_error(client.add_account_alias(bob, "@alice-ops").await, 400);
+    assert_api_error(client.add_account_alias(bob, "@b").await, 400);
+    assert_api_error(
+        client.add_account_alias(AccountId::new(), "@ghost").await,
+        404,
+    );
+    client.add_account_alias(bob, "@bob").await.unwrap();
+
+    assert_eq!(client.get_account_alias(&ops.alias).await.unwrap(), ops);
+    assert_eq!(
+        client
+            .resolve_account(&AccountRef::Alias(ops.alias.clone()))
+            .await
+            .unwrap(),
+        alice
+    );
+    assert_eq!(
+        client.account_aliases(alice).await.unwrap(),
+        vec![ops.clone()]
+    );
+
+    let bob_alias = Alias::parse("@bob").unwrap();
+    let tx = client
+        .send_transfer(&TransferRequest {
+            from_account_id: AccountRef::Alias(ops.alias.clone()),
+            to_account_id: AccountRef::Alias(bob_alias.clone()),
+            amount: PositiveAmount::new(300).unwrap(),
+            currency: CurrencyCode::USD,
+            idempotency_key: None,
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(tx.source_account_id, Some(alice));
+    assert_eq!(tx.destination_account_id, Some(bob));
+    assert_eq!(client.get_account(bob).await.unwrap().balance.amount(), 300);
+
+    assert_api_error(client.remove_account_alias(bob, &ops.alias).await, 404);
+    client
+        .remove_account_alias(alice, &ops.alias)
+        .await
+        .unwrap();
+    assert_api_error(client.get_account_alias(&ops.alias).await, 404);
+    assert_api_error(
+        client
+            .send_withdrawal(&WithdrawRequest {
+                account_id: AccountRef::Alias(ops.alias.clone()),
+                amount: PositiveAmount::new(1).unwrap(),
+                currency: CurrencyCode::USD,
+                idempotency_key: None,
+                reference: None,
+                counterparty: None,
+                purpose_code: None,
+                metadata: Default::default(),
+                encrypted_memo: None,
+            })
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_fee_schedule_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let merchant = funded_account(&server, "Merchant", 0).await;
+
+    let tiers = vec![
+        FeeTier {
+            up_to: Some(10_000),
+            flat_fee: 25,
+            ..FeeTier::default()
+        },
+        FeeTier {
+            percentage_bps: 150,
+            max_fee: Some(2_500),
+            ..FeeTier::default()
+        },
+    ];
+    let schedule = client
+        .create_fee_schedule("Standard", tiers.clone())
+        .await
+        .unwrap();
+    assert_eq!(schedule.tiers, tiers);
+    assert_eq!(
+        client.get_fee_schedule(schedule.id).await.unwrap(),
+        schedule
+    );
+    assert_eq!(
+        client.list_fee_schedules().await.unwrap(),
+        vec![schedule.clone()]
+    );
+
+    let assignment = client
+        .assign_fee_schedule(merchant, schedule.id, None)
+        .await
+        .unwrap();
+    let fees = client.account_fees(merchant).await.unwrap();
+    assert_eq!(fees.current, Some(assignment.clone()));
+    assert_eq!(fees.assignments, vec![assignment]);
+
+    let quote = client
+        .quote_fee(merchant, PositiveAmount::new(20_000).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(quote.fee, 300);
+    assert_eq!(quote.fee_schedule_id, Some(schedule.id));
+
+    let last_year = Utc::now() - Duration::days(365);
+    assert_api_error(
+        client
+            .assign_fee_schedule(merchant, schedule.id, Some(last_year))
+            .await,
+        400,
+    );
+    assert_api_error(
+        client
+            .assign_fee_schedule(merchant, FeeScheduleId::new(), None)
+            .await,
+        404,
+    );
+    assert_api_error(client.create_fee_schedule("Empty", Vec::new()).await, 400);
+}
+
+#[tokio::test]
+async fn test_settlement_batch_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+    client
+        .withdraw(
+            alice,
+            PositiveAmount::new(300).unwrap(),
+            CurrencyCode::USD,
+            None,
+            Some("Payout".into()),
+        )
+        .await
+        .unwrap();
+
+    let batch = client.create_settlement_batch(None).await.unwrap();
+    assert_eq!(batch.status, SettlementBatchStatus::Pending);
+    assert_eq!(batch.payout_count, 1);
+    assert_eq!(client.get_settlement_batch(batch.id).await.unwrap(), batch);
+    assert_eq!(
+        client.list_settlement_batches().await.unwrap(),
+        vec![batch.clone()]
+    );
+    assert_api_error(client.create_settlement_batch(None).await, 400);
+
+    let csv = client
+        .export_settlement_batch(batch.id, SettlementExportFormat::Csv)
+        .await
+        .unwrap();
+    assert!(csv.lines().nth(1).unwrap().contains(",300,USD,"));
+    assert_api_error(
+        client
+            .export_settlement_batch(batch.id, SettlementExportFormat::Pain001)
+            .await,
+        400,
+    );
+
+    let submitted = client
+        .update_settlement_batch_status(batch.id, SettlementBatchStatus::Submitted)
+        .await
+        .unwrap();
+    assert_eq!(submitted.status, SettlementBatchStatus::Submitted);
+    assert_api_error(
+        client
+            .update_settlement_batch_status(batch.id, SettlementBatchStatus::Pending)
+            .await,
+        400,
+    );
+    assert_api_error(
+        client.get_settlement_batch(SettlementBatchId::new()).await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_journal_export() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    funded_account(&server, "Alice", 1000).await;
+
+    let today = Utc::now().date_naive();
+    let csv = client
+        .export_journal(JournalExportFormat::Quickbooks, today, today)
+        .await
+        .unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].contains(",1000,10.00,,"));
+    assert!(lines[2].contains(",2000,,10.00,"));
+
+    assert_api_error(
+        client
+            .export_journal(JournalExportFormat::Xero, today, today - Duration::days(1))
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_export_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+
+    let today = Utc::now().date_naive();
+    let request = ExportRequest::Journal {
+        format: JournalExportFormat::Quickbooks,
+        from: today,
+        to: today,
+    };
+    let export = client.create_export(&request).await.unwrap();
+    assert_eq!(export.request, request);
+    let download = loop {
+        let download = client.get_export(export.id).await.unwrap();
+        if download.export.status != ExportStatus::Pending {
+            break download;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    };
+    assert_eq!(download.export.status, ExportStatus::Ready);
+    let url = download.download_url.unwrap();
+    let csv = client.download_export(&url).await.unwrap();
+    assert_eq!(
+        csv,
+        client
+            .export_journal(JournalExportFormat::Quickbooks, today, today)
+            .await
+            .unwrap()
+    );
+
+    // The link is the credential: no API key needed, but tampering fails.
+    let anonymous = PaymentsClient::new(&server.base_url);
+    assert_eq!(anonymous.download_export(&url).await.unwrap(), csv);
+    assert_api_error(anonymous.download_export(&format!("{}0", url)).await, 400);
+
+    let raw = account_key(&client, "alice-app", alice).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(alice_client.create_export(&request).await, 400);
+    assert_api_error(alice_client.get_export(export.id).await, 400);
+    assert_api_error(client.get_export(ExportId::new()).await, 404);
+    assert_api_error(
+        client
+            .create_export(&ExportRequest::Journal {
+                format: JournalExportFormat::Xero,
+                from: today,
+                to: today - Duration::days(1),
+            })
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_batch_applies_atomically_or_per_item() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+    let bob = funded_account(&server, "Bob", 0).await;
+    client.add_account_alias(bob, "@bob-pay").await.unwrap();
+    let pay_bob = |amount, key: &str| {
+        BatchOperation::Transfer(TransferRequest {
+            from_account_id: AccountRef::Id(alice),
+            to_account_id: "@bob-pay".parse().unwrap(),
+            amount,
+            currency: CurrencyCode::USD,
+            idempotency_key: Some(key.to_string()),
+            reference: None,
+            purpose_code: None,
+            convert_currency: false,
+            quote_id: None,
+            metadata: Default::default(),
+            encrypted_memo: None,
+        })
+    };
+
+    let atomic = client
+        .batch(&BatchRequest {
+            mode: BatchMode::Atomic,
+            operations: vec![
+                pay_bob(PositiveAmount::new(600).unwrap(), "pay-1"),
+                pay_bob(PositiveAmount::new(600).unwrap(), "pay-2"),
+            ],
+        })
+        .await
+        .unwrap();
+    assert_eq!((atomic.succeeded, atomic.failed), (0, 1));
+    assert_eq!(atomic.results[0].status, BatchItemStatus::RolledBack);
+    assert_eq!(atomic.results[1].status, BatchItemStatus::Failed);
+    assert_eq!(atomic.results[1].error.as_ref().unwrap().code, 400);
+    assert_eq!(
+        client.get_account(alice).await.unwrap().balance.amount(),
+        1000
+    );
+
+    let best_effort = client
+        .batch(&BatchRequest {
+            mode: BatchMode::BestEffort,
+            operations: vec![
+                pay_bob(PositiveAmount::new(600).unwrap(), "pay-1"),
+                pay_bob(PositiveAmount::new(600).unwrap(), "pay-2"),
+            ],
+        })
+        .await
+        .unwrap();
+    assert_eq!((best_effort.succeeded, best_effort.failed), (1, 1));
+    let paid = best_effort.results[0].transaction.as_ref().unwrap();
+    assert_eq!(paid.destination_account_id, Some(bob));
+    assert_eq!(client.get_account(bob).await.unwrap().balance.amount(), 600);
+
+    let replayed = client
+        .batch(&BatchRequest {
+            mode: BatchMode::Atomic,
+            operations: vec![pay_bob(PositiveAmount::new(600).unwrap(), "pay-1")],
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        replayed.results[0].transaction.as_ref().unwrap().id,
+        paid.id
+    );
+    assert_eq!(client.get_account(bob).await.unwrap().balance.amount(), 600);
+
+    let raw = account_key(&client, "bob-app", bob).await;
+    let bob_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        bob_client
+            .batch(&BatchRequest {
+                mode: BatchMode::BestEffort,
+                operations: vec![pay_bob(PositiveAmount::new(1).unwrap(), "pay-3")],
+            })
+            .await,
+        400,
+    );
+    assert_api_error(
+        client
+            .batch(&BatchRequest::<AccountRef> {
+                mode: BatchMode::Atomic,
+                operations: Vec::new(),
+            })
+            .await,
+        400,
+    );
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Scheduled Payments
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_scheduled_payment_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1_000).await;
+    let bob = funded_account(&server, "Bob", 0).await;
+
+    let request = CreateScheduledPaymentRequest {
+        transaction_type: TransactionType::Transfer,
+        from_account_id: alice,
+        to_account_id: Some(bob),
+        amount: PositiveAmount::new(250).unwrap(),
+        currency: CurrencyCode::USD,
+        reference: Some("Rent".into()),
+        purpose_code: None,
+        schedule: PaymentSchedule::Cron {
+            expression: "0 9 1 * *".into(),
+            timezone: "Europe/Berlin".into(),
+        },
+        start_at: None,
+    };
+    let payment = client.create_scheduled_payment(&request).await.unwrap();
+    assert_eq!(payment.schedule, request.schedule);
+    assert_eq!(payment.status, ScheduledPaymentStatus::Active);
+    assert!(payment.next_run_at > Utc::now());
+    assert_eq!(
+        client.get_scheduled_payment(payment.id).await.unwrap(),
+        payment
+    );
+    assert_eq!(
+        client.list_scheduled_payments(Some(alice)).await.unwrap(),
+        vec![payment.clone()]
+    );
+    assert!(
+        client
+            .list_scheduled_payments(Some(bob))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let paused = client.pause_scheduled_payment(payment.id).await.unwrap();
+    assert_eq!(paused.status, ScheduledPaymentStatus::Paused);
+    let resumed = client.resume_scheduled_payment(payment.id).await.unwrap();
+    assert_eq!(resumed.status, ScheduledPaymentStatus::Active);
+    assert_eq!(resumed.next_run_at, payment.next_run_at);
+
+    // A key scoped to the payee cannot see or change the payer's schedule.
+    let raw = account_key(&client, "bob-app", bob).await;
+    let bob_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert!(
+        bob_client
+            .list_scheduled_payments(None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_api_error(bob_client.pause_scheduled_payment(payment.id).await, 400);
+    assert_api_error(
+        client
+            .get_scheduled_payment(ScheduledPaymentId::new())
+            .await,
+        404,
+    );
+    assert_api_error(
+        client
+            .create_scheduled_payment(&CreateScheduledPaymentRequest {
+                schedule: PaymentSchedule::Interval { every_seconds: 1 },
+                ..request
+            })
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_hold_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1_000).await;
+    let bob = funded_account(&server, "Bob", 0).await;
+
+    let request = AuthorizeRequest {
+        account_id: alice,
+        amount: PositiveAmount::new(700).unwrap(),
+        currency: CurrencyCode::USD,
+        reference: Some("Hotel".into()),
+        purpose_code: None,
+        expires_in_secs: Some(3600),
+    };
+    let hold = client.authorize(&request).await.unwrap();
+    assert_eq!(hold.status, HoldStatus::Authorized);
+    assert_eq!(client.get_hold(hold.id).await.unwrap(), hold);
+    assert_eq!(client.list_holds(alice).await.unwrap(), vec![hold.clone()]);
+
+    // The hold counts against what can be spent but stays in the balance.
+    let account = client.get_account(alice).await.unwrap();
+    assert_eq!((account.balance.amount(), account.held), (1_000, 700));
+    assert_api_error(
+        client
+            .withdraw(
+                alice,
+                PositiveAmount::new(400).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        400,
+    );
+
+    let captured = client
+        .capture_hold(hold.id, Some(PositiveAmount::new(500).unwrap()))
+        .await
+        .unwrap();
+    assert_eq!(captured.status, HoldStatus::Captured);
+    assert_eq!(captured.captured_amount, Some(500));
+    let account = client.get_account(alice).await.unwrap();
+    assert_eq!((account.balance.amount(), account.held), (500, 0));
+    assert_api_error(client.void_hold(hold.id).await, 400);
+
+    assert_api_error(client.authorize(&request).await, 400);
+    let second = client
+        .authorize(&AuthorizeRequest {
+            amount: PositiveAmount::new(200).unwrap(),
+            ..request.clone()
+        })
+        .await
+        .unwrap();
+    let voided = client.void_hold(second.id).await.unwrap();
+    assert_eq!(voided.status, HoldStatus::Voided);
+    assert_eq!(client.get_account(alice).await.unwrap().held, 0);
+
+    // A key scoped to another account cannot see or settle Alice's holds.
+    let raw = account_key(&client, "bob-app", bob).await;
+    let bob_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(bob_client.get_hold(hold.id).await, 400);
+    assert_api_error(bob_client.list_holds(alice).await, 400);
+    assert_api_error(bob_client.authorize(&request).await, 400);
+    assert_api_error(client.get_hold(HoldId::new()).await, 404);
+    assert_api_error(
+        client
+            .authorize(&AuthorizeRequest {
+                expires_in_secs: Some(0),
+                ..request
+            })
+            .await,
+        400,
+    );
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Reports
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_exposure_report() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+
+    let report = client
+        .exposure_report(&ExposureQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(report.base_currency, CurrencyCode::USD);
+    assert_eq!(report.total, 1000);
+    assert_eq!(report.currencies.len(), 1);
+    assert_eq!(report.currencies[0].account_count, 1);
+
+    let in_euros = client
+        .exposure_report(&ExposureQuery {
+            base: Some(CurrencyCode::EUR),
+            as_of: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(in_euros.base_currency, CurrencyCode::EUR);
+    assert_eq!(in_euros.currencies[0].balance, 1000);
+
+    // No rate snapshots have been recorded on this server.
+    let past = ExposureQuery {
+        base: None,
+        as_of: Some(Utc::now() - Duration::days(1)),
+    };
+    assert_api_error(client.exposure_report(&past).await, 404);
+
+    let raw = account_key(&client, "alice-app", alice).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        alice_client
+            .exposure_report(&ExposureQuery::default())
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_float_report() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+    client
+        .withdraw(
+            alice,
+            PositiveAmount::new(300).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let today = Utc::now().date_naive();
+    let query = FloatReportQuery {
+        from: Some(today - Duration::days(1)),
+        to: Some(today + Duration::days(1)),
+    };
+    let report = client.float_report(&query).await.unwrap();
+    assert_eq!(
+        report.positions,
+        vec![FloatPosition {
+            currency: CurrencyCode::USD,
+            customer_balance: 700,
+            float_balance: -700,
+        }]
+    );
+    let flows = &report.flows;
+    assert_eq!(flows.iter().map(|f| f.deposits).sum::<i64>(), 1000);
+    assert_eq!(flows.iter().map(|f| f.withdrawals).sum::<i64>(), 300);
+    assert_eq!(flows.iter().map(|f| f.net).sum::<i64>(), 700);
+
+    let reversed = FloatReportQuery {
+        from: Some(today),
+        to: Some(today - Duration::days(1)),
+    };
+    assert_api_error(client.float_report(&reversed).await, 400);
+
+    let raw = account_key(&client, "alice-app", alice).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        alice_client
+            .float_report(&FloatReportQuery::default())
+            .await,
+        400,
+    );
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Statements
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_statement_round_trip() {
+    let last_month = StatementPeriod::previous(Utc::now());
+    let repo = InMemoryRepo::new();
+    let account = AccountBuilder::new()
+        .balance(2_500)
+        .created_at(last_month.starts_at() - Duration::days(1))
+        .build();
+    repo.insert_account(account.clone());
+    repo.insert_transaction(
+        TransactionBuilder::deposit(account.id)
+            .amount(2_500)
+            .created_at(last_month.starts_at() + Duration::days(3))
+            .build(),
+    );
+    let server = spawn_test_server_with(repo).await;
+    let client = server.client();
+
+    let email = client
+        .set_statement_email(account.id, Some("alice@example.com"))
+        .await
+        .unwrap();
+    assert_eq!(email.email.as_deref(), Some("alice@example.com"));
+    assert_eq!(client.statement_email(account.id).await.unwrap(), email);
+    assert_api_error(
+        client.set_statement_email(account.id, Some("nope")).await,
+        400,
+    );
+
+    let issued = client.issue_statements(None).await.unwrap();
+    assert_eq!(issued.len(), 1);
+    assert_eq!(issued[0].closing_balance, 2_500);
+    assert!(client.issue_statements(None).await.unwrap().is_empty());
+    assert_api_error(client.issue_statements(Some("2026-13")).await, 400);
+    let this_month = StatementPeriod::containing(Utc::now().date_naive()).to_string();
+    assert_api_error(client.issue_statements(Some(&this_month)).await, 400);
+
+    assert_eq!(client.list_statements(account.id).await.unwrap(), issued);
+    let download = client.get_statement(issued[0].id).await.unwrap();
+    assert_eq!(download.statement, issued[0]);
+    let csv = client.download_statement(&download).await.unwrap();
+    assert!(csv.starts_with("date,transaction_id,type,"));
+    assert!(csv.contains(",DEPOSIT,,25.00,25.00,USD"));
+
+    // The link is the credential: no API key needed, but tampering fails.
+    let anonymous = PaymentsClient::new(&server.base_url);
+    assert_eq!(anonymous.download_statement(&download).await.unwrap(), csv);
+    let mut forged = download.clone();
+    forged.download_url = forged.download_url.replace("signature=", "signature=0");
+    assert_api_error(anonymous.download_statement(&forged).await, 400);
+}
+
+#[tokio::test]
+async fn test_account_statement_formats() {
+    let repo = InMemoryRepo::new();
+    let account = AccountBuilder::new().build();
+    repo.insert_account(account.clone());
+    let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+    let at = |d: u32| day(d).and_hms_opt(12, 0, 0).unwrap().and_utc();
+    for (d, amount) in [(1, 1_000), (5, 2_500), (20, 400)] {
+        repo.insert_transaction(
+            TransactionBuilder::deposit(account.id)
+                .amount(amount)
+                .created_at(at(d))
+                .build(),
+        );
+    }
+    let server = spawn_test_server_with(repo).await;
+    let client = server.client();
+
+    let statement = client
+        .account_statement(account.id, day(2), day(10))
+        .await
+        .unwrap();
+    assert_eq!(statement.opening_balance, 1_000);
+    assert_eq!(statement.closing_balance, 3_500);
+    assert_eq!(statement.lines.len(), 1);
+    assert_eq!(statement.lines[0].balance, 3_500);
+
+    let csv = client
+        .download_account_statement(account.id, day(2), day(10), StatementFormat::Csv)
+        .await
+        .unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[1], "2026-03-02,,OPENING BALANCE,,,10.00,USD");
+    assert!(rows[2].ends_with(",DEPOSIT,,25.00,35.00,USD"));
+    assert_eq!(rows[3], "2026-03-10,,CLOSING BALANCE,,,35.00,USD");
+
+    assert_api_error(
+        client.account_statement(account.id, day(10), day(2)).await,
+        400,
+    );
+    let other = funded_account(&server, "Bob", 0).await;
+    let raw = account_key(&client, "bob-app", other).await;
+    let bob_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        bob_client
+            .account_statement(account.id, day(2), day(10))
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_balance_history_is_backfilled() {
+    let repo = InMemoryRepo::new();
+    let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+    let at = |d: u32| day(d).and_hms_opt(12, 0, 0).unwrap().and_utc();
+    let account = AccountBuilder::new().created_at(at(1)).build();
+    repo.insert_account(account.clone());
+    for (d, amount) in [(1, 1_000), (4, 2_500)] {
+        repo.insert_transaction(
+            TransactionBuilder::deposit(account.id)
+                .amount(amount)
+                .created_at(at(d))
+                .build(),
+        );
+    }
+    let server = spawn_test_server_with(repo).await;
+    let client = server.client();
+
+    let empty = client
+        .balance_history(account.id, Some(day(1)), Some(day(5)))
+        .await
+        .unwrap();
+    assert!(empty.balances.is_empty());
+
+    assert!(client.record_balance_snapshots().await.unwrap() >= 5);
+    assert_eq!(client.record_balance_snapshots().await.unwrap(), 0);
+    let history = client
+        .balance_history(account.id, Some(day(2)), Some(day(5)))
+        .await
+        .unwrap();
+    assert_eq!(history.account_id, account.id);
+    let balances: Vec<_> = history
+        .balances
+        .iter()
+        .map(|b| (b.date, b.balance))
+        .collect();
+    assert_eq!(
+        balances,
+        [
+            (day(2), 1_000),
+            (day(3), 1_000),
+            (day(4), 3_500),
+            (day(5), 3_500)
+        ]
+    );
+
+    assert_api_error(
+        client
+            .balance_history(account.id, Some(day(5)), Some(day(2)))
+            .await,
+        400,
+    );
+    let other = funded_account(&server, "Bob", 0).await;
+    let raw = account_key(&client, "bob-app", other).await;
+    let bob_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        bob_client.balance_history(account.id, None, None).await,
+        400,
+    );
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Webhooks
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_webhook_round_trip() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+
+    let registered = client
+        .register_webhook(
+            "http://127.0.0.1:9/hook",
+            vec!["deposit.success".into(), "transfer.success".into()],
+        )
+        .await
+        .unwrap();
+    assert!(registered.is_active);
+    assert!(!registered.secret.is_empty());
+    assert_eq!(registered.events.len(), 2);
+
+    let listed = client.list_webhooks().await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, registered.id);
+    assert_eq!(listed[0].url, registered.url);
+    assert_eq!(listed[0].timeout_ms, 10_000);
+    assert_eq!(listed[0].connect_timeout_ms, 3_000);
+
+    assert_api_error(client.register_webhook("", vec![]).await, 400);
+}
+
+#[tokio::test]
+async fn test_registered_webhook_receives_the_next_payment() {
+    let repo = Arc::new(InMemoryRepo::new());
+    let server = spawn_test_server_with(repo.clone()).await;
+    let client = server.client();
+    let account = funded_account(&server, "Alice", 100).await;
+
+    // The payment above cached an empty endpoint list; registering drops it.
+    let registered = client
+        .register_webhook("http://127.0.0.1:9/hook", vec!["withdraw.success".into()])
+        .await
+        .unwrap();
+    client
+        .withdraw(
+            account,
+            PositiveAmount::new(40).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let events = repo.webhook_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].endpoint_id.to_string(), registered.id);
+}
+
+#[tokio::test]
+async fn test_captured_hold_queues_webhooks_for_the_worker() {
+    let repo = Arc::new(InMemoryRepo::new());
+    let server = spawn_test_server_with(repo.clone()).await;
+    let client = server.client();
+    let account = funded_account(&server, "Alice", 1_000).await;
+    client
+        .register_webhook("http://127.0.0.1:9/hook", vec!["*".into()])
+        .await
+        .unwrap();
+
+    let hold = client
+        .authorize(&AuthorizeRequest {
+            account_id: account,
+            amount: PositiveAmount::new(600).unwrap(),
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            expires_in_secs: None,
+        })
+        .await
+        .unwrap();
+    client.capture_hold(hold.id, None).await.unwrap();
+
+    // Stored with the capture and left for the worker; the service sends nothing.
+    let events = repo.webhook_events();
+    let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(
+        types,
+        vec!["hold.authorized", "withdraw.success", "hold.captured"]
+    );
+    assert!(events.iter().all(|e| e.status == WebhookStatus::Pending));
+}
+
+#[tokio::test]
+async fn test_webhook_subscriptions_match_wildcards() {
+    let repo = Arc::new(InMemoryRepo::new());
+    let server = spawn_test_server_with(repo.clone()).await;
+    let client = server.client();
+    let everything = client
+        .register_webhook("http://127.0.0.1:9/all", vec![])
+        .await
+        .unwrap();
+    let deposits = client
+        .register_webhook("http://127.0.0.1:9/deposits", vec!["deposit.*".into()])
+        .await
+        .unwrap();
+    client
+        .register_webhook("http://127.0.0.1:9/holds", vec!["hold.*".into()])
+        .await
+        .unwrap();
+    assert_api_error(
+        client
+            .register_webhook("http://127.0.0.1:9/bad", vec!["deposit*".into()])
+            .await,
+        400,
+    );
+
+    let account = funded_account(&server, "Alice", 100).await;
+    client
+        .withdraw(
+            account,
+            PositiveAmount::new(40).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let mut queued: Vec<_> = repo
+        .webhook_events()
+        .into_iter()
+        .map(|event| (event.endpoint_id.to_string(), event.event_type))
+        .collect();
+    queued.sort();
+    let mut expected = vec![
+        (everything.id.clone(), "account.created".to_string()),
+        (everything.id.clone(), "deposit.success".to_string()),
+        (everything.id.clone(), "withdraw.success".to_string()),
+        (deposits.id.clone(), "deposit.success".to_string()),
+    ];
+    expected.sort();
+    assert_eq!(queued, expected);
+}
+
+#[tokio::test]
+async fn test_webhook_timeouts_are_configurable() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let request = |timeout_ms, connect_timeout_ms| RegisterWebhookRequest {
+        url: "http://127.0.0.1:9/hook".into(),
+        events: vec!["deposit.success".into()],
+        timeout_ms,
+        connect_timeout_ms,
+        signature_algorithm: Default::default(),
+    };
+
+    let registered = client
+        .send_webhook_registration(&request(Some(2_000), Some(500)))
+        .await
+        .unwrap();
+    assert_eq!(registered.timeout_ms, 2_000);
+    assert_eq!(registered.connect_timeout_ms, 500);
+
+    // A short delivery timeout caps the default connect timeout
+    let short = client
+        .send_webhook_registration(&request(Some(1_000), None))
+        .await
+        .unwrap();
+    assert_eq!(short.connect_timeout_ms, 1_000);
+
+    let listed = client.list_webhooks().await.unwrap();
+    let listed = listed.iter().find(|w| w.id == registered.id).unwrap();
+    assert_eq!(listed.timeout_ms, 2_000);
+
+    for (timeout, connect) in [
+        (Some(0), None),
+        (Some(60_001), None),
+        (Some(1_000), Some(2_000)),
+    ] {
+        assert_api_error(
+            client
+                .send_webhook_registration(&request(timeout, connect))
+                .await,
+            400,
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_oversized_webhook_payload_is_fetched_by_id() {
+    let repo = Arc::new(InMemoryRepo::new());
+    let server = spawn_test_server_with(repo.clone()).await;
+    let client = server.client();
+    let account = funded_account(&server, "Alice", 0).await;
+    client
+        .register_webhook("http://127.0.0.1:9/hook", vec!["deposit.success".into()])
+        .await
+        .unwrap();
+
+    let reference = "r".repeat(MAX_WEBHOOK_PAYLOAD_BYTES);
+    client
+        .deposit(
+            account,
+            PositiveAmount::new(100).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    client
+        .deposit(
+            account,
+            PositiveAmount::new(100).unwrap(),
+            CurrencyCode::USD,
+            None,
+            Some(reference.clone()),
+        )
+        .await
+        .unwrap();
+    let events = repo.webhook_events();
+    assert_eq!(events.len(), 2);
+
+    let small = client
+        .webhook_event(&events[0].id.to_string())
+        .await
+        .unwrap();
+    assert!(!small.payload_truncated);
+    let large = client
+        .webhook_event(&events[1].id.to_string())
+        .await
+        .unwrap();
+    assert!(large.payload_truncated);
+    assert_eq!(large.event_type, "deposit.success");
+    assert_eq!(large.payload["reference"], reference.as_str());
+
+    let raw = account_key(&client, "alice-app", account).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        alice_client.webhook_event(&events[1].id.to_string()).await,
+        400,
+    );
+    assert_api_error(
+        client
+            .webhook_event(&uuid::Uuid::new_v4().to_string())
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_dead_lettered_webhooks_are_exported_and_retried() {
+    let repo = Arc::new(InMemoryRepo::new());
+    let server = spawn_test_server_with(repo.clone()).await;
+    let client = server.client();
+    let account = funded_account(&server, "Alice", 0).await;
+    let webhook = client
+        .register_webhook(
+            "http://127.0.0.1:9/hook",
+            vec!["deposit.success".into(), "withdraw.success".into()],
+        )
+        .await
+        .unwrap();
+    for amount in [100, 200] {
+        client
+            .deposit(
+                account,
+                PositiveAmount::new(amount).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    client
+        .withdraw(
+            account,
+            PositiveAmount::new(50).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    for event in repo.webhook_events() {
+        repo.dead_letter_webhook(event.id, "HTTP 503");
+    }
+
+    let deposits = DeadLetterQuery {
+        endpoint_id: Some(webhook.id.parse().unwrap()),
+        event_type: Some("deposit.success".into()),
+        failed_since: None,
+    };
+    let exported = client.dead_lettered_webhooks(&deposits).await.unwrap();
+    assert_eq!(exported.len(), 2);
+    assert!(exported.iter().all(|e| e.status == "DEAD_LETTERED"));
+    assert_eq!(exported[1].payload["amount"], 200);
+    assert_eq!(exported[1].last_error.as_deref(), Some("HTTP 503"));
+
+    let retried = client
+        .retry_dead_lettered_webhooks(&deposits)
+        .await
+        .unwrap();
+    assert_eq!(retried.requeued, 2);
+    assert!(
+        client
+            .dead_lettered_webhooks(&deposits)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let requeued = client
+        .webhook_event(&exported[0].id.to_string())
+        .await
+        .unwrap();
+    assert_eq!(requeued.status, "PENDING");
+    assert_eq!(requeued.attempts, 0);
+
+    let everything = DeadLetterQuery::default();
+    assert_eq!(
+        client
+            .retry_dead_lettered_webhooks(&everything)
+            .await
+            .unwrap()
+            .requeued,
+        1
+    );
+
+    let raw = account_key(&client, "alice-app", account).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(alice_client.dead_lettered_webhooks(&everything).await, 400);
+    assert_api_error(
+        alice_client.retry_dead_lettered_webhooks(&everything).await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_delivery_log_and_redelivery() {
+    let repo = Arc::new(InMemoryRepo::new());
+    let server = spawn_test_server_with(repo.clone()).await;
+    let client = server.client();
+    let account = funded_account(&server, "Alice", 0).await;
+    let webhook = client
+        .register_webhook("http://127.0.0.1:9/hook", vec!["deposit.success".into()])
+        .await
+        .unwrap();
+    for amount in [100, 200, 300] {
+        client
+            .deposit(
+                account,
+                PositiveAmount::new(amount).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    let failed = repo.webhook_events()[1].id;
+    repo.dead_letter_webhook(failed, "HTTP 503");
+
+    let log = client
+        .webhook_deliveries(&webhook.id, &WebhookDeliveriesQuery::default())
+        .await
+        .unwrap();
+    let sequences: Vec<_> = log.iter().map(|d| d.delivery_sequence).collect();
+    assert_eq!(sequences, vec![3, 2, 1]);
+    assert_eq!(log[1].id, failed);
+    assert_eq!(log[1].status, "DEAD_LETTERED");
+    assert_eq!(log[1].attempts, 1);
+    assert_eq!(log[1].last_error.as_deref(), Some("HTTP 503"));
+
+    let dead = WebhookDeliveriesQuery {
+        status: Some("dead_lettered".into()),
+        ..Default::default()
+    };
+    let listed = client.webhook_deliveries(&webhook.id, &dead).await.unwrap();
+    assert_eq!(listed, vec![log[1].clone()]);
+    let older = WebhookDeliveriesQuery {
+        before_sequence: Some(2),
+        limit: Some(5),
+        ..Default::default()
+    };
+    let listed = client
+        .webhook_deliveries(&webhook.id, &older)
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].delivery_sequence, 1);
+
+    let redelivered = client
+        .redeliver_webhook_event(&failed.to_string())
+        .await
+        .unwrap();
+    assert_eq!(redelivered.status, "PENDING");
+    assert_eq!(redelivered.attempts, 0);
+    assert_eq!(redelivered.last_error.as_deref(), Some("HTTP 503"));
+    // Now pending, so there is nothing to redeliver.
+    assert_api_error(
+        client.redeliver_webhook_event(&failed.to_string()).await,
+        400,
+    );
+    assert_api_error(
+        client
+            .redeliver_webhook_event(&uuid::Uuid::new_v4().to_string())
+            .await,
+        404,
+    );
+
+    let invalid = WebhookDeliveriesQuery {
+        status: Some("lost".into()),
+        ..Default::default()
+    };
+    assert_api_error(client.webhook_deliveries(&webhook.id, &invalid).await, 400);
+    assert_api_error(
+        client
+            .webhook_deliveries(
+                &uuid::Uuid::new_v4().to_string(),
+                &WebhookDeliveriesQuery::default(),
+            )
+            .await,
+        404,
+    );
+
+    let raw = account_key(&client, "alice-app", account).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        alice_client
+            .webhook_deliveries(&webhook.id, &WebhookDeliveriesQuery::default())
+            .await,
+        400,
+    );
+    assert_api_error(
+        alice_client
+            .redeliver_webhook_event(&failed.to_string())
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_events_catch_up_by_delivery_sequence() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let account = funded_account(&server, "Alice", 0).await;
+    let webhook = client
+        .register_webhook("http://127.0.0.1:9/hook", vec!["deposit.success".into()])
+        .await
+        .unwrap();
+    for _ in 0..3 {
+        client
+            .deposit(
+                account,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    let query = |after_sequence, limit| WebhookEventsQuery {
+        after_sequence,
+        limit,
+    };
+    let events = client
+        .webhook_events_after(&webhook.id, &query(1, None))
+        .await
+        .unwrap();
+    let sequences: Vec<_> = events.iter().map(|e| e.delivery_sequence).collect();
+    assert_eq!(sequences, vec![2, 3]);
+    assert!(events.iter().all(|e| e.event_type == "deposit.success"));
+    let first = client
+        .webhook_events_after(&webhook.id, &query(0, Some(1)))
+        .await
+        .unwrap();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].delivery_sequence, 1);
+    assert_eq!(
+        client
+            .webhook_event(&first[0].id.to_string())
+            .await
+            .unwrap()
+            .delivery_sequence,
+        1
+    );
+
+    let raw = account_key(&client, "alice-app", account).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        alice_client
+            .webhook_events_after(&webhook.id, &query(0, None))
+            .await,
+        400,
+    );
+    assert_api_error(
+        client
+            .webhook_events_after(&uuid::Uuid::new_v4().to_string(), &query(0, None))
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_event_catalog_lists_lifecycle_events() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+
+    let catalog = client.list_event_types().await.unwrap();
+    let names: Vec<_> = catalog.iter().map(|e| e.name.as_str()).collect();
+    assert!(names.contains(&"account.created"));
+    assert!(names.contains(&"api_key.created"));
+    assert!(names.contains(&"transaction.blocked"));
+
+    let deposit = catalog
+        .iter()
+        .find(|e| e.name == "deposit.success")
+        .unwrap();
+    let amount = deposit.payload.iter().find(|f| f.name == "amount").unwrap();
+    assert_eq!(amount.ty, "integer");
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Maintenance Mode
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_maintenance_mode_blocks_mutations_only() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+
+    let status = client
+        .set_maintenance(true, Some("db upgrade".into()))
+        .await
+        .unwrap();
+    assert!(status.enabled);
+    assert_eq!(status.reason.as_deref(), Some("db upgrade"));
+    assert!(client.maintenance_status().await.unwrap().enabled);
+
+    assert_api_error(
+        client
+            .deposit(
+                alice,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        503,
+    );
+    assert_api_error(
+        client
+            .create_account(OwnerId::DEFAULT, "Bob", CurrencyCode::USD)
+            .await,
+        503,
+    );
+    assert_eq!(
+        client.get_account(alice).await.unwrap().balance.amount(),
+        1000
+    );
+    assert_eq!(
+        client
+            .list_transactions(alice, &TransactionListQuery::default())
+            .await
+            .unwrap()
+            .data
+            .len(),
+        1
+    );
+
+    let status = client.set_maintenance(false, None).await.unwrap();
+    assert!(!status.enabled);
+    client
+        .deposit(
+            alice,
+            PositiveAmount::new(100).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_status_page_shows_health_and_incident() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let anonymous = PaymentsClient::new(&server.base_url);
+
+    let status = anonymous.service_status().await.unwrap();
+    assert_eq!(status.status, ServiceHealth::Operational);
+    assert_eq!(status.incident, None);
+
+    let status = client
+        .set_incident(Some("Deposits are delayed".into()))
+        .await
+        .unwrap();
+    assert_eq!(
+        status.incident.map(|incident| incident.message).as_deref(),
+        Some("Deposits are delayed")
+    );
+    let alice = funded_account(&server, "Alice", 0).await;
+    let raw = account_key(&client, "alice-app", alice).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(alice_client.set_incident(None).await, 400);
+    assert_api_error(anonymous.set_incident(None).await, 401);
+
+    // The banner can still be changed while the service is read-only.
+    client.set_maintenance(true, None).await.unwrap();
+    let status = anonymous.service_status().await.unwrap();
+    assert_eq!(status.status, ServiceHealth::Maintenance);
+    assert!(status.incident.is_some());
+    assert_eq!(client.set_incident(None).await.unwrap().incident, None);
+}
+
+#[tokio::test]
+async fn test_metrics_count_requests_and_transactions() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1000).await;
+    client
+        .withdraw(
+            alice,
+            PositiveAmount::new(250).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    client.get_account(alice).await.unwrap();
+
+    let metrics = client.metrics().await.unwrap();
+    assert!(metrics.contains(
+        "http_requests_total{method=\"GET\",route=\"/api/accounts/{id}\",status=\"200\"} 1\n"
+    ));
+    assert!(
+        metrics.contains("payments_transactions_total{type=\"WITHDRAWAL\",currency=\"USD\"} 1\n")
+    );
+    assert!(
+        metrics.contains(
+            "payments_transaction_volume_total{type=\"DEPOSIT\",currency=\"USD\"} 1000\n"
+        )
+    );
+    assert!(metrics.contains("# TYPE http_request_duration_seconds histogram\n"));
+    let repo_calls: u64 = metrics
+        .lines()
+        .find_map(|line| {
+            line.strip_prefix(
+                "http_request_repo_calls_total{method=\"GET\",route=\"/api/accounts/{id}\"} ",
+            )
+        })
+        .expect("repository calls counted for GET /api/accounts/{id}")
+        .parse()
+        .unwrap();
+    assert!(repo_calls > 0);
+
+    let raw = account_key(&client, "alice-app", alice).await;
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(alice_client.metrics().await, 400);
+    assert_api_error(PaymentsClient::new(&server.base_url).metrics().await, 401);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// API Keys & Auth
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_api_key_lifecycle() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+
+    let raw = client.create_api_key("ci").await.unwrap();
+    let keys = client.list_api_keys().await.unwrap();
+    assert_eq!(keys.len(), 2);
+    let ci = keys.iter().find(|k| k.name == "ci").unwrap();
+    assert!(ci.is_active);
+    assert!(chrono::DateTime::parse_from_rfc3339(&ci.created_at).is_ok());
+
+    let ci_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert!(ci_client.list_accounts().await.is_ok());
+
+    // Deleting only requests the change; the key works until another admin
+    // key approves it.
+    let change = client.delete_api_key(&ci.id).await.unwrap();
+    assert_eq!(change.status, ChangeStatus::Pending);
+    assert!(ci_client.list_accounts().await.is_ok());
+    assert_api_error(client.approve_change(change.id).await, 400);
+
+    let approved = ci_client.approve_change(change.id).await.unwrap();
+    assert_eq!(approved.status, ChangeStatus::Approved);
+    assert_eq!(approved.decided_by.unwrap().to_string(), ci.id);
+    assert_api_error(ci_client.list_accounts().await, 401);
+    assert_api_error(client.approve_change(change.id).await, 400);
+    assert_api_error(client.delete_api_key(&ci.id).await, 404);
+    assert_api_error(client.delete_api_key("not-a-uuid").await, 400);
+}
+
+#[tokio::test]
+async fn test_test_keys_only_see_test_accounts() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let live = client
+        .create_account(OwnerId::DEFAULT, "Live", CurrencyCode::USD)
+        .await
+        .unwrap();
+
+    // Without scopes, a test key can only read
+    let raw = client.create_test_api_key("reader", None).await.unwrap();
+    let reader = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_api_error(
+        reader
+            .create_account(OwnerId::DEFAULT, "Sandbox", CurrencyCode::USD)
+            .await,
+        403,
+    );
+
+    let raw = client
+        .create_test_api_key("sandbox", Some(&ApiKeyScope::ALL))
+        .await
+        .unwrap();
+    assert!(raw.starts_with("sk_test_"));
+    let sandbox = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    let test = sandbox
+        .create_account(OwnerId::DEFAULT, "Sandbox", CurrencyCode::USD)
+        .await
+        .unwrap();
+    assert_eq!(test.mode, Mode::Test);
+
+    let ids = |accounts: Vec<Account>| accounts.into_iter().map(|a| a.id).collect::<Vec<_>>();
+    assert_eq!(ids(sandbox.list_accounts().await.unwrap()), vec![test.id]);
+    assert!(!ids(client.list_accounts().await.unwrap()).contains(&test.id));
+    assert_api_error(sandbox.get_account(live.id).await, 404);
+    assert_api_error(client.get_account(test.id).await, 404);
+
+    let keys = sandbox.list_api_keys().await.unwrap();
+    assert_eq!(keys.len(), 2);
+    assert!(keys.iter().all(|key| key.mode == Mode::Test));
+}
+
+#[tokio::test]
+async fn test_webhook_url_change_needs_second_admin() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let second = PaymentsClient::new(&server.base_url)
+        .with_api_key(client.create_api_key("second").await.unwrap());
+    let webhook = client
+        .register_webhook("https://example.com/old", vec![])
+        .await
+        .unwrap();
+    let id = webhook.id;
+
+    let rejected = client
+        .update_webhook_url(&id, "https://attacker.example/hook")
+        .await
+        .unwrap();
+    // The requester may withdraw their own change.
+    let rejected = client.reject_change(rejected.id).await.unwrap();
+    assert_eq!(rejected.status, ChangeStatus::Rejected);
+    assert_api_error(second.approve_change(rejected.id).await, 400);
+    assert_eq!(
+        client.list_webhooks().await.unwrap()[0].url,
+        "https://example.com/old"
+    );
+
+    let change = client
+        .update_webhook_url(&id, "https://example.com/new")
+        .await
+        .unwrap();
+    assert_eq!(
+        change.action,
+        ChangeAction::SetWebhookUrl {
+            endpoint_id: id.parse().unwrap(),
+            url: "https://example.com/new".into(),
+        }
+    );
+    second.approve_change(change.id).await.unwrap();
+    assert_eq!(
+        client.list_webhooks().await.unwrap()[0].url,
+        "https://example.com/new"
+    );
+
+    // Every decision stays on record.
+    let history = client.list_changes(None).await.unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].id, change.id);
+    let pending = client.list_changes(Some(ChangeStatus::Pending)).await;
+    assert!(pending.unwrap().is_empty());
+    assert_eq!(
+        client.get_change(rejected.id).await.unwrap().decided_by,
+        rejected.decided_by
+    );
+
+    assert_api_error(client.update_webhook_url(&id, "").await, 400);
+    assert_api_error(
+        client
+            .update_webhook_url(&AccountId::new().to_string(), "https://example.com")
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_endpoint_update_rotate_and_delete() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let account = funded_account(&server, "Alice", 0).await;
+    let webhook = client
+        .register_webhook("http://127.0.0.1:9/hook", vec!["deposit.success".into()])
+        .await
+        .unwrap();
+    let id = webhook.id.clone();
+    let deposit_and_count_events = || async {
+        client
+            .deposit(
+                account,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let query = WebhookEventsQuery {
+            after_sequence: 0,
+            limit: None,
+        };
+        client
+            .webhook_events_after(&id, &query)
+            .await
+            .unwrap()
+            .len()
+    };
+
+    // A paused endpoint gets no events.
+    let paused = client.update_webhook(&id, None, Some(false)).await.unwrap();
+    assert!(!paused.is_active);
+    assert_eq!(paused.events, vec!["deposit.success"]);
+    assert_eq!(deposit_and_count_events().await, 0);
+
+    let resumed = client
+        .update_webhook(&id, Some(vec!["hold.*".into()]), Some(true))
+        .await
+        .unwrap();
+    assert!(resumed.is_active);
+    assert_eq!(resumed.events, vec!["hold.*"]);
+    assert_eq!(deposit_and_count_events().await, 0);
+    client
+        .update_webhook(&id, Some(vec![]), None)
+        .await
+        .unwrap();
+    assert_eq!(deposit_and_count_events().await, 1);
+
+    assert_api_error(client.update_webhook(&id, None, None).await, 400);
+    assert_api_error(
+        client
+            .update_webhook(&id, Some(vec!["hold*".into()]), None)
+            .await,
+        400,
+    );
+
+    let rotated = client.rotate_webhook_secret(&id).await.unwrap();
+    assert_ne!(rotated.secret, webhook.secret);
+    assert!(rotated.secret.starts_with("whsec_"));
+    assert_eq!(
+        client.list_webhooks().await.unwrap()[0].secret,
+        rotated.secret
+    );
+
+    client.delete_webhook(&id).await.unwrap();
+    assert!(client.list_webhooks().await.unwrap().is_empty());
+    assert_api_error(client.delete_webhook(&id).await, 404);
+    assert_api_error(client.rotate_webhook_secret(&id).await, 404);
+    assert_api_error(client.update_webhook(&id, None, Some(true)).await, 404);
+}
+
+#[tokio::test]
+async fn test_webhook_signature_algorithm_is_chosen_per_endpoint() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+
+    let webhook = client
+        .register_webhook("http://127.0.0.1:9/hook", vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        webhook.signature_algorithm,
+        WebhookSignatureAlgorithm::HmacSha256
+    );
+    assert_eq!(webhook.public_key, None);
+
+    let ed25519 = client
+        .send_webhook_registration(&RegisterWebhookRequest {
+            url: "http://127.0.0.1:9/signed".into(),
+            events: vec![],
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            signature_algorithm: WebhookSignatureAlgorithm::Ed25519,
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        ed25519.signature_algorithm,
+        WebhookSignatureAlgorithm::Ed25519
+    );
+    let public_key = ed25519.public_key.clone().unwrap();
+    assert_eq!(public_key.len(), 64);
+
+    // Switching applies at once and leaves the other settings alone
+    let switched = client
+        .send_webhook_update(
+            &webhook.id,
+            &UpdateWebhookRequest {
+                signature_algorithm: Some(WebhookSignatureAlgorithm::HmacSha512),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        switched.signature_algorithm,
+        WebhookSignatureAlgorithm::HmacSha512
+    );
+    assert!(switched.is_active);
+
+    // Rotating the secret rotates the signing key too
+    let rotated = client.rotate_webhook_secret(&ed25519.id).await.unwrap();
+    assert_ne!(rotated.public_key.unwrap(), public_key);
+
+    // A new url needs approval, so it cannot ride along
+    assert_api_error(
+        client
+            .send_webhook_update(
+                &webhook.id,
+                &UpdateWebhookRequest {
+                    url: Some("http://127.0.0.1:9/other".into()),
+                    signature_algorithm: Some(WebhookSignatureAlgorithm::Ed25519),
+                    ..Default::default()
+                },
+            )
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_batch_registers_all_or_nothing() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let endpoint = |url: &str, events: &[&str]| RegisterWebhookRequest {
+        url: url.into(),
+        events: events.iter().map(|e| e.to_string()).collect(),
+        timeout_ms: None,
+        connect_timeout_ms: None,
+        signature_algorithm: Default::default(),
+    };
+
+    let registered = client
+        .register_webhooks(vec![
+            endpoint("http://127.0.0.1:9/ledger", &["deposit.success"]),
+            RegisterWebhookRequest {
+                signature_algorithm: WebhookSignatureAlgorithm::Ed25519,
+                ..endpoint("http://127.0.0.1:9/holds", &["hold.*"])
+            },
+        ])
+        .await
+        .unwrap();
+    assert_eq!(registered.len(), 2);
+    assert_eq!(registered[0].url, "http://127.0.0.1:9/ledger");
+    assert_eq!(registered[0].events, vec!["deposit.success"]);
+    assert_eq!(registered[1].events, vec!["hold.*"]);
+    assert!(registered[1].public_key.is_some());
+    assert_ne!(registered[0].secret, registered[1].secret);
+    assert_eq!(client.list_webhooks().await.unwrap().len(), 2);
+
+    // One bad item registers nothing, including the good ones before it
+    let err = client
+        .register_webhooks(vec![
+            endpoint("http://127.0.0.1:9/fine", &[]),
+            endpoint("http://127.0.0.1:9/bad", &["hold*"]),
+        ])
+        .await
+        .unwrap_err();
+    match err {
+        ClientError::Api {
+            status, message, ..
+        } => {
+            assert_eq!(status, 400);
+            assert!(message.contains("endpoints[1]"), "{}", message);
+        }
+        other => panic!("expected API error 400, got {:?}", other),
+    }
+    assert_api_error(client.register_webhooks(vec![]).await, 400);
+    assert_eq!(client.list_webhooks().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_scoped_api_key_is_limited_to_its_account() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1_000).await;
+    let bob = funded_account(&server, "Bob", 1_000).await;
+
+    let raw = client
+        .create_scoped_api_key("alice-app", alice)
+        .await
+        .unwrap();
+    let keys = client.list_api_keys().await.unwrap();
+    let scoped = keys.iter().find(|k| k.name == "alice-app").unwrap();
+    assert_eq!(scoped.account_id, Some(alice));
+    // Created without scopes, it can only read.
+    assert_eq!(scoped.scopes, ApiKeyScope::READ);
+
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    assert_eq!(alice_client.get_account(alice).await.unwrap().id, alice);
+    let visible = alice_client.list_accounts().await.unwrap();
+    assert_eq!(visible.len(), 1);
+    assert_api_error(alice_client.get_account(bob).await, 400);
+    assert_api_error(
+        alice_client
+            .deposit(
+                alice,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        403,
+    );
+
+    // Even holding every scope, it stays limited to its account.
+    let raw = client
+        .create_api_key_with_scopes("alice-admin", Some(alice), &ApiKeyScope::ALL)
+        .await
+        .unwrap();
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+
+    // Scoped keys cannot manage keys, or they could mint an admin key.
+    assert_api_error(alice_client.create_api_key("escalate").await, 400);
+    assert_api_error(alice_client.list_api_keys().await, 400);
+    assert_api_error(alice_client.delete_api_key(&scoped.id).await, 400);
+
+    // Nor webhooks, which would hear about every account.
+    assert_api_error(
+        alice_client
+            .register_webhook("https://example.com/hook", vec![])
+            .await,
+        400,
+    );
+    assert_api_error(alice_client.register_webhooks(vec![]).await, 400);
+    assert_api_error(alice_client.list_webhooks().await, 400);
+
+    assert_api_error(
+        client
+            .create_scoped_api_key("ghost", AccountId::new())
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_api_key_scopes_are_enforced() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1_000).await;
+
+    let raw = client
+        .create_api_key_with_scopes(
+            "reporting",
+            None,
+            &[ApiKeyScope::AccountsRead, ApiKeyScope::TransactionsRead],
+        )
+        .await
+        .unwrap();
+    let keys = client.list_api_keys().await.unwrap();
+    let reporting = keys.iter().find(|k| k.name == "reporting").unwrap();
+    assert_eq!(
+        reporting.scopes,
+        vec![ApiKeyScope::AccountsRead, ApiKeyScope::TransactionsRead]
+    );
+    let admin = keys.iter().find(|k| k.name != "reporting").unwrap();
+    assert_eq!(admin.scopes, ApiKeyScope::ALL);
+
+    let reporting_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    reporting_client.get_account(alice).await.unwrap();
+    reporting_client
+        .list_transactions(alice, &TransactionListQuery::default())
+        .await
+        .unwrap();
+    assert_api_error(
+        reporting_client
+            .deposit(
+                alice,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        403,
+    );
+    assert_api_error(reporting_client.list_webhooks().await, 403);
+    assert_api_error(reporting_client.create_api_key("escalate").await, 403);
+    // Reading its own usage needs no scope.
+    reporting_client.api_key_usage(&reporting.id).await.unwrap();
+
+    // A key cannot grant scopes it does not hold.
+    let limited = client
+        .create_api_key_with_scopes("key-admin", None, &[ApiKeyScope::KeysAdmin])
+        .await
+        .unwrap();
+    let limited_client = PaymentsClient::new(&server.base_url).with_api_key(&limited);
+    assert_api_error(
+        limited_client
+            .create_api_key_with_scopes("escalate", None, &[ApiKeyScope::TransactionsWrite])
+            .await,
+        400,
+    );
+    assert_api_error(
+        client
+            .create_api_key_with_scopes("nothing", None, &[])
+            .await,
+        400,
+    );
+}
+
+#[tokio::test]
+async fn test_api_key_usage() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 0).await;
+
+    let raw = account_key(&client, "alice-app", alice).await;
+    let keys = client.list_api_keys().await.unwrap();
+    let scoped = keys.iter().find(|k| k.name == "alice-app").unwrap();
+    let admin = keys.iter().find(|k| k.name != "alice-app").unwrap();
+
+    let alice_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    alice_client
+        .deposit(
+            alice,
+            PositiveAmount::new(250).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    alice_client.get_account(alice).await.unwrap();
+
+    // A key can read its own usage; the request doing so is not yet counted.
+    let usage = alice_client.api_key_usage(&scoped.id).await.unwrap();
+    assert_eq!(usage.api_key_id.to_string(), scoped.id);
+    let hours: Vec<_> = usage.windows.iter().map(|w| w.hours).collect();
+    assert_eq!(hours, vec![1, 24, 720]);
+    let current = &usage.windows[0];
+    assert_eq!((current.requests, current.rate_limited), (2, 0));
+    assert_eq!(current.volume.len(), 1);
+    assert_eq!(current.volume[0].transaction_type, TransactionType::Deposit);
+    assert_eq!(
+        (current.volume[0].count, current.volume[0].amount),
+        (1, 250)
+    );
+
+    let seen_by_admin = client.api_key_usage(&scoped.id).await.unwrap();
+    assert_eq!(seen_by_admin.windows[2].requests, 3);
+
+    assert_api_error(alice_client.api_key_usage(&admin.id).await, 400);
+    assert_api_error(
+        client
+            .api_key_usage(&payments_types::ApiKeyId::new().to_string())
+            .await,
+        404,
+    );
+}
+
+#[tokio::test]
+async fn test_api_key_audit_trail() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let raw = client.create_api_key("ci").await.unwrap();
+    let keys = client.list_api_keys().await.unwrap();
+    let ci = keys.iter().find(|k| k.name == "ci").unwrap();
+    let admin = keys.iter().find(|k| k.name != "ci").unwrap();
+
+    let audit = client.api_key_audit(&ci.id).await.unwrap();
+    assert!(audit.is_active);
+    assert!(audit.last_used_at.is_none());
+    assert_eq!(audit.entries.len(), 1);
+    assert_eq!(audit.entries[0].action, ApiKeyAuditAction::Created);
+    assert_eq!(audit.entries[0].actor.unwrap().to_string(), admin.id);
+
+    // Uses within a minute of each other are recorded once.
+    let ci_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+    ci_client.list_accounts().await.unwrap();
+    ci_client.list_accounts().await.unwrap();
+    let audit = client.api_key_audit(&ci.id).await.unwrap();
+    assert!(audit.last_used_at.is_some());
+    let actions: Vec<_> = audit.entries.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![ApiKeyAuditAction::Created, ApiKeyAuditAction::Used]
+    );
+
+    // The deleted key stays on record with who approved its deletion.
+    let change = ci_client.delete_api_key(&admin.id).await.unwrap();
+    client.approve_change(change.id).await.unwrap();
+    assert_api_error(client.list_accounts().await, 401);
+    let audit = ci_client.api_key_audit(&admin.id).await.unwrap();
+    assert!(!audit.is_active);
+    assert!(audit.deactivated_at.is_some());
+    assert_eq!(audit.deactivated_by.unwrap().to_string(), admin.id);
+    let last = audit.entries.last().unwrap();
+    assert_eq!(last.action, ApiKeyAuditAction::Deactivated);
+    assert_eq!(last.actor, audit.deactivated_by);
+
+    assert_api_error(
+        ci_client
+            .api_key_audit(&payments_types::ApiKeyId::new().to_string())
+            .await,
+        404,
+    );
+    assert_api_error(ci_client.api_key_audit("not-a-uuid").await, 400);
+    let reader = PaymentsClient::new(&server.base_url).with_api_key(
+        ci_client
+            .create_api_key_with_scopes("reader", None, &[ApiKeyScope::AccountsRead])
+            .await
+            .unwrap(),
+    );
+    assert_api_error(reader.api_key_audit(&ci.id).await, 403);
+}
+
+#[tokio::test]
+async fn test_session_token_reads_one_account() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+    let alice = funded_account(&server, "Alice", 1_000).await;
+    let bob = funded_account(&server, "Bob", 0).await;
+
+    let raw = client.create_api_key("dashboard").await.unwrap();
+    let keys = client.list_api_keys().await.unwrap();
+    let dashboard = keys.iter().find(|k| k.name == "dashboard").unwrap();
+    let dashboard_client = PaymentsClient::new(&server.base_url).with_api_key(&raw);
+
+    let session = dashboard_client
+        .create_session_token(alice, Some(600))
+        .await
+        .unwrap();
+    assert!(session.token.starts_with("st_"));
+    assert_eq!(session.account_id, alice);
+    assert_eq!(
+        session.scopes,
+        vec![ApiKeyScope::AccountsRead, ApiKeyScope::TransactionsRead]
+    );
+    assert!(session.expires_at <= Utc::now() + Duration::seconds(600));
+
+    let session_client = PaymentsClient::new(&server.base_url).with_api_key(&session.token);
+    assert_eq!(
+        session_client
+            .get_account(alice)
+            .await
+            .unwrap()
+            .balance
+            .amount(),
+        1_000
+    );
+    session_client
+        .list_transactions(alice, &TransactionListQuery::default())
+        .await
+        .unwrap();
+    let listed = session_client.list_accounts().await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_api_error(session_client.get_account(bob).await, 400);
+    assert_api_error(
+        session_client
+            .deposit(
+                alice,
+                PositiveAmount::new(100).unwrap(),
+                CurrencyCode::USD,
+                None,
+                None,
+            )
+            .await,
+        403,
+    );
+    assert_api_error(session_client.api_key_usage(&dashboard.id).await, 403);
+    assert_api_error(session_client.create_session_token(alice, None).await, 400);
+
+    assert_api_error(
+        dashboard_client
+            .create_session_token(alice, Some(24 * 60 * 60))
+            .await,
+        400,
+    );
+    assert_api_error(
+        dashboard_client
+            .create_session_token(AccountId::new(), None)
+            .await,
+        404,
+    );
+
+    // Deleting the issuing key ends its tokens.
+    let change = client.delete_api_key(&dashboard.id).await.unwrap();
+    dashboard_client.approve_change(change.id).await.unwrap();
+    assert_api_error(session_client.get_account(alice).await, 401);
+    let tampered = PaymentsClient::new(&server.base_url).with_api_key("st_bogus");
+    assert_api_error(tampered.get_account(alice).await, 401);
+}
+
+#[tokio::test]
+async fn test_unauthenticated_requests_are_rejected() {
+    let server = spawn_test_server().await;
+    let bad_key = PaymentsClient::new(&server.base_url).with_api_key("sk_bogus");
+
+    assert_api_error(bad_key.list_accounts().await, 401);
+    assert_api_error(bad_key.get_account(AccountId::new()).await, 401);
+    assert_api_error(bad_key.list_webhooks().await, 401);
+    assert_api_error(bad_key.list_api_keys().await, 401);
+}
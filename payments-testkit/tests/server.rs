@@ -0,0 +1,62 @@
+//! End-to-end checks that the fake server is wired like the real one.
+
+use payments_client::ClientError;
+use payments_testkit::{AccountBuilder, InMemoryRepo, spawn_test_server, spawn_test_server_with};
+use payments_types::{CurrencyCode, OwnerId, PositiveAmount};
+
+#[tokio::test]
+async fn test_server_serves_authenticated_requests() {
+    let server = spawn_test_server().await;
+    let client = server.client();
+
+    assert!(client.health().await.unwrap());
+
+    let account = client
+        .create_account(OwnerId::DEFAULT, "Alice", CurrencyCode::USD)
+        .await
+        .unwrap();
+    client
+        .deposit(
+            account.id,
+            PositiveAmount::new(1500).unwrap(),
+            CurrencyCode::USD,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let account = client.get_account(account.id).await.unwrap();
+    assert_eq!(account.balance.amount(), 1500);
+}
+
+#[tokio::test]
+async fn test_server_rejects_missing_api_key() {
+    let server = spawn_test_server().await;
+    let anonymous = payments_client::PaymentsClient::new(&server.base_url);
+
+    let result = anonymous.list_accounts().await;
+    assert!(matches!(result, Err(ClientError::Api { status: 401, .. })));
+}
+
+#[tokio::test]
+async fn test_server_with_seeded_repo() {
+    let repo = InMemoryRepo::new();
+    let seeded = AccountBuilder::new().name("Seeded").balance(700).build();
+    repo.insert_account(seeded.clone());
+
+    let server = spawn_test_server_with(repo).await;
+    let account = server.client().get_account(seeded.id).await.unwrap();
+    assert_eq!(account.name, "Seeded");
+    assert_eq!(account.balance.amount(), 700);
+}
+
+#[tokio::test]
+async fn test_bootstrap_is_closed() {
+    // The testkit key already exists, so bootstrapping must be refused.
+    let server = spawn_test_server().await;
+    let result = payments_client::PaymentsClient::new(&server.base_url)
+        .bootstrap("second")
+        .await;
+    assert!(matches!(result, Err(ClientError::Api { status: 400, .. })));
+}
@@ -5,7 +5,10 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use super::mode::Mode;
 use super::money::{CurrencyCode, DynMoney};
+use super::owner::OwnerId;
+use super::transaction::Transaction;
 use crate::error::DomainError;
 
 /// Unique identifier for an Account.
@@ -33,6 +36,16 @@ impl AccountId {
     pub fn into_uuid(self) -> Uuid {
         self.0
     }
+
+    /// Normalizes a search query into a lowercase ID prefix, or returns `None`
+    /// if the query cannot be the start of a hyphenated UUID.
+    pub fn search_prefix(query: &str) -> Option<String> {
+        let prefix = query.trim().to_ascii_lowercase();
+        let valid = !prefix.is_empty()
+            && prefix.len() <= 36
+            && prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+        valid.then_some(prefix)
+    }
 }
 
 impl Default for AccountId {
@@ -55,6 +68,52 @@ impl std::str::FromStr for AccountId {
     }
 }
 
+/// Where an account is in its lifecycle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    /// Had no transactions for the configured dormancy period; stays dormant
+    /// until explicitly reactivated
+    Dormant,
+    /// Blocked by an admin: money may come in but not leave until unfrozen
+    Frozen,
+    /// Emptied and shut for good; no money moves in or out
+    Closed,
+}
+
+impl AsRef<str> for AccountStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Active => "ACTIVE",
+            Self::Dormant => "DORMANT",
+            Self::Frozen => "FROZEN",
+            Self::Closed => "CLOSED",
+        }
+    }
+}
+
+impl std::fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl std::str::FromStr for AccountStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "ACTIVE" => Ok(Self::Active),
+            "DORMANT" => Ok(Self::Dormant),
+            "FROZEN" => Ok(Self::Frozen),
+            "CLOSED" => Ok(Self::Closed),
+            _ => Err(format!("Unknown account status: {}", s)),
+        }
+    }
+}
+
 /// A financial account that can hold a balance.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
@@ -62,14 +121,41 @@ pub struct Account {
     pub id: AccountId,
     /// Human-readable account name
     pub name: String,
-    /// Current balance (includes currency information)
+    /// Current balance (includes currency information); negative while the
+    /// account is overdrawn
     pub balance: DynMoney,
+    /// Part of the balance reserved by open holds, in minor units
+    #[serde(default)]
+    pub held: i64,
     /// When the account was created
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub status: AccountStatus,
+    /// When `status` last changed; `None` if it never has
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_changed_at: Option<DateTime<Utc>>,
+    /// The account this one was merged into and closed in favour of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged_into: Option<AccountId>,
+    /// How far below zero withdrawals and transfers may take the balance, in
+    /// minor units
+    #[serde(default)]
+    pub overdraft_limit: i64,
+    /// The customer that holds the account
+    #[serde(default = "default_owner")]
+    pub owner_id: OwnerId,
+    /// Whether the account holds test or live money
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+fn default_owner() -> OwnerId {
+    OwnerId::DEFAULT
 }
 
 impl Account {
-    /// Creates a new account with zero balance.
+    /// Creates a new account with zero balance, held by the
+    /// [default owner](OwnerId::DEFAULT) until [`with_owner`](Self::with_owner).
     ///
     /// # Validation
     /// - Name cannot be empty
@@ -84,7 +170,14 @@ impl Account {
             id: AccountId::new(),
             name,
             balance: DynMoney::zero(currency),
+            held: 0,
             created_at: Utc::now(),
+            status: AccountStatus::Active,
+            status_changed_at: None,
+            merged_into: None,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: Mode::Live,
         })
     }
 
@@ -99,15 +192,77 @@ impl Account {
             id,
             name,
             balance,
+            held: 0,
             created_at,
+            status: AccountStatus::Active,
+            status_changed_at: None,
+            merged_into: None,
+            overdraft_limit: 0,
+            owner_id: OwnerId::DEFAULT,
+            mode: Mode::Live,
         }
     }
 
+    /// Sets the amount reserved by open holds.
+    pub fn with_held(mut self, held: i64) -> Self {
+        self.held = held;
+        self
+    }
+
+    /// Sets the status and when it last changed.
+    pub fn with_status(mut self, status: AccountStatus, changed_at: Option<DateTime<Utc>>) -> Self {
+        self.status = status;
+        self.status_changed_at = changed_at;
+        self
+    }
+
+    /// Sets the account this one was merged into.
+    pub fn with_merged_into(mut self, merged_into: Option<AccountId>) -> Self {
+        self.merged_into = merged_into;
+        self
+    }
+
+    /// Sets how far below zero the balance may go.
+    pub fn with_overdraft_limit(mut self, overdraft_limit: i64) -> Self {
+        self.overdraft_limit = overdraft_limit;
+        self
+    }
+
+    /// Sets the customer that holds the account.
+    pub fn with_owner(mut self, owner_id: OwnerId) -> Self {
+        self.owner_id = owner_id;
+        self
+    }
+
+    /// Puts the account in `mode`.
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
     /// Returns the account's currency.
     pub fn currency(&self) -> CurrencyCode {
         self.balance.currency()
     }
 
+    /// Balance not reserved by holds plus the overdraft limit, which
+    /// withdrawals and transfers may spend.
+    pub fn available(&self) -> i64 {
+        self.balance.amount() - self.held + self.overdraft_limit
+    }
+
+    /// Returns `true` if the name contains `query` (case-insensitive) or the
+    /// ID starts with it. Repository adapters implement the same rule in SQL.
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.trim();
+        if query.is_empty() {
+            return false;
+        }
+        self.name.to_lowercase().contains(&query.to_lowercase())
+            || AccountId::search_prefix(query)
+                .is_some_and(|prefix| self.id.to_string().starts_with(&prefix))
+    }
+
     /// Deposits money into the account.
     ///
     /// # Validation
@@ -122,17 +277,89 @@ impl Account {
     ///
     /// # Validation
     /// - Currency must match
-    /// - Sufficient funds required
+    /// - Sufficient available funds required: unheld balance plus the
+    ///   overdraft limit
     pub fn withdraw(&mut self, amount: DynMoney) -> Result<(), DomainError> {
-        self.balance = self.balance.checked_sub(amount)?;
+        self.check_available(amount)?;
+        self.balance = DynMoney::balance(self.balance.amount() - amount.amount(), self.currency());
         Ok(())
     }
+
+    /// Reserves `amount` for a hold without moving it.
+    ///
+    /// # Validation
+    /// - Currency must match
+    /// - Sufficient available funds required: unheld balance plus the
+    ///   overdraft limit
+    pub fn place_hold(&mut self, amount: DynMoney) -> Result<(), DomainError> {
+        self.check_available(amount)?;
+        self.held += amount.amount();
+        Ok(())
+    }
+
+    /// Releases `amount` previously reserved by [`Account::place_hold`].
+    pub fn release_hold(&mut self, amount: i64) {
+        self.held = (self.held - amount).max(0);
+    }
+
+    fn check_available(&self, amount: DynMoney) -> Result<(), DomainError> {
+        if amount.currency() != self.currency() {
+            return Err(DomainError::CurrencyMismatch {
+                expected: self.currency(),
+                got: amount.currency(),
+            });
+        }
+        if amount.amount() > self.available() {
+            return Err(DomainError::InsufficientFunds {
+                available: self.available(),
+                requested: amount.amount(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// An account merged into another: its balance moved over by `transaction`
+/// (none if it was empty), then the account closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountMerge {
+    /// The merged account, now closed and linked to `target`
+    pub source: Account,
+    /// The account it was merged into, holding the moved balance
+    pub target: Account,
+    pub transaction: Option<Transaction>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_matches_search() {
+        let account = Account::new("Alice Smith".into(), CurrencyCode::USD).unwrap();
+        let id = account.id.to_string();
+
+        assert!(account.matches_search("smith"));
+        assert!(account.matches_search("ALICE"));
+        assert!(account.matches_search(&id[..8].to_uppercase()));
+        assert!(!account.matches_search("bob"));
+        assert!(!account.matches_search("  "));
+    }
+
+    #[test]
+    fn test_status_round_trips_through_its_name() {
+        for status in [
+            AccountStatus::Active,
+            AccountStatus::Dormant,
+            AccountStatus::Frozen,
+            AccountStatus::Closed,
+        ] {
+            assert_eq!(status.as_ref().parse::<AccountStatus>(), Ok(status));
+        }
+        assert_eq!("frozen".parse::<AccountStatus>(), Ok(AccountStatus::Frozen));
+        assert!("deleted".parse::<AccountStatus>().is_err());
+    }
+
     #[test]
     fn test_account_creation() {
         let account = Account::new("Test Account".into(), CurrencyCode::USD).unwrap();
@@ -176,6 +403,55 @@ mod tests {
         assert!(matches!(result, Err(DomainError::InsufficientFunds { .. })));
     }
 
+    #[test]
+    fn test_withdraw_into_overdraft() {
+        let mut account = Account::new("Test".into(), CurrencyCode::USD)
+            .unwrap()
+            .with_overdraft_limit(100);
+        account
+            .deposit(DynMoney::new(50, CurrencyCode::USD).unwrap())
+            .unwrap();
+
+        account
+            .withdraw(DynMoney::new(150, CurrencyCode::USD).unwrap())
+            .unwrap();
+        assert_eq!(account.balance.amount(), -100);
+        assert_eq!(account.available(), 0);
+
+        let result = account.withdraw(DynMoney::new(1, CurrencyCode::USD).unwrap());
+        assert!(matches!(result, Err(DomainError::InsufficientFunds { .. })));
+    }
+
+    #[test]
+    fn test_holds_reduce_available_balance() {
+        let mut account = Account::new("Test".into(), CurrencyCode::USD).unwrap();
+        account
+            .deposit(DynMoney::new(100, CurrencyCode::USD).unwrap())
+            .unwrap();
+        account
+            .place_hold(DynMoney::new(70, CurrencyCode::USD).unwrap())
+            .unwrap();
+        assert_eq!(account.balance.amount(), 100);
+        assert_eq!(account.available(), 30);
+
+        let result = account.withdraw(DynMoney::new(40, CurrencyCode::USD).unwrap());
+        assert!(matches!(
+            result,
+            Err(DomainError::InsufficientFunds {
+                available: 30,
+                requested: 40
+            })
+        ));
+        let result = account.place_hold(DynMoney::new(40, CurrencyCode::USD).unwrap());
+        assert!(matches!(result, Err(DomainError::InsufficientFunds { .. })));
+
+        account.release_hold(70);
+        account
+            .withdraw(DynMoney::new(40, CurrencyCode::USD).unwrap())
+            .unwrap();
+        assert_eq!(account.available(), 60);
+    }
+
     #[test]
     fn test_currency_mismatch() {
         let mut account = Account::new("Test".into(), CurrencyCode::USD).unwrap();
@@ -0,0 +1,160 @@
+//! Who is making a request.
+
+use super::{AccountId, ApiKey, ApiKeyId, ApiKeyScope, Mode, OwnerId};
+use crate::error::AppError;
+
+/// The identity a request is made with: the API key it authenticated with,
+/// the account or owner that key is limited to and what it may do.
+///
+/// Inbound adapters build one per request and pass it to the service, which
+/// applies access rules itself so HTTP and gRPC enforce the same ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActorContext {
+    pub api_key_id: ApiKeyId,
+    /// The key's name, for logs
+    pub name: String,
+    /// The only account the key may act on; `None` for admin and
+    /// owner-bound keys
+    pub account_id: Option<AccountId>,
+    /// The owner whose accounts the key may act on; `None` for admin and
+    /// account-bound keys
+    pub owner_id: Option<OwnerId>,
+    /// The owner's accounts when the request was authenticated, so access
+    /// checks need no lookups
+    pub owned_accounts: Vec<AccountId>,
+    pub scopes: Vec<ApiKeyScope>,
+    /// Whether the key acts on test or live accounts
+    pub mode: Mode,
+}
+
+impl ActorContext {
+    /// Whether the key is an admin key, limited to neither an account nor
+    /// an owner.
+    pub fn is_admin(&self) -> bool {
+        self.account_id.is_none() && self.owner_id.is_none()
+    }
+
+    /// Whether the key may act on `target`.
+    pub fn may_access(&self, target: AccountId) -> bool {
+        match (self.account_id, self.owner_id) {
+            (Some(allowed), _) => allowed == target,
+            (None, Some(_)) => self.owned_accounts.contains(&target),
+            (None, None) => true,
+        }
+    }
+
+    /// The accounts the key is limited to; `None` for admin keys.
+    pub fn visible_accounts(&self) -> Option<Vec<AccountId>> {
+        match (self.account_id, self.owner_id) {
+            (Some(account_id), _) => Some(vec![account_id]),
+            (None, Some(_)) => Some(self.owned_accounts.clone()),
+            (None, None) => None,
+        }
+    }
+
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// Refuses keys limited to an account or an owner.
+    pub fn ensure_admin(&self) -> Result<(), AppError> {
+        if self.is_admin() {
+            Ok(())
+        } else {
+            Err(AppError::AccessDenied("admin API key required".into()))
+        }
+    }
+
+    /// Refuses keys limited to an account other than `target`, or to an
+    /// owner that does not hold it.
+    pub fn ensure_access(&self, target: AccountId) -> Result<(), AppError> {
+        if self.may_access(target) {
+            Ok(())
+        } else {
+            Err(AppError::AccessDenied(
+                "API key not authorized for this account".into(),
+            ))
+        }
+    }
+
+    /// Refuses keys bound to an account, or to an owner other than
+    /// `owner_id`.
+    pub fn ensure_owner(&self, owner_id: OwnerId) -> Result<(), AppError> {
+        match (self.account_id, self.owner_id) {
+            (None, None) => Ok(()),
+            (None, Some(own)) if own == owner_id => Ok(()),
+            _ => Err(AppError::AccessDenied(
+                "API key not authorized for this owner".into(),
+            )),
+        }
+    }
+
+    /// Sets the accounts of the owner the key is bound to.
+    pub fn with_owned_accounts(mut self, accounts: Vec<AccountId>) -> Self {
+        self.owned_accounts = accounts;
+        self
+    }
+}
+
+impl From<&ApiKey> for ActorContext {
+    fn from(key: &ApiKey) -> Self {
+        Self {
+            api_key_id: key.id,
+            name: key.name.clone(),
+            account_id: key.account_id,
+            owner_id: key.owner_id,
+            owned_accounts: Vec::new(),
+            scopes: key.scopes.clone(),
+            mode: key.mode,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_keys_may_only_act_on_their_account() {
+        let own = AccountId::new();
+        let admin = ActorContext::from(&ApiKey::new("admin".into(), "hash".into(), None));
+        let scoped = ActorContext::from(&ApiKey::new("scoped".into(), "hash".into(), Some(own)));
+
+        assert!(admin.ensure_admin().is_ok());
+        assert!(admin.ensure_access(own).is_ok());
+
+        assert!(matches!(
+            scoped.ensure_admin(),
+            Err(AppError::AccessDenied(_))
+        ));
+        assert!(scoped.ensure_access(own).is_ok());
+        assert!(matches!(
+            scoped.ensure_access(AccountId::new()),
+            Err(AppError::AccessDenied(_))
+        ));
+    }
+
+    #[test]
+    fn test_owner_keys_may_act_on_the_owners_accounts() {
+        let (own, other) = (AccountId::new(), AccountId::new());
+        let key = ApiKey::new("owner".into(), "hash".into(), None).with_owner(OwnerId::new());
+        let actor = ActorContext::from(&key).with_owned_accounts(vec![own]);
+
+        assert!(!actor.is_admin());
+        assert!(matches!(
+            actor.ensure_admin(),
+            Err(AppError::AccessDenied(_))
+        ));
+        assert!(actor.ensure_access(own).is_ok());
+        assert!(matches!(
+            actor.ensure_access(other),
+            Err(AppError::AccessDenied(_))
+        ));
+        assert_eq!(actor.visible_accounts(), Some(vec![own]));
+        assert!(actor.ensure_owner(key.owner_id.unwrap()).is_ok());
+        assert!(matches!(
+            actor.ensure_owner(OwnerId::new()),
+            Err(AppError::AccessDenied(_))
+        ));
+    }
+}
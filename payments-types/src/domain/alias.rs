@@ -0,0 +1,219 @@
+//! Account aliases: human-friendly names for accounts.
+//!
+//! An alias such as `@alice-ops` can be used wherever an account ID is
+//! accepted. Aliases are unique across all accounts, compared without regard
+//! to case, and an account may have several of them. Removing an alias
+//! frees it for any account to claim.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use utoipa::openapi::{ObjectBuilder, RefOr, Schema, Type};
+
+use super::AccountId;
+use crate::error::DomainError;
+
+const MIN_ALIAS_LEN: usize = 3;
+const MAX_ALIAS_LEN: usize = 32;
+/// Most aliases a single account may hold.
+pub const MAX_ALIASES_PER_ACCOUNT: usize = 5;
+
+/// A validated alias, displayed and serialized with its leading `@`.
+///
+/// Aliases are 3-32 lower-case letters, digits and single hyphens, start
+/// with a letter and do not end with a hyphen. Parsing lower-cases the input
+/// and accepts it with or without the `@`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+#[schema(value_type = String, example = "@alice-ops")]
+pub struct Alias(String);
+
+impl Alias {
+    /// Validates and normalizes `value`.
+    pub fn parse(value: &str) -> Result<Self, DomainError> {
+        let trimmed = value.trim();
+        let name = trimmed.strip_prefix('@').unwrap_or(trimmed);
+        let name = name.to_ascii_lowercase();
+        let valid = (MIN_ALIAS_LEN..=MAX_ALIAS_LEN).contains(&name.len())
+            && name.starts_with(|c: char| c.is_ascii_lowercase())
+            && !name.ends_with('-')
+            && !name.contains("--")
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid {
+            return Err(DomainError::ValidationError(format!(
+                "Invalid alias '{}': use {}-{} letters, digits or single hyphens, starting with a letter",
+                trimmed, MIN_ALIAS_LEN, MAX_ALIAS_LEN
+            )));
+        }
+        Ok(Self(name))
+    }
+
+    /// The alias without its `@`, as stored.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Alias {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{}", self.0)
+    }
+}
+
+impl std::str::FromStr for Alias {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Alias {
+    type Error = DomainError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Alias> for String {
+    fn from(alias: Alias) -> Self {
+        alias.to_string()
+    }
+}
+
+/// An alias registered to an account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AccountAlias {
+    pub alias: Alias,
+    pub account_id: AccountId,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An account given by ID or by alias.
+///
+/// Written as a UUID or as an alias with its leading `@`, so the two can
+/// never be confused.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum AccountRef {
+    Id(AccountId),
+    Alias(Alias),
+}
+
+impl From<AccountId> for AccountRef {
+    fn from(id: AccountId) -> Self {
+        Self::Id(id)
+    }
+}
+
+impl From<Alias> for AccountRef {
+    fn from(alias: Alias) -> Self {
+        Self::Alias(alias)
+    }
+}
+
+impl std::fmt::Display for AccountRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => id.fmt(f),
+            Self::Alias(alias) => alias.fmt(f),
+        }
+    }
+}
+
+impl std::str::FromStr for AccountRef {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with('@') {
+            return Alias::parse(s).map(Self::Alias);
+        }
+        s.parse()
+            .map(Self::Id)
+            .map_err(|_| DomainError::ValidationError(format!("Invalid account ID: {}", s)))
+    }
+}
+
+impl TryFrom<String> for AccountRef {
+    type Error = DomainError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<AccountRef> for String {
+    fn from(account: AccountRef) -> Self {
+        account.to_string()
+    }
+}
+
+impl utoipa::PartialSchema for AccountRef {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .description(Some("Account ID (UUID) or alias such as `@alice-ops`"))
+            .examples([serde_json::json!("@alice-ops")])
+            .into()
+    }
+}
+
+impl ToSchema for AccountRef {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alias_is_normalized() {
+        let alias = Alias::parse(" @Alice-Ops ").unwrap();
+        assert_eq!(alias.as_str(), "alice-ops");
+        assert_eq!(alias.to_string(), "@alice-ops");
+        assert_eq!(Alias::parse("alice-ops").unwrap(), alias);
+        assert_eq!(
+            serde_json::to_string(&alias).unwrap(),
+            "\"@alice-ops\"".to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_aliases_are_rejected() {
+        for value in [
+            "@ab",
+            "@1password",
+            "@alice-",
+            "@alice--ops",
+            "@alice_ops",
+            "@alice ops",
+            "@",
+            &format!("@{}", "a".repeat(33)),
+        ] {
+            assert!(
+                matches!(Alias::parse(value), Err(DomainError::ValidationError(_))),
+                "{value} should be rejected"
+            );
+        }
+        assert!(serde_json::from_str::<Alias>("\"@no\"").is_err());
+    }
+
+    #[test]
+    fn test_account_ref_parses_ids_and_aliases() {
+        let id = AccountId::new();
+        assert_eq!(
+            id.to_string().parse::<AccountRef>().unwrap(),
+            AccountRef::Id(id)
+        );
+        let alias: AccountRef = "@Treasury".parse().unwrap();
+        assert_eq!(alias, AccountRef::Alias(Alias::parse("treasury").unwrap()));
+        assert_eq!(alias.to_string(), "@treasury");
+        // Without the `@` a name is never taken for an alias.
+        assert!("treasury".parse::<AccountRef>().is_err());
+
+        let json = serde_json::to_string(&AccountRef::Id(id)).unwrap();
+        assert_eq!(serde_json::from_str::<AccountId>(&json).unwrap(), id);
+    }
+}
@@ -2,9 +2,11 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::AccountId;
+use super::{AccountId, Mode, OwnerId};
+use crate::error::DomainError;
 
 /// Unique identifier for an API key.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -48,29 +50,316 @@ impl std::str::FromStr for ApiKeyId {
     }
 }
 
+/// A permission granted to an API key.
+///
+/// Each route requires at most one scope; a key without it is refused with
+/// `403`. Read scopes cover `GET` requests and write scopes everything else.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, ToSchema,
+)]
+pub enum ApiKeyScope {
+    #[serde(rename = "accounts:read")]
+    AccountsRead,
+    #[serde(rename = "accounts:write")]
+    AccountsWrite,
+    #[serde(rename = "transactions:read")]
+    TransactionsRead,
+    #[serde(rename = "transactions:write")]
+    TransactionsWrite,
+    #[serde(rename = "webhooks:read")]
+    WebhooksRead,
+    #[serde(rename = "webhooks:write")]
+    WebhooksWrite,
+    /// Record payments received over external rails; held by integration
+    /// keys.
+    #[serde(rename = "inbound:write")]
+    InboundWrite,
+    /// Manage API keys and run operator actions such as maintenance mode.
+    #[serde(rename = "keys:admin")]
+    KeysAdmin,
+}
+
+impl ApiKeyScope {
+    /// Every scope, in the order they are listed.
+    pub const ALL: [ApiKeyScope; 8] = [
+        Self::AccountsRead,
+        Self::AccountsWrite,
+        Self::TransactionsRead,
+        Self::TransactionsWrite,
+        Self::WebhooksRead,
+        Self::WebhooksWrite,
+        Self::InboundWrite,
+        Self::KeysAdmin,
+    ];
+
+    /// The read scopes, granted to restricted keys (bound to an account or
+    /// owner, or in test mode) created without an explicit list.
+    pub const READ: [ApiKeyScope; 2] = [Self::AccountsRead, Self::TransactionsRead];
+
+    /// Every scope, granted to keys created without an explicit list.
+    pub fn all() -> Vec<ApiKeyScope> {
+        Self::ALL.to_vec()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AccountsRead => "accounts:read",
+            Self::AccountsWrite => "accounts:write",
+            Self::TransactionsRead => "transactions:read",
+            Self::TransactionsWrite => "transactions:write",
+            Self::WebhooksRead => "webhooks:read",
+            Self::WebhooksWrite => "webhooks:write",
+            Self::InboundWrite => "inbound:write",
+            Self::KeysAdmin => "keys:admin",
+        }
+    }
+}
+
+impl std::fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ApiKeyScope {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let scope = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == scope)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.iter().map(ApiKeyScope::as_str).collect();
+                DomainError::ValidationError(format!(
+                    "Unknown API key scope: {}. Expected one of {}",
+                    s,
+                    known.join(", ")
+                ))
+            })
+    }
+}
+
 /// An API key for authenticating requests.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKey {
     pub id: ApiKeyId,
     pub name: String,
+    /// Never serialized, so keys can travel in events and logs safely.
+    #[serde(skip_serializing)]
     pub key_hash: String,
     pub account_id: Option<AccountId>,
+    /// The owner whose accounts the key may act on, in place of a single
+    /// account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<OwnerId>,
+    /// What the key may do. Keys recorded before scopes existed hold all
+    /// of them.
+    #[serde(default = "ApiKeyScope::all")]
+    pub scopes: Vec<ApiKeyScope>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
+    /// When the key last authenticated a request, to the nearest
+    /// [`LAST_USED_RESOLUTION_SECS`]
     pub last_used_at: Option<DateTime<Utc>>,
+    /// When the key was deleted; deleted keys are kept for their audit trail
+    #[serde(default)]
+    pub deactivated_at: Option<DateTime<Utc>>,
+    /// Admin key that approved the deletion
+    #[serde(default)]
+    pub deactivated_by: Option<ApiKeyId>,
+    /// Requests the key may make per minute, in place of the server-wide
+    /// default
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
+    /// Whether the key acts on test or live accounts
+    #[serde(default)]
+    pub mode: Mode,
 }
 
 impl ApiKey {
-    /// Creates a new API key with the given name and hash.
+    /// Creates a new API key with the given name and hash, holding every
+    /// scope.
     pub fn new(name: String, key_hash: String, account_id: Option<AccountId>) -> Self {
         Self {
             id: ApiKeyId::new(),
             name,
             key_hash,
             account_id,
+            owner_id: None,
+            scopes: ApiKeyScope::all(),
             is_active: true,
             created_at: Utc::now(),
             last_used_at: None,
+            deactivated_at: None,
+            deactivated_by: None,
+            rate_limit_per_minute: None,
+            mode: Mode::Live,
+        }
+    }
+
+    /// Binds the key to the accounts of `owner_id`.
+    pub fn with_owner(mut self, owner_id: OwnerId) -> Self {
+        self.owner_id = Some(owner_id);
+        self
+    }
+
+    /// Puts the key in `mode`.
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Restricts the key to `scopes`.
+    pub fn with_scopes(mut self, scopes: Vec<ApiKeyScope>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+/// Highest per-key quota an admin can set, in requests per minute.
+pub const MAX_RATE_LIMIT_PER_MINUTE: u32 = 1_000_000;
+
+/// How stale a key's `last_used_at` may get before a request updates it
+/// (1 minute), so a busy key is not written to on every request.
+pub const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+/// Something that happened to an API key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyAuditAction {
+    /// The key was issued
+    Created,
+    /// The key was deleted and stopped authenticating
+    Deactivated,
+    /// The key authenticated a request and its `last_used_at` moved on
+    Used,
+}
+
+impl AsRef<str> for ApiKeyAuditAction {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Created => "created",
+            Self::Deactivated => "deactivated",
+            Self::Used => "used",
         }
     }
 }
+
+impl std::fmt::Display for ApiKeyAuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl std::str::FromStr for ApiKeyAuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "created" => Ok(Self::Created),
+            "deactivated" => Ok(Self::Deactivated),
+            "used" => Ok(Self::Used),
+            _ => Err(format!("Unknown API key audit action: {}", s)),
+        }
+    }
+}
+
+/// One entry in an API key's audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyAuditEntry {
+    #[schema(value_type = String)]
+    pub api_key_id: ApiKeyId,
+    pub action: ApiKeyAuditAction,
+    /// Admin key that acted on the key; absent for the bootstrap key's
+    /// creation and for uses, which the key makes itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub actor: Option<ApiKeyId>,
+    pub at: DateTime<Utc>,
+}
+
+/// An API key, deleted or not, with everything that happened to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyAudit {
+    #[schema(value_type = String)]
+    pub api_key_id: ApiKeyId,
+    pub name: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub deactivated_at: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>)]
+    pub deactivated_by: Option<ApiKeyId>,
+    /// Oldest first
+    pub entries: Vec<ApiKeyAuditEntry>,
+}
+
+/// How long a session token stays valid unless asked otherwise (15 minutes).
+pub const DEFAULT_SESSION_TTL_SECS: u64 = 15 * 60;
+
+/// The longest a session token may stay valid (1 hour).
+pub const MAX_SESSION_TTL_SECS: u64 = 60 * 60;
+
+/// A short-lived token that can only read one account, issued by an API key
+/// for dashboards that must not hold the key itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SessionToken {
+    /// Bearer token to send instead of the API key (shown only once)
+    #[schema(example = "st_...")]
+    pub token: String,
+    pub account_id: AccountId,
+    /// The read scopes the token holds
+    #[schema(example = json!(["accounts:read", "transactions:read"]))]
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl SessionToken {
+    /// The scopes a session token may hold, at most.
+    pub const SCOPES: [ApiKeyScope; 2] = [ApiKeyScope::AccountsRead, ApiKeyScope::TransactionsRead];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_round_trips_through_its_name() {
+        for scope in ApiKeyScope::ALL {
+            assert_eq!(scope.as_str().parse::<ApiKeyScope>().unwrap(), scope);
+            assert_eq!(
+                serde_json::to_string(&scope).unwrap(),
+                format!("\"{}\"", scope)
+            );
+        }
+        assert_eq!(
+            " Keys:Admin ".parse::<ApiKeyScope>().unwrap(),
+            ApiKeyScope::KeysAdmin
+        );
+        assert!(matches!(
+            "accounts:delete".parse::<ApiKeyScope>(),
+            Err(DomainError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_keys_without_scopes_hold_all_of_them() {
+        let json = serde_json::json!({
+            "id": ApiKeyId::new(),
+            "name": "legacy",
+            "key_hash": "hash",
+            "account_id": null,
+            "is_active": true,
+            "created_at": Utc::now(),
+            "last_used_at": null,
+        });
+        let key: ApiKey = serde_json::from_value(json).unwrap();
+        assert!(ApiKeyScope::ALL.iter().all(|s| key.has_scope(*s)));
+        assert_eq!(key.mode, Mode::Live);
+    }
+}
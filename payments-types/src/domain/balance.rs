@@ -0,0 +1,109 @@
+//! Daily balance history.
+//!
+//! Each account's balance at the end of every UTC day is stored once the day
+//! is over, so charts can read months of history without replaying the
+//! transactions behind it.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{AccountId, CurrencyCode, Transaction};
+
+/// An account's balance at the end of one UTC day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DailyBalance {
+    pub date: NaiveDate,
+    /// Closing balance in minor units
+    #[schema(example = 98000)]
+    pub balance: i64,
+}
+
+impl DailyBalance {
+    /// Closing balances of `account_id` for every day in `from..=to`,
+    /// replayed from its full transaction history.
+    pub fn from_history(
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+        history: &[Transaction],
+    ) -> Vec<Self> {
+        let mut history: Vec<&Transaction> = history.iter().collect();
+        history.sort_by_key(|tx| tx.created_at);
+
+        let mut pending = history.into_iter().peekable();
+        let mut balance = 0;
+        from.iter_days()
+            .take_while(|date| *date <= to)
+            .map(|date| {
+                let ends_at = day_end(date);
+                while let Some(tx) = pending.next_if(|tx| tx.created_at < ends_at) {
+                    balance += tx.signed_amount_for(account_id);
+                }
+                Self { date, balance }
+            })
+            .collect()
+    }
+}
+
+/// An account's closing balances over a range of days, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BalanceHistory {
+    pub account_id: AccountId,
+    pub currency: CurrencyCode,
+    /// Days without a recorded balance yet are left out
+    pub balances: Vec<DailyBalance>,
+}
+
+/// The instant `date` ends, i.e. the following midnight UTC.
+fn day_end(date: NaiveDate) -> DateTime<Utc> {
+    date.succ_opt().map_or(DateTime::<Utc>::MAX_UTC, |day| {
+        day.and_time(NaiveTime::MIN).and_utc()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::domain::DynMoney;
+
+    #[test]
+    fn test_history_replays_into_closing_balances() {
+        let (alice, bob) = (AccountId::new(), AccountId::new());
+        let usd = |amount| DynMoney::new(amount, CurrencyCode::USD).unwrap();
+        let at = |day, hour| Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+        let mut history = vec![
+            Transaction::transfer(alice, bob, usd(300), None, None),
+            Transaction::deposit(alice, usd(1_000), None, None),
+            Transaction::withdrawal(alice, usd(200), None, None),
+        ];
+        history[0].created_at = at(3, 9);
+        history[1].created_at = at(1, 23);
+        history[2].created_at = at(3, 0);
+
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let balances = DailyBalance::from_history(alice, day(1), day(4), &history);
+        let closing: Vec<i64> = balances.iter().map(|b| b.balance).collect();
+        assert_eq!(closing, [1_000, 1_000, 500, 500]);
+        assert_eq!(balances[0].date, day(1));
+
+        // Starting later still counts everything before the range.
+        let later = DailyBalance::from_history(alice, day(3), day(3), &history);
+        assert_eq!(
+            later,
+            [DailyBalance {
+                date: day(3),
+                balance: 500
+            }]
+        );
+        assert_eq!(
+            DailyBalance::from_history(bob, day(2), day(3), &history)
+                .iter()
+                .map(|b| b.balance)
+                .collect::<Vec<_>>(),
+            [0, 300]
+        );
+    }
+}
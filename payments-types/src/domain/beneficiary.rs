@@ -0,0 +1,209 @@
+//! Beneficiaries: external accounts an account pays out to.
+//!
+//! A new beneficiary is sent two small deposits of random amounts and stays
+//! `Pending` until the customer confirms both amounts, proving they control
+//! the external account. Payouts only go to `Verified` beneficiaries.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{AccountId, CurrencyCode};
+use crate::error::DomainError;
+
+/// Largest micro-deposit sent to a new beneficiary, in minor units.
+pub const MAX_MICRO_DEPOSIT: i64 = 99;
+
+/// Wrong guesses at the micro-deposit amounts allowed before the
+/// beneficiary fails verification for good.
+pub const MAX_VERIFICATION_ATTEMPTS: u32 = 3;
+
+/// Unique identifier for a beneficiary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct BeneficiaryId(Uuid);
+
+impl BeneficiaryId {
+    /// Creates a new random BeneficiaryId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a BeneficiaryId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for BeneficiaryId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for BeneficiaryId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for BeneficiaryId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// Where a beneficiary is in verification. Only `Verified` ones are paid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BeneficiaryStatus {
+    /// Micro-deposits sent, waiting for the customer to confirm them
+    Pending,
+    /// The customer confirmed the micro-deposit amounts
+    Verified,
+    /// Too many wrong amounts; the beneficiary must be added again
+    Failed,
+}
+
+impl AsRef<str> for BeneficiaryStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Verified => "VERIFIED",
+            Self::Failed => "FAILED",
+        }
+    }
+}
+
+impl std::fmt::Display for BeneficiaryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl std::str::FromStr for BeneficiaryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "PENDING" => Ok(Self::Pending),
+            "VERIFIED" => Ok(Self::Verified),
+            "FAILED" => Ok(Self::Failed),
+            _ => Err(format!("Unknown beneficiary status: {}", s)),
+        }
+    }
+}
+
+/// An external account that an account pays out to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Beneficiary {
+    pub id: BeneficiaryId,
+    /// Account that pays the beneficiary
+    pub account_id: AccountId,
+    #[schema(example = "ACME Supplies")]
+    pub name: String,
+    /// Identifier at the external institution (IBAN, account number, ...);
+    /// payouts are matched to beneficiaries by it
+    #[schema(example = "GB33BUKB20201555555555")]
+    pub external_id: String,
+    /// Currency the micro-deposits were sent in
+    pub currency: CurrencyCode,
+    pub status: BeneficiaryStatus,
+    /// Wrong confirmations so far
+    pub failed_attempts: u32,
+    /// The two micro-deposit amounts, in minor units; never returned by the API
+    #[serde(skip)]
+    pub micro_deposits: [i64; 2],
+    pub created_at: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+impl Beneficiary {
+    /// Returns `true` once payouts may be sent to this beneficiary.
+    pub fn is_verified(&self) -> bool {
+        self.status == BeneficiaryStatus::Verified
+    }
+
+    /// Records an attempt to confirm the micro-deposit `amounts`, in either
+    /// order, at `now`. Returns whether they matched; a wrong guess that uses
+    /// up the last attempt fails the beneficiary.
+    ///
+    /// Fails if the beneficiary is no longer pending.
+    pub fn confirm(&mut self, amounts: [i64; 2], now: DateTime<Utc>) -> Result<bool, DomainError> {
+        if self.status != BeneficiaryStatus::Pending {
+            return Err(DomainError::ValidationError(format!(
+                "Beneficiary is {}, not PENDING",
+                self.status
+            )));
+        }
+        let mut sent = self.micro_deposits;
+        let mut given = amounts;
+        sent.sort_unstable();
+        given.sort_unstable();
+        if sent == given {
+            self.status = BeneficiaryStatus::Verified;
+            self.verified_at = Some(now);
+            return Ok(true);
+        }
+        self.failed_attempts += 1;
+        if self.failed_attempts >= MAX_VERIFICATION_ATTEMPTS {
+            self.status = BeneficiaryStatus::Failed;
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn beneficiary() -> Beneficiary {
+        Beneficiary {
+            id: BeneficiaryId::new(),
+            account_id: AccountId::new(),
+            name: "ACME Supplies".into(),
+            external_id: "GB33BUKB20201555555555".into(),
+            currency: CurrencyCode::GBP,
+            status: BeneficiaryStatus::Pending,
+            failed_attempts: 0,
+            micro_deposits: [12, 47],
+            created_at: Utc::now(),
+            verified_at: None,
+        }
+    }
+
+    #[test]
+    fn test_confirm_accepts_amounts_in_either_order() {
+        let mut beneficiary = beneficiary();
+        let now = Utc::now();
+        assert!(beneficiary.confirm([47, 12], now).unwrap());
+        assert!(beneficiary.is_verified());
+        assert_eq!(beneficiary.verified_at, Some(now));
+        assert!(beneficiary.confirm([12, 47], now).is_err());
+    }
+
+    #[test]
+    fn test_confirm_fails_after_too_many_wrong_amounts() {
+        let mut beneficiary = beneficiary();
+        for attempt in 1..=MAX_VERIFICATION_ATTEMPTS {
+            assert!(!beneficiary.confirm([12, 48], Utc::now()).unwrap());
+            assert_eq!(beneficiary.failed_attempts, attempt);
+        }
+        assert_eq!(beneficiary.status, BeneficiaryStatus::Failed);
+        assert!(beneficiary.confirm([12, 47], Utc::now()).is_err());
+        assert!(!beneficiary.is_verified());
+    }
+}
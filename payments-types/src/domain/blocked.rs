@@ -0,0 +1,34 @@
+//! Payments refused by the service's limits.
+//!
+//! Unlike the risk check, limits never hold a payment for review: one over a
+//! cap is refused with the limit's error code (`AMOUNT_LIMIT_EXCEEDED`,
+//! `DAILY_DEBIT_LIMIT_EXCEEDED` and so on) and announced as a
+//! `transaction.blocked` event.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{AccountId, CurrencyCode, TransactionType};
+use crate::dto::ErrorCode;
+
+/// A payment the service's limits refused.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BlockedTransaction {
+    pub transaction_type: TransactionType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_account_id: Option<AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_account_id: Option<AccountId>,
+    #[schema(example = 150000)]
+    pub amount: i64,
+    pub currency: CurrencyCode,
+    /// The code the payment was refused with
+    pub error_code: ErrorCode,
+    /// Which limit the payment broke, for humans
+    #[schema(
+        example = "Daily debit limit exceeded: 400000 already sent, 150000 requested, limit 500000"
+    )]
+    pub reason: String,
+    pub blocked_at: DateTime<Utc>,
+}
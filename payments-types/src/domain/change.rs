@@ -0,0 +1,183 @@
+//! Changes to security settings that need a second admin's approval.
+//!
+//! Deleting an API key or pointing a webhook somewhere else can lock out an
+//! integration or leak payment events, so one admin key only requests the
+//! change and a different one has to approve it before it is applied.
+//! Requests are kept once decided, so the list doubles as an audit log of
+//! who asked for what and who let it through.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{ApiKeyId, WebhookEndpointId};
+
+/// Unique identifier for a change request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct ChangeRequestId(Uuid);
+
+impl ChangeRequestId {
+    /// Creates a new random ChangeRequestId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a ChangeRequestId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for ChangeRequestId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ChangeRequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for ChangeRequestId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// The security operation a change request applies once approved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeAction {
+    /// Deactivates an API key
+    DeleteApiKey {
+        #[schema(value_type = String)]
+        api_key_id: ApiKeyId,
+    },
+    /// Sends a webhook endpoint's deliveries to a new URL
+    SetWebhookUrl {
+        endpoint_id: WebhookEndpointId,
+        #[schema(example = "https://example.com/webhook")]
+        url: String,
+    },
+}
+
+impl std::fmt::Display for ChangeAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DeleteApiKey { api_key_id } => write!(f, "delete API key {}", api_key_id),
+            Self::SetWebhookUrl { endpoint_id, url } => {
+                write!(f, "set webhook {} URL to {}", endpoint_id, url)
+            }
+        }
+    }
+}
+
+/// Where a change request is in its approval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeStatus {
+    /// Waiting for a second admin
+    Pending,
+    /// Approved and applied
+    Approved,
+    /// Turned down; nothing was changed
+    Rejected,
+}
+
+impl AsRef<str> for ChangeStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Approved => "APPROVED",
+            Self::Rejected => "REJECTED",
+        }
+    }
+}
+
+impl std::fmt::Display for ChangeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl std::str::FromStr for ChangeStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "PENDING" => Ok(Self::Pending),
+            "APPROVED" => Ok(Self::Approved),
+            "REJECTED" => Ok(Self::Rejected),
+            _ => Err(format!("Unknown change status: {}", s)),
+        }
+    }
+}
+
+/// A requested security change and who decided on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ChangeRequest {
+    pub id: ChangeRequestId,
+    pub action: ChangeAction,
+    pub status: ChangeStatus,
+    /// Admin key that asked for the change
+    #[schema(value_type = String)]
+    pub requested_by: ApiKeyId,
+    pub requested_at: DateTime<Utc>,
+    /// Admin key that approved or rejected it
+    #[schema(value_type = Option<String>)]
+    pub decided_by: Option<ApiKeyId>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+impl ChangeRequest {
+    /// A pending request by `requested_by` to apply `action`.
+    pub fn new(action: ChangeAction, requested_by: ApiKeyId, requested_at: DateTime<Utc>) -> Self {
+        Self {
+            id: ChangeRequestId::new(),
+            action,
+            status: ChangeStatus::Pending,
+            requested_by,
+            requested_at,
+            decided_by: None,
+            decided_at: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_is_tagged_by_type() {
+        let endpoint_id = WebhookEndpointId::new();
+        let action = ChangeAction::SetWebhookUrl {
+            endpoint_id,
+            url: "https://example.com/new".into(),
+        };
+        let json = serde_json::to_value(&action).unwrap();
+        assert_eq!(json["type"], "set_webhook_url");
+        assert_eq!(json["endpoint_id"], endpoint_id.to_string());
+        assert_eq!(
+            serde_json::from_value::<ChangeAction>(json).unwrap(),
+            action
+        );
+        assert_eq!("pending".parse::<ChangeStatus>(), Ok(ChangeStatus::Pending));
+    }
+}
@@ -0,0 +1,744 @@
+//! Domain events emitted by the payment service.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{
+    Account, AccountId, AccountMerge, ApiKey, BlockedTransaction, Hold, Mode, QuotaWarning,
+    ScheduledPayment, StatementDownload, Transaction,
+};
+
+/// A field of an event's webhook payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct EventField {
+    #[schema(value_type = String, example = "account_id")]
+    pub name: &'static str,
+    /// JSON type of the value; a trailing `?` marks a nullable field.
+    #[serde(rename = "type")]
+    #[schema(value_type = String, example = "string")]
+    pub ty: &'static str,
+}
+
+/// A webhook event type and the shape of its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct EventSpec {
+    #[schema(value_type = String, example = "deposit.success")]
+    pub name: &'static str,
+    #[schema(value_type = String)]
+    pub description: &'static str,
+    #[schema(value_type = Vec<EventField>)]
+    pub payload: &'static [EventField],
+}
+
+const fn field(name: &'static str, ty: &'static str) -> EventField {
+    EventField { name, ty }
+}
+
+/// Every event type webhooks can subscribe to.
+pub const EVENT_CATALOG: &[EventSpec] = &[
+    EventSpec {
+        name: "account.created",
+        description: "An account was opened.",
+        payload: &[
+            field("account_id", "string"),
+            field("name", "string"),
+            field("currency", "string"),
+        ],
+    },
+    EventSpec {
+        name: "account.dormant",
+        description: "An account had no transactions for the dormancy period and was flagged dormant.",
+        payload: &[
+            field("account_id", "string"),
+            field("name", "string"),
+            field("currency", "string"),
+            field("balance", "integer"),
+            field("dormant_since", "string"),
+        ],
+    },
+    EventSpec {
+        name: "account.frozen",
+        description: "An admin froze an account; money can no longer leave it until it is unfrozen.",
+        payload: &[
+            field("account_id", "string"),
+            field("name", "string"),
+            field("currency", "string"),
+            field("balance", "integer"),
+            field("frozen_at", "string"),
+        ],
+    },
+    EventSpec {
+        name: "account.merged",
+        description: "An admin merged an account into another; its balance moved over and it was closed.",
+        payload: &[
+            field("account_id", "string"),
+            field("merged_into", "string"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("transaction_id", "string?"),
+            field("merged_at", "string"),
+        ],
+    },
+    EventSpec {
+        name: "deposit.success",
+        description: "Funds were deposited into an account.",
+        payload: &[
+            field("transaction_id", "string"),
+            field("account_id", "string"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("reference", "string?"),
+            field("counterparty", "object?"),
+            field("metadata", "object"),
+        ],
+    },
+    EventSpec {
+        name: "withdraw.success",
+        description: "Funds were withdrawn from an account.",
+        payload: &[
+            field("transaction_id", "string"),
+            field("account_id", "string"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("reference", "string?"),
+            field("counterparty", "object?"),
+            field("purpose_code", "string?"),
+            field("metadata", "object"),
+        ],
+    },
+    EventSpec {
+        name: "transfer.success",
+        description: "Funds moved between two accounts.",
+        payload: &[
+            field("transaction_id", "string"),
+            field("from_account_id", "string"),
+            field("to_account_id", "string"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("reference", "string?"),
+            field("purpose_code", "string?"),
+            field("conversion", "object?"),
+            field("metadata", "object"),
+        ],
+    },
+    EventSpec {
+        name: "transaction.blocked",
+        description: "A payment was refused by a limit; nothing was recorded.",
+        payload: &[
+            field("transaction_type", "string"),
+            field("from_account_id", "string?"),
+            field("to_account_id", "string?"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("error_code", "string"),
+            field("reason", "string"),
+            field("blocked_at", "string"),
+        ],
+    },
+    EventSpec {
+        name: "api_key.created",
+        description: "An API key was issued. The raw key is never included.",
+        payload: &[
+            field("api_key_id", "string"),
+            field("name", "string"),
+            field("account_id", "string?"),
+        ],
+    },
+    EventSpec {
+        name: "statement.ready",
+        description: "A monthly statement was issued. The download URL needs no API key and expires.",
+        payload: &[
+            field("statement_id", "string"),
+            field("account_id", "string"),
+            field("period_start", "string"),
+            field("period_end", "string"),
+            field("currency", "string"),
+            field("opening_balance", "integer"),
+            field("closing_balance", "integer"),
+            field("download_url", "string"),
+            field("url_expires_at", "string"),
+        ],
+    },
+    EventSpec {
+        name: "scheduled_payment.executed",
+        description: "A scheduled payment ran and made its transaction.",
+        payload: &[
+            field("scheduled_payment_id", "string"),
+            field("transaction_type", "string"),
+            field("from_account_id", "string"),
+            field("to_account_id", "string?"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("transaction_id", "string"),
+            field("next_run_at", "string"),
+        ],
+    },
+    EventSpec {
+        name: "scheduled_payment.failed",
+        description: "A scheduled payment came due but its transaction was rejected. It stays active and runs again when next due.",
+        payload: &[
+            field("scheduled_payment_id", "string"),
+            field("transaction_type", "string"),
+            field("from_account_id", "string"),
+            field("to_account_id", "string?"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("error", "string"),
+            field("next_run_at", "string"),
+        ],
+    },
+    EventSpec {
+        name: "hold.authorized",
+        description: "Funds were reserved on an account by an authorization.",
+        payload: &[
+            field("hold_id", "string"),
+            field("account_id", "string"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("status", "string"),
+            field("reference", "string?"),
+            field("expires_at", "string"),
+            field("captured_amount", "integer?"),
+            field("transaction_id", "string?"),
+        ],
+    },
+    EventSpec {
+        name: "hold.captured",
+        description: "A hold was captured; the withdrawal it made is also announced as withdraw.success.",
+        payload: &[
+            field("hold_id", "string"),
+            field("account_id", "string"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("status", "string"),
+            field("reference", "string?"),
+            field("expires_at", "string"),
+            field("captured_amount", "integer?"),
+            field("transaction_id", "string?"),
+        ],
+    },
+    EventSpec {
+        name: "hold.voided",
+        description: "A hold was voided and its funds released.",
+        payload: &[
+            field("hold_id", "string"),
+            field("account_id", "string"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("status", "string"),
+            field("reference", "string?"),
+            field("expires_at", "string"),
+            field("captured_amount", "integer?"),
+            field("transaction_id", "string?"),
+        ],
+    },
+    EventSpec {
+        name: "hold.expired",
+        description: "A hold was not captured in time and its funds were released.",
+        payload: &[
+            field("hold_id", "string"),
+            field("account_id", "string"),
+            field("amount", "integer"),
+            field("currency", "string"),
+            field("status", "string"),
+            field("reference", "string?"),
+            field("expires_at", "string"),
+            field("captured_amount", "integer?"),
+            field("transaction_id", "string?"),
+        ],
+    },
+    EventSpec {
+        name: "quota.warning",
+        description: "A table's row count reached the warning threshold of its soft quota. Raised again only after it drops back below.",
+        payload: &[
+            field("resource", "string"),
+            field("rows", "integer"),
+            field("limit", "integer"),
+            field("used", "number"),
+            field("warn_at", "number"),
+            field("checked_at", "string"),
+        ],
+    },
+];
+
+/// Looks up an event type in [`EVENT_CATALOG`].
+pub fn event_spec(name: &str) -> Option<&'static EventSpec> {
+    EVENT_CATALOG.iter().find(|spec| spec.name == name)
+}
+
+/// Something that happened to an account, emitted after it was persisted.
+///
+/// Each event carries the entity it describes; the entity's ID and
+/// `created_at` double as the event's identity and timestamp.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum DomainEvent {
+    AccountCreated(Account),
+    AccountDormant(Account),
+    AccountFrozen(Account),
+    AccountMerged(Box<AccountMerge>),
+    FundsDeposited(Transaction),
+    FundsWithdrawn(Transaction),
+    TransferCompleted(Transaction),
+    TransactionBlocked(BlockedTransaction),
+    ApiKeyCreated(ApiKey),
+    StatementReady(StatementDownload),
+    ScheduledPaymentExecuted(ScheduledPayment),
+    ScheduledPaymentFailed(ScheduledPayment),
+    HoldAuthorized(Hold),
+    HoldCaptured(Hold),
+    HoldVoided(Hold),
+    HoldExpired(Hold),
+    QuotaWarning(QuotaWarning),
+}
+
+impl DomainEvent {
+    /// Name used for webhook subscriptions (e.g. `deposit.success`).
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEvent::AccountCreated(_) => "account.created",
+            DomainEvent::AccountDormant(_) => "account.dormant",
+            DomainEvent::AccountFrozen(_) => "account.frozen",
+            DomainEvent::AccountMerged(_) => "account.merged",
+            DomainEvent::FundsDeposited(_) => "deposit.success",
+            DomainEvent::FundsWithdrawn(_) => "withdraw.success",
+            DomainEvent::TransferCompleted(_) => "transfer.success",
+            DomainEvent::TransactionBlocked(_) => "transaction.blocked",
+            DomainEvent::ApiKeyCreated(_) => "api_key.created",
+            DomainEvent::StatementReady(_) => "statement.ready",
+            DomainEvent::ScheduledPaymentExecuted(_) => "scheduled_payment.executed",
+            DomainEvent::ScheduledPaymentFailed(_) => "scheduled_payment.failed",
+            DomainEvent::HoldAuthorized(_) => "hold.authorized",
+            DomainEvent::HoldCaptured(_) => "hold.captured",
+            DomainEvent::HoldVoided(_) => "hold.voided",
+            DomainEvent::HoldExpired(_) => "hold.expired",
+            DomainEvent::QuotaWarning(_) => "quota.warning",
+        }
+    }
+
+    /// When the underlying change happened.
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        match self {
+            DomainEvent::AccountCreated(account) => account.created_at,
+            DomainEvent::AccountDormant(account) | DomainEvent::AccountFrozen(account) => {
+                account.status_changed_at.unwrap_or(account.created_at)
+            }
+            DomainEvent::AccountMerged(merge) => merge
+                .source
+                .status_changed_at
+                .unwrap_or(merge.source.created_at),
+            DomainEvent::FundsDeposited(tx)
+            | DomainEvent::FundsWithdrawn(tx)
+            | DomainEvent::TransferCompleted(tx) => tx.created_at,
+            DomainEvent::TransactionBlocked(blocked) => blocked.blocked_at,
+            DomainEvent::ApiKeyCreated(key) => key.created_at,
+            DomainEvent::StatementReady(ready) => ready.statement.created_at,
+            DomainEvent::ScheduledPaymentExecuted(payment)
+            | DomainEvent::ScheduledPaymentFailed(payment) => {
+                payment.last_run_at.unwrap_or(payment.created_at)
+            }
+            DomainEvent::HoldAuthorized(hold)
+            | DomainEvent::HoldCaptured(hold)
+            | DomainEvent::HoldVoided(hold)
+            | DomainEvent::HoldExpired(hold) => hold.resolved_at.unwrap_or(hold.created_at),
+            DomainEvent::QuotaWarning(warning) => warning.checked_at,
+        }
+    }
+
+    /// The accounts the event concerns, for showing it only to keys scoped
+    /// to one of them. Events about no account (e.g. an admin key being
+    /// created) return none.
+    pub fn account_ids(&self) -> Vec<AccountId> {
+        match self {
+            DomainEvent::AccountCreated(account)
+            | DomainEvent::AccountDormant(account)
+            | DomainEvent::AccountFrozen(account) => vec![account.id],
+            DomainEvent::AccountMerged(merge) => vec![merge.source.id, merge.target.id],
+            DomainEvent::FundsDeposited(tx)
+            | DomainEvent::FundsWithdrawn(tx)
+            | DomainEvent::TransferCompleted(tx) => tx
+                .source_account_id
+                .into_iter()
+                .chain(tx.destination_account_id)
+                .collect(),
+            DomainEvent::TransactionBlocked(blocked) => blocked
+                .from_account_id
+                .into_iter()
+                .chain(blocked.to_account_id)
+                .collect(),
+            DomainEvent::ApiKeyCreated(key) => key.account_id.into_iter().collect(),
+            DomainEvent::StatementReady(ready) => vec![ready.statement.account_id],
+            DomainEvent::ScheduledPaymentExecuted(payment)
+            | DomainEvent::ScheduledPaymentFailed(payment) => {
+                std::iter::once(payment.from_account_id)
+                    .chain(payment.to_account_id)
+                    .collect()
+            }
+            DomainEvent::HoldAuthorized(hold)
+            | DomainEvent::HoldCaptured(hold)
+            | DomainEvent::HoldVoided(hold)
+            | DomainEvent::HoldExpired(hold) => vec![hold.account_id],
+            DomainEvent::QuotaWarning(_) => Vec::new(),
+        }
+    }
+
+    /// The mode of the account or key the event describes, for events that
+    /// carry one. Other events take the mode of their
+    /// [`account_ids`](Self::account_ids).
+    pub fn mode(&self) -> Option<Mode> {
+        match self {
+            DomainEvent::AccountCreated(account)
+            | DomainEvent::AccountDormant(account)
+            | DomainEvent::AccountFrozen(account) => Some(account.mode),
+            DomainEvent::AccountMerged(merge) => Some(merge.source.mode),
+            DomainEvent::ApiKeyCreated(key) => Some(key.mode),
+            _ => None,
+        }
+    }
+
+    /// Webhook payload for this event.
+    pub fn payload(&self) -> serde_json::Value {
+        match self {
+            DomainEvent::AccountCreated(account) => serde_json::json!({
+                "account_id": account.id,
+                "name": account.name,
+                "currency": account.currency(),
+            }),
+            DomainEvent::AccountDormant(account) => serde_json::json!({
+                "account_id": account.id,
+                "name": account.name,
+                "currency": account.currency(),
+                "balance": account.balance.amount(),
+                "dormant_since": account.status_changed_at,
+            }),
+            DomainEvent::AccountFrozen(account) => serde_json::json!({
+                "account_id": account.id,
+                "name": account.name,
+                "currency": account.currency(),
+                "balance": account.balance.amount(),
+                "frozen_at": account.status_changed_at,
+            }),
+            DomainEvent::AccountMerged(merge) => serde_json::json!({
+                "account_id": merge.source.id,
+                "merged_into": merge.target.id,
+                "amount": merge.transaction.as_ref().map_or(0, |tx| tx.amount.amount()),
+                "currency": merge.source.currency(),
+                "transaction_id": merge.transaction.as_ref().map(|tx| tx.id),
+                "merged_at": merge.source.status_changed_at,
+            }),
+            DomainEvent::FundsDeposited(tx) => serde_json::json!({
+                "transaction_id": tx.id,
+                "account_id": tx.destination_account_id,
+                "amount": tx.amount.amount(),
+                "currency": tx.amount.currency(),
+                "reference": tx.reference,
+                "counterparty": tx.counterparty,
+                "metadata": tx.metadata,
+            }),
+            DomainEvent::FundsWithdrawn(tx) => serde_json::json!({
+                "transaction_id": tx.id,
+                "account_id": tx.source_account_id,
+                "amount": tx.amount.amount(),
+                "currency": tx.amount.currency(),
+                "reference": tx.reference,
+                "counterparty": tx.counterparty,
+                "purpose_code": tx.purpose_code,
+                "metadata": tx.metadata,
+            }),
+            DomainEvent::TransferCompleted(tx) => serde_json::json!({
+                "transaction_id": tx.id,
+                "from_account_id": tx.source_account_id,
+                "to_account_id": tx.destination_account_id,
+                "amount": tx.amount.amount(),
+                "currency": tx.amount.currency(),
+                "reference": tx.reference,
+                "purpose_code": tx.purpose_code,
+                "conversion": tx.conversion,
+                "metadata": tx.metadata,
+            }),
+            DomainEvent::TransactionBlocked(blocked) => serde_json::json!({
+                "transaction_type": blocked.transaction_type,
+                "from_account_id": blocked.from_account_id,
+                "to_account_id": blocked.to_account_id,
+                "amount": blocked.amount,
+                "currency": blocked.currency,
+                "error_code": blocked.error_code,
+                "reason": blocked.reason,
+                "blocked_at": blocked.blocked_at,
+            }),
+            DomainEvent::ApiKeyCreated(key) => serde_json::json!({
+                "api_key_id": key.id,
+                "name": key.name,
+                "account_id": key.account_id,
+            }),
+            DomainEvent::StatementReady(ready) => serde_json::json!({
+                "statement_id": ready.statement.id,
+                "account_id": ready.statement.account_id,
+                "period_start": ready.statement.period_start,
+                "period_end": ready.statement.period_end,
+                "currency": ready.statement.currency,
+                "opening_balance": ready.statement.opening_balance,
+                "closing_balance": ready.statement.closing_balance,
+                "download_url": ready.download_url,
+                "url_expires_at": ready.url_expires_at,
+            }),
+            DomainEvent::ScheduledPaymentExecuted(payment) => serde_json::json!({
+                "scheduled_payment_id": payment.id,
+                "transaction_type": payment.transaction_type,
+                "from_account_id": payment.from_account_id,
+                "to_account_id": payment.to_account_id,
+                "amount": payment.amount,
+                "currency": payment.currency,
+                "transaction_id": payment.last_transaction_id,
+                "next_run_at": payment.next_run_at,
+            }),
+            DomainEvent::ScheduledPaymentFailed(payment) => serde_json::json!({
+                "scheduled_payment_id": payment.id,
+                "transaction_type": payment.transaction_type,
+                "from_account_id": payment.from_account_id,
+                "to_account_id": payment.to_account_id,
+                "amount": payment.amount,
+                "currency": payment.currency,
+                "error": payment.last_error,
+                "next_run_at": payment.next_run_at,
+            }),
+            DomainEvent::HoldAuthorized(hold)
+            | DomainEvent::HoldCaptured(hold)
+            | DomainEvent::HoldVoided(hold)
+            | DomainEvent::HoldExpired(hold) => serde_json::json!({
+                "hold_id": hold.id,
+                "account_id": hold.account_id,
+                "amount": hold.amount,
+                "currency": hold.currency,
+                "status": hold.status,
+                "reference": hold.reference,
+                "expires_at": hold.expires_at,
+                "captured_amount": hold.captured_amount,
+                "transaction_id": hold.transaction_id,
+            }),
+            DomainEvent::QuotaWarning(warning) => serde_json::json!({
+                "resource": warning.usage.resource,
+                "rows": warning.usage.rows,
+                "limit": warning.usage.limit,
+                "used": warning.usage.used,
+                "warn_at": warning.warn_at,
+                "checked_at": warning.checked_at,
+            }),
+        }
+    }
+
+    /// Whether the event belongs in the event log: it moves money or changes
+    /// an account. Blocked payments, key creations, statement links (which
+    /// carry a signed URL) and quota warnings are only sent to webhooks.
+    pub fn is_logged(&self) -> bool {
+        !matches!(
+            self,
+            DomainEvent::TransactionBlocked(_)
+                | DomainEvent::ApiKeyCreated(_)
+                | DomainEvent::StatementReady(_)
+                | DomainEvent::QuotaWarning(_)
+        )
+    }
+}
+
+/// A domain event as kept in the event log, one line of an event log export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LoggedEvent {
+    /// Position in the log; one more than the event committed before it
+    pub sequence: i64,
+    /// Event type, as in [`EVENT_CATALOG`]
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub occurred_at: DateTime<Utc>,
+    /// When the event was committed; exports are partitioned by its UTC day
+    pub recorded_at: DateTime<Utc>,
+    /// The event's webhook payload
+    pub data: serde_json::Value,
+    /// Mode of the accounts the event concerns
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        AccountStatus, CurrencyCode, DynMoney, HoldId, HoldStatus, PaymentSchedule,
+        ScheduledPaymentId, ScheduledPaymentStatus, Statement, StatementId, StatementPeriod,
+        StorageResource, StorageUsage, TransactionId, TransactionType,
+    };
+    use crate::dto::ErrorCode;
+
+    fn payload_keys(event: &DomainEvent) -> Vec<String> {
+        let mut keys: Vec<String> = event
+            .payload()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_event_types_match_webhook_names() {
+        let account = Account::new("Alice".into(), CurrencyCode::USD).unwrap();
+        let money = DynMoney::new(500, CurrencyCode::USD).unwrap();
+        let tx = Transaction::transfer(account.id, AccountId::new(), money, None, None);
+
+        assert_eq!(
+            DomainEvent::AccountCreated(account).event_type(),
+            "account.created"
+        );
+        assert_eq!(
+            DomainEvent::TransferCompleted(tx.clone()).event_type(),
+            "transfer.success"
+        );
+
+        assert_eq!(
+            DomainEvent::TransferCompleted(tx.clone()).account_ids(),
+            vec![
+                tx.source_account_id.unwrap(),
+                tx.destination_account_id.unwrap()
+            ]
+        );
+        let deposit = Transaction::deposit(tx.source_account_id.unwrap(), money, None, None);
+        assert_eq!(
+            DomainEvent::FundsDeposited(deposit).account_ids(),
+            vec![tx.source_account_id.unwrap()]
+        );
+
+        let payload = DomainEvent::TransferCompleted(tx.clone()).payload();
+        assert_eq!(payload["amount"], 500);
+        assert_eq!(
+            payload["from_account_id"],
+            serde_json::json!(tx.source_account_id)
+        );
+    }
+
+    #[test]
+    fn test_catalog_matches_payloads() {
+        let account = Account::new("Alice".into(), CurrencyCode::USD).unwrap();
+        let money = DynMoney::new(500, CurrencyCode::USD).unwrap();
+        let scheduled = ScheduledPayment {
+            id: ScheduledPaymentId::new(),
+            transaction_type: TransactionType::Transfer,
+            from_account_id: account.id,
+            to_account_id: Some(AccountId::new()),
+            amount: 500,
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            schedule: PaymentSchedule::Interval {
+                every_seconds: 3600,
+            },
+            status: ScheduledPaymentStatus::Active,
+            next_run_at: Utc::now(),
+            last_run_at: Some(Utc::now()),
+            last_transaction_id: Some(TransactionId::new()),
+            last_error: None,
+            created_at: Utc::now(),
+        };
+        let hold = Hold {
+            id: HoldId::new(),
+            account_id: account.id,
+            amount: 500,
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            status: HoldStatus::Authorized,
+            expires_at: Utc::now(),
+            captured_amount: None,
+            transaction_id: None,
+            created_at: Utc::now(),
+            resolved_at: None,
+        };
+        let events = [
+            DomainEvent::AccountCreated(account.clone()),
+            DomainEvent::AccountDormant(
+                account
+                    .clone()
+                    .with_status(AccountStatus::Dormant, Some(Utc::now())),
+            ),
+            DomainEvent::AccountFrozen(
+                account
+                    .clone()
+                    .with_status(AccountStatus::Frozen, Some(Utc::now())),
+            ),
+            DomainEvent::AccountMerged(Box::new(AccountMerge {
+                source: account
+                    .clone()
+                    .with_status(AccountStatus::Closed, Some(Utc::now())),
+                target: account.clone(),
+                transaction: Some(Transaction::transfer(
+                    account.id,
+                    AccountId::new(),
+                    money,
+                    None,
+                    None,
+                )),
+            })),
+            DomainEvent::FundsDeposited(Transaction::deposit(account.id, money, None, None)),
+            DomainEvent::FundsWithdrawn(Transaction::withdrawal(account.id, money, None, None)),
+            DomainEvent::TransferCompleted(Transaction::transfer(
+                account.id,
+                AccountId::new(),
+                money,
+                None,
+                None,
+            )),
+            DomainEvent::TransactionBlocked(BlockedTransaction {
+                transaction_type: TransactionType::Withdrawal,
+                from_account_id: Some(account.id),
+                to_account_id: None,
+                amount: 500,
+                currency: CurrencyCode::USD,
+                error_code: ErrorCode::AmountLimitExceeded,
+                reason: "Amount 500 exceeds the maximum transaction amount of 100".into(),
+                blocked_at: Utc::now(),
+            }),
+            DomainEvent::ApiKeyCreated(ApiKey::new("ci".into(), "hash".into(), None)),
+            DomainEvent::StatementReady(StatementDownload {
+                statement: Statement::from_history(
+                    StatementId::new(),
+                    &account,
+                    StatementPeriod::previous(Utc::now()),
+                    &[],
+                    Utc::now(),
+                )
+                .0,
+                download_url: "https://payments.example/statement".into(),
+                url_expires_at: Utc::now(),
+            }),
+            DomainEvent::ScheduledPaymentExecuted(scheduled.clone()),
+            DomainEvent::ScheduledPaymentFailed(scheduled),
+            DomainEvent::HoldAuthorized(hold.clone()),
+            DomainEvent::HoldCaptured(hold.clone()),
+            DomainEvent::HoldVoided(hold.clone()),
+            DomainEvent::HoldExpired(hold),
+            DomainEvent::QuotaWarning(QuotaWarning {
+                usage: StorageUsage::new(StorageResource::Accounts, 90, Some(100), 0.8),
+                warn_at: 0.8,
+                checked_at: Utc::now(),
+            }),
+        ];
+
+        assert_eq!(events.len(), EVENT_CATALOG.len());
+        for event in &events {
+            let spec = event_spec(event.event_type()).expect("event missing from catalog");
+            let mut documented: Vec<String> =
+                spec.payload.iter().map(|f| f.name.to_string()).collect();
+            documented.sort();
+            assert_eq!(payload_keys(event), documented, "{}", spec.name);
+        }
+    }
+
+    #[test]
+    fn test_api_key_payload_omits_hash() {
+        let key = ApiKey::new("ci".into(), "secret-hash".into(), None);
+        let payload = DomainEvent::ApiKeyCreated(key).payload();
+        assert!(!payload.to_string().contains("secret-hash"));
+    }
+}
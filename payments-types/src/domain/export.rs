@@ -0,0 +1,279 @@
+//! Asynchronous file exports.
+//!
+//! Large files (journal exports, settlement batch files) are rendered in the
+//! background instead of inside the request that asks for them. The caller
+//! gets an export in `PENDING` status, polls it until it is `READY`, and then
+//! fetches the file through a short-lived signed download link.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::SettlementBatchId;
+use super::mode::Mode;
+
+/// Unique identifier for an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct ExportId(Uuid);
+
+impl ExportId {
+    /// Creates a new random ExportId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates an ExportId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for ExportId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ExportId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for ExportId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// File format of a settlement batch export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SettlementExportFormat {
+    /// One row per payout
+    #[default]
+    Csv,
+    /// ISO 20022 customer credit transfer initiation (pain.001.001.03)
+    Pain001,
+}
+
+impl SettlementExportFormat {
+    /// MIME type of the exported file.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Pain001 => "application/xml; charset=utf-8",
+        }
+    }
+
+    /// File name extension of the exported file.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Pain001 => "xml",
+        }
+    }
+}
+
+/// Accounting tool a journal export is laid out for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalExportFormat {
+    /// QuickBooks Online journal entry import
+    #[default]
+    Quickbooks,
+    /// Xero manual journal import
+    Xero,
+}
+
+impl JournalExportFormat {
+    /// Lower-case name, as used in the query string and file names.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Quickbooks => "quickbooks",
+            Self::Xero => "xero",
+        }
+    }
+}
+
+/// What to export: the body of `POST /api/exports`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExportRequest {
+    /// Journal entries for the UTC days `from..=to`
+    Journal {
+        #[serde(default)]
+        format: JournalExportFormat,
+        from: NaiveDate,
+        to: NaiveDate,
+    },
+    /// A settlement batch file for the bank
+    SettlementBatch {
+        batch_id: SettlementBatchId,
+        #[serde(default)]
+        format: SettlementExportFormat,
+    },
+}
+
+impl ExportRequest {
+    /// Name the file is downloaded as.
+    pub fn filename(&self) -> String {
+        match self {
+            Self::Journal { format, from, to } => {
+                format!("journal-{}-{}-{}.csv", format.as_str(), from, to)
+            }
+            Self::SettlementBatch { batch_id, format } => {
+                format!("settlement-{}.{}", batch_id, format.extension())
+            }
+        }
+    }
+
+    /// MIME type of the file.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Journal { .. } => "text/csv; charset=utf-8",
+            Self::SettlementBatch { format, .. } => format.content_type(),
+        }
+    }
+}
+
+/// Where an export is in its lifecycle: `PENDING`, then `READY` or `FAILED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExportStatus {
+    /// Queued or being rendered
+    Pending,
+    /// Rendered; the file can be downloaded
+    Ready,
+    /// Rendering failed; see the export's `error`
+    Failed,
+}
+
+impl AsRef<str> for ExportStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Ready => "READY",
+            Self::Failed => "FAILED",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl std::str::FromStr for ExportStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "PENDING" => Ok(Self::Pending),
+            "READY" => Ok(Self::Ready),
+            "FAILED" => Ok(Self::Failed),
+            _ => Err(format!("Unknown export status: {}", s)),
+        }
+    }
+}
+
+/// A file export and how far along it is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Export {
+    pub id: ExportId,
+    pub request: ExportRequest,
+    pub status: ExportStatus,
+    /// Why rendering failed, for `FAILED` exports
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// When the export became `READY` or `FAILED`
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Mode of the key that asked for it; the file only covers that mode
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+impl Export {
+    /// A new live `PENDING` export.
+    pub fn new(id: ExportId, request: ExportRequest, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            request,
+            status: ExportStatus::Pending,
+            error: None,
+            created_at,
+            completed_at: None,
+            mode: Mode::Live,
+        }
+    }
+
+    /// Puts the export in `mode`.
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// An export with a time-limited link to its file once it is ready.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ExportDownload {
+    pub export: Export,
+    /// Signed URL that serves the file without an API key until it expires;
+    /// only set for `READY` exports
+    pub download_url: Option<String>,
+    pub url_expires_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_round_trips_with_defaults() {
+        let request: ExportRequest =
+            serde_json::from_str(r#"{"kind":"journal","from":"2026-03-01","to":"2026-03-31"}"#)
+                .unwrap();
+        assert_eq!(
+            request,
+            ExportRequest::Journal {
+                format: JournalExportFormat::Quickbooks,
+                from: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+                to: NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+            }
+        );
+        assert_eq!(
+            request.filename(),
+            "journal-quickbooks-2026-03-01-2026-03-31.csv"
+        );
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ExportRequest>(&json).unwrap(),
+            request
+        );
+
+        let batch_id = SettlementBatchId::new();
+        let request: ExportRequest = serde_json::from_value(serde_json::json!({
+            "kind": "settlement_batch",
+            "batch_id": batch_id,
+            "format": "pain001",
+        }))
+        .unwrap();
+        assert_eq!(request.filename(), format!("settlement-{}.xml", batch_id));
+        assert!(request.content_type().starts_with("application/xml"));
+    }
+}
@@ -0,0 +1,171 @@
+//! Currency exposure reporting.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::account::Account;
+use super::money::CurrencyCode;
+
+/// Exchange rates at one point in time, kept so past reports can be
+/// reproduced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// US dollars per unit of each currency
+    pub usd_rates: HashMap<CurrencyCode, f64>,
+}
+
+impl RateSnapshot {
+    /// Units of `to` per unit of `from`, if both currencies were quoted.
+    pub fn rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        Some(self.usd_rates.get(&from)? / self.usd_rates.get(&to)?)
+    }
+}
+
+/// Accounts held in one currency and their combined balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CurrencyBalance {
+    pub currency: CurrencyCode,
+    #[schema(example = 42)]
+    pub account_count: i64,
+    /// Sum in smallest currency unit
+    #[schema(example = 1250000)]
+    pub balance: i64,
+}
+
+impl CurrencyBalance {
+    /// Counts `accounts` and sums their balances per currency, in currency
+    /// code order.
+    pub fn tally<'a>(accounts: impl IntoIterator<Item = &'a Account>) -> Vec<Self> {
+        let mut totals: Vec<Self> = Vec::new();
+        for account in accounts {
+            let currency = account.currency();
+            match totals.iter_mut().find(|t| t.currency == currency) {
+                Some(total) => {
+                    total.account_count += 1;
+                    total.balance += account.balance.amount();
+                }
+                None => totals.push(Self {
+                    currency,
+                    account_count: 1,
+                    balance: account.balance.amount(),
+                }),
+            }
+        }
+        totals.sort_by_key(|t| t.currency.code());
+        totals
+    }
+}
+
+/// One currency's share of the exposure report.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CurrencyExposure {
+    pub currency: CurrencyCode,
+    #[schema(example = 42)]
+    pub account_count: i64,
+    /// Sum of balances in smallest unit of `currency`
+    #[schema(example = 1250000)]
+    pub balance: i64,
+    /// Units of the base currency per unit of `currency`
+    #[schema(example = 0.92)]
+    pub rate: f64,
+    /// `balance` in smallest unit of the base currency
+    #[schema(example = 1150000)]
+    pub converted: i64,
+}
+
+/// Balances across all accounts, per currency and in one base currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExposureReport {
+    pub base_currency: CurrencyCode,
+    /// When the rates used were taken
+    pub rates_as_of: DateTime<Utc>,
+    /// Every currency's balance converted to the base currency, summed
+    #[schema(example = 1150000)]
+    pub total: i64,
+    /// Largest converted exposure first
+    pub currencies: Vec<CurrencyExposure>,
+}
+
+impl ExposureReport {
+    /// Converts `balances` into `base` at `rates`.
+    ///
+    /// Returns the first currency `rates` has no quote for, if any.
+    pub fn build(
+        base: CurrencyCode,
+        balances: &[CurrencyBalance],
+        rates: &RateSnapshot,
+    ) -> Result<Self, CurrencyCode> {
+        let mut currencies = balances
+            .iter()
+            .map(|b| {
+                let rate = rates.rate(b.currency, base).ok_or(b.currency)?;
+                Ok(CurrencyExposure {
+                    currency: b.currency,
+                    account_count: b.account_count,
+                    balance: b.balance,
+                    rate,
+                    converted: (b.balance as f64 * rate).round() as i64,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        currencies.sort_by(|a, b| {
+            b.converted
+                .cmp(&a.converted)
+                .then_with(|| a.currency.code().cmp(b.currency.code()))
+        });
+        Ok(Self {
+            base_currency: base,
+            rates_as_of: rates.taken_at,
+            total: currencies.iter().map(|c| c.converted).sum(),
+            currencies,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_converts_and_ranks_currencies() {
+        let rates = RateSnapshot {
+            taken_at: Utc::now(),
+            usd_rates: HashMap::from([
+                (CurrencyCode::USD, 1.0),
+                (CurrencyCode::EUR, 1.1),
+                (CurrencyCode::GBP, 1.25),
+            ]),
+        };
+        let balances = [
+            CurrencyBalance {
+                currency: CurrencyCode::GBP,
+                account_count: 1,
+                balance: 1_000,
+            },
+            CurrencyBalance {
+                currency: CurrencyCode::EUR,
+                account_count: 2,
+                balance: 10_000,
+            },
+        ];
+
+        let report = ExposureReport::build(CurrencyCode::USD, &balances, &rates).unwrap();
+        assert_eq!(report.total, 11_000 + 1_250);
+        assert_eq!(report.currencies[0].currency, CurrencyCode::EUR);
+        assert_eq!(report.currencies[1].converted, 1_250);
+
+        let report = ExposureReport::build(CurrencyCode::EUR, &balances[1..], &rates).unwrap();
+        assert_eq!(report.total, 10_000);
+        assert_eq!(
+            ExposureReport::build(CurrencyCode::INR, &balances, &rates),
+            Err(CurrencyCode::GBP)
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Fee schedules and their assignment to accounts.
+//!
+//! Schedules are immutable once created: to change an account's fees, create
+//! a new schedule and assign it from a future date. Assignments are never
+//! updated or deleted, so the history doubles as an audit trail.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::AccountId;
+use crate::error::DomainError;
+
+/// Basis points in 100%.
+const BPS_PER_UNIT: i128 = 10_000;
+
+/// Unique identifier for a fee schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct FeeScheduleId(Uuid);
+
+impl FeeScheduleId {
+    /// Creates a new random FeeScheduleId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a FeeScheduleId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for FeeScheduleId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for FeeScheduleId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for FeeScheduleId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// Pricing for payments up to a given amount.
+///
+/// The fee is `flat_fee + amount * percentage_bps / 10000`, rounded half up
+/// and then clamped to `min_fee..=max_fee`. All amounts are in minor units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FeeTier {
+    /// Largest payment amount this tier prices; `None` for the open-ended last tier
+    #[serde(default)]
+    #[schema(example = 100000)]
+    pub up_to: Option<i64>,
+    /// Fixed part of the fee
+    #[serde(default)]
+    #[schema(example = 25)]
+    pub flat_fee: i64,
+    /// Proportional part of the fee in basis points (150 = 1.5%)
+    #[serde(default)]
+    #[schema(example = 150)]
+    pub percentage_bps: u32,
+    /// Lowest fee charged in this tier
+    #[serde(default)]
+    #[schema(example = 50)]
+    pub min_fee: Option<i64>,
+    /// Highest fee charged in this tier
+    #[serde(default)]
+    #[schema(example = 2500)]
+    pub max_fee: Option<i64>,
+}
+
+impl FeeTier {
+    /// Computes this tier's fee for `amount`.
+    pub fn fee_for(&self, amount: i64) -> i64 {
+        let proportional =
+            (amount as i128 * self.percentage_bps as i128 + BPS_PER_UNIT / 2) / BPS_PER_UNIT;
+        let fee = (self.flat_fee as i128 + proportional).min(i64::MAX as i128) as i64;
+        let fee = self.min_fee.map_or(fee, |min| fee.max(min));
+        self.max_fee.map_or(fee, |max| fee.min(max))
+    }
+}
+
+/// A named, versioned set of fee tiers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FeeSchedule {
+    pub id: FeeScheduleId,
+    #[schema(example = "Standard 2025")]
+    pub name: String,
+    /// Tiers in ascending `up_to` order; the last one is open-ended
+    pub tiers: Vec<FeeTier>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FeeSchedule {
+    /// Checks that `tiers` price every positive amount exactly once.
+    ///
+    /// Tiers must have strictly ascending `up_to` bounds, end with an
+    /// open-ended tier, and carry non-negative fees with `min_fee <= max_fee`.
+    pub fn validate_tiers(tiers: &[FeeTier]) -> Result<(), DomainError> {
+        let invalid = |msg: String| Err(DomainError::ValidationError(msg));
+        let Some(last) = tiers.last() else {
+            return invalid("A fee schedule needs at least one tier".into());
+        };
+        if last.up_to.is_some() {
+            return invalid("The last fee tier must have no up_to bound".into());
+        }
+
+        let mut previous_bound = 0;
+        for (i, tier) in tiers.iter().enumerate() {
+            if let Some(up_to) = tier.up_to {
+                if i + 1 == tiers.len() || up_to <= previous_bound {
+                    return invalid(format!(
+                        "Tier {}: up_to must be positive and greater than the previous tier's",
+                        i + 1
+                    ));
+                }
+                previous_bound = up_to;
+            } else if i + 1 != tiers.len() {
+                return invalid(format!("Tier {}: only the last tier may omit up_to", i + 1));
+            }
+
+            let negative = tier.flat_fee < 0
+                || tier.min_fee.is_some_and(|m| m < 0)
+                || tier.max_fee.is_some_and(|m| m < 0);
+            if negative {
+                return invalid(format!("Tier {}: fees cannot be negative", i + 1));
+            }
+            if let (Some(min), Some(max)) = (tier.min_fee, tier.max_fee)
+                && min > max
+            {
+                return invalid(format!("Tier {}: min_fee exceeds max_fee", i + 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the tier that prices `amount`.
+    pub fn tier_for(&self, amount: i64) -> Option<&FeeTier> {
+        self.tiers
+            .iter()
+            .find(|tier| tier.up_to.is_none_or(|up_to| amount <= up_to))
+    }
+
+    /// Computes the fee for a payment of `amount`.
+    pub fn fee_for(&self, amount: i64) -> i64 {
+        self.tier_for(amount).map_or(0, |tier| tier.fee_for(amount))
+    }
+}
+
+/// A fee schedule applied to an account from `effective_from` onwards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FeeAssignment {
+    pub account_id: AccountId,
+    pub fee_schedule_id: FeeScheduleId,
+    /// When the schedule starts to apply
+    pub effective_from: DateTime<Utc>,
+    /// When the assignment was recorded
+    pub assigned_at: DateTime<Utc>,
+}
+
+impl FeeAssignment {
+    /// Picks the assignment in force at `at`: the latest to take effect by
+    /// then, with later-recorded assignments winning ties.
+    pub fn effective_at(assignments: &[FeeAssignment], at: DateTime<Utc>) -> Option<&Self> {
+        assignments
+            .iter()
+            .filter(|a| a.effective_from <= at)
+            .max_by_key(|a| (a.effective_from, a.assigned_at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn tier(up_to: Option<i64>, flat_fee: i64, percentage_bps: u32) -> FeeTier {
+        FeeTier {
+            up_to,
+            flat_fee,
+            percentage_bps,
+            min_fee: None,
+            max_fee: None,
+        }
+    }
+
+    #[test]
+    fn test_tier_fee_rounds_and_clamps() {
+        let mut t = tier(None, 25, 150);
+        // 25 + 1.5% of 1_010 = 25 + 15.15
+        assert_eq!(t.fee_for(1_010), 40);
+        // 25 + 1.5% of 1_030 = 25 + 15.45, which rounds to 15
+        assert_eq!(t.fee_for(1_030), 40);
+        assert_eq!(t.fee_for(1_034), 41);
+
+        t.min_fee = Some(100);
+        t.max_fee = Some(500);
+        assert_eq!(t.fee_for(10), 100);
+        assert_eq!(t.fee_for(1_000_000), 500);
+        assert_eq!(tier(None, 0, 10_000).fee_for(i64::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn test_schedule_picks_tier_by_amount() {
+        let schedule = FeeSchedule {
+            id: FeeScheduleId::new(),
+            name: "Standard".into(),
+            tiers: vec![tier(Some(1_000), 10, 0), tier(None, 0, 100)],
+            created_at: Utc::now(),
+        };
+        assert!(FeeSchedule::validate_tiers(&schedule.tiers).is_ok());
+        assert_eq!(schedule.fee_for(1_000), 10);
+        assert_eq!(schedule.fee_for(5_000), 50);
+    }
+
+    #[test]
+    fn test_validate_tiers_rejects_gaps_and_bad_fees() {
+        let cases = [
+            vec![],
+            vec![tier(Some(1_000), 0, 0)],
+            vec![tier(None, 0, 0), tier(None, 0, 0)],
+            vec![
+                tier(Some(500), 0, 0),
+                tier(Some(500), 0, 0),
+                tier(None, 0, 0),
+            ],
+            vec![tier(None, -1, 0)],
+            vec![FeeTier {
+                min_fee: Some(10),
+                max_fee: Some(5),
+                ..FeeTier::default()
+            }],
+        ];
+        for tiers in cases {
+            assert!(FeeSchedule::validate_tiers(&tiers).is_err(), "{:?}", tiers);
+        }
+    }
+
+    #[test]
+    fn test_effective_assignment_applies_prospectively() {
+        let now = Utc::now();
+        let account_id = AccountId::new();
+        let assignment = |days: i64, schedule: FeeScheduleId| FeeAssignment {
+            account_id,
+            fee_schedule_id: schedule,
+            effective_from: now + Duration::days(days),
+            assigned_at: now,
+        };
+        let (old, new) = (FeeScheduleId::new(), FeeScheduleId::new());
+        let history = vec![assignment(-30, old), assignment(7, new)];
+
+        let current = FeeAssignment::effective_at(&history, now).unwrap();
+        assert_eq!(current.fee_schedule_id, old);
+        let later = FeeAssignment::effective_at(&history, now + Duration::days(7)).unwrap();
+        assert_eq!(later.fee_schedule_id, new);
+        assert!(FeeAssignment::effective_at(&history, now - Duration::days(31)).is_none());
+    }
+}
@@ -0,0 +1,276 @@
+//! The float: the system account on the other side of external money.
+//!
+//! Deposits bring money into the ledger from outside and withdrawals send it
+//! back out, so on their own they change customer balances with no
+//! counter-entry. Each currency has a float account that takes the other side
+//! of every such movement, as does the FX provider's side of a cross-currency
+//! transfer. The float's balance is therefore minus the money that came in net,
+//! and customer balances plus the float sum to zero in every currency.
+//!
+//! The float is derived from the transactions rather than stored, so it can
+//! never drift from them; comparing it with the stored customer balances
+//! catches money that moved without a transaction.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{Account, CurrencyCode, Transaction, TransactionType};
+
+/// One currency's float next to the customer balances it offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FloatPosition {
+    pub currency: CurrencyCode,
+    /// Sum of all account balances, in minor units
+    #[schema(example = 1250000)]
+    pub customer_balance: i64,
+    /// Balance of the float account: minus the net money that came in
+    #[schema(example = -1250000)]
+    pub float_balance: i64,
+}
+
+impl FloatPosition {
+    /// What the two sides are off by; 0 when the ledger balances.
+    pub fn imbalance(&self) -> i64 {
+        self.customer_balance + self.float_balance
+    }
+
+    /// Whether customer balances and the float cancel out.
+    pub fn is_balanced(&self) -> bool {
+        self.imbalance() == 0
+    }
+
+    /// Positions per currency from `accounts` and every transaction ever
+    /// made, by currency code.
+    pub fn tally<'a>(
+        accounts: impl IntoIterator<Item = &'a Account>,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+    ) -> Vec<Self> {
+        let mut positions: Vec<Self> = Vec::new();
+        let mut position =
+            |currency: CurrencyCode| match positions.iter().position(|p| p.currency == currency) {
+                Some(index) => index,
+                None => {
+                    positions.push(Self {
+                        currency,
+                        customer_balance: 0,
+                        float_balance: 0,
+                    });
+                    positions.len() - 1
+                }
+            };
+
+        let mut balances = Vec::new();
+        for account in accounts {
+            balances.push((position(account.currency()), account.balance.amount()));
+        }
+        let mut nets = Vec::new();
+        for flow in FloatFlow::tally(transactions) {
+            nets.push((position(flow.currency), flow.net));
+        }
+
+        for (index, balance) in balances {
+            positions[index].customer_balance += balance;
+        }
+        for (index, net) in nets {
+            positions[index].float_balance -= net;
+        }
+        positions.sort_by_key(|p| p.currency.code());
+        positions
+    }
+}
+
+/// Money that entered or left the ledger in one currency on one UTC day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FloatFlow {
+    pub date: NaiveDate,
+    pub currency: CurrencyCode,
+    /// Deposited, in minor units
+    #[schema(example = 500000)]
+    pub deposits: i64,
+    /// Withdrawn, including captured holds
+    #[schema(example = 320000)]
+    pub withdrawals: i64,
+    /// Credited to accounts in this currency by cross-currency transfers
+    #[schema(example = 9200)]
+    pub converted_in: i64,
+    /// Debited from accounts in this currency by cross-currency transfers
+    #[schema(example = 0)]
+    pub converted_out: i64,
+    /// Money in minus money out
+    #[schema(example = 189200)]
+    pub net: i64,
+}
+
+impl FloatFlow {
+    fn new(date: NaiveDate, currency: CurrencyCode) -> Self {
+        Self {
+            date,
+            currency,
+            deposits: 0,
+            withdrawals: 0,
+            converted_in: 0,
+            converted_out: 0,
+            net: 0,
+        }
+    }
+
+    /// Sums `transactions` into one flow per day and currency that saw
+    /// external money, by day then currency code. Same-currency transfers
+    /// stay within the ledger and are left out.
+    pub fn tally<'a>(transactions: impl IntoIterator<Item = &'a Transaction>) -> Vec<Self> {
+        let mut flows: Vec<Self> = Vec::new();
+        let mut add = |date: NaiveDate, currency: CurrencyCode, apply: &dyn Fn(&mut Self)| {
+            let index = match flows
+                .iter()
+                .position(|f| f.date == date && f.currency == currency)
+            {
+                Some(index) => index,
+                None => {
+                    flows.push(Self::new(date, currency));
+                    flows.len() - 1
+                }
+            };
+            apply(&mut flows[index]);
+        };
+
+        for tx in transactions {
+            let date = tx.created_at.date_naive();
+            let amount = tx.amount.amount();
+            match (tx.transaction_type, tx.conversion) {
+                (TransactionType::Deposit, _) => {
+                    add(date, tx.amount.currency(), &|f| f.deposits += amount)
+                }
+                (TransactionType::Withdrawal, _) => {
+                    add(date, tx.amount.currency(), &|f| f.withdrawals += amount)
+                }
+                (TransactionType::Transfer, Some(conversion)) => {
+                    let credited = conversion.converted_amount;
+                    add(date, tx.amount.currency(), &|f| f.converted_out += amount);
+                    add(date, credited.currency(), &|f| {
+                        f.converted_in += credited.amount()
+                    });
+                }
+                (TransactionType::Transfer, None) => {}
+            }
+        }
+
+        for flow in &mut flows {
+            flow.net = flow.deposits + flow.converted_in - flow.withdrawals - flow.converted_out;
+        }
+        flows.sort_by_key(|f| (f.date, f.currency.code()));
+        flows
+    }
+}
+
+/// The float in every currency now, and the external flows behind it over a
+/// range of days.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FloatReport {
+    /// Current position per currency, by currency code
+    pub positions: Vec<FloatPosition>,
+    /// Days without external money are left out
+    pub flows: Vec<FloatFlow>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::domain::{AccountId, DynMoney, FxConversion};
+
+    #[test]
+    fn test_position_balances_when_float_offsets_customers() {
+        let position = FloatPosition {
+            currency: CurrencyCode::USD,
+            customer_balance: 700,
+            float_balance: -700,
+        };
+        assert!(position.is_balanced());
+
+        let drifted = FloatPosition {
+            float_balance: -650,
+            ..position
+        };
+        assert_eq!(drifted.imbalance(), 50);
+        assert!(!drifted.is_balanced());
+    }
+
+    #[test]
+    fn test_tally_positions_offsets_every_deposit_and_withdrawal() {
+        let usd = |amount| DynMoney::new(amount, CurrencyCode::USD).unwrap();
+        let mut alice = Account::new("Alice".into(), CurrencyCode::USD).unwrap();
+        let bob = Account::new("Bob".into(), CurrencyCode::EUR).unwrap();
+        alice.deposit(usd(1_000)).unwrap();
+        alice.withdraw(usd(400)).unwrap();
+        let transactions = vec![
+            Transaction::deposit(alice.id, usd(1_000), None, None),
+            Transaction::withdrawal(alice.id, usd(400), None, None),
+        ];
+
+        let positions = FloatPosition::tally([&alice, &bob], &transactions);
+        assert_eq!(
+            positions,
+            vec![
+                FloatPosition {
+                    currency: CurrencyCode::EUR,
+                    customer_balance: 0,
+                    float_balance: 0,
+                },
+                FloatPosition {
+                    currency: CurrencyCode::USD,
+                    customer_balance: 600,
+                    float_balance: -600,
+                },
+            ]
+        );
+        assert!(positions.iter().all(FloatPosition::is_balanced));
+    }
+
+    #[test]
+    fn test_tally_sums_external_money_per_day_and_currency() {
+        let (alice, bob) = (AccountId::new(), AccountId::new());
+        let usd = |amount| DynMoney::new(amount, CurrencyCode::USD).unwrap();
+        let at = |day| Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        let on = |day, mut tx: Transaction| {
+            tx.created_at = at(day);
+            tx
+        };
+        let conversion = FxConversion::at_rate(usd(100), CurrencyCode::EUR, 0.9).unwrap();
+        let transactions = vec![
+            on(2, Transaction::withdrawal(alice, usd(200), None, None)),
+            on(1, Transaction::deposit(alice, usd(1_000), None, None)),
+            on(1, Transaction::transfer(alice, bob, usd(300), None, None)),
+            on(
+                2,
+                Transaction::transfer(alice, bob, usd(100), None, None)
+                    .with_conversion(Some(conversion)),
+            ),
+        ];
+
+        let flows = FloatFlow::tally(&transactions);
+        let day = |d| at(d).date_naive();
+        assert_eq!(
+            flows,
+            vec![
+                FloatFlow {
+                    deposits: 1_000,
+                    net: 1_000,
+                    ..FloatFlow::new(day(1), CurrencyCode::USD)
+                },
+                FloatFlow {
+                    converted_in: 90,
+                    net: 90,
+                    ..FloatFlow::new(day(2), CurrencyCode::EUR)
+                },
+                FloatFlow {
+                    withdrawals: 200,
+                    converted_out: 100,
+                    net: -300,
+                    ..FloatFlow::new(day(2), CurrencyCode::USD)
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Holds for two-phase (authorize, then capture or void) payments.
+//!
+//! A hold reserves part of an account's balance without moving it: the
+//! account's `held` total goes up and its available balance goes down. The
+//! hold is later captured (the money is withdrawn), voided, or expires, and
+//! the reservation is released either way.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{AccountId, CurrencyCode, TransactionId};
+use crate::error::DomainError;
+
+/// How long a hold lasts when the request does not say.
+pub const DEFAULT_HOLD_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Longest a hold may last before it expires.
+pub const MAX_HOLD_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Unique identifier for a hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct HoldId(Uuid);
+
+impl HoldId {
+    /// Creates a new random HoldId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a HoldId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for HoldId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for HoldId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for HoldId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// Where a hold is in its lifecycle. Only `Authorized` holds reserve funds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HoldStatus {
+    /// Funds are reserved, waiting for capture or void
+    Authorized,
+    /// Funds were withdrawn
+    Captured,
+    /// Released without moving funds
+    Voided,
+    /// Released because it was not captured in time
+    Expired,
+}
+
+impl AsRef<str> for HoldStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Authorized => "AUTHORIZED",
+            Self::Captured => "CAPTURED",
+            Self::Voided => "VOIDED",
+            Self::Expired => "EXPIRED",
+        }
+    }
+}
+
+impl std::fmt::Display for HoldStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl std::str::FromStr for HoldStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "AUTHORIZED" => Ok(Self::Authorized),
+            "CAPTURED" => Ok(Self::Captured),
+            "VOIDED" => Ok(Self::Voided),
+            "EXPIRED" => Ok(Self::Expired),
+            _ => Err(format!("Unknown hold status: {}", s)),
+        }
+    }
+}
+
+/// Funds reserved on an account by an authorization.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Hold {
+    pub id: HoldId,
+    pub account_id: AccountId,
+    /// Amount reserved, in minor units
+    #[schema(example = 4999)]
+    pub amount: i64,
+    pub currency: CurrencyCode,
+    pub reference: Option<String>,
+    pub purpose_code: Option<String>,
+    pub status: HoldStatus,
+    /// When an uncaptured hold is released
+    pub expires_at: DateTime<Utc>,
+    /// Amount withdrawn on capture; at most `amount`
+    pub captured_amount: Option<i64>,
+    /// Withdrawal made on capture
+    pub transaction_id: Option<TransactionId>,
+    pub created_at: DateTime<Utc>,
+    /// When the hold was captured, voided or expired
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl Hold {
+    /// Returns `true` while the hold still reserves funds.
+    pub fn is_open(&self) -> bool {
+        self.status == HoldStatus::Authorized
+    }
+
+    /// Fails unless the hold can still be captured or released.
+    pub fn check_open(&self) -> Result<(), DomainError> {
+        if !self.is_open() {
+            return Err(DomainError::ValidationError(format!(
+                "Hold is {}, not AUTHORIZED",
+                self.status
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that `amount` can be captured from this hold at `now`.
+    pub fn check_capture(&self, amount: i64, now: DateTime<Utc>) -> Result<(), DomainError> {
+        self.check_open()?;
+        if self.expires_at <= now {
+            return Err(DomainError::ValidationError("Hold has expired".into()));
+        }
+        if amount <= 0 || amount > self.amount {
+            return Err(DomainError::ValidationError(format!(
+                "Capture amount must be between 1 and {}",
+                self.amount
+            )));
+        }
+        Ok(())
+    }
+
+    /// Marks the hold captured by the withdrawal `transaction_id` of `amount`.
+    pub fn capture(&mut self, amount: i64, transaction_id: TransactionId, now: DateTime<Utc>) {
+        self.status = HoldStatus::Captured;
+        self.captured_amount = Some(amount);
+        self.transaction_id = Some(transaction_id);
+        self.resolved_at = Some(now);
+    }
+
+    /// Marks the hold released with `status` (`Voided` or `Expired`).
+    pub fn release(&mut self, status: HoldStatus, now: DateTime<Utc>) {
+        self.status = status;
+        self.resolved_at = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_capture_checks_status_expiry_and_amount() {
+        let now = Utc::now();
+        let mut hold = Hold {
+            id: HoldId::new(),
+            account_id: AccountId::new(),
+            amount: 500,
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            status: HoldStatus::Authorized,
+            expires_at: now + Duration::hours(1),
+            captured_amount: None,
+            transaction_id: None,
+            created_at: now,
+            resolved_at: None,
+        };
+        assert!(hold.check_capture(500, now).is_ok());
+        assert!(hold.check_capture(501, now).is_err());
+        assert!(hold.check_capture(0, now).is_err());
+        assert!(hold.check_capture(100, now + Duration::hours(1)).is_err());
+
+        let tx = TransactionId::new();
+        hold.capture(300, tx, now);
+        assert!(!hold.is_open());
+        assert_eq!(hold.captured_amount, Some(300));
+        assert_eq!(hold.transaction_id, Some(tx));
+        assert!(hold.check_capture(100, now).is_err());
+        assert_eq!("captured".parse::<HoldStatus>(), Ok(HoldStatus::Captured));
+    }
+}
@@ -0,0 +1,82 @@
+//! Responses stored by idempotency key.
+//!
+//! A payment sent with an idempotency key is made once; repeating the request
+//! returns the response stored the first time instead of a rebuilt one. The
+//! hash of the first request is kept next to it, so reusing a key for a
+//! different payment is refused rather than silently answered with the old
+//! response.
+//!
+//! Keys are scoped to the endpoint they were sent to, so the same key used on
+//! two routes stores two responses.
+//!
+//! While the first request is still being answered its key holds an
+//! in-progress record instead, so a concurrent retry is refused rather than
+//! made a second time. A reservation left behind by a request that never
+//! finished, such as one on a server that crashed, lapses after
+//! [`IDEMPOTENCY_RESERVATION_TTL`] and the next request takes it over.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Endpoint the payment operations store their responses under.
+pub const PAYMENTS_IDEMPOTENCY_ENDPOINT: &str = "payments";
+
+/// How long an in-progress record holds its key.
+pub const IDEMPOTENCY_RESERVATION_TTL: TimeDelta = TimeDelta::minutes(5);
+
+/// The response a request made with an idempotency key got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    /// Route the key was sent to, such as `POST /api/accounts`
+    pub endpoint: String,
+    pub key: String,
+    /// SHA-256 of the operation and request the key was first used with
+    pub request_hash: String,
+    /// HTTP status the response was returned with
+    pub status: u16,
+    /// The response returned, as JSON
+    pub response: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl IdempotencyRecord {
+    /// Status an in-progress record is stored with (`102 Processing`).
+    pub const IN_PROGRESS_STATUS: u16 = 102;
+
+    /// A record reserving `key` for the request hashing to `request_hash`
+    /// while it is answered. Each reservation carries its own token, so the
+    /// request that made it can tell it apart from a concurrent one's.
+    pub fn in_progress(
+        endpoint: impl Into<String>,
+        key: impl Into<String>,
+        request_hash: impl Into<String>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            key: key.into(),
+            request_hash: request_hash.into(),
+            status: Self::IN_PROGRESS_STATUS,
+            response: serde_json::json!({ "reservation": uuid::Uuid::new_v4() }),
+            created_at,
+        }
+    }
+
+    /// Whether the request this record was stored for is still being
+    /// answered.
+    pub fn is_in_progress(&self) -> bool {
+        self.status == Self::IN_PROGRESS_STATUS
+    }
+
+    /// Whether this is a reservation that lapsed before `now`, which a new
+    /// request may take over.
+    pub fn is_expired_reservation(&self, now: DateTime<Utc>) -> bool {
+        self.is_in_progress() && self.created_at <= now - IDEMPOTENCY_RESERVATION_TTL
+    }
+
+    /// Whether this record was stored for the request hashing to
+    /// `request_hash`.
+    pub fn matches(&self, request_hash: &str) -> bool {
+        self.request_hash == request_hash
+    }
+}
@@ -0,0 +1,67 @@
+//! Per-account limits tuned by risk teams.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::DomainError;
+
+/// Limits for one account, applied on top of the service-wide caps.
+///
+/// All amounts are in minor units; `None` leaves the dimension unlimited
+/// (apart from any global cap). When both an account limit and a global
+/// cap are set, the lower one wins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AccountLimits {
+    /// Largest amount a single deposit, withdrawal or outgoing transfer may move
+    #[serde(default)]
+    #[schema(example = 100000)]
+    pub max_transaction_amount: Option<i64>,
+    /// Largest balance a deposit or incoming transfer may leave behind
+    #[serde(default)]
+    #[schema(example = 1000000)]
+    pub max_balance: Option<i64>,
+    /// Most the account may send out (withdrawals and transfers) in any 24 hours
+    #[serde(default)]
+    #[schema(example = 250000)]
+    pub daily_debit_limit: Option<i64>,
+}
+
+impl AccountLimits {
+    /// Returns `true` if no limit is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Rejects zero or negative limits.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        let fields = [
+            ("max_transaction_amount", self.max_transaction_amount),
+            ("max_balance", self.max_balance),
+            ("daily_debit_limit", self.daily_debit_limit),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value.filter(|v| *v <= 0) {
+                return Err(DomainError::ValidationError(format!(
+                    "{} must be positive, got {}",
+                    name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_non_positive_limits() {
+        assert!(AccountLimits::default().validate().is_ok());
+        let limits = AccountLimits {
+            daily_debit_limit: Some(0),
+            ..AccountLimits::default()
+        };
+        assert!(limits.validate().is_err());
+    }
+}
@@ -0,0 +1,228 @@
+//! Encrypted memos: payment descriptions the platform cannot read.
+//!
+//! A customer registers the keys it encrypts memos with on its account. Only
+//! a key's metadata is stored — the algorithm and a fingerprint that tells a
+//! reader which key to decrypt with — never the key itself. A payment may
+//! then carry a memo encrypted under an active key of the payer or the
+//! payee; the ciphertext is stored and returned exactly as sent.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::AccountId;
+use crate::error::DomainError;
+
+/// Most active memo keys a single account may hold.
+pub const MAX_MEMO_KEYS_PER_ACCOUNT: usize = 10;
+
+/// Longest memo ciphertext accepted, in base64 characters.
+pub const MAX_MEMO_CIPHERTEXT_LEN: usize = 8_192;
+
+const MAX_ALGORITHM_LEN: usize = 40;
+const MIN_FINGERPRINT_LEN: usize = 8;
+const MAX_FINGERPRINT_LEN: usize = 128;
+
+/// Unique identifier for a memo key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct MemoKeyId(Uuid);
+
+impl MemoKeyId {
+    /// Creates a new random MemoKeyId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a MemoKeyId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for MemoKeyId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for MemoKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for MemoKeyId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// A key an account encrypts memos with, as known to the service: its
+/// metadata only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MemoKey {
+    pub id: MemoKeyId,
+    /// Account that holds the key
+    pub account_id: AccountId,
+    /// Cipher the customer encrypts with; not interpreted by the service
+    #[schema(example = "xchacha20poly1305")]
+    pub algorithm: String,
+    /// The customer's fingerprint of the key, for picking the key to
+    /// decrypt with
+    #[schema(example = "sha256:9f86d081884c7d65")]
+    pub fingerprint: String,
+    pub created_at: DateTime<Utc>,
+    /// When the key was retired; memos already written with it are kept,
+    /// but new ones may not use it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+impl MemoKey {
+    /// A new active key for `account_id`, after checking `algorithm` is
+    /// 1-40 lower-case letters, digits, `-` or `_` and `fingerprint` is
+    /// 8-128 visible ASCII characters.
+    pub fn new(
+        account_id: AccountId,
+        algorithm: &str,
+        fingerprint: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Self, DomainError> {
+        let algorithm = algorithm.trim();
+        let valid_algorithm = (1..=MAX_ALGORITHM_LEN).contains(&algorithm.len())
+            && algorithm
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid_algorithm {
+            return Err(DomainError::ValidationError(format!(
+                "Invalid memo key algorithm '{}': use 1-{} lower-case letters, digits, '-' or '_'",
+                algorithm, MAX_ALGORITHM_LEN
+            )));
+        }
+        let fingerprint = fingerprint.trim();
+        let valid_fingerprint = (MIN_FINGERPRINT_LEN..=MAX_FINGERPRINT_LEN)
+            .contains(&fingerprint.len())
+            && fingerprint.chars().all(|c| c.is_ascii_graphic());
+        if !valid_fingerprint {
+            return Err(DomainError::ValidationError(format!(
+                "Invalid memo key fingerprint: use {}-{} visible ASCII characters",
+                MIN_FINGERPRINT_LEN, MAX_FINGERPRINT_LEN
+            )));
+        }
+        Ok(Self {
+            id: MemoKeyId::new(),
+            account_id,
+            algorithm: algorithm.to_string(),
+            fingerprint: fingerprint.to_string(),
+            created_at: now,
+            retired_at: None,
+        })
+    }
+
+    /// Whether new memos may be encrypted with this key.
+    pub fn is_active(&self) -> bool {
+        self.retired_at.is_none()
+    }
+}
+
+/// A memo encrypted by the customer, stored and returned as sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EncryptedMemo {
+    /// Memo key of the payer or the payee the memo is encrypted with
+    pub key_id: MemoKeyId,
+    /// Standard base64 of everything the algorithm needs to decrypt, such
+    /// as nonce, ciphertext and tag
+    #[schema(example = "3q2+7wAAAAB0aGlzIGlzIG5vdCByZWFsbHkgZW5jcnlwdGVk")]
+    pub ciphertext: String,
+}
+
+impl EncryptedMemo {
+    /// Checks the ciphertext is non-empty, padded standard base64 of at most
+    /// [`MAX_MEMO_CIPHERTEXT_LEN`] characters.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        let text = self.ciphertext.as_bytes();
+        if text.is_empty() || text.len() > MAX_MEMO_CIPHERTEXT_LEN {
+            return Err(DomainError::ValidationError(format!(
+                "Memo ciphertext must be 1 to {} characters",
+                MAX_MEMO_CIPHERTEXT_LEN
+            )));
+        }
+        let data = text.strip_suffix(b"==").or_else(|| text.strip_suffix(b"="));
+        let data = data.unwrap_or(text);
+        let is_base64 = text.len().is_multiple_of(4)
+            && data
+                .iter()
+                .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/');
+        if !is_base64 {
+            return Err(DomainError::ValidationError(
+                "Memo ciphertext must be standard base64".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_key_metadata_is_validated() {
+        let account = AccountId::new();
+        let key = MemoKey::new(
+            account,
+            " xchacha20poly1305 ",
+            "sha256:9f86d081",
+            Utc::now(),
+        )
+        .unwrap();
+        assert_eq!(key.algorithm, "xchacha20poly1305");
+        assert!(key.is_active());
+
+        for (algorithm, fingerprint) in [
+            ("", "sha256:9f86d081"),
+            ("AES-GCM", "sha256:9f86d081"),
+            ("aes gcm", "sha256:9f86d081"),
+            ("aes-256-gcm", "short"),
+            ("aes-256-gcm", "has a space"),
+        ] {
+            assert!(
+                MemoKey::new(account, algorithm, fingerprint, Utc::now()).is_err(),
+                "{algorithm} {fingerprint}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_memo_ciphertext_must_be_base64() {
+        let memo = |ciphertext: &str| EncryptedMemo {
+            key_id: MemoKeyId::new(),
+            ciphertext: ciphertext.into(),
+        };
+        for valid in ["AAAA", "3q2+7w==", "3q2+7wA=", "ab/+"] {
+            assert!(memo(valid).validate().is_ok(), "{valid}");
+        }
+        for invalid in ["", "AAA", "AA=A", "3q2-7w==", "a===", "plain text memo"] {
+            assert!(memo(invalid).validate().is_err(), "{invalid}");
+        }
+        assert!(
+            memo(&"A".repeat(MAX_MEMO_CIPHERTEXT_LEN + 4))
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -1,13 +1,95 @@
 //! Domain models for the payment service.
 
 pub mod account;
+pub mod actor;
+pub mod alias;
 pub mod api_key;
+pub mod balance;
+pub mod beneficiary;
+pub mod blocked;
+pub mod change;
+pub mod event;
+pub mod export;
+pub mod exposure;
+pub mod fee;
+pub mod float;
+pub mod hold;
+pub mod idempotency;
+pub mod limits;
+pub mod memo;
+pub mod mode;
 pub mod money;
+pub mod owner;
+pub mod quote;
+pub mod risk;
+pub mod schedule;
+pub mod settlement;
+pub mod spending;
+pub mod statement;
+pub mod storage;
 pub mod transaction;
+pub mod usage;
 pub mod webhook;
 
-pub use account::{Account, AccountId};
-pub use api_key::{ApiKey, ApiKeyId};
-pub use money::{CurrencyCode, DynMoney};
-pub use transaction::{Transaction, TransactionId, TransactionType};
-pub use webhook::{WebhookEndpoint, WebhookEndpointId, WebhookEvent, WebhookStatus};
+pub use account::{Account, AccountId, AccountMerge, AccountStatus};
+pub use actor::ActorContext;
+pub use alias::{AccountAlias, AccountRef, Alias, MAX_ALIASES_PER_ACCOUNT};
+pub use api_key::{
+    ApiKey, ApiKeyAudit, ApiKeyAuditAction, ApiKeyAuditEntry, ApiKeyId, ApiKeyScope,
+    DEFAULT_SESSION_TTL_SECS, LAST_USED_RESOLUTION_SECS, MAX_RATE_LIMIT_PER_MINUTE,
+    MAX_SESSION_TTL_SECS, SessionToken,
+};
+pub use balance::{BalanceHistory, DailyBalance};
+pub use beneficiary::{
+    Beneficiary, BeneficiaryId, BeneficiaryStatus, MAX_MICRO_DEPOSIT, MAX_VERIFICATION_ATTEMPTS,
+};
+pub use blocked::BlockedTransaction;
+pub use change::{ChangeAction, ChangeRequest, ChangeRequestId, ChangeStatus};
+pub use event::{DomainEvent, EVENT_CATALOG, EventField, EventSpec, LoggedEvent, event_spec};
+pub use export::{
+    Export, ExportDownload, ExportId, ExportRequest, ExportStatus, JournalExportFormat,
+    SettlementExportFormat,
+};
+pub use exposure::{CurrencyBalance, CurrencyExposure, ExposureReport, RateSnapshot};
+pub use fee::{FeeAssignment, FeeSchedule, FeeScheduleId, FeeTier};
+pub use float::{FloatFlow, FloatPosition, FloatReport};
+pub use hold::{DEFAULT_HOLD_TTL_SECS, Hold, HoldId, HoldStatus, MAX_HOLD_TTL_SECS};
+pub use idempotency::{
+    IDEMPOTENCY_RESERVATION_TTL, IdempotencyRecord, PAYMENTS_IDEMPOTENCY_ENDPOINT,
+};
+pub use limits::AccountLimits;
+pub use memo::{
+    EncryptedMemo, MAX_MEMO_CIPHERTEXT_LEN, MAX_MEMO_KEYS_PER_ACCOUNT, MemoKey, MemoKeyId,
+};
+pub use mode::Mode;
+pub use money::{
+    AmountFormat, CurrencyCode, DynMoney, MAX_DISPLAY_DECIMALS, PositiveAmount, Rounding,
+};
+pub use owner::{Owner, OwnerId};
+pub use quote::{DEFAULT_QUOTE_TTL_SECS, MAX_QUOTE_TTL_SECS, Quote, QuoteId};
+pub use risk::{RiskAssessment, RiskDecision};
+pub use schedule::{
+    MAX_SCHEDULE_INTERVAL_SECS, MIN_SCHEDULE_INTERVAL_SECS, PaymentSchedule, ScheduledPayment,
+    ScheduledPaymentId, ScheduledPaymentStatus,
+};
+pub use settlement::{CurrencyTotal, SettlementBatch, SettlementBatchId, SettlementBatchStatus};
+pub use spending::{SpendingRules, normalize_purpose_code};
+pub use statement::{
+    AccountStatement, Statement, StatementDownload, StatementFormat, StatementId, StatementLine,
+    StatementPeriod,
+};
+pub use storage::{QuotaWarning, StorageCounts, StorageReport, StorageResource, StorageUsage};
+pub use transaction::{
+    Counterparty, FxConversion, Transaction, TransactionCursor, TransactionFilter, TransactionId,
+    TransactionSearch, TransactionType,
+};
+pub use usage::{
+    ApiKeyUsage, ApiKeyUsageBucket, ApiKeyVolumeBucket, USAGE_WINDOW_HOURS, UsageWindow,
+    VolumeTotal, usage_hour, usage_window_start,
+};
+pub use webhook::{
+    DeadLetterFilter, MAX_WEBHOOK_PAYLOAD_BYTES, MAX_WEBHOOK_TIMEOUT_MS, NewWebhookEndpoint,
+    WebhookDeliveryFilter, WebhookEndpoint, WebhookEndpointId, WebhookEvent,
+    WebhookSignatureAlgorithm, WebhookStatus, WebhookTimeouts, event_pattern_matches,
+    validate_event_patterns, webhook_delivery_payload,
+};
@@ -0,0 +1,72 @@
+//! Test and live modes.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Whether an API key or account handles real money.
+///
+/// Every key and account is in one mode. A test key only sees and moves
+/// money between test accounts, so integrations can be exercised against
+/// the same server without touching live balances.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    Test,
+    /// Real money; keys and accounts recorded before modes existed are live
+    #[default]
+    Live,
+}
+
+impl Mode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Test => "test",
+            Self::Live => "live",
+        }
+    }
+
+    /// Prefix of the secret of an API key in this mode.
+    pub fn key_prefix(&self) -> &'static str {
+        match self {
+            Self::Test => "sk_test_",
+            Self::Live => "sk_live_",
+        }
+    }
+}
+
+impl std::fmt::Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "test" => Ok(Self::Test),
+            "live" => Ok(Self::Live),
+            _ => Err(format!("Unknown mode: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_round_trips_through_its_name() {
+        for mode in [Mode::Test, Mode::Live] {
+            assert_eq!(mode.to_string().parse::<Mode>().unwrap(), mode);
+            assert_eq!(
+                serde_json::to_string(&mode).unwrap(),
+                format!("\"{}\"", mode)
+            );
+        }
+        assert_eq!("LIVE".parse::<Mode>().unwrap(), Mode::Live);
+        assert!("sandbox".parse::<Mode>().is_err());
+        assert_eq!(Mode::default(), Mode::Live);
+    }
+}
@@ -5,13 +5,15 @@
 
 // Re-export type-safe currency types from exchange-rates
 pub use exchange_rates::{
-    Currency, CurrencyCode, EUR, GBP, INR, Money, USD, convert, convert_at_base_rate,
-    convert_dynamic, get_all_rates, get_base_rate, get_rate, get_rate_dynamic,
+    Currency, CurrencyCode, CurrencyInfo, CurrencyRegistry, EUR, GBP, INR, Money, RegistryError,
+    USD, convert, convert_at_base_rate, convert_dynamic, get_all_rates, get_base_rate, get_rate,
+    get_rate_dynamic,
 };
 
 use crate::error::DomainError;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 /// Dynamic money for runtime operations (API/DB layer).
 /// Uses the exchange-rates library for type-safe conversions internally.
@@ -30,6 +32,12 @@ impl DynMoney {
         Ok(Self { amount, currency })
     }
 
+    /// Creates an account balance, which unlike an amount may be negative
+    /// when the account is overdrawn.
+    pub fn balance(amount: i64, currency: CurrencyCode) -> Self {
+        Self { amount, currency }
+    }
+
     /// Creates a zero-value DynMoney for the given currency.
     pub fn zero(currency: CurrencyCode) -> Self {
         Self {
@@ -109,12 +117,237 @@ impl DynMoney {
     }
 }
 
+/// An amount of money to move, in minor units, that is at least 1.
+///
+/// Request DTOs carry amounts as this type, so a zero or negative amount is
+/// refused when the request is parsed rather than by each operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "i64", into = "i64")]
+pub struct PositiveAmount(i64);
+
+impl PositiveAmount {
+    /// Fails with [`DomainError::NonPositiveAmount`] unless `amount` is at
+    /// least 1.
+    pub fn new(amount: i64) -> Result<Self, DomainError> {
+        if amount <= 0 {
+            return Err(DomainError::NonPositiveAmount(amount));
+        }
+        Ok(Self(amount))
+    }
+
+    /// Returns the amount in smallest currency unit.
+    pub fn get(self) -> i64 {
+        self.0
+    }
+}
+
+impl TryFrom<i64> for PositiveAmount {
+    type Error = DomainError;
+
+    fn try_from(amount: i64) -> Result<Self, Self::Error> {
+        Self::new(amount)
+    }
+}
+
+impl From<PositiveAmount> for i64 {
+    fn from(amount: PositiveAmount) -> Self {
+        amount.0
+    }
+}
+
+impl PartialEq<i64> for PositiveAmount {
+    fn eq(&self, other: &i64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<PositiveAmount> for i64 {
+    fn eq(&self, other: &PositiveAmount) -> bool {
+        *self == other.0
+    }
+}
+
+impl fmt::Display for PositiveAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for PositiveAmount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let amount: i64 = s
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' is not a whole number of minor units", s))?;
+        Self::new(amount).map_err(|e| e.to_string())
+    }
+}
+
+impl utoipa::PartialSchema for PositiveAmount {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::Integer)
+            .format(Some(utoipa::openapi::SchemaFormat::KnownFormat(
+                utoipa::openapi::KnownFormat::Int64,
+            )))
+            .minimum(Some(1))
+            .description(Some("Amount in smallest currency unit, at least 1"))
+            .into()
+    }
+}
+
+impl utoipa::ToSchema for PositiveAmount {}
+
+impl DynMoney {
+    /// An amount to move, which unlike [`DynMoney::new`] cannot fail.
+    pub fn positive(amount: PositiveAmount, currency: CurrencyCode) -> Self {
+        Self {
+            amount: amount.get(),
+            currency,
+        }
+    }
+}
+
 impl fmt::Display for DynMoney {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let major = self.amount / 100;
-        let minor = (self.amount % 100).abs();
-        write!(f, "{}{}.{:02}", self.currency.symbol(), major, minor)
+        f.write_str(&AmountFormat::default().format_money(self))
+    }
+}
+
+/// Most decimals an [`AmountFormat`] may fix amounts to.
+pub const MAX_DISPLAY_DECIMALS: u32 = 6;
+
+/// How digits dropped by an [`AmountFormat`] are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Round to the nearest value, halves away from zero
+    #[default]
+    HalfUp,
+    /// Drop the digits
+    Truncate,
+}
+
+/// How amounts are written for people to read: in statements, their emails
+/// and the CLI.
+///
+/// By default every currency gets its own number of decimals, so no digit
+/// is lost. Fixing `decimals` writes every currency with that many instead,
+/// padding with zeros or dropping digits according to `rounding`. Files
+/// other systems import (payment files, journals) always use the currency's
+/// own decimals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AmountFormat {
+    /// Decimals for every currency; `None` uses each currency's own
+    pub decimals: Option<u32>,
+    pub rounding: Rounding,
+}
+
+impl AmountFormat {
+    /// Number of decimals `currency` has, e.g. 2 for cents.
+    pub fn currency_decimals(currency: CurrencyCode) -> u32 {
+        (currency.minor_units_per_major().max(1) as u32).ilog10()
+    }
+
+    /// Number of decimals amounts in `currency` are written with.
+    pub fn decimals_for(&self, currency: CurrencyCode) -> u32 {
+        self.decimals
+            .unwrap_or_else(|| Self::currency_decimals(currency))
+    }
+
+    /// Writes `minor` units of `currency` in major units, e.g. `1234` USD as
+    /// `12.34`.
+    pub fn format(&self, minor: i64, currency: CurrencyCode) -> String {
+        let exact = Self::currency_decimals(currency);
+        let decimals = self.decimals_for(currency);
+        let mut value = minor as i128;
+        if decimals >= exact {
+            value *= 10i128.pow(decimals - exact);
+        } else {
+            let divisor = 10i128.pow(exact - decimals);
+            let dropped = value % divisor;
+            value /= divisor;
+            if self.rounding == Rounding::HalfUp && dropped.abs() * 2 >= divisor {
+                value += dropped.signum();
+            }
+        }
+        format_decimal(value, decimals)
+    }
+
+    /// Writes `money` with its currency symbol, e.g. `-$12.34`.
+    pub fn format_money(&self, money: &DynMoney) -> String {
+        let amount = self.format(money.amount, money.currency);
+        match amount.strip_prefix('-') {
+            Some(amount) => format!("-{}{}", money.currency.symbol(), amount),
+            None => format!("{}{}", money.currency.symbol(), amount),
+        }
+    }
+}
+
+/// Parses a policy such as `decimals=2,rounding=truncate`. `decimals` is a
+/// number up to [`MAX_DISPLAY_DECIMALS`] or `currency`; `rounding` is
+/// `half-up` or `truncate`. Settings left out keep their defaults.
+impl FromStr for AmountFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut format = Self::default();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let Some((key, value)) = entry.split_once('=') else {
+                return Err(format!(
+                    "Invalid amount format setting '{}': expected key=value",
+                    entry
+                ));
+            };
+            let value = value.trim().to_ascii_lowercase();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "decimals" if value == "currency" => format.decimals = None,
+                "decimals" => match value.parse() {
+                    Ok(decimals) if decimals <= MAX_DISPLAY_DECIMALS => {
+                        format.decimals = Some(decimals)
+                    }
+                    _ => {
+                        return Err(format!(
+                            "Invalid decimals '{}': expected currency or 0 to {}",
+                            value, MAX_DISPLAY_DECIMALS
+                        ));
+                    }
+                },
+                "rounding" => {
+                    format.rounding = match value.as_str() {
+                        "half-up" => Rounding::HalfUp,
+                        "truncate" => Rounding::Truncate,
+                        other => {
+                            return Err(format!(
+                                "Unknown rounding '{}': use half-up or truncate",
+                                other
+                            ));
+                        }
+                    }
+                }
+                other => return Err(format!("Unknown amount format setting: {}", other)),
+            }
+        }
+        Ok(format)
+    }
+}
+
+/// Writes `value`, scaled by `10^decimals`, as a decimal number.
+fn format_decimal(value: i128, decimals: u32) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let value = value.abs();
+    if decimals == 0 {
+        return format!("{}{}", sign, value);
     }
+    let scale = 10i128.pow(decimals);
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        value / scale,
+        value % scale,
+        width = decimals as usize
+    )
 }
 
 #[cfg(test)]
@@ -134,6 +367,29 @@ mod tests {
         assert!(matches!(result, Err(DomainError::NegativeAmount)));
     }
 
+    #[test]
+    fn test_positive_amount_refuses_zero_and_negatives() {
+        assert_eq!(PositiveAmount::new(1).unwrap().get(), 1);
+        for amount in [0, -1, i64::MIN] {
+            assert!(matches!(
+                PositiveAmount::new(amount),
+                Err(DomainError::NonPositiveAmount(a)) if a == amount
+            ));
+        }
+
+        let parsed: PositiveAmount = serde_json::from_str("250").unwrap();
+        assert_eq!(parsed, 250);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), "250");
+        let err = serde_json::from_str::<PositiveAmount>("0").unwrap_err();
+        assert!(
+            err.to_string().contains("Amount must be positive"),
+            "{}",
+            err
+        );
+        assert!("-5".parse::<PositiveAmount>().is_err());
+        assert_eq!("42".parse::<PositiveAmount>().unwrap(), 42);
+    }
+
     #[test]
     fn test_money_addition() {
         let a = DynMoney::new(100, CurrencyCode::USD).unwrap();
@@ -154,6 +410,43 @@ mod tests {
     fn test_money_display() {
         let money = DynMoney::new(1050, CurrencyCode::USD).unwrap();
         assert_eq!(format!("{}", money), "$10.50");
+        let overdrawn = DynMoney::balance(-50, CurrencyCode::USD);
+        assert_eq!(format!("{}", overdrawn), "-$0.50");
+    }
+
+    #[test]
+    fn test_amount_format_policies() {
+        let usd = CurrencyCode::USD;
+        let exact = AmountFormat::default();
+        assert_eq!(exact.format(123_456, usd), "1234.56");
+        assert_eq!(exact.format(-5, usd), "-0.05");
+
+        let padded: AmountFormat = "decimals=4".parse().unwrap();
+        assert_eq!(padded.format(1_250, usd), "12.5000");
+
+        let whole: AmountFormat = "decimals=0".parse().unwrap();
+        assert_eq!(whole.format(1_250, usd), "13");
+        assert_eq!(whole.format(-1_250, usd), "-13");
+        assert_eq!(whole.format(1_249, usd), "12");
+        let truncated: AmountFormat = "decimals=0, rounding=truncate".parse().unwrap();
+        assert_eq!(truncated.format(1_299, usd), "12");
+        assert_eq!(truncated.format(-99, usd), "0");
+        assert_eq!(
+            truncated.format_money(&DynMoney::balance(-1_299, usd)),
+            "-$12"
+        );
+
+        assert_eq!("decimals=currency".parse::<AmountFormat>().unwrap(), exact);
+        assert_eq!("".parse::<AmountFormat>().unwrap(), exact);
+        for invalid in [
+            "decimals=7",
+            "decimals=two",
+            "rounding=up",
+            "precision=2",
+            "2",
+        ] {
+            assert!(invalid.parse::<AmountFormat>().is_err(), "{}", invalid);
+        }
     }
 
     #[test]
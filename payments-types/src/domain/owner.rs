@@ -0,0 +1,161 @@
+//! Account owners: the customers accounts belong to.
+//!
+//! Every account has exactly one owner. Accounts opened before owners
+//! existed belong to the [`OwnerId::DEFAULT`] owner, which every store
+//! creates and which cannot be deleted. An API key may be bound to an owner
+//! instead of a single account, acting on every account the owner holds.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::DomainError;
+
+const MAX_OWNER_NAME_LEN: usize = 200;
+const MAX_OWNER_EMAIL_LEN: usize = 254;
+
+/// Unique identifier for an Owner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct OwnerId(Uuid);
+
+impl OwnerId {
+    /// The owner of accounts opened before owners existed.
+    pub const DEFAULT: OwnerId = OwnerId(Uuid::nil());
+
+    /// Creates a new random OwnerId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates an OwnerId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for OwnerId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for OwnerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for OwnerId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// A customer that holds accounts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Owner {
+    pub id: OwnerId,
+    /// Person or organization name
+    #[schema(example = "Acme Ltd")]
+    pub name: String,
+    /// Contact address; not used to send anything
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "finance@acme.example")]
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Owner {
+    /// A new owner, after checking the name and email.
+    pub fn new(name: &str, email: Option<&str>, now: DateTime<Utc>) -> Result<Self, DomainError> {
+        let mut owner = Self {
+            id: OwnerId::new(),
+            name: String::new(),
+            email: None,
+            created_at: now,
+        };
+        owner.rename(name)?;
+        owner.set_email(email)?;
+        Ok(owner)
+    }
+
+    /// Changes the name to `name`, trimmed; it must be 1-200 characters.
+    pub fn rename(&mut self, name: &str) -> Result<(), DomainError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_OWNER_NAME_LEN {
+            return Err(DomainError::ValidationError(format!(
+                "Owner name must be 1 to {} characters",
+                MAX_OWNER_NAME_LEN
+            )));
+        }
+        self.name = name.to_string();
+        Ok(())
+    }
+
+    /// Changes the email to `email`, trimmed; a blank one clears it.
+    pub fn set_email(&mut self, email: Option<&str>) -> Result<(), DomainError> {
+        let email = email.map(str::trim).filter(|e| !e.is_empty());
+        if let Some(email) = email {
+            let valid = email.len() <= MAX_OWNER_EMAIL_LEN
+                && !email.chars().any(char::is_whitespace)
+                && email
+                    .split_once('@')
+                    .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+            if !valid {
+                return Err(DomainError::ValidationError(format!(
+                    "Invalid owner email '{}'",
+                    email
+                )));
+            }
+        }
+        self.email = email.map(str::to_string);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_name_and_email_are_validated() {
+        let owner = Owner::new(" Acme Ltd ", Some(" finance@acme.example "), Utc::now()).unwrap();
+        assert_eq!(owner.name, "Acme Ltd");
+        assert_eq!(owner.email.as_deref(), Some("finance@acme.example"));
+
+        let owner = Owner::new("Acme Ltd", Some("  "), Utc::now()).unwrap();
+        assert_eq!(owner.email, None);
+
+        for (name, email) in [
+            ("", None),
+            ("Acme", Some("finance")),
+            ("Acme", Some("@acme.example")),
+            ("Acme", Some("finance@acme")),
+            ("Acme", Some("fin ance@acme.example")),
+        ] {
+            assert!(
+                matches!(
+                    Owner::new(name, email, Utc::now()),
+                    Err(DomainError::ValidationError(_))
+                ),
+                "{:?} {:?}",
+                name,
+                email
+            );
+        }
+    }
+}
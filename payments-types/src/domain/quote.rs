@@ -0,0 +1,217 @@
+//! Quotes that lock an exchange rate for a later transfer.
+//!
+//! A quote fixes the rate and converted amount for converting a given amount
+//! between two currencies until it expires. A transfer that names the quote
+//! is converted at the quoted rate instead of the current one, and spends
+//! the quote: it cannot pay for a second transfer.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{CurrencyCode, DynMoney, FxConversion, Mode, TransactionId};
+use crate::error::DomainError;
+
+/// How long a quote holds its rate when the request does not say.
+pub const DEFAULT_QUOTE_TTL_SECS: u64 = 60;
+
+/// Longest a quote may hold its rate.
+pub const MAX_QUOTE_TTL_SECS: u64 = 15 * 60;
+
+/// Unique identifier for a quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct QuoteId(Uuid);
+
+impl QuoteId {
+    /// Creates a new random QuoteId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a QuoteId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for QuoteId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for QuoteId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for QuoteId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// An exchange rate locked for converting one amount.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Quote {
+    pub id: QuoteId,
+    /// Currency the transfer is made in
+    pub from_currency: CurrencyCode,
+    /// Currency of the destination account
+    pub to_currency: CurrencyCode,
+    /// Amount converted, in minor units of `from_currency`
+    #[schema(example = 10000)]
+    pub amount: i64,
+    /// Units of `to_currency` per unit of `from_currency`
+    #[schema(example = 0.92)]
+    pub rate: f64,
+    /// Amount credited, in minor units of `to_currency`
+    #[schema(example = 9200)]
+    pub converted_amount: i64,
+    pub created_at: DateTime<Utc>,
+    /// When the rate stops being honoured
+    pub expires_at: DateTime<Utc>,
+    /// Transfer the quote was spent on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<TransactionId>,
+    /// Mode of the key that asked for it; only transfers in that mode can
+    /// spend it
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+impl Quote {
+    /// Whether the quote no longer holds its rate at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Fails if the quote expired at or before `now`.
+    pub fn check_unexpired(&self, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if self.is_expired(now) {
+            return Err(DomainError::ValidationError(format!(
+                "Quote {} has expired",
+                self.id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Marks the quote spent on `transaction_id`. Spending it again on the
+    /// same transaction, as a replayed transfer does, changes nothing.
+    pub fn spend(&mut self, transaction_id: TransactionId) -> Result<(), DomainError> {
+        match self.transaction_id {
+            Some(spent_on) if spent_on != transaction_id => Err(already_used(self.id)),
+            _ => {
+                self.transaction_id = Some(transaction_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// The conversion a transfer made with this quote applies.
+    pub fn conversion(&self) -> Result<FxConversion, DomainError> {
+        Ok(FxConversion {
+            rate: self.rate,
+            converted_amount: DynMoney::new(self.converted_amount, self.to_currency)?,
+        })
+    }
+
+    /// Fails unless the quote is for converting `amount` of `currency` into
+    /// `to`.
+    pub fn check_covers(
+        &self,
+        amount: i64,
+        currency: CurrencyCode,
+        to: CurrencyCode,
+    ) -> Result<(), DomainError> {
+        if currency != self.from_currency || to != self.to_currency {
+            return Err(DomainError::ValidationError(format!(
+                "Quote {} converts {} to {}, not {} to {}",
+                self.id, self.from_currency, self.to_currency, currency, to
+            )));
+        }
+        if amount != self.amount {
+            return Err(DomainError::ValidationError(format!(
+                "Quote {} is for an amount of {}, not {}",
+                self.id, self.amount, amount
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The error for spending a quote another transfer already spent.
+pub fn already_used(id: QuoteId) -> DomainError {
+    DomainError::ValidationError(format!("Quote {} was already used", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn quote() -> Quote {
+        let now = Utc::now();
+        Quote {
+            id: QuoteId::new(),
+            from_currency: CurrencyCode::USD,
+            to_currency: CurrencyCode::EUR,
+            amount: 10_000,
+            rate: 0.92,
+            converted_amount: 9_200,
+            created_at: now,
+            expires_at: now + TimeDelta::seconds(60),
+            transaction_id: None,
+            mode: Mode::Live,
+        }
+    }
+
+    #[test]
+    fn test_quote_covers_only_its_own_conversion() {
+        let quote = quote();
+        assert!(
+            quote
+                .check_covers(10_000, CurrencyCode::USD, CurrencyCode::EUR)
+                .is_ok()
+        );
+        assert!(
+            quote
+                .check_covers(9_999, CurrencyCode::USD, CurrencyCode::EUR)
+                .is_err()
+        );
+        assert!(
+            quote
+                .check_covers(10_000, CurrencyCode::USD, CurrencyCode::GBP)
+                .is_err()
+        );
+        assert_eq!(quote.conversion().unwrap().converted_amount.amount(), 9_200);
+        assert!(!quote.is_expired(quote.created_at));
+        assert!(quote.is_expired(quote.expires_at));
+    }
+
+    #[test]
+    fn test_quote_is_spent_once() {
+        let mut quote = quote();
+        let first = TransactionId::new();
+        quote.spend(first).unwrap();
+        quote.spend(first).unwrap();
+        assert!(quote.spend(TransactionId::new()).is_err());
+        assert_eq!(quote.transaction_id, Some(first));
+    }
+}
@@ -0,0 +1,137 @@
+//! Risk decisions about payments.
+//!
+//! A [`RiskCheck`](crate::ports::RiskCheck) assesses every payment before
+//! money moves. Denied payments are rejected; held ones go through with the
+//! assessment recorded on the transaction for someone to review.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What a risk check decided, from least to most severe.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RiskDecision {
+    #[default]
+    Allow,
+    /// The payment is made but held for manual review
+    Hold,
+    /// The payment is rejected
+    Deny,
+}
+
+impl AsRef<str> for RiskDecision {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Allow => "ALLOW",
+            Self::Hold => "HOLD",
+            Self::Deny => "DENY",
+        }
+    }
+}
+
+impl std::fmt::Display for RiskDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl std::str::FromStr for RiskDecision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "ALLOW" => Ok(Self::Allow),
+            "HOLD" => Ok(Self::Hold),
+            "DENY" => Ok(Self::Deny),
+            _ => Err(format!("Unknown risk decision: {}", s)),
+        }
+    }
+}
+
+/// A risk check's decision and why it was made.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RiskAssessment {
+    pub decision: RiskDecision,
+    /// Why the payment was held or denied; empty when it was allowed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(example = json!(["First transfer of 5000.00 or more to this beneficiary"]))]
+    pub reasons: Vec<String>,
+}
+
+impl RiskAssessment {
+    /// Lets the payment through.
+    pub fn allow() -> Self {
+        Self::default()
+    }
+
+    /// Lets the payment through but holds it for review.
+    pub fn hold(reason: impl Into<String>) -> Self {
+        Self {
+            decision: RiskDecision::Hold,
+            reasons: vec![reason.into()],
+        }
+    }
+
+    /// Rejects the payment.
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self {
+            decision: RiskDecision::Deny,
+            reasons: vec![reason.into()],
+        }
+    }
+
+    /// Combines two assessments: the more severe decision wins, and the
+    /// reasons behind it are kept.
+    pub fn and(self, other: Self) -> Self {
+        match self.decision.cmp(&other.decision) {
+            std::cmp::Ordering::Less => other,
+            std::cmp::Ordering::Greater => self,
+            std::cmp::Ordering::Equal => Self {
+                decision: self.decision,
+                reasons: self.reasons.into_iter().chain(other.reasons).collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_severe_decision_wins() {
+        let held = RiskAssessment::allow()
+            .and(RiskAssessment::hold("velocity"))
+            .and(RiskAssessment::hold("amount"));
+        assert_eq!(held.decision, RiskDecision::Hold);
+        assert_eq!(held.reasons, ["velocity", "amount"]);
+
+        let denied = held.and(RiskAssessment::deny("blocked"));
+        assert_eq!(denied, RiskAssessment::deny("blocked"));
+        assert_eq!(
+            RiskAssessment::allow().and(RiskAssessment::allow()),
+            RiskAssessment::allow()
+        );
+    }
+
+    #[test]
+    fn test_decision_round_trips_as_text() {
+        for decision in [RiskDecision::Allow, RiskDecision::Hold, RiskDecision::Deny] {
+            assert_eq!(decision.to_string().parse::<RiskDecision>(), Ok(decision));
+        }
+        assert!("maybe".parse::<RiskDecision>().is_err());
+    }
+}
@@ -0,0 +1,411 @@
+//! Scheduled and recurring payments.
+//!
+//! A scheduled payment is a withdrawal or transfer that the scheduler job
+//! makes on its own every time the payment's schedule comes due. Schedules
+//! are either a fixed interval or a cron expression read in an IANA time
+//! zone, so "09:00 Europe/Berlin every weekday" stays at 09:00 local time
+//! across daylight saving changes.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{AccountId, CurrencyCode, TransactionId, TransactionType};
+use crate::error::DomainError;
+
+/// Shortest interval a schedule may repeat at; the scheduler does not poll
+/// more often than this.
+pub const MIN_SCHEDULE_INTERVAL_SECS: u64 = 60;
+
+/// Longest interval a schedule may repeat at (a leap year).
+pub const MAX_SCHEDULE_INTERVAL_SECS: u64 = 366 * 24 * 60 * 60;
+
+/// Unique identifier for a scheduled payment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct ScheduledPaymentId(Uuid);
+
+impl ScheduledPaymentId {
+    /// Creates a new random ScheduledPaymentId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a ScheduledPaymentId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for ScheduledPaymentId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ScheduledPaymentId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for ScheduledPaymentId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+fn utc_timezone() -> String {
+    "UTC".into()
+}
+
+/// When a scheduled payment comes due.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PaymentSchedule {
+    /// Every `every_seconds`, starting at the payment's start time
+    Interval {
+        #[schema(example = 86400)]
+        every_seconds: u64,
+    },
+    /// Whenever a five-field cron expression matches, read in `timezone`
+    Cron {
+        #[schema(example = "0 9 * * MON-FRI")]
+        expression: String,
+        /// IANA time zone name
+        #[serde(default = "utc_timezone")]
+        #[schema(example = "Europe/Berlin")]
+        timezone: String,
+    },
+}
+
+impl PaymentSchedule {
+    /// Checks the interval bounds, the cron expression and the time zone.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        match self {
+            Self::Interval { every_seconds } => {
+                if !(MIN_SCHEDULE_INTERVAL_SECS..=MAX_SCHEDULE_INTERVAL_SECS)
+                    .contains(every_seconds)
+                {
+                    return Err(DomainError::ValidationError(format!(
+                        "every_seconds must be between {} and {}",
+                        MIN_SCHEDULE_INTERVAL_SECS, MAX_SCHEDULE_INTERVAL_SECS
+                    )));
+                }
+                Ok(())
+            }
+            Self::Cron {
+                expression,
+                timezone,
+            } => {
+                parse_cron(expression)?;
+                parse_timezone(timezone)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// First time the schedule is due at or after `start`.
+    pub fn first_run(&self, start: DateTime<Utc>) -> Result<DateTime<Utc>, DomainError> {
+        match self {
+            Self::Interval { .. } => Ok(start),
+            Self::Cron { .. } => self.cron_occurrence(start, true),
+        }
+    }
+
+    /// Next time the schedule is due after `now`, given that it was last due
+    /// at `due_at`.
+    ///
+    /// A `due_at` still in the future is kept. Runs that fell due while
+    /// nothing was running (downtime, a paused payment) are skipped rather
+    /// than made up: interval schedules move forward in whole intervals from
+    /// `due_at`, cron schedules jump to the next match.
+    pub fn next_run(
+        &self,
+        due_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, DomainError> {
+        if due_at > now {
+            return Ok(due_at);
+        }
+        match self {
+            Self::Interval { every_seconds } => {
+                let every = interval_seconds(*every_seconds)?;
+                let missed = (now - due_at).num_seconds() / every;
+                Ok(due_at + TimeDelta::seconds((missed + 1) * every))
+            }
+            Self::Cron { .. } => self.cron_occurrence(now, false),
+        }
+    }
+
+    fn cron_occurrence(
+        &self,
+        from: DateTime<Utc>,
+        inclusive: bool,
+    ) -> Result<DateTime<Utc>, DomainError> {
+        let Self::Cron {
+            expression,
+            timezone,
+        } = self
+        else {
+            unreachable!("cron_occurrence on an interval schedule");
+        };
+        let cron = parse_cron(expression)?;
+        let tz = parse_timezone(timezone)?;
+        cron.find_next_occurrence(&from.with_timezone(&tz), inclusive)
+            .map(|at| at.with_timezone(&Utc))
+            .map_err(|e| {
+                DomainError::ValidationError(format!("Cron expression never matches: {}", e))
+            })
+    }
+}
+
+fn interval_seconds(every_seconds: u64) -> Result<i64, DomainError> {
+    i64::try_from(every_seconds)
+        .ok()
+        .filter(|s| *s > 0)
+        .ok_or_else(|| DomainError::ValidationError("every_seconds is out of range".into()))
+}
+
+fn parse_cron(expression: &str) -> Result<Cron, DomainError> {
+    Cron::new(expression)
+        .parse()
+        .map_err(|e| DomainError::ValidationError(format!("Invalid cron expression: {}", e)))
+}
+
+fn parse_timezone(timezone: &str) -> Result<Tz, DomainError> {
+    timezone
+        .parse()
+        .map_err(|_| DomainError::ValidationError(format!("Unknown time zone: {}", timezone)))
+}
+
+/// Whether the scheduler picks up a scheduled payment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScheduledPaymentStatus {
+    /// Runs whenever it comes due
+    Active,
+    /// Skipped until resumed
+    Paused,
+}
+
+impl AsRef<str> for ScheduledPaymentStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Active => "ACTIVE",
+            Self::Paused => "PAUSED",
+        }
+    }
+}
+
+impl std::fmt::Display for ScheduledPaymentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl std::str::FromStr for ScheduledPaymentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "ACTIVE" => Ok(Self::Active),
+            "PAUSED" => Ok(Self::Paused),
+            _ => Err(format!("Unknown scheduled payment status: {}", s)),
+        }
+    }
+}
+
+/// A withdrawal or transfer made on a schedule, and how its last run went.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledPayment {
+    pub id: ScheduledPaymentId,
+    /// `WITHDRAWAL` or `TRANSFER`
+    pub transaction_type: TransactionType,
+    pub from_account_id: AccountId,
+    /// Destination of a transfer; `None` for withdrawals
+    pub to_account_id: Option<AccountId>,
+    #[schema(example = 2500)]
+    pub amount: i64,
+    pub currency: CurrencyCode,
+    pub reference: Option<String>,
+    pub purpose_code: Option<String>,
+    pub schedule: PaymentSchedule,
+    pub status: ScheduledPaymentStatus,
+    /// When the payment is next due
+    pub next_run_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Transaction made by the last run, if it succeeded
+    pub last_transaction_id: Option<TransactionId>,
+    /// Why the last run failed, if it did
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ScheduledPayment {
+    /// Key the run due at `due_at` is made with, so a run that is retried
+    /// after a crash does not pay twice.
+    pub fn idempotency_key(&self, due_at: DateTime<Utc>) -> String {
+        format!("scheduled:{}:{}", self.id, due_at.timestamp())
+    }
+
+    /// Records the outcome of the run that was due at `next_run_at` and
+    /// moves `next_run_at` on to the following occurrence after `ran_at`.
+    pub fn record_run(
+        &mut self,
+        ran_at: DateTime<Utc>,
+        outcome: Result<TransactionId, String>,
+    ) -> Result<(), DomainError> {
+        self.next_run_at = self.schedule.next_run(self.next_run_at, ran_at)?;
+        self.last_run_at = Some(ran_at);
+        match outcome {
+            Ok(transaction_id) => {
+                self.last_transaction_id = Some(transaction_id);
+                self.last_error = None;
+            }
+            Err(error) => {
+                self.last_transaction_id = None;
+                self.last_error = Some(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reactivates a paused payment. Occurrences that fell due while it was
+    /// paused are skipped.
+    pub fn resume(&mut self, now: DateTime<Utc>) -> Result<(), DomainError> {
+        self.next_run_at = self.schedule.next_run(self.next_run_at, now)?;
+        self.status = ScheduledPaymentStatus::Active;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn cron(expression: &str, timezone: &str) -> PaymentSchedule {
+        PaymentSchedule::Cron {
+            expression: expression.into(),
+            timezone: timezone.into(),
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_schedules() {
+        assert!(
+            PaymentSchedule::Interval { every_seconds: 30 }
+                .validate()
+                .is_err()
+        );
+        assert!(
+            PaymentSchedule::Interval {
+                every_seconds: 3600
+            }
+            .validate()
+            .is_ok()
+        );
+        assert!(cron("not a cron", "UTC").validate().is_err());
+        assert!(cron("0 9 * * *", "Mars/Olympus").validate().is_err());
+        assert!(cron("0 9 * * MON-FRI", "Europe/Berlin").validate().is_ok());
+
+        let parsed: PaymentSchedule =
+            serde_json::from_str(r#"{"type":"cron","expression":"0 9 * * *"}"#).unwrap();
+        assert_eq!(parsed, cron("0 9 * * *", "UTC"));
+    }
+
+    #[test]
+    fn test_interval_skips_missed_runs() {
+        let schedule = PaymentSchedule::Interval {
+            every_seconds: 3600,
+        };
+        let due = utc(2026, 3, 1, 10, 0);
+        assert_eq!(schedule.first_run(due).unwrap(), due);
+        assert_eq!(schedule.next_run(due, due).unwrap(), utc(2026, 3, 1, 11, 0));
+        // Down for two and a half hours: the runs at 11:00 and 12:00 are skipped.
+        assert_eq!(
+            schedule.next_run(due, utc(2026, 3, 1, 12, 30)).unwrap(),
+            utc(2026, 3, 1, 13, 0)
+        );
+        let future = utc(2026, 3, 2, 0, 0);
+        assert_eq!(schedule.next_run(future, due).unwrap(), future);
+    }
+
+    #[test]
+    fn test_cron_follows_local_time_across_dst() {
+        let schedule = cron("0 9 * * *", "Europe/Berlin");
+        // Berlin is UTC+1 in winter and UTC+2 from 29 March 2026.
+        let first = schedule.first_run(utc(2026, 3, 28, 0, 0)).unwrap();
+        assert_eq!(first, utc(2026, 3, 28, 8, 0));
+        let second = schedule.next_run(first, first).unwrap();
+        assert_eq!(second, utc(2026, 3, 29, 7, 0));
+
+        // Starting exactly on an occurrence runs it.
+        assert_eq!(schedule.first_run(second).unwrap(), second);
+    }
+
+    #[test]
+    fn test_record_run_and_resume() {
+        let created = utc(2026, 3, 1, 10, 0);
+        let mut payment = ScheduledPayment {
+            id: ScheduledPaymentId::new(),
+            transaction_type: TransactionType::Withdrawal,
+            from_account_id: AccountId::new(),
+            to_account_id: None,
+            amount: 100,
+            currency: CurrencyCode::USD,
+            reference: None,
+            purpose_code: None,
+            schedule: PaymentSchedule::Interval {
+                every_seconds: 3600,
+            },
+            status: ScheduledPaymentStatus::Active,
+            next_run_at: created,
+            last_run_at: None,
+            last_transaction_id: None,
+            last_error: None,
+            created_at: created,
+        };
+        assert_eq!(
+            payment.idempotency_key(created),
+            format!("scheduled:{}:{}", payment.id, created.timestamp())
+        );
+
+        payment
+            .record_run(utc(2026, 3, 1, 10, 1), Err("Insufficient funds".into()))
+            .unwrap();
+        assert_eq!(payment.next_run_at, utc(2026, 3, 1, 11, 0));
+        assert_eq!(payment.last_error.as_deref(), Some("Insufficient funds"));
+
+        let tx = TransactionId::new();
+        payment.record_run(utc(2026, 3, 1, 11, 0), Ok(tx)).unwrap();
+        assert_eq!(payment.last_transaction_id, Some(tx));
+        assert_eq!(payment.last_error, None);
+
+        payment.status = ScheduledPaymentStatus::Paused;
+        payment.resume(utc(2026, 3, 1, 15, 30)).unwrap();
+        assert_eq!(payment.status, ScheduledPaymentStatus::Active);
+        assert_eq!(payment.next_run_at, utc(2026, 3, 1, 16, 0));
+    }
+}
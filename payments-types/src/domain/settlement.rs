@@ -0,0 +1,229 @@
+//! Settlement batches: outgoing payouts grouped for the bank.
+//!
+//! A batch collects every withdrawal made up to its cut-off that is not in
+//! another batch yet, and fixes its per-currency totals at that moment.
+//! Treasury then submits the batch file to the bank and marks the batch
+//! settled once the bank confirms. Cancelling a batch releases its payouts
+//! so the next batch picks them up again.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::mode::Mode;
+use super::{CurrencyCode, Transaction};
+
+/// Unique identifier for a settlement batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct SettlementBatchId(Uuid);
+
+impl SettlementBatchId {
+    /// Creates a new random SettlementBatchId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a SettlementBatchId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for SettlementBatchId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for SettlementBatchId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for SettlementBatchId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// Where a settlement batch is in its lifecycle.
+///
+/// `PENDING -> SUBMITTED -> SETTLED`, with `CANCELLED` reachable from either
+/// of the first two (e.g. when the bank rejects the file).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SettlementBatchStatus {
+    /// Created and waiting to be sent to the bank
+    Pending,
+    /// Sent to the bank, awaiting confirmation
+    Submitted,
+    /// Confirmed by the bank; final
+    Settled,
+    /// Abandoned and its payouts released; final
+    Cancelled,
+}
+
+impl SettlementBatchStatus {
+    /// Returns `true` if a batch in this status may move to `next`.
+    pub fn can_transition_to(self, next: Self) -> bool {
+        use SettlementBatchStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Submitted)
+                | (Pending, Cancelled)
+                | (Submitted, Settled)
+                | (Submitted, Cancelled)
+        )
+    }
+}
+
+impl AsRef<str> for SettlementBatchStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Submitted => "SUBMITTED",
+            Self::Settled => "SETTLED",
+            Self::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+impl std::fmt::Display for SettlementBatchStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl std::str::FromStr for SettlementBatchStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "PENDING" => Ok(Self::Pending),
+            "SUBMITTED" => Ok(Self::Submitted),
+            "SETTLED" => Ok(Self::Settled),
+            "CANCELLED" => Ok(Self::Cancelled),
+            _ => Err(format!("Unknown settlement batch status: {}", s)),
+        }
+    }
+}
+
+/// Number and sum of a batch's payouts in one currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CurrencyTotal {
+    pub currency: CurrencyCode,
+    #[schema(example = 12)]
+    pub payout_count: i64,
+    /// Sum in smallest currency unit
+    #[schema(example = 1250000)]
+    pub amount: i64,
+}
+
+/// A group of payouts settled with the bank together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SettlementBatch {
+    pub id: SettlementBatchId,
+    pub status: SettlementBatchStatus,
+    /// Latest withdrawal time the batch covers
+    pub cutoff: DateTime<Utc>,
+    #[schema(example = 12)]
+    pub payout_count: i64,
+    /// Per-currency totals, ordered by currency code
+    pub totals: Vec<CurrencyTotal>,
+    pub created_at: DateTime<Utc>,
+    /// When the status last changed
+    pub updated_at: DateTime<Utc>,
+    /// Whether the batch pays out test or live money
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+impl SettlementBatch {
+    /// Sums `payouts` per currency, ordered by currency code.
+    pub fn totals_for(payouts: &[Transaction]) -> Vec<CurrencyTotal> {
+        let mut totals: Vec<CurrencyTotal> = Vec::new();
+        for payout in payouts {
+            let currency = payout.amount.currency();
+            match totals.iter_mut().find(|t| t.currency == currency) {
+                Some(total) => {
+                    total.payout_count += 1;
+                    total.amount += payout.amount.amount();
+                }
+                None => totals.push(CurrencyTotal {
+                    currency,
+                    payout_count: 1,
+                    amount: payout.amount.amount(),
+                }),
+            }
+        }
+        totals.sort_by_key(|t| t.currency.code());
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AccountId, DynMoney};
+
+    #[test]
+    fn test_status_transitions() {
+        use SettlementBatchStatus::*;
+        assert!(Pending.can_transition_to(Submitted));
+        assert!(Submitted.can_transition_to(Settled));
+        assert!(Submitted.can_transition_to(Cancelled));
+        assert!(!Pending.can_transition_to(Settled));
+        assert!(!Settled.can_transition_to(Cancelled));
+        assert!(!Cancelled.can_transition_to(Pending));
+        assert_eq!("submitted".parse::<SettlementBatchStatus>(), Ok(Submitted));
+    }
+
+    #[test]
+    fn test_totals_are_per_currency() {
+        let payout = |amount, currency| {
+            Transaction::withdrawal(
+                AccountId::new(),
+                DynMoney::new(amount, currency).unwrap(),
+                None,
+                None,
+            )
+        };
+        let payouts = [
+            payout(500, CurrencyCode::USD),
+            payout(200, CurrencyCode::EUR),
+            payout(300, CurrencyCode::USD),
+        ];
+
+        let totals = SettlementBatch::totals_for(&payouts);
+        assert_eq!(
+            totals,
+            vec![
+                CurrencyTotal {
+                    currency: CurrencyCode::EUR,
+                    payout_count: 1,
+                    amount: 200
+                },
+                CurrencyTotal {
+                    currency: CurrencyCode::USD,
+                    payout_count: 2,
+                    amount: 800
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Spending controls: which purpose codes an account may debit with.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::DomainError;
+
+/// Longest accepted purpose code.
+const MAX_PURPOSE_CODE_LEN: usize = 32;
+
+/// Normalizes a purpose code to upper case, rejecting malformed ones.
+///
+/// Codes are 1-32 ASCII letters, digits, `_` or `-` (e.g. `PAYROLL`, `5411`).
+pub fn normalize_purpose_code(code: &str) -> Result<String, DomainError> {
+    let code = code.trim();
+    let valid = !code.is_empty()
+        && code.len() <= MAX_PURPOSE_CODE_LEN
+        && code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(DomainError::ValidationError(format!(
+            "Invalid purpose code '{}': use 1-{} letters, digits, '_' or '-'",
+            code, MAX_PURPOSE_CODE_LEN
+        )));
+    }
+    Ok(code.to_ascii_uppercase())
+}
+
+/// Per-account allow/deny lists for the purpose codes of outgoing payments.
+///
+/// An account without rules may debit with any purpose (or none). A denied
+/// code always loses; a non-empty allow list additionally requires every
+/// withdrawal and outgoing transfer to carry one of the listed codes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SpendingRules {
+    /// Purpose codes the account may use; empty allows any
+    #[serde(default)]
+    #[schema(example = json!(["PAYROLL", "SUPPLIERS"]))]
+    pub allowed: Vec<String>,
+    /// Purpose codes the account may never use
+    #[serde(default)]
+    #[schema(example = json!(["GAMBLING"]))]
+    pub denied: Vec<String>,
+}
+
+impl SpendingRules {
+    /// Returns `true` if the rules restrict nothing.
+    pub fn is_empty(&self) -> bool {
+        self.allowed.is_empty() && self.denied.is_empty()
+    }
+
+    /// Upper-cases, validates and de-duplicates both lists.
+    pub fn normalized(self) -> Result<Self, DomainError> {
+        let normalize = |codes: Vec<String>| -> Result<Vec<String>, DomainError> {
+            let mut codes = codes
+                .iter()
+                .map(|c| normalize_purpose_code(c))
+                .collect::<Result<Vec<_>, _>>()?;
+            codes.sort();
+            codes.dedup();
+            Ok(codes)
+        };
+        Ok(Self {
+            allowed: normalize(self.allowed)?,
+            denied: normalize(self.denied)?,
+        })
+    }
+
+    /// Returns `true` if a debit with `purpose_code` is permitted.
+    ///
+    /// `purpose_code` must already be normalized.
+    pub fn permits(&self, purpose_code: Option<&str>) -> bool {
+        match purpose_code {
+            Some(code) if self.denied.iter().any(|d| d == code) => false,
+            Some(code) => self.allowed.is_empty() || self.allowed.iter().any(|a| a == code),
+            None => self.allowed.is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_purpose_code() {
+        assert_eq!(normalize_purpose_code(" payroll ").unwrap(), "PAYROLL");
+        assert_eq!(normalize_purpose_code("5411").unwrap(), "5411");
+        assert!(normalize_purpose_code("").is_err());
+        assert!(normalize_purpose_code("has space").is_err());
+        assert!(normalize_purpose_code(&"X".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_rules_without_lists_permit_everything() {
+        let rules = SpendingRules::default();
+        assert!(rules.is_empty());
+        assert!(rules.permits(None));
+        assert!(rules.permits(Some("ANYTHING")));
+    }
+
+    #[test]
+    fn test_deny_list_blocks_listed_codes() {
+        let rules = SpendingRules {
+            allowed: vec![],
+            denied: vec!["gambling".into()],
+        }
+        .normalized()
+        .unwrap();
+
+        assert!(!rules.permits(Some("GAMBLING")));
+        assert!(rules.permits(Some("PAYROLL")));
+        assert!(rules.permits(None));
+    }
+
+    #[test]
+    fn test_allow_list_requires_a_listed_code() {
+        let rules = SpendingRules {
+            allowed: vec!["PAYROLL".into(), "payroll".into(), "SUPPLIERS".into()],
+            denied: vec!["SUPPLIERS".into()],
+        }
+        .normalized()
+        .unwrap();
+
+        assert_eq!(rules.allowed, vec!["PAYROLL", "SUPPLIERS"]);
+        assert!(rules.permits(Some("PAYROLL")));
+        assert!(!rules.permits(Some("RENT")));
+        assert!(!rules.permits(None));
+        // Deny wins over allow.
+        assert!(!rules.permits(Some("SUPPLIERS")));
+    }
+}
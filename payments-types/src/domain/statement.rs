@@ -0,0 +1,424 @@
+//! Monthly account statements.
+//!
+//! A statement summarises one account over a calendar month (UTC): the
+//! balance going in, every transaction in the month and the balance coming
+//! out. Statements are issued once per account and month, after the month
+//! has ended, so they never change once issued.
+//!
+//! An [`AccountStatement`] is the same summary over any range of days,
+//! generated on request and not stored.
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{Account, AccountId, CurrencyCode, Transaction, TransactionId, TransactionType};
+
+/// Unique identifier for a statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct StatementId(Uuid);
+
+impl StatementId {
+    /// Creates a new random StatementId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Creates a StatementId from an existing UUID.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Returns the UUID value.
+    pub fn into_uuid(self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for StatementId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for StatementId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for StatementId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+/// A calendar month, the period a statement covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StatementPeriod {
+    /// First day of the month
+    pub start: NaiveDate,
+}
+
+impl StatementPeriod {
+    /// The month containing `date`.
+    pub fn containing(date: NaiveDate) -> Self {
+        Self {
+            start: date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// The last full month before the one containing `now`.
+    pub fn previous(now: DateTime<Utc>) -> Self {
+        let current = Self::containing(now.date_naive());
+        Self {
+            start: current.start - Months::new(1),
+        }
+    }
+
+    /// Parses a `YYYY-MM` month.
+    pub fn parse(month: &str) -> Option<Self> {
+        NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d")
+            .ok()
+            .map(|start| Self { start })
+    }
+
+    /// Last day of the month, inclusive.
+    pub fn end(&self) -> NaiveDate {
+        self.next_start().pred_opt().unwrap_or(self.start)
+    }
+
+    /// Start of the month as a timestamp.
+    pub fn starts_at(&self) -> DateTime<Utc> {
+        self.start.and_time(NaiveTime::MIN).and_utc()
+    }
+
+    /// Start of the following month: the period's exclusive end.
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        self.next_start().and_time(NaiveTime::MIN).and_utc()
+    }
+
+    fn next_start(&self) -> NaiveDate {
+        self.start + Months::new(1)
+    }
+}
+
+impl std::fmt::Display for StatementPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.start.format("%Y-%m"))
+    }
+}
+
+/// One account's statement for one month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Statement {
+    pub id: StatementId,
+    pub account_id: AccountId,
+    /// First day of the month covered
+    pub period_start: NaiveDate,
+    /// Last day of the month covered, inclusive
+    pub period_end: NaiveDate,
+    pub currency: CurrencyCode,
+    /// Balance at the start of the period, in minor units
+    #[schema(example = 150000)]
+    pub opening_balance: i64,
+    /// Balance at the end of the period, in minor units
+    #[schema(example = 98000)]
+    pub closing_balance: i64,
+    #[schema(example = 14)]
+    pub transaction_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Statement {
+    /// Summarises `account` over `period` from its full transaction history.
+    ///
+    /// Returns the statement together with the period's transactions, oldest
+    /// first, for rendering.
+    pub fn from_history(
+        id: StatementId,
+        account: &Account,
+        period: StatementPeriod,
+        history: &[Transaction],
+        created_at: DateTime<Utc>,
+    ) -> (Self, Vec<Transaction>) {
+        let (starts_at, ends_at) = (period.starts_at(), period.ends_at());
+        let opening_balance = history
+            .iter()
+            .filter(|tx| tx.created_at < starts_at)
+            .map(|tx| tx.signed_amount_for(account.id))
+            .sum::<i64>();
+        let mut in_period: Vec<Transaction> = history
+            .iter()
+            .filter(|tx| tx.created_at >= starts_at && tx.created_at < ends_at)
+            .cloned()
+            .collect();
+        in_period.sort_by_key(|tx| tx.created_at);
+        let closing_balance = opening_balance
+            + in_period
+                .iter()
+                .map(|tx| tx.signed_amount_for(account.id))
+                .sum::<i64>();
+
+        let statement = Self {
+            id,
+            account_id: account.id,
+            period_start: period.start,
+            period_end: period.end(),
+            currency: account.currency(),
+            opening_balance,
+            closing_balance,
+            transaction_count: in_period.len() as i64,
+            created_at,
+        };
+        (statement, in_period)
+    }
+
+    /// The month this statement covers.
+    pub fn period(&self) -> StatementPeriod {
+        StatementPeriod::containing(self.period_start)
+    }
+}
+
+/// File layout of an on-demand account statement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementFormat {
+    /// The same CSV layout as monthly statements
+    #[default]
+    Csv,
+    /// An [`AccountStatement`] document, e.g. for rendering a PDF
+    Json,
+}
+
+impl StatementFormat {
+    /// Lower-case name, as used in the query string and file names.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// One transaction on a statement, with the balance after it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StatementLine {
+    pub transaction_id: TransactionId,
+    pub transaction_type: TransactionType,
+    /// Counterparty and reference, whichever the transaction has
+    pub description: String,
+    /// Effect on the balance in minor units: negative for debits
+    #[schema(example = -2500)]
+    pub amount: i64,
+    /// Balance after this transaction, in minor units
+    #[schema(example = 97500)]
+    pub balance: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl StatementLine {
+    /// Lines for `account_id`'s `transactions`, oldest first, with the
+    /// running balance starting from `opening_balance`.
+    pub fn running(
+        account_id: AccountId,
+        opening_balance: i64,
+        transactions: &[Transaction],
+    ) -> Vec<Self> {
+        let mut balance = opening_balance;
+        transactions
+            .iter()
+            .map(|tx| {
+                let amount = tx.signed_amount_for(account_id);
+                balance += amount;
+                Self {
+                    transaction_id: tx.id,
+                    transaction_type: tx.transaction_type,
+                    description: describe(tx),
+                    amount,
+                    balance,
+                    created_at: tx.created_at,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Counterparty and reference, whichever the transaction has.
+fn describe(tx: &Transaction) -> String {
+    let counterparty = tx.counterparty.as_ref().map(|c| c.name.as_str());
+    [counterparty, tx.reference.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" - ")
+}
+
+/// An account's activity over the UTC days `from..=to`, generated on request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AccountStatement {
+    pub account_id: AccountId,
+    pub account_name: String,
+    pub currency: CurrencyCode,
+    /// First day covered
+    pub from: NaiveDate,
+    /// Last day covered, inclusive
+    pub to: NaiveDate,
+    /// Balance at the start of `from`, in minor units
+    #[schema(example = 100000)]
+    pub opening_balance: i64,
+    /// Balance at the end of `to`, in minor units
+    #[schema(example = 97500)]
+    pub closing_balance: i64,
+    /// The period's transactions, oldest first
+    pub lines: Vec<StatementLine>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl AccountStatement {
+    /// Summarises `account` over `from..=to` from its full transaction history.
+    pub fn from_history(
+        account: &Account,
+        from: NaiveDate,
+        to: NaiveDate,
+        history: &[Transaction],
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        let starts_at = from.and_time(NaiveTime::MIN).and_utc();
+        let ends_at = to.succ_opt().map_or(DateTime::<Utc>::MAX_UTC, |day| {
+            day.and_time(NaiveTime::MIN).and_utc()
+        });
+        let opening_balance = history
+            .iter()
+            .filter(|tx| tx.created_at < starts_at)
+            .map(|tx| tx.signed_amount_for(account.id))
+            .sum::<i64>();
+        let mut in_period: Vec<Transaction> = history
+            .iter()
+            .filter(|tx| tx.created_at >= starts_at && tx.created_at < ends_at)
+            .cloned()
+            .collect();
+        in_period.sort_by_key(|tx| tx.created_at);
+        let lines = StatementLine::running(account.id, opening_balance, &in_period);
+
+        Self {
+            account_id: account.id,
+            account_name: account.name.clone(),
+            currency: account.currency(),
+            from,
+            to,
+            opening_balance,
+            closing_balance: lines.last().map_or(opening_balance, |line| line.balance),
+            lines,
+            generated_at,
+        }
+    }
+}
+
+/// A statement with a time-limited link to download it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StatementDownload {
+    pub statement: Statement,
+    /// Signed URL that serves the statement without an API key until it expires
+    pub download_url: String,
+    pub url_expires_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::domain::DynMoney;
+
+    #[test]
+    fn test_period_boundaries() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 8, 0, 0).unwrap();
+        let period = StatementPeriod::previous(now);
+        assert_eq!(period.to_string(), "2026-02");
+        assert_eq!(period.end(), NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+        assert_eq!(
+            period.ends_at(),
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(StatementPeriod::parse("2026-02"), Some(period));
+        assert_eq!(
+            StatementPeriod::previous(Utc.with_ymd_and_hms(2026, 1, 31, 0, 0, 0).unwrap())
+                .to_string(),
+            "2025-12"
+        );
+        assert!(StatementPeriod::parse("2026-13").is_none());
+    }
+
+    #[test]
+    fn test_balances_come_from_history() {
+        let account = Account::new("Alice".into(), CurrencyCode::USD).unwrap();
+        let period = StatementPeriod::parse("2026-02").unwrap();
+        let at = |day: u32| {
+            Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap()
+                + chrono::Duration::days(day as i64 - 1)
+        };
+        let money = |amount| DynMoney::new(amount, CurrencyCode::USD).unwrap();
+        let mut deposit = Transaction::deposit(account.id, money(1_000), None, None);
+        deposit.created_at = at(0);
+        let mut withdrawal = Transaction::withdrawal(account.id, money(300), None, None);
+        withdrawal.created_at = at(15);
+        let mut transfer_in =
+            Transaction::transfer(AccountId::new(), account.id, money(50), None, None);
+        transfer_in.created_at = at(2);
+        let mut next_month = Transaction::deposit(account.id, money(5), None, None);
+        next_month.created_at = at(29);
+
+        let history = [withdrawal.clone(), deposit, next_month, transfer_in.clone()];
+        let (statement, lines) =
+            Statement::from_history(StatementId::new(), &account, period, &history, Utc::now());
+        assert_eq!(statement.opening_balance, 1_000);
+        assert_eq!(statement.closing_balance, 750);
+        assert_eq!(statement.transaction_count, 2);
+        let ids: Vec<_> = lines.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, vec![transfer_in.id, withdrawal.id]);
+    }
+
+    #[test]
+    fn test_account_statement_covers_whole_days() {
+        let account = Account::new("Alice".into(), CurrencyCode::USD).unwrap();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 2, d).unwrap();
+        let at = |d: u32, hour: u32| Utc.with_ymd_and_hms(2026, 2, d, hour, 0, 0).unwrap();
+        let money = |amount| DynMoney::new(amount, CurrencyCode::USD).unwrap();
+        let mut before = Transaction::deposit(account.id, money(1_000), None, None);
+        before.created_at = at(9, 23);
+        let mut first = Transaction::withdrawal(account.id, money(300), None, Some("Rent".into()));
+        first.created_at = at(10, 0);
+        let mut last = Transaction::deposit(account.id, money(50), None, None);
+        last.created_at = at(12, 23);
+        let mut after = Transaction::deposit(account.id, money(5), None, None);
+        after.created_at = at(13, 0);
+
+        let history = [last.clone(), after, first.clone(), before];
+        let statement =
+            AccountStatement::from_history(&account, day(10), day(12), &history, Utc::now());
+        assert_eq!(statement.opening_balance, 1_000);
+        assert_eq!(statement.closing_balance, 750);
+        let lines: Vec<_> = statement
+            .lines
+            .iter()
+            .map(|line| (line.transaction_id, line.amount, line.balance))
+            .collect();
+        assert_eq!(lines, vec![(first.id, -300, 700), (last.id, 50, 750)]);
+        assert_eq!(statement.lines[0].description, "Rent");
+
+        let empty =
+            AccountStatement::from_history(&account, day(14), day(20), &history, Utc::now());
+        assert_eq!((empty.opening_balance, empty.closing_balance), (755, 755));
+        assert!(empty.lines.is_empty());
+    }
+}
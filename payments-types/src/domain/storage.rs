@@ -0,0 +1,124 @@
+//! Storage growth against soft quotas.
+//!
+//! A deployment is one tenant: row counts cover the whole store. Quotas are
+//! soft — crossing one raises a `quota.warning` event and never blocks writes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A table whose growth is tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageResource {
+    Accounts,
+    Transactions,
+    /// Webhook deliveries, including delivered and dead-lettered ones
+    WebhookEvents,
+}
+
+impl StorageResource {
+    pub const ALL: [StorageResource; 3] = [Self::Accounts, Self::Transactions, Self::WebhookEvents];
+}
+
+impl AsRef<str> for StorageResource {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Accounts => "accounts",
+            Self::Transactions => "transactions",
+            Self::WebhookEvents => "webhook_events",
+        }
+    }
+}
+
+impl std::fmt::Display for StorageResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// Row counts of the tracked tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageCounts {
+    pub accounts: i64,
+    pub transactions: i64,
+    pub webhook_events: i64,
+}
+
+impl StorageCounts {
+    pub fn rows(&self, resource: StorageResource) -> i64 {
+        match resource {
+            StorageResource::Accounts => self.accounts,
+            StorageResource::Transactions => self.transactions,
+            StorageResource::WebhookEvents => self.webhook_events,
+        }
+    }
+}
+
+/// One table's row count against its quota.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StorageUsage {
+    pub resource: StorageResource,
+    #[schema(example = 41250)]
+    pub rows: i64,
+    /// Soft limit; absent when the table has no quota
+    #[schema(example = 50000)]
+    pub limit: Option<i64>,
+    /// `rows / limit`, absent when the table has no quota
+    #[schema(example = 0.825)]
+    pub used: Option<f64>,
+    /// Whether `used` has reached the warning threshold
+    pub warning: bool,
+}
+
+impl StorageUsage {
+    /// Usage of `rows` against `limit`, warning at or above `warn_at`.
+    pub fn new(resource: StorageResource, rows: i64, limit: Option<i64>, warn_at: f64) -> Self {
+        let used = limit.filter(|l| *l > 0).map(|l| rows as f64 / l as f64);
+        Self {
+            resource,
+            rows,
+            limit,
+            used,
+            warning: used.is_some_and(|u| u >= warn_at),
+        }
+    }
+}
+
+/// Row counts of every tracked table against its quota.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StorageReport {
+    pub checked_at: DateTime<Utc>,
+    /// Fraction of a limit that raises a warning
+    #[schema(example = 0.8)]
+    pub warn_at: f64,
+    pub resources: Vec<StorageUsage>,
+}
+
+/// A table crossed its warning threshold, raised once per crossing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuotaWarning {
+    pub usage: StorageUsage,
+    /// Fraction of the limit that raises a warning
+    pub warn_at: f64,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_warns_at_the_threshold() {
+        let usage = StorageUsage::new(StorageResource::Accounts, 80, Some(100), 0.8);
+        assert_eq!(usage.used, Some(0.8));
+        assert!(usage.warning);
+
+        let usage = StorageUsage::new(StorageResource::Accounts, 79, Some(100), 0.8);
+        assert!(!usage.warning);
+
+        let usage = StorageUsage::new(StorageResource::Accounts, 1_000, None, 0.8);
+        assert_eq!(usage.used, None);
+        assert!(!usage.warning);
+    }
+}
@@ -1,12 +1,18 @@
 //! Transaction domain model.
 
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use super::account::AccountId;
-use super::money::DynMoney;
+use super::memo::EncryptedMemo;
+use super::mode::Mode;
+use super::money::{CurrencyCode, DynMoney};
+use super::risk::RiskAssessment;
+use crate::error::DomainError;
 
 /// Unique identifier for a Transaction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
@@ -56,7 +62,7 @@ impl std::str::FromStr for TransactionId {
 }
 
 /// The type/direction of a transaction.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionType {
     /// Money coming into an account from external source
@@ -77,10 +83,63 @@ impl std::fmt::Display for TransactionType {
     }
 }
 
+/// The external party on the other side of a deposit or withdrawal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Counterparty {
+    /// Display name, e.g. the payer's account holder name
+    #[schema(example = "ACME Payroll Ltd")]
+    pub name: String,
+    /// Identifier at the external institution (IBAN, account number, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "GB33BUKB20201555555555")]
+    pub external_id: Option<String>,
+}
+
+/// Currency conversion applied to a transfer between accounts held in
+/// different currencies.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FxConversion {
+    /// Units of the destination currency per unit of the source currency
+    #[schema(example = 0.92)]
+    pub rate: f64,
+    /// Amount credited to the destination account, in its currency
+    #[schema(value_type = Object)]
+    pub converted_amount: DynMoney,
+}
+
+impl FxConversion {
+    /// Converts `amount` into `to` at `rate`, rounding to the nearest minor
+    /// unit.
+    pub fn at_rate(amount: DynMoney, to: CurrencyCode, rate: f64) -> Result<Self, DomainError> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(DomainError::ValidationError(format!(
+                "Invalid exchange rate {} for {} -> {}",
+                rate,
+                amount.currency(),
+                to
+            )));
+        }
+        let converted = (amount.amount() as f64 * rate).round();
+        if converted < 1.0 || converted > i64::MAX as f64 {
+            return Err(DomainError::ValidationError(format!(
+                "{} {} cannot be converted to {}",
+                amount.amount(),
+                amount.currency(),
+                to
+            )));
+        }
+        Ok(Self {
+            rate,
+            converted_amount: DynMoney::new(converted as i64, to)?,
+        })
+    }
+}
+
 /// A recorded financial transaction.
 ///
 /// Transactions are immutable once created - they represent
-/// a historical record of what happened.
+/// a historical record of what happened. The one exception is a held
+/// [`RiskAssessment`], recorded once right after the money moved.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     /// Unique identifier
@@ -97,6 +156,25 @@ pub struct Transaction {
     pub idempotency_key: Option<String>,
     /// External reference (e.g., invoice number)
     pub reference: Option<String>,
+    /// External payer/payee for deposits and withdrawals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty: Option<Counterparty>,
+    /// What a withdrawal or transfer was for (see `SpendingRules`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purpose_code: Option<String>,
+    /// Rate and credited amount of a cross-currency transfer; `amount` is
+    /// what was debited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<FxConversion>,
+    /// Risk check decision when the payment was held for review
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<RiskAssessment>,
+    /// Key-value pairs the integrator attached, e.g. an order ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+    /// Memo encrypted by the customer, returned as sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_memo: Option<EncryptedMemo>,
     /// When the transaction was created
     pub created_at: DateTime<Utc>,
 }
@@ -117,6 +195,12 @@ impl Transaction {
             destination_account_id: Some(destination),
             idempotency_key,
             reference,
+            counterparty: None,
+            purpose_code: None,
+            conversion: None,
+            risk: None,
+            metadata: HashMap::new(),
+            encrypted_memo: None,
             created_at: Utc::now(),
         }
     }
@@ -136,6 +220,12 @@ impl Transaction {
             destination_account_id: None,
             idempotency_key,
             reference,
+            counterparty: None,
+            purpose_code: None,
+            conversion: None,
+            risk: None,
+            metadata: HashMap::new(),
+            encrypted_memo: None,
             created_at: Utc::now(),
         }
     }
@@ -156,6 +246,12 @@ impl Transaction {
             destination_account_id: Some(destination),
             idempotency_key,
             reference,
+            counterparty: None,
+            purpose_code: None,
+            conversion: None,
+            risk: None,
+            metadata: HashMap::new(),
+            encrypted_memo: None,
             created_at: Utc::now(),
         }
     }
@@ -180,9 +276,216 @@ impl Transaction {
             destination_account_id,
             idempotency_key,
             reference,
+            counterparty: None,
+            purpose_code: None,
+            conversion: None,
+            risk: None,
+            metadata: HashMap::new(),
+            encrypted_memo: None,
             created_at,
         }
     }
+
+    /// Attaches the external payer/payee.
+    pub fn with_counterparty(mut self, counterparty: Option<Counterparty>) -> Self {
+        self.counterparty = counterparty;
+        self
+    }
+
+    /// Attaches the purpose code the payment was made under.
+    pub fn with_purpose_code(mut self, purpose_code: Option<String>) -> Self {
+        self.purpose_code = purpose_code;
+        self
+    }
+
+    /// Attaches the conversion a cross-currency transfer was made at.
+    pub fn with_conversion(mut self, conversion: Option<FxConversion>) -> Self {
+        self.conversion = conversion;
+        self
+    }
+
+    /// Attaches the risk assessment the payment was held under.
+    pub fn with_risk(mut self, risk: Option<RiskAssessment>) -> Self {
+        self.risk = risk;
+        self
+    }
+
+    /// Attaches the integrator's metadata.
+    pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Attaches the customer's encrypted memo.
+    pub fn with_encrypted_memo(mut self, encrypted_memo: Option<EncryptedMemo>) -> Self {
+        self.encrypted_memo = encrypted_memo;
+        self
+    }
+
+    /// Amount credited to the destination account, in its currency.
+    pub fn credited_amount(&self) -> DynMoney {
+        self.conversion
+            .map_or(self.amount, |conversion| conversion.converted_amount)
+    }
+
+    /// Signed effect on `account_id`'s balance: positive when the account
+    /// was credited, negative when debited, zero if it was not involved.
+    pub fn signed_amount_for(&self, account_id: AccountId) -> i64 {
+        let credit = if self.destination_account_id == Some(account_id) {
+            self.credited_amount().amount()
+        } else {
+            0
+        };
+        let debit = if self.source_account_id == Some(account_id) {
+            self.amount.amount()
+        } else {
+            0
+        };
+        credit - debit
+    }
+}
+
+/// Narrows an account's transaction history; unset fields match everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionFilter {
+    /// Only transactions of this type
+    pub transaction_type: Option<TransactionType>,
+    /// Created at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Created before this time
+    pub to: Option<DateTime<Utc>>,
+    /// Amount of at least this many minor units
+    pub min_amount: Option<i64>,
+    /// Amount of at most this many minor units
+    pub max_amount: Option<i64>,
+}
+
+impl TransactionFilter {
+    /// Whether `tx` passes every bound that is set.
+    pub fn matches(&self, tx: &Transaction) -> bool {
+        let amount = tx.amount.amount();
+        self.transaction_type
+            .is_none_or(|t| tx.transaction_type == t)
+            && self.from.is_none_or(|from| tx.created_at >= from)
+            && self.to.is_none_or(|to| tx.created_at < to)
+            && self.min_amount.is_none_or(|min| amount >= min)
+            && self.max_amount.is_none_or(|max| amount <= max)
+    }
+}
+
+/// Criteria for searching transactions across every account; unset fields
+/// match everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionSearch {
+    /// Case-insensitive fragment of the reference
+    pub reference: Option<String>,
+    /// Sent from or to this account
+    pub account_id: Option<AccountId>,
+    /// Debited in this currency
+    pub currency: Option<CurrencyCode>,
+    /// Carrying this metadata key
+    pub metadata_key: Option<String>,
+    /// With this value under `metadata_key`
+    pub metadata_value: Option<String>,
+    /// Type, time and amount bounds
+    pub filter: TransactionFilter,
+    /// Sent from or to an account in this mode. Checked against the
+    /// accounts by the repository, not by [`matches`](Self::matches).
+    pub mode: Option<Mode>,
+}
+
+impl TransactionSearch {
+    /// Whether no criterion is set, so the search would match every
+    /// transaction.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether `tx` meets every criterion that is set.
+    pub fn matches(&self, tx: &Transaction) -> bool {
+        self.reference.as_deref().is_none_or(|fragment| {
+            tx.reference
+                .as_deref()
+                .is_some_and(|r| r.to_lowercase().contains(&fragment.to_lowercase()))
+        }) && self.account_id.is_none_or(|id| {
+            tx.source_account_id == Some(id) || tx.destination_account_id == Some(id)
+        }) && self.currency.is_none_or(|c| tx.amount.currency() == c)
+            && self.matches_metadata(tx)
+            && self.filter.matches(tx)
+    }
+
+    /// Whether `tx` carries `metadata_key`, with `metadata_value` if set.
+    pub fn matches_metadata(&self, tx: &Transaction) -> bool {
+        self.metadata_key.as_ref().is_none_or(|key| {
+            tx.metadata.get(key).is_some_and(|value| {
+                self.metadata_value
+                    .as_ref()
+                    .is_none_or(|expected| value == expected)
+            })
+        })
+    }
+}
+
+/// Position in a newest-first transaction listing: the next page starts with
+/// the transaction that sorts right after this one.
+///
+/// Transactions are ordered by `created_at`, then ID, both descending, so a
+/// cursor stays valid while new transactions are being recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: TransactionId,
+}
+
+impl TransactionCursor {
+    /// The cursor just past `tx`.
+    pub fn after(tx: &Transaction) -> Self {
+        Self {
+            created_at: tx.created_at,
+            id: tx.id,
+        }
+    }
+
+    /// Whether `tx` comes after the cursor in newest-first order.
+    pub fn precedes(&self, tx: &Transaction) -> bool {
+        (tx.created_at, tx.id.0) < (self.created_at, self.id.0)
+    }
+}
+
+/// Encoded as `{seconds}_{nanos}_{id}`; clients treat it as opaque.
+impl std::fmt::Display for TransactionCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}_{}_{}",
+            self.created_at.timestamp(),
+            self.created_at.timestamp_subsec_nanos(),
+            self.id
+        )
+    }
+}
+
+impl std::str::FromStr for TransactionCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid cursor: {}", s);
+        let mut parts = s.splitn(3, '_');
+        let secs: i64 = parts
+            .next()
+            .and_then(|p| p.parse().ok())
+            .ok_or_else(invalid)?;
+        let nanos: u32 = parts
+            .next()
+            .and_then(|p| p.parse().ok())
+            .ok_or_else(invalid)?;
+        let id = parts
+            .next()
+            .and_then(|p| p.parse().ok())
+            .ok_or_else(invalid)?;
+        let created_at = DateTime::from_timestamp(secs, nanos).ok_or_else(invalid)?;
+        Ok(Self { created_at, id })
+    }
 }
 
 #[cfg(test)]
@@ -190,6 +493,69 @@ mod tests {
     use super::*;
     use crate::domain::CurrencyCode;
 
+    #[test]
+    fn test_search_matches_every_set_criterion() {
+        let (alice, bob) = (AccountId::new(), AccountId::new());
+        let usd = |amount| DynMoney::new(amount, CurrencyCode::USD).unwrap();
+        let tx = Transaction::transfer(alice, bob, usd(2_500), None, Some("INV-2026-0042".into()));
+
+        let search = TransactionSearch {
+            reference: Some("inv-2026".into()),
+            account_id: Some(bob),
+            currency: Some(CurrencyCode::USD),
+            filter: TransactionFilter {
+                min_amount: Some(2_000),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(!search.is_empty());
+        assert!(search.matches(&tx));
+
+        let other_reference = TransactionSearch {
+            reference: Some("INV-2025".into()),
+            ..search.clone()
+        };
+        assert!(!other_reference.matches(&tx));
+        let other_currency = TransactionSearch {
+            currency: Some(CurrencyCode::EUR),
+            ..search.clone()
+        };
+        assert!(!other_currency.matches(&tx));
+        let other_account = TransactionSearch {
+            account_id: Some(AccountId::new()),
+            ..search
+        };
+        assert!(!other_account.matches(&tx));
+
+        let unreferenced = Transaction::deposit(alice, usd(2_500), None, None);
+        assert!(
+            !TransactionSearch {
+                reference: Some("INV".into()),
+                ..Default::default()
+            }
+            .matches(&unreferenced)
+        );
+        assert!(TransactionSearch::default().is_empty());
+    }
+
+    #[test]
+    fn test_search_matches_metadata_key_and_value() {
+        let usd = DynMoney::new(2_500, CurrencyCode::USD).unwrap();
+        let tx = Transaction::deposit(AccountId::new(), usd, None, None)
+            .with_metadata(HashMap::from([("order_id".into(), "ord_42".into())]));
+        let search = |key: &str, value: Option<&str>| TransactionSearch {
+            metadata_key: Some(key.into()),
+            metadata_value: value.map(Into::into),
+            ..Default::default()
+        };
+
+        assert!(search("order_id", None).matches(&tx));
+        assert!(search("order_id", Some("ord_42")).matches(&tx));
+        assert!(!search("order_id", Some("ord_43")).matches(&tx));
+        assert!(!search("customer_id", None).matches(&tx));
+    }
+
     #[test]
     fn test_deposit_creation() {
         let account = AccountId::new();
@@ -214,4 +580,48 @@ mod tests {
         assert_eq!(tx.destination_account_id, Some(bob));
         assert_eq!(tx.idempotency_key, Some("key123".to_string()));
     }
+
+    #[test]
+    fn test_conversion_credits_destination_in_its_currency() {
+        let alice = AccountId::new();
+        let bob = AccountId::new();
+        let amount = DynMoney::new(1_000, CurrencyCode::USD).unwrap();
+        let conversion = FxConversion::at_rate(amount, CurrencyCode::EUR, 0.915).unwrap();
+        assert_eq!(conversion.converted_amount.amount(), 915);
+        assert_eq!(conversion.converted_amount.currency(), CurrencyCode::EUR);
+
+        let tx =
+            Transaction::transfer(alice, bob, amount, None, None).with_conversion(Some(conversion));
+        assert_eq!(tx.signed_amount_for(alice), -1_000);
+        assert_eq!(tx.signed_amount_for(bob), 915);
+
+        assert!(FxConversion::at_rate(amount, CurrencyCode::EUR, 0.0).is_err());
+        assert!(FxConversion::at_rate(amount, CurrencyCode::EUR, f64::NAN).is_err());
+        let cent = DynMoney::new(1, CurrencyCode::INR).unwrap();
+        assert!(FxConversion::at_rate(cent, CurrencyCode::USD, 0.012).is_err());
+    }
+
+    #[test]
+    fn test_cursor_round_trips_and_orders_newest_first() {
+        let account = AccountId::new();
+        let amount = DynMoney::new(500, CurrencyCode::USD).unwrap();
+        let older = Transaction::deposit(account, amount, None, None);
+        let mut newer = Transaction::deposit(account, amount, None, None);
+        newer.created_at = older.created_at + chrono::Duration::nanoseconds(1);
+
+        let cursor = TransactionCursor::after(&newer);
+        assert_eq!(cursor.to_string().parse(), Ok(cursor));
+        assert!(cursor.precedes(&older));
+        assert!(!cursor.precedes(&newer));
+        assert!("12_x_y".parse::<TransactionCursor>().is_err());
+
+        let filter = TransactionFilter {
+            transaction_type: Some(TransactionType::Deposit),
+            min_amount: Some(500),
+            max_amount: Some(499),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&older));
+        assert!(TransactionFilter::default().matches(&older));
+    }
 }
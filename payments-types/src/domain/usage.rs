@@ -0,0 +1,191 @@
+//! Per-API-key usage metering.
+//!
+//! Usage is counted in UTC hour buckets: requests and rate-limit hits per
+//! key, and money moved per key, transaction type and currency. Reports sum
+//! the buckets over a few recent windows.
+
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::api_key::ApiKeyId;
+use super::money::CurrencyCode;
+use super::transaction::TransactionType;
+
+/// Windows reported by [`ApiKeyUsage::summarize`], in hours.
+pub const USAGE_WINDOW_HOURS: [i64; 3] = [1, 24, 30 * 24];
+
+/// Start of the UTC hour bucket `at` falls in.
+pub fn usage_hour(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.duration_trunc(TimeDelta::hours(1)).unwrap_or(at)
+}
+
+/// Requests one API key made in one hour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyUsageBucket {
+    pub api_key_id: ApiKeyId,
+    pub hour: DateTime<Utc>,
+    pub requests: i64,
+    /// Requests rejected with `429 Too Many Requests`
+    pub rate_limited: i64,
+}
+
+/// Money one API key moved in one hour, for one transaction type and currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyVolumeBucket {
+    pub api_key_id: ApiKeyId,
+    pub hour: DateTime<Utc>,
+    pub transaction_type: TransactionType,
+    pub currency: CurrencyCode,
+    pub count: i64,
+    /// Sum in smallest currency unit
+    pub amount: i64,
+}
+
+/// Money moved in a window, for one transaction type and currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VolumeTotal {
+    pub transaction_type: TransactionType,
+    pub currency: CurrencyCode,
+    #[schema(example = 12)]
+    pub count: i64,
+    /// Sum in smallest currency unit
+    #[schema(example = 250000)]
+    pub amount: i64,
+}
+
+/// Usage over one window ending now.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UsageWindow {
+    /// Length in hours; windows start on the hour, so `1` is the current hour so far
+    #[schema(example = 24)]
+    pub hours: i64,
+    pub since: DateTime<Utc>,
+    #[schema(example = 1200)]
+    pub requests: i64,
+    /// Requests rejected with `429 Too Many Requests`
+    #[schema(example = 3)]
+    pub rate_limited: i64,
+    /// Successful deposits, withdrawals and transfers, by type and currency
+    pub volume: Vec<VolumeTotal>,
+}
+
+/// Request and money-movement totals for one API key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyUsage {
+    #[schema(value_type = String, example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub api_key_id: ApiKeyId,
+    pub as_of: DateTime<Utc>,
+    /// Shortest window first
+    pub windows: Vec<UsageWindow>,
+}
+
+impl ApiKeyUsage {
+    /// Sums `usage` and `volume` buckets into each of [`USAGE_WINDOW_HOURS`]
+    /// ending at `now`.
+    pub fn summarize(
+        api_key_id: ApiKeyId,
+        now: DateTime<Utc>,
+        usage: &[ApiKeyUsageBucket],
+        volume: &[ApiKeyVolumeBucket],
+    ) -> Self {
+        let windows = USAGE_WINDOW_HOURS
+            .iter()
+            .map(|&hours| {
+                let since = usage_window_start(now, hours);
+                let buckets = usage.iter().filter(|b| b.hour >= since);
+                let mut totals: Vec<VolumeTotal> = Vec::new();
+                for bucket in volume.iter().filter(|b| b.hour >= since) {
+                    match totals.iter_mut().find(|t| {
+                        t.transaction_type == bucket.transaction_type
+                            && t.currency == bucket.currency
+                    }) {
+                        Some(total) => {
+                            total.count += bucket.count;
+                            total.amount += bucket.amount;
+                        }
+                        None => totals.push(VolumeTotal {
+                            transaction_type: bucket.transaction_type,
+                            currency: bucket.currency,
+                            count: bucket.count,
+                            amount: bucket.amount,
+                        }),
+                    }
+                }
+                totals.sort_by_key(|t| (t.transaction_type as u8, t.currency.code()));
+                UsageWindow {
+                    hours,
+                    since,
+                    requests: buckets.clone().map(|b| b.requests).sum(),
+                    rate_limited: buckets.map(|b| b.rate_limited).sum(),
+                    volume: totals,
+                }
+            })
+            .collect();
+        Self {
+            api_key_id,
+            as_of: now,
+            windows,
+        }
+    }
+}
+
+/// First hour bucket of the `hours`-long window ending at `now`.
+pub fn usage_window_start(now: DateTime<Utc>, hours: i64) -> DateTime<Utc> {
+    usage_hour(now) - TimeDelta::hours(hours - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_summarize_sums_buckets_per_window() {
+        let key = ApiKeyId::new();
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 12, 30, 0).unwrap();
+        let hour = |h: i64| usage_hour(now) - TimeDelta::hours(h);
+        let usage = [
+            ApiKeyUsageBucket {
+                api_key_id: key,
+                hour: hour(0),
+                requests: 5,
+                rate_limited: 1,
+            },
+            ApiKeyUsageBucket {
+                api_key_id: key,
+                hour: hour(3),
+                requests: 10,
+                rate_limited: 0,
+            },
+            ApiKeyUsageBucket {
+                api_key_id: key,
+                hour: hour(48),
+                requests: 100,
+                rate_limited: 7,
+            },
+        ];
+        let deposit = |h: i64, amount: i64| ApiKeyVolumeBucket {
+            api_key_id: key,
+            hour: hour(h),
+            transaction_type: TransactionType::Deposit,
+            currency: CurrencyCode::USD,
+            count: 1,
+            amount,
+        };
+        let volume = [deposit(0, 500), deposit(5, 250)];
+
+        let report = ApiKeyUsage::summarize(key, now, &usage, &volume);
+        let windows: Vec<_> = report
+            .windows
+            .iter()
+            .map(|w| (w.hours, w.requests, w.rate_limited))
+            .collect();
+        assert_eq!(windows, vec![(1, 5, 1), (24, 15, 1), (720, 115, 8)]);
+        assert_eq!(report.windows[0].since, hour(0));
+        assert_eq!(report.windows[0].volume[0].amount, 500);
+        assert_eq!(report.windows[1].volume[0].count, 2);
+        assert_eq!(report.windows[1].volume[0].amount, 750);
+    }
+}
@@ -1,15 +1,48 @@
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use super::mode::Mode;
+use crate::error::DomainError;
+
+/// Largest payload, serialized, that is delivered inline. Larger payloads are
+/// kept in full but delivered as a stub; see [`webhook_delivery_payload`].
+pub const MAX_WEBHOOK_PAYLOAD_BYTES: usize = 64 * 1024;
+
+/// Serialized size of `payload` in bytes.
+fn payload_size(payload: &serde_json::Value) -> usize {
+    serde_json::to_vec(payload).map_or(0, |bytes| bytes.len())
+}
+
+/// What event `event_id` delivers: `payload` itself, or when it is over
+/// [`MAX_WEBHOOK_PAYLOAD_BYTES`] a stub flagged `payload_truncated` that
+/// receivers resolve with `GET /api/webhooks/events/{id}`.
+pub fn webhook_delivery_payload(event_id: Uuid, payload: &serde_json::Value) -> serde_json::Value {
+    let size = payload_size(payload);
+    if size <= MAX_WEBHOOK_PAYLOAD_BYTES {
+        return payload.clone();
+    }
+    serde_json::json!({
+        "payload_truncated": true,
+        "event_id": event_id,
+        "payload_bytes": size,
+    })
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum WebhookStatus {
+    /// Not attempted yet
     #[default]
     Pending,
     Processing,
     Completed,
+    /// The last attempt failed; retried at `next_attempt_at`
     Failed,
+    /// Every attempt failed; never retried
+    DeadLettered,
 }
 
 impl AsRef<str> for WebhookStatus {
@@ -19,6 +52,7 @@ impl AsRef<str> for WebhookStatus {
             Self::Processing => "PROCESSING",
             Self::Completed => "COMPLETED",
             Self::Failed => "FAILED",
+            Self::DeadLettered => "DEAD_LETTERED",
         }
     }
 }
@@ -29,6 +63,33 @@ impl std::fmt::Display for WebhookStatus {
     }
 }
 
+impl std::str::FromStr for WebhookStatus {
+    type Err = DomainError;
+
+    /// Parses the upper-case name used in storage and responses, in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "PENDING" => Ok(Self::Pending),
+            "PROCESSING" => Ok(Self::Processing),
+            "COMPLETED" => Ok(Self::Completed),
+            "FAILED" => Ok(Self::Failed),
+            "DEAD_LETTERED" => Ok(Self::DeadLettered),
+            _ => Err(DomainError::ValidationError(format!(
+                "Unknown webhook status: {}. Expected PENDING, PROCESSING, COMPLETED, FAILED or DEAD_LETTERED",
+                s
+            ))),
+        }
+    }
+}
+
+impl WebhookStatus {
+    /// Whether the last delivery attempt failed, so the event may be
+    /// redelivered on request.
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed | Self::DeadLettered)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookEvent {
     pub id: Uuid,
@@ -40,6 +101,14 @@ pub struct WebhookEvent {
     pub processed_at: Option<DateTime<Utc>>,
     pub attempts: i32,
     pub last_error: Option<String>,
+    /// When the next delivery attempt is due; `None` once delivered or dead-lettered
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// Position among its endpoint's events, counting from 1; assigned when
+    /// the event is stored, so `0` until then
+    pub delivery_sequence: i64,
+    /// Mode of the endpoint the event was queued for
+    #[serde(default)]
+    pub mode: Mode,
 }
 
 impl WebhookEvent {
@@ -48,18 +117,209 @@ impl WebhookEvent {
         event_type: impl Into<String>,
         payload: serde_json::Value,
     ) -> Self {
+        let now = Utc::now();
         Self {
             id: Uuid::new_v4(),
             endpoint_id,
             event_type: event_type.into(),
             payload,
             status: WebhookStatus::Pending,
-            created_at: Utc::now(),
+            created_at: now,
             processed_at: None,
             attempts: 0,
             last_error: None,
+            next_attempt_at: Some(now),
+            delivery_sequence: 0,
+            mode: Mode::default(),
         }
     }
+
+    /// Whether the payload is too large to deliver inline.
+    pub fn payload_truncated(&self) -> bool {
+        payload_size(&self.payload) > MAX_WEBHOOK_PAYLOAD_BYTES
+    }
+
+    /// The body delivered for this event; see [`webhook_delivery_payload`].
+    pub fn delivery_payload(&self) -> serde_json::Value {
+        webhook_delivery_payload(self.id, &self.payload)
+    }
+}
+
+/// Selects dead-lettered events to export or redeliver; unset fields match
+/// everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeadLetterFilter {
+    /// Only events queued for this endpoint
+    pub endpoint_id: Option<WebhookEndpointId>,
+    /// Only events of this type
+    pub event_type: Option<String>,
+    /// Dead-lettered at or after this time
+    pub failed_since: Option<DateTime<Utc>>,
+    /// Only events queued for endpoints in this mode
+    pub mode: Option<Mode>,
+}
+
+impl DeadLetterFilter {
+    /// Whether `event` is dead-lettered and passes every bound that is set.
+    pub fn matches(&self, event: &WebhookEvent) -> bool {
+        event.status == WebhookStatus::DeadLettered
+            && self.endpoint_id.is_none_or(|id| event.endpoint_id == id.0)
+            && self
+                .event_type
+                .as_ref()
+                .is_none_or(|t| &event.event_type == t)
+            && self
+                .failed_since
+                .is_none_or(|since| event.processed_at.is_some_and(|at| at >= since))
+            && self.mode.is_none_or(|mode| event.mode == mode)
+    }
+}
+
+/// Selects an endpoint's events for its delivery log; unset fields match
+/// everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookDeliveryFilter {
+    /// Only events in this state
+    pub status: Option<WebhookStatus>,
+    /// Only events with a lower delivery sequence, to page back in time
+    pub before_sequence: Option<i64>,
+}
+
+impl WebhookDeliveryFilter {
+    /// Whether `event` passes every bound that is set.
+    pub fn matches(&self, event: &WebhookEvent) -> bool {
+        self.status.as_ref().is_none_or(|s| &event.status == s)
+            && self
+                .before_sequence
+                .is_none_or(|before| event.delivery_sequence < before)
+    }
+}
+
+/// Longest delivery timeout an endpoint may ask for.
+pub const MAX_WEBHOOK_TIMEOUT_MS: u32 = 60_000;
+
+/// How long a delivery to one endpoint may take before it counts as failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WebhookTimeouts {
+    /// Whole attempt: connecting, sending and reading the response
+    pub timeout_ms: u32,
+    /// Establishing the connection
+    pub connect_timeout_ms: u32,
+}
+
+impl Default for WebhookTimeouts {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 10_000,
+            connect_timeout_ms: 3_000,
+        }
+    }
+}
+
+impl WebhookTimeouts {
+    /// Fills unset values from the defaults, never letting the connect
+    /// timeout default above the overall timeout.
+    ///
+    /// Rejects zero values, values over [`MAX_WEBHOOK_TIMEOUT_MS`] and a
+    /// connect timeout longer than the overall one.
+    pub fn new(
+        timeout_ms: Option<u32>,
+        connect_timeout_ms: Option<u32>,
+    ) -> Result<Self, DomainError> {
+        let defaults = Self::default();
+        let timeout_ms = timeout_ms.unwrap_or(defaults.timeout_ms);
+        let connect_timeout_ms =
+            connect_timeout_ms.unwrap_or(defaults.connect_timeout_ms.min(timeout_ms));
+        for (name, value) in [
+            ("timeout_ms", timeout_ms),
+            ("connect_timeout_ms", connect_timeout_ms),
+        ] {
+            if value == 0 || value > MAX_WEBHOOK_TIMEOUT_MS {
+                return Err(DomainError::ValidationError(format!(
+                    "{} must be between 1 and {}, got {}",
+                    name, MAX_WEBHOOK_TIMEOUT_MS, value
+                )));
+            }
+        }
+        if connect_timeout_ms > timeout_ms {
+            return Err(DomainError::ValidationError(format!(
+                "connect_timeout_ms ({}) must not exceed timeout_ms ({})",
+                connect_timeout_ms, timeout_ms
+            )));
+        }
+        Ok(Self {
+            timeout_ms,
+            connect_timeout_ms,
+        })
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms.into())
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_millis(self.connect_timeout_ms.into())
+    }
+}
+
+/// How deliveries to an endpoint are signed, advertised to receivers in the
+/// `X-Webhook-Signature-Algorithm` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebhookSignatureAlgorithm {
+    /// Hex HMAC-SHA256 keyed with the endpoint's secret
+    #[default]
+    HmacSha256,
+    /// Hex HMAC-SHA512 keyed with the endpoint's secret
+    HmacSha512,
+    /// Hex Ed25519 detached signature, checked with the endpoint's public
+    /// key, so receivers need no secret
+    Ed25519,
+}
+
+impl AsRef<str> for WebhookSignatureAlgorithm {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::HmacSha256 => "hmac-sha256",
+            Self::HmacSha512 => "hmac-sha512",
+            Self::Ed25519 => "ed25519",
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookSignatureAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl std::str::FromStr for WebhookSignatureAlgorithm {
+    type Err = DomainError;
+
+    /// Parses the name used in storage, headers and requests, in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hmac-sha256" => Ok(Self::HmacSha256),
+            "hmac-sha512" => Ok(Self::HmacSha512),
+            "ed25519" => Ok(Self::Ed25519),
+            _ => Err(DomainError::ValidationError(format!(
+                "Unknown signature algorithm: {}. Expected hmac-sha256, hmac-sha512 or ed25519",
+                s
+            ))),
+        }
+    }
+}
+
+/// A webhook endpoint waiting to be registered, checked and with its
+/// timeouts filled in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewWebhookEndpoint {
+    pub url: String,
+    pub events: Vec<String>,
+    pub timeouts: WebhookTimeouts,
+    pub signature_algorithm: WebhookSignatureAlgorithm,
+    /// Whether the endpoint receives test or live events
+    pub mode: Mode,
 }
 
 /// A registered webhook endpoint for a business.
@@ -68,9 +328,73 @@ pub struct WebhookEndpoint {
     pub id: Uuid,
     pub url: String,
     pub secret: String,
-    pub events: Vec<String>, // Event types to subscribe to, e.g., ["transaction.created"]
+    pub events: Vec<String>, // Event types or patterns, e.g., ["deposit.success", "hold.*"]
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
+    pub timeouts: WebhookTimeouts,
+    pub signature_algorithm: WebhookSignatureAlgorithm,
+    /// Hex Ed25519 public key deliveries verify against, derived from the
+    /// signing key; `None` unless the endpoint signs with Ed25519
+    pub public_key: Option<String>,
+    /// Random hex seed of the endpoint's Ed25519 key. Unlike the secret it
+    /// stays on the server, so receivers cannot sign deliveries themselves.
+    #[serde(skip)]
+    pub signing_key: String,
+    /// Whether the endpoint receives test or live events
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+impl WebhookEndpoint {
+    /// The key deliveries are signed with: the secret for HMAC algorithms,
+    /// the server-held signing key for Ed25519.
+    pub fn signature_key(&self) -> &str {
+        match self.signature_algorithm {
+            WebhookSignatureAlgorithm::Ed25519 => &self.signing_key,
+            _ => &self.secret,
+        }
+    }
+
+    /// Whether events of `event_type` are queued for this endpoint. No
+    /// subscriptions means every event.
+    pub fn subscribes_to(&self, event_type: &str) -> bool {
+        self.events.is_empty()
+            || self
+                .events
+                .iter()
+                .any(|pattern| event_pattern_matches(pattern, event_type))
+    }
+}
+
+/// Whether the subscription `pattern` covers `event_type`: `*` covers every
+/// event and `deposit.*` every event starting `deposit.`; anything else must
+/// match exactly.
+pub fn event_pattern_matches(pattern: &str, event_type: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some("") => true,
+        Some(prefix) if prefix.ends_with('.') => event_type.starts_with(prefix),
+        _ => pattern == event_type,
+    }
+}
+
+/// Checks subscription patterns: `*` may only appear alone or as the last
+/// segment, as in `hold.*`.
+pub fn validate_event_patterns(patterns: &[String]) -> Result<(), DomainError> {
+    for pattern in patterns {
+        let wildcard = pattern.find('*');
+        let valid = !pattern.is_empty()
+            && match wildcard {
+                None => true,
+                Some(at) => at == pattern.len() - 1 && (at == 0 || pattern[..at].ends_with('.')),
+            };
+        if !valid {
+            return Err(DomainError::ValidationError(format!(
+                "Invalid event pattern '{}': use an event type, `*` or a prefix such as `hold.*`",
+                pattern
+            )));
+        }
+    }
+    Ok(())
 }
 
 /// Wrapper type for webhook endpoint ID.
@@ -106,3 +430,173 @@ impl std::str::FromStr for WebhookEndpointId {
         Ok(Self(Uuid::parse_str(s)?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_oversized_payloads_are_delivered_as_stubs() {
+        let small = WebhookEvent::new(Uuid::new_v4(), "deposit.success", json!({"amount": 1}));
+        assert!(!small.payload_truncated());
+        assert_eq!(small.delivery_payload(), small.payload);
+
+        let reference = "x".repeat(MAX_WEBHOOK_PAYLOAD_BYTES);
+        let large = WebhookEvent::new(
+            Uuid::new_v4(),
+            "deposit.success",
+            json!({"reference": reference}),
+        );
+        assert!(large.payload_truncated());
+        let stub = large.delivery_payload();
+        assert_eq!(stub["payload_truncated"], true);
+        assert_eq!(stub["event_id"], large.id.to_string());
+        assert!(stub["payload_bytes"].as_u64().unwrap() > MAX_WEBHOOK_PAYLOAD_BYTES as u64);
+    }
+
+    #[test]
+    fn test_dead_letter_filter_matches_only_dead_letters() {
+        let endpoint = WebhookEndpointId::new();
+        let failed_at = Utc::now();
+        let mut event = WebhookEvent::new(endpoint.0, "deposit.success", json!({}));
+        event.status = WebhookStatus::DeadLettered;
+        event.processed_at = Some(failed_at);
+
+        assert!(DeadLetterFilter::default().matches(&event));
+        let filter = DeadLetterFilter {
+            endpoint_id: Some(endpoint),
+            event_type: Some("deposit.success".into()),
+            failed_since: Some(failed_at),
+            mode: Some(Mode::Live),
+        };
+        assert!(filter.matches(&event));
+        assert!(
+            !DeadLetterFilter {
+                mode: Some(Mode::Test),
+                ..filter.clone()
+            }
+            .matches(&event)
+        );
+        assert!(
+            !DeadLetterFilter {
+                failed_since: Some(failed_at + chrono::TimeDelta::seconds(1)),
+                ..filter.clone()
+            }
+            .matches(&event)
+        );
+        assert!(
+            !DeadLetterFilter {
+                event_type: Some("withdraw.success".into()),
+                ..filter.clone()
+            }
+            .matches(&event)
+        );
+        assert!(
+            !DeadLetterFilter {
+                endpoint_id: Some(WebhookEndpointId::new()),
+                ..filter.clone()
+            }
+            .matches(&event)
+        );
+
+        event.status = WebhookStatus::Failed;
+        assert!(!filter.matches(&event));
+    }
+
+    #[test]
+    fn test_subscriptions_match_wildcards() {
+        let endpoint = |events: &[&str]| WebhookEndpoint {
+            id: Uuid::new_v4(),
+            url: "https://example.com/hook".into(),
+            secret: "secret".into(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            is_active: true,
+            created_at: Utc::now(),
+            timeouts: WebhookTimeouts::default(),
+            signature_algorithm: WebhookSignatureAlgorithm::default(),
+            public_key: None,
+            signing_key: String::new(),
+            mode: Mode::Live,
+        };
+
+        assert!(endpoint(&[]).subscribes_to("hold.voided"));
+        assert!(endpoint(&["*"]).subscribes_to("deposit.success"));
+        let holds = endpoint(&["hold.*", "deposit.success"]);
+        assert!(holds.subscribes_to("hold.captured"));
+        assert!(holds.subscribes_to("deposit.success"));
+        assert!(!holds.subscribes_to("withdraw.success"));
+        assert!(!holds.subscribes_to("holds.captured"));
+        assert!(!endpoint(&["deposit"]).subscribes_to("deposit.success"));
+    }
+
+    #[test]
+    fn test_event_patterns_validate() {
+        let patterns = |p: &[&str]| p.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert!(validate_event_patterns(&patterns(&["*", "hold.*", "deposit.success"])).is_ok());
+        for invalid in ["", "hold*", "*.success", "hold.*.x", "**"] {
+            assert!(
+                validate_event_patterns(&patterns(&[invalid])).is_err(),
+                "{:?} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_status_parses_any_case() {
+        for status in [
+            WebhookStatus::Pending,
+            WebhookStatus::Processing,
+            WebhookStatus::Completed,
+            WebhookStatus::Failed,
+            WebhookStatus::DeadLettered,
+        ] {
+            assert_eq!(status.to_string().parse::<WebhookStatus>().unwrap(), status);
+        }
+        assert_eq!(
+            "dead_lettered".parse::<WebhookStatus>().unwrap(),
+            WebhookStatus::DeadLettered
+        );
+        assert!("delivered".parse::<WebhookStatus>().is_err());
+        assert!(WebhookStatus::Failed.is_failed());
+        assert!(!WebhookStatus::Completed.is_failed());
+    }
+
+    #[test]
+    fn test_timeouts_default_and_validate() {
+        assert_eq!(
+            WebhookTimeouts::new(None, None).unwrap(),
+            WebhookTimeouts::default()
+        );
+        let short = WebhookTimeouts::new(Some(1_000), None).unwrap();
+        assert_eq!((short.timeout_ms, short.connect_timeout_ms), (1_000, 1_000));
+        assert_eq!(short.timeout(), Duration::from_secs(1));
+
+        assert!(WebhookTimeouts::new(Some(0), None).is_err());
+        assert!(WebhookTimeouts::new(Some(MAX_WEBHOOK_TIMEOUT_MS + 1), None).is_err());
+        assert!(WebhookTimeouts::new(Some(2_000), Some(5_000)).is_err());
+    }
+
+    #[test]
+    fn test_signature_algorithms_parse_their_names() {
+        for algorithm in [
+            WebhookSignatureAlgorithm::HmacSha256,
+            WebhookSignatureAlgorithm::HmacSha512,
+            WebhookSignatureAlgorithm::Ed25519,
+        ] {
+            let name = algorithm.to_string();
+            assert_eq!(
+                name.parse::<WebhookSignatureAlgorithm>().unwrap(),
+                algorithm
+            );
+            assert_eq!(serde_json::to_value(algorithm).unwrap(), json!(name));
+        }
+        assert_eq!(
+            "HMAC-SHA512".parse::<WebhookSignatureAlgorithm>().unwrap(),
+            WebhookSignatureAlgorithm::HmacSha512
+        );
+        assert!("rsa-sha256".parse::<WebhookSignatureAlgorithm>().is_err());
+    }
+}
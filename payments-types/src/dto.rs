@@ -1,9 +1,20 @@
 //! Data Transfer Objects (DTOs) for requests and responses.
 
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
-use crate::domain::{AccountId, CurrencyCode, TransactionId};
+use crate::domain::{
+    AccountId, AccountRef, AccountStatus, ApiKeyScope, Counterparty, CurrencyCode, EncryptedMemo,
+    FeeAssignment, FeeScheduleId, FeeTier, JournalExportFormat, LoggedEvent, Mode, OwnerId,
+    PaymentSchedule, PositiveAmount, QuoteId, RiskAssessment, SettlementBatchStatus,
+    SettlementExportFormat, StatementFormat, Transaction, TransactionId, TransactionType,
+    WebhookEvent,
+};
+use crate::error::DomainError;
+use crate::ports::Warning;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Account DTOs
@@ -12,11 +23,21 @@ use crate::domain::{AccountId, CurrencyCode, TransactionId};
 /// Request to create a new account.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateAccountRequest {
-    /// Name of the account holder
+    /// Customer that holds the account
+    pub owner_id: OwnerId,
+    /// Name of the account
     #[schema(example = "Alice")]
     pub name: String,
     #[serde(default = "default_currency")]
     pub currency: CurrencyCode,
+    /// How far below zero the balance may go, in minor units; defaults to 0
+    #[serde(default)]
+    #[schema(example = 5000)]
+    pub overdraft_limit: i64,
+    /// Whether the account holds test or live money; defaults to the mode of
+    /// the API key opening it, which is the only mode it may open
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<Mode>,
 }
 
 fn default_currency() -> CurrencyCode {
@@ -34,7 +55,121 @@ pub struct AccountResponse {
     /// Current balance in smallest currency unit (e.g., cents)
     #[schema(example = 10000)]
     pub balance: i64,
+    /// Part of the balance reserved by open holds; withdrawals and transfers
+    /// may spend only `balance - held + overdraft_limit`
+    #[schema(example = 2500)]
+    pub held: i64,
+    /// How far below zero the balance may go, in minor units
+    #[schema(example = 0)]
+    pub overdraft_limit: i64,
     pub currency: CurrencyCode,
+    pub status: AccountStatus,
+    /// When `status` last changed; omitted if it never has
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_changed_at: Option<DateTime<Utc>>,
+    /// The account this one was merged into; omitted unless it was
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged_into: Option<AccountId>,
+    /// Customer that holds the account
+    pub owner_id: OwnerId,
+    /// Whether the account holds test or live money
+    pub mode: Mode,
+}
+
+/// Request to change an account's settings; omitted fields are left as they
+/// are.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UpdateAccountRequest {
+    /// New overdraft limit in minor units; 0 allows no overdraft
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 5000)]
+    pub overdraft_limit: Option<i64>,
+}
+
+/// Request to merge an account into another.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MergeAccountRequest {
+    /// Account ID (UUID) or alias of the account to keep
+    pub into: AccountRef,
+}
+
+/// Response after merging an account into another.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountMergeResponse {
+    /// The merged account, now `CLOSED` with `merged_into` set
+    pub source: AccountResponse,
+    /// The account kept, holding the moved balance
+    pub target: AccountResponse,
+    /// The transfer that moved the balance; null if there was none to move
+    pub transaction: Option<TransactionResponse>,
+}
+
+/// Query string for `GET /api/accounts`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AccountListQuery {
+    /// Only accounts held by this owner
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<String>)]
+    pub owner_id: Option<OwnerId>,
+}
+
+/// Query string for `GET /api/accounts/search`.
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AccountSearchQuery {
+    /// Case-insensitive name fragment or account ID prefix
+    pub q: String,
+    /// Maximum number of results (default 20, capped at 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Request to register an alias for an account.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddAliasRequest {
+    /// 3-32 letters, digits or single hyphens, starting with a letter; the
+    /// leading `@` is optional
+    #[schema(example = "@alice-ops")]
+    pub alias: String,
+}
+
+/// Request to register a memo key for an account. Only the key's metadata
+/// is sent; the key itself never leaves the customer.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateMemoKeyRequest {
+    /// Cipher the memos are encrypted with: 1-40 lower-case letters, digits,
+    /// `-` or `_`
+    #[schema(example = "xchacha20poly1305")]
+    pub algorithm: String,
+    /// The customer's fingerprint of the key: 8-128 visible ASCII characters
+    #[schema(example = "sha256:9f86d081884c7d65")]
+    pub fingerprint: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Owner DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Request to create an account owner.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateOwnerRequest {
+    /// Person or organization name, 1-200 characters
+    #[schema(example = "Acme Ltd")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "finance@acme.example")]
+    pub email: Option<String>,
+}
+
+/// Request to change an owner; omitted fields are left as they are.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UpdateOwnerRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New contact address; an empty one clears it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -42,13 +177,16 @@ pub struct AccountResponse {
 // ─────────────────────────────────────────────────────────────────────────────
 
 /// Request to deposit money into an account.
+///
+/// The HTTP API also accepts the account by alias, as a
+/// `DepositRequest<AccountRef>`.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub struct DepositRequest {
+pub struct DepositRequest<A = AccountId> {
     /// Target account ID
-    pub account_id: AccountId,
+    pub account_id: A,
     /// Amount to deposit in smallest currency unit
     #[schema(example = 1000)]
-    pub amount: i64,
+    pub amount: PositiveAmount,
     pub currency: CurrencyCode,
     /// Optional idempotency key to prevent duplicate transactions
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -56,16 +194,47 @@ pub struct DepositRequest {
     /// Optional reference for the transaction
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference: Option<String>,
+    /// Who paid the money in (for bank-style statements)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty: Option<Counterparty>,
+    /// Key-value pairs to store with the transaction, e.g. an order ID;
+    /// at most 50 keys of up to 40 characters, values up to 500
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schema(example = json!({"order_id": "ord_1042"}))]
+    pub metadata: HashMap<String, String>,
+    /// Memo encrypted under an active memo key of the payer or the payee;
+    /// stored and returned as sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_memo: Option<EncryptedMemo>,
+}
+
+impl<A> DepositRequest<A> {
+    /// The same deposit into `account_id`.
+    pub fn with_account<B>(self, account_id: B) -> DepositRequest<B> {
+        DepositRequest {
+            account_id,
+            amount: self.amount,
+            currency: self.currency,
+            idempotency_key: self.idempotency_key,
+            reference: self.reference,
+            counterparty: self.counterparty,
+            metadata: self.metadata,
+            encrypted_memo: self.encrypted_memo,
+        }
+    }
 }
 
 /// Request to withdraw money from an account.
+///
+/// The HTTP API also accepts the account by alias, as a
+/// `WithdrawRequest<AccountRef>`.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub struct WithdrawRequest {
+pub struct WithdrawRequest<A = AccountId> {
     /// Source account ID
-    pub account_id: AccountId,
+    pub account_id: A,
     /// Amount to withdraw in smallest currency unit
     #[schema(example = 500)]
-    pub amount: i64,
+    pub amount: PositiveAmount,
     pub currency: CurrencyCode,
     /// Optional idempotency key to prevent duplicate transactions
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -73,18 +242,54 @@ pub struct WithdrawRequest {
     /// Optional reference for the transaction
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference: Option<String>,
+    /// Who the money is paid out to (for bank-style statements)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty: Option<Counterparty>,
+    /// What the payment is for; checked against the account's spending rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "PAYROLL")]
+    pub purpose_code: Option<String>,
+    /// Key-value pairs to store with the transaction, e.g. an order ID;
+    /// at most 50 keys of up to 40 characters, values up to 500
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schema(example = json!({"order_id": "ord_1042"}))]
+    pub metadata: HashMap<String, String>,
+    /// Memo encrypted under an active memo key of the payer or the payee;
+    /// stored and returned as sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_memo: Option<EncryptedMemo>,
+}
+
+impl<A> WithdrawRequest<A> {
+    /// The same withdrawal from `account_id`.
+    pub fn with_account<B>(self, account_id: B) -> WithdrawRequest<B> {
+        WithdrawRequest {
+            account_id,
+            amount: self.amount,
+            currency: self.currency,
+            idempotency_key: self.idempotency_key,
+            reference: self.reference,
+            counterparty: self.counterparty,
+            purpose_code: self.purpose_code,
+            metadata: self.metadata,
+            encrypted_memo: self.encrypted_memo,
+        }
+    }
 }
 
 /// Request to transfer money between accounts.
+///
+/// The HTTP API also accepts either account by alias, as a
+/// `TransferRequest<AccountRef>`.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub struct TransferRequest {
+pub struct TransferRequest<A = AccountId> {
     /// Source account ID
-    pub from_account_id: AccountId,
+    pub from_account_id: A,
     /// Destination account ID
-    pub to_account_id: AccountId,
+    pub to_account_id: A,
     /// Amount to transfer in smallest currency unit
     #[schema(example = 500)]
-    pub amount: i64,
+    pub amount: PositiveAmount,
     pub currency: CurrencyCode,
     /// Optional idempotency key to prevent duplicate transactions
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -92,6 +297,96 @@ pub struct TransferRequest {
     /// Optional reference for the transaction
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference: Option<String>,
+    /// What the payment is for; checked against the source account's spending rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "SUPPLIERS")]
+    pub purpose_code: Option<String>,
+    /// Key-value pairs to store with the transaction, e.g. an order ID;
+    /// at most 50 keys of up to 40 characters, values up to 500
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schema(example = json!({"order_id": "ord_1042"}))]
+    pub metadata: HashMap<String, String>,
+    /// Memo encrypted under an active memo key of the payer or the payee;
+    /// stored and returned as sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_memo: Option<EncryptedMemo>,
+    /// Convert `amount` at the current exchange rate if the destination
+    /// account holds another currency; without it such transfers are rejected
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub convert_currency: bool,
+    /// Convert at the rate locked by this quote from `POST /api/quotes`
+    /// rather than the current one. The quote must be unexpired, unspent and
+    /// for this amount and pair of currencies; it implies `convert_currency`
+    /// and is spent by the transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_id: Option<QuoteId>,
+}
+
+impl<A> TransferRequest<A> {
+    /// The same transfer from `from_account_id` to `to_account_id`.
+    pub fn with_accounts<B>(self, from_account_id: B, to_account_id: B) -> TransferRequest<B> {
+        TransferRequest {
+            from_account_id,
+            to_account_id,
+            amount: self.amount,
+            currency: self.currency,
+            idempotency_key: self.idempotency_key,
+            reference: self.reference,
+            purpose_code: self.purpose_code,
+            metadata: self.metadata,
+            encrypted_memo: self.encrypted_memo,
+            convert_currency: self.convert_currency,
+            quote_id: self.quote_id,
+        }
+    }
+}
+
+/// Request to reserve funds on an account until they are captured or voided.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuthorizeRequest {
+    /// Account the funds are reserved on
+    pub account_id: AccountId,
+    /// Amount to reserve in smallest currency unit
+    #[schema(example = 4999)]
+    pub amount: PositiveAmount,
+    pub currency: CurrencyCode,
+    /// Optional reference, copied onto the withdrawal made on capture
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// What the payment is for; checked against the account's spending rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "SUPPLIERS")]
+    pub purpose_code: Option<String>,
+    /// Seconds until an uncaptured hold is released (default 7 days, at most 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 86400)]
+    pub expires_in_secs: Option<u64>,
+}
+
+/// Request to capture a hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CaptureRequest {
+    /// Amount to withdraw, at most the amount held (default: all of it). The
+    /// rest of the hold is released.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 4500)]
+    pub amount: Option<PositiveAmount>,
+}
+
+/// Request to lock an exchange rate for a later transfer.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateQuoteRequest {
+    /// Currency the transfer will be made in
+    pub from_currency: CurrencyCode,
+    /// Currency of the destination account
+    pub to_currency: CurrencyCode,
+    /// Amount to convert in smallest unit of `from_currency`
+    #[schema(example = 10000)]
+    pub amount: PositiveAmount,
+    /// Seconds the rate is held for (default 60, at most 900)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 60)]
+    pub expires_in_secs: Option<u64>,
 }
 
 /// Response after a successful transaction.
@@ -106,6 +401,29 @@ pub struct TransactionResponse {
     /// New balance of destination account (for deposits/transfers)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub new_balance_destination: Option<i64>,
+    /// Risk check decision, present only when the payment was held for review
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<RiskAssessment>,
+    /// Non-blocking notes about the payment, empty when nothing looked unusual
+    pub warnings: Vec<Warning>,
+}
+
+/// A response body with the warnings raised while producing it.
+///
+/// Serialized as the body's own fields plus `warnings`, which is always
+/// present so clients can rely on it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithWarnings<T> {
+    #[serde(flatten)]
+    pub value: T,
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+}
+
+impl<T> WithWarnings<T> {
+    pub fn new(value: T, warnings: Vec<Warning>) -> Self {
+        Self { value, warnings }
+    }
 }
 
 /// Status of a transaction.
@@ -117,6 +435,224 @@ pub enum TransactionStatus {
     Failed,
 }
 
+/// Query string for `GET /api/accounts/{id}/transactions`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TransactionListQuery {
+    /// Maximum number of transactions per page (default 50, capped at 200)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// `next_cursor` of the previous page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Only transactions of this type
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    #[param(rename = "type", inline)]
+    pub transaction_type: Option<TransactionType>,
+    /// Created at or after this time (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    /// Created before this time (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+    /// Smallest amount to include, in minor units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<i64>,
+    /// Largest amount to include, in minor units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<i64>,
+}
+
+/// Query string for `GET /api/transactions/search`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TransactionSearchQuery {
+    /// Maximum number of transactions per page (default 50, capped at 200)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// `next_cursor` of the previous page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Case-insensitive fragment of the reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Only transactions carrying this metadata key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_key: Option<String>,
+    /// Only transactions whose `metadata_key` has this value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_value: Option<String>,
+    /// Sent from or to this account (ID or alias)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<String>)]
+    pub account: Option<AccountRef>,
+    /// Only transactions of this type
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    #[param(rename = "type", inline)]
+    pub transaction_type: Option<TransactionType>,
+    /// Only transactions debited in this currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(inline)]
+    pub currency: Option<CurrencyCode>,
+    /// Created at or after this time (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    /// Created before this time (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+    /// Smallest amount to include, in minor units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<i64>,
+    /// Largest amount to include, in minor units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<i64>,
+}
+
+/// One page of transactions, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionPage {
+    pub data: Vec<Transaction>,
+    /// Pass as `cursor` to get the next page; `null` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Longest external reference accepted for an inbound payment.
+pub const MAX_EXTERNAL_REFERENCE_LEN: usize = 140;
+
+/// Most metadata keys a transaction may carry.
+pub const MAX_METADATA_KEYS: usize = 50;
+
+/// Longest metadata key accepted, in characters.
+pub const MAX_METADATA_KEY_LEN: usize = 40;
+
+/// Longest metadata value accepted, in characters.
+pub const MAX_METADATA_VALUE_LEN: usize = 500;
+
+/// Funds received over external rails (e.g. a bank transfer), reported by an
+/// integration.
+///
+/// The HTTP API also accepts the account by alias, as an
+/// `InboundPaymentRequest<AccountRef>`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InboundPaymentRequest<A = AccountId> {
+    /// Account credited with the funds
+    pub account_id: A,
+    /// Amount received in smallest currency unit
+    #[schema(example = 25000)]
+    pub amount: PositiveAmount,
+    pub currency: CurrencyCode,
+    /// The rail's unique ID for the payment, at most 140 characters;
+    /// reporting it again records nothing new
+    #[schema(example = "SEPA-2026-000123")]
+    pub external_reference: String,
+    /// Remittance information sent with the payment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Who sent the payment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty: Option<Counterparty>,
+}
+
+impl<A> InboundPaymentRequest<A> {
+    /// The same payment into `account_id`.
+    pub fn with_account<B>(self, account_id: B) -> InboundPaymentRequest<B> {
+        InboundPaymentRequest {
+            account_id,
+            amount: self.amount,
+            currency: self.currency,
+            external_reference: self.external_reference,
+            reference: self.reference,
+            counterparty: self.counterparty,
+        }
+    }
+}
+
+/// An inbound payment as recorded in the ledger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundPayment {
+    /// The deposit that credited the funds
+    pub transaction: Transaction,
+    /// Whether the external reference had been recorded before, in which
+    /// case nothing new was credited
+    pub duplicate: bool,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Batch DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Most operations accepted in one batch.
+pub const MAX_BATCH_OPERATIONS: usize = 1000;
+
+/// How a batch treats an operation that fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// Apply every operation or none of them
+    #[default]
+    Atomic,
+    /// Apply each operation on its own, carrying on past failures
+    BestEffort,
+}
+
+/// One operation in a batch, tagged by `type`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchOperation<A = AccountId> {
+    Deposit(DepositRequest<A>),
+    Withdraw(WithdrawRequest<A>),
+    Transfer(TransferRequest<A>),
+}
+
+/// Request to make many payments at once.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchRequest<A = AccountId> {
+    /// `atomic` (default) or `best_effort`
+    #[serde(default)]
+    pub mode: BatchMode,
+    /// Operations to make, in order (at most 1000)
+    pub operations: Vec<BatchOperation<A>>,
+}
+
+/// What happened to one operation of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    /// The payment was made (or replayed by its idempotency key)
+    Succeeded,
+    /// The payment was refused; see `error`
+    Failed,
+    /// Not made because another operation of an atomic batch failed
+    RolledBack,
+}
+
+/// Result of one operation, at the same `index` as in the request.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub status: BatchItemStatus,
+    /// The transaction made, when `status` is `succeeded`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub transaction: Option<Transaction>,
+    /// Non-blocking notes about the payment
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+    /// Why the operation failed, when `status` is `failed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ProblemDetails>,
+}
+
+/// Per-operation results of a batch.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchResponse {
+    pub mode: BatchMode,
+    /// Operations made, including ones replayed by their idempotency key
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Webhook DTOs
 // ─────────────────────────────────────────────────────────────────────────────
@@ -127,10 +663,63 @@ pub struct RegisterWebhookRequest {
     /// The URL to receive webhook notifications
     #[schema(example = "https://example.com/webhook")]
     pub url: String,
-    /// Event types to subscribe to. If empty, subscribes to all events.
+    /// Event types to subscribe to; `*` subscribes to every event and a
+    /// prefix such as `hold.*` to every event type starting `hold.`. If
+    /// empty, subscribes to all events.
     #[serde(default)]
-    #[schema(example = json!(["deposit.success", "withdraw.success"]))]
+    #[schema(example = json!(["deposit.success", "hold.*"]))]
     pub events: Vec<String>,
+    /// Limit on a whole delivery attempt in milliseconds (default 10000, at most 60000)
+    #[serde(default)]
+    #[schema(example = 5000)]
+    pub timeout_ms: Option<u32>,
+    /// Limit on connecting in milliseconds (default 3000, at most `timeout_ms`)
+    #[serde(default)]
+    #[schema(example = 1000)]
+    pub connect_timeout_ms: Option<u32>,
+    /// How deliveries are signed
+    #[serde(default)]
+    pub signature_algorithm: crate::WebhookSignatureAlgorithm,
+}
+
+impl TryFrom<RegisterWebhookRequest> for crate::NewWebhookEndpoint {
+    type Error = DomainError;
+
+    /// Checks the URL, subscription patterns and timeouts. The endpoint is
+    /// live until the service gives it the caller's mode.
+    fn try_from(req: RegisterWebhookRequest) -> Result<Self, Self::Error> {
+        if req.url.is_empty() {
+            return Err(DomainError::ValidationError(
+                "Webhook URL cannot be empty".into(),
+            ));
+        }
+        crate::validate_event_patterns(&req.events)?;
+        Ok(Self {
+            timeouts: crate::WebhookTimeouts::new(req.timeout_ms, req.connect_timeout_ms)?,
+            url: req.url,
+            events: req.events,
+            signature_algorithm: req.signature_algorithm,
+            mode: crate::Mode::default(),
+        })
+    }
+}
+
+/// Most endpoints registered by one `POST /api/webhooks/batch`.
+pub const MAX_WEBHOOK_BATCH: usize = 100;
+
+/// Request to register several webhook endpoints at once.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegisterWebhooksRequest {
+    /// Endpoints to register, each with its own subscriptions (at most 100).
+    /// Either all of them are registered or none is.
+    pub endpoints: Vec<RegisterWebhookRequest>,
+}
+
+/// The endpoints a batch registered, in request order, each with its own
+/// signing secret.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegisterWebhooksResponse {
+    pub endpoints: Vec<WebhookResponse>,
 }
 
 /// Response after registering a webhook.
@@ -141,10 +730,810 @@ pub struct WebhookResponse {
     /// The registered webhook URL
     #[schema(example = "https://example.com/webhook")]
     pub url: String,
-    /// Secret key for verifying webhook signatures (HMAC-SHA256)
+    /// HMAC key deliveries are signed with; Ed25519 deliveries are signed
+    /// with a key that stays on the server and verify with `public_key`
     pub secret: String,
-    /// List of subscribed event types
+    /// Subscribed event types and patterns; empty means all events
     pub events: Vec<String>,
     /// Whether the webhook is active
     pub is_active: bool,
+    /// Limit on a whole delivery attempt in milliseconds
+    #[schema(example = 10000)]
+    pub timeout_ms: u32,
+    /// Limit on connecting in milliseconds
+    #[schema(example = 3000)]
+    pub connect_timeout_ms: u32,
+    /// How deliveries are signed
+    pub signature_algorithm: crate::WebhookSignatureAlgorithm,
+    /// Whether the endpoint receives test or live events, from the key that
+    /// registered it
+    pub mode: Mode,
+    /// Hex Ed25519 public key to verify deliveries with, for `ed25519`
+    /// endpoints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+/// Changes to a webhook endpoint.
+///
+/// A new `url` waits for a second admin's approval, so it is sent on its own;
+/// `events`, `is_active` and `signature_algorithm` apply at once.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UpdateWebhookRequest {
+    /// Send deliveries to this URL once another admin approves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "https://example.com/new-webhook")]
+    pub url: Option<String>,
+    /// Replace the subscribed event types and patterns; empty means all events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = json!(["deposit.success", "hold.*"]))]
+    pub events: Option<Vec<String>>,
+    /// Pause (`false`) or resume (`true`) deliveries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+    /// Sign deliveries from now on with this algorithm
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_algorithm: Option<crate::WebhookSignatureAlgorithm>,
+}
+
+impl From<crate::WebhookEndpoint> for WebhookResponse {
+    fn from(endpoint: crate::WebhookEndpoint) -> Self {
+        Self {
+            id: crate::WebhookEndpointId::from_uuid(endpoint.id),
+            url: endpoint.url,
+            secret: endpoint.secret,
+            events: endpoint.events,
+            is_active: endpoint.is_active,
+            timeout_ms: endpoint.timeouts.timeout_ms,
+            connect_timeout_ms: endpoint.timeouts.connect_timeout_ms,
+            signature_algorithm: endpoint.signature_algorithm,
+            mode: endpoint.mode,
+            public_key: endpoint.public_key,
+        }
+    }
+}
+
+/// Query string for `GET /api/webhooks/{id}/events`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebhookEventsQuery {
+    /// Only events with a higher delivery sequence; `0` (the default) starts
+    /// from the endpoint's first event
+    #[serde(default)]
+    pub after_sequence: i64,
+    /// Maximum number of events (default 100, capped at 500)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Query string for `GET /api/webhooks/{id}/deliveries`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebhookDeliveriesQuery {
+    /// Only events in this state: `PENDING`, `PROCESSING`, `COMPLETED`,
+    /// `FAILED` or `DEAD_LETTERED`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Only events with a lower delivery sequence; pass the last sequence of
+    /// a page to get the next, older one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before_sequence: Option<i64>,
+    /// Maximum number of events (default 100, capped at 500)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Query string for `GET /api/stream`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventStreamQuery {
+    /// Comma-separated event patterns, as in webhook subscriptions (e.g.
+    /// `deposit.*,transfer.success`); every event when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<String>,
+}
+
+/// Body of `POST /api/webhooks/events/retry` and query string of
+/// `GET /api/webhooks/events/dead-letters`; unset fields match every
+/// dead-lettered event.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeadLetterQuery {
+    /// Only events queued for this endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    #[param(value_type = Option<String>)]
+    pub endpoint_id: Option<crate::WebhookEndpointId>,
+    /// Only events of this type, e.g. `deposit.success`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<String>,
+    /// Dead-lettered at or after this time (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_since: Option<DateTime<Utc>>,
+}
+
+impl From<DeadLetterQuery> for crate::DeadLetterFilter {
+    fn from(query: DeadLetterQuery) -> Self {
+        Self {
+            endpoint_id: query.endpoint_id,
+            event_type: query.event_type,
+            failed_since: query.failed_since,
+            mode: None,
+        }
+    }
+}
+
+/// Result of a bulk webhook retry.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeadLetterRetryResponse {
+    /// Dead-lettered events queued for delivery again
+    #[schema(example = 120)]
+    pub requeued: u64,
+}
+
+/// A webhook event with its full payload.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookEventResponse {
+    /// Sent as the `X-Webhook-Event-Id` header and in truncated payload stubs
+    pub id: uuid::Uuid,
+    pub endpoint_id: crate::WebhookEndpointId,
+    #[schema(example = "deposit.success")]
+    pub event_type: String,
+    /// Position among the endpoint's events, counting from 1; sent as the
+    /// `X-Webhook-Delivery-Sequence` header
+    #[schema(example = 42)]
+    pub delivery_sequence: i64,
+    /// `PENDING`, `PROCESSING`, `COMPLETED`, `FAILED` or `DEAD_LETTERED`
+    #[schema(example = "COMPLETED")]
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// Whether deliveries carried a stub because the payload is over the
+    /// size limit
+    pub payload_truncated: bool,
+    /// The full payload, whatever its size
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+}
+
+impl From<WebhookEvent> for WebhookEventResponse {
+    fn from(event: WebhookEvent) -> Self {
+        Self {
+            id: event.id,
+            endpoint_id: crate::WebhookEndpointId::from_uuid(event.endpoint_id),
+            payload_truncated: event.payload_truncated(),
+            event_type: event.event_type,
+            delivery_sequence: event.delivery_sequence,
+            status: event.status.to_string(),
+            attempts: event.attempts,
+            last_error: event.last_error,
+            created_at: event.created_at,
+            processed_at: event.processed_at,
+            next_attempt_at: event.next_attempt_at,
+            payload: event.payload,
+        }
+    }
+}
+
+/// One entry of an endpoint's delivery log: a webhook event without its
+/// payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    /// Event ID; fetch the payload from `GET /api/webhooks/events/{id}`
+    pub id: uuid::Uuid,
+    pub endpoint_id: crate::WebhookEndpointId,
+    #[schema(example = "deposit.success")]
+    pub event_type: String,
+    #[schema(example = 42)]
+    pub delivery_sequence: i64,
+    /// `PENDING`, `PROCESSING`, `COMPLETED`, `FAILED` or `DEAD_LETTERED`
+    #[schema(example = "FAILED")]
+    pub status: String,
+    /// Delivery attempts made so far
+    #[schema(example = 3)]
+    pub attempts: i32,
+    /// Why the last attempt failed
+    #[schema(example = "HTTP 503")]
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// When the event was delivered or dead-lettered
+    pub processed_at: Option<DateTime<Utc>>,
+    /// When the next attempt is due
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookEvent> for WebhookDeliveryResponse {
+    fn from(event: WebhookEvent) -> Self {
+        Self {
+            id: event.id,
+            endpoint_id: crate::WebhookEndpointId::from_uuid(event.endpoint_id),
+            event_type: event.event_type,
+            delivery_sequence: event.delivery_sequence,
+            status: event.status.to_string(),
+            attempts: event.attempts,
+            last_error: event.last_error,
+            created_at: event.created_at,
+            processed_at: event.processed_at,
+            next_attempt_at: event.next_attempt_at,
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Fee DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Request to create a fee schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateFeeScheduleRequest {
+    #[schema(example = "Standard 2025")]
+    pub name: String,
+    /// Tiers in ascending `up_to` order, ending with an open-ended tier
+    pub tiers: Vec<FeeTier>,
+}
+
+/// Request to apply a fee schedule to an account.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AssignFeeScheduleRequest {
+    pub fee_schedule_id: FeeScheduleId,
+    /// When the schedule starts to apply; defaults to now and cannot be in the past
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_from: Option<DateTime<Utc>>,
+}
+
+/// An account's fee schedule assignments.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountFees {
+    /// The assignment in force now, if any
+    pub current: Option<FeeAssignment>,
+    /// Every assignment ever recorded, oldest effective date first
+    pub assignments: Vec<FeeAssignment>,
+}
+
+/// Query string for `GET /api/accounts/{id}/fees/quote`.
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeeQuoteQuery {
+    /// Payment amount in smallest currency unit
+    pub amount: PositiveAmount,
+}
+
+/// The fee an account would pay for a payment made now.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeeQuote {
+    pub account_id: AccountId,
+    #[schema(example = 10000)]
+    pub amount: i64,
+    /// Fee in smallest currency unit; 0 when no schedule applies
+    #[schema(example = 175)]
+    pub fee: i64,
+    /// Schedule that priced the payment
+    pub fee_schedule_id: Option<FeeScheduleId>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Beneficiary DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Request to add an external account that an account pays out to.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateBeneficiaryRequest {
+    /// Account that will pay the beneficiary
+    pub account_id: AccountId,
+    #[schema(example = "ACME Supplies")]
+    pub name: String,
+    /// Identifier at the external institution (IBAN, account number, ...)
+    #[schema(example = "GB33BUKB20201555555555")]
+    pub external_id: String,
+}
+
+/// Request to confirm the micro-deposits sent to a beneficiary.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VerifyBeneficiaryRequest {
+    /// The two amounts received, in smallest currency unit, in either order
+    #[schema(example = json!([12, 47]))]
+    pub amounts: Vec<i64>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Settlement DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Request to batch up the payouts made so far.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CreateSettlementBatchRequest {
+    /// Latest withdrawal time to include; defaults to now and cannot be in the future
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cutoff: Option<DateTime<Utc>>,
+}
+
+/// Request to move a settlement batch along its lifecycle.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateSettlementBatchStatusRequest {
+    pub status: SettlementBatchStatus,
+}
+
+/// Query string for `GET /api/settlement-batches/{id}/export`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SettlementExportQuery {
+    /// `csv` (default) or `pain001`
+    #[serde(default)]
+    pub format: SettlementExportFormat,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Statement DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Where an account's statements are emailed; `null` sends them by webhook only.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StatementEmail {
+    #[serde(default)]
+    #[schema(example = "finance@example.com")]
+    pub email: Option<String>,
+}
+
+/// Request to issue statements for a month.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct IssueStatementsRequest {
+    /// `YYYY-MM`; defaults to the last full month
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "2026-03")]
+    pub month: Option<String>,
+}
+
+/// Query string of a signed statement download URL.
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatementDownloadQuery {
+    /// Unix time the link expires at
+    pub expires: i64,
+    /// Hex HMAC-SHA256 of the statement ID and `expires`
+    pub signature: String,
+}
+
+/// Query string for `GET /api/accounts/{id}/statement`.
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AccountStatementQuery {
+    /// First day of the period (UTC), inclusive
+    #[param(example = "2026-03-01")]
+    pub from: NaiveDate,
+    /// Last day of the period (UTC), inclusive
+    #[param(example = "2026-03-31")]
+    pub to: NaiveDate,
+    /// `csv` (default) or `json`
+    #[serde(default)]
+    pub format: StatementFormat,
+}
+
+/// Query string for `GET /api/accounts/{id}/balance-history`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BalanceHistoryQuery {
+    /// First day (UTC), inclusive; defaults to 30 days before `to`
+    #[param(example = "2026-03-01")]
+    pub from: Option<NaiveDate>,
+    /// Last day (UTC), inclusive; defaults to yesterday
+    #[param(example = "2026-03-31")]
+    pub to: Option<NaiveDate>,
+}
+
+/// Response from `POST /api/admin/balance-snapshots`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BalanceSnapshotResponse {
+    /// Daily closing balances stored by this run, across all accounts
+    #[schema(example = 42)]
+    pub recorded: usize,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Accounting Export DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Query string for `GET /api/exports/journal`.
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JournalExportQuery {
+    /// `quickbooks` (default) or `xero`
+    #[serde(default)]
+    pub format: JournalExportFormat,
+    /// First day of the period (UTC), inclusive
+    #[param(example = "2026-03-01")]
+    pub from: NaiveDate,
+    /// Last day of the period (UTC), inclusive
+    #[param(example = "2026-03-31")]
+    pub to: NaiveDate,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Event Log DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Response header of `GET /api/exports/events` with the UTC day the page's
+/// events were recorded on; absent when the page is empty.
+pub const EVENT_LOG_DAY_HEADER: &str = "x-event-log-day";
+
+/// Response header of `GET /api/exports/events` with the `after_sequence` to
+/// ask for next.
+pub const EVENT_LOG_CURSOR_HEADER: &str = "x-event-log-cursor";
+
+/// Query string for `GET /api/exports/events`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventLogQuery {
+    /// Only events with a higher sequence; pass the previous page's cursor.
+    /// `0` (the default) starts from the first event
+    #[serde(default)]
+    pub after_sequence: i64,
+    /// Maximum number of events (default 1000, capped at 10000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// A page of the event log whose events were all recorded on one UTC day,
+/// so each page can be loaded into that day's partition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventLogPage {
+    /// The day the events were recorded on; `None` when there are none yet
+    pub day: Option<NaiveDate>,
+    pub events: Vec<LoggedEvent>,
+    /// `after_sequence` for the next page: the last event's sequence, or the
+    /// one asked for when there are no new events
+    pub cursor: i64,
+}
+
+impl EventLogPage {
+    /// Pages `events`, listed after `after_sequence`, up to the first one
+    /// recorded on another day than the first.
+    pub fn new(after_sequence: i64, mut events: Vec<LoggedEvent>) -> Self {
+        let day = events.first().map(|e| e.recorded_at.date_naive());
+        if let Some(day) = day {
+            let same_day = events
+                .iter()
+                .take_while(|e| e.recorded_at.date_naive() == day)
+                .count();
+            events.truncate(same_day);
+        }
+        let cursor = events.last().map_or(after_sequence, |e| e.sequence);
+        Self {
+            day,
+            events,
+            cursor,
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Report DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Query string for `GET /api/reports/exposure`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExposureQuery {
+    /// Currency to convert balances into (default USD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(inline)]
+    pub base: Option<CurrencyCode>,
+    /// Use the last rates recorded at or before this time (RFC 3339)
+    /// instead of current rates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Query string for `GET /api/reports/float`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FloatReportQuery {
+    /// First day (UTC) of external flows, inclusive; defaults to 30 days
+    /// before `to`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(example = "2026-03-01")]
+    pub from: Option<NaiveDate>,
+    /// Last day (UTC), inclusive; defaults to today
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(example = "2026-03-31")]
+    pub to: Option<NaiveDate>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Scheduled Payment DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Request to make a withdrawal or transfer on a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateScheduledPaymentRequest {
+    /// `WITHDRAWAL` or `TRANSFER`
+    pub transaction_type: TransactionType,
+    /// Account the money is taken from
+    pub from_account_id: AccountId,
+    /// Destination account; required for transfers, not allowed for withdrawals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_account_id: Option<AccountId>,
+    /// Amount of each payment in smallest currency unit
+    #[schema(example = 2500)]
+    pub amount: PositiveAmount,
+    pub currency: CurrencyCode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Checked against the source account's spending rules on every run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purpose_code: Option<String>,
+    pub schedule: PaymentSchedule,
+    /// Earliest time of the first run; defaults to now and cannot be in the past
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_at: Option<DateTime<Utc>>,
+}
+
+/// Query string for `GET /api/scheduled-payments`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScheduledPaymentQuery {
+    /// Only payments drawing on this account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<String>)]
+    pub account_id: Option<AccountId>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Admin DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Request to switch maintenance mode on or off.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetMaintenanceRequest {
+    pub enabled: bool,
+    /// Shown to clients whose mutations are rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Database migration in progress")]
+    pub reason: Option<String>,
+}
+
+/// Current maintenance mode state.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceStatus {
+    /// When `true`, mutations are rejected with 503 and reads keep working
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Request to post or clear the incident banner on `/status`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SetIncidentRequest {
+    /// Banner text; omit it or send `null` to clear the banner
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Card deposits are delayed by up to 10 minutes")]
+    pub message: Option<String>,
+}
+
+/// Request to give an API key its own quota.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SetApiKeyRateLimitRequest {
+    /// Requests the key may make per minute; omit it or send `null` to fall
+    /// back to the server-wide default
+    #[serde(default)]
+    #[schema(example = 1000)]
+    pub requests_per_minute: Option<u32>,
+}
+
+/// Coarse health reported by `/status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceHealth {
+    /// Reads and writes are being served
+    Operational,
+    /// The database is not answering
+    Degraded,
+    /// Read-only maintenance mode is on
+    Maintenance,
+}
+
+/// An incident banner posted by an admin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Incident {
+    #[schema(example = "Card deposits are delayed by up to 10 minutes")]
+    pub message: String,
+    pub posted_at: DateTime<Utc>,
+}
+
+/// Public service status, for integrators' status checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ServiceStatus {
+    pub status: ServiceHealth,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incident: Option<Incident>,
+}
+
+/// Operational detail for runbooks and monitoring, from
+/// `GET /api/admin/diagnostics`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Diagnostics {
+    /// `degraded` when the database is down or any check is past its limit
+    pub status: ServiceHealth,
+    /// Round trip of a trivial read; absent when the database is not answering
+    #[schema(example = 1.8)]
+    pub database_latency_ms: Option<f64>,
+    /// Latest migration this build applies
+    #[schema(example = 34)]
+    pub schema_version: u32,
+    /// Webhook events waiting to be delivered; absent when the database is
+    /// not answering
+    #[schema(example = 12)]
+    pub webhook_backlog: Option<i64>,
+    /// Share of its quota the busiest API key has used, from 0 to 1
+    #[schema(example = 0.25)]
+    pub rate_limit_saturation: f64,
+    /// What is degraded, one line per failing check
+    #[schema(example = json!(["webhook backlog is 1500 events (limit 1000)"]))]
+    pub problems: Vec<String>,
+}
+
+/// Whether an instance should be sent traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessStatus {
+    /// Every dependency answered
+    Ready,
+    /// At least one dependency did not answer
+    NotReady,
+}
+
+/// Whether a dependency answered its probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyStatus {
+    Up,
+    Down,
+}
+
+/// How one dependency answered a readiness probe.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DependencyCheck {
+    #[schema(example = "database")]
+    pub name: String,
+    pub status: DependencyStatus,
+    /// How long the probe took, whether or not it succeeded
+    #[schema(example = 1.8)]
+    pub latency_ms: f64,
+}
+
+/// Body of `GET /health/ready`, sent with 200 and 503 alike.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Readiness {
+    /// `ready` only when every check is `up`
+    pub status: ReadinessStatus,
+    pub checks: Vec<DependencyCheck>,
+}
+
+/// Query string for `GET /api/admin/changes`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangeRequestQuery {
+    /// Only changes in this state, e.g. `PENDING`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<String>)]
+    pub status: Option<crate::ChangeStatus>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Session Token DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Request to exchange the caller's API key for a read-only session token.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateSessionTokenRequest {
+    /// The only account the token can read
+    pub account_id: AccountId,
+    /// Seconds until the token expires (default 15 minutes, at most 1 hour)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 900)]
+    pub expires_in_secs: Option<u64>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Error DTOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Machine-readable reason for an error response, stable across releases.
+///
+/// Match on this rather than on `error`, whose wording may change. New codes
+/// may be added; treat unknown ones by their HTTP status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The request is malformed or fails validation (400)
+    InvalidRequest,
+    /// An amount that must be positive is zero or negative (400)
+    InvalidAmount,
+    /// The debited account's available balance is too low (400)
+    InsufficientFunds,
+    /// The amount's currency differs from the account's (400)
+    CurrencyMismatch,
+    /// A transfer between accounts of different currencies (400)
+    CrossCurrencyTransfer,
+    /// Missing, invalid or expired API key or session token (401)
+    Unauthorized,
+    /// The API key lacks the route's scope (403)
+    InsufficientScope,
+    /// The credentials may not use this route at all (403)
+    Forbidden,
+    /// The account does not exist (404)
+    AccountNotFound,
+    /// Some other resource does not exist (404)
+    NotFound,
+    /// A concurrent change got there first; safe to retry (409)
+    Conflict,
+    /// The idempotency key was used with different parameters (409)
+    IdempotencyKeyConflict,
+    /// The amount exceeds the maximum transaction amount (422)
+    AmountLimitExceeded,
+    /// The balance would exceed the maximum account balance (422)
+    BalanceLimitExceeded,
+    /// The account's daily debit limit would be exceeded (422)
+    DailyDebitLimitExceeded,
+    /// The account took part in too many transactions in the past hour (422)
+    HourlyTransactionLimitExceeded,
+    /// The account's spending rules refuse the payment (422)
+    SpendingRuleViolation,
+    /// The account is dormant and must be reactivated first (422)
+    AccountDormant,
+    /// The account is frozen (422)
+    AccountFrozen,
+    /// The account is closed (422)
+    AccountClosed,
+    /// A risk check denied the payment (422)
+    RiskDenied,
+    /// A stored currency code is not supported by this release (422)
+    UnsupportedCurrency,
+    /// Too many requests; see `retry_after_seconds` (429)
+    RateLimited,
+    /// An unexpected server error (500)
+    InternalError,
+    /// The service cannot be reached right now; safe to retry (503)
+    ServiceUnavailable,
+    /// The service is read-only for maintenance; see `reason` (503)
+    MaintenanceMode,
+}
+
+/// Body of every 4xx and 5xx response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({"error": "Invalid amount: Amount must be positive, got 0", "code": 400, "error_code": "INVALID_AMOUNT"}))]
+pub struct ProblemDetails {
+    /// What went wrong, for humans
+    pub error: String,
+    /// The HTTP status code
+    #[schema(example = 400)]
+    pub code: u16,
+    /// What went wrong, for programs
+    pub error_code: ErrorCode,
+    /// The scope the API key lacks (`INSUFFICIENT_SCOPE` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "transactions:write")]
+    pub required_scope: Option<ApiKeyScope>,
+    /// Why the service is read-only (`MAINTENANCE_MODE` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Seconds to wait before retrying (`429` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
+}
+
+impl ProblemDetails {
+    /// A problem with status `code` and no further details.
+    pub fn new(code: u16, error_code: ErrorCode, error: impl Into<String>) -> Self {
+        Self {
+            error: error.into(),
+            code,
+            error_code,
+            required_scope: None,
+            reason: None,
+            retry_after_seconds: None,
+        }
+    }
 }
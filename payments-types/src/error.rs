@@ -1,6 +1,7 @@
 //! Error types for the payment service.
 
 use crate::domain::{AccountId, CurrencyCode};
+use crate::dto::ErrorCode;
 
 /// Domain-level errors (business logic violations).
 #[derive(Debug, thiserror::Error)]
@@ -8,6 +9,9 @@ pub enum DomainError {
     #[error("Amount cannot be negative")]
     NegativeAmount,
 
+    #[error("Amount must be positive, got {0}")]
+    NonPositiveAmount(i64),
+
     #[error("Currency mismatch: expected {expected}, got {got}")]
     CurrencyMismatch {
         expected: CurrencyCode,
@@ -45,8 +49,27 @@ pub enum RepoError {
     #[error("Entity not found")]
     NotFound,
 
+    /// A stored currency code this build does not know, e.g. one written by
+    /// a newer release.
+    #[error("Unsupported currency: {0}")]
+    UnsupportedCurrency(String),
+
+    /// Another writer changed the same rows first and nothing was written.
+    /// Safe to retry.
     #[error("Conflict: {0}")]
     Conflict(String),
+
+    /// The database could not be reached (pool timeout, connection reset).
+    /// Safe to retry.
+    #[error("Database unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl RepoError {
+    /// Returns `true` if the operation may succeed when retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, RepoError::Unavailable(_) | RepoError::Conflict(_))
+    }
 }
 
 /// Application-level errors (for HTTP responses).
@@ -57,14 +80,121 @@ pub enum AppError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    /// A zero or negative amount where money must move
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
+    /// The request's API key may not do this
+    #[error("Access denied: {0}")]
+    AccessDenied(String),
+
+    #[error("Account not found: {0}")]
+    AccountNotFound(AccountId),
+
     #[error("Insufficient funds: available {available}, requested {requested}")]
     InsufficientFunds { available: i64, requested: i64 },
 
+    #[error("Currency mismatch: expected {expected}, got {got}")]
+    CurrencyMismatch {
+        expected: CurrencyCode,
+        got: CurrencyCode,
+    },
+
+    #[error("Cannot transfer between accounts with different currencies")]
+    CrossCurrencyTransfer,
+
+    #[error("Amount {amount} exceeds the maximum transaction amount of {max}")]
+    AmountLimitExceeded { amount: i64, max: i64 },
+
+    #[error("Balance {balance} would exceed the maximum account balance of {max}")]
+    BalanceLimitExceeded { balance: i64, max: i64 },
+
+    #[error("Daily debit limit exceeded: {spent} already sent, {requested} requested, limit {max}")]
+    DailyDebitLimitExceeded {
+        spent: i64,
+        requested: i64,
+        max: i64,
+    },
+
+    #[error("Hourly transaction limit exceeded: {count} in the past hour, limit {max}")]
+    HourlyTransactionLimitExceeded { count: i64, max: i64 },
+
+    #[error("Spending rule violation: {0}")]
+    SpendingRuleViolation(String),
+
+    #[error("Account {0} is dormant and must be reactivated before money can leave it")]
+    AccountDormant(AccountId),
+
+    #[error("Account {0} is frozen and must be unfrozen before money can leave it")]
+    AccountFrozen(AccountId),
+
+    #[error("Account {0} is closed")]
+    AccountClosed(AccountId),
+
+    #[error("Payment denied by risk check: {0}")]
+    RiskDenied(String),
+
+    #[error("Unsupported currency: {0}")]
+    UnsupportedCurrency(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Idempotency key conflict: key {0} was already used with different parameters")]
+    IdempotencyKeyConflict(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+}
+
+impl AppError {
+    /// Returns `true` if a payment was refused for breaking an amount,
+    /// balance, daily debit or hourly transaction limit.
+    pub fn is_limit_exceeded(&self) -> bool {
+        matches!(
+            self,
+            AppError::AmountLimitExceeded { .. }
+                | AppError::BalanceLimitExceeded { .. }
+                | AppError::DailyDebitLimitExceeded { .. }
+                | AppError::HourlyTransactionLimitExceeded { .. }
+        )
+    }
+
+    /// The machine-readable code the error is reported with.
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            // Refused keys have always been answered with 400
+            AppError::BadRequest(_) | AppError::AccessDenied(_) => ErrorCode::InvalidRequest,
+            AppError::InvalidAmount(_) => ErrorCode::InvalidAmount,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::AccountNotFound(_) => ErrorCode::AccountNotFound,
+            AppError::InsufficientFunds { .. } => ErrorCode::InsufficientFunds,
+            AppError::CurrencyMismatch { .. } => ErrorCode::CurrencyMismatch,
+            AppError::CrossCurrencyTransfer => ErrorCode::CrossCurrencyTransfer,
+            AppError::AmountLimitExceeded { .. } => ErrorCode::AmountLimitExceeded,
+            AppError::BalanceLimitExceeded { .. } => ErrorCode::BalanceLimitExceeded,
+            AppError::DailyDebitLimitExceeded { .. } => ErrorCode::DailyDebitLimitExceeded,
+            AppError::HourlyTransactionLimitExceeded { .. } => {
+                ErrorCode::HourlyTransactionLimitExceeded
+            }
+            AppError::SpendingRuleViolation(_) => ErrorCode::SpendingRuleViolation,
+            AppError::AccountDormant(_) => ErrorCode::AccountDormant,
+            AppError::AccountFrozen(_) => ErrorCode::AccountFrozen,
+            AppError::AccountClosed(_) => ErrorCode::AccountClosed,
+            AppError::RiskDenied(_) => ErrorCode::RiskDenied,
+            AppError::UnsupportedCurrency(_) => ErrorCode::UnsupportedCurrency,
+            AppError::Conflict(_) => ErrorCode::Conflict,
+            AppError::IdempotencyKeyConflict(_) => ErrorCode::IdempotencyKeyConflict,
+            AppError::Internal(_) => ErrorCode::InternalError,
+            AppError::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
+        }
+    }
 }
 
 impl From<RepoError> for AppError {
@@ -78,14 +208,83 @@ impl From<RepoError> for AppError {
                 requested,
             },
             RepoError::Domain(DomainError::ValidationError(msg)) => AppError::BadRequest(msg),
-            RepoError::Domain(DomainError::AccountNotFound(id)) => {
-                AppError::NotFound(format!("Account not found: {}", id))
+            RepoError::Domain(
+                e @ (DomainError::NegativeAmount | DomainError::NonPositiveAmount(_)),
+            ) => AppError::InvalidAmount(e.to_string()),
+            RepoError::Domain(DomainError::AccountNotFound(id)) => AppError::AccountNotFound(id),
+            RepoError::Domain(DomainError::CurrencyMismatch { expected, got }) => {
+                AppError::CurrencyMismatch { expected, got }
+            }
+            RepoError::Domain(DomainError::CrossCurrencyTransfer) => {
+                AppError::CrossCurrencyTransfer
+            }
+            RepoError::Domain(DomainError::IdempotencyKeyConflict(key)) => {
+                AppError::IdempotencyKeyConflict(key)
             }
-            RepoError::Domain(e) => AppError::BadRequest(e.to_string()),
             RepoError::NotFound => AppError::NotFound("Resource not found".into()),
+            RepoError::UnsupportedCurrency(code) => AppError::UnsupportedCurrency(code),
             RepoError::Database(e) => AppError::Internal(e),
             RepoError::Transaction(e) => AppError::Internal(e),
-            RepoError::Conflict(e) => AppError::BadRequest(e),
+            RepoError::Conflict(e) => AppError::Conflict(e),
+            RepoError::Unavailable(e) => AppError::ServiceUnavailable(e),
         }
     }
 }
+
+impl From<DomainError> for AppError {
+    fn from(err: DomainError) -> Self {
+        AppError::from(RepoError::Domain(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unavailable_and_conflicts_are_transient() {
+        assert!(RepoError::Unavailable("pool timed out".into()).is_transient());
+        assert!(RepoError::Conflict("balance changed".into()).is_transient());
+        assert!(!RepoError::Database("syntax error".into()).is_transient());
+        assert!(!RepoError::NotFound.is_transient());
+    }
+
+    #[test]
+    fn test_domain_errors_keep_their_error_codes() {
+        let missing = AppError::from(RepoError::Domain(DomainError::AccountNotFound(
+            AccountId::new(),
+        )));
+        assert_eq!(missing.error_code(), ErrorCode::AccountNotFound);
+        let cross = AppError::from(RepoError::Domain(DomainError::CrossCurrencyTransfer));
+        assert_eq!(cross.error_code(), ErrorCode::CrossCurrencyTransfer);
+        let negative = AppError::from(RepoError::Domain(DomainError::NegativeAmount));
+        assert_eq!(negative.error_code(), ErrorCode::InvalidAmount);
+        assert_eq!(
+            serde_json::to_value(ErrorCode::CrossCurrencyTransfer).unwrap(),
+            "CROSS_CURRENCY_TRANSFER"
+        );
+    }
+
+    #[test]
+    fn test_non_positive_amounts_share_one_error_code() {
+        for amount in [0, -100] {
+            let err = AppError::from(crate::PositiveAmount::new(amount).unwrap_err());
+            assert!(matches!(&err, AppError::InvalidAmount(_)));
+            assert_eq!(err.error_code(), ErrorCode::InvalidAmount);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_currency_keeps_its_error_code() {
+        let err = AppError::from(RepoError::UnsupportedCurrency("CHF".into()));
+        assert!(matches!(&err, AppError::UnsupportedCurrency(code) if code == "CHF"));
+        assert_eq!(err.error_code(), ErrorCode::UnsupportedCurrency);
+        assert!(!RepoError::UnsupportedCurrency("CHF".into()).is_transient());
+    }
+
+    #[test]
+    fn test_unavailable_maps_to_service_unavailable() {
+        let err = AppError::from(RepoError::Unavailable("connection reset".into()));
+        assert!(matches!(err, AppError::ServiceUnavailable(msg) if msg == "connection reset"));
+    }
+}
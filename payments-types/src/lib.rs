@@ -19,12 +19,46 @@ pub mod ports;
 
 // Re-export commonly used types
 pub use domain::{
-    Account, AccountId, ApiKey, ApiKeyId, CurrencyCode, DynMoney, Transaction, TransactionId,
-    TransactionType, WebhookEndpoint, WebhookEndpointId, WebhookEvent, WebhookStatus,
+    Account, AccountAlias, AccountId, AccountLimits, AccountMerge, AccountRef, AccountStatement,
+    AccountStatus, ActorContext, Alias, AmountFormat, ApiKey, ApiKeyAudit, ApiKeyAuditAction,
+    ApiKeyAuditEntry, ApiKeyId, ApiKeyScope, ApiKeyUsage, ApiKeyUsageBucket, ApiKeyVolumeBucket,
+    BalanceHistory, Beneficiary, BeneficiaryId, BeneficiaryStatus, BlockedTransaction,
+    ChangeAction, ChangeRequest, ChangeRequestId, ChangeStatus, Counterparty, CurrencyBalance,
+    CurrencyCode, CurrencyExposure, CurrencyTotal, DEFAULT_HOLD_TTL_SECS, DEFAULT_QUOTE_TTL_SECS,
+    DEFAULT_SESSION_TTL_SECS, DailyBalance, DeadLetterFilter, DomainEvent, DynMoney, EVENT_CATALOG,
+    EncryptedMemo, EventField, EventSpec, Export, ExportDownload, ExportId, ExportRequest,
+    ExportStatus, ExposureReport, FeeAssignment, FeeSchedule, FeeScheduleId, FeeTier, FloatFlow,
+    FloatPosition, FloatReport, FxConversion, Hold, HoldId, HoldStatus,
+    IDEMPOTENCY_RESERVATION_TTL, IdempotencyRecord, JournalExportFormat, LAST_USED_RESOLUTION_SECS,
+    LoggedEvent, MAX_ALIASES_PER_ACCOUNT, MAX_DISPLAY_DECIMALS, MAX_HOLD_TTL_SECS,
+    MAX_MEMO_CIPHERTEXT_LEN, MAX_MEMO_KEYS_PER_ACCOUNT, MAX_MICRO_DEPOSIT, MAX_QUOTE_TTL_SECS,
+    MAX_RATE_LIMIT_PER_MINUTE, MAX_SCHEDULE_INTERVAL_SECS, MAX_SESSION_TTL_SECS,
+    MAX_VERIFICATION_ATTEMPTS, MAX_WEBHOOK_PAYLOAD_BYTES, MAX_WEBHOOK_TIMEOUT_MS,
+    MIN_SCHEDULE_INTERVAL_SECS, MemoKey, MemoKeyId, Mode, NewWebhookEndpoint, Owner, OwnerId,
+    PAYMENTS_IDEMPOTENCY_ENDPOINT, PaymentSchedule, PositiveAmount, QuotaWarning, Quote, QuoteId,
+    RateSnapshot, RiskAssessment, RiskDecision, Rounding, ScheduledPayment, ScheduledPaymentId,
+    ScheduledPaymentStatus, SessionToken, SettlementBatch, SettlementBatchId,
+    SettlementBatchStatus, SettlementExportFormat, SpendingRules, Statement, StatementDownload,
+    StatementFormat, StatementId, StatementLine, StatementPeriod, StorageCounts, StorageReport,
+    StorageResource, StorageUsage, Transaction, TransactionCursor, TransactionFilter,
+    TransactionId, TransactionSearch, TransactionType, USAGE_WINDOW_HOURS, UsageWindow,
+    VolumeTotal, WebhookDeliveryFilter, WebhookEndpoint, WebhookEndpointId, WebhookEvent,
+    WebhookSignatureAlgorithm, WebhookStatus, WebhookTimeouts, event_pattern_matches, event_spec,
+    normalize_purpose_code, usage_hour, usage_window_start, validate_event_patterns,
+    webhook_delivery_payload,
 };
 pub use dto::*;
 pub use error::{AppError, DomainError, RepoError};
-pub use ports::{ExchangeError, ExchangeRateProvider, TransactionRepository};
+pub use ports::{
+    AccountActivity, Attachment, Clock, EventPublisher, ExchangeError, ExchangeRateProvider,
+    FixedClock, IdGenerator, IdempotencyStore, LedgerOperation, MetricLabels, Metrics, NoopMetrics,
+    Notification, Notifier, NotifyError, PaymentCheck, Payout, PayoutError, PayoutInstruction,
+    PayoutProvider, PayoutStatus, RandomIdGenerator, RateLimitDecision, RateLimitError,
+    RateLimitStore, RiskCheck, SequentialIdGenerator, SystemClock, TransactionRepository,
+    UnitOfWork, Warning, WarningRule,
+};
 
 // Re-export type-safe currency types from exchange-rates for internal use
-pub use exchange_rates::{Currency, EUR, GBP, INR, Money, USD};
+pub use exchange_rates::{
+    Currency, CurrencyInfo, CurrencyRegistry, EUR, GBP, INR, Money, RegistryError, USD,
+};
@@ -0,0 +1,16 @@
+//! Event publisher port.
+//!
+//! Publishers receive every `DomainEvent` the service emits, after the change
+//! has been persisted. Implementations can forward to SSE clients, a message
+//! bus, an audit log, etc.
+
+use crate::domain::DomainEvent;
+
+/// Port trait for domain event consumers.
+///
+/// Publishing is fire-and-forget: the originating operation has already
+/// succeeded, so implementations handle (and log) their own failures.
+#[async_trait::async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, event: &DomainEvent);
+}
@@ -0,0 +1,61 @@
+//! ID generation port.
+//!
+//! Adapters ask an `IdGenerator` for new identifiers instead of calling
+//! `Uuid::new_v4()` directly, so tests can produce predictable IDs.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use uuid::Uuid;
+
+/// Source of new unique identifiers.
+pub trait IdGenerator: Send + Sync {
+    /// Returns a new identifier.
+    fn new_id(&self) -> Uuid;
+}
+
+/// Random (v4) UUIDs, as used in production.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn new_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// Hands out `00000000-0000-0000-0000-000000000001`, `...002`, and so on.
+#[derive(Debug, Default)]
+pub struct SequentialIdGenerator {
+    next: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn new_id(&self) -> Uuid {
+        let n = self.next.fetch_add(1, Ordering::SeqCst) + 1;
+        Uuid::from_u128(n as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_ids() {
+        let ids = SequentialIdGenerator::new();
+        assert_eq!(
+            ids.new_id().to_string(),
+            "00000000-0000-0000-0000-000000000001"
+        );
+        assert_eq!(
+            ids.new_id().to_string(),
+            "00000000-0000-0000-0000-000000000002"
+        );
+    }
+}
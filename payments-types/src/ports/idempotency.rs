@@ -0,0 +1,28 @@
+//! Idempotency store port.
+//!
+//! Stores keep the full response a request made with an idempotency key got,
+//! keyed by endpoint and key, so a retry is answered with exactly that
+//! response. The database is the default store; a shared cache such as Redis
+//! can hold them instead, expiring each after a while.
+
+use crate::domain::IdempotencyRecord;
+use crate::error::RepoError;
+
+/// Port trait for storing responses by idempotency key.
+#[async_trait::async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Gets the response stored for `key` sent to `endpoint`.
+    async fn get(&self, endpoint: &str, key: &str) -> Result<Option<IdempotencyRecord>, RepoError>;
+
+    /// Stores `record` unless its endpoint and key already have one, and
+    /// returns the record they hold afterwards. A reservation that had
+    /// expired by `record.created_at` is replaced.
+    async fn save(&self, record: &IdempotencyRecord) -> Result<IdempotencyRecord, RepoError>;
+
+    /// Stores `record` in place of whatever its endpoint and key hold.
+    async fn replace(&self, record: &IdempotencyRecord) -> Result<(), RepoError>;
+
+    /// Forgets the record stored for `key` sent to `endpoint`, so the key can
+    /// be used again.
+    async fn remove(&self, endpoint: &str, key: &str) -> Result<(), RepoError>;
+}
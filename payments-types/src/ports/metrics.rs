@@ -0,0 +1,50 @@
+//! Metrics port.
+//!
+//! The service and adapters count what they do through a `Metrics` handle
+//! instead of talking to a metrics backend, so the exporter can be swapped or
+//! left out entirely.
+
+/// Label name/value pairs identifying one series of a metric.
+pub type MetricLabels<'a> = &'a [(&'static str, &'a str)];
+
+/// HTTP requests handled, by method, route and status code.
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+/// Seconds taken to handle HTTP requests, by method and route.
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+/// Repository calls made while handling HTTP requests, by method and route.
+/// Divided by [`HTTP_REQUESTS_TOTAL`] it gives the calls per request.
+pub const HTTP_REQUEST_REPO_CALLS_TOTAL: &str = "http_request_repo_calls_total";
+/// Completed transactions, by type and currency.
+pub const TRANSACTIONS_TOTAL: &str = "payments_transactions_total";
+/// Money moved by completed transactions in minor units, by type and currency.
+pub const TRANSACTION_VOLUME_TOTAL: &str = "payments_transaction_volume_total";
+/// Completed transfers converted between currencies, by `from` and `to`
+/// currency.
+pub const CONVERSIONS_TOTAL: &str = "payments_conversions_total";
+/// Exchange-rate quotes issued, by `from` and `to` currency.
+pub const QUOTES_ISSUED_TOTAL: &str = "payments_quotes_issued_total";
+/// Transfers refused because the quote they named had expired, by `from` and
+/// `to` currency.
+pub const QUOTES_EXPIRED_TOTAL: &str = "payments_quotes_expired_total";
+/// Webhook delivery attempts, by outcome (`success` or `failure`).
+pub const WEBHOOK_DELIVERIES_TOTAL: &str = "payments_webhook_deliveries_total";
+
+/// Sink for counters and histograms.
+pub trait Metrics: Send + Sync {
+    /// Adds `value` to the counter `name` for `labels`.
+    fn increment_counter(&self, name: &'static str, labels: MetricLabels<'_>, value: u64);
+
+    /// Records one observation of `value` in the histogram `name` for
+    /// `labels`.
+    fn observe_histogram(&self, name: &'static str, labels: MetricLabels<'_>, value: f64);
+}
+
+/// Metrics that go nowhere, used when no exporter is configured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl Metrics for NoopMetrics {
+    fn increment_counter(&self, _name: &'static str, _labels: MetricLabels<'_>, _value: u64) {}
+
+    fn observe_histogram(&self, _name: &'static str, _labels: MetricLabels<'_>, _value: f64) {}
+}
@@ -3,8 +3,28 @@
 //! These are the contracts that adapters must implement.
 //! The application layer depends on these traits, not concrete implementations.
 
+mod clock;
+mod events;
 mod exchange;
+mod id;
+mod idempotency;
+pub mod metrics;
+mod notifier;
+mod payout;
+mod rate_limit;
 mod repository;
+mod risk;
+mod warnings;
 
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use events::EventPublisher;
 pub use exchange::{ExchangeError, ExchangeRateProvider};
-pub use repository::TransactionRepository;
+pub use id::{IdGenerator, RandomIdGenerator, SequentialIdGenerator};
+pub use idempotency::IdempotencyStore;
+pub use metrics::{MetricLabels, Metrics, NoopMetrics};
+pub use notifier::{Attachment, Notification, Notifier, NotifyError};
+pub use payout::{Payout, PayoutError, PayoutInstruction, PayoutProvider, PayoutStatus};
+pub use rate_limit::{RateLimitDecision, RateLimitError, RateLimitStore};
+pub use repository::{LedgerOperation, TransactionRepository, UnitOfWork};
+pub use risk::RiskCheck;
+pub use warnings::{AccountActivity, PaymentCheck, Warning, WarningRule};
@@ -0,0 +1,40 @@
+//! Notifier port.
+//!
+//! Notifiers deliver messages to account holders outside the API, such as
+//! emailed statements. Implementations can be SMTP relays, email APIs, or
+//! recorders in tests.
+
+/// Error type for notification delivery.
+#[derive(Debug, thiserror::Error)]
+pub enum NotifyError {
+    #[error("Invalid recipient: {0}")]
+    InvalidRecipient(String),
+
+    #[error("Delivery failed: {0}")]
+    DeliveryFailed(String),
+}
+
+/// A file sent along with a notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+/// A message to one recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Recipient email address
+    pub to: String,
+    pub subject: String,
+    /// Plain-text body
+    pub body: String,
+    pub attachment: Option<Attachment>,
+}
+
+/// Port trait for out-of-band message delivery.
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, notification: &Notification) -> Result<(), NotifyError>;
+}
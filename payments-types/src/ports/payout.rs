@@ -0,0 +1,85 @@
+//! Payout provider port.
+//!
+//! A payout provider sends money that has left the ledger on to a
+//! beneficiary over external rails. Implementations can be bank or payment
+//! processor API clients, simulators for development, or recorders in tests.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Counterparty, DynMoney, TransactionId};
+
+/// Error type for payout operations.
+#[derive(Debug, thiserror::Error)]
+pub enum PayoutError {
+    #[error("Payout rejected: {0}")]
+    Rejected(String),
+
+    #[error("Unknown payout: {0}")]
+    NotFound(String),
+
+    #[error("Payout {0} can no longer be cancelled")]
+    NotCancellable(String),
+
+    #[error("Service unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// Money to send to a beneficiary outside the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayoutInstruction {
+    /// Withdrawal that debited the money; providers deduplicate on it, so
+    /// resending an instruction never pays twice
+    pub transaction_id: TransactionId,
+    pub amount: DynMoney,
+    /// Who is paid, with their account at the receiving institution
+    pub beneficiary: Counterparty,
+    /// Shown to the beneficiary, e.g. an invoice number
+    pub reference: Option<String>,
+}
+
+/// Where a payout is on the rails.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum PayoutStatus {
+    /// Accepted by the provider and on its way
+    Pending,
+    /// Credited to the beneficiary
+    Completed,
+    /// Returned by the rails; the money is back with the provider
+    Failed { reason: String },
+    /// Stopped before it completed
+    Cancelled,
+}
+
+impl PayoutStatus {
+    /// Whether the payout will no longer change.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Pending)
+    }
+}
+
+/// A provider's record of one payout.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Payout {
+    /// The provider's ID for the payout, used to query or cancel it
+    pub provider_reference: String,
+    pub transaction_id: TransactionId,
+    pub status: PayoutStatus,
+    /// When the provider accepted the payout
+    pub initiated_at: DateTime<Utc>,
+}
+
+/// Port trait for sending payouts over external rails.
+#[async_trait::async_trait]
+pub trait PayoutProvider: Send + Sync {
+    /// Hands a payout to the rails. Resending an instruction for the same
+    /// transaction returns the payout already made.
+    async fn initiate(&self, instruction: &PayoutInstruction) -> Result<Payout, PayoutError>;
+
+    /// Looks up a payout by its provider reference.
+    async fn status(&self, provider_reference: &str) -> Result<Payout, PayoutError>;
+
+    /// Stops a payout that is still pending.
+    async fn cancel(&self, provider_reference: &str) -> Result<Payout, PayoutError>;
+}
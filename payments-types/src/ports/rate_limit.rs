@@ -0,0 +1,41 @@
+//! Rate limit store port.
+//!
+//! Stores count requests against each caller's quota. An in-process store
+//! limits each instance on its own; a shared one, such as Redis, enforces
+//! the quota across every replica behind a load balancer.
+
+use std::time::Duration;
+
+/// Error type for rate limit stores.
+#[derive(Debug, thiserror::Error)]
+pub enum RateLimitError {
+    #[error("Rate limit store unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// The outcome of checking one request against its key's quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    /// Requests the key may make per period
+    pub limit: u32,
+    /// Requests the key has left before it is limited
+    pub remaining: u32,
+    /// How long to wait before the next request is allowed, when this one
+    /// was refused
+    pub retry_after: Option<Duration>,
+}
+
+/// Port trait for counting requests against quotas.
+///
+/// A quota allows a burst of `requests` at once, with one more replenished
+/// every `period`.
+#[async_trait::async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Counts one request from `key`, unless it is over its quota.
+    async fn check(
+        &self,
+        key: &str,
+        requests: u32,
+        period: Duration,
+    ) -> Result<RateLimitDecision, RateLimitError>;
+}
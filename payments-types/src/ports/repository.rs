@@ -3,10 +3,140 @@
 //! This is the primary port in our hexagonal architecture.
 //! Adapters (Postgres, SQLite, InMemory) will implement this trait.
 
-use crate::domain::{Account, AccountId, Transaction, TransactionId};
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::domain::{
+    Account, AccountAlias, AccountId, AccountLimits, AccountStatus, Alias, ApiKeyUsageBucket,
+    ApiKeyVolumeBucket, Beneficiary, BeneficiaryId, ChangeRequest, ChangeRequestId, ChangeStatus,
+    CurrencyBalance, DailyBalance, Export, ExportId, FeeAssignment, FeeSchedule, FeeScheduleId,
+    FeeTier, FloatPosition, FxConversion, Hold, HoldId, HoldStatus, IdempotencyRecord, MemoKey,
+    MemoKeyId, Mode, Owner, OwnerId, Quote, QuoteId, RateSnapshot, RiskAssessment,
+    ScheduledPayment, ScheduledPaymentId, SettlementBatch, SettlementBatchId,
+    SettlementBatchStatus, SpendingRules, Statement, StatementId, StorageCounts, Transaction,
+    TransactionCursor, TransactionFilter, TransactionId, TransactionSearch, WebhookTimeouts,
+};
 use crate::dto::{CreateAccountRequest, DepositRequest, TransferRequest, WithdrawRequest};
 use crate::error::RepoError;
 
+/// One balance change made through a [`UnitOfWork`].
+#[derive(Debug, Clone)]
+pub enum LedgerOperation {
+    Deposit(DepositRequest),
+    Withdraw(WithdrawRequest),
+    /// A transfer, converted when the conversion is set.
+    Transfer(TransferRequest, Option<FxConversion>),
+}
+
+/// Writes made together in one database transaction.
+///
+/// Started with [`TransactionRepository::begin`], so the service can store a
+/// payment together with everything recorded about it (its risk assessment,
+/// the webhook events announcing it) instead of each write committing on its
+/// own. Nothing is visible to other callers until [`commit`](Self::commit);
+/// dropping the unit uncommitted discards all of it.
+///
+/// Only writes are staged, apart from the reads limits are checked against:
+/// read anything else through the repository before beginning, as a database
+/// may block other connections while the unit is open.
+#[async_trait::async_trait]
+pub trait UnitOfWork: Send {
+    /// Makes `operation` as its single-operation method would, including
+    /// idempotency replays, seeing the balances left by earlier operations.
+    async fn apply(&mut self, operation: LedgerOperation) -> Result<Transaction, RepoError>;
+
+    /// Locks `account_ids` until the unit ends, always in the same order
+    /// whatever order they are given in. Lock a payment's accounts with this
+    /// before counting against their limits, so that two payments between
+    /// the same accounts in opposite directions wait for each other instead
+    /// of deadlocking. Databases that do not lock rows need not do anything.
+    async fn lock_accounts(&mut self, account_ids: &[AccountId]) -> Result<(), RepoError>;
+
+    /// Sums the amounts sent out of `account_id` (withdrawals and outgoing
+    /// transfers) and held on it by holds not yet captured after `since`, as
+    /// seen by this unit. Databases that can
+    /// lock the account do so until the unit ends, so that two debits racing
+    /// for the same limit are counted one after the other.
+    async fn debited_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError>;
+
+    /// Counts the transactions `account_id` sent or received after `since`,
+    /// as seen by this unit, locking the account like
+    /// [`debited_since`](Self::debited_since).
+    async fn transactions_since(
+        &mut self,
+        account_id: AccountId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RepoError>;
+
+    /// See [`TransactionRepository::create_hold`].
+    async fn create_hold(&mut self, hold: &Hold) -> Result<(), RepoError>;
+
+    /// See [`TransactionRepository::record_transaction_risk`].
+    async fn record_transaction_risk(
+        &mut self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError>;
+
+    /// See [`TransactionRepository::create_webhook_event`].
+    async fn create_webhook_event(
+        &mut self,
+        endpoint_id: crate::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<crate::WebhookEvent, RepoError>;
+
+    /// See [`TransactionRepository::append_event`].
+    async fn append_event(
+        &mut self,
+        event: &crate::DomainEvent,
+        mode: Mode,
+    ) -> Result<(), RepoError>;
+
+    /// See [`TransactionRepository::capture_hold`].
+    async fn capture_hold(
+        &mut self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError>;
+
+    /// See [`TransactionRepository::release_hold`].
+    async fn release_hold(
+        &mut self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError>;
+
+    /// Closes account `id` at `now`, linking it to the account `into` it was
+    /// merged into. Returns the closed account, or `None` if it does not
+    /// exist, is already closed, or still has a balance or open holds.
+    async fn close_merged_account(
+        &mut self,
+        id: AccountId,
+        into: AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError>;
+
+    /// Marks quote `id` spent on `transaction_id`. Fails with a validation
+    /// error if it was spent on another transaction; spending it again on the same one, as an idempotent replay does,
+    /// changes nothing.
+    async fn spend_quote(
+        &mut self,
+        id: QuoteId,
+        transaction_id: TransactionId,
+    ) -> Result<(), RepoError>;
+
+    /// Makes everything staged visible at once.
+    async fn commit(self: Box<Self>) -> Result<(), RepoError>;
+}
+
 /// The main repository port for payment operations.
 ///
 /// All operations that modify balances MUST be atomic.
@@ -23,8 +153,141 @@ pub trait TransactionRepository: Send + Sync + 'static {
     /// Gets an account by ID.
     async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError>;
 
-    /// Lists all accounts.
-    async fn list_accounts(&self) -> Result<Vec<Account>, RepoError>;
+    /// Lists the accounts in `mode`, or all of them, newest first.
+    async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError>;
+
+    /// Finds up to `limit` accounts in `mode` matching `query`, ordered by name.
+    ///
+    /// Matching follows [`Account::matches_search`]: case-insensitive substring
+    /// on the name, or prefix on the ID.
+    async fn search_accounts(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: Mode,
+    ) -> Result<Vec<Account>, RepoError>;
+
+    /// Gets an account's spending rules (empty if none were set).
+    async fn get_spending_rules(&self, account_id: AccountId) -> Result<SpendingRules, RepoError>;
+
+    /// Replaces an account's spending rules.
+    async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<(), RepoError>;
+
+    /// Gets an account's limits (all unset if none were configured).
+    async fn get_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, RepoError>;
+
+    /// Replaces an account's limits.
+    async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<(), RepoError>;
+
+    /// Lists up to `limit` active accounts opened before `idle_since` that
+    /// have had no transactions since then, oldest first.
+    async fn list_idle_accounts(
+        &self,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Account>, RepoError>;
+
+    /// Moves an account from status `from` to `to` at `now`. Returns the
+    /// updated account, or `None` if it does not exist or is not in `from`.
+    async fn update_account_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError>;
+
+    /// Sets how far below zero an account's balance may go. Returns the
+    /// updated account, or `None` if it does not exist.
+    async fn set_overdraft_limit(
+        &self,
+        id: AccountId,
+        limit: i64,
+    ) -> Result<Option<Account>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Account Aliases
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Registers an alias, returning the stored entry.
+    ///
+    /// Registering an alias the account already holds returns the existing
+    /// entry. Fails with `RepoError::Conflict` if another account holds it.
+    async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError>;
+
+    /// Gets the account an alias is registered to.
+    async fn get_account_alias(&self, alias: &Alias) -> Result<Option<AccountAlias>, RepoError>;
+
+    /// Lists an account's aliases, oldest first.
+    async fn list_account_aliases(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, RepoError>;
+
+    /// Removes an alias from an account. Returns `false` if the account
+    /// does not hold it.
+    async fn remove_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<bool, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Owners
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new owner.
+    async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError>;
+
+    /// Gets an owner by ID.
+    async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError>;
+
+    /// Lists every owner, oldest first.
+    async fn list_owners(&self) -> Result<Vec<Owner>, RepoError>;
+
+    /// Saves an owner's name and email; `None` if it does not exist.
+    async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError>;
+
+    /// Deletes an owner that holds no accounts; `false` if it does not exist.
+    async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError>;
+
+    /// Lists the accounts an owner holds, only those in `mode` when it is
+    /// set, newest first.
+    async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Account>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Memo Keys
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new memo key.
+    async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError>;
+
+    /// Gets a memo key by ID.
+    async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError>;
+
+    /// Lists an account's memo keys, retired ones included, oldest first.
+    async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError>;
+
+    /// Retires an account's memo key at `at`, keeping an earlier retirement.
+    /// Returns the key as stored, or `None` if the account does not hold it.
+    async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        id: MemoKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<MemoKey>, RepoError>;
 
     // ─────────────────────────────────────────────────────────────────────────────
     // Transaction Operations (MUST be atomic)
@@ -39,6 +302,26 @@ pub trait TransactionRepository: Send + Sync + 'static {
     /// Transfers money between two accounts.
     async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError>;
 
+    /// Transfers money between accounts held in different currencies:
+    /// debits `req.amount` in `req.currency` and credits
+    /// `conversion.converted_amount`.
+    async fn transfer_with_conversion(
+        &self,
+        req: TransferRequest,
+        conversion: FxConversion,
+    ) -> Result<Transaction, RepoError>;
+
+    /// Starts a [`UnitOfWork`] for writes that must be made together.
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork + '_>, RepoError>;
+
+    /// Records the risk assessment a transaction was held under. Does
+    /// nothing if the transaction already has one.
+    async fn record_transaction_risk(
+        &self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError>;
+
     // ─────────────────────────────────────────────────────────────────────────────
     // Idempotency & History
     // ─────────────────────────────────────────────────────────────────────────────
@@ -55,6 +338,35 @@ pub trait TransactionRepository: Send + Sync + 'static {
         account_id: AccountId,
     ) -> Result<Vec<Transaction>, RepoError>;
 
+    /// Lists up to `limit` of an account's transactions matching `filter`,
+    /// newest first, starting right after `after` when given.
+    async fn list_transactions_page(
+        &self,
+        account_id: AccountId,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError>;
+
+    /// Lists up to `limit` transactions across all accounts matching
+    /// `search`, newest first, starting right after `after` when given.
+    async fn search_transactions(
+        &self,
+        search: &TransactionSearch,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError>;
+
+    /// Lists every transaction created in `from..to` (end exclusive), oldest
+    /// first, optionally only those moving money in or out of an account in
+    /// `mode`.
+    async fn list_transactions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Transaction>, RepoError>;
+
     // ─────────────────────────────────────────────────────────────────────────────
     // API Key Verification
     // ─────────────────────────────────────────────────────────────────────────────
@@ -63,9 +375,36 @@ pub trait TransactionRepository: Send + Sync + 'static {
     async fn verify_api_key_hash(&self, key_hash: &str)
     -> Result<Option<crate::ApiKey>, RepoError>;
 
-    /// Creates a new API key with the given name and returns the raw key (only shown once).
-    /// The key is stored as a hash in the database.
-    async fn create_api_key(&self, name: &str) -> Result<(crate::ApiKey, String), RepoError>;
+    /// Creates a new API key with the given name and scopes and returns the raw key
+    /// (only shown once), prefixed with `mode`'s
+    /// [`key_prefix`](crate::Mode::key_prefix). The key is stored as a hash in
+    /// the database.
+    async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[crate::ApiKeyScope],
+        mode: crate::Mode,
+    ) -> Result<(crate::ApiKey, String), RepoError>;
+
+    /// Like [`create_api_key`](Self::create_api_key), but the key can only
+    /// operate on `account_id`.
+    async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[crate::ApiKeyScope],
+        mode: crate::Mode,
+    ) -> Result<(crate::ApiKey, String), RepoError>;
+
+    /// Like [`create_api_key`](Self::create_api_key), but the key can only
+    /// operate on the accounts `owner_id` holds.
+    async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[crate::ApiKeyScope],
+        mode: crate::Mode,
+    ) -> Result<(crate::ApiKey, String), RepoError>;
 
     /// Counts the number of active API keys in the system.
     async fn count_api_keys(&self) -> Result<i64, RepoError>;
@@ -73,28 +412,1401 @@ pub trait TransactionRepository: Send + Sync + 'static {
     /// Lists all API keys (without exposing the raw keys).
     async fn list_api_keys(&self) -> Result<Vec<crate::ApiKey>, RepoError>;
 
-    /// Deletes (deactivates) an API key by ID.
-    async fn delete_api_key(&self, id: crate::ApiKeyId) -> Result<bool, RepoError>;
+    /// Deactivates an API key, recording who deleted it and when. The key is
+    /// kept for its audit trail. Returns `false` if it was not found or is
+    /// already inactive.
+    async fn delete_api_key(
+        &self,
+        id: crate::ApiKeyId,
+        deactivated_by: crate::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, RepoError>;
+
+    /// Sets when a key last authenticated a request.
+    async fn touch_api_key(&self, id: crate::ApiKeyId, at: DateTime<Utc>) -> Result<(), RepoError>;
+
+    /// Sets the requests per minute a key may make, or clears the override
+    /// when `None`. Returns `false` if no active key has the ID.
+    async fn set_api_key_rate_limit(
+        &self,
+        id: crate::ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<bool, RepoError>;
+
+    /// Gets an API key by ID, including deactivated ones.
+    async fn get_api_key(&self, id: crate::ApiKeyId) -> Result<Option<crate::ApiKey>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // API Key Audit
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Appends an entry to a key's audit trail.
+    async fn insert_api_key_audit(&self, entry: &crate::ApiKeyAuditEntry) -> Result<(), RepoError>;
+
+    /// Lists a key's audit trail, oldest first.
+    async fn list_api_key_audit(
+        &self,
+        id: crate::ApiKeyId,
+    ) -> Result<Vec<crate::ApiKeyAuditEntry>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // API Key Usage
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Adds the bucket's counts to the stored bucket for the same key and hour.
+    async fn add_api_key_usage(&self, usage: &ApiKeyUsageBucket) -> Result<(), RepoError>;
+
+    /// Adds the bucket's count and amount to the stored bucket for the same
+    /// key, hour, transaction type and currency.
+    async fn add_api_key_volume(&self, volume: &ApiKeyVolumeBucket) -> Result<(), RepoError>;
+
+    /// Lists a key's usage buckets from the hour `since` on.
+    async fn list_api_key_usage(
+        &self,
+        id: crate::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyUsageBucket>, RepoError>;
+
+    /// Lists a key's volume buckets from the hour `since` on.
+    async fn list_api_key_volume(
+        &self,
+        id: crate::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyVolumeBucket>, RepoError>;
 
     // ─────────────────────────────────────────────────────────────────────────────
     // Webhook Endpoint Management
     // ─────────────────────────────────────────────────────────────────────────────
 
-    /// Registers a new webhook endpoint.
+    /// Registers a new live webhook endpoint.
     async fn register_webhook_endpoint(
         &self,
         url: &str,
         events: Vec<String>,
+        timeouts: WebhookTimeouts,
+        signature_algorithm: crate::WebhookSignatureAlgorithm,
     ) -> Result<crate::WebhookEndpoint, RepoError>;
 
-    /// Lists all active webhook endpoints.
-    async fn list_webhook_endpoints(&self) -> Result<Vec<crate::WebhookEndpoint>, RepoError>;
+    /// Registers several webhook endpoints in one database transaction:
+    /// either all of them are registered or none is. The endpoints come
+    /// back in the order given.
+    async fn register_webhook_endpoints(
+        &self,
+        endpoints: Vec<crate::NewWebhookEndpoint>,
+    ) -> Result<Vec<crate::WebhookEndpoint>, RepoError>;
+
+    /// Lists all active webhook endpoints, or only those in `mode`.
+    async fn list_webhook_endpoints(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<crate::WebhookEndpoint>, RepoError>;
+
+    /// Gets a webhook endpoint by ID, active or not.
+    async fn get_webhook_endpoint(
+        &self,
+        id: crate::WebhookEndpointId,
+    ) -> Result<Option<crate::WebhookEndpoint>, RepoError>;
+
+    /// Points an endpoint's deliveries at `url`. Returns `None` when the
+    /// endpoint does not exist.
+    async fn update_webhook_endpoint_url(
+        &self,
+        id: crate::WebhookEndpointId,
+        url: &str,
+    ) -> Result<Option<crate::WebhookEndpoint>, RepoError>;
+
+    /// Replaces an endpoint's subscriptions, turns its deliveries on or off
+    /// and/or changes how they are signed, leaving unset fields as they are.
+    /// Returns `None` when the endpoint does not exist.
+    async fn update_webhook_endpoint(
+        &self,
+        id: crate::WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<crate::WebhookSignatureAlgorithm>,
+    ) -> Result<Option<crate::WebhookEndpoint>, RepoError>;
+
+    /// Gives an endpoint a new signing secret. Returns `None` when the
+    /// endpoint does not exist.
+    async fn rotate_webhook_secret(
+        &self,
+        id: crate::WebhookEndpointId,
+    ) -> Result<Option<crate::WebhookEndpoint>, RepoError>;
 
-    /// Creates a new webhook event to be sent to a specific endpoint.
+    /// Deletes an endpoint. Events already queued for it are kept. Returns
+    /// whether it existed.
+    async fn delete_webhook_endpoint(
+        &self,
+        id: crate::WebhookEndpointId,
+    ) -> Result<bool, RepoError>;
+
+    /// Creates a new webhook event to be sent to a specific endpoint, in the
+    /// endpoint's mode.
     async fn create_webhook_event(
         &self,
         endpoint_id: crate::WebhookEndpointId,
         event_type: &str,
         payload: serde_json::Value,
     ) -> Result<crate::WebhookEvent, RepoError>;
+
+    /// Gets a webhook event by ID, with its full payload.
+    async fn get_webhook_event(
+        &self,
+        id: uuid::Uuid,
+    ) -> Result<Option<crate::WebhookEvent>, RepoError>;
+
+    /// Lists dead-lettered events matching `filter`, oldest first, with their
+    /// full payloads.
+    async fn list_dead_lettered_webhooks(
+        &self,
+        filter: &crate::DeadLetterFilter,
+    ) -> Result<Vec<crate::WebhookEvent>, RepoError>;
+
+    /// Makes dead-lettered events matching `filter` due now, with their
+    /// attempts reset so they get the full retry budget again. Returns how
+    /// many were requeued.
+    async fn requeue_dead_lettered_webhooks(
+        &self,
+        filter: &crate::DeadLetterFilter,
+    ) -> Result<u64, RepoError>;
+
+    /// Counts events still waiting to be delivered: pending ones and failed
+    /// ones with retries left, whether or not they are due yet.
+    async fn count_webhook_backlog(&self) -> Result<i64, RepoError>;
+
+    /// Counts the rows of each table tracked against storage quotas.
+    async fn storage_counts(&self) -> Result<StorageCounts, RepoError>;
+
+    /// Makes the cheapest round trip the store has, to check it answers.
+    async fn ping(&self) -> Result<(), RepoError>;
+
+    /// Lists up to `limit` of an endpoint's events with a delivery sequence
+    /// above `after_sequence`, lowest first.
+    async fn list_webhook_events_after(
+        &self,
+        endpoint_id: crate::WebhookEndpointId,
+        after_sequence: i64,
+        limit: usize,
+    ) -> Result<Vec<crate::WebhookEvent>, RepoError>;
+
+    /// Lists up to `limit` of an endpoint's events matching `filter`, highest
+    /// delivery sequence first.
+    async fn list_webhook_deliveries(
+        &self,
+        endpoint_id: crate::WebhookEndpointId,
+        filter: &crate::WebhookDeliveryFilter,
+        limit: usize,
+    ) -> Result<Vec<crate::WebhookEvent>, RepoError>;
+
+    /// Makes a failed or dead-lettered event due now, with its attempts reset
+    /// so it gets the full retry budget again. Returns `false`, changing
+    /// nothing, when the event is missing or not failed.
+    async fn requeue_webhook_event(&self, id: uuid::Uuid) -> Result<bool, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Event Log
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Appends `event`, which happened in `mode`, to the event log, numbered
+    /// one after the last event.
+    async fn append_event(&self, event: &crate::DomainEvent, mode: Mode) -> Result<(), RepoError>;
+
+    /// Lists up to `limit` logged events with a sequence above `after`, in
+    /// order, optionally only those in `mode`. Sequences are handed out in commit order, so an event never
+    /// appears behind one already listed.
+    async fn list_logged_events(
+        &self,
+        after: i64,
+        limit: usize,
+        mode: Option<Mode>,
+    ) -> Result<Vec<crate::LoggedEvent>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Fee Schedules
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new (immutable) fee schedule.
+    async fn create_fee_schedule(
+        &self,
+        name: &str,
+        tiers: &[FeeTier],
+    ) -> Result<FeeSchedule, RepoError>;
+
+    /// Gets a fee schedule by ID.
+    async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<Option<FeeSchedule>, RepoError>;
+
+    /// Lists all fee schedules, newest first.
+    async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError>;
+
+    /// Records that `fee_schedule_id` applies to an account from `effective_from`.
+    async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: DateTime<Utc>,
+    ) -> Result<FeeAssignment, RepoError>;
+
+    /// Lists an account's fee schedule assignments, oldest effective date first.
+    async fn list_fee_assignments(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<FeeAssignment>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Settlement Batches
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Atomically gathers every withdrawal from an account in `mode` created
+    /// at or before `cutoff` that is not in a live batch into a new `PENDING`
+    /// batch in that mode.
+    ///
+    /// Returns `None` (and creates nothing) when there are no such payouts.
+    async fn create_settlement_batch(
+        &self,
+        cutoff: DateTime<Utc>,
+        mode: Mode,
+    ) -> Result<Option<SettlementBatch>, RepoError>;
+
+    /// Gets a settlement batch by ID.
+    async fn get_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Option<SettlementBatch>, RepoError>;
+
+    /// Lists settlement batches, optionally only those in `mode`, newest
+    /// first.
+    async fn list_settlement_batches(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<SettlementBatch>, RepoError>;
+
+    /// Moves a batch from `from` to `to`, releasing its payouts when `to` is
+    /// `CANCELLED`.
+    ///
+    /// Returns `None` if the batch does not exist or is no longer in `from`.
+    async fn update_settlement_batch_status(
+        &self,
+        id: SettlementBatchId,
+        from: SettlementBatchStatus,
+        to: SettlementBatchStatus,
+    ) -> Result<Option<SettlementBatch>, RepoError>;
+
+    /// Lists the payouts in a batch, oldest first (empty once cancelled).
+    async fn list_settlement_batch_payouts(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Vec<Transaction>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Statements
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Gets the address an account's statements are emailed to, if any.
+    async fn get_statement_email(&self, account_id: AccountId)
+    -> Result<Option<String>, RepoError>;
+
+    /// Sets or clears (`None`) the address an account's statements are emailed to.
+    async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<(), RepoError>;
+
+    /// Stores an issued statement with its rendered file.
+    ///
+    /// Returns `false`, storing nothing, if the account already has a
+    /// statement for the same period.
+    async fn insert_statement(
+        &self,
+        statement: &Statement,
+        content: &str,
+    ) -> Result<bool, RepoError>;
+
+    /// Gets a statement by ID.
+    async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError>;
+
+    /// Gets the rendered file of a statement.
+    async fn get_statement_content(&self, id: StatementId) -> Result<Option<String>, RepoError>;
+
+    /// Lists an account's statements, latest period first.
+    async fn list_statements(&self, account_id: AccountId) -> Result<Vec<Statement>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Exports
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new export, without a file yet.
+    async fn insert_export(&self, export: &Export) -> Result<(), RepoError>;
+
+    /// Records how an export finished: its status, error and completion time,
+    /// plus the rendered file when there is one.
+    async fn finish_export(&self, export: &Export, content: Option<&str>) -> Result<(), RepoError>;
+
+    /// Gets an export by ID.
+    async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError>;
+
+    /// Gets the rendered file of an export, if it has one.
+    async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError>;
+
+    /// Deletes exports created before `cutoff`, returning how many were removed.
+    async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Scheduled Payments
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new scheduled payment.
+    async fn insert_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<(), RepoError>;
+
+    /// Gets a scheduled payment by ID.
+    async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<Option<ScheduledPayment>, RepoError>;
+
+    /// Lists scheduled payments, oldest first; only those drawing on
+    /// `account_id`, or on an account in `mode`, when they are set.
+    async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<ScheduledPayment>, RepoError>;
+
+    /// Lists up to `limit` active payments due at or before `now`, the
+    /// longest overdue first.
+    async fn list_due_scheduled_payments(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ScheduledPayment>, RepoError>;
+
+    /// Stores the outcome of the run that was due at `due_at`: the payment's
+    /// `next_run_at`, `last_run_at`, `last_transaction_id` and `last_error`.
+    ///
+    /// Only applies if the stored `next_run_at` is still `due_at`, so when
+    /// two schedulers race for the same run exactly one records it. Returns
+    /// whether this call did.
+    async fn record_scheduled_payment_run(
+        &self,
+        payment: &ScheduledPayment,
+        due_at: DateTime<Utc>,
+    ) -> Result<bool, RepoError>;
+
+    /// Stores a payment's status and `next_run_at` after a pause or resume.
+    async fn update_scheduled_payment_status(
+        &self,
+        payment: &ScheduledPayment,
+    ) -> Result<(), RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Holds
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new authorized hold and adds its amount to the account's
+    /// `held` total. Fails with `InsufficientFunds` if the account's
+    /// available balance does not cover it.
+    async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError>;
+
+    /// Gets a hold by ID.
+    async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError>;
+
+    /// Lists an account's holds, newest first.
+    async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError>;
+
+    /// Lists up to `limit` authorized holds that expired at or before `now`,
+    /// the longest expired first.
+    async fn list_expired_holds(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Hold>, RepoError>;
+
+    /// Atomically releases an authorized hold, withdraws `amount` of it from
+    /// the account and marks the hold captured at `now`. Returns the updated
+    /// hold and the withdrawal.
+    ///
+    /// Fails with a validation error if the hold is no longer authorized,
+    /// has expired by `now`, or holds less than `amount`.
+    async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError>;
+
+    /// Atomically releases an authorized hold without moving funds and marks
+    /// it `status` (voided or expired) at `now`.
+    ///
+    /// Fails with a validation error if the hold is no longer authorized.
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Beneficiaries
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new beneficiary.
+    async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError>;
+
+    /// Gets a beneficiary by ID.
+    async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Option<Beneficiary>, RepoError>;
+
+    /// Lists an account's beneficiaries, newest first.
+    async fn list_beneficiaries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, RepoError>;
+
+    /// Stores a beneficiary's `status`, `failed_attempts` and `verified_at`
+    /// after a confirmation attempt.
+    ///
+    /// Only applies if the stored beneficiary is still pending with
+    /// `failed_attempts` wrong attempts, so of two racing attempts exactly
+    /// one counts. Returns whether this call did.
+    async fn record_beneficiary_attempt(
+        &self,
+        beneficiary: &Beneficiary,
+        failed_attempts: u32,
+    ) -> Result<bool, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Exposure
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Counts accounts, optionally only those in `mode`, and sums their
+    /// balances per currency.
+    async fn currency_balances(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<CurrencyBalance>, RepoError>;
+
+    /// Sums customer balances per currency next to the float: minus the
+    /// money deposited, withdrawn or converted across currencies, net. Both
+    /// come from one consistent read, optionally of `mode`'s accounts and
+    /// their transactions only.
+    async fn float_positions(&self, mode: Option<Mode>) -> Result<Vec<FloatPosition>, RepoError>;
+
+    /// Stores a snapshot of exchange rates.
+    async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError>;
+
+    /// Gets the latest rate snapshot taken at or before `at`.
+    async fn rate_snapshot_at(&self, at: DateTime<Utc>) -> Result<Option<RateSnapshot>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // FX Quotes
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new quote.
+    async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError>;
+
+    /// Gets a quote by ID, spent or not.
+    async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Balance History
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores an account's closing balances, replacing any already stored
+    /// for the same days.
+    async fn save_daily_balances(
+        &self,
+        account_id: AccountId,
+        balances: &[DailyBalance],
+    ) -> Result<(), RepoError>;
+
+    /// Lists an account's stored closing balances in `from..=to`, oldest first.
+    async fn list_daily_balances(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyBalance>, RepoError>;
+
+    /// Gets the last day an account has a stored closing balance for.
+    async fn latest_daily_balance_date(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<NaiveDate>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Change Requests
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Stores a new change request.
+    async fn insert_change_request(&self, request: &ChangeRequest) -> Result<(), RepoError>;
+
+    /// Gets a change request by ID.
+    async fn get_change_request(
+        &self,
+        id: ChangeRequestId,
+    ) -> Result<Option<ChangeRequest>, RepoError>;
+
+    /// Lists change requests, optionally only those in `status`, newest first.
+    async fn list_change_requests(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, RepoError>;
+
+    /// Records the decision on a pending change request. Returns `None`,
+    /// changing nothing, when the request is missing or already decided.
+    async fn decide_change_request(
+        &self,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: crate::ApiKeyId,
+        decided_at: DateTime<Utc>,
+    ) -> Result<Option<ChangeRequest>, RepoError>;
+
+    // ─────────────────────────────────────────────────────────────────────────────
+    // Idempotency
+    // ─────────────────────────────────────────────────────────────────────────────
+
+    /// Gets the response stored for an idempotency key sent to `endpoint`.
+    async fn get_idempotency_record(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, RepoError>;
+
+    /// Stores `record` unless its endpoint and key already have one, and
+    /// returns the record they hold afterwards. A reservation that had
+    /// expired by `record.created_at` is replaced.
+    async fn save_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, RepoError>;
+
+    /// Stores `record` in place of whatever its endpoint and key hold.
+    async fn replace_idempotency_record(&self, record: &IdempotencyRecord)
+    -> Result<(), RepoError>;
+
+    /// Forgets the record stored for an idempotency key sent to `endpoint`.
+    async fn remove_idempotency_record(&self, endpoint: &str, key: &str) -> Result<(), RepoError>;
+}
+
+/// Shares one repository between the service and background jobs.
+#[async_trait::async_trait]
+impl<R: TransactionRepository + ?Sized> TransactionRepository for Arc<R> {
+    async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, RepoError> {
+        (**self).create_account(req).await
+    }
+
+    async fn get_account(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
+        (**self).get_account(id).await
+    }
+
+    async fn list_accounts(&self, mode: Option<Mode>) -> Result<Vec<Account>, RepoError> {
+        (**self).list_accounts(mode).await
+    }
+
+    async fn search_accounts(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: Mode,
+    ) -> Result<Vec<Account>, RepoError> {
+        (**self).search_accounts(query, limit, mode).await
+    }
+
+    async fn get_spending_rules(&self, account_id: AccountId) -> Result<SpendingRules, RepoError> {
+        (**self).get_spending_rules(account_id).await
+    }
+
+    async fn set_spending_rules(
+        &self,
+        account_id: AccountId,
+        rules: &SpendingRules,
+    ) -> Result<(), RepoError> {
+        (**self).set_spending_rules(account_id, rules).await
+    }
+
+    async fn get_account_limits(&self, account_id: AccountId) -> Result<AccountLimits, RepoError> {
+        (**self).get_account_limits(account_id).await
+    }
+
+    async fn set_account_limits(
+        &self,
+        account_id: AccountId,
+        limits: &AccountLimits,
+    ) -> Result<(), RepoError> {
+        (**self).set_account_limits(account_id, limits).await
+    }
+
+    async fn list_idle_accounts(
+        &self,
+        idle_since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Account>, RepoError> {
+        (**self).list_idle_accounts(idle_since, limit).await
+    }
+
+    async fn update_account_status(
+        &self,
+        id: AccountId,
+        from: AccountStatus,
+        to: AccountStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Account>, RepoError> {
+        (**self).update_account_status(id, from, to, now).await
+    }
+
+    async fn set_overdraft_limit(
+        &self,
+        id: AccountId,
+        limit: i64,
+    ) -> Result<Option<Account>, RepoError> {
+        (**self).set_overdraft_limit(id, limit).await
+    }
+
+    async fn add_account_alias(&self, alias: &AccountAlias) -> Result<AccountAlias, RepoError> {
+        (**self).add_account_alias(alias).await
+    }
+
+    async fn get_account_alias(&self, alias: &Alias) -> Result<Option<AccountAlias>, RepoError> {
+        (**self).get_account_alias(alias).await
+    }
+
+    async fn list_account_aliases(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountAlias>, RepoError> {
+        (**self).list_account_aliases(account_id).await
+    }
+
+    async fn remove_account_alias(
+        &self,
+        account_id: AccountId,
+        alias: &Alias,
+    ) -> Result<bool, RepoError> {
+        (**self).remove_account_alias(account_id, alias).await
+    }
+
+    async fn create_owner(&self, owner: &Owner) -> Result<Owner, RepoError> {
+        (**self).create_owner(owner).await
+    }
+
+    async fn get_owner(&self, id: OwnerId) -> Result<Option<Owner>, RepoError> {
+        (**self).get_owner(id).await
+    }
+
+    async fn list_owners(&self) -> Result<Vec<Owner>, RepoError> {
+        (**self).list_owners().await
+    }
+
+    async fn update_owner(&self, owner: &Owner) -> Result<Option<Owner>, RepoError> {
+        (**self).update_owner(owner).await
+    }
+
+    async fn delete_owner(&self, id: OwnerId) -> Result<bool, RepoError> {
+        (**self).delete_owner(id).await
+    }
+
+    async fn list_owner_accounts(
+        &self,
+        owner_id: OwnerId,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Account>, RepoError> {
+        (**self).list_owner_accounts(owner_id, mode).await
+    }
+
+    async fn add_memo_key(&self, key: &MemoKey) -> Result<MemoKey, RepoError> {
+        (**self).add_memo_key(key).await
+    }
+
+    async fn get_memo_key(&self, id: MemoKeyId) -> Result<Option<MemoKey>, RepoError> {
+        (**self).get_memo_key(id).await
+    }
+
+    async fn list_memo_keys(&self, account_id: AccountId) -> Result<Vec<MemoKey>, RepoError> {
+        (**self).list_memo_keys(account_id).await
+    }
+
+    async fn retire_memo_key(
+        &self,
+        account_id: AccountId,
+        id: MemoKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<Option<MemoKey>, RepoError> {
+        (**self).retire_memo_key(account_id, id, at).await
+    }
+
+    async fn create_fee_schedule(
+        &self,
+        name: &str,
+        tiers: &[FeeTier],
+    ) -> Result<FeeSchedule, RepoError> {
+        (**self).create_fee_schedule(name, tiers).await
+    }
+
+    async fn get_fee_schedule(&self, id: FeeScheduleId) -> Result<Option<FeeSchedule>, RepoError> {
+        (**self).get_fee_schedule(id).await
+    }
+
+    async fn list_fee_schedules(&self) -> Result<Vec<FeeSchedule>, RepoError> {
+        (**self).list_fee_schedules().await
+    }
+
+    async fn assign_fee_schedule(
+        &self,
+        account_id: AccountId,
+        fee_schedule_id: FeeScheduleId,
+        effective_from: DateTime<Utc>,
+    ) -> Result<FeeAssignment, RepoError> {
+        (**self)
+            .assign_fee_schedule(account_id, fee_schedule_id, effective_from)
+            .await
+    }
+
+    async fn list_fee_assignments(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<FeeAssignment>, RepoError> {
+        (**self).list_fee_assignments(account_id).await
+    }
+
+    async fn create_settlement_batch(
+        &self,
+        cutoff: DateTime<Utc>,
+        mode: Mode,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        (**self).create_settlement_batch(cutoff, mode).await
+    }
+
+    async fn get_settlement_batch(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        (**self).get_settlement_batch(id).await
+    }
+
+    async fn list_settlement_batches(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<SettlementBatch>, RepoError> {
+        (**self).list_settlement_batches(mode).await
+    }
+
+    async fn update_settlement_batch_status(
+        &self,
+        id: SettlementBatchId,
+        from: SettlementBatchStatus,
+        to: SettlementBatchStatus,
+    ) -> Result<Option<SettlementBatch>, RepoError> {
+        (**self).update_settlement_batch_status(id, from, to).await
+    }
+
+    async fn list_settlement_batch_payouts(
+        &self,
+        id: SettlementBatchId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        (**self).list_settlement_batch_payouts(id).await
+    }
+
+    async fn get_statement_email(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<String>, RepoError> {
+        (**self).get_statement_email(account_id).await
+    }
+
+    async fn set_statement_email(
+        &self,
+        account_id: AccountId,
+        email: Option<&str>,
+    ) -> Result<(), RepoError> {
+        (**self).set_statement_email(account_id, email).await
+    }
+
+    async fn insert_statement(
+        &self,
+        statement: &Statement,
+        content: &str,
+    ) -> Result<bool, RepoError> {
+        (**self).insert_statement(statement, content).await
+    }
+
+    async fn get_statement(&self, id: StatementId) -> Result<Option<Statement>, RepoError> {
+        (**self).get_statement(id).await
+    }
+
+    async fn get_statement_content(&self, id: StatementId) -> Result<Option<String>, RepoError> {
+        (**self).get_statement_content(id).await
+    }
+
+    async fn list_statements(&self, account_id: AccountId) -> Result<Vec<Statement>, RepoError> {
+        (**self).list_statements(account_id).await
+    }
+
+    async fn insert_export(&self, export: &Export) -> Result<(), RepoError> {
+        (**self).insert_export(export).await
+    }
+
+    async fn finish_export(&self, export: &Export, content: Option<&str>) -> Result<(), RepoError> {
+        (**self).finish_export(export, content).await
+    }
+
+    async fn get_export(&self, id: ExportId) -> Result<Option<Export>, RepoError> {
+        (**self).get_export(id).await
+    }
+
+    async fn get_export_content(&self, id: ExportId) -> Result<Option<String>, RepoError> {
+        (**self).get_export_content(id).await
+    }
+
+    async fn delete_exports_before(&self, cutoff: DateTime<Utc>) -> Result<u64, RepoError> {
+        (**self).delete_exports_before(cutoff).await
+    }
+
+    async fn insert_scheduled_payment(&self, payment: &ScheduledPayment) -> Result<(), RepoError> {
+        (**self).insert_scheduled_payment(payment).await
+    }
+
+    async fn get_scheduled_payment(
+        &self,
+        id: ScheduledPaymentId,
+    ) -> Result<Option<ScheduledPayment>, RepoError> {
+        (**self).get_scheduled_payment(id).await
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_id: Option<AccountId>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        (**self).list_scheduled_payments(account_id, mode).await
+    }
+
+    async fn list_due_scheduled_payments(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ScheduledPayment>, RepoError> {
+        (**self).list_due_scheduled_payments(now, limit).await
+    }
+
+    async fn record_scheduled_payment_run(
+        &self,
+        payment: &ScheduledPayment,
+        due_at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        (**self).record_scheduled_payment_run(payment, due_at).await
+    }
+
+    async fn update_scheduled_payment_status(
+        &self,
+        payment: &ScheduledPayment,
+    ) -> Result<(), RepoError> {
+        (**self).update_scheduled_payment_status(payment).await
+    }
+
+    async fn create_hold(&self, hold: &Hold) -> Result<(), RepoError> {
+        (**self).create_hold(hold).await
+    }
+
+    async fn get_hold(&self, id: HoldId) -> Result<Option<Hold>, RepoError> {
+        (**self).get_hold(id).await
+    }
+
+    async fn list_holds(&self, account_id: AccountId) -> Result<Vec<Hold>, RepoError> {
+        (**self).list_holds(account_id).await
+    }
+
+    async fn list_expired_holds(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Hold>, RepoError> {
+        (**self).list_expired_holds(now, limit).await
+    }
+
+    async fn capture_hold(
+        &self,
+        id: HoldId,
+        amount: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(Hold, Transaction), RepoError> {
+        (**self).capture_hold(id, amount, now).await
+    }
+
+    async fn release_hold(
+        &self,
+        id: HoldId,
+        status: HoldStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Hold, RepoError> {
+        (**self).release_hold(id, status, now).await
+    }
+
+    async fn create_beneficiary(&self, beneficiary: &Beneficiary) -> Result<(), RepoError> {
+        (**self).create_beneficiary(beneficiary).await
+    }
+
+    async fn get_beneficiary(&self, id: BeneficiaryId) -> Result<Option<Beneficiary>, RepoError> {
+        (**self).get_beneficiary(id).await
+    }
+
+    async fn list_beneficiaries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Beneficiary>, RepoError> {
+        (**self).list_beneficiaries(account_id).await
+    }
+
+    async fn record_beneficiary_attempt(
+        &self,
+        beneficiary: &Beneficiary,
+        failed_attempts: u32,
+    ) -> Result<bool, RepoError> {
+        (**self)
+            .record_beneficiary_attempt(beneficiary, failed_attempts)
+            .await
+    }
+
+    async fn currency_balances(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<CurrencyBalance>, RepoError> {
+        (**self).currency_balances(mode).await
+    }
+
+    async fn float_positions(&self, mode: Option<Mode>) -> Result<Vec<FloatPosition>, RepoError> {
+        (**self).float_positions(mode).await
+    }
+
+    async fn insert_rate_snapshot(&self, snapshot: &RateSnapshot) -> Result<(), RepoError> {
+        (**self).insert_rate_snapshot(snapshot).await
+    }
+
+    async fn rate_snapshot_at(&self, at: DateTime<Utc>) -> Result<Option<RateSnapshot>, RepoError> {
+        (**self).rate_snapshot_at(at).await
+    }
+
+    async fn insert_quote(&self, quote: &Quote) -> Result<(), RepoError> {
+        (**self).insert_quote(quote).await
+    }
+
+    async fn get_quote(&self, id: QuoteId) -> Result<Option<Quote>, RepoError> {
+        (**self).get_quote(id).await
+    }
+
+    async fn save_daily_balances(
+        &self,
+        account_id: AccountId,
+        balances: &[DailyBalance],
+    ) -> Result<(), RepoError> {
+        (**self).save_daily_balances(account_id, balances).await
+    }
+
+    async fn list_daily_balances(
+        &self,
+        account_id: AccountId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyBalance>, RepoError> {
+        (**self).list_daily_balances(account_id, from, to).await
+    }
+
+    async fn latest_daily_balance_date(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<NaiveDate>, RepoError> {
+        (**self).latest_daily_balance_date(account_id).await
+    }
+
+    async fn insert_change_request(&self, request: &ChangeRequest) -> Result<(), RepoError> {
+        (**self).insert_change_request(request).await
+    }
+
+    async fn get_change_request(
+        &self,
+        id: ChangeRequestId,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        (**self).get_change_request(id).await
+    }
+
+    async fn list_change_requests(
+        &self,
+        status: Option<ChangeStatus>,
+    ) -> Result<Vec<ChangeRequest>, RepoError> {
+        (**self).list_change_requests(status).await
+    }
+
+    async fn decide_change_request(
+        &self,
+        id: ChangeRequestId,
+        status: ChangeStatus,
+        decided_by: crate::ApiKeyId,
+        decided_at: DateTime<Utc>,
+    ) -> Result<Option<ChangeRequest>, RepoError> {
+        (**self)
+            .decide_change_request(id, status, decided_by, decided_at)
+            .await
+    }
+
+    async fn get_idempotency_record(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, RepoError> {
+        (**self).get_idempotency_record(endpoint, key).await
+    }
+
+    async fn save_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotencyRecord, RepoError> {
+        (**self).save_idempotency_record(record).await
+    }
+
+    async fn replace_idempotency_record(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> Result<(), RepoError> {
+        (**self).replace_idempotency_record(record).await
+    }
+
+    async fn remove_idempotency_record(&self, endpoint: &str, key: &str) -> Result<(), RepoError> {
+        (**self).remove_idempotency_record(endpoint, key).await
+    }
+
+    async fn deposit(&self, req: DepositRequest) -> Result<Transaction, RepoError> {
+        (**self).deposit(req).await
+    }
+
+    async fn withdraw(&self, req: WithdrawRequest) -> Result<Transaction, RepoError> {
+        (**self).withdraw(req).await
+    }
+
+    async fn transfer(&self, req: TransferRequest) -> Result<Transaction, RepoError> {
+        (**self).transfer(req).await
+    }
+
+    async fn transfer_with_conversion(
+        &self,
+        req: TransferRequest,
+        conversion: FxConversion,
+    ) -> Result<Transaction, RepoError> {
+        (**self).transfer_with_conversion(req, conversion).await
+    }
+
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork + '_>, RepoError> {
+        (**self).begin().await
+    }
+
+    async fn record_transaction_risk(
+        &self,
+        id: TransactionId,
+        risk: &RiskAssessment,
+    ) -> Result<(), RepoError> {
+        (**self).record_transaction_risk(id, risk).await
+    }
+
+    async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<Transaction>, RepoError> {
+        (**self).find_by_idempotency_key(key).await
+    }
+
+    async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, RepoError> {
+        (**self).get_transaction(id).await
+    }
+
+    async fn list_transactions_for_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        (**self).list_transactions_for_account(account_id).await
+    }
+
+    async fn list_transactions_page(
+        &self,
+        account_id: AccountId,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        (**self)
+            .list_transactions_page(account_id, filter, after, limit)
+            .await
+    }
+
+    async fn search_transactions(
+        &self,
+        search: &TransactionSearch,
+        after: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        (**self).search_transactions(search, after, limit).await
+    }
+
+    async fn list_transactions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: Option<Mode>,
+    ) -> Result<Vec<Transaction>, RepoError> {
+        (**self).list_transactions_between(from, to, mode).await
+    }
+
+    async fn verify_api_key_hash(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<crate::ApiKey>, RepoError> {
+        (**self).verify_api_key_hash(key_hash).await
+    }
+
+    async fn create_api_key(
+        &self,
+        name: &str,
+        scopes: &[crate::ApiKeyScope],
+        mode: crate::Mode,
+    ) -> Result<(crate::ApiKey, String), RepoError> {
+        (**self).create_api_key(name, scopes, mode).await
+    }
+
+    async fn create_scoped_api_key(
+        &self,
+        name: &str,
+        account_id: AccountId,
+        scopes: &[crate::ApiKeyScope],
+        mode: crate::Mode,
+    ) -> Result<(crate::ApiKey, String), RepoError> {
+        (**self)
+            .create_scoped_api_key(name, account_id, scopes, mode)
+            .await
+    }
+
+    async fn create_owner_api_key(
+        &self,
+        name: &str,
+        owner_id: OwnerId,
+        scopes: &[crate::ApiKeyScope],
+        mode: crate::Mode,
+    ) -> Result<(crate::ApiKey, String), RepoError> {
+        (**self)
+            .create_owner_api_key(name, owner_id, scopes, mode)
+            .await
+    }
+
+    async fn count_api_keys(&self) -> Result<i64, RepoError> {
+        (**self).count_api_keys().await
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<crate::ApiKey>, RepoError> {
+        (**self).list_api_keys().await
+    }
+
+    async fn delete_api_key(
+        &self,
+        id: crate::ApiKeyId,
+        deactivated_by: crate::ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, RepoError> {
+        (**self).delete_api_key(id, deactivated_by, at).await
+    }
+
+    async fn touch_api_key(&self, id: crate::ApiKeyId, at: DateTime<Utc>) -> Result<(), RepoError> {
+        (**self).touch_api_key(id, at).await
+    }
+
+    async fn set_api_key_rate_limit(
+        &self,
+        id: crate::ApiKeyId,
+        rate_limit_per_minute: Option<u32>,
+    ) -> Result<bool, RepoError> {
+        (**self)
+            .set_api_key_rate_limit(id, rate_limit_per_minute)
+            .await
+    }
+
+    async fn insert_api_key_audit(&self, entry: &crate::ApiKeyAuditEntry) -> Result<(), RepoError> {
+        (**self).insert_api_key_audit(entry).await
+    }
+
+    async fn list_api_key_audit(
+        &self,
+        id: crate::ApiKeyId,
+    ) -> Result<Vec<crate::ApiKeyAuditEntry>, RepoError> {
+        (**self).list_api_key_audit(id).await
+    }
+
+    async fn get_api_key(&self, id: crate::ApiKeyId) -> Result<Option<crate::ApiKey>, RepoError> {
+        (**self).get_api_key(id).await
+    }
+
+    async fn add_api_key_usage(&self, usage: &ApiKeyUsageBucket) -> Result<(), RepoError> {
+        (**self).add_api_key_usage(usage).await
+    }
+
+    async fn add_api_key_volume(&self, volume: &ApiKeyVolumeBucket) -> Result<(), RepoError> {
+        (**self).add_api_key_volume(volume).await
+    }
+
+    async fn list_api_key_usage(
+        &self,
+        id: crate::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyUsageBucket>, RepoError> {
+        (**self).list_api_key_usage(id, since).await
+    }
+
+    async fn list_api_key_volume(
+        &self,
+        id: crate::ApiKeyId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApiKeyVolumeBucket>, RepoError> {
+        (**self).list_api_key_volume(id, since).await
+    }
+
+    async fn register_webhook_endpoint(
+        &self,
+        url: &str,
+        events: Vec<String>,
+        timeouts: WebhookTimeouts,
+        signature_algorithm: crate::WebhookSignatureAlgorithm,
+    ) -> Result<crate::WebhookEndpoint, RepoError> {
+        (**self)
+            .register_webhook_endpoint(url, events, timeouts, signature_algorithm)
+            .await
+    }
+
+    async fn register_webhook_endpoints(
+        &self,
+        endpoints: Vec<crate::NewWebhookEndpoint>,
+    ) -> Result<Vec<crate::WebhookEndpoint>, RepoError> {
+        (**self).register_webhook_endpoints(endpoints).await
+    }
+
+    async fn list_webhook_endpoints(
+        &self,
+        mode: Option<Mode>,
+    ) -> Result<Vec<crate::WebhookEndpoint>, RepoError> {
+        (**self).list_webhook_endpoints(mode).await
+    }
+
+    async fn get_webhook_endpoint(
+        &self,
+        id: crate::WebhookEndpointId,
+    ) -> Result<Option<crate::WebhookEndpoint>, RepoError> {
+        (**self).get_webhook_endpoint(id).await
+    }
+
+    async fn update_webhook_endpoint_url(
+        &self,
+        id: crate::WebhookEndpointId,
+        url: &str,
+    ) -> Result<Option<crate::WebhookEndpoint>, RepoError> {
+        (**self).update_webhook_endpoint_url(id, url).await
+    }
+
+    async fn update_webhook_endpoint(
+        &self,
+        id: crate::WebhookEndpointId,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+        signature_algorithm: Option<crate::WebhookSignatureAlgorithm>,
+    ) -> Result<Option<crate::WebhookEndpoint>, RepoError> {
+        (**self)
+            .update_webhook_endpoint(id, events, is_active, signature_algorithm)
+            .await
+    }
+
+    async fn rotate_webhook_secret(
+        &self,
+        id: crate::WebhookEndpointId,
+    ) -> Result<Option<crate::WebhookEndpoint>, RepoError> {
+        (**self).rotate_webhook_secret(id).await
+    }
+
+    async fn delete_webhook_endpoint(
+        &self,
+        id: crate::WebhookEndpointId,
+    ) -> Result<bool, RepoError> {
+        (**self).delete_webhook_endpoint(id).await
+    }
+
+    async fn create_webhook_event(
+        &self,
+        endpoint_id: crate::WebhookEndpointId,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<crate::WebhookEvent, RepoError> {
+        (**self)
+            .create_webhook_event(endpoint_id, event_type, payload)
+            .await
+    }
+
+    async fn get_webhook_event(
+        &self,
+        id: uuid::Uuid,
+    ) -> Result<Option<crate::WebhookEvent>, RepoError> {
+        (**self).get_webhook_event(id).await
+    }
+
+    async fn list_dead_lettered_webhooks(
+        &self,
+        filter: &crate::DeadLetterFilter,
+    ) -> Result<Vec<crate::WebhookEvent>, RepoError> {
+        (**self).list_dead_lettered_webhooks(filter).await
+    }
+
+    async fn requeue_dead_lettered_webhooks(
+        &self,
+        filter: &crate::DeadLetterFilter,
+    ) -> Result<u64, RepoError> {
+        (**self).requeue_dead_lettered_webhooks(filter).await
+    }
+
+    async fn count_webhook_backlog(&self) -> Result<i64, RepoError> {
+        (**self).count_webhook_backlog().await
+    }
+
+    async fn storage_counts(&self) -> Result<StorageCounts, RepoError> {
+        (**self).storage_counts().await
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        (**self).ping().await
+    }
+
+    async fn list_webhook_events_after(
+        &self,
+        endpoint_id: crate::WebhookEndpointId,
+        after_sequence: i64,
+        limit: usize,
+    ) -> Result<Vec<crate::WebhookEvent>, RepoError> {
+        (**self)
+            .list_webhook_events_after(endpoint_id, after_sequence, limit)
+            .await
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        endpoint_id: crate::WebhookEndpointId,
+        filter: &crate::WebhookDeliveryFilter,
+        limit: usize,
+    ) -> Result<Vec<crate::WebhookEvent>, RepoError> {
+        (**self)
+            .list_webhook_deliveries(endpoint_id, filter, limit)
+            .await
+    }
+
+    async fn requeue_webhook_event(&self, id: uuid::Uuid) -> Result<bool, RepoError> {
+        (**self).requeue_webhook_event(id).await
+    }
+
+    async fn append_event(&self, event: &crate::DomainEvent, mode: Mode) -> Result<(), RepoError> {
+        (**self).append_event(event, mode).await
+    }
+
+    async fn list_logged_events(
+        &self,
+        after: i64,
+        limit: usize,
+        mode: Option<Mode>,
+    ) -> Result<Vec<crate::LoggedEvent>, RepoError> {
+        (**self).list_logged_events(after, limit, mode).await
+    }
 }
@@ -0,0 +1,18 @@
+//! Risk check port.
+//!
+//! A risk check runs before every deposit, withdrawal and transfer and
+//! decides whether it may go ahead. Implementations range from allowing
+//! everything to calling out to a fraud scoring service.
+
+use super::warnings::PaymentCheck;
+use crate::domain::RiskAssessment;
+
+/// Port trait for pre-payment risk checks.
+///
+/// Checks cannot fail: one that cannot reach a decision, e.g. because its
+/// scoring service is down, chooses for itself whether to allow, hold or
+/// deny.
+#[async_trait::async_trait]
+pub trait RiskCheck: Send + Sync {
+    async fn assess(&self, payment: &PaymentCheck) -> RiskAssessment;
+}
@@ -0,0 +1,71 @@
+//! Warning rule port.
+//!
+//! Warning rules flag payments that look unusual, such as a transfer into an
+//! account nobody has used in a year, without blocking them. Their warnings
+//! are returned alongside the payment. Implementations are plain checks over
+//! the accounts' state before the payment is made.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::{Account, CurrencyCode, Transaction, TransactionType};
+
+/// A non-blocking note about a payment that went through.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Warning {
+    /// Stable identifier of the rule that raised it
+    #[schema(example = "dormant_account")]
+    pub code: String,
+    /// Human-readable explanation
+    #[schema(example = "Destination account has been dormant for 13 months")]
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// An account taking part in a payment, with its latest transactions.
+#[derive(Debug, Clone)]
+pub struct AccountActivity {
+    pub account: Account,
+    /// Most recent transactions first; only the latest few are loaded.
+    pub recent: Vec<Transaction>,
+}
+
+impl AccountActivity {
+    /// When the account last moved money, or was opened if it never has.
+    pub fn last_active_at(&self) -> DateTime<Utc> {
+        self.recent
+            .first()
+            .map_or(self.account.created_at, |tx| tx.created_at)
+    }
+}
+
+/// A payment about to be made, as seen by warning rules and risk checks.
+#[derive(Debug, Clone)]
+pub struct PaymentCheck {
+    pub transaction_type: TransactionType,
+    /// Amount in minor units of `currency`
+    pub amount: i64,
+    pub currency: CurrencyCode,
+    /// Account debited; `None` for deposits
+    pub source: Option<AccountActivity>,
+    /// Account credited; `None` for withdrawals
+    pub destination: Option<AccountActivity>,
+    pub now: DateTime<Utc>,
+}
+
+/// Port trait for payment warning rules.
+///
+/// Rules must not fail or block: a payment a rule objects to still goes
+/// through, with the rule's warnings attached.
+pub trait WarningRule: Send + Sync {
+    fn check(&self, payment: &PaymentCheck) -> Vec<Warning>;
+}
@@ -0,0 +1,48 @@
+#!/usr/bin/env bash
+# Generate the TypeScript and Python API clients from the OpenAPI spec.
+#
+# Usage: ./scripts/common/generate_clients.sh [--check]
+#
+# Re-exports clients/openapi.json from the server code, then regenerates
+# clients/typescript and clients/python with openapi-generator (run through
+# Docker so no Java or Node install is needed). With --check, fails instead
+# of rewriting when the committed spec no longer matches the code.
+
+set -euo pipefail
+source "$(dirname "$0")/lib.sh"
+
+cd "$(dirname "$0")/../.."
+
+GENERATOR_IMAGE="openapitools/openapi-generator-cli:v7.12.0"
+SPEC="clients/openapi.json"
+
+print_step "Exporting OpenAPI spec..."
+FRESH_SPEC=$(mktemp)
+trap 'rm -f "$FRESH_SPEC"' EXIT
+cargo run -q -p payments-app --no-default-features --features sqlite -- --dump-openapi "$FRESH_SPEC"
+
+if [[ "${1:-}" == "--check" ]]; then
+    if ! diff -q "$SPEC" "$FRESH_SPEC" > /dev/null; then
+        print_error "$SPEC is stale; run ./scripts/common/generate_clients.sh and commit the result"
+        exit 1
+    fi
+    print_success "$SPEC matches the server"
+else
+    cp "$FRESH_SPEC" "$SPEC"
+    print_success "Wrote $SPEC"
+fi
+
+generate() {
+    local generator="$1" out="$2" props="$3"
+    print_step "Generating $out ($generator)..."
+    rm -rf "$out"
+    docker run --rm -u "$(id -u):$(id -g)" -v "$PWD:/local" "$GENERATOR_IMAGE" generate \
+        -i "/local/$SPEC" \
+        -g "$generator" \
+        -o "/local/$out" \
+        --additional-properties "$props"
+    print_success "Generated $out"
+}
+
+generate typescript-fetch clients/typescript "npmName=@payments/client,supportsES6=true"
+generate python clients/python "packageName=payments_client,projectName=payments-client"
@@ -49,7 +49,7 @@ http_get() {
 
 create_account() {
     local name="$1"
-    local result=$(http_post "/api/accounts" "{\"name\":\"$name\",\"currency\":\"USD\"}")
+    local result=$(http_post "/api/accounts" "{\"owner_id\":\"00000000-0000-0000-0000-000000000000\",\"name\":\"$name\",\"currency\":\"USD\"}")
     echo "$result" | grep -o '"id":"[^"]*"' | cut -d'"' -f4
 }
 
@@ -145,7 +145,7 @@ TESTS_PASSED=$((TESTS_PASSED + 1))
 print_step "CHAOS TEST 6: Invalid Handling"
 ERRORS=0
 # Empty name
-if http_post "/api/accounts" '{"name":"   ","currency":"USD"}' | grep -qi "error"; then
+if http_post "/api/accounts" '{"owner_id":"00000000-0000-0000-0000-000000000000","name":"   ","currency":"USD"}' | grep -qi "error"; then
     print_success "Empty name rejected"
 else
     print_error "Empty name accepted"
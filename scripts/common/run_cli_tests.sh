@@ -49,13 +49,13 @@ fi
 # Test: Create account
 # ─────────────────────────────────────────────────────────────────────────────
 print_step "Creating account 'Alice Corp'..."
-ALICE_JSON=$($CLI account create "Alice Corp" --currency USD)
+ALICE_JSON=$($CLI account create "Alice Corp" --owner 00000000-0000-0000-0000-000000000000 --currency USD)
 ALICE_ID=$(echo "$ALICE_JSON" | grep -o '"id": *"[^"]*"' | cut -d'"' -f4)
 assert_contains "Create account Alice" "Alice" "$ALICE_JSON" && \
 echo "   Created: $ALICE_ID" && TESTS_PASSED=$((TESTS_PASSED + 1)) || TESTS_FAILED=$((TESTS_FAILED + 1))
 
 print_step "Creating account 'Bob Inc'..."
-BOB_JSON=$($CLI account create "Bob Inc" --currency USD)
+BOB_JSON=$($CLI account create "Bob Inc" --owner 00000000-0000-0000-0000-000000000000 --currency USD)
 BOB_ID=$(echo "$BOB_JSON" | grep -o '"id": *"[^"]*"' | cut -d'"' -f4)
 assert_contains "Create account Bob" "Bob" "$BOB_JSON" && \
 echo "   Created: $BOB_ID" && TESTS_PASSED=$((TESTS_PASSED + 1)) || TESTS_FAILED=$((TESTS_FAILED + 1))
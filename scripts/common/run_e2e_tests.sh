@@ -54,7 +54,7 @@ assert_contains "Health Body" "healthy" "$HEALTH" && TESTS_PASSED=$((TESTS_PASSE
 print_step "Testing Account Operations"
 
 # Create account
-ALICE_RESP=$(curl -s -X POST "$BASE_URL/api/accounts" -H "Content-Type: application/json" -H "$AUTH_HEADER" -d '{"name":"Alice","currency":"USD"}')
+ALICE_RESP=$(curl -s -X POST "$BASE_URL/api/accounts" -H "Content-Type: application/json" -H "$AUTH_HEADER" -d '{"owner_id":"00000000-0000-0000-0000-000000000000","name":"Alice","currency":"USD"}')
 ALICE_ID=$(echo "$ALICE_RESP" | grep -o '"id":"[^"]*"' | cut -d'"' -f4)
 assert_contains "Create account ID" "id" "$ALICE_RESP" && \
 assert_contains "Create account Name" "Alice" "$ALICE_RESP" && \
@@ -75,7 +75,7 @@ assert_status "Get non-existent account" "404" "$BASE_URL/api/accounts/00000000-
 assert_status "Get invalid UUID" "400" "$BASE_URL/api/accounts/invalid-uuid"
 
 # Empty name validation
-assert_status "Create with empty name" "400" "$BASE_URL/api/accounts" "POST" '{"name":"   ","currency":"USD"}'
+assert_status "Create with empty name" "400" "$BASE_URL/api/accounts" "POST" '{"owner_id":"00000000-0000-0000-0000-000000000000","name":"   ","currency":"USD"}'
 
 # ─────────────────────────────────────────────────────────────────────────────
 # DEPOSIT
@@ -118,7 +118,7 @@ assert_status "Withdraw insufficient funds" "400" "$BASE_URL/api/transactions/wi
 # ─────────────────────────────────────────────────────────────────────────────
 print_step "Testing Transfer Operations"
 
-BOB_RESP=$(curl -s -X POST "$BASE_URL/api/accounts" -H "Content-Type: application/json" -H "$AUTH_HEADER" -d '{"name":"Bob","currency":"USD"}')
+BOB_RESP=$(curl -s -X POST "$BASE_URL/api/accounts" -H "Content-Type: application/json" -H "$AUTH_HEADER" -d '{"owner_id":"00000000-0000-0000-0000-000000000000","name":"Bob","currency":"USD"}')
 BOB_ID=$(echo "$BOB_RESP" | grep -o '"id":"[^"]*"' | cut -d'"' -f4)
 
 XFER_RESP=$(curl -s -X POST "$BASE_URL/api/transactions/transfer" -H "Content-Type: application/json" -H "$AUTH_HEADER" \
@@ -135,7 +135,7 @@ assert_status "Self transfer rejected" "400" "$BASE_URL/api/transactions/transfe
     "{\"from_account_id\":\"$ALICE_ID\",\"to_account_id\":\"$ALICE_ID\",\"amount\":100,\"currency\":\"USD\"}"
 
 # Cross-currency transfer
-EUR_RESP=$(curl -s -X POST "$BASE_URL/api/accounts" -H "Content-Type: application/json" -H "$AUTH_HEADER" -d '{"name":"Euro Account","currency":"EUR"}')
+EUR_RESP=$(curl -s -X POST "$BASE_URL/api/accounts" -H "Content-Type: application/json" -H "$AUTH_HEADER" -d '{"owner_id":"00000000-0000-0000-0000-000000000000","name":"Euro Account","currency":"EUR"}')
 EUR_ID=$(echo "$EUR_RESP" | grep -o '"id":"[^"]*"' | cut -d'"' -f4)
 curl -s -X POST "$BASE_URL/api/transactions/deposit" -H "Content-Type: application/json" -H "$AUTH_HEADER" \
     -d "{\"account_id\":\"$EUR_ID\",\"amount\":5000,\"currency\":\"EUR\"}" >/dev/null
@@ -71,8 +71,8 @@ cargo run -q -p payments-cli -- webhook register \
 
 # 1. Create Accounts
 print_step "Creating Accounts..."
-ACCT_A=$(cargo run -q -p payments-cli -- account create "Alice" --currency USD | grep -o '"id": *"[^"]*"' | cut -d'"' -f4 | head -n1)
-ACCT_B=$(cargo run -q -p payments-cli -- account create "Bob" --currency USD | grep -o '"id": *"[^"]*"' | cut -d'"' -f4 | head -n1)
+ACCT_A=$(cargo run -q -p payments-cli -- account create "Alice" --owner 00000000-0000-0000-0000-000000000000 --currency USD | grep -o '"id": *"[^"]*"' | cut -d'"' -f4 | head -n1)
+ACCT_B=$(cargo run -q -p payments-cli -- account create "Bob" --owner 00000000-0000-0000-0000-000000000000 --currency USD | grep -o '"id": *"[^"]*"' | cut -d'"' -f4 | head -n1)
 
 print_success "Alice: $ACCT_A"
 print_success "Bob: $ACCT_B"